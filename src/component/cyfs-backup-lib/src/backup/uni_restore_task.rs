@@ -10,4 +10,9 @@ pub struct UniRestoreParams {
     pub isolate: String,
     pub archive: PathBuf,
     pub password: Option<ProtectedPassword>,
+
+    // Rebuild a brand-new device from the archive alone, the archive must contain the device's identity,
+    // and the target cyfs_root must not be activated yet
+    #[serde(default)]
+    pub bare_metal: bool,
 }
\ No newline at end of file
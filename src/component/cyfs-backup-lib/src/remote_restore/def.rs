@@ -12,6 +12,10 @@ pub struct RemoteRestoreParams {
     pub isolate: Option<String>,
     pub password: Option<ProtectedPassword>,
 
+    // See UniRestoreParams::bare_metal
+    #[serde(default)]
+    pub bare_metal: bool,
+
     // Remote archive info
     pub remote_archive: String,
 }
@@ -23,6 +27,7 @@ impl RemoteRestoreParams {
             cyfs_root: None,
             isolate: None,
             password: None,
+            bare_metal: false,
             remote_archive: remote_archive.into(),
         }
     }
@@ -7,6 +7,7 @@ use cyfs_backup_lib::*;
 use cyfs_base::*;
 use cyfs_bdt::ChunkReaderRef;
 use cyfs_lib::*;
+use cyfs_util::StorageRootCategory;
 
 use std::path::{Path, PathBuf};
 
@@ -93,10 +94,12 @@ impl UniBackupTask {
 
         self.status_manager.update_phase(BackupTaskPhase::Stat);
 
-        self.run_stat(params.clone()).await?;
+        let required_space = self.run_stat(params.clone()).await?;
 
         self.status_manager.update_phase(BackupTaskPhase::Backup);
-        let ret = self.run_backup(loader, device_id, owner, params).await;
+        let ret = self
+            .run_backup(loader, device_id, owner, params, required_space)
+            .await;
 
         match ret {
             Ok((index, uni_meta)) => Ok(BackupResult {
@@ -107,7 +110,8 @@ impl UniBackupTask {
         }
     }
 
-    async fn run_stat(&self, params: UniBackupParams) -> BuckyResult<()> {
+    // return the total bytes of the data to backup
+    async fn run_stat(&self, params: UniBackupParams) -> BuckyResult<u64> {
         let uni_stat = UniBackupStat::new(self.noc.clone(), self.ndc.clone());
         let uni_stat = uni_stat.stat().await?;
 
@@ -121,9 +125,10 @@ impl UniBackupTask {
             files: keydata_stat,
         };
 
+        let bytes = stat.objects.bytes + stat.chunks.bytes + stat.files.bytes;
         self.status_manager.init_stat(stat);
 
-        Ok(())
+        Ok(bytes)
     }
 
     fn check_target_dir(dir: &Path) -> BuckyResult<()> {
@@ -160,11 +165,11 @@ impl UniBackupTask {
         match &params.target_file.dir {
             Some(dir) => std::borrow::Cow::Borrowed(dir),
             None => {
+                let root = cyfs_util::get_storage_root(StorageRootCategory::BackupStaging);
                 let dir = if params.isolate.is_empty() {
-                    cyfs_util::get_cyfs_root_path_ref().join(format!("data/backup/{}", params.id))
+                    root.join(format!("backup/{}", params.id))
                 } else {
-                    cyfs_util::get_cyfs_root_path_ref()
-                        .join(format!("data/backup/{}/{}", params.isolate, params.id))
+                    root.join(format!("backup/{}/{}", params.isolate, params.id))
                 };
 
                 std::borrow::Cow::Owned(dir)
@@ -178,11 +183,17 @@ impl UniBackupTask {
         device_id: DeviceId,
         owner: Option<ObjectId>,
         params: UniBackupParams,
+        required_space: u64,
     ) -> BuckyResult<(ObjectArchiveIndex, ObjectArchiveMetaForUniBackup)> {
         let backup_dir = Self::backup_dir(&params);
 
         Self::check_target_dir(&backup_dir)?;
 
+        // Backup to the default staging dir, should check the free space of the disk first
+        if params.target_file.dir.is_none() {
+            cyfs_util::check_storage_space(StorageRootCategory::BackupStaging, required_space)?;
+        }
+
         info!("backup local dir is: {}", backup_dir.display());
 
         std::fs::create_dir_all(backup_dir.as_path()).map_err(|e| {
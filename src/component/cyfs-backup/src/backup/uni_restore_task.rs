@@ -56,6 +56,15 @@ impl UniRestoreTask {
     }
 
     async fn run_restore(&self, params: UniRestoreParams) -> BuckyResult<RestoreResult> {
+        let cyfs_root = PathBuf::from(&params.cyfs_root);
+        let bare_metal = if params.bare_metal {
+            let helper = BareMetalRestoreHelper::new(&cyfs_root, &params.isolate);
+            helper.check_target()?;
+            Some(helper)
+        } else {
+            None
+        };
+
         self.status_manager
             .update_phase(RestoreTaskPhase::LoadAndVerify);
 
@@ -74,12 +83,15 @@ impl UniRestoreTask {
                 BuckyError::new(BuckyErrorCode::InvalidData, msg)
             })?;
 
+        if let Some(helper) = &bare_metal {
+            helper.check_archive(&meta)?;
+        }
+
         self.status_manager.init_stat(&meta);
 
         self.status_manager
             .update_phase(RestoreTaskPhase::RestoreKeyData);
 
-        let cyfs_root = PathBuf::from(&params.cyfs_root);
         let restorer = StackLocalObjectRestorer::create(cyfs_root, &params.isolate).await?;
         let restorer = Arc::new(Box::new(restorer) as Box<dyn ObjectRestorer>);
 
@@ -114,8 +126,25 @@ impl UniRestoreTask {
             key_data_restore.run().await?;
         }
 
+        let index = loader.index().await;
+
+        // The restored identity must be the archive's device, otherwise remove it to avoid being activated
+        if let Some(helper) = &bare_metal {
+            let device_id = match helper.verify_identity(&index) {
+                Ok(v) => v,
+                Err(e) => {
+                    helper.remove_identity();
+                    return Err(e);
+                }
+            };
+            info!(
+                "bare metal restore complete, device will be activated on next bind check! device={}",
+                device_id
+            );
+        }
+
         let result = RestoreResult {
-            index: loader.index().await,
+            index,
             uni_meta: Some(meta),
         };
 
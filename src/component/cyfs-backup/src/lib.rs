@@ -18,5 +18,12 @@ pub use crypto::*;
 pub use service::*;
 pub use remote_restore::*;
 
+// used by the stack to import/export object packs directly
+pub use archive::ArchiveInnerFileMeta;
+pub use object_pack::{
+    ObjectPackFactory, ObjectPackInnerFile, ObjectPackInnerFileData, ObjectPackReader,
+    ObjectPackWriter,
+};
+
 #[macro_use]
 extern crate log;
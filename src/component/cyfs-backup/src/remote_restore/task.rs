@@ -217,6 +217,7 @@ impl RemoteRestoreTask {
             isolate,
             archive: self.archive_dir.clone(),
             password: params.password,
+            bare_metal: params.bare_metal,
         };
 
         self.status
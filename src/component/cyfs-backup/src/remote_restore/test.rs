@@ -14,6 +14,7 @@ async fn download_folder() {
         cyfs_root: Some("C://cyfs/tmp/remote_store".to_owned()),
         isolate: None,
         password: None,
+        bare_metal: false,
 
         remote_archive: "http://127.0.0.1:8887/test65/${filename}?token=123456".to_owned(),
     };
@@ -75,6 +76,7 @@ async fn download_file() {
         cyfs_root: Some("C://cyfs/tmp/remote_store".to_owned()),
         isolate: None,
         password: None,
+        bare_metal: false,
 
         remote_archive: "http://127.0.0.1:8887/test65.zip?token=123456".to_owned(),
     };
@@ -0,0 +1,172 @@
+use crate::meta::*;
+use cyfs_base::*;
+
+use std::path::{Path, PathBuf};
+
+// Rebuild a brand-new device from an archive alone.
+// The identity(device.desc & device.sec under {cyfs}/etc/desc) is restored with the key data at the end, after all the objects and chunks,
+// so the ood-control's bind monitor will only find the device activated after everything else is in place.
+pub struct BareMetalRestoreHelper {
+    cyfs_root: PathBuf,
+    isolate: String,
+}
+
+impl BareMetalRestoreHelper {
+    pub fn new(cyfs_root: &Path, isolate: &str) -> Self {
+        Self {
+            cyfs_root: cyfs_root.to_owned(),
+            isolate: isolate.to_owned(),
+        }
+    }
+
+    // Relative to cyfs_root, same as the LOCAL_DEVICE_MANAGER used by backup
+    fn desc_dir(&self) -> String {
+        if self.isolate.is_empty() {
+            "etc/desc".to_owned()
+        } else {
+            format!("etc/desc/{}", self.isolate)
+        }
+    }
+
+    fn noc_meta_file(&self) -> String {
+        if self.isolate.is_empty() {
+            "data/named-object-cache/meta.db".to_owned()
+        } else {
+            format!("data/{}/named-object-cache/meta.db", self.isolate)
+        }
+    }
+
+    fn desc_file(&self) -> PathBuf {
+        self.cyfs_root.join(self.desc_dir()).join("device.desc")
+    }
+
+    fn sec_file(&self) -> PathBuf {
+        self.cyfs_root.join(self.desc_dir()).join("device.sec")
+    }
+
+    // The target cyfs_root must be a new one, never overwrite an activated device or existing noc
+    pub fn check_target(&self) -> BuckyResult<()> {
+        let desc_file = self.desc_file();
+        if desc_file.exists() {
+            let msg = format!(
+                "bare metal restore but target device already activated! desc={}",
+                desc_file.display()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+        }
+
+        let noc_file = self.cyfs_root.join(self.noc_meta_file());
+        if noc_file.exists() {
+            let msg = format!(
+                "bare metal restore but target noc already exists! noc={}",
+                noc_file.display()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+        }
+
+        Ok(())
+    }
+
+    fn is_covered(key_data: &KeyDataMeta, path: &str) -> bool {
+        match key_data.data_type {
+            KeyDataType::File => key_data.local_path == path,
+            KeyDataType::Dir => {
+                path == key_data.local_path || path.starts_with(&format!("{}/", key_data.local_path))
+            }
+        }
+    }
+
+    // The archive must contain the identity and the noc, which are usually ignored by key-data-filter
+    pub fn check_archive(&self, meta: &ObjectArchiveMetaForUniBackup) -> BuckyResult<()> {
+        let required = [
+            format!("{}/device.desc", self.desc_dir()),
+            format!("{}/device.sec", self.desc_dir()),
+            self.noc_meta_file(),
+        ];
+
+        for path in &required {
+            if !meta.key_data.iter().any(|item| Self::is_covered(item, path)) {
+                let msg = format!(
+                    "bare metal restore but the archive has no required key data! path={}",
+                    path
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Check the restored identity is the same device as the archive's
+    pub fn verify_identity(&self, index: &ObjectArchiveIndex) -> BuckyResult<DeviceId> {
+        let desc_file = self.desc_file();
+        let mut buf = vec![];
+        let (device, _) = Device::decode_from_file(&desc_file, &mut buf).map_err(|e| {
+            let msg = format!(
+                "load restored device desc failed! file={}, {}",
+                desc_file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(e.code(), msg)
+        })?;
+
+        let sec_file = self.sec_file();
+        let mut buf = vec![];
+        let (private_key, _) = PrivateKey::decode_from_file(&sec_file, &mut buf).map_err(|e| {
+            let msg = format!(
+                "load restored device private key failed! file={}, {}",
+                sec_file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(e.code(), msg)
+        })?;
+
+        let device_id = device.desc().device_id();
+        if device_id != index.device_id {
+            let msg = format!(
+                "restored device is not the archive's device! archive={}, got={}",
+                index.device_id, device_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        if private_key.public() != *device.desc().public_key() {
+            let msg = format!(
+                "restored device private key is not match the desc! device={}",
+                device_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        info!(
+            "bare metal restore verify identity success! device={}, owner={:?}",
+            device_id,
+            device.desc().owner()
+        );
+
+        Ok(device_id)
+    }
+
+    pub fn remove_identity(&self) {
+        for file in [self.desc_file(), self.sec_file()] {
+            if file.exists() {
+                if let Err(e) = std::fs::remove_file(&file) {
+                    error!(
+                        "remove restored identity file failed! file={}, {}",
+                        file.display(),
+                        e
+                    );
+                } else {
+                    warn!("remove restored identity file! file={}", file.display());
+                }
+            }
+        }
+    }
+}
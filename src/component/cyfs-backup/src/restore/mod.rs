@@ -1,5 +1,7 @@
+mod bare_metal;
 mod local;
 mod restorer;
 
+pub use bare_metal::*;
 pub use restorer::*;
 pub use local::*;
\ No newline at end of file
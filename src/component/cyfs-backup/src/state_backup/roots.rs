@@ -97,6 +97,7 @@ impl RootObjectBackup {
                 let req = NamedObjectCacheSelectObjectRequest {
                     filter: NamedObjectCacheSelectObjectFilter {
                         obj_type: Some(category.object_type),
+                        last_access_before: None,
                     },
                     opt: opt.clone(),
                 };
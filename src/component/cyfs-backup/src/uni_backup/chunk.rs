@@ -29,6 +29,7 @@ impl UniChunkBackup {
         let mut opt = SelectChunkOption::default();
         let filter = SelectChunkFilter {
             state: Some(ChunkState::Ready),
+            last_access_before: None,
         };
 
         loop {
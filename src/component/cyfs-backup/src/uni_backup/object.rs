@@ -29,6 +29,7 @@ impl UniObjectBackup {
         let mut opt = NamedObjectCacheSelectObjectOption {
             page_index: 0,
             page_size: 1024,
+            cursor: None,
         };
         
         let filter = NamedObjectCacheSelectObjectFilter::default();
@@ -0,0 +1,239 @@
+use super::error::{BuckyError, BuckyErrorCode, BuckyResult};
+
+use async_std::prelude::*;
+use once_cell::sync::OnceCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+// 进程内的虚拟时钟，安装之后bucky_time_now/bucky_sleep/bucky_timeout都使用虚拟时间，由模拟器或测试显式推进
+// 只能在进程启动、协议栈加载之前安装一次
+static VIRTUAL_CLOCK: OnceCell<VirtualClock> = OnceCell::new();
+
+struct VirtualClockState {
+    now: u64,
+
+    // (到期时间, 序号) -> waker，同一时间到期的按注册顺序唤醒
+    timers: BTreeMap<(u64, u64), Option<Waker>>,
+    next_seq: u64,
+}
+
+#[derive(Clone)]
+pub struct VirtualClock(Arc<Mutex<VirtualClockState>>);
+
+impl VirtualClock {
+    fn new(start: u64) -> Self {
+        Self(Arc::new(Mutex::new(VirtualClockState {
+            now: start,
+            timers: BTreeMap::new(),
+            next_seq: 0,
+        })))
+    }
+
+    // 从start(bucky time)开始计时，已经安装过的话返回错误
+    pub fn install(start: u64) -> BuckyResult<Self> {
+        let clock = Self::new(start);
+        VIRTUAL_CLOCK.set(clock.clone()).map_err(|_| {
+            let msg = "virtual clock already installed!".to_owned();
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::AlreadyExists, msg)
+        })?;
+
+        info!("virtual clock installed, start={}", start);
+        Ok(clock)
+    }
+
+    pub fn get() -> Option<&'static Self> {
+        VIRTUAL_CLOCK.get()
+    }
+
+    pub fn now(&self) -> u64 {
+        self.0.lock().unwrap().now
+    }
+
+    // 还没有到期的定时器数量
+    pub fn pending_timers(&self) -> usize {
+        self.0.lock().unwrap().timers.len()
+    }
+
+    // 最近一个定时器的到期时间
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.0
+            .lock()
+            .unwrap()
+            .timers
+            .keys()
+            .next()
+            .map(|(deadline, _)| *deadline)
+    }
+
+    // 推进dur，期间到期的定时器按到期时间的顺序唤醒
+    pub fn advance(&self, dur: Duration) {
+        let target = self.now() + dur.as_micros() as u64;
+        while let Some(deadline) = self.next_deadline() {
+            if deadline > target {
+                break;
+            }
+            self.fire(deadline);
+        }
+
+        let mut state = self.0.lock().unwrap();
+        if state.now < target {
+            state.now = target;
+        }
+    }
+
+    // 直接跳到下一个定时器的到期时间并唤醒，返回新的时间；没有定时器时返回None
+    pub fn advance_to_next(&self) -> Option<u64> {
+        let deadline = self.next_deadline()?;
+        self.fire(deadline);
+        Some(deadline)
+    }
+
+    fn fire(&self, deadline: u64) {
+        let wakers: Vec<Waker> = {
+            let mut state = self.0.lock().unwrap();
+            if state.now < deadline {
+                state.now = deadline;
+            }
+
+            let remain = state.timers.split_off(&(deadline + 1, 0));
+            let fired = std::mem::replace(&mut state.timers, remain);
+            fired.into_values().flatten().collect()
+        };
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn sleep(&self, dur: Duration) -> VirtualSleep {
+        let mut state = self.0.lock().unwrap();
+        let deadline = state.now + dur.as_micros() as u64;
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.timers.insert((deadline, seq), None);
+
+        VirtualSleep {
+            clock: self.clone(),
+            key: (deadline, seq),
+        }
+    }
+}
+
+pub struct VirtualSleep {
+    clock: VirtualClock,
+    key: (u64, u64),
+}
+
+impl Future for VirtualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.clock.0.lock().unwrap();
+        match state.timers.get_mut(&self.key) {
+            Some(waker) => {
+                *waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            // 到期时已经从定时器列表里移除
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+impl Drop for VirtualSleep {
+    fn drop(&mut self) {
+        self.clock.0.lock().unwrap().timers.remove(&self.key);
+    }
+}
+
+// 当前时间，安装了虚拟时钟时返回虚拟时间
+pub(crate) fn clock_now() -> Option<u64> {
+    VIRTUAL_CLOCK.get().map(|clock| clock.now())
+}
+
+// 替代async_std::task::sleep，虚拟时钟下由时钟推进唤醒
+pub async fn bucky_sleep(dur: Duration) {
+    match VIRTUAL_CLOCK.get() {
+        Some(clock) => clock.sleep(dur).await,
+        None => async_std::task::sleep(dur).await,
+    }
+}
+
+// 替代async_std::future::timeout，超时返回BuckyErrorCode::Timeout
+pub async fn bucky_timeout<F, T>(dur: Duration, fut: F) -> BuckyResult<T>
+where
+    F: Future<Output = T>,
+{
+    let timeout = async {
+        bucky_sleep(dur).await;
+        Err(BuckyError::new(
+            BuckyErrorCode::Timeout,
+            format!("future timeout after {:?}", dur),
+        ))
+    };
+
+    async { Ok(fut.await) }.race(timeout).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bucky_time_now;
+
+    #[async_std::test]
+    async fn test_virtual_clock() {
+        let clock = VirtualClock::new(bucky_time_now());
+        let start = clock.now();
+
+        let fired = Arc::new(Mutex::new(vec![]));
+        for secs in [3u64, 1, 2] {
+            let clock = clock.clone();
+            let fired = fired.clone();
+            async_std::task::spawn(async move {
+                clock.sleep(Duration::from_secs(secs)).await;
+                fired.lock().unwrap().push(secs);
+            });
+        }
+
+        let wait_fired = |count: usize| {
+            let fired = fired.clone();
+            async move {
+                while fired.lock().unwrap().len() < count {
+                    async_std::task::yield_now().await;
+                }
+            }
+        };
+
+        // 等待所有任务注册定时器
+        while clock.pending_timers() < 3 {
+            async_std::task::yield_now().await;
+        }
+
+        assert_eq!(clock.advance_to_next(), Some(start + 1000 * 1000));
+        wait_fired(1).await;
+
+        clock.advance(Duration::from_millis(1500));
+        wait_fired(2).await;
+        assert_eq!(clock.now(), start + 2500 * 1000);
+        assert_eq!(clock.pending_timers(), 1);
+
+        // 被取消的sleep会移除自己的定时器
+        let ret = async_std::future::timeout(
+            Duration::from_millis(50),
+            clock.sleep(Duration::from_secs(10)),
+        )
+        .await;
+        assert!(ret.is_err());
+        assert_eq!(clock.pending_timers(), 1);
+
+        clock.advance(Duration::from_secs(1));
+        wait_fired(3).await;
+        assert_eq!(*fired.lock().unwrap(), vec![1, 2, 3]);
+        assert_eq!(clock.pending_timers(), 0);
+    }
+}
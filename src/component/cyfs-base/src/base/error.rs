@@ -129,6 +129,12 @@ pub enum BuckySystemErrorCode {
     ErrorTimestamp = 264,
     DecNotRunning = 265,
 
+    // 存储因为磁盘满或者io错误处于只读模式，拒绝写入
+    StorageReadOnly = 266,
+
+    // 服务端负载过高，暂时拒绝新的请求
+    Busy = 267,
+
     // 在system error code里面，meta_error默认值都取值5000
     MetaError = 5000,
 
@@ -248,6 +254,9 @@ pub enum BuckyErrorCode {
     ErrorTimestamp,
     DecNotRunning,
 
+    StorageReadOnly,
+    Busy,
+
     // meta chain的error段，取值范围是[0, BUCKY_META_ERROR_CODE_MAX)
     MetaError(u16),
 
@@ -341,6 +350,8 @@ impl Into<BuckySystemErrorCode> for BuckyErrorCode {
             Self::InvalidTarget => BuckySystemErrorCode::InvalidTarget,
             Self::ErrorTimestamp => BuckySystemErrorCode::ErrorTimestamp,
             Self::DecNotRunning => BuckySystemErrorCode::DecNotRunning,
+            Self::StorageReadOnly => BuckySystemErrorCode::StorageReadOnly,
+            Self::Busy => BuckySystemErrorCode::Busy,
 
             Self::MetaError(_) => BuckySystemErrorCode::MetaError,
             Self::DecError(_) => BuckySystemErrorCode::DecError,
@@ -436,6 +447,8 @@ impl Into<BuckyErrorCode> for BuckySystemErrorCode {
             Self::InvalidTarget => BuckyErrorCode::InvalidTarget,
             Self::ErrorTimestamp => BuckyErrorCode::ErrorTimestamp,
             Self::DecNotRunning => BuckyErrorCode::DecNotRunning,
+            Self::StorageReadOnly => BuckyErrorCode::StorageReadOnly,
+            Self::Busy => BuckyErrorCode::Busy,
 
             Self::MetaError => BuckyErrorCode::MetaError(0),
             Self::DecError => BuckyErrorCode::DecError(0),
@@ -921,6 +934,11 @@ impl BuckyError {
         self.origin
     }
 
+    pub fn with_origin(mut self, origin: BuckyOriginError) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
     fn format(&self) -> String {
         format!("err: ({:?}, {}, {:?})", self.code, self.msg, self.origin)
     }
@@ -1,6 +1,7 @@
 mod access_string;
 mod base36;
 mod channel;
+mod clock;
 mod constants;
 pub mod endpoint;
 mod error;
@@ -13,6 +14,7 @@ mod time;
 pub use access_string::*;
 pub use base36::*;
 pub use channel::*;
+pub use clock::*;
 pub use constants::*;
 pub use endpoint::*;
 pub use error::*;
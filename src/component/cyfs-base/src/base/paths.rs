@@ -4,11 +4,29 @@ pub const CYFS_GLOBAL_STATE_META_PATH: &str = "/.cyfs/meta";
 // Config path, the system-dec must has one
 pub const CYFS_GLOBAL_STATE_CONFIG_PATH: &str = "/.cyfs/config";
 
+// Dec config documents, in system dec's global state
+pub const CYFS_DEC_CONFIG_PATH: &str = "/.cyfs/config/dec";
+
+// dec service registry, in system dec's global state
+pub const CYFS_DEC_SERVICE_PATH: &str = "/.cyfs/service";
+
+// dec resource limit policies, in system dec's global state
+pub const CYFS_DEC_RESOURCE_POLICY_PATH: &str = "/.cyfs/dec_resource";
+
+// share link records, in ood's system dec's local cache
+pub const CYFS_SHARE_PATH: &str = "/.cyfs/share";
+
+// WebAuthn credentials of the owner for admin confirmation, in system dec's global state
+pub const CYFS_ADMIN_WEBAUTHN_PATH: &str = "/.cyfs/admin/webauthn";
+
 // Friends, in system dec's global state
 pub const CYFS_FRIENDS_PATH: &str = "/user/friends";
 pub const CYFS_FRIENDS_LIST_PATH: &str = "/user/friends/list";
 pub const CYFS_FRIENDS_OPTION_PATH: &str = "/user/friends/option";
 
+// Contacts, in system dec's global state
+pub const CYFS_CONTACTS_PATH: &str = "/user/contacts";
+
 // AppManager related paths
 pub const CYFS_APP_LOCAL_LIST_PATH: &str = "/app/manager/local_list";
 pub const CYFS_APP_LOCAL_STATUS_PATH: &str = "/app/${DecAppId}/local_status";
@@ -20,6 +38,7 @@ pub const CYFS_KNOWN_ZONES_PATH: &str = "/data/known-zones";
 pub const CYFS_API_VIRTUAL_PATH: &str = "/.cyfs/api";
 pub const CYFS_HANDLER_VIRTUAL_PATH: &str = "/.cyfs/api/handler";
 pub const CYFS_CRYPTO_VIRTUAL_PATH: &str = "/.cyfs/api/crypto";
+pub const CYFS_RPC_VIRTUAL_PATH: &str = "/.cyfs/api/rpc";
 
 pub const CYFS_GLOBAL_STATE_VIRTUAL_PATH: &str = "/.cyfs/api/global_state";
 pub const CYFS_GLOBAL_STATE_ROOT_VIRTUAL_PATH: &str = "/.cyfs/api/global_state/root";
@@ -29,6 +48,10 @@ pub const CYFS_SYSTEM_VIRTUAL_PATH: &str = "/.cyfs/api/system";
 pub const CYFS_SYSTEM_ADMIN_VIRTUAL_PATH: &str = "/.cyfs/api/system/admin";
 pub const CYFS_SYSTEM_ROLE_VIRTUAL_PATH: &str = "/.cyfs/api/system/role";
 pub const CYFS_SYSTEM_APP_VIRTUAL_PATH: &str = "/.cyfs/api/system/app";
+pub const CYFS_SYSTEM_ZONE_EVENT_VIRTUAL_PATH: &str = "/.cyfs/api/system/zone_event";
+pub const CYFS_SYSTEM_DEVICE_HEALTH_VIRTUAL_PATH: &str = "/.cyfs/api/system/device_health";
+pub const CYFS_SYSTEM_CONTACTS_VIRTUAL_PATH: &str = "/.cyfs/api/system/contacts";
+pub const CYFS_SYSTEM_QUEUE_VIRTUAL_PATH: &str = "/.cyfs/api/system/queue";
 
 //App control cmds (e.g.: Start, Stop, Install, Uninstall)
 pub const CYFS_SYSTEM_APP_CMD_VIRTUAL_PATH: &str = "/.cyfs/api/system/app/cmd";
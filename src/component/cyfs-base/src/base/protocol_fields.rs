@@ -19,6 +19,17 @@ pub const CYFS_FILTER_DEC_ID: &str = "cyfs-filter-dec-id";
 pub const CYFS_REQ_PATH: &str = "cyfs-req-path";
 pub const CYFS_INNER_PATH: &str = "cyfs-inner-path";
 
+// request deadline in bucky time, the request will be aborted after it expired
+pub const CYFS_DEADLINE: &str = "cyfs-deadline";
+
+// 客户端生成的请求id，服务端据此对重试的请求去重
+pub const CYFS_REQUEST_ID: &str = "cyfs-request-id";
+
+// front服务把/a/{dec}/api/*的请求转发给dec本地服务时注入的请求来源，客户端携带的同名头会被移除
+pub const CYFS_SOURCE_DEVICE: &str = "cyfs-source-device";
+pub const CYFS_SOURCE_ZONE_CATEGORY: &str = "cyfs-source-zone-category";
+pub const CYFS_SOURCE_DEC_ID: &str = "cyfs-source-dec-id";
+
 pub const CYFS_CONTEXT: &str = "cyfs-context";
 pub const CYFS_TASK_GROUP: &str = "cyfs-task-group";
 
@@ -57,7 +68,10 @@ pub const CYFS_REFERER_OBJECT: &str = "cyfs-referer-object";
 pub const CYFS_FILTER_FLAGS: &str = "cyfs-filter-flags";
 pub const CYFS_REVISION: &str = "cyfs-revision";
 pub const CYFS_ROOT: &str = "cyfs-root";
+pub const CYFS_NEXT_CURSOR: &str = "cyfs-next-cursor";
+pub const CYFS_TOTAL_HINT: &str = "cyfs-total-hint";
 pub const CYFS_ACCESS: &str = "cyfs-access";
+pub const CYFS_PUT_PRECONDITION: &str = "cyfs-put-precondition";
 
 pub const CYFS_TIMEOUT: &str = "cyfs-timeout";
 
@@ -82,11 +96,18 @@ pub const CYFS_OBJECT_EXPIRES_TIME: &str = "cyfs-object-expires-time";
 // chunk 相关头部
 pub const CYFS_CHUNK_STATE: &str = "cyfs-chunk-state";
 pub const CYFS_CHUNK_EXIST: &str = "cyfs-chunk-exist";
+pub const CYFS_CHUNK_SIZE: &str = "cyfs-chunk-size";
 
 // gateway提供的来源信息头
 pub const CYFS_REMOTE_DEVICE: &str = "cyfs-remote-device";
 pub const CYFS_REMOTE_VPORT: &str = "cyfs-remote-vport";
 
+// 跨zone写请求的防重放签名
+pub const CYFS_REQUEST_NONCE: &str = "cyfs-request-nonce";
+pub const CYFS_REQUEST_TIMESTAMP: &str = "cyfs-request-timestamp";
+pub const CYFS_REQUEST_BODY_HASH: &str = "cyfs-request-body-hash";
+pub const CYFS_REQUEST_SIGN: &str = "cyfs-request-sign";
+
 // root_state
 pub const CYFS_ROOT_STATE_ACTION: &str = "cyfs-root-state-aciton";
 pub const CYFS_OP_ENV_ACTION: &str = "cyfs-op-env-action";
@@ -27,6 +27,10 @@ pub fn unix_time_to_bucky_time(time: u64) -> u64 {
 }
 
 pub fn bucky_time_now() -> u64 {
+    if let Some(now) = super::clock::clock_now() {
+        return now;
+    }
+
     system_time_to_bucky_time(&SystemTime::now())
 }
 
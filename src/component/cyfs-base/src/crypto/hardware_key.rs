@@ -0,0 +1,258 @@
+use crate::*;
+
+use async_trait::async_trait;
+use generic_array::GenericArray;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+// 硬件密钥的类型，对应配置里key handle的scheme部分
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum HardwareKeyType {
+    Pkcs11,
+    Tpm,
+    SecureEnclave,
+}
+
+impl HardwareKeyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pkcs11 => "pkcs11",
+            Self::Tpm => "tpm",
+            Self::SecureEnclave => "secure-enclave",
+        }
+    }
+}
+
+impl std::fmt::Display for HardwareKeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for HardwareKeyType {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        match s {
+            "pkcs11" => Ok(Self::Pkcs11),
+            "tpm" => Ok(Self::Tpm),
+            "secure-enclave" => Ok(Self::SecureEnclave),
+            _ => {
+                let msg = format!("unknown hardware key type: {}", s);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg))
+            }
+        }
+    }
+}
+
+// 配置中引用硬件密钥的句柄，格式为 {type}:{path}，比如
+// pkcs11:token=cyfs;object=device
+// tpm:0x81000001
+// secure-enclave:com.cyfs.device
+// path部分由对应的provider解释，这里不做校验
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HardwareKeyHandle {
+    pub key_type: HardwareKeyType,
+    pub path: String,
+}
+
+impl std::fmt::Display for HardwareKeyHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.key_type, self.path)
+    }
+}
+
+impl FromStr for HardwareKeyHandle {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        let (key_type, path) = s.split_once(':').ok_or_else(|| {
+            let msg = format!("invalid hardware key handle, should be type:path: {}", s);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        if path.is_empty() {
+            let msg = format!("invalid hardware key handle, empty path: {}", s);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+
+        Ok(Self {
+            key_type: HardwareKeyType::from_str(key_type)?,
+            path: path.to_owned(),
+        })
+    }
+}
+
+// 私钥保存在硬件中的密钥，只对外提供摘要签名
+// rsa密钥返回 PKCS#1 v1.5(SHA-256) 格式的签名，secp256k1密钥返回64字节的 r||s
+#[async_trait]
+pub trait HardwareKey: Send + Sync {
+    fn handle(&self) -> &HardwareKeyHandle;
+    fn public_key(&self) -> &PublicKey;
+    async fn sign_digest(&self, digest: &HashValue) -> BuckyResult<Vec<u8>>;
+}
+
+// 具体的硬件后端(PKCS#11模块，TPM，secure enclave)由宿主程序实现并注册
+pub trait HardwareKeyProvider: Send + Sync {
+    fn key_type(&self) -> HardwareKeyType;
+    fn open(&self, handle: &HardwareKeyHandle) -> BuckyResult<Arc<dyn HardwareKey>>;
+}
+
+lazy_static::lazy_static! {
+    static ref HARDWARE_KEY_PROVIDERS: RwLock<HashMap<HardwareKeyType, Arc<dyn HardwareKeyProvider>>> = RwLock::new(HashMap::new());
+}
+
+pub fn register_hardware_key_provider(provider: Arc<dyn HardwareKeyProvider>) {
+    let key_type = provider.key_type();
+    info!("register hardware key provider: {}", key_type);
+
+    if let Some(_) = HARDWARE_KEY_PROVIDERS
+        .write()
+        .unwrap()
+        .insert(key_type, provider)
+    {
+        warn!("hardware key provider replaced: {}", key_type);
+    }
+}
+
+pub fn open_hardware_key(handle: &HardwareKeyHandle) -> BuckyResult<Arc<dyn HardwareKey>> {
+    let provider = HARDWARE_KEY_PROVIDERS
+        .read()
+        .unwrap()
+        .get(&handle.key_type)
+        .cloned()
+        .ok_or_else(|| {
+            let msg = format!("hardware key provider not registered: {}", handle);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotSupport, msg)
+        })?;
+
+    let key = provider.open(handle)?;
+    info!(
+        "open hardware key success: handle={}, key_type={}",
+        handle,
+        key.public_key().key_type_str()
+    );
+
+    Ok(key)
+}
+
+// 按硬件返回的原始签名长度转换为SignData
+pub(crate) fn sign_data_from_raw(public_key: &PublicKey, sign: &[u8]) -> BuckyResult<SignData> {
+    let expect_len = match public_key {
+        PublicKey::Rsa(_) => public_key.key_size(),
+        PublicKey::Secp256k1(_) => 64,
+        PublicKey::Invalid => {
+            let msg = "invalid public key for hardware sign".to_owned();
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+    };
+    if sign.len() != expect_len {
+        let msg = format!(
+            "invalid hardware sign length! except={}, got={}",
+            expect_len,
+            sign.len()
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+    }
+
+    // 和PrivateKey::sign里的memcpy一致，按本机字节序转换
+    let words: Vec<u32> = sign
+        .chunks(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+
+    let sign_data = match (public_key, sign.len()) {
+        (PublicKey::Secp256k1(_), _) => SignData::Ecc(GenericArray::clone_from_slice(&words)),
+        (_, 128) => SignData::Rsa1024(GenericArray::clone_from_slice(&words)),
+        (_, 256) => SignData::Rsa2048(GenericArray::clone_from_slice(&words)),
+        (_, 384) => SignData::Rsa3072(GenericArray::clone_from_slice(&words)),
+        (_, len) => {
+            let msg = format!("unsupport rsa key length! {}", len);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+        }
+    };
+
+    Ok(sign_data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hardware_key_handle() {
+        let handle = HardwareKeyHandle::from_str("pkcs11:token=cyfs;object=device").unwrap();
+        assert_eq!(handle.key_type, HardwareKeyType::Pkcs11);
+        assert_eq!(handle.path, "token=cyfs;object=device");
+        assert_eq!(handle.to_string(), "pkcs11:token=cyfs;object=device");
+
+        let handle = HardwareKeyHandle::from_str("tpm:0x81000001").unwrap();
+        assert_eq!(handle.key_type, HardwareKeyType::Tpm);
+
+        assert!(HardwareKeyHandle::from_str("device.sec").is_err());
+        assert!(HardwareKeyHandle::from_str("hsm:slot0").is_err());
+        assert!(HardwareKeyHandle::from_str("tpm:").is_err());
+    }
+
+    // 用内存中的rsa私钥模拟硬件，只暴露摘要签名
+    struct SoftHardwareKey {
+        handle: HardwareKeyHandle,
+        public_key: PublicKey,
+        secret: PrivateKey,
+    }
+
+    #[async_trait]
+    impl HardwareKey for SoftHardwareKey {
+        fn handle(&self) -> &HardwareKeyHandle {
+            &self.handle
+        }
+
+        fn public_key(&self) -> &PublicKey {
+            &self.public_key
+        }
+
+        async fn sign_digest(&self, digest: &HashValue) -> BuckyResult<Vec<u8>> {
+            match &self.secret {
+                PrivateKey::Rsa(secret) => Ok(secret.sign(
+                    rsa::PaddingScheme::new_pkcs1v15_sign(Some(rsa::Hash::SHA2_256)),
+                    digest.as_slice(),
+                )?),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_hardware_key_sign() {
+        let secret = PrivateKey::generate_rsa(1024).unwrap();
+        let public_key = secret.public();
+        let key = SoftHardwareKey {
+            handle: HardwareKeyHandle::from_str("pkcs11:token=test").unwrap(),
+            public_key: public_key.clone(),
+            secret,
+        };
+
+        let signer = RsaCPUObjectSigner::new_with_hardware_key(Arc::new(key));
+        assert!(signer.hardware_key().is_some());
+
+        async_std::task::block_on(async move {
+            let data = b"hardware key sign test";
+            let sign = signer
+                .sign(data, &SignatureSource::RefIndex(0))
+                .await
+                .unwrap();
+
+            let verifier = RsaCPUObjectVerifier::new(public_key);
+            assert!(verifier.verify(data, &sign).await);
+            assert!(!verifier.verify(b"other data", &sign).await);
+        });
+    }
+}
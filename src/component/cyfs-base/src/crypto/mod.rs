@@ -1,4 +1,5 @@
 mod aes;
+mod hardware_key;
 mod hash;
 mod hash_util;
 mod private_key;
@@ -10,6 +11,7 @@ mod verifier;
 mod verifier_util;
 
 pub use self::aes::*;
+pub use hardware_key::*;
 pub use hash::*;
 pub use hash_util::*;
 pub use private_key::*;
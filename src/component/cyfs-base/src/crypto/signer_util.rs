@@ -1,17 +1,43 @@
 use crate::*;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 
 // 提供一个默认签名器实现
 
+#[derive(Clone)]
+enum SignerSecret {
+    Soft(PrivateKey),
+    Hardware(Arc<dyn HardwareKey>),
+}
+
+#[derive(Clone)]
 pub struct RsaCPUObjectSigner {
     public_key: PublicKey,
-    secret: PrivateKey,
+    secret: SignerSecret,
 }
 
 impl RsaCPUObjectSigner {
     pub fn new(public_key: PublicKey, secret: PrivateKey) -> Self {
-        RsaCPUObjectSigner { public_key, secret }
+        RsaCPUObjectSigner {
+            public_key,
+            secret: SignerSecret::Soft(secret),
+        }
+    }
+
+    // 私钥保存在PKCS#11/TPM/secure enclave中，签名时只把摘要交给硬件
+    pub fn new_with_hardware_key(key: Arc<dyn HardwareKey>) -> Self {
+        RsaCPUObjectSigner {
+            public_key: key.public_key().clone(),
+            secret: SignerSecret::Hardware(key),
+        }
+    }
+
+    pub fn hardware_key(&self) -> Option<&HardwareKeyHandle> {
+        match &self.secret {
+            SignerSecret::Soft(_) => None,
+            SignerSecret::Hardware(key) => Some(key.handle()),
+        }
     }
 }
 
@@ -22,7 +48,24 @@ impl Signer for RsaCPUObjectSigner {
     }
 
     async fn sign(&self, data: &[u8], sign_source: &SignatureSource) -> BuckyResult<Signature> {
-        self.secret.sign(data, sign_source.clone())
+        match &self.secret {
+            SignerSecret::Soft(secret) => secret.sign(data, sign_source.clone()),
+            SignerSecret::Hardware(key) => {
+                // 和PrivateKey::sign保持一致，签名内容包含签名时刻
+                let create_time = bucky_time_now();
+                let mut data_new = data.to_vec();
+                data_new.extend_from_slice(&create_time.to_vec()?);
+
+                let hash = hash_data(&data_new);
+                let sign = key.sign_digest(&hash).await.map_err(|e| {
+                    error!("sign with hardware key error! key={}, {}", key.handle(), e);
+                    e
+                })?;
+                let sign_data = sign_data_from_raw(&self.public_key, &sign)?;
+
+                Ok(Signature::new(sign_source.clone(), 0, create_time, sign_data))
+            }
+        }
     }
 }
 
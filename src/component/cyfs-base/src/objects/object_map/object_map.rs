@@ -959,6 +959,12 @@ pub struct ObjectMapHubItem {
     id: ObjectId,
 }
 
+impl ObjectMapHubItem {
+    pub fn id(&self) -> &ObjectId {
+        &self.id
+    }
+}
+
 #[derive(Clone, Debug, RawEncode, RawDecode)]
 pub struct ObjectMapHubContent {
     depth: u8,
@@ -189,10 +189,9 @@ impl TransContextHolderInner {
         filter: &DownloadSourceFilter,
         limit: usize,
     ) -> (LinkedList<DownloadSource<DeviceDesc>>, Timestamp) {
-        let mut result = LinkedList::new();
         let ret = self.get_context().await;
         if ret.is_none() {
-            return (result, 0);
+            return (LinkedList::new(), 0);
         }
 
         let context = ret.unwrap();
@@ -200,16 +199,13 @@ impl TransContextHolderInner {
             .object
             .body_expect("context object should has body!")
             .update_time();
-        let mut count = 0;
-        for source in &context.source_list {
-            if filter.check(source) {
-                result.push_back(source.clone());
-                count += 1;
-                if count >= limit {
-                    return (result, ts);
-                }
-            }
-        }
+        let ts = std::cmp::max(ts, self.state.scores().changed_at());
+
+        // sources are sorted by observed score, and the bad ones are pruned
+        let result = self
+            .state
+            .scores()
+            .select(&context.source_list, filter, limit);
 
         (result, ts)
     }
@@ -254,7 +250,8 @@ impl TransContextHolderInner {
                     .body_expect("context object should has body!")
                     .update_time();
 
-                ts
+                // source's state changed will also trigger the downloader to requery the sources
+                std::cmp::max(ts, self.state.scores().changed_at())
             }
         }
     }
@@ -373,4 +370,8 @@ impl DownloadContext for TransContextHolder {
     fn on_drain(&self, task: &dyn LeafDownloadTask, when: Timestamp) {
         self.0.state.on_drain(task, when);
     }
+
+    fn source_stats(&self) -> Vec<DownloadSourceStat> {
+        self.0.state.scores().stats()
+    }
 }
@@ -1,6 +1,7 @@
 mod manager;
 mod context;
 mod state;
+mod score;
 
 pub use manager::*;
 pub use context::*;
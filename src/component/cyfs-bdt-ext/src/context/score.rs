@@ -0,0 +1,277 @@
+use cyfs_base::*;
+use cyfs_bdt::channel::DownloadSessionState;
+use cyfs_bdt::*;
+
+use std::collections::{HashMap, LinkedList};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Failed source will not be used again until backoff expired, the interval doubles on each continuous failure
+const SOURCE_RETRY_MIN_INTERVAL: Duration = Duration::from_secs(10);
+const SOURCE_RETRY_MAX_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
+// Source with high error rate or very low throughput compared to the best one will be pruned
+const SOURCE_PRUNE_MIN_SESSIONS: u32 = 3;
+const SOURCE_PRUNE_ERROR_RATE: f64 = 0.6;
+const SOURCE_PRUNE_THROUGHPUT_RATIO: f64 = 0.1;
+
+#[derive(Default)]
+struct SourceScore {
+    sessions: u32,
+    finished: u32,
+    failed: u32,
+    continuous_failed: u32,
+
+    downloaded: u64,
+    download_time: Duration,
+
+    retry_at: Option<Timestamp>,
+}
+
+impl SourceScore {
+    fn throughput(&self) -> u32 {
+        let secs = self.download_time.as_secs_f64();
+        if self.finished == 0 || secs <= 0.0 {
+            return 0;
+        }
+
+        (self.downloaded as f64 / secs) as u32
+    }
+
+    fn error_rate(&self) -> f64 {
+        let completed = self.finished + self.failed;
+        if completed == 0 {
+            return 0.0;
+        }
+
+        self.failed as f64 / completed as f64
+    }
+
+    fn in_backoff(&self, now: Timestamp) -> bool {
+        match self.retry_at {
+            Some(retry_at) => retry_at > now,
+            None => false,
+        }
+    }
+
+    // Untried source has a neutral score, so it will be tried before sources known to be bad
+    fn score(&self, best_throughput: u32) -> u32 {
+        let success_rate =
+            (self.finished as f64 + 1.0) / ((self.finished + self.failed) as f64 + 2.0);
+        let throughput_rate = if best_throughput == 0 || self.finished == 0 {
+            0.5
+        } else {
+            self.throughput() as f64 / best_throughput as f64
+        };
+
+        (100.0 * success_rate * (0.5 + 0.5 * throughput_rate)) as u32
+    }
+
+    fn is_pruned(&self, best_throughput: u32) -> bool {
+        if self.finished + self.failed < SOURCE_PRUNE_MIN_SESSIONS {
+            return false;
+        }
+
+        if self.error_rate() >= SOURCE_PRUNE_ERROR_RATE {
+            return true;
+        }
+
+        self.finished > 0
+            && best_throughput > 0
+            && (self.throughput() as f64) < best_throughput as f64 * SOURCE_PRUNE_THROUGHPUT_RATIO
+    }
+}
+
+struct SourceScoreList {
+    list: HashMap<DeviceId, SourceScore>,
+
+    // Bumped when the usable source set changed, so chunk downloaders will requery the context
+    changed_at: Timestamp,
+}
+
+impl SourceScoreList {
+    fn best_throughput(&self) -> u32 {
+        self.list.values().map(|item| item.throughput()).max().unwrap_or(0)
+    }
+
+    fn is_usable(&self, target: &DeviceId, now: Timestamp, best_throughput: u32) -> bool {
+        match self.list.get(target) {
+            Some(item) => !item.in_backoff(now) && !item.is_pruned(best_throughput),
+            None => true,
+        }
+    }
+
+    fn score_of(&self, target: &DeviceId, best_throughput: u32) -> u32 {
+        match self.list.get(target) {
+            Some(item) => item.score(best_throughput),
+            None => SourceScore::default().score(best_throughput),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct ContextSourceScoreManager(Arc<Mutex<SourceScoreList>>);
+
+impl ContextSourceScoreManager {
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(SourceScoreList {
+            list: HashMap::new(),
+            changed_at: 0,
+        })))
+    }
+
+    pub fn on_session_start(&self, target: &DeviceId) {
+        let mut scores = self.0.lock().unwrap();
+        scores.list.entry(target.to_owned()).or_default().sessions += 1;
+    }
+
+    pub fn on_session_finished(
+        &self,
+        target: &DeviceId,
+        chunk: &ChunkId,
+        state: &DownloadSessionState,
+        elapsed: Duration,
+    ) {
+        let mut scores = self.0.lock().unwrap();
+        let best_throughput = scores.best_throughput();
+        let item = scores.list.entry(target.to_owned()).or_default();
+        let pruned = item.is_pruned(best_throughput);
+
+        match state {
+            DownloadSessionState::Finished => {
+                item.finished += 1;
+                item.continuous_failed = 0;
+                item.downloaded += chunk.len() as u64;
+                item.download_time += elapsed;
+                item.retry_at = None;
+            }
+            DownloadSessionState::Canceled(e) => {
+                // Canceled by the task itself or context updated, not the source's fault
+                match e.code() {
+                    BuckyErrorCode::UserCanceled | BuckyErrorCode::Interrupted => return,
+                    _ => {}
+                }
+
+                item.failed += 1;
+                item.continuous_failed += 1;
+
+                let interval = SOURCE_RETRY_MIN_INTERVAL
+                    .saturating_mul(1 << std::cmp::min(item.continuous_failed - 1, 16))
+                    .min(SOURCE_RETRY_MAX_INTERVAL);
+                let retry_at = bucky_time_now() + interval.as_micros() as u64;
+                item.retry_at = Some(retry_at);
+
+                warn!(
+                    "download source failed and will retry later! source={}, chunk={}, failed={}, retry after {:?}, {}",
+                    target, chunk, item.failed, interval, e
+                );
+            }
+            DownloadSessionState::Downloading => return,
+        }
+
+        let changed = item.retry_at.is_some() || pruned != item.is_pruned(best_throughput);
+        if changed {
+            scores.changed_at = bucky_time_now();
+        }
+    }
+
+    // Also check and notify the sources whose backoff expired
+    pub fn changed_at(&self) -> Timestamp {
+        let mut scores = self.0.lock().unwrap();
+        let now = bucky_time_now();
+
+        let mut expired = false;
+        for (target, item) in scores.list.iter_mut() {
+            if let Some(retry_at) = item.retry_at {
+                if retry_at <= now {
+                    info!("download source backoff expired, now can retry! source={}", target);
+                    item.retry_at = None;
+                    expired = true;
+                }
+            }
+        }
+
+        if expired {
+            scores.changed_at = now;
+        }
+
+        scores.changed_at
+    }
+
+    // Sort the sources by score, the sources in backoff or pruned will be skipped if there are usable alternatives.
+    // all_sources is all the sources in context, used to check if there are alternatives out of the filter
+    pub fn select(
+        &self,
+        all_sources: &[DownloadSource<DeviceDesc>],
+        filter: &DownloadSourceFilter,
+        limit: usize,
+    ) -> LinkedList<DownloadSource<DeviceDesc>> {
+        let scores = self.0.lock().unwrap();
+        let now = bucky_time_now();
+        let best_throughput = scores.best_throughput();
+
+        let mut candidates: Vec<(u32, bool, &DownloadSource<DeviceDesc>)> = all_sources
+            .iter()
+            .filter(|source| filter.check(source))
+            .map(|source| {
+                let target = source.target.device_id();
+                (
+                    scores.score_of(&target, best_throughput),
+                    scores.is_usable(&target, now, best_throughput),
+                    source,
+                )
+            })
+            .collect();
+
+        let has_usable = candidates.iter().any(|(_, usable, _)| *usable);
+        // Only check alternatives when the downloader is checking its current source
+        let has_alternative = filter.include_target.is_some()
+            && all_sources.iter().any(|source| {
+                let target = source.target.device_id();
+                let excluded = filter
+                    .exclude_target
+                    .as_ref()
+                    .map(|list| list.contains(&target))
+                    .unwrap_or(false);
+                !excluded && scores.is_usable(&target, now, best_throughput)
+            });
+
+        if has_usable {
+            candidates.retain(|(_, usable, _)| *usable);
+        } else if has_alternative {
+            // Current sources are all bad but there are better ones out of the filter,
+            // return empty so the downloader will drop the current source and requery
+            candidates.clear();
+        }
+
+        candidates.sort_by(|left, right| right.0.cmp(&left.0));
+
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, source)| source.clone())
+            .collect()
+    }
+
+    pub fn stats(&self) -> Vec<DownloadSourceStat> {
+        let scores = self.0.lock().unwrap();
+        let now = bucky_time_now();
+        let best_throughput = scores.best_throughput();
+
+        scores
+            .list
+            .iter()
+            .map(|(target, item)| DownloadSourceStat {
+                target: target.to_owned(),
+                sessions: item.sessions,
+                finished: item.finished,
+                failed: item.failed,
+                downloaded: item.downloaded,
+                throughput: item.throughput(),
+                score: item.score(best_throughput),
+                retry_at: item.retry_at.filter(|retry_at| *retry_at > now),
+                pruned: item.is_pruned(best_throughput),
+            })
+            .collect()
+    }
+}
@@ -1,3 +1,4 @@
+use super::score::ContextSourceScoreManager;
 use cyfs_base::*;
 use cyfs_bdt::channel::DownloadSessionState;
 use cyfs_bdt::ndn::channel::DownloadSession;
@@ -37,7 +38,8 @@ struct ContextSourceDownloadState {
 pub(super) struct ContextSourceDownloadStateManager {
     all: Arc<Mutex<HashMap<ContextSourceIndex, ContextSourceDownloadState>>>,
     cancel_strategy: NDNTaskCancelStrategy,
-    cancel_source_strategy: NDNTaskCancelSourceStrategy
+    cancel_source_strategy: NDNTaskCancelSourceStrategy,
+    scores: ContextSourceScoreManager,
 }
 
 impl ContextSourceDownloadStateManager {
@@ -48,10 +50,15 @@ impl ContextSourceDownloadStateManager {
         Self {
             all: Arc::new(Mutex::new(HashMap::new())),
             cancel_strategy,
-            cancel_source_strategy
+            cancel_source_strategy,
+            scores: ContextSourceScoreManager::new(),
         }
     }
 
+    pub fn scores(&self) -> &ContextSourceScoreManager {
+        &self.scores
+    }
+
     pub fn on_new_session(
         &self,
         task: &dyn LeafDownloadTask,
@@ -59,11 +66,13 @@ impl ContextSourceDownloadStateManager {
         update_at: Timestamp,
     ) {
         self.add_session(task, session, update_at);
+        self.scores.on_session_start(&session.source().target);
 
         let this = self.clone();
         let session = session.clone();
         let group = task.abs_group_path();
         async_std::task::spawn(async move {
+            let start = std::time::Instant::now();
             let state =  match this.cancel_source_strategy {
                 NDNTaskCancelSourceStrategy::ZeroSpeed(atomic, timeout) => {
                     {
@@ -104,6 +113,12 @@ impl ContextSourceDownloadStateManager {
                 }, 
                 NDNTaskCancelSourceStrategy::None => session.wait_finish().await
             };
+            this.scores.on_session_finished(
+                &session.source().target,
+                session.chunk(),
+                &state,
+                start.elapsed(),
+            );
             this.on_session_finished(&session, state, group);
         });
     }
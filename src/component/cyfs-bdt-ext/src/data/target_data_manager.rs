@@ -2,9 +2,48 @@ use crate::*;
 use cyfs_base::*;
 use cyfs_lib::*;
 
+use async_std::channel::Sender;
 use async_std::io::Read;
-use futures::AsyncReadExt;
+use cyfs_bdt::{LeafDownloadTask, NdnTask, NdnTaskState};
+use futures::{AsyncReadExt, FutureExt};
 use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// notify the download task watcher when the response is read to the end; if it's dropped before that,
+// the watcher's channel is closed and the requester is treated as gone away
+struct DownloadTaskReader {
+    reader: Box<dyn Read + Unpin + Send + Sync + 'static>,
+    watcher: Option<Sender<()>>,
+}
+
+impl DownloadTaskReader {
+    fn new(reader: Box<dyn Read + Unpin + Send + Sync + 'static>, watcher: Sender<()>) -> Self {
+        Self {
+            reader,
+            watcher: Some(watcher),
+        }
+    }
+}
+
+impl Read for DownloadTaskReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let ret = Pin::new(self.reader.as_mut()).poll_read(cx, buf);
+        if let Poll::Ready(Ok(0)) = &ret {
+            if buf.len() > 0 {
+                if let Some(watcher) = self.watcher.take() {
+                    let _ = watcher.try_send(());
+                }
+            }
+        }
+
+        ret
+    }
+}
 
 // 用以向远程device发起chunk/file操作
 pub struct TargetDataManager {
@@ -36,6 +75,7 @@ impl TargetDataManager {
         file_obj: &File,
         group: Option<&str>,
         ranges: Option<Vec<Range<u64>>>,
+        deadline: Option<u64>,
     ) -> BuckyResult<(
         Box<dyn Read + Unpin + Send + Sync + 'static>,
         u64,
@@ -80,6 +120,8 @@ impl TargetDataManager {
             reader.task().abs_group_path(),
         );
 
+        let watcher = Self::watch_task(reader.task().clone_as_leaf_task(), deadline);
+
         let resp = if self.need_cache {
             let reader = ChunkListCacheReader::new(
                 self.named_data_components.clone(),
@@ -110,6 +152,7 @@ impl TargetDataManager {
             }
         };
         
+        let resp = Box::new(DownloadTaskReader::new(resp, watcher));
         let resp = Self::wait_read_and_return(resp).await?;
 
         Ok((resp, total_size, Some(id)))
@@ -122,6 +165,7 @@ impl TargetDataManager {
         chunk_id: &ChunkId,
         group: Option<&str>,
         ranges: Option<Vec<Range<u64>>>,
+        deadline: Option<u64>,
     ) -> BuckyResult<(
         Box<dyn Read + Unpin + Send + Sync + 'static>,
         u64,
@@ -163,6 +207,8 @@ impl TargetDataManager {
             reader.task().abs_group_path(),
         );
 
+        let watcher = Self::watch_task(reader.task().clone_as_leaf_task(), deadline);
+
         let resp = if self.need_cache {
             let reader = ChunkListCacheReader::new(
                 self.named_data_components.clone(),
@@ -192,11 +238,58 @@ impl TargetDataManager {
             }
         };
         
+        let resp = Box::new(DownloadTaskReader::new(resp, watcher));
         let resp = Self::wait_read_and_return(resp).await?;
 
         Ok((resp, total_size as u64, Some(id)))
     }
 
+    // cancel the download task if it's still running after the request's deadline,
+    // or if the reader is dropped before reaching the end (eg. the http connection is closed)
+    fn watch_task(task: Box<dyn LeafDownloadTask>, deadline: Option<u64>) -> Sender<()> {
+        let (tx, rx) = async_std::channel::bounded(1);
+        let dur = RequestDeadline::remaining(deadline);
+
+        async_std::task::spawn(async move {
+            let expired = async move {
+                match dur {
+                    Some(dur) => async_std::task::sleep(dur).await,
+                    None => futures::future::pending::<()>().await,
+                }
+            };
+
+            let (code, reason) =
+                match futures::future::select(rx.recv().boxed(), expired.boxed()).await {
+                    futures::future::Either::Left((Ok(_), _)) => {
+                        // the reader has reached the end
+                        return;
+                    }
+                    futures::future::Either::Left((Err(_), _)) => {
+                        (BuckyErrorCode::Aborted, "reader dropped")
+                    }
+                    futures::future::Either::Right(_) => {
+                        (BuckyErrorCode::Timeout, "request deadline expired")
+                    }
+                };
+
+            match task.state() {
+                NdnTaskState::Running | NdnTaskState::Paused => {
+                    warn!(
+                        "download task still not finished, now will cancel! task={}, reason={}",
+                        task, reason
+                    );
+                    let e = BuckyError::new(code, reason);
+                    if let Err(e) = task.cancel_by_error(e) {
+                        error!("cancel download task failed! task={}, {}", task, e);
+                    }
+                }
+                _ => {}
+            }
+        });
+
+        tx
+    }
+
     async fn wait_read_and_return(mut resp: Box<dyn Read + Unpin + Send + Sync + 'static>,) -> BuckyResult<Box<dyn Read + Unpin + Send + Sync + 'static>> {
         let mut buf = vec![0; 1];
         resp.read_exact(&mut buf).await.map_err(|e| {
@@ -58,4 +58,7 @@ pub struct BdtStackParams {
 
     // sn ping interval in seconds, default is 25s
     pub ping_interval: Option<u32>,
+
+    // persist tunnel session keys to resume tunnels after a quick restart, default is false
+    pub session_resume: Option<bool>,
 }
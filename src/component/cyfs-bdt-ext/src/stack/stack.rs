@@ -57,6 +57,26 @@ impl BdtStackHelper {
         }
     }
 
+    fn init_session_path(isolate: &str) -> BuckyResult<std::path::PathBuf> {
+        let dir = cyfs_util::get_cyfs_root_path().join("data");
+        let dir = if isolate.len() > 0 {
+            dir.join(isolate)
+        } else {
+            dir
+        };
+        let dir = dir.join("bdt");
+
+        if !dir.is_dir() {
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                let msg = format!("create bdt session dir error! dir={}, err={}", dir.display(), e);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+            }
+        }
+
+        Ok(dir.join("tunnel-session"))
+    }
+
     pub async fn init_bdt_stack(
         params: BdtStackParams,
         device_cache: Box<dyn DeviceCache>,
@@ -80,6 +100,10 @@ impl BdtStackHelper {
             bdt_params.config.sn_client.ping.interval = std::time::Duration::from_secs(ping_interval as u64);
         }
 
+        if params.session_resume.unwrap_or(false) {
+            bdt_params.config.session.path = Some(Self::init_session_path(isolate)?);
+        }
+
         // select sn_list via the sn_mode config
         let wait_online;
         let sn_list = match params.sn_mode {
@@ -0,0 +1,139 @@
+use once_cell::sync::OnceCell;
+use cyfs_base::*;
+use crate::{
+    types::*,
+    protocol::*
+};
+
+// 抓包记录的格式版本，写在每条记录的第一个字节
+const CAPTURE_RECORD_VERSION: u8 = 0;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CaptureDirection {
+    In = 0,
+    Out = 1
+}
+
+// 解密之后的package box头部信息，只记录包类型，不记录负载
+#[derive(Clone, Debug)]
+pub struct CapturedPackageBox {
+    pub direction: CaptureDirection,
+    pub local: Endpoint,
+    pub remote: Endpoint,
+    pub remote_device: DeviceId,
+    // (协议版本, cmd code)
+    pub packages: Vec<(u8, u8)>
+}
+
+impl CapturedPackageBox {
+    pub(crate) fn from_box(direction: CaptureDirection, local: &Endpoint, remote: &Endpoint, package_box: &PackageBox) -> Self {
+        Self {
+            direction,
+            local: *local,
+            remote: *remote,
+            remote_device: package_box.remote().clone(),
+            packages: package_box.packages().iter().map(|p| (p.version(), p.cmd_code() as u8)).collect()
+        }
+    }
+
+    pub fn to_vec(&self) -> BuckyResult<Vec<u8>> {
+        let mut buf = vec![CAPTURE_RECORD_VERSION, self.direction as u8];
+        buf.append(&mut self.local.to_vec()?);
+        buf.append(&mut self.remote.to_vec()?);
+        buf.append(&mut self.remote_device.to_vec()?);
+        buf.push(self.packages.len() as u8);
+        for (version, cmd_code) in &self.packages {
+            buf.push(*version);
+            buf.push(*cmd_code);
+        }
+        Ok(buf)
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> BuckyResult<Self> {
+        if buf.len() < 2 {
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, "capture record too short"));
+        }
+        if buf[0] != CAPTURE_RECORD_VERSION {
+            return Err(BuckyError::new(BuckyErrorCode::NotSupport, format!("unknown capture record version {}", buf[0])));
+        }
+        let direction = match buf[1] {
+            0 => CaptureDirection::In,
+            1 => CaptureDirection::Out,
+            v @ _ => return Err(BuckyError::new(BuckyErrorCode::InvalidData, format!("invalid capture direction {}", v)))
+        };
+        let (local, buf) = Endpoint::raw_decode(&buf[2..])?;
+        let (remote, buf) = Endpoint::raw_decode(buf)?;
+        let (remote_device, buf) = DeviceId::raw_decode(buf)?;
+        let (count, buf) = u8::raw_decode(buf)?;
+        if buf.len() < count as usize * 2 {
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, "capture record too short"));
+        }
+        let packages = buf.chunks(2).take(count as usize).map(|c| (c[0], c[1])).collect();
+
+        Ok(Self {
+            direction,
+            local,
+            remote,
+            remote_device,
+            packages
+        })
+    }
+}
+
+impl std::fmt::Display for CapturedPackageBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let arrow = match self.direction {
+            CaptureDirection::In => "<-",
+            CaptureDirection::Out => "->"
+        };
+        write!(f, "{} {} {} remote_device={} packages=[", self.local, arrow, self.remote, self.remote_device)?;
+        for (i, (version, cmd_code)) in self.packages.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match PackageCmdCode::try_from(*cmd_code) {
+                Ok(cmd_code) => write!(f, "{:?}(v{})", cmd_code, version)?,
+                Err(_) => write!(f, "Unknown({:#x})(v{})", cmd_code, version)?
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+pub trait PackageCapture: Send + Sync {
+    fn capture(&self, package_box: CapturedPackageBox);
+}
+
+static PACKAGE_CAPTURE: OnceCell<Box<dyn PackageCapture>> = OnceCell::new();
+
+// 进程内只能设置一次，对所有stack的udp package box生效
+pub fn set_package_capture(capture: Box<dyn PackageCapture>) -> BuckyResult<()> {
+    PACKAGE_CAPTURE.set(capture)
+        .map_err(|_| BuckyError::new(BuckyErrorCode::AlreadyExists, "package capture already set"))
+}
+
+pub(crate) fn capture_package_box(direction: CaptureDirection, local: &Endpoint, remote: &Endpoint, package_box: &PackageBox) {
+    if let Some(capture) = PACKAGE_CAPTURE.get() {
+        capture.capture(CapturedPackageBox::from_box(direction, local, remote, package_box));
+    }
+}
+
+#[test]
+fn captured_package_box_codec() {
+    use std::str::FromStr;
+
+    let record = CapturedPackageBox {
+        direction: CaptureDirection::Out,
+        local: Endpoint::from_str("L4udp192.168.1.1:8050").unwrap(),
+        remote: Endpoint::from_str("W4udp1.2.3.4:8060").unwrap(),
+        remote_device: DeviceId::default(),
+        packages: vec![(0, PackageCmdCode::Exchange as u8), (0, PackageCmdCode::SynTunnel as u8)]
+    };
+    let buf = record.to_vec().unwrap();
+    let decoded = CapturedPackageBox::from_bytes(&buf).unwrap();
+    assert_eq!(decoded.direction, record.direction);
+    assert_eq!(decoded.local, record.local);
+    assert_eq!(decoded.remote, record.remote);
+    assert_eq!(decoded.packages, record.packages);
+    assert!(decoded.to_string().contains("SynTunnel(v0)"));
+}
@@ -1,7 +1,12 @@
 mod command;
 mod stub;
 mod ping;
+mod probe;
+mod capture;
 
 pub use command::{debug_command_line, DebugCommand};
 pub use stub::{DebugStub, Config};
 pub use ping::{PingStub, Pinger};
+pub use probe::{Prober, ProbeResult};
+pub use capture::{CaptureDirection, CapturedPackageBox, PackageCapture, set_package_capture};
+pub(crate) use capture::capture_package_box;
@@ -0,0 +1,135 @@
+use cyfs_base::*;
+use crate::{
+    stack::{
+        WeakStack,
+        Stack
+    },
+    datagram::{
+        self,
+        DatagramOptions,
+        DatagramTunnelGuard
+    },
+    tunnel::{
+        TunnelState,
+        ProxyType,
+        BuildTunnelTraceItem
+    },
+    types::*,
+};
+use async_std::{
+    sync::Arc,
+    future,
+};
+use std::time::Duration;
+use std::io::ErrorKind;
+
+pub struct ProbeResult {
+    pub remote: DeviceId,
+    // 建立tunnel的每一步，包括尝试的endpoint，sn call和proxy
+    pub trace: Vec<BuildTunnelTraceItem>,
+    // 每个udp endpoint pair上的打洞次数
+    pub hole_punch: Vec<(EndpointPair, u32)>,
+    // 最终选中的tunnel，超时或者失败时为None
+    pub tunnel: Option<(EndpointPair, ProxyType)>,
+    pub elapsed: Duration
+}
+
+impl ProbeResult {
+    pub fn tunnel_type(&self) -> &'static str {
+        match &self.tunnel {
+            Some((ep_pair, proxy)) => {
+                if *proxy != ProxyType::None {
+                    "udp-proxy"
+                } else if ep_pair.is_udp() {
+                    "udp"
+                } else if ep_pair.is_reverse_tcp() {
+                    "reverse-tcp"
+                } else {
+                    "tcp"
+                }
+            },
+            None => "none"
+        }
+    }
+}
+
+impl std::fmt::Display for ProbeResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "probe {}", self.remote)?;
+        for item in &self.trace {
+            writeln!(f, "  {}", item)?;
+        }
+        for (ep_pair, attempts) in &self.hole_punch {
+            writeln!(f, "  hole punch on {} {} times", ep_pair, attempts)?;
+        }
+        match &self.tunnel {
+            Some((ep_pair, proxy)) => write!(f, "tunnel {} on {}, proxy {:?}, cost {}ms", self.tunnel_type(), ep_pair, proxy, self.elapsed.as_millis()),
+            None => write!(f, "tunnel not established in {}ms", self.elapsed.as_millis())
+        }
+    }
+}
+
+struct ProberImpl {
+    stack: WeakStack,
+    datagram_tunnel: DatagramTunnelGuard,
+}
+
+// 类似traceroute，重新建立到对端的tunnel并记录建立过程
+#[derive(Clone)]
+pub struct Prober(Arc<ProberImpl>);
+
+impl Prober {
+    pub fn open(weak_stack: WeakStack) -> BuckyResult<Self> {
+        let stack = Stack::from(&weak_stack);
+        let datagram_tunnel = stack.datagram_manager().bind(0)
+            .map_err(|err| format!("bind datagram tunnel failed for {}", err))?;
+
+        Ok(Self(Arc::new(ProberImpl {
+            stack: weak_stack,
+            datagram_tunnel,
+        })))
+    }
+
+    pub async fn probe(&self, remote: Device, timeout: Duration) -> BuckyResult<ProbeResult> {
+        let stack = Stack::from(&self.0.stack);
+        let remote_id = remote.desc().device_id();
+        stack.device_cache().add(&remote_id, &remote);
+
+        // 已有的tunnel先reset，保证走完整的建立过程
+        let tunnel = stack.tunnel_manager().create_container(remote.desc())?;
+        tunnel.reset();
+
+        let start_at = bucky_time_now();
+        let mut options = DatagramOptions::default();
+        options.sequence = Some(TempSeq::from(start_at as u32));
+        if let Err(err) = self.0.datagram_tunnel.send_to(
+            b"probe",
+            &mut options,
+            &remote_id,
+            datagram::ReservedVPort::Debug.into()) {
+            if err.kind() != ErrorKind::NotConnected {
+                return Err(BuckyError::new(BuckyErrorCode::CodeError, format!("probe remote={} send err={:?}", remote_id, err)));
+            }
+        }
+
+        let _ = future::timeout(timeout, tunnel.wait_active()).await;
+        let elapsed = Duration::from_micros(bucky_time_now() - start_at);
+
+        let tunnel_used = match tunnel.state() {
+            TunnelState::Active(_) => tunnel.default_tunnel().ok().map(|t| {
+                let t = t.as_ref();
+                (EndpointPair::from((*t.local(), *t.remote())), t.proxy())
+            }),
+            _ => None
+        };
+
+        let trace = tunnel.build_trace();
+        Ok(ProbeResult {
+            remote: remote_id,
+            trace: trace.as_ref().map(|trace| trace.items()).unwrap_or_default(),
+            hole_punch: trace.as_ref().map(|trace| trace.hole_punch_attempts()).unwrap_or_default(),
+            tunnel: tunnel_used,
+            elapsed
+        })
+    }
+}
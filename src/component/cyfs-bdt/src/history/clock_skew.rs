@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap, 
+    sync::RwLock, 
+    time::Duration
+};
+use cyfs_base::*;
+use crate::types::*;
+
+#[derive(Clone)]
+pub struct Config {
+    // 偏差在这个范围内认为两端时钟是同步的
+    pub tolerance: Duration, 
+    // 偏差超过这个范围的时间戳直接拒绝
+    pub max_skew: Duration, 
+    pub capacity: usize, 
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tolerance: Duration::from_secs(60), 
+            max_skew: Duration::from_secs(12 * 3600), 
+            capacity: 1024, 
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ClockSkew {
+    // 对端时钟 - 本地时钟，微秒；包含了单程的传输延迟
+    pub offset: i64, 
+    pub samples: u32, 
+    pub update_time: Timestamp, 
+}
+
+impl ClockSkew {
+    pub fn offset_secs(&self) -> i64 {
+        self.offset / 1_000_000
+    }
+}
+
+#[derive(Debug)]
+pub enum ClockSkewCheck {
+    InSync, 
+    // 在可以兼容的范围内，按估计的偏差计算mixhash
+    Skewed(ClockSkew), 
+    // 偏差(微秒)超过max_skew
+    OutOfRange(i64), 
+}
+
+// 根据exchange、sn ping等带时间戳的包估计每个对端的时钟偏差
+pub struct ClockSkewEstimator {
+    config: Config, 
+    peers: RwLock<HashMap<DeviceId, ClockSkew>>, 
+}
+
+impl ClockSkewEstimator {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config, 
+            peers: RwLock::new(HashMap::new()), 
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn check(&self, remote: &DeviceId, remote_time: Timestamp) -> ClockSkewCheck {
+        self.check_at(remote, remote_time, bucky_time_now())
+    }
+
+    fn check_at(&self, remote: &DeviceId, remote_time: Timestamp, now: Timestamp) -> ClockSkewCheck {
+        let sample = remote_time as i64 - now as i64;
+        if sample.unsigned_abs() > self.config.max_skew.as_micros() as u64 {
+            return ClockSkewCheck::OutOfRange(sample);
+        }
+
+        let tolerance = self.config.tolerance.as_micros() as u64;
+        let skew = {
+            let mut peers = self.peers.write().unwrap();
+            match peers.get_mut(remote) {
+                Some(skew) => {
+                    // 偏差突变(比如对端校准了时钟)时重新估计，否则平滑掉传输延迟的抖动
+                    if (sample - skew.offset).unsigned_abs() > tolerance {
+                        skew.offset = sample;
+                        skew.samples = 1;
+                    } else {
+                        skew.offset = (skew.offset * 7 + sample) / 8;
+                        skew.samples += 1;
+                    }
+                    skew.update_time = now;
+                    *skew
+                }, 
+                None => {
+                    if peers.len() >= self.config.capacity {
+                        let oldest = peers.iter().min_by_key(|(_, skew)| skew.update_time).map(|(id, _)| id.clone());
+                        if let Some(oldest) = oldest {
+                            peers.remove(&oldest);
+                        }
+                    }
+                    let skew = ClockSkew {
+                        offset: sample, 
+                        samples: 1, 
+                        update_time: now, 
+                    };
+                    peers.insert(remote.clone(), skew);
+                    skew
+                }
+            }
+        };
+
+        if skew.offset.unsigned_abs() > tolerance {
+            ClockSkewCheck::Skewed(skew)
+        } else {
+            ClockSkewCheck::InSync
+        }
+    }
+
+    pub fn get(&self, remote: &DeviceId) -> Option<ClockSkew> {
+        self.peers.read().unwrap().get(remote).cloned()
+    }
+
+    // 偏差超过容忍范围的对端，用于排查连接失败
+    pub fn skewed_peers(&self) -> Vec<(DeviceId, ClockSkew)> {
+        let tolerance = self.config.tolerance.as_micros() as u64;
+        self.peers.read().unwrap().iter()
+            .filter(|(_, skew)| skew.offset.unsigned_abs() > tolerance)
+            .map(|(id, skew)| (id.clone(), *skew))
+            .collect()
+    }
+}
+
+#[test]
+fn clock_skew_estimate() {
+    let remote = DeviceId::default();
+    let estimator = ClockSkewEstimator::new(Config::default());
+    let now = bucky_time_now();
+    let minute = 60 * 1_000_000;
+
+    assert!(matches!(estimator.check_at(&remote, now + 1_000_000, now), ClockSkewCheck::InSync));
+    match estimator.check_at(&remote, now + 20 * minute, now) {
+        ClockSkewCheck::Skewed(skew) => assert_eq!(skew.offset_secs(), 20 * 60), 
+        _ => unreachable!()
+    }
+    assert!(matches!(estimator.check_at(&remote, now + 24 * 60 * minute, now), ClockSkewCheck::OutOfRange(_)));
+    assert_eq!(estimator.skewed_peers().len(), 1);
+}
@@ -95,10 +95,7 @@ impl Keystore {
                 let (enc_key, encrypted) = peer_desc.public_key().gen_aeskey_and_encrypt().unwrap();
                 let mix_key = AesKey::random();
                 let encrypted = EncryptedKey::Unconfirmed(encrypted);
-                let key = MixAesKey {
-                    enc_key, 
-                    mix_key, 
-                };
+                let key = MixAesKey::new(enc_key, mix_key);
                 mgr.add_key(&key, &peer_desc.device_id(), encrypted.clone());
                 FoundKey {
                     key,
@@ -114,11 +111,38 @@ impl Keystore {
         mgr.add_key(key, remote, EncryptedKey::None)
     }
 
+    // pending为true时只记录协商结果，等收到对端用新算法加密的包之后再切换
+    pub fn update_cipher(&self, remote: &DeviceId, mix_key: &AesKey, cipher: TunnelCipher, pending: bool) {
+        let mut mgr = self.key_manager.lock().unwrap();
+        mgr.update_cipher(remote, mix_key, cipher, pending);
+    }
+
+    // 用keystore中最新的协商状态刷新key的加密算法，key可能是在协商完成之前拿到的
+    pub fn refresh_cipher(&self, remote: &DeviceId, key: &mut MixAesKey) {
+        let mgr = self.key_manager.lock().unwrap();
+        mgr.refresh_cipher(remote, key);
+    }
+
     pub fn reset_peer(&self, device_id: &DeviceId) {
         info!("keystore reset peer {}", device_id);
         let mut mgr = self.key_manager.lock().unwrap();
         mgr.reset_peer(device_id);
     }
+
+    // 导出每个peer最近使用的已确认key和最近访问时间，用于持久化会话
+    pub(crate) fn export_keys(&self) -> Vec<(FoundKey, SystemTime)> {
+        let mgr = self.key_manager.lock().unwrap();
+        mgr.export_keys()
+    }
+
+    // 重启后恢复持久化的key，对端还保留着这个key时可以跳过密钥交换
+    pub(crate) fn restore_key(&self, key: &MixAesKey, remote: &DeviceId, encrypted: EncryptedKey) {
+        if encrypted.is_unconfirmed() {
+            return;
+        }
+        let mut mgr = self.key_manager.lock().unwrap();
+        mgr.add_key(key, remote, encrypted)
+    }
 }
 
 struct KeyManager {
@@ -269,6 +293,8 @@ impl KeyManager {
                 let mut exist = exist.as_ref().borrow_mut();
                 let info = &mut exist.info;
                 info.last_access_time = now;
+                // 对端时钟偏差的估计有变化，之后按新的偏差重算hash
+                info.key.clock_skew = key.clock_skew;
                 let _is_changed = info.update(false, expire_time);
                 // <TODO>持久化
                 target
@@ -377,6 +403,51 @@ impl KeyManager {
         }
     }
 
+    fn update_cipher(&mut self, peerid: &DeviceId, mix_key: &AesKey, cipher: TunnelCipher, pending: bool) {
+        if let Some(found_key_list) = self.peerid_key_map.get(peerid) {
+            if let Some(exist) = found_key_list.iter().find(|k| k.as_ref().borrow().info.key.mix_key == *mix_key) {
+                let mut exist = exist.borrow_mut();
+                let key = &mut exist.info.key;
+                if pending {
+                    if key.cipher != cipher {
+                        key.pending_cipher = Some(cipher);
+                    }
+                } else {
+                    if key.cipher != cipher {
+                        info!("keystore update cipher remote={} cipher {}=>{}", peerid, key.cipher, cipher);
+                        key.cipher = cipher;
+                    }
+                    key.pending_cipher = None;
+                }
+            }
+        }
+    }
+
+    fn refresh_cipher(&self, peerid: &DeviceId, key: &mut MixAesKey) {
+        if let Some(found_key_list) = self.peerid_key_map.get(peerid) {
+            if let Some(exist) = found_key_list.iter().find(|k| k.as_ref().borrow().info.key.mix_key == key.mix_key) {
+                let exist = exist.as_ref().borrow();
+                key.cipher = exist.info.key.cipher;
+                key.pending_cipher = exist.info.key.pending_cipher;
+            }
+        }
+    }
+
+    fn export_keys(&self) -> Vec<(FoundKey, SystemTime)> {
+        let now = SystemTime::now();
+        let mut keys = vec![];
+        for key_list in self.peerid_key_map.values() {
+            let last = key_list.iter()
+                .map(|key| key.as_ref().borrow())
+                .filter(|key| key.info.expire_time > now && !key.info.encrypted.is_unconfirmed())
+                .max_by_key(|key| key.info.last_access_time);
+            if let Some(last) = last {
+                keys.push((last.found(), last.info.last_access_time));
+            }
+        }
+        keys
+    }
+
     fn reset_peer(&mut self, device_id: &DeviceId) {
         let found_map = self.peerid_key_map.get_mut(device_id);
         if let Some(found_key_list) = found_map {
@@ -391,6 +462,9 @@ impl KeyManager {
                     } {
                         info!("keystore reset key remote={} key={}", device_id, mut_ref.info.key);
                         mut_ref.info.encrypted = EncryptedKey::Unconfirmed(encrypted);
+                        // 重新建立tunnel时再协商加密算法
+                        mut_ref.info.key.cipher = TunnelCipher::AesCbc;
+                        mut_ref.info.key.pending_cipher = None;
                         true
                     } else {
                         info!("keystore drop key remote={} key={}", device_id, mut_ref.info.key);
@@ -465,7 +539,7 @@ struct HashedKeyInfo {
 impl HashedKeyInfo {
     // 重算hash, 返回(新增hash,失效hash)
     fn rehash(&mut self) -> (Vec<KeyMixHash>, Vec<KeyMixHash>) {
-        let minute_timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() / 60;
+        let minute_timestamp = self.info.key.minute_timestamp();
         let min = minute_timestamp - (MIX_HASH_LIVE_MINUTES as u64 - 1) / 2;
         let max = minute_timestamp + (MIX_HASH_LIVE_MINUTES as u64 - 1) / 2;
 
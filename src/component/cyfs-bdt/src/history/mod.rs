@@ -1 +1,3 @@
-pub mod keystore;
\ No newline at end of file
+pub mod keystore;
+pub mod session;
+pub mod clock_skew;
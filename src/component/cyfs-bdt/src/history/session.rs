@@ -0,0 +1,400 @@
+use log::*;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Mutex, atomic::{AtomicBool, Ordering}},
+    time::Duration,
+};
+use serde::{Deserialize, Serialize};
+use cyfs_base::*;
+use crate::types::*;
+use super::keystore::{EncryptedKey, Keystore};
+
+// 文件格式: magic(8) + 用本地设备公钥加密的aes key + aes加密的json
+const SESSION_FILE_MAGIC: &[u8; 8] = b"BDTSESS1";
+
+#[derive(Clone)]
+pub struct Config {
+    // 为None时不保存会话
+    pub path: Option<PathBuf>,
+    pub save_interval: Duration,
+    // 只恢复这个时间内活跃过的会话，超过之后对端的keystore里大概率已经没有对应的key
+    pub resume_expire: Duration,
+    // 用恢复的key直连保存的endpoint的等待时间，超时后走完整的密钥交换和sn call
+    pub resume_timeout: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionRecord {
+    remote: String,
+    enc_key: String,
+    mix_key: String,
+    cipher: u8,
+    // 本地发起的key保存交换时用对端公钥加密的enc_key，对端发起的为None
+    encrypted: Option<String>,
+    endpoints: Vec<String>,
+    active_time: Timestamp,
+}
+
+impl SessionRecord {
+    fn decode(&self) -> BuckyResult<(DeviceId, MixAesKey, EncryptedKey, Vec<Endpoint>)> {
+        let remote = DeviceId::from_str(&self.remote)?;
+        let mut key = MixAesKey::new(
+            AesKey::from(Self::decode_hex(&self.enc_key)?),
+            AesKey::from(Self::decode_hex(&self.mix_key)?),
+        );
+        key.cipher = TunnelCipher::try_from(self.cipher)?;
+        let encrypted = match &self.encrypted {
+            Some(encrypted) => EncryptedKey::Confirmed(Self::decode_hex(encrypted)?),
+            None => EncryptedKey::None,
+        };
+        let endpoints = self.endpoints.iter().filter_map(|ep| Endpoint::from_str(ep).ok()).collect();
+        Ok((remote, key, encrypted, endpoints))
+    }
+
+    fn decode_hex(s: &str) -> BuckyResult<Vec<u8>> {
+        hex::decode(s).map_err(|e| BuckyError::new(BuckyErrorCode::InvalidFormat, format!("invalid hex {}", e)))
+    }
+}
+
+struct SessionState {
+    // 重启后从文件恢复的endpoint，每个peer只尝试一次
+    resumable: BTreeMap<DeviceId, Vec<Endpoint>>,
+    // 最近一次保存时各peer活跃的endpoint，tunnel被回收之后还能继续保存
+    endpoints: BTreeMap<DeviceId, (Vec<Endpoint>, Timestamp)>,
+}
+
+// 持久化tunnel的会话key和最近活跃的endpoint，进程快速重启之后
+// 对最近活跃的peer直接用原来的key连接原来的endpoint，不需要重新交换密钥和sn call
+pub struct SessionStore {
+    config: Config,
+    keystore: Keystore,
+    state: Mutex<SessionState>,
+    closed: AtomicBool,
+}
+
+impl SessionStore {
+    pub fn open(config: Config, keystore: Keystore) -> Self {
+        let store = Self {
+            config,
+            keystore,
+            state: Mutex::new(SessionState {
+                resumable: BTreeMap::new(),
+                endpoints: BTreeMap::new(),
+            }),
+            closed: AtomicBool::new(false),
+        };
+
+        if let Some(path) = store.config.path.as_ref() {
+            if path.exists() {
+                match store.load(path) {
+                    Ok(count) => info!("session store restored {} sessions from {}", count, path.display()),
+                    Err(err) => warn!("session store load from {} failed for {}", path.display(), err),
+                }
+                // 会话只恢复一次，避免下次重启时用到已经过期的key
+                let _ = std::fs::remove_file(path);
+            }
+        }
+
+        store
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.path.is_some()
+    }
+
+    // stack关闭之后不再定时保存
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    fn load(&self, path: &PathBuf) -> BuckyResult<usize> {
+        let data = std::fs::read(path)?;
+        let records = self.decrypt(&data)?;
+
+        let now = bucky_time_now();
+        let expire = self.config.resume_expire.as_micros() as u64;
+        let mut state = self.state.lock().unwrap();
+        let mut count = 0;
+        for record in records {
+            if record.active_time + expire < now {
+                continue;
+            }
+            match record.decode() {
+                Ok((remote, key, encrypted, endpoints)) => {
+                    self.keystore.restore_key(&key, &remote, encrypted);
+                    if endpoints.len() > 0 {
+                        state.resumable.insert(remote.clone(), endpoints.clone());
+                        state.endpoints.insert(remote, (endpoints, record.active_time));
+                    }
+                    count += 1;
+                },
+                Err(err) => {
+                    warn!("session store ignore invalid record of {} for {}", record.remote, err);
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    // 取出重启前保存的endpoint，只在重启后第一次连接这个peer时返回
+    pub fn take_resumable(&self, remote: &DeviceId) -> Option<Vec<Endpoint>> {
+        self.state.lock().unwrap().resumable.remove(remote)
+    }
+
+    // active: 当前active的udp tunnel的远端endpoint
+    pub fn save(&self, active: Vec<(DeviceId, Endpoint)>) -> BuckyResult<usize> {
+        let path = match self.config.path.as_ref() {
+            Some(path) => path,
+            None => return Ok(0),
+        };
+
+        let now = bucky_time_now();
+        let expire = self.config.resume_expire.as_micros() as u64;
+        let records: Vec<SessionRecord> = {
+            let mut state = self.state.lock().unwrap();
+            let mut active_endpoints: BTreeMap<DeviceId, Vec<Endpoint>> = BTreeMap::new();
+            for (remote, ep) in active {
+                active_endpoints.entry(remote).or_insert_with(Vec::new).push(ep);
+            }
+            for (remote, endpoints) in active_endpoints {
+                state.endpoints.insert(remote, (endpoints, now));
+            }
+            state.endpoints.retain(|_, (_, active_time)| *active_time + expire >= now);
+
+            self.keystore.export_keys().into_iter().filter_map(|(found, last_access)| {
+                let active_time = system_time_to_bucky_time(&last_access);
+                if active_time + expire < now {
+                    return None;
+                }
+                let encrypted = match &found.encrypted {
+                    EncryptedKey::Confirmed(encrypted) => Some(hex::encode(encrypted)),
+                    EncryptedKey::None => None,
+                    EncryptedKey::Unconfirmed(_) => return None,
+                };
+                let endpoints = state.endpoints.get(&found.peerid)
+                    .map(|(endpoints, _)| endpoints.iter().map(|ep| ep.to_string()).collect())
+                    .unwrap_or_default();
+                Some(SessionRecord {
+                    remote: found.peerid.to_string(),
+                    enc_key: hex::encode(found.key.enc_key.as_slice()),
+                    mix_key: hex::encode(found.key.mix_key.as_slice()),
+                    cipher: found.key.cipher as u8,
+                    encrypted,
+                    endpoints,
+                    active_time,
+                })
+            }).collect()
+        };
+
+        let data = self.encrypt(&records)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, path)?;
+
+        Ok(records.len())
+    }
+
+    fn encrypt(&self, records: &Vec<SessionRecord>) -> BuckyResult<Vec<u8>> {
+        let json = serde_json::to_vec(records)
+            .map_err(|e| BuckyError::new(BuckyErrorCode::InvalidData, format!("encode session failed for {}", e)))?;
+
+        let (aes_key, encrypted_key) = self.keystore.public_key().gen_aeskey_and_encrypt()?;
+        let header_len = SESSION_FILE_MAGIC.len() + encrypted_key.len();
+        let mut data = vec![0u8; header_len + AesKey::padded_len(json.len())];
+        data[..SESSION_FILE_MAGIC.len()].copy_from_slice(SESSION_FILE_MAGIC);
+        data[SESSION_FILE_MAGIC.len()..header_len].copy_from_slice(&encrypted_key);
+        let len = aes_key.encrypt(&json, &mut data[header_len..], json.len())?;
+        data.truncate(header_len + len);
+
+        Ok(data)
+    }
+
+    fn decrypt(&self, data: &[u8]) -> BuckyResult<Vec<SessionRecord>> {
+        if !data.starts_with(SESSION_FILE_MAGIC) {
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, "invalid session file magic"));
+        }
+        let (remain, aes_key) = self.keystore.private_key().decrypt_aeskey_data(&data[SESSION_FILE_MAGIC.len()..])?;
+        let aes_key = AesKey::from(aes_key);
+
+        let mut json = remain.to_vec();
+        let len = json.len();
+        let len = aes_key.inplace_decrypt(&mut json, len)?;
+        json.truncate(len);
+
+        serde_json::from_slice(&json)
+            .map_err(|e| BuckyError::new(BuckyErrorCode::InvalidFormat, format!("decode session failed for {}", e)))
+    }
+}
+
+
+#[test]
+fn save_and_resume() {
+    use super::keystore;
+
+    let private_key = PrivateKey::generate_rsa(1024).unwrap();
+    let device = Device::new(
+        None,
+        UniqueId::default(),
+        vec![],
+        vec![],
+        vec![],
+        private_key.public(),
+        Area::default(),
+        DeviceCategory::PC,
+    )
+    .build();
+    let open_keystore = || Keystore::new(
+        private_key.clone(), 
+        device.desc().clone(), 
+        RsaCPUObjectSigner::new(private_key.public(), private_key.clone()), 
+        keystore::Config {
+            active_time: Duration::from_secs(300),
+            capacity: 100,
+        });
+
+    let path = std::env::temp_dir().join(format!("bdt-session-test-{}", rand::random::<u32>()));
+    let config = Config {
+        path: Some(path.clone()),
+        save_interval: Duration::from_secs(60),
+        resume_expire: Duration::from_secs(240),
+        resume_timeout: Duration::from_secs(1),
+    };
+
+    let remote = DeviceId::default();
+    let remote_ep = Endpoint::from_str("W4udp127.0.0.1:10000").unwrap();
+    let key = MixAesKey::new(AesKey::random(), AesKey::random());
+
+    let keystore = open_keystore();
+    keystore.add_key(&key, &remote);
+    let store = SessionStore::open(config.clone(), keystore);
+    assert_eq!(store.save(vec![(remote.clone(), remote_ep.clone())]).unwrap(), 1);
+    assert!(path.exists());
+
+    // 重启之后key和endpoint都恢复，文件只用一次
+    let keystore = open_keystore();
+    let store = SessionStore::open(config.clone(), keystore.clone());
+    assert!(!path.exists());
+    let found = keystore.get_key_by_remote(&remote, false).unwrap();
+    assert!(found.key.mix_key == key.mix_key);
+    assert_eq!(store.take_resumable(&remote), Some(vec![remote_ep]));
+    assert_eq!(store.take_resumable(&remote), None);
+
+    // 不是本设备的私钥无法解密
+    store.save(vec![]).unwrap();
+    let other_key = PrivateKey::generate_rsa(1024).unwrap();
+    let other = SessionStore::open(config, Keystore::new(
+        other_key.clone(), 
+        device.desc().clone(), 
+        RsaCPUObjectSigner::new(other_key.public(), other_key.clone()), 
+        keystore::Config {
+            active_time: Duration::from_secs(300),
+            capacity: 100,
+        }));
+    assert!(other.take_resumable(&remote).is_none());
+    assert!(!path.exists());
+}
+
+#[test]
+fn save_filter() {
+    use super::keystore;
+
+    let new_device = |private_key: &PrivateKey| Device::new(
+        None,
+        UniqueId::default(),
+        vec![],
+        vec![],
+        vec![],
+        private_key.public(),
+        Area::default(),
+        DeviceCategory::PC,
+    )
+    .build();
+    let private_key = PrivateKey::generate_rsa(1024).unwrap();
+    let device = new_device(&private_key);
+    let open_keystore = || Keystore::new(
+        private_key.clone(), 
+        device.desc().clone(), 
+        RsaCPUObjectSigner::new(private_key.public(), private_key.clone()), 
+        keystore::Config {
+            active_time: Duration::from_secs(300),
+            capacity: 100,
+        });
+
+    // 没有配置路径时不保存
+    let store = SessionStore::open(Config {
+        path: None,
+        save_interval: Duration::from_secs(60),
+        resume_expire: Duration::from_secs(240),
+        resume_timeout: Duration::from_secs(1),
+    }, open_keystore());
+    assert!(!store.is_enabled());
+    assert_eq!(store.save(vec![]).unwrap(), 0);
+
+    let path = std::env::temp_dir().join(format!("bdt-session-test-{}", rand::random::<u32>()));
+    let config = Config {
+        path: Some(path.clone()),
+        save_interval: Duration::from_secs(60),
+        resume_expire: Duration::from_secs(240),
+        resume_timeout: Duration::from_secs(1),
+    };
+
+    // 本地发起还没确认的key不保存，已确认的key恢复之后保留对端公钥加密的enc_key
+    let unconfirmed_key = PrivateKey::generate_rsa(1024).unwrap();
+    let unconfirmed = new_device(&unconfirmed_key);
+    let confirmed = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+    let confirmed_key = MixAesKey::new(AesKey::random(), AesKey::random());
+
+    let keystore = open_keystore();
+    keystore.create_key(unconfirmed.desc(), false);
+    keystore.restore_key(&confirmed_key, &confirmed, EncryptedKey::Confirmed(vec![1, 2, 3]));
+    let store = SessionStore::open(config.clone(), keystore);
+    assert_eq!(store.save(vec![]).unwrap(), 1);
+
+    let keystore = open_keystore();
+    let store = SessionStore::open(config.clone(), keystore.clone());
+    assert!(keystore.get_key_by_remote(&unconfirmed.desc().device_id(), false).is_none());
+    let found = keystore.get_key_by_remote(&confirmed, false).unwrap();
+    assert!(found.key.mix_key == confirmed_key.mix_key);
+    match found.encrypted {
+        EncryptedKey::Confirmed(encrypted) => assert_eq!(encrypted, vec![1, 2, 3]),
+        _ => unreachable!(),
+    }
+    // 保存时没有active的tunnel，只恢复key
+    assert!(store.take_resumable(&confirmed).is_none());
+
+    // 超过resume_expire的记录不恢复
+    let remote = DeviceId::default();
+    let key = MixAesKey::new(AesKey::random(), AesKey::random());
+    let record = |active_time: Timestamp| SessionRecord {
+        remote: remote.to_string(),
+        enc_key: hex::encode(key.enc_key.as_slice()),
+        mix_key: hex::encode(key.mix_key.as_slice()),
+        cipher: key.cipher as u8,
+        encrypted: None,
+        endpoints: vec!["W4udp127.0.0.1:10000".to_owned()],
+        active_time,
+    };
+    let expired = bucky_time_now() - Duration::from_secs(300).as_micros() as u64;
+    std::fs::write(&path, store.encrypt(&vec![record(expired)]).unwrap()).unwrap();
+    let keystore = open_keystore();
+    let store = SessionStore::open(config.clone(), keystore.clone());
+    assert!(keystore.get_key_by_remote(&remote, false).is_none());
+    assert!(store.take_resumable(&remote).is_none());
+
+    std::fs::write(&path, store.encrypt(&vec![record(bucky_time_now())]).unwrap()).unwrap();
+    let keystore = open_keystore();
+    let store = SessionStore::open(config, keystore.clone());
+    assert!(keystore.get_key_by_remote(&remote, false).is_some());
+    assert_eq!(store.take_resumable(&remote).unwrap().len(), 1);
+    assert!(!path.exists());
+}
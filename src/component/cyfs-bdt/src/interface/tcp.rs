@@ -250,7 +250,7 @@ impl RawEncodeWithContext<OtherBoxEncodeContext> for PackageBox {
 
         encrypt_in_len -= buf.len();
         // 用aes 加密package的部分
-        let len = self.key().enc_key.inplace_encrypt(to_encrypt_buf, encrypt_in_len)?;
+        let len = self.key().inplace_encrypt(to_encrypt_buf, encrypt_in_len)?;
 
         Ok(&mut to_encrypt_buf[len..])
     }
@@ -264,23 +264,26 @@ enum DecryptBuffer<'de> {
 struct OtherBoxDecodeContext<'de> {
     decrypt_buf: DecryptBuffer<'de>,
     remote: &'de DeviceId,
-    key: &'de MixAesKey,
+    key: MixAesKey,
+    keystore: &'de keystore::Keystore, 
 }
 
 impl<'de> OtherBoxDecodeContext<'de> {
-    pub fn new_copy(decrypt_buf: &'de mut [u8], remote: &'de DeviceId, key: &'de MixAesKey) -> Self {
+    pub fn new_copy(decrypt_buf: &'de mut [u8], remote: &'de DeviceId, key: MixAesKey, keystore: &'de keystore::Keystore) -> Self {
         Self {
             decrypt_buf: DecryptBuffer::Copy(decrypt_buf),
             remote,
             key,
+            keystore, 
         }
     }
 
-    pub fn new_inplace(ptr: *mut u8, len: usize, remote: &'de DeviceId, key: &'de MixAesKey) -> Self {
+    pub fn new_inplace(ptr: *mut u8, len: usize, remote: &'de DeviceId, key: MixAesKey, keystore: &'de keystore::Keystore) -> Self {
         Self {
             decrypt_buf: DecryptBuffer::Inplace(ptr, len),
             remote,
             key,
+            keystore, 
         }
     }
 
@@ -303,7 +306,11 @@ impl<'de> OtherBoxDecodeContext<'de> {
     }
 
     pub fn key(&self) -> &MixAesKey {
-        self.key
+        &self.key
+    }
+
+    pub fn keystore(&self) -> &'de keystore::Keystore {
+        self.keystore
     }
 }
 
@@ -312,13 +319,20 @@ impl<'de> RawDecodeWithContext<'de, OtherBoxDecodeContext<'de>> for PackageBox {
         buf: &'de [u8],
         context: OtherBoxDecodeContext<'de>,
     ) -> BuckyResult<(Self, &'de [u8])> {
-        let key = context.key().clone();
+        let mut key = context.key().clone();
 
         let remote = context.remote().clone();
+        let keystore = context.keystore();
 
         let decrypt_buf = unsafe { context.decrypt_buf(buf) };
         // 用key 解密数据
-        let decrypt_len = key.enc_key.inplace_decrypt(decrypt_buf, buf.len())?;
+        let (decrypt_len, used_cipher) = key.inplace_decrypt(decrypt_buf, buf.len())?;
+        // 对端已经开始使用协商的算法加密，本端也切换过去
+        if key.pending_cipher == Some(used_cipher) {
+            keystore.update_cipher(&remote, &key.mix_key, used_cipher, false);
+            key.cipher = used_cipher;
+            key.pending_cipher = None;
+        }
         let remain_buf = &buf[buf.len()..];
         let decrypt_buf = &decrypt_buf[..decrypt_len];
         let mut packages = vec![];
@@ -483,6 +497,7 @@ struct AcceptInterfaceImpl {
     local: Endpoint,
     remote: Endpoint,
     key: MixAesKey,
+    keystore: keystore::Keystore,
 }
 
 #[derive(Clone)]
@@ -549,6 +564,7 @@ impl AcceptInterface {
                 local,
                 remote,
                 key: first_box.key().clone(),
+                keystore: keystore.clone(),
             })),
             first_box,
         ))
@@ -558,8 +574,11 @@ impl AcceptInterface {
         &self.0.socket
     }
 
-    pub fn key(&self) -> &MixAesKey {
-        &self.0.key
+    // 加密算法在interface创建之后才协商完成，每次都从keystore取最新的协商状态
+    pub fn key(&self) -> MixAesKey {
+        let mut key = self.0.key.clone();
+        self.0.keystore.refresh_cipher(&self.0.remote_device_id, &mut key);
+        key
     }
 
     pub fn remote_device_id(&self) -> &DeviceId {
@@ -582,7 +601,7 @@ impl AcceptInterface {
         let mut package_box =
             PackageBox::encrypt_box(
                 self.remote_device_id().clone(), 
-                self.0.key.clone());
+                self.key());
         package_box.append(packages);
         let mut socket = self.socket().clone();
         socket
@@ -602,7 +621,9 @@ impl Into<PackageInterface> for AcceptInterface {
             local: self.0.local,
             remote: self.0.remote,
             socket: self.0.socket.clone(),
-            key: self.0.key.clone(),
+            send_key: RwLock::new(self.0.key.clone()),
+            recv_key: RwLock::new(self.0.key.clone()),
+            keystore: self.0.keystore.clone(),
             remote_device_id: self.0.remote_device_id.clone(),
         }))
     }
@@ -615,6 +636,7 @@ struct InterfaceImpl {
     remote: Endpoint,
     remote_device_desc: DeviceDesc,
     key: MixAesKey,
+    keystore: keystore::Keystore,
 }
 
 impl std::fmt::Display for Interface {
@@ -637,6 +659,7 @@ impl Interface {
         remote_device_id: DeviceId,
         remote_device_desc: DeviceDesc,
         key: MixAesKey,
+        keystore: &keystore::Keystore,
         timeout: Duration,
     ) -> Result<Interface, BuckyError> {
         // let socket = socket2::Socket::new(socket2::Domain::ipv4(), socket2::Type::stream(), None).unwrap();
@@ -667,6 +690,7 @@ impl Interface {
             remote_device_id,
             remote_device_desc,
             key,
+            keystore: keystore.clone(),
         }));
         debug!("{} connected", interface);
         Ok(interface)
@@ -685,7 +709,7 @@ impl Interface {
             .ok_or_else(|| BuckyError::new(BuckyErrorCode::CryptoError, "key not exists"))?;
         let mut buffer = [0u8; udp::MTU_LARGE];
         let mut package_box =
-            PackageBox::encrypt_box(self.0.remote_device_id.clone(), self.0.key.clone());
+            PackageBox::encrypt_box(self.0.remote_device_id.clone(), self.key());
         if let keystore::EncryptedKey::Unconfirmed(encrypted) = key_stub.encrypted {
             if let PackageCmdCode::Exchange = packages[0].cmd_code() {
                 let exchg: &mut Exchange = packages[0].as_mut();
@@ -753,7 +777,8 @@ impl Interface {
             box_buf.as_mut_ptr(),
             box_buf.len(),
             &self.0.remote_device_id,
-            self.key()
+            self.key(), 
+            &self.0.keystore
         );
         PackageBox::raw_decode_with_context(box_buf, context).map(|(package_box, _)| package_box)
     }
@@ -762,8 +787,11 @@ impl Interface {
         &self.0.socket
     }
 
-    pub fn key(&self) -> &MixAesKey {
-        &self.0.key
+    // 加密算法在interface创建之后才协商完成，每次都从keystore取最新的协商状态
+    pub fn key(&self) -> MixAesKey {
+        let mut key = self.0.key.clone();
+        self.0.keystore.refresh_cipher(&self.0.remote_device_id, &mut key);
+        key
     }
 
     pub fn remote_endpoint(&self) -> &Endpoint {
@@ -777,7 +805,9 @@ impl Into<PackageInterface> for Interface {
             local: self.0.local,
             remote: self.0.remote,
             socket: self.0.socket.clone(),
-            key: self.0.key.clone(),
+            send_key: RwLock::new(self.0.key.clone()),
+            recv_key: RwLock::new(self.0.key.clone()),
+            keystore: self.0.keystore.clone(),
             remote_device_id: self.0.remote_device_id.clone(),
         }))
     }
@@ -787,7 +817,10 @@ struct PackageInterfaceImpl {
     local: Endpoint,
     remote: Endpoint,
     socket: TcpStream,
-    key: MixAesKey,
+    // 混合密钥交换之后两个方向分别在约定的位置切换到新key
+    send_key: RwLock<MixAesKey>,
+    recv_key: RwLock<MixAesKey>,
+    keystore: keystore::Keystore,
     remote_device_id: DeviceId,
 }
 #[derive(Clone)]
@@ -822,6 +855,31 @@ impl PackageInterface {
         }
     }
 
+    // 加密算法在interface创建之后才协商完成，每次都从keystore取最新的协商状态
+    pub fn key(&self) -> MixAesKey {
+        let mut key = self.0.send_key.read().unwrap().clone();
+        self.0.keystore.refresh_cipher(&self.0.remote_device_id, &mut key);
+        key
+    }
+
+    pub fn recv_key(&self) -> MixAesKey {
+        let mut key = self.0.recv_key.read().unwrap().clone();
+        self.0.keystore.refresh_cipher(&self.0.remote_device_id, &mut key);
+        key
+    }
+
+    // 之后发出的box使用新key加密，只能在send loop里调用，保证和发送顺序一致
+    pub fn update_send_key(&self, key: MixAesKey) {
+        info!("{} update send key to {}", self, key);
+        *self.0.send_key.write().unwrap() = key;
+    }
+
+    // 之后收到的box使用新key解密，只能在recv loop里调用
+    pub fn update_recv_key(&self, key: MixAesKey) {
+        info!("{} update recv key to {}", self, key);
+        *self.0.recv_key.write().unwrap() = key;
+    }
+
     pub fn raw_header_data_len() -> usize {
         u16::raw_bytes().unwrap()
     }
@@ -838,7 +896,8 @@ impl PackageInterface {
                     box_buf.as_mut_ptr(),
                     box_buf.len(),
                     &self.0.remote_device_id,
-                    &self.0.key,
+                    self.recv_key(),
+                    &self.0.keystore,
                 );
                 let package = PackageBox::raw_decode_with_context(box_buf, context)
                     .map(|(package_box, _)| package_box)?;
@@ -871,7 +930,7 @@ impl PackageInterface {
         let package_box =
             PackageBox::from_package(
                 self.0.remote_device_id.clone(), 
-                self.0.key.clone(), 
+                self.key(), 
                 package);
         let mut context = PackageBoxEncodeContext(OtherBoxEncodeContext {plaintext});
         socket
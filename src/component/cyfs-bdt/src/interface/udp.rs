@@ -2,7 +2,8 @@ use crate::{
     types::*, 
     history::keystore,
     protocol::*,
-    stack::{Stack, WeakStack}
+    stack::{Stack, WeakStack}, 
+    debug::{CaptureDirection, capture_package_box}
 };
 use super::{
     manager::UpdateOuterResult
@@ -17,24 +18,34 @@ use socket2::{Socket, Domain, Type};
 pub struct Config {
     pub sim_loss_rate: u8, 
     pub recv_buffer: usize, 
-    pub sn_only: bool
+    pub sn_only: bool, 
+    // 发出的包都设置DF，超过路径mtu时直接丢弃而不是分片，配合tunnel上的mtu探测使用；
+    // 只在linux上生效
+    pub dont_fragment: bool
 }
 
 pub struct UdpPackageBox {
     package_box: PackageBox,
     remote: Endpoint,
     local: Interface,
+    len: usize,
 }
 
 impl UdpPackageBox {
-    pub fn new(package_box: PackageBox, local: Interface, remote: Endpoint) -> Self {
+    pub fn new(package_box: PackageBox, local: Interface, remote: Endpoint, len: usize) -> Self {
         Self {
             package_box,
             local,
             remote,
+            len,
         }
     }
 
+    // 收到的udp包长度
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
     pub fn remote(&self) -> &Endpoint {
         &self.remote
     }
@@ -55,6 +66,12 @@ impl AsRef<PackageBox> for UdpPackageBox {
     }
 }
 
+impl AsMut<PackageBox> for UdpPackageBox {
+    fn as_mut(&mut self) -> &mut PackageBox {
+        &mut self.package_box
+    }
+}
+
 //const MAGIC_NUMBER: u16 = u16::from_be_bytes([0u8, 0x80u8]);
 
 pub trait OnUdpPackageBox {
@@ -200,6 +217,10 @@ impl Interface {
             }
         }?;
 
+        if config.dont_fragment {
+            Self::set_dont_fragment(&socket, local.addr().is_ipv6());
+        }
+
         Ok(Self(Arc::new(InterfaceImpl {
             config, 
             mapping_port, 
@@ -210,6 +231,33 @@ impl Interface {
     }
 
 
+    #[cfg(target_os = "linux")]
+    fn set_dont_fragment(socket: &UdpSocket, ipv6: bool) {
+        use std::os::unix::io::AsRawFd;
+        let (level, name, value) = if ipv6 {
+            (libc::IPPROTO_IPV6, libc::IPV6_MTU_DISCOVER, libc::IPV6_PMTUDISC_PROBE)
+        } else {
+            (libc::IPPROTO_IP, libc::IP_MTU_DISCOVER, libc::IP_PMTUDISC_PROBE)
+        };
+        let ret = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                level,
+                name,
+                &value as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>().try_into().unwrap(),
+            )
+        };
+        if ret < 0 {
+            warn!("set udp socket dont fragment failed, {}", std::io::Error::last_os_error());
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn set_dont_fragment(_socket: &UdpSocket, _ipv6: bool) {
+        warn!("udp socket dont fragment is not supported on this platform");
+    }
+
     pub fn mapping_port(&self) -> Option<u16> {
         self.0.mapping_port
     }
@@ -307,6 +355,7 @@ impl Interface {
                 }
             }
         }
+        let recv_len = recv.len();
         let ctx =
             PackageBoxDecodeContext::new_inplace(recv.as_mut_ptr(), recv.len(), stack.keystore());
         match PackageBox::raw_decode_with_context(recv, ctx) {
@@ -314,6 +363,7 @@ impl Interface {
                 if self.0.config.sn_only && !package_box.is_sn() {
                     return;
                 }
+                capture_package_box(CaptureDirection::In, &self.local(), &from, &package_box);
                 let local_interface = self.clone();
                 if package_box.has_exchange() {
                     async_std::task::spawn(async move {
@@ -326,6 +376,7 @@ impl Interface {
                             package_box,
                             local_interface,
                             from,
+                            recv_len,
                         ));
                     });
                 } else {
@@ -333,6 +384,7 @@ impl Interface {
                         package_box,
                         local_interface,
                         from,
+                        recv_len,
                     ));
                 }
             }
@@ -393,6 +445,7 @@ impl Interface {
                     e
                 })?;
             let send_len = buf_len - next_ptr.len();
+            capture_package_box(CaptureDirection::Out, &self.local(), to, package_box);
             self.send_buf_to(&crypto_buf[..send_len], to)
         })
     }
@@ -417,6 +470,7 @@ impl Interface {
             for (from, to) in iter {
                 if from.local().is_same_ip_version(&to) {
                     send_count += 1;
+                    capture_package_box(CaptureDirection::Out, &from.local(), &to, package_box);
                     let is_continue =
                         on_result(&from, &to, from.send_buf_to(&crypto_buf[..send_len], &to));
                     if !is_continue {
@@ -658,8 +712,11 @@ impl RawEncodeWithContext<PackageBoxEncodeContext> for PackageBox {
         // 用aes 加密package的部分
         let len = if context.plaintext {
             encrypt_in_len
+        } else if self.has_exchange() && !context.ignore_exchange {
+            // 对端从exchange中才能得到key，这时还没有协商算法，只能用aes-cbc
+            TunnelCipher::AesCbc.inplace_encrypt(&self.key().enc_key, to_encrypt_buf, encrypt_in_len)?
         } else {
-            self.key().enc_key.inplace_encrypt(to_encrypt_buf, encrypt_in_len)?
+            self.key().inplace_encrypt(to_encrypt_buf, encrypt_in_len)?
         };
 
         //info!("package_box udp encode: encrypt_in_len={} len={} buf_len={} plaintext={}", 
@@ -703,7 +760,8 @@ impl<'de>
         }
 
         struct KeyInfo {
-            enc_key: AesKey, 
+            // exchange的key还没有mix_key，这里只用来解密
+            key: MixAesKey, 
             mix_hash: KeyMixHash, 
             stub: KeyStub
         }
@@ -713,11 +771,11 @@ impl<'de>
         let (key_info, buf) = {
             match context.key_from_mixhash(&mix_hash) {
                 Some((remote, key)) => {
-                        mix_key = Some(key.mix_key);
+                        mix_key = Some(key.mix_key.clone());
 
                     (KeyInfo {
                         stub: KeyStub::Exist(remote), 
-                        enc_key: key.enc_key, 
+                        key, 
                         mix_hash
                     }, hash_buf)
                 }, 
@@ -731,7 +789,7 @@ impl<'de>
                     let (mix_hash, remain) = KeyMixHash::raw_decode(remain)?;
                     (KeyInfo {
                         stub: KeyStub::Exchange(encrypted), 
-                        enc_key, 
+                        key: MixAesKey::new(enc_key, AesKey::default()), 
                         mix_hash, 
                     }, remain)
                 }
@@ -743,10 +801,11 @@ impl<'de>
         } else {
             0
         };
+        let keystore = context.keystore;
         // 把原数据拷贝到context 给的buffer上去
         let decrypt_buf = unsafe { context.decrypt_buf(buf) };
         // 用key 解密数据
-        let decrypt_len =  key_info.enc_key.inplace_decrypt(decrypt_buf, buf.len())?;
+        let (decrypt_len, used_cipher) = key_info.key.inplace_decrypt(decrypt_buf, buf.len())?;
         let remain_buf = &buf[buf.len()..];
         let decrypt_buf = &decrypt_buf[..decrypt_len];
 
@@ -813,10 +872,21 @@ impl<'de>
             }
         }
 
-        let key = MixAesKey {
-            enc_key: key_info.enc_key, 
-            mix_key: mix_key.unwrap()
+        let mut key = MixAesKey {
+            enc_key: key_info.key.enc_key, 
+            mix_key: mix_key.unwrap(), 
+            cipher: key_info.key.cipher, 
+            pending_cipher: key_info.key.pending_cipher, 
+            clock_skew: key_info.key.clock_skew, 
         };
+        // 对端已经开始使用协商的算法加密，本端也切换过去
+        if let KeyStub::Exist(remote) = &key_info.stub {
+            if key.pending_cipher == Some(used_cipher) {
+                keystore.update_cipher(remote, &key.mix_key, used_cipher, false);
+                key.cipher = used_cipher;
+                key.pending_cipher = None;
+            }
+        }
         match key_info.stub {
             KeyStub::Exist(remote) => {
                 let mut package_box = PackageBox::encrypt_box(remote,key );
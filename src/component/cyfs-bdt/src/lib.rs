@@ -22,8 +22,9 @@ pub use types::*;
 pub use sn::types::*;
 pub use sn::client::SnStatus;
 pub use stack::{Stack, StackConfig, StackOpenParams, StackGuard};
+pub use history::clock_skew::{ClockSkew, ClockSkewCheck, ClockSkewEstimator};
 pub use interface::udp::MTU;
-pub use stream::{StreamListenerGuard, StreamGuard};
+pub use stream::{StreamListenerGuard, StreamGuard, StreamCloseReason, BufferAutotuneConfig};
 pub use datagram::{DatagramTunnelGuard, Datagram, DatagramOptions};
 pub use tunnel::{BuildTunnelParams};
 pub use finder::OuterDeviceCache as DeviceCache;
@@ -0,0 +1,321 @@
+use log::*;
+use std::{
+    sync::Mutex,
+    collections::BTreeSet,
+};
+use async_std::{
+    sync::Arc,
+};
+use cyfs_base::*;
+use super::scheduler::UploadScheduler;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UploadAdmissionPolicy {
+    // 不做准入控制，总是接受新的上传会话
+    Accept,
+    // 回复WouldBlock，下载端在block_interval之后重新发送interest
+    Queue,
+    // 回复Busy，下载端取消这个源上的会话，改从其他源下载
+    Reject,
+    // 接受新会话，但是在调度器中给这个remote较低的权重；调度器不限速时等同于Accept
+    Throttle,
+}
+
+#[derive(Clone)]
+pub struct UploadAdmissionConfig {
+    pub policy: UploadAdmissionPolicy,
+    // 上行带宽，单位byte/s；为0时使用调度器的max_upload_speed，两者都为0时只按会话数判断
+    pub upstream_capacity: u32,
+    // 上行利用率达到该百分比时认为拥塞
+    pub high_watermark: u8,
+    // 同时上传的会话数上限，为0时不限制
+    pub max_sessions: u32,
+    // Throttle策略下拥塞时接受的remote使用的调度权重
+    pub throttled_weight: u32,
+}
+
+impl Default for UploadAdmissionConfig {
+    fn default() -> Self {
+        Self {
+            policy: UploadAdmissionPolicy::Queue,
+            upstream_capacity: 0,
+            high_watermark: 90,
+            max_sessions: 0,
+            throttled_weight: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UploadAdmitResult {
+    Accepted,
+    Throttled,
+    Queued,
+    Rejected,
+}
+
+impl UploadAdmitResult {
+    // 不接受新会话时回复给下载端的错误码
+    pub fn resp_err(&self) -> Option<BuckyErrorCode> {
+        match self {
+            Self::Accepted | Self::Throttled => None,
+            Self::Queued => Some(BuckyErrorCode::WouldBlock),
+            Self::Rejected => Some(BuckyErrorCode::Busy),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct UploadAdmissionStat {
+    pub cur_speed: u32,
+    pub session_count: u32,
+    pub congested: bool,
+    // 从启动开始累计的各种处理结果的次数
+    pub accepted: u64,
+    pub throttled: u64,
+    pub queued: u64,
+    pub rejected: u64,
+}
+
+struct AdmissionState {
+    cur_speed: u32,
+    session_count: u32,
+    // 上次统计之后新接受的会话数，还没有计入session_count
+    admitted: u32,
+    // 因为拥塞被降低了权重的remote，拥塞解除后恢复
+    throttled_remotes: BTreeSet<DeviceId>,
+    stat: UploadAdmissionStat,
+}
+
+struct AdmissionImpl {
+    config: UploadAdmissionConfig,
+    scheduler: UploadScheduler,
+    state: Mutex<AdmissionState>,
+}
+
+// 上传端新会话的准入控制，根据上行带宽利用率和会话数决定接受、排队、拒绝或者降速接受，
+// 避免同时服务大量下载端时所有会话都因为带宽不足超时
+#[derive(Clone)]
+pub struct UploadAdmission(Arc<AdmissionImpl>);
+
+impl std::fmt::Display for UploadAdmission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UploadAdmission:{{policy:{:?}}}", self.config().policy)
+    }
+}
+
+impl UploadAdmission {
+    pub fn new(config: UploadAdmissionConfig, scheduler: UploadScheduler) -> Self {
+        Self(Arc::new(AdmissionImpl {
+            config,
+            scheduler,
+            state: Mutex::new(AdmissionState {
+                cur_speed: 0,
+                session_count: 0,
+                admitted: 0,
+                throttled_remotes: BTreeSet::new(),
+                stat: UploadAdmissionStat::default(),
+            })
+        }))
+    }
+
+    pub fn config(&self) -> &UploadAdmissionConfig {
+        &self.0.config
+    }
+
+    fn upstream_capacity(&self) -> u32 {
+        if self.config().upstream_capacity > 0 {
+            self.config().upstream_capacity
+        } else {
+            self.0.scheduler.max_upload_speed()
+        }
+    }
+
+    fn is_congested(&self, state: &AdmissionState) -> bool {
+        let config = self.config();
+        if config.max_sessions > 0 && state.session_count + state.admitted >= config.max_sessions {
+            return true;
+        }
+        let capacity = self.upstream_capacity();
+        capacity > 0 && state.cur_speed as u64 * 100 >= capacity as u64 * config.high_watermark as u64
+    }
+
+    // channel manager每次调度时更新当前的上行速度和上传会话数
+    pub(super) fn on_upstream_stat(&self, cur_speed: u32, session_count: u32) {
+        let recovered = {
+            let mut state = self.0.state.lock().unwrap();
+            state.cur_speed = cur_speed;
+            state.session_count = session_count;
+            state.admitted = 0;
+            let congested = self.is_congested(&state);
+            if state.stat.congested != congested {
+                info!("{} upstream congested changed to {}, speed={}, sessions={}", self, congested, cur_speed, session_count);
+                state.stat.congested = congested;
+            }
+            if !congested && state.throttled_remotes.len() > 0 {
+                let mut remotes = BTreeSet::new();
+                std::mem::swap(&mut remotes, &mut state.throttled_remotes);
+                remotes
+            } else {
+                BTreeSet::new()
+            }
+        };
+
+        for remote in recovered {
+            self.0.scheduler.reset_weight(&remote);
+        }
+    }
+
+    // 收到新的interest，创建上传会话之前调用
+    pub fn admit(&self, remote: &DeviceId) -> UploadAdmitResult {
+        let config = self.config();
+        let mut state = self.0.state.lock().unwrap();
+
+        let result = if config.policy == UploadAdmissionPolicy::Accept || !self.is_congested(&state) {
+            UploadAdmitResult::Accepted
+        } else {
+            match config.policy {
+                UploadAdmissionPolicy::Queue => UploadAdmitResult::Queued,
+                UploadAdmissionPolicy::Reject => UploadAdmitResult::Rejected,
+                _ => UploadAdmitResult::Throttled,
+            }
+        };
+
+        match result {
+            UploadAdmitResult::Accepted => {
+                state.admitted += 1;
+                state.stat.accepted += 1;
+            },
+            UploadAdmitResult::Throttled => {
+                state.admitted += 1;
+                state.stat.throttled += 1;
+                // 单独设置过权重的remote不受影响
+                if state.throttled_remotes.contains(remote) || self.0.scheduler.custom_weight(remote).is_none() {
+                    if state.throttled_remotes.insert(remote.clone()) {
+                        let _ = self.0.scheduler.set_weight(remote, std::cmp::max(config.throttled_weight, 1));
+                    }
+                }
+            },
+            UploadAdmitResult::Queued => {
+                state.stat.queued += 1;
+            },
+            UploadAdmitResult::Rejected => {
+                state.stat.rejected += 1;
+            }
+        }
+
+        if result != UploadAdmitResult::Accepted {
+            info!("{} {:?} new upload session from {}, speed={}, sessions={}", self, result, remote, state.cur_speed, state.session_count + state.admitted);
+        }
+
+        result
+    }
+
+    pub fn stat(&self) -> UploadAdmissionStat {
+        let state = self.0.state.lock().unwrap();
+        let mut stat = state.stat.clone();
+        stat.cur_speed = state.cur_speed;
+        stat.session_count = state.session_count;
+        stat
+    }
+}
+
+
+#[test]
+fn admit_by_speed() {
+    use super::scheduler::UploadSchedulerConfig;
+
+    let remote = DeviceId::default();
+    let new_admission = |policy| {
+        // 没有配置upstream_capacity时使用调度器的带宽上限
+        let scheduler = UploadScheduler::new(UploadSchedulerConfig {
+            max_upload_speed: 1000,
+            ..Default::default()
+        });
+        UploadAdmission::new(UploadAdmissionConfig {
+            policy,
+            ..Default::default()
+        }, scheduler)
+    };
+
+    let admission = new_admission(UploadAdmissionPolicy::Queue);
+    admission.on_upstream_stat(800, 1);
+    assert_eq!(admission.admit(&remote), UploadAdmitResult::Accepted);
+    admission.on_upstream_stat(900, 1);
+    assert!(admission.stat().congested);
+    let result = admission.admit(&remote);
+    assert_eq!(result, UploadAdmitResult::Queued);
+    assert_eq!(result.resp_err(), Some(BuckyErrorCode::WouldBlock));
+
+    let admission = new_admission(UploadAdmissionPolicy::Reject);
+    admission.on_upstream_stat(900, 1);
+    let result = admission.admit(&remote);
+    assert_eq!(result, UploadAdmitResult::Rejected);
+    assert_eq!(result.resp_err(), Some(BuckyErrorCode::Busy));
+
+    let admission = new_admission(UploadAdmissionPolicy::Accept);
+    admission.on_upstream_stat(1000, 1);
+    assert_eq!(admission.admit(&remote), UploadAdmitResult::Accepted);
+
+    let stat = admission.stat();
+    assert_eq!((stat.cur_speed, stat.session_count), (1000, 1));
+    assert_eq!((stat.accepted, stat.queued, stat.rejected), (1, 0, 0));
+}
+
+#[test]
+fn admit_by_sessions() {
+    use super::scheduler::UploadSchedulerConfig;
+
+    let remote = DeviceId::default();
+    let admission = UploadAdmission::new(UploadAdmissionConfig {
+        max_sessions: 2,
+        ..Default::default()
+    }, UploadScheduler::new(UploadSchedulerConfig::default()));
+
+    // 没有带宽上限时只按会话数判断，两次统计之间接受的会话也计入
+    admission.on_upstream_stat(u32::MAX, 1);
+    assert_eq!(admission.admit(&remote), UploadAdmitResult::Accepted);
+    assert_eq!(admission.admit(&remote), UploadAdmitResult::Queued);
+
+    admission.on_upstream_stat(0, 1);
+    assert_eq!(admission.admit(&remote), UploadAdmitResult::Accepted);
+
+    let stat = admission.stat();
+    assert_eq!((stat.accepted, stat.queued), (2, 1));
+}
+
+#[test]
+fn admit_throttle() {
+    use std::str::FromStr;
+    use super::scheduler::UploadSchedulerConfig;
+
+    let remote1 = DeviceId::default();
+    let remote2 = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+    let scheduler = UploadScheduler::new(UploadSchedulerConfig::default());
+    let admission = UploadAdmission::new(UploadAdmissionConfig {
+        policy: UploadAdmissionPolicy::Throttle,
+        upstream_capacity: 1000,
+        throttled_weight: 2,
+        ..Default::default()
+    }, scheduler.clone());
+
+    scheduler.set_weight(&remote2, 5).unwrap();
+    admission.on_upstream_stat(950, 1);
+    let result = admission.admit(&remote1);
+    assert_eq!(result, UploadAdmitResult::Throttled);
+    assert_eq!(result.resp_err(), None);
+    assert_eq!(scheduler.custom_weight(&remote1), Some(2));
+
+    // 单独设置过权重的remote保持原来的权重
+    assert_eq!(admission.admit(&remote2), UploadAdmitResult::Throttled);
+    assert_eq!(scheduler.custom_weight(&remote2), Some(5));
+
+    // 拥塞解除之后恢复被降低的权重
+    admission.on_upstream_stat(100, 2);
+    assert!(!admission.stat().congested);
+    assert_eq!(scheduler.custom_weight(&remote1), None);
+    assert_eq!(scheduler.custom_weight(&remote2), Some(5));
+    assert_eq!(admission.admit(&remote1), UploadAdmitResult::Accepted);
+    assert_eq!(admission.stat().throttled, 2);
+}
@@ -28,6 +28,8 @@ use super::{
     upload::*, 
     protocol::v0::*, 
     tunnel::*,
+    scheduler::*, 
+    admission::*, 
 };
 
 
@@ -39,7 +41,9 @@ pub struct Config {
     pub msl: Duration, 
     pub udp: udp::Config, 
     pub history_speed: HistorySpeedConfig, 
-    pub reserve_timeout: Duration
+    pub reserve_timeout: Duration, 
+    pub upload_scheduler: UploadSchedulerConfig, 
+    pub upload_admission: UploadAdmissionConfig
 }
 
 
@@ -195,6 +199,7 @@ struct ChannelImpl {
     stack: WeakStack, 
     tunnel: TunnelGuard, 
     command_tunnel: DatagramTunnelGuard, 
+    upload_scheduler: UploadScheduler, 
     command_seq: TempSeqGenerator,  
     download_seq: TempSeqGenerator, 
     state: RwLock<StateImpl>, 
@@ -222,7 +227,8 @@ impl Channel {
     pub fn new(
         weak_stack: WeakStack, 
         tunnel: TunnelGuard, 
-        command_tunnel: DatagramTunnelGuard
+        command_tunnel: DatagramTunnelGuard, 
+        upload_scheduler: UploadScheduler
     ) -> Self {
         let stack = Stack::from(&weak_stack);
         let config = stack.config().ndn.channel.clone();
@@ -230,6 +236,7 @@ impl Channel {
             stack: weak_stack, 
             tunnel, 
             command_tunnel, 
+            upload_scheduler, 
             command_seq: TempSeqGenerator::new(), 
             download_seq: TempSeqGenerator::new(), 
             state: RwLock::new(StateImpl {
@@ -257,6 +264,15 @@ impl Channel {
         &self.0.config
     }
 
+    pub fn upload_scheduler(&self) -> &UploadScheduler {
+        &self.0.upload_scheduler
+    }
+
+    // 默认tunnel上不会分片的piece大小，用来决定下载时请求的piece粒度
+    pub fn piece_payload(&self) -> usize {
+        self.default_tunnel().map(|tunnel| tunnel.piece_payload()).unwrap_or(PieceData::max_payload())
+    }
+
     fn default_tunnel(&self) -> BuckyResult<DynamicChannelTunnel> {
         self.tunnel_of(self.0.tunnel.default_tunnel()?)
     }
@@ -481,6 +497,19 @@ impl Channel {
             session.on_interest(self, command)
         } else {
             let stack = self.stack();
+            let admit = stack.ndn().channel_manager().upload_admission().admit(self.tunnel().remote());
+            if let Some(err) = admit.resp_err() {
+                info!("{} will not upload for {:?}, admission={:?}", self, command, admit);
+                self.resp_interest(RespInterest {
+                    session_id: command.session_id.clone(), 
+                    chunk: command.chunk.clone(), 
+                    err, 
+                    redirect: None,
+                    redirect_referer: None,
+                    to: None,
+                });
+                return Ok(());
+            }
             stack.ndn().event_handler().on_newly_interest(&self.stack(), command, self).await
         }
     }
@@ -86,6 +86,8 @@ struct SessionImpl {
     referer: Option<String>,  
     group_path: Option<String>, 
     state: RwLock<StateImpl>, 
+    integrity: RwLock<DownloadIntegrityStat>, 
+    traffic: RwLock<DownloadTrafficStat>, 
 }
 
 #[derive(Clone)]
@@ -117,6 +119,8 @@ impl DownloadSession {
                 send_ctrl_time: None, 
                 err
             })), 
+            integrity: RwLock::new(DownloadIntegrityStat::default()), 
+            traffic: RwLock::new(DownloadTrafficStat::default()), 
         }))
     }
 
@@ -143,6 +147,8 @@ impl DownloadSession {
                 channel, 
                 cache
             })), 
+            integrity: RwLock::new(DownloadIntegrityStat::default()), 
+            traffic: RwLock::new(DownloadTrafficStat::default()), 
         }))
     }
 
@@ -203,6 +209,14 @@ impl DownloadSession {
         &self.0.session_id
     }
 
+    pub fn integrity_stat(&self) -> DownloadIntegrityStat {
+        self.0.integrity.read().unwrap().clone()
+    }
+
+    pub fn traffic_stat(&self) -> DownloadTrafficStat {
+        self.0.traffic.read().unwrap().clone()
+    }
+
     pub fn ptr_eq(&self, other: &Self) -> bool {
         Arc::ptr_eq(&self.0, &other.0)
     }
@@ -275,7 +289,29 @@ impl DownloadSession {
         };
 
         let push_to_decoder = |provider: Box<dyn ChunkDecoder>| {
-            let result = provider.push_piece_data(piece).unwrap(); 
+            let result = match provider.push_piece_data(piece) {
+                Ok(result) => result, 
+                Err(err) => {
+                    // 解码或者写入缓存失败的piece计为损坏，丢弃之后等待重传
+                    warn!("{} push piece data failed, piece={:?}, err={}", self, piece.desc, err);
+                    self.0.integrity.write().unwrap().corrupt += 1;
+                    self.0.traffic.write().unwrap().on_recv(&self.source().target, piece.data.len() as u64, false, bucky_time_now());
+                    return;
+                }
+            };
+            {
+                let mut integrity = self.0.integrity.write().unwrap();
+                if !result.valid {
+                    integrity.out_of_window += 1;
+                } else if result.exists {
+                    integrity.duplicate += 1;
+                }
+            }
+            self.0.traffic.write().unwrap().on_recv(
+                &self.source().target, 
+                piece.data.len() as u64, 
+                result.valid && !result.exists, 
+                bucky_time_now());
             if let Some(waiters) = {
                 let state = &mut *self.0.state.write().unwrap();
                 match state {
@@ -18,6 +18,8 @@ use super::super::{
 };
 use super::{
     channel::{Channel},
+    scheduler::UploadScheduler, 
+    admission::UploadAdmission, 
 };
 
 struct ChannelGuard {
@@ -60,6 +62,8 @@ struct Channels {
 struct ManagerImpl {
     stack: WeakStack, 
     command_tunnel: DatagramTunnelGuard, 
+    upload_scheduler: UploadScheduler, 
+    upload_admission: UploadAdmission, 
     channels: RwLock<Channels>
 }
 
@@ -77,9 +81,13 @@ impl ChannelManager {
     pub fn new(weak_stack: WeakStack) -> Self {
         let stack = Stack::from(&weak_stack);
         let command_tunnel = stack.datagram_manager().bind_reserved(datagram::ReservedVPort::Channel).unwrap();
+        let upload_scheduler = UploadScheduler::new(stack.config().ndn.channel.upload_scheduler.clone());
+        let upload_admission = UploadAdmission::new(stack.config().ndn.channel.upload_admission.clone(), upload_scheduler.clone());
         let manager = Self(Arc::new(ManagerImpl {
             stack: weak_stack.clone(), 
             command_tunnel, 
+            upload_scheduler, 
+            upload_admission, 
             channels: RwLock::new(Channels {
                 download_history_speed: HistorySpeed::new(0, stack.config().ndn.channel.history_speed.clone()), 
                 download_cur_speed: 0, 
@@ -114,6 +122,16 @@ impl ChannelManager {
         format!("ChannelCount: {}, UploadSessionCount:{}, DownloadSessionCount:{}", channel_count, upload_session_count, download_session_count)
     }
 
+    // 上传端跨remote的公平调度，可以通过这里调整带宽上限和每个remote的权重
+    pub fn upload_scheduler(&self) -> &UploadScheduler {
+        &self.0.upload_scheduler
+    }
+
+    // 上行拥塞时新上传会话的准入控制
+    pub fn upload_admission(&self) -> &UploadAdmission {
+        &self.0.upload_admission
+    }
+
     pub fn channel_of(&self, remote: &DeviceId) -> Option<Channel> {
         self.0.channels.read().unwrap().entries.get(remote).map(|guard| guard.get())
     }
@@ -131,7 +149,8 @@ impl ChannelManager {
             let channel = Channel::new(
                 self.0.stack.clone(), 
                 tunnel, 
-                self.0.command_tunnel.clone());
+                self.0.command_tunnel.clone(), 
+                self.0.upload_scheduler.clone());
             channels.entries.insert(remote, ChannelGuard { reserving: None, channel: channel.clone() });
 
             channel
@@ -178,6 +197,7 @@ impl ChannelManager {
             channels.upload_history_speed.update(None, when);
         }
 
+        self.0.upload_admission.on_upstream_stat(upload_cur_speed, upload_session_count);
     }
 
     fn download_cur_speed(&self) -> u32 {
@@ -4,9 +4,13 @@ pub mod protocol;
 mod download;
 mod upload;
 mod manager;
+mod scheduler;
+mod admission;
 
 
 pub use download::*;
 pub use upload::*;
 pub use channel::{Channel, ChannelState, Config};
-pub use manager::ChannelManager;
\ No newline at end of file
+pub use manager::ChannelManager;
+pub use scheduler::*;
+pub use admission::*;
\ No newline at end of file
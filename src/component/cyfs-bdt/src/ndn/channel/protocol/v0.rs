@@ -422,6 +422,16 @@ impl PieceData {
         UdpTunnel::raw_data_max_payload_len() - u8::raw_bytes().unwrap() - Self::max_header_len()
     }
 
+    // 按探测到的路径mtu计算piece大小，不超过默认的max_payload
+    pub fn payload_of_mtu(mtu: usize) -> usize {
+        let overhead = UdpTunnel::raw_data_max_len() - Self::max_payload();
+        if mtu <= overhead {
+            Self::max_payload()
+        } else {
+            usize::min(Self::max_payload(), mtu - overhead)
+        }
+    }
+
     pub fn encode_header<'a>(
         buf: &'a mut [u8],
         session_id: &TempSeq,  
@@ -0,0 +1,264 @@
+use log::*;
+use std::{
+    sync::Mutex,
+    collections::BTreeMap,
+    time::Duration,
+};
+use async_std::{
+    sync::Arc,
+};
+use cyfs_base::*;
+use crate::{
+    types::*
+};
+use super::protocol::v0::PieceData;
+
+
+#[derive(Clone)]
+pub struct UploadSchedulerConfig {
+    // 上传总带宽上限，单位byte/s；为0时不做调度，每个tunnel只受自身拥塞控制约束
+    pub max_upload_speed: u32,
+    // 每轮调度的时长，每轮按权重重新分配份额
+    pub round_interval: Duration,
+    // 没有单独设置权重的remote使用的权重
+    pub default_weight: u32,
+    // 每个remote每轮至少可以发送的piece数，防止低权重的remote饿死
+    pub min_round_pieces: u32,
+}
+
+impl Default for UploadSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_upload_speed: 0,
+            round_interval: Duration::from_millis(100),
+            default_weight: 1,
+            min_round_pieces: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UploadRemoteStat {
+    pub remote: DeviceId,
+    pub weight: u32,
+    // 当前轮剩余的份额和已经发送的piece数
+    pub quota: u32,
+    pub sent: u32,
+}
+
+struct RemoteState {
+    quota: u32,
+    sent: u32,
+    // 当前轮是否有发送需求，下一轮只给有需求的remote分配份额
+    requested: bool,
+}
+
+struct SchedulerState {
+    max_upload_speed: u32,
+    weights: BTreeMap<DeviceId, u32>,
+    round_start: Timestamp,
+    capacity: u32,
+    granted: u32,
+    remotes: BTreeMap<DeviceId, RemoteState>,
+}
+
+impl SchedulerState {
+    fn weight_of(&self, remote: &DeviceId, config: &UploadSchedulerConfig) -> u32 {
+        self.weights.get(remote).cloned().unwrap_or(config.default_weight)
+    }
+
+    fn reserved(&self) -> u32 {
+        self.remotes.values().map(|r| r.quota).sum()
+    }
+
+    // 开始新的一轮，只保留上一轮有发送需求的remote，并按权重分配份额
+    fn next_round(&mut self, now: Timestamp, config: &UploadSchedulerConfig) {
+        let round_secs = config.round_interval.as_secs_f64();
+        self.capacity = std::cmp::max(
+            1,
+            (self.max_upload_speed as f64 * round_secs / PieceData::max_payload() as f64) as u32
+        );
+        self.round_start = now;
+        self.granted = 0;
+
+        let mut remotes = BTreeMap::new();
+        std::mem::swap(&mut remotes, &mut self.remotes);
+        let active: Vec<DeviceId> = remotes.into_iter().filter(|(_, r)| r.requested).map(|(remote, _)| remote).collect();
+
+        let total_weight: u64 = active.iter().map(|remote| self.weight_of(remote, config) as u64).sum();
+        for remote in active {
+            let weight = self.weight_of(&remote, config) as u64;
+            let share = if total_weight > 0 {
+                (self.capacity as u64 * weight / total_weight) as u32
+            } else {
+                0
+            };
+            self.remotes.insert(remote, RemoteState {
+                quota: std::cmp::max(share, config.min_round_pieces),
+                sent: 0,
+                requested: false
+            });
+        }
+    }
+}
+
+struct SchedulerImpl {
+    config: UploadSchedulerConfig,
+    state: Mutex<SchedulerState>,
+}
+
+// 上传端跨remote的加权公平调度，所有channel tunnel发送piece前都要先申请份额
+// 每一轮的前半段按权重给每个remote保留份额，未被保留的部分可以被任意remote借用；
+// 后半段所有剩余份额都可以借用，避免有remote用不完份额时浪费带宽
+#[derive(Clone)]
+pub struct UploadScheduler(Arc<SchedulerImpl>);
+
+impl std::fmt::Display for UploadScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UploadScheduler:{{max_upload_speed:{}}}", self.max_upload_speed())
+    }
+}
+
+impl UploadScheduler {
+    pub fn new(config: UploadSchedulerConfig) -> Self {
+        let max_upload_speed = config.max_upload_speed;
+        Self(Arc::new(SchedulerImpl {
+            config,
+            state: Mutex::new(SchedulerState {
+                max_upload_speed,
+                weights: BTreeMap::new(),
+                round_start: 0,
+                capacity: 0,
+                granted: 0,
+                remotes: BTreeMap::new(),
+            })
+        }))
+    }
+
+    pub fn config(&self) -> &UploadSchedulerConfig {
+        &self.0.config
+    }
+
+    pub fn max_upload_speed(&self) -> u32 {
+        self.0.state.lock().unwrap().max_upload_speed
+    }
+
+    pub fn set_max_upload_speed(&self, speed: u32) {
+        let mut state = self.0.state.lock().unwrap();
+        if state.max_upload_speed != speed {
+            info!("{} set max upload speed {} -> {}", self, state.max_upload_speed, speed);
+            state.max_upload_speed = speed;
+            // 下次申请时立即按新的带宽开始新的一轮
+            state.round_start = 0;
+        }
+    }
+
+    pub fn weight_of(&self, remote: &DeviceId) -> u32 {
+        self.0.state.lock().unwrap().weight_of(remote, self.config())
+    }
+
+    // 单独设置过的权重，没有设置时返回None
+    pub fn custom_weight(&self, remote: &DeviceId) -> Option<u32> {
+        self.0.state.lock().unwrap().weights.get(remote).cloned()
+    }
+
+    pub fn set_weight(&self, remote: &DeviceId, weight: u32) -> BuckyResult<()> {
+        if weight == 0 {
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, "upload weight should not be 0"));
+        }
+        info!("{} set upload weight of {} to {}", self, remote, weight);
+        self.0.state.lock().unwrap().weights.insert(remote.clone(), weight);
+        Ok(())
+    }
+
+    pub fn reset_weight(&self, remote: &DeviceId) {
+        if self.0.state.lock().unwrap().weights.remove(remote).is_some() {
+            info!("{} reset upload weight of {}", self, remote);
+        }
+    }
+
+    pub fn weights(&self) -> Vec<(DeviceId, u32)> {
+        self.0.state.lock().unwrap().weights.iter().map(|(remote, weight)| (remote.clone(), *weight)).collect()
+    }
+
+    pub fn stat(&self) -> Vec<UploadRemoteStat> {
+        let state = self.0.state.lock().unwrap();
+        state.remotes.iter().map(|(remote, r)| UploadRemoteStat {
+            remote: remote.clone(),
+            weight: state.weight_of(remote, self.config()),
+            quota: r.quota,
+            sent: r.sent
+        }).collect()
+    }
+
+    // 申请发送count个piece，返回实际可以发送的数量
+    pub fn acquire(&self, remote: &DeviceId, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        let config = self.config();
+        let mut state = self.0.state.lock().unwrap();
+        if state.max_upload_speed == 0 {
+            return count;
+        }
+
+        let now = bucky_time_now();
+        if now < state.round_start
+            || Duration::from_micros(now - state.round_start) >= config.round_interval {
+            state.next_round(now, config);
+        }
+
+        // 本轮新加入的remote先给最小份额，保证不会等到下一轮才能发送
+        if !state.remotes.contains_key(remote) {
+            state.remotes.insert(remote.clone(), RemoteState {
+                quota: config.min_round_pieces,
+                sent: 0,
+                requested: false
+            });
+        }
+
+        let wanted = std::cmp::min(count, u32::MAX as usize) as u32;
+        let own = {
+            let r = state.remotes.get_mut(remote).unwrap();
+            r.requested = true;
+            let own = std::cmp::min(wanted, r.quota);
+            r.quota -= own;
+            own
+        };
+
+        let mut granted = own;
+        if granted < wanted {
+            let half_round = now - state.round_start >= config.round_interval.as_micros() as u64 / 2;
+            let used = state.granted + own;
+            let borrowable = if half_round {
+                state.capacity.saturating_sub(used)
+            } else {
+                state.capacity.saturating_sub(used + state.reserved())
+            };
+            granted += std::cmp::min(wanted - granted, borrowable);
+        }
+
+        state.granted += granted;
+        state.remotes.get_mut(remote).unwrap().sent += granted;
+
+        granted as usize
+    }
+
+    // 申请到的份额没有用完时归还
+    pub fn release(&self, remote: &DeviceId, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let mut state = self.0.state.lock().unwrap();
+        if state.max_upload_speed == 0 {
+            return;
+        }
+        let count = std::cmp::min(count, u32::MAX as usize) as u32;
+        let count = std::cmp::min(count, state.granted);
+        state.granted -= count;
+        if let Some(r) = state.remotes.get_mut(remote) {
+            r.quota += count;
+            r.sent = r.sent.saturating_sub(count);
+        }
+    }
+}
@@ -16,6 +16,7 @@ use super::super::super::{
 };
 use super::super::{
     protocol::v0::*, 
+    scheduler::UploadScheduler
 };
 use super::{
     tunnel::*
@@ -25,7 +26,9 @@ struct TunnelImpl {
     start_at: Timestamp, 
     active_timestamp: Timestamp, 
     raw_tunnel: RawTunnel, 
-    uploaders: Uploaders
+    uploaders: Uploaders, 
+    remote: DeviceId, 
+    scheduler: UploadScheduler
 }
 
 #[derive(Clone)]
@@ -40,13 +43,17 @@ impl std::fmt::Display for TcpTunnel {
 impl TcpTunnel {
     pub fn new(
         raw_tunnel: RawTunnel, 
-        active_timestamp: Timestamp
+        active_timestamp: Timestamp, 
+        remote: DeviceId, 
+        scheduler: UploadScheduler
     ) -> Self {
         Self(Arc::new(TunnelImpl {
             active_timestamp, 
             start_at: bucky_time_now(), 
             raw_tunnel, 
-            uploaders: Uploaders::new()
+            uploaders: Uploaders::new(), 
+            remote, 
+            scheduler
         }))
     }
 }
@@ -68,6 +75,10 @@ impl ChannelTunnel for TcpTunnel {
         self.0.start_at
     }
 
+    fn piece_payload(&self) -> usize {
+        PieceData::max_payload()
+    }
+
     fn active_timestamp(&self) -> Timestamp {
         self.0.active_timestamp
     }
@@ -81,13 +92,23 @@ impl ChannelTunnel for TcpTunnel {
     }
 
     fn on_time_escape(&self, _now: Timestamp) -> BuckyResult<()> {
+        if self.uploaders().is_empty() {
+            return Ok(());
+        }
+        let remote = &self.0.remote;
+        let scheduler = &self.0.scheduler;
         while !self.0.raw_tunnel.is_data_piece_full()? {
+            // 多个remote并发上传时按权重分配发送份额
+            if scheduler.acquire(remote, 1) == 0 {
+                break;
+            }
             let mut piece_buf = [0u8; interface::udp::MTU];
             let piece_len = self.uploaders().next_piece(&mut piece_buf[u16::raw_bytes().unwrap()..]);
             if piece_len > 0 {
                 let _ = (piece_len as u16).raw_encode(&mut piece_buf, &None).unwrap();
                 let _ = self.0.raw_tunnel.send_data_piece(&mut piece_buf)?;
             } else {
+                scheduler.release(remote, 1);
                 break;
             }
         }
@@ -14,7 +14,8 @@ use super::super::super::{
 use super::super::{
     protocol::v0::*, 
     channel::Channel, 
-    upload::*
+    upload::*, 
+    scheduler::UploadScheduler
 };
 use super::{
     udp::UdpTunnel, 
@@ -29,6 +30,8 @@ pub trait ChannelTunnel: std::fmt::Display + Send + Sync {
     fn raw_ptr_eq(&self, tunnel: &DynamicTunnel) -> bool;
     fn active_timestamp(&self) -> Timestamp;
     fn start_at(&self) -> Timestamp;
+    // 在这个tunnel上发送不会分片的piece大小
+    fn piece_payload(&self) -> usize;
 
     fn on_piece_data(&self, piece: &PieceData) -> BuckyResult<()>;
     fn on_resp_estimate(&self, est: &ChannelEstimate) -> BuckyResult<()>;
@@ -44,10 +47,12 @@ pub type DynamicChannelTunnel = Box<dyn ChannelTunnel>;
 
 pub fn new_channel_tunnel(channel: &Channel, raw_tunnel: DynamicTunnel) -> BuckyResult<DynamicChannelTunnel> {
     if let TunnelState::Active(active_timestamp) = raw_tunnel.as_ref().state() {
+        let remote = channel.tunnel().remote().clone();
+        let scheduler = channel.upload_scheduler().clone();
         if raw_tunnel.as_ref().local().is_udp() {
-            Ok(UdpTunnel::new(channel.config().clone(), raw_tunnel.clone_as_tunnel(), active_timestamp).clone_as_tunnel())
+            Ok(UdpTunnel::new(channel.config().clone(), raw_tunnel.clone_as_tunnel(), active_timestamp, remote, scheduler).clone_as_tunnel())
         } else if raw_tunnel.as_ref().local().is_tcp() {
-            Ok(TcpTunnel::new(raw_tunnel.clone_as_tunnel(), active_timestamp).clone_as_tunnel())
+            Ok(TcpTunnel::new(raw_tunnel.clone_as_tunnel(), active_timestamp, remote, scheduler).clone_as_tunnel())
         } else {
             unreachable!()
         }
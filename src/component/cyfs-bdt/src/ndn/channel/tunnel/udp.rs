@@ -20,7 +20,8 @@ use super::super::super::{
 };
 use super::super::{
     protocol::v0::*, 
-    channel::{self}
+    channel::{self}, 
+    scheduler::UploadScheduler
 };
 use super::{
     tunnel::*
@@ -83,6 +84,8 @@ struct TunnelImpl {
     cc: Mutex<CcImpl>, 
     resp_estimate: Mutex<RespEstimateStub>, 
     uploaders: Uploaders,
+    remote: DeviceId, 
+    scheduler: UploadScheduler, 
     package_queue: Arc<Mutex<LinkedList<PacePackage>>>, 
 }
 
@@ -99,7 +102,9 @@ impl UdpTunnel {
     pub fn new(
         config: channel::Config, 
         raw_tunnel: RawTunnel, 
-        active_timestamp: Timestamp) -> Self {
+        active_timestamp: Timestamp, 
+        remote: DeviceId, 
+        scheduler: UploadScheduler) -> Self {
         let cc = CcImpl::new(&config.udp.cc, raw_tunnel.owner().map(|t| t.generate_sequence()).unwrap_or_default());
         Self(Arc::new(TunnelImpl {
             config, 
@@ -112,6 +117,8 @@ impl UdpTunnel {
                 recved: 0
             }), 
             uploaders: Uploaders::new(),
+            remote, 
+            scheduler, 
             package_queue: Arc::new(Mutex::new(Default::default())),
         }))
     }
@@ -120,9 +127,10 @@ impl UdpTunnel {
         &self.0.config
     }
 
-    fn send_pieces(&self, piece_count: usize) {
+    // 返回实际发送的piece数
+    fn send_pieces(&self, piece_count: usize) -> usize {
         if piece_count == 0 {
-            return;
+            return 0;
         }
         // trace!("{} schedule send pieces count {}", self, piece_count);
         struct BufferIndex {
@@ -194,8 +202,10 @@ impl UdpTunnel {
                     let mut cc = self.0.cc.lock().unwrap();
                     cc.cc.on_sent(bucky_time_now(), send_bytes as u64, last_est_seq.value() as u64);
                 }
+
+                sent
             })
-        });      
+        })
     }
 }
 
@@ -222,6 +232,10 @@ impl ChannelTunnel for UdpTunnel {
         self.0.start_at
     }
 
+    fn piece_payload(&self) -> usize {
+        PieceData::payload_of_mtu(self.0.raw_tunnel.mtu())
+    }
+
     fn active_timestamp(&self) -> Timestamp {
         self.0.active_timestamp
     }
@@ -275,6 +289,9 @@ impl ChannelTunnel for UdpTunnel {
                 
                 cc.cc.on_estimate(rtt, delay, false);
                 debug!("{} estimate rtt:{:?} delay:{:?} rto:{:?}", self, rtt, delay, cc.cc.rto());
+                if let Some(owner) = self.0.raw_tunnel.owner() {
+                    owner.stats().on_rtt(rtt);
+                }
 
                 est_index = Some(cc.est_stubs.len() - 1 - index);
 
@@ -317,6 +334,7 @@ impl ChannelTunnel for UdpTunnel {
         if TunnelState::Dead == self.0.raw_tunnel.state() {
             return Err(BuckyError::new(BuckyErrorCode::ErrorState, "tunnel's dead"));
         }
+        let mut lost = 0;
         let send_count = {
             let mut cc = self.0.cc.lock().unwrap();
             cc.cc.on_time_escape(now);
@@ -333,6 +351,7 @@ impl ChannelTunnel for UdpTunnel {
             }
 
             if let Some(index) = loss_from_index {
+                lost = loss_count;
                 cc.no_resp_counter += 1;
                 cc.break_counter += 1;
                 if cc.break_counter > self.config().udp.break_loss_count {
@@ -372,7 +391,17 @@ impl ChannelTunnel for UdpTunnel {
             self.0.raw_tunnel.mark_dead(TunnelState::Active(self.0.active_timestamp));
             err
         })?;
-        self.send_pieces(send_count);
+        if lost > 0 {
+            if let Some(owner) = self.0.raw_tunnel.owner() {
+                owner.stats().on_loss(lost as u64);
+            }
+        }
+        if send_count > 0 && !self.uploaders().is_empty() {
+            // 多个remote并发上传时按权重分配发送份额
+            let granted = self.0.scheduler.acquire(&self.0.remote, send_count);
+            let sent = self.send_pieces(granted);
+            self.0.scheduler.release(&self.0.remote, granted - sent);
+        }
         Ok(())
     }
 
@@ -166,14 +166,11 @@ impl ChunkCache {
             return Some(0..0);
         }
         let range = usize::min(range.start, self.chunk().len())..usize::min(range.end, self.chunk().len());
-        let index_start = (range.start / PieceData::max_payload()) as u32;
-        let index_end = ((range.end - 1) / PieceData::max_payload()) as u32;
-        for index in index_start..index_end + 1 {
-            if !self.stream().exists(index).unwrap() {
-                return None;
-            }
+        if self.stream().range_exists(&(range.start as u64..range.end as u64)).unwrap() {
+            Some(range)
+        } else {
+            None
         }
-        return Some(range);
     }
 
     pub async fn wait_exists<T: futures::Future<Output=BuckyError>, A: Fn() -> T>(
@@ -193,11 +190,7 @@ impl ChunkCache {
             return Ok(r);
         }
         let range = usize::min(range.start, self.chunk().len())..usize::min(range.end, self.chunk().len());
-        let index_start = (range.start / PieceData::max_payload()) as u32;
-        let index_end = ((range.end - 1) / PieceData::max_payload()) as u32;
-        for index in index_start..index_end + 1 {
-            self.stream().wait_exists(index, abort()).await?;
-        }
+        self.stream().wait_range_exists(range.start as u64..range.end as u64, abort()).await?;
         trace!("{} wait_exists {:?} return {:?}", self, range, range);
         Ok(range)
     }
@@ -27,6 +27,9 @@ use super::{
 struct StateImpl {
     raw_cache: OnceCell<Box<dyn RawCache>>, 
     pushed_len: usize, 
+    // 缓存中标记piece是否存在的粒度，下载开始前可以按路径的mtu调整，一旦写入就固定下来
+    unit: usize, 
+    unit_fixed: bool, 
     indices: IncomeIndexQueue, 
     waiters: BTreeMap::<u32, StateWaiter>
 }
@@ -60,11 +63,14 @@ impl std::fmt::Display for ChunkStreamCache {
 
 impl ChunkStreamCache {
     pub fn new(chunk: &ChunkId) -> Self {
-        let end = PieceDesc::stream_end_index(chunk, PieceData::max_payload() as u32) + 1;
+        let unit = PieceData::max_payload();
+        let end = PieceDesc::stream_end_index(chunk, unit as u32) + 1;
         Self(Arc::new(CacheImpl {
             chunk: chunk.clone(),
             state: RwLock::new(StateImpl {
                 pushed_len: 0,
+                unit, 
+                unit_fixed: false, 
                 raw_cache: OnceCell::new(), 
                 indices: IncomeIndexQueue::new(end), 
                 waiters: BTreeMap::new()
@@ -91,7 +97,8 @@ impl ChunkStreamCache {
             match state.raw_cache.set(raw_cache) {
                 Ok(_) => {
                     if finished {
-                        let end = PieceDesc::stream_end_index(self.chunk(), PieceData::max_payload() as u32) + 1;
+                        state.unit_fixed = true;
+                        let end = state.indices.end();
                         state.indices.push(0..end);
                         let mut waiters = Default::default();
                         std::mem::swap(&mut waiters, &mut state.waiters);
@@ -115,6 +122,34 @@ impl ChunkStreamCache {
         &self.0.chunk
     }
 
+    pub fn unit(&self) -> usize {
+        self.0.state.read().unwrap().unit
+    }
+
+    // 还没有piece写入时把粒度调整为unit，返回最终使用的粒度；
+    // 等待中的waiter按旧的粒度注册，全部唤醒后由调用者按新的粒度重新检查
+    pub fn adapt_unit(&self, unit: usize) -> usize {
+        let (unit, waiters) = {
+            let mut state = self.0.state.write().unwrap();
+            if state.unit_fixed || state.unit == unit || unit == 0 {
+                state.unit_fixed = true;
+                return state.unit;
+            }
+            info!("{} adapt unit from {} to {}", self, state.unit, unit);
+            state.unit = unit;
+            state.unit_fixed = true;
+            state.indices = IncomeIndexQueue::new(PieceDesc::stream_end_index(self.chunk(), unit as u32) + 1);
+            let mut waiters = Default::default();
+            std::mem::swap(&mut waiters, &mut state.waiters);
+            (unit, waiters)
+        };
+
+        for (_, waiter) in waiters {
+            waiter.wake();
+        }
+        unit
+    }
+
     fn require_index(&self, desc: &ChunkCodecDesc) -> Option<(Option<u32>, Option<Vec<Range<u32>>>)> {
         let (start, end, step) = desc.unwrap_as_stream();
         self.0.state.read().unwrap().indices.require(start, end, step)
@@ -124,7 +159,19 @@ impl ChunkStreamCache {
         trace!("{} push piece data:{:?}", self, piece.desc);
 
         let (index, range) = piece.desc.stream_piece_range(self.chunk());
-        let index_result = self.0.state.read().unwrap().indices.try_push(index..index + 1);
+        let index_result = {
+            let state = self.0.state.read().unwrap();
+            let (_, piece_unit) = piece.desc.unwrap_as_stream();
+            if piece_unit as usize != state.unit {
+                PushIndexResult {
+                    valid: false, 
+                    exists: false, 
+                    finished: state.indices.finished()
+                }
+            } else {
+                state.indices.try_push(index..index + 1)
+            }
+        };
         if !index_result.pushed() {
             trace!("{} push piece data:{:?}, result:{:?}", self, piece.desc, index_result);
             return Ok(index_result);
@@ -149,6 +196,7 @@ impl ChunkStreamCache {
                 let mut state = self.0.state.write().unwrap();
                 let result = state.indices.push(index..index + 1);
                 if result.pushed() {
+                    state.unit_fixed = true;
                     state.pushed_len += len;
                 }
                 (result, state.waiters.remove(&index))
@@ -165,10 +213,95 @@ impl ChunkStreamCache {
         }
     }
 
+    // 一次写入完整的chunk数据，数据需要调用者校验过；之前按piece写入的部分直接覆盖
+    pub fn fill(&self, data: &[u8]) -> BuckyResult<()> {
+        if data.len() != self.chunk().len() {
+            return Err(BuckyError::new(BuckyErrorCode::InvalidInput, "len mismatch"));
+        }
+
+        let mut writer = {
+            let state = self.0.state.read().unwrap();
+            state.raw_cache.get().ok_or_else(|| BuckyError::new(BuckyErrorCode::ErrorState, "not loaded"))?.sync_writer()
+        }?;
+        writer.seek(SeekFrom::Start(0))?;
+        writer.write_all(data)?;
+
+        let waiters = {
+            let mut state = self.0.state.write().unwrap();
+            state.unit_fixed = true;
+            let end = state.indices.end();
+            state.indices = IncomeIndexQueue::new(end);
+            state.indices.push(0..end);
+            state.pushed_len = data.len();
+            let mut waiters = Default::default();
+            std::mem::swap(&mut waiters, &mut state.waiters);
+            waiters
+        };
+        info!("{} filled", self);
+
+        for (_, waiter) in waiters {
+            waiter.wake();
+        }
+        Ok(())
+    }
+
     pub fn exists(&self, index: u32) -> BuckyResult<bool> {
         self.0.state.read().unwrap().indices.exists(index)
     }
 
+    fn first_missing(state: &StateImpl, range: &Range<u64>) -> BuckyResult<Option<u32>> {
+        if range.start >= range.end {
+            return Ok(None);
+        }
+        let unit = state.unit as u64;
+        for index in (range.start / unit) as u32..((range.end - 1) / unit) as u32 + 1 {
+            if !state.indices.exists(index)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    // 按字节范围检查，和请求方使用的piece大小无关
+    pub fn range_exists(&self, range: &Range<u64>) -> BuckyResult<bool> {
+        let state = self.0.state.read().unwrap();
+        Self::first_missing(&state, range).map(|missing| missing.is_none())
+    }
+
+    pub fn piece_exists(&self, piece_desc: &PieceDesc) -> BuckyResult<bool> {
+        let (_, range) = piece_desc.stream_piece_range(self.chunk());
+        self.range_exists(&range)
+    }
+
+    pub async fn wait_range_exists<T: futures::Future<Output=BuckyError>>(&self, range: Range<u64>, abort: T) -> BuckyResult<()> {
+        trace!("{} wait_range_exists:{:?}", self, range);
+        let mut abort = Box::pin(abort);
+        loop {
+            let waiter = {
+                let mut state = self.0.state.write().unwrap();
+                let index = match Self::first_missing(&state, &range)? {
+                    Some(index) => index, 
+                    None => {
+                        trace!("{} wait_range_exists:{:?} returned", self, range);
+                        return Ok(());
+                    }
+                };
+                if let Some(waiters) = state.waiters.get_mut(&index) {
+                    waiters.new_waiter()
+                } else {
+                    let mut waiters = StateWaiter::new();
+                    let waiter = waiters.new_waiter();
+                    state.waiters.insert(index, waiters);
+                    waiter
+                }
+            };
+            StateWaiter::abort_wait(&mut abort, waiter, || ()).await.map_err(|err| {
+                trace!("{} wait_range_exists:{:?} failed: {}", self, range, err);
+                err
+            })?;
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.0.state.read().unwrap().pushed_len
     }
@@ -220,8 +353,8 @@ impl ChunkStreamCache {
     ) -> BuckyResult<usize> {
         trace!("{} async read:{:?}", self, piece_desc);
 
-        let (index, range) = piece_desc.stream_piece_range(self.chunk());
-        if self.wait_exists(index, abort).await.is_err() {
+        let (_, range) = piece_desc.stream_piece_range(self.chunk());
+        if self.wait_range_exists(range.clone(), abort).await.is_err() {
             trace!("{} async read:{:?}, read:{}", self, piece_desc, 0);
             return Ok(0);
         }
@@ -260,8 +393,8 @@ impl ChunkStreamCache {
     ) -> BuckyResult<usize> {
         trace!("{} sync_try_read desc: {:?},offset_in_piece: {}, buffer: {} ", self, piece_desc, offset_in_piece, buffer.len());
 
-        let (index, range) = piece_desc.stream_piece_range(self.chunk());
-        match self.exists(index) {
+        let (_, range) = piece_desc.stream_piece_range(self.chunk());
+        match self.range_exists(&range) {
             Ok(exists) => {
                 if !exists {
                     trace!("{} sync_try_read not exists, desc: {:?},offset_in_piece: {}, buffer: {} ", self, piece_desc, offset_in_piece, buffer.len());
@@ -304,8 +437,8 @@ impl ChunkStreamCache {
         offset_in_piece: usize,  
         buffer: &mut [u8]
     ) -> BuckyResult<usize> {
-        let (index, range) = piece_desc.stream_piece_range(self.chunk());
-        match self.exists(index) {
+        let (_, range) = piece_desc.stream_piece_range(self.chunk());
+        match self.range_exists(&range) {
             Ok(exists) => {
                 if !exists {
                     return Err(BuckyError::new(BuckyErrorCode::NotFound, "not exists"));
@@ -522,13 +655,13 @@ impl ChunkEncoder for AsyncStreamEncoder {
             AsyncEncoderPendingState::None => {
                 if let Some(index) = state.indices.next() {
                     trace!("{} try pop next piece {}", self, index);
-                    if self.cache().exists(index)
+                    let (_, _, step) = self.desc().unwrap_as_stream();
+                    let piece_desc = PieceDesc::Range(index, step.abs() as u16);
+                    if self.cache().piece_exists(&piece_desc)
                         .map_err(|err| {
                             error!("{} exists error {}", self, index);
                             err
                         }).unwrap() {
-                        let (_, _, step) = self.desc().unwrap_as_stream();
-                        let piece_desc = PieceDesc::Range(index, step.abs() as u16);
                         let buf_len = buf.len();
                         let buf = PieceData::encode_header(
                             buf, 
@@ -678,13 +811,13 @@ impl ChunkEncoder for SyncStreamEncoder {
     fn next_piece(&self, session_id: &TempSeq, buf: &mut [u8]) -> BuckyResult<usize> {
         let mut state = self.0.state.lock().unwrap();
         if let Some(index) = state.indices.next() {
-            if self.cache().exists(index)
+            let (_, _, step) = self.desc().unwrap_as_stream();
+            let piece_desc = PieceDesc::Range(index, step.abs() as u16);
+            if self.cache().piece_exists(&piece_desc)
                 .map_err(|err| {
                     error!("{} exists error {}", self, index);
                     err
                 }).unwrap() {
-                let (_, _, step) = self.desc().unwrap_as_stream();
-                let piece_desc = PieceDesc::Range(index, step.abs() as u16);
                 let buf_len = buf.len();
                 let buf = PieceData::encode_header(
                     buf, 
@@ -16,6 +16,7 @@ use super::super::{
 };
 use super::{
     cache::*, 
+    loopback
 };
 
 #[derive(Debug)]
@@ -142,6 +143,22 @@ impl SingleStreamSession {
         self.trying.as_session()
     }
 
+    fn integrity_stat(&self) -> DownloadIntegrityStat {
+        let mut stat = DownloadIntegrityStat::default();
+        for session in self.tried.iter().chain(self.trying()) {
+            stat.merge(&session.integrity_stat());
+        }
+        stat
+    }
+
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        let mut stat = DownloadTrafficStat::default();
+        for session in self.tried.iter().chain(self.trying()) {
+            stat.merge(&session.traffic_stat());
+        }
+        stat
+    }
+
     fn next_filter(&self) -> DownloadSourceFilter {
         DownloadSourceFilter {
             exclude_target: Some(self.tried.iter().map(|session| session.source().target.clone()).collect()), 
@@ -242,6 +259,9 @@ struct ChunkDowloaderImpl {
     task: Box<dyn LeafDownloadTask>, 
     cache: ChunkCache, 
     state: RwLock<StateImpl>, 
+    // 结束时所有session的统计，进入Finished之后session不再保留
+    integrity: RwLock<DownloadIntegrityStat>, 
+    traffic: RwLock<DownloadTrafficStat>, 
 }
 
 impl std::fmt::Display for ChunkDowloaderImpl {
@@ -303,6 +323,8 @@ impl ChunkDownloader {
             cache, 
             task, 
             state: RwLock::new(StateImpl::Loading), 
+            integrity: RwLock::new(DownloadIntegrityStat::default()), 
+            traffic: RwLock::new(DownloadTrafficStat::default()), 
         }));
 
         {
@@ -377,12 +399,22 @@ impl ChunkDownloader {
     async fn start_session(&self, op: StartSessionOp) {
         info!("{} will start session, op_id={}", self, op.op_id);
 
+        // 来源在本进程内时直接复制，sync_finished会把状态切到Finished
+        if loopback::try_copy(&op.source.target.device_id(), self.cache()).await {
+            info!("{} finished by loopback, op_id={}, source={}", self, op.op_id, op.source.target.device_id());
+            return;
+        }
+
         let stack = Stack::from(&self.0.stack);
         let channel = stack.ndn().channel_manager().create_channel(&op.source.target).unwrap();   
 
         let mut source: DownloadSource<DeviceId> = op.source.into();
+        // 没有指定piece大小时按通道上探测到的mtu决定，缓存已经有数据的话沿用缓存的粒度
         source.codec_desc = match &source.codec_desc {
-            ChunkCodecDesc::Unknown => ChunkCodecDesc::Stream(None, None, None).fill_values(self.chunk()), 
+            ChunkCodecDesc::Unknown | ChunkCodecDesc::Stream(None, None, None) => {
+                let step = self.cache().stream().adapt_unit(channel.piece_payload());
+                ChunkCodecDesc::Stream(None, None, Some(step as i32)).fill_values(self.chunk())
+            }, 
             ChunkCodecDesc::Stream(..) => source.codec_desc.fill_values(self.chunk()), 
             _ => unimplemented!()
         };
@@ -417,6 +449,10 @@ impl ChunkDownloader {
         if self.cache().wait_exists(0..self.cache().chunk().len(), || self.owner().wait_user_canceled()).await.is_ok() {
             info!("{} finished", self);
             let state = &mut *self.0.state.write().unwrap();
+            if let StateImpl::Downloading(downloading) = state {
+                *self.0.integrity.write().unwrap() = downloading.integrity_stat();
+                *self.0.traffic.write().unwrap() = downloading.traffic_stat();
+            }
             *state = StateImpl::Finished;
         }
     }
@@ -455,6 +491,20 @@ impl ChunkDownloader {
         }
     }
 
+    pub fn integrity_stat(&self) -> DownloadIntegrityStat {
+        match &*self.0.state.read().unwrap() {
+            StateImpl::Downloading(downloading) => downloading.integrity_stat(), 
+            _ => self.0.integrity.read().unwrap().clone()
+        }
+    }
+
+    pub fn traffic_stat(&self) -> DownloadTrafficStat {
+        match &*self.0.state.read().unwrap() {
+            StateImpl::Downloading(downloading) => downloading.traffic_stat(), 
+            _ => self.0.traffic.read().unwrap().clone()
+        }
+    }
+
     pub fn history_speed(&self) -> u32 {
         match &*self.0.state.read().unwrap() {
             StateImpl::Downloading(downloading) => downloading.trying().map(|s| s.history_speed()).unwrap_or_default(), 
@@ -0,0 +1,124 @@
+use log::*;
+use std::{
+    collections::BTreeMap, 
+    sync::Mutex, 
+};
+use once_cell::sync::Lazy;
+use async_std::io::ReadExt;
+use cyfs_base::*;
+use crate::{
+    stack::{WeakStack, Stack}
+};
+use super::{
+    storage::ChunkReader, 
+    cache::*
+};
+
+// 进程内打开的所有协议栈，模拟器和测试里同一进程会打开多个协议栈
+static LOOPBACK_STACKS: Lazy<Mutex<BTreeMap<DeviceId, WeakStack>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+pub(super) fn register(local: &DeviceId, stack: WeakStack) {
+    LOOPBACK_STACKS.lock().unwrap().insert(local.clone(), stack);
+}
+
+// 协议栈释放时调用，同一个device可能先后打开多次，只清理已经释放的
+pub(super) fn purge() {
+    LOOPBACK_STACKS.lock().unwrap().retain(|_, weak| weak.upgrade().is_some());
+}
+
+fn find(target: &DeviceId) -> Option<Stack> {
+    let stacks = LOOPBACK_STACKS.lock().unwrap();
+    stacks.get(target).and_then(|weak| weak.upgrade().map(|_| Stack::from(weak)))
+}
+
+// 从对方的chunk store读出完整的chunk，校验不通过的数据不能写入本地缓存
+async fn read_verified(store: &dyn ChunkReader, chunk: &ChunkId) -> BuckyResult<Vec<u8>> {
+    let mut reader = store.get(chunk).await?;
+    let mut data = Vec::with_capacity(chunk.len());
+    reader.read_to_end(&mut data).await?;
+
+    if ChunkId::calculate_sync(&data)? != *chunk {
+        return Err(BuckyError::new(BuckyErrorCode::InvalidData, "verify failed"));
+    }
+    Ok(data)
+}
+
+// 下载源是本机或者同进程的协议栈时，不走channel，直接从对方的chunk store复制并校验
+// 返回false时由调用者走正常的下载流程
+pub(super) async fn try_copy(target: &DeviceId, cache: &ChunkCache) -> bool {
+    let stack = match find(target) {
+        Some(stack) => stack, 
+        None => return false
+    };
+    if !stack.config().ndn.chunk.loopback {
+        return false;
+    }
+
+    let chunk = cache.chunk();
+    let store = stack.ndn().chunk_manager().store();
+    if !store.exists(chunk).await {
+        debug!("{} loopback source {} has no chunk", cache, target);
+        return false;
+    }
+
+    let result = async {
+        let data = read_verified(store, chunk).await?;
+        cache.stream().fill(&data)
+    }.await;
+
+    match result {
+        Ok(_) => {
+            info!("{} copied from loopback source {}", cache, target);
+            true
+        }, 
+        Err(err) => {
+            warn!("{} copy from loopback source {} failed for {}", cache, target, err);
+            false
+        }
+    }
+}
+
+
+#[test]
+fn read_and_fill() {
+    use std::sync::Arc;
+    use crate::{utils::MemChunkStore, ndn::PieceDesc};
+
+    async_std::task::block_on(async {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+        let chunk = ChunkId::calculate_sync(&data).unwrap();
+
+        let store = MemChunkStore::new();
+        let err = read_verified(&store, &chunk).await.unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::NotFound);
+
+        // 对方store里的数据和chunk id不匹配
+        let mut tampered = data.clone();
+        tampered[0] ^= 0x01;
+        store.add(chunk.clone(), Arc::new(tampered)).await.unwrap();
+        let err = read_verified(&store, &chunk).await.unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        store.add(chunk.clone(), Arc::new(data.clone())).await.unwrap();
+        let copied = read_verified(&store, &chunk).await.unwrap();
+        assert_eq!(copied, data);
+
+        let cache = ChunkStreamCache::new(&chunk);
+        let err = cache.fill(&copied).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::ErrorState);
+
+        cache.load(false, Box::new(MemCache::with_capacity(chunk.len()))).unwrap();
+        let err = cache.fill(&copied[1..]).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidInput);
+
+        // 写入之后整个chunk都可读
+        cache.fill(&copied).unwrap();
+        assert_eq!(cache.len(), data.len());
+        assert!(cache.range_exists(&(0..data.len() as u64)).unwrap());
+
+        let unit = cache.unit();
+        let mut buffer = vec![0u8; unit];
+        let read = cache.sync_try_read(&PieceDesc::Range(1, unit as u16), 0, &mut buffer).unwrap();
+        assert_eq!(&buffer[..read], &data[unit..(2 * unit).min(data.len())]);
+    });
+}
@@ -18,12 +18,15 @@ use super::super::{
 use super::{
     storage::*,  
     cache::*,
-    download::*
+    download::*, 
+    loopback
 };
 
 #[derive(Clone)]
 pub struct Config {
-    pub raw_caches: RawCacheConfig
+    pub raw_caches: RawCacheConfig, 
+    // 下载源是本机或者同进程的协议栈时直接从chunk store复制
+    pub loopback: bool
 }
 
 struct Downloaders(LinkedList<WeakChunkDownloader>);
@@ -107,12 +110,19 @@ impl ChunkReader for EmptyChunkWrapper {
 
 
 
+impl Drop for ChunkManager {
+    fn drop(&mut self) {
+        loopback::purge();
+    }
+}
+
 impl ChunkManager {
     pub(crate) fn new(
         weak_stack: WeakStack, 
         store: Box<dyn ChunkReader>
     ) -> Self {
         let stack = Stack::from(&weak_stack);
+        loopback::register(stack.local_device_id(), weak_stack.clone());
         Self { 
             stack: weak_stack, 
             store: Box::new(EmptyChunkWrapper::new(store)), 
@@ -3,6 +3,7 @@ mod cache;
 mod storage;
 mod download;
 mod manager;
+mod loopback;
 
 pub use chunk_list::*;
 pub use storage::*;
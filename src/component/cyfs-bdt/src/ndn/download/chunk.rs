@@ -43,6 +43,9 @@ struct StateImpl {
     abs_path: Option<String>, 
     control_state: ControlStateImpl, 
     task_state: TaskStateImpl,
+    // 离开Downloading状态时保存downloader的统计
+    integrity: DownloadIntegrityStat, 
+    traffic: DownloadTrafficStat, 
 }
 
 struct ChunkTaskImpl {
@@ -78,6 +81,8 @@ impl ChunkTask {
                     TaskStateImpl::Finished
                 }, 
                 control_state: ControlStateImpl::Normal(StateWaiter::new()),
+                integrity: DownloadIntegrityStat::default(), 
+                traffic: DownloadTrafficStat::default(), 
             }),
             chunk, 
         }))
@@ -108,13 +113,23 @@ impl LeafDownloadTask for ChunkTask {
         let mut state = self.0.state.write().unwrap();
 
         match &state.task_state {
-            TaskStateImpl::Downloading(_) => {
+            TaskStateImpl::Downloading(downloading) => {
                 info!("{} mark finished", self);
+                state.integrity = downloading.downloader.integrity_stat();
+                state.traffic = downloading.downloader.traffic_stat();
                 state.task_state = TaskStateImpl::Finished;
             }, 
             _ => {}
         };
     }
+
+    fn integrity_stat(&self) -> DownloadIntegrityStat {
+        let state = self.0.state.read().unwrap();
+        match &state.task_state {
+            TaskStateImpl::Downloading(downloading) => downloading.downloader.integrity_stat(), 
+            _ => state.integrity.clone()
+        }
+    }
 }
 
 
@@ -191,8 +206,10 @@ impl NdnTask for ChunkTask {
             };
 
             match &state.task_state {
-                TaskStateImpl::Downloading(_) => {
+                TaskStateImpl::Downloading(downloading) => {
                     info!("{} cancel by err {}", self, err);
+                    state.integrity = downloading.downloader.integrity_stat();
+                    state.traffic = downloading.downloader.traffic_stat();
                     state.task_state = TaskStateImpl::Error(err);
                 }, 
                 _ => {}
@@ -248,6 +265,14 @@ impl DownloadTask for ChunkTask {
         }
     }
 
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        let state = self.0.state.read().unwrap();
+        match &state.task_state {
+            TaskStateImpl::Downloading(downloading) => downloading.downloader.traffic_stat(), 
+            _ => state.traffic.clone()
+        }
+    }
+
     async fn wait_user_canceled(&self) -> BuckyError {
         let waiter = {
             let mut state = self.0.state.write().unwrap();
@@ -28,6 +28,23 @@ struct DownloadingState {
     cur_speed: ProgressCounter,  
     cur_chunk: (ChunkDownloader, usize), 
     history_speed: HistorySpeed,
+    // 已经完成的chunk的统计
+    integrity: DownloadIntegrityStat, 
+    traffic: DownloadTrafficStat, 
+}
+
+impl DownloadingState {
+    fn integrity_stat(&self) -> DownloadIntegrityStat {
+        let mut stat = self.integrity.clone();
+        stat.merge(&self.cur_chunk.0.integrity_stat());
+        stat
+    }
+
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        let mut stat = self.traffic.clone();
+        stat.merge(&self.cur_chunk.0.traffic_stat());
+        stat
+    }
 }
 
 enum ControlStateImpl {
@@ -46,6 +63,9 @@ struct StateImpl {
     abs_path: Option<String>, 
     control_state: ControlStateImpl, 
     task_state: TaskStateImpl,
+    // 离开Downloading状态时保存的统计
+    integrity: DownloadIntegrityStat, 
+    traffic: DownloadTrafficStat, 
 }
 
 struct TaskImpl {
@@ -84,6 +104,8 @@ impl ChunkListTask {
                     TaskStateImpl::Finished(0)
                 },
                 control_state: ControlStateImpl::Normal(StateWaiter::new()),
+                integrity: DownloadIntegrityStat::default(), 
+                traffic: DownloadTrafficStat::default(), 
             }),
             chunk_list, 
         }))
@@ -111,6 +133,8 @@ impl ChunkListTask {
                     cur_speed: ProgressCounter::new(0), 
                     cur_chunk: (downloader.clone(), index), 
                     history_speed: HistorySpeed::new(0, stack.config().ndn.channel.history_speed.clone()), 
+                    integrity: DownloadIntegrityStat::default(), 
+                    traffic: DownloadTrafficStat::default(), 
                 });
                 Ok(downloader.cache().clone())
             }, 
@@ -119,6 +143,8 @@ impl ChunkListTask {
                 if *cur_index != index {
                     debug!("{} create new cache, old_index={}, old_chunk={}, index={}, chunk={}", self, *cur_index, downloader.cache().chunk(), index, chunk);
                     downloading.downloaded += downloader.cache().stream().len() as u64;
+                    downloading.integrity.merge(&downloader.integrity_stat());
+                    downloading.traffic.merge(&downloader.traffic_stat());
                     downloading.cur_chunk = (stack.ndn().chunk_manager().create_downloader(chunk, self.clone_as_leaf_task()), index);
                 }
                 Ok(downloading.cur_chunk.0.cache().clone())
@@ -150,11 +176,22 @@ impl LeafDownloadTask for ChunkListTask {
             TaskStateImpl::Downloading(downloading) => {
                 info!("{} mark finished", self);
                 downloading.downloaded += downloading.cur_chunk.0.cache().stream().len() as u64;
-                state.task_state = TaskStateImpl::Finished(downloading.downloaded);
+                let downloaded = downloading.downloaded;
+                state.integrity = downloading.integrity_stat();
+                state.traffic = downloading.traffic_stat();
+                state.task_state = TaskStateImpl::Finished(downloaded);
             }, 
             _ => {}
         };
     }
+
+    fn integrity_stat(&self) -> DownloadIntegrityStat {
+        let state = self.0.state.read().unwrap();
+        match &state.task_state {
+            TaskStateImpl::Downloading(downloading) => downloading.integrity_stat(), 
+            _ => state.integrity.clone()
+        }
+    }
 }
 
 impl NdnTask for ChunkListTask {
@@ -217,8 +254,10 @@ impl NdnTask for ChunkListTask {
             };
 
             match &state.task_state {
-                TaskStateImpl::Downloading(_) => {
+                TaskStateImpl::Downloading(downloading) => {
                     info!("{} cancel by err {}", self, err);
+                    state.integrity = downloading.integrity_stat();
+                    state.traffic = downloading.traffic_stat();
                     state.task_state = TaskStateImpl::Error(err);
                 }, 
                 _ => {}
@@ -259,6 +298,14 @@ impl DownloadTask for ChunkListTask {
         }
     }
 
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        let state = self.0.state.read().unwrap();
+        match &state.task_state {
+            TaskStateImpl::Downloading(downloading) => downloading.traffic_stat(), 
+            _ => state.traffic.clone()
+        }
+    }
+
     async fn wait_user_canceled(&self) -> BuckyError {
         let waiter = {
             let mut state = self.0.state.write().unwrap();
@@ -108,6 +108,134 @@ pub trait DownloadContext: Send + Sync {
         _task: &dyn LeafDownloadTask, 
         /*event trigered based on context's update_at*/
         _update_at: Timestamp) {}
+    // observed stats of sources used in this context
+    fn source_stats(&self) -> Vec<DownloadSourceStat> {
+        vec![]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DownloadSourceStat {
+    pub target: DeviceId, 
+    pub sessions: u32, 
+    pub finished: u32, 
+    pub failed: u32, 
+    // bytes of finished sessions
+    pub downloaded: u64, 
+    // average throughput of finished sessions, bytes per second
+    pub throughput: u32, 
+    // 0~100, higher is better
+    pub score: u32, 
+    // source is in backoff after failure and will not be used until then
+    pub retry_at: Option<Timestamp>, 
+    // source is skipped while there are better sources
+    pub pruned: bool, 
+}
+
+// data quality of pieces received by download sessions, 
+// helps to distinguish network loss from malicious or broken sources
+#[derive(Clone, Debug, Default)]
+pub struct DownloadIntegrityStat {
+    // pieces failed to be decoded or written to cache
+    pub corrupt: u64, 
+    // pieces already received before
+    pub duplicate: u64, 
+    // pieces out of session's requested range or with unmatched piece size
+    pub out_of_window: u64, 
+}
+
+impl DownloadIntegrityStat {
+    pub fn merge(&mut self, other: &Self) {
+        self.corrupt += other.corrupt;
+        self.duplicate += other.duplicate;
+        self.out_of_window += other.out_of_window;
+    }
+}
+
+// bytes received from one source by download sessions
+#[derive(Clone, Debug)]
+pub struct DownloadSourceTraffic {
+    pub target: DeviceId, 
+    // bytes of all received pieces, including corrupt, duplicate and out of window ones
+    pub received: u64, 
+    // bytes of pieces newly written to cache
+    pub downloaded: u64, 
+    pub first_recv: Timestamp, 
+    pub last_recv: Timestamp, 
+}
+
+impl DownloadSourceTraffic {
+    // average of downloaded bytes per second from first to last received piece
+    pub fn average_speed(&self) -> u32 {
+        average_speed(self.downloaded, self.first_recv, self.last_recv)
+    }
+}
+
+fn average_speed(downloaded: u64, first_recv: Timestamp, last_recv: Timestamp) -> u32 {
+    if last_recv > first_recv {
+        ((downloaded as u128 * 1000 * 1000) / (last_recv - first_recv) as u128) as u32
+    } else {
+        0
+    }
+}
+
+// cumulative traffic of download sessions, grouped by source
+#[derive(Clone, Debug, Default)]
+pub struct DownloadTrafficStat {
+    pub sources: Vec<DownloadSourceTraffic>, 
+}
+
+impl DownloadTrafficStat {
+    pub fn on_recv(&mut self, target: &DeviceId, len: u64, downloaded: bool, when: Timestamp) {
+        let source = if let Some(source) = self.sources.iter_mut().find(|s| s.target.eq(target)) {
+            source
+        } else {
+            self.sources.push(DownloadSourceTraffic {
+                target: target.clone(), 
+                received: 0, 
+                downloaded: 0, 
+                first_recv: when, 
+                last_recv: when, 
+            });
+            self.sources.last_mut().unwrap()
+        };
+
+        source.received += len;
+        if downloaded {
+            source.downloaded += len;
+        }
+        source.last_recv = when;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for other in &other.sources {
+            if let Some(source) = self.sources.iter_mut().find(|s| s.target.eq(&other.target)) {
+                source.received += other.received;
+                source.downloaded += other.downloaded;
+                source.first_recv = source.first_recv.min(other.first_recv);
+                source.last_recv = source.last_recv.max(other.last_recv);
+            } else {
+                self.sources.push(other.clone());
+            }
+        }
+    }
+
+    pub fn received(&self) -> u64 {
+        self.sources.iter().map(|s| s.received).sum()
+    }
+
+    pub fn downloaded(&self) -> u64 {
+        self.sources.iter().map(|s| s.downloaded).sum()
+    }
+
+    pub fn average_speed(&self) -> u32 {
+        let first_recv = self.sources.iter().map(|s| s.first_recv).min();
+        let last_recv = self.sources.iter().map(|s| s.last_recv).max();
+        match (first_recv, last_recv) {
+            (Some(first_recv), Some(last_recv)) => average_speed(self.downloaded(), first_recv, last_recv), 
+            _ => 0
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -160,6 +288,10 @@ pub trait DownloadTask: NdnTask {
     }
 
     fn calc_speed(&self, when: Timestamp) -> u32;
+
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        DownloadTrafficStat::default()
+    }
 }
 
 
@@ -172,6 +304,12 @@ pub trait LeafDownloadTask: DownloadTask + std::fmt::Display {
     fn abs_group_path(&self) -> Option<String>;
     fn context(&self) -> &dyn DownloadContext;
     fn finish(&self);
+    fn source_stats(&self) -> Vec<DownloadSourceStat> {
+        self.context().source_stats()
+    }
+    fn integrity_stat(&self) -> DownloadIntegrityStat {
+        DownloadIntegrityStat::default()
+    }
 }
 
 
@@ -308,3 +446,40 @@ impl async_std::io::Read for DownloadTaskReader {
         }))
     }
 }
+
+
+#[test]
+fn traffic_stat() {
+    use std::str::FromStr;
+
+    let source1 = DeviceId::default();
+    let source2 = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+
+    let mut stat = DownloadTrafficStat::default();
+    assert_eq!(stat.average_speed(), 0);
+
+    // 重复和损坏的piece只计入received
+    stat.on_recv(&source1, 1000, true, 1_000_000);
+    stat.on_recv(&source1, 1000, false, 1_500_000);
+    stat.on_recv(&source1, 1000, true, 2_000_000);
+    assert_eq!(stat.sources.len(), 1);
+    assert_eq!(stat.received(), 3000);
+    assert_eq!(stat.downloaded(), 2000);
+    assert_eq!(stat.sources[0].average_speed(), 2000);
+
+    // 只收到一个piece时没有速度
+    let mut other = DownloadTrafficStat::default();
+    other.on_recv(&source2, 500, true, 3_000_000);
+    assert_eq!(other.average_speed(), 0);
+    other.on_recv(&source1, 1000, true, 3_000_000);
+
+    stat.merge(&other);
+    assert_eq!(stat.sources.len(), 2);
+    assert_eq!(stat.received(), 4500);
+    assert_eq!(stat.downloaded(), 3500);
+    let source = stat.sources.iter().find(|s| s.target == source1).unwrap();
+    assert_eq!((source.received, source.downloaded), (4000, 3000));
+    assert_eq!((source.first_recv, source.last_recv), (1_000_000, 3_000_000));
+    assert_eq!(source.average_speed(), 1500);
+    assert_eq!(stat.average_speed(), 1750);
+}
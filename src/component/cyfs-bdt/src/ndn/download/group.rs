@@ -20,6 +20,8 @@ struct DownloadingState {
     history_downloaded: u64, 
     downloaded: u64, 
     history_speed: HistorySpeed, 
+    // 已经结束的子任务的流量统计
+    history_traffic: DownloadTrafficStat, 
 }
 
 enum TaskStateImpl {
@@ -36,6 +38,8 @@ enum ControlStateImpl {
 struct StateImpl {
     task_state: TaskStateImpl, 
     control_state: ControlStateImpl, 
+    // 每次calc_speed时更新，包括已经结束和正在下载的子任务
+    traffic: DownloadTrafficStat, 
 }
 
 struct TaskImpl {
@@ -58,8 +62,10 @@ impl DownloadGroup {
                     history_downloaded: 0, 
                     downloaded: 0, 
                     closed: false, 
+                    history_traffic: DownloadTrafficStat::default(), 
                 }),
                 control_state: ControlStateImpl::Normal(StateWaiter::new()), 
+                traffic: DownloadTrafficStat::default(), 
             })
         }))
     }
@@ -242,21 +248,27 @@ impl DownloadTask for DownloadGroup {
         let mut running = vec![];
         let mut cur_speed = 0;
         let mut running_downloaded = 0;
+        let state = &mut *state;
         match &mut state.task_state {
             TaskStateImpl::Downloading(downloading) => {
+                let mut running_traffic = DownloadTrafficStat::default();
                 for sub in &downloading.running {
                     cur_speed += sub.calc_speed(when);
                     match sub.state() {
                         NdnTaskState::Finished | NdnTaskState::Error(_) => {
                             downloading.history_downloaded += sub.transfered();
+                            downloading.history_traffic.merge(&sub.traffic_stat());
                         }, 
                         _ => {
                             running_downloaded += sub.transfered();
+                            running_traffic.merge(&sub.traffic_stat());
                             running.push(sub.clone_as_download_task());
                         }
                     }  
                 }
                 downloading.downloaded = downloading.history_downloaded + running_downloaded;
+                state.traffic = downloading.history_traffic.clone();
+                state.traffic.merge(&running_traffic);
                 downloading.history_speed.update(Some(cur_speed), when);
                 if running.len() == 0 && downloading.closed {
                     state.task_state = TaskStateImpl::Finished(downloading.downloaded);
@@ -269,6 +281,10 @@ impl DownloadTask for DownloadGroup {
         }
     }
 
+    fn traffic_stat(&self) -> DownloadTrafficStat {
+        self.0.state.read().unwrap().traffic.clone()
+    }
+
     async fn wait_user_canceled(&self) -> BuckyError {
         let waiter = {
             let mut state = self.0.state.write().unwrap();
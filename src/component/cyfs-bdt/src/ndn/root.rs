@@ -161,6 +161,10 @@ impl UploadRoot {
         };
         self.sub.sub_task(abs_path)
     }
+
+    pub fn cur_speed(&self) -> u32 {
+        self.sub.cur_speed()
+    }
 }
 
 #[derive(Clone)]
@@ -432,6 +432,8 @@ pub enum PackageCmdCode {
     AckAckTunnel = 3,
     PingTunnel = 4,
     PingTunnelResp = 5,
+    HybridKemOffer = 6,
+    HybridKemAnswer = 7,
 
     SnCall = 0x20,
     SnCallResp = 0x21,
@@ -465,7 +467,7 @@ impl PackageCmdCode {
     }
 
     pub fn is_tunnel(&self) -> bool {
-        (*self >= Self::SynTunnel) && (*self <= Self::PingTunnelResp)
+        (*self >= Self::SynTunnel) && (*self <= Self::HybridKemAnswer)
             || (*self >= Self::Datagram) && (*self <= Self::TcpSynConnection)
     }
 
@@ -494,6 +496,8 @@ impl TryFrom<u8> for PackageCmdCode {
             3u8 => Ok(Self::AckAckTunnel),
             4u8 => Ok(Self::PingTunnel),
             5u8 => Ok(Self::PingTunnelResp),
+            6u8 => Ok(Self::HybridKemOffer),
+            7u8 => Ok(Self::HybridKemAnswer),
             0x20u8 => Ok(Self::SnCall),
             0x21u8 => Ok(Self::SnCallResp),
             0x22u8 => Ok(Self::SnCalled),
@@ -527,6 +531,11 @@ pub trait Package {
     fn cmd_code() -> PackageCmdCode;
 }
 
+// Exchange的能力位编码在flags的高8位，不增加包体，旧版本解码时会忽略
+pub const EXCHANGE_CAPABILITY_HYBRID_KEM: u8 = 1;
+// 支持解码带padding的PingTunnel，可以在udp tunnel上探测路径mtu
+pub const EXCHANGE_CAPABILITY_MTU_PROBE: u8 = 2;
+
 #[derive(Clone)]
 pub struct Exchange {
     pub sequence: TempSeq,
@@ -536,17 +545,19 @@ pub struct Exchange {
     pub sign: Signature,
     pub from_device_desc: Device,
     pub mix_key: AesKey,
+    pub capabilities: u8, 
 }
 
 impl std::fmt::Debug for Exchange {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Exchange:{{sequence:{:?}, to_device_id:{:?}, from_device_desc:{}, mix_key:{:?}}}",
+            "Exchange:{{sequence:{:?}, to_device_id:{:?}, from_device_desc:{}, mix_key:{:?}, capabilities:{}}}",
             self.sequence,
             self.to_device_id,
             self.from_device_desc.desc().device_id(), 
             self.mix_key.to_hex().unwrap(),
+            self.capabilities
         )
     }
 }
@@ -573,6 +584,10 @@ impl Exchange {
         }
     }
 
+    pub fn has_capability(&self, capability: u8) -> bool {
+        self.capabilities & capability != 0
+    }
+
     fn to_sign(&self) -> HashValue {
         let seq = self.sequence.raw_encode_to_buffer().unwrap();
         let to_device_id = self.to_device_id.raw_encode_to_buffer().unwrap();
@@ -583,6 +598,10 @@ impl Exchange {
         sha256.input(&to_device_id);
         sha256.input(&send_time);
         sha256.input(&self.key_encrypted);
+        // 能力位也要签名，避免被中间人去掉而降级；为0时不参与，和旧版本的签名一致
+        if self.capabilities != 0 {
+            sha256.input(&[self.capabilities]);
+        }
         sha256.result().into()
     }
 }
@@ -608,7 +627,8 @@ impl From<(&SynTunnel, Vec<u8>, AesKey)> for Exchange {
             key_encrypted, 
             sign: Signature::default(),
             from_device_desc: syn_tunnel.from_device_desc.clone(),
-            mix_key
+            mix_key, 
+            capabilities: 0
         }
     }
 }
@@ -624,7 +644,8 @@ impl From<(&SynProxy, Vec<u8>, AesKey)> for Exchange {
             key_encrypted, 
             sign: Signature::default(),
             from_device_desc: syn_proxy.from_peer_info.clone(),
-            mix_key
+            mix_key, 
+            capabilities: 0
         }
     }
 }
@@ -653,6 +674,7 @@ impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for Exchange
             context.check_encode(buf, "device_desc", &self.from_device_desc, flags.next())?;
         let _buf =
             context.check_encode(buf, "mix_key", &self.mix_key, flags.next())?;
+        context.set_flags((self.capabilities as u16) << 8);
         context.finish(enc_buf)
     }
 }
@@ -670,6 +692,7 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
         let (sign, buf) = context.decode(buf, "Exchange.seq_key_sign", flags.next())?;
         let (from_device_desc, buf) = context.check_decode(buf, "device_desc", flags.next())?;
         let (mix_key, buf) = context.check_decode(buf, "mix_key", flags.next())?;
+        let capabilities = (context.flags() >> 8) as u8;
 
         Ok((
             Self {
@@ -679,7 +702,8 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
                 key_encrypted: vec![],
                 sign,
                 from_device_desc,
-                mix_key
+                mix_key, 
+                capabilities
             },
             buf,
         ))
@@ -712,6 +736,7 @@ fn encode_protocol_exchange() {
         sign: Signature::default(),
         from_device_desc: device,
         mix_key: AesKey::random(),
+        capabilities: EXCHANGE_CAPABILITY_HYBRID_KEM,
     };
 
     let mut buf = [0u8; udp::MTU];
@@ -735,7 +760,49 @@ fn encode_protocol_exchange() {
     assert_eq!(
         dst.from_device_desc.desc().device_id(),
         src.from_device_desc.desc().device_id()
+    );
+    assert!(dst.has_capability(EXCHANGE_CAPABILITY_HYBRID_KEM));
+}
+
+#[test]
+fn sign_protocol_exchange_capabilities() {
+    let private_key = PrivateKey::generate_rsa(1024).unwrap();
+    let device = Device::new(
+        None,
+        UniqueId::default(),
+        vec![],
+        vec![],
+        vec![],
+        private_key.public(),
+        Area::default(),
+        DeviceCategory::PC,
     )
+    .build();
+
+    let (_key, key_encrypted) = private_key.public().gen_aeskey_and_encrypt().unwrap();
+    let mut exchange = Exchange {
+        sequence: TempSeq::from(rand::random::<u32>()), 
+        to_device_id: DeviceId::default(), 
+        send_time: bucky_time_now(),
+        key_encrypted, 
+        sign: Signature::default(),
+        from_device_desc: device,
+        mix_key: AesKey::random(),
+        capabilities: 0,
+    };
+    let legacy = exchange.to_sign();
+
+    exchange.capabilities = EXCHANGE_CAPABILITY_HYBRID_KEM;
+    let signed = exchange.to_sign();
+    assert_ne!(legacy, signed);
+
+    // 去掉能力位后签名校验不通过
+    let signer = RsaCPUObjectSigner::new(private_key.public(), private_key.clone());
+    async_std::task::block_on(exchange.sign(&signer)).unwrap();
+    assert!(async_std::task::block_on(exchange.verify(&DeviceId::default())));
+
+    exchange.capabilities = 0;
+    assert!(!async_std::task::block_on(exchange.verify(&DeviceId::default())));
 }
 
 
@@ -746,6 +813,8 @@ pub struct SynTunnel {
     pub sequence: TempSeq,
     pub from_device_desc: Device,
     pub send_time: Timestamp,
+    // 发起方期望使用的加密算法，为None时使用兼容的aes-cbc
+    pub cipher: Option<TunnelCipher>, 
 }
 
 
@@ -753,10 +822,11 @@ impl std::fmt::Debug for SynTunnel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "SynTunnel:{{sequence:{:?}, to_device_id:{:?}, from_device_desc:{}}}",
+            "SynTunnel:{{sequence:{:?}, to_device_id:{:?}, from_device_desc:{}, cipher:{:?}}}",
             self.sequence,
             self.to_device_id,
             self.from_device_desc.desc().device_id(), 
+            self.cipher, 
         )
     }
 }
@@ -792,7 +862,8 @@ impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for SynTunnel
         let buf = context.check_encode(buf, "to_device_id", &self.to_device_id, flags.next())?;
         let buf = context.check_encode(buf, "sequence", &self.sequence, flags.next())?;
         let buf = context.check_encode(buf, "device_desc", &self.from_device_desc, flags.next())?;
-        let _buf = context.check_encode(buf, "send_time", &self.send_time, flags.next())?;
+        let buf = context.check_encode(buf, "send_time", &self.send_time, flags.next())?;
+        let _buf = context.option_encode(buf, &self.cipher, flags.next())?;
         context.finish(enc_buf)
     }
 }
@@ -810,6 +881,7 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
         let (sequence, buf) = context.check_decode(buf, "sequence", flags.next())?;
         let (from_device_desc, buf) = context.check_decode(buf, "device_desc", flags.next())?;
         let (send_time, buf) = context.check_decode(buf, "send_time", flags.next())?;
+        let (cipher, buf) = context.option_decode(buf, flags.next())?;
         Ok((
             Self {
                 protocol_version, 
@@ -818,6 +890,7 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
                 sequence,
                 from_device_desc,
                 send_time,
+                cipher, 
             },
             buf,
         ))
@@ -861,6 +934,7 @@ fn encode_protocol_syn_tunnel() {
         sequence: TempSeq::from(rand::random::<u32>()),
         from_device_desc: from_device,
         send_time: bucky_time_now(),
+        cipher: Some(TunnelCipher::ChaCha20Poly1305), 
     };
 
     let mut buf = [0u8; udp::MTU];
@@ -884,6 +958,7 @@ fn encode_protocol_syn_tunnel() {
         dst.from_device_desc.desc().device_id(),
         src.from_device_desc.desc().device_id()
     );
+    assert_eq!(dst.cipher, src.cipher);
 }
 
 pub const ACK_TUNNEL_RESULT_OK: u8 = 0;
@@ -898,6 +973,8 @@ pub struct AckTunnel {
     pub send_time: Timestamp,
     pub mtu: u16,
     pub to_device_desc: Device,
+    // 响应方选择的加密算法，只有SynTunnel中带了cipher时才会回复
+    pub cipher: Option<TunnelCipher>, 
 }
 
 impl Package for AckTunnel {
@@ -933,7 +1010,8 @@ impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for AckTunnel
         let buf = context.encode(buf, &self.result, flags.next())?;
         let buf = context.check_encode(buf, "send_time", &self.send_time, flags.next())?;
         let buf = context.encode(buf, &self.mtu, flags.next())?;
-        let _buf = context.check_encode(buf, "device_desc", &self.to_device_desc, flags.next())?;
+        let buf = context.check_encode(buf, "device_desc", &self.to_device_desc, flags.next())?;
+        let _buf = context.option_encode(buf, &self.cipher, flags.next())?;
         context.finish(enc_buf)
     }
 }
@@ -952,6 +1030,7 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
         let (send_time, buf) = context.check_decode(buf, "send_time", flags.next())?;
         let (mtu, buf) = context.decode(buf, "AckTunnel.mtu", flags.next())?;
         let (to_device_desc, buf) = context.check_decode(buf, "device_desc", flags.next())?;
+        let (cipher, buf) = context.option_decode(buf, flags.next())?;
         Ok((
             Self {
                 protocol_version, 
@@ -961,6 +1040,7 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
                 send_time,
                 mtu,
                 to_device_desc,
+                cipher, 
             },
             buf,
         ))
@@ -992,6 +1072,7 @@ fn encode_protocol_ack_tunnel() {
         send_time: bucky_time_now(),
         mtu: rand::random::<u16>(),
         to_device_desc: to_device,
+        cipher: None, 
     };
 
     let mut buf = [0u8; udp::MTU];
@@ -1013,6 +1094,7 @@ fn encode_protocol_ack_tunnel() {
     assert_eq!(dst.result, src.result);
     assert_eq!(dst.send_time, src.send_time);
     assert_eq!(dst.mtu, src.mtu);
+    assert_eq!(dst.cipher, src.cipher);
     assert_eq!(
         dst.to_device_desc.desc().device_id(),
         src.to_device_desc.desc().device_id()
@@ -1080,7 +1162,8 @@ impl From<(&SnCall, Vec<u8>, AesKey)> for Exchange {
             key_encrypted, 
             sign: Signature::default(),
             from_device_desc: sn_call.peer_info.clone().unwrap(),
-            mix_key
+            mix_key, 
+            capabilities: 0
         }
     }
 }
@@ -1293,6 +1376,7 @@ impl From<(&SnPing, Device, Vec<u8>, AesKey)> for Exchange {
             sign: Signature::default(),
             from_device_desc: local_device, 
             mix_key, 
+            capabilities: 0
         }
     }
 }
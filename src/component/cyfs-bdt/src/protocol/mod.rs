@@ -22,6 +22,8 @@ macro_rules! downcast_tunnel_handle {
             protocol::PackageCmdCode::AckAckTunnel => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::AckAckTunnel>().unwrap()),
             protocol::PackageCmdCode::PingTunnel => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::PingTunnel>().unwrap()),
             protocol::PackageCmdCode::PingTunnelResp => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::PingTunnelResp>().unwrap()),
+            protocol::PackageCmdCode::HybridKemOffer => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::HybridKemOffer>().unwrap()),
+            protocol::PackageCmdCode::HybridKemAnswer => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::HybridKemAnswer>().unwrap()),
             protocol::PackageCmdCode::Datagram => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::Datagram>().unwrap()),
             protocol::PackageCmdCode::SessionData => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::SessionData>().unwrap()),
             protocol::PackageCmdCode::TcpSynConnection => $handler($dynamic_package.as_any().downcast_ref::<protocol::v0::TcpSynConnection>().unwrap()),
@@ -103,6 +103,22 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, (&mut Contex
                     Err(BuckyError::new(BuckyErrorCode::NotSupport, "greater protocol version"))
                 }
             }, 
+            PackageCmdCode::HybridKemOffer => {
+                if *version == 0 {
+                    v0::HybridKemOffer::raw_decode_with_context(buf, merge_context)
+                        .map(|(pkg, buf)| (DynamicPackage::from(pkg), buf))
+                } else {
+                    Err(BuckyError::new(BuckyErrorCode::NotSupport, "greater protocol version"))
+                }
+            }, 
+            PackageCmdCode::HybridKemAnswer => {
+                if *version == 0 {
+                    v0::HybridKemAnswer::raw_decode_with_context(buf, merge_context)
+                        .map(|(pkg, buf)| (DynamicPackage::from(pkg), buf))
+                } else {
+                    Err(BuckyError::new(BuckyErrorCode::NotSupport, "greater protocol version"))
+                }
+            }, 
             PackageCmdCode::SnCallResp => {
                 if *version == 0 {
                     v0::SnCallResp::raw_decode_with_context(buf, merge_context)
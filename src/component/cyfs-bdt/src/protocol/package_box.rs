@@ -72,6 +72,11 @@ impl PackageBox {
         &self.key
     }
 
+    // 按对端的时钟计算mixhash
+    pub(crate) fn set_clock_skew(&mut self, clock_skew: i64) {
+        self.key.clock_skew = clock_skew;
+    }
+
     pub fn has_exchange(&self) -> bool {
         self.packages.get(0).unwrap().cmd_code().is_exchange()
     }
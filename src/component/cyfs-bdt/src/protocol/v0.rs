@@ -85,6 +85,8 @@ pub struct PingTunnel {
     pub package_id: u32,
     pub send_time: Timestamp,
     pub recv_data: u64,
+    // 探测路径mtu时填充到指定的包大小，只发给在exchange中声明了支持的对端
+    pub padding: Option<Vec<u8>>,
 }
 
 impl Package for PingTunnel {
@@ -116,7 +118,8 @@ impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for PingTunne
         let (mut context, buf) = context::Encode::<Self, Context>::new(enc_buf, merge_context)?;
         let buf = context.encode(buf, &self.package_id, flags.next())?;
         let buf = context.encode(buf, &self.send_time, flags.next())?;
-        let _buf = context.encode(buf, &self.recv_data, flags.next())?;
+        let buf = context.encode(buf, &self.recv_data, flags.next())?;
+        let _buf = context.option_encode(buf, &self.padding, flags.next())?;
         context.finish(enc_buf)
     }
 }
@@ -131,12 +134,14 @@ impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context
         let (package_id, buf) = context.decode(buf, "PingTunnel.package_id", flags.next())?;
         let (send_time, buf) = context.decode(buf, "PingTunnel.send_time", flags.next())?;
         let (recv_data, buf) = context.decode(buf, "PingTunnel.recv_data", flags.next())?;
+        let (padding, buf) = context.option_decode(buf, flags.next())?;
 
         Ok((
             Self {
                 package_id,
                 send_time,
                 recv_data,
+                padding,
             },
             buf,
         ))
@@ -151,6 +156,7 @@ fn encode_protocol_ping_tunnel() {
         package_id: rand::random::<u32>(),
         send_time: bucky_time_now(),
         recv_data: rand::random::<u64>(),
+        padding: None,
     };
 
     let mut buf = [0u8; udp::MTU];
@@ -171,6 +177,35 @@ fn encode_protocol_ping_tunnel() {
     assert_eq!(dst.package_id, src.package_id);
     assert_eq!(dst.send_time, src.send_time);
     assert_eq!(dst.recv_data, src.recv_data);
+    assert!(dst.padding.is_none());
+}
+
+#[test]
+fn encode_protocol_ping_tunnel_padding() {
+    use crate::interface::udp;
+
+    let src = PingTunnel {
+        package_id: rand::random::<u32>(),
+        send_time: bucky_time_now(),
+        recv_data: 0,
+        padding: Some(vec![0u8; 1000]),
+    };
+
+    let mut buf = [0u8; udp::MTU];
+    let remain = src
+        .raw_encode_with_context(&mut buf, &mut merge_context::OtherEncode::default(), &None)
+        .unwrap();
+    let remain = remain.len();
+
+    let dec = &buf[..buf.len() - remain];
+    let (_, dec) = u8::raw_decode(dec).unwrap();
+    let (dst, remain) =
+        PingTunnel::raw_decode_with_context(&dec, &mut merge_context::OtherDecode::default())
+            .unwrap();
+
+    assert_eq!(dst.package_id, src.package_id);
+    assert_eq!(dst.padding.unwrap().len(), 1000);
+    assert_eq!(remain.len(), 0);
 }
 
 #[derive(Debug)]
@@ -268,6 +303,210 @@ fn encode_protocol_ping_tunnel_resp() {
     assert_eq!(dst.recv_data, src.recv_data);
 }
 
+// 混合密钥交换的响应方发出，携带本端临时的 x25519 公钥和 kyber 公钥
+#[derive(Clone)]
+pub struct HybridKemOffer {
+    pub sequence: TempSeq,
+    pub x25519_public: Vec<u8>,
+    pub kyber_public: Vec<u8>,
+}
+
+impl std::fmt::Debug for HybridKemOffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HybridKemOffer:{{sequence:{:?}}}", self.sequence)
+    }
+}
+
+impl Package for HybridKemOffer {
+    fn version(&self) -> u8 {
+        0
+    }
+
+    fn cmd_code() -> PackageCmdCode {
+        PackageCmdCode::HybridKemOffer
+    }
+}
+
+impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for HybridKemOffer {
+    fn raw_measure_with_context(
+        &self,
+        _merge_context: &mut Context,
+        _purpose: &Option<RawEncodePurpose>,
+    ) -> Result<usize, BuckyError> {
+        unimplemented!()
+    }
+
+    fn raw_encode_with_context<'a>(
+        &self,
+        enc_buf: &'a mut [u8],
+        merge_context: &mut Context,
+        _purpose: &Option<RawEncodePurpose>,
+    ) -> Result<&'a mut [u8], BuckyError> {
+        let mut flags = context::FlagsCounter::new();
+        let (mut context, buf) = context::Encode::<Self, Context>::new(enc_buf, merge_context)?;
+        let buf = context.encode(buf, &self.sequence, flags.next())?;
+        let buf = context.encode(buf, &self.x25519_public, flags.next())?;
+        let _buf = context.encode(buf, &self.kyber_public, flags.next())?;
+        context.finish(enc_buf)
+    }
+}
+
+impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context>
+    for HybridKemOffer
+{
+    fn raw_decode_with_context(
+        buf: &'de [u8],
+        merge_context: &mut Context,
+    ) -> Result<(Self, &'de [u8]), BuckyError> {
+        let mut flags = context::FlagsCounter::new();
+        let (mut context, buf) = context::Decode::new(buf, merge_context)?;
+        let (sequence, buf) = context.decode(buf, "HybridKemOffer.sequence", flags.next())?;
+        let (x25519_public, buf) = context.decode(buf, "HybridKemOffer.x25519_public", flags.next())?;
+        let (kyber_public, buf) = context.decode(buf, "HybridKemOffer.kyber_public", flags.next())?;
+
+        Ok((
+            Self {
+                sequence,
+                x25519_public,
+                kyber_public,
+            },
+            buf,
+        ))
+    }
+}
+
+// 发起方收到 HybridKemOffer 之后回复，携带本端临时的 x25519 公钥和 kyber 封装的密文
+#[derive(Clone)]
+pub struct HybridKemAnswer {
+    pub sequence: TempSeq,
+    pub x25519_public: Vec<u8>,
+    pub kyber_ciphertext: Vec<u8>,
+}
+
+impl std::fmt::Debug for HybridKemAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HybridKemAnswer:{{sequence:{:?}}}", self.sequence)
+    }
+}
+
+impl Package for HybridKemAnswer {
+    fn version(&self) -> u8 {
+        0
+    }
+
+    fn cmd_code() -> PackageCmdCode {
+        PackageCmdCode::HybridKemAnswer
+    }
+}
+
+impl<Context: merge_context::Encode> RawEncodeWithContext<Context> for HybridKemAnswer {
+    fn raw_measure_with_context(
+        &self,
+        _merge_context: &mut Context,
+        _purpose: &Option<RawEncodePurpose>,
+    ) -> Result<usize, BuckyError> {
+        unimplemented!()
+    }
+
+    fn raw_encode_with_context<'a>(
+        &self,
+        enc_buf: &'a mut [u8],
+        merge_context: &mut Context,
+        _purpose: &Option<RawEncodePurpose>,
+    ) -> Result<&'a mut [u8], BuckyError> {
+        let mut flags = context::FlagsCounter::new();
+        let (mut context, buf) = context::Encode::<Self, Context>::new(enc_buf, merge_context)?;
+        let buf = context.encode(buf, &self.sequence, flags.next())?;
+        let buf = context.encode(buf, &self.x25519_public, flags.next())?;
+        let _buf = context.encode(buf, &self.kyber_ciphertext, flags.next())?;
+        context.finish(enc_buf)
+    }
+}
+
+impl<'de, Context: merge_context::Decode> RawDecodeWithContext<'de, &mut Context>
+    for HybridKemAnswer
+{
+    fn raw_decode_with_context(
+        buf: &'de [u8],
+        merge_context: &mut Context,
+    ) -> Result<(Self, &'de [u8]), BuckyError> {
+        let mut flags = context::FlagsCounter::new();
+        let (mut context, buf) = context::Decode::new(buf, merge_context)?;
+        let (sequence, buf) = context.decode(buf, "HybridKemAnswer.sequence", flags.next())?;
+        let (x25519_public, buf) = context.decode(buf, "HybridKemAnswer.x25519_public", flags.next())?;
+        let (kyber_ciphertext, buf) = context.decode(buf, "HybridKemAnswer.kyber_ciphertext", flags.next())?;
+
+        Ok((
+            Self {
+                sequence,
+                x25519_public,
+                kyber_ciphertext,
+            },
+            buf,
+        ))
+    }
+}
+
+#[test]
+fn encode_protocol_hybrid_kem_offer() {
+    use crate::interface::udp;
+
+    let src = HybridKemOffer {
+        sequence: TempSeq::from(rand::random::<u32>()),
+        x25519_public: vec![1u8; 32],
+        kyber_public: vec![2u8; 1184],
+    };
+
+    let mut buf = [0u8; udp::MTU];
+    let remain = src
+        .raw_encode_with_context(&mut buf, &mut merge_context::OtherEncode::default(), &None)
+        .unwrap();
+    let remain = remain.len();
+
+    let dec = &buf[..buf.len() - remain];
+    let (cmd, dec) = u8::raw_decode(dec)
+        .map(|(code, dec)| (PackageCmdCode::try_from(code).unwrap(), dec))
+        .unwrap();
+    assert_eq!(cmd, PackageCmdCode::HybridKemOffer);
+    let (dst, _) =
+        HybridKemOffer::raw_decode_with_context(&dec, &mut merge_context::OtherDecode::default())
+            .unwrap();
+
+    assert_eq!(dst.sequence, src.sequence);
+    assert_eq!(dst.x25519_public, src.x25519_public);
+    assert_eq!(dst.kyber_public, src.kyber_public);
+}
+
+#[test]
+fn encode_protocol_hybrid_kem_answer() {
+    use crate::interface::udp;
+
+    let src = HybridKemAnswer {
+        sequence: TempSeq::from(rand::random::<u32>()),
+        x25519_public: vec![1u8; 32],
+        kyber_ciphertext: vec![3u8; 1088],
+    };
+
+    let mut buf = [0u8; udp::MTU];
+    let remain = src
+        .raw_encode_with_context(&mut buf, &mut merge_context::OtherEncode::default(), &None)
+        .unwrap();
+    let remain = remain.len();
+
+    let dec = &buf[..buf.len() - remain];
+    let (cmd, dec) = u8::raw_decode(dec)
+        .map(|(code, dec)| (PackageCmdCode::try_from(code).unwrap(), dec))
+        .unwrap();
+    assert_eq!(cmd, PackageCmdCode::HybridKemAnswer);
+    let (dst, _) =
+        HybridKemAnswer::raw_decode_with_context(&dec, &mut merge_context::OtherDecode::default())
+            .unwrap();
+
+    assert_eq!(dst.sequence, src.sequence);
+    assert_eq!(dst.x25519_public, src.x25519_public);
+    assert_eq!(dst.kyber_ciphertext, src.kyber_ciphertext);
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum DatagramType {
     Data = 1,
@@ -1268,7 +1507,8 @@ impl From<(&TcpSynConnection, Vec<u8>, AesKey)> for Exchange {
             key_encrypted, 
             sign: Signature::default(),
             from_device_desc: tcp_syn.from_device_desc.clone(),
-            mix_key
+            mix_key, 
+            capabilities: 0
         }
     }
 }
@@ -1457,7 +1697,8 @@ impl From<(&TcpAckConnection, DeviceId, Vec<u8>, AesKey)> for Exchange {
             key_encrypted, 
             sign: Signature::default(),
             from_device_desc: tcp_ack.to_device_desc.clone(),
-            mix_key
+            mix_key, 
+            capabilities: 0
         }
     }
 }
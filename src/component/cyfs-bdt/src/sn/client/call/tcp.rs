@@ -66,6 +66,7 @@ impl TcpCall {
             packages.remote().clone(), 
             stack.device_cache().get_inner(packages.remote()).unwrap().desc().clone(), 
             packages.key().clone(), 
+            stack.keystore(), 
             self.0.timeout).await?;
         
         let escaped = Duration::from_micros(bucky_time_now() - start);
@@ -18,7 +18,8 @@ use crate::{
 use super::{
     cache::*, 
     ping::{PingConfig, PingClients, SnStatus}, 
-    call::{CallConfig, CallManager}
+    call::{CallConfig, CallManager}, 
+    probe::{ProbeConfig, SnProber, SnRtt}
 };
 
 pub trait PingClientCalledEvent<Context=()>: Send + Sync {
@@ -31,6 +32,7 @@ pub struct Config {
     pub atomic_interval: Duration, 
     pub ping: PingConfig, 
     pub call: CallConfig,
+    pub probe: ProbeConfig, 
 }
 
 struct ManagerImpl {
@@ -39,6 +41,7 @@ struct ManagerImpl {
     cache: SnCache, 
     ping: RwLock<PingClients>, 
     call: CallManager,
+    prober: SnProber, 
 }
 
 #[derive(Clone)]
@@ -52,8 +55,9 @@ impl ClientManager {
         let gen_seq = Arc::new(TempSeqGenerator::new());
         let manager = Self(Arc::new(ManagerImpl {
             cache: SnCache::new(), 
-            ping: RwLock::new(PingClients::new(stack.clone(), gen_seq.clone(), net_listener, vec![], local_device)),
+            ping: RwLock::new(PingClients::new(stack.clone(), gen_seq.clone(), net_listener, vec![], local_device, vec![])),
             call: CallManager::create(stack.clone()), 
+            prober: SnProber::new(stack.clone(), gen_seq.clone(), config.probe.clone()), 
             gen_seq, 
             stack, 
         }));
@@ -65,6 +69,7 @@ impl ClientManager {
                     let now = bucky_time_now();
                     manager.ping().on_time_escape(now);
                     manager.call().on_time_escape(now);
+                    manager.on_probe_time_escape(now);
                     let _ = future::timeout(atomic_interval, future::pending::<()>()).await;
                 }
             });
@@ -91,7 +96,8 @@ impl ClientManager {
                         self.0.gen_seq.clone(), 
                         to_close.net_listener().reset(None), 
                         to_close.sn_list().clone(), 
-                        to_close.default_local()
+                        to_close.default_local(), 
+                        self.prefer_order(to_close.sn_list())
                     );
                     *ping = to_start.clone();
                     Some((to_start, to_close))
@@ -115,12 +121,14 @@ impl ClientManager {
         let (to_start, to_close) = {
             let mut ping = self.0.ping.write().unwrap();
             let to_close = ping.clone();
+            let prefer = self.prefer_order(&sn_list);
             let to_start = PingClients::new(
                 self.0.stack.clone(), 
                 self.0.gen_seq.clone(), 
                 to_close.net_listener().reset(None), 
                 sn_list, 
-                to_close.default_local()
+                to_close.default_local(), 
+                prefer
             );
             *ping = to_start.clone();
             (to_start, to_close)
@@ -144,6 +152,95 @@ impl ClientManager {
     pub fn call(&self) -> &CallManager {
         &self.0.call
     }
+
+    // 通过sn查询远端最新的device，call不带payload，对端收到后会直接忽略
+    pub async fn query_device(&self, remote: &DeviceId, sn_list: &Vec<DeviceId>) -> BuckyResult<Device> {
+        let sessions = self.call().call(None, remote, sn_list, |_| vec![]).await?;
+        while let Some(session) = sessions.next().await? {
+            match session.result() {
+                Some(Ok(device)) => {
+                    info!("query device from sn success, remote={}, sn={}", remote, session.sn());
+                    return Ok(device);
+                }, 
+                Some(Err(err)) => {
+                    debug!("query device from sn failed, remote={}, sn={}, err={}", remote, session.sn(), err);
+                }, 
+                None => {}
+            }
+        }
+
+        Err(BuckyError::new(BuckyErrorCode::NotFound, format!("query device {} from sn not found", remote)))
+    }
+
+    pub fn prober(&self) -> &SnProber {
+        &self.0.prober
+    }
+
+    // 所有配置的sn按延迟排序的结果
+    pub fn sn_ranking(&self) -> Vec<SnRtt> {
+        self.0.prober.ranking()
+    }
+
+    fn prefer_order(&self, sn_list: &Vec<Device>) -> Vec<DeviceId> {
+        let sn_list: Vec<DeviceId> = sn_list.iter().map(|sn| sn.desc().device_id()).collect();
+        self.0.prober.prefer_order(&sn_list)
+    }
+
+    fn on_probe_time_escape(&self, now: Timestamp) {
+        let ping = self.ping();
+        if self.0.prober.on_time_escape(now) {
+            self.reselect(&ping);
+        }
+
+        // 只有一个sn时没有选择的余地
+        if ping.sn_list().len() > 1 && self.0.prober.need_probe(ping.sn_list(), now) {
+            self.0.prober.start(ping.sn_list(), ping.net_listener(), ping.default_local(), now);
+        }
+    }
+
+    fn reselect(&self, ping: &PingClients) {
+        if ping.status() != Some(SnStatus::Online) {
+            return;
+        }
+        let active = match ping.default_client() {
+            Some(client) => client.sn().clone(), 
+            None => return
+        };
+
+        if let Some(better) = self.0.prober.better_than(&active) {
+            if !self.ping().ptr_eq(ping) {
+                return;
+            }
+            info!("{} switch sn from {} to {} for lower rtt", ping, active, better);
+            let clients = self.reset_sn_list(ping.sn_list().clone());
+            self.keep_online(clients);
+        }
+    }
+
+    // 切换后原来的PingClients已经停止，由这里负责上线和离线重试
+    fn keep_online(&self, clients: PingClients) {
+        let manager = self.clone();
+        let interval = self.0.prober.config().offline_retry_interval;
+        task::spawn(async move {
+            let mut clients = clients;
+            loop {
+                match clients.wait_online().await {
+                    Ok(SnStatus::Online) => {
+                        if clients.wait_offline().await.is_err() {
+                            break;
+                        }
+                    }, 
+                    Ok(SnStatus::Offline) => {}, 
+                    Err(_) => break
+                }
+                let _ = future::timeout(interval, future::pending::<()>()).await;
+                match manager.reset() {
+                    Some(next) => clients = next, 
+                    None => break
+                }
+            }
+        });
+    }
 }
 
 impl OnUdpPackageBox for ClientManager {
@@ -156,7 +253,9 @@ impl OnUdpPackageBox for ClientManager {
                     match pkg.as_any().downcast_ref::<SnPingResp>() {
                         None => return Err(BuckyError::new(BuckyErrorCode::InvalidData, "should be SnPingResp")),
                         Some(ping_resp) => {
-                            let _ = self.ping().on_udp_ping_resp(ping_resp, &from, from_interface.clone());
+                            if !self.0.prober.on_udp_ping_resp(ping_resp, &from) {
+                                let _ = self.ping().on_udp_ping_resp(ping_resp, &from, from_interface.clone());
+                            }
                         }
                     }
                 },
@@ -3,7 +3,9 @@ mod cache;
 pub mod ping;
 pub mod call;
 mod manager;
+mod probe;
 
 pub use cache::*;
 pub use ping::{PingClients, SnStatus};
-pub use manager::*;
\ No newline at end of file
+pub use manager::*;
+pub use probe::{ProbeConfig, SnProber, SnRtt};
\ No newline at end of file
@@ -109,11 +109,17 @@ impl PingClients {
         gen_seq: Arc<TempSeqGenerator>, 
         net_listener: NetListener, 
         sn_list: Vec<Device>, 
-        local_device: Device
+        local_device: Device, 
+        prefer: Vec<DeviceId>
     ) -> Self {
         let strong_stack = Stack::from(&stack);
         let mut remain: Vec<(usize, DeviceId)> = sn_list.iter().map(|d| d.desc().device_id()).enumerate().collect();
-        remain.sort_by(|(_, l), (_, r)| r.object_id().distance(strong_stack.local_device_id().object_id()).cmp(&l.object_id().distance(strong_stack.local_device_id().object_id())));
+        // 有探测结果时按延迟排序，其余的按距离排序；从尾部取，所以倒序
+        let prefer_index = |sn: &DeviceId| prefer.iter().position(|p| p == sn).unwrap_or(prefer.len());
+        remain.sort_by(|(_, l), (_, r)| {
+            prefer_index(r).cmp(&prefer_index(l))
+                .then_with(|| r.object_id().distance(strong_stack.local_device_id().object_id()).cmp(&l.object_id().distance(strong_stack.local_device_id().object_id())))
+        });
    
         Self(Arc::new(ClientsImpl {
             stack, 
@@ -128,6 +134,10 @@ impl PingClients {
         }))
     }
 
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
     pub fn net_listener(&self) -> &NetListener {
         &self.0.net_listener
     }
@@ -0,0 +1,411 @@
+use log::*;
+use std::{
+    time::Duration,
+    sync::{Arc, RwLock},
+    collections::BTreeMap,
+};
+use async_std::{
+    task
+};
+use cyfs_base::*;
+use crate::{
+    types::*,
+    protocol::{*, v0::*},
+    interface::{NetListener, udp::{Interface, PackageBoxEncodeContext}},
+    history::keystore,
+    stack::{WeakStack, Stack}
+};
+
+
+#[derive(Clone)]
+pub struct ProbeConfig {
+    // 两轮探测之间的间隔
+    pub interval: Duration,
+    pub resend_interval: Duration,
+    pub timeout: Duration,
+    // 延迟至少降低这么多才切换sn，避免抖动
+    pub min_improve: Duration,
+    // 因为延迟切换sn之后，离线重试的间隔
+    pub offline_retry_interval: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct SnRtt {
+    pub sn: DeviceId,
+    // 平滑后的rtt，从未探测成功时为None
+    pub rtt: Option<Duration>,
+    pub healthy: bool,
+    pub fail_count: u32,
+    pub last_probe_time: Timestamp,
+}
+
+struct ProbeRequest {
+    sn: DeviceId,
+    send_time: Timestamp,
+}
+
+struct ProbeTarget {
+    sn: Device,
+    interface: Interface,
+    endpoints: Vec<Endpoint>,
+    responsed: bool,
+}
+
+struct ProbeRound {
+    start_time: Timestamp,
+    last_send_time: Timestamp,
+    local_device: Device,
+    targets: Vec<ProbeTarget>,
+    pending: BTreeMap<TempSeq, ProbeRequest>,
+}
+
+struct ProbeState {
+    stats: BTreeMap<DeviceId, SnRtt>,
+    round: Option<ProbeRound>,
+    last_round_time: Timestamp,
+}
+
+struct ProberImpl {
+    stack: WeakStack,
+    gen_seq: Arc<TempSeqGenerator>,
+    config: ProbeConfig,
+    state: RwLock<ProbeState>,
+}
+
+// 定时向配置的所有sn发送ping测量rtt，用于选择延迟最低的sn
+#[derive(Clone)]
+pub struct SnProber(Arc<ProberImpl>);
+
+impl std::fmt::Display for SnProber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stack = Stack::from(&self.0.stack);
+        write!(f, "SnProber{{local:{}}}", stack.local_device_id())
+    }
+}
+
+impl SnProber {
+    pub(crate) fn new(stack: WeakStack, gen_seq: Arc<TempSeqGenerator>, config: ProbeConfig) -> Self {
+        Self(Arc::new(ProberImpl {
+            stack,
+            gen_seq,
+            config,
+            state: RwLock::new(ProbeState {
+                stats: BTreeMap::new(),
+                round: None,
+                last_round_time: 0,
+            })
+        }))
+    }
+
+    pub fn config(&self) -> &ProbeConfig {
+        &self.0.config
+    }
+
+    // 健康的按rtt升序，其次是还没有探测结果的，最后是探测失败的
+    pub fn ranking(&self) -> Vec<SnRtt> {
+        let mut ranking: Vec<SnRtt> = self.0.state.read().unwrap().stats.values().cloned().collect();
+        ranking.sort_by_key(Self::rank_key);
+        ranking
+    }
+
+    fn rank_key(stat: &SnRtt) -> (u8, Duration, u32) {
+        match stat.rtt {
+            Some(rtt) if stat.healthy => (0, rtt, 0),
+            _ => if stat.fail_count == 0 {
+                (1, Duration::ZERO, 0)
+            } else {
+                (2, Duration::ZERO, stat.fail_count)
+            }
+        }
+    }
+
+    // 按ranking排序的sn列表，只包含传入的sn
+    pub fn prefer_order(&self, sn_list: &[DeviceId]) -> Vec<DeviceId> {
+        self.ranking().into_iter().map(|stat| stat.sn).filter(|sn| sn_list.contains(sn)).collect()
+    }
+
+    // 返回比当前sn延迟明显更低的健康sn
+    pub fn better_than(&self, active: &DeviceId) -> Option<DeviceId> {
+        let state = self.0.state.read().unwrap();
+        let active_rtt = state.stats.get(active).filter(|stat| stat.healthy).and_then(|stat| stat.rtt)?;
+        state.stats.values()
+            .filter(|stat| stat.healthy && stat.sn != *active)
+            .filter_map(|stat| stat.rtt.map(|rtt| (rtt, &stat.sn)))
+            .filter(|(rtt, _)| *rtt + self.0.config.min_improve < active_rtt)
+            .min_by_key(|(rtt, _)| *rtt)
+            .map(|(_, sn)| sn.clone())
+    }
+
+    pub(crate) fn need_probe(&self, sn_list: &[Device], now: Timestamp) -> bool {
+        let state = self.0.state.read().unwrap();
+        if state.round.is_some() {
+            return false;
+        }
+        if sn_list.iter().any(|sn| !state.stats.contains_key(&sn.desc().device_id())) {
+            return true;
+        }
+        now > state.last_round_time && Duration::from_micros(now - state.last_round_time) > self.0.config.interval
+    }
+
+    pub(crate) fn start(&self, sn_list: &[Device], net_listener: &NetListener, local_device: Device, now: Timestamp) {
+        let mut targets = vec![];
+        for sn in sn_list {
+            for interface in net_listener.udp().iter().filter(|interface| interface.local().addr().is_ipv4()) {
+                let endpoints: Vec<Endpoint> = sn.connect_info().endpoints().iter().filter(|endpoint| endpoint.is_udp() && endpoint.is_same_ip_version(&interface.local())).cloned().collect();
+                if endpoints.len() > 0 {
+                    targets.push(ProbeTarget {
+                        sn: sn.clone(),
+                        interface: interface.clone(),
+                        endpoints,
+                        responsed: false
+                    });
+                    break;
+                }
+            }
+        }
+
+        let to_send = {
+            let mut state = self.0.state.write().unwrap();
+            if state.round.is_some() {
+                return;
+            }
+            // 已经不在列表中的sn不再保留
+            state.stats.retain(|sn, _| sn_list.iter().any(|d| d.desc().device_id() == *sn));
+            state.last_round_time = now;
+            if targets.len() == 0 {
+                return;
+            }
+
+            info!("{} start probe round, sn count {}", self, targets.len());
+            let mut round = ProbeRound {
+                start_time: now,
+                last_send_time: now,
+                local_device,
+                targets,
+                pending: BTreeMap::new(),
+            };
+            let to_send = Self::prepare_send(&self.0.gen_seq, &mut round, now);
+            state.round = Some(round);
+            to_send
+        };
+
+        self.send(to_send);
+    }
+
+    fn prepare_send(gen_seq: &TempSeqGenerator, round: &mut ProbeRound, now: Timestamp) -> Vec<(TempSeq, Device, Interface, Vec<Endpoint>, Device)> {
+        let mut to_send = vec![];
+        for target in round.targets.iter().filter(|target| !target.responsed) {
+            let seq = gen_seq.generate();
+            round.pending.insert(seq, ProbeRequest {
+                sn: target.sn.desc().device_id(),
+                send_time: now
+            });
+            to_send.push((seq, target.sn.clone(), target.interface.clone(), target.endpoints.clone(), round.local_device.clone()));
+        }
+        round.last_send_time = now;
+        to_send
+    }
+
+    fn send(&self, to_send: Vec<(TempSeq, Device, Interface, Vec<Endpoint>, Device)>) {
+        for (seq, sn, interface, endpoints, local_device) in to_send {
+            let prober = self.clone();
+            task::spawn(async move {
+                let _ = prober.send_ping(seq, sn, interface, endpoints, local_device).await;
+            });
+        }
+    }
+
+    async fn send_ping(&self, seq: TempSeq, sn: Device, interface: Interface, endpoints: Vec<Endpoint>, local_device: Device) -> BuckyResult<()> {
+        let stack = Stack::from(&self.0.stack);
+        let sn_id = sn.desc().device_id();
+
+        let ping_pkg = SnPing {
+            protocol_version: 0,
+            stack_version: 0,
+            seq,
+            from_peer_id: Some(stack.local_device_id().clone()),
+            sn_peer_id: sn_id.clone(),
+            peer_info: Some(local_device.clone()),
+            send_time: bucky_time_now(),
+            contract_id: None,
+            receipt: None
+        };
+
+        let key_stub = stack.keystore().create_key(sn.desc(), true);
+        let mut pkg_box = PackageBox::encrypt_box(sn_id.clone(), key_stub.key.clone());
+
+        if let keystore::EncryptedKey::Unconfirmed(key_encrypted) = key_stub.encrypted {
+            let mut exchg = Exchange::from((&ping_pkg, local_device, key_encrypted, key_stub.key.mix_key));
+            let _ = exchg.sign(stack.keystore().signer()).await;
+            pkg_box.push(exchg);
+        }
+        pkg_box.push(ping_pkg);
+
+        let mut context = PackageBoxEncodeContext::default();
+        for endpoint in endpoints {
+            let result = interface.send_box_to(&mut context, &pkg_box, &endpoint);
+            debug!("{} probe seq:{:?} from {} to {}/{}, result: {:?}", self, seq, interface.local(), sn_id, endpoint, result);
+        }
+        Ok(())
+    }
+
+    // 返回true表示这个resp是探测发出的ping的回复
+    pub(crate) fn on_udp_ping_resp(&self, resp: &SnPingResp, from: &Endpoint) -> bool {
+        let now = bucky_time_now();
+        let mut state = self.0.state.write().unwrap();
+        let request = match state.round.as_mut().and_then(|round| round.pending.remove(&resp.seq)) {
+            Some(request) => request,
+            None => return false
+        };
+        if request.sn != resp.sn_peer_id {
+            return true;
+        }
+
+        let round = state.round.as_mut().unwrap();
+        if let Some(target) = round.targets.iter_mut().find(|target| target.sn.desc().device_id() == request.sn) {
+            if target.responsed {
+                return true;
+            }
+            target.responsed = true;
+        }
+
+        let sample = Duration::from_micros(now.saturating_sub(request.send_time));
+        debug!("{} probe resp from {}/{}, rtt {:?}", self, request.sn, from, sample);
+
+        let stat = state.stats.entry(request.sn.clone()).or_insert_with(|| SnRtt {
+            sn: request.sn.clone(),
+            rtt: None,
+            healthy: false,
+            fail_count: 0,
+            last_probe_time: now
+        });
+        stat.rtt = Some(match stat.rtt {
+            Some(rtt) => (rtt * 3 + sample) / 4,
+            None => sample
+        });
+        stat.healthy = true;
+        stat.fail_count = 0;
+        stat.last_probe_time = now;
+
+        true
+    }
+
+    // 返回true表示一轮探测刚刚结束
+    pub(crate) fn on_time_escape(&self, now: Timestamp) -> bool {
+        let (finished, to_send) = {
+            let mut state = self.0.state.write().unwrap();
+            let (finished, to_send) = match state.round.as_mut() {
+                Some(round) => {
+                    if round.targets.iter().all(|target| target.responsed) {
+                        (true, vec![])
+                    } else if now > round.start_time && Duration::from_micros(now - round.start_time) > self.0.config.timeout {
+                        (true, vec![])
+                    } else if now > round.last_send_time && Duration::from_micros(now - round.last_send_time) > self.0.config.resend_interval {
+                        (false, Self::prepare_send(&self.0.gen_seq, round, now))
+                    } else {
+                        (false, vec![])
+                    }
+                },
+                None => (false, vec![])
+            };
+
+            if finished {
+                let round = state.round.take().unwrap();
+                for target in round.targets.iter().filter(|target| !target.responsed) {
+                    let sn = target.sn.desc().device_id();
+                    warn!("{} probe {} timeout", self, sn);
+                    let stat = state.stats.entry(sn.clone()).or_insert_with(|| SnRtt {
+                        sn,
+                        rtt: None,
+                        healthy: false,
+                        fail_count: 0,
+                        last_probe_time: now
+                    });
+                    stat.healthy = false;
+                    stat.fail_count += 1;
+                    stat.last_probe_time = now;
+                }
+                info!("{} probe round finished, ranking {:?}", self, state.stats.values().map(|stat| (&stat.sn, stat.rtt)).collect::<Vec<_>>());
+            }
+
+            (finished, to_send)
+        };
+
+        self.send(to_send);
+        finished
+    }
+}
+
+
+#[test]
+fn rank_and_prefer() {
+    let prober = SnProber::new(
+        std::sync::Weak::new(), 
+        Arc::new(TempSeqGenerator::new()), 
+        ProbeConfig {
+            interval: Duration::from_secs(600),
+            resend_interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(5),
+            min_improve: Duration::from_millis(20),
+            offline_retry_interval: Duration::from_secs(60),
+        });
+
+    let private_key = PrivateKey::generate_rsa(1024).unwrap();
+    let sn = |i: u8| Device::new(
+        None,
+        UniqueId::create(&[i]),
+        vec![],
+        vec![],
+        vec![],
+        private_key.public(),
+        Area::default(),
+        DeviceCategory::OOD,
+    )
+    .build()
+    .desc()
+    .device_id();
+    let now = bucky_time_now();
+    let add = |sn: DeviceId, rtt: Option<u64>, healthy: bool, fail_count: u32| {
+        prober.0.state.write().unwrap().stats.insert(sn.clone(), SnRtt {
+            sn, 
+            rtt: rtt.map(Duration::from_millis), 
+            healthy, 
+            fail_count, 
+            last_probe_time: now
+        });
+    };
+
+    // 0: 100ms, 1: 70ms, 2: 90ms, 3: 还没有结果, 4: 失败一次, 5: 失败三次(之前的rtt不再参与排序)
+    add(sn(0), Some(100), true, 0);
+    add(sn(1), Some(70), true, 0);
+    add(sn(2), Some(90), true, 0);
+    add(sn(3), None, false, 0);
+    add(sn(4), None, false, 1);
+    add(sn(5), Some(10), false, 3);
+
+    let ranking: Vec<DeviceId> = prober.ranking().into_iter().map(|stat| stat.sn).collect();
+    assert_eq!(ranking, vec![sn(1), sn(2), sn(0), sn(3), sn(4), sn(5)]);
+
+    // 只返回传入的sn
+    assert_eq!(prober.prefer_order(&[sn(5), sn(0), sn(2)]), vec![sn(2), sn(0), sn(5)]);
+
+    // 至少快min_improve才切换
+    assert_eq!(prober.better_than(&sn(0)), Some(sn(1)));
+    assert_eq!(prober.better_than(&sn(2)), None);
+    assert_eq!(prober.better_than(&sn(1)), None);
+    // 当前sn没有有效的rtt时不切换
+    assert_eq!(prober.better_than(&sn(3)), None);
+    assert_eq!(prober.better_than(&sn(5)), None);
+
+    // 不是探测发出的ping
+    let resp = SnPingResp {
+        seq: TempSeq::from(1), 
+        sn_peer_id: sn(0), 
+        result: 0, 
+        peer_info: None, 
+        end_point_array: vec![], 
+        receipt: None,
+    };
+    assert!(!prober.on_udp_ping_resp(&resp, &Endpoint::default()));
+    assert!(!prober.on_time_escape(now));
+}
@@ -0,0 +1,375 @@
+use std::{
+    collections::HashSet,
+    net::IpAddr,
+    sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}},
+    time::Duration,
+};
+use lru_time_cache::LruCache;
+use serde::Serialize;
+use cyfs_base::*;
+use crate::types::*;
+
+
+#[derive(Clone)]
+pub struct SnLimiterConfig {
+    // 每个来源ip每秒允许的包数和突发上限，0表示不限制
+    pub ip_rate: u32,
+    pub ip_burst: u32,
+    // 每个device每秒允许的包数和突发上限，0表示不限制
+    pub device_rate: u32,
+    pub device_burst: u32,
+    // 在窗口期内ping次数超过阈值视为ping flood，device会被封禁一段时间
+    pub ping_flood_threshold: u32,
+    pub ping_flood_window: Duration,
+    pub ban_duration: Duration,
+    // 空闲超过这个时间的计数项会被回收，需要大于ban_duration，否则封禁会提前失效
+    pub idle_timeout: Duration,
+    // 最多跟踪的来源ip和device数，超过时淘汰最久没有收到包的项，避免伪造来源的包耗尽内存
+    pub max_tracked_ips: usize,
+    pub max_tracked_devices: usize,
+}
+
+// 默认阈值按客户端的默认配置估算，和Stack::open里的sn_client配置保持一致
+const CLIENT_PING_INTERVAL: Duration = Duration::from_secs(25);
+const CLIENT_PING_RESEND_INTERVAL: Duration = Duration::from_millis(500);
+const CLIENT_PING_RESEND_TIMEOUT: Duration = Duration::from_secs(5);
+const CLIENT_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// 一个device通常有ipv4/ipv6、有线/无线的多个interface，每个interface各自ping
+const CLIENT_PING_INTERFACES: u32 = 4;
+
+impl SnLimiterConfig {
+    // 估算正常客户端在窗口期内最多发出的ping包数：
+    // 每个interface每隔ping_interval发起一次ping，没有收到回应时每隔resend_interval重发，直到resend_timeout；
+    // sn探测也按resend_interval重发，直到probe_timeout
+    pub fn ping_flood_threshold(
+        window: Duration,
+        ping_interval: Duration,
+        resend_interval: Duration,
+        resend_timeout: Duration,
+        probe_timeout: Duration,
+        interfaces: u32,
+    ) -> u32 {
+        let resend_interval = resend_interval.as_millis().max(1);
+        let sessions = (window.as_millis() / ping_interval.as_millis().max(1) + 1) as u32;
+        let per_session = (resend_timeout.as_millis() / resend_interval + 1) as u32;
+        let probe = (probe_timeout.as_millis() / resend_interval + 1) as u32;
+        (sessions * per_session + probe) * interfaces
+    }
+}
+
+impl Default for SnLimiterConfig {
+    fn default() -> Self {
+        let ping_flood_window = Duration::from_secs(60);
+        Self {
+            ip_rate: 50,
+            ip_burst: 200,
+            device_rate: 20,
+            device_burst: 100,
+            ping_flood_threshold: Self::ping_flood_threshold(
+                ping_flood_window,
+                CLIENT_PING_INTERVAL,
+                CLIENT_PING_RESEND_INTERVAL,
+                CLIENT_PING_RESEND_TIMEOUT,
+                CLIENT_PROBE_TIMEOUT,
+                CLIENT_PING_INTERFACES,
+            ),
+            ping_flood_window,
+            ban_duration: Duration::from_secs(300),
+            idle_timeout: Duration::from_secs(600),
+            max_tracked_ips: 100000,
+            max_tracked_devices: 100000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SnLimiterStat {
+    pub passed: u64,
+    pub denied: u64,
+    pub ip_limited: u64,
+    pub device_limited: u64,
+    pub ping_flood: u64,
+    pub banned_devices: usize,
+    pub tracked_ips: usize,
+    pub tracked_devices: usize,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_time: Timestamp,
+}
+
+impl TokenBucket {
+    fn new(burst: u32, now: Timestamp) -> Self {
+        Self {
+            tokens: burst as f64,
+            last_time: now,
+        }
+    }
+
+    fn take(&mut self, rate: u32, burst: u32, now: Timestamp) -> bool {
+        if now > self.last_time {
+            let escaped = (now - self.last_time) as f64 / 1000_000.0;
+            self.tokens = (self.tokens + escaped * rate as f64).min(burst as f64);
+            self.last_time = now;
+        }
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct PingCounter {
+    window_start: Timestamp,
+    count: u32,
+}
+
+struct DeviceState {
+    bucket: TokenBucket,
+    ping: PingCounter,
+    banned_until: Option<Timestamp>,
+}
+
+struct Rules {
+    allow_ips: HashSet<IpAddr>,
+    deny_ips: HashSet<IpAddr>,
+    allow_devices: HashSet<DeviceId>,
+    deny_devices: HashSet<DeviceId>,
+}
+
+struct LimiterImpl {
+    config: SnLimiterConfig,
+    rules: Mutex<Rules>,
+    ips: Mutex<LruCache<IpAddr, TokenBucket>>,
+    devices: Mutex<LruCache<DeviceId, DeviceState>>,
+
+    passed: AtomicU64,
+    denied: AtomicU64,
+    ip_limited: AtomicU64,
+    device_limited: AtomicU64,
+    ping_flood: AtomicU64,
+}
+
+// sn的限流和黑白名单，先在收包时按来源ip过滤，解包后再按device过滤
+#[derive(Clone)]
+pub struct SnLimiter(Arc<LimiterImpl>);
+
+impl SnLimiter {
+    pub fn new(config: SnLimiterConfig) -> Self {
+        let ips = LruCache::with_expiry_duration_and_capacity(config.idle_timeout, config.max_tracked_ips);
+        let devices = LruCache::with_expiry_duration_and_capacity(config.idle_timeout, config.max_tracked_devices);
+        Self(Arc::new(LimiterImpl {
+            config,
+            rules: Mutex::new(Rules {
+                allow_ips: Default::default(),
+                deny_ips: Default::default(),
+                allow_devices: Default::default(),
+                deny_devices: Default::default(),
+            }),
+            ips: Mutex::new(ips),
+            devices: Mutex::new(devices),
+            passed: AtomicU64::new(0),
+            denied: AtomicU64::new(0),
+            ip_limited: AtomicU64::new(0),
+            device_limited: AtomicU64::new(0),
+            ping_flood: AtomicU64::new(0),
+        }))
+    }
+
+    pub fn allow_ip(&self, ip: IpAddr) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.deny_ips.remove(&ip);
+        rules.allow_ips.insert(ip);
+    }
+
+    pub fn deny_ip(&self, ip: IpAddr) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.allow_ips.remove(&ip);
+        rules.deny_ips.insert(ip);
+    }
+
+    pub fn remove_ip_rule(&self, ip: &IpAddr) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.allow_ips.remove(ip);
+        rules.deny_ips.remove(ip);
+    }
+
+    pub fn allow_device(&self, device_id: DeviceId) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.deny_devices.remove(&device_id);
+        rules.allow_devices.insert(device_id);
+    }
+
+    pub fn deny_device(&self, device_id: DeviceId) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.allow_devices.remove(&device_id);
+        rules.deny_devices.insert(device_id);
+    }
+
+    pub fn remove_device_rule(&self, device_id: &DeviceId) {
+        let mut rules = self.0.rules.lock().unwrap();
+        rules.allow_devices.remove(device_id);
+        rules.deny_devices.remove(device_id);
+    }
+
+    // 解包之前调用，返回false时直接丢弃
+    pub fn check_endpoint(&self, from: &SocketAddr, now: Timestamp) -> bool {
+        let ip = from.ip();
+        {
+            let rules = self.0.rules.lock().unwrap();
+            if rules.allow_ips.contains(&ip) {
+                return true;
+            }
+            if rules.deny_ips.contains(&ip) {
+                self.0.denied.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        }
+
+        let config = &self.0.config;
+        if config.ip_rate == 0 {
+            return true;
+        }
+
+        let mut ips = self.0.ips.lock().unwrap();
+        let bucket = ips.entry(ip).or_insert_with(|| TokenBucket::new(config.ip_burst, now));
+        if bucket.take(config.ip_rate, config.ip_burst, now) {
+            true
+        } else {
+            self.0.ip_limited.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    // 解包之后调用，返回false时不再处理
+    pub fn check_device(&self, device_id: &DeviceId, is_ping: bool, now: Timestamp) -> bool {
+        {
+            let rules = self.0.rules.lock().unwrap();
+            if rules.allow_devices.contains(device_id) {
+                self.0.passed.fetch_add(1, Ordering::Relaxed);
+                return true;
+            }
+            if rules.deny_devices.contains(device_id) {
+                self.0.denied.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        }
+
+        let config = &self.0.config;
+        let mut devices = self.0.devices.lock().unwrap();
+        let state = devices.entry(device_id.clone()).or_insert_with(|| DeviceState {
+            bucket: TokenBucket::new(config.device_burst, now),
+            ping: PingCounter {
+                window_start: now,
+                count: 0,
+            },
+            banned_until: None,
+        });
+
+        if let Some(until) = state.banned_until {
+            if now < until {
+                self.0.denied.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            info!("sn limiter unban device {}", device_id);
+            state.banned_until = None;
+        }
+
+        if is_ping && config.ping_flood_threshold > 0 {
+            let window = config.ping_flood_window.as_micros() as u64;
+            if now > state.ping.window_start + window {
+                state.ping.window_start = now;
+                state.ping.count = 0;
+            }
+            state.ping.count += 1;
+
+            if state.ping.count > config.ping_flood_threshold {
+                warn!("sn limiter detect ping flood from {}, count {} in {:?}, will ban for {:?}",
+                    device_id, state.ping.count, config.ping_flood_window, config.ban_duration);
+                state.banned_until = Some(now + config.ban_duration.as_micros() as u64);
+                state.ping.count = 0;
+                self.0.ping_flood.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        }
+
+        if config.device_rate > 0 && !state.bucket.take(config.device_rate, config.device_burst, now) {
+            self.0.device_limited.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        self.0.passed.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    pub fn stat(&self) -> SnLimiterStat {
+        let now = bucky_time_now();
+        let (banned_devices, tracked_devices) = {
+            let devices = self.0.devices.lock().unwrap();
+            let banned = devices.peek_iter().filter(|(_, state)| match state.banned_until {
+                Some(until) => now < until,
+                None => false,
+            }).count();
+            (banned, devices.len())
+        };
+
+        SnLimiterStat {
+            passed: self.0.passed.load(Ordering::Relaxed),
+            denied: self.0.denied.load(Ordering::Relaxed),
+            ip_limited: self.0.ip_limited.load(Ordering::Relaxed),
+            device_limited: self.0.device_limited.load(Ordering::Relaxed),
+            ping_flood: self.0.ping_flood.load(Ordering::Relaxed),
+            banned_devices,
+            tracked_ips: self.0.ips.lock().unwrap().len(),
+            tracked_devices,
+        }
+    }
+}
+
+#[test]
+fn sn_limiter() {
+    let config = SnLimiterConfig {
+        max_tracked_ips: 2, 
+        ..Default::default()
+    };
+
+    // 默认配置下正常客户端4个interface，每次ping都重发到超时，60秒内不会被当成ping flood
+    assert_eq!(config.ping_flood_threshold, 160);
+    let limiter = SnLimiter::new(config.clone());
+    let device = DeviceId::default();
+    let start = bucky_time_now();
+    let resend = CLIENT_PING_RESEND_INTERVAL.as_micros() as u64;
+    let mut sent = 0;
+    for session in 0..3 {
+        let session_start = start + session * CLIENT_PING_INTERVAL.as_micros() as u64;
+        for i in 0..(CLIENT_PING_RESEND_TIMEOUT.as_micros() as u64 / resend + 1) {
+            for _ in 0..CLIENT_PING_INTERFACES {
+                assert!(limiter.check_device(&device, true, session_start + i * resend));
+                sent += 1;
+            }
+        }
+    }
+    assert!(sent < config.ping_flood_threshold);
+
+    // 超过阈值被封禁，封禁期间其它包也被丢弃
+    let limiter = SnLimiter::new(config.clone());
+    let mut now = start;
+    for _ in 0..config.ping_flood_threshold {
+        now += 100_000;
+        assert!(limiter.check_device(&device, true, now));
+    }
+    assert!(!limiter.check_device(&device, true, now + 100_000));
+    assert!(!limiter.check_device(&device, false, now + 1_000_000));
+    assert_eq!(limiter.stat().banned_devices, 1);
+
+    // 伪造大量来源ip时只跟踪最近的max_tracked_ips个
+    for i in 0..100u8 {
+        let from = SocketAddr::from(([10, 0, 0, i], 8060));
+        assert!(limiter.check_endpoint(&from, start));
+    }
+    assert_eq!(limiter.stat().tracked_ips, 2);
+}
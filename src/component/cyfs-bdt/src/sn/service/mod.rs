@@ -1,12 +1,15 @@
 mod receipt;
 mod net_listener;
+mod limiter;
 mod peer_manager;
+mod peer_store;
 mod service;
 mod resend_queue;
 mod call_stub;
 mod statistic;
 
 pub use receipt::*;
+pub use limiter::{SnLimiter, SnLimiterConfig, SnLimiterStat};
 pub use service::*;
 
 
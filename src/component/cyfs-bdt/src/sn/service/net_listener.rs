@@ -25,11 +25,12 @@ use crate::{
         tcp::{AcceptInterface, PackageInterface},
     }, sn::service::statistic::StatisticManager, 
 };
-use super::SnService;
+use super::{SnService, limiter::SnLimiter};
 
 pub struct NetListener {
     thread_pool: ThreadPool, 
-    close_notifier: async_std::channel::Sender<()>
+    close_notifier: async_std::channel::Sender<()>, 
+    udp_listeners: Vec<UdpListener>, 
 }
 
 impl NetListener {
@@ -57,6 +58,12 @@ impl NetListener {
         }
 
         if let Some(cmd_pkg) = cmd_pkg {
+            let is_ping = matches!(cmd_pkg.cmd_code(), PackageCmdCode::SnPing);
+            if !service.limiter().check_device(pkg_box.remote(), is_ping, bucky_time_now()) {
+                debug!("sn limiter drop package from {}, remote {:?}", pkg_box.remote(), resp_sender.remote());
+                return;
+            }
+
             if let PackageCmdCode::SnPing = cmd_pkg.cmd_code() {
                 let peer_info = if let Some(ping_req) = cmd_pkg.as_any().downcast_ref::<SnPing>() {
                     ping_req.peer_info.as_ref()
@@ -277,7 +284,7 @@ impl NetListener {
     }
     */
 
-    async fn bind(endpoints: &[Endpoint], local_device_id: &DeviceId, key_store: &Keystore) -> (Vec<BuckyResult<UdpListener>>, Vec<BuckyResult<TcpAcceptor>>) {
+    async fn bind(endpoints: &[Endpoint], local_device_id: &DeviceId, key_store: &Keystore, limiter: &SnLimiter) -> (Vec<BuckyResult<UdpListener>>, Vec<BuckyResult<TcpAcceptor>>) {
         let mut udp_futures = vec![];
         let mut tcp_futures = vec![];
 
@@ -288,10 +295,10 @@ impl NetListener {
         for endpoint in endpoints {
             match endpoint.protocol() {
                 Protocol::Udp => {
-                    udp_futures.push(UdpListener::bind(endpoint.addr().clone(), key_store.clone()));
+                    udp_futures.push(UdpListener::bind(endpoint.addr().clone(), key_store.clone(), limiter.clone()));
                 },
                 Protocol::Tcp => {
-                    tcp_futures.push(TcpAcceptor::bind(local_device_id.clone(), endpoint.addr().clone(), key_store.clone()));
+                    tcp_futures.push(TcpAcceptor::bind(local_device_id.clone(), endpoint.addr().clone(), key_store.clone(), limiter.clone()));
                 }
                 Protocol::Unk => {
                     log::info!("sn-miner unknown listener.")
@@ -313,8 +320,8 @@ impl NetListener {
         endpoints_v4: &[Endpoint], 
         service: SnService) -> BuckyResult<(NetListener, usize, usize)> {
         
-        let (mut udp_results, mut tcp_results) = Self::bind(endpoints_v6, service.local_device_id(), service.key_store()).await;
-        let (mut udp_results_v4, mut tcp_results_v4) = Self::bind(endpoints_v4, service.local_device_id(), service.key_store()).await;
+        let (mut udp_results, mut tcp_results) = Self::bind(endpoints_v6, service.local_device_id(), service.key_store(), service.limiter()).await;
+        let (mut udp_results_v4, mut tcp_results_v4) = Self::bind(endpoints_v4, service.local_device_id(), service.key_store(), service.limiter()).await;
 
         udp_results.append(&mut udp_results_v4);
         tcp_results.append(&mut tcp_results_v4);
@@ -376,10 +383,18 @@ impl NetListener {
 
         Ok((NetListener {
             thread_pool, 
-            close_notifier
+            close_notifier, 
+            udp_listeners, 
         }, udp_count, tcp_count))
     }
 
+    // 用持久化的地址和密钥恢复到客户端的udp通道
+    pub(super) fn udp_sender(&self, local: &SocketAddr, remote_device_id: DeviceId, key: MixAesKey, to_addr: SocketAddr) -> Option<UdpSender> {
+        self.udp_listeners.iter()
+            .find(|l| l.0.addr == *local)
+            .map(|l| UdpSender::new(l.0.clone(), remote_device_id, key, to_addr))
+    }
+
     pub fn close(self) {
         // self.close_notifier.send(()).await;
     }
@@ -400,13 +415,14 @@ struct UdpInterface {
     addr: SocketAddr,
     socket: UdpSocket,
     key_store: Keystore,
+    limiter: SnLimiter,
 }
 
 #[derive(Clone)]
 struct UdpListener(Arc<UdpInterface>);
 
 impl UdpListener {
-    async fn bind(addr: SocketAddr, key_store: Keystore) -> BuckyResult<UdpListener> {
+    async fn bind(addr: SocketAddr, key_store: Keystore, limiter: SnLimiter) -> BuckyResult<UdpListener> {
         let addr_str = addr.to_string();
 
         match UdpSocket::bind(addr.clone()).await {
@@ -427,7 +443,8 @@ impl UdpListener {
                 Ok(UdpListener(Arc::new(UdpInterface {
                     addr,
                     socket,
-                    key_store
+                    key_store,
+                    limiter,
                 })))
             }
         }
@@ -441,6 +458,11 @@ impl UdpListener {
             match rr {
                 Ok((len, from)) => {
                     trace!("udp({}) recv {} bytes from {}", self.0.addr, len, from);
+                    // 解包前按来源ip限流，避免解密消耗
+                    if !self.0.limiter.check_endpoint(&from, bucky_time_now()) {
+                        trace!("udp({}) limiter drop {} bytes from {}", self.0.addr, len, from);
+                        continue;
+                    }
                     let recv = &mut recv_buf[..len];
 
                     let ctx = PackageBoxDecodeContext::new_inplace(recv.as_mut_ptr(), recv.len(), &self.0.key_store);
@@ -486,10 +508,11 @@ struct TcpAcceptor {
     addr: SocketAddr,
     socket: Arc<TcpListener>,
     key_store: Keystore,
+    limiter: SnLimiter,
 }
 
 impl TcpAcceptor {
-    async fn bind(local_device_id: DeviceId, addr: SocketAddr, key_store: Keystore) -> BuckyResult<TcpAcceptor> {
+    async fn bind(local_device_id: DeviceId, addr: SocketAddr, key_store: Keystore, limiter: SnLimiter) -> BuckyResult<TcpAcceptor> {
         match TcpListener::bind(addr.clone()).await {
             Err(e) => {
                 warn!("tcp-listener({}) bind failed, err: {}", addr, e);
@@ -502,6 +525,7 @@ impl TcpAcceptor {
                     addr,
                     socket: Arc::new(socket),
                     key_store,
+                    limiter,
                 })
             }
         }
@@ -511,6 +535,11 @@ impl TcpAcceptor {
         loop {
             match self.socket.accept().await {
                 Ok((socket, from_addr)) => {
+                    if !self.limiter.check_endpoint(&from_addr, bucky_time_now()) {
+                        debug!("tcp-listener({}) limiter drop stream from {:?}", self.addr, from_addr);
+                        let _ = socket.shutdown(Shutdown::Both);
+                        continue;
+                    }
                     debug!("tcp-listener({}) accept a stream, will read the first package, from {:?}", self.addr, from_addr);
                     match AcceptInterface::accept(socket.clone(), &self.local_device_id,&self.key_store, Duration::from_secs(2)).await {
                         Ok((interface, first_box)) => {
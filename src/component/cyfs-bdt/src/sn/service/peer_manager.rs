@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, hash_map}, 
+    collections::{HashMap, HashSet, hash_map}, 
     time::Duration, 
     sync::{Arc, atomic::{AtomicU64, Ordering}}
 };
@@ -9,19 +9,24 @@ use crate::{
     types::*, 
 };
 use super::{
-    net_listener::UdpSender, statistic::{PeerStatus, StatisticManager, }
+    net_listener::UdpSender, 
+    peer_store::PeerRecord, 
+    statistic::{PeerStatus, StatisticManager, }
 };
 
 struct Config {
     pub client_ping_interval: Duration, 
-    pub client_ping_timeout: Duration
+    pub client_ping_timeout: Duration, 
+    // 没有变化的peer，间隔这么久刷新一次持久化记录的时间
+    pub store_refresh_interval: Duration, 
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             client_ping_interval: Duration::from_millis(25000),
-            client_ping_timeout: Duration::from_secs(300)
+            client_ping_timeout: Duration::from_secs(300), 
+            store_refresh_interval: Duration::from_secs(120), 
         }
     }
 }
@@ -45,6 +50,8 @@ struct CachedPeerInfo {
 
     pub last_ping_seq: TempSeq,
     pub peer_status: PeerStatus,
+    // 最后一次写入持久化存储的时间，0表示需要写入
+    pub last_store_time: Timestamp,
     // pub call_peers: HashMap<DeviceId, TempSeq>, // <peerid, last_call_seq>
     // pub receipt: SnServiceReceipt,
     // pub last_receipt_request_time: ReceiptRequestTime,
@@ -86,6 +93,7 @@ impl CachedPeerInfo {
             last_send_time: send_time,
             last_call_time: 0,
             peer_status,
+            last_store_time: 0,
             // call_peers: Default::default(),
             // receipt: Default::default(),
             // last_receipt_request_time: ReceiptRequestTime::None,
@@ -103,9 +111,25 @@ impl CachedPeerInfo {
 
     fn update_key(&mut self, aes_key: &MixAesKey) {
         if let Some(k) = self.aes_key.as_mut() {
+            if k.mix_key != aes_key.mix_key {
+                self.last_store_time = 0;
+            }
             *k = aes_key.clone();
         } else {
             self.aes_key = Some(aes_key.clone());
+            self.last_store_time = 0;
+        }
+    }
+
+    fn to_record(&self, device_id: &DeviceId, now: Timestamp) -> PeerRecord {
+        PeerRecord {
+            device_id: device_id.clone(), 
+            desc: self.desc.clone(), 
+            key: self.aes_key.clone().unwrap_or_else(|| self.sender.key().clone()), 
+            local: self.sender.local().clone(), 
+            remote: self.sender.remote().clone(), 
+            last_send_time: self.last_send_time, 
+            update_time: now, 
         }
     }
 
@@ -131,6 +155,7 @@ impl CachedPeerInfo {
 
         self.desc = desc.clone();
         self.is_wan = has_wan_endpoint(desc);
+        self.last_store_time = 0;
         Ok(true)
     }
 }
@@ -138,6 +163,8 @@ impl CachedPeerInfo {
 struct Peers {
     active_peers: HashMap<DeviceId, CachedPeerInfo>,
     knock_peers: HashMap<DeviceId, CachedPeerInfo>,
+    // 需要写入持久化存储的peer
+    dirty_peers: HashSet<DeviceId>,
 }
 
 impl Peers {
@@ -190,6 +217,7 @@ impl PeerManager {
             peers: Mutex::new(Peers {
                 active_peers: Default::default(),
                 knock_peers: Default::default(),
+                dirty_peers: Default::default(),
             }),
             last_knock_time: AtomicU64::new(bucky_time_now()),
             config: Default::default(),
@@ -249,6 +277,9 @@ impl PeerManager {
             // 客户端被签名的地址才被更新，避免恶意伪装
             if contain_addr(&cached_peer.desc, sender.remote()) 
                 || cached_peer.sender.key().mix_key != sender.key().mix_key {
+                if cached_peer.sender.remote() != sender.remote() {
+                    cached_peer.last_store_time = 0;
+                }
                 cached_peer.sender = sender.clone();
             }
 
@@ -259,10 +290,22 @@ impl PeerManager {
             true
         };
 
+        let now = bucky_time_now();
+        let store_refresh_interval = self.config.store_refresh_interval.as_micros() as u64;
+        let need_store = |cached_peer: &CachedPeerInfo| -> bool {
+            cached_peer.last_store_time == 0 || now > cached_peer.last_store_time + store_refresh_interval
+        };
+
         let mut peers = self.peers.lock().unwrap();
         // 1.从活跃peer中搜索已有cache
         if let Some(p) = peers.active_peers.get_mut(&peerid) {
-            return exist_cache_found(p);
+            if !exist_cache_found(p) {
+                return false;
+            }
+            if need_store(p) {
+                peers.dirty_peers.insert(peerid);
+            }
+            return true;
         }
 
         // 2.从待淘汰peer中搜索已有cache
@@ -275,6 +318,9 @@ impl PeerManager {
             None
         };
         if let Some(to_active) = to_active {
+            if need_store(&to_active) {
+                peers.dirty_peers.insert(peerid.clone());
+            }
             let old = peers.active_peers.insert(peerid.clone(), to_active);
             assert!(old.is_none());
             return true;
@@ -285,12 +331,55 @@ impl PeerManager {
             Some(desc) => {
                 let old = peers.active_peers.insert(peerid.clone(), CachedPeerInfo::new(desc.clone(), sender, aes_key, send_time, seq, self.statistic_manager.get_peer_status(peerid.clone(), send_time)));
                 assert!(old.is_none());
+                peers.dirty_peers.insert(peerid);
                 true
             }
             None => false
         }
     }
 
+    // 从持久化存储恢复的peer放入待淘汰列表，一个超时周期内没有ping就会被清理
+    pub fn restore(&self, records: Vec<(PeerRecord, UdpSender)>) {
+        let mut peers = self.peers.lock().unwrap();
+        for (record, sender) in records {
+            if peers.active_peers.contains_key(&record.device_id) 
+                || peers.knock_peers.contains_key(&record.device_id) {
+                continue;
+            }
+
+            let mut cached_peer = CachedPeerInfo::new(
+                record.desc, 
+                Arc::new(sender), 
+                Some(&record.key), 
+                record.last_send_time, 
+                TempSeq::default(), 
+                self.statistic_manager.get_peer_status(record.device_id.clone(), record.last_send_time));
+            cached_peer.last_store_time = record.update_time;
+            peers.knock_peers.insert(record.device_id, cached_peer);
+        }
+    }
+
+    // 取出需要写入持久化存储的记录
+    pub fn take_store_records(&self, now: Timestamp) -> Vec<PeerRecord> {
+        let mut guard = self.peers.lock().unwrap();
+        let peers = &mut *guard;
+        let dirty_peers: Vec<DeviceId> = peers.dirty_peers.drain().collect();
+
+        let mut records = Vec::with_capacity(dirty_peers.len());
+        for device_id in dirty_peers {
+            let cached_peer = match peers.active_peers.get_mut(&device_id) {
+                Some(p) => Some(p),
+                None => peers.knock_peers.get_mut(&device_id),
+            };
+            if let Some(cached_peer) = cached_peer {
+                cached_peer.last_store_time = now;
+                records.push(cached_peer.to_record(&device_id, now));
+            }
+        }
+
+        records
+    }
+
     pub fn try_knock_timeout(&self, now: Timestamp) -> Option<Vec<DeviceId>> {
         let last_knock_time = self.last_knock_time.load(Ordering::SeqCst);
         let drop_maps = if now > last_knock_time && Duration::from_micros(now - last_knock_time) > self.config.client_ping_timeout {
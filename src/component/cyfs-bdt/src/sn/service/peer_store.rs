@@ -0,0 +1,243 @@
+use std::{
+    path::Path,
+    sync::Mutex,
+    time::Duration,
+    convert::TryFrom,
+    str::FromStr,
+};
+use rusqlite::{params, Connection};
+use cyfs_base::*;
+use crate::types::*;
+
+
+// 持久化的客户端注册信息，sn重启后用来恢复call路由
+pub struct PeerRecord {
+    pub device_id: DeviceId,
+    pub desc: Device,
+    pub key: MixAesKey,
+    // 收到ping的本地udp地址和客户端的外网地址
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub last_send_time: Timestamp,
+    pub update_time: Timestamp,
+}
+
+pub struct PeerStore {
+    conn: Mutex<Connection>,
+    ttl: Duration,
+}
+
+impl PeerStore {
+    pub fn open(path: &Path, ttl: Duration) -> BuckyResult<Self> {
+        if let Some(dir) = path.parent() {
+            if !dir.is_dir() {
+                std::fs::create_dir_all(dir).map_err(|e| {
+                    let msg = format!("create sn peer store dir failed, dir={}, err={}", dir.display(), e);
+                    log::error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                })?;
+            }
+        }
+
+        let conn = Connection::open(path).map_err(|e| {
+            let msg = format!("open sn peer store failed, path={}, err={}", path.display(), e);
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::SqliteError, msg)
+        })?;
+
+        let sql = r#"CREATE TABLE IF NOT EXISTS sn_peers (
+            device_id TEXT PRIMARY KEY NOT NULL UNIQUE,
+            desc BLOB NOT NULL,
+            enc_key BLOB NOT NULL,
+            mix_key BLOB NOT NULL,
+            cipher INTEGER NOT NULL,
+            local_addr TEXT NOT NULL,
+            remote_addr TEXT NOT NULL,
+            last_send_time INTEGER NOT NULL,
+            update_time INTEGER NOT NULL
+        );"#;
+        conn.execute(sql, []).map_err(Self::map_sql_error)?;
+
+        log::info!("sn peer store opened, path={}, ttl={:?}", path.display(), ttl);
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            ttl,
+        })
+    }
+
+    fn map_sql_error(e: rusqlite::Error) -> BuckyError {
+        let msg = format!("sn peer store sql error: {}", e);
+        log::error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::SqliteError, msg)
+    }
+
+    pub fn save(&self, records: &[PeerRecord]) -> BuckyResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(Self::map_sql_error)?;
+        for record in records {
+            tx.execute(
+                "REPLACE INTO sn_peers (device_id, desc, enc_key, mix_key, cipher, local_addr, remote_addr, last_send_time, update_time)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    record.device_id.to_string(),
+                    record.desc.to_vec()?,
+                    record.key.enc_key.to_vec()?,
+                    record.key.mix_key.to_vec()?,
+                    record.key.cipher as u8,
+                    record.local.to_string(),
+                    record.remote.to_string(),
+                    record.last_send_time as i64,
+                    record.update_time as i64,
+                ]).map_err(Self::map_sql_error)?;
+        }
+        tx.commit().map_err(Self::map_sql_error)
+    }
+
+    pub fn remove(&self, devices: &[DeviceId]) -> BuckyResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(Self::map_sql_error)?;
+        for device_id in devices {
+            tx.execute("DELETE FROM sn_peers WHERE device_id=?1", params![device_id.to_string()])
+                .map_err(Self::map_sql_error)?;
+        }
+        tx.commit().map_err(Self::map_sql_error)
+    }
+
+    // 加载所有未过期的记录，过期的直接删除
+    pub fn load(&self, now: Timestamp) -> BuckyResult<Vec<PeerRecord>> {
+        let min_time = now.saturating_sub(self.ttl.as_micros() as u64);
+
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM sn_peers WHERE update_time<?1", params![min_time as i64])
+            .map_err(Self::map_sql_error)?;
+        if removed > 0 {
+            log::info!("sn peer store removed {} expired records", removed);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT device_id, desc, enc_key, mix_key, cipher, local_addr, remote_addr, last_send_time, update_time FROM sn_peers")
+            .map_err(Self::map_sql_error)?;
+        let rows = stmt.query_map([], Self::read_row).map_err(Self::map_sql_error)?;
+
+        let mut records = vec![];
+        for row in rows {
+            let row = row.map_err(Self::map_sql_error)?;
+            match Self::decode_row(row) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("sn peer store ignore invalid record, err={}", e)
+            }
+        }
+
+        Ok(records)
+    }
+
+    fn read_row(row: &rusqlite::Row) -> rusqlite::Result<RawRow> {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+            row.get(5)?,
+            row.get(6)?,
+            row.get(7)?,
+            row.get(8)?,
+        ))
+    }
+
+    fn decode_row(row: RawRow) -> BuckyResult<PeerRecord> {
+        let (device_id, desc, enc_key, mix_key, cipher, local, remote, last_send_time, update_time) = row;
+
+        let parse_addr = |s: &str| -> BuckyResult<SocketAddr> {
+            s.parse().map_err(|e| BuckyError::new(BuckyErrorCode::InvalidData, format!("invalid addr {}, {}", s, e)))
+        };
+
+        let mut key = MixAesKey::new(AesKey::clone_from_slice(&enc_key)?, AesKey::clone_from_slice(&mix_key)?);
+        key.cipher = TunnelCipher::try_from(cipher)?;
+
+        Ok(PeerRecord {
+            device_id: DeviceId::from_str(&device_id)?,
+            desc: Device::clone_from_slice(&desc)?,
+            key,
+            local: parse_addr(&local)?,
+            remote: parse_addr(&remote)?,
+            last_send_time: last_send_time as u64,
+            update_time: update_time as u64,
+        })
+    }
+}
+
+type RawRow = (String, Vec<u8>, Vec<u8>, Vec<u8>, u8, String, String, i64, i64);
+
+
+#[test]
+fn save_and_load() {
+    let private_key = PrivateKey::generate_rsa(1024).unwrap();
+    let device = Device::new(
+        None,
+        UniqueId::default(),
+        vec![],
+        vec![],
+        vec![],
+        private_key.public(),
+        Area::default(),
+        DeviceCategory::PC,
+    )
+    .build();
+    let device_id = device.desc().device_id();
+
+    let mut key = MixAesKey::new(AesKey::random(), AesKey::random());
+    key.cipher = TunnelCipher::ChaCha20Poly1305;
+
+    let now = bucky_time_now();
+    let record = PeerRecord {
+        device_id: device_id.clone(), 
+        desc: device.clone(), 
+        key: key.clone(), 
+        local: "127.0.0.1:8060".parse().unwrap(), 
+        remote: "10.0.0.2:50000".parse().unwrap(), 
+        last_send_time: now - 1000, 
+        update_time: now,
+    };
+
+    let path = std::env::temp_dir().join(format!("sn-peer-store-test-{}", rand::random::<u32>())).join("peers.db");
+    let ttl = Duration::from_secs(300);
+    let store = PeerStore::open(&path, ttl).unwrap();
+    store.save(&[record]).unwrap();
+
+    // 重新打开之后可以恢复全部注册信息
+    let store = PeerStore::open(&path, ttl).unwrap();
+    let records = store.load(now).unwrap();
+    assert_eq!(records.len(), 1);
+    let found = &records[0];
+    assert_eq!(found.device_id, device_id);
+    assert_eq!(found.desc.desc().device_id(), device_id);
+    assert!(found.key.enc_key == key.enc_key);
+    assert!(found.key.mix_key == key.mix_key);
+    assert_eq!(found.key.cipher, TunnelCipher::ChaCha20Poly1305);
+    assert_eq!(found.local.to_string(), "127.0.0.1:8060");
+    assert_eq!(found.remote.to_string(), "10.0.0.2:50000");
+    assert_eq!(found.last_send_time, now - 1000);
+    assert_eq!(found.update_time, now);
+
+    // 超过ttl的记录在加载时被删除
+    let expired = now + ttl.as_micros() as u64 + 1;
+    assert!(store.load(expired).unwrap().is_empty());
+    assert!(store.load(now).unwrap().is_empty());
+
+    let record = PeerRecord {
+        device_id: device_id.clone(), 
+        desc: device, 
+        key, 
+        local: "127.0.0.1:8060".parse().unwrap(), 
+        remote: "10.0.0.2:50000".parse().unwrap(), 
+        last_send_time: now, 
+        update_time: now,
+    };
+    store.save(&[record]).unwrap();
+    store.remove(&[device_id]).unwrap();
+    assert!(store.load(now).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
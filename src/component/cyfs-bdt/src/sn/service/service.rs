@@ -3,8 +3,9 @@ use futures::executor::ThreadPool;
 use log::*;
 use std::{
     any::Any,
+    path::PathBuf,
     sync::{
-        atomic::{self, AtomicBool},
+        atomic::{self, AtomicBool, AtomicU64},
         Arc,
     },
     time::Duration,
@@ -13,7 +14,10 @@ use std::{
 use cyfs_base::*;
 
 use crate::{
-    history::keystore::{self, Keystore},
+    history::{
+        keystore::{self, Keystore},
+        clock_skew::{self, ClockSkewCheck, ClockSkewEstimator},
+    },
     protocol::{*, v0::*},
     types::*,
 };
@@ -21,7 +25,9 @@ use crate::{
 use super::{
     call_stub::CallStub,
     net_listener::{MessageSender, NetListener, UdpSender},
+    limiter::{SnLimiter, SnLimiterConfig, SnLimiterStat},
     peer_manager::PeerManager,
+    peer_store::PeerStore,
     receipt::*,
     resend_queue::{ResendQueue, ResendCallbackTrait},
 };
@@ -32,6 +38,30 @@ use super::{
 //     begin_time: Instant,
 // }
 
+pub struct SnServiceConfig {
+    // 客户端注册信息的持久化存储，为空时只保存在内存中
+    pub peer_store: Option<PathBuf>,
+    // 持久化记录的有效期，重启时超过有效期的记录会被丢弃
+    pub peer_store_ttl: Duration,
+    pub peer_store_flush_interval: Duration,
+    // 按来源ip和device的限流配置
+    pub limiter: SnLimiterConfig,
+    // 客户端时钟偏差的容忍范围
+    pub clock_skew: clock_skew::Config,
+}
+
+impl Default for SnServiceConfig {
+    fn default() -> Self {
+        Self {
+            peer_store: None,
+            peer_store_ttl: Duration::from_secs(30 * 60),
+            peer_store_flush_interval: Duration::from_secs(10),
+            limiter: SnLimiterConfig::default(),
+            clock_skew: clock_skew::Config::default(),
+        }
+    }
+}
+
 struct ServiceImpl {
     seq_generator: TempSeqGenerator,
     key_store: Keystore,
@@ -46,6 +76,12 @@ struct ServiceImpl {
     resend_queue: Option<ResendQueue>,
     call_stub: CallStub,
 
+    peer_store: Option<Arc<PeerStore>>,
+    peer_store_flush_interval: Duration,
+    last_store_flush_time: AtomicU64,
+
+    limiter: SnLimiter,
+    clock_skew: ClockSkewEstimator,
 }
 
 #[derive(Clone)]
@@ -56,9 +92,29 @@ impl SnService {
         local_device: Device,
         local_secret: PrivateKey,
         contract: Box<dyn SnServiceContractServer + Send + Sync>,
+    ) -> SnService {
+        Self::new_with_config(local_device, local_secret, contract, SnServiceConfig::default())
+    }
+
+    pub fn new_with_config(
+        local_device: Device,
+        local_secret: PrivateKey,
+        contract: Box<dyn SnServiceContractServer + Send + Sync>,
+        config: SnServiceConfig,
     ) -> SnService {
         let thread_pool = ThreadPool::new().unwrap();
 
+        // 打开失败不影响服务，只是退化为纯内存
+        let peer_store = config.peer_store.as_ref().and_then(|path| {
+            match PeerStore::open(path.as_path(), config.peer_store_ttl) {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    error!("open sn peer store failed, registrations will not be persisted! {}", e);
+                    None
+                }
+            }
+        });
+
         let service = Self(Arc::new(ServiceImpl {
             seq_generator: TempSeqGenerator::new(),
             key_store: Keystore::new(
@@ -82,6 +138,11 @@ impl SnService {
             call_stub: CallStub::new(),
             thread_pool: thread_pool.clone(),
             contract,
+            peer_store,
+            peer_store_flush_interval: config.peer_store_flush_interval,
+            last_store_flush_time: AtomicU64::new(bucky_time_now()),
+            limiter: SnLimiter::new(config.limiter.clone()),
+            clock_skew: ClockSkewEstimator::new(config.clock_skew.clone()),
             // call_tracker: CallTracker {
             //     calls: Default::default(),
             //     begin_time: Instant::now()
@@ -107,7 +168,7 @@ impl SnService {
             };
         }
 
-        let _listener = match NetListener::listen(&endpoints_v6, &endpoints_v4, self.clone()).await
+        let listener = match NetListener::listen(&endpoints_v6, &endpoints_v4, self.clone()).await
         {
             Ok((listener, udp_count, _)) => {
                 if udp_count == 0 {
@@ -123,6 +184,8 @@ impl SnService {
             Err(e) => Err(e),
         }?;
 
+        self.restore_peers(&listener);
+
         // 清理过期数据
         let timer = {
             let service = self.clone();
@@ -161,6 +224,19 @@ impl SnService {
         &self.0.key_store
     }
 
+    pub fn clock_skew(&self) -> &ClockSkewEstimator {
+        &self.0.clock_skew
+    }
+
+    // 可以在运行时调整黑白名单
+    pub fn limiter(&self) -> &SnLimiter {
+        &self.0.limiter
+    }
+
+    pub fn limiter_stat(&self) -> SnLimiterStat {
+        self.0.limiter.stat()
+    }
+
     fn resend_queue(&self) -> &ResendQueue {
         self.0.resend_queue.as_ref().unwrap()
     }
@@ -198,14 +274,82 @@ impl SnService {
         });
     }
 
+    fn restore_peers(&self, listener: &NetListener) {
+        let store = match self.0.peer_store.as_ref() {
+            Some(store) => store,
+            None => return,
+        };
+
+        let records = match store.load(bucky_time_now()) {
+            Ok(records) => records,
+            Err(e) => {
+                error!("load peers from store failed! {}", e);
+                return;
+            }
+        };
+
+        let total = records.len();
+        let mut peers = Vec::with_capacity(total);
+        for record in records {
+            match listener.udp_sender(&record.local, record.device_id.clone(), record.key.clone(), record.remote.clone()) {
+                Some(sender) => {
+                    self.key_store().add_key(&record.key, &record.device_id);
+                    peers.push((record, sender));
+                }
+                None => {
+                    warn!("restore peer {} ignored for local udp {} not listened.", record.device_id, record.local);
+                }
+            }
+        }
+
+        info!("restore peers from store, total: {}, restored: {}", total, peers.len());
+        self.peer_manager().restore(peers);
+    }
+
+    fn flush_peer_store(&self, now: Timestamp, drops: Option<Vec<DeviceId>>) {
+        let store = match self.0.peer_store.as_ref() {
+            Some(store) => store.clone(),
+            None => return,
+        };
+
+        let last = self.0.last_store_flush_time.load(atomic::Ordering::SeqCst);
+        let interval = self.0.peer_store_flush_interval.as_micros() as u64;
+        if drops.is_none() && now < last + interval {
+            return;
+        }
+        self.0.last_store_flush_time.store(now, atomic::Ordering::SeqCst);
+
+        let records = self.peer_manager().take_store_records(now);
+        if records.is_empty() && drops.is_none() {
+            return;
+        }
+
+        self.thread_pool().spawn_ok(async move {
+            if let Some(drops) = drops {
+                if let Err(e) = store.remove(&drops) {
+                    warn!("remove timeout peers from store failed, count: {}, {}", drops.len(), e);
+                }
+            }
+
+            if !records.is_empty() {
+                match store.save(&records) {
+                    Ok(_) => debug!("save peers to store, count: {}", records.len()),
+                    Err(e) => warn!("save peers to store failed, count: {}, {}", records.len(), e),
+                }
+            }
+        });
+    }
+
     fn clean_timeout_resource(&self) {
         let now = bucky_time_now();
 
-        if let Some(drops) = self.peer_manager().try_knock_timeout(now) {
-            for device in &drops {
+        let drops = self.peer_manager().try_knock_timeout(now);
+        if let Some(drops) = drops.as_ref() {
+            for device in drops {
                 self.key_store().reset_peer(device)
             }
         }
+        self.flush_peer_store(now, drops);
 
         self.resend_queue().try_resend(now);
         self.0.call_stub.recycle(now);
@@ -230,7 +374,29 @@ impl SnService {
         let cmd_pkg = match first_pkg.cmd_code() {
             PackageCmdCode::Exchange => {
                 let exchg = <Box<dyn Any + Send>>::downcast::<Exchange>(first_pkg.into_any()); // pkg.into_any().downcast::<Exchange>();
-                if let Ok(_) = exchg {
+                if let Ok(exchg) = exchg {
+                    // 时钟偏差在兼容范围内的客户端，按它的时间计算mixhash
+                    if exchg.send_time != 0 {
+                        match self.clock_skew().check(pkg_box.remote(), exchg.send_time) {
+                            ClockSkewCheck::InSync => {}
+                            ClockSkewCheck::Skewed(skew) => {
+                                warn!(
+                                    "client clock skewed {}s, from: {}.",
+                                    skew.offset_secs(),
+                                    pkg_box.remote()
+                                );
+                                pkg_box.set_clock_skew(skew.offset_secs());
+                            }
+                            ClockSkewCheck::OutOfRange(offset) => {
+                                warn!(
+                                    "ignore exchange for client clock skewed {}s, from: {}.",
+                                    offset / 1_000_000,
+                                    pkg_box.remote()
+                                );
+                                return;
+                            }
+                        }
+                    }
                     self.key_store().add_key(pkg_box.key(), pkg_box.remote());
                 } else {
                     warn!("fetch exchange failed, from: {:?}.", resp_sender.remote());
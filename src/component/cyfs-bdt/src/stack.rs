@@ -17,7 +17,7 @@ use crate::{
     cc::{self},
     datagram::{self, DatagramManager},
     finder::*,
-    history::keystore,
+    history::{keystore, session, clock_skew::{self, ClockSkewCheck, ClockSkewEstimator}},
     interface::{
         self, 
         NetManager, 
@@ -51,6 +51,8 @@ pub struct StackConfig {
     pub statistic_interval: Duration, 
     pub device_cache: DeviceCacheConfig, 
     pub keystore: keystore::Config,
+    pub session: session::Config, 
+    pub clock_skew: clock_skew::Config, 
     pub interface: interface::Config, 
     pub sn_client: sn::client::Config,
     pub tunnel: tunnel::Config,
@@ -68,6 +70,13 @@ impl StackConfig {
                 active_time: Duration::from_secs(300),
                 capacity: 10000,
             }, 
+            session: session::Config {
+                path: None, 
+                save_interval: Duration::from_secs(60), 
+                resume_expire: Duration::from_secs(240), 
+                resume_timeout: Duration::from_secs(1), 
+            }, 
+            clock_skew: clock_skew::Config::default(), 
             device_cache: DeviceCacheConfig {
                 expire: Duration::from_secs(5 * 60),
                 capacity: 1024 * 1024
@@ -76,7 +85,8 @@ impl StackConfig {
                 udp: interface::udp::Config {
                     sn_only: false, 
                     sim_loss_rate: 0, 
-                    recv_buffer: 52428800
+                    recv_buffer: 52428800, 
+                    dont_fragment: false
                 }
             },
             sn_client: sn::client::Config {
@@ -94,12 +104,23 @@ impl StackConfig {
                     udp: sn::client::call::udp::Config {
                         resend_interval: Duration::from_millis(500),
                     }
+                }, 
+                probe: sn::client::ProbeConfig {
+                    interval: Duration::from_secs(300), 
+                    resend_interval: Duration::from_millis(500), 
+                    timeout: Duration::from_secs(3), 
+                    min_improve: Duration::from_millis(30), 
+                    offline_retry_interval: Duration::from_secs(30), 
                 }
             },
             tunnel: tunnel::Config {
                 retain_timeout: Duration::from_secs(60),
                 retry_sn_timeout: Duration::from_secs(2), 
                 connect_timeout: Duration::from_secs(5),
+                cipher: TunnelCipher::AesCbc, 
+                hybrid_kem: false, 
+                hybrid_kem_required: false, 
+                stats_history: 60, 
                 tcp: tunnel::tcp::Config {
                     connect_timeout: Duration::from_secs(5), 
                     confirm_timeout: Duration::from_secs(5), 
@@ -116,6 +137,9 @@ impl StackConfig {
                     connect_timeout: Duration::from_secs(5),
                     ping_interval: Duration::from_secs(30),
                     ping_timeout: Duration::from_secs(60 * 3),
+                    mtu_probe: true, 
+                    mtu_probe_timeout: Duration::from_secs(1), 
+                    mtu_cache_expire: Duration::from_secs(600), 
                 },
             },
             stream: stream::Config {
@@ -137,12 +161,14 @@ impl StackConfig {
                         atomic_interval: Duration::from_millis(10),
                         break_overtime: Duration::from_secs(60),
                         msl: Duration::from_secs(60), 
+                        linger: Duration::from_secs(120), 
                         cc: cc::Config {
                             init_rto: Duration::from_secs(1),
                             min_rto: Duration::from_millis(200),
                             cc_impl: cc::ImplConfig::BBR(Default::default()),
                         },
                     },
+                    autotune: stream::BufferAutotuneConfig::default(),
                 },
             },
             datagram: datagram::Config {
@@ -177,13 +203,27 @@ impl StackConfig {
                         attenuation: 0.5, 
                         expire: Duration::from_secs(20),  
                         atomic: Duration::from_secs(1)
+                    }, 
+                    upload_scheduler: ndn::channel::UploadSchedulerConfig {
+                        max_upload_speed: 0, 
+                        round_interval: Duration::from_millis(100), 
+                        default_weight: 1, 
+                        min_round_pieces: 1
+                    }, 
+                    upload_admission: ndn::channel::UploadAdmissionConfig {
+                        policy: ndn::channel::UploadAdmissionPolicy::Queue, 
+                        upstream_capacity: 0, 
+                        high_watermark: 90, 
+                        max_sessions: 0, 
+                        throttled_weight: 1
                     }
                 }, 
                 chunk: ndn::chunk::Config{
                     raw_caches: RawCacheConfig {
                         mem_capacity: 1024 * 1024 * 1024, 
                         tmp_dir: PathBuf::new()
-                    }
+                    }, 
+                    loopback: true
                 }
             }, 
             debug: None
@@ -197,6 +237,8 @@ pub struct StackImpl {
     local_const: DeviceDesc,
     id_generator: IncreaseIdGenerator,
     keystore: keystore::Keystore,
+    session_store: session::SessionStore, 
+    clock_skew: ClockSkewEstimator, 
     device_cache: DeviceCache,
     net_manager: NetManager,
     lazy_components: Option<StackLazyComponents>, 
@@ -297,6 +339,7 @@ impl Stack {
             signer,
             params.config.keystore.clone(),
         );
+        let session_store = session::SessionStore::open(params.config.session.clone(), key_store.clone());
 
         let mut outer_cache = None;
         std::mem::swap(&mut outer_cache, &mut params.outer_cache);
@@ -307,6 +350,8 @@ impl Stack {
             local_const: local_device.desc().clone(),
             id_generator: IncreaseIdGenerator::new(),
             keystore: key_store,
+            session_store, 
+            clock_skew: ClockSkewEstimator::new(params.config.clock_skew.clone()), 
             device_cache: DeviceCache::new(&params.config.device_cache, outer_cache),
             net_manager,
             lazy_components: None, 
@@ -404,6 +449,27 @@ impl Stack {
             }
         });
 
+        if stack.session_store().is_enabled() {
+            // 只持有弱引用，stack关闭或者释放之后退出
+            let weak_stack = stack.to_weak();
+            let save_interval = stack.config().session.save_interval;
+            task::spawn(async move {
+                loop {
+                    let _ = future::timeout(save_interval, future::pending::<()>()).await;
+                    match weak_stack.upgrade() {
+                        Some(stack) => {
+                            let stack = Stack(stack);
+                            if stack.session_store().is_closed() {
+                                break;
+                            }
+                            stack.save_session();
+                        }, 
+                        None => break
+                    }
+                }
+            });
+        }
+
         info!("{}: opened, version 0.5.4", stack); 
         Ok(StackGuard::from(stack))
     }
@@ -420,6 +486,51 @@ impl Stack {
         &self.0.keystore
     }
 
+    // 对端时钟偏差的估计
+    pub fn clock_skew(&self) -> &ClockSkewEstimator {
+        &self.0.clock_skew
+    }
+
+    // 按exchange的发送时间估计对端时钟偏差，偏差过大的拒绝，可以兼容的记到key上再加入keystore
+    pub(crate) fn on_exchange(&self, package_box: &mut PackageBox) -> Result<(), BuckyError> {
+        let send_time = {
+            let exchange: &Exchange = package_box.packages()[0].as_ref();
+            exchange.send_time
+        };
+        // 没有填发送时间的不做检查
+        let clock_skew = if send_time == 0 {
+            0
+        } else {
+            match self.clock_skew().check(package_box.remote(), send_time) {
+                ClockSkewCheck::InSync => 0, 
+                ClockSkewCheck::Skewed(skew) => {
+                    warn!("{} remote {} clock skewed {}s", self, package_box.remote(), skew.offset_secs());
+                    skew.offset_secs()
+                }, 
+                ClockSkewCheck::OutOfRange(offset) => {
+                    let msg = format!("{} ignore exchange from {} for clock skewed {}s out of range", self, package_box.remote(), offset / 1_000_000);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::Expired, msg));
+                }
+            }
+        };
+        package_box.set_clock_skew(clock_skew);
+        self.keystore().add_key(package_box.key(), package_box.remote());
+        Ok(())
+    }
+
+    pub(crate) fn session_store(&self) -> &session::SessionStore {
+        &self.0.session_store
+    }
+
+    fn save_session(&self) {
+        let active = self.tunnel_manager().active_udp_endpoints();
+        match self.session_store().save(active) {
+            Ok(count) => debug!("{} saved {} sessions", self, count), 
+            Err(err) => warn!("{} save sessions failed for {}", self, err)
+        }
+    }
+
     pub fn net_manager(&self) -> &NetManager {
         &self.0.net_manager
     }
@@ -464,6 +575,11 @@ impl Stack {
 
     pub fn close(&self) {
         //unimplemented!()
+        // 保存会话，进程快速重启之后可以直接恢复到最近活跃的peer的tunnel
+        if self.session_store().is_enabled() && !self.session_store().is_closed() {
+            self.save_session();
+            self.session_store().close();
+        }
     }
 
     pub fn reset_sn_list(&self, sn_list: Vec<Device>) -> PingClients {
@@ -522,15 +638,11 @@ impl From<&WeakStack> for Stack {
 }
 
 impl OnUdpPackageBox for Stack {
-    fn on_udp_package_box(&self, package_box: UdpPackageBox) -> Result<(), BuckyError> {
+    fn on_udp_package_box(&self, mut package_box: UdpPackageBox) -> Result<(), BuckyError> {
         trace!("{} on_udp_package_box", self.local_device_id().as_ref());
-        //FIXME: 用sequence 和 send time 过滤
+        //FIXME: 用sequence 过滤
         if package_box.as_ref().has_exchange() {
-            // let exchange: &Exchange = package_box.as_ref().packages()[0].as_ref();
-            self.keystore().add_key(
-                package_box.as_ref().key(),
-                package_box.as_ref().remote()
-            );
+            self.on_exchange(package_box.as_mut())?;
         }
         if package_box.as_ref().is_tunnel() {
             self.tunnel_manager().on_udp_package_box(package_box)
@@ -560,13 +672,11 @@ impl OnTcpInterface for Stack {
     fn on_tcp_interface(
         &self,
         interface: tcp::AcceptInterface,
-        first_box: PackageBox,
+        mut first_box: PackageBox,
     ) -> Result<OnPackageResult, BuckyError> {
-        //FIXME: 用sequence 和 send time 过滤
+        //FIXME: 用sequence 过滤
         if first_box.has_exchange() {
-            // let exchange: &Exchange = first_box.packages()[0].as_ref();
-            self.keystore()
-                .add_key(first_box.key(), first_box.remote());
+            self.on_exchange(&mut first_box)?;
         }
         if first_box.is_tunnel() {
             self.tunnel_manager().on_tcp_interface(interface, first_box)
@@ -590,7 +700,7 @@ impl PingClientCalledEvent for Stack {
         use udp::*;
         let mut crypto_buf = vec![0u8; called.payload.as_ref().len()];
         let ctx = PackageBoxDecodeContext::new_copy(crypto_buf.as_mut(), self.keystore());
-        let caller_box = PackageBox::raw_decode_with_context(
+        let mut caller_box = PackageBox::raw_decode_with_context(
             called.payload.as_ref(),
             (ctx, Some(called.into())),
         ).map(|(package_box, _)| package_box)
@@ -599,8 +709,7 @@ impl PingClientCalledEvent for Stack {
             err
         })?;
         if caller_box.has_exchange() {
-            // let exchange: &Exchange = caller_box.packages()[0].as_ref();
-            self.keystore().add_key(caller_box.key(), caller_box.remote());
+            self.on_exchange(&mut caller_box)?;
         }
         self.tunnel_manager().on_called(called, caller_box)
     }
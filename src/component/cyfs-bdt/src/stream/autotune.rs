@@ -0,0 +1,187 @@
+use crate::types::*;
+use cyfs_base::*;
+use std::time::Duration;
+
+// 按带宽时延积(BDP)自动调整package stream的发送和接收缓冲区，
+// 配置里的send_buffer/recv_buffer作为初始值；tcp stream仍然使用固定大小
+#[derive(Clone, Debug)]
+pub struct BufferAutotuneConfig {
+    pub enable: bool,
+    pub min_buffer: usize,
+    pub max_buffer: usize,
+    // 缓冲区为BDP的倍数，留出余量应对抖动和ack延迟
+    pub bdp_factor: f32,
+    // 两次调整之间的最小间隔
+    pub interval: Duration,
+}
+
+impl Default for BufferAutotuneConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            min_buffer: 1024 * 256,
+            max_buffer: 1024 * 1024 * 16,
+            bdp_factor: 2.0,
+            interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl BufferAutotuneConfig {
+    // rate: bytes/s
+    pub fn target(&self, rate: u64, rtt: Duration) -> usize {
+        let bdp = rate as u128 * rtt.as_micros() / 1_000_000;
+        let target = (bdp as f64 * self.bdp_factor as f64) as u128;
+        target
+            .max(self.min_buffer as u128)
+            .min(self.max_buffer as u128) as usize
+    }
+}
+
+pub(crate) struct BufferAutotuner {
+    config: BufferAutotuneConfig,
+    current: usize,
+    last_adjust: Timestamp,
+}
+
+impl BufferAutotuner {
+    pub fn new(config: &BufferAutotuneConfig, initial: usize) -> Self {
+        let current = if config.enable {
+            initial.max(config.min_buffer).min(config.max_buffer)
+        } else {
+            initial
+        };
+
+        Self {
+            config: config.clone(),
+            current,
+            last_adjust: 0,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // 根据最新的带宽和rtt采样计算缓冲区大小，有变化时返回新的大小；
+    // 增长立即生效，缩小每次最多减半，避免在抖动的链路上来回调整
+    pub fn on_sample(&mut self, now: Timestamp, rate: u64, rtt: Duration) -> Option<usize> {
+        if !self.config.enable || rate == 0 || rtt.is_zero() {
+            return None;
+        }
+        if now < self.last_adjust + self.config.interval.as_micros() as u64 {
+            return None;
+        }
+
+        let target = self.config.target(rate, rtt);
+        let next = if target >= self.current {
+            target
+        } else {
+            target.max(self.current / 2)
+        };
+        if next == self.current {
+            return None;
+        }
+
+        self.current = next;
+        self.last_adjust = now;
+        Some(next)
+    }
+
+    // 接收缓冲区只能增长，已经缓存的乱序数据不能丢弃
+    pub fn on_sample_grow(&mut self, now: Timestamp, rate: u64, rtt: Duration) -> Option<usize> {
+        if self.config.enable && self.config.target(rate, rtt) <= self.current {
+            return None;
+        }
+        self.on_sample(now, rate, rtt)
+    }
+}
+
+// 接收端没有自己的带宽估计，按间隔统计收到的数据量
+pub(crate) struct RecvRateSampler {
+    bytes: u64,
+    since: Timestamp,
+}
+
+impl RecvRateSampler {
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            bytes: 0,
+            since: now,
+        }
+    }
+
+    pub fn on_recv(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+    }
+
+    // 超过interval之后返回这段时间的速率(bytes/s)并重新开始统计
+    pub fn sample(&mut self, now: Timestamp, interval: Duration) -> Option<u64> {
+        if now <= self.since {
+            return None;
+        }
+        let elapsed = now - self.since;
+        if elapsed < interval.as_micros() as u64 {
+            return None;
+        }
+
+        let rate = self.bytes * 1_000_000 / elapsed;
+        self.bytes = 0;
+        self.since = now;
+        Some(rate)
+    }
+}
+
+#[test]
+fn test_autotune() {
+    let config = BufferAutotuneConfig {
+        enable: true,
+        min_buffer: 1024 * 64,
+        max_buffer: 1024 * 1024 * 4,
+        bdp_factor: 2.0,
+        interval: Duration::from_millis(100),
+    };
+    let interval = config.interval.as_micros() as u64;
+
+    let mut tuner = BufferAutotuner::new(&config, 1024);
+    assert_eq!(tuner.current(), 1024 * 64);
+
+    // 10MB/s * 100ms = 1MB bdp
+    let now = bucky_time_now();
+    let rtt = Duration::from_millis(100);
+    assert_eq!(
+        tuner.on_sample(now, 1024 * 1024 * 10, rtt),
+        Some(1024 * 1024 * 2)
+    );
+    // 间隔内不再调整
+    assert_eq!(tuner.on_sample(now + 1, 1024 * 1024 * 100, rtt), None);
+
+    // 不超过上限
+    let now = now + interval;
+    assert_eq!(
+        tuner.on_sample(now, 1024 * 1024 * 100, rtt),
+        Some(1024 * 1024 * 4)
+    );
+
+    // 缩小时每次最多减半
+    let now = now + interval;
+    assert_eq!(tuner.on_sample(now, 1024, rtt), Some(1024 * 1024 * 2));
+    let now = now + interval;
+    assert_eq!(tuner.on_sample_grow(now, 1024, rtt), None);
+
+    let mut sampler = RecvRateSampler::new(now);
+    sampler.on_recv(1024 * 100);
+    assert_eq!(sampler.sample(now + 1, config.interval), None);
+    assert_eq!(
+        sampler.sample(now + interval, config.interval),
+        Some(1024 * 1000)
+    );
+
+    let config = BufferAutotuneConfig {
+        enable: false,
+        ..config
+    };
+    let mut tuner = BufferAutotuner::new(&config, 1024);
+    assert_eq!(tuner.current(), 1024);
+    assert_eq!(tuner.on_sample(now, 1024 * 1024 * 10, rtt), None);
+}
@@ -1,6 +1,6 @@
 mod dep {
     pub use super::super::{
-        package::PackageStream, stream_provider::StreamProvider, tcp::TcpStream,
+        autotune::BufferAutotuneConfig, package::PackageStream, stream_provider::StreamProvider, tcp::TcpStream,
     };
     pub use crate::{
         types::*, 
@@ -331,6 +331,7 @@ pub struct Config {
     pub drain: f32,
     pub tcp: super::tcp::Config,
     pub package: super::package::Config,
+    pub autotune: BufferAutotuneConfig,
 }
 
 impl Config {
@@ -363,6 +364,39 @@ impl fmt::Display for StreamState {
     }
 }
 
+// stream进入Closed的原因
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum StreamCloseReason {
+    // 双方都完成了fin/ack交换，已经写入的数据都被对端确认
+    Graceful,
+    // 本地reset，未发送和未确认的数据被丢弃
+    LocalReset,
+    // 收到对端的reset
+    RemoteReset,
+    // shutdown之后在linger时间内没有完成fin/ack交换，已经reset
+    LingerTimeout,
+    // 长时间没有收到对端的响应
+    Timeout,
+    // 底层连接出错
+    Broken,
+    ConnectFailed,
+}
+
+impl fmt::Display for StreamCloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Graceful => "graceful",
+            Self::LocalReset => "local-reset",
+            Self::RemoteReset => "remote-reset",
+            Self::LingerTimeout => "linger-timeout",
+            Self::Timeout => "timeout",
+            Self::Broken => "broken",
+            Self::ConnectFailed => "connect-failed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 enum StreamStateImpl {
     Initial(TunnelGuard),
     Connecting(StreamConnectingState, TunnelGuard),
@@ -385,6 +419,9 @@ struct StreamContainerImpl {
     sequence: TempSeq,
     state: RwLock<StreamStateImpl>,
     answer_data: RwLock<Option<Vec<u8>>>,
+    // 单个stream的缓冲区自动调整配置，None时使用stack的配置
+    autotune: RwLock<Option<BufferAutotuneConfig>>,
+    close_reason: RwLock<Option<StreamCloseReason>>,
 }
 
 #[derive(Clone)]
@@ -420,7 +457,9 @@ impl StreamContainer {
             local_id,
             sequence: sequence,
             state: RwLock::new(StreamStateImpl::Initial(tunnel)),
-            answer_data: RwLock::new(None)
+            answer_data: RwLock::new(None), 
+            autotune: RwLock::new(None), 
+            close_reason: RwLock::new(None), 
         };
 
         StreamContainer(Arc::new(stream_impl))
@@ -600,7 +639,7 @@ impl StreamContainer {
                     _ => vec![],
                 };
 
-                let stream = TcpStream::new(self.clone(), socket, key.enc_key)?;
+                let stream = TcpStream::new(self.clone(), socket, key)?;
                 (
                     Box::new(stream.clone()) as Box<dyn StreamProvider>,
                     Box::new(stream) as Box<dyn StreamProvider>,
@@ -662,6 +701,7 @@ impl StreamContainer {
             StreamStateImpl::Connecting(ref mut connecting_state, tunnel) => match connecting_state {
                 StreamConnectingState::Accept(ref mut acceptor) => {
                     let waiter = acceptor.waiter.transfer();
+                    self.set_close_reason(StreamCloseReason::ConnectFailed);
                     info!("{} accepting=>closed", self);
                     *state = StreamStateImpl::Closed;
                     Ok((waiter, None))
@@ -669,6 +709,7 @@ impl StreamContainer {
                 StreamConnectingState::Connect(ref mut connector) => {
                     let waiter = connector.waiter.transfer();
                     let tunnel = tunnel.clone();
+                    self.set_close_reason(StreamCloseReason::ConnectFailed);
                     info!("{} connecting=>closed", self);
                     let state_dump = connector
                         .state
@@ -904,6 +945,7 @@ impl StreamContainer {
 
     pub(crate) fn break_with_error(&self, err: BuckyError, reserving: bool, marking: bool) {
         error!("{} break with err {}", self, err);
+        self.set_close_reason(StreamCloseReason::Broken);
         let state_dump = {
             let state = &mut *self.0.state.write().unwrap();
             match state {
@@ -928,11 +970,35 @@ impl StreamContainer {
     }
 
     pub(super) fn on_shutdown(&self, reserving: bool) {
+        self.set_close_reason(StreamCloseReason::Graceful);
         *self.0.state.write().unwrap() = StreamStateImpl::Closed;
         self.stack().stream_manager().remove_stream(self, reserving);
     }
 
 
+    // 只记录第一次设置的原因，provider在调用on_shutdown/break_with_error之前设置更具体的原因
+    pub(crate) fn set_close_reason(&self, reason: StreamCloseReason) {
+        let close_reason = &mut *self.0.close_reason.write().unwrap();
+        if close_reason.is_none() {
+            info!("{} close for {}", self, reason);
+            *close_reason = Some(reason);
+        }
+    }
+
+    // stream进入Closed之后返回关闭的原因
+    pub fn close_reason(&self) -> Option<StreamCloseReason> {
+        self.0.close_reason.read().unwrap().clone()
+    }
+
+    // 需要在连接建立之前设置才会生效，accept的stream在confirm之前设置
+    pub fn set_buffer_autotune(&self, config: BufferAutotuneConfig) {
+        *self.0.autotune.write().unwrap() = Some(config);
+    }
+
+    pub(crate) fn buffer_autotune(&self) -> Option<BufferAutotuneConfig> {
+        self.0.autotune.read().unwrap().clone()
+    }
+
     pub async fn confirm(&self, answer: &[u8]) -> BuckyResult<()> {
         if answer.len() > ANSWER_MAX_LEN {
             return Err(BuckyError::new(
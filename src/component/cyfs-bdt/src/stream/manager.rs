@@ -14,7 +14,8 @@ use crate::{
 };
 use super::{
     container::*, 
-    listener::*
+    listener::*, 
+    autotune::BufferAutotuneConfig
 };
 use log::*;
 
@@ -121,6 +122,17 @@ impl StreamManager {
         port: u16, 
         question: Vec<u8>, 
         build_params: BuildTunnelParams
+    ) -> Result<StreamGuard, BuckyError> {
+        self.connect_with_autotune(port, question, build_params, None).await
+    }
+
+    // autotune为None时使用stack配置的缓冲区自动调整参数
+    pub async fn connect_with_autotune(
+        &self, 
+        port: u16, 
+        question: Vec<u8>, 
+        build_params: BuildTunnelParams, 
+        autotune: Option<BufferAutotuneConfig>
     ) -> Result<StreamGuard, BuckyError> {
         if question.len() > QUESTION_MAX_LEN {
             return Err(BuckyError::new(
@@ -141,6 +153,9 @@ impl StreamManager {
             port, 
             local_id, 
             tunnel.generate_sequence());
+        if let Some(autotune) = autotune {
+            stream.set_buffer_autotune(autotune);
+        }
         manager_impl.stream_entries.write().unwrap().id_entries.insert(local_id, stream.clone());
         stream.connect(question, build_params).await.map_err(|err| {self.remove_stream(&stream, true); err})?;
         Ok(StreamGuard::from(stream))
@@ -4,6 +4,7 @@ pub mod tcp;
 pub mod container;
 pub mod listener;
 mod manager;
+mod autotune;
 
 #[derive(Clone)]
 pub struct Config {
@@ -11,6 +12,7 @@ pub struct Config {
     pub listener: listener::Config
 }
 
-pub use container::{StreamProviderSelector, StreamContainer, StreamGuard, StreamState};
+pub use autotune::BufferAutotuneConfig;
+pub use container::{StreamProviderSelector, StreamContainer, StreamGuard, StreamState, StreamCloseReason};
 pub use listener::{StreamListener, StreamListenerGuard, StreamListenerState, StreamIncoming};
 pub use manager::{StreamManager, WeakStreamManager, RemoteSequence};
\ No newline at end of file
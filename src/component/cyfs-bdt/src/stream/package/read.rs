@@ -18,6 +18,7 @@ use crate::{
 use super::{
     recv_queue::RecvQueue, 
     stream::PackageStream,
+    super::autotune::{BufferAutotuner, RecvRateSampler},
 };
 
 
@@ -45,10 +46,30 @@ struct ReadProviderImpl {
     read_waiter: Option<ReadStub>, 
     readable_waiter: Option<Waker>, 
     queue: RecvQueue, 
-    nagle: NagleState
+    nagle: NagleState, 
+    tuner: BufferAutotuner, 
+    sampler: RecvRateSampler, 
 }
 
 impl ReadProviderImpl {
+    fn drain(&self, stream: &PackageStream) -> usize {
+        (self.queue.capability() as f32 * stream.config().drain) as usize
+    }
+
+    // 接收端没有带宽估计，用一段时间内收到的数据量和tunnel的rtt估算，
+    // 窗口限制了速率时，扩大窗口后速率随之上升，直到达到链路的带宽
+    fn autotune(&mut self, stream: &PackageStream, now: Timestamp, recv: usize) {
+        self.sampler.on_recv(recv);
+        if let Some(rate) = self.sampler.sample(now, stream.config().autotune.interval) {
+            if let Some(rtt) = stream.tunnel_stats().rtt() {
+                if let Some(capability) = self.tuner.on_sample_grow(now, rate, rtt) {
+                    debug!("{} autotune recv buffer from {} to {}", stream, self.queue.capability(), capability);
+                    self.queue.resize(capability);
+                }
+            }
+        }
+    }
+
     fn check_close_waiting(&self) -> bool {
         self.remote_closed.is_some() && self.queue.stream_len() == 0
     }
@@ -100,11 +121,10 @@ impl ReadProviderImpl {
             return (Poll::Pending, None);
         }
         let former_len = self.queue.stream_len();
-        let config = stream.config();
         if former_len >= buf.len() {
             debug!("{} read {} bytes ready for buffer enough", stream, buf.len());
             self.timeout = false;
-        } else if former_len > config.recv_drain() {
+        } else if former_len > self.drain(stream) {
             debug!("{} read {} bytes ready for buffer greater than drain", stream, buf.len());
             self.timeout = false;
         } else if self.remote_closed.is_some() {
@@ -146,7 +166,7 @@ impl ReadProviderImpl {
                 self.timeout = true;
                 to_wake = Some(stub.waker.clone());
                 self.read_waiter = None;
-            } else if total > stream.config().recv_drain() {
+            } else if total > self.drain(stream) {
                 debug!("{} wake read at {} {} bytes for drain", stream, stub.time, stub.len);
                 to_wake = Some(stub.waker.clone());
                 self.read_waiter = None;
@@ -161,15 +181,19 @@ pub struct ReadProvider(Mutex<ReadProviderState>);
 
 impl ReadProvider {
     pub fn new(config: &super::super::container::Config) -> Self {
+        let tuner = BufferAutotuner::new(&config.autotune, config.recv_buffer);
         Self(Mutex::new(
             ReadProviderState::Open(ReadProviderImpl {
                 remote_closed: None, 
                 timeout: false, 
                 read_waiter: None, 
                 readable_waiter: None, 
-                queue: RecvQueue::new(config.recv_buffer), 
+                // 接收缓冲区只会扩大，初始值不小于配置的recv_buffer
+                queue: RecvQueue::new(tuner.current().max(config.recv_buffer)), 
                 // 初始化 NagleState 为 nagle，保证在不send的时候，也会回复第一个ack作为ackack
-                nagle: NagleState::Nagle(bucky_time_now())
+                nagle: NagleState::Nagle(bucky_time_now()), 
+                tuner, 
+                sampler: RecvRateSampler::new(bucky_time_now()), 
             })))
     }
 
@@ -227,6 +251,15 @@ impl ReadProvider {
     }
 
     pub fn break_with_error(&self, _err: BuckyError) {
+        self.close_with_error(std::io::ErrorKind::BrokenPipe, "stream broken")
+    }
+
+    // 本地或者对端reset，之后的读返回ConnectionReset
+    pub fn reset(&self) {
+        self.close_with_error(std::io::ErrorKind::ConnectionReset, "stream reset")
+    }
+
+    fn close_with_error(&self, kind: std::io::ErrorKind, msg: &str) {
         let to_wake = {
             let mut to_wake = LinkedList::new();
             let state = &mut *cyfs_debug::lock!(self.0).unwrap();
@@ -240,7 +273,7 @@ impl ReadProvider {
                     }
                     *state = ReadProviderState::Closed(
                         provider.queue.stream_end(), 
-                        Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "stream broken"))
+                        Err(std::io::Error::new(kind, msg))
                     );
                 }, 
                 ReadProviderState::Closed(_, _) => {
@@ -378,6 +411,9 @@ impl OnPackage<SessionData, (&PackageStream, &mut Vec<DynamicPackage>)> for Read
                         if fin {
                             provider.remote_closed = Some(now);
                         }
+                        if confirmed > 0 {
+                            provider.autotune(stream, now, confirmed);
+                        }
                         if (confirmed > 0 && session_data.payload.as_ref().len() < PackageStream::mss()) || fin {
                             if packages.len() == 0 || !(&packages[packages.len() - 1] as &dyn AsRef<SessionData>).as_ref().is_flags_contain(SESSIONDATA_FLAG_ACK) {
                                 trace!("{} will ack for fin or nagle income", stream);
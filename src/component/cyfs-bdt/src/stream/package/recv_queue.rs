@@ -121,6 +121,25 @@ impl RecvQueue {
         self.start + self.stream_writer.len() as u64
     }
 
+    pub fn capability(&self) -> usize {
+        self.capability as usize
+    }
+
+    // 只能扩大，已经接收的数据搬到新的ringbuf里
+    pub fn resize(&mut self, capability: usize) {
+        if capability as u64 <= self.capability {
+            return;
+        }
+        let stream_buffer = ringbuf::RingBuffer::new(capability);
+        let (mut stream_writer, stream_reader) = stream_buffer.split();
+        let mut buf = vec![0u8; self.stream_reader.len()];
+        let read = self.stream_reader.pop_slice(&mut buf[..]);
+        stream_writer.push_slice(&buf[..read]);
+        self.stream_writer = stream_writer;
+        self.stream_reader = stream_reader;
+        self.capability = capability as u64;
+    }
+
     fn alloc_block(&mut self, pkg: &SessionData) -> Block {
         Block {
             data: Vec::from(pkg.payload.as_ref()), 
@@ -96,7 +96,17 @@ impl SendQueue {
     }
 
     pub fn remain(&self) -> usize {
-        self.capacity - self.used()
+        // 缓冲区缩小之后已经写入的数据可能超过capacity
+        self.capacity.saturating_sub(self.used())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // 只影响后续写入，已经在队列里的数据不受影响
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
     }
 
     pub fn start(&self) -> u64 {
@@ -320,6 +330,7 @@ impl SendQueue {
                             if now > *send_time && Duration::from_micros(now - *send_time) > timeout {
                                 *send_time = now;
                                 packages.push(DynamicPackage::from(block.to_session_data(now)));
+                                stream.tunnel_stats().on_resend(1);
                                 let _ = logging && {trace!("{} block resend for timeout {}", stream, block); true};
                             } else {
                                 let _ = logging && {trace!("{} block wont resend for hasnt timeout {}", stream, block); true};
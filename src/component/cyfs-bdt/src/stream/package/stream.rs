@@ -16,11 +16,11 @@ use crate::{
     types::*, 
     protocol::{*, v0::*}, 
     interface,
-    tunnel::{udp::Tunnel as UdpTunnel, tunnel::Tunnel, TunnelContainer}, 
+    tunnel::{udp::Tunnel as UdpTunnel, tunnel::Tunnel, TunnelContainer, TunnelStats}, 
     cc
 };
 use super::super::{
-    container::StreamContainer, 
+    container::{StreamContainer, StreamCloseReason}, 
     stream_provider::{Shutdown, StreamProvider}};
 use super::{
     write::WriteProvider,  
@@ -33,6 +33,9 @@ pub struct Config {
     pub atomic_interval: Duration, 
     pub break_overtime: Duration,  
     pub msl: Duration, 
+    // shutdown之后等待对端确认fin的最长时间，超时后reset；
+    // 为0时shutdown(Both)直接reset并丢弃没有发送的数据
+    pub linger: Duration, 
     pub cc: cc::Config
 }
 
@@ -45,12 +48,14 @@ struct PackageStreamImpl {
     config: super::super::container::Config, 
     owner_disp: String, 
     tunnel: UdpTunnel, 
+    stats: TunnelStats, 
     local_id: IncreaseId, 
     remote_id: IncreaseId, 
     write_provider: WriteProvider, 
     read_provider: ReadProvider, 
     pacer: Mutex<cc::pacing::Pacer>, 
     package_queue: Arc<Mutex<LinkedList<PacePackage>>>, 
+    close_reason: Mutex<Option<StreamCloseReason>>, 
 }
 
 #[derive(Clone)]
@@ -74,7 +79,10 @@ impl PackageStream {
         remote_id: IncreaseId,
     ) -> BuckyResult<Self> {
         let owner_disp = format!("{}", owner);
-        let config = tunnel.stack().config().stream.stream.clone();
+        let mut config = tunnel.stack().config().stream.stream.clone();
+        if let Some(autotune) = owner.buffer_autotune() {
+            config.autotune = autotune;
+        }
 	let pacer_enable = false;
 
         let write_provider = WriteProvider::new(&config);
@@ -83,12 +91,14 @@ impl PackageStream {
             owner_disp, 
             config, 
             tunnel: tunnel.default_udp_tunnel()?, 
+            stats: tunnel.stats().clone(), 
             local_id, 
             remote_id, 
             write_provider,
             read_provider,
             pacer: Mutex::new(cc::pacing::Pacer::new(pacer_enable, PackageStream::mss() * 10, PackageStream::mss())),
             package_queue: Arc::new(Mutex::new(Default::default())),
+            close_reason: Mutex::new(None), 
         }));
 
         Ok(stream)
@@ -106,6 +116,33 @@ impl PackageStream {
         &self.0.read_provider
     }
 
+    pub fn tunnel_stats(&self) -> &TunnelStats {
+        &self.0.stats
+    }
+
+    fn set_close_reason(&self, reason: StreamCloseReason) {
+        let close_reason = &mut *self.0.close_reason.lock().unwrap();
+        if close_reason.is_none() {
+            *close_reason = Some(reason);
+        }
+    }
+
+    fn close_reason(&self) -> Option<StreamCloseReason> {
+        self.0.close_reason.lock().unwrap().clone()
+    }
+
+    // 通知对端reset并关闭读写，没有发送和没有确认的数据被丢弃
+    fn reset_with(&self, reason: StreamCloseReason) {
+        info!("{} reset for {}", self, reason);
+        self.set_close_reason(reason);
+        let mut package = SessionData::new();
+        package.flags_add(SESSIONDATA_FLAG_RESET);
+        package.send_time = bucky_time_now();
+        let _ = self.send_packages(vec![DynamicPackage::from(package)]);
+        self.write_provider().reset(self);
+        self.read_provider().reset();
+    }
+
     fn package_delay(&self, package: DynamicPackage, send_time: Instant) {
         let mut package_queue = self.0.package_queue.lock().unwrap();
         package_queue.push_back(PacePackage {
@@ -220,9 +257,14 @@ impl StreamProvider for PackageStream {
         task::spawn(async move {
             loop {
                 let now = bucky_time_now();
+                if stream.write_provider().is_linger_expired(now, stream.config().package.linger) {
+                    warn!("{} fin not acked in linger {:?}", stream, stream.config().package.linger);
+                    stream.reset_with(StreamCloseReason::LingerTimeout);
+                }
                 let mut packages = Vec::new(); 
                 let write_result = stream.write_provider().on_time_escape(&stream, now, &mut packages);
                 if write_result.is_err() {
+                    owner.set_close_reason(StreamCloseReason::Timeout);
                     owner.break_with_error(write_result.unwrap_err(), true, true);
                     stream.read_provider().break_with_error(BuckyError::new(BuckyErrorCode::ErrorState, "stream broken"));
                     break;
@@ -230,6 +272,9 @@ impl StreamProvider for PackageStream {
                 let write_result = write_result.unwrap();
                 let read_result = stream.read_provider().on_time_escape(&stream, now, &mut packages);
                 if write_result.is_err() && read_result.is_err() {
+                    if let Some(reason) = stream.close_reason() {
+                        owner.set_close_reason(reason);
+                    }
                     owner.on_shutdown(true);
                     break;
                 }
@@ -249,8 +294,13 @@ impl StreamProvider for PackageStream {
                 let _ = self.read_provider().close();
             }, 
             Shutdown::Both => {
-                let _ = self.write_provider().close(self, None);
-                let _ = self.read_provider().close();
+                if self.config().package.linger.is_zero() {
+                    self.reset_with(StreamCloseReason::LocalReset);
+                } else {
+                    // 已经写入的数据和fin继续发送，直到对端确认或者linger超时
+                    let _ = self.write_provider().close(self, None);
+                    let _ = self.read_provider().close();
+                }
             }
         }
         Ok(())
@@ -303,8 +353,11 @@ impl OnPackage<SessionData> for PackageStream {
         } else {
             trace!("{} on session data {}", self, session_data);
             let write_result = if session_data.is_reset() {
+                debug!("{} recv reset from remote", self);
+                self.set_close_reason(StreamCloseReason::RemoteReset);
                 self.write_provider().reset(self);
-                OnPackageResult::Continue
+                self.read_provider().reset();
+                OnPackageResult::Handled
             } else {
                 self.write_provider().on_package(session_data, (self, &mut packages))?
             };
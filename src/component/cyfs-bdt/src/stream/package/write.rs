@@ -15,6 +15,7 @@ use crate::{
 use super::{
     send_queue::SendQueue, 
     stream::PackageStream, 
+    super::autotune::BufferAutotuner, 
 };
 
 struct EstimateStub {
@@ -34,9 +35,24 @@ struct WriteProviderImpl {
     last_recv: Timestamp, 
     cc: CongestionControl,
     app_limited: bool,
+    tuner: BufferAutotuner, 
+    // 第一次调用close的时间，用于linger超时
+    close_at: Option<Timestamp>, 
 }
 
 impl WriteProviderImpl {
+    fn drain(&self, stream: &PackageStream) -> usize {
+        (self.queue.capacity() as f32 * stream.config().drain) as usize
+    }
+
+    // 用拥塞控制估计的带宽和rtt调整发送缓冲区
+    fn autotune(&mut self, stream: &PackageStream, now: Timestamp) {
+        if let Some(capacity) = self.tuner.on_sample(now, self.cc.rate(), self.cc.rtt()) {
+            debug!("{} autotune send buffer from {} to {}", stream, self.queue.capacity(), capacity);
+            self.queue.set_capacity(capacity);
+        }
+    }
+
     fn check_wnd(&mut self, stream: &PackageStream, now: Timestamp, timeout: Duration, packages: &mut Vec<DynamicPackage>, logging: bool) {
         self.queue.check_wnd(stream, now, timeout, self.cc.cwnd(), packages, logging);
         self.on_pre_send_package(stream, packages);
@@ -50,6 +66,7 @@ impl WriteProviderImpl {
         let (lost, _break) = if self.queue.flight() > 0 {
             let lost = self.queue.check_timeout(now, self.cc.rto());
             if lost > 0 {
+                stream.tunnel_stats().on_loss(lost as u64);
                 if lost >= self.queue.flight() {
                     let d = Duration::from_micros(now - self.last_recv);
                     if d > stream.config().package.break_overtime {
@@ -118,16 +135,19 @@ pub struct WriteProvider(Mutex<WriteProviderState>);
 
 impl WriteProvider {
     pub fn new(config: &super::super::container::Config) -> Self {
+        let tuner = BufferAutotuner::new(&config.autotune, config.send_buffer);
         Self(Mutex::new(WriteProviderState::Open(WriteProviderImpl {
             write_waiter: None, 
             flush_waiters: LinkedList::new(), 
             close_waiter: None, 
-            queue: SendQueue::new(config.send_buffer), 
+            queue: SendQueue::new(tuner.current()), 
             est_id: IncreaseIdGenerator::new(), 
             est_stubs: LinkedList::new(), 
             last_recv: bucky_time_now(), 
             cc: CongestionControl::new(PackageStream::mss(), &config.package.cc),
             app_limited: false,
+            tuner, 
+            close_at: None, 
         })))
     }
 
@@ -231,6 +251,9 @@ impl WriteProvider {
             match state {
                 WriteProviderState::Open(provider) => {
                     provider.queue.close(stream);
+                    if provider.close_at.is_none() {
+                        provider.close_at = Some(bucky_time_now());
+                    }
                     provider.close_waiter = waker.map(|w| w.clone());
                     provider.check_wnd(stream, bucky_time_now(), provider.cc.rto(), &mut packages, false);
                     Poll::Pending
@@ -242,6 +265,18 @@ impl WriteProvider {
         result
     }
 
+    // close之后超过linger还没有收到fin的确认
+    pub fn is_linger_expired(&self, now: Timestamp, linger: Duration) -> bool {
+        let state = &*cyfs_debug::lock!(self.0).unwrap();
+        match state {
+            WriteProviderState::Open(provider) => match provider.close_at {
+                Some(close_at) => now > close_at && Duration::from_micros(now - close_at) > linger,
+                None => false,
+            },
+            WriteProviderState::Closed => false,
+        }
+    }
+
     pub fn on_sent(&self, sent_bytes: u64, last_packet_number: u64) {
         let state = &mut *cyfs_debug::lock!(self.0).unwrap();
         match state {
@@ -313,6 +348,7 @@ impl OnPackage<SessionData, (&PackageStream, &mut Vec<DynamicPackage>)> for Writ
                                 let rtt = Duration::from_micros(bucky_time_now() - sample.send_time);
                                 let delay = rtt / 2;
                                 provider.cc.on_estimate(rtt, delay, provider.app_limited);
+                                stream.tunnel_stats().on_rtt(rtt);
                                 debug!("{} estimate rtt:{:?} delay:{:?} rto:{:?}", stream, rtt, delay, provider.cc.rto());
                                 to_remove = Some(index);
                                 break;  
@@ -346,9 +382,10 @@ impl OnPackage<SessionData, (&PackageStream, &mut Vec<DynamicPackage>)> for Writ
                             bucky_time_now(),
                             provider.app_limited);
                             debug!("{} update cwnd: {}", stream, provider.cc.cwnd());
+                            provider.autotune(stream, now);
                             provider.check_wnd(stream, now, provider.cc.rto(), &mut packages, false);
                             
-                            if provider.queue.used() < stream.config().send_drain() {
+                            if provider.queue.used() < provider.drain(stream) {
                                 if let Some(waiter) = provider.write_waiter.as_ref() {
                                     waiters.push_back(waiter.clone());
                                     provider.write_waiter = None;
@@ -1,7 +1,7 @@
 use log::*;
 use std::{
     time::Duration, 
-    sync::{atomic::{AtomicPtr, Ordering}, Mutex}, 
+    sync::{atomic::{AtomicPtr, Ordering}, Mutex, RwLock}, 
     task::{Context, Poll, Waker}, 
     collections::LinkedList
 };
@@ -73,7 +73,7 @@ fn box_header_len() -> usize {
 impl SendQueue {
     fn new(config: &super::container::Config) -> Self {
         let record_count = config.send_buffer / config.tcp.max_record as usize + 1;
-        let send_buffer = record_count * (box_header_len() + AesKey::padded_len(config.tcp.max_record as usize));
+        let send_buffer = record_count * (box_header_len() + TunnelCipher::max_padded_len(config.tcp.max_record as usize));
         Self {
             state: SendQueueState::Open,
             nagle_id_generator: IncreaseIdGenerator::new(), 
@@ -357,7 +357,7 @@ impl RecvQueue {
         }, record_waker, data_producer)
     }
 
-    async fn recv_record<'a>(socket: &mut async_std::net::TcpStream, key: &AesKey,  
+    async fn recv_record<'a>(socket: &mut async_std::net::TcpStream, stream: &TcpStream,  
         header_buffer: &'a mut [u8], record_buffer: &'a mut [u8]) -> Result<usize, BuckyError> {
         let r = socket.read_exact(header_buffer).await;
         if r.is_err() {
@@ -371,7 +371,7 @@ impl RecvQueue {
         let (len, _) = u16::raw_decode(header_buffer)?;
         let len = len as usize;
         let _ = socket.read_exact(&mut record_buffer[..len]).await?;
-        let len = key.inplace_decrypt(record_buffer, len)?;
+        let len = stream.decrypt_record(record_buffer, len)?;
         Ok(len)
     }
 
@@ -381,11 +381,10 @@ impl RecvQueue {
         data_producer: ringbuf::Producer<u8>) {
         let mut socket = stream.0.socket.clone();
         let config = &stream.0.config;
-        let key = &stream.0.key;
         let mut data_producer = data_producer;
 
         let mut header_buffer = vec![0u8; box_header_len()];
-        let mut record_buffer = vec![0u8; AesKey::padded_len(config.tcp.max_record as usize)]; // TODO, padded_len 调用变化
+        let mut record_buffer = vec![0u8; TunnelCipher::max_padded_len(config.tcp.max_record as usize)];
         loop {
             if data_producer.len() + config.tcp.max_record as usize > data_producer.capacity() {
                 trace!("{} recv buffer full waiting", stream);
@@ -402,7 +401,7 @@ impl RecvQueue {
                 // let _ = future::timeout(Duration::from_millis(100), future::pending::<()>()).await;
             } else {
                 trace!("{} pre recv record", stream);
-                match Self::recv_record(&mut socket, key, &mut header_buffer[..], &mut record_buffer[..]).await {
+                match Self::recv_record(&mut socket, &stream, &mut header_buffer[..], &mut record_buffer[..]).await {
                     Ok(len) => {
                         if len == 0 {
                             debug!("{} recv record bytes {}", stream, len);
@@ -615,7 +614,8 @@ struct TcpStreamImpl {
     config: super::container::Config,
     local: Endpoint, 
     remote: Endpoint, 
-    key: AesKey, 
+    // 使用tunnel上协商的加密算法
+    key: RwLock<MixAesKey>, 
     socket: async_std::net::TcpStream, 
     send_queue: Mutex<Option<SendQueue>>, 
     read_provider: Mutex<PollReadProvider>, 
@@ -634,7 +634,7 @@ impl TcpStream {
     pub fn new(
         owner: StreamContainer, 
         socket: async_std::net::TcpStream, 
-        key: AesKey) -> BuckyResult<Self> {
+        key: MixAesKey) -> BuckyResult<Self> {
         let local = Endpoint::from((Protocol::Tcp, socket.local_addr()?));
         let remote = Endpoint::from((Protocol::Tcp, socket.peer_addr()?));
         let stack = owner.stack();
@@ -647,7 +647,7 @@ impl TcpStream {
             config, 
             local, 
             remote, 
-            key, 
+            key: RwLock::new(key), 
             socket, 
             send_queue: Mutex::new(Some(send_queue)), 
             read_provider: Mutex::new(PollReadProvider::Open(recv_queue))
@@ -680,14 +680,28 @@ impl TcpStream {
         }
     }
 
+    // 对端已经开始使用协商的算法加密，本端也切换过去
+    fn decrypt_record(&self, record: &mut [u8], len: usize) -> BuckyResult<usize> {
+        let key = self.0.key.read().unwrap().clone();
+        let (len, used_cipher) = key.inplace_decrypt(record, len)?;
+        if key.pending_cipher == Some(used_cipher) {
+            self.0.owner.stack().keystore().update_cipher(self.0.owner.remote().0, &key.mix_key, used_cipher, false);
+            let key = &mut *self.0.key.write().unwrap();
+            key.cipher = used_cipher;
+            key.pending_cipher = None;
+        }
+        Ok(len)
+    }
+
     fn encode_record(&self, to_send: &ToSend) -> (AtomicPtr<u8>, usize) {
         trace!("{} encode_record {{data_len:{},exists_len:{}}}", self, to_send.data_len, to_send.exists_len);
         let _config = &self.0.config;
+        let key = self.0.key.read().unwrap().clone();
         let mut buffer = unsafe {
             std::slice::from_raw_parts_mut(to_send.buffer_ptr, to_send.buffer_len)
         };
         if to_send.exists_len != 0 {
-            let record_len = self.0.key.inplace_encrypt(&mut buffer[box_header_len()..], to_send.exists_len).unwrap();
+            let record_len = key.inplace_encrypt(&mut buffer[box_header_len()..], to_send.exists_len).unwrap();
             let _ = (record_len as u16).raw_encode(buffer, &None).unwrap();
             buffer = &mut buffer[box_header_len() + record_len..];
         }
@@ -701,7 +715,8 @@ impl TcpStream {
                     data.len()
                 }
             };
-            let record_len = self.0.key.encrypt(&data, &mut buffer[box_header_len()..], data_len).unwrap();
+            buffer[box_header_len()..box_header_len() + data_len].copy_from_slice(&data[..data_len]);
+            let record_len = key.inplace_encrypt(&mut buffer[box_header_len()..], data_len).unwrap();
             let _ = (record_len as u16).raw_encode(buffer, &None).unwrap();
             buffer = &mut buffer[box_header_len() + record_len..];
             data = &data[data_len..];
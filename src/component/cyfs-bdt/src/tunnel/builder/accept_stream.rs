@@ -333,13 +333,16 @@ impl AcceptStreamBuilder {
            
             // first box 包含 ack tunnel 和 session data
             let tunnel = self.tunnel();
+            // 接受对端提议的加密算法，并在回复中带回
+            tunnel.on_remote_cipher(syn_tunnel.cipher, caller_box.key());
             let ack_tunnel = SynTunnel {
                 protocol_version: tunnel.protocol_version(), 
                 stack_version: tunnel.stack_version(), 
                 to_device_id: syn_tunnel.from_device_desc.desc().device_id(),
                 sequence: syn_tunnel.sequence,
                 from_device_desc: local,
-                send_time: 0
+                send_time: 0, 
+                cipher: syn_tunnel.cipher
             };
             let mut first_box = PackageBox::encrypt_box(caller_box.remote().clone(), caller_box.key().clone());
             first_box.append(vec![DynamicPackage::from(ack_tunnel), DynamicPackage::from(confirm_ack.package_syn_ack.clone_with_data())]);
@@ -412,6 +415,7 @@ impl AcceptStreamBuilder {
             remote_device_id.clone(), 
             remote_device_desc, 
             key, 
+            stack.keystore(), 
             stack.config().tunnel.tcp.connect_timeout).await
             .map_err(|err| { 
                 tunnel.mark_dead(tunnel.state());
@@ -444,7 +448,7 @@ impl AcceptStreamBuilder {
                 stream.establish_with(
                     StreamProviderSelector::Tcp(
                         tcp_interface.socket().clone(), 
-                        tcp_interface.key().clone(), 
+                        tcp_interface.key(), 
                         None)).await
             }, 
             TCP_ACK_CONNECTION_RESULT_REFUSED => {
@@ -561,7 +565,7 @@ impl OnPackage<TcpSynConnection, tcp::AcceptInterface> for AcceptStreamBuilder {
                     Ok(_) => builder.building_stream().establish_with(
                         StreamProviderSelector::Tcp(
                             interface.socket().clone(), 
-                            interface.key().clone(), 
+                            interface.key(), 
                             None)).await, 
                     Err(err) => {
                         let _ = builder.building_stream().cancel_connecting_with(&err);
@@ -666,6 +670,7 @@ impl OnPackage<TcpSynConnection> for AcceptStreamBuilder {
                 remote_id.clone(), 
                 remote_desc, 
                 key,
+                stack.keystore(), 
                 stack.config().tunnel.tcp.connect_timeout).await?;
             
             let tcp_ack = builder.wait_confirm().await.map(|ack| {
@@ -696,7 +701,7 @@ impl OnPackage<TcpSynConnection> for AcceptStreamBuilder {
                                 stream.establish_with(
                                     StreamProviderSelector::Tcp(
                                         interface.socket().clone(), 
-                                        interface.key().clone(), 
+                                        interface.key(), 
                                         None)).await
                             }, 
                             TCP_ACK_CONNECTION_RESULT_REFUSED => {
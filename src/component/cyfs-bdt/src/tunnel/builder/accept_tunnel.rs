@@ -67,13 +67,17 @@ impl AcceptTunnelBuilder {
             let syn_tunnel: &SynTunnel = caller_box.packages_no_exchange()[0].as_ref();           
             // first box 包含 ack tunnel 和 session data
             let tunnel = &self.0.tunnel;
+            tunnel.check_hybrid_kem_policy(&caller_box)?;
+            // 接受对端提议的加密算法，并在回复中带回
+            tunnel.on_remote_cipher(syn_tunnel.cipher, caller_box.key());
             let ack_tunnel = SynTunnel {
                 protocol_version: tunnel.protocol_version(), 
                 stack_version: tunnel.stack_version(), 
                 to_device_id: syn_tunnel.from_device_desc.desc().device_id(),
                 sequence: syn_tunnel.sequence,
                 from_device_desc: local,
-                send_time: 0
+                send_time: 0, 
+                cipher: syn_tunnel.cipher
             };
             let mut first_box = PackageBox::encrypt_box(caller_box.remote().clone(), caller_box.key().clone());
             first_box.append(vec![DynamicPackage::from(ack_tunnel)]);
@@ -82,6 +86,20 @@ impl AcceptTunnelBuilder {
             info!("{} build with key {}", self, first_box.key());
             let _ = self.explore_endpoint_pair(&syn_tunnel.from_device_desc, first_box.clone(), |_| true);
 
+            // 经过sn call的连接在udp tunnel联通之后再发送混合密钥交换的offer
+            if let Some(offer) = tunnel.hybrid_kem_offer(&caller_box, syn_tunnel.sequence) {
+                let tunnel = tunnel.clone();
+                let key = caller_box.key().clone();
+                task::spawn(async move {
+                    if let TunnelState::Active(_) = tunnel.wait_active().await {
+                        if let Ok(udp_tunnel) = tunnel.default_udp_tunnel() {
+                            let offer_box = PackageBox::from_package(tunnel.remote().clone(), key, DynamicPackage::from(offer));
+                            let _ = udp_tunnel.send_box(&offer_box);
+                        }
+                    }
+                });
+            }
+
             if let Some(proxy_builder) = {
                 let state = &mut *self.0.state.write().unwrap();
                 match state {
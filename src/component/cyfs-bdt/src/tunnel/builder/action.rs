@@ -2,7 +2,7 @@ use std::time::{Duration};
 use async_std::{sync::Arc, future, task};
 use async_trait::{async_trait};
 use cyfs_base::*;
-use crate::protocol;
+use crate::{protocol, types::EndpointPair};
 use super::super::*;
 use tunnel::{Tunnel, TunnelState};
 use log::*;
@@ -33,18 +33,31 @@ impl SynUdpTunnel {
         tunnel: udp::Tunnel, 
         first_box: Arc<protocol::PackageBox>, 
         interval: Duration) -> Self {
+        Self::with_trace(tunnel, first_box, interval, None)
+    }
+
+    // trace不为空时记录每个endpoint pair上的打洞次数
+    pub fn with_trace(
+        tunnel: udp::Tunnel, 
+        first_box: Arc<protocol::PackageBox>, 
+        interval: Duration, 
+        trace: Option<BuildTunnelTrace>) -> Self {
         let action = Self(Arc::new(SynUdpTunnelImpl {
             tunnel: tunnel.clone(), 
         }));
         // 有可能收到called之后触发 syn tunnel， 
         if tunnel.try_update_key(&first_box).is_err() {
             let action = action.clone();
+            let ep_pair = EndpointPair::from((*tunnel.local(), *tunnel.remote()));
             task::spawn(async move {
                 loop {
                     match tunnel.state() {
                         TunnelState::Connecting => {
                             let result = tunnel.send_box(&first_box);
                             debug!("{} send first box result: {:?}", action, result);
+                            if let Some(trace) = trace.as_ref() {
+                                trace.on_hole_punch(&ep_pair);
+                            }
                         },
                         _ => break,
                     } 
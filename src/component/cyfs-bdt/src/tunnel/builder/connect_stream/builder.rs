@@ -214,7 +214,8 @@ impl ConnectStreamBuilder {
             return None;
         }
         let syn_session_data = syn_session_data.unwrap();
-        let key_stub = stack.keystore().create_key(self.tunnel().remote_const(), true);
+        let mut key_stub = stack.keystore().create_key(self.tunnel().remote_const(), true);
+        let cipher = self.tunnel().propose_cipher(&mut key_stub.key);
         // 生成第一个package box
         let mut first_box = PackageBox::encrypt_box(
             self.tunnel().remote().clone(), 
@@ -226,10 +227,12 @@ impl ConnectStreamBuilder {
             to_device_id: self.tunnel().remote().clone(),
             from_device_desc: local.clone(),
             sequence: syn_session_data.syn_info.as_ref().unwrap().sequence.clone(), 
-            send_time: syn_session_data.send_time.clone()
+            send_time: syn_session_data.send_time.clone(), 
+            cipher
         };
         if let keystore::EncryptedKey::Unconfirmed(encrypted) = key_stub.encrypted {
             let mut exchg = Exchange::from((&syn_tunnel, encrypted, key_stub.key.mix_key));
+            exchg.capabilities = self.tunnel().exchange_capabilities();
             let _ = exchg.sign(stack.keystore().signer()).await;
             first_box.push(exchg);
         }
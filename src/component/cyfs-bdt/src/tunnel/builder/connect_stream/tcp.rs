@@ -125,6 +125,7 @@ impl ConnectTcpStream {
                                                                         tunnel.remote().clone(),
                                                                         tunnel.remote_const().clone(),
                                                                         key.key, 
+                                                                        &keystore, 
                                                                         Stack::from(&ca.0.stack).config().tunnel.tcp.connect_timeout
                     ).await;
 
@@ -272,7 +273,7 @@ impl ConnectStreamAction for ConnectTcpStream {
 
         Ok(StreamProviderSelector::Tcp(
                 interface.socket().clone(), 
-                interface.key().clone(), 
+                interface.key(), 
                 Some(ack.clone())))
     }
 }
@@ -471,7 +472,7 @@ impl ConnectStreamAction for AcceptReverseTcpStream {
 
         Ok(StreamProviderSelector::Tcp(
                 interface.socket().clone(), 
-                interface.key().clone(), 
+                interface.key(), 
                 None))
     }
 }
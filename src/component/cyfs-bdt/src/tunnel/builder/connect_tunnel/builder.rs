@@ -43,7 +43,8 @@ struct ConnectTunnelBuilderImpl {
     tunnel: TunnelContainer,
     params: BuildTunnelParams, 
     sequence: TempSeq,
-    state: RwLock<ConnectTunnelBuilderState>
+    state: RwLock<ConnectTunnelBuilderState>, 
+    trace: BuildTunnelTrace
 }
 
 #[derive(Clone)]
@@ -58,19 +59,25 @@ impl std::fmt::Display for ConnectTunnelBuilder {
 impl ConnectTunnelBuilder {
     pub fn new(stack: WeakStack, tunnel: TunnelContainer, params: BuildTunnelParams) -> Self {
         let sequence = tunnel.generate_sequence();
+        let start_at = bucky_time_now();
         Self(Arc::new(ConnectTunnelBuilderImpl {
             stack, 
-            start_at: bucky_time_now(), 
+            start_at, 
             tunnel,
             params, 
             sequence, 
             state: RwLock::new(ConnectTunnelBuilderState::Connecting(ConnectingState {
                 proxy: None, 
                 waiter:StateWaiter::new()
-            }))
+            })), 
+            trace: BuildTunnelTrace::new(start_at)
         }))
     }
 
+    pub fn trace(&self) -> &BuildTunnelTrace {
+        &self.0.trace
+    }
+
     fn escaped(&self) -> Duration {
         let now = bucky_time_now();
         if now > self.0.start_at {
@@ -85,10 +92,24 @@ impl ConnectTunnelBuilder {
         let local = stack.sn_client().ping().default_local();
         let build_params = &self.0.params;
 
-        let first_box = Arc::new(self.first_box(&local).await);
+        let mut first_box = Arc::new(self.first_box(&local).await);
 
         info!("{} build with key {}", self, first_box.key());
         let remote_id = build_params.remote_const.device_id();
+
+        if let Some(endpoints) = stack.session_store().take_resumable(&remote_id) {
+            if self.resume_session(&stack, endpoints, first_box.clone()).await {
+                return Ok(());
+            }
+            if TunnelBuilderState::Closed == self.state() {
+                return Ok(());
+            }
+            // 对端已经没有恢复的key，重新走完整的密钥交换
+            stack.keystore().reset_peer(&remote_id);
+            first_box = Arc::new(self.first_box(&local).await);
+            info!("{} resume session failed, rebuild with key {}", self, first_box.key());
+        }
+
         let cached_remote = stack.device_cache().get_inner(&remote_id);
         let known_remote = cached_remote.as_ref().or_else(|| build_params.remote_desc.as_ref());
 
@@ -101,6 +122,7 @@ impl ConnectTunnelBuilder {
    
         if actions.len() == 0 {
             let nearest_sn = build_params.nearest_sn(&stack);
+            self.0.trace.record(BuildTunnelTraceEvent::ResolveSn(nearest_sn.clone()));
             if let Some(sn) = nearest_sn {
                 info!("{} call nearest sn, sn={}", self, sn);
                 let timeout_ret = future::timeout(stack.config().tunnel.retry_sn_timeout, self.call_sn(vec![sn.clone()], first_box.clone())).await;
@@ -165,6 +187,7 @@ impl ConnectTunnelBuilder {
                     match state {
                         ConnectTunnelBuilderState::Connecting(connecting) => {
                             info!("{} connecting=>dead", self);
+                            self.0.trace.record(BuildTunnelTraceEvent::Closed);
                             let mut ret_waiter = StateWaiter::new();
                             connecting.waiter.transfer_into(&mut ret_waiter);
                             *state = ConnectTunnelBuilderState::Closed;
@@ -196,6 +219,12 @@ impl ConnectTunnelBuilder {
                     match state {
                         ConnectTunnelBuilderState::Connecting(connecting) => {
                             info!("{} connecting=>establish", builder);
+                            if let Ok(tunnel) = builder.0.tunnel.default_tunnel() {
+                                let tunnel = tunnel.as_ref();
+                                builder.0.trace.record(BuildTunnelTraceEvent::Establish(
+                                    EndpointPair::from((*tunnel.local(), *tunnel.remote())), 
+                                    tunnel.proxy()));
+                            }
                             let mut ret_waiter = StateWaiter::new();
                             connecting.waiter.transfer_into(&mut ret_waiter);
                             *state = ConnectTunnelBuilderState::Establish;
@@ -215,6 +244,7 @@ impl ConnectTunnelBuilder {
                     match state {
                         ConnectTunnelBuilderState::Connecting(connecting) => {
                             info!("{} connecting=>dead", builder);
+                            builder.0.trace.record(BuildTunnelTraceEvent::Closed);
                             let mut ret_waiter = StateWaiter::new();
                             connecting.waiter.transfer_into(&mut ret_waiter);
                             *state = ConnectTunnelBuilderState::Closed;
@@ -266,6 +296,7 @@ impl ConnectTunnelBuilder {
     async fn call_sn_inner(&self, sn_list: Vec<DeviceId>, first_box: Arc<PackageBox>) -> BuckyResult<()> {
         let stack = Stack::from(&self.0.stack);
         let tunnel = &self.0.tunnel;
+        self.0.trace.record(BuildTunnelTraceEvent::CallSn(sn_list.clone()));
         let call_session = stack.sn_client().call().call(
             None,
             tunnel.remote(),
@@ -283,6 +314,7 @@ impl ConnectTunnelBuilder {
                 buf
             }).await.map_err(|err| {
                 error!("{} call sn failed, sn={:?}, err={}", self, sn_list, err);
+                self.0.trace.record(BuildTunnelTraceEvent::SnFailed { sn: sn_list.clone(), err: err.clone() });
                 err
             })?; 
         
@@ -293,6 +325,10 @@ impl ConnectTunnelBuilder {
                 .ok().and_then(|opt| opt) {
                 match session.result().unwrap() {
                     Ok(remote) => {
+                        self.0.trace.record(BuildTunnelTraceEvent::SnResponsed { 
+                            sn: session.sn().clone(), 
+                            endpoints: remote.connect_info().endpoints().clone() 
+                        });
                         if let Some(proxy_buidler) = {
                             info!("{} call sn session responsed, sn={:?}, endpoints={:?}", self, session.sn(), remote.connect_info().endpoints());
                             let state = &mut *self.0.state.write().unwrap();
@@ -316,9 +352,11 @@ impl ConnectTunnelBuilder {
                         } {
                             //FIXME: 使用正确的proxy策略
                             for proxy in stack.proxy_manager().active_proxies() {
+                                self.0.trace.record(BuildTunnelTraceEvent::SynProxy(ProxyType::Active(proxy.clone())));
                                 let _ = proxy_buidler.syn_proxy(ProxyType::Active(proxy)).await;
                             }
                             for proxy in remote.connect_info().passive_pn_list().iter().cloned() {
+                                self.0.trace.record(BuildTunnelTraceEvent::SynProxy(ProxyType::Passive(proxy.clone())));
                                 let _ = proxy_buidler.syn_proxy(ProxyType::Passive(proxy)).await;
                             }
                         }
@@ -328,6 +366,7 @@ impl ConnectTunnelBuilder {
                     },
                     Err(err) => {
                         error!("{} call sn session failed, sn={:?}, err={}", self, session.sn(), err);
+                        self.0.trace.record(BuildTunnelTraceEvent::SnFailed { sn: vec![session.sn().clone()], err: err.clone() });
                     }
                 }
             } else {
@@ -343,24 +382,51 @@ impl ConnectTunnelBuilder {
         }
     }
 
+    // 用重启前保存的key直连重启前活跃的endpoint，成功时不需要密钥交换和sn call
+    async fn resume_session(&self, stack: &Stack, endpoints: Vec<Endpoint>, first_box: Arc<PackageBox>) -> bool {
+        let remote_id = self.0.tunnel.remote();
+        match stack.keystore().get_key_by_remote(remote_id, false) {
+            Some(found) if !found.encrypted.is_unconfirmed() => {}, 
+            _ => return false
+        }
+
+        info!("{} try resume session with endpoints {:?}", self, endpoints);
+        let actions = self.explore_endpoints(&endpoints, first_box, |_| true);
+        if actions.len() == 0 {
+            return false;
+        }
+
+        match future::timeout(stack.config().session.resume_timeout, self.wait_establish()).await {
+            Ok(Ok(_)) => {
+                info!("{} resume session finished", self);
+                true
+            }, 
+            _ => false
+        }
+    }
+
     fn explore_endpoint_pair<F: Fn(&Endpoint) -> bool>(&self, remote: &Device, first_box: Arc<PackageBox>, filter: F) -> Vec<DynBuildTunnelAction> {
+        self.explore_endpoints(remote.connect_info().endpoints(), first_box, filter)
+    }
+
+    fn explore_endpoints<F: Fn(&Endpoint) -> bool>(&self, endpoints: &Vec<Endpoint>, first_box: Arc<PackageBox>, filter: F) -> Vec<DynBuildTunnelAction> {
         let stack = Stack::from(&self.0.stack);
         let tunnel = &self.0.tunnel;
         let net_listener = stack.net_manager().listener();
 
         let mut actions = vec![];
-
-        let connect_info = remote.connect_info();
         
         // FIXME: ipv6 udp frame may not support supper frame, simply ignore it now
         for udp_interface in net_listener.udp().iter().filter(|ui| ui.local().addr().is_ipv4()) {
-            for remote_ep in connect_info.endpoints().iter().filter(|ep| ep.is_udp() && ep.is_same_ip_version(&udp_interface.local()) && filter(ep)) {
+            for remote_ep in endpoints.iter().filter(|ep| ep.is_udp() && ep.is_same_ip_version(&udp_interface.local()) && filter(ep)) {
                 if let Ok((udp_tunnel, newly_created)) = tunnel.create_tunnel(EndpointPair::from((udp_interface.local(), *remote_ep)), ProxyType::None) {
                     if newly_created {
-                        let action = SynUdpTunnel::new(
+                        self.0.trace.record(BuildTunnelTraceEvent::ExploreEndpoint(EndpointPair::from((udp_interface.local(), *remote_ep))));
+                        let action = SynUdpTunnel::with_trace(
                             udp_tunnel, 
                             first_box.clone(), 
-                            tunnel.config().udp.holepunch_interval); 
+                            tunnel.config().udp.holepunch_interval, 
+                            Some(self.0.trace.clone())); 
                         actions.push(Box::new(action) as DynBuildTunnelAction);
                     }
                 }  
@@ -368,9 +434,10 @@ impl ConnectTunnelBuilder {
         }
 
         // for local_ip in net_listener.ip_set() {
-            for remote_ep in connect_info.endpoints().iter().filter(|ep| ep.is_tcp() && filter(ep)) {
+            for remote_ep in endpoints.iter().filter(|ep| ep.is_tcp() && filter(ep)) {
                 if let Ok((tunnel, newly_created)) = tunnel.create_tunnel(EndpointPair::from((Endpoint::default_tcp(remote_ep), *remote_ep)), ProxyType::None) {
                     if newly_created {
+                        self.0.trace.record(BuildTunnelTraceEvent::ExploreEndpoint(EndpointPair::from((Endpoint::default_tcp(remote_ep), *remote_ep))));
                         let action = ConnectTcpTunnel::new(tunnel);
                         actions.push(Box::new(action) as DynBuildTunnelAction);
                     }
@@ -386,7 +453,8 @@ impl ConnectTunnelBuilder {
         let stack = Stack::from(&self.0.stack);
         let tunnel = &self.0.tunnel;
 
-        let key_stub = stack.keystore().create_key(tunnel.remote_const(), true);
+        let mut key_stub = stack.keystore().create_key(tunnel.remote_const(), true);
+        let cipher = tunnel.propose_cipher(&mut key_stub.key);
         // 生成第一个package box
         let mut first_box = PackageBox::encrypt_box(tunnel.remote().clone(), key_stub.key.clone());
             
@@ -396,10 +464,12 @@ impl ConnectTunnelBuilder {
             to_device_id: tunnel.remote().clone(), 
             from_device_desc: local.clone(),
             sequence: self.sequence(), 
-            send_time: bucky_time_now()
+            send_time: bucky_time_now(), 
+            cipher
         };
         if let keystore::EncryptedKey::Unconfirmed(key_encrypted) = key_stub.encrypted {
             let mut exchange = Exchange::from((&syn_tunnel, key_encrypted, key_stub.key.mix_key));
+            exchange.capabilities = tunnel.exchange_capabilities();
             let _ = exchange.sign(stack.keystore().signer()).await;
             first_box.push(exchange);
         }
@@ -452,6 +522,7 @@ impl PingClientCalledEvent for ConnectTunnelBuilder {
         let builder = self.clone();
         let active_pn_list = called.active_pn_list.clone();
         let remote_timestamp = called.peer_info.get_obj_update_time();
+        builder.0.trace.record(BuildTunnelTraceEvent::SnCalled);
         task::spawn(async move {
             let stack = Stack::from(&builder.0.stack);
             let first_box = builder.first_box(&stack.sn_client().ping().default_local()).await;
@@ -5,9 +5,11 @@ mod connect_stream;
 mod accept_stream;
 mod connect_tunnel;
 mod accept_tunnel;
+mod trace;
 pub use action::{BuildTunnelAction, SynUdpTunnel};
 pub use builder::*;
 pub use connect_stream::*; 
 pub use accept_stream::*;
 pub use connect_tunnel::*;
-pub use accept_tunnel::*;
\ No newline at end of file
+pub use accept_tunnel::*;
+pub use trace::*;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration
+};
+use cyfs_base::*;
+use crate::{
+    types::*,
+    tunnel::ProxyType
+};
+
+// 建立tunnel过程中的关键步骤，用于诊断连接问题
+#[derive(Clone, Debug)]
+pub enum BuildTunnelTraceEvent {
+    // 选出的对端最近的sn，None时没有可用的sn
+    ResolveSn(Option<DeviceId>),
+    // 向对端的一个endpoint发起尝试，udp为打洞，tcp为直连
+    ExploreEndpoint(EndpointPair),
+    CallSn(Vec<DeviceId>),
+    SnResponsed {
+        sn: DeviceId,
+        endpoints: Vec<Endpoint>
+    },
+    SnFailed {
+        sn: Vec<DeviceId>,
+        err: BuckyError
+    },
+    // 收到对端的sn called
+    SnCalled,
+    SynProxy(ProxyType),
+    Establish(EndpointPair, ProxyType),
+    Closed
+}
+
+impl fmt::Display for BuildTunnelTraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ResolveSn(sn) => match sn {
+                Some(sn) => write!(f, "resolve sn {}", sn),
+                None => write!(f, "no sn resolved")
+            },
+            Self::ExploreEndpoint(ep_pair) => write!(f, "explore endpoint {}", ep_pair),
+            Self::CallSn(sn) => write!(f, "call sn {:?}", sn),
+            Self::SnResponsed { sn, endpoints } => write!(f, "sn {} responsed, remote endpoints {:?}", sn, endpoints),
+            Self::SnFailed { sn, err } => write!(f, "call sn {:?} failed, {}", sn, err),
+            Self::SnCalled => write!(f, "sn called from remote"),
+            Self::SynProxy(proxy) => write!(f, "syn proxy {:?}", proxy),
+            Self::Establish(ep_pair, proxy) => write!(f, "establish on {}, proxy {:?}", ep_pair, proxy),
+            Self::Closed => write!(f, "closed")
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BuildTunnelTraceItem {
+    // 相对于开始建立的时间
+    pub elapsed: Duration,
+    pub event: BuildTunnelTraceEvent
+}
+
+impl fmt::Display for BuildTunnelTraceItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{}ms {}", self.elapsed.as_millis(), self.event)
+    }
+}
+
+struct BuildTunnelTraceImpl {
+    start_at: Timestamp,
+    items: Mutex<Vec<BuildTunnelTraceItem>>,
+    // 每个udp endpoint pair上发出的打洞包个数
+    hole_punch: Mutex<Vec<(EndpointPair, u32)>>
+}
+
+#[derive(Clone)]
+pub struct BuildTunnelTrace(Arc<BuildTunnelTraceImpl>);
+
+impl BuildTunnelTrace {
+    pub(crate) fn new(start_at: Timestamp) -> Self {
+        Self(Arc::new(BuildTunnelTraceImpl {
+            start_at,
+            items: Mutex::new(vec![]),
+            hole_punch: Mutex::new(vec![])
+        }))
+    }
+
+    pub fn start_at(&self) -> Timestamp {
+        self.0.start_at
+    }
+
+    pub fn items(&self) -> Vec<BuildTunnelTraceItem> {
+        self.0.items.lock().unwrap().clone()
+    }
+
+    pub fn hole_punch_attempts(&self) -> Vec<(EndpointPair, u32)> {
+        self.0.hole_punch.lock().unwrap().clone()
+    }
+
+    pub(crate) fn on_hole_punch(&self, ep_pair: &EndpointPair) {
+        let mut hole_punch = self.0.hole_punch.lock().unwrap();
+        match hole_punch.iter_mut().find(|(exists, _)| exists == ep_pair) {
+            Some((_, attempts)) => *attempts += 1,
+            None => hole_punch.push((ep_pair.clone(), 1))
+        }
+    }
+
+    pub(crate) fn record(&self, event: BuildTunnelTraceEvent) {
+        let now = bucky_time_now();
+        let elapsed = if now > self.0.start_at {
+            Duration::from_micros(now - self.0.start_at)
+        } else {
+            Duration::from_micros(0)
+        };
+        self.0.items.lock().unwrap().push(BuildTunnelTraceItem {
+            elapsed,
+            event
+        });
+    }
+}
@@ -31,6 +31,7 @@ use crate::{
     MTU
 };
 use super::{
+    hybrid_kem::{HybridKemResponder, answer_offer}, 
     tunnel::*, 
     builder::*, 
     udp, 
@@ -64,7 +65,14 @@ impl BuildTunnelParams {
     }
 
     pub(crate) fn retry_sn_list(&self, stack: &Stack, nearest: &DeviceId) -> Option<Vec<DeviceId>> {
-        self.remote_sn.clone().or_else(|| Some(stack.sn_client().cache().known_list()))
+        self.remote_sn.clone().or_else(|| {
+                // 没有对端的sn时，已知的sn里优先重试延迟低的
+                let known_list = stack.sn_client().cache().known_list();
+                let mut sn_list = stack.sn_client().prober().prefer_order(&known_list);
+                let rest: Vec<DeviceId> = known_list.into_iter().filter(|sn| !sn_list.contains(sn)).collect();
+                sn_list.extend(rest);
+                Some(sn_list)
+            })
             .map(|sn_list| sn_list.into_iter().filter(|sn| sn != nearest).collect())
 
     }
@@ -75,6 +83,16 @@ pub struct Config {
     pub retain_timeout: Duration,  
     pub retry_sn_timeout: Duration, 
     pub connect_timeout: Duration, 
+    // 发起连接时向对端提议的加密算法，aes-cbc时不协商，和旧版本兼容；
+    // 其他算法要求对端版本支持协商，没有aes指令集的设备可以配置为 TunnelCipher::preferred()
+    pub cipher: TunnelCipher, 
+    // 开启之后在exchange中声明支持 x25519+kyber 混合密钥交换，双方都开启时在udp tunnel上派生新的key；
+    // 对端不支持时不影响建立连接
+    pub hybrid_kem: bool, 
+    // 要求对端支持混合密钥交换，开启后对端的exchange没有声明支持时拒绝建立tunnel；开启时也会声明本端支持
+    pub hybrid_kem_required: bool, 
+    // 按分钟保留的tunnel统计记录数
+    pub stats_history: usize, 
     pub tcp: tcp::Config, 
     pub udp: udp::Config
 }
@@ -118,10 +136,26 @@ enum TunnelStateImpl {
     Dead(TunnelDeadState)
 }
 
+enum HybridKemState {
+    None, 
+    // 响应方已经发出offer，等待answer
+    Offered(HybridKemResponder), 
+    // 发起方已经回复answer，收到对端用新key加密的包之后切换
+    Answered {
+        from: AesKey, 
+        answer: HybridKemAnswer, 
+        key: MixAesKey
+    }
+}
+
 struct TunnelContainerState {
     last_update: Timestamp, 
     tunnel_state: TunnelStateImpl, 
-    tunnel_entries: BTreeMap<EndpointPair, DynamicTunnel>
+    tunnel_entries: BTreeMap<EndpointPair, DynamicTunnel>, 
+    cipher: TunnelCipher, 
+    hybrid_kem: HybridKemState, 
+    // 最近一次主动建立tunnel的过程记录
+    build_trace: Option<BuildTunnelTrace>
 }
 
 struct TunnelContainerImpl {
@@ -130,6 +164,7 @@ struct TunnelContainerImpl {
     remote: DeviceId,  
     remote_const: DeviceDesc, 
     sequence_generator: TempSeqGenerator, 
+    stats: TunnelStats, 
     state: RwLock<TunnelContainerState>,
 }
 
@@ -137,13 +172,14 @@ struct TunnelContainerImpl {
 pub struct TunnelContainer(Arc<TunnelContainerImpl>);
 
 impl TunnelContainer {
-    pub(super) fn new(stack: WeakStack, remote_const: DeviceDesc, config: Config) -> Self {
+    pub(super) fn new(stack: WeakStack, remote_const: DeviceDesc, config: Config, stats: TunnelStats) -> Self {
         Self(Arc::new(TunnelContainerImpl {
             stack, 
             config, 
             remote: remote_const.device_id(), 
             remote_const, 
             sequence_generator: TempSeqGenerator::new(), 
+            stats, 
             state: RwLock::new(TunnelContainerState {
                 tunnel_entries: BTreeMap::new(), 
                 last_update: bucky_time_now(), 
@@ -151,7 +187,10 @@ impl TunnelContainer {
                     waiter: StateWaiter::new(), 
                     build_state: TunnelBuildState::Idle, 
                     packages: LinkedList::new()
-                })
+                }), 
+                cipher: TunnelCipher::AesCbc, 
+                hybrid_kem: HybridKemState::None, 
+                build_trace: None
             }), 
         }))
     }
@@ -213,6 +252,10 @@ impl TunnelContainer {
         &self.0.remote
     }
 
+    pub fn stats(&self) -> &TunnelStats {
+        &self.0.stats
+    }
+
     pub fn remote_const(&self) -> &DeviceDesc {
         &self.0.remote_const
     }
@@ -225,6 +268,166 @@ impl TunnelContainer {
         0
     }
 
+    // 当前和对端协商使用的加密算法
+    pub fn cipher(&self) -> TunnelCipher {
+        self.0.state.read().unwrap().cipher
+    }
+
+    // 发送SynTunnel时按配置提议加密算法，本端先准备好用它解密
+    pub(crate) fn propose_cipher(&self, key: &mut MixAesKey) -> Option<TunnelCipher> {
+        let cipher = self.config().cipher;
+        if cipher == TunnelCipher::AesCbc {
+            None
+        } else {
+            self.stack().keystore().update_cipher(self.remote(), &key.mix_key, cipher, true);
+            if key.cipher != cipher {
+                key.pending_cipher = Some(cipher);
+            }
+            Some(cipher)
+        }
+    }
+
+    // 对端在SynTunnel/AckTunnel中带上的算法，对端此时已经可以用它解密，本端直接切换；
+    // 没有带的话(旧版本或者对端不协商)回退到aes-cbc
+    pub(crate) fn on_remote_cipher(&self, cipher: Option<TunnelCipher>, key: &MixAesKey) {
+        let cipher = cipher.unwrap_or(TunnelCipher::AesCbc);
+        self.stack().keystore().update_cipher(self.remote(), &key.mix_key, cipher, false);
+        let mut state = self.0.state.write().unwrap();
+        if state.cipher != cipher {
+            info!("{} cipher changed {}=>{}", self, state.cipher, cipher);
+            state.cipher = cipher;
+        }
+    }
+
+    fn hybrid_kem_enabled(&self) -> bool {
+        self.config().hybrid_kem || self.config().hybrid_kem_required
+    }
+
+    // 发起方在exchange中声明的能力
+    pub(crate) fn exchange_capabilities(&self) -> u8 {
+        let mut capabilities = 0;
+        if self.hybrid_kem_enabled() {
+            capabilities |= EXCHANGE_CAPABILITY_HYBRID_KEM;
+        }
+        if self.config().udp.mtu_probe {
+            capabilities |= EXCHANGE_CAPABILITY_MTU_PROBE;
+        }
+        capabilities
+    }
+
+    // 本地策略要求混合密钥交换时，拒绝exchange中没有声明支持的对端；
+    // 不带exchange的box用的是之前已经检查过的key，这里不再检查
+    pub(crate) fn check_hybrid_kem_policy(&self, in_box: &PackageBox) -> BuckyResult<()> {
+        if !self.config().hybrid_kem_required || !in_box.has_exchange() {
+            return Ok(());
+        }
+        let exchange: &Exchange = in_box.packages()[0].as_ref();
+        if exchange.has_capability(EXCHANGE_CAPABILITY_HYBRID_KEM) {
+            Ok(())
+        } else {
+            let msg = format!("{} refuse exchange without hybrid kem capability", self);
+            warn!("{}", msg);
+            Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
+        }
+    }
+
+    // 响应方收到带exchange的SynTunnel，双方都支持混合密钥交换时返回要发给对端的offer；
+    // 对端重发SynTunnel时复用同一个offer
+    pub(crate) fn hybrid_kem_offer(&self, in_box: &PackageBox, sequence: TempSeq) -> Option<HybridKemOffer> {
+        if !self.hybrid_kem_enabled() || !in_box.has_exchange() {
+            return None;
+        }
+        let exchange: &Exchange = in_box.packages()[0].as_ref();
+        if !exchange.has_capability(EXCHANGE_CAPABILITY_HYBRID_KEM) {
+            return None;
+        }
+
+        let mut state = self.0.state.write().unwrap();
+        if let HybridKemState::Offered(responder) = &state.hybrid_kem {
+            if responder.mix_key() == &in_box.key().mix_key {
+                return Some(responder.offer().clone());
+            }
+        }
+        let responder = HybridKemResponder::new(sequence, in_box.key());
+        let offer = responder.offer().clone();
+        state.hybrid_kem = HybridKemState::Offered(responder);
+        Some(offer)
+    }
+
+    // 发起方收到offer，返回要回复的answer和派生的新key；新key先加入keystore用于解密，udp tunnel暂时还用旧key加密，
+    // 等收到对端用新key加密的包再切换
+    pub(crate) fn on_hybrid_kem_offer(&self, offer: &HybridKemOffer, key: &MixAesKey) -> BuckyResult<Option<(HybridKemAnswer, MixAesKey)>> {
+        if !self.hybrid_kem_enabled() {
+            debug!("{} ignore hybrid kem offer for not enabled", self);
+            return Ok(None);
+        }
+
+        let mut state = self.0.state.write().unwrap();
+        if let HybridKemState::Answered { from, answer, key: new_key } = &state.hybrid_kem {
+            if from == &key.mix_key && answer.sequence == offer.sequence {
+                return Ok(Some((answer.clone(), new_key.clone())));
+            }
+        }
+        let (answer, new_key) = answer_offer(offer, key)?;
+        info!("{} hybrid kem answered, new key {}", self, new_key);
+        self.stack().keystore().add_key(&new_key, self.remote());
+        state.hybrid_kem = HybridKemState::Answered {
+            from: key.mix_key.clone(), 
+            answer: answer.clone(), 
+            key: new_key.clone()
+        };
+        Ok(Some((answer, new_key)))
+    }
+
+    // 响应方收到answer，返回派生的新key，调用者用它切换tunnel
+    pub(crate) fn on_hybrid_kem_answer(&self, answer: &HybridKemAnswer, key: &MixAesKey) -> BuckyResult<MixAesKey> {
+        let mut state = self.0.state.write().unwrap();
+        let new_key = match &state.hybrid_kem {
+            HybridKemState::Offered(responder) => responder.accept(answer, key), 
+            _ => Err(BuckyError::new(BuckyErrorCode::ErrorState, "hybrid kem not offered"))
+        }?;
+        info!("{} hybrid kem accepted, new key {}", self, new_key);
+        self.stack().keystore().add_key(&new_key, self.remote());
+        state.hybrid_kem = HybridKemState::None;
+        Ok(new_key)
+    }
+
+    // 发起方收到用新key加密的包，说明对端已经切换
+    pub(crate) fn hybrid_kem_confirmed(&self, key: &MixAesKey) -> Option<MixAesKey> {
+        let mut state = self.0.state.write().unwrap();
+        let confirmed = match &state.hybrid_kem {
+            HybridKemState::Answered { key: new_key, .. } => new_key.mix_key == key.mix_key, 
+            _ => false
+        };
+        if confirmed {
+            info!("{} hybrid kem confirmed by remote", self);
+            state.hybrid_kem = HybridKemState::None;
+            Some(key.clone())
+        } else {
+            None
+        }
+    }
+
+    // tcp上响应方切换发送key之前把answer原样发回，发起方收到之后切换接收key
+    pub(crate) fn on_hybrid_kem_echo(&self, echo: &HybridKemAnswer) -> Option<MixAesKey> {
+        let mut state = self.0.state.write().unwrap();
+        let new_key = match &state.hybrid_kem {
+            HybridKemState::Answered { answer, key, .. } => {
+                if answer.sequence == echo.sequence && answer.x25519_public == echo.x25519_public {
+                    Some(key.clone())
+                } else {
+                    None
+                }
+            }, 
+            _ => None
+        };
+        if new_key.is_some() {
+            info!("{} hybrid kem confirmed by remote echo", self);
+            state.hybrid_kem = HybridKemState::None;
+        }
+        new_key
+    }
+
     pub fn default_tunnel(&self) -> BuckyResult<DynamicTunnel> {
         let state = self.0.state.read().unwrap();
         match &state.tunnel_state {
@@ -309,6 +512,7 @@ impl TunnelContainer {
                             // 创建新的 tunnel builder
                             let builder = ConnectTunnelBuilder::new(self.0.stack.clone(), self.clone(), build_params);
                             connecting.build_state = TunnelBuildState::ConnectTunnel(builder.clone());
+                            state.build_trace = Some(builder.trace().clone());
                             Some(builder)
                         }, 
                         _ => {
@@ -320,6 +524,7 @@ impl TunnelContainer {
                 TunnelStateImpl::Dead(_) => {
                     let builder = ConnectTunnelBuilder::new(self.0.stack.clone(), self.clone(), build_params);
                     state.last_update = bucky_time_now();
+                    state.build_trace = Some(builder.trace().clone());
                     let mut packages = LinkedList::new();
                     packages.push_back((package, plaintext));
                     state.tunnel_state = TunnelStateImpl::Connecting(TunnelConnectingState {
@@ -349,6 +554,10 @@ impl TunnelContainer {
         }
     }
 
+    pub fn build_trace(&self) -> Option<BuildTunnelTrace> {
+        self.0.state.read().unwrap().build_trace.clone()
+    }
+
     pub fn state(&self) -> TunnelState {
         match &self.0.state.read().unwrap().tunnel_state {
             TunnelStateImpl::Connecting(_) => TunnelState::Connecting, 
@@ -916,6 +1125,7 @@ impl OnUdpPackageBox for TunnelContainer {
             }, 
             None => self.create_tunnel::<udp::Tunnel>(ep_pair, ProxyType::None).map(|(t, _)| t)
         }?;
+        self.0.stats.on_recv(udp_box.len());
         // 为了udp 和 tcp tunnel的package 流向一致，直接把box转给udp tunnel，
         // 需要一致处理的package从udp/tcp tunnel回调container的 OnPackage
         udp_tunnel.on_udp_package_box(udp_box)
@@ -936,6 +1146,7 @@ impl OnUdpRawData<(interface::udp::Interface, DeviceId, MixAesKey, Endpoint)> fo
         // 为了udp 和 tcp tunnel的package 流向一致，直接把box转给udp tunnel，
         // 需要一致处理的package从udp/tcp tunnel回调container的 OnPackage
         let _ = udp_tunnel.active(&key, false, None);
+        self.0.stats.on_recv(data.len());
         self.on_raw_data(data, DynamicTunnel::new(udp_tunnel))
     }
 }
@@ -0,0 +1,128 @@
+use cyfs_base::*;
+use hkdf::Hkdf;
+use sha2::Sha256;
+use pqcrypto_kyber::kyber768;
+use pqcrypto_traits::kem::{
+    PublicKey as KemPublicKey,
+    Ciphertext as KemCiphertext,
+    SharedSecret as KemSharedSecret
+};
+use x25519_dalek::{StaticSecret, PublicKey as X25519PublicKey};
+use crate::{
+    types::*,
+    protocol::v0::*
+};
+
+const HYBRID_KEM_INFO: &[u8] = b"cyfs-bdt hybrid kem x25519+kyber768";
+
+// 混合密钥交换：在 exchange 建立的 aes key 之上，再叠加 x25519 和 kyber768 两个共享密钥派生新的key，
+// 任意一个算法没有被攻破，新key都是安全的；旧key参与派生，所以仍然有rsa签名保证的身份认证
+pub(crate) struct HybridKemResponder {
+    sequence: TempSeq,
+    // 基于哪个key发起的，收到answer时校验
+    mix_key: AesKey,
+    x25519_secret: StaticSecret,
+    kyber_secret: kyber768::SecretKey,
+    offer: HybridKemOffer
+}
+
+impl HybridKemResponder {
+    pub fn new(sequence: TempSeq, key: &MixAesKey) -> Self {
+        let x25519_secret = StaticSecret::new(rand::rngs::OsRng);
+        let x25519_public = X25519PublicKey::from(&x25519_secret);
+        let (kyber_public, kyber_secret) = kyber768::keypair();
+        Self {
+            sequence,
+            mix_key: key.mix_key.clone(),
+            x25519_secret,
+            kyber_secret,
+            offer: HybridKemOffer {
+                sequence,
+                x25519_public: x25519_public.as_bytes().to_vec(),
+                kyber_public: kyber_public.as_bytes().to_vec()
+            }
+        }
+    }
+
+    pub fn mix_key(&self) -> &AesKey {
+        &self.mix_key
+    }
+
+    pub fn offer(&self) -> &HybridKemOffer {
+        &self.offer
+    }
+
+    pub fn accept(&self, answer: &HybridKemAnswer, key: &MixAesKey) -> BuckyResult<MixAesKey> {
+        if answer.sequence != self.sequence || key.mix_key != self.mix_key {
+            return Err(BuckyError::new(BuckyErrorCode::InvalidInput, "hybrid kem answer not match offer"));
+        }
+        let remote_public = x25519_public_from(&answer.x25519_public)?;
+        let x25519_shared = self.x25519_secret.diffie_hellman(&remote_public);
+        let ciphertext = kyber768::Ciphertext::from_bytes(&answer.kyber_ciphertext)
+            .map_err(|_| BuckyError::new(BuckyErrorCode::InvalidData, "invalid kyber ciphertext"))?;
+        let kyber_shared = kyber768::decapsulate(&ciphertext, &self.kyber_secret);
+        Ok(derive_key(key, x25519_shared.as_bytes(), kyber_shared.as_bytes()))
+    }
+}
+
+// 发起方处理offer，返回回复给对端的answer和派生的新key
+pub(crate) fn answer_offer(offer: &HybridKemOffer, key: &MixAesKey) -> BuckyResult<(HybridKemAnswer, MixAesKey)> {
+    let remote_public = x25519_public_from(&offer.x25519_public)?;
+    let x25519_secret = StaticSecret::new(rand::rngs::OsRng);
+    let x25519_public = X25519PublicKey::from(&x25519_secret);
+    let x25519_shared = x25519_secret.diffie_hellman(&remote_public);
+
+    let kyber_public = kyber768::PublicKey::from_bytes(&offer.kyber_public)
+        .map_err(|_| BuckyError::new(BuckyErrorCode::InvalidData, "invalid kyber public key"))?;
+    let (kyber_shared, ciphertext) = kyber768::encapsulate(&kyber_public);
+
+    let answer = HybridKemAnswer {
+        sequence: offer.sequence,
+        x25519_public: x25519_public.as_bytes().to_vec(),
+        kyber_ciphertext: ciphertext.as_bytes().to_vec()
+    };
+    Ok((answer, derive_key(key, x25519_shared.as_bytes(), kyber_shared.as_bytes())))
+}
+
+fn x25519_public_from(bytes: &[u8]) -> BuckyResult<X25519PublicKey> {
+    let bytes = <[u8; 32]>::try_from(bytes)
+        .map_err(|_| BuckyError::new(BuckyErrorCode::InvalidData, "invalid x25519 public key"))?;
+    Ok(X25519PublicKey::from(bytes))
+}
+
+fn derive_key(key: &MixAesKey, x25519_shared: &[u8], kyber_shared: &[u8]) -> MixAesKey {
+    let mut ikm = Vec::with_capacity(key.enc_key.len() + x25519_shared.len() + kyber_shared.len());
+    ikm.extend_from_slice(key.enc_key.as_slice());
+    ikm.extend_from_slice(x25519_shared);
+    ikm.extend_from_slice(kyber_shared);
+
+    let hk = Hkdf::<Sha256>::new(Some(key.mix_key.as_slice()), &ikm);
+    let mut okm = [0u8; 96];
+    hk.expand(HYBRID_KEM_INFO, &mut okm).unwrap();
+
+    let mut enc_key = [0u8; 48];
+    enc_key.copy_from_slice(&okm[..48]);
+    let mut mix_key = [0u8; 48];
+    mix_key.copy_from_slice(&okm[48..]);
+
+    let mut new_key = MixAesKey::new(AesKey::from(&enc_key), AesKey::from(&mix_key));
+    // 已经协商好的加密算法保持不变
+    new_key.cipher = key.cipher;
+    new_key.clock_skew = key.clock_skew;
+    new_key
+}
+
+#[test]
+fn hybrid_kem_derive() {
+    let key = MixAesKey::new(AesKey::random(), AesKey::random());
+    let responder = HybridKemResponder::new(TempSeq::from(1), &key);
+    let (answer, initiator_key) = answer_offer(responder.offer(), &key).unwrap();
+    let responder_key = responder.accept(&answer, &key).unwrap();
+
+    assert_eq!(initiator_key.enc_key, responder_key.enc_key);
+    assert_eq!(initiator_key.mix_key, responder_key.mix_key);
+    assert_ne!(initiator_key.mix_key, key.mix_key);
+
+    let other = MixAesKey::new(AesKey::random(), AesKey::random());
+    assert!(responder.accept(&answer, &other).is_err());
+}
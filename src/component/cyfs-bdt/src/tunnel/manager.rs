@@ -18,6 +18,9 @@ use crate::{
     stack::{Stack, WeakStack}
 };
 use super::container::{TunnelGuard, TunnelContainer, Config};
+use super::tunnel::{ProxyType, TunnelState};
+use super::mtu::PathMtuCache;
+use super::stats::*;
 
 struct TunnelKeeper {
     reserving: Option<Timestamp>, 
@@ -49,7 +52,10 @@ impl TunnelKeeper {
 
 struct TunnelManagerImpl {
     stack: WeakStack, 
-    entries: RwLock<BTreeMap<DeviceId, TunnelKeeper>>
+    entries: RwLock<BTreeMap<DeviceId, TunnelKeeper>>, 
+    // container回收之后保留统计记录，直到超出记录的时间范围
+    stats: RwLock<BTreeMap<DeviceId, TunnelStats>>, 
+    path_mtu: PathMtuCache
 }
 
 #[derive(Clone)]
@@ -57,9 +63,12 @@ pub struct TunnelManager(Arc<TunnelManagerImpl>);
 
 impl TunnelManager {
     pub fn new(stack: WeakStack) -> Self {
+        let mtu_cache_expire = Stack::from(&stack).config().tunnel.udp.mtu_cache_expire;
         let manager = Self(Arc::new(TunnelManagerImpl {
             stack, 
-            entries: RwLock::new(BTreeMap::new())
+            entries: RwLock::new(BTreeMap::new()), 
+            stats: RwLock::new(BTreeMap::new()), 
+            path_mtu: PathMtuCache::new(mtu_cache_expire)
         }));
 
         {
@@ -89,6 +98,13 @@ impl TunnelManager {
             info!("{} will remove tunnel for not used, channel={}", self, remote);
             entries.remove(&remote);
         }
+
+        let history = Stack::from(&self.0.stack).config().tunnel.stats_history as u64 * 60 * 1000 * 1000;
+        let mut stats = self.0.stats.write().unwrap();
+        stats.retain(|remote, stats| {
+            entries.contains_key(remote) 
+                || stats.last_active().map(|last| when < last + history).unwrap_or(false)
+        });
     }
 
     fn config_for(&self, _remote_const: &DeviceDesc) -> Config {
@@ -104,12 +120,19 @@ impl TunnelManager {
         if let Some(tunnel) = entries.get(&remote) {
             Ok(tunnel.get())
         } else {
-            let tunnel = TunnelGuard::new(TunnelContainer::new(self.0.stack.clone(), remote_const.clone(), self.config_for(remote_const)));
+            let config = self.config_for(remote_const);
+            let stats = self.0.stats.write().unwrap().entry(remote.clone())
+                .or_insert_with(|| TunnelStats::new(config.stats_history)).clone();
+            let tunnel = TunnelGuard::new(TunnelContainer::new(self.0.stack.clone(), remote_const.clone(), config, stats));
             entries.insert(remote, TunnelKeeper { reserving: None, tunnel: tunnel.clone() });
             Ok(tunnel)
         } 
     }
 
+    pub fn path_mtu(&self) -> &PathMtuCache {
+        &self.0.path_mtu
+    }
+
     pub(crate) fn container_of(&self, remote: &DeviceId) -> Option<TunnelGuard> {
         let entries = self.0.entries.read().unwrap();
         entries.get(&remote).map(|tunnel| {
@@ -117,6 +140,73 @@ impl TunnelManager {
         })
     }
 
+    pub fn stats_of(&self, remote: &DeviceId) -> Option<TunnelStats> {
+        self.0.stats.read().unwrap().get(remote).cloned()
+    }
+
+    pub fn all_stats(&self) -> Vec<(DeviceId, TunnelStats)> {
+        self.0.stats.read().unwrap().iter()
+            .map(|(remote, stats)| (remote.clone(), stats.clone()))
+            .collect()
+    }
+
+    // 导出所有remote的统计记录，csv在每行前加上remote列，json按remote分组
+    pub fn export_stats(&self, format: TunnelStatsFormat, since: Option<Timestamp>) -> String {
+        let stats = self.0.stats.read().unwrap();
+        match format {
+            TunnelStatsFormat::Csv => {
+                let mut csv = format!("remote,{}", TunnelStatsSample::csv_header());
+                for (remote, stats) in stats.iter() {
+                    for sample in stats.history(since) {
+                        csv.push_str(&format!("\n{},{}", remote, sample.to_csv_row()));
+                    }
+                }
+                csv
+            }, 
+            TunnelStatsFormat::Json => {
+                let all: BTreeMap<String, Vec<TunnelStatsSample>> = stats.iter()
+                    .map(|(remote, stats)| (remote.to_string(), stats.history(since)))
+                    .collect();
+                serde_json::to_string(&all).unwrap()
+            }
+        }
+    }
+
+    // 当前active的直连udp tunnel的远端endpoint，保存会话时使用
+    pub(crate) fn active_udp_endpoints(&self) -> Vec<(DeviceId, Endpoint)> {
+        let entries = self.0.entries.read().unwrap();
+        entries.iter().filter_map(|(remote, keeper)| {
+            keeper.tunnel.default_tunnel().ok()
+                .filter(|tunnel| tunnel.as_ref().remote().is_udp() && tunnel.as_ref().proxy() == ProxyType::None)
+                .map(|tunnel| (remote.clone(), *tunnel.as_ref().remote()))
+        }).collect()
+    }
+
+    // 处于dead状态的tunnel的远端，这些device的endpoint可能已经变化
+    pub fn dead_remotes(&self) -> Vec<DeviceId> {
+        let entries = self.0.entries.read().unwrap();
+        entries.iter().filter(|(_, keeper)| keeper.tunnel.state() == TunnelState::Dead).map(|(remote, _)| remote.clone()).collect()
+    }
+
+    // 远端的desc更新后调用，更新device cache；没有连通的tunnel重置后，下次建立时使用新的endpoint
+    pub fn update_remote_device(&self, remote: &Device) {
+        let stack = Stack::from(&self.0.stack);
+        let remote_id = remote.desc().device_id();
+        stack.device_cache().add(&remote_id, remote);
+
+        if let Some(tunnel) = self.container_of(&remote_id) {
+            match tunnel.state() {
+                TunnelState::Active(_) => {
+                    debug!("{} remote device updated, keep active tunnel, remote={}", self, remote_id);
+                }, 
+                _ => {
+                    info!("{} remote device updated, reset tunnel, remote={}, endpoints={:?}", self, remote_id, remote.connect_info().endpoints());
+                    tunnel.reset();
+                }
+            }
+        }
+    }
+
     pub fn reset(&self) {
         let entries = self.0.entries.read().unwrap();
         for (_, tunnel) in entries.iter() {
@@ -125,8 +215,13 @@ impl TunnelManager {
     }
 
     pub(crate) fn on_statistic(&self) -> String {
-        let tunnel_count = self.0.entries.read().unwrap().len();
-        format!("TunnelCount: {}", tunnel_count)
+        let entries = self.0.entries.read().unwrap();
+        let tunnel_count = entries.len();
+        let ciphers: Vec<String> = TunnelCipher::all().iter().map(|cipher| {
+            let count = entries.values().filter(|keeper| keeper.tunnel.cipher() == *cipher).count();
+            format!("{}: {}", cipher, count)
+        }).collect();
+        format!("TunnelCount: {}, TunnelCipher: {{{}}}", tunnel_count, ciphers.join(", "))
     }
 }
 
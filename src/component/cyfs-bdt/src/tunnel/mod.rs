@@ -4,9 +4,14 @@ pub mod tcp;
 mod container;
 mod builder;
 mod manager;
+mod hybrid_kem;
+mod mtu;
+mod stats;
 
 pub use container::Config;
 pub use builder::*;
 pub use container::*;
 pub use manager::*;
+pub use mtu::*;
+pub use stats::*;
 pub use tunnel::*;
\ No newline at end of file
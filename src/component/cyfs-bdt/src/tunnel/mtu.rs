@@ -0,0 +1,60 @@
+use std::{
+    time::Duration,
+    collections::BTreeMap,
+    sync::{Arc, RwLock}
+};
+use cyfs_base::*;
+use crate::types::*;
+
+// 探测用的udp包大小，从大到小依次尝试，第一个收到回复的作为路径mtu；
+// 最大值和默认的udp::MTU一致，1280是ipv6要求的最小mtu，再小的链路按不可用处理
+pub const MTU_PROBE_SIZES: [usize; 5] = [1472, 1432, 1400, 1360, 1280];
+
+struct PathMtu {
+    mtu: usize,
+    update_at: Timestamp
+}
+
+struct PathMtuCacheImpl {
+    expire: Duration,
+    entries: RwLock<BTreeMap<EndpointPair, PathMtu>>
+}
+
+// 按(local, remote)缓存udp路径的mtu，tunnel重建时直接使用，过期之后重新探测
+#[derive(Clone)]
+pub struct PathMtuCache(Arc<PathMtuCacheImpl>);
+
+impl PathMtuCache {
+    pub fn new(expire: Duration) -> Self {
+        Self(Arc::new(PathMtuCacheImpl {
+            expire,
+            entries: RwLock::new(BTreeMap::new())
+        }))
+    }
+
+    pub fn get(&self, local: &Endpoint, remote: &Endpoint) -> Option<usize> {
+        let entries = self.0.entries.read().unwrap();
+        entries.get(&EndpointPair::from((*local, *remote))).and_then(|path| {
+            if bucky_time_now() < path.update_at + self.0.expire.as_micros() as u64 {
+                Some(path.mtu)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn update(&self, local: &Endpoint, remote: &Endpoint, mtu: usize) {
+        let now = bucky_time_now();
+        let mut entries = self.0.entries.write().unwrap();
+        let expire = self.0.expire.as_micros() as u64;
+        entries.retain(|_, path| now < path.update_at + expire);
+        entries.insert(EndpointPair::from((*local, *remote)), PathMtu {
+            mtu,
+            update_at: now
+        });
+    }
+
+    pub fn remove(&self, local: &Endpoint, remote: &Endpoint) {
+        self.0.entries.write().unwrap().remove(&EndpointPair::from((*local, *remote)));
+    }
+}
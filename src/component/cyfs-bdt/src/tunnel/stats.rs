@@ -0,0 +1,185 @@
+use std::{
+    time::Duration,
+    collections::VecDeque,
+    sync::{Arc, Mutex}
+};
+use serde::Serialize;
+use cyfs_base::*;
+use crate::types::*;
+
+const MINUTE: u64 = 60 * 1000 * 1000;
+
+// 一分钟内的tunnel统计；rtt单位为微秒，没有采样时为0
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TunnelStatsSample {
+    // 这一分钟的开始时间
+    pub minute: Timestamp,
+    pub send_bytes: u64,
+    pub recv_bytes: u64,
+    pub send_packages: u64,
+    pub recv_packages: u64,
+    pub rtt_samples: u64,
+    pub rtt_avg: u64,
+    pub rtt_min: u64,
+    pub rtt_max: u64,
+    pub loss: u64,
+    pub resend: u64,
+    #[serde(skip)]
+    rtt_sum: u64,
+}
+
+impl TunnelStatsSample {
+    pub fn csv_header() -> &'static str {
+        "minute,send_bytes,recv_bytes,send_packages,recv_packages,rtt_samples,rtt_avg,rtt_min,rtt_max,loss,resend"
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!("{},{},{},{},{},{},{},{},{},{},{}",
+            self.minute,
+            self.send_bytes,
+            self.recv_bytes,
+            self.send_packages,
+            self.recv_packages,
+            self.rtt_samples,
+            self.rtt_avg,
+            self.rtt_min,
+            self.rtt_max,
+            self.loss,
+            self.resend)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TunnelStatsFormat {
+    Csv,
+    Json,
+}
+
+struct StatsImpl {
+    capacity: usize,
+    current: TunnelStatsSample,
+    history: VecDeque<TunnelStatsSample>,
+}
+
+impl StatsImpl {
+    fn roll(&mut self, minute: Timestamp) {
+        if minute <= self.current.minute {
+            return;
+        }
+        // 没有收发的分钟不占用历史记录
+        let current = std::mem::replace(&mut self.current, TunnelStatsSample {
+            minute,
+            ..Default::default()
+        });
+        if current.minute != 0 {
+            self.history.push_back(current);
+        }
+        while self.history.len() >= self.capacity.max(1) {
+            self.history.pop_front();
+        }
+    }
+}
+
+// 按分钟记录的tunnel统计，保留最近capacity分钟；
+// 由TunnelManager按remote持有，container被回收之后历史记录还在，方便事后排查
+#[derive(Clone)]
+pub struct TunnelStats(Arc<Mutex<StatsImpl>>);
+
+impl TunnelStats {
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(StatsImpl {
+            capacity,
+            current: TunnelStatsSample::default(),
+            history: VecDeque::new(),
+        })))
+    }
+
+    fn update<F: FnOnce(&mut TunnelStatsSample)>(&self, f: F) {
+        let minute = bucky_time_now() / MINUTE * MINUTE;
+        let mut stats = self.0.lock().unwrap();
+        stats.roll(minute);
+        f(&mut stats.current);
+    }
+
+    pub fn on_send(&self, bytes: usize) {
+        self.update(|sample| {
+            sample.send_bytes += bytes as u64;
+            sample.send_packages += 1;
+        });
+    }
+
+    pub fn on_recv(&self, bytes: usize) {
+        self.update(|sample| {
+            sample.recv_bytes += bytes as u64;
+            sample.recv_packages += 1;
+        });
+    }
+
+    pub fn on_rtt(&self, rtt: Duration) {
+        let rtt = rtt.as_micros() as u64;
+        self.update(|sample| {
+            if sample.rtt_samples == 0 || rtt < sample.rtt_min {
+                sample.rtt_min = rtt;
+            }
+            if rtt > sample.rtt_max {
+                sample.rtt_max = rtt;
+            }
+            sample.rtt_samples += 1;
+            sample.rtt_sum += rtt;
+            sample.rtt_avg = sample.rtt_sum / sample.rtt_samples;
+        });
+    }
+
+    pub fn on_loss(&self, count: u64) {
+        self.update(|sample| sample.loss += count);
+    }
+
+    pub fn on_resend(&self, count: u64) {
+        self.update(|sample| sample.resend += count);
+    }
+
+    // 最近一分钟的平均rtt，当前这一分钟还没有采样时使用上一条记录
+    pub fn rtt(&self) -> Option<Duration> {
+        let stats = self.0.lock().unwrap();
+        std::iter::once(&stats.current)
+            .chain(stats.history.iter().rev())
+            .find(|sample| sample.rtt_samples > 0)
+            .map(|sample| Duration::from_micros(sample.rtt_avg))
+    }
+
+    // 最后一次有收发的时间，没有记录时返回None
+    pub fn last_active(&self) -> Option<Timestamp> {
+        let stats = self.0.lock().unwrap();
+        if stats.current.minute != 0 {
+            Some(stats.current.minute)
+        } else {
+            None
+        }
+    }
+
+    // 按时间顺序返回since之后的记录，包括当前还没有结束的这一分钟
+    pub fn history(&self, since: Option<Timestamp>) -> Vec<TunnelStatsSample> {
+        let since = since.unwrap_or(0) / MINUTE * MINUTE;
+        let stats = self.0.lock().unwrap();
+        stats.history.iter()
+            .chain(std::iter::once(&stats.current))
+            .filter(|sample| sample.minute != 0 && sample.minute >= since)
+            .cloned()
+            .collect()
+    }
+
+    pub fn export(&self, format: TunnelStatsFormat, since: Option<Timestamp>) -> String {
+        let history = self.history(since);
+        match format {
+            TunnelStatsFormat::Csv => {
+                let mut csv = String::from(TunnelStatsSample::csv_header());
+                for sample in history {
+                    csv.push('\n');
+                    csv.push_str(&sample.to_csv_row());
+                }
+                csv
+            },
+            TunnelStatsFormat::Json => serde_json::to_string(&history).unwrap()
+        }
+    }
+}
@@ -66,6 +66,8 @@ struct ConnectingState {
 enum PackageElem {
     Package(DynamicPackage), 
     RawData(Vec<u8>),  
+    // 用当前key发出package之后切换发送key，之后的box都用新key加密
+    SwitchKey(DynamicPackage, MixAesKey), 
 }
 
 enum CommandElem {
@@ -570,7 +572,9 @@ impl Tunnel {
     async fn connect_inner(&self, owner: TunnelContainer, interface: Option<tcp::Interface>) -> Result<(tcp::PackageInterface, Timestamp, TempSeq), BuckyError> {
         info!("{} connect interface", self);
         let stack = owner.stack();
-        let key_stub = stack.keystore().create_key(owner.remote_const(), true);
+        let mut key_stub = stack.keystore().create_key(owner.remote_const(), true);
+        let cipher = owner.propose_cipher(&mut key_stub.key);
+        let key = key_stub.key.clone();
         let interface = if let Some(interface) = interface {
             Ok(interface)
         } else {
@@ -580,6 +584,7 @@ impl Tunnel {
             owner.remote().clone(), 
             owner.remote_const().clone(), 
             key_stub.key, 
+            stack.keystore(), 
             owner.config().tcp.connect_timeout).await
         }?;
         let syn_seq = owner.generate_sequence();
@@ -589,9 +594,20 @@ impl Tunnel {
             to_device_id: owner.remote().clone(),
             sequence: syn_seq.clone(),
             from_device_desc: stack.sn_client().ping().default_local(), 
-            send_time: bucky_time_now()
+            send_time: bucky_time_now(), 
+            cipher
         };
-        let resp_box = interface.confirm_connect(&stack, vec![DynamicPackage::from(syn_tunnel)], owner.config().tcp.confirm_timeout).await?;
+        let mut packages = vec![];
+        if let keystore::EncryptedKey::Unconfirmed(encrypted) = key_stub.encrypted {
+            // 新建的interface由这里构造exchange，带上本端支持的能力
+            if interface.key().mix_key == key.mix_key {
+                let mut exchange = Exchange::from((&syn_tunnel, encrypted, key.mix_key.clone()));
+                exchange.capabilities = owner.exchange_capabilities();
+                packages.push(DynamicPackage::from(exchange));
+            }
+        }
+        packages.push(DynamicPackage::from(syn_tunnel));
+        let resp_box = interface.confirm_connect(&stack, packages, owner.config().tcp.confirm_timeout).await?;
         
         if resp_box.packages().len() != 1 {
             Err(BuckyError::new(BuckyErrorCode::InvalidData, "should response AckTunnel"))
@@ -599,6 +615,7 @@ impl Tunnel {
             Err(BuckyError::new(BuckyErrorCode::InvalidData, "should response AckTunnel"))
         } else {
             let ack_tunnel: &AckTunnel = resp_box.packages()[0].as_ref();
+            owner.on_remote_cipher(ack_tunnel.cipher, &key);
             let _ = owner.on_package(ack_tunnel, None);
             if ack_tunnel.result == ACK_TUNNEL_RESULT_OK {
                 let remote_timestamp = ack_tunnel.to_device_desc.body().as_ref().unwrap().update_time();
@@ -616,7 +633,8 @@ impl Tunnel {
         let remote = stack.device_cache().get_inner(owner.remote()).ok_or_else(| | BuckyError::new(BuckyErrorCode::NotFound, "device not cached"))?;
         let sn_id = remote.connect_info().sn_list().get(0).ok_or_else(| | BuckyError::new(BuckyErrorCode::NotFound, "device no sn"))?;
 
-        let key_stub = stack.keystore().create_key(owner.remote_const(), true);
+        let mut key_stub = stack.keystore().create_key(owner.remote_const(), true);
+        let cipher = owner.propose_cipher(&mut key_stub.key);
         let mut syn_box = PackageBox::encrypt_box(owner.remote().clone(), key_stub.key.clone());
         let syn_tunnel = SynTunnel {
             protocol_version: owner.protocol_version(), 
@@ -624,7 +642,8 @@ impl Tunnel {
             to_device_id: owner.remote().clone(),
             sequence: owner.generate_sequence(),
             from_device_desc: stack.sn_client().ping().default_local(), 
-            send_time: bucky_time_now()
+            send_time: bucky_time_now(), 
+            cipher
         };
         if let keystore::EncryptedKey::Unconfirmed(encrypted) = key_stub.encrypted {
             let mut exchg = Exchange::from((&syn_tunnel, encrypted, key_stub.key.mix_key));
@@ -665,6 +684,36 @@ impl Tunnel {
         Ok(())
     }
 
+    fn send_package_then_switch_key(&self, package: DynamicPackage, key: MixAesKey) -> BuckyResult<()> {
+        let signal_writer = match &*self.0.state.lock().unwrap() {
+            TunnelState::Active(active) => Ok(active.signal_writer.clone()), 
+            _ => Err(BuckyError::new(BuckyErrorCode::ErrorState, "tunnel not active"))
+        }?;
+        // 丢弃时两端都不切换，不会导致key不一致
+        signal_writer.try_send(SignalElem::Package(PackageElem::SwitchKey(package, key)))
+            .map_err(|_| BuckyError::new(BuckyErrorCode::Pending, "full"))
+    }
+
+    // tcp上的混合密钥交换，两个方向各自切换：
+    // 发起方回复answer之后切换发送key；响应方收到answer切换接收key，把answer原样发回之后切换发送key；
+    // 发起方收到发回的answer切换接收key
+    fn on_hybrid_kem_offer(&self, owner: &TunnelContainer, interface: &PackageInterface, offer: &HybridKemOffer) -> BuckyResult<()> {
+        if let Some((answer, new_key)) = owner.on_hybrid_kem_offer(offer, &interface.recv_key())? {
+            self.send_package_then_switch_key(DynamicPackage::from(answer), new_key)?;
+        }
+        Ok(())
+    }
+
+    fn on_hybrid_kem_answer(&self, owner: &TunnelContainer, interface: &PackageInterface, answer: &HybridKemAnswer) -> BuckyResult<()> {
+        if let Some(new_key) = owner.on_hybrid_kem_echo(answer) {
+            interface.update_recv_key(new_key);
+            return Ok(());
+        }
+        let new_key = owner.on_hybrid_kem_answer(answer, &interface.recv_key())?;
+        interface.update_recv_key(new_key.clone());
+        self.send_package_then_switch_key(DynamicPackage::from(answer.clone()), new_key)
+    }
+
     fn on_interface_error(&self, from: &PackageInterface, err: &BuckyError) {
         error!("{} interface error {} from {}", self, err, from);
 
@@ -743,7 +792,12 @@ impl Tunnel {
                     pkg: PackageElem) -> BuckyResult<()> {
                     match pkg {
                         PackageElem::Package(package) => interface.send_package(send_buf, package, false).await, 
-                        PackageElem::RawData(data) => interface.send_raw_data(data).await
+                        PackageElem::RawData(data) => interface.send_raw_data(data).await, 
+                        PackageElem::SwitchKey(package, key) => {
+                            interface.send_package(send_buf, package, false).await?;
+                            interface.update_send_key(key);
+                            Ok(())
+                        }
                     }
                 }
 
@@ -849,17 +903,23 @@ impl Tunnel {
                 Ok(recv_box) => {
                     tunnel.0.last_active.store(bucky_time_now(), Ordering::SeqCst);
                     match recv_box {
-                        RecvBox::Package(package_box) => {
+                        RecvBox::Package(mut package_box) => {
                             let stack = owner.stack();
                             if package_box.has_exchange() {
-                                // let exchange: &Exchange = package_box.packages()[0].as_ref();
-                                stack.keystore().add_key(package_box.key(), package_box.remote());
+                                if stack.on_exchange(&mut package_box).is_err() {
+                                    continue;
+                                }
                             }
                             if let Err(err) = package_box.packages().iter().try_for_each(|pkg| {
                                 if pkg.cmd_code() == PackageCmdCode::PingTunnel {
                                     tunnel.on_package(AsRef::<PingTunnel>::as_ref(pkg), None).map(|_| ())
                                 } else if pkg.cmd_code() == PackageCmdCode::PingTunnelResp {
                                     tunnel.on_package(AsRef::<PingTunnelResp>::as_ref(pkg), None).map(|_| ())
+                                } else if pkg.cmd_code() == PackageCmdCode::HybridKemOffer {
+                                    tunnel.on_hybrid_kem_offer(&owner, &interface, AsRef::<HybridKemOffer>::as_ref(pkg))
+                                } else if pkg.cmd_code() == PackageCmdCode::HybridKemAnswer {
+                                    // 接收key要在读下一个box之前切换，所以在recv loop里同步处理
+                                    tunnel.on_hybrid_kem_answer(&owner, &interface, AsRef::<HybridKemAnswer>::as_ref(pkg))
                                 } else {
                                     downcast_session_handle!(pkg, |pkg| owner.on_package(pkg, None)).map(|_| ())
                                 }
@@ -966,6 +1026,7 @@ impl Tunnel {
                                         package_id: 0,
                                         send_time: now,
                                         recv_data: 0,
+                                        padding: None,
                                     };
                                     let _ = tunnel::Tunnel::send_package(&tunnel, DynamicPackage::from(ping));
                                 }
@@ -1235,8 +1296,18 @@ impl OnTcpInterface for Tunnel {
                     }
                 }
             };
+            let ret = match &owner {
+                Some(owner) if ret == ACK_TUNNEL_RESULT_OK && owner.check_hybrid_kem_policy(&first_box).is_err() => ACK_TUNNEL_RESULT_REFUSED, 
+                _ => ret
+            };
             if let Some(owner) = owner {
                 owner.on_package(syn_tunnel, None)?;
+                let cipher = if ret == ACK_TUNNEL_RESULT_OK {
+                    owner.on_remote_cipher(syn_tunnel.cipher, first_box.key());
+                    syn_tunnel.cipher
+                } else {
+                    None
+                };
                 let ack_tunnel = AckTunnel {
                     protocol_version: owner.protocol_version(), 
                     stack_version: owner.stack_version(),  
@@ -1244,14 +1315,25 @@ impl OnTcpInterface for Tunnel {
                     result: ret,
                     send_time: bucky_time_now(),
                     mtu: udp::MTU as u16,
-                    to_device_desc: owner.stack().sn_client().ping().default_local()
+                    to_device_desc: owner.stack().sn_client().ping().default_local(), 
+                    cipher
+                };
+                let offer = if ret == ACK_TUNNEL_RESULT_OK {
+                    owner.hybrid_kem_offer(&first_box, syn_tunnel.sequence)
+                } else {
+                    None
                 };
                 let tunnel = self.clone();
                 task::spawn(async move {
                     let syn_seq = ack_tunnel.sequence;
                     let confirm_ret = interface.confirm_accept(vec![DynamicPackage::from(ack_tunnel)]).await;
                     if ret == ACK_TUNNEL_RESULT_OK {
+                        let confirmed = confirm_ret.is_ok();
                         tunnel.active_with_interface(confirm_ret.map(|_| (interface.into(), remote_timestamp, syn_seq)));
+                        // 在AckTunnel之后用旧key发出offer
+                        if let Some(offer) = offer.filter(|_| confirmed) {
+                            let _ = tunnel::Tunnel::send_package(&tunnel, DynamicPackage::from(offer));
+                        }
                     } else {
                         // do nothing
                     }
@@ -1,8 +1,8 @@
 use log::*;
 use std::{
     time::Duration, 
-    sync::RwLock, 
-    sync::atomic::{AtomicI32, AtomicU64, Ordering}
+    sync::{RwLock, Mutex}, 
+    sync::atomic::{AtomicI32, AtomicU64, AtomicUsize, Ordering}
 };
 use async_std::{
     sync::{Arc}, 
@@ -18,6 +18,7 @@ use crate::{
 };
 use super::{
     tunnel::{self, DynamicTunnel, TunnelOwner, ProxyType}, 
+    mtu::MTU_PROBE_SIZES, 
     TunnelContainer
 };
 
@@ -70,7 +71,17 @@ pub struct Config {
     pub holepunch_interval: Duration, 
     pub connect_timeout: Duration, 
     pub ping_interval: Duration, 
-    pub ping_timeout: Duration
+    pub ping_timeout: Duration, 
+    // 对端在exchange中声明支持时探测路径mtu，结果按(local, remote)缓存mtu_cache_expire
+    pub mtu_probe: bool, 
+    pub mtu_probe_timeout: Duration, 
+    pub mtu_cache_expire: Duration
+}
+
+struct MtuProbeState {
+    started: bool, 
+    // 等待回复的探测包id
+    pending: Option<(u32, StateWaiter)>
 }
 
 struct TunnelImpl {
@@ -80,7 +91,8 @@ struct TunnelImpl {
     state: RwLock<TunnelState>, 
     keeper_count: AtomicI32, 
     last_active: AtomicU64,
-    mtu: usize,
+    mtu: AtomicUsize,
+    mtu_probe: Mutex<MtuProbeState>, 
 }
 
 #[derive(Clone)]
@@ -100,6 +112,7 @@ impl Tunnel {
         remote: Endpoint, 
         proxy: ProxyType) -> Self {
         let local = interface.local();
+        let mtu = container.stack().tunnel_manager().path_mtu().get(&local, &remote).unwrap_or(MTU);
         let state = TunnelState::Connecting(ConnectingState {
             container: container.clone(), 
             owner: owner.clone_as_tunnel_owner(), 
@@ -107,7 +120,11 @@ impl Tunnel {
             waiter: StateWaiter::new()
         });
         let tunnel = Self(Arc::new(TunnelImpl {
-            mtu: MTU,
+            mtu: AtomicUsize::new(mtu),
+            mtu_probe: Mutex::new(MtuProbeState {
+                started: false, 
+                pending: None
+            }),
             local, 
             remote, 
             proxy, 
@@ -284,6 +301,141 @@ impl Tunnel {
         udp::MTU
     }
 
+    // 对端支持时开始探测路径mtu，每个tunnel只探测一次；缓存中有结果的话直接使用
+    pub(super) fn start_mtu_probe(&self) {
+        let container = match self.owner() {
+            Some(container) => container, 
+            None => return
+        };
+        let config = container.config().udp.clone();
+        if !config.mtu_probe {
+            return;
+        }
+        {
+            let mut probe = self.0.mtu_probe.lock().unwrap();
+            if probe.started {
+                return;
+            }
+            probe.started = true;
+        }
+
+        let local = *tunnel::Tunnel::local(self);
+        let remote = *tunnel::Tunnel::remote(self);
+        let path_mtu = container.stack().tunnel_manager().path_mtu().clone();
+        if let Some(mtu) = path_mtu.get(&local, &remote) {
+            debug!("{} use cached mtu {}", self, mtu);
+            self.0.mtu.store(mtu, Ordering::SeqCst);
+            return;
+        }
+
+        let tunnel = self.clone();
+        task::spawn(async move {
+            match tunnel.probe_mtu(config.mtu_probe_timeout).await {
+                Some(mtu) => {
+                    info!("{} path mtu probed {}", tunnel, mtu);
+                    path_mtu.update(&local, &remote, mtu);
+                    tunnel.0.mtu.store(mtu, Ordering::SeqCst);
+                }, 
+                None => {
+                    info!("{} path mtu probe got no response, keep mtu {}", tunnel, tunnel::Tunnel::mtu(&tunnel));
+                }
+            }
+        });
+    }
+
+    async fn probe_mtu(&self, timeout: Duration) -> Option<usize> {
+        for size in MTU_PROBE_SIZES.iter() {
+            // 每个大小发两次，避免偶尔的丢包把mtu探测小了
+            for _ in 0..2 {
+                match self.send_mtu_probe(*size, timeout).await {
+                    Ok(true) => return Some(*size), 
+                    Ok(false) => continue, 
+                    Err(err) => {
+                        debug!("{} stop mtu probe for {}", self, err);
+                        return None;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    async fn send_mtu_probe(&self, size: usize, timeout: Duration) -> BuckyResult<bool> {
+        let (container, interface, key) = {
+            if let TunnelState::Active(active_state) = &*self.0.state.read().unwrap() {
+                Ok((active_state.container.clone(), active_state.interface.clone(), active_state.key.clone()))
+            } else {
+                Err(BuckyError::new(BuckyErrorCode::ErrorState, "tunnel not active"))
+            }
+        }?;
+
+        let package_id = loop {
+            let id = rand::random::<u32>();
+            if id != 0 {
+                break id;
+            }
+        };
+
+        // 加密之后的长度不好直接算，先按空的padding编码一次，再逐步修正到不超过size
+        let mut buf = vec![0u8; udp::MTU_LARGE];
+        let mut padding_len = 0;
+        let mut encoded = 0;
+        for _ in 0..4 {
+            let ping = PingTunnel {
+                package_id, 
+                send_time: bucky_time_now(), 
+                recv_data: 0, 
+                padding: Some(vec![0u8; padding_len]),
+            };
+            let package_box = PackageBox::from_package(container.remote().clone(), key.clone(), DynamicPackage::from(ping));
+            let mut context = PackageBoxEncodeContext::default();
+            context.set_ignore_exchange(ProxyType::None != self.0.proxy);
+            let buf_len = buf.len();
+            encoded = buf_len - package_box.raw_encode_with_context(&mut buf[..], &mut context, &None)?.len();
+            if encoded > size {
+                padding_len = padding_len.saturating_sub(encoded - size);
+            } else if encoded + 16 <= size {
+                padding_len += size - encoded;
+            } else {
+                break;
+            }
+        }
+        if encoded > size {
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, "probe package too large"));
+        }
+
+        let waiter = {
+            let mut probe = self.0.mtu_probe.lock().unwrap();
+            let mut waiters = StateWaiter::new();
+            let waiter = waiters.new_waiter();
+            probe.pending = Some((package_id, waiters));
+            waiter
+        };
+        trace!("{} send mtu probe {} bytes", self, encoded);
+        interface.send_buf_to(&buf[..encoded], tunnel::Tunnel::remote(self))?;
+        let acked = future::timeout(timeout, StateWaiter::wait(waiter, || ())).await.is_ok();
+
+        let mut probe = self.0.mtu_probe.lock().unwrap();
+        if probe.pending.as_ref().map(|(id, _)| *id == package_id).unwrap_or(false) {
+            probe.pending = None;
+        }
+        Ok(acked)
+    }
+
+    fn on_mtu_probe_resp(&self, package_id: u32) {
+        let waiters = {
+            let mut probe = self.0.mtu_probe.lock().unwrap();
+            if probe.pending.as_ref().map(|(id, _)| *id == package_id).unwrap_or(false) {
+                probe.pending.take().map(|(_, waiters)| waiters)
+            } else {
+                None
+            }
+        };
+        if let Some(waiters) = waiters {
+            waiters.wake();
+        }
+    }
+
     pub(super) fn raw_data_header_len_impl() -> usize {
         KeyMixHash::raw_bytes().unwrap()
     }
@@ -306,7 +458,7 @@ impl Tunnel {
 #[async_trait]
 impl tunnel::Tunnel for Tunnel {
     fn mtu(&self) -> usize {
-        self.0.mtu
+        self.0.mtu.load(Ordering::SeqCst)
     }
 
     fn ptr_eq(&self, other: &tunnel::DynamicTunnel) -> bool {
@@ -342,18 +494,20 @@ impl tunnel::Tunnel for Tunnel {
     }
 
     fn send_raw_data(&self, data: &mut [u8]) -> Result<usize, BuckyError> {
-        let (key, interface) = {
+        let (container, key, interface) = {
             let state = &*self.0.state.read().unwrap();
             match state {
                 TunnelState::Connecting(_) => Err(BuckyError::new(BuckyErrorCode::ErrorState, "tunnel not active")), 
-                TunnelState::Active(active) => Ok((active.key.clone(), active.interface.clone())), 
+                TunnelState::Active(active) => Ok((active.container.clone(), active.key.clone(), active.interface.clone())), 
                 TunnelState::Dead => Err(BuckyError::new(BuckyErrorCode::ErrorState, "tunnel dead"))
             }
         }?;
 
         assert_eq!(data.len() > Self::raw_data_header_len_impl(), true);
         
-        interface.send_raw_data_to(&key, data, tunnel::Tunnel::remote(self))
+        let sent_len = interface.send_raw_data_to(&key, data, tunnel::Tunnel::remote(self))?;
+        container.stats().on_send(sent_len);
+        Ok(sent_len)
     }
 
     fn send_package(&self, package: DynamicPackage) -> Result<usize, BuckyError> {
@@ -368,6 +522,7 @@ impl tunnel::Tunnel for Tunnel {
         let mut context = PackageBoxEncodeContext::default();
         context.set_ignore_exchange(ProxyType::None != self.0.proxy);
         let sent_len = interface.send_box_to(&mut context, &package_box, tunnel::Tunnel::remote(self))?;
+        tunnel_container.stats().on_send(sent_len);
         Ok(sent_len)
     }
 
@@ -414,6 +569,7 @@ impl tunnel::Tunnel for Tunnel {
                                         package_id: 0,
                                         send_time: now,
                                         recv_data: 0,
+                                        padding: None,
                                     };
                                     let _ = tunnel::Tunnel::send_package(&tunnel, DynamicPackage::from(ping));
                                 }
@@ -487,6 +643,11 @@ impl tunnel::Tunnel for Tunnel {
 
 impl OnUdpPackageBox for Tunnel {
     fn on_udp_package_box(&self, udp_box: udp::UdpPackageBox) -> Result<(), BuckyError> {
+        if let Some(container) = self.owner() {
+            if let Some(new_key) = container.hybrid_kem_confirmed(udp_box.as_ref().key()) {
+                let _ = self.active(&new_key, true, None);
+            }
+        }
         for p in udp_box.as_ref().packages_no_exchange() {
             match downcast_tunnel_handle!(p, |p| self.on_package(p, udp_box.as_ref()))? {
                 OnPackageResult::Break => break, 
@@ -500,7 +661,11 @@ impl OnUdpPackageBox for Tunnel {
 
 impl OnPackage<SynTunnel, &PackageBox> for Tunnel {
     fn on_package(&self, syn_tunnel: &SynTunnel, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
+        if let Some(container) = self.owner() {
+            container.check_hybrid_kem_policy(in_box)?;
+        }
         let container = self.active_by_package(in_box, Some(syn_tunnel.from_device_desc.body().as_ref().unwrap().update_time()))?;
+        container.on_remote_cipher(syn_tunnel.cipher, in_box.key());
         // TODO: 考虑合并ack 和 session data
         // 回复ack tunnel
         let ack = AckTunnel {
@@ -510,7 +675,8 @@ impl OnPackage<SynTunnel, &PackageBox> for Tunnel {
             result: 0,
             send_time: 0,
             mtu: udp::MTU as u16,
-            to_device_desc: container.stack().sn_client().ping().default_local()
+            to_device_desc: container.stack().sn_client().ping().default_local(), 
+            cipher: syn_tunnel.cipher
         };
 
         let mut package_box = PackageBox::encrypt_box(
@@ -518,6 +684,20 @@ impl OnPackage<SynTunnel, &PackageBox> for Tunnel {
             in_box.key().clone());
         package_box.append(vec![DynamicPackage::from(ack)]);
         let _ = self.send_box(&package_box);
+        // kyber公钥比较大，offer单独发一个box
+        if let Some(offer) = container.hybrid_kem_offer(in_box, syn_tunnel.sequence) {
+            let offer_box = PackageBox::from_package(
+                container.remote().clone(), 
+                in_box.key().clone(), 
+                DynamicPackage::from(offer));
+            let _ = self.send_box(&offer_box);
+        }
+        if in_box.has_exchange() {
+            let exchange: &Exchange = in_box.packages()[0].as_ref();
+            if exchange.has_capability(EXCHANGE_CAPABILITY_MTU_PROBE) {
+                self.start_mtu_probe();
+            }
+        }
          // 传回给 container 处理
          container.on_package(syn_tunnel, None)
     }
@@ -526,6 +706,7 @@ impl OnPackage<SynTunnel, &PackageBox> for Tunnel {
 impl OnPackage<AckTunnel, &PackageBox> for Tunnel {
     fn on_package(&self, pkg: &AckTunnel, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
         let container = self.active_by_package(in_box, Some(pkg.to_device_desc.body().as_ref().unwrap().update_time()))?;
+        container.on_remote_cipher(pkg.cipher, in_box.key());
         // 传回给 container 处理
         container.on_package(pkg, None)
     }
@@ -548,14 +729,54 @@ impl OnPackage<PingTunnel, &PackageBox> for Tunnel {
             recv_data: 0,
         };
         let _ = tunnel::Tunnel::send_package(self, DynamicPackage::from(ping_resp));
+        // 收到对端的探测包说明对端也支持，反方向的路径单独探测
+        if ping.padding.is_some() {
+            self.start_mtu_probe();
+        }
         Ok(OnPackageResult::Handled)
     }
 }
 
 impl OnPackage<PingTunnelResp, &PackageBox> for Tunnel {
-    fn on_package(&self, _: &PingTunnelResp, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
+    fn on_package(&self, resp: &PingTunnelResp, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
         let _ = self.active_by_package(in_box, None)?;
-        // do nothing
+        if resp.ack_package_id != 0 {
+            self.on_mtu_probe_resp(resp.ack_package_id);
+        }
+        Ok(OnPackageResult::Handled)
+    }
+}
+
+impl OnPackage<HybridKemOffer, &PackageBox> for Tunnel {
+    fn on_package(&self, offer: &HybridKemOffer, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
+        let container = self.active_by_package(in_box, None)?;
+        if let Some((answer, _)) = container.on_hybrid_kem_offer(offer, in_box.key())? {
+            let answer_box = PackageBox::from_package(
+                container.remote().clone(), 
+                in_box.key().clone(), 
+                DynamicPackage::from(answer));
+            let _ = self.send_box(&answer_box);
+        }
+        Ok(OnPackageResult::Handled)
+    }
+}
+
+impl OnPackage<HybridKemAnswer, &PackageBox> for Tunnel {
+    fn on_package(&self, answer: &HybridKemAnswer, in_box: &PackageBox) -> Result<OnPackageResult, BuckyError> {
+        let container = self.active_by_package(in_box, None)?;
+        let new_key = container.on_hybrid_kem_answer(answer, in_box.key())?;
+        let _ = self.active(&new_key, true, None);
+        // 用新key发一个ping，对端收到之后切换
+        let ping = PingTunnel {
+            package_id: 0,
+            send_time: bucky_time_now(),
+            recv_data: 0,
+            padding: None,
+        };
+        let _ = self.send_box(&PackageBox::from_package(
+            container.remote().clone(), 
+            new_key, 
+            DynamicPackage::from(ping)));
         Ok(OnPackageResult::Handled)
     }
 }
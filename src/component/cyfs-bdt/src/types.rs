@@ -3,6 +3,7 @@ use cyfs_base::*;
 use futures::future::{AbortHandle, AbortRegistration, Abortable};
 use rand::Rng;
 use std::fmt;
+use aes_gcm::aead::{AeadInPlace, NewAead, generic_array::GenericArray};
 use std::{
     hash::{Hash, Hasher},
     collections::LinkedList,
@@ -12,22 +13,241 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH}
 };
 
+// tunnel上加密package box使用的对称加密算法
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TunnelCipher {
+    // 兼容旧版本的 aes-256-cbc，所有版本都支持
+    AesCbc = 0, 
+    AesGcm = 1, 
+    // 没有aes指令集的设备上(比如很多arm的ood)比aes快很多
+    ChaCha20Poly1305 = 2, 
+}
+
+const AEAD_NONCE_LEN: usize = 12;
+const AEAD_TAG_LEN: usize = 16;
+
+impl TunnelCipher {
+    pub fn all() -> &'static [TunnelCipher] {
+        &[Self::AesCbc, Self::AesGcm, Self::ChaCha20Poly1305]
+    }
+
+    // 根据当前cpu是否支持aes指令集选择更快的算法
+    pub fn preferred() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if std::is_x86_feature_detected!("aes") {
+                return Self::AesGcm;
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("aes") {
+                return Self::AesGcm;
+            }
+        }
+        Self::ChaCha20Poly1305
+    }
+
+    pub fn is_aead(&self) -> bool {
+        *self != Self::AesCbc
+    }
+
+    // 加密之后的最大长度
+    pub fn padded_len(&self, in_len: usize) -> usize {
+        match self {
+            Self::AesCbc => AesKey::padded_len(in_len), 
+            _ => AEAD_NONCE_LEN + in_len + AEAD_TAG_LEN
+        }
+    }
+
+    // 任意算法加密之后的最大长度，对端可能用和本端不同的算法
+    pub fn max_padded_len(in_len: usize) -> usize {
+        Self::all().iter().map(|cipher| cipher.padded_len(in_len)).max().unwrap()
+    }
+
+    // aead的输出格式为 nonce + ciphertext + tag，使用aes key的前32字节作为密钥
+    pub fn inplace_encrypt(&self, key: &AesKey, inout: &mut [u8], in_len: usize) -> BuckyResult<usize> {
+        match self {
+            Self::AesCbc => key.inplace_encrypt(inout, in_len), 
+            Self::AesGcm => Self::aead_encrypt(aes_gcm::Aes256Gcm::new(GenericArray::from_slice(&key.as_slice()[..32])), inout, in_len), 
+            Self::ChaCha20Poly1305 => Self::aead_encrypt(chacha20poly1305::ChaCha20Poly1305::new(GenericArray::from_slice(&key.as_slice()[..32])), inout, in_len), 
+        }
+    }
+
+    pub fn inplace_decrypt(&self, key: &AesKey, inout: &mut [u8], in_len: usize) -> BuckyResult<usize> {
+        match self {
+            Self::AesCbc => key.inplace_decrypt(inout, in_len), 
+            Self::AesGcm => Self::aead_decrypt(aes_gcm::Aes256Gcm::new(GenericArray::from_slice(&key.as_slice()[..32])), inout, in_len), 
+            Self::ChaCha20Poly1305 => Self::aead_decrypt(chacha20poly1305::ChaCha20Poly1305::new(GenericArray::from_slice(&key.as_slice()[..32])), inout, in_len), 
+        }
+    }
+
+    fn aead_encrypt<A: AeadInPlace>(cipher: A, inout: &mut [u8], in_len: usize) -> BuckyResult<usize> {
+        let out_len = AEAD_NONCE_LEN + in_len + AEAD_TAG_LEN;
+        if inout.len() < out_len {
+            let msg = format!("aead encrypt buffer not enough, except {}, got {}", out_len, inout.len());
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
+
+        inout.copy_within(0..in_len, AEAD_NONCE_LEN);
+        let (nonce, remain) = inout.split_at_mut(AEAD_NONCE_LEN);
+        rand::thread_rng().fill(nonce);
+        let (payload, tail) = remain.split_at_mut(in_len);
+        let tag = cipher.encrypt_in_place_detached(GenericArray::from_slice(nonce), &[], payload)
+            .map_err(|_| BuckyError::new(BuckyErrorCode::CryptoError, "aead encrypt failed"))?;
+        tail[..AEAD_TAG_LEN].copy_from_slice(tag.as_slice());
+
+        Ok(out_len)
+    }
+
+    // 校验失败时不会修改inout，调用者可以继续尝试其他算法
+    fn aead_decrypt<A: AeadInPlace>(cipher: A, inout: &mut [u8], in_len: usize) -> BuckyResult<usize> {
+        if in_len < AEAD_NONCE_LEN + AEAD_TAG_LEN {
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, "aead encrypted data too short"));
+        }
+
+        let payload_len = in_len - AEAD_NONCE_LEN - AEAD_TAG_LEN;
+        {
+            let (nonce, remain) = inout.split_at_mut(AEAD_NONCE_LEN);
+            let (payload, tail) = remain.split_at_mut(payload_len);
+            cipher.decrypt_in_place_detached(
+                GenericArray::from_slice(nonce), 
+                &[], 
+                payload, 
+                GenericArray::from_slice(&tail[..AEAD_TAG_LEN]))
+                .map_err(|_| BuckyError::new(BuckyErrorCode::CryptoError, "aead decrypt failed"))?;
+        }
+        inout.copy_within(AEAD_NONCE_LEN..AEAD_NONCE_LEN + payload_len, 0);
+
+        Ok(payload_len)
+    }
+}
+
+impl std::fmt::Display for TunnelCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::AesCbc => "aes-cbc", 
+            Self::AesGcm => "aes-gcm", 
+            Self::ChaCha20Poly1305 => "chacha20-poly1305", 
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl TryFrom<u8> for TunnelCipher {
+    type Error = BuckyError;
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0u8 => Ok(Self::AesCbc), 
+            1u8 => Ok(Self::AesGcm), 
+            2u8 => Ok(Self::ChaCha20Poly1305), 
+            _ => Err(BuckyError::new(
+                BuckyErrorCode::InvalidParam,
+                format!("invalid tunnel cipher value {}", v),
+            )),
+        }
+    }
+}
+
+impl RawFixedBytes for TunnelCipher {
+    fn raw_bytes() -> Option<usize> {
+        u8::raw_bytes()
+    }
+}
+
+impl RawEncode for TunnelCipher {
+    fn raw_measure(&self, _purpose: &Option<RawEncodePurpose>) -> Result<usize, BuckyError> {
+        Ok(<u8 as RawFixedBytes>::raw_bytes().unwrap())
+    }
+
+    fn raw_encode<'a>(
+        &self,
+        buf: &'a mut [u8],
+        purpose: &Option<RawEncodePurpose>,
+    ) -> Result<&'a mut [u8], BuckyError> {
+        (*self as u8).raw_encode(buf, purpose)
+    }
+}
+
+impl<'de> RawDecode<'de> for TunnelCipher {
+    fn raw_decode(buf: &'de [u8]) -> Result<(Self, &'de [u8]), BuckyError> {
+        let (v, buf) = u8::raw_decode(buf)?;
+        Ok((Self::try_from(v)?, buf))
+    }
+}
+
 #[derive(Clone)]
 pub struct MixAesKey {
     pub enc_key: AesKey, 
-    pub mix_key: AesKey
+    pub mix_key: AesKey, 
+    // 当前用于加密的算法
+    pub cipher: TunnelCipher, 
+    // 已经协商但还没有被对端确认的算法，收到用它加密的包之后切换到cipher
+    pub pending_cipher: Option<TunnelCipher>, 
+    // 对端时钟相对本地的偏差(秒)，mixhash按对端的时间计算
+    pub clock_skew: i64, 
 }
 
 impl std::fmt::Display for MixAesKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "enc {}, mix {}", self.enc_key.to_hex().unwrap(), self.mix_key.to_hex().unwrap())
+        write!(f, "enc {}, mix {}, cipher {}", self.enc_key.to_hex().unwrap(), self.mix_key.to_hex().unwrap(), self.cipher)
     }
 }
 
 
 impl MixAesKey {
+    pub fn new(enc_key: AesKey, mix_key: AesKey) -> Self {
+        Self {
+            enc_key, 
+            mix_key, 
+            cipher: TunnelCipher::AesCbc, 
+            pending_cipher: None, 
+            clock_skew: 0, 
+        }
+    }
+
+    // 按对端时钟计算的当前分钟数
+    pub fn minute_timestamp(&self) -> u64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        ((now + self.clock_skew).max(0) / 60) as u64
+    }
+
     pub fn mix_hash(&self) -> KeyMixHash {
-        self.mix_key.mix_hash(Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() / 60))
+        self.mix_key.mix_hash(Some(self.minute_timestamp()))
+    }
+
+    pub fn padded_len(&self, in_len: usize) -> usize {
+        self.cipher.padded_len(in_len)
+    }
+
+    pub fn inplace_encrypt(&self, inout: &mut [u8], in_len: usize) -> BuckyResult<usize> {
+        self.cipher.inplace_encrypt(&self.enc_key, inout, in_len)
+    }
+
+    // 切换算法的过程中两端可能用不同的算法加密，aead校验失败不会修改数据，
+    // 所以依次尝试 pending_cipher，cipher；只有还在协商或者没有协商出aead时才回退到兼容的 aes-cbc，
+    // 已经确认aead之后不再接受 aes-cbc，避免被降级；返回实际使用的算法
+    pub fn inplace_decrypt(&self, inout: &mut [u8], in_len: usize) -> BuckyResult<(usize, TunnelCipher)> {
+        let mut candidates = Vec::with_capacity(2);
+        if let Some(pending) = self.pending_cipher {
+            candidates.push(pending);
+        }
+        if !candidates.contains(&self.cipher) {
+            candidates.push(self.cipher);
+        }
+
+        for cipher in candidates.iter().filter(|c| c.is_aead()) {
+            if let Ok(len) = cipher.inplace_decrypt(&self.enc_key, inout, in_len) {
+                return Ok((len, *cipher));
+            }
+        }
+
+        if self.cipher.is_aead() && self.pending_cipher.is_none() {
+            return Err(BuckyError::new(BuckyErrorCode::CryptoError, format!("aead decrypt failed and aes-cbc not allowed after {} confirmed", self.cipher)));
+        }
+
+        let len = TunnelCipher::AesCbc.inplace_decrypt(&self.enc_key, inout, in_len)?;
+        Ok((len, TunnelCipher::AesCbc))
     }
 }
 
@@ -262,7 +482,7 @@ impl IncreaseIdGenerator {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EndpointPair(Endpoint, Endpoint);
 
 impl std::fmt::Display for EndpointPair {
@@ -367,3 +587,41 @@ impl StateWaiter {
         self.wakers.len()
     }
 }
+
+#[test]
+fn tunnel_cipher_inplace() {
+    let key = AesKey::random();
+    let plain = b"tunnel cipher agility test".to_vec();
+    for cipher in TunnelCipher::all() {
+        let mut buf = vec![0u8; cipher.padded_len(plain.len())];
+        buf[..plain.len()].copy_from_slice(&plain[..]);
+        let len = cipher.inplace_encrypt(&key, &mut buf, plain.len()).unwrap();
+
+        // 还在切换中的key也要能解开旧算法和新算法加密的数据
+        let mut mix_key = MixAesKey::new(key.clone(), AesKey::random());
+        mix_key.pending_cipher = Some(*cipher);
+        let (dec_len, used) = mix_key.inplace_decrypt(&mut buf, len).unwrap();
+        assert_eq!(used, *cipher);
+        assert_eq!(&buf[..dec_len], &plain[..]);
+    }
+
+    let mut buf = vec![0u8; TunnelCipher::AesGcm.padded_len(plain.len())];
+    buf[..plain.len()].copy_from_slice(&plain[..]);
+    let len = TunnelCipher::AesGcm.inplace_encrypt(&key, &mut buf, plain.len()).unwrap();
+    assert!(TunnelCipher::ChaCha20Poly1305.inplace_decrypt(&key, &mut buf, len).is_err());
+    let dec_len = TunnelCipher::AesGcm.inplace_decrypt(&key, &mut buf, len).unwrap();
+    assert_eq!(&buf[..dec_len], &plain[..]);
+
+    // 确认aead之后不能再用aes-cbc降级
+    let mut buf = vec![0u8; TunnelCipher::AesCbc.padded_len(plain.len())];
+    buf[..plain.len()].copy_from_slice(&plain[..]);
+    let len = TunnelCipher::AesCbc.inplace_encrypt(&key, &mut buf, plain.len()).unwrap();
+    let mut mix_key = MixAesKey::new(key.clone(), AesKey::random());
+    mix_key.cipher = TunnelCipher::AesGcm;
+    assert!(mix_key.inplace_decrypt(&mut buf.clone(), len).is_err());
+    mix_key.pending_cipher = Some(TunnelCipher::ChaCha20Poly1305);
+    mix_key.cipher = TunnelCipher::AesCbc;
+    let (dec_len, used) = mix_key.inplace_decrypt(&mut buf, len).unwrap();
+    assert_eq!(used, TunnelCipher::AesCbc);
+    assert_eq!(&buf[..dec_len], &plain[..]);
+}
@@ -14,6 +14,10 @@ pub trait ChunkCache: Send + Sync
     async fn new_chunk(&self, chunk_id: &ChunkId) -> BuckyResult<Box<dyn ChunkMut>>;
     async fn delete_chunk(&self, chunk_id: &ChunkId) -> BuckyResult<()>;
     async fn put_chunk(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()>;
+
+    // 使用存储密钥加密之后保存，读取时自动解密
+    async fn put_chunk_encrypted(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()>;
+    async fn is_encrypted(&self, chunk_id: &ChunkId) -> bool;
     async fn is_exist(&self, chunk_id: &ChunkId) -> bool;
     async fn get_chunk_meta(&self, chunk_id: &ChunkId, chunk_type: ChunkType) -> BuckyResult<ChunkMeta>;
 }
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 use std::sync::{Arc, Once, RwLock};
-use cyfs_chunk_lib::{Chunk, ChunkMeta, ChunkMut};
+use std::time::Duration;
+use cyfs_chunk_lib::{Chunk, ChunkMeta, ChunkMut, MemChunk};
 use cyfs_base::*;
+use cyfs_util::{ColdPackStat, ColdPackStore};
 use crate::{ChunkCache, LocalChunkCache, SingleDiskChunkCache, DiskScanner, ChunkType};
 
 static mut CHUNK_MANAGER_INSTANCE: Option<ChunkManager> = None;
@@ -11,12 +13,15 @@ pub struct CYFSDiskScanner;
 
 impl DiskScanner for CYFSDiskScanner {
     fn get_cache_path_list(&self) -> Vec<(PathBuf, u64)> {
-        vec![(cyfs_util::get_cyfs_root_path().join("data").join("chunk-cache"), 1024*1024*1024*1024)]
+        vec![(cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::Chunk).join("chunk-cache"), 1024*1024*1024*1024)]
     }
 }
 
 pub struct ChunkManager {
-    chunk_cache: RwLock<Option<Arc<dyn ChunkCache>>>
+    chunk_cache: RwLock<Option<Arc<dyn ChunkCache>>>,
+
+    // 冷存储层，归档的chunk在访问时自动水合回chunk cache
+    cold: RwLock<Option<ColdPackStore>>,
 }
 
 pub type ChunkManagerRef = Arc<ChunkManager>;
@@ -24,7 +29,8 @@ pub type ChunkManagerRef = Arc<ChunkManager>;
 impl ChunkManager {
     pub fn new() -> Self {
         Self {
-            chunk_cache: RwLock::new(None)
+            chunk_cache: RwLock::new(None),
+            cold: RwLock::new(None),
         }
     }
 
@@ -35,7 +41,119 @@ impl ChunkManager {
             assert!(slot.is_none());
             *slot = Some(chunk_cache);
         }
-        
+
+        let isolate = if isolate.is_empty() { "default" } else { isolate };
+        let dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::Chunk).join("chunk-cold").join(isolate);
+        let cold = ColdPackStore::open(dir, false)?;
+        *self.cold.write().unwrap() = Some(cold);
+
+        Ok(())
+    }
+
+    fn cold(&self) -> Option<ColdPackStore> {
+        self.cold.read().unwrap().clone()
+    }
+
+    pub fn is_archived(&self, chunk_id: &ChunkId) -> bool {
+        match self.cold() {
+            Some(cold) => cold.contains(&chunk_id.object_id()),
+            None => false,
+        }
+    }
+
+    pub fn cold_stat(&self) -> ColdPackStat {
+        match self.cold() {
+            Some(cold) => cold.stat(),
+            None => ColdPackStat::default(),
+        }
+    }
+
+    pub fn set_hydrate_budget(&self, budget: Duration) {
+        if let Some(cold) = self.cold() {
+            cold.set_hydrate_budget(budget);
+        }
+    }
+
+    // 把chunk归档到一个新的pack并从chunk cache里删除，返回实际归档的数量
+    pub async fn archive_chunks(&self, list: &[ChunkId]) -> BuckyResult<usize> {
+        let cold = self.cold().ok_or_else(|| {
+            BuckyError::new(BuckyErrorCode::NotInit, "chunk cold tier not init")
+        })?;
+
+        let mut items = Vec::with_capacity(list.len());
+        let mut archived = vec![];
+        for chunk_id in list {
+            let id = chunk_id.object_id();
+            if cold.contains(&id) {
+                continue;
+            }
+
+            match self.get_chunk(chunk_id, ChunkType::MemChunk).await {
+                Ok(chunk) => {
+                    items.push((id, chunk.into_vec()));
+                    archived.push(chunk_id);
+                }
+                Err(e) => {
+                    log::warn!("load chunk for archive failed! chunk={}, {}", chunk_id, e);
+                }
+            }
+        }
+
+        let count = cold.archive(items).await?;
+
+        // pack已经落盘，这里删除失败只会导致chunk cache里多一份数据
+        for chunk_id in archived {
+            if let Err(e) = self.delete_hot_chunk(chunk_id).await {
+                log::warn!("remove archived chunk failed! chunk={}, {}", chunk_id, e);
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn delete_hot_chunk(&self, chunk_id: &ChunkId) -> BuckyResult<()> {
+        let chunk_cache = {
+            let chunk_cache = self.chunk_cache.read().unwrap();
+            chunk_cache.as_ref().unwrap().clone()
+        };
+        chunk_cache.delete_chunk(chunk_id).await
+    }
+
+    // 返回true表示chunk已经水合回chunk cache
+    async fn hydrate(&self, chunk_id: &ChunkId) -> BuckyResult<bool> {
+        let cold = match self.cold() {
+            Some(cold) => cold,
+            None => return Ok(false),
+        };
+
+        let chunk_cache = {
+            let chunk_cache = self.chunk_cache.read().unwrap();
+            chunk_cache.as_ref().unwrap().clone()
+        };
+        let restore_id = chunk_id.to_owned();
+        let ret = cold
+            .hydrate(&chunk_id.object_id(), move |data, encrypted| async move {
+                let chunk: Box<dyn Chunk> = Box::new(MemChunk::from(data));
+                if encrypted {
+                    chunk_cache.put_chunk_encrypted(&restore_id, chunk).await
+                } else {
+                    chunk_cache.put_chunk(&restore_id, chunk).await
+                }
+            })
+            .await?;
+
+        Ok(ret.is_some())
+    }
+
+    // 新写入或者删除的chunk不再需要冷存储里的数据
+    async fn drop_stub(&self, chunk_id: &ChunkId) -> BuckyResult<()> {
+        if let Some(cold) = self.cold() {
+            let id = chunk_id.object_id();
+            if cold.contains(&id) {
+                cold.remove(&id).await?;
+            }
+        }
+
         Ok(())
     }
 
@@ -44,7 +162,16 @@ impl ChunkManager {
             let chunk_cache = self.chunk_cache.read().unwrap();
             chunk_cache.as_ref().unwrap().clone()
         };
-        chunk_cache.get_chunk(chunk_id, chunk_type).await
+        match chunk_cache.get_chunk(chunk_id, chunk_type).await {
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                if self.hydrate(chunk_id).await? {
+                    chunk_cache.get_chunk(chunk_id, chunk_type).await
+                } else {
+                    Err(e)
+                }
+            }
+            ret @ _ => ret,
+        }
     }
 
     pub async fn new_chunk(&self, chunk_id: &ChunkId) -> BuckyResult<Box<dyn ChunkMut>> {
@@ -56,19 +183,40 @@ impl ChunkManager {
     }
 
     pub async fn delete_chunk(&self, chunk_id: &ChunkId) -> BuckyResult<()> {
+        self.delete_hot_chunk(chunk_id).await?;
+        self.drop_stub(chunk_id).await
+    }
+
+    pub async fn put_chunk(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()> {
+        cyfs_util::check_storage_space(cyfs_util::StorageRootCategory::Chunk, chunk_id.len() as u64)?;
+
         let chunk_cache = {
             let chunk_cache = self.chunk_cache.read().unwrap();
             chunk_cache.as_ref().unwrap().clone()
         };
-        chunk_cache.delete_chunk(chunk_id).await
+        let ret = chunk_cache.put_chunk(chunk_id, chunk).await;
+        cyfs_util::report_storage_write_result(cyfs_util::StorageRootCategory::Chunk, &ret);
+
+        ret?;
+        self.drop_stub(chunk_id).await
     }
 
-    pub async fn put_chunk(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()> {
+    // 本地存储密钥未解锁时返回错误，不会按明文保存
+    pub async fn put_chunk_encrypted(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()> {
+        cyfs_util::check_storage_space(
+            cyfs_util::StorageRootCategory::Chunk,
+            cyfs_util::StorageCipher::encrypted_len(chunk_id.len()) as u64,
+        )?;
+
         let chunk_cache = {
             let chunk_cache = self.chunk_cache.read().unwrap();
             chunk_cache.as_ref().unwrap().clone()
         };
-        chunk_cache.put_chunk(chunk_id, chunk).await
+        let ret = chunk_cache.put_chunk_encrypted(chunk_id, chunk).await;
+        cyfs_util::report_storage_write_result(cyfs_util::StorageRootCategory::Chunk, &ret);
+
+        ret?;
+        self.drop_stub(chunk_id).await
     }
 
     pub async fn exist(&self, chunk_id: &ChunkId) -> bool {
@@ -76,7 +224,12 @@ impl ChunkManager {
             let chunk_cache = self.chunk_cache.read().unwrap();
             chunk_cache.as_ref().unwrap().clone()
         };
-        chunk_cache.is_exist(chunk_id).await
+        if chunk_cache.is_exist(chunk_id).await {
+            return true;
+        }
+
+        // 冷存储里的chunk在读取时水合，这里不触发
+        self.is_archived(chunk_id)
     }
 
     pub async fn get_chunk_meta(&self, chunk_id: &ChunkId, chunk_type: ChunkType) -> BuckyResult<ChunkMeta> {
@@ -84,7 +237,16 @@ impl ChunkManager {
             let chunk_cache = self.chunk_cache.read().unwrap();
             chunk_cache.as_ref().unwrap().clone()
         };
-        chunk_cache.get_chunk_meta(chunk_id, chunk_type).await
+        match chunk_cache.get_chunk_meta(chunk_id, chunk_type).await {
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                if self.hydrate(chunk_id).await? {
+                    chunk_cache.get_chunk_meta(chunk_id, chunk_type).await
+                } else {
+                    Err(e)
+                }
+            }
+            ret @ _ => ret,
+        }
     }
 }
 
@@ -2,6 +2,7 @@ use crate::{Chunk, ChunkCache, ChunkMut, ChunkType, MMapChunk, MMapChunkMut, Mem
 use cyfs_base::*;
 use cyfs_chunk_lib::{ChunkMeta};
 use cyfs_debug::Mutex;
+use cyfs_util::StorageCipher;
 use futures_lite::AsyncWriteExt;
 use num_traits::abs;
 use num_traits::float::Float;
@@ -398,7 +399,11 @@ impl<CACHE: TSingleDiskChunkCache + ChunkCache, SCANNER: DiskScanner>
             if max_cache.is_some() {
                 let tmp_cache = max_cache.unwrap();
                 if let Ok(chunk) = tmp_cache.get_chunk(chunk_id, ChunkType::MMapChunk).await {
-                    cache.put_chunk(chunk_id, chunk).await?;
+                    if tmp_cache.is_encrypted(chunk_id).await {
+                        cache.put_chunk_encrypted(chunk_id, chunk).await?;
+                    } else {
+                        cache.put_chunk(chunk_id, chunk).await?;
+                    }
                     tmp_cache.delete_chunk(chunk_id).await?;
                     return Ok(());
                 }
@@ -482,6 +487,18 @@ impl<CACHE: TSingleDiskChunkCache + ChunkCache, SCANNER: DiskScanner> ChunkCache
         cache.put_chunk(chunk_id, chunk).await
     }
 
+    async fn put_chunk_encrypted(&self, chunk_id: &ChunkId, chunk: Box<dyn Chunk>) -> BuckyResult<()> {
+        let cache = self.alloc_disk_cache(chunk_id)?;
+        cache.put_chunk_encrypted(chunk_id, chunk).await
+    }
+
+    async fn is_encrypted(&self, chunk_id: &ChunkId) -> bool {
+        match self.get_disk_cache(chunk_id) {
+            Ok(cache) => cache.is_encrypted(chunk_id).await,
+            Err(_) => false,
+        }
+    }
+
     async fn is_exist(&self, chunk_id: &ChunkId) -> bool {
         let cache = match self.get_disk_cache(chunk_id) {
             Ok(cache) => cache,
@@ -529,6 +546,9 @@ pub struct SingleDiskChunkCache {
     path: PathBuf,
     cache_id: HashValue,
 
+    // 默认使用owner解锁后的全局密钥，测试时可以单独指定
+    cipher: Option<StorageCipher>,
+
     #[cfg(target_os = "windows")]
     upgrade: super::old_base36::ChunkStorageUpgrade,
 }
@@ -617,11 +637,60 @@ impl SingleDiskChunkCache {
             }
         };
 
-        if chunk_id.len() as u64 != file_meta.len() {
-            false
-        } else {
-            true
+        // 加密保存的chunk文件长度包含了头部和填充
+        file_meta.len() == chunk_id.len() as u64
+            || file_meta.len() == StorageCipher::encrypted_len(chunk_id.len()) as u64
+    }
+
+    fn is_encrypted_file(chunk_id: &ChunkId, file_path: &Path) -> bool {
+        match std::fs::metadata(file_path) {
+            Ok(meta) => meta.len() == StorageCipher::encrypted_len(chunk_id.len()) as u64,
+            Err(_) => false,
+        }
+    }
+
+    // 存储密钥在owner解锁之后才会设置，所以每次使用时读取
+    fn cipher(&self) -> Option<&StorageCipher> {
+        self.cipher
+            .as_ref()
+            .or_else(|| cyfs_util::get_storage_cipher())
+    }
+
+    // 加密的chunk只能整体解密到内存里，不支持mmap
+    async fn load_encrypted_chunk(&self, chunk_id: &ChunkId, file_path: &Path) -> BuckyResult<Vec<u8>> {
+        let cipher = self.cipher().ok_or_else(|| {
+            let msg = format!(
+                "chunk is encrypted but storage key not unlocked! chunk={}, file={}",
+                chunk_id,
+                file_path.display()
+            );
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
+        })?;
+
+        let buf = async_std::fs::read(file_path).await.map_err(|e| {
+            let msg = format!(
+                "open encrypted chunk's file error! chunk={}, file={}, {}",
+                chunk_id,
+                file_path.display(),
+                e
+            );
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let data = cipher.decrypt(chunk_id.as_slice(), &buf)?;
+        if data.len() != chunk_id.len() {
+            let msg = format!(
+                "decrypt chunk but got mismatched length! chunk={}, got={}",
+                chunk_id,
+                data.len()
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
         }
+
+        Ok(data)
     }
 
     async fn write_with_verify(
@@ -695,6 +764,7 @@ impl TSingleDiskChunkCache for SingleDiskChunkCache {
 
             path,
             cache_id,
+            cipher: None,
         }
     }
 
@@ -757,6 +827,12 @@ impl ChunkCache for SingleDiskChunkCache {
             }
         }
 
+        if Self::is_encrypted_file(chunk_id, &file_path) {
+            let buf = self.load_encrypted_chunk(chunk_id, &file_path).await?;
+            let chunk: Box<dyn Chunk> = Box::new(MemChunk::from(buf));
+            return Ok(chunk);
+        }
+
         match chunk_type {
             ChunkType::MMapChunk => {
                 let chunk: Box<dyn Chunk> = Box::new(MMapChunk::open(file_path, None).await?);
@@ -911,6 +987,76 @@ impl ChunkCache for SingleDiskChunkCache {
         Ok(())
     }
 
+    async fn put_chunk_encrypted(&self, chunk_id: &ChunkId, mut chunk: Box<dyn Chunk>) -> BuckyResult<()> {
+        // 未解锁时不能退化为明文保存
+        let cipher = self.cipher().ok_or_else(|| {
+            let msg = format!(
+                "chunk should be encrypted but storage key not unlocked! chunk={}",
+                chunk_id
+            );
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
+        })?;
+
+        let file_path = self.get_file_path(chunk_id, true);
+        if file_path.exists() {
+            log::info!(
+                "put encrypted chunk but local file already exist! chunk={}, file={}",
+                chunk_id,
+                file_path.display()
+            );
+            return Ok(());
+        }
+
+        chunk.as_mut().seek(std::io::SeekFrom::Start(0)).await?;
+
+        let mut data = vec![0u8; chunk_id.len()];
+        let mut read_len = 0;
+        while read_len < data.len() {
+            let bytes = chunk.read(&mut data[read_len..]).await?;
+            if bytes == 0 {
+                break;
+            }
+            read_len += bytes;
+        }
+
+        let actual_id = ChunkId::calculate(&data[..read_len]).await?;
+        if actual_id != *chunk_id {
+            let msg = format!(
+                "mismatched chunk data! chunk={}, got={}",
+                chunk_id, actual_id
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let buf = cipher.encrypt(chunk_id.as_slice(), &data)?;
+        if let Err(e) = async_std::fs::write(&file_path, &buf).await {
+            let _ = async_std::fs::remove_file(&file_path).await;
+
+            let msg = format!(
+                "put encrypted chunk to local file failed! chunk={}, file={}, {}",
+                chunk_id,
+                file_path.display(),
+                e
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+        }
+
+        log::info!(
+            "put encrypted chunk to local file complete! chunk={}, file={}",
+            chunk_id,
+            file_path.display()
+        );
+        Ok(())
+    }
+
+    async fn is_encrypted(&self, chunk_id: &ChunkId) -> bool {
+        let file_path = self.get_file_path(chunk_id, false);
+        Self::is_encrypted_file(chunk_id, &file_path)
+    }
+
     async fn is_exist(&self, chunk_id: &ChunkId) -> bool {
         self.chunk_exist(chunk_id)
     }
@@ -932,6 +1078,11 @@ impl ChunkCache for SingleDiskChunkCache {
             return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
         }
 
+        if Self::is_encrypted_file(chunk_id, &file_path) {
+            let buf = self.load_encrypted_chunk(chunk_id, &file_path).await?;
+            return Ok(ChunkMeta::MemChunk(buf));
+        }
+
         match chunk_type {
             ChunkType::MMapChunk => Ok(ChunkMeta::MMapChunk(
                 file_path.to_string_lossy().to_string(),
@@ -1140,6 +1291,18 @@ mod test_local_chunk_cache {
             Ok(())
         }
 
+        async fn put_chunk_encrypted(
+            &self,
+            chunk_id: &ChunkId,
+            chunk: Box<dyn Chunk>,
+        ) -> BuckyResult<()> {
+            self.put_chunk(chunk_id, chunk).await
+        }
+
+        async fn is_encrypted(&self, _chunk_id: &ChunkId) -> bool {
+            false
+        }
+
         async fn is_exist(&self, _chunk_id: &ChunkId) -> bool {
             todo!()
         }
@@ -1213,6 +1376,49 @@ mod test_local_chunk_cache {
         }
     }
 
+    #[test]
+    fn test_put_chunk_encrypted() {
+        async_std::task::block_on(async move {
+            let path = cyfs_util::get_temp_path().join("test_chunk_cache_encrypt");
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+
+            let mut cache = crate::SingleDiskChunkCache::new(path);
+            let data = b"private chunk data".to_vec();
+            let chunk_id = ChunkId::calculate(&data).await.unwrap();
+
+            // 未解锁时拒绝写入，不会落盘明文
+            let e = cache
+                .put_chunk_encrypted(
+                    &chunk_id,
+                    Box::new(cyfs_chunk_lib::MemChunk::from(data.clone())),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(e.code(), BuckyErrorCode::PermissionDenied);
+            assert!(!cache.get_file_path(&chunk_id, false).exists());
+
+            cache.cipher = Some(cyfs_util::StorageCipher::from_passphrase("test", b"salt"));
+            cache
+                .put_chunk_encrypted(
+                    &chunk_id,
+                    Box::new(cyfs_chunk_lib::MemChunk::from(data.clone())),
+                )
+                .await
+                .unwrap();
+            assert!(cache.is_encrypted(&chunk_id).await);
+
+            let raw = std::fs::read(cache.get_file_path(&chunk_id, false)).unwrap();
+            assert!(raw.windows(data.len()).all(|v| v != data.as_slice()));
+
+            let chunk = cache.get_chunk(&chunk_id, ChunkType::MemChunk).await.unwrap();
+            let mut reader = ChunkRead::new(chunk);
+            let mut buf = vec![];
+            reader.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, data);
+        });
+    }
+
     #[test]
     fn test_alloc() {
         async_std::task::block_on(async move {
@@ -5,6 +5,7 @@ use serde::Serialize;
 
 use std::collections::hash_map::RandomState;
 use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 
 #[derive(Clone, ProtobufEncode, ProtobufDecode, ProtobufTransform, Serialize)]
 #[cyfs_protobuf_type(crate::codec::protos::DecAppDescContent)]
@@ -35,6 +36,8 @@ pub struct DecAppContent {
     desc: Option<String>,
     source_desc: HashMap<String, String>,
     tags: HashMap<String, String>,
+    // 依赖的其它dec app，dec_id -> 版本要求(semver)
+    dependencies: HashMap<ObjectId, String>,
 }
 
 impl BodyContent for DecAppContent {
@@ -60,12 +63,18 @@ impl ProtobufTransform<protos::DecAppContent> for DecAppContent {
             tags.insert(item.key, item.value);
         }
 
+        let mut dependencies = HashMap::new();
+        for item in value.dependencies {
+            dependencies.insert(ObjectId::from_str(&item.key)?, item.value);
+        }
+
         let mut ret = DecAppContent {
             source,
             source_desc,
             icon: None,
             desc: None,
             tags,
+            dependencies,
         };
 
         if value.icon.is_some() {
@@ -102,12 +111,23 @@ impl ProtobufTransform<&DecAppContent> for protos::DecAppContent {
             tags.push(protos::StringStringMapItem { key: k, value: v });
         }
 
+        let dependencies_map: BTreeMap<String, String> = value
+            .dependencies
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_owned()))
+            .collect();
+        let mut dependencies = vec![];
+        for (k, v) in dependencies_map {
+            dependencies.push(protos::StringStringMapItem { key: k, value: v });
+        }
+
         let mut ret = Self {
             source,
             source_desc,
             icon: None,
             desc: None,
             tags,
+            dependencies,
         };
 
         if let Some(icon) = &value.icon {
@@ -153,6 +173,11 @@ pub trait DecAppObj {
     fn remove_tag(&mut self, tag: &str);
     fn tags(&self) -> &HashMap<String, String>;
 
+    // 依赖的dec app和版本要求，版本要求为*表示任意版本
+    fn dependencies(&self) -> &HashMap<ObjectId, String>;
+    fn set_dependency(&mut self, dec_id: ObjectId, req_semver: String);
+    fn remove_dependency(&mut self, dec_id: &ObjectId);
+
     fn generate_id(owner: ObjectId, id: &str) -> ObjectId;
 }
 
@@ -190,6 +215,7 @@ impl DecAppObj for DecApp {
             desc: None,
             source_desc: HashMap::new(),
             tags: HashMap::new(),
+            dependencies: HashMap::new(),
         };
         let desc = DecAppDescContent { id: id.to_owned() };
         DecAppBuilder::new(desc, body)
@@ -381,6 +407,28 @@ impl DecAppObj for DecApp {
         &self.body_expect("").content().tags
     }
 
+    fn dependencies(&self) -> &HashMap<ObjectId, String> {
+        &self.body_expect("").content().dependencies
+    }
+
+    fn set_dependency(&mut self, dec_id: ObjectId, req_semver: String) {
+        self.body_mut_expect("")
+            .content_mut()
+            .dependencies
+            .insert(dec_id, req_semver);
+        self.body_mut_expect("")
+            .increase_update_time(bucky_time_now());
+    }
+
+    fn remove_dependency(&mut self, dec_id: &ObjectId) {
+        self.body_mut_expect("")
+            .content_mut()
+            .dependencies
+            .remove(dec_id);
+        self.body_mut_expect("")
+            .increase_update_time(bucky_time_now());
+    }
+
     fn generate_id(owner: ObjectId, id: &str) -> ObjectId {
         Self::create(owner, id).desc().calculate_id()
     }
@@ -445,4 +493,19 @@ mod test {
         let ret = dec_app.find_version("<=1.3.8", None).unwrap();
         assert_eq!(ret, "1.3.7");
     }
+
+    #[test]
+    fn test_dependencies() {
+        let owner = ObjectId::default();
+        let mut dec_app = DecApp::create(owner.clone(), "test-dec-app");
+        let dep = DecApp::generate_id(owner.clone(), "test-dep-app");
+        dec_app.set_dependency(dep.clone(), "^1.2".to_owned());
+        dec_app.set_dependency(owner.clone(), "*".to_owned());
+        dec_app.remove_dependency(&owner);
+
+        let buf = dec_app.to_vec().unwrap();
+        let dec_app = DecApp::clone_from_slice(&buf).unwrap();
+        assert_eq!(dec_app.dependencies().len(), 1);
+        assert_eq!(dec_app.dependencies().get(&dep).unwrap(), "^1.2");
+    }
 }
\ No newline at end of file
@@ -75,9 +75,13 @@ impl ObjectFormatAutoWithSerde for TransContextBodyContent {}
 impl ObjectFormatAutoWithSerde for ZoneDescContent {}
 impl ObjectFormatAutoWithSerde for ZoneBodyContent {}
 
+impl ObjectFormatAutoWithSerde for ZoneEventDescContent {}
+impl ObjectFormatAutoWithSerde for ZoneEventBodyContent {}
+
 
 pub fn register_core_objects_format() {
     FORMAT_FACTORY.register(CoreObjectType::Zone, format_json::<Zone>);
+    FORMAT_FACTORY.register(CoreObjectType::ZoneEvent, format_json::<ZoneEvent>);
     FORMAT_FACTORY.register(CoreObjectType::Storage, format_json::<Storage>);
     FORMAT_FACTORY.register(CoreObjectType::Text, format_json::<Text>);
 
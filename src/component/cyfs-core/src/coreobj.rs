@@ -9,6 +9,9 @@ pub enum CoreObjectType {
     // admin control
     Admin = 33,
 
+    // zone内的事件记录
+    ZoneEvent = 34,
+
     // 基于object的存储
     Storage = 40,
 
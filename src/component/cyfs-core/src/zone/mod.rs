@@ -1,3 +1,5 @@
 mod zone;
+mod zone_event;
 
-pub use zone::*;
\ No newline at end of file
+pub use zone::*;
+pub use zone_event::*;
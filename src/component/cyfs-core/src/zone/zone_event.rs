@@ -0,0 +1,264 @@
+use crate::codec::*;
+use crate::coreobj::CoreObjectType;
+use cyfs_base::*;
+use serde::Serialize;
+
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize)]
+#[repr(u8)]
+pub enum ZoneEventCategory {
+    DeviceOnline = 0,
+    DeviceOffline = 1,
+    SyncComplete = 2,
+    AppInstall = 3,
+    AppUninstall = 4,
+    BackupRun = 5,
+    AclDenied = 6,
+    SyncConflict = 7,
+}
+
+impl ZoneEventCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeviceOnline => "device-online",
+            Self::DeviceOffline => "device-offline",
+            Self::SyncComplete => "sync-complete",
+            Self::AppInstall => "app-install",
+            Self::AppUninstall => "app-uninstall",
+            Self::BackupRun => "backup-run",
+            Self::AclDenied => "acl-denied",
+            Self::SyncConflict => "sync-conflict",
+        }
+    }
+}
+
+impl std::fmt::Display for ZoneEventCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ZoneEventCategory {
+    type Err = BuckyError;
+
+    fn from_str(value: &str) -> BuckyResult<Self> {
+        let ret = match value {
+            "device-online" => Self::DeviceOnline,
+            "device-offline" => Self::DeviceOffline,
+            "sync-complete" => Self::SyncComplete,
+            "app-install" => Self::AppInstall,
+            "app-uninstall" => Self::AppUninstall,
+            "backup-run" => Self::BackupRun,
+            "acl-denied" => Self::AclDenied,
+            "sync-conflict" => Self::SyncConflict,
+            _ => {
+                let msg = format!("unknown zone event category: {}", value);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+impl TryFrom<u8> for ZoneEventCategory {
+    type Error = BuckyError;
+
+    fn try_from(value: u8) -> BuckyResult<Self> {
+        let ret = match value {
+            0 => Self::DeviceOnline,
+            1 => Self::DeviceOffline,
+            2 => Self::SyncComplete,
+            3 => Self::AppInstall,
+            4 => Self::AppUninstall,
+            5 => Self::BackupRun,
+            6 => Self::AclDenied,
+            7 => Self::SyncConflict,
+            _ => {
+                let msg = format!("unknown zone event category value: {}", value);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, ProtobufEncode, ProtobufDecode, ProtobufTransformType, Serialize)]
+#[cyfs_protobuf_type(crate::codec::protos::ZoneEventDescContent)]
+pub struct ZoneEventDescContent {
+    category: ZoneEventCategory,
+
+    // 事件发生的设备
+    device: DeviceId,
+
+    // 相关的dec，比如安装的app，被拒绝的请求来源
+    dec_id: Option<ObjectId>,
+
+    // bucky time
+    time: u64,
+
+    detail: String,
+}
+
+impl DescContent for ZoneEventDescContent {
+    fn obj_type() -> u16 {
+        CoreObjectType::ZoneEvent as u16
+    }
+
+    fn format(&self) -> u8 {
+        OBJECT_CONTENT_CODEC_FORMAT_PROTOBUF
+    }
+
+    type OwnerType = Option<ObjectId>;
+    type AreaType = SubDescNone;
+    type AuthorType = SubDescNone;
+    type PublicKeyType = SubDescNone;
+}
+
+impl ProtobufTransform<protos::ZoneEventDescContent> for ZoneEventDescContent {
+    fn transform(value: protos::ZoneEventDescContent) -> BuckyResult<Self> {
+        let mut ret = Self {
+            category: ProtobufCodecHelper::decode_value(value.category as u8)?,
+            device: ProtobufCodecHelper::decode_buf(value.device)?,
+            dec_id: None,
+            time: value.time,
+            detail: value.detail,
+        };
+
+        if let Some(dec_id) = value.dec_id {
+            ret.dec_id = Some(ProtobufCodecHelper::decode_buf(dec_id)?);
+        }
+
+        Ok(ret)
+    }
+}
+
+impl ProtobufTransform<&ZoneEventDescContent> for protos::ZoneEventDescContent {
+    fn transform(value: &ZoneEventDescContent) -> BuckyResult<Self> {
+        let mut ret = Self {
+            category: value.category as u32,
+            device: value.device.to_vec()?,
+            dec_id: None,
+            time: value.time,
+            detail: value.detail.clone(),
+        };
+
+        if let Some(dec_id) = &value.dec_id {
+            ret.dec_id = Some(dec_id.to_vec()?);
+        }
+
+        Ok(ret)
+    }
+}
+
+#[derive(Clone, Default, Debug, ProtobufEmptyEncode, ProtobufEmptyDecode, Serialize)]
+pub struct ZoneEventBodyContent {}
+
+impl BodyContent for ZoneEventBodyContent {
+    fn format(&self) -> u8 {
+        OBJECT_CONTENT_CODEC_FORMAT_PROTOBUF
+    }
+}
+
+type ZoneEventType = NamedObjType<ZoneEventDescContent, ZoneEventBodyContent>;
+type ZoneEventBuilder = NamedObjectBuilder<ZoneEventDescContent, ZoneEventBodyContent>;
+
+pub type ZoneEventId = NamedObjectId<ZoneEventType>;
+pub type ZoneEvent = NamedObjectBase<ZoneEventType>;
+
+pub trait ZoneEventObj {
+    fn create(
+        owner: Option<ObjectId>,
+        category: ZoneEventCategory,
+        device: DeviceId,
+        dec_id: Option<ObjectId>,
+        detail: String,
+    ) -> Self;
+
+    fn category(&self) -> ZoneEventCategory;
+    fn device(&self) -> &DeviceId;
+    fn dec_id(&self) -> &Option<ObjectId>;
+    fn time(&self) -> u64;
+    fn detail(&self) -> &str;
+}
+
+impl ZoneEventObj for ZoneEvent {
+    fn create(
+        owner: Option<ObjectId>,
+        category: ZoneEventCategory,
+        device: DeviceId,
+        dec_id: Option<ObjectId>,
+        detail: String,
+    ) -> Self {
+        let desc = ZoneEventDescContent {
+            category,
+            device,
+            dec_id,
+            time: bucky_time_now(),
+            detail,
+        };
+
+        ZoneEventBuilder::new(desc, ZoneEventBodyContent {})
+            .option_owner(owner)
+            .no_create_time()
+            .build()
+    }
+
+    fn category(&self) -> ZoneEventCategory {
+        self.desc().content().category
+    }
+
+    fn device(&self) -> &DeviceId {
+        &self.desc().content().device
+    }
+
+    fn dec_id(&self) -> &Option<ObjectId> {
+        &self.desc().content().dec_id
+    }
+
+    fn time(&self) -> u64 {
+        self.desc().content().time
+    }
+
+    fn detail(&self) -> &str {
+        &self.desc().content().detail
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use cyfs_base::*;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_codec() {
+        let device = DeviceId::from_str("5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty").unwrap();
+        let dec_id = ObjectId::from_str("9tGpLNnErEbyzuMgRLcRX6An1Sn8ZQHYZ5wkfjGyC5j5").unwrap();
+
+        let event = ZoneEvent::create(
+            None,
+            ZoneEventCategory::AppInstall,
+            device.clone(),
+            Some(dec_id.clone()),
+            "version=1.0.0".to_owned(),
+        );
+        let buf = event.to_vec().unwrap();
+        let event2 = ZoneEvent::clone_from_slice(&buf).unwrap();
+
+        assert_eq!(event2.category(), ZoneEventCategory::AppInstall);
+        assert_eq!(*event2.device(), device);
+        assert_eq!(*event2.dec_id(), Some(dec_id));
+        assert_eq!(event2.time(), event.time());
+        assert_eq!(event2.detail(), "version=1.0.0");
+        assert_eq!(event2.desc().calculate_id(), event.desc().calculate_id());
+
+        let category = ZoneEventCategory::from_str(ZoneEventCategory::AclDenied.as_str()).unwrap();
+        assert_eq!(category, ZoneEventCategory::AclDenied);
+    }
+}
@@ -1,4 +1,5 @@
 use super::panic::*;
+use super::snapshot::*;
 use crate::bug_report::*;
 use cyfs_base::*;
 use cyfs_util::*;
@@ -32,6 +33,8 @@ struct PanicManagerImpl {
 
     exit_on_panic: bool,
 
+    snapshot: PanicSnapshotConfig,
+
     on_panic: OnPanicEventManager,
 
     // reporters
@@ -57,6 +60,7 @@ impl PanicManagerImpl {
             log_to_file: builder.log_to_file,
             log_dir: builder.log_dir,
             exit_on_panic: builder.exit_on_panic,
+            snapshot: builder.snapshot,
 
             on_panic: OnPanicEventManager::new(),
             reporter,
@@ -90,9 +94,15 @@ impl PanicManager {
         self.0.on_panic.clone()
     }
 
-    fn on_panic(&self, info: CyfsPanicInfo) {
+    fn on_panic(&self, mut info: CyfsPanicInfo) {
+        if self.0.snapshot.enable {
+            let snapshot = PanicSnapshotManager::get_instance().collect(&self.0.snapshot);
+            info.snapshot = Some(snapshot);
+        }
+
         if self.0.log_to_file {
             self.log_to_file(&info);
+            self.bundle_to_file(&info);
         }
 
         if let Some(reporter) = &self.0.reporter {
@@ -136,6 +146,32 @@ impl PanicManager {
             error!("write panic log failed! dir={}, {}", file_path.display(), e);
         }
     }
+
+    // 完整的panic信息和状态快照，可以直接附加到bug报告里
+    fn bundle_to_file(&self, info: &CyfsPanicInfo) {
+        if info.snapshot.is_none() {
+            return;
+        }
+
+        let req = PanicReportRequest::new(&self.0.product_name, &self.0.service_name, info.clone());
+        let content = match serde_json::to_string_pretty(&req) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("encode panic bundle failed! {}", e);
+                return;
+            }
+        };
+
+        let file_name = format!("{}_panic_{}.bundle.json", self.0.service_name, info.hash);
+        let file_path = self.0.log_dir.join(file_name);
+        if let Err(e) = std::fs::write(&file_path, content) {
+            error!(
+                "write panic bundle failed! file={}, {}",
+                file_path.display(),
+                e
+            );
+        }
+    }
 }
 
 pub struct PanicBuilder {
@@ -150,6 +186,8 @@ pub struct PanicBuilder {
 
     // Whether to end the process after PANIC
     exit_on_panic: bool,
+
+    snapshot: PanicSnapshotConfig,
 }
 
 impl PanicBuilder {
@@ -169,6 +207,7 @@ impl PanicBuilder {
             disable_bug_report: false,
             bug_reporter: vec![],
             exit_on_panic: false,
+            snapshot: PanicSnapshotConfig::default(),
         }
     }
 
@@ -214,6 +253,19 @@ impl PanicBuilder {
         self
     }
 
+    // Whether to collect the snapshot of registered PanicSnapshotProvider on panic, the default is true
+    pub fn snapshot(mut self, enable: bool) -> Self {
+        self.snapshot.enable = enable;
+        self
+    }
+
+    // Size limit of the snapshot, in bytes; the default is 64KB for each provider and 512KB for total
+    pub fn snapshot_size_limit(mut self, max_item_size: usize, max_total_size: usize) -> Self {
+        self.snapshot.max_item_size = max_item_size;
+        self.snapshot.max_total_size = max_total_size;
+        self
+    }
+
     pub fn build(self) -> PanicManager {
         PanicManager::new(self)
     }
@@ -1,5 +1,7 @@
 mod panic;
 mod manager;
+mod snapshot;
 
 pub use manager::*;
-pub use panic::*;
\ No newline at end of file
+pub use panic::*;
+pub use snapshot::*;
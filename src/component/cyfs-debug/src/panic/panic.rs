@@ -1,3 +1,4 @@
+use super::snapshot::CyfsPanicSnapshot;
 use panic::PanicInfo;
 
 use backtrace::{Backtrace, BacktraceFrame};
@@ -11,6 +12,10 @@ pub struct CyfsPanicInfo {
     pub msg: String,
     pub msg_with_symbol: String,
     pub hash: String,
+
+    // panic时的运行状态快照，未开启或者还未收集时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<CyfsPanicSnapshot>,
 }
 
 impl CyfsPanicInfo {
@@ -25,6 +30,7 @@ impl CyfsPanicInfo {
             msg,
             msg_with_symbol,
             hash,
+            snapshot: None,
         };
 
         warn!("{}", ret.msg);
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// panic时抓取运行状态快照的提供者，比如活跃的任务、打开的tunnel、最近的请求等
+// 在panic线程之外的线程里调用，实现里不要阻塞等锁，拿不到锁时返回部分信息即可
+pub trait PanicSnapshotProvider: Send + Sync {
+    fn snapshot(&self) -> serde_json::Value;
+}
+
+pub type PanicSnapshotProviderRef = Arc<dyn PanicSnapshotProvider>;
+
+// 快照里这些字段可能包含对象内容或者用户数据，只保留长度
+const SCRUB_KEYS: &[&str] = &[
+    "object_raw",
+    "data",
+    "body",
+    "payload",
+    "content",
+    "value",
+    "private_key",
+    "secret",
+];
+
+const MAX_STRING_LEN: usize = 1024;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CyfsPanicSnapshot {
+    // bucky time
+    pub time: u64,
+
+    // provider name -> snapshot
+    pub items: BTreeMap<String, serde_json::Value>,
+
+    // 超过大小限制或者超时被丢弃的provider
+    pub dropped: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PanicSnapshotConfig {
+    pub enable: bool,
+    pub max_item_size: usize,
+    pub max_total_size: usize,
+    pub timeout: Duration,
+}
+
+impl Default for PanicSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            max_item_size: 1024 * 64,
+            max_total_size: 1024 * 512,
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+pub struct PanicSnapshotManager {
+    providers: Mutex<BTreeMap<String, PanicSnapshotProviderRef>>,
+}
+
+impl PanicSnapshotManager {
+    fn new() -> Self {
+        Self {
+            providers: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn get_instance() -> &'static Self {
+        use once_cell::sync::OnceCell;
+        static S_INSTANCE: OnceCell<PanicSnapshotManager> = OnceCell::new();
+        S_INSTANCE.get_or_init(|| Self::new())
+    }
+
+    // 同名的provider会被替换
+    pub fn register(&self, name: &str, provider: PanicSnapshotProviderRef) {
+        info!("register panic snapshot provider: {}", name);
+        self.providers
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), provider);
+    }
+
+    pub fn unregister(&self, name: &str) -> bool {
+        self.providers.lock().unwrap().remove(name).is_some()
+    }
+
+    pub(crate) fn collect(&self, config: &PanicSnapshotConfig) -> CyfsPanicSnapshot {
+        let providers: Vec<(String, PanicSnapshotProviderRef)> = match self.providers.try_lock() {
+            Ok(list) => list
+                .iter()
+                .map(|(name, provider)| (name.clone(), provider.clone()))
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let mut snapshot = CyfsPanicSnapshot {
+            time: cyfs_base::bucky_time_now(),
+            items: BTreeMap::new(),
+            dropped: vec![],
+        };
+
+        let mut pending: Vec<String> = providers.iter().map(|(name, _)| name.clone()).collect();
+
+        // 在独立的线程里逐个收集，provider卡住的时候超时放弃剩余的
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _ = std::thread::Builder::new()
+            .name("panic-snapshot".to_owned())
+            .spawn(move || {
+                for (name, provider) in providers {
+                    let value = provider.snapshot();
+                    if tx.send((name, value)).is_err() {
+                        break;
+                    }
+                }
+            });
+
+        let deadline = Instant::now() + config.timeout;
+        let mut total = 0;
+        while !pending.is_empty() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            let (name, mut value) = match rx.recv_timeout(deadline - now) {
+                Ok(ret) => ret,
+                Err(_) => break,
+            };
+            pending.retain(|v| *v != name);
+
+            scrub(&mut value);
+            let size = serde_json::to_string(&value).map(|s| s.len()).unwrap_or(0);
+            if size > config.max_item_size || total + size > config.max_total_size {
+                warn!(
+                    "panic snapshot exceed size limit and dropped! provider={}, size={}",
+                    name, size
+                );
+                snapshot.dropped.push(name);
+                continue;
+            }
+
+            total += size;
+            snapshot.items.insert(name, value);
+        }
+
+        snapshot.dropped.append(&mut pending);
+        snapshot
+    }
+}
+
+// 抹掉快照里的敏感字段，过长的字符串截断
+pub(crate) fn scrub(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if SCRUB_KEYS.contains(&k.as_str()) {
+                    let len = match v {
+                        serde_json::Value::Null => continue,
+                        serde_json::Value::String(s) => s.len(),
+                        _ => v.to_string().len(),
+                    };
+                    *v = serde_json::Value::String(format!("[scrubbed {} bytes]", len));
+                } else {
+                    scrub(v);
+                }
+            }
+        }
+        serde_json::Value::Array(list) => {
+            for v in list.iter_mut() {
+                scrub(v);
+            }
+        }
+        serde_json::Value::String(s) => {
+            if s.len() > MAX_STRING_LEN {
+                let mut end = MAX_STRING_LEN;
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let len = s.len();
+                s.truncate(end);
+                s.push_str(&format!("...[{} bytes]", len));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RecentRequest {
+    time: u64,
+    desc: String,
+}
+
+// 按processor分类记录最近的N个请求，注册到PanicSnapshotManager后panic时一并输出
+#[derive(Clone)]
+pub struct RecentRequestRecorder {
+    capacity: usize,
+    list: Arc<Mutex<HashMap<String, VecDeque<RecentRequest>>>>,
+}
+
+impl RecentRequestRecorder {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            list: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // desc只应包含请求的摘要，比如method和path，不要包含对象内容
+    pub fn record(&self, processor: &str, desc: String) {
+        let item = RecentRequest {
+            time: cyfs_base::bucky_time_now(),
+            desc,
+        };
+
+        let mut list = self.list.lock().unwrap();
+        let queue = list
+            .entry(processor.to_owned())
+            .or_insert_with(|| VecDeque::with_capacity(self.capacity));
+
+        if queue.len() >= self.capacity {
+            queue.pop_front();
+        }
+        queue.push_back(item);
+    }
+}
+
+impl PanicSnapshotProvider for RecentRequestRecorder {
+    fn snapshot(&self) -> serde_json::Value {
+        let list = match self.list.try_lock() {
+            Ok(list) => list,
+            Err(_) => return serde_json::Value::String("[locked]".to_owned()),
+        };
+
+        let all: BTreeMap<&String, &VecDeque<RecentRequest>> = list.iter().collect();
+        serde_json::to_value(all).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestProvider(serde_json::Value);
+
+    impl PanicSnapshotProvider for TestProvider {
+        fn snapshot(&self) -> serde_json::Value {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let recorder = RecentRequestRecorder::new(2);
+        recorder.record("non", "get_object 1".to_owned());
+        recorder.record("non", "get_object 2".to_owned());
+        recorder.record("non", "get_object 3".to_owned());
+        recorder.record("ndn", "get_data 1".to_owned());
+
+        let value = recorder.snapshot();
+        assert_eq!(value["non"].as_array().unwrap().len(), 2);
+        assert_eq!(value["non"][0]["desc"], "get_object 2");
+
+        let manager = PanicSnapshotManager::new();
+        manager.register("requests", Arc::new(recorder));
+        manager.register(
+            "object",
+            Arc::new(TestProvider(serde_json::json!({
+                "id": "test",
+                "object_raw": "0123456789",
+                "list": [{ "body": { "k": 1 } }],
+            }))),
+        );
+        manager.register(
+            "large",
+            Arc::new(TestProvider(serde_json::Value::Array(vec![
+                serde_json::Value::String("a".repeat(MAX_STRING_LEN));
+                128
+            ]))),
+        );
+
+        let snapshot = manager.collect(&PanicSnapshotConfig::default());
+        assert_eq!(snapshot.items.len(), 2);
+        assert_eq!(snapshot.dropped, vec!["large".to_owned()]);
+
+        let object = &snapshot.items["object"];
+        assert_eq!(object["id"], "test");
+        assert_eq!(object["object_raw"], "[scrubbed 10 bytes]");
+        assert_eq!(object["list"][0]["body"], "[scrubbed 7 bytes]");
+    }
+}
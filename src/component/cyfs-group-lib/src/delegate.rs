@@ -42,6 +42,12 @@ pub trait RPathDelegate: Sync + Send {
         block: &GroupConsensusBlock,
         object_map_processor: &dyn GroupObjectMapProcessor,
     );
+
+    // called before the proposal is queued for consensus when the fee verification is enabled
+    // for the rpath, check the fee or anything else to reject the spam proposals.
+    async fn on_admit(&self, _proposal: &GroupProposal) -> BuckyResult<()> {
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
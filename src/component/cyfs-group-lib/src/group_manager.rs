@@ -18,9 +18,9 @@ use cyfs_lib::{
 use cyfs_util::EventListenerAsyncRoutine;
 
 use crate::{
-    DelegateFactory, ExecuteResult, GroupCommand, GroupCommandCommited, GroupCommandExecute,
-    GroupCommandExecuteResult, GroupCommandNewRPath, GroupCommandObject, GroupCommandType,
-    GroupCommandVerify, RPathClient, RPathDelegate, RPathService,
+    DelegateFactory, ExecuteResult, GroupCommand, GroupCommandAdmit, GroupCommandCommited,
+    GroupCommandExecute, GroupCommandExecuteResult, GroupCommandNewRPath, GroupCommandObject,
+    GroupCommandType, GroupCommandVerify, RPathClient, RPathDelegate, RPathService,
 };
 
 type ServiceByRPath = HashMap<String, RPathService>;
@@ -242,6 +242,7 @@ impl GroupManager {
             crate::GroupCommandBodyContent::Commited(cmd) => {
                 self.on_commited(cmd).await.map(|_| None)
             }
+            crate::GroupCommandBodyContent::Admit(cmd) => self.on_admit(cmd).await.map(|_| None),
         }
     }
 
@@ -369,6 +370,36 @@ impl GroupManager {
         Ok(())
     }
 
+    async fn on_admit(&self, cmd: GroupCommandAdmit) -> BuckyResult<()> {
+        let rpath = cmd.proposal.rpath();
+        let service = self
+            .find_or_restart_service(
+                rpath.group_id(),
+                rpath.dec_id(),
+                rpath.rpath(),
+                &None,
+                false,
+            )
+            .await
+            .map_err(|err| {
+                log::warn!(
+                    "group on_admit find service {:?} failed, err: {:?}",
+                    cmd.proposal.rpath(),
+                    err
+                );
+                err
+            })?;
+
+        service.on_admit(&cmd.proposal).await.map_err(|err| {
+            log::warn!(
+                "group on_admit {:?} rejected, err: {:?}",
+                cmd.proposal.rpath(),
+                err
+            );
+            err
+        })
+    }
+
     async fn find_or_restart_service(
         &self,
         group_id: &ObjectId,
@@ -38,6 +38,7 @@ pub enum GroupCommandType {
     ExecuteResult,
     Verify,
     Commited,
+    Admit,
 }
 
 #[derive(Clone, RawEncode, RawDecode)]
@@ -47,6 +48,7 @@ pub enum GroupCommandBodyContent {
     ExecuteResult(GroupCommandExecuteResult),
     Verify(GroupCommandVerify),
     Commited(GroupCommandCommited),
+    Admit(GroupCommandAdmit),
 }
 
 pub type GroupCommandObjectType = NamedObjType<GroupCommandDescContent, GroupCommandBodyContent>;
@@ -63,6 +65,7 @@ impl GroupCommandBodyContent {
             GroupCommandBodyContent::ExecuteResult(_) => GroupCommandType::ExecuteResult,
             GroupCommandBodyContent::Verify(_) => GroupCommandType::Verify,
             GroupCommandBodyContent::Commited(_) => GroupCommandType::Commited,
+            GroupCommandBodyContent::Admit(_) => GroupCommandType::Admit,
         }
     }
 }
@@ -288,6 +291,29 @@ impl ProtobufTransform<&GroupCommandCommited> for super::codec::protos::GroupCom
     }
 }
 
+// ask the dec whether to accept the proposal before it's queued for consensus
+#[derive(Clone, ProtobufEncode, ProtobufDecode, ProtobufTransformType)]
+#[cyfs_protobuf_type(super::codec::protos::GroupCommandAdmit)]
+pub struct GroupCommandAdmit {
+    pub proposal: GroupProposal,
+}
+
+impl ProtobufTransform<super::codec::protos::GroupCommandAdmit> for GroupCommandAdmit {
+    fn transform(value: super::codec::protos::GroupCommandAdmit) -> BuckyResult<Self> {
+        Ok(Self {
+            proposal: GroupProposal::raw_decode(value.proposal.as_slice())?.0,
+        })
+    }
+}
+
+impl ProtobufTransform<&GroupCommandAdmit> for super::codec::protos::GroupCommandAdmit {
+    fn transform(value: &GroupCommandAdmit) -> BuckyResult<Self> {
+        Ok(Self {
+            proposal: value.proposal.to_vec()?,
+        })
+    }
+}
+
 impl From<GroupCommandNewRPath> for GroupCommand {
     fn from(cmd: GroupCommandNewRPath) -> Self {
         let desc = GroupCommandDescContent {};
@@ -402,3 +428,26 @@ impl TryInto<GroupCommandCommited> for GroupCommand {
         }
     }
 }
+
+impl From<GroupCommandAdmit> for GroupCommand {
+    fn from(cmd: GroupCommandAdmit) -> Self {
+        let desc = GroupCommandDescContent {};
+        let body = GroupCommandBodyContent::Admit(cmd);
+        GroupCommandBuilder::new(desc, body).build()
+    }
+}
+
+impl TryInto<GroupCommandAdmit> for GroupCommand {
+    type Error = BuckyError;
+
+    fn try_into(self) -> Result<GroupCommandAdmit, Self::Error> {
+        let cmd_type = self.cmd_type();
+        match self.into_cmd() {
+            GroupCommandBodyContent::Admit(cmd) => Ok(cmd),
+            _ => Err(BuckyError::new(
+                BuckyErrorCode::Unmatch,
+                format!("is {:?}, expect Admit", cmd_type),
+            )),
+        }
+    }
+}
@@ -12,6 +12,83 @@ pub struct GroupRPathStatus {
     pub status_map: HashMap<ObjectId, NONObjectInfo>,
 }
 
+// the overhead of a repeated bytes field in protobuf: tag + varint length
+const STATUS_FIELD_OVERHEAD: usize = 8;
+
+impl GroupRPathStatus {
+    // split the status into several parts, the encoded size of each part is less than `max_len`.
+    // all parts carry the same block and certificate, so each part can be verified alone.
+    pub fn split(&self, max_len: usize) -> BuckyResult<Vec<GroupRPathStatus>> {
+        let empty = || Self {
+            block_desc: self.block_desc.clone(),
+            certificate: self.certificate.clone(),
+            status_map: HashMap::new(),
+        };
+
+        let header_len = empty().raw_measure(&None)?;
+
+        let mut parts = vec![];
+        let mut part = empty();
+        let mut part_len = header_len;
+        for (id, obj) in self.status_map.iter() {
+            let obj_len = obj.raw_measure(&None)? + STATUS_FIELD_OVERHEAD;
+            if header_len + obj_len > max_len {
+                let msg = format!(
+                    "the status {} is too large to encode, len: {}, limit: {}",
+                    id,
+                    header_len + obj_len,
+                    max_len
+                );
+                log::error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            if part_len + obj_len > max_len {
+                parts.push(std::mem::replace(&mut part, empty()));
+                part_len = header_len;
+            }
+
+            part.status_map.insert(id.clone(), obj.clone());
+            part_len += obj_len;
+        }
+        parts.push(part);
+
+        Ok(parts)
+    }
+
+    // merge the part splitted from the same status
+    pub fn merge(&mut self, part: GroupRPathStatus) -> BuckyResult<()> {
+        if self.block_desc.object_id() != part.block_desc.object_id()
+            || self.certificate.block_id != part.certificate.block_id
+        {
+            let msg = format!(
+                "merge status from different block, expect: {}, got: {}",
+                self.block_desc.object_id(),
+                part.block_desc.object_id()
+            );
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        self.status_map.extend(part.status_map);
+        Ok(())
+    }
+}
+
+fn check_decode_remain(remain: &[u8], name: &str) -> BuckyResult<()> {
+    if remain.len() > 0 {
+        let msg = format!(
+            "decode {} for GroupRPathStatus failed, {} bytes remain",
+            name,
+            remain.len()
+        );
+        log::error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+    }
+
+    Ok(())
+}
+
 impl RawEncode for GroupRPathStatus {
     fn raw_measure(&self, _purpose: &Option<RawEncodePurpose>) -> BuckyResult<usize> {
         let block_desc = self.block_desc.to_vec()?;
@@ -64,16 +141,16 @@ impl<'de> RawDecode<'de> for GroupRPathStatus {
 
         let (block_desc, remain) =
             GroupConsensusBlockDesc::raw_decode(proto.block_desc.as_slice())?;
-        assert_eq!(remain.len(), 0);
+        check_decode_remain(remain, "block_desc")?;
         let (certificate, remain) = HotstuffBlockQC::raw_decode(proto.certificate.as_slice())?;
-        assert_eq!(remain.len(), 0);
+        check_decode_remain(remain, "certificate")?;
         let mut status_map = HashMap::new();
         for obj_buf in proto.status_list.iter() {
             log::debug!("will decode len: {}", obj_buf.len());
             // size + object_id
             let status = if obj_buf.len() > OBJECT_ID_LEN + 1 {
                 let (status, remain) = NONObjectInfo::raw_decode(obj_buf.as_slice())?;
-                assert_eq!(remain.len(), 0);
+                check_decode_remain(remain, "status")?;
                 status
             } else if obj_buf.len() == OBJECT_ID_LEN + 1 {
                 NONObjectInfo::new(
@@ -103,4 +180,96 @@ impl<'de> RawDecode<'de> for GroupRPathStatus {
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use cyfs_core::{GroupConsensusBlock, GroupConsensusBlockObject, GroupRPath, Text, TextObj};
+
+    fn new_status(height: u64, count: usize) -> GroupRPathStatus {
+        let owner = ObjectId::default();
+        let rpath = GroupRPath::new(owner.clone(), owner.clone(), "test-rpath".to_owned());
+        let block = GroupConsensusBlock::create(
+            rpath,
+            vec![],
+            None,
+            height,
+            ObjectId::default(),
+            height,
+            ObjectId::default(),
+            None,
+            None,
+            owner,
+        );
+
+        let mut status_map = HashMap::new();
+        for i in 0..count {
+            let text = Text::create(&format!("status-{}", i), "", "x".repeat(1000));
+            let object_id = text.desc().calculate_id();
+            let object = NONObjectInfo::new(object_id.clone(), text.to_vec().unwrap(), None);
+            status_map.insert(object_id, object);
+        }
+
+        GroupRPathStatus {
+            block_desc: block.named_object().desc().clone(),
+            certificate: HotstuffBlockQC {
+                round: height,
+                ..Default::default()
+            },
+            status_map,
+        }
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let status = new_status(1, 20);
+        let max_len = 4096;
+
+        let parts = status.split(max_len).unwrap();
+        assert!(parts.len() > 1);
+
+        let mut merged = GroupRPathStatus {
+            block_desc: status.block_desc.clone(),
+            certificate: status.certificate.clone(),
+            status_map: HashMap::new(),
+        };
+        for part in parts {
+            // every part is encoded under the limit and can be decoded alone
+            let buf = part.to_vec().unwrap();
+            assert!(buf.len() <= max_len);
+            let (part, remain) = GroupRPathStatus::raw_decode(buf.as_slice()).unwrap();
+            assert_eq!(remain.len(), 0);
+            assert_eq!(part.block_desc.object_id(), status.block_desc.object_id());
+
+            merged.merge(part).unwrap();
+        }
+
+        let mut keys = merged.status_map.keys().collect::<Vec<_>>();
+        let mut expected = status.status_map.keys().collect::<Vec<_>>();
+        keys.sort();
+        expected.sort();
+        assert_eq!(keys, expected);
+
+        // the small status is not splitted
+        let parts = status.split(usize::MAX).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].status_map.len(), 20);
+
+        let parts = new_status(1, 0).split(max_len).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].status_map.is_empty());
+    }
+
+    #[test]
+    fn test_split_and_merge_invalid() {
+        let status = new_status(1, 2);
+
+        // one status can not be splitted
+        let err = status.split(1024).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::OutOfLimit);
+
+        // the parts from another block can not be merged
+        let mut other = new_status(2, 1);
+        let err = other.merge(status).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::Unmatch);
+        assert_eq!(other.status_map.len(), 1);
+    }
+}
@@ -53,6 +53,8 @@ impl RPathClient {
                     level: NONAPILevel::Router,
                     target: Some(proposal.rpath().group_id().clone()),
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object: NONObjectInfo::new(proposal.desc().object_id(), proposal.to_vec()?, None),
             })
@@ -121,6 +121,10 @@ impl RPathService {
             )
             .await
     }
+
+    pub(crate) async fn on_admit(&self, proposal: &GroupProposal) -> BuckyResult<()> {
+        self.0.delegate.on_admit(proposal).await
+    }
 }
 
 struct GroupObjectMapProcessorImpl {
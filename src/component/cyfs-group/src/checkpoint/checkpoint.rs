@@ -0,0 +1,211 @@
+use cyfs_base::{
+    hash_data, BuckyError, BuckyErrorCode, BuckyResult, HashValue, ObjectId, ObjectIdDataBuilder,
+    ObjectLink, PublicKey, RawConvertTo, RawFrom, RsaCPUObjectSigner, RsaCPUObjectVerifier,
+    Signature, SignatureSource, Signer, Verifier,
+};
+use cyfs_base_meta::{Data, SavedMetaObject};
+use cyfs_core::{GroupConsensusBlock, GroupConsensusBlockObject, GroupRPath};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+// the committed block of rpath, which is anchored on the meta chain
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GroupCheckpoint {
+    pub group_id: ObjectId,
+    pub dec_id: ObjectId,
+    pub rpath: String,
+    pub height: u64,
+    pub round: u64,
+    pub block_id: ObjectId,
+    // the group members at the block, the signer of the anchor should be one of them
+    pub group_shell_id: ObjectId,
+}
+
+impl GroupCheckpoint {
+    pub fn from_block(block: &GroupConsensusBlock) -> Self {
+        let rpath = block.rpath();
+        Self {
+            group_id: rpath.group_id().clone(),
+            dec_id: rpath.dec_id().clone(),
+            rpath: rpath.rpath().to_owned(),
+            height: block.height(),
+            round: block.round(),
+            block_id: block.block_id().object_id().clone(),
+            group_shell_id: block.group_shell_id().clone(),
+        }
+    }
+
+    pub fn is_rpath(&self, rpath: &GroupRPath) -> bool {
+        &self.group_id == rpath.group_id()
+            && &self.dec_id == rpath.dec_id()
+            && self.rpath.as_str() == rpath.rpath()
+    }
+
+    // the checkpoint of an rpath is always saved with the same id on the meta chain
+    pub fn anchor_id(rpath: &GroupRPath) -> ObjectId {
+        let mut buf = vec![];
+        buf.extend_from_slice(rpath.group_id().as_slice());
+        buf.extend_from_slice(rpath.dec_id().as_slice());
+        buf.extend_from_slice(rpath.rpath().as_bytes());
+
+        let hash = hash_data(buf.as_slice());
+        ObjectIdDataBuilder::new()
+            .data(&hash.as_slice()[..31])
+            .build()
+            .unwrap()
+    }
+
+    pub fn hash(&self) -> HashValue {
+        let mut sha256 = sha2::Sha256::new();
+        sha256.input(self.group_id.as_slice());
+        sha256.input(self.dec_id.as_slice());
+        sha256.input(self.rpath.as_bytes());
+        sha256.input(self.height.to_le_bytes());
+        sha256.input(self.round.to_le_bytes());
+        sha256.input(self.block_id.as_slice());
+        sha256.input(self.group_shell_id.as_slice());
+        sha256.result().into()
+    }
+}
+
+// the checkpoint saved on the meta chain, signed by the member who published it.
+// anyone can write the data with the anchor id on the meta chain, so the reader should
+// verify the signer is a member of the group before trusting it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupCheckpointAnchor {
+    pub checkpoint: GroupCheckpoint,
+    pub signer: ObjectId,
+    // the hex of the encoded signature
+    signature: String,
+}
+
+impl GroupCheckpointAnchor {
+    pub async fn sign(
+        checkpoint: GroupCheckpoint,
+        local_device_id: ObjectId,
+        signer: &RsaCPUObjectSigner,
+    ) -> BuckyResult<Self> {
+        let signature = signer
+            .sign(
+                checkpoint.hash().as_slice(),
+                &SignatureSource::Object(ObjectLink {
+                    obj_id: local_device_id,
+                    obj_owner: None,
+                }),
+            )
+            .await?;
+
+        Ok(Self {
+            checkpoint,
+            signer: local_device_id,
+            signature: signature.to_hex()?,
+        })
+    }
+
+    pub async fn verify(&self, public_key: &PublicKey) -> BuckyResult<()> {
+        let mut buf = vec![];
+        let signature = Signature::clone_from_hex(self.signature.as_str(), &mut buf)?;
+        let verifier = RsaCPUObjectVerifier::new(public_key.clone());
+        if !verifier
+            .verify(self.checkpoint.hash().as_slice(), &signature)
+            .await
+        {
+            let msg = format!(
+                "the signature of checkpoint is invalid! checkpoint: {:?}, signer: {}",
+                self.checkpoint, self.signer
+            );
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidSignature, msg));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_meta_object(&self) -> BuckyResult<SavedMetaObject> {
+        let data = serde_json::to_vec(self).map_err(|err| {
+            let msg = format!("encode group checkpoint failed! {:?}, {}", self, err);
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        let checkpoint = &self.checkpoint;
+        let id = GroupCheckpoint::anchor_id(&GroupRPath::new(
+            checkpoint.group_id.clone(),
+            checkpoint.dec_id.clone(),
+            checkpoint.rpath.clone(),
+        ));
+
+        Ok(SavedMetaObject::Data(Data { id, data }))
+    }
+
+    pub fn from_meta_object(obj: &SavedMetaObject) -> BuckyResult<Self> {
+        match obj {
+            SavedMetaObject::Data(data) => {
+                serde_json::from_slice(data.data.as_slice()).map_err(|err| {
+                    let msg = format!("decode group checkpoint failed! id={}, {}", data.id, err);
+                    log::error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })
+            }
+            _ => {
+                let msg = format!("group checkpoint should be saved as data on meta chain!");
+                log::error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_checkpoint {
+    use super::*;
+    use cyfs_base::PrivateKey;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_codec() {
+        let group_id = ObjectId::from_str("5r4MYfF7qVAbn1gdNy9JaNQUW5DfFM8yD3pnwFWY8nn4").unwrap();
+        let dec_id = ObjectId::from_str("5r4MYfF8wo73agKvNjPu7ENuJKABYEFDZ4xi6efweF9D").unwrap();
+        let block_id = ObjectId::from_str("5aSixgLtjoYcAFH9isc6KCqDgKfTJ8jpgASAoiRz5NLk").unwrap();
+        let device_id = ObjectId::default();
+
+        let checkpoint = GroupCheckpoint {
+            group_id: group_id.clone(),
+            dec_id: dec_id.clone(),
+            rpath: "/test".to_owned(),
+            height: 10,
+            round: 12,
+            block_id,
+            group_shell_id: group_id.clone(),
+        };
+
+        let rpath = GroupRPath::new(group_id, dec_id, "/test".to_owned());
+        assert!(checkpoint.is_rpath(&rpath));
+
+        async_std::task::block_on(async move {
+            let secret = PrivateKey::generate_rsa(1024).unwrap();
+            let signer = RsaCPUObjectSigner::new(secret.public(), secret.clone());
+            let anchor = GroupCheckpointAnchor::sign(checkpoint.clone(), device_id, &signer)
+                .await
+                .unwrap();
+
+            let obj = anchor.to_meta_object().unwrap();
+            assert_eq!(obj.id(), GroupCheckpoint::anchor_id(&rpath));
+
+            let decoded = GroupCheckpointAnchor::from_meta_object(&obj).unwrap();
+            assert_eq!(decoded.checkpoint, checkpoint);
+            assert_eq!(decoded.signer, device_id);
+            decoded.verify(&secret.public()).await.unwrap();
+
+            // signed by other key
+            let other = PrivateKey::generate_rsa(1024).unwrap();
+            let err = decoded.verify(&other.public()).await.unwrap_err();
+            assert_eq!(err.code(), BuckyErrorCode::InvalidSignature);
+
+            // the checkpoint is modified
+            let mut forged = decoded.clone();
+            forged.checkpoint.height += 1;
+            assert!(forged.verify(&secret.public()).await.is_err());
+        });
+    }
+}
@@ -0,0 +1,9 @@
+// anchor the committed blocks of rpath on the meta chain
+
+mod checkpoint;
+mod publisher;
+mod verifier;
+
+pub use checkpoint::*;
+pub use publisher::*;
+pub use verifier::*;
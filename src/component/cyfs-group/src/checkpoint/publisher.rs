@@ -0,0 +1,211 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::Duration,
+};
+
+use cyfs_base::{
+    BuckyError, BuckyErrorCode, BuckyResult, ObjectId, PrivateKey, RsaCPUObjectSigner,
+    StandardObject,
+};
+use cyfs_core::{GroupConsensusBlock, GroupRPath};
+use cyfs_meta_lib::MetaClient;
+
+use crate::{
+    helper::Timer, GroupCheckpoint, GroupCheckpointAnchor, MetaClientTimeout,
+    CHECKPOINT_PUBLISH_INTERVAL,
+};
+
+#[derive(Clone)]
+pub struct GroupCheckpointConfig {
+    // the account to pay for the checkpoint on the meta chain
+    pub owner: StandardObject,
+    pub secret: PrivateKey,
+    pub interval: Duration,
+}
+
+impl GroupCheckpointConfig {
+    pub fn new(owner: StandardObject, secret: PrivateKey) -> Self {
+        Self {
+            owner,
+            secret,
+            interval: CHECKPOINT_PUBLISH_INTERVAL,
+        }
+    }
+}
+
+struct PublishState {
+    latest: Option<GroupCheckpoint>,
+    published_height: Option<u64>,
+    is_created: bool,
+}
+
+struct GroupCheckpointPublisherRaw {
+    rpath: GroupRPath,
+    config: GroupCheckpointConfig,
+    meta_client: Arc<MetaClient>,
+    // the checkpoint is signed by the local device, which should be an ood of the group
+    local_device_id: ObjectId,
+    signer: Arc<RsaCPUObjectSigner>,
+    state: Mutex<PublishState>,
+    is_stopped: AtomicBool,
+}
+
+// publish the latest committed block of rpath to the meta chain periodically
+#[derive(Clone)]
+pub(crate) struct GroupCheckpointPublisher(Arc<GroupCheckpointPublisherRaw>);
+
+impl GroupCheckpointPublisher {
+    pub fn new(
+        rpath: GroupRPath,
+        config: GroupCheckpointConfig,
+        meta_client: Arc<MetaClient>,
+        local_device_id: ObjectId,
+        signer: Arc<RsaCPUObjectSigner>,
+    ) -> Self {
+        let raw = GroupCheckpointPublisherRaw {
+            rpath,
+            config,
+            meta_client,
+            local_device_id,
+            signer,
+            state: Mutex::new(PublishState {
+                latest: None,
+                published_height: None,
+                is_created: false,
+            }),
+            is_stopped: AtomicBool::new(false),
+        };
+
+        Self(Arc::new(raw))
+    }
+
+    // the task only holds a weak reference, it will exit when the publisher is stopped
+    // or all the references are dropped.
+    pub fn start(&self) {
+        let publisher = Arc::downgrade(&self.0);
+        let interval = self.0.config.interval.as_millis() as u64;
+        async_std::task::spawn(async move {
+            let mut timer = Timer::new(interval);
+            loop {
+                timer.wait_next().await;
+                match Weak::upgrade(&publisher) {
+                    Some(raw) if !raw.is_stopped.load(Ordering::SeqCst) => {
+                        let _ = Self(raw).publish().await;
+                    }
+                    _ => break,
+                }
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        log::info!("stop publish checkpoint of {:?}", self.0.rpath);
+        self.0.is_stopped.store(true, Ordering::SeqCst);
+    }
+
+    pub fn on_commited(&self, block: &GroupConsensusBlock) -> BuckyResult<()> {
+        let checkpoint = GroupCheckpoint::from_block(block);
+        if !checkpoint.is_rpath(&self.0.rpath) {
+            let msg = format!(
+                "the commited block is not for the rpath of checkpoint! expect: {:?}, got: {:?}",
+                self.0.rpath, checkpoint
+            );
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        let mut state = self.0.state.lock().unwrap();
+        if state
+            .latest
+            .as_ref()
+            .map_or(true, |latest| latest.height < checkpoint.height)
+        {
+            state.latest = Some(checkpoint);
+        }
+
+        Ok(())
+    }
+
+    async fn publish(&self) -> BuckyResult<()> {
+        let (checkpoint, is_created) = {
+            let state = self.0.state.lock().unwrap();
+            match state.latest.as_ref() {
+                Some(latest)
+                    if state
+                        .published_height
+                        .map_or(true, |height| height < latest.height) =>
+                {
+                    (latest.clone(), state.is_created)
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        let anchor = GroupCheckpointAnchor::sign(
+            checkpoint.clone(),
+            self.0.local_device_id.clone(),
+            self.0.signer.as_ref(),
+        )
+        .await?;
+        let desc = anchor.to_meta_object()?;
+        let meta_client = self.0.meta_client.as_ref();
+        let config = &self.0.config;
+
+        let is_created = if is_created {
+            true
+        } else {
+            match meta_client.get_desc_timeout(&desc.id()).await {
+                Ok(_) => true,
+                Err(err) if err.code() == BuckyErrorCode::NotFound => false,
+                Err(err) => {
+                    log::warn!(
+                        "query checkpoint of {:?} from meta chain failed, err: {:?}",
+                        self.0.rpath,
+                        err
+                    );
+                    return Err(err);
+                }
+            }
+        };
+
+        let ret = if is_created {
+            meta_client
+                .update_desc(&config.owner, &desc, None, None, &config.secret)
+                .await
+        } else {
+            meta_client
+                .create_desc(&config.owner, &desc, 0, 0, 0, &config.secret)
+                .await
+        };
+
+        match ret {
+            Ok(tx_id) => {
+                log::info!(
+                    "publish checkpoint of {:?} to meta chain, height: {}, round: {}, block: {}, tx: {}",
+                    self.0.rpath,
+                    checkpoint.height,
+                    checkpoint.round,
+                    checkpoint.block_id,
+                    tx_id
+                );
+
+                let mut state = self.0.state.lock().unwrap();
+                state.is_created = true;
+                state.published_height = Some(checkpoint.height);
+                Ok(())
+            }
+            Err(err) => {
+                log::warn!(
+                    "publish checkpoint of {:?} to meta chain failed, height: {}, err: {:?}",
+                    self.0.rpath,
+                    checkpoint.height,
+                    err
+                );
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use cyfs_base::{
+    BuckyError, BuckyErrorCode, BuckyResult, Device, Group, NamedObject, ObjectDesc,
+    SingleKeyObjectDesc,
+};
+use cyfs_core::{GroupConsensusBlock, GroupConsensusBlockObject, GroupRPath};
+use cyfs_meta_lib::MetaClient;
+
+use crate::{GroupCheckpoint, GroupCheckpointAnchor, MetaClientTimeout};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GroupCheckpointVerifyResult {
+    // no checkpoint anchored for the rpath
+    NoCheckpoint,
+    // the synced block is same as the checkpoint
+    Matched,
+    // the synced block is lower than the checkpoint, it should be synchronized again
+    Behind(GroupCheckpoint),
+    // the synced block is higher than the checkpoint, and it's consistent with the checkpoint
+    Ahead(GroupCheckpoint),
+}
+
+// cross-check the synced blocks with the checkpoints anchored on the meta chain
+#[derive(Clone)]
+pub struct GroupCheckpointVerifier {
+    meta_client: Arc<MetaClient>,
+}
+
+impl GroupCheckpointVerifier {
+    pub fn new(meta_client: Arc<MetaClient>) -> Self {
+        Self { meta_client }
+    }
+
+    // the anchor is not verified, use `verify_anchor` before trusting it
+    pub async fn load_anchor(
+        &self,
+        rpath: &GroupRPath,
+    ) -> BuckyResult<Option<GroupCheckpointAnchor>> {
+        let id = GroupCheckpoint::anchor_id(rpath);
+        let obj = match self.meta_client.get_desc_timeout(&id).await {
+            Ok(obj) => obj,
+            Err(err) if err.code() == BuckyErrorCode::NotFound => return Ok(None),
+            Err(err) => {
+                log::warn!(
+                    "load checkpoint of {:?} from meta chain failed, err: {:?}",
+                    rpath,
+                    err
+                );
+                return Err(err);
+            }
+        };
+
+        let anchor = GroupCheckpointAnchor::from_meta_object(&obj)?;
+        if !anchor.checkpoint.is_rpath(rpath) {
+            let msg = format!(
+                "the checkpoint loaded from meta chain is not for the rpath! expect: {:?}, got: {:?}",
+                rpath, anchor.checkpoint
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        Ok(Some(anchor))
+    }
+
+    // the anchor should be signed by an ood of the group at the checkpoint
+    pub async fn verify_anchor(
+        anchor: &GroupCheckpointAnchor,
+        group: &Group,
+        signer: &Device,
+    ) -> BuckyResult<()> {
+        let signer_id = signer.desc().object_id();
+        if signer_id != anchor.signer {
+            let msg = format!(
+                "the device is not the signer of checkpoint! expect: {}, got: {}",
+                anchor.signer, signer_id
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        if group
+            .ood_list()
+            .iter()
+            .find(|ood| ood.object_id() == &anchor.signer)
+            .is_none()
+        {
+            let msg = format!(
+                "the signer of checkpoint is not an ood of the group! signer: {}, checkpoint: {:?}",
+                anchor.signer, anchor.checkpoint
+            );
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        anchor.verify(signer.desc().public_key()).await
+    }
+
+    pub fn verify_with_checkpoint(
+        block: &GroupConsensusBlock,
+        checkpoint: GroupCheckpoint,
+    ) -> BuckyResult<GroupCheckpointVerifyResult> {
+        let block_id = block.block_id().object_id();
+
+        // the height and round are both increasing with the committed blocks.
+        let is_conflict = if block.height() == checkpoint.height {
+            block_id != &checkpoint.block_id || block.round() != checkpoint.round
+        } else if block.height() > checkpoint.height {
+            block.round() <= checkpoint.round
+        } else {
+            block.round() >= checkpoint.round
+        };
+
+        if is_conflict {
+            let msg = format!(
+                "the synced block is conflict with the checkpoint on meta chain! block: {}, height: {}, round: {}, checkpoint: {:?}",
+                block_id,
+                block.height(),
+                block.round(),
+                checkpoint
+            );
+            log::error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Conflict, msg));
+        }
+
+        let ret = if block.height() == checkpoint.height {
+            GroupCheckpointVerifyResult::Matched
+        } else if block.height() > checkpoint.height {
+            GroupCheckpointVerifyResult::Ahead(checkpoint)
+        } else {
+            GroupCheckpointVerifyResult::Behind(checkpoint)
+        };
+
+        Ok(ret)
+    }
+}
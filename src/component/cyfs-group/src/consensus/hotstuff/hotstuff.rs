@@ -6,9 +6,9 @@ use std::{
 
 use async_std::channel::{Receiver, Sender};
 use cyfs_base::{
-    bucky_time_to_system_time, BuckyError, BuckyErrorCode, BuckyResult, Group, NamedObject,
-    ObjectDesc, ObjectId, ObjectLink, ObjectTypeCode, OwnerObjectDesc, RawConvertTo, RawDecode,
-    RawEncode, RsaCPUObjectSigner, SignatureSource, Signer,
+    bucky_time_now, bucky_time_to_system_time, BuckyError, BuckyErrorCode, BuckyResult, Group,
+    NamedObject, ObjectDesc, ObjectId, ObjectLink, ObjectTypeCode, OwnerObjectDesc, RawConvertTo,
+    RawDecode, RawEncode, RsaCPUObjectSigner, SignatureSource, Signer,
 };
 use cyfs_core::{
     GroupConsensusBlock, GroupConsensusBlockObject, GroupConsensusBlockProposal, GroupProposal,
@@ -24,10 +24,10 @@ use crate::{
     dec_state::{CallReplyNotifier, CallReplyWaiter, StatePusher},
     helper::Timer,
     storage::GroupShellManager,
-    Committee, GroupObjectMapProcessor, GroupStorage, HotstuffMessage, PendingProposalConsumer,
-    RPathEventNotifier, SyncBound, VoteMgr, VoteThresholded, BLOCK_COUNT_REST_TO_SYNC,
-    CHANNEL_CAPACITY, GROUP_DEFAULT_CONSENSUS_INTERVAL, HOTSTUFF_TIMEOUT_DEFAULT,
-    PROPOSAL_MAX_TIMEOUT, TIME_PRECISION,
+    check_proposal_timing, Committee, GroupObjectMapProcessor, GroupStorage, HotstuffMessage,
+    PendingProposalConsumer, ProposalTiming, RPathEventNotifier, RoundTimeout, SyncBound,
+    VoteMgr, VoteThresholded, BLOCK_COUNT_REST_TO_SYNC, CHANNEL_CAPACITY,
+    HOTSTUFF_TIMEOUT_DEFAULT, QUERY_BLOCKS_PAGE_LEN, QUERY_BLOCKS_PAGE_SIZE, TIME_PRECISION,
 };
 
 /**
@@ -63,6 +63,7 @@ impl Hotstuff {
         proposal_consumer: PendingProposalConsumer,
         event_notifier: RPathEventNotifier,
         rpath: GroupRPath,
+        round_timeout: RoundTimeout,
     ) -> Self {
         let (tx_message, rx_message) = async_std::channel::bounded(CHANNEL_CAPACITY);
         let proposal_result_notifier = CallReplyNotifier::new();
@@ -91,6 +92,7 @@ impl Hotstuff {
             event_notifier,
             rpath.clone(),
             proposal_result_notifier.clone(),
+            round_timeout,
         );
 
         async_std::task::spawn(async move { runner.run().await });
@@ -248,6 +250,23 @@ impl Hotstuff {
             log::warn!("{} send message failed, may lost, err={:?}.", msg, err);
         }
     }
+
+    pub async fn on_query_blocks(&self, min_height: u64, max_height: u64, remote: ObjectId) {
+        let msg = format!(
+            "[hotstuff] local: {:?}, on_query_blocks: [{}-{}], remote: {:?}.",
+            self, min_height, max_height, remote
+        );
+
+        log::debug!("{}", msg);
+
+        if let Err(err) = self
+            .tx_message
+            .send((HotstuffMessage::QueryBlocks(min_height, max_height), remote))
+            .await
+        {
+            log::warn!("{} send message failed, may lost, err={:?}.", msg, err);
+        }
+    }
 }
 
 struct HotstuffRunner {
@@ -262,6 +281,8 @@ struct HotstuffRunner {
     max_quorum_round: u64,
     max_quorum_height: u64,
     timer: Timer, // 定时器
+    round_timeout: RoundTimeout,
+    last_vote_sent: Option<(ObjectId, u64)>, // (block_id, bucky_time) of the vote sent to the next leader, to sample the RTT
     vote_mgr: VoteMgr,
     network_sender: crate::network::Sender,
     non_driver: crate::network::NONDriverHelper,
@@ -305,6 +326,7 @@ impl HotstuffRunner {
         event_notifier: RPathEventNotifier,
         rpath: GroupRPath,
         proposal_result_notifier: CallReplyNotifier<ObjectId, BuckyResult<Option<NONObjectInfo>>>,
+        round_timeout: RoundTimeout,
     ) -> Self {
         let max_round_block = store.block_with_max_round();
         let last_qc = store.last_qc();
@@ -335,7 +357,7 @@ impl HotstuffRunner {
         let tc = last_tc.clone();
 
         let vote_mgr = VoteMgr::new(committee.clone(), round);
-        let init_timer_interval = GROUP_DEFAULT_CONSENSUS_INTERVAL;
+        let init_timer_interval = round_timeout.current().as_millis() as u64;
         let max_quorum_round = round - 1;
         let header_height = store.header_height();
         let max_quorum_height = if header_height == 0 {
@@ -365,6 +387,8 @@ impl HotstuffRunner {
             round,
             high_qc,
             timer: Timer::new(init_timer_interval),
+            round_timeout,
+            last_vote_sent: None,
             vote_mgr,
             network_sender,
             tx_message,
@@ -869,6 +893,8 @@ impl HotstuffRunner {
             return Err(BuckyError::new(BuckyErrorCode::Expired, "block expired"));
         }
 
+        self.sample_rtt(block);
+
         if let Err(err) = self.non_driver.put_block(block).await {
             if err.code() != BuckyErrorCode::AlreadyExists
                 && err.code() != BuckyErrorCode::NotChange
@@ -967,6 +993,7 @@ impl HotstuffRunner {
                 self.handle_vote(&vote, Some(block), self.local_device_id)
                     .await?;
             } else {
+                self.last_vote_sent = Some((vote.block_id, bucky_time_now()));
                 self.network_sender
                     .post_message(
                         HotstuffMessage::BlockVote(vote),
@@ -1133,7 +1160,7 @@ impl HotstuffRunner {
             round + 1
         );
 
-        self.timer.reset(GROUP_DEFAULT_CONSENSUS_INTERVAL);
+        self.timer.reset(self.round_timeout_ms());
         self.round = round + 1;
         self.vote_mgr.cleanup(self.round);
         self.tc = None;
@@ -1501,14 +1528,18 @@ impl HotstuffRunner {
                 let proposal = proposals
                     .get(&proposal_id)
                     .expect("should load all proposals");
-                let proposal_timestamp = bucky_time_to_system_time(proposal.desc().create_time());
-                if Self::calc_time_delta(block_timestamp, proposal_timestamp) > TIME_PRECISION {
+                // the block timestamp is later than the leader checked, so the ending is tolerated
+                let timing = check_proposal_timing(proposal, block_timestamp, TIME_PRECISION);
+                if timing != ProposalTiming::Ready {
                     log::warn!(
-                        "[hotstuff] block {} check timestamp {:?} failed with proposal({:?}) {:?}",
+                        "[hotstuff] block {} check timestamp {:?} failed with proposal({:?}) {:?}, create: {:?}, begining: {:?}, ending: {:?}",
                         block.block_id(),
                         block_timestamp,
                         proposal_id,
-                        proposal_timestamp
+                        timing,
+                        bucky_time_to_system_time(proposal.desc().create_time()),
+                        proposal.effective_begining().map(bucky_time_to_system_time),
+                        proposal.effective_ending().map(bucky_time_to_system_time)
                     );
                     return false;
                 }
@@ -1960,7 +1991,7 @@ impl HotstuffRunner {
             .await
         {
             Ok(group) => {
-                self.timer.reset(GROUP_DEFAULT_CONSENSUS_INTERVAL);
+                self.timer.reset(self.round_timeout_ms());
                 group
             }
             Err(err) => {
@@ -2122,23 +2153,23 @@ impl HotstuffRunner {
                 }
             }
 
-            let create_time = bucky_time_to_system_time(proposal.desc().create_time());
-            if Self::calc_time_delta(now, create_time) > TIME_PRECISION {
-                // 时间误差太大
-                remove_proposals.push(proposal.desc().object_id());
-                time_adjust_proposals.push(proposal);
-                continue;
-            }
-
-            let ending = proposal
-                .effective_ending()
-                .map_or(now.checked_add(PROPOSAL_MAX_TIMEOUT).unwrap(), |ending| {
-                    bucky_time_to_system_time(ending)
-                });
-            if now >= ending {
-                remove_proposals.push(proposal.desc().object_id());
-                timeout_proposals.push(proposal);
-                continue;
+            match check_proposal_timing(&proposal, now, Duration::ZERO) {
+                ProposalTiming::Ready => {}
+                ProposalTiming::Scheduled(_) => {
+                    // keep it in the pending pool until the effective beginning time
+                    continue;
+                }
+                ProposalTiming::Expired => {
+                    remove_proposals.push(proposal_id);
+                    timeout_proposals.push(proposal);
+                    continue;
+                }
+                ProposalTiming::ErrorTimestamp => {
+                    // 时间误差太大
+                    remove_proposals.push(proposal_id);
+                    time_adjust_proposals.push(proposal);
+                    continue;
+                }
             }
 
             match self
@@ -2691,6 +2722,89 @@ impl HotstuffRunner {
         Ok(())
     }
 
+    async fn handle_query_blocks(
+        &self,
+        min_height: u64,
+        max_height: u64,
+        remote: ObjectId,
+    ) -> BuckyResult<()> {
+        let result = self.load_verifiable_blocks(min_height, max_height).await;
+        if let Err(err) = result.as_ref() {
+            log::warn!(
+                "[hotstuff] local: {:?}, query blocks [{}-{}] from {} failed, err: {:?}.",
+                self,
+                min_height,
+                max_height,
+                remote,
+                err
+            );
+        }
+
+        self.network_sender
+            .post_message(
+                HotstuffMessage::VerifiableBlocks(min_height, result),
+                self.rpath.clone(),
+                &remote,
+            )
+            .await;
+
+        Ok(())
+    }
+
+    // only the committed blocks are responded, the page is truncated by count and length,
+    // the requestor should continue from the next height of the last block.
+    async fn load_verifiable_blocks(
+        &self,
+        min_height: u64,
+        max_height: u64,
+    ) -> BuckyResult<(Vec<GroupConsensusBlock>, HotstuffBlockQC)> {
+        let max_height = max_height
+            .min(self.store.header_height())
+            .min(min_height.saturating_add(QUERY_BLOCKS_PAGE_SIZE - 1));
+        if min_height > max_height {
+            return Err(BuckyError::new(
+                BuckyErrorCode::NotFound,
+                format!(
+                    "the blocks from {} are not committed, header: {}",
+                    min_height,
+                    self.store.header_height()
+                ),
+            ));
+        }
+
+        let mut blocks = vec![];
+        let mut len = 0;
+        for height in min_height..=max_height {
+            let block = self.store.get_block_by_height(height).await?;
+            len += block.raw_measure(&None)?;
+            blocks.push(block);
+            if len >= QUERY_BLOCKS_PAGE_LEN {
+                break;
+            }
+        }
+
+        // the qc of the last block is carried by the next block
+        let last_height = blocks.last().unwrap().height();
+        let qc = match self.store.get_block_by_height(last_height + 1).await {
+            Ok(next_block) => next_block.qc().clone(),
+            Err(_) if blocks.len() > 1 => blocks.pop().unwrap().qc().clone(),
+            Err(err) => return Err(err),
+        };
+
+        let last_block = blocks.last().unwrap();
+        match qc {
+            Some(qc) if &qc.block_id == last_block.block_id().object_id() => Ok((blocks, qc)),
+            _ => Err(BuckyError::new(
+                BuckyErrorCode::NotFound,
+                format!(
+                    "the qc of block {}/{} not found",
+                    last_block.block_id(),
+                    last_block.height()
+                ),
+            )),
+        }
+    }
+
     async fn check_group_is_latest(&self, group_shell_id: &ObjectId) -> BuckyResult<bool> {
         let (_, latest_shell_id) = self.shell_mgr.group();
         if &latest_shell_id == group_shell_id {
@@ -2838,9 +2952,7 @@ impl HotstuffRunner {
 
         let duration = latest_group
             .as_ref()
-            .map_or(HOTSTUFF_TIMEOUT_DEFAULT, |_g| {
-                GROUP_DEFAULT_CONSENSUS_INTERVAL
-            });
+            .map_or(HOTSTUFF_TIMEOUT_DEFAULT, |_g| self.round_timeout_ms());
         self.timer.reset(duration);
 
         if let Ok(leader) = self.committee.get_leader(None, self.round, None).await {
@@ -2914,6 +3026,9 @@ impl HotstuffRunner {
                     Ok((HotstuffMessage::ProposalResult(_, _), _)) => panic!("should process by DecStateSynchronizer"),
                     Ok((HotstuffMessage::QueryState(sub_path), remote)) => self.handle_query_state(sub_path, remote).await,
                     Ok((HotstuffMessage::VerifiableState(_, _), _)) => panic!("should process by DecStateRequestor"),
+                    Ok((HotstuffMessage::VerifiableStatePart(_, _, _, _), _)) => panic!("should process by DecStateRequestor"),
+                    Ok((HotstuffMessage::QueryBlocks(min_height, max_height), remote)) => self.handle_query_blocks(min_height, max_height, remote).await,
+                    Ok((HotstuffMessage::VerifiableBlocks(_, _), _)) => panic!("should process by DecStateRequestor"),
                     Err(e) => {
                         log::warn!("[hotstuff] rx_message closed, err: {:?}.", e);
                         Ok(())
@@ -2949,6 +3064,16 @@ impl HotstuffRunner {
                     }
                 },
                 () = self.timer.wait_next().fuse() => self.local_timeout_round().await,
+                expired = self.proposal_consumer.wait_expired().fuse() => match expired {
+                    Ok(proposals) => {
+                        self.notify_timeout_proposals(proposals).await;
+                        Ok(())
+                    },
+                    Err(e) => {
+                        log::warn!("[hotstuff] expired proposal channel closed, err: {:?}.", e);
+                        Ok(())
+                    }
+                },
                 wait_round = Self::proposal_waiter(self.rx_proposal_waiter.clone()).fuse() => {
                     self.rx_proposal_waiter = None;
                     if wait_round == self.round {
@@ -2968,6 +3093,27 @@ impl HotstuffRunner {
         }
     }
 
+    fn round_timeout_ms(&self) -> u64 {
+        self.round_timeout.current().as_millis() as u64
+    }
+
+    // the block from the next leader with the QC for the block voted by me,
+    // the RTT is the time from the vote sent to this block received.
+    fn sample_rtt(&mut self, block: &GroupConsensusBlock) {
+        let qc_block_id = match block.qc().as_ref() {
+            Some(qc) => qc.block_id,
+            None => return,
+        };
+
+        if let Some((voted_block_id, send_time)) = self.last_vote_sent {
+            if voted_block_id == qc_block_id {
+                self.last_vote_sent = None;
+                let rtt = Duration::from_micros(bucky_time_now().saturating_sub(send_time));
+                self.round_timeout.on_rtt(rtt);
+            }
+        }
+    }
+
     fn debug_identify(&self) -> String {
         format!("{:?}-{:?}-{}", self.rpath, self.local_device_id, self.round)
     }
@@ -1,3 +1,5 @@
 mod hotstuff;
+mod round_timeout;
 
 pub(crate) use hotstuff::*;
+pub use round_timeout::*;
@@ -0,0 +1,176 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    GROUP_DEFAULT_CONSENSUS_INTERVAL, HOTSTUFF_TIMEOUT_CEILING, HOTSTUFF_TIMEOUT_FLOOR,
+    HOTSTUFF_TIMEOUT_RTT_MULTIPLE,
+};
+
+#[derive(Clone, Debug)]
+pub struct RoundTimeoutConfig {
+    // the timeout is not less than `floor` even if the members are very close
+    pub floor: Duration,
+    // the timeout is not more than `ceiling` even if the network is very poor
+    pub ceiling: Duration,
+    // timeout = (srtt + 4 * rttvar) * rtt_multiple
+    pub rtt_multiple: u32,
+}
+
+impl Default for RoundTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            floor: HOTSTUFF_TIMEOUT_FLOOR,
+            ceiling: HOTSTUFF_TIMEOUT_CEILING,
+            rtt_multiple: HOTSTUFF_TIMEOUT_RTT_MULTIPLE,
+        }
+    }
+}
+
+struct RoundTimeoutRaw {
+    config: RoundTimeoutConfig,
+    srtt: Option<Duration>,
+    rttvar: Duration,
+    sample_count: u64,
+}
+
+impl RoundTimeoutRaw {
+    fn clamp(config: &RoundTimeoutConfig, timeout: Duration) -> Duration {
+        timeout.min(config.ceiling).max(config.floor)
+    }
+
+    fn current(&self) -> Duration {
+        match self.srtt {
+            Some(srtt) => Self::clamp(
+                &self.config,
+                (srtt + self.rttvar * 4) * self.config.rtt_multiple.max(1),
+            ),
+            None => Self::clamp(
+                &self.config,
+                Duration::from_millis(GROUP_DEFAULT_CONSENSUS_INTERVAL),
+            ),
+        }
+    }
+}
+
+// the timeout of the consensus round, adaptive to the RTT measured among the members,
+// so the WAN groups will not change the view spuriously, and the LAN groups keep responsive.
+// the RTT is sampled by each member from the vote sent to the next leader to the block with the QC
+// for the voted block received from it.
+#[derive(Clone)]
+pub struct RoundTimeout(Arc<Mutex<RoundTimeoutRaw>>);
+
+impl RoundTimeout {
+    pub fn new(config: RoundTimeoutConfig) -> Self {
+        Self(Arc::new(Mutex::new(RoundTimeoutRaw {
+            config,
+            srtt: None,
+            rttvar: Duration::ZERO,
+            sample_count: 0,
+        })))
+    }
+
+    pub fn config(&self) -> RoundTimeoutConfig {
+        self.0.lock().unwrap().config.clone()
+    }
+
+    pub fn set_config(&self, config: RoundTimeoutConfig) {
+        let mut raw = self.0.lock().unwrap();
+        log::info!(
+            "round timeout config changed: {:?} -> {:?}",
+            raw.config,
+            config
+        );
+        raw.config = config;
+    }
+
+    // the timeout used for the next round
+    pub fn current(&self) -> Duration {
+        self.0.lock().unwrap().current()
+    }
+
+    // the smoothed RTT, None if there is no sample yet
+    pub fn srtt(&self) -> Option<Duration> {
+        self.0.lock().unwrap().srtt
+    }
+
+    pub fn rttvar(&self) -> Duration {
+        self.0.lock().unwrap().rttvar
+    }
+
+    pub fn sample_count(&self) -> u64 {
+        self.0.lock().unwrap().sample_count
+    }
+
+    pub(crate) fn on_rtt(&self, rtt: Duration) {
+        let mut raw = self.0.lock().unwrap();
+        // the sample larger than ceiling is meaningless, and will make the estimation slow to recover
+        let rtt = rtt.min(raw.config.ceiling);
+        match raw.srtt {
+            Some(srtt) => {
+                let delta = if srtt > rtt { srtt - rtt } else { rtt - srtt };
+                raw.rttvar = raw.rttvar * 3 / 4 + delta / 4;
+                raw.srtt = Some(srtt * 7 / 8 + rtt / 8);
+            }
+            None => {
+                raw.srtt = Some(rtt);
+                raw.rttvar = rtt / 2;
+            }
+        }
+        raw.sample_count += 1;
+
+        log::debug!(
+            "round timeout rtt sampled: {:?}, srtt: {:?}, rttvar: {:?}, timeout: {:?}",
+            rtt,
+            raw.srtt,
+            raw.rttvar,
+            raw.current()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_round_timeout {
+    use super::*;
+
+    #[test]
+    fn test_adaptive() {
+        let timeout = RoundTimeout::new(RoundTimeoutConfig {
+            floor: Duration::from_millis(500),
+            ceiling: Duration::from_secs(30),
+            rtt_multiple: 2,
+        });
+        // no sample yet
+        assert_eq!(
+            timeout.current(),
+            Duration::from_millis(GROUP_DEFAULT_CONSENSUS_INTERVAL)
+        );
+
+        // LAN
+        for _ in 0..100 {
+            timeout.on_rtt(Duration::from_millis(2));
+        }
+        assert_eq!(timeout.current(), Duration::from_millis(500));
+
+        // WAN
+        for _ in 0..100 {
+            timeout.on_rtt(Duration::from_millis(800));
+        }
+        let current = timeout.current();
+        assert!(current >= Duration::from_millis(1600));
+        assert!(current < Duration::from_secs(30));
+
+        // very poor network
+        for _ in 0..100 {
+            timeout.on_rtt(Duration::from_secs(100));
+        }
+        assert_eq!(timeout.current(), Duration::from_secs(30));
+        assert_eq!(timeout.sample_count(), 300);
+
+        let mut config = timeout.config();
+        config.ceiling = Duration::from_secs(10);
+        timeout.set_config(config);
+        assert_eq!(timeout.current(), Duration::from_secs(10));
+    }
+}
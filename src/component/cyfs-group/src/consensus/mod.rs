@@ -4,5 +4,7 @@ mod synchronizer;
 mod vote;
 
 pub(crate) use hotstuff::*;
+pub use hotstuff::{RoundTimeout, RoundTimeoutConfig};
 pub(crate) use proposal::*;
+pub use proposal::{GroupProposalStatus, ProposalAdmission, ProposalAdmissionConfig};
 pub(crate) use vote::*;
@@ -0,0 +1,181 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use cyfs_base::{
+    BuckyError, BuckyErrorCode, BuckyResult, NamedObject, ObjectId, OwnerObjectDesc,
+};
+use cyfs_core::GroupProposal;
+
+use crate::{
+    RPathEventNotifier, PROPOSAL_ADMISSION_BURST, PROPOSAL_ADMISSION_MAX_MEMBERS,
+    PROPOSAL_ADMISSION_PERIOD, PROPOSAL_ADMISSION_RATE,
+};
+
+#[derive(Clone, Debug)]
+pub struct ProposalAdmissionConfig {
+    // max proposals accepted from one member in `period`, 0 for unlimited
+    pub rate: u32,
+    pub period: Duration,
+    // max proposals accepted from one member at once
+    pub burst: u32,
+    // reject the proposals without owner, they share one quota otherwise
+    pub reject_anonymous: bool,
+    // ask the dec to verify the fee of the proposal by `RPathDelegate::on_admit`
+    pub verify_fee: bool,
+}
+
+impl Default for ProposalAdmissionConfig {
+    fn default() -> Self {
+        Self {
+            rate: PROPOSAL_ADMISSION_RATE,
+            period: PROPOSAL_ADMISSION_PERIOD,
+            burst: PROPOSAL_ADMISSION_BURST,
+            reject_anonymous: false,
+            verify_fee: false,
+        }
+    }
+}
+
+struct MemberQuota {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+struct ProposalAdmissionRaw {
+    config: ProposalAdmissionConfig,
+    quotas: HashMap<Option<ObjectId>, MemberQuota>,
+}
+
+impl ProposalAdmissionRaw {
+    fn refill(config: &ProposalAdmissionConfig, quota: &mut MemberQuota, now: Instant) {
+        let elapsed = now.duration_since(quota.last_refill).as_secs_f64();
+        let per_sec = config.rate as f64 / config.period.as_secs_f64().max(0.001);
+        quota.tokens = (quota.tokens + elapsed * per_sec).min(config.burst.max(1) as f64);
+        quota.last_refill = now;
+    }
+
+    // drop the members whose quota has been full, they are same as the new members
+    fn shrink(&mut self, now: Instant) {
+        let config = &self.config;
+        self.quotas.retain(|_, quota| {
+            Self::refill(config, quota, now);
+            quota.tokens < config.burst.max(1) as f64
+        });
+    }
+}
+
+// admission control of the proposals pushed to the rpath, applied before the proposal queued
+// for consensus, so the open groups are not spammable trivially.
+#[derive(Clone)]
+pub struct ProposalAdmission(Arc<Mutex<ProposalAdmissionRaw>>);
+
+impl ProposalAdmission {
+    pub fn new(config: ProposalAdmissionConfig) -> Self {
+        Self(Arc::new(Mutex::new(ProposalAdmissionRaw {
+            config,
+            quotas: HashMap::new(),
+        })))
+    }
+
+    pub fn config(&self) -> ProposalAdmissionConfig {
+        self.0.lock().unwrap().config.clone()
+    }
+
+    pub fn set_config(&self, config: ProposalAdmissionConfig) {
+        let mut raw = self.0.lock().unwrap();
+        log::info!(
+            "proposal admission config changed: {:?} -> {:?}",
+            raw.config,
+            config
+        );
+        raw.config = config;
+        raw.quotas.clear();
+    }
+
+    pub fn check_rate(&self, member: Option<&ObjectId>) -> BuckyResult<()> {
+        let mut raw = self.0.lock().unwrap();
+        if member.is_none() && raw.config.reject_anonymous {
+            let msg = "proposal without owner is not allowed";
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        if raw.config.rate == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if raw.quotas.len() >= PROPOSAL_ADMISSION_MAX_MEMBERS {
+            raw.shrink(now);
+        }
+
+        let raw = &mut *raw;
+        let config = &raw.config;
+        let quota = raw
+            .quotas
+            .entry(member.cloned())
+            .or_insert_with(|| MemberQuota {
+                tokens: config.burst.max(1) as f64,
+                last_refill: now,
+            });
+        ProposalAdmissionRaw::refill(config, quota, now);
+
+        if quota.tokens < 1.0 {
+            let msg = format!(
+                "too many proposals from {:?}, limit: {} per {:?}",
+                member, config.rate, config.period
+            );
+            log::warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
+
+        quota.tokens -= 1.0;
+        Ok(())
+    }
+
+    pub(crate) async fn admit(
+        &self,
+        proposal: &GroupProposal,
+        event_notifier: &RPathEventNotifier,
+    ) -> BuckyResult<()> {
+        self.check_rate(proposal.desc().owner().as_ref())?;
+
+        if self.config().verify_fee {
+            event_notifier.on_admit(proposal.clone()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_admission {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        let admission = ProposalAdmission::new(ProposalAdmissionConfig {
+            rate: 1,
+            period: Duration::from_secs(3600),
+            burst: 2,
+            reject_anonymous: true,
+            verify_fee: false,
+        });
+
+        let member1 = ObjectId::default();
+        assert!(admission.check_rate(Some(&member1)).is_ok());
+        assert!(admission.check_rate(Some(&member1)).is_ok());
+        assert!(admission.check_rate(Some(&member1)).is_err());
+        assert!(admission.check_rate(None).is_err());
+
+        let mut config = admission.config();
+        config.rate = 0;
+        admission.set_config(config);
+        for _ in 0..10 {
+            assert!(admission.check_rate(Some(&member1)).is_ok());
+        }
+    }
+}
@@ -1,3 +1,7 @@
+mod admission;
 mod pending_proposal_mgr;
+mod timing;
 
+pub use admission::*;
 pub use pending_proposal_mgr::*;
+pub(crate) use timing::*;
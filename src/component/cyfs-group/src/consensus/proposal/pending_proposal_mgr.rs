@@ -1,16 +1,39 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
 
 use async_std::channel::{Receiver, Sender};
 use cyfs_base::{BuckyError, BuckyErrorCode, BuckyResult, NamedObject, ObjectDesc, ObjectId};
 use cyfs_core::GroupProposal;
 use futures::FutureExt;
 
-use crate::CHANNEL_CAPACITY;
+use crate::{
+    helper::Timer, CHANNEL_CAPACITY, PROPOSAL_MAX_TIMEOUT, PROPOSAL_SCHEDULE_CHECK_INTERVAL,
+};
+
+use super::{check_proposal_timing, ProposalTiming};
+
+// the status of the proposal pushed to this node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupProposalStatus {
+    // waiting to be packed into a block
+    Pending,
+    // waiting for the effective beginning time(bucky time)
+    Scheduled(u64),
+    // dropped for not committed before the effective ending time
+    Expired,
+    // removed from the pending pool after committed or failed, the result is replied to the proposer
+    Finished,
+    // never received or forgotten
+    Unknown,
+}
 
 pub enum ProposalConsumeMessage {
     Query(Sender<Vec<GroupProposal>>),
     Wait(Sender<()>),
     Remove(Vec<ObjectId>),
+    Status(ObjectId, Sender<GroupProposalStatus>),
 }
 
 pub struct PendingProposalMgr {
@@ -26,27 +49,38 @@ impl PendingProposalMgr {
     pub fn new() -> (PendingProposalHandler, PendingProposalConsumer) {
         let (tx_product, rx_product) = async_std::channel::bounded(CHANNEL_CAPACITY);
         let (tx_consume, rx_consume) = async_std::channel::bounded(CHANNEL_CAPACITY);
+        let (tx_expired, rx_expired) = async_std::channel::bounded(CHANNEL_CAPACITY);
 
         async_std::task::spawn(async move {
             PendingProposalMgrRunner {
                 rx_product,
                 rx_consume,
+                tx_expired,
                 buffer: HashMap::new(),
                 tx_proposal_waker: None,
+                finished: HashMap::new(),
+                timer: Timer::new(PROPOSAL_SCHEDULE_CHECK_INTERVAL),
             }
             .run()
             .await
         });
 
         (
-            PendingProposalHandler { tx_product },
-            PendingProposalConsumer { tx_consume },
+            PendingProposalHandler {
+                tx_product,
+                tx_consume: tx_consume.clone(),
+            },
+            PendingProposalConsumer {
+                tx_consume,
+                rx_expired,
+            },
         )
     }
 }
 
 pub struct PendingProposalHandler {
     tx_product: Sender<GroupProposal>,
+    tx_consume: Sender<ProposalConsumeMessage>,
 }
 
 impl PendingProposalHandler {
@@ -59,10 +93,27 @@ impl PendingProposalHandler {
             BuckyError::new(BuckyErrorCode::ErrorState, "channel closed")
         })
     }
+
+    pub async fn proposal_status(&self, proposal_id: ObjectId) -> BuckyResult<GroupProposalStatus> {
+        let (sender, receiver) = async_std::channel::bounded(1);
+        self.tx_consume
+            .send(ProposalConsumeMessage::Status(proposal_id, sender))
+            .await
+            .map_err(|e| {
+                log::error!("[pending_proposal_mgr] send message(status) failed: {}", e);
+                BuckyError::new(BuckyErrorCode::ErrorState, "channel closed")
+            })?;
+
+        receiver.recv().await.map_err(|e| {
+            log::error!("[pending_proposal_mgr] recv message(status) failed: {}", e);
+            BuckyError::new(BuckyErrorCode::ErrorState, "channel closed")
+        })
+    }
 }
 
 pub struct PendingProposalConsumer {
     tx_consume: Sender<ProposalConsumeMessage>,
+    rx_expired: Receiver<Vec<GroupProposal>>,
 }
 
 impl PendingProposalConsumer {
@@ -103,15 +154,28 @@ impl PendingProposalConsumer {
                 BuckyError::new(BuckyErrorCode::ErrorState, "channel closed")
             })
     }
+
+    // the proposals dropped from the pending pool for expired, should notify the proposers
+    pub async fn wait_expired(&self) -> BuckyResult<Vec<GroupProposal>> {
+        self.rx_expired.recv().await.map_err(|e| {
+            log::error!("[pending_proposal_mgr] recv expired proposals failed: {}", e);
+            BuckyError::new(BuckyErrorCode::ErrorState, "channel closed")
+        })
+    }
 }
 
 struct PendingProposalMgrRunner {
     rx_product: Receiver<GroupProposal>,
     rx_consume: Receiver<ProposalConsumeMessage>,
+    tx_expired: Sender<Vec<GroupProposal>>,
     tx_proposal_waker: Option<Sender<()>>,
 
     // TODO: 需要设计一个结构便于按时间或数量拆分
     buffer: HashMap<ObjectId, GroupProposal>,
+
+    // the proposals removed from the buffer recently, for status query
+    finished: HashMap<ObjectId, (GroupProposalStatus, Instant)>,
+    timer: Timer,
 }
 
 impl PendingProposalMgrRunner {
@@ -119,19 +183,96 @@ impl PendingProposalMgrRunner {
         self.buffer.iter().map(|(_, p)| p.clone()).collect()
     }
 
+    // the scheduled proposals will not wake the waiter until the effective beginning time
+    fn has_ready_proposal(&self) -> bool {
+        let now = SystemTime::now();
+        self.buffer.values().any(|proposal| {
+            match check_proposal_timing(proposal, now, Duration::ZERO) {
+                ProposalTiming::Scheduled(_) => false,
+                _ => true,
+            }
+        })
+    }
+
+    async fn wake_waiter(&mut self) {
+        if !self.has_ready_proposal() {
+            return;
+        }
+
+        if let Some(waker) = self.tx_proposal_waker.take() {
+            if let Err(err) = waker.send(()).await {
+                log::warn!("[pending_proposal_mgr] wake proposal waiter failed, err: {:?}", err);
+            }
+        }
+    }
+
+    fn handle_query_status(&self, proposal_id: &ObjectId) -> GroupProposalStatus {
+        match self.buffer.get(proposal_id) {
+            Some(proposal) => {
+                match check_proposal_timing(proposal, SystemTime::now(), Duration::ZERO) {
+                    ProposalTiming::Scheduled(begining) => GroupProposalStatus::Scheduled(begining),
+                    _ => GroupProposalStatus::Pending,
+                }
+            }
+            None => self
+                .finished
+                .get(proposal_id)
+                .map_or(GroupProposalStatus::Unknown, |(status, _)| *status),
+        }
+    }
+
+    async fn check_expired(&mut self) {
+        let now = SystemTime::now();
+        let expired_ids: Vec<ObjectId> = self
+            .buffer
+            .iter()
+            .filter(|(_, proposal)| {
+                check_proposal_timing(proposal, now, Duration::ZERO) == ProposalTiming::Expired
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let checked_at = Instant::now();
+        self.finished
+            .retain(|_, (_, at)| checked_at.duration_since(*at) < PROPOSAL_MAX_TIMEOUT);
+
+        if expired_ids.len() > 0 {
+            log::warn!(
+                "[pending_proposal_mgr] drop expired proposals {:?}",
+                expired_ids
+            );
+
+            let mut expired = vec![];
+            for id in expired_ids {
+                if let Some(proposal) = self.buffer.remove(&id) {
+                    self.finished
+                        .insert(id, (GroupProposalStatus::Expired, checked_at));
+                    expired.push(proposal);
+                }
+            }
+
+            if let Err(err) = self.tx_expired.send(expired).await {
+                log::warn!(
+                    "[pending_proposal_mgr] notify expired proposals failed, err: {:?}",
+                    err
+                );
+            }
+        }
+
+        // some scheduled proposals maybe ready now
+        self.wake_waiter().await;
+    }
+
     async fn run(&mut self) {
         loop {
             futures::select! {
                 proposal = self.rx_product.recv().fuse() => {
                     if let Ok(proposal) = proposal {
                         self.buffer.insert(proposal.desc().object_id(), proposal);
-                        if let Some(waker) = self.tx_proposal_waker.take() {
-                            if let Err(err) = waker.send(()).await {
-                                log::warn!("[pending_proposal_mgr] wake proposal waiter when new proposal received failed, err: {:?}", err);
-                            }
-                        }
+                        self.wake_waiter().await;
                     }
                 },
+                () = self.timer.wait_next().fuse() => self.check_expired().await,
                 message = self.rx_consume.recv().fuse() => {
                     if let Ok(message) = message {
                        match message {
@@ -142,17 +283,21 @@ impl PendingProposalMgrRunner {
                                 }
                             },
                             ProposalConsumeMessage::Remove(proposal_ids) => {
-                                for id in &proposal_ids {
-                                    self.buffer.remove(id);
+                                let now = Instant::now();
+                                for id in proposal_ids {
+                                    if self.buffer.remove(&id).is_some() {
+                                        self.finished.insert(id, (GroupProposalStatus::Finished, now));
+                                    }
                                 }
                             },
                             ProposalConsumeMessage::Wait(tx_waker) => {
-                                if self.buffer.len() > 0 {
-                                    if let Err(err) = tx_waker.send(()).await {
-                                        log::warn!("[pending_proposal_mgr] wake proposal waiter failed, err: {:?}", err);
-                                    }
-                                } else {
-                                    self.tx_proposal_waker = Some(tx_waker)
+                                self.tx_proposal_waker = Some(tx_waker);
+                                self.wake_waiter().await;
+                            }
+                            ProposalConsumeMessage::Status(proposal_id, sender) => {
+                                let status = self.handle_query_status(&proposal_id);
+                                if let Err(err) = sender.send(status).await {
+                                    log::warn!("[pending_proposal_mgr] return proposal status failed, err: {:?}", err);
                                 }
                             }
                         }
@@ -0,0 +1,154 @@
+use std::time::{Duration, SystemTime};
+
+use cyfs_base::{bucky_time_to_system_time, NamedObject, ObjectDesc};
+use cyfs_core::{GroupProposal, GroupProposalObject};
+
+use crate::{PROPOSAL_MAX_SCHEDULE_DELAY, TIME_PRECISION};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ProposalTiming {
+    Ready,
+    // wait for the effective beginning time(bucky time)
+    Scheduled(u64),
+    // the effective ending time has passed
+    Expired,
+    // the create time is too far from now, or the schedule is out of range
+    ErrorTimestamp,
+}
+
+fn calc_time_delta(t1: SystemTime, t2: SystemTime) -> Duration {
+    t1.duration_since(t2).or(t2.duration_since(t1)).unwrap()
+}
+
+// check the proposal can be packed into the block at `now`.
+// `ending_tolerance` is for the block verifier, the block timestamp is later than the leader checked.
+//
+// the scheduled proposal is allowed to wait in the pending pool for `PROPOSAL_MAX_SCHEDULE_DELAY`,
+// it's less than `PROPOSAL_MAX_TIMEOUT`, so the finished proposals will not be replayed.
+pub(crate) fn check_proposal_timing(
+    proposal: &GroupProposal,
+    now: SystemTime,
+    ending_tolerance: Duration,
+) -> ProposalTiming {
+    let create_time = bucky_time_to_system_time(proposal.desc().create_time());
+
+    if let Some(ending) = proposal.effective_ending() {
+        let ending = bucky_time_to_system_time(ending);
+        if now >= ending.checked_add(ending_tolerance).unwrap_or(ending) {
+            return ProposalTiming::Expired;
+        }
+    }
+
+    match proposal.effective_begining() {
+        None => {
+            if calc_time_delta(now, create_time) > TIME_PRECISION {
+                ProposalTiming::ErrorTimestamp
+            } else {
+                ProposalTiming::Ready
+            }
+        }
+        Some(begining) => {
+            let begining_time = bucky_time_to_system_time(begining);
+            if let Ok(ahead) = create_time.duration_since(now) {
+                if ahead > TIME_PRECISION {
+                    return ProposalTiming::ErrorTimestamp;
+                }
+            }
+
+            match begining_time.duration_since(create_time) {
+                Ok(delay) if delay > PROPOSAL_MAX_SCHEDULE_DELAY => {
+                    return ProposalTiming::ErrorTimestamp;
+                }
+                _ => {}
+            }
+
+            if now < begining_time {
+                return ProposalTiming::Scheduled(begining);
+            }
+
+            match now.duration_since(create_time) {
+                Ok(elapsed) if elapsed > PROPOSAL_MAX_SCHEDULE_DELAY + TIME_PRECISION => {
+                    ProposalTiming::Expired
+                }
+                _ => ProposalTiming::Ready,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_timing {
+    use super::*;
+    use cyfs_base::{system_time_to_bucky_time, ObjectId};
+    use cyfs_core::GroupRPath;
+
+    fn make_proposal(
+        create_time: SystemTime,
+        begining: Option<SystemTime>,
+        ending: Option<SystemTime>,
+    ) -> GroupProposal {
+        GroupProposal::create(
+            GroupRPath::new(ObjectId::default(), ObjectId::default(), "test".to_string()),
+            "test".to_string(),
+            None,
+            None,
+            Some(system_time_to_bucky_time(&create_time)),
+            ObjectId::default(),
+            None,
+            begining.map(|t| system_time_to_bucky_time(&t)),
+            ending.map(|t| system_time_to_bucky_time(&t)),
+        )
+        .build()
+    }
+
+    #[test]
+    fn test_check_timing() {
+        let now = SystemTime::now();
+        let minute = Duration::from_secs(60);
+
+        let proposal = make_proposal(now, None, None);
+        assert_eq!(
+            check_proposal_timing(&proposal, now, Duration::ZERO),
+            ProposalTiming::Ready
+        );
+        assert_eq!(
+            check_proposal_timing(&proposal, now + minute * 2, Duration::ZERO),
+            ProposalTiming::ErrorTimestamp
+        );
+
+        let proposal = make_proposal(now, None, Some(now + Duration::from_secs(10)));
+        assert_eq!(
+            check_proposal_timing(&proposal, now + Duration::from_secs(20), Duration::ZERO),
+            ProposalTiming::Expired
+        );
+        assert_eq!(
+            check_proposal_timing(&proposal, now + Duration::from_secs(20), TIME_PRECISION),
+            ProposalTiming::Ready
+        );
+
+        let begining = now + minute * 10;
+        let proposal = make_proposal(now, Some(begining), None);
+        assert_eq!(
+            check_proposal_timing(&proposal, now, Duration::ZERO),
+            ProposalTiming::Scheduled(system_time_to_bucky_time(&begining))
+        );
+        assert_eq!(
+            check_proposal_timing(&proposal, begining, Duration::ZERO),
+            ProposalTiming::Ready
+        );
+        assert_eq!(
+            check_proposal_timing(
+                &proposal,
+                now + PROPOSAL_MAX_SCHEDULE_DELAY + minute * 2,
+                Duration::ZERO
+            ),
+            ProposalTiming::Expired
+        );
+
+        let proposal = make_proposal(now, Some(now + PROPOSAL_MAX_SCHEDULE_DELAY * 2), None);
+        assert_eq!(
+            check_proposal_timing(&proposal, now, Duration::ZERO),
+            ProposalTiming::ErrorTimestamp
+        );
+    }
+}
@@ -15,12 +15,26 @@ pub const HOTSTUFF_TIMEOUT_DEFAULT: u64 = 5000;
 pub const CHANNEL_CAPACITY: usize = 1000;
 pub const TIME_PRECISION: Duration = Duration::from_millis(60000);
 pub const PROPOSAL_MAX_TIMEOUT: Duration = Duration::from_secs(3600);
+pub const PROPOSAL_MAX_SCHEDULE_DELAY: Duration = Duration::from_secs(1800); // the effective beginning should be less than PROPOSAL_MAX_TIMEOUT after created.
+pub const PROPOSAL_SCHEDULE_CHECK_INTERVAL: u64 = 1000; // check the scheduled and expired proposals every 1000 ms.
 pub const SYNCHRONIZER_TIMEOUT: u64 = 500;
 pub const SYNCHRONIZER_TRY_TIMES: usize = 3;
 pub const CLIENT_POLL_TIMEOUT: Duration = Duration::from_millis(5000);
 pub const STATE_NOTIFY_COUNT_PER_ROUND: usize = 8;
 pub const NET_PROTOCOL_VPORT: u16 = 2048;
+pub const PACKAGE_MAX_LEN: usize = 1024 * 128; // the large package will be splitted or dropped, the datagram is limited to 255 fragments.
+pub const STATE_PARTS_TIMEOUT: Duration = Duration::from_secs(60); // drop the incomplete parts of the verifiable state.
+pub const QUERY_BLOCKS_PAGE_SIZE: u64 = 32; // the max count of blocks responded for a query.
+pub const QUERY_BLOCKS_PAGE_LEN: usize = PACKAGE_MAX_LEN / 2; // stop appending blocks to the page when the encoded length exceeded.
 pub const MEMORY_CACHE_SIZE: usize = 1024;
 pub const MEMORY_CACHE_DURATION: Duration = Duration::from_secs(300);
 pub const GROUP_DEFAULT_CONSENSUS_INTERVAL: u64 = 5000; // default 5000 ms
 pub const BLOCK_COUNT_REST_TO_SYNC: u64 = 8; // the node will stop most work, and synchronize the lost blocks.
+pub const CHECKPOINT_PUBLISH_INTERVAL: Duration = Duration::from_secs(600); // publish the checkpoint to meta chain every 10 minutes.
+pub const PROPOSAL_ADMISSION_RATE: u32 = 60; // default 60 proposals per member every period.
+pub const PROPOSAL_ADMISSION_PERIOD: Duration = Duration::from_secs(60);
+pub const PROPOSAL_ADMISSION_BURST: u32 = 20;
+pub const PROPOSAL_ADMISSION_MAX_MEMBERS: usize = 4096; // shrink the quotas of idle members when exceeded.
+pub const HOTSTUFF_TIMEOUT_FLOOR: Duration = Duration::from_millis(2000); // the round timeout adaptive to the RTT is not less than it by default.
+pub const HOTSTUFF_TIMEOUT_CEILING: Duration = Duration::from_secs(60);
+pub const HOTSTUFF_TIMEOUT_RTT_MULTIPLE: u32 = 4;
@@ -7,21 +7,25 @@ use cyfs_base::{
 };
 use cyfs_core::{GroupConsensusBlock, GroupProposal};
 use cyfs_group_lib::{
-    ExecuteResult, GroupCommand, GroupCommandCommited, GroupCommandExecute,
+    ExecuteResult, GroupCommand, GroupCommandAdmit, GroupCommandCommited, GroupCommandExecute,
     GroupCommandExecuteResult, GroupCommandVerify,
 };
 use cyfs_lib::NONObjectInfo;
 
-use crate::NONDriverHelper;
+use crate::{GroupCheckpointPublisher, NONDriverHelper};
 
 #[derive(Clone)]
 pub(crate) struct RPathEventNotifier {
     non_driver: NONDriverHelper,
+    checkpoint: Option<GroupCheckpointPublisher>,
 }
 
 impl RPathEventNotifier {
-    pub fn new(driver: NONDriverHelper) -> Self {
-        Self { non_driver: driver }
+    pub fn new(driver: NONDriverHelper, checkpoint: Option<GroupCheckpointPublisher>) -> Self {
+        Self {
+            non_driver: driver,
+            checkpoint,
+        }
     }
 
     pub async fn on_execute(
@@ -107,11 +111,40 @@ impl RPathEventNotifier {
         Ok(())
     }
 
+    pub async fn on_admit(&self, proposal: GroupProposal) -> BuckyResult<()> {
+        let cmd = GroupCommandAdmit { proposal };
+
+        let cmd = GroupCommand::from(cmd);
+        let object_raw_buf = cmd.to_vec()?;
+        let any_obj = cyfs_base::AnyNamedObject::Core(TypelessCoreObject::clone_from_slice(
+            object_raw_buf.as_slice(),
+        )?);
+
+        let result = self
+            .non_driver
+            .post_object(
+                NONObjectInfo {
+                    object_id: cmd.desc().object_id(),
+                    object_raw: object_raw_buf,
+                    object: Some(Arc::new(any_obj)),
+                },
+                None,
+            )
+            .await?;
+
+        assert!(result.is_none());
+        Ok(())
+    }
+
     pub async fn on_commited(
         &self,
         prev_state_id: Option<ObjectId>,
         block: GroupConsensusBlock,
     ) {
+        if let Some(checkpoint) = self.checkpoint.as_ref() {
+            let _ = checkpoint.on_commited(&block);
+        }
+
         let cmd = GroupCommandCommited {
             prev_state_id,
             block,
@@ -2,8 +2,8 @@ use std::{collections::HashMap, sync::Arc};
 
 use async_std::sync::RwLock;
 use cyfs_base::{
-    BuckyErrorCode, BuckyResult, GroupId, NamedObject, ObjectDesc, ObjectId, OwnerObjectDesc,
-    RawConvertTo, RawFrom, RsaCPUObjectSigner, TypelessCoreObject,
+    BuckyError, BuckyErrorCode, BuckyResult, GroupId, NamedObject, ObjectDesc, ObjectId,
+    OwnerObjectDesc, RawConvertTo, RawFrom, RsaCPUObjectSigner, TypelessCoreObject,
 };
 use cyfs_bdt::{DatagramTunnelGuard, StackGuard};
 use cyfs_core::{DecAppId, GroupConsensusBlock, GroupConsensusBlockObject, GroupRPath};
@@ -13,8 +13,10 @@ use cyfs_meta_lib::{MetaClient, MetaMinerTarget};
 
 use crate::{
     storage::{GroupShellManager, GroupStorage},
-    HotstuffMessage, HotstuffPackage, NONDriver, NONDriverHelper, RPathClient, RPathEventNotifier,
-    RPathService, NET_PROTOCOL_VPORT,
+    GroupCheckpointConfig, GroupCheckpointPublisher, GroupCheckpointVerifier,
+    GroupCheckpointVerifyResult, HotstuffMessage, HotstuffPackage, NONDriver, NONDriverHelper,
+    ProposalAdmissionConfig, RPathClient, RPathEventNotifier, RPathService, RoundTimeoutConfig,
+    NET_PROTOCOL_VPORT,
 };
 
 type ServiceByRPath = HashMap<String, RPathService>;
@@ -38,6 +40,10 @@ struct LocalInfo {
     bdt_stack: StackGuard,
     global_state_mgr: GlobalStateManagerRawProcessorRef,
     meta_client: Arc<MetaClient>,
+    checkpoint_config: std::sync::RwLock<Option<GroupCheckpointConfig>>,
+    checkpoint_publishers: std::sync::Mutex<Vec<GroupCheckpointPublisher>>,
+    admission_config: std::sync::RwLock<ProposalAdmissionConfig>,
+    round_timeout_config: std::sync::RwLock<RoundTimeoutConfig>,
 }
 
 #[derive(Clone)]
@@ -61,6 +67,10 @@ impl GroupManager {
             bdt_stack,
             global_state_mgr,
             meta_client: Arc::new(metaclient),
+            checkpoint_config: std::sync::RwLock::new(None),
+            checkpoint_publishers: std::sync::Mutex::new(vec![]),
+            admission_config: std::sync::RwLock::new(ProposalAdmissionConfig::default()),
+            round_timeout_config: std::sync::RwLock::new(RoundTimeoutConfig::default()),
         };
 
         let raw = GroupRPathMgrRaw {
@@ -169,6 +179,78 @@ impl GroupManager {
         }
     }
 
+    // publish the checkpoints of the rpath services created later to the meta chain
+    pub fn enable_checkpoint(&self, config: GroupCheckpointConfig) {
+        *self.local_info().checkpoint_config.write().unwrap() = Some(config);
+    }
+
+    // stop publishing the checkpoints of all the rpath services
+    pub fn disable_checkpoint(&self) {
+        let local_info = self.local_info();
+        *local_info.checkpoint_config.write().unwrap() = None;
+
+        let publishers = std::mem::take(&mut *local_info.checkpoint_publishers.lock().unwrap());
+        for publisher in publishers {
+            publisher.stop();
+        }
+    }
+
+    // the admission config of the rpath services created later,
+    // use `RPathService::admission` to change it for the rpath started already
+    pub fn set_proposal_admission(&self, config: ProposalAdmissionConfig) {
+        *self.local_info().admission_config.write().unwrap() = config;
+    }
+
+    // the round timeout config of the rpath services created later,
+    // use `RPathService::round_timeout` to change it for the rpath started already
+    pub fn set_round_timeout(&self, config: RoundTimeoutConfig) {
+        *self.local_info().round_timeout_config.write().unwrap() = config;
+    }
+
+    // verify the state synchronized by the client with the checkpoint on the meta chain
+    pub async fn verify_checkpoint(
+        &self,
+        group_id: &ObjectId,
+        dec_id: &ObjectId,
+        rpath: &str,
+    ) -> BuckyResult<GroupCheckpointVerifyResult> {
+        let client = self.rpath_client(group_id, dec_id, rpath).await?;
+        let header_block = client.header_block().await.ok_or_else(|| {
+            let msg = format!(
+                "verify checkpoint but no state synchronized, group: {}, dec_id: {}, rpath: {}",
+                group_id, dec_id, rpath
+            );
+            log::warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        let verifier = GroupCheckpointVerifier::new(self.local_info().meta_client.clone());
+        let anchor = match verifier.load_anchor(header_block.rpath()).await? {
+            Some(anchor) => anchor,
+            None => return Ok(GroupCheckpointVerifyResult::NoCheckpoint),
+        };
+
+        let non_driver = NONDriverHelper::new(
+            self.local_info().non_driver.clone(),
+            dec_id.clone(),
+            self.local_info()
+                .bdt_stack
+                .local_device_id()
+                .object_id()
+                .clone(),
+        );
+        let shell_mgr = self
+            .check_group_shell_mgr(group_id, non_driver.clone(), None)
+            .await?;
+        let group = shell_mgr
+            .get_group(group_id, Some(&anchor.checkpoint.group_shell_id), None)
+            .await?;
+        let signer = non_driver.get_device(&anchor.signer).await?;
+        GroupCheckpointVerifier::verify_anchor(&anchor, &group, &signer).await?;
+
+        GroupCheckpointVerifier::verify_with_checkpoint(&header_block, anchor.checkpoint)
+    }
+
     pub async fn set_sync_path(&self, _dec_id: &str, _path: String) -> BuckyResult<()> {
         unimplemented!()
     }
@@ -455,6 +537,79 @@ impl GroupManager {
                     )
                     .await;
             }
+            HotstuffPackage::VerifiableStatePart(sub_path, index, count, status) => {
+                let rpath = status.block_desc.content().rpath();
+                let client = self
+                    .rpath_client(rpath.group_id(), rpath.dec_id(), rpath.rpath())
+                    .await
+                    .map_err(|err| {
+                        log::error!(
+                            "new msg(VerifiableStatePart) received, and find rpath client failed, {:?}. local: {}, err: {:?}",
+                            rpath,
+                            self.local_info().bdt_stack.local_device_id(),
+                            err
+                        );
+                        err
+                    })?;
+                client
+                    .on_message(
+                        HotstuffMessage::VerifiableStatePart(sub_path, index, count, status),
+                        remote,
+                    )
+                    .await;
+            }
+            HotstuffPackage::QueryBlocks(target, min_height, max_height) => {
+                let rpath = target.check_rpath();
+                let service = self
+                    .find_rpath_service_inner(
+                        rpath.group_id(),
+                        rpath.dec_id(),
+                        rpath.rpath(),
+                        true,
+                        None,
+                        Some(&remote),
+                    )
+                    .await
+                    .map_err(|err| {
+                        log::error!(
+                            "new msg(QueryBlocks) received, and find rpath service failed, {:?}. local: {}, err: {:?}",
+                            rpath,
+                            self.local_info().bdt_stack.local_device_id(),
+                            err
+                        );
+                        err
+                    })?;
+                service
+                    .on_message(HotstuffMessage::QueryBlocks(min_height, max_height), remote)
+                    .await;
+            }
+            HotstuffPackage::VerifiableBlocks(min_height, result) => {
+                let rpath = result.as_ref().map_or_else(
+                    |(_, target)| target.check_rpath(),
+                    |(blocks, _)| blocks[0].rpath(),
+                );
+                let client = self
+                    .rpath_client(rpath.group_id(), rpath.dec_id(), rpath.rpath())
+                    .await
+                    .map_err(|err| {
+                        log::error!(
+                            "new msg(VerifiableBlocks) received, and find rpath client failed, {:?}. local: {}, err: {:?}",
+                            rpath,
+                            self.local_info().bdt_stack.local_device_id(),
+                            err
+                        );
+                        err
+                    })?;
+                client
+                    .on_message(
+                        HotstuffMessage::VerifiableBlocks(
+                            min_height,
+                            result.map_err(|(err, _)| err),
+                        ),
+                        remote,
+                    )
+                    .await;
+            }
         }
 
         Ok(())
@@ -586,16 +741,41 @@ impl GroupManager {
             match found {
                 std::collections::hash_map::Entry::Occupied(found) => Ok(found.get().clone()),
                 std::collections::hash_map::Entry::Vacant(entry) => {
+                    let rpath =
+                        GroupRPath::new(group_id.clone(), dec_id.clone(), rpath.to_string());
+                    let checkpoint_config = local_info.checkpoint_config.read().unwrap().clone();
+                    let admission_config = local_info.admission_config.read().unwrap().clone();
+                    let round_timeout_config =
+                        local_info.round_timeout_config.read().unwrap().clone();
+                    let checkpoint = checkpoint_config.map(|config| {
+                        let publisher = GroupCheckpointPublisher::new(
+                            rpath.clone(),
+                            config,
+                            local_info.meta_client.clone(),
+                            local_device_id.object_id().clone(),
+                            signer.clone(),
+                        );
+                        publisher.start();
+                        local_info
+                            .checkpoint_publishers
+                            .lock()
+                            .unwrap()
+                            .push(publisher.clone());
+                        publisher
+                    });
+
                     let service = RPathService::start(
                         local_id,
                         local_device_id.object_id().clone(),
-                        GroupRPath::new(group_id.clone(), dec_id.clone(), rpath.to_string()),
+                        rpath,
                         signer,
-                        RPathEventNotifier::new(non_driver.clone()),
+                        RPathEventNotifier::new(non_driver.clone(), checkpoint),
                         network_sender,
                         non_driver,
                         shell_mgr,
                         store,
+                        admission_config,
+                        round_timeout_config,
                     );
                     entry.insert(service.clone());
                     Ok(service)
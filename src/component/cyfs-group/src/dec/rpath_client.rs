@@ -1,10 +1,13 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use cyfs_base::{
     BuckyError, BuckyErrorCode, BuckyResult, GroupMemberScope, NamedObject, ObjectDesc, ObjectId,
     RawConvertTo,
 };
-use cyfs_core::{GroupConsensusBlock, GroupProposal, GroupProposalObject, GroupRPath};
+use cyfs_core::{
+    GroupConsensusBlock, GroupConsensusBlockObject, GroupProposal, GroupProposalObject, GroupRPath,
+    HotstuffBlockQC,
+};
 use cyfs_lib::{GlobalStateRawProcessorRef, NONObjectInfo};
 use rand::Rng;
 
@@ -22,6 +25,7 @@ struct RPathClientRaw {
     network_sender: crate::network::Sender,
     state_sync: DecStateSynchronizer,
     state_requestor: DecStateRequestor,
+    dec_store: DecStorage,
 }
 
 #[derive(Clone)]
@@ -63,6 +67,7 @@ impl RPathClient {
         );
 
         let raw = RPathClientRaw {
+            dec_store,
             rpath,
             non_driver,
             network_sender,
@@ -217,8 +222,128 @@ impl RPathClient {
         Err(err)
     }
 
-    pub async fn get_block(&self, _height: Option<u64>) -> BuckyResult<GroupConsensusBlock> {
-        unimplemented!()
+    // the header block synchronized from the group
+    pub async fn header_block(&self) -> Option<GroupConsensusBlock> {
+        self.0
+            .dec_store
+            .cur_state()
+            .await
+            .map(|state| state.header_block)
+    }
+
+    // the header block is returned if the height is None
+    pub async fn get_block(&self, height: Option<u64>) -> BuckyResult<GroupConsensusBlock> {
+        let height = match height {
+            Some(height) => height,
+            None => {
+                return self.header_block().await.ok_or_else(|| {
+                    BuckyError::new(BuckyErrorCode::NotFound, "no header block synchronized")
+                })
+            }
+        };
+
+        self.get_blocks(height..height + 1)
+            .await?
+            .pop()
+            .map(|(block, _)| block)
+            .ok_or_else(|| {
+                BuckyError::new(
+                    BuckyErrorCode::NotFound,
+                    format!("block at height {} is not committed", height),
+                )
+            })
+    }
+
+    // get the committed blocks in the range with the qc of each block from the members,
+    // the chain is verified locally, so it is safe to get the history from any member.
+    // the result will be shorter than the range if the tail blocks are not committed.
+    pub async fn get_blocks(
+        &self,
+        range: Range<u64>,
+    ) -> BuckyResult<Vec<(GroupConsensusBlock, HotstuffBlockQC)>> {
+        let mut blocks: Vec<(GroupConsensusBlock, HotstuffBlockQC)> = vec![];
+        let mut next_height = range.start;
+
+        while next_height < range.end {
+            let mut page = match self.query_blocks(next_height, range.end - 1).await {
+                Ok(page) => page,
+                Err(err) if err.code() == BuckyErrorCode::NotFound && blocks.len() > 0 => break,
+                Err(err) => return Err(err),
+            };
+            page.truncate((range.end - next_height) as usize);
+
+            if let Some((last_block, _)) = blocks.last() {
+                let first_block = &page[0].0;
+                if first_block.prev_block_id() != Some(last_block.block_id().object_id()) {
+                    let msg = format!(
+                        "the block {} is not linked to {}",
+                        first_block.block_id(),
+                        last_block.block_id()
+                    );
+                    log::warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+                }
+            }
+
+            next_height += page.len() as u64;
+            blocks.append(&mut page);
+        }
+
+        Ok(blocks)
+    }
+
+    // query one page of blocks from [min_height, max_height], it's verified by the requestor.
+    async fn query_blocks(
+        &self,
+        min_height: u64,
+        max_height: u64,
+    ) -> BuckyResult<Vec<(GroupConsensusBlock, HotstuffBlockQC)>> {
+        let group = self
+            .0
+            .shell_mgr
+            .get_group(self.0.rpath.group_id(), None, None)
+            .await?;
+
+        let members =
+            group.select_members_with_distance(&self.0.local_device_id, GroupMemberScope::All);
+        let req_msg = HotstuffMessage::QueryBlocks(min_height, max_height);
+
+        let waiter = self.0.state_requestor.wait_query_blocks(min_height).await;
+        let mut waiter_future = Some(waiter.wait());
+
+        let mut exe_result = None;
+
+        for member in members {
+            self.0
+                .network_sender
+                .post_message(req_msg.clone(), self.0.rpath.clone(), member)
+                .await;
+
+            match futures::future::select(
+                waiter_future.take().unwrap(),
+                Box::pin(async_std::task::sleep(CLIENT_POLL_TIMEOUT)),
+            )
+            .await
+            {
+                futures::future::Either::Left((result, _)) => match result {
+                    Err(_) => return Err(BuckyError::new(BuckyErrorCode::Unknown, "unknown")),
+                    Ok(result) => match result {
+                        Ok(result) => return Ok(result),
+                        Err(e) => {
+                            // try the next member, the remote may be behind or evil
+                            exe_result = Some(e);
+                            waiter_future = Some(waiter.wait());
+                        }
+                    },
+                },
+                futures::future::Either::Right((_, waiter)) => {
+                    waiter_future = Some(waiter);
+                }
+            }
+        }
+
+        let err = exe_result.map_or(BuckyError::new(BuckyErrorCode::Timeout, "timeout"), |e| e);
+        Err(err)
     }
 
     pub(crate) async fn on_message(&self, msg: HotstuffMessage, remote: ObjectId) {
@@ -253,6 +378,19 @@ impl RPathClient {
                     .on_verifiable_state(sub_path, result, remote)
                     .await
             }
+            HotstuffMessage::VerifiableStatePart(sub_path, index, count, status) => {
+                self.0
+                    .state_requestor
+                    .on_verifiable_state_part(sub_path, index, count, status, remote)
+                    .await
+            }
+            HotstuffMessage::QueryBlocks(_, _) => unreachable!(),
+            HotstuffMessage::VerifiableBlocks(min_height, result) => {
+                self.0
+                    .state_requestor
+                    .on_verifiable_blocks(min_height, result, remote)
+                    .await
+            }
         }
     }
 }
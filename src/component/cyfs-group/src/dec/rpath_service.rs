@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use cyfs_base::{
     AnyNamedObject, BuckyError, BuckyErrorCode, BuckyResult, NamedObject, ObjectDesc, ObjectId,
@@ -10,8 +13,9 @@ use cyfs_lib::NONObjectInfo;
 use crate::{
     network::NONDriverHelper,
     storage::{GroupShellManager, GroupStorage},
-    Committee, Hotstuff, HotstuffMessage, PendingProposalHandler, PendingProposalMgr,
-    RPathEventNotifier,
+    check_proposal_timing, Committee, GroupProposalStatus, Hotstuff, HotstuffMessage,
+    PendingProposalHandler, PendingProposalMgr, ProposalAdmission, ProposalAdmissionConfig,
+    ProposalTiming, RPathEventNotifier, RoundTimeout, RoundTimeoutConfig,
 };
 
 struct RPathServiceRaw {
@@ -21,6 +25,9 @@ struct RPathServiceRaw {
     pending_proposal_handle: PendingProposalHandler,
     hotstuff: Hotstuff,
     non_driver: NONDriverHelper,
+    event_notifier: RPathEventNotifier,
+    admission: ProposalAdmission,
+    round_timeout: RoundTimeout,
 }
 
 #[derive(Clone)]
@@ -37,6 +44,8 @@ impl RPathService {
         non_driver: NONDriverHelper,
         shell_mgr: GroupShellManager,
         store: GroupStorage,
+        admission_config: ProposalAdmissionConfig,
+        round_timeout_config: RoundTimeoutConfig,
     ) -> Self {
         let (pending_proposal_handle, pending_proposal_consumer) = PendingProposalMgr::new();
         let committee = Committee::new(
@@ -45,6 +54,7 @@ impl RPathService {
             shell_mgr.clone(),
             local_device_id,
         );
+        let round_timeout = RoundTimeout::new(round_timeout_config);
         let hotstuff = Hotstuff::new(
             local_id,
             local_device_id,
@@ -55,8 +65,9 @@ impl RPathService {
             non_driver.clone(),
             shell_mgr,
             pending_proposal_consumer,
-            event_notifier,
+            event_notifier.clone(),
             rpath.clone(),
+            round_timeout.clone(),
         );
 
         let raw = RPathServiceRaw {
@@ -66,6 +77,9 @@ impl RPathService {
             rpath,
             hotstuff,
             non_driver,
+            event_notifier,
+            admission: ProposalAdmission::new(admission_config),
+            round_timeout,
         };
 
         Self(Arc::new(raw))
@@ -83,6 +97,43 @@ impl RPathService {
 
         log::info!("group({:?}) push proposal {}", self.rpath(), proposal_id);
 
+        match check_proposal_timing(&proposal, SystemTime::now(), Duration::ZERO) {
+            ProposalTiming::Ready | ProposalTiming::Scheduled(_) => {}
+            ProposalTiming::Expired => {
+                log::warn!(
+                    "group({:?}) proposal {} rejected for expired",
+                    self.rpath(),
+                    proposal_id
+                );
+                return Err(BuckyError::new(BuckyErrorCode::Timeout, "timeout"));
+            }
+            ProposalTiming::ErrorTimestamp => {
+                log::warn!(
+                    "group({:?}) proposal {} rejected for error timestamp",
+                    self.rpath(),
+                    proposal_id
+                );
+                return Err(BuckyError::new(
+                    BuckyErrorCode::ErrorTimestamp,
+                    "error timestamp",
+                ));
+            }
+        }
+
+        self.0
+            .admission
+            .admit(&proposal, &self.0.event_notifier)
+            .await
+            .map_err(|err| {
+                log::warn!(
+                    "group({:?}) proposal {} rejected, err: {:?}",
+                    self.rpath(),
+                    proposal_id,
+                    err
+                );
+                err
+            })?;
+
         let object_raw = proposal.to_vec()?;
         let any_obj =
             AnyNamedObject::Core(TypelessCoreObject::clone_from_slice(object_raw.as_slice())?);
@@ -98,6 +149,24 @@ impl RPathService {
         )
     }
 
+    // the status of the proposal pushed to this node, the scheduled proposals are pending
+    // until the effective beginning time, and dropped when the effective ending time passed.
+    pub async fn proposal_status(&self, proposal_id: ObjectId) -> BuckyResult<GroupProposalStatus> {
+        self.0
+            .pending_proposal_handle
+            .proposal_status(proposal_id)
+            .await
+    }
+
+    pub fn admission(&self) -> &ProposalAdmission {
+        &self.0.admission
+    }
+
+    // the round timeout adaptive to the RTT among the members, `current()` for debugging
+    pub fn round_timeout(&self) -> &RoundTimeout {
+        &self.0.round_timeout
+    }
+
     pub fn select_branch(&self, _block_id: ObjectId, _source: ObjectId) -> BuckyResult<()> {
         unimplemented!()
     }
@@ -123,6 +192,14 @@ impl RPathService {
                 self.0.hotstuff.on_query_state(sub_path, remote).await
             }
             HotstuffMessage::VerifiableState(_, _) => unreachable!(),
+            HotstuffMessage::VerifiableStatePart(_, _, _, _) => unreachable!(),
+            HotstuffMessage::QueryBlocks(min_height, max_height) => {
+                self.0
+                    .hotstuff
+                    .on_query_blocks(min_height, max_height, remote)
+                    .await
+            }
+            HotstuffMessage::VerifiableBlocks(_, _) => unreachable!(),
         }
     }
 }
@@ -1,26 +1,48 @@
 // the manager of the DEC's state that synchronized from the group's rpath
 
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
-use cyfs_base::{BuckyResult, ObjectId};
-use cyfs_core::GroupRPath;
+use cyfs_base::{BuckyError, BuckyErrorCode, BuckyResult, NamedObject, ObjectDesc, ObjectId};
+use cyfs_core::{GroupConsensusBlock, GroupConsensusBlockObject, GroupRPath, HotstuffBlockQC};
 use cyfs_group_lib::GroupRPathStatus;
 use cyfs_lib::NONObjectInfo;
 use futures::FutureExt;
 
-use crate::{storage::DecStorage, Committee, HotstuffMessage, CHANNEL_CAPACITY};
+use crate::{
+    storage::DecStorage, Committee, HotstuffMessage, CHANNEL_CAPACITY, STATE_PARTS_TIMEOUT,
+};
 
 use super::{CallReplyNotifier, CallReplyWaiter};
 
 enum DecStateRequestorMessage {
     QueryState(String),                                     // sub-path
     VerifiableState(String, BuckyResult<GroupRPathStatus>), // (sub-path, result)
+    VerifiableStatePart(String, u16, u16, GroupRPathStatus), // (sub-path, index, count, part)
+    VerifiableBlocks(
+        u64,
+        BuckyResult<(Vec<GroupConsensusBlock>, HotstuffBlockQC)>,
+    ), // (min-height, (blocks, qc of the last block))
+}
+
+pub type VerifiableBlocks = Vec<(GroupConsensusBlock, HotstuffBlockQC)>;
+
+// the parts of a large state received from the remote
+struct VerifiableStateParts {
+    count: u16,
+    received: HashSet<u16>,
+    status: GroupRPathStatus,
+    first_recv_time: Instant,
 }
 
 struct DecStateRequestorRaw {
     local_device_id: ObjectId,
     tx_dec_state_req_message: async_std::channel::Sender<(DecStateRequestorMessage, ObjectId)>,
     query_state_notifier: CallReplyNotifier<String, BuckyResult<Option<NONObjectInfo>>>,
+    query_blocks_notifier: CallReplyNotifier<u64, BuckyResult<VerifiableBlocks>>,
 }
 
 #[derive(Clone)]
@@ -37,6 +59,7 @@ impl DecStateRequestor {
     ) -> Self {
         let (tx, rx) = async_std::channel::bounded(CHANNEL_CAPACITY);
         let notifier = CallReplyNotifier::new();
+        let blocks_notifier = CallReplyNotifier::new();
 
         let mut runner = DecStateRequestorRunner::new(
             local_device_id,
@@ -47,6 +70,7 @@ impl DecStateRequestor {
             network_sender,
             non_driver,
             notifier.clone(),
+            blocks_notifier.clone(),
         );
 
         async_std::task::spawn(async move { runner.run().await });
@@ -55,6 +79,7 @@ impl DecStateRequestor {
             local_device_id,
             tx_dec_state_req_message: tx,
             query_state_notifier: notifier,
+            query_blocks_notifier: blocks_notifier,
         }))
     }
 
@@ -65,6 +90,13 @@ impl DecStateRequestor {
         self.0.query_state_notifier.prepare(sub_path).await
     }
 
+    pub async fn wait_query_blocks(
+        &self,
+        min_height: u64,
+    ) -> CallReplyWaiter<BuckyResult<VerifiableBlocks>> {
+        self.0.query_blocks_notifier.prepare(min_height).await
+    }
+
     pub async fn on_query_state(&self, sub_path: String, remote: ObjectId) {
         if let Err(err) = self
             .0
@@ -97,6 +129,51 @@ impl DecStateRequestor {
             log::warn!("post verifiable state command to processor failed will ignore it, sub_path: {}, rmote: {}, err: {:?}", sub_path, remote, err);
         }
     }
+
+    pub async fn on_verifiable_state_part(
+        &self,
+        sub_path: String,
+        index: u16,
+        count: u16,
+        status: GroupRPathStatus,
+        remote: ObjectId,
+    ) {
+        if let Err(err) = self
+            .0
+            .tx_dec_state_req_message
+            .send((
+                DecStateRequestorMessage::VerifiableStatePart(
+                    sub_path.clone(),
+                    index,
+                    count,
+                    status,
+                ),
+                remote,
+            ))
+            .await
+        {
+            log::warn!("post verifiable state part command to processor failed will ignore it, sub_path: {}, rmote: {}, err: {:?}", sub_path, remote, err);
+        }
+    }
+
+    pub async fn on_verifiable_blocks(
+        &self,
+        min_height: u64,
+        result: BuckyResult<(Vec<GroupConsensusBlock>, HotstuffBlockQC)>,
+        remote: ObjectId,
+    ) {
+        if let Err(err) = self
+            .0
+            .tx_dec_state_req_message
+            .send((
+                DecStateRequestorMessage::VerifiableBlocks(min_height, result),
+                remote,
+            ))
+            .await
+        {
+            log::warn!("post verifiable blocks command to processor failed will ignore it, min_height: {}, rmote: {}, err: {:?}", min_height, remote, err);
+        }
+    }
 }
 
 struct DecStateRequestorRunner {
@@ -110,6 +187,8 @@ struct DecStateRequestorRunner {
     network_sender: crate::network::Sender,
     non_driver: crate::network::NONDriverHelper,
     query_state_notifier: CallReplyNotifier<String, BuckyResult<Option<NONObjectInfo>>>,
+    query_blocks_notifier: CallReplyNotifier<u64, BuckyResult<VerifiableBlocks>>,
+    state_parts: HashMap<(String, ObjectId), VerifiableStateParts>, // (sub-path, remote)
 }
 
 impl DecStateRequestorRunner {
@@ -125,6 +204,7 @@ impl DecStateRequestorRunner {
         network_sender: crate::network::Sender,
         non_driver: crate::network::NONDriverHelper,
         query_state_notifier: CallReplyNotifier<String, BuckyResult<Option<NONObjectInfo>>>,
+        query_blocks_notifier: CallReplyNotifier<u64, BuckyResult<VerifiableBlocks>>,
     ) -> Self {
         Self {
             local_device_id,
@@ -133,9 +213,11 @@ impl DecStateRequestorRunner {
             // timer: Timer::new(SYNCHRONIZER_TIMEOUT),
             store,
             query_state_notifier,
+            query_blocks_notifier,
             network_sender,
             non_driver,
             committee,
+            state_parts: HashMap::new(),
         }
     }
 
@@ -174,6 +256,177 @@ impl DecStateRequestorRunner {
         }
     }
 
+    async fn handle_verifiable_state_part(
+        &mut self,
+        sub_path: String,
+        index: u16,
+        count: u16,
+        status: GroupRPathStatus,
+        remote: ObjectId,
+    ) {
+        let now = Instant::now();
+        self.state_parts
+            .retain(|_, parts| now.duration_since(parts.first_recv_time) < STATE_PARTS_TIMEOUT);
+
+        if index >= count {
+            log::warn!(
+                "[dec-state-sync] ignore invalid state part {}/{}, sub_path: {}, remote: {}",
+                index,
+                count,
+                sub_path,
+                remote
+            );
+            return;
+        }
+
+        let key = (sub_path, remote);
+        let parts = match self.state_parts.remove(&key) {
+            Some(mut parts) => {
+                // the state is updated, the remote is sending the new one
+                let is_same = parts.count == count
+                    && parts.status.block_desc.object_id() == status.block_desc.object_id();
+                if is_same {
+                    if parts.received.insert(index) {
+                        if let Err(err) = parts.status.merge(status) {
+                            log::warn!(
+                                "[dec-state-sync] merge state part failed, sub_path: {}, remote: {}, err: {:?}",
+                                key.0,
+                                key.1,
+                                err
+                            );
+                        }
+                    }
+                    parts
+                } else {
+                    VerifiableStateParts {
+                        count,
+                        received: HashSet::from([index]),
+                        status,
+                        first_recv_time: now,
+                    }
+                }
+            }
+            None => VerifiableStateParts {
+                count,
+                received: HashSet::from([index]),
+                status,
+                first_recv_time: now,
+            },
+        };
+
+        if parts.received.len() < parts.count as usize {
+            self.state_parts.insert(key, parts);
+            return;
+        }
+
+        let (sub_path, remote) = key;
+        self.handle_verifiable_state(sub_path, Ok(parts.status), remote)
+            .await
+    }
+
+    async fn handle_verifiable_blocks(
+        &mut self,
+        min_height: u64,
+        result: BuckyResult<(Vec<GroupConsensusBlock>, HotstuffBlockQC)>,
+        remote: ObjectId,
+    ) {
+        let result = match result {
+            Ok((blocks, qc)) => self.check_blocks(min_height, blocks, qc, &remote).await,
+            Err(e) => Err(e),
+        };
+
+        log::debug!(
+            "handle_verifiable_blocks min_height: {}, remote: {}, result: {:?}",
+            min_height,
+            remote,
+            result.as_ref().map(|blocks| blocks.len())
+        );
+        self.query_blocks_notifier.reply(&min_height, result).await
+    }
+
+    // the blocks should be linked one by one from the min-height,
+    // and the qc of each block is carried by the next block, the last one is attached.
+    async fn check_blocks(
+        &self,
+        min_height: u64,
+        blocks: Vec<GroupConsensusBlock>,
+        last_qc: HotstuffBlockQC,
+        remote: &ObjectId,
+    ) -> BuckyResult<VerifiableBlocks> {
+        let qcs = Self::check_blocks_link(&self.rpath, min_height, &blocks, last_qc, remote)?;
+
+        for (block, qc) in blocks.iter().zip(qcs.iter()) {
+            self.committee
+                .verify_block_desc_with_qc(block.named_object().desc(), qc, remote.clone())
+                .await?;
+        }
+
+        Ok(blocks.into_iter().zip(qcs).collect())
+    }
+
+    // check the height and the link of the blocks, return the qc of each block to be verified.
+    fn check_blocks_link(
+        rpath: &GroupRPath,
+        min_height: u64,
+        blocks: &[GroupConsensusBlock],
+        last_qc: HotstuffBlockQC,
+        remote: &ObjectId,
+    ) -> BuckyResult<Vec<HotstuffBlockQC>> {
+        let mut qcs = Vec::with_capacity(blocks.len());
+        for (i, block) in blocks.iter().enumerate() {
+            if !block.check() || block.rpath() != rpath || block.height() != min_height + i as u64 {
+                let msg = format!(
+                    "[dec-state-sync] invalid block {}/{} from {}, expect height: {}",
+                    block.block_id(),
+                    block.height(),
+                    remote,
+                    min_height + i as u64
+                );
+                log::warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+
+            let qc = match blocks.get(i + 1) {
+                Some(next_block) => {
+                    if next_block.prev_block_id() != Some(block.block_id().object_id()) {
+                        let msg = format!(
+                            "[dec-state-sync] block {} is not linked to {} from {}",
+                            next_block.block_id(),
+                            block.block_id(),
+                            remote
+                        );
+                        log::warn!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+                    }
+
+                    next_block.qc().clone().ok_or_else(|| {
+                        BuckyError::new(
+                            BuckyErrorCode::InvalidData,
+                            format!("no qc in block {}", next_block.block_id()),
+                        )
+                    })?
+                }
+                None => last_qc.clone(),
+            };
+
+            if qc.round != block.round() {
+                return Err(BuckyError::new(
+                    BuckyErrorCode::Unmatch,
+                    format!(
+                        "the round of qc is unmatch with block {}, {} != {}",
+                        block.block_id(),
+                        qc.round,
+                        block.round()
+                    ),
+                ));
+            }
+
+            qcs.push(qc);
+        }
+
+        Ok(qcs)
+    }
+
     async fn check_sub_path_value<'a>(
         &self,
         sub_path: &str,
@@ -199,6 +452,8 @@ impl DecStateRequestorRunner {
                 message = self.rx_dec_state_req_message.recv().fuse() => match message {
                     Ok((DecStateRequestorMessage::QueryState(sub_path), remote)) => self.handle_query_state(sub_path, remote).await,
                     Ok((DecStateRequestorMessage::VerifiableState(sub_path, result), remote)) => self.handle_verifiable_state(sub_path, result, remote).await,
+                    Ok((DecStateRequestorMessage::VerifiableStatePart(sub_path, index, count, status), remote)) => self.handle_verifiable_state_part(sub_path, index, count, status, remote).await,
+                    Ok((DecStateRequestorMessage::VerifiableBlocks(min_height, result), remote)) => self.handle_verifiable_blocks(min_height, result, remote).await,
                     Err(e) => {
                         log::warn!("[dec-state-sync] rx closed, err: {:?}.", e);
                     },
@@ -208,3 +463,135 @@ impl DecStateRequestorRunner {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_rpath(rpath: &str) -> GroupRPath {
+        let owner = ObjectId::default();
+        GroupRPath::new(owner.clone(), owner, rpath.to_owned())
+    }
+
+    fn new_qc(block: &GroupConsensusBlock) -> HotstuffBlockQC {
+        HotstuffBlockQC {
+            block_id: block.block_id().object_id().clone(),
+            prev_block_id: block.prev_block_id().cloned(),
+            round: block.round(),
+            votes: vec![],
+        }
+    }
+
+    // the chain of blocks from the height 1, the round is equal to the height
+    fn new_chain(count: u64) -> Vec<GroupConsensusBlock> {
+        let mut blocks: Vec<GroupConsensusBlock> = vec![];
+        for height in 1..=count {
+            let qc = blocks.last().map(new_qc);
+            let block = GroupConsensusBlock::create(
+                new_rpath("test-rpath"),
+                vec![],
+                None,
+                height,
+                ObjectId::default(),
+                height,
+                ObjectId::default(),
+                qc,
+                None,
+                ObjectId::default(),
+            );
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_check_blocks_link() {
+        let rpath = new_rpath("test-rpath");
+        let remote = ObjectId::default();
+        let blocks = new_chain(5);
+        let last_qc = new_qc(&blocks[4]);
+
+        let qcs = DecStateRequestorRunner::check_blocks_link(
+            &rpath,
+            1,
+            &blocks,
+            last_qc.clone(),
+            &remote,
+        )
+        .unwrap();
+        assert_eq!(qcs.len(), 5);
+        for (block, qc) in blocks.iter().zip(qcs.iter()) {
+            assert_eq!(&qc.block_id, block.block_id().object_id());
+        }
+
+        // a page from the middle of the chain
+        let qcs = DecStateRequestorRunner::check_blocks_link(
+            &rpath,
+            3,
+            &blocks[2..],
+            last_qc.clone(),
+            &remote,
+        )
+        .unwrap();
+        assert_eq!(qcs.len(), 3);
+
+        // the page is not started from the min-height
+        let err = DecStateRequestorRunner::check_blocks_link(
+            &rpath,
+            2,
+            &blocks[2..],
+            last_qc.clone(),
+            &remote,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        // the blocks of other rpath
+        let err = DecStateRequestorRunner::check_blocks_link(
+            &new_rpath("other-rpath"),
+            1,
+            &blocks,
+            last_qc.clone(),
+            &remote,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        // the last qc is not for the last block
+        let err = DecStateRequestorRunner::check_blocks_link(
+            &rpath,
+            1,
+            &blocks,
+            new_qc(&blocks[3]),
+            &remote,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::Unmatch);
+    }
+
+    #[test]
+    fn test_check_blocks_unlinked() {
+        let rpath = new_rpath("test-rpath");
+        let remote = ObjectId::default();
+
+        // the block at height 2 is replaced by a fork, the block at height 3 is not linked to it
+        let mut blocks = new_chain(3);
+        blocks[1] = GroupConsensusBlock::create(
+            rpath.clone(),
+            vec![],
+            None,
+            2,
+            blocks[0].block_id().object_id().clone(),
+            2,
+            ObjectId::default(),
+            Some(new_qc(&blocks[0])),
+            None,
+            ObjectId::default(),
+        );
+        let last_qc = new_qc(&blocks[2]);
+
+        let err = DecStateRequestorRunner::check_blocks_link(&rpath, 1, &blocks, last_qc, &remote)
+            .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::Unmatch);
+    }
+}
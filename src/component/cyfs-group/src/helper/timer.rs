@@ -1,33 +1,31 @@
-use std::ops::Sub;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use cyfs_base::{bucky_sleep, bucky_time_now};
+
+// driven by the stack clock, so the virtual clock of the simulator can advance it
 pub struct Timer {
-    last_wake_time: Instant,
+    last_wake_time: u64,
     duration: Duration,
 }
 
 impl Timer {
     pub fn new(duration: u64) -> Self {
         Self {
-            last_wake_time: Instant::now(),
+            last_wake_time: bucky_time_now(),
             duration: Duration::from_millis(duration),
         }
     }
 
     pub fn reset(&mut self, duration: u64) {
         self.duration = Duration::from_millis(duration);
-        self.last_wake_time = Instant::now();
+        self.last_wake_time = bucky_time_now();
     }
 
     pub async fn wait_next(&mut self) {
-        let elapsed = Instant::now().duration_since(self.last_wake_time);
+        let elapsed = Duration::from_micros(bucky_time_now().saturating_sub(self.last_wake_time));
         if elapsed < self.duration {
-            let _ = async_std::future::timeout(
-                self.duration.sub(elapsed),
-                std::future::pending::<()>(),
-            )
-            .await;
+            bucky_sleep(self.duration - elapsed).await;
         }
-        self.last_wake_time = Instant::now();
+        self.last_wake_time = bucky_time_now();
     }
 }
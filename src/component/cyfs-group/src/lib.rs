@@ -1,3 +1,4 @@
+mod checkpoint;
 mod consensus;
 mod constant;
 mod dec;
@@ -7,7 +8,12 @@ mod network;
 mod statepath;
 mod storage;
 
+pub use checkpoint::*;
 pub(crate) use consensus::*;
+pub use consensus::{
+    GroupProposalStatus, ProposalAdmission, ProposalAdmissionConfig, RoundTimeout,
+    RoundTimeoutConfig,
+};
 pub use constant::*;
 pub use dec::*;
 pub use network::*;
@@ -5,6 +5,7 @@ mod protocol;
 mod sender;
 
 pub(crate) use listener::*;
+pub(crate) use meta_client_timeout::*;
 pub use non_driver::*;
 pub(crate) use protocol::*;
 pub(crate) use sender::*;
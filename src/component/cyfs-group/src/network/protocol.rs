@@ -95,6 +95,12 @@ pub(crate) enum HotstuffMessage {
     ), // (proposal-id, (ExecuteResult, block, qc))
     QueryState(String),
     VerifiableState(String, BuckyResult<GroupRPathStatus>),
+    VerifiableStatePart(String, u16, u16, GroupRPathStatus), // (sub-path, index, count, part)
+    QueryBlocks(u64, u64),                                   // [min-height, max-height]
+    VerifiableBlocks(
+        u64,
+        BuckyResult<(Vec<GroupConsensusBlock>, HotstuffBlockQC)>,
+    ), // (min-height, (blocks, qc of the last block))
 }
 
 impl std::fmt::Debug for HotstuffMessage {
@@ -171,27 +177,80 @@ impl std::fmt::Debug for HotstuffMessage {
                     f,
                     "HotstuffMessage::VerifiableState({}, {:?})",
                     sub_path,
-                    result.as_ref().map(|status| {
-                        let desc = status.block_desc.content();
-                        format!(
-                            "({:?}/{:?}, {}/{}/{}) sub-count: {:?}",
-                            desc.result_state_id(),
-                            status.block_desc.object_id(),
-                            desc.height(),
-                            desc.round(),
-                            status.certificate.round,
-                            status.status_map.iter().map(|(key, _)| key).collect_vec()
-                        )
-                    })
+                    result.as_ref().map(|status| fmt_status(status))
+                )
+            }
+            Self::VerifiableStatePart(sub_path, index, count, status) => {
+                write!(
+                    f,
+                    "HotstuffMessage::VerifiableStatePart({}, {}/{}, {})",
+                    sub_path,
+                    index,
+                    count,
+                    fmt_status(status)
+                )
+            }
+            Self::QueryBlocks(min_height, max_height) => {
+                write!(
+                    f,
+                    "HotstuffMessage::QueryBlocks([{}-{}])",
+                    min_height, max_height
+                )
+            }
+            Self::VerifiableBlocks(min_height, result) => {
+                write!(
+                    f,
+                    "HotstuffMessage::VerifiableBlocks({}, {:?})",
+                    min_height,
+                    result.as_ref().map(|(blocks, qc)| fmt_blocks(blocks, qc))
                 )
             }
         }
     }
 }
 
+// the status may be very large, only the count and a few keys are printed
+fn fmt_status(status: &GroupRPathStatus) -> String {
+    const MAX_PRINT_KEYS: usize = 8;
+
+    let desc = status.block_desc.content();
+    let keys = status
+        .status_map
+        .keys()
+        .take(MAX_PRINT_KEYS)
+        .collect_vec();
+    format!(
+        "({:?}/{:?}, {}/{}/{}) sub-count: {}, sub-keys: {:?}{}",
+        desc.result_state_id(),
+        status.block_desc.object_id(),
+        desc.height(),
+        desc.round(),
+        status.certificate.round,
+        status.status_map.len(),
+        keys,
+        if status.status_map.len() > MAX_PRINT_KEYS {
+            "..."
+        } else {
+            ""
+        }
+    )
+}
+
+fn fmt_blocks(blocks: &[GroupConsensusBlock], qc: &HotstuffBlockQC) -> String {
+    format!(
+        "count: {}, heights: [{:?}-{:?}], qc: {}/{}",
+        blocks.len(),
+        blocks.first().map(|b| b.height()),
+        blocks.last().map(|b| b.height()),
+        qc.block_id,
+        qc.round
+    )
+}
+
 const PACKAGE_FLAG_BITS: usize = 1;
 const PACKAGE_FLAG_PROPOSAL_RESULT_OK: u8 = 0x80u8;
 const PACKAGE_FLAG_QUERY_STATE_RESULT_OK: u8 = 0x80u8;
+const PACKAGE_FLAG_QUERY_BLOCKS_RESULT_OK: u8 = 0x80u8;
 
 #[derive(Clone)]
 pub(crate) enum HotstuffPackage {
@@ -216,6 +275,12 @@ pub(crate) enum HotstuffPackage {
         String,
         Result<GroupRPathStatus, (BuckyError, ProtocolAddress)>,
     ),
+    VerifiableStatePart(String, u16, u16, GroupRPathStatus), // (sub-path, index, count, part)
+    QueryBlocks(ProtocolAddress, u64, u64),                  // [min-height, max-height]
+    VerifiableBlocks(
+        u64,
+        Result<(Vec<GroupConsensusBlock>, HotstuffBlockQC), (BuckyError, ProtocolAddress)>,
+    ), // (min-height, (blocks, qc of the last block))
 }
 
 impl std::fmt::Debug for HotstuffPackage {
@@ -296,21 +361,36 @@ impl std::fmt::Debug for HotstuffPackage {
                     f,
                     "HotstuffPackage::VerifiableState({}, {:?})",
                     sub_path,
+                    result
+                        .as_ref()
+                        .map_or_else(|(err, _)| Err(err), |status| Ok(fmt_status(status)))
+                )
+            }
+            Self::VerifiableStatePart(sub_path, index, count, status) => {
+                write!(
+                    f,
+                    "HotstuffPackage::VerifiableStatePart({}, {}/{}, {})",
+                    sub_path,
+                    index,
+                    count,
+                    fmt_status(status)
+                )
+            }
+            Self::QueryBlocks(_, min_height, max_height) => {
+                write!(
+                    f,
+                    "HotstuffPackage::QueryBlocks([{}-{}])",
+                    min_height, max_height
+                )
+            }
+            Self::VerifiableBlocks(min_height, result) => {
+                write!(
+                    f,
+                    "HotstuffPackage::VerifiableBlocks({}, {:?})",
+                    min_height,
                     result.as_ref().map_or_else(
-                        |(err, _)| { Err(err) },
-                        |status| {
-                            let desc = status.block_desc.content();
-                            let ok = format!(
-                                "({:?}/{:?}, {}/{}/{}) sub-count: {:?}",
-                                desc.result_state_id(),
-                                status.block_desc.object_id(),
-                                desc.height(),
-                                desc.round(),
-                                status.certificate.round,
-                                status.status_map.iter().map(|(key, _)| key).collect_vec()
-                            );
-                            Ok(ok)
-                        }
+                        |(err, _)| Err(err),
+                        |(blocks, qc)| Ok(fmt_blocks(blocks, qc))
                     )
                 )
             }
@@ -337,6 +417,14 @@ impl HotstuffPackage {
                 |(_, addr)| addr.check_rpath(),
                 |status| status.block_desc.content().rpath(),
             ),
+            HotstuffPackage::VerifiableStatePart(_, _, _, status) => {
+                status.block_desc.content().rpath()
+            }
+            HotstuffPackage::QueryBlocks(addr, _, _) => addr.check_rpath(),
+            HotstuffPackage::VerifiableBlocks(_, result) => result.as_ref().map_or_else(
+                |(_, addr)| addr.check_rpath(),
+                |(blocks, _)| blocks[0].rpath(),
+            ),
         }
     }
 }
@@ -351,7 +439,14 @@ fn encode_with_length<'a, O: RawEncode>(
     let before_len = buf.len();
     let buf = obj.raw_encode(buf, purpose)?;
     let len = before_len - buf.len();
-    assert!(len <= (1 << (length_size << 3)) - 1);
+    if len > (1 << (length_size << 3)) - 1 {
+        let msg = format!(
+            "the object is too large to encode, len: {}, length-size: {}",
+            len, length_size
+        );
+        log::error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+    }
     len_buf.copy_from_slice(&len.to_le_bytes()[..length_size]);
 
     Ok(buf)
@@ -367,9 +462,22 @@ fn decode_with_length<'de, O: RawDecode<'de>>(
     let mut len_buf_4 = [0u8; 4];
     len_buf_4[..length_size].copy_from_slice(len_buf);
     let len = u32::from_le_bytes(len_buf_4) as usize;
+    if len > buf.len() {
+        let msg = format!(
+            "decode object failed, length: {}, buffer: {}",
+            len,
+            buf.len()
+        );
+        log::error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+    }
 
     let (obj, remain) = O::raw_decode(&buf[..len])?;
-    assert_eq!(remain.len(), 0);
+    if remain.len() > 0 {
+        let msg = format!("decode object failed, {} bytes remain", remain.len());
+        log::error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+    }
 
     Ok((obj, &buf[len..]))
 }
@@ -423,6 +531,33 @@ impl RawEncode for HotstuffPackage {
                         }
                     }
             }
+            HotstuffPackage::VerifiableStatePart(sub_path, index, count, status) => {
+                sub_path.raw_measure(purpose)?
+                    + index.raw_measure(purpose)?
+                    + count.raw_measure(purpose)?
+                    + 3
+                    + status.raw_measure(purpose)?
+            }
+            HotstuffPackage::QueryBlocks(addr, min_height, max_height) => {
+                2 + addr.raw_measure(purpose)?
+                    + min_height.raw_measure(purpose)?
+                    + max_height.raw_measure(purpose)?
+            }
+            HotstuffPackage::VerifiableBlocks(min_height, result) => {
+                min_height.raw_measure(purpose)?
+                    + match result {
+                        Ok((blocks, qc)) => {
+                            let mut len = 2 + 3 + qc.raw_measure(purpose)?;
+                            for block in blocks {
+                                len += 3 + block.raw_measure(purpose)?;
+                            }
+                            len
+                        }
+                        Err((err, addr)) => {
+                            err.raw_measure(purpose)? + 2 + addr.raw_measure(purpose)?
+                        }
+                    }
+            }
         };
 
         Ok(1 + len)
@@ -516,6 +651,47 @@ impl RawEncode for HotstuffPackage {
                     }
                 }
             }
+            HotstuffPackage::VerifiableStatePart(sub_path, index, count, status) => {
+                buf[0] = 10;
+                let buf = &mut buf[1..];
+                let buf = sub_path.raw_encode(buf, purpose)?;
+                let buf = index.raw_encode(buf, purpose)?;
+                let buf = count.raw_encode(buf, purpose)?;
+                encode_with_length(buf, status, purpose, 3)
+            }
+            HotstuffPackage::QueryBlocks(addr, min_height, max_height) => {
+                buf[0] = 11;
+                let buf = &mut buf[1..];
+                let buf = encode_with_length(buf, addr, purpose, 2)?;
+                let buf = min_height.raw_encode(buf, purpose)?;
+                max_height.raw_encode(buf, purpose)
+            }
+            HotstuffPackage::VerifiableBlocks(min_height, result) => {
+                buf[0] = 12;
+                if result.is_ok() {
+                    buf[0] |= PACKAGE_FLAG_QUERY_BLOCKS_RESULT_OK;
+                }
+                let buf = &mut buf[1..];
+                let buf = min_height.raw_encode(buf, purpose)?;
+                match result {
+                    Ok((blocks, qc)) => {
+                        if blocks.is_empty() || blocks.len() > u16::MAX as usize {
+                            let msg = format!("invalid block count {} to encode", blocks.len());
+                            log::error!("{}", msg);
+                            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+                        }
+                        let mut buf = (blocks.len() as u16).raw_encode(buf, purpose)?;
+                        for block in blocks {
+                            buf = encode_with_length(buf, block, purpose, 3)?;
+                        }
+                        encode_with_length(buf, qc, purpose, 3)
+                    }
+                    Err((err, addr)) => {
+                        let buf = err.raw_encode(buf, purpose)?;
+                        encode_with_length(buf, addr, purpose, 2)
+                    }
+                }
+            }
         }
     }
 }
@@ -625,6 +801,65 @@ impl<'de> RawDecode<'de> for HotstuffPackage {
                     }
                 }
             }
+            10 => {
+                let buf = &buf[1..];
+                let (sub_path, buf) = String::raw_decode(buf)?;
+                let (index, buf) = u16::raw_decode(buf)?;
+                let (count, buf) = u16::raw_decode(buf)?;
+                let (status, buf) = decode_with_length(buf, 3)?;
+                assert_eq!(buf.len(), 0);
+                Ok((
+                    HotstuffPackage::VerifiableStatePart(sub_path, index, count, status),
+                    buf,
+                ))
+            }
+            11 => {
+                let buf = &buf[1..];
+                let (addr, buf) = decode_with_length(buf, 2)?;
+                let (min_height, buf) = u64::raw_decode(buf)?;
+                let (max_height, buf) = u64::raw_decode(buf)?;
+                assert_eq!(buf.len(), 0);
+                Ok((
+                    HotstuffPackage::QueryBlocks(addr, min_height, max_height),
+                    buf,
+                ))
+            }
+            12 => {
+                let is_ok = (buf[0] & PACKAGE_FLAG_QUERY_BLOCKS_RESULT_OK) != 0;
+                let buf = &buf[1..];
+                let (min_height, buf) = u64::raw_decode(buf)?;
+                match is_ok {
+                    true => {
+                        let (count, mut buf) = u16::raw_decode(buf)?;
+                        if count == 0 {
+                            let msg = "decode verifiable blocks failed, no block";
+                            log::error!("{}", msg);
+                            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+                        }
+                        let mut blocks = Vec::with_capacity(count as usize);
+                        for _ in 0..count {
+                            let (block, remain) = decode_with_length(buf, 3)?;
+                            blocks.push(block);
+                            buf = remain;
+                        }
+                        let (qc, buf) = decode_with_length(buf, 3)?;
+                        assert_eq!(buf.len(), 0);
+                        Ok((
+                            HotstuffPackage::VerifiableBlocks(min_height, Ok((blocks, qc))),
+                            buf,
+                        ))
+                    }
+                    false => {
+                        let (err, buf) = BuckyError::raw_decode(buf)?;
+                        let (addr, buf) = decode_with_length(buf, 2)?;
+                        assert_eq!(buf.len(), 0);
+                        Ok((
+                            HotstuffPackage::VerifiableBlocks(min_height, Err((err, addr))),
+                            buf,
+                        ))
+                    }
+                }
+            }
             _ => unreachable!("unknown protocol"),
         }
     }
@@ -665,6 +900,18 @@ impl HotstuffPackage {
                 sub_path,
                 result.map_err(|err| (err, ProtocolAddress::Full(rpath))),
             ),
+            HotstuffMessage::VerifiableStatePart(sub_path, index, count, status) => {
+                HotstuffPackage::VerifiableStatePart(sub_path, index, count, status)
+            }
+            HotstuffMessage::QueryBlocks(min_height, max_height) => {
+                HotstuffPackage::QueryBlocks(ProtocolAddress::Full(rpath), min_height, max_height)
+            }
+            HotstuffMessage::VerifiableBlocks(min_height, result) => {
+                HotstuffPackage::VerifiableBlocks(
+                    min_height,
+                    result.map_err(|err| (err, ProtocolAddress::Full(rpath))),
+                )
+            }
         }
     }
 }
@@ -685,4 +932,170 @@ impl ProtocolAddress {
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use cyfs_core::{Text, TextObj};
+    use std::collections::HashMap;
+
+    fn new_rpath() -> GroupRPath {
+        let owner = ObjectId::default();
+        GroupRPath::new(owner.clone(), owner, "test-rpath".to_owned())
+    }
+
+    fn new_status(count: usize) -> GroupRPathStatus {
+        let block = GroupConsensusBlock::create(
+            new_rpath(),
+            vec![],
+            None,
+            1,
+            ObjectId::default(),
+            1,
+            ObjectId::default(),
+            None,
+            None,
+            ObjectId::default(),
+        );
+
+        let mut status_map = HashMap::new();
+        for i in 0..count {
+            let text = Text::create(&format!("status-{}", i), "", "value");
+            let object_id = text.desc().calculate_id();
+            let object = NONObjectInfo::new(object_id.clone(), text.to_vec().unwrap(), None);
+            status_map.insert(object_id, object);
+        }
+
+        GroupRPathStatus {
+            block_desc: block.named_object().desc().clone(),
+            certificate: HotstuffBlockQC::default(),
+            status_map,
+        }
+    }
+
+    #[test]
+    fn test_length_check() {
+        // the object is larger than the length field
+        let obj = vec![0u8; 300];
+        let mut buf = vec![0u8; 400];
+        let err = encode_with_length(buf.as_mut_slice(), &obj, &None, 1).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::OutOfLimit);
+
+        let remain = encode_with_length(buf.as_mut_slice(), &obj, &None, 2).unwrap();
+        let len = 400 - remain.len();
+        let (decoded, remain) = decode_with_length::<Vec<u8>>(&buf[..len], 2).unwrap();
+        assert_eq!(decoded, obj);
+        assert_eq!(remain.len(), 0);
+
+        // the length is larger than the buffer
+        let err = decode_with_length::<Vec<u8>>(&buf[..len - 1], 2).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::OutOfLimit);
+
+        // some bytes are not decoded
+        let buf = [5u8, 1, 0, 0, 0, 9];
+        let err = decode_with_length::<u32>(&buf, 1).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+    }
+
+    #[test]
+    fn test_state_part_codec() {
+        let status = new_status(3);
+        let msg = HotstuffMessage::VerifiableStatePart("sub-path".to_owned(), 1, 4, status.clone());
+        let pkg = HotstuffPackage::from_msg(msg, new_rpath());
+        assert_eq!(pkg.rpath().rpath(), "test-rpath");
+
+        let buf = pkg.to_vec().unwrap();
+        assert_eq!(buf.len(), pkg.raw_measure(&None).unwrap());
+
+        let (pkg, remain) = HotstuffPackage::raw_decode(buf.as_slice()).unwrap();
+        assert_eq!(remain.len(), 0);
+        match pkg {
+            HotstuffPackage::VerifiableStatePart(sub_path, index, count, part) => {
+                assert_eq!(sub_path, "sub-path");
+                assert_eq!((index, count), (1, 4));
+                assert_eq!(part.block_desc.object_id(), status.block_desc.object_id());
+                assert_eq!(part.status_map.len(), 3);
+                for id in status.status_map.keys() {
+                    assert!(part.status_map.contains_key(id));
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_blocks_codec() {
+        let pkg = HotstuffPackage::from_msg(HotstuffMessage::QueryBlocks(3, 10), new_rpath());
+        let buf = pkg.to_vec().unwrap();
+        assert_eq!(buf.len(), pkg.raw_measure(&None).unwrap());
+        let (pkg, remain) = HotstuffPackage::raw_decode(buf.as_slice()).unwrap();
+        assert_eq!(remain.len(), 0);
+        match pkg {
+            HotstuffPackage::QueryBlocks(addr, min_height, max_height) => {
+                assert_eq!(addr.check_rpath().rpath(), "test-rpath");
+                assert_eq!((min_height, max_height), (3, 10));
+            }
+            _ => unreachable!(),
+        }
+
+        let blocks: Vec<GroupConsensusBlock> = (3..6)
+            .map(|height| {
+                GroupConsensusBlock::create(
+                    new_rpath(),
+                    vec![],
+                    None,
+                    height,
+                    ObjectId::default(),
+                    height,
+                    ObjectId::default(),
+                    None,
+                    None,
+                    ObjectId::default(),
+                )
+            })
+            .collect();
+        let qc = HotstuffBlockQC {
+            block_id: blocks[2].block_id().object_id().clone(),
+            round: 5,
+            ..Default::default()
+        };
+
+        let msg = HotstuffMessage::VerifiableBlocks(3, Ok((blocks.clone(), qc)));
+        let pkg = HotstuffPackage::from_msg(msg, new_rpath());
+        assert_eq!(pkg.rpath().rpath(), "test-rpath");
+        let buf = pkg.to_vec().unwrap();
+        assert_eq!(buf.len(), pkg.raw_measure(&None).unwrap());
+        let (pkg, remain) = HotstuffPackage::raw_decode(buf.as_slice()).unwrap();
+        assert_eq!(remain.len(), 0);
+        match pkg {
+            HotstuffPackage::VerifiableBlocks(min_height, Ok((decoded, qc))) => {
+                assert_eq!(min_height, 3);
+                assert_eq!(decoded.len(), 3);
+                for (block, origin) in decoded.iter().zip(blocks.iter()) {
+                    assert_eq!(block.block_id(), origin.block_id());
+                }
+                assert_eq!(&qc.block_id, blocks[2].block_id().object_id());
+                assert_eq!(qc.round, 5);
+            }
+            _ => unreachable!(),
+        }
+
+        let err = BuckyError::new(BuckyErrorCode::NotFound, "not committed");
+        let msg = HotstuffMessage::VerifiableBlocks(3, Err(err));
+        let pkg = HotstuffPackage::from_msg(msg, new_rpath());
+        let buf = pkg.to_vec().unwrap();
+        let (pkg, _) = HotstuffPackage::raw_decode(buf.as_slice()).unwrap();
+        match pkg {
+            HotstuffPackage::VerifiableBlocks(min_height, Err((err, addr))) => {
+                assert_eq!(min_height, 3);
+                assert_eq!(err.code(), BuckyErrorCode::NotFound);
+                assert_eq!(addr.check_rpath().rpath(), "test-rpath");
+            }
+            _ => unreachable!(),
+        }
+
+        // an empty page should be responded as an error
+        let msg = HotstuffMessage::VerifiableBlocks(3, Ok((vec![], HotstuffBlockQC::default())));
+        let pkg = HotstuffPackage::from_msg(msg, new_rpath());
+        let err = pkg.to_vec().unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::OutOfLimit);
+    }
+}
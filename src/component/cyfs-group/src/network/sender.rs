@@ -3,13 +3,13 @@ use std::{
     time::Instant,
 };
 
-use cyfs_base::{DeviceId, ObjectId, PeopleId, RawEncode};
+use cyfs_base::{BuckyError, BuckyErrorCode, DeviceId, ObjectId, PeopleId, RawEncode};
 use cyfs_bdt::{DatagramOptions, DatagramTunnelGuard};
 use cyfs_core::GroupRPath;
 
-use crate::{HotstuffMessage, HotstuffPackage};
+use crate::{HotstuffMessage, HotstuffPackage, PACKAGE_MAX_LEN};
 
-use super::NONDriverHelper;
+use super::{NONDriverHelper, ProtocolAddress};
 
 #[derive(Clone)]
 pub struct Sender {
@@ -57,15 +57,124 @@ impl Sender {
             _ => panic!("invalid remote type: {:?}", to.obj_type_code()),
         };
 
-        let pkg = HotstuffPackage::from_msg(msg, rpath);
+        let pkg = HotstuffPackage::from_msg(msg, rpath.clone());
 
-        let len = pkg.raw_measure(&None).unwrap();
+        let len = match pkg.raw_measure(&None) {
+            Ok(len) => len,
+            Err(err) => {
+                log::error!(
+                    "[group-sender] {:?}-{} post group message to {:?} measure failed, pkg: {:?}, err: {:?}",
+                    rpath,
+                    self.local_device_id,
+                    remote,
+                    pkg,
+                    err
+                );
+                return;
+            }
+        };
+
+        if len > PACKAGE_MAX_LEN {
+            if let HotstuffPackage::VerifiableState(sub_path, Ok(status)) = &pkg {
+                // the large state is splitted into several parts, and merged by the receiver
+                let parts = status
+                    .split(PACKAGE_MAX_LEN.saturating_sub(sub_path.len() + 16))
+                    .and_then(|parts| {
+                        if parts.len() > u16::MAX as usize {
+                            Err(BuckyError::new(
+                                BuckyErrorCode::OutOfLimit,
+                                format!("too many parts: {}", parts.len()),
+                            ))
+                        } else {
+                            Ok(parts)
+                        }
+                    });
+
+                match parts {
+                    Ok(parts) => {
+                        let count = parts.len() as u16;
+                        for (index, part) in parts.into_iter().enumerate() {
+                            self.send_package(
+                                HotstuffPackage::VerifiableStatePart(
+                                    sub_path.clone(),
+                                    index as u16,
+                                    count,
+                                    part,
+                                ),
+                                &remote,
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!(
+                            "[group-sender] {:?}-{} post group message to {:?} split state failed, pkg: {:?}, err: {:?}",
+                            rpath,
+                            self.local_device_id,
+                            remote,
+                            pkg,
+                            err
+                        );
+                        self.send_package(
+                            HotstuffPackage::VerifiableState(
+                                sub_path.clone(),
+                                Err((err, ProtocolAddress::Full(rpath))),
+                            ),
+                            &remote,
+                        );
+                    }
+                }
+            } else {
+                log::error!(
+                    "[group-sender] {:?}-{} post group message to {:?} ignored for too large, pkg: {:?}, len: {}, limit: {}",
+                    rpath,
+                    self.local_device_id,
+                    remote,
+                    pkg,
+                    len,
+                    PACKAGE_MAX_LEN
+                );
+            }
+            return;
+        }
+
+        self.send_package(pkg, &remote);
+    }
+
+    fn send_package(&self, pkg: HotstuffPackage, remote: &DeviceId) {
+        let len = match pkg.raw_measure(&None) {
+            Ok(len) => len,
+            Err(err) => {
+                log::error!(
+                    "[group-sender] {:?}-{} post group message to {:?} measure failed, pkg: {:?}, err: {:?}",
+                    pkg.rpath(),
+                    self.local_device_id,
+                    remote,
+                    pkg,
+                    err
+                );
+                return;
+            }
+        };
         let mut buf = Vec::with_capacity(len);
         buf.resize(len, 0);
-        let remain = pkg.raw_encode(buf.as_mut_slice(), &None).unwrap();
+
+        let remain_len = match pkg.raw_encode(buf.as_mut_slice(), &None) {
+            Ok(remain) => remain.len(),
+            Err(err) => {
+                log::error!(
+                    "[group-sender] {:?}-{} post group message to {:?} encode failed, pkg: {:?}, err: {:?}",
+                    pkg.rpath(),
+                    self.local_device_id,
+                    remote,
+                    pkg,
+                    err
+                );
+                return;
+            }
+        };
 
         assert_eq!(
-            remain.len(),
+            remain_len,
             0,
             "[group-sender] {:?}-{} post group message to {:?} encode err, pkg: {:?}, len: {}",
             pkg.rpath(),
@@ -93,7 +202,7 @@ impl Sender {
 
         if let Err(err) = self
             .datagram
-            .send_to(buf.as_slice(), &mut options, &remote, self.vport)
+            .send_to(buf.as_slice(), &mut options, remote, self.vport)
         {
             log::warn!(
                 "[group-sender] {:?}-{} post group message to {:?} failed, the caller should retry, pkg: {:?}, len: {}",
@@ -12,9 +12,65 @@ pub struct AdminGlobalStateAccessModeData {
     pub access_mode: GlobalStateAccessMode,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub enum AdminNOCOrphanBlobAction {
+    Keep,
+    RebuildMeta,
+    Quarantine,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct AdminNOCCheckData {
+    pub remove_dangling_meta: bool,
+    pub orphan_blob_action: AdminNOCOrphanBlobAction,
+    // 0 means no limit
+    pub ops_per_sec: u32,
+}
+
+// toml格式的配置片段，只包含需要重新加载的段，比如
+// [front]
+// app_name_cache_size = 512
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct AdminReloadConfigData {
+    pub config: String,
+}
+
+// 注册或者移除owner的WebAuthn认证器，注册之后敏感操作需要认证器确认，只能发给ood
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct AdminWebAuthnCredentialData {
+    // base64url编码的credential id
+    pub credential_id: String,
+
+    // hex编码的P-256公钥(SEC1格式)，移除时可以为空
+    pub public_key: String,
+
+    // 注册时使用的rp id，比如 www.cyfs.com
+    pub rp_id: String,
+
+    pub remove: bool,
+}
+
+// owner提供本地存储加密的口令，解锁之后才允许加密保存和读取，只能发给ood
+#[derive(Clone, Eq, PartialEq, Serialize)]
+pub struct AdminUnlockStorageData {
+    #[serde(skip_serializing)]
+    pub passphrase: String,
+}
+
+// 避免在日志里输出口令
+impl std::fmt::Debug for AdminUnlockStorageData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AdminUnlockStorageData {{ passphrase: *** }}")
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub enum AdminCommand {
     GlobalStateAccessMode(AdminGlobalStateAccessModeData),
+    NOCCheck(AdminNOCCheckData),
+    ReloadConfig(AdminReloadConfigData),
+    WebAuthnCredential(AdminWebAuthnCredentialData),
+    UnlockStorage(AdminUnlockStorageData),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -112,6 +168,128 @@ impl TryFrom<&AdminGlobalStateAccessModeData> for protos::AdminGlobalStateAccess
 
 impl_default_protobuf_raw_codec!(AdminGlobalStateAccessModeData);
 
+impl TryFrom<protos::AdminNOCCheckData> for AdminNOCCheckData {
+    type Error = BuckyError;
+
+    fn try_from(value: protos::AdminNOCCheckData) -> BuckyResult<Self> {
+        let orphan_blob_action = match value.orphan_blob_action {
+            protos::AdminNOCCheckData_OrphanBlobAction::Keep => AdminNOCOrphanBlobAction::Keep,
+            protos::AdminNOCCheckData_OrphanBlobAction::RebuildMeta => {
+                AdminNOCOrphanBlobAction::RebuildMeta
+            }
+            protos::AdminNOCCheckData_OrphanBlobAction::Quarantine => {
+                AdminNOCOrphanBlobAction::Quarantine
+            }
+        };
+
+        Ok(Self {
+            remove_dangling_meta: value.remove_dangling_meta,
+            orphan_blob_action,
+            ops_per_sec: value.ops_per_sec,
+        })
+    }
+}
+
+impl TryFrom<&AdminNOCCheckData> for protos::AdminNOCCheckData {
+    type Error = BuckyError;
+
+    fn try_from(value: &AdminNOCCheckData) -> BuckyResult<Self> {
+        let orphan_blob_action = match value.orphan_blob_action {
+            AdminNOCOrphanBlobAction::Keep => protos::AdminNOCCheckData_OrphanBlobAction::Keep,
+            AdminNOCOrphanBlobAction::RebuildMeta => {
+                protos::AdminNOCCheckData_OrphanBlobAction::RebuildMeta
+            }
+            AdminNOCOrphanBlobAction::Quarantine => {
+                protos::AdminNOCCheckData_OrphanBlobAction::Quarantine
+            }
+        };
+
+        let mut ret = Self::new();
+        ret.set_remove_dangling_meta(value.remove_dangling_meta);
+        ret.set_orphan_blob_action(orphan_blob_action);
+        ret.set_ops_per_sec(value.ops_per_sec);
+
+        Ok(ret)
+    }
+}
+
+impl_default_protobuf_raw_codec!(AdminNOCCheckData);
+
+impl TryFrom<protos::AdminReloadConfigData> for AdminReloadConfigData {
+    type Error = BuckyError;
+
+    fn try_from(mut value: protos::AdminReloadConfigData) -> BuckyResult<Self> {
+        Ok(Self {
+            config: value.take_config(),
+        })
+    }
+}
+
+impl TryFrom<&AdminReloadConfigData> for protos::AdminReloadConfigData {
+    type Error = BuckyError;
+
+    fn try_from(value: &AdminReloadConfigData) -> BuckyResult<Self> {
+        let mut ret = Self::new();
+        ret.set_config(value.config.clone());
+
+        Ok(ret)
+    }
+}
+
+impl_default_protobuf_raw_codec!(AdminReloadConfigData);
+
+impl TryFrom<protos::AdminWebAuthnCredentialData> for AdminWebAuthnCredentialData {
+    type Error = BuckyError;
+
+    fn try_from(mut value: protos::AdminWebAuthnCredentialData) -> BuckyResult<Self> {
+        Ok(Self {
+            credential_id: value.take_credential_id(),
+            public_key: value.take_public_key(),
+            rp_id: value.take_rp_id(),
+            remove: value.remove,
+        })
+    }
+}
+
+impl TryFrom<&AdminWebAuthnCredentialData> for protos::AdminWebAuthnCredentialData {
+    type Error = BuckyError;
+
+    fn try_from(value: &AdminWebAuthnCredentialData) -> BuckyResult<Self> {
+        let mut ret = Self::new();
+        ret.set_credential_id(value.credential_id.clone());
+        ret.set_public_key(value.public_key.clone());
+        ret.set_rp_id(value.rp_id.clone());
+        ret.set_remove(value.remove);
+
+        Ok(ret)
+    }
+}
+
+impl_default_protobuf_raw_codec!(AdminWebAuthnCredentialData);
+
+impl TryFrom<protos::AdminUnlockStorageData> for AdminUnlockStorageData {
+    type Error = BuckyError;
+
+    fn try_from(mut value: protos::AdminUnlockStorageData) -> BuckyResult<Self> {
+        Ok(Self {
+            passphrase: value.take_passphrase(),
+        })
+    }
+}
+
+impl TryFrom<&AdminUnlockStorageData> for protos::AdminUnlockStorageData {
+    type Error = BuckyError;
+
+    fn try_from(value: &AdminUnlockStorageData) -> BuckyResult<Self> {
+        let mut ret = Self::new();
+        ret.set_passphrase(value.passphrase.clone());
+
+        Ok(ret)
+    }
+}
+
+impl_default_protobuf_raw_codec!(AdminUnlockStorageData);
+
 impl TryFrom<protos::AdminDescContent> for AdminDescContent {
     type Error = BuckyError;
 
@@ -131,6 +309,51 @@ impl TryFrom<protos::AdminDescContent> for AdminDescContent {
                     ProtobufCodecHelper::decode_nested_item(value.take_global_state_access_mode())?;
                 AdminCommand::GlobalStateAccessMode(data)
             }
+            protos::AdminDescContent_Command::NOCCheck => {
+                if !value.has_noc_check() {
+                    let msg = format!("invalid AdminDescContent noc_check field! {:?}", value);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+                }
+
+                let data = ProtobufCodecHelper::decode_nested_item(value.take_noc_check())?;
+                AdminCommand::NOCCheck(data)
+            }
+            protos::AdminDescContent_Command::ReloadConfig => {
+                if !value.has_reload_config() {
+                    let msg = format!("invalid AdminDescContent reload_config field! {:?}", value);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+                }
+
+                let data = ProtobufCodecHelper::decode_nested_item(value.take_reload_config())?;
+                AdminCommand::ReloadConfig(data)
+            }
+            protos::AdminDescContent_Command::WebAuthnCredential => {
+                if !value.has_webauthn_credential() {
+                    let msg = format!(
+                        "invalid AdminDescContent webauthn_credential field! {:?}",
+                        value
+                    );
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+                }
+
+                let data =
+                    ProtobufCodecHelper::decode_nested_item(value.take_webauthn_credential())?;
+                AdminCommand::WebAuthnCredential(data)
+            }
+            protos::AdminDescContent_Command::UnlockStorage => {
+                if !value.has_unlock_storage() {
+                    // 不输出value，避免口令出现在日志里
+                    let msg = "invalid AdminDescContent unlock_storage field!".to_owned();
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+                }
+
+                let data = ProtobufCodecHelper::decode_nested_item(value.take_unlock_storage())?;
+                AdminCommand::UnlockStorage(data)
+            }
         };
 
         let target = ProtobufCodecHelper::decode_buf(value.take_target())?;
@@ -152,6 +375,26 @@ impl TryFrom<&AdminDescContent> for protos::AdminDescContent {
                 let data = data.try_into()?;
                 ret.set_global_state_access_mode(data);
             }
+            AdminCommand::NOCCheck(ref data) => {
+                ret.set_cmd(protos::AdminDescContent_Command::NOCCheck);
+                let data = data.try_into()?;
+                ret.set_noc_check(data);
+            }
+            AdminCommand::ReloadConfig(ref data) => {
+                ret.set_cmd(protos::AdminDescContent_Command::ReloadConfig);
+                let data = data.try_into()?;
+                ret.set_reload_config(data);
+            }
+            AdminCommand::WebAuthnCredential(ref data) => {
+                ret.set_cmd(protos::AdminDescContent_Command::WebAuthnCredential);
+                let data = data.try_into()?;
+                ret.set_webauthn_credential(data);
+            }
+            AdminCommand::UnlockStorage(ref data) => {
+                ret.set_cmd(protos::AdminDescContent_Command::UnlockStorage);
+                let data = data.try_into()?;
+                ret.set_unlock_storage(data);
+            }
         }
 
         ret.set_target(value.target.to_vec().unwrap());
@@ -220,4 +463,74 @@ mod test {
         let c_cmd = obj.into_command();
         assert_eq!(c_cmd, cmd);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_noc_check_object() {
+        let data = AdminNOCCheckData {
+            remove_dangling_meta: true,
+            orphan_blob_action: AdminNOCOrphanBlobAction::Quarantine,
+            ops_per_sec: 100,
+        };
+
+        let cmd = AdminCommand::NOCCheck(data);
+
+        let target = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        let obj = AdminObject::create(PeopleId::default().into(), target, cmd.clone());
+        let buf = obj.to_vec().unwrap();
+
+        let obj = AdminObject::clone_from_slice(&buf).unwrap();
+        assert_eq!(obj.into_command(), cmd);
+    }
+
+    #[test]
+    fn test_reload_config_object() {
+        let data = AdminReloadConfigData {
+            config: "[front]\napp_name_cache_size = 512\n".to_owned(),
+        };
+
+        let cmd = AdminCommand::ReloadConfig(data);
+
+        let target = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        let obj = AdminObject::create(PeopleId::default().into(), target, cmd.clone());
+        let buf = obj.to_vec().unwrap();
+
+        let obj = AdminObject::clone_from_slice(&buf).unwrap();
+        assert_eq!(obj.into_command(), cmd);
+    }
+
+    #[test]
+    fn test_webauthn_credential_object() {
+        let data = AdminWebAuthnCredentialData {
+            credential_id: "AAECAwQFBgc".to_owned(),
+            public_key: "04".to_owned() + &"11".repeat(64),
+            rp_id: "www.cyfs.com".to_owned(),
+            remove: false,
+        };
+
+        let cmd = AdminCommand::WebAuthnCredential(data);
+
+        let target = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        let obj = AdminObject::create(PeopleId::default().into(), target, cmd.clone());
+        let buf = obj.to_vec().unwrap();
+
+        let obj = AdminObject::clone_from_slice(&buf).unwrap();
+        assert_eq!(obj.into_command(), cmd);
+    }
+
+    #[test]
+    fn test_unlock_storage_object() {
+        let data = AdminUnlockStorageData {
+            passphrase: "owner passphrase".to_owned(),
+        };
+        assert!(!format!("{:?}", data).contains("owner passphrase"));
+
+        let cmd = AdminCommand::UnlockStorage(data);
+
+        let target = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        let obj = AdminObject::create(PeopleId::default().into(), target, cmd.clone());
+        let buf = obj.to_vec().unwrap();
+
+        let obj = AdminObject::clone_from_slice(&buf).unwrap();
+        assert_eq!(obj.into_command(), cmd);
+    }
+}
@@ -0,0 +1,20 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// create_admin_confirm_challenge
+pub struct AdminConfirmCreateChallengeInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub action: AdminConfirmAction,
+    pub target: String,
+}
+
+pub type AdminConfirmCreateChallengeInputResponse = AdminConfirmCreateChallengeOutputResponse;
+
+// admin_confirm
+pub struct AdminConfirmInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub assertion: WebAuthnAssertion,
+}
+
+pub type AdminConfirmInputResponse = AdminConfirmOutputResponse;
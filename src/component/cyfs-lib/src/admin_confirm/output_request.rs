@@ -0,0 +1,172 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+// 需要owner的WebAuthn认证器确认的敏感操作，owner注册了认证器之后才会要求确认
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AdminConfirmAction {
+    // root_state上的rmeta权限修改，target为被修改的dec_id
+    AclChange,
+
+    // 从回收站恢复，target为回收站记录id
+    Restore,
+
+    // 彻底删除回收站记录，target为回收站记录id，清空整个回收站时为*
+    PurgeTrash,
+
+    // 注册或者移除认证器，target为credential_id；第一个认证器注册时不需要确认
+    CredentialChange,
+
+    // 停止当前ood在sn和链上的注册(zone迁移的切换注册步骤)，target为当前ood的device_id
+    DeviceRevoke,
+
+    // 使用当前zone的备份恢复到新ood(zone迁移)，target为迁移任务id
+    BackupRestore,
+}
+
+impl Display for AdminConfirmAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = match self {
+            Self::AclChange => "acl_change",
+            Self::Restore => "restore",
+            Self::PurgeTrash => "purge_trash",
+            Self::CredentialChange => "credential_change",
+            Self::DeviceRevoke => "device_revoke",
+            Self::BackupRestore => "backup_restore",
+        };
+
+        write!(f, "{}", v)
+    }
+}
+
+impl FromStr for AdminConfirmAction {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        match s {
+            "acl_change" => Ok(Self::AclChange),
+            "restore" => Ok(Self::Restore),
+            "purge_trash" => Ok(Self::PurgeTrash),
+            "credential_change" => Ok(Self::CredentialChange),
+            "device_revoke" => Ok(Self::DeviceRevoke),
+            "backup_restore" => Ok(Self::BackupRestore),
+            _ => {
+                let msg = format!("unknown AdminConfirmAction value: {}", s);
+                error!("{}", msg);
+
+                Err(BuckyError::new(BuckyErrorCode::InvalidData, msg))
+            }
+        }
+    }
+}
+
+// 申请一个challenge，用于navigator.credentials.get的publicKey.challenge
+#[derive(Debug, Clone)]
+pub struct AdminConfirmCreateChallengeOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub action: AdminConfirmAction,
+    pub target: String,
+}
+
+impl Display for AdminConfirmCreateChallengeOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, action: {}, target: {}",
+            self.common, self.action, self.target
+        )
+    }
+}
+
+impl AdminConfirmCreateChallengeOutputRequest {
+    pub fn new(action: AdminConfirmAction, target: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            action,
+            target: target.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConfirmCreateChallengeOutputResponse {
+    // base64url编码，和clientDataJSON里的challenge一致
+    pub challenge: String,
+    pub rp_id: String,
+
+    // 已注册的认证器，用于allowCredentials，base64url编码
+    pub credential_ids: Vec<String>,
+
+    // bucky time
+    pub expire_time: u64,
+}
+
+impl Display for AdminConfirmCreateChallengeOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "challenge: {}, rp_id: {}, credentials: {}, expire_time: {}",
+            self.challenge,
+            self.rp_id,
+            self.credential_ids.len(),
+            self.expire_time
+        )
+    }
+}
+
+// 认证器返回的AuthenticatorAssertionResponse，字段都是base64url编码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnAssertion {
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+
+    // DER编码的ES256签名
+    pub signature: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdminConfirmOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub assertion: WebAuthnAssertion,
+}
+
+impl Display for AdminConfirmOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, credential_id: {}",
+            self.common, self.assertion.credential_id
+        )
+    }
+}
+
+impl AdminConfirmOutputRequest {
+    pub fn new(assertion: WebAuthnAssertion) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            assertion,
+        }
+    }
+}
+
+// 确认成功后，在expire_time之前可以执行一次对应的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConfirmOutputResponse {
+    pub action: AdminConfirmAction,
+    pub target: String,
+    pub expire_time: u64,
+}
+
+impl Display for AdminConfirmOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "action: {}, target: {}, expire_time: {}",
+            self.action, self.target, self.expire_time
+        )
+    }
+}
@@ -0,0 +1,65 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<AdminConfirmCreateChallengeOutputRequest>
+    for AdminConfirmCreateChallengeOutputRequest
+{
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "action", &self.action);
+        JsonCodecHelper::encode_string_field(&mut obj, "target", &self.target);
+        obj
+    }
+
+    fn decode_json(
+        obj: &Map<String, Value>,
+    ) -> BuckyResult<AdminConfirmCreateChallengeOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            action: JsonCodecHelper::decode_string_field(obj, "action")?,
+            target: JsonCodecHelper::decode_string_field(obj, "target")?,
+        })
+    }
+}
+
+impl JsonCodec<AdminConfirmOutputRequest> for AdminConfirmOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(
+            &mut obj,
+            "credential_id",
+            &self.assertion.credential_id,
+        );
+        JsonCodecHelper::encode_string_field(
+            &mut obj,
+            "client_data_json",
+            &self.assertion.client_data_json,
+        );
+        JsonCodecHelper::encode_string_field(
+            &mut obj,
+            "authenticator_data",
+            &self.assertion.authenticator_data,
+        );
+        JsonCodecHelper::encode_string_field(&mut obj, "signature", &self.assertion.signature);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<AdminConfirmOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            assertion: WebAuthnAssertion {
+                credential_id: JsonCodecHelper::decode_string_field(obj, "credential_id")?,
+                client_data_json: JsonCodecHelper::decode_string_field(obj, "client_data_json")?,
+                authenticator_data: JsonCodecHelper::decode_string_field(
+                    obj,
+                    "authenticator_data",
+                )?,
+                signature: JsonCodecHelper::decode_string_field(obj, "signature")?,
+            },
+        })
+    }
+}
@@ -0,0 +1,19 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait AdminConfirmOutputProcessor: Sync + Send + 'static {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeOutputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeOutputResponse>;
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmOutputRequest,
+    ) -> BuckyResult<AdminConfirmOutputResponse>;
+}
+
+pub type AdminConfirmOutputProcessorRef = Arc<dyn AdminConfirmOutputProcessor>;
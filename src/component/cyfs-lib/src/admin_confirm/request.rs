@@ -0,0 +1,7 @@
+use super::output_request::*;
+
+pub type AdminConfirmCreateChallengeRequest = AdminConfirmCreateChallengeOutputRequest;
+pub type AdminConfirmCreateChallengeResponse = AdminConfirmCreateChallengeOutputResponse;
+
+pub type AdminConfirmRequest = AdminConfirmOutputRequest;
+pub type AdminConfirmResponse = AdminConfirmOutputResponse;
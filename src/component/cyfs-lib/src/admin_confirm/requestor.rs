@@ -0,0 +1,142 @@
+use super::output_request::*;
+use super::processor::*;
+use super::request::*;
+use crate::{base::*, requestor::*, SharedObjectStackDecID, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use http_types::{Method, Request, Url};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct AdminConfirmRequestor {
+    dec_id: Option<SharedObjectStackDecID>,
+    requestor: HttpRequestorRef,
+    service_url: Url,
+}
+
+impl AdminConfirmRequestor {
+    pub fn new(dec_id: Option<SharedObjectStackDecID>, requestor: HttpRequestorRef) -> Self {
+        let addr = requestor.remote_addr();
+
+        let url = format!("http://{}/admin_confirm/", addr);
+        let url = Url::parse(&url).unwrap();
+
+        Self {
+            dec_id,
+            requestor,
+            service_url: url,
+        }
+    }
+
+    pub fn into_processor(self) -> AdminConfirmOutputProcessorRef {
+        Arc::new(self)
+    }
+
+    pub fn clone_processor(&self) -> AdminConfirmOutputProcessorRef {
+        self.clone().into_processor()
+    }
+
+    fn encode_common_headers(&self, com_req: &UtilOutputRequestCommon, http_req: &mut Request) {
+        if let Some(dec_id) = &com_req.dec_id {
+            http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+        } else if let Some(dec_id) = &self.dec_id {
+            if let Some(dec_id) = dec_id.get() {
+                http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+            }
+        }
+
+        RequestorHelper::encode_opt_header_with_encoding(
+            http_req,
+            cyfs_base::CYFS_REQ_PATH,
+            com_req.req_path.as_deref(),
+        );
+
+        if let Some(target) = &com_req.target {
+            http_req.insert_header(cyfs_base::CYFS_TARGET, target.to_string());
+        }
+
+        http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+    }
+
+    pub async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeResponse> {
+        let url = self.service_url.join("challenge").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!(
+                    "parse create_admin_confirm_challenge resp body error! err={}",
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "admin confirm create_admin_confirm_challenge failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn admin_confirm(
+        &self,
+        req: AdminConfirmRequest,
+    ) -> BuckyResult<AdminConfirmResponse> {
+        let url = self.service_url.join("confirm").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse admin_confirm resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "admin confirm admin_confirm failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminConfirmOutputProcessor for AdminConfirmRequestor {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeOutputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeOutputResponse> {
+        Self::create_admin_confirm_challenge(self, req).await
+    }
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmOutputRequest,
+    ) -> BuckyResult<AdminConfirmOutputResponse> {
+        Self::admin_confirm(self, req).await
+    }
+}
@@ -0,0 +1,30 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// register_api_route
+pub struct ApiGatewayRegisterRouteInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub path: String,
+    pub port: u16,
+    pub strip_prefix: bool,
+    pub access: Option<AccessString>,
+}
+
+pub type ApiGatewayRegisterRouteInputResponse = ApiGatewayRegisterRouteOutputResponse;
+
+// unregister_api_route
+pub struct ApiGatewayUnregisterRouteInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub path: Option<String>,
+}
+
+pub type ApiGatewayUnregisterRouteInputResponse = ApiGatewayUnregisterRouteOutputResponse;
+
+// get_api_routes
+pub struct ApiGatewayGetRoutesInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+}
+
+pub type ApiGatewayGetRoutesInputResponse = ApiGatewayGetRoutesOutputResponse;
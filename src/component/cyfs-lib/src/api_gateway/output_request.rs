@@ -0,0 +1,164 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// dec通过front服务的/a/{dec}/api/*对外提供的http路由，front服务反向代理到dec在当前设备上的本地端口
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DecApiRoute {
+    pub dec_id: ObjectId,
+
+    // 路由前缀，相对于/a/{dec}/api，比如/v1，为/时匹配所有请求
+    pub path: String,
+
+    // dec服务在当前设备上监听的端口，只会转发到127.0.0.1
+    pub port: u16,
+
+    // 转发时是否去掉路由前缀
+    pub strip_prefix: bool,
+
+    // 访问权限，AccessString的值，按请求来源检查call权限
+    pub access: u32,
+
+    pub register_time: u64,
+}
+
+impl DecApiRoute {
+    // 返回转发给dec服务的路径，不匹配时返回None
+    pub fn match_path(&self, path: &str) -> Option<String> {
+        let prefix = self.path.trim_end_matches('/');
+        if prefix.is_empty() {
+            return Some(path.to_owned());
+        }
+
+        let rest = path.strip_prefix(prefix)?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+
+        if !self.strip_prefix {
+            Some(path.to_owned())
+        } else if rest.is_empty() {
+            Some("/".to_owned())
+        } else {
+            Some(rest.to_owned())
+        }
+    }
+}
+
+// 注册或者更新请求方dec在当前设备上的api路由，同一个前缀重复注册会覆盖
+#[derive(Debug, Clone)]
+pub struct ApiGatewayRegisterRouteOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub path: String,
+    pub port: u16,
+    pub strip_prefix: bool,
+
+    // 为空使用AccessString::dec_default()
+    pub access: Option<AccessString>,
+}
+
+impl Display for ApiGatewayRegisterRouteOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, path: {}, port: {}, strip_prefix: {}, access: {:?}",
+            self.common, self.path, self.port, self.strip_prefix, self.access
+        )
+    }
+}
+
+impl ApiGatewayRegisterRouteOutputRequest {
+    pub fn new(path: impl Into<String>, port: u16) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            path: path.into(),
+            port,
+            strip_prefix: false,
+            access: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiGatewayRegisterRouteOutputResponse {
+    pub route: DecApiRoute,
+}
+
+impl Display for ApiGatewayRegisterRouteOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dec: {}, path: {}, port: {}",
+            self.route.dec_id, self.route.path, self.route.port
+        )
+    }
+}
+
+// 移除请求方dec在当前设备上的api路由，path为空时移除该dec的所有路由
+#[derive(Debug, Clone)]
+pub struct ApiGatewayUnregisterRouteOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub path: Option<String>,
+}
+
+impl Display for ApiGatewayUnregisterRouteOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, path: {:?}", self.common, self.path)
+    }
+}
+
+impl ApiGatewayUnregisterRouteOutputRequest {
+    pub fn new(path: Option<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            path,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiGatewayUnregisterRouteOutputResponse {
+    // 被移除的路由
+    pub list: Vec<DecApiRoute>,
+}
+
+impl Display for ApiGatewayUnregisterRouteOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "removed: {}", self.list.len())
+    }
+}
+
+// 查询当前设备上注册的api路由，dec_id为空时返回所有dec的路由
+#[derive(Debug, Clone)]
+pub struct ApiGatewayGetRoutesOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+}
+
+impl Display for ApiGatewayGetRoutesOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, dec_id: {:?}", self.common, self.dec_id)
+    }
+}
+
+impl ApiGatewayGetRoutesOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiGatewayGetRoutesOutputResponse {
+    pub list: Vec<DecApiRoute>,
+}
+
+impl Display for ApiGatewayGetRoutesOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
@@ -0,0 +1,66 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ApiGatewayRegisterRouteOutputRequest> for ApiGatewayRegisterRouteOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "path", &self.path);
+        JsonCodecHelper::encode_number_field(&mut obj, "port", self.port);
+        JsonCodecHelper::encode_bool_field(&mut obj, "strip_prefix", self.strip_prefix);
+
+        if let Some(access) = &self.access {
+            JsonCodecHelper::encode_number_field(&mut obj, "access", access.value());
+        }
+
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ApiGatewayRegisterRouteOutputRequest> {
+        let access: Option<u32> = JsonCodecHelper::decode_option_int_field(obj, "access")?;
+
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            path: JsonCodecHelper::decode_string_field(obj, "path")?,
+            port: JsonCodecHelper::decode_int_field(obj, "port")?,
+            strip_prefix: JsonCodecHelper::decode_bool_field(obj, "strip_prefix")?,
+            access: access.map(|v| AccessString::new(v)),
+        })
+    }
+}
+
+impl JsonCodec<ApiGatewayUnregisterRouteOutputRequest> for ApiGatewayUnregisterRouteOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "path", self.path.as_ref());
+        obj
+    }
+
+    fn decode_json(
+        obj: &Map<String, Value>,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            path: JsonCodecHelper::decode_option_string_field(obj, "path")?,
+        })
+    }
+}
+
+impl JsonCodec<ApiGatewayGetRoutesOutputRequest> for ApiGatewayGetRoutesOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ApiGatewayGetRoutesOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+        })
+    }
+}
@@ -0,0 +1,24 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ApiGatewayOutputProcessor: Sync + Send + 'static {
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteOutputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteOutputResponse>;
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteOutputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteOutputResponse>;
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesOutputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesOutputResponse>;
+}
+
+pub type ApiGatewayOutputProcessorRef = Arc<dyn ApiGatewayOutputProcessor>;
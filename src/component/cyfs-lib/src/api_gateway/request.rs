@@ -0,0 +1,10 @@
+use super::output_request::*;
+
+pub type ApiGatewayRegisterRouteRequest = ApiGatewayRegisterRouteOutputRequest;
+pub type ApiGatewayRegisterRouteResponse = ApiGatewayRegisterRouteOutputResponse;
+
+pub type ApiGatewayUnregisterRouteRequest = ApiGatewayUnregisterRouteOutputRequest;
+pub type ApiGatewayUnregisterRouteResponse = ApiGatewayUnregisterRouteOutputResponse;
+
+pub type ApiGatewayGetRoutesRequest = ApiGatewayGetRoutesOutputRequest;
+pub type ApiGatewayGetRoutesResponse = ApiGatewayGetRoutesOutputResponse;
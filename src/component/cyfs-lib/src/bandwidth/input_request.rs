@@ -0,0 +1,13 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// get_bandwidth_report
+pub struct BandwidthGetReportInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub month: Option<String>,
+    pub dec_id: Option<ObjectId>,
+    pub by_day: bool,
+}
+
+pub type BandwidthGetReportInputResponse = BandwidthGetReportOutputResponse;
@@ -0,0 +1,91 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// 流量统计
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum BandwidthDirection {
+    Upload,
+    Download,
+}
+
+// Bdt为tunnel层统计的全部流量；Http和Ndn为其中可以归属到具体dec的部分，不能和Bdt相加
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum BandwidthInterface {
+    Bdt,
+    Http,
+    Ndn,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthUsageItem {
+    // YYYY-MM-DD(UTC)，按月汇总时为空
+    pub day: Option<String>,
+
+    pub direction: BandwidthDirection,
+    pub interface: BandwidthInterface,
+
+    // 对端zone的owner，无法确定时为空
+    pub peer_zone: Option<ObjectId>,
+
+    // 流量归属的dec，Bdt统计的流量总是为空
+    pub dec_id: Option<ObjectId>,
+
+    pub bytes: u64,
+}
+
+// 查询目标设备(默认为当前设备)某个月的流量统计
+#[derive(Debug, Clone)]
+pub struct BandwidthGetReportOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    // YYYY-MM(UTC)，为空时为当前月
+    pub month: Option<String>,
+
+    // 只返回指定dec的流量
+    pub dec_id: Option<ObjectId>,
+
+    // 是否按天返回，否则按月汇总
+    pub by_day: bool,
+}
+
+impl Display for BandwidthGetReportOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, month: {:?}, dec_id: {:?}, by_day: {}",
+            self.common, self.month, self.dec_id, self.by_day
+        )
+    }
+}
+
+impl BandwidthGetReportOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            month: None,
+            dec_id: None,
+            by_day: false,
+        }
+    }
+
+    pub fn new_month(month: impl Into<String>) -> Self {
+        let mut ret = Self::new();
+        ret.month = Some(month.into());
+        ret
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthGetReportOutputResponse {
+    pub month: String,
+    pub list: Vec<BandwidthUsageItem>,
+}
+
+impl Display for BandwidthGetReportOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "month: {}, list: {}", self.month, self.list.len())
+    }
+}
@@ -0,0 +1,24 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<BandwidthGetReportOutputRequest> for BandwidthGetReportOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "month", self.month.as_ref());
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_bool_field(&mut obj, "by_day", self.by_day);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<BandwidthGetReportOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            month: JsonCodecHelper::decode_option_string_field(obj, "month")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            by_day: JsonCodecHelper::decode_bool_field(obj, "by_day")?,
+        })
+    }
+}
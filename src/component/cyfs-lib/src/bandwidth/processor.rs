@@ -0,0 +1,14 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait BandwidthOutputProcessor: Sync + Send + 'static {
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportOutputRequest,
+    ) -> BuckyResult<BandwidthGetReportOutputResponse>;
+}
+
+pub type BandwidthOutputProcessorRef = Arc<dyn BandwidthOutputProcessor>;
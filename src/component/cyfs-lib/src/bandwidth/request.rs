@@ -0,0 +1,4 @@
+use super::output_request::*;
+
+pub type BandwidthGetReportRequest = BandwidthGetReportOutputRequest;
+pub type BandwidthGetReportResponse = BandwidthGetReportOutputResponse;
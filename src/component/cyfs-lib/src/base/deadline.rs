@@ -0,0 +1,87 @@
+use cyfs_base::*;
+
+use std::future::Future;
+use std::time::Duration;
+
+// The deadline of a request, in bucky time; none means no limit
+pub struct RequestDeadline;
+
+impl RequestDeadline {
+    pub fn from_timeout(timeout: Duration) -> u64 {
+        bucky_time_now() + timeout.as_micros() as u64
+    }
+
+    pub fn is_expired(deadline: Option<u64>) -> bool {
+        match deadline {
+            Some(deadline) => bucky_time_now() >= deadline,
+            None => false,
+        }
+    }
+
+    // The remaining time before the deadline, return Some(0) if already expired
+    pub fn remaining(deadline: Option<u64>) -> Option<Duration> {
+        deadline.map(|deadline| {
+            let now = bucky_time_now();
+            if now >= deadline {
+                Duration::from_micros(0)
+            } else {
+                Duration::from_micros(deadline - now)
+            }
+        })
+    }
+
+    pub fn check(deadline: Option<u64>) -> BuckyResult<()> {
+        if Self::is_expired(deadline) {
+            let msg = format!(
+                "request deadline expired! deadline={}, now={}",
+                deadline.unwrap(),
+                bucky_time_now()
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+        }
+
+        Ok(())
+    }
+
+    // Run the future until it completes or the deadline expires
+    pub async fn run<F, T>(deadline: Option<u64>, fut: F) -> BuckyResult<T>
+    where
+        F: Future<Output = BuckyResult<T>>,
+    {
+        match Self::remaining(deadline) {
+            Some(dur) => match async_std::future::timeout(dur, fut).await {
+                Ok(ret) => ret,
+                Err(_) => {
+                    let msg = format!(
+                        "request been aborted by deadline! deadline={}",
+                        deadline.unwrap()
+                    );
+                    warn!("{}", msg);
+                    Err(BuckyError::new(BuckyErrorCode::Timeout, msg))
+                }
+            },
+            None => fut.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        assert!(!RequestDeadline::is_expired(None));
+        assert!(RequestDeadline::check(None).is_ok());
+
+        let deadline = RequestDeadline::from_timeout(Duration::from_secs(10));
+        assert!(!RequestDeadline::is_expired(Some(deadline)));
+        assert!(RequestDeadline::remaining(Some(deadline)).unwrap() > Duration::from_secs(5));
+
+        let deadline = bucky_time_now() - 1;
+        let err = RequestDeadline::check(Some(deadline)).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::Timeout);
+        assert_eq!(RequestDeadline::remaining(Some(deadline)), Some(Duration::from_micros(0)));
+    }
+}
@@ -0,0 +1,210 @@
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+// The structured error body of the http response, with retry hints for the caller.
+// The error returned by the requestors keeps the hints of the response in its origin,
+// so the SDK users can get them with `RequestErrorInfo::from(&e)`
+#[derive(Clone, Debug)]
+pub struct RequestErrorInfo {
+    pub code: BuckyErrorCode,
+    pub msg: String,
+
+    // whether the same request may succeed if retried later
+    pub retryable: bool,
+
+    // suggested delay before retry, in seconds
+    pub retry_after: Option<u32>,
+}
+
+impl RequestErrorInfo {
+    pub fn new(code: BuckyErrorCode, msg: impl Into<String>) -> Self {
+        Self {
+            code,
+            msg: msg.into(),
+            retryable: Self::is_retryable_code(code),
+            retry_after: Self::default_retry_after(code),
+        }
+    }
+
+    // Errors caused by the network or the temporary state of the stack, the others will always fail on retry
+    pub fn is_retryable_code(code: BuckyErrorCode) -> bool {
+        match code {
+            BuckyErrorCode::Timeout
+            | BuckyErrorCode::ErrorState
+            | BuckyErrorCode::ConnectFailed
+            | BuckyErrorCode::ConnectInterZoneFailed
+            | BuckyErrorCode::ConnectionRefused
+            | BuckyErrorCode::ConnectionReset
+            | BuckyErrorCode::ConnectionAborted
+            | BuckyErrorCode::NotConnected
+            | BuckyErrorCode::Interrupted
+            | BuckyErrorCode::WouldBlock
+            | BuckyErrorCode::BrokenPipe
+            | BuckyErrorCode::OutofSessionLimit
+            | BuckyErrorCode::NetworkError
+            | BuckyErrorCode::Pending
+            | BuckyErrorCode::NotInit
+            | BuckyErrorCode::DecNotRunning
+            | BuckyErrorCode::StorageReadOnly
+            | BuckyErrorCode::Busy => true,
+            _ => false,
+        }
+    }
+
+    pub fn default_retry_after(code: BuckyErrorCode) -> Option<u32> {
+        match code {
+            BuckyErrorCode::OutofSessionLimit | BuckyErrorCode::Pending => Some(1),
+            BuckyErrorCode::ConnectFailed | BuckyErrorCode::ConnectInterZoneFailed => Some(3),
+            BuckyErrorCode::NotInit | BuckyErrorCode::DecNotRunning => Some(5),
+            BuckyErrorCode::Busy => Some(10),
+            BuckyErrorCode::StorageReadOnly => Some(30),
+            _ => None,
+        }
+    }
+
+    pub fn into_error(self) -> BuckyError {
+        let origin = BuckyOriginError::ErrorMsg(self.encode_string());
+        BuckyError::new(self.code, self.msg).with_origin(origin)
+    }
+}
+
+impl From<&BuckyError> for RequestErrorInfo {
+    fn from(e: &BuckyError) -> Self {
+        // the error from the requestors, use the hints of the response
+        if let Some(BuckyOriginError::ErrorMsg(body)) = e.origin() {
+            if let Ok(info) = Self::decode_string(body) {
+                if info.code == e.code() {
+                    return info;
+                }
+            }
+        }
+
+        Self::new(e.code(), e.msg())
+    }
+}
+
+impl From<BuckyError> for RequestErrorInfo {
+    fn from(e: BuckyError) -> Self {
+        Self::from(&e)
+    }
+}
+
+impl Into<BuckyError> for RequestErrorInfo {
+    fn into(self) -> BuckyError {
+        self.into_error()
+    }
+}
+
+// keep compatible with the encoding of BuckyError, so the old versions can still decode the body
+impl JsonCodec<RequestErrorInfo> for RequestErrorInfo {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+
+        let code: u16 = self.code.into();
+        obj.insert("code".to_owned(), Value::String(code.to_string()));
+        obj.insert("msg".to_owned(), Value::String(self.msg.clone()));
+        JsonCodecHelper::encode_bool_field(&mut obj, "retryable", self.retryable);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "retry_after", self.retry_after);
+
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<Self> {
+        let code: u16 = match obj.get("code") {
+            Some(v) => JsonCodecHelper::decode_to_int(v)?,
+            None => BuckyErrorCode::Unknown.into(),
+        };
+        let code = BuckyErrorCode::from(code);
+
+        let msg = match obj.get("msg") {
+            Some(v) => v.as_str().unwrap_or("").to_owned(),
+            None => "".to_owned(),
+        };
+
+        // the response from old version stack has no retry hints
+        let retryable = match obj.get("retryable") {
+            Some(_) => JsonCodecHelper::decode_bool_field(obj, "retryable")?,
+            None => Self::is_retryable_code(code),
+        };
+
+        let retry_after = match obj.get("retry_after") {
+            Some(_) => JsonCodecHelper::decode_option_int_field(obj, "retry_after")?,
+            None => Self::default_retry_after(code),
+        };
+
+        Ok(Self {
+            code,
+            msg,
+            retryable,
+            retry_after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_codec() {
+        let info = RequestErrorInfo::new(BuckyErrorCode::ConnectFailed, "connect failed");
+        assert!(info.retryable);
+        assert_eq!(info.retry_after, Some(3));
+
+        let s = info.encode_string();
+        let ret = RequestErrorInfo::decode_string(&s).unwrap();
+        assert_eq!(ret.code, BuckyErrorCode::ConnectFailed);
+        assert_eq!(ret.msg, "connect failed");
+        assert!(ret.retryable);
+        assert_eq!(ret.retry_after, Some(3));
+
+        // still compatible with the BuckyError encoding
+        let e = BuckyError::decode_string(&s).unwrap();
+        assert_eq!(e.code(), BuckyErrorCode::ConnectFailed);
+
+        let e = BuckyError::new(BuckyErrorCode::NotFound, "not found");
+        let ret = RequestErrorInfo::decode_string(&e.encode_string()).unwrap();
+        assert_eq!(ret.code, BuckyErrorCode::NotFound);
+        assert!(!ret.retryable);
+        assert!(ret.retry_after.is_none());
+    }
+
+    #[test]
+    fn test_from_requestor_error() {
+        // the hints of the response, eg. overridden by the Retry-After header
+        let mut info = RequestErrorInfo::new(BuckyErrorCode::Busy, "busy");
+        info.retry_after = Some(42);
+
+        let e = info.clone().into_error();
+        assert_eq!(e.code(), BuckyErrorCode::Busy);
+        assert_eq!(e.msg(), "busy");
+
+        let ret = RequestErrorInfo::from(&e);
+        assert!(ret.retryable);
+        assert_eq!(ret.retry_after, Some(42));
+
+        // the origin is lost after clone, fall back to the hints of the error code
+        let ret = RequestErrorInfo::from(&e.clone());
+        assert_eq!(
+            ret.retry_after,
+            RequestErrorInfo::default_retry_after(BuckyErrorCode::Busy)
+        );
+
+        // the error without origin, eg. decoded from the body directly
+        for code in [
+            BuckyErrorCode::OutofSessionLimit,
+            BuckyErrorCode::DecNotRunning,
+            BuckyErrorCode::PermissionDenied,
+        ] {
+            let info = RequestErrorInfo::new(code, "test");
+            let e = BuckyError::decode_string(&info.encode_string()).unwrap();
+
+            let ret = RequestErrorInfo::from(&e);
+            assert_eq!(ret.code, info.code);
+            assert_eq!(ret.msg, info.msg);
+            assert_eq!(ret.retryable, info.retryable);
+            assert_eq!(ret.retry_after, info.retry_after);
+        }
+    }
+}
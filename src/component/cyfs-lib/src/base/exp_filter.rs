@@ -1087,6 +1087,48 @@ pub trait ExpReservedTokenTranslator {
     fn trans(&self, token: &str) -> ExpTokenEvalValue;
 }
 
+// 缓存关键字的计算结果，同一个请求依次匹配多个表达式时，避免重复计算
+pub struct ExpReservedTokenCachedTranslator<'a, T: ExpReservedTokenTranslator> {
+    translator: &'a T,
+    cache: std::cell::RefCell<std::collections::HashMap<String, ExpTokenEvalValue>>,
+}
+
+impl<'a, T: ExpReservedTokenTranslator> ExpReservedTokenCachedTranslator<'a, T> {
+    pub fn new(translator: &'a T) -> Self {
+        Self {
+            translator,
+            cache: std::cell::RefCell::new(std::collections::HashMap::new()),
+        }
+    }
+}
+
+impl<'a, T: ExpReservedTokenTranslator> ExpReservedTokenTranslator
+    for ExpReservedTokenCachedTranslator<'a, T>
+{
+    fn trans(&self, token: &str) -> ExpTokenEvalValue {
+        if let Some(v) = self.cache.borrow().get(token) {
+            return v.clone();
+        }
+
+        let v = self.translator.trans(token);
+        self.cache.borrow_mut().insert(token.to_owned(), v.clone());
+        v
+    }
+}
+
+// 表达式成立的必要条件: 顶层&&连接的"关键字==常量"子表达式
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExpRequiredCondition {
+    pub token: String,
+    pub value: ExpTokenEvalValue,
+}
+
+impl ExpRequiredCondition {
+    pub fn is_match(&self, translator: &impl ExpReservedTokenTranslator) -> bool {
+        translator.trans(&self.token) == self.value
+    }
+}
+
 #[derive(Debug, Clone)]
 enum ExpEvalItem {
     Op(ExpOp),
@@ -1230,6 +1272,60 @@ impl ExpEvaluator {
     pub fn into_exp(self) -> String {
         self.exp
     }
+
+    // 提取表达式成立的必要条件，可以在完整求值前快速排除不匹配的请求
+    // 只分析顶层的&&，包含||或者!的子表达式不提取条件
+    pub fn required_conditions(&self) -> Vec<ExpRequiredCondition> {
+        enum Operand<'a> {
+            Reserved(&'a str),
+            Value(&'a ExpTokenEvalValue),
+            Exp(Vec<ExpRequiredCondition>),
+        }
+
+        let mut operands: Vec<Operand> = vec![];
+        for item in self.rpn.iter() {
+            match item {
+                ExpEvalItem::Op(op) => match op.arity() {
+                    ExpOpArity::Unary => {
+                        if operands.pop().is_none() {
+                            return vec![];
+                        }
+                        operands.push(Operand::Exp(vec![]));
+                    }
+                    ExpOpArity::Binary => {
+                        let (right, left) = match (operands.pop(), operands.pop()) {
+                            (Some(right), Some(left)) => (right, left),
+                            _ => return vec![],
+                        };
+
+                        let conditions = match (op, left, right) {
+                            (ExpOp::EQ, Operand::Reserved(token), Operand::Value(value))
+                            | (ExpOp::EQ, Operand::Value(value), Operand::Reserved(token)) => {
+                                vec![ExpRequiredCondition {
+                                    token: token.to_owned(),
+                                    value: value.clone(),
+                                }]
+                            }
+                            (ExpOp::AND, Operand::Exp(mut left), Operand::Exp(right)) => {
+                                left.extend(right);
+                                left
+                            }
+                            _ => vec![],
+                        };
+                        operands.push(Operand::Exp(conditions));
+                    }
+                },
+                ExpEvalItem::ReservedToken(v) => operands.push(Operand::Reserved(v)),
+                ExpEvalItem::EvalToken(v) => operands.push(Operand::Value(v)),
+                ExpEvalItem::ConstToken(_) => operands.push(Operand::Exp(vec![])),
+            }
+        }
+
+        match operands.pop() {
+            Some(Operand::Exp(conditions)) if operands.is_empty() => conditions,
+            _ => vec![],
+        }
+    }
     
     fn convert(
         exp: &str,
@@ -1704,4 +1800,39 @@ mod exp_tests {
         let result = exp.eval(&translator).unwrap();
         assert_eq!(result, true);
     }
+
+    #[test]
+    fn test_required_conditions() {
+        let mut token_list = ExpReservedTokenList::new();
+        token_list.add_glob("req_path");
+        token_list.add_i8("a");
+        token_list.add_i32("b");
+        token_list.add_bool("c");
+
+        let exp = ExpEvaluator::new("a == 10 && (b == 1 && c) && req_path == '/**/*.js'", &token_list).unwrap();
+        let conditions = exp.required_conditions();
+        assert_eq!(conditions.len(), 3);
+        assert_eq!(conditions[0].token, "a");
+        assert_eq!(conditions[0].value, ExpTokenEvalValue::I8(10));
+        assert_eq!(conditions[1].token, "b");
+        assert_eq!(conditions[2].token, "req_path");
+
+        let translator = ExpReservedTokenCachedTranslator::new(&TestTranslator {});
+        assert!(conditions.iter().all(|c| c.is_match(&translator)));
+
+        let exp = ExpEvaluator::new("a == 10 || b == 1", &token_list).unwrap();
+        assert!(exp.required_conditions().is_empty());
+
+        let exp = ExpEvaluator::new("!(a == 10) && 1 == b", &token_list).unwrap();
+        let conditions = exp.required_conditions();
+        assert_eq!(conditions.len(), 1);
+        assert_eq!(conditions[0].token, "b");
+        assert_eq!(conditions[0].value, ExpTokenEvalValue::I32(1));
+
+        let exp = ExpEvaluator::new("a == 9 && b == 1", &token_list).unwrap();
+        assert!(!exp
+            .required_conditions()
+            .iter()
+            .all(|c| c.is_match(&translator)));
+    }
 }
@@ -1,23 +1,31 @@
 
 mod config;
+mod deadline;
+mod error_info;
 mod exp_filter;
 mod front;
+mod page;
 mod protocol;
 mod range;
 mod request;
 mod requestor_helper;
+mod retry;
 mod select_request;
 mod source;
 mod tcp_listener;
 mod zone;
 
 pub use config::*;
+pub use deadline::*;
+pub use error_info::*;
 pub use exp_filter::*;
 pub use front::*;
+pub use page::*;
 pub use protocol::*;
 pub use range::*;
 pub use request::*;
 pub use requestor_helper::*;
+pub use retry::*;
 pub use select_request::*;
 pub use source::*;
 pub use tcp_listener::*;
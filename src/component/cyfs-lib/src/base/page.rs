@@ -0,0 +1,315 @@
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+
+// 列表类接口的默认分页大小和上限，超过上限的请求会被截断
+pub const CYFS_PAGE_SIZE_DEFAULT: u32 = 64;
+pub const CYFS_PAGE_SIZE_MAX: u32 = 1024;
+
+const PAGE_CURSOR_VERSION_OFFSET: u8 = 1;
+
+// 分页游标，对调用方不透明，只能原样传回给服务端读取下一页
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PageCursor(String);
+
+impl PageCursor {
+    // 基于偏移量的游标: version(1) + offset(8)
+    pub fn from_offset(offset: u64) -> Self {
+        let mut buf = Vec::with_capacity(9);
+        buf.push(PAGE_CURSOR_VERSION_OFFSET);
+        buf.extend_from_slice(&offset.to_be_bytes());
+
+        Self(hex::encode(buf))
+    }
+
+    pub fn to_offset(&self) -> BuckyResult<u64> {
+        let buf = hex::decode(&self.0).map_err(|e| {
+            let msg = format!("invalid page cursor: {}, {}", self.0, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        if buf.len() != 9 || buf[0] != PAGE_CURSOR_VERSION_OFFSET {
+            let msg = format!("unsupported page cursor: {}", self.0);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let mut offset = [0u8; 8];
+        offset.copy_from_slice(&buf[1..]);
+        Ok(u64::from_be_bytes(offset))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for PageCursor {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        let ret = Self(s.to_owned());
+        ret.to_offset()?;
+
+        Ok(ret)
+    }
+}
+
+// 列表类接口通用的分页请求，cursor为None表示从第一页开始
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub cursor: Option<PageCursor>,
+    pub page_size: Option<u32>,
+}
+
+impl PageRequest {
+    pub fn new(page_size: u32) -> Self {
+        Self {
+            cursor: None,
+            page_size: Some(page_size),
+        }
+    }
+
+    pub fn with_cursor(cursor: PageCursor, page_size: u32) -> Self {
+        Self {
+            cursor: Some(cursor),
+            page_size: Some(page_size),
+        }
+    }
+
+    // 截断到上限之后的分页大小
+    pub fn page_size(&self) -> u32 {
+        match self.page_size {
+            Some(page_size) => std::cmp::min(page_size, CYFS_PAGE_SIZE_MAX),
+            None => CYFS_PAGE_SIZE_DEFAULT,
+        }
+    }
+
+    pub fn offset(&self) -> BuckyResult<u64> {
+        match &self.cursor {
+            Some(cursor) => cursor.to_offset(),
+            None => Ok(0),
+        }
+    }
+
+    // 用于JsonCodec的请求，字段直接平铺在请求对象里
+    pub fn encode_json_fields(&self, obj: &mut Map<String, Value>) {
+        JsonCodecHelper::encode_option_string_field(obj, "cursor", self.cursor.as_ref());
+        JsonCodecHelper::encode_option_string_field(obj, "page_size", self.page_size.as_ref());
+    }
+
+    pub fn decode_json_fields(obj: &Map<String, Value>) -> BuckyResult<Option<Self>> {
+        let cursor = JsonCodecHelper::decode_option_string_field(obj, "cursor")?;
+        let page_size = JsonCodecHelper::decode_option_string_field(obj, "page_size")?;
+        if cursor.is_none() && page_size.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self { cursor, page_size }))
+    }
+}
+
+// 分页结果的附加信息
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PageInfo {
+    // 为None表示已经是最后一页
+    pub next_cursor: Option<PageCursor>,
+
+    // 总数的估计值，服务端无法低成本获取时为None
+    pub total_hint: Option<u64>,
+}
+
+impl PageInfo {
+    // 按偏移量分页的结果，没有total_hint时返回满页就认为还有下一页
+    pub fn from_offset(offset: u64, count: usize, page_size: u32, total_hint: Option<u64>) -> Self {
+        let end = offset + count as u64;
+        let has_more = match total_hint {
+            Some(total) => count > 0 && end < total,
+            None => count > 0 && count as u64 >= page_size as u64,
+        };
+
+        Self {
+            next_cursor: if has_more {
+                Some(PageCursor::from_offset(end))
+            } else {
+                None
+            },
+            total_hint,
+        }
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.next_cursor.is_none()
+    }
+
+    pub fn encode_json_fields(&self, obj: &mut Map<String, Value>) {
+        JsonCodecHelper::encode_option_string_field(obj, "next_cursor", self.next_cursor.as_ref());
+        JsonCodecHelper::encode_option_string_field(obj, "total_hint", self.total_hint.as_ref());
+    }
+
+    pub fn decode_json_fields(obj: &Map<String, Value>) -> BuckyResult<Self> {
+        Ok(Self {
+            next_cursor: JsonCodecHelper::decode_option_string_field(obj, "next_cursor")?,
+            total_hint: JsonCodecHelper::decode_option_string_field(obj, "total_hint")?,
+        })
+    }
+}
+
+pub type PageFetchFuture<T> = Pin<Box<dyn Future<Output = BuckyResult<(Vec<T>, PageInfo)>> + Send>>;
+
+// 按游标自动读取后续分页的迭代器
+pub struct PageIterator<T> {
+    fetch: Box<dyn FnMut(PageRequest) -> PageFetchFuture<T> + Send + Sync>,
+    page_size: u32,
+
+    cursor: Option<PageCursor>,
+    finished: bool,
+    buffer: VecDeque<T>,
+    total_hint: Option<u64>,
+}
+
+impl<T> PageIterator<T> {
+    pub fn new<F>(page_size: u32, fetch: F) -> Self
+    where
+        F: FnMut(PageRequest) -> PageFetchFuture<T> + Send + Sync + 'static,
+    {
+        Self {
+            fetch: Box::new(fetch),
+            page_size,
+            cursor: None,
+            finished: false,
+            buffer: VecDeque::new(),
+            total_hint: None,
+        }
+    }
+
+    // 最近一次读取时服务端返回的总数估计
+    pub fn total_hint(&self) -> Option<u64> {
+        self.total_hint
+    }
+
+    // 读取下一页，已经读完时返回None
+    pub async fn next_page(&mut self) -> BuckyResult<Option<Vec<T>>> {
+        if !self.buffer.is_empty() {
+            return Ok(Some(self.buffer.drain(..).collect()));
+        }
+
+        if self.finished {
+            return Ok(None);
+        }
+
+        let req = PageRequest {
+            cursor: self.cursor.clone(),
+            page_size: Some(self.page_size),
+        };
+        let (list, page) = (self.fetch)(req).await?;
+
+        // 游标没有前进说明服务端出错，避免死循环
+        if page.next_cursor.is_some() && page.next_cursor == self.cursor {
+            let msg = format!("page cursor not advanced: {:?}", self.cursor);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        self.total_hint = page.total_hint;
+        self.finished = page.next_cursor.is_none();
+        self.cursor = page.next_cursor;
+
+        if list.is_empty() && self.finished {
+            return Ok(None);
+        }
+
+        Ok(Some(list))
+    }
+
+    pub async fn next(&mut self) -> BuckyResult<Option<T>> {
+        while self.buffer.is_empty() {
+            match self.next_page().await? {
+                Some(list) => self.buffer.extend(list),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(self.buffer.pop_front())
+    }
+
+    // 读取剩余的所有分页
+    pub async fn collect(mut self) -> BuckyResult<Vec<T>> {
+        let mut all: Vec<T> = self.buffer.drain(..).collect();
+        while let Some(list) = self.next_page().await? {
+            all.extend(list);
+        }
+
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fetch_from(all: Vec<u32>) -> PageIterator<u32> {
+        let total = all.len() as u64;
+        PageIterator::new(3, move |req: PageRequest| {
+            let all = all.clone();
+            Box::pin(async move {
+                let offset = req.offset()?;
+                let page_size = req.page_size();
+                let list: Vec<u32> = all
+                    .into_iter()
+                    .skip(offset as usize)
+                    .take(page_size as usize)
+                    .collect();
+                let page = PageInfo::from_offset(offset, list.len(), page_size, Some(total));
+                Ok((list, page))
+            }) as PageFetchFuture<u32>
+        })
+    }
+
+    #[test]
+    fn test_cursor() {
+        let cursor = PageCursor::from_offset(1024);
+        assert_eq!(cursor.to_offset().unwrap(), 1024);
+        assert_eq!(PageCursor::from_str(cursor.as_str()).unwrap(), cursor);
+        assert!(PageCursor::from_str("xyz").is_err());
+        assert!(PageCursor::from_str("00").is_err());
+
+        let req = PageRequest::new(CYFS_PAGE_SIZE_MAX + 1);
+        assert_eq!(req.page_size(), CYFS_PAGE_SIZE_MAX);
+        assert_eq!(req.offset().unwrap(), 0);
+        assert_eq!(PageRequest::default().page_size(), CYFS_PAGE_SIZE_DEFAULT);
+
+        let page = PageInfo::from_offset(0, 10, 10, None);
+        assert_eq!(page.next_cursor.unwrap().to_offset().unwrap(), 10);
+        assert!(PageInfo::from_offset(0, 9, 10, None).is_last());
+        assert!(PageInfo::from_offset(0, 10, 10, Some(10)).is_last());
+    }
+
+    #[test]
+    fn test_iterator() {
+        async_std::task::block_on(async move {
+            let all: Vec<u32> = (0..8).collect();
+
+            let mut it = fetch_from(all.clone());
+            assert_eq!(it.next_page().await.unwrap(), Some(vec![0, 1, 2]));
+            assert_eq!(it.total_hint(), Some(8));
+            assert_eq!(it.next().await.unwrap(), Some(3));
+            assert_eq!(it.collect().await.unwrap(), vec![4, 5, 6, 7]);
+
+            let it = fetch_from(vec![]);
+            assert!(it.collect().await.unwrap().is_empty());
+        });
+    }
+}
@@ -1,7 +1,11 @@
 use cyfs_base::*;
 
+use async_std::io::Read;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::ops::{Deref, DerefMut, Range};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 /*
 Remember that the range is zero-indexed, so Range: bytes=0-999 is actually requesting 1000 bytes, not 999, so respond with something like:
@@ -315,17 +319,7 @@ pub struct RequestorRangeHelper {}
 
 impl RequestorRangeHelper {
     // https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Range
-    fn encode_content_range(range: &Vec<Range<u64>>, size: u64) -> String {
-        assert!(range.len() > 0);
-        if range.len() > 1 {
-            warn!(
-                "only single range support for http range protocol! ranges={:?}, size={}",
-                range, size
-            );
-        }
-
-        let range = &range[0];
-
+    pub fn encode_content_range(range: &Range<u64>, size: u64) -> String {
         if size > 0 {
             format!("bytes {}-{}/{}", range.start, range.end - 1, size)
         } else {
@@ -340,11 +334,15 @@ impl RequestorRangeHelper {
     pub fn new_range_response(range_resp: &NDNDataResponseRange) -> http_types::Response {
         let mut resp = match range_resp {
             NDNDataResponseRange::Range((ranges, len)) => {
+                assert!(ranges.len() > 0);
                 let mut resp =
                     RequestorHelper::new_response(http_types::StatusCode::PartialContent);
 
-                let value = Self::encode_content_range(ranges, *len);
-                resp.insert_header(http_types::headers::CONTENT_RANGE, value);
+                // 多个range的Content-Range放在multipart/byteranges的每个part里面
+                if ranges.len() == 1 {
+                    let value = Self::encode_content_range(&ranges[0], *len);
+                    resp.insert_header(http_types::headers::CONTENT_RANGE, value);
+                }
 
                 resp
             }
@@ -371,3 +369,144 @@ impl RequestorRangeHelper {
         resp
     }
 }
+
+enum MultipartSegment {
+    Bytes(Vec<u8>),
+    Data(u64),
+}
+
+// 把按顺序拼接在一起的多个range的数据，编码为multipart/byteranges格式
+// https://developer.mozilla.org/en-US/docs/Web/HTTP/Range_requests#multipart_ranges
+pub struct MultipartByteRangesReader {
+    boundary: String,
+    len: u64,
+    segments: VecDeque<MultipartSegment>,
+    reader: Box<dyn Read + Unpin + Send + Sync + 'static>,
+}
+
+impl MultipartByteRangesReader {
+    pub fn new(
+        ranges: &Vec<Range<u64>>,
+        size: u64,
+        reader: Box<dyn Read + Unpin + Send + Sync + 'static>,
+    ) -> Self {
+        let boundary = format!("cyfs-byteranges-{:016x}", bucky_time_now());
+
+        let mut segments = VecDeque::new();
+        for range in ranges {
+            let header = format!(
+                "--{}\r\nContent-Type: application/octet-stream\r\nContent-Range: {}\r\n\r\n",
+                boundary,
+                RequestorRangeHelper::encode_content_range(range, size)
+            );
+            segments.push_back(MultipartSegment::Bytes(header.into_bytes()));
+            segments.push_back(MultipartSegment::Data(range.end - range.start));
+            segments.push_back(MultipartSegment::Bytes(b"\r\n".to_vec()));
+        }
+        segments.push_back(MultipartSegment::Bytes(
+            format!("--{}--\r\n", boundary).into_bytes(),
+        ));
+
+        let len = segments
+            .iter()
+            .map(|seg| match seg {
+                MultipartSegment::Bytes(buf) => buf.len() as u64,
+                MultipartSegment::Data(len) => *len,
+            })
+            .sum();
+
+        Self {
+            boundary,
+            len,
+            segments,
+            reader,
+        }
+    }
+
+    pub fn content_type(&self) -> String {
+        format!("multipart/byteranges; boundary={}", self.boundary)
+    }
+
+    // 编码后的总长度
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+impl Read for MultipartByteRangesReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        loop {
+            match this.segments.front_mut() {
+                None => break Poll::Ready(Ok(0)),
+                Some(MultipartSegment::Bytes(bytes)) => {
+                    if bytes.is_empty() {
+                        this.segments.pop_front();
+                        continue;
+                    }
+
+                    let size = std::cmp::min(bytes.len(), buf.len());
+                    buf[..size].copy_from_slice(&bytes[..size]);
+                    bytes.drain(..size);
+                    break Poll::Ready(Ok(size));
+                }
+                Some(MultipartSegment::Data(left)) => {
+                    if *left == 0 {
+                        this.segments.pop_front();
+                        continue;
+                    }
+
+                    let except_len = std::cmp::min(*left, buf.len() as u64) as usize;
+                    match Pin::new(&mut this.reader).poll_read(cx, &mut buf[..except_len]) {
+                        Poll::Ready(Ok(0)) => {
+                            let msg = format!(
+                                "range data reader reach end but still {} bytes left!",
+                                left
+                            );
+                            error!("{}", msg);
+                            let e = BuckyError::new(BuckyErrorCode::IoError, msg);
+                            break Poll::Ready(Err(e.into()));
+                        }
+                        Poll::Ready(Ok(size)) => {
+                            *left -= size as u64;
+                            break Poll::Ready(Ok(size));
+                        }
+                        Poll::Ready(Err(e)) => break Poll::Ready(Err(e)),
+                        Poll::Pending => break Poll::Pending,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::io::ReadExt;
+
+    #[async_std::test]
+    async fn test_multipart_byteranges() {
+        let ranges = vec![0..2, 5..8];
+        let data = Box::new(async_std::io::Cursor::new(b"abfgh".to_vec()));
+        let mut reader = MultipartByteRangesReader::new(&ranges, 10, data);
+        let boundary = reader.boundary.clone();
+        let len = reader.len();
+
+        let mut body = String::new();
+        reader.read_to_string(&mut body).await.unwrap();
+        assert_eq!(body.len() as u64, len);
+
+        let expect = format!(
+            "--{b}\r\nContent-Type: application/octet-stream\r\nContent-Range: bytes 0-1/10\r\n\r\nab\r\n\
+            --{b}\r\nContent-Type: application/octet-stream\r\nContent-Range: bytes 5-7/10\r\n\r\nfgh\r\n\
+            --{b}--\r\n",
+            b = boundary
+        );
+        assert_eq!(body, expect);
+    }
+}
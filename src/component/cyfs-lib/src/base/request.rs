@@ -9,6 +9,9 @@ pub const CYFS_REQUEST_FLAG_DELETE_WITH_QUERY: u32 = 0x01 << 1;
 // get_object，列举当前dir/inner_path下的内容
 pub const CYFS_REQUEST_FLAG_LIST_DIR: u32 = 0x01 << 2;
 
+// delete_object和path_op_env的remove_with_key，删除的内容先放入回收站，在清理前可以恢复
+pub const CYFS_REQUEST_FLAG_DELETE_TO_TRASH: u32 = 0x01 << 4;
+
 //// NDN request flags
 ///
 // get_data/trans_task，target object is file/dir, 跨device请求直接使用chunk级别的acl，不再使用所属的file/dir
@@ -1,3 +1,4 @@
+use super::error_info::RequestErrorInfo;
 use cyfs_base::*;
 
 use async_std::net::{SocketAddr, TcpStream};
@@ -178,6 +179,7 @@ impl BuckyErrorStatusCodeTrans {
             BuckyErrorCode::Ignored => StatusCode::NotAcceptable,
             BuckyErrorCode::NotHandled => StatusCode::NotImplemented,
             BuckyErrorCode::RangeNotSatisfiable => StatusCode::RequestedRangeNotSatisfiable,
+            BuckyErrorCode::StorageReadOnly => StatusCode::InsufficientStorage,
             _ => {
                 warn!("unknown error code: {}", e);
                 StatusCode::InternalServerError
@@ -199,6 +201,7 @@ impl BuckyErrorStatusCodeTrans {
             StatusCode::InternalServerError => BuckyErrorCode::Unknown,
             StatusCode::NotImplemented => BuckyErrorCode::NotHandled,
             StatusCode::RequestedRangeNotSatisfiable => BuckyErrorCode::RangeNotSatisfiable,
+            StatusCode::InsufficientStorage => BuckyErrorCode::StorageReadOnly,
             _ => BuckyErrorCode::Unknown,
         }
     }
@@ -231,8 +234,13 @@ impl RequestorHelper {
 
         let mut resp = Self::new_response(code);
 
-        // always encode error content to body
-        let body = e.encode_string();
+        // always encode error content to body, with the retry hints
+        let info = RequestErrorInfo::from(&e);
+        if let Some(retry_after) = &info.retry_after {
+            resp.insert_header(http_types::headers::RETRY_AFTER, retry_after.to_string());
+        }
+
+        let body = info.encode_string();
         resp.set_content_type(tide::http::mime::JSON);
         resp.set_body(body);
 
@@ -241,34 +249,49 @@ impl RequestorHelper {
 
     // 从body里面提取buckyerror
     // 对于status是success情况下，一律解析为BuckyErrorCode
+    // 返回的错误带有解析出来的重试信息，调用方通过RequestErrorInfo::from(&e)获取
     pub async fn error_from_resp(resp: &mut Response) -> BuckyError {
+        Self::error_info_from_resp(resp).await.into_error()
+    }
+
+    // 从body里面提取带重试信息的错误
+    pub async fn error_info_from_resp(resp: &mut Response) -> RequestErrorInfo {
         // assert!(!resp.status().is_success());
 
         let err_code = BuckyErrorStatusCodeTrans::status_code_to_bucky_error(resp.status());
 
-        // 尝试从body里面读取编码后的BuckyError
-        let body = resp.body_string().await.map_err(|e| {
-            let msg = format!("read error string from response error: {}", e);
-            error!("{}", msg);
+        // Retry-After header has higher priority than the body
+        let retry_after: Option<u32> = match resp.header(http_types::headers::RETRY_AFTER) {
+            Some(v) => u32::from_str(v.last().as_str()).ok(),
+            None => None,
+        };
 
-            BuckyError::from(err_code)
-        });
-        if let Err(e) = body {
-            return e;
-        }
+        // 尝试从body里面读取编码后的错误信息
+        let body = match resp.body_string().await {
+            Ok(body) => body,
+            Err(e) => {
+                error!("read error string from response error: {}", e);
+                String::new()
+            }
+        };
 
-        let body = body.unwrap();
-        if body.is_empty() {
-            BuckyError::from(err_code)
+        let mut info = if body.is_empty() {
+            RequestErrorInfo::new(err_code, "")
         } else {
-            match BuckyError::decode_string(&body) {
-                Ok(e) => e,
+            match RequestErrorInfo::decode_string(&body) {
+                Ok(info) => info,
                 Err(e) => {
                     error!("invalid error string from response: {}, {}", body, e);
-                    BuckyError::new(err_code, body)
+                    RequestErrorInfo::new(err_code, body)
                 }
             }
+        };
+
+        if retry_after.is_some() {
+            info.retry_after = retry_after;
         }
+
+        info
     }
 
     pub fn insert_device_list_header(http_req: &mut Request, device_list: &Vec<DeviceId>) {
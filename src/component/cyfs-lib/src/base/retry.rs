@@ -0,0 +1,158 @@
+use super::deadline::RequestDeadline;
+use cyfs_base::*;
+
+use std::future::Future;
+use std::time::Duration;
+
+// 请求id的最大长度，服务端会拒绝超长的id
+pub const CYFS_REQUEST_ID_MAX_LEN: usize = 128;
+
+// 客户端生成的请求id，随请求通过cyfs-request-id头部发送
+// 服务端在一段时间内对同一个来源的相同id只执行一次，重试时直接返回第一次的结果
+pub struct RequestId;
+
+impl RequestId {
+    pub fn gen() -> String {
+        let buf: [u8; 16] = rand::random();
+        hex::encode(buf)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RequestRetryPolicy {
+    // 失败后最多重试的次数，0表示不重试
+    pub max_retry: u32,
+
+    // 重试间隔，每次翻倍直到最大值
+    pub min_interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl Default for RequestRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retry: 3,
+            min_interval: Duration::from_millis(500),
+            max_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RequestRetryPolicy {
+    pub fn new(max_retry: u32) -> Self {
+        Self {
+            max_retry,
+            ..Default::default()
+        }
+    }
+
+    // 只有超时、连接失败、服务端排队满等短暂的错误才会重试
+    pub fn is_transient(e: &BuckyError) -> bool {
+        match e.code() {
+            BuckyErrorCode::Timeout
+            | BuckyErrorCode::ConnectFailed
+            | BuckyErrorCode::ConnectionRefused
+            | BuckyErrorCode::ConnectionReset
+            | BuckyErrorCode::ConnectionAborted
+            | BuckyErrorCode::NotConnected
+            | BuckyErrorCode::BrokenPipe
+            | BuckyErrorCode::Interrupted
+            | BuckyErrorCode::OutOfLimit
+            | BuckyErrorCode::OutofSessionLimit => true,
+            _ => false,
+        }
+    }
+
+    // f每次调用发起一次请求，请求的截止时间过了之后不再重试
+    pub async fn run<F, Fut, T>(
+        &self,
+        name: &str,
+        deadline: Option<u64>,
+        mut f: F,
+    ) -> BuckyResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = BuckyResult<T>>,
+    {
+        let mut interval = self.min_interval;
+        let mut retry = 0;
+        loop {
+            let e = match f().await {
+                Ok(ret) => return Ok(ret),
+                Err(e) => e,
+            };
+
+            if retry >= self.max_retry
+                || !Self::is_transient(&e)
+                || RequestDeadline::is_expired(deadline)
+            {
+                return Err(e);
+            }
+
+            retry += 1;
+            warn!(
+                "request failed, now will retry! name={}, retry={}/{}, interval={:?}, {}",
+                name, retry, self.max_retry, interval, e
+            );
+
+            bucky_sleep(interval).await;
+            interval = std::cmp::min(interval * 2, self.max_interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_retry() {
+        async_std::task::block_on(async move {
+            let policy = RequestRetryPolicy {
+                max_retry: 3,
+                min_interval: Duration::from_millis(1),
+                max_interval: Duration::from_millis(2),
+            };
+
+            // 短暂错误重试到成功
+            let count = &AtomicU32::new(0);
+            let ret = policy
+                .run("test", None, || async move {
+                    if count.fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err(BuckyError::from(BuckyErrorCode::Timeout))
+                    } else {
+                        Ok(1)
+                    }
+                })
+                .await;
+            assert_eq!(ret.unwrap(), 1);
+            assert_eq!(count.load(Ordering::SeqCst), 3);
+
+            // 其它错误直接返回
+            let count = &AtomicU32::new(0);
+            let ret: BuckyResult<u32> = policy
+                .run("test", None, || async move {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    Err(BuckyError::from(BuckyErrorCode::PermissionDenied))
+                })
+                .await;
+            assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::PermissionDenied);
+            assert_eq!(count.load(Ordering::SeqCst), 1);
+
+            // 超过重试次数
+            let count = &AtomicU32::new(0);
+            let ret: BuckyResult<u32> = policy
+                .run("test", None, || async move {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    Err(BuckyError::from(BuckyErrorCode::ConnectFailed))
+                })
+                .await;
+            assert!(ret.is_err());
+            assert_eq!(count.load(Ordering::SeqCst), 4);
+        });
+
+        assert_eq!(RequestId::gen().len(), 32);
+        assert_ne!(RequestId::gen(), RequestId::gen());
+    }
+}
@@ -0,0 +1,35 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// get_contacts
+pub struct ContactsGetInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type ContactsGetInputResponse = ContactsGetOutputResponse;
+
+// add_contact
+pub struct ContactsAddInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub card: ContactCard,
+}
+
+pub type ContactsAddInputResponse = ContactsAddOutputResponse;
+
+// reply_contact
+pub struct ContactsReplyInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub owner_id: ObjectId,
+    pub accept: bool,
+}
+
+pub type ContactsReplyInputResponse = ContactsReplyOutputResponse;
+
+// remove_contact
+pub struct ContactsRemoveInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub owner_id: ObjectId,
+}
+
+pub type ContactsRemoveInputResponse = ContactsRemoveOutputResponse;
@@ -0,0 +1,236 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+// 联系人名片，可以编码成二维码内容分享给对方: cyfs://contact/{owner_id}?ood={ood_id}&name={name}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactCard {
+    // 对方zone的owner，一般是people
+    pub owner_id: ObjectId,
+
+    // 对方的ood，为空时通过owner查找
+    pub ood_id: Option<DeviceId>,
+
+    pub name: Option<String>,
+}
+
+const CONTACT_QR_PAYLOAD_PREFIX: &str = "cyfs://contact/";
+
+impl ContactCard {
+    pub fn new(owner_id: ObjectId) -> Self {
+        Self {
+            owner_id,
+            ood_id: None,
+            name: None,
+        }
+    }
+
+    pub fn to_qr_payload(&self) -> String {
+        let mut url =
+            http_types::Url::parse(&format!("{}{}", CONTACT_QR_PAYLOAD_PREFIX, self.owner_id))
+                .unwrap();
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(ood_id) = &self.ood_id {
+                query.append_pair("ood", &ood_id.to_string());
+            }
+            if let Some(name) = &self.name {
+                query.append_pair("name", name);
+            }
+        }
+
+        url.to_string().trim_end_matches('?').to_owned()
+    }
+
+    pub fn from_qr_payload(payload: &str) -> BuckyResult<Self> {
+        let payload = payload.trim();
+        if !payload.starts_with(CONTACT_QR_PAYLOAD_PREFIX) {
+            let msg = format!("invalid contact qr payload: {}", payload);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+
+        let url = http_types::Url::parse(payload).map_err(|e| {
+            let msg = format!("invalid contact qr payload: {}, {}", payload, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let owner_id = ObjectId::from_str(url.path().trim_start_matches('/'))?;
+        let mut card = Self::new(owner_id);
+        for (k, v) in url.query_pairs() {
+            match k.as_ref() {
+                "ood" => card.ood_id = Some(DeviceId::from_str(&v)?),
+                "name" => card.name = Some(v.into_owned()),
+                _ => {
+                    warn!("unknown contact qr payload param: {}={}", k, v);
+                }
+            }
+        }
+
+        Ok(card)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactStatus {
+    // 已经向对方发起请求，等待对方确认
+    Requesting,
+
+    // 收到对方的请求，等待本地确认
+    Pending,
+
+    // 双方已经完成验证
+    Verified,
+
+    // 本地拒绝了对方的请求
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactInfo {
+    pub owner_id: ObjectId,
+    pub ood_id: Option<DeviceId>,
+    pub name: Option<String>,
+    pub status: ContactStatus,
+
+    // bucky time
+    pub create_time: u64,
+    pub update_time: u64,
+}
+
+// 当前zone的联系人列表，保存在ood上，没有指定target时默认路由到ood
+#[derive(Debug, Clone)]
+pub struct ContactsGetOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for ContactsGetOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl ContactsGetOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactsGetOutputResponse {
+    pub list: Vec<ContactInfo>,
+}
+
+impl Display for ContactsGetOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+// 添加联系人，会向对方发起验证请求
+#[derive(Debug, Clone)]
+pub struct ContactsAddOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub card: ContactCard,
+}
+
+impl Display for ContactsAddOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, card: {:?}", self.common, self.card)
+    }
+}
+
+impl ContactsAddOutputRequest {
+    pub fn new(card: ContactCard) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            card,
+        }
+    }
+
+    pub fn new_with_qr_payload(payload: &str) -> BuckyResult<Self> {
+        let card = ContactCard::from_qr_payload(payload)?;
+        Ok(Self::new(card))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactsAddOutputResponse {
+    pub contact: ContactInfo,
+}
+
+impl Display for ContactsAddOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "contact: {:?}", self.contact)
+    }
+}
+
+// 确认或者拒绝对方发起的请求
+#[derive(Debug, Clone)]
+pub struct ContactsReplyOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub owner_id: ObjectId,
+    pub accept: bool,
+}
+
+impl Display for ContactsReplyOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, owner_id: {}, accept: {}",
+            self.common, self.owner_id, self.accept
+        )
+    }
+}
+
+impl ContactsReplyOutputRequest {
+    pub fn new(owner_id: ObjectId, accept: bool) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            owner_id,
+            accept,
+        }
+    }
+}
+
+pub type ContactsReplyOutputResponse = ContactsAddOutputResponse;
+
+#[derive(Debug, Clone)]
+pub struct ContactsRemoveOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub owner_id: ObjectId,
+}
+
+impl Display for ContactsRemoveOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, owner_id: {}", self.common, self.owner_id)
+    }
+}
+
+impl ContactsRemoveOutputRequest {
+    pub fn new(owner_id: ObjectId) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            owner_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactsRemoveOutputResponse {
+    // 被移除的联系人，不存在时为空
+    pub contact: Option<ContactInfo>,
+}
+
+impl Display for ContactsRemoveOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "contact: {:?}", self.contact)
+    }
+}
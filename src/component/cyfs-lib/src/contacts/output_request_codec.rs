@@ -0,0 +1,60 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ContactsAddOutputRequest> for ContactsAddOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "owner_id", &self.card.owner_id);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "ood_id", self.card.ood_id.as_ref());
+        JsonCodecHelper::encode_option_string_field(&mut obj, "name", self.card.name.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ContactsAddOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            card: ContactCard {
+                owner_id: JsonCodecHelper::decode_string_field(obj, "owner_id")?,
+                ood_id: JsonCodecHelper::decode_option_string_field(obj, "ood_id")?,
+                name: JsonCodecHelper::decode_option_string_field(obj, "name")?,
+            },
+        })
+    }
+}
+
+impl JsonCodec<ContactsReplyOutputRequest> for ContactsReplyOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "owner_id", &self.owner_id);
+        JsonCodecHelper::encode_bool_field(&mut obj, "accept", self.accept);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ContactsReplyOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            owner_id: JsonCodecHelper::decode_string_field(obj, "owner_id")?,
+            accept: JsonCodecHelper::decode_bool_field(obj, "accept")?,
+        })
+    }
+}
+
+impl JsonCodec<ContactsRemoveOutputRequest> for ContactsRemoveOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "owner_id", &self.owner_id);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ContactsRemoveOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            owner_id: JsonCodecHelper::decode_string_field(obj, "owner_id")?,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ContactsOutputProcessor: Sync + Send + 'static {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetOutputRequest,
+    ) -> BuckyResult<ContactsGetOutputResponse>;
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddOutputRequest,
+    ) -> BuckyResult<ContactsAddOutputResponse>;
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyOutputRequest,
+    ) -> BuckyResult<ContactsReplyOutputResponse>;
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveOutputRequest,
+    ) -> BuckyResult<ContactsRemoveOutputResponse>;
+}
+
+pub type ContactsOutputProcessorRef = Arc<dyn ContactsOutputProcessor>;
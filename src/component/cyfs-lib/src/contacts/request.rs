@@ -0,0 +1,13 @@
+use super::output_request::*;
+
+pub type ContactsGetRequest = ContactsGetOutputRequest;
+pub type ContactsGetResponse = ContactsGetOutputResponse;
+
+pub type ContactsAddRequest = ContactsAddOutputRequest;
+pub type ContactsAddResponse = ContactsAddOutputResponse;
+
+pub type ContactsReplyRequest = ContactsReplyOutputRequest;
+pub type ContactsReplyResponse = ContactsReplyOutputResponse;
+
+pub type ContactsRemoveRequest = ContactsRemoveOutputRequest;
+pub type ContactsRemoveResponse = ContactsRemoveOutputResponse;
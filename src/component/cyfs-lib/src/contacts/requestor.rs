@@ -0,0 +1,215 @@
+use super::output_request::*;
+use super::processor::*;
+use super::request::*;
+use crate::{base::*, requestor::*, SharedObjectStackDecID, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use http_types::{Method, Request, Url};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ContactsRequestor {
+    dec_id: Option<SharedObjectStackDecID>,
+    requestor: HttpRequestorRef,
+    service_url: Url,
+}
+
+impl ContactsRequestor {
+    pub fn new(dec_id: Option<SharedObjectStackDecID>, requestor: HttpRequestorRef) -> Self {
+        let addr = requestor.remote_addr();
+
+        let url = format!("http://{}/contacts/", addr);
+        let url = Url::parse(&url).unwrap();
+
+        Self {
+            dec_id,
+            requestor,
+            service_url: url,
+        }
+    }
+
+    pub fn into_processor(self) -> ContactsOutputProcessorRef {
+        Arc::new(self)
+    }
+
+    pub fn clone_processor(&self) -> ContactsOutputProcessorRef {
+        self.clone().into_processor()
+    }
+
+    fn encode_common_headers(&self, com_req: &UtilOutputRequestCommon, http_req: &mut Request) {
+        if let Some(dec_id) = &com_req.dec_id {
+            http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+        } else if let Some(dec_id) = &self.dec_id {
+            if let Some(dec_id) = dec_id.get() {
+                http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+            }
+        }
+
+        RequestorHelper::encode_opt_header_with_encoding(
+            http_req,
+            cyfs_base::CYFS_REQ_PATH,
+            com_req.req_path.as_deref(),
+        );
+
+        if let Some(target) = &com_req.target {
+            http_req.insert_header(cyfs_base::CYFS_TARGET, target.to_string());
+        }
+
+        http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+    }
+
+    // get_contacts
+    fn encode_get_contacts_request(&self, req: ContactsGetRequest) -> Request {
+        let url = self.service_url.join("list").unwrap();
+        let mut http_req = Request::new(Method::Get, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+
+        http_req
+    }
+
+    pub async fn get_contacts(&self, req: ContactsGetRequest) -> BuckyResult<ContactsGetResponse> {
+        let http_req = self.encode_get_contacts_request(req);
+
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_contacts resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "contacts get_contacts failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn add_contact(&self, req: ContactsAddRequest) -> BuckyResult<ContactsAddResponse> {
+        let url = self.service_url.join("add").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse add_contact resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "contacts add_contact failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn reply_contact(
+        &self,
+        req: ContactsReplyRequest,
+    ) -> BuckyResult<ContactsReplyResponse> {
+        let url = self.service_url.join("reply").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse reply_contact resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "contacts reply_contact failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn remove_contact(
+        &self,
+        req: ContactsRemoveRequest,
+    ) -> BuckyResult<ContactsRemoveResponse> {
+        let url = self.service_url.join("remove").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse remove_contact resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "contacts remove_contact failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContactsOutputProcessor for ContactsRequestor {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetOutputRequest,
+    ) -> BuckyResult<ContactsGetOutputResponse> {
+        Self::get_contacts(self, req).await
+    }
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddOutputRequest,
+    ) -> BuckyResult<ContactsAddOutputResponse> {
+        Self::add_contact(self, req).await
+    }
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyOutputRequest,
+    ) -> BuckyResult<ContactsReplyOutputResponse> {
+        Self::reply_contact(self, req).await
+    }
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveOutputRequest,
+    ) -> BuckyResult<ContactsRemoveOutputResponse> {
+        Self::remove_contact(self, req).await
+    }
+}
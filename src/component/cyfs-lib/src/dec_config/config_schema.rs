@@ -0,0 +1,351 @@
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+// dec配置文档使用的schema，是json schema的一个子集:
+// type(字符串或者字符串数组): object/array/string/number/integer/boolean/null
+// object: properties, required, additionalProperties(只支持bool)
+// array: items, minItems, maxItems
+// string: minLength, maxLength
+// number/integer: minimum, maximum
+// 任意类型: enum
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DecConfigSchema {
+    schema: Value,
+}
+
+const SCHEMA_TYPES: [&str; 7] = [
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+impl DecConfigSchema {
+    pub fn parse(schema: &str) -> BuckyResult<Self> {
+        let schema: Value = serde_json::from_str(schema).map_err(|e| {
+            let msg = format!("invalid dec config schema json! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Self::check_schema("$", &schema)?;
+
+        Ok(Self { schema })
+    }
+
+    pub fn as_value(&self) -> &Value {
+        &self.schema
+    }
+
+    pub fn validate(&self, value: &Value) -> BuckyResult<()> {
+        Self::validate_node("$", &self.schema, value).map_err(|msg| {
+            let msg = format!("dec config not match the schema! {}", msg);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    // 同时解析json，返回解析后的值
+    pub fn validate_str(&self, value: &str) -> BuckyResult<Value> {
+        let value = Self::parse_value(value)?;
+        self.validate(&value)?;
+
+        Ok(value)
+    }
+
+    pub fn parse_value(value: &str) -> BuckyResult<Value> {
+        serde_json::from_str(value).map_err(|e| {
+            let msg = format!("invalid dec config value json! {}", e);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })
+    }
+
+    fn invalid_schema(path: &str, reason: &str) -> BuckyError {
+        let msg = format!("invalid dec config schema at {}: {}", path, reason);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+    }
+
+    fn check_schema(path: &str, schema: &Value) -> BuckyResult<()> {
+        let obj = schema
+            .as_object()
+            .ok_or_else(|| Self::invalid_schema(path, "schema should be object"))?;
+
+        if let Some(v) = obj.get("type") {
+            let types = match v {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(list) => list.iter().filter_map(|t| t.as_str()).collect(),
+                _ => vec![],
+            };
+            if types.is_empty() || types.iter().any(|t| !SCHEMA_TYPES.contains(t)) {
+                return Err(Self::invalid_schema(path, "unknown type"));
+            }
+        }
+
+        if let Some(v) = obj.get("properties") {
+            let props = v
+                .as_object()
+                .ok_or_else(|| Self::invalid_schema(path, "properties should be object"))?;
+            for (k, v) in props {
+                Self::check_schema(&format!("{}.{}", path, k), v)?;
+            }
+        }
+
+        if let Some(v) = obj.get("required") {
+            let valid = v
+                .as_array()
+                .map(|list| list.iter().all(|k| k.is_string()))
+                .unwrap_or(false);
+            if !valid {
+                return Err(Self::invalid_schema(path, "required should be string array"));
+            }
+        }
+
+        if let Some(v) = obj.get("additionalProperties") {
+            if !v.is_boolean() {
+                return Err(Self::invalid_schema(
+                    path,
+                    "only bool additionalProperties supported",
+                ));
+            }
+        }
+
+        if let Some(v) = obj.get("items") {
+            Self::check_schema(&format!("{}[]", path), v)?;
+        }
+
+        if let Some(v) = obj.get("enum") {
+            if !v.is_array() {
+                return Err(Self::invalid_schema(path, "enum should be array"));
+            }
+        }
+
+        for key in ["minLength", "maxLength", "minItems", "maxItems"] {
+            if let Some(v) = obj.get(key) {
+                if !v.is_u64() {
+                    return Err(Self::invalid_schema(path, &format!("{} should be uint", key)));
+                }
+            }
+        }
+
+        for key in ["minimum", "maximum"] {
+            if let Some(v) = obj.get(key) {
+                if !v.is_number() {
+                    return Err(Self::invalid_schema(path, &format!("{} should be number", key)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn type_of(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn match_type(t: &str, value: &Value) -> bool {
+        match t {
+            "integer" => value.is_i64() || value.is_u64(),
+            _ => t == Self::type_of(value),
+        }
+    }
+
+    fn validate_node(path: &str, schema: &Value, value: &Value) -> Result<(), String> {
+        let schema = match schema.as_object() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        match schema.get("type") {
+            Some(Value::String(t)) => {
+                if !Self::match_type(t, value) {
+                    return Err(format!(
+                        "{}: expect {}, got {}",
+                        path,
+                        t,
+                        Self::type_of(value)
+                    ));
+                }
+            }
+            Some(Value::Array(list)) => {
+                let matched = list
+                    .iter()
+                    .filter_map(|t| t.as_str())
+                    .any(|t| Self::match_type(t, value));
+                if !matched {
+                    return Err(format!(
+                        "{}: expect one of {}, got {}",
+                        path,
+                        Value::Array(list.clone()),
+                        Self::type_of(value)
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(Value::Array(list)) = schema.get("enum") {
+            if !list.contains(value) {
+                return Err(format!("{}: value not in enum {}", path, Value::Array(list.clone())));
+            }
+        }
+
+        match value {
+            Value::Object(obj) => Self::validate_object(path, schema, obj)?,
+            Value::Array(list) => {
+                Self::check_len(path, schema, "minItems", "maxItems", list.len())?;
+                if let Some(items) = schema.get("items") {
+                    for (i, item) in list.iter().enumerate() {
+                        Self::validate_node(&format!("{}[{}]", path, i), items, item)?;
+                    }
+                }
+            }
+            Value::String(s) => {
+                Self::check_len(path, schema, "minLength", "maxLength", s.chars().count())?;
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or(0.0);
+                if let Some(min) = schema.get("minimum").and_then(|v| v.as_f64()) {
+                    if n < min {
+                        return Err(format!("{}: {} is less than minimum {}", path, n, min));
+                    }
+                }
+                if let Some(max) = schema.get("maximum").and_then(|v| v.as_f64()) {
+                    if n > max {
+                        return Err(format!("{}: {} is greater than maximum {}", path, n, max));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn validate_object(
+        path: &str,
+        schema: &Map<String, Value>,
+        obj: &Map<String, Value>,
+    ) -> Result<(), String> {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !obj.contains_key(key) {
+                    return Err(format!("{}: missing required property {}", path, key));
+                }
+            }
+        }
+
+        let props = schema.get("properties").and_then(|v| v.as_object());
+        let additional = schema
+            .get("additionalProperties")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        for (k, v) in obj {
+            match props.and_then(|props| props.get(k)) {
+                Some(prop) => Self::validate_node(&format!("{}.{}", path, k), prop, v)?,
+                None => {
+                    if !additional {
+                        return Err(format!("{}: unknown property {}", path, k));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_len(
+        path: &str,
+        schema: &Map<String, Value>,
+        min_key: &str,
+        max_key: &str,
+        len: usize,
+    ) -> Result<(), String> {
+        if let Some(min) = schema.get(min_key).and_then(|v| v.as_u64()) {
+            if (len as u64) < min {
+                return Err(format!("{}: length {} is less than {} {}", path, len, min_key, min));
+            }
+        }
+        if let Some(max) = schema.get(max_key).and_then(|v| v.as_u64()) {
+            if len as u64 > max {
+                return Err(format!("{}: length {} is greater than {} {}", path, len, max_key, max));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ToString for DecConfigSchema {
+    fn to_string(&self) -> String {
+        self.schema.to_string()
+    }
+}
+
+impl FromStr for DecConfigSchema {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["name", "port"],
+        "additionalProperties": false,
+        "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "port": { "type": "integer", "minimum": 1, "maximum": 65535 },
+            "mode": { "enum": ["fast", "safe"] },
+            "peers": { "type": "array", "maxItems": 2, "items": { "type": "string" } },
+            "extra": { "type": ["object", "null"] }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        assert!(DecConfigSchema::parse(SCHEMA).is_ok());
+        assert!(DecConfigSchema::parse("{}").is_ok());
+
+        assert!(DecConfigSchema::parse("[]").is_err());
+        assert!(DecConfigSchema::parse(r#"{"type": "int"}"#).is_err());
+        assert!(DecConfigSchema::parse(r#"{"required": "name"}"#).is_err());
+        assert!(DecConfigSchema::parse(r#"{"additionalProperties": {}}"#).is_err());
+        assert!(DecConfigSchema::parse(r#"{"properties": {"a": {"maxLength": -1}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = DecConfigSchema::parse(SCHEMA).unwrap();
+
+        assert!(schema.validate_str(r#"{"name": "a", "port": 80}"#).is_ok());
+        assert!(schema
+            .validate_str(r#"{"name": "a", "port": 80, "mode": "safe", "peers": ["x"], "extra": null}"#)
+            .is_ok());
+
+        assert!(schema.validate_str(r#"{"name": "a"}"#).is_err());
+        assert!(schema.validate_str(r#"{"name": "", "port": 80}"#).is_err());
+        assert!(schema.validate_str(r#"{"name": "a", "port": 80.5}"#).is_err());
+        assert!(schema.validate_str(r#"{"name": "a", "port": 70000}"#).is_err());
+        assert!(schema.validate_str(r#"{"name": "a", "port": 80, "mode": "slow"}"#).is_err());
+        assert!(schema
+            .validate_str(r#"{"name": "a", "port": 80, "peers": ["x", "y", "z"]}"#)
+            .is_err());
+        assert!(schema.validate_str(r#"{"name": "a", "port": 80, "extra": 1}"#).is_err());
+        assert!(schema.validate_str(r#"{"name": "a", "port": 80, "other": 1}"#).is_err());
+        assert!(schema.validate_str("not json").is_err());
+    }
+}
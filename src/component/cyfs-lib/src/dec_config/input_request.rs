@@ -0,0 +1,44 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// set_dec_config
+pub struct DecConfigSetInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+    pub value: String,
+    pub schema: Option<String>,
+    pub prev_version: Option<u64>,
+}
+
+pub type DecConfigSetInputResponse = DecConfigSetOutputResponse;
+
+// get_dec_config
+pub struct DecConfigGetInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+}
+
+pub type DecConfigGetInputResponse = DecConfigGetOutputResponse;
+
+// remove_dec_config
+pub struct DecConfigRemoveInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+}
+
+pub type DecConfigRemoveInputResponse = DecConfigRemoveOutputResponse;
+
+// watch_dec_config
+pub struct DecConfigWatchInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+    pub version: u64,
+    pub timeout: Option<u64>,
+}
+
+pub type DecConfigWatchInputResponse = DecConfigWatchOutputResponse;
@@ -0,0 +1,214 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// dec的配置文档，保存在ood的root_state系统dec下，随root_state同步到zone内的所有设备
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DecConfigDocument {
+    pub dec_id: ObjectId,
+    pub name: String,
+
+    // json格式的配置内容
+    pub value: String,
+
+    // json格式的schema，为空表示不校验，格式参考DecConfigSchema
+    pub schema: Option<String>,
+
+    // 每次修改递增，从1开始
+    pub version: u64,
+
+    // bucky time
+    pub update_time: u64,
+}
+
+// 只允许本zone的system dec修改配置，请求会路由到ood处理
+// dec_id为空表示请求方自己的dec
+#[derive(Debug, Clone)]
+pub struct DecConfigSetOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+    pub value: String,
+
+    // 不为空时同时替换schema，为空时使用已有的schema校验
+    pub schema: Option<String>,
+
+    // 不为空时要求当前版本一致，用以并发修改的检查，0表示配置还不存在
+    pub prev_version: Option<u64>,
+}
+
+impl Display for DecConfigSetOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {:?}, name: {}, schema: {}, prev_version: {:?}",
+            self.common,
+            self.dec_id,
+            self.name,
+            self.schema.is_some(),
+            self.prev_version
+        )
+    }
+}
+
+impl DecConfigSetOutputRequest {
+    pub fn new(
+        dec_id: Option<ObjectId>,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            name: name.into(),
+            value: value.into(),
+            schema: None,
+            prev_version: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecConfigSetOutputResponse {
+    pub doc: DecConfigDocument,
+}
+
+impl Display for DecConfigSetOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "name: {}, version: {}", self.doc.name, self.doc.version)
+    }
+}
+
+// 从当前设备同步的root_state读取，需要读取ood上的最新值时target指定为ood
+#[derive(Debug, Clone)]
+pub struct DecConfigGetOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+}
+
+impl Display for DecConfigGetOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {:?}, name: {}",
+            self.common, self.dec_id, self.name
+        )
+    }
+}
+
+impl DecConfigGetOutputRequest {
+    pub fn new(dec_id: Option<ObjectId>, name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            name: name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecConfigGetOutputResponse {
+    pub doc: Option<DecConfigDocument>,
+}
+
+impl Display for DecConfigGetOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version: {:?}", self.doc.as_ref().map(|doc| doc.version))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecConfigRemoveOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+}
+
+impl Display for DecConfigRemoveOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {:?}, name: {}",
+            self.common, self.dec_id, self.name
+        )
+    }
+}
+
+impl DecConfigRemoveOutputRequest {
+    pub fn new(dec_id: Option<ObjectId>, name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            name: name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecConfigRemoveOutputResponse {
+    // 被移除的配置，不存在时为空
+    pub doc: Option<DecConfigDocument>,
+}
+
+impl Display for DecConfigRemoveOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version: {:?}", self.doc.as_ref().map(|doc| doc.version))
+    }
+}
+
+// 等待配置变化，版本和version不一致时立即返回，否则等到变化或者超时
+#[derive(Debug, Clone)]
+pub struct DecConfigWatchOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub name: String,
+
+    // 调用方已知的版本，0表示配置还不存在
+    pub version: u64,
+
+    // 毫秒，为空使用默认值，服务端会限制最大值
+    pub timeout: Option<u64>,
+}
+
+impl Display for DecConfigWatchOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {:?}, name: {}, version: {}, timeout: {:?}",
+            self.common, self.dec_id, self.name, self.version, self.timeout
+        )
+    }
+}
+
+impl DecConfigWatchOutputRequest {
+    pub fn new(dec_id: Option<ObjectId>, name: impl Into<String>, version: u64) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            name: name.into(),
+            version,
+            timeout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecConfigWatchOutputResponse {
+    // 超时返回时为false
+    pub changed: bool,
+    pub doc: Option<DecConfigDocument>,
+}
+
+impl Display for DecConfigWatchOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "changed: {}, version: {:?}",
+            self.changed,
+            self.doc.as_ref().map(|doc| doc.version)
+        )
+    }
+}
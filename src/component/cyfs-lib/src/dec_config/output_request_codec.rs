@@ -0,0 +1,86 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<DecConfigSetOutputRequest> for DecConfigSetOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_string_field(&mut obj, "value", &self.value);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "schema", self.schema.as_ref());
+        JsonCodecHelper::encode_option_number_field(&mut obj, "prev_version", self.prev_version);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<DecConfigSetOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            value: JsonCodecHelper::decode_string_field(obj, "value")?,
+            schema: JsonCodecHelper::decode_option_string_field(obj, "schema")?,
+            prev_version: JsonCodecHelper::decode_option_int_field(obj, "prev_version")?,
+        })
+    }
+}
+
+impl JsonCodec<DecConfigGetOutputRequest> for DecConfigGetOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<DecConfigGetOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
+
+impl JsonCodec<DecConfigRemoveOutputRequest> for DecConfigRemoveOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<DecConfigRemoveOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
+
+impl JsonCodec<DecConfigWatchOutputRequest> for DecConfigWatchOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_number_field(&mut obj, "version", self.version);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "timeout", self.timeout);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<DecConfigWatchOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            version: JsonCodecHelper::decode_int_field(obj, "version")?,
+            timeout: JsonCodecHelper::decode_option_int_field(obj, "timeout")?,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait DecConfigOutputProcessor: Sync + Send + 'static {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetOutputRequest,
+    ) -> BuckyResult<DecConfigSetOutputResponse>;
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetOutputRequest,
+    ) -> BuckyResult<DecConfigGetOutputResponse>;
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveOutputRequest,
+    ) -> BuckyResult<DecConfigRemoveOutputResponse>;
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchOutputRequest,
+    ) -> BuckyResult<DecConfigWatchOutputResponse>;
+}
+
+pub type DecConfigOutputProcessorRef = Arc<dyn DecConfigOutputProcessor>;
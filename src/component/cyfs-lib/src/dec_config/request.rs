@@ -0,0 +1,13 @@
+use super::output_request::*;
+
+pub type DecConfigSetRequest = DecConfigSetOutputRequest;
+pub type DecConfigSetResponse = DecConfigSetOutputResponse;
+
+pub type DecConfigGetRequest = DecConfigGetOutputRequest;
+pub type DecConfigGetResponse = DecConfigGetOutputResponse;
+
+pub type DecConfigRemoveRequest = DecConfigRemoveOutputRequest;
+pub type DecConfigRemoveResponse = DecConfigRemoveOutputResponse;
+
+pub type DecConfigWatchRequest = DecConfigWatchOutputRequest;
+pub type DecConfigWatchResponse = DecConfigWatchOutputResponse;
@@ -0,0 +1,215 @@
+use super::output_request::*;
+use super::processor::*;
+use super::request::*;
+use crate::{base::*, requestor::*, SharedObjectStackDecID, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use http_types::{Method, Request, Url};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct DecConfigRequestor {
+    dec_id: Option<SharedObjectStackDecID>,
+    requestor: HttpRequestorRef,
+    service_url: Url,
+}
+
+impl DecConfigRequestor {
+    pub fn new(dec_id: Option<SharedObjectStackDecID>, requestor: HttpRequestorRef) -> Self {
+        let addr = requestor.remote_addr();
+
+        let url = format!("http://{}/dec_config/", addr);
+        let url = Url::parse(&url).unwrap();
+
+        Self {
+            dec_id,
+            requestor,
+            service_url: url,
+        }
+    }
+
+    pub fn into_processor(self) -> DecConfigOutputProcessorRef {
+        Arc::new(self)
+    }
+
+    pub fn clone_processor(&self) -> DecConfigOutputProcessorRef {
+        self.clone().into_processor()
+    }
+
+    fn encode_common_headers(&self, com_req: &UtilOutputRequestCommon, http_req: &mut Request) {
+        if let Some(dec_id) = &com_req.dec_id {
+            http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+        } else if let Some(dec_id) = &self.dec_id {
+            if let Some(dec_id) = dec_id.get() {
+                http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+            }
+        }
+
+        RequestorHelper::encode_opt_header_with_encoding(
+            http_req,
+            cyfs_base::CYFS_REQ_PATH,
+            com_req.req_path.as_deref(),
+        );
+
+        if let Some(target) = &com_req.target {
+            http_req.insert_header(cyfs_base::CYFS_TARGET, target.to_string());
+        }
+
+        http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+    }
+
+    pub async fn set_dec_config(
+        &self,
+        req: DecConfigSetRequest,
+    ) -> BuckyResult<DecConfigSetResponse> {
+        let url = self.service_url.join("set").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse set_dec_config resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "dec config set_dec_config failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_dec_config(
+        &self,
+        req: DecConfigGetRequest,
+    ) -> BuckyResult<DecConfigGetResponse> {
+        let url = self.service_url.join("get").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_dec_config resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "dec config get_dec_config failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveRequest,
+    ) -> BuckyResult<DecConfigRemoveResponse> {
+        let url = self.service_url.join("remove").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse remove_dec_config resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "dec config remove_dec_config failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchRequest,
+    ) -> BuckyResult<DecConfigWatchResponse> {
+        let url = self.service_url.join("watch").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse watch_dec_config resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "dec config watch_dec_config failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DecConfigOutputProcessor for DecConfigRequestor {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetOutputRequest,
+    ) -> BuckyResult<DecConfigSetOutputResponse> {
+        Self::set_dec_config(self, req).await
+    }
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetOutputRequest,
+    ) -> BuckyResult<DecConfigGetOutputResponse> {
+        Self::get_dec_config(self, req).await
+    }
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveOutputRequest,
+    ) -> BuckyResult<DecConfigRemoveOutputResponse> {
+        Self::remove_dec_config(self, req).await
+    }
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchOutputRequest,
+    ) -> BuckyResult<DecConfigWatchOutputResponse> {
+        Self::watch_dec_config(self, req).await
+    }
+}
@@ -7,6 +7,10 @@ use std::str::FromStr;
 pub enum RouterEventCategory {
     TestEvent,
     ZoneRoleChanged,
+    DescRenewFailed,
+    ZoneEventRecorded,
+    StorageModeChanged,
+    SyncConflict,
 }
 
 impl RouterEventCategory {
@@ -14,6 +18,10 @@ impl RouterEventCategory {
         match self {
             Self::TestEvent => "test_event",
             Self::ZoneRoleChanged => "zone_role_changed",
+            Self::DescRenewFailed => "desc_renew_failed",
+            Self::ZoneEventRecorded => "zone_event_recorded",
+            Self::StorageModeChanged => "storage_mode_changed",
+            Self::SyncConflict => "sync_conflict",
         }
     }
 }
@@ -30,6 +38,10 @@ impl FromStr for RouterEventCategory {
         let ret = match s {
             "test_event" => Self::TestEvent,
             "zone_role_changed" => Self::ZoneRoleChanged,
+            "desc_renew_failed" => Self::DescRenewFailed,
+            "zone_event_recorded" => Self::ZoneEventRecorded,
+            "storage_mode_changed" => Self::StorageModeChanged,
+            "sync_conflict" => Self::SyncConflict,
 
             v @ _ => {
                 let msg = format!("unknown router event category: {}", v);
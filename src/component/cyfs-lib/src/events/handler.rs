@@ -154,4 +154,30 @@ impl RouterEventManagerProcessor for RouterEventManager {
     ) -> &dyn RouterEventProcessor<ZoneRoleChangedEventRequest, ZoneRoleChangedEventResponse> {
         self
     }
+
+    fn desc_renew_failed_event(
+        &self,
+    ) -> &dyn RouterEventProcessor<DescRenewFailedEventRequest, DescRenewFailedEventResponse> {
+        self
+    }
+
+    fn zone_event_recorded_event(
+        &self,
+    ) -> &dyn RouterEventProcessor<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse>
+    {
+        self
+    }
+
+    fn storage_mode_changed_event(
+        &self,
+    ) -> &dyn RouterEventProcessor<StorageModeChangedEventRequest, StorageModeChangedEventResponse>
+    {
+        self
+    }
+
+    fn sync_conflict_event(
+        &self,
+    ) -> &dyn RouterEventProcessor<SyncConflictEventRequest, SyncConflictEventResponse> {
+        self
+    }
 }
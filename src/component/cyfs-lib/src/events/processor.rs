@@ -27,6 +27,10 @@ where
 pub trait RouterEventManagerProcessor: Send + Sync {
     fn test_event(&self) -> &dyn RouterEventProcessor<TestEventRequest, TestEventResponse>;
     fn zone_role_changed_event(&self) -> &dyn RouterEventProcessor<ZoneRoleChangedEventRequest, ZoneRoleChangedEventResponse>;
+    fn desc_renew_failed_event(&self) -> &dyn RouterEventProcessor<DescRenewFailedEventRequest, DescRenewFailedEventResponse>;
+    fn zone_event_recorded_event(&self) -> &dyn RouterEventProcessor<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse>;
+    fn storage_mode_changed_event(&self) -> &dyn RouterEventProcessor<StorageModeChangedEventRequest, StorageModeChangedEventResponse>;
+    fn sync_conflict_event(&self) -> &dyn RouterEventProcessor<SyncConflictEventRequest, SyncConflictEventResponse>;
 }
 
 pub type RouterEventManagerProcessorRef = Arc<Box<dyn RouterEventManagerProcessor>>;
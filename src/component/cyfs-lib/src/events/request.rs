@@ -170,3 +170,234 @@ pub type RouterEventZoneRoleChangedEventRequest = RouterEventRequest<ZoneRoleCha
 
 // response
 pub type RouterEventZoneRoleChangedEventResult = RouterEventResponse<ZoneRoleChangedEventResponse>;
+
+// desc renew failed
+pub struct DescRenewFailedEventRequest {
+    // 续期失败的desc，device或者people
+    pub object_id: ObjectId,
+    // 链上desc当前的过期时间，bucky_time格式，链上查不到时为None
+    pub expired_time: Option<u64>,
+    pub error: BuckyError,
+}
+crate::declare_event_empty_param!(DescRenewFailedEventResponse, DescRenewFailed);
+
+impl std::fmt::Display for DescRenewFailedEventRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "object={}, expired_time={:?}, error={}", self.object_id, self.expired_time, self.error)
+    }
+}
+
+impl JsonCodec<Self> for DescRenewFailedEventRequest {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "object_id", &self.object_id);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "expired_time", self.expired_time);
+        JsonCodecHelper::encode_field(&mut obj, "error", &self.error);
+
+        obj
+    }
+
+    fn decode_json(
+        obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> cyfs_base::BuckyResult<Self> {
+        Ok(Self {
+            object_id: JsonCodecHelper::decode_string_field(obj, "object_id")?,
+            expired_time: JsonCodecHelper::decode_option_int_field(obj, "expired_time")?,
+            error: JsonCodecHelper::decode_field(obj, "error")?,
+        })
+    }
+}
+
+impl RouterEventCategoryInfo for DescRenewFailedEventRequest {
+    fn category() -> RouterEventCategory {
+        RouterEventCategory::DescRenewFailed
+    }
+}
+
+// request
+pub type RouterEventDescRenewFailedEventRequest = RouterEventRequest<DescRenewFailedEventRequest>;
+
+// response
+pub type RouterEventDescRenewFailedEventResult = RouterEventResponse<DescRenewFailedEventResponse>;
+
+// zone event recorded
+pub struct ZoneEventRecordedEventRequest {
+    pub event_id: ObjectId,
+    // ZoneEvent对象
+    pub event: NONObjectInfo,
+}
+crate::declare_event_empty_param!(ZoneEventRecordedEventResponse, ZoneEventRecorded);
+
+impl std::fmt::Display for ZoneEventRecordedEventRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "event={}", self.event_id)
+    }
+}
+
+impl JsonCodec<Self> for ZoneEventRecordedEventRequest {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "event_id", &self.event_id);
+        JsonCodecHelper::encode_field(&mut obj, "event", &self.event);
+
+        obj
+    }
+
+    fn decode_json(
+        obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> cyfs_base::BuckyResult<Self> {
+        Ok(Self {
+            event_id: JsonCodecHelper::decode_string_field(obj, "event_id")?,
+            event: JsonCodecHelper::decode_field(obj, "event")?,
+        })
+    }
+}
+
+impl RouterEventCategoryInfo for ZoneEventRecordedEventRequest {
+    fn category() -> RouterEventCategory {
+        RouterEventCategory::ZoneEventRecorded
+    }
+}
+
+// request
+pub type RouterEventZoneEventRecordedEventRequest = RouterEventRequest<ZoneEventRecordedEventRequest>;
+
+// response
+pub type RouterEventZoneEventRecordedEventResult = RouterEventResponse<ZoneEventRecordedEventResponse>;
+
+// storage mode changed
+pub struct StorageModeChangedEventRequest {
+    // 数据根目录，noc/chunk/trans-tmp/backup-staging
+    pub root: String,
+    pub read_only: bool,
+    // 进入只读模式的原因，disk-full或者io-error，恢复时为None
+    pub reason: Option<String>,
+    pub msg: String,
+}
+crate::declare_event_empty_param!(StorageModeChangedEventResponse, StorageModeChanged);
+
+impl std::fmt::Display for StorageModeChangedEventRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "root={}, read_only={}, reason={:?}, msg={}", self.root, self.read_only, self.reason, self.msg)
+    }
+}
+
+impl JsonCodec<Self> for StorageModeChangedEventRequest {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "root", &self.root);
+        JsonCodecHelper::encode_bool_field(&mut obj, "read_only", self.read_only);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "reason", self.reason.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "msg", &self.msg);
+
+        obj
+    }
+
+    fn decode_json(
+        obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> cyfs_base::BuckyResult<Self> {
+        Ok(Self {
+            root: JsonCodecHelper::decode_string_field(obj, "root")?,
+            read_only: JsonCodecHelper::decode_bool_field(obj, "read_only")?,
+            reason: JsonCodecHelper::decode_option_string_field(obj, "reason")?,
+            msg: JsonCodecHelper::decode_string_field(obj, "msg")?,
+        })
+    }
+}
+
+impl RouterEventCategoryInfo for StorageModeChangedEventRequest {
+    fn category() -> RouterEventCategory {
+        RouterEventCategory::StorageModeChanged
+    }
+}
+
+// request
+pub type RouterEventStorageModeChangedEventRequest = RouterEventRequest<StorageModeChangedEventRequest>;
+
+// response
+pub type RouterEventStorageModeChangedEventResult = RouterEventResponse<StorageModeChangedEventResponse>;
+
+// sync conflict
+// 设备同步root_state时发现和ood修改了同一路径，所有dec的handler都会收到，需要自己按dec_id过滤
+pub struct SyncConflictEventRequest {
+    pub conflict: SyncConflictInfo,
+}
+
+impl std::fmt::Display for SyncConflictEventRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "conflict={}", self.conflict)
+    }
+}
+
+impl JsonCodec<Self> for SyncConflictEventRequest {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = Map::new();
+        obj.insert("conflict".to_owned(), serde_json::to_value(&self.conflict).unwrap());
+
+        obj
+    }
+
+    fn decode_json(
+        obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> cyfs_base::BuckyResult<Self> {
+        Ok(Self {
+            conflict: JsonCodecHelper::decode_serde_field(obj, "conflict")?,
+        })
+    }
+}
+
+impl RouterEventCategoryInfo for SyncConflictEventRequest {
+    fn category() -> RouterEventCategory {
+        RouterEventCategory::SyncConflict
+    }
+}
+
+// resolution为空表示不处理，按照配置的策略；Merged时object为合并后的对象
+pub struct SyncConflictEventResponse {
+    pub resolution: Option<SyncConflictResolution>,
+    pub object: Option<NONObjectInfo>,
+}
+
+impl std::fmt::Display for SyncConflictEventResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "resolution={:?}", self.resolution)?;
+        if let Some(object) = &self.object {
+            write!(f, ", object={}", object.object_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl JsonCodec<Self> for SyncConflictEventResponse {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = Map::new();
+        if let Some(resolution) = &self.resolution {
+            obj.insert("resolution".to_owned(), serde_json::to_value(resolution).unwrap());
+        }
+        JsonCodecHelper::encode_option_field(&mut obj, "object", self.object.as_ref());
+
+        obj
+    }
+
+    fn decode_json(
+        obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> cyfs_base::BuckyResult<Self> {
+        Ok(Self {
+            resolution: JsonCodecHelper::decode_option_serde_field(obj, "resolution")?,
+            object: JsonCodecHelper::decode_option_field(obj, "object")?,
+        })
+    }
+}
+
+impl RouterEventCategoryInfo for SyncConflictEventResponse {
+    fn category() -> RouterEventCategory {
+        RouterEventCategory::SyncConflict
+    }
+}
+
+// request
+pub type RouterEventSyncConflictEventRequest = RouterEventRequest<SyncConflictEventRequest>;
+
+// response
+pub type RouterEventSyncConflictEventResult = RouterEventResponse<SyncConflictEventResponse>;
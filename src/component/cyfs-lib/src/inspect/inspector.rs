@@ -0,0 +1,257 @@
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+use std::collections::{hash_map::Entry, HashMap};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+type ObjectInspectFormatter = Arc<Box<dyn Fn(&[u8]) -> BuckyResult<Value> + Send + Sync>>;
+
+// 对象解析所使用的解码器
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ObjectInspectDecoder {
+    // 标准对象
+    Standard,
+
+    // 已注册的core对象和dec对象
+    Core,
+    Dec,
+
+    // 没有注册对应类型，只能按照通用格式解析，content保持hex
+    Typeless,
+}
+
+impl ObjectInspectDecoder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Core => "core",
+            Self::Dec => "dec",
+            Self::Typeless => "typeless",
+        }
+    }
+}
+
+impl ToString for ObjectInspectDecoder {
+    fn to_string(&self) -> String {
+        self.as_str().to_owned()
+    }
+}
+
+impl FromStr for ObjectInspectDecoder {
+    type Err = BuckyError;
+
+    fn from_str(value: &str) -> BuckyResult<Self> {
+        let ret = match value {
+            "standard" => Self::Standard,
+            "core" => Self::Core,
+            "dec" => Self::Dec,
+            "typeless" => Self::Typeless,
+            v @ _ => {
+                let msg = format!("unknown object inspect decoder: {}", v);
+                error!("{}", msg);
+
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+// 通用的对象解析，把任意对象解码为结构化的json，用于调试和命令行工具
+// 标准对象和core对象使用FORMAT_FACTORY，dec对象由各个dec按照(dec_id, obj_type)注册
+// 不同dec的自定义对象类型可能相同，所以不能直接注册到FORMAT_FACTORY
+pub struct ObjectInspector {
+    dec_types: Mutex<HashMap<(ObjectId, u16), ObjectInspectFormatter>>,
+}
+
+impl ObjectInspector {
+    pub fn new() -> Self {
+        crate::register_all_objects_format();
+
+        Self {
+            dec_types: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn register_dec_object<F>(&self, dec_id: &ObjectId, obj_type: u16, formatter: F)
+    where
+        F: 'static + Fn(&[u8]) -> BuckyResult<Value> + Send + Sync,
+    {
+        let f: ObjectInspectFormatter = Arc::new(Box::new(formatter));
+
+        let mut all = self.dec_types.lock().unwrap();
+        match all.entry((dec_id.to_owned(), obj_type)) {
+            Entry::Vacant(v) => {
+                info!(
+                    "register dec object inspector: dec={}, obj_type={}",
+                    dec_id, obj_type
+                );
+                v.insert(f);
+            }
+            Entry::Occupied(mut o) => {
+                warn!(
+                    "register dec object inspector but already exists! dec={}, obj_type={}",
+                    dec_id, obj_type
+                );
+                o.insert(f);
+            }
+        }
+    }
+
+    // 对象实现了ObjectFormat的情况下，直接按类型注册
+    pub fn register_dec_object_type<T>(&self, dec_id: &ObjectId, obj_type: u16)
+    where
+        T: for<'de> RawDecode<'de> + ObjectFormat,
+    {
+        self.register_dec_object(dec_id, obj_type, format_json::<T>)
+    }
+
+    pub fn unregister_dec_object(&self, dec_id: &ObjectId, obj_type: u16) -> bool {
+        let ret = self
+            .dec_types
+            .lock()
+            .unwrap()
+            .remove(&(dec_id.to_owned(), obj_type));
+
+        ret.is_some()
+    }
+
+    pub fn inspect(&self, object_raw: &[u8]) -> BuckyResult<Value> {
+        let (object, _) = AnyNamedObject::raw_decode(object_raw).map_err(|e| {
+            let msg = format!("decode object for inspect failed! {}", e);
+            error!("{}", msg);
+            BuckyError::new(e.code(), msg)
+        })?;
+
+        Ok(self.inspect_object(&object, object_raw))
+    }
+
+    pub fn inspect_hex(&self, object_raw: &str) -> BuckyResult<Value> {
+        let buf = hex::decode(object_raw.trim()).map_err(|e| {
+            let msg = format!("invalid object raw hex string! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        self.inspect(&buf)
+    }
+
+    // object_raw需要是object对应的编码
+    pub fn inspect_object(&self, object: &AnyNamedObject, object_raw: &[u8]) -> Value {
+        let object_id = object.calculate_id();
+        let (decoder, value) = self.format_object(object, object_raw);
+
+        let mut map = Map::new();
+        JsonCodecHelper::encode_string_field(&mut map, "object_id", &object_id);
+        JsonCodecHelper::encode_number_field(&mut map, "object_type", object.obj_type());
+        JsonCodecHelper::encode_string_field_2(
+            &mut map,
+            "object_type_code",
+            format!("{:?}", object.obj_type_code()),
+        );
+        JsonCodecHelper::encode_string_field(
+            &mut map,
+            "object_category",
+            &object_id.object_category(),
+        );
+        if let Some(dec_id) = object.dec_id() {
+            JsonCodecHelper::encode_string_field(&mut map, "dec_id", dec_id);
+        }
+        JsonCodecHelper::encode_string_field(&mut map, "decoder", &decoder);
+        JsonCodecHelper::encode_number_field(&mut map, "size", object_raw.len() as u64);
+        map.insert("object".to_owned(), value);
+
+        map.into()
+    }
+
+    // 只返回对象本身的json(desc/body/signs/nonce)，以及使用的解码器
+    pub fn format_object(
+        &self,
+        object: &AnyNamedObject,
+        object_raw: &[u8],
+    ) -> (ObjectInspectDecoder, Value) {
+        let obj_type = object.obj_type();
+        if object.obj_type_code() != ObjectTypeCode::Custom {
+            return (ObjectInspectDecoder::Standard, object.format_json());
+        }
+
+        if object_type_helper::is_dec_app_object(obj_type) {
+            if let Some(dec_id) = object.dec_id() {
+                let f = self
+                    .dec_types
+                    .lock()
+                    .unwrap()
+                    .get(&(dec_id.to_owned(), obj_type))
+                    .map(|f| f.clone());
+                if let Some(f) = f {
+                    match f(object_raw) {
+                        Ok(v) => return (ObjectInspectDecoder::Dec, v),
+                        Err(e) => {
+                            warn!(
+                                "inspect dec object failed! dec={}, obj_type={}, {}",
+                                dec_id, obj_type, e
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // 兼容直接注册到FORMAT_FACTORY的对象
+        if let Some(v) = FORMAT_FACTORY.format(obj_type, object_raw) {
+            let decoder = if object_type_helper::is_core_object(obj_type) {
+                ObjectInspectDecoder::Core
+            } else {
+                ObjectInspectDecoder::Dec
+            };
+            return (decoder, v);
+        }
+
+        (ObjectInspectDecoder::Typeless, object.format_json())
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref OBJECT_INSPECTOR: ObjectInspector = ObjectInspector::new();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::*;
+
+    #[test]
+    fn test_inspect() {
+        let inspector = ObjectInspector::new();
+
+        let owner = ObjectId::default();
+        let file = File::new(
+            owner,
+            100,
+            HashValue::default(),
+            ChunkList::ChunkInList(vec![]),
+        )
+        .no_create_time()
+        .build();
+        let value = inspector.inspect(&file.to_vec().unwrap()).unwrap();
+        assert_eq!(value["decoder"], "standard");
+        assert_eq!(value["object_id"], file.desc().calculate_id().to_string());
+        assert!(value["object"]["desc"].is_object());
+
+        let text = Text::create("inspect", "header", "value");
+        let raw = text.to_vec().unwrap();
+        let value = inspector.inspect_hex(&hex::encode(&raw)).unwrap();
+        assert_eq!(value["decoder"], "core");
+        assert_eq!(value["size"], raw.len());
+
+        let dec_id = ObjectId::default();
+        inspector.register_dec_object(&dec_id, 32768, |_buf| Ok(Value::from("dec")));
+        assert!(inspector.unregister_dec_object(&dec_id, 32768));
+        assert!(!inspector.unregister_dec_object(&dec_id, 32768));
+
+        assert!(inspector.inspect(&raw[..raw.len() / 2]).is_err());
+        assert!(inspector.inspect_hex("not hex").is_err());
+    }
+}
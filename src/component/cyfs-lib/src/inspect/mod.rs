@@ -0,0 +1,3 @@
+mod inspector;
+
+pub use inspector::*;
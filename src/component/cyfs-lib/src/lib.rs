@@ -1,18 +1,33 @@
 mod acl;
 mod admin;
+mod admin_confirm;
+mod api_gateway;
+mod bandwidth;
 mod base;
+mod contacts;
 mod crypto;
+mod dec_config;
 mod events;
+mod inspect;
 mod ndn;
 mod non;
+mod object_history;
+mod object_refs;
 mod prelude;
+mod queue;
 mod rmeta;
 mod root_state;
 mod router_handler;
+mod rpc;
+mod schedule;
+mod search;
+mod service_discovery;
+mod share;
 mod stack;
 mod storage;
 mod sync;
 mod trans;
+mod trash;
 mod util;
 mod ws;
 mod zone;
@@ -22,19 +37,34 @@ mod traversal;
 pub use crate::util::*;
 pub use acl::*;
 pub use admin::*;
+pub use admin_confirm::*;
+pub use api_gateway::*;
+pub use bandwidth::*;
 pub use base::*;
+pub use contacts::*;
 pub use crypto::*;
+pub use dec_config::*;
 pub use events::*;
+pub use inspect::*;
 pub use ndn::*;
 pub use non::*;
+pub use object_history::*;
+pub use object_refs::*;
 pub use prelude::*;
+pub use queue::*;
 pub use rmeta::*;
 pub use root_state::*;
 pub use router_handler::*;
+pub use rpc::*;
+pub use schedule::*;
+pub use search::*;
+pub use service_discovery::*;
+pub use share::*;
 pub use stack::*;
 pub use storage::*;
 pub use sync::*;
 pub use trans::*;
+pub use trash::*;
 pub use ws::*;
 pub use zone::*;
 pub use requestor::*;
@@ -53,6 +83,17 @@ pub fn register_core_objects_format() {
     FORMAT_FACTORY.register(cyfs_core::CoreObjectType::Admin, format_json::<AdminObject>);
 }
 
+// 注册core和lib里面的所有对象格式，可以重复调用
+pub fn register_all_objects_format() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static INIT_DONE: AtomicBool = AtomicBool::new(false);
+    if !INIT_DONE.swap(true, Ordering::SeqCst) {
+        cyfs_core::register_core_objects_format();
+        register_core_objects_format();
+    }
+}
+
 cyfs_base::declare_module_perf_isolate!("cyfs-lib");
 
 #[cfg(test)]
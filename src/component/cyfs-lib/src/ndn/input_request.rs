@@ -30,11 +30,18 @@ pub struct NDNInputRequestCommon {
 
     pub flags: u32,
 
+    // 请求的截止时间(bucky time)，超时后请求会被中止
+    pub deadline: Option<u64>,
+
     // input链的自定义数据
     pub user_data: Option<NDNInputRequestUserData>,
 }
 
 impl NDNInputRequestCommon {
+    pub fn check_deadline(&self) -> BuckyResult<()> {
+        RequestDeadline::check(self.deadline)
+    }
+
     pub fn check_param_with_referer(&self, object_id: &ObjectId) -> BuckyResult<()> {
         match object_id.obj_type_code() {
             ObjectTypeCode::Chunk => {
@@ -126,6 +133,10 @@ impl fmt::Display for NDNInputRequestCommon {
 
         write!(f, ", flags: {}", self.flags)?;
 
+        if let Some(deadline) = &self.deadline {
+            write!(f, ", deadline: {}", deadline)?;
+        }
+
         Ok(())
     }
 }
@@ -14,6 +14,7 @@ impl JsonCodec<NDNInputRequestCommon> for NDNInputRequestCommon {
         JsonCodecHelper::encode_option_string_field(&mut obj, "target", self.target.as_ref());
         JsonCodecHelper::encode_str_array_field(&mut obj, "referer_object", &self.referer_object);
         JsonCodecHelper::encode_number_field(&mut obj, "flags", self.flags);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "deadline", self.deadline);
 
         obj
     }
@@ -26,6 +27,7 @@ impl JsonCodec<NDNInputRequestCommon> for NDNInputRequestCommon {
             referer_object: JsonCodecHelper::decode_str_array_field(obj, "referer_object")?,
             target: JsonCodecHelper::decode_option_string_field(obj, "target")?,
             flags: JsonCodecHelper::decode_int_field(obj, "flags")?,
+            deadline: JsonCodecHelper::decode_option_int_field(obj, "deadline")?,
             user_data: None,
         })
     }
@@ -24,6 +24,9 @@ pub struct NDNOutputRequestCommon {
     pub referer_object: Vec<NDNDataRefererObject>,
 
     pub flags: u32,
+
+    // The deadline of the request in bucky time, the request will be aborted after it expired
+    pub deadline: Option<u64>,
 }
 
 impl NDNOutputRequestCommon {
@@ -35,6 +38,7 @@ impl NDNOutputRequestCommon {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         }
     }
 }
@@ -58,6 +62,10 @@ impl fmt::Display for NDNOutputRequestCommon {
 
         write!(f, ", flags: {}", self.flags)?;
 
+        if let Some(deadline) = &self.deadline {
+            write!(f, ", deadline: {}", deadline)?;
+        }
+
         Ok(())
     }
 }
@@ -13,6 +13,7 @@ impl JsonCodec<NDNOutputRequestCommon> for NDNOutputRequestCommon {
         JsonCodecHelper::encode_option_string_field(&mut obj, "target", self.target.as_ref());
         JsonCodecHelper::encode_str_array_field(&mut obj, "referer_object", &self.referer_object);
         JsonCodecHelper::encode_number_field(&mut obj, "flags", self.flags);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "deadline", self.deadline);
 
         obj
     }
@@ -25,6 +26,7 @@ impl JsonCodec<NDNOutputRequestCommon> for NDNOutputRequestCommon {
             target: JsonCodecHelper::decode_option_string_field(obj, "target")?,
             referer_object: JsonCodecHelper::decode_str_array_field(obj, "referer_object")?,
             flags: JsonCodecHelper::decode_int_field(obj, "flags")?,
+            deadline: JsonCodecHelper::decode_option_int_field(obj, "deadline")?,
         })
     }
 }
@@ -131,6 +131,10 @@ impl NDNRequestor {
         }
 
         http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+
+        if let Some(deadline) = &com_req.deadline {
+            http_req.insert_header(cyfs_base::CYFS_DEADLINE, deadline.to_string());
+        }
     }
 
     fn encode_put_data_request(&self, req: &NDNPutDataOutputRequest) -> Request {
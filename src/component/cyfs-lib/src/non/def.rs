@@ -422,16 +422,8 @@ impl JsonCodec<NONObjectInfo> for NONObjectInfo {
 
 impl ObjectFormat for NONObjectInfo {
     fn format_json(&self) -> serde_json::Value {
-        let obj = self.object();
-        if obj.obj_type_code() != ObjectTypeCode::Custom {
-            obj.format_json()
-        } else {
-            let obj_type = obj.obj_type();
-            match FORMAT_FACTORY.format(obj_type, &self.object_raw) {
-                Some(ret) => ret,
-                None => obj.format_json(),
-            }
-        }
+        let (_, ret) = crate::OBJECT_INSPECTOR.format_object(self.object(), &self.object_raw);
+        ret
     }
 }
 
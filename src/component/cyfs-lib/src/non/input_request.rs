@@ -19,6 +19,18 @@ pub struct NONInputRequestCommon {
     pub target: Option<ObjectId>,
 
     pub flags: u32,
+
+    // 请求的截止时间(bucky time)，超时后请求会被中止
+    pub deadline: Option<u64>,
+
+    // 客户端生成的请求id，重试时保持不变，服务端对put/delete请求按id去重
+    pub request_id: Option<String>,
+}
+
+impl NONInputRequestCommon {
+    pub fn check_deadline(&self) -> BuckyResult<()> {
+        RequestDeadline::check(self.deadline)
+    }
 }
 
 impl fmt::Display for NONInputRequestCommon {
@@ -34,6 +46,14 @@ impl fmt::Display for NONInputRequestCommon {
 
         write!(f, ", flags: {}", self.flags)?;
 
+        if let Some(deadline) = &self.deadline {
+            write!(f, ", deadline: {}", deadline)?;
+        }
+
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request_id: {}", request_id)?;
+        }
+
         Ok(())
     }
 }
@@ -134,6 +154,7 @@ pub struct NONPutObjectInputRequest {
 
     pub object: NONObjectInfo,
     pub access: Option<AccessString>,
+    pub precondition: Option<NamedObjectCachePutPrecondition>,
 }
 
 impl fmt::Display for NONPutObjectInputRequest {
@@ -143,12 +164,15 @@ impl fmt::Display for NONPutObjectInputRequest {
         if let Some(access) = &self.access {
             write!(f, ", access: {}", access.to_string())?;
         }
+        if let Some(precondition) = &self.precondition {
+            write!(f, ", precondition: {}", precondition)?;
+        }
 
         Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NONPutObjectInputResponse {
     pub result: NONPutObjectResult,
     pub object_update_time: Option<u64>,
@@ -271,7 +295,7 @@ impl fmt::Display for NONDeleteObjectInputRequest {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct NONDeleteObjectInputResponse {
     pub object: Option<NONObjectInfo>,
 }
@@ -12,6 +12,12 @@ impl JsonCodec<NONInputRequestCommon> for NONInputRequestCommon {
         JsonCodecHelper::encode_string_field(&mut obj, "level", &self.level);
         JsonCodecHelper::encode_option_string_field(&mut obj, "target", self.target.as_ref());
         JsonCodecHelper::encode_number_field(&mut obj, "flags", self.flags);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "deadline", self.deadline);
+        JsonCodecHelper::encode_option_string_field(
+            &mut obj,
+            "request_id",
+            self.request_id.as_ref(),
+        );
 
         obj
     }
@@ -23,6 +29,8 @@ impl JsonCodec<NONInputRequestCommon> for NONInputRequestCommon {
             level: JsonCodecHelper::decode_string_field(obj, "level")?,
             target: JsonCodecHelper::decode_option_string_field(obj, "target")?,
             flags: JsonCodecHelper::decode_int_field(obj, "flags")?,
+            deadline: JsonCodecHelper::decode_option_int_field(obj, "deadline")?,
+            request_id: JsonCodecHelper::decode_option_string_field(obj, "request_id")?,
         })
     }
 }
@@ -22,6 +22,12 @@ pub struct NONOutputRequestCommon {
     pub target: Option<ObjectId>,
 
     pub flags: u32,
+
+    // 请求的截止时间(bucky time)，超时后请求会被中止
+    pub deadline: Option<u64>,
+
+    // 客户端生成的请求id，重试时保持不变，服务端对put/delete请求按id去重
+    pub request_id: Option<String>,
 }
 
 impl NONOutputRequestCommon {
@@ -33,6 +39,8 @@ impl NONOutputRequestCommon {
             level,
             target: None,
             flags: 0,
+            deadline: None,
+            request_id: None,
         }
     }
 }
@@ -56,6 +64,14 @@ impl fmt::Display for NONOutputRequestCommon {
 
         write!(f, ", flags: {}", self.flags)?;
 
+        if let Some(deadline) = &self.deadline {
+            write!(f, ", deadline: {}", deadline)?;
+        }
+
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request_id: {}", request_id)?;
+        }
+
         Ok(())
     }
 }
@@ -67,6 +83,9 @@ pub struct NONPutObjectOutputRequest {
 
     pub object: NONObjectInfo,
     pub access: Option<AccessString>,
+
+    // 写入前置条件，不满足时返回Conflict错误
+    pub precondition: Option<NamedObjectCachePutPrecondition>,
 }
 
 impl NONPutObjectOutputRequest {
@@ -75,6 +94,7 @@ impl NONPutObjectOutputRequest {
             common: NONOutputRequestCommon::new(level),
             object: NONObjectInfo::new(object_id, object_raw, None),
             access: None,
+            precondition: None,
         }
     }
 
@@ -104,6 +124,9 @@ impl fmt::Display for NONPutObjectOutputRequest {
         if let Some(access) = &self.access {
             write!(f, ", access: {}", access.to_string())?;
         }
+        if let Some(precondition) = &self.precondition {
+            write!(f, ", precondition: {}", precondition)?;
+        }
 
         Ok(())
     }
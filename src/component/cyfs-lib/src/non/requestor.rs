@@ -185,6 +185,14 @@ impl NONRequestor {
         }
 
         http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+
+        if let Some(deadline) = &com_req.deadline {
+            http_req.insert_header(cyfs_base::CYFS_DEADLINE, deadline.to_string());
+        }
+
+        if let Some(request_id) = &com_req.request_id {
+            http_req.insert_header(cyfs_base::CYFS_REQUEST_ID, request_id.as_str());
+        }
     }
 
     fn encode_put_object_request(&self, req: &NONPutObjectOutputRequest) -> Request {
@@ -206,6 +214,12 @@ impl NONRequestor {
             http_req.insert_header(cyfs_base::CYFS_ACCESS, access.value().to_string());
         }
 
+        RequestorHelper::encode_opt_header(
+            &mut http_req,
+            cyfs_base::CYFS_PUT_PRECONDITION,
+            &req.precondition,
+        );
+
         http_req
     }
 
@@ -261,6 +275,7 @@ impl NONRequestor {
             common: req.common,
             object: NONObjectInfo::new(req.object_id, vec![], None),
             access: req.access,
+            precondition: None,
         };
 
         self.put_object(req).await
@@ -469,6 +484,52 @@ impl NONRequestor {
             Err(e)
         }
     }
+
+    // 以下方法在超时、连接失败等短暂错误时按policy重试
+    // put和delete会先生成请求id，重试时保持不变，服务端据此去重，避免超时后的重试导致重复写入
+    pub async fn put_object_with_retry(
+        &self,
+        mut req: NONPutObjectOutputRequest,
+        policy: &RequestRetryPolicy,
+    ) -> BuckyResult<NONPutObjectOutputResponse> {
+        if req.common.request_id.is_none() {
+            req.common.request_id = Some(RequestId::gen());
+        }
+
+        policy
+            .run("put_object", req.common.deadline, || {
+                self.put_object(req.clone())
+            })
+            .await
+    }
+
+    pub async fn get_object_with_retry(
+        &self,
+        req: NONGetObjectOutputRequest,
+        policy: &RequestRetryPolicy,
+    ) -> BuckyResult<NONGetObjectOutputResponse> {
+        policy
+            .run("get_object", req.common.deadline, || {
+                self.get_object(req.clone())
+            })
+            .await
+    }
+
+    pub async fn delete_object_with_retry(
+        &self,
+        mut req: NONDeleteObjectOutputRequest,
+        policy: &RequestRetryPolicy,
+    ) -> BuckyResult<NONDeleteObjectOutputResponse> {
+        if req.common.request_id.is_none() {
+            req.common.request_id = Some(RequestId::gen());
+        }
+
+        policy
+            .run("delete_object", req.common.deadline, || {
+                self.delete_object(req.clone())
+            })
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -0,0 +1,20 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// get_object_history
+pub struct ObjectHistoryListInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub object_id: ObjectId,
+}
+
+pub type ObjectHistoryListInputResponse = ObjectHistoryListOutputResponse;
+
+// get_object_revision
+pub struct ObjectHistoryGetRevisionInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub object_id: ObjectId,
+    pub revision: u32,
+}
+
+pub type ObjectHistoryGetRevisionInputResponse = ObjectHistoryGetRevisionOutputResponse;
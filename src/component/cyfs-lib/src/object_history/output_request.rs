@@ -0,0 +1,82 @@
+use crate::util::{deserialize_hex, serialize_hex};
+use crate::{NamedObjectCacheObjectVersion, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// 查询noc里对象保留的历史版本，需要在rmeta的object meta里配置versions
+#[derive(Debug, Clone)]
+pub struct ObjectHistoryListOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub object_id: ObjectId,
+}
+
+impl Display for ObjectHistoryListOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, object_id: {}", self.common, self.object_id)
+    }
+}
+
+impl ObjectHistoryListOutputRequest {
+    pub fn new(object_id: ObjectId) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            object_id,
+        }
+    }
+}
+
+// 按revision倒序，最新的在前
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectHistoryListOutputResponse {
+    pub list: Vec<NamedObjectCacheObjectVersion>,
+}
+
+impl Display for ObjectHistoryListOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectHistoryGetRevisionOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub object_id: ObjectId,
+    pub revision: u32,
+}
+
+impl Display for ObjectHistoryGetRevisionOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, object_id: {}, revision: {}",
+            self.common, self.object_id, self.revision
+        )
+    }
+}
+
+impl ObjectHistoryGetRevisionOutputRequest {
+    pub fn new(object_id: ObjectId, revision: u32) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            object_id,
+            revision,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectHistoryGetRevisionOutputResponse {
+    pub version: NamedObjectCacheObjectVersion,
+
+    pub object_id: ObjectId,
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub object_raw: Vec<u8>,
+}
+
+impl Display for ObjectHistoryGetRevisionOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "object_id: {}, {}", self.object_id, self.version)
+    }
+}
@@ -0,0 +1,38 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ObjectHistoryListOutputRequest> for ObjectHistoryListOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "object_id", &self.object_id);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ObjectHistoryListOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            object_id: JsonCodecHelper::decode_string_field(obj, "object_id")?,
+        })
+    }
+}
+
+impl JsonCodec<ObjectHistoryGetRevisionOutputRequest> for ObjectHistoryGetRevisionOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "object_id", &self.object_id);
+        JsonCodecHelper::encode_number_field(&mut obj, "revision", self.revision);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ObjectHistoryGetRevisionOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            object_id: JsonCodecHelper::decode_string_field(obj, "object_id")?,
+            revision: JsonCodecHelper::decode_int_field(obj, "revision")?,
+        })
+    }
+}
@@ -0,0 +1,19 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ObjectHistoryOutputProcessor: Sync + Send + 'static {
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListOutputRequest,
+    ) -> BuckyResult<ObjectHistoryListOutputResponse>;
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionOutputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionOutputResponse>;
+}
+
+pub type ObjectHistoryOutputProcessorRef = Arc<dyn ObjectHistoryOutputProcessor>;
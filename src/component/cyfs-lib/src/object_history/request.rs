@@ -0,0 +1,7 @@
+use super::output_request::*;
+
+pub type ObjectHistoryListRequest = ObjectHistoryListOutputRequest;
+pub type ObjectHistoryListResponse = ObjectHistoryListOutputResponse;
+
+pub type ObjectHistoryGetRevisionRequest = ObjectHistoryGetRevisionOutputRequest;
+pub type ObjectHistoryGetRevisionResponse = ObjectHistoryGetRevisionOutputResponse;
@@ -0,0 +1,14 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// query_object_refs
+pub struct ObjectRefsQueryInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub query_type: ObjectRefsQueryType,
+    pub object_id: Option<ObjectId>,
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+pub type ObjectRefsQueryInputResponse = ObjectRefsQueryOutputResponse;
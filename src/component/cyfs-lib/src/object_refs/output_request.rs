@@ -0,0 +1,111 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+// 对象引用关系的查询类型
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ObjectRefsQueryType {
+    // 引用了object_id的对象
+    Referrers,
+
+    // object_id引用的对象和chunk
+    References,
+
+    // 曾经被引用，但当前没有任何对象引用的对象和chunk
+    Orphans,
+}
+
+impl ObjectRefsQueryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Referrers => "referrers",
+            Self::References => "references",
+            Self::Orphans => "orphans",
+        }
+    }
+}
+
+impl Display for ObjectRefsQueryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ObjectRefsQueryType {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        let ret = match s {
+            "referrers" => Self::Referrers,
+            "references" => Self::References,
+            "orphans" => Self::Orphans,
+            _ => {
+                let msg = format!("unknown object refs query type: {}", s);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectRefsQueryOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    pub query_type: ObjectRefsQueryType,
+
+    // Referrers和References需要指定，Orphans忽略
+    pub object_id: Option<ObjectId>,
+
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+impl Display for ObjectRefsQueryOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, query_type: {}, object_id: {:?}, page_index: {}, page_size: {}",
+            self.common, self.query_type, self.object_id, self.page_index, self.page_size
+        )
+    }
+}
+
+impl ObjectRefsQueryOutputRequest {
+    pub fn new_referrers(object_id: ObjectId) -> Self {
+        Self::new(ObjectRefsQueryType::Referrers, Some(object_id))
+    }
+
+    pub fn new_references(object_id: ObjectId) -> Self {
+        Self::new(ObjectRefsQueryType::References, Some(object_id))
+    }
+
+    pub fn new_orphans() -> Self {
+        Self::new(ObjectRefsQueryType::Orphans, None)
+    }
+
+    fn new(query_type: ObjectRefsQueryType, object_id: Option<ObjectId>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            query_type,
+            object_id,
+            page_index: 0,
+            page_size: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectRefsQueryOutputResponse {
+    pub list: Vec<ObjectId>,
+}
+
+impl Display for ObjectRefsQueryOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {:?}", self.list)
+    }
+}
@@ -0,0 +1,40 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ObjectRefsQueryOutputRequest> for ObjectRefsQueryOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "query_type", &self.query_type);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "object_id", self.object_id.as_ref());
+        JsonCodecHelper::encode_number_field(&mut obj, "page_index", self.page_index);
+        JsonCodecHelper::encode_number_field(&mut obj, "page_size", self.page_size);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ObjectRefsQueryOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            query_type: JsonCodecHelper::decode_string_field(obj, "query_type")?,
+            object_id: JsonCodecHelper::decode_option_string_field(obj, "object_id")?,
+            page_index: JsonCodecHelper::decode_int_field(obj, "page_index")?,
+            page_size: JsonCodecHelper::decode_int_field(obj, "page_size")?,
+        })
+    }
+}
+
+impl JsonCodec<ObjectRefsQueryOutputResponse> for ObjectRefsQueryOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_str_array_field(&mut obj, "list", &self.list);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ObjectRefsQueryOutputResponse> {
+        Ok(Self {
+            list: JsonCodecHelper::decode_str_array_field(obj, "list")?,
+        })
+    }
+}
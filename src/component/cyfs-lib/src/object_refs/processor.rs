@@ -0,0 +1,14 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ObjectRefsOutputProcessor: Sync + Send + 'static {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryOutputRequest,
+    ) -> BuckyResult<ObjectRefsQueryOutputResponse>;
+}
+
+pub type ObjectRefsOutputProcessorRef = Arc<dyn ObjectRefsOutputProcessor>;
@@ -0,0 +1,4 @@
+use super::output_request::*;
+
+pub type ObjectRefsQueryRequest = ObjectRefsQueryOutputRequest;
+pub type ObjectRefsQueryResponse = ObjectRefsQueryOutputResponse;
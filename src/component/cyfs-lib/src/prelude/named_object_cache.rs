@@ -50,6 +50,75 @@ pub struct NamedObjectCachePutObjectRequest {
     pub context: Option<String>,
     pub last_access_rpath: Option<String>,
     pub access_string: Option<u32>,
+
+    // 写入前置条件，不满足时返回Conflict错误
+    pub precondition: Option<NamedObjectCachePutPrecondition>,
+}
+
+// put_object的乐观并发控制，多个写入方修改同一个可变对象时避免互相覆盖
+// 同一个对象的id不变，所以通过body的update_time来区分版本
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NamedObjectCachePutPrecondition {
+    // 对象必须不存在
+    NotExists,
+
+    // 对象必须存在，并且当前保存的update_time和指定值相同
+    UpdateTime(u64),
+}
+
+impl NamedObjectCachePutPrecondition {
+    // 检查当前保存的对象是否满足条件，current为None表示对象不存在
+    pub fn check(
+        &self,
+        object_id: &ObjectId,
+        current: Option<&NamedObjectMetaData>,
+    ) -> BuckyResult<()> {
+        let update_time = current.map(|meta| meta.object_update_time.unwrap_or(0));
+        let ok = match self {
+            Self::NotExists => current.is_none(),
+            Self::UpdateTime(v) => update_time == Some(*v),
+        };
+
+        if !ok {
+            let msg = format!(
+                "put object precondition failed! object={}, precondition={}, current update_time={:?}",
+                object_id, self, update_time,
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Conflict, msg));
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for NamedObjectCachePutPrecondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotExists => write!(f, "not-exists"),
+            Self::UpdateTime(v) => write!(f, "update-time:{}", v),
+        }
+    }
+}
+
+impl std::str::FromStr for NamedObjectCachePutPrecondition {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        if s == "not-exists" {
+            return Ok(Self::NotExists);
+        }
+
+        if let Some(v) = s.strip_prefix("update-time:") {
+            if let Ok(v) = v.parse::<u64>() {
+                return Ok(Self::UpdateTime(v));
+            }
+        }
+
+        let msg = format!("invalid put object precondition: {}", s);
+        error!("{}", msg);
+        Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg))
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -76,6 +145,9 @@ pub struct NamedObjectCacheUpdateObjectMetaRequest {
     pub context: Option<String>,
     pub last_access_rpath: Option<String>,
     pub access_string: Option<u32>,
+
+    // the object_update_time of the object whose signs has been verified
+    pub sign_verified_update_time: Option<u64>,
 }
 
 impl NamedObjectCacheUpdateObjectMetaRequest {
@@ -84,6 +156,7 @@ impl NamedObjectCacheUpdateObjectMetaRequest {
             && self.context.is_none()
             && self.last_access_rpath.is_none()
             && self.access_string.is_none()
+            && self.sign_verified_update_time.is_none()
     }
 }
 
@@ -137,6 +210,19 @@ pub struct NamedObjectMetaData {
 
     pub last_access_rpath: Option<String>,
     pub access_string: u32,
+
+    // the object's signs has been verified with the owner at which object_update_time, none means never verified
+    pub sign_verified_update_time: Option<u64>,
+}
+
+impl NamedObjectMetaData {
+    // the verify result is invalid once the object been updated
+    pub fn is_sign_verified(&self, object_update_time: Option<u64>) -> bool {
+        match self.sign_verified_update_time {
+            Some(v) => v == object_update_time.unwrap_or(0),
+            None => false,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -204,12 +290,16 @@ pub struct NamedObjectCacheStat {
 #[derive(Debug, Clone)]
 pub struct NamedObjectCacheSelectObjectFilter {
     pub obj_type: Option<u16>,
+
+    // 只选择最近访问时间早于指定时间的对象，结果按访问时间从早到晚排序
+    pub last_access_before: Option<u64>,
 }
 
 impl Default for NamedObjectCacheSelectObjectFilter {
     fn default() -> Self {
         Self {
             obj_type: None,
+            last_access_before: None,
         }
     }
 }
@@ -221,6 +311,9 @@ pub struct NamedObjectCacheSelectObjectOption {
 
     // The page number currently read, starting from 0
     pub page_index: usize,
+
+    // The cursor returned by the previous page, page_index is ignored if specified
+    pub cursor: Option<PageCursor>,
 }
 
 impl Default for NamedObjectCacheSelectObjectOption {
@@ -228,6 +321,7 @@ impl Default for NamedObjectCacheSelectObjectOption {
         Self {
             page_size: 1024,
             page_index: 0,
+            cursor: None,
         }
     }
 }
@@ -249,6 +343,60 @@ pub struct NamedObjectCacheSelectObjectData {
 #[derive(Debug)]
 pub struct NamedObjectCacheSelectObjectResponse {
     pub list: Vec<NamedObjectCacheSelectObjectData>,
+    pub page: PageInfo,
+}
+
+// get_object_history
+#[derive(Clone, Debug)]
+pub struct NamedObjectCacheGetObjectHistoryRequest {
+    pub source: RequestSourceInfo,
+
+    pub object_id: ObjectId,
+}
+
+// 对象被新的body替换前保留的历史版本，revision在同一个对象内单调递增
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NamedObjectCacheObjectVersion {
+    pub revision: u32,
+
+    pub object_update_time: Option<u64>,
+
+    // 被替换的时间
+    pub archive_time: u64,
+
+    pub object_size: u32,
+}
+
+impl std::fmt::Display for NamedObjectCacheObjectVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "revision={}, object_update_time={:?}, archive_time={}, size={}",
+            self.revision, self.object_update_time, self.archive_time, self.object_size
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedObjectCacheGetObjectHistoryResponse {
+    // 按revision从新到旧排列
+    pub list: Vec<NamedObjectCacheObjectVersion>,
+}
+
+// get_object_revision
+#[derive(Clone, Debug)]
+pub struct NamedObjectCacheGetObjectRevisionRequest {
+    pub source: RequestSourceInfo,
+
+    pub object_id: ObjectId,
+
+    pub revision: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedObjectCacheObjectRevisionData {
+    pub version: NamedObjectCacheObjectVersion,
+    pub object: NONObjectInfo,
 }
 
 #[async_trait::async_trait]
@@ -307,6 +455,16 @@ pub trait NamedObjectCache: Sync + Send {
 
     async fn stat(&self) -> BuckyResult<NamedObjectCacheStat>;
 
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse>;
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>>;
+
     // for internal use only
     async fn select_object(
         &self,
@@ -321,6 +479,29 @@ pub trait NamedObjectCache: Sync + Send {
 
 pub type NamedObjectCacheRef = Arc<Box<dyn NamedObjectCache>>;
 
+// iterate all the objects match the filter page by page
+pub fn noc_select_object_iter(
+    noc: NamedObjectCacheRef,
+    filter: NamedObjectCacheSelectObjectFilter,
+    page_size: u32,
+) -> PageIterator<NamedObjectCacheSelectObjectData> {
+    PageIterator::new(page_size, move |page| {
+        let noc = noc.clone();
+        let req = NamedObjectCacheSelectObjectRequest {
+            filter: filter.clone(),
+            opt: NamedObjectCacheSelectObjectOption {
+                page_size: page.page_size() as usize,
+                page_index: 0,
+                cursor: page.cursor,
+            },
+        };
+        Box::pin(async move {
+            let resp = noc.select_object(&req).await?;
+            Ok((resp.list, resp.page))
+        }) as PageFetchFuture<NamedObjectCacheSelectObjectData>
+    })
+}
+
 impl ObjectSelectorDataProvider for NamedObjectMetaData {
     fn object_id(&self) -> &ObjectId {
         &self.object_id
@@ -366,6 +547,20 @@ pub trait NamedObjectCacheObjectMetaAccessProvider: Sync + Send {
         source: &RequestSourceInfo,
         permissions: AccessPermissions,
     ) -> BuckyResult<Option<()>>;
+
+    // 查询对象匹配的历史版本保留数量，没有配置返回None
+    async fn get_object_versions(
+        &self,
+        target_dec_id: &ObjectId,
+        object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<Option<u32>>;
+
+    // 查询对象的blob是否需要加密存储
+    async fn get_object_encrypt(
+        &self,
+        target_dec_id: &ObjectId,
+        object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<bool>;
 }
 
 pub type NamedObjectCacheObjectMetaAccessProviderRef = Arc<Box<dyn NamedObjectCacheObjectMetaAccessProvider>>;
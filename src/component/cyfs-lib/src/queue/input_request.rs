@@ -0,0 +1,50 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// create_queue
+pub struct QueueCreateInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+    pub config: QueueConfig,
+}
+
+pub type QueueCreateInputResponse = QueueCreateOutputResponse;
+
+// push_queue
+pub struct QueuePushInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub zone: Option<ObjectId>,
+    pub dec_id: ObjectId,
+    pub name: String,
+    pub object_id: ObjectId,
+    pub object_raw: Vec<u8>,
+}
+
+pub type QueuePushInputResponse = QueuePushOutputResponse;
+
+// pull_queue
+pub struct QueuePullInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+    pub max_count: u32,
+}
+
+pub type QueuePullInputResponse = QueuePullOutputResponse;
+
+// ack_queue
+pub struct QueueAckInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+    pub keys: Vec<String>,
+}
+
+pub type QueueAckInputResponse = QueueAckOutputResponse;
+
+// get_queue_dead_letters
+pub struct QueueGetDeadLettersInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+}
+
+pub type QueueGetDeadLettersInputResponse = QueueGetDeadLettersOutputResponse;
@@ -0,0 +1,254 @@
+use crate::util::{deserialize_hex, serialize_hex};
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// 队列的配置，由队列所属的dec创建时指定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueConfig {
+    // 每条消息最多投递的次数，超过后进入死信
+    pub max_attempts: u32,
+
+    // pull之后超过这个时间没有ack的消息会被重新投递
+    pub lease_secs: u32,
+
+    // 是否接收其它zone推送的消息
+    pub allow_remote: bool,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            lease_secs: 30,
+            allow_remote: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueMessage {
+    // 消息在队列中的唯一标识，ack时使用
+    pub key: String,
+
+    pub object_id: ObjectId,
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub object_raw: Vec<u8>,
+
+    // 推送方的zone和dec，zone为空表示当前zone
+    pub source_zone: Option<ObjectId>,
+    pub source_dec: ObjectId,
+
+    // bucky time
+    pub push_time: u64,
+
+    // 已经投递的次数，包括本次
+    pub attempts: u32,
+}
+
+// 队列只保存在ood上，属于发起请求的dec，没有指定target时默认路由到当前zone的ood
+#[derive(Debug, Clone)]
+pub struct QueueCreateOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+    pub config: QueueConfig,
+}
+
+impl Display for QueueCreateOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {}, config: {:?}",
+            self.common, self.name, self.config
+        )
+    }
+}
+
+impl QueueCreateOutputRequest {
+    pub fn new(name: impl Into<String>, config: QueueConfig) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+            config,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueCreateOutputResponse {
+    pub config: QueueConfig,
+}
+
+impl Display for QueueCreateOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "config: {:?}", self.config)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuePushOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    // 目标队列所在的zone，可以是zone内的任意对象(people/device等)，为空表示当前zone
+    pub zone: Option<ObjectId>,
+
+    // 目标队列所属的dec和名字
+    pub dec_id: ObjectId,
+    pub name: String,
+
+    pub object_id: ObjectId,
+    pub object_raw: Vec<u8>,
+}
+
+impl Display for QueuePushOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, zone: {:?}, dec: {}, name: {}, object: {}",
+            self.common, self.zone, self.dec_id, self.name, self.object_id
+        )
+    }
+}
+
+impl QueuePushOutputRequest {
+    pub fn new(
+        dec_id: ObjectId,
+        name: impl Into<String>,
+        object_id: ObjectId,
+        object_raw: Vec<u8>,
+    ) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            zone: None,
+            dec_id,
+            name: name.into(),
+            object_id,
+            object_raw,
+        }
+    }
+
+    pub fn new_remote(
+        zone: ObjectId,
+        dec_id: ObjectId,
+        name: impl Into<String>,
+        object_id: ObjectId,
+        object_raw: Vec<u8>,
+    ) -> Self {
+        let mut ret = Self::new(dec_id, name, object_id, object_raw);
+        ret.zone = Some(zone);
+        ret
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuePushOutputResponse {
+    pub key: String,
+}
+
+impl Display for QueuePushOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key: {}", self.key)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuePullOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+    pub max_count: u32,
+}
+
+impl Display for QueuePullOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {}, max_count: {}",
+            self.common, self.name, self.max_count
+        )
+    }
+}
+
+impl QueuePullOutputRequest {
+    pub fn new(name: impl Into<String>, max_count: u32) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+            max_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuePullOutputResponse {
+    pub list: Vec<QueueMessage>,
+}
+
+impl Display for QueuePullOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+// ack同时适用于正常消息和死信
+#[derive(Debug, Clone)]
+pub struct QueueAckOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+    pub keys: Vec<String>,
+}
+
+impl Display for QueueAckOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {}, keys: {:?}",
+            self.common, self.name, self.keys
+        )
+    }
+}
+
+impl QueueAckOutputRequest {
+    pub fn new(name: impl Into<String>, keys: Vec<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+            keys,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueAckOutputResponse {
+    pub acked: u32,
+}
+
+impl Display for QueueAckOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "acked: {}", self.acked)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueueGetDeadLettersOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+}
+
+impl Display for QueueGetDeadLettersOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, name: {}", self.common, self.name)
+    }
+}
+
+impl QueueGetDeadLettersOutputRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+        }
+    }
+}
+
+pub type QueueGetDeadLettersOutputResponse = QueuePullOutputResponse;
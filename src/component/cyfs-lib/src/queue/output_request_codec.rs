@@ -0,0 +1,115 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<QueueCreateOutputRequest> for QueueCreateOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_number_field(&mut obj, "max_attempts", self.config.max_attempts);
+        JsonCodecHelper::encode_number_field(&mut obj, "lease_secs", self.config.lease_secs);
+        JsonCodecHelper::encode_bool_field(&mut obj, "allow_remote", self.config.allow_remote);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<QueueCreateOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            config: QueueConfig {
+                max_attempts: JsonCodecHelper::decode_int_field(obj, "max_attempts")?,
+                lease_secs: JsonCodecHelper::decode_int_field(obj, "lease_secs")?,
+                allow_remote: JsonCodecHelper::decode_bool_field(obj, "allow_remote")?,
+            },
+        })
+    }
+}
+
+impl JsonCodec<QueuePushOutputRequest> for QueuePushOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "zone", self.zone.as_ref());
+        JsonCodecHelper::encode_string_field(&mut obj, "dec_id", &self.dec_id);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_string_field(&mut obj, "object_id", &self.object_id);
+        JsonCodecHelper::encode_string_field(
+            &mut obj,
+            "object_raw",
+            &hex::encode(&self.object_raw),
+        );
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<QueuePushOutputRequest> {
+        let object_raw: String = JsonCodecHelper::decode_string_field(obj, "object_raw")?;
+        let object_raw = hex::decode(&object_raw).map_err(|e| {
+            let msg = format!("invalid queue message object_raw hex! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            zone: JsonCodecHelper::decode_option_string_field(obj, "zone")?,
+            dec_id: JsonCodecHelper::decode_string_field(obj, "dec_id")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            object_id: JsonCodecHelper::decode_string_field(obj, "object_id")?,
+            object_raw,
+        })
+    }
+}
+
+impl JsonCodec<QueuePullOutputRequest> for QueuePullOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_number_field(&mut obj, "max_count", self.max_count);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<QueuePullOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            max_count: JsonCodecHelper::decode_int_field(obj, "max_count")?,
+        })
+    }
+}
+
+impl JsonCodec<QueueAckOutputRequest> for QueueAckOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_str_array_field(&mut obj, "keys", &self.keys);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<QueueAckOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            keys: JsonCodecHelper::decode_str_array_field(obj, "keys")?,
+        })
+    }
+}
+
+impl JsonCodec<QueueGetDeadLettersOutputRequest> for QueueGetDeadLettersOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<QueueGetDeadLettersOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
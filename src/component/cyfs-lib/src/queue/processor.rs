@@ -0,0 +1,27 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait QueueOutputProcessor: Sync + Send + 'static {
+    async fn create_queue(
+        &self,
+        req: QueueCreateOutputRequest,
+    ) -> BuckyResult<QueueCreateOutputResponse>;
+
+    async fn push_queue(&self, req: QueuePushOutputRequest)
+        -> BuckyResult<QueuePushOutputResponse>;
+
+    async fn pull_queue(&self, req: QueuePullOutputRequest)
+        -> BuckyResult<QueuePullOutputResponse>;
+
+    async fn ack_queue(&self, req: QueueAckOutputRequest) -> BuckyResult<QueueAckOutputResponse>;
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersOutputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersOutputResponse>;
+}
+
+pub type QueueOutputProcessorRef = Arc<dyn QueueOutputProcessor>;
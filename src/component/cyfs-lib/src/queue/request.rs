@@ -0,0 +1,16 @@
+use super::output_request::*;
+
+pub type QueueCreateRequest = QueueCreateOutputRequest;
+pub type QueueCreateResponse = QueueCreateOutputResponse;
+
+pub type QueuePushRequest = QueuePushOutputRequest;
+pub type QueuePushResponse = QueuePushOutputResponse;
+
+pub type QueuePullRequest = QueuePullOutputRequest;
+pub type QueuePullResponse = QueuePullOutputResponse;
+
+pub type QueueAckRequest = QueueAckOutputRequest;
+pub type QueueAckResponse = QueueAckOutputResponse;
+
+pub type QueueGetDeadLettersRequest = QueueGetDeadLettersOutputRequest;
+pub type QueueGetDeadLettersResponse = QueueGetDeadLettersOutputResponse;
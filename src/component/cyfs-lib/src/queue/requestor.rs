@@ -0,0 +1,222 @@
+use super::output_request::*;
+use super::processor::*;
+use super::request::*;
+use crate::{base::*, requestor::*, SharedObjectStackDecID, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use http_types::{Method, Request, Url};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct QueueRequestor {
+    dec_id: Option<SharedObjectStackDecID>,
+    requestor: HttpRequestorRef,
+    service_url: Url,
+}
+
+impl QueueRequestor {
+    pub fn new(dec_id: Option<SharedObjectStackDecID>, requestor: HttpRequestorRef) -> Self {
+        let addr = requestor.remote_addr();
+
+        let url = format!("http://{}/queue/", addr);
+        let url = Url::parse(&url).unwrap();
+
+        Self {
+            dec_id,
+            requestor,
+            service_url: url,
+        }
+    }
+
+    pub fn into_processor(self) -> QueueOutputProcessorRef {
+        Arc::new(self)
+    }
+
+    pub fn clone_processor(&self) -> QueueOutputProcessorRef {
+        self.clone().into_processor()
+    }
+
+    fn encode_common_headers(&self, com_req: &UtilOutputRequestCommon, http_req: &mut Request) {
+        if let Some(dec_id) = &com_req.dec_id {
+            http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+        } else if let Some(dec_id) = &self.dec_id {
+            if let Some(dec_id) = dec_id.get() {
+                http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+            }
+        }
+
+        RequestorHelper::encode_opt_header_with_encoding(
+            http_req,
+            cyfs_base::CYFS_REQ_PATH,
+            com_req.req_path.as_deref(),
+        );
+
+        if let Some(target) = &com_req.target {
+            http_req.insert_header(cyfs_base::CYFS_TARGET, target.to_string());
+        }
+
+        http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+    }
+
+    pub async fn create_queue(&self, req: QueueCreateRequest) -> BuckyResult<QueueCreateResponse> {
+        let url = self.service_url.join("create").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse create_queue resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("queue create_queue failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn push_queue(&self, req: QueuePushRequest) -> BuckyResult<QueuePushResponse> {
+        let url = self.service_url.join("push").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse push_queue resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("queue push_queue failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn pull_queue(&self, req: QueuePullRequest) -> BuckyResult<QueuePullResponse> {
+        let url = self.service_url.join("pull").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse pull_queue resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("queue pull_queue failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn ack_queue(&self, req: QueueAckRequest) -> BuckyResult<QueueAckResponse> {
+        let url = self.service_url.join("ack").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse ack_queue resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("queue ack_queue failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersRequest,
+    ) -> BuckyResult<QueueGetDeadLettersResponse> {
+        let url = self.service_url.join("dead_letters").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_queue_dead_letters resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "queue get_queue_dead_letters failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueOutputProcessor for QueueRequestor {
+    async fn create_queue(
+        &self,
+        req: QueueCreateOutputRequest,
+    ) -> BuckyResult<QueueCreateOutputResponse> {
+        Self::create_queue(self, req).await
+    }
+
+    async fn push_queue(
+        &self,
+        req: QueuePushOutputRequest,
+    ) -> BuckyResult<QueuePushOutputResponse> {
+        Self::push_queue(self, req).await
+    }
+
+    async fn pull_queue(
+        &self,
+        req: QueuePullOutputRequest,
+    ) -> BuckyResult<QueuePullOutputResponse> {
+        Self::pull_queue(self, req).await
+    }
+
+    async fn ack_queue(&self, req: QueueAckOutputRequest) -> BuckyResult<QueueAckOutputResponse> {
+        Self::ack_queue(self, req).await
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersOutputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersOutputResponse> {
+        Self::get_queue_dead_letters(self, req).await
+    }
+}
@@ -1,4 +1,5 @@
 use super::requestor::*;
+use super::sign::RequestSignHelper;
 use cyfs_base::*;
 use cyfs_bdt::*;
 
@@ -79,6 +80,14 @@ impl BdtHttpRequestor {
         &self.device_id
     }
 
+    // zone以owner区分，owner相同的设备属于同一个zone
+    fn is_same_zone(&self) -> bool {
+        match self.bdt_stack.local_const().owner() {
+            Some(owner) => self.device.read().unwrap().desc().owner().as_ref() == Some(owner),
+            None => false,
+        }
+    }
+
     async fn connect(&self, with_remote_desc: bool) -> BuckyResult<StreamGuard> {
         let begin = std::time::Instant::now();
 
@@ -174,7 +183,12 @@ impl HttpRequestor for BdtHttpRequestor {
         // bdt_stream.display_ref_count();
 
         let req = req.take().unwrap();
-        let req = self.add_default_headers(req);
+        let mut req = self.add_default_headers(req);
+
+        // 跨zone的写请求附带签名，对端据此做防重放检查；同zone的请求对端不做检查，不需要签名
+        if RequestSignHelper::is_mutating(req.method()) && !self.is_same_zone() {
+            RequestSignHelper::sign(&mut req, self.bdt_stack.keystore().private_key()).await?;
+        }
 
         match async_h1::connect(bdt_stream, req).await {
             Ok(resp) => {
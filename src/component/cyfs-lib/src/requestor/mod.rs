@@ -1,11 +1,13 @@
 mod bdt;
 mod requestor;
+mod sign;
 mod tcp;
 mod ws;
 mod surf;
 
 pub use bdt::*;
 pub use requestor::*;
+pub use sign::*;
 pub use tcp::*;
 pub use ws::*;
 pub use self::surf::*;
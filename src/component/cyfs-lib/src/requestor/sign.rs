@@ -0,0 +1,189 @@
+use crate::base::RequestorHelper;
+use cyfs_base::*;
+
+use http_types::{Method, Request};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct RequestSignInfo {
+    pub nonce: String,
+    pub timestamp: u64,
+
+    // 签名时body的hash，接收方读取完body之后需要校验
+    pub body_hash: HashValue,
+}
+
+// 跨zone请求的签名，用于对端做防重放检查
+// 签名内容为method+path+query+nonce+timestamp+body_hash
+pub struct RequestSignHelper;
+
+impl RequestSignHelper {
+    // 除了读取类的请求，都视为会修改对端状态
+    pub fn is_mutating(method: Method) -> bool {
+        match method {
+            Method::Get | Method::Head | Method::Options => false,
+            _ => true,
+        }
+    }
+
+    fn sign_data(req: &Request, nonce: &str, timestamp: u64, body_hash: &HashValue) -> HashValue {
+        let url = req.url();
+        let data = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            req.method(),
+            url.path(),
+            url.query().unwrap_or(""),
+            nonce,
+            timestamp,
+            body_hash.to_hex_string(),
+        );
+
+        hash_data(data.as_bytes())
+    }
+
+    // 需要读取整个body计算hash，读取之后重新设置回请求
+    pub async fn sign(req: &mut Request, private_key: &PrivateKey) -> BuckyResult<RequestSignInfo> {
+        let body = req.take_body();
+        let mime = body.mime().clone();
+        let body = body.into_bytes().await.map_err(|e| {
+            let msg = format!(
+                "read request body for sign failed! url={}, {}",
+                req.url(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+        let body_hash = hash_data(&body);
+
+        let mut body = http_types::Body::from_bytes(body);
+        body.set_mime(mime);
+        req.set_body(body);
+
+        let nonce = format!("{:016x}", rand::random::<u64>());
+        let timestamp = bucky_time_now();
+
+        let hash = Self::sign_data(req, &nonce, timestamp, &body_hash);
+        let sign = private_key.sign(hash.as_slice(), SignatureSource::RefIndex(0))?;
+
+        req.insert_header(CYFS_REQUEST_NONCE, &nonce);
+        req.insert_header(CYFS_REQUEST_TIMESTAMP, timestamp.to_string());
+        req.insert_header(CYFS_REQUEST_BODY_HASH, body_hash.to_hex_string());
+        req.insert_header(CYFS_REQUEST_SIGN, sign.to_hex()?);
+
+        Ok(RequestSignInfo {
+            nonce,
+            timestamp,
+            body_hash,
+        })
+    }
+
+    // 请求没有携带签名返回None，签名不正确返回错误
+    // 这里只校验请求头，body是否和body_hash一致需要调用者在读取body时校验
+    pub fn verify(req: &Request, public_key: &PublicKey) -> BuckyResult<Option<RequestSignInfo>> {
+        let sign: Option<String> = RequestorHelper::decode_optional_header(req, CYFS_REQUEST_SIGN)?;
+        let sign = match sign {
+            Some(sign) => sign,
+            None => return Ok(None),
+        };
+
+        let nonce: String = RequestorHelper::decode_header(req, CYFS_REQUEST_NONCE)?;
+        let timestamp: u64 = RequestorHelper::decode_header(req, CYFS_REQUEST_TIMESTAMP)?;
+        let body_hash: String = RequestorHelper::decode_header(req, CYFS_REQUEST_BODY_HASH)?;
+        let body_hash = HashValue::from_str(&body_hash).map_err(|e| {
+            let msg = format!("invalid request body hash! url={}, {}", req.url(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+        if nonce.is_empty() {
+            let msg = format!("request sign with empty nonce! url={}", req.url());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let mut buf = vec![];
+        let sign = Signature::clone_from_hex(&sign, &mut buf).map_err(|e| {
+            let msg = format!("invalid request sign! url={}, {}", req.url(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidSignature, msg)
+        })?;
+
+        let hash = Self::sign_data(req, &nonce, timestamp, &body_hash);
+        if !public_key.verify(hash.as_slice(), &sign) {
+            let msg = format!(
+                "verify request sign failed! url={}, nonce={}, timestamp={}",
+                req.url(),
+                nonce,
+                timestamp
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidSignature, msg));
+        }
+
+        Ok(Some(RequestSignInfo {
+            nonce,
+            timestamp,
+            body_hash,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_request_sign() {
+        let private_key = PrivateKey::generate_rsa(1024).unwrap();
+        let public_key = private_key.public();
+
+        let url = http_types::Url::parse("http://127.0.0.1/non/object?dec_id=test").unwrap();
+        let mut req = Request::new(Method::Put, url.clone());
+        req.set_body("object data");
+        assert!(RequestSignHelper::verify(&req, &public_key)
+            .unwrap()
+            .is_none());
+
+        let info =
+            async_std::task::block_on(RequestSignHelper::sign(&mut req, &private_key)).unwrap();
+        assert_eq!(info.body_hash, hash_data(b"object data"));
+
+        let ret = RequestSignHelper::verify(&req, &public_key)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ret.nonce, info.nonce);
+        assert_eq!(ret.timestamp, info.timestamp);
+        assert_eq!(ret.body_hash, info.body_hash);
+
+        // 签名之后body保持不变
+        let body = async_std::task::block_on(req.body_string()).unwrap();
+        assert_eq!(body, "object data");
+
+        // 签名不能挪到其它请求上使用
+        let mut other = Request::new(Method::Delete, url.clone());
+        for name in [
+            CYFS_REQUEST_NONCE,
+            CYFS_REQUEST_TIMESTAMP,
+            CYFS_REQUEST_BODY_HASH,
+            CYFS_REQUEST_SIGN,
+        ] {
+            other.insert_header(name, req.header(name).unwrap().last().as_str());
+        }
+        assert!(RequestSignHelper::verify(&other, &public_key).is_err());
+
+        // 替换body_hash之后签名校验失败
+        let mut other = Request::new(Method::Put, url);
+        for name in [
+            CYFS_REQUEST_NONCE,
+            CYFS_REQUEST_TIMESTAMP,
+            CYFS_REQUEST_SIGN,
+        ] {
+            other.insert_header(name, req.header(name).unwrap().last().as_str());
+        }
+        other.insert_header(
+            CYFS_REQUEST_BODY_HASH,
+            hash_data(b"other data").to_hex_string(),
+        );
+        assert!(RequestSignHelper::verify(&other, &public_key).is_err());
+    }
+}
@@ -160,6 +160,150 @@ impl GlobalStatePathGroupAccess {
     }
 }
 
+const MINUTES_PER_DAY: u16 = 24 * 60;
+const MICROSECONDS_PER_MINUTE: u64 = 60 * 1000 * 1000;
+
+// Recurring time window in one day, such as 09:00-18:00 on workdays
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GlobalStateAccessSchedule {
+    // bit0 for Monday ... bit6 for Sunday, 0 for every day
+    pub weekdays: u8,
+
+    // [begin, end) in minutes of the day, begin > end means the window crosses midnight
+    pub begin_minute: u16,
+    pub end_minute: u16,
+
+    // the timezone of the schedule in minutes, eg. 480 for UTC+8
+    pub utc_offset_minutes: i16,
+}
+
+impl GlobalStateAccessSchedule {
+    pub fn check_valid(&self) -> bool {
+        self.begin_minute < MINUTES_PER_DAY
+            && self.end_minute < MINUTES_PER_DAY
+            && self.begin_minute != self.end_minute
+            && self.weekdays < 0x80
+    }
+
+    // now: bucky time
+    pub fn is_active(&self, now: u64) -> bool {
+        let minutes = (bucky_time_to_unix_time(now) / MICROSECONDS_PER_MINUTE) as i64
+            + self.utc_offset_minutes as i64;
+        let minutes = minutes.max(0) as u64;
+
+        let minute = (minutes % MINUTES_PER_DAY as u64) as u16;
+        let days = minutes / MINUTES_PER_DAY as u64;
+
+        // a window crossing midnight belongs to the day it begins
+        let (in_window, days) = if self.begin_minute < self.end_minute {
+            (
+                minute >= self.begin_minute && minute < self.end_minute,
+                days,
+            )
+        } else if minute >= self.begin_minute {
+            (true, days)
+        } else {
+            (minute < self.end_minute, days.saturating_sub(1))
+        };
+
+        if !in_window {
+            return false;
+        }
+
+        if self.weekdays == 0 {
+            return true;
+        }
+
+        // 1970-01-01 is Thursday
+        let weekday = (days + 3) % 7;
+        self.weekdays & (1 << weekday) != 0
+    }
+}
+
+impl std::fmt::Display for GlobalStateAccessSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "weekdays={:#09b}, {:02}:{:02}-{:02}:{:02}, utc_offset={}",
+            self.weekdays,
+            self.begin_minute / 60,
+            self.begin_minute % 60,
+            self.end_minute / 60,
+            self.end_minute % 60,
+            self.utc_offset_minutes,
+        )
+    }
+}
+
+// The validity of an access item, the item is ignored when not active,
+// and will be removed after the end time
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GlobalStateAccessValidity {
+    // bucky time, None for unbounded
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+
+    pub schedule: Option<GlobalStateAccessSchedule>,
+}
+
+impl GlobalStateAccessValidity {
+    pub fn new_expire(end: u64) -> Self {
+        Self {
+            start: None,
+            end: Some(end),
+            schedule: None,
+        }
+    }
+
+    pub fn check_valid(&self) -> bool {
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if start >= end {
+                return false;
+            }
+        }
+
+        match &self.schedule {
+            Some(schedule) => schedule.check_valid(),
+            None => true,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        match self.end {
+            Some(end) => now >= end,
+            None => false,
+        }
+    }
+
+    pub fn is_active(&self, now: u64) -> bool {
+        if let Some(start) = self.start {
+            if now < start {
+                return false;
+            }
+        }
+
+        if self.is_expired(now) {
+            return false;
+        }
+
+        match &self.schedule {
+            Some(schedule) => schedule.is_active(now),
+            None => true,
+        }
+    }
+}
+
+impl std::fmt::Display for GlobalStateAccessValidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "start={:?}, end={:?}", self.start, self.end)?;
+        if let Some(schedule) = &self.schedule {
+            write!(f, ", schedule=({})", schedule)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct GlobalStatePathAccessItem {
     // GlobalState path, must end with /
@@ -167,19 +311,44 @@ pub struct GlobalStatePathAccessItem {
 
     // Access value
     pub access: GlobalStatePathGroupAccess,
+
+    // Valid time window, None for always
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validity: Option<GlobalStateAccessValidity>,
 }
 
 impl GlobalStatePathAccessItem {
     pub fn check_valid(&self) -> bool {
+        if let Some(validity) = &self.validity {
+            if !validity.check_valid() {
+                return false;
+            }
+        }
+
         self.access.check_valid()
     }
 
+    pub fn is_active(&self, now: u64) -> bool {
+        match &self.validity {
+            Some(validity) => validity.is_active(now),
+            None => true,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        match &self.validity {
+            Some(validity) => validity.is_expired(now),
+            None => false,
+        }
+    }
+
     pub fn new(path: &str, access: u32) -> Self {
         let path = GlobalStatePathHelper::fix_path(path).to_string();
 
         Self {
             path,
             access: GlobalStatePathGroupAccess::Default(access),
+            validity: None,
         }
     }
 
@@ -202,9 +371,15 @@ impl GlobalStatePathAccessItem {
                 dec,
                 access,
             }),
+            validity: None,
         }
     }
 
+    pub fn with_validity(mut self, validity: GlobalStateAccessValidity) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
     pub fn try_fix_path(&mut self) {
         self.path = GlobalStatePathHelper::fix_path(&self.path).to_string();
     }
@@ -212,7 +387,10 @@ impl GlobalStatePathAccessItem {
 
 impl std::fmt::Display for GlobalStatePathAccessItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.path, self.access)
+        match &self.validity {
+            Some(validity) => write!(f, "({}, {}, {})", self.path, self.access, validity),
+            None => write!(f, "({}, {})", self.path, self.access),
+        }
     }
 }
 
@@ -299,4 +477,61 @@ mod test {
         let s = serde_json::to_string(&t).unwrap();
         print!("{}", s);
     }
+
+    #[test]
+    fn test_validity() {
+        // 2023-01-02 10:00:00 UTC, Monday
+        let monday = unix_time_to_bucky_time(1672653600 * 1000 * 1000);
+        let hour = 3600 * 1000 * 1000;
+
+        let workday = GlobalStateAccessSchedule {
+            weekdays: 0b0011111,
+            begin_minute: 9 * 60,
+            end_minute: 18 * 60,
+            utc_offset_minutes: 0,
+        };
+        assert!(workday.check_valid());
+        assert!(workday.is_active(monday));
+        assert!(!workday.is_active(monday + 9 * hour));
+        assert!(!workday.is_active(monday + 5 * 24 * hour));
+
+        // 10:00 UTC is 18:00 in UTC+8
+        let mut utc8 = workday.clone();
+        utc8.utc_offset_minutes = 480;
+        assert!(!utc8.is_active(monday));
+        assert!(utc8.is_active(monday - hour));
+
+        // 22:00-06:00 on Monday night
+        let night = GlobalStateAccessSchedule {
+            weekdays: 0b0000001,
+            begin_minute: 22 * 60,
+            end_minute: 6 * 60,
+            utc_offset_minutes: 0,
+        };
+        assert!(!night.is_active(monday));
+        assert!(night.is_active(monday + 13 * hour));
+        assert!(night.is_active(monday + 19 * hour));
+        assert!(!night.is_active(monday + 21 * hour));
+        assert!(!night.is_active(monday - 5 * hour));
+        assert!(!night.is_active(monday - 13 * hour));
+
+        let validity = GlobalStateAccessValidity {
+            start: Some(monday),
+            end: Some(monday + hour),
+            schedule: None,
+        };
+        assert!(validity.check_valid());
+        assert!(!validity.is_active(monday - 1));
+        assert!(validity.is_active(monday));
+        assert!(validity.is_expired(monday + hour));
+
+        let item = GlobalStatePathAccessItem::new("/a/b", 0).with_validity(validity);
+        let s = serde_json::to_string(&item).unwrap();
+        let item2: GlobalStatePathAccessItem = serde_json::from_str(&s).unwrap();
+        assert_eq!(item, item2);
+
+        let item: GlobalStatePathAccessItem =
+            serde_json::from_str(r#"{"path":"/a/b/","access":{"Default":0}}"#).unwrap();
+        assert!(item.validity.is_none());
+    }
 }
@@ -60,6 +60,14 @@ pub struct GlobalStatePathConfigItem {
 
     // 重建深度.0表示无引用深度，1表示会重建其引用的1层对象。不配置则根据对象的Selector确定初始重建深度。对大文件不自动重建，需要手动将depth设置为1.
     pub depth: Option<u8>,
+
+    // 从其它zone获取对象时，是否需要校验对象的owner签名，不配置则不校验
+    #[serde(default)]
+    pub verify_sign: Option<bool>,
+
+    // 其它zone的请求被拒绝时，是否记录为待审批的访问请求，不配置则不记录
+    #[serde(default)]
+    pub pending_access: Option<bool>,
 }
 
 impl GlobalStatePathConfigItem {
@@ -71,4 +79,6 @@ impl GlobalStatePathConfigItem {
 pub struct GlobalStatePathConfigItemValue {
     pub storage_state: Option<GlobalStatePathStorageState>,
     pub depth: Option<u8>,
+    pub verify_sign: Option<bool>,
+    pub pending_access: Option<bool>,
 }
\ No newline at end of file
@@ -20,6 +20,14 @@ pub enum MetaAction {
     GlobalStateAddPathConfig,
     GlobalStateRemovePathConfig,
     GlobalStateClearPathConfig,
+
+    GlobalStateAddEgress,
+    GlobalStateRemoveEgress,
+    GlobalStateClearEgress,
+
+    GlobalStateListPendingAccess,
+    GlobalStateApprovePendingAccess,
+    GlobalStateRejectPendingAccess,
 }
 
 impl ToString for MetaAction {
@@ -40,6 +48,14 @@ impl ToString for MetaAction {
             Self::GlobalStateAddPathConfig => "global-state-add-path-config",
             Self::GlobalStateRemovePathConfig => "global-state-remove-path-config",
             Self::GlobalStateClearPathConfig => "global-state-clear-path-config",
+
+            Self::GlobalStateAddEgress => "global-state-add-egress",
+            Self::GlobalStateRemoveEgress => "global-state-remove-egress",
+            Self::GlobalStateClearEgress => "global-state-clear-egress",
+
+            Self::GlobalStateListPendingAccess => "global-state-list-pending-access",
+            Self::GlobalStateApprovePendingAccess => "global-state-approve-pending-access",
+            Self::GlobalStateRejectPendingAccess => "global-state-reject-pending-access",
         })
         .to_owned()
     }
@@ -65,6 +81,14 @@ impl FromStr for MetaAction {
             "global-state-add-path-config" => Self::GlobalStateAddPathConfig,
             "global-state-remove-path-config" => Self::GlobalStateRemovePathConfig,
             "global-state-clear-path-config" => Self::GlobalStateClearPathConfig,
+
+            "global-state-add-egress" => Self::GlobalStateAddEgress,
+            "global-state-remove-egress" => Self::GlobalStateRemoveEgress,
+            "global-state-clear-egress" => Self::GlobalStateClearEgress,
+
+            "global-state-list-pending-access" => Self::GlobalStateListPendingAccess,
+            "global-state-approve-pending-access" => Self::GlobalStateApprovePendingAccess,
+            "global-state-reject-pending-access" => Self::GlobalStateRejectPendingAccess,
             
             v @ _ => {
                 let msg = format!("unknown meta action: {}", v);
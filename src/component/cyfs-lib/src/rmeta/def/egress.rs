@@ -0,0 +1,28 @@
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+
+// dec的出站请求允许到达的目标，可以是zone(people/group)或者具体的device
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GlobalStateEgressItem {
+    pub target: ObjectId,
+}
+
+impl GlobalStateEgressItem {
+    pub fn new(target: ObjectId) -> Self {
+        Self { target }
+    }
+
+    pub fn check_valid(&self) -> bool {
+        match self.target.obj_type_code() {
+            ObjectTypeCode::Device | ObjectTypeCode::People | ObjectTypeCode::Group => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for GlobalStateEgressItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "target={}", self.target)
+    }
+}
@@ -5,6 +5,8 @@ mod object;
 mod config;
 mod path;
 mod handler;
+mod egress;
+mod pending;
 
 pub use access::*;
 pub use link::*;
@@ -12,4 +14,6 @@ pub use def::*;
 pub use object::*;
 pub use config::*;
 pub use path::*;
-pub use handler::*;
\ No newline at end of file
+pub use handler::*;
+pub use egress::*;
+pub use pending::*;
\ No newline at end of file
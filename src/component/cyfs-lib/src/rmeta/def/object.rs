@@ -14,11 +14,21 @@ pub struct GlobalStateObjectMetaItem {
 
     // Object referer's depth, default is 1
     pub depth: Option<u8>,
+
+    // Keep the previous N versions of the matched mutable objects in noc, none or 0 means no history
+    pub versions: Option<u32>,
+
+    // Encrypt the matched objects' blobs at rest in noc, requires the stack started with a storage key
+    pub encrypt: Option<bool>,
 }
 
 impl std::fmt::Display for GlobalStateObjectMetaItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {}, {:?})", self.selector, self.access, self.depth)
+        write!(
+            f,
+            "({}, {}, {:?}, {:?}, {:?})",
+            self.selector, self.access, self.depth, self.versions, self.encrypt
+        )
     }
 }
 
@@ -28,6 +38,12 @@ pub struct GlobalStateObjectMetaConfigItemValue {
 
     // Object referer's depth, default is 1
     pub depth: Option<u8>,
+
+    // Object history versions to keep
+    pub versions: Option<u32>,
+
+    // Encrypt object blob at rest
+    pub encrypt: Option<bool>,
 }
 
 pub trait ObjectSelectorDataProvider: Send + Sync {
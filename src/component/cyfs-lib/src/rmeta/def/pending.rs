@@ -0,0 +1,53 @@
+use super::access::*;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+
+// 其它zone的请求被rmeta拒绝后记录的待审批访问请求，zone owner可以通过管理dec批准或者拒绝
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GlobalStatePendingAccessItem {
+    pub id: u64,
+
+    // 被拒绝的请求路径，批准后以此路径添加访问规则
+    pub path: String,
+
+    // 请求来源的zone，没有zone信息时使用来源device
+    pub source_zone: ObjectId,
+    pub source_dec: ObjectId,
+
+    pub access: u8, /*AccessPermissions*/
+
+    pub create_time: u64,
+    pub update_time: u64,
+
+    // 同一来源对同一路径被拒绝的次数
+    pub count: u32,
+}
+
+impl GlobalStatePendingAccessItem {
+    // 批准后添加的访问规则，只对请求来源的zone和dec生效
+    pub fn to_access_item(&self) -> GlobalStatePathAccessItem {
+        GlobalStatePathAccessItem::new_group(
+            &self.path,
+            Some(self.source_zone.clone()),
+            None,
+            Some(self.source_dec.clone()),
+            self.access,
+        )
+    }
+}
+
+impl std::fmt::Display for GlobalStatePendingAccessItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "id={}, path={}, source_zone={}, source_dec={}, access={}, count={}",
+            self.id,
+            self.path,
+            self.source_zone,
+            cyfs_core::dec_id_to_string(&self.source_dec),
+            AccessPermissions::format_u8(self.access),
+            self.count,
+        )
+    }
+}
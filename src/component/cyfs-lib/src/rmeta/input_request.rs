@@ -119,4 +119,50 @@ pub struct GlobalStateMetaClearPathConfigInputRequest {
     pub common: MetaInputRequestCommon,
 }
 
-pub type GlobalStateMetaClearPathConfigInputResponse = GlobalStateMetaClearPathConfigOutputResponse;
\ No newline at end of file
+pub type GlobalStateMetaClearPathConfigInputResponse = GlobalStateMetaClearPathConfigOutputResponse;
+
+// egress
+#[derive(Clone, Debug)]
+pub struct GlobalStateMetaAddEgressInputRequest {
+    pub common: MetaInputRequestCommon,
+
+    pub item: GlobalStateEgressItem,
+}
+
+pub type GlobalStateMetaAddEgressInputResponse = GlobalStateMetaAddEgressOutputResponse;
+
+pub type GlobalStateMetaRemoveEgressInputRequest = GlobalStateMetaAddEgressInputRequest;
+pub type GlobalStateMetaRemoveEgressInputResponse = GlobalStateMetaRemoveEgressOutputResponse;
+
+#[derive(Clone, Debug)]
+pub struct GlobalStateMetaClearEgressInputRequest {
+    pub common: MetaInputRequestCommon,
+}
+
+pub type GlobalStateMetaClearEgressInputResponse = GlobalStateMetaClearEgressOutputResponse;
+
+// pending access
+#[derive(Clone, Debug)]
+pub struct GlobalStateMetaListPendingAccessInputRequest {
+    pub common: MetaInputRequestCommon,
+
+    pub page: Option<PageRequest>,
+}
+
+pub type GlobalStateMetaListPendingAccessInputResponse =
+    GlobalStateMetaListPendingAccessOutputResponse;
+
+#[derive(Clone, Debug)]
+pub struct GlobalStateMetaApprovePendingAccessInputRequest {
+    pub common: MetaInputRequestCommon,
+
+    pub id: u64,
+}
+
+pub type GlobalStateMetaApprovePendingAccessInputResponse =
+    GlobalStateMetaApprovePendingAccessOutputResponse;
+
+pub type GlobalStateMetaRejectPendingAccessInputRequest =
+    GlobalStateMetaApprovePendingAccessInputRequest;
+pub type GlobalStateMetaRejectPendingAccessInputResponse =
+    GlobalStateMetaRejectPendingAccessOutputResponse;
@@ -1,5 +1,6 @@
 
 use super::def::*;
+use crate::base::*;
 use cyfs_base::*;
 
 use serde::{Deserialize, Serialize};
@@ -172,4 +173,70 @@ pub struct GlobalStateMetaClearPathConfigOutputRequest {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GlobalStateMetaClearPathConfigOutputResponse {
     pub count: u32,
-}
\ No newline at end of file
+}
+
+// egress
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaAddEgressOutputRequest {
+    pub common: MetaOutputRequestCommon,
+
+    pub item: GlobalStateEgressItem,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaAddEgressOutputResponse {
+    pub updated: bool,
+}
+
+pub type GlobalStateMetaRemoveEgressOutputRequest = GlobalStateMetaAddEgressOutputRequest;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaRemoveEgressOutputResponse {
+    pub item: Option<GlobalStateEgressItem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaClearEgressOutputRequest {
+    pub common: MetaOutputRequestCommon,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaClearEgressOutputResponse {
+    pub count: u32,
+}
+
+// pending access
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaListPendingAccessOutputRequest {
+    pub common: MetaOutputRequestCommon,
+
+    // return all items if not specified
+    #[serde(default)]
+    pub page: Option<PageRequest>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaListPendingAccessOutputResponse {
+    pub list: Vec<GlobalStatePendingAccessItem>,
+
+    #[serde(default)]
+    pub page: PageInfo,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaApprovePendingAccessOutputRequest {
+    pub common: MetaOutputRequestCommon,
+
+    pub id: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateMetaApprovePendingAccessOutputResponse {
+    // 不存在时为None
+    pub item: Option<GlobalStatePendingAccessItem>,
+}
+
+pub type GlobalStateMetaRejectPendingAccessOutputRequest =
+    GlobalStateMetaApprovePendingAccessOutputRequest;
+pub type GlobalStateMetaRejectPendingAccessOutputResponse =
+    GlobalStateMetaApprovePendingAccessOutputResponse;
@@ -68,6 +68,38 @@ pub trait GlobalStateMetaOutputProcessor: Sync + Send + 'static {
         &self,
         req: GlobalStateMetaClearPathConfigOutputRequest,
     ) -> BuckyResult<GlobalStateMetaClearPathConfigOutputResponse>;
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressOutputResponse>;
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressOutputResponse>;
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressOutputResponse>;
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessOutputResponse>;
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessOutputResponse>;
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessOutputResponse>;
 }
 
 pub type GlobalStateMetaOutputProcessorRef = Arc<Box<dyn GlobalStateMetaOutputProcessor>>;
@@ -136,6 +168,19 @@ pub trait GlobalStateMetaRawProcessor: Send + Sync {
     async fn clear_path_config(&self) -> BuckyResult<usize>;
 
     async fn query_path_config(&self, path: &str) -> Option<GlobalStatePathConfigItemValue>;
+
+    // egress
+    async fn add_egress(&self, item: GlobalStateEgressItem) -> BuckyResult<bool>;
+
+    async fn remove_egress(
+        &self,
+        item: GlobalStateEgressItem,
+    ) -> BuckyResult<Option<GlobalStateEgressItem>>;
+
+    async fn clear_egress(&self) -> BuckyResult<usize>;
+
+    // check if the dec's output requests can reach the target zone or device, empty egress list means no limit
+    async fn check_egress(&self, target_owner: Option<&ObjectId>, target_device: &DeviceId) -> bool;
 }
 
 pub type GlobalStateMetaRawProcessorRef = Arc<Box<dyn GlobalStateMetaRawProcessor>>;
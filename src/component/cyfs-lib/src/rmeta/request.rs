@@ -37,3 +37,22 @@ pub type GlobalStateMetaRemovePathConfigResponse = GlobalStateMetaRemovePathConf
 
 pub type GlobalStateMetaClearPathConfigRequest = GlobalStateMetaClearPathConfigOutputRequest;
 pub type GlobalStateMetaClearPathConfigResponse = GlobalStateMetaClearPathConfigOutputResponse;
+
+
+pub type GlobalStateMetaAddEgressRequest = GlobalStateMetaAddEgressOutputRequest;
+pub type GlobalStateMetaAddEgressResponse = GlobalStateMetaAddEgressOutputResponse;
+
+pub type GlobalStateMetaRemoveEgressRequest = GlobalStateMetaRemoveEgressOutputRequest;
+pub type GlobalStateMetaRemoveEgressResponse = GlobalStateMetaRemoveEgressOutputResponse;
+
+pub type GlobalStateMetaClearEgressRequest = GlobalStateMetaClearEgressOutputRequest;
+pub type GlobalStateMetaClearEgressResponse = GlobalStateMetaClearEgressOutputResponse;
+
+pub type GlobalStateMetaListPendingAccessRequest = GlobalStateMetaListPendingAccessOutputRequest;
+pub type GlobalStateMetaListPendingAccessResponse = GlobalStateMetaListPendingAccessOutputResponse;
+
+pub type GlobalStateMetaApprovePendingAccessRequest = GlobalStateMetaApprovePendingAccessOutputRequest;
+pub type GlobalStateMetaApprovePendingAccessResponse = GlobalStateMetaApprovePendingAccessOutputResponse;
+
+pub type GlobalStateMetaRejectPendingAccessRequest = GlobalStateMetaRejectPendingAccessOutputRequest;
+pub type GlobalStateMetaRejectPendingAccessResponse = GlobalStateMetaRejectPendingAccessOutputResponse;
@@ -567,6 +567,237 @@ impl GlobalStateMetaRequestor {
             Err(e)
         }
     }
+
+    // global-state-meta add-egress
+    fn encode_add_egress_request(&self, req: &GlobalStateMetaAddEgressOutputRequest) -> Request {
+        let url = self.service_url.join("egress").unwrap();
+        let mut http_req = Request::new(Method::Put, url);
+        self.encode_common_headers(MetaAction::GlobalStateAddEgress, &req.common, &mut http_req);
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressOutputResponse> {
+        let http_req = self.encode_add_egress_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp = RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta add egress success: req={:?}, resp={:?}",
+                req, resp,
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("global state meta add egress error! req={:?}, {}", req, e);
+            Err(e)
+        }
+    }
+
+    // global-state-meta remove-egress
+    fn encode_remove_egress_request(
+        &self,
+        req: &GlobalStateMetaRemoveEgressOutputRequest,
+    ) -> Request {
+        let url = self.service_url.join("egress").unwrap();
+        let mut http_req = Request::new(Method::Delete, url);
+        self.encode_common_headers(
+            MetaAction::GlobalStateRemoveEgress,
+            &req.common,
+            &mut http_req,
+        );
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressOutputResponse> {
+        let http_req = self.encode_remove_egress_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp = RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta remove egress success: req={:?}, resp={:?}",
+                req, resp,
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("global state meta remove egress error! req={:?}, {}", req, e);
+            Err(e)
+        }
+    }
+
+    // global-state-meta clear-egress
+    fn encode_clear_egress_request(&self, req: &GlobalStateMetaClearEgressOutputRequest) -> Request {
+        let url = self.service_url.join("egresses").unwrap();
+        let mut http_req = Request::new(Method::Delete, url);
+        self.encode_common_headers(MetaAction::GlobalStateClearEgress, &req.common, &mut http_req);
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressOutputResponse> {
+        let http_req = self.encode_clear_egress_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp = RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta clear egresses success: req={:?}, resp={:?}",
+                req, resp,
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("global state meta clear egresses error! req={:?}, {}", req, e);
+            Err(e)
+        }
+    }
+
+    // global-state-meta list-pending-access
+    fn encode_list_pending_access_request(
+        &self,
+        req: &GlobalStateMetaListPendingAccessOutputRequest,
+    ) -> Request {
+        let url = self.service_url.join("pending-accesses").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(
+            MetaAction::GlobalStateListPendingAccess,
+            &req.common,
+            &mut http_req,
+        );
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessOutputResponse> {
+        let http_req = self.encode_list_pending_access_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp: GlobalStateMetaListPendingAccessOutputResponse =
+                RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta list pending access success: req={:?}, count={}",
+                req,
+                resp.list.len(),
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "global state meta list pending access error! req={:?}, {}",
+                req, e
+            );
+            Err(e)
+        }
+    }
+
+    // global-state-meta approve-pending-access
+    fn encode_approve_pending_access_request(
+        &self,
+        req: &GlobalStateMetaApprovePendingAccessOutputRequest,
+    ) -> Request {
+        let url = self.service_url.join("pending-access").unwrap();
+        let mut http_req = Request::new(Method::Put, url);
+        self.encode_common_headers(
+            MetaAction::GlobalStateApprovePendingAccess,
+            &req.common,
+            &mut http_req,
+        );
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessOutputResponse> {
+        let http_req = self.encode_approve_pending_access_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp = RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta approve pending access success: req={:?}, resp={:?}",
+                req, resp,
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "global state meta approve pending access error! req={:?}, {}",
+                req, e
+            );
+            Err(e)
+        }
+    }
+
+    // global-state-meta reject-pending-access
+    fn encode_reject_pending_access_request(
+        &self,
+        req: &GlobalStateMetaRejectPendingAccessOutputRequest,
+    ) -> Request {
+        let url = self.service_url.join("pending-access").unwrap();
+        let mut http_req = Request::new(Method::Delete, url);
+        self.encode_common_headers(
+            MetaAction::GlobalStateRejectPendingAccess,
+            &req.common,
+            &mut http_req,
+        );
+
+        let value = serde_json::to_string(&req).unwrap();
+        http_req.set_body(value);
+        http_req
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessOutputResponse> {
+        let http_req = self.encode_reject_pending_access_request(&req);
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp = RequestorHelper::decode_serde_json_body(&mut resp).await?;
+            info!(
+                "global state meta reject pending access success: req={:?}, resp={:?}",
+                req, resp,
+            );
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "global state meta reject pending access error! req={:?}, {}",
+                req, e
+            );
+            Err(e)
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -656,4 +887,48 @@ impl GlobalStateMetaOutputProcessor for GlobalStateMetaRequestor {
     ) -> BuckyResult<GlobalStateMetaClearPathConfigOutputResponse> {
         Self::clear_path_config(&self, req).await
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressOutputResponse> {
+        Self::add_egress(&self, req).await
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressOutputResponse> {
+        Self::remove_egress(&self, req).await
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressOutputResponse> {
+        Self::clear_egress(&self, req).await
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessOutputResponse> {
+        Self::list_pending_access(&self, req).await
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessOutputResponse> {
+        Self::approve_pending_access(&self, req).await
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessOutputResponse> {
+        Self::reject_pending_access(&self, req).await
+    }
 }
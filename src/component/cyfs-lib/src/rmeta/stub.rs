@@ -2,8 +2,10 @@ use super::def::*;
 use super::output_request::*;
 use super::processor::*;
 use super::request::*;
+use crate::base::*;
 use cyfs_base::*;
 
+#[derive(Clone)]
 pub struct GlobalStateMetaStub {
     target: Option<ObjectId>,
     target_dec_id: Option<ObjectId>,
@@ -219,4 +221,136 @@ impl GlobalStateMetaStub {
         let resp = self.processor.clear_path_config(req).await?;
         Ok(resp.count)
     }
+
+    // egress, only the system dec in current zone can config the egress list of other decs
+    pub async fn add_egress(&self, item: GlobalStateEgressItem) -> BuckyResult<bool> {
+        let req = GlobalStateMetaAddEgressRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            item,
+        };
+
+        let resp = self.processor.add_egress(req).await?;
+        Ok(resp.updated)
+    }
+
+    pub async fn remove_egress(
+        &self,
+        item: GlobalStateEgressItem,
+    ) -> BuckyResult<Option<GlobalStateEgressItem>> {
+        let req = GlobalStateMetaRemoveEgressRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            item,
+        };
+
+        let resp = self.processor.remove_egress(req).await?;
+        Ok(resp.item)
+    }
+
+    pub async fn clear_egress(&self) -> BuckyResult<u32> {
+        let req = GlobalStateMetaClearEgressRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+        };
+
+        let resp = self.processor.clear_egress(req).await?;
+        Ok(resp.count)
+    }
+
+    // pending access, the requests from other zones denied by rmeta, only the system dec in current zone can approve or reject them
+    pub async fn list_pending_access(&self) -> BuckyResult<Vec<GlobalStatePendingAccessItem>> {
+        let req = GlobalStateMetaListPendingAccessRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            page: None,
+        };
+
+        let resp = self.processor.list_pending_access(req).await?;
+        Ok(resp.list)
+    }
+
+    pub async fn list_pending_access_by_cursor(
+        &self,
+        page: PageRequest,
+    ) -> BuckyResult<(Vec<GlobalStatePendingAccessItem>, PageInfo)> {
+        let req = GlobalStateMetaListPendingAccessRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            page: Some(page),
+        };
+
+        let resp = self.processor.list_pending_access(req).await?;
+        Ok((resp.list, resp.page))
+    }
+
+    // iterate all pending access items page by page
+    pub fn list_pending_access_iter(
+        &self,
+        page_size: u32,
+    ) -> PageIterator<GlobalStatePendingAccessItem> {
+        let this = self.clone();
+        PageIterator::new(page_size, move |page| {
+            let this = this.clone();
+            Box::pin(async move { this.list_pending_access_by_cursor(page).await })
+                as PageFetchFuture<GlobalStatePendingAccessItem>
+        })
+    }
+
+    // approve the pending access and add a access item for the source zone and dec
+    pub async fn approve_pending_access(
+        &self,
+        id: u64,
+    ) -> BuckyResult<Option<GlobalStatePendingAccessItem>> {
+        let req = GlobalStateMetaApprovePendingAccessRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            id,
+        };
+
+        let resp = self.processor.approve_pending_access(req).await?;
+        Ok(resp.item)
+    }
+
+    pub async fn reject_pending_access(
+        &self,
+        id: u64,
+    ) -> BuckyResult<Option<GlobalStatePendingAccessItem>> {
+        let req = GlobalStateMetaRejectPendingAccessRequest {
+            common: MetaOutputRequestCommon {
+                dec_id: None,
+                target_dec_id: self.target_dec_id.clone(),
+                target: self.target.clone(),
+                flags: 0,
+            },
+            id,
+        };
+
+        let resp = self.processor.reject_pending_access(req).await?;
+        Ok(resp.item)
+    }
 }
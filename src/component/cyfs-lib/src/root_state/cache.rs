@@ -96,6 +96,7 @@ impl ObjectMapNOCCache for ObjectMapNOCCacheAdapter {
             context: None,
             last_access_rpath: None,
             access_string: access.map(|v| v.value()),
+            precondition: None,
         };
 
         self.noc.put_object(&req).await.map_err(|e| {
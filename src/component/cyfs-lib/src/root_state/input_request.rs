@@ -449,6 +449,9 @@ pub struct RootStateAccessorListInputRequest {
     // read elements by page
     pub page_index: Option<u32>,
     pub page_size: Option<u32>,
+
+    // read elements from the cursor returned by the previous page, will ignore page_index if specified
+    pub cursor: Option<PageCursor>,
 }
 
 impl fmt::Display for RootStateAccessorListInputRequest {
@@ -457,8 +460,8 @@ impl fmt::Display for RootStateAccessorListInputRequest {
 
         write!(
             f,
-            ", inner_path={}, page_index: {:?}, page_size: {:?}",
-            self.inner_path, self.page_index, self.page_size
+            ", inner_path={}, page_index: {:?}, page_size: {:?}, cursor: {:?}",
+            self.inner_path, self.page_index, self.page_size, self.cursor
         )
     }
 }
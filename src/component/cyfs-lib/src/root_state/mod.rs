@@ -7,6 +7,7 @@ mod request;
 mod requestor;
 mod stub;
 mod cache;
+mod sync_conflict;
 
 pub use def::*;
 pub use input_request::*;
@@ -16,3 +17,4 @@ pub use request::*;
 pub use requestor::*;
 pub use stub::*;
 pub use cache::*;
+pub use sync_conflict::*;
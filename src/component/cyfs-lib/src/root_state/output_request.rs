@@ -977,6 +977,9 @@ pub struct RootStateAccessorListOutputRequest {
     // read elements by page
     pub page_index: Option<u32>,
     pub page_size: Option<u32>,
+
+    // read elements from the cursor returned by the previous page, will ignore page_index if specified
+    pub cursor: Option<PageCursor>,
 }
 
 impl RootStateAccessorListOutputRequest {
@@ -986,6 +989,7 @@ impl RootStateAccessorListOutputRequest {
             inner_path: inner_path.into(),
             page_index: None,
             page_size: None,
+            cursor: None,
         }
     }
 
@@ -995,6 +999,17 @@ impl RootStateAccessorListOutputRequest {
             inner_path: inner_path.into(),
             page_index: Some(page_index),
             page_size: Some(page_size),
+            cursor: None,
+        }
+    }
+
+    pub fn new_with_cursor(inner_path: impl Into<String>, page: PageRequest) -> Self {
+        Self {
+            common: RootStateOutputRequestCommon::new(),
+            inner_path: inner_path.into(),
+            page_index: None,
+            page_size: page.page_size,
+            cursor: page.cursor,
         }
     }
 }
@@ -1005,8 +1020,8 @@ impl fmt::Display for RootStateAccessorListOutputRequest {
 
         write!(
             f,
-            ", inner_path={}, page_index: {:?}, page_size: {:?}",
-            self.inner_path, self.page_index, self.page_size
+            ", inner_path={}, page_index: {:?}, page_size: {:?}, cursor: {:?}",
+            self.inner_path, self.page_index, self.page_size, self.cursor
         )
     }
 }
@@ -1016,4 +1031,7 @@ pub struct RootStateAccessorListOutputResponse {
 
     pub root: ObjectId,
     pub revision: u64,
+
+    // next_cursor is none if it's the last page
+    pub page: PageInfo,
 }
@@ -1096,6 +1096,10 @@ impl GlobalStateAccessorRequestor {
             if let Some(page_size) = &req.page_size {
                 querys.append_pair("page_size", &page_size.to_string());
             }
+
+            if let Some(cursor) = &req.cursor {
+                querys.append_pair("cursor", cursor.as_str());
+            }
         }
 
         let mut http_req = Request::new(Method::Get, url);
@@ -1110,11 +1114,16 @@ impl GlobalStateAccessorRequestor {
         let list = RequestorHelper::decode_json_body(resp).await?;
         let root = RequestorHelper::decode_header(resp, cyfs_base::CYFS_ROOT)?;
         let revision = RequestorHelper::decode_header(resp, cyfs_base::CYFS_REVISION)?;
+        let page = PageInfo {
+            next_cursor: RequestorHelper::decode_optional_header(resp, cyfs_base::CYFS_NEXT_CURSOR)?,
+            total_hint: RequestorHelper::decode_optional_header(resp, cyfs_base::CYFS_TOTAL_HINT)?,
+        };
 
         Ok(RootStateAccessorListOutputResponse {
             list,
             root,
             revision,
+            page,
         })
     }
 
@@ -1,5 +1,6 @@
 use super::output_request::*;
 use super::processor::*;
+use crate::base::*;
 use crate::non::NONGetObjectOutputResponse;
 use cyfs_base::*;
 
@@ -632,6 +633,21 @@ impl PathOpEnvStub {
         Ok(resp.value)
     }
 
+    // 删除的值放入回收站，提交后在清理前可以通过util.restore_trash恢复
+    pub async fn remove_with_path_to_trash(
+        &self,
+        full_path: impl Into<String>,
+        prev_value: Option<ObjectId>,
+    ) -> BuckyResult<Option<ObjectId>> {
+        let mut req = OpEnvRemoveWithKeyOutputRequest::new_full_path(full_path, prev_value);
+        req.common.target = self.target.clone();
+        req.common.target_dec_id = self.target_dec_id.clone();
+        req.common.flags |= CYFS_REQUEST_FLAG_DELETE_TO_TRASH;
+
+        let resp = self.processor.remove_with_key(req).await?;
+        Ok(resp.value)
+    }
+
     // set methods
     pub async fn contains(
         &self,
@@ -1166,4 +1182,32 @@ impl GlobalStateAccessorStub {
         let resp = self.processor.list(req).await?;
         Ok(resp.list)
     }
+
+    pub async fn list_by_cursor(
+        &self,
+        path: impl Into<String>,
+        page: PageRequest,
+    ) -> BuckyResult<(Vec<ObjectMapContentItem>, PageInfo)> {
+        let mut req = RootStateAccessorListOutputRequest::new_with_cursor(path, page);
+        req.common.target = self.target.clone();
+        req.common.target_dec_id = self.target_dec_id.clone();
+
+        let resp = self.processor.list(req).await?;
+        Ok((resp.list, resp.page))
+    }
+
+    // iterate all the elements of the path, the next page will be fetched automatically
+    pub fn list_iter(
+        &self,
+        path: impl Into<String>,
+        page_size: u32,
+    ) -> PageIterator<ObjectMapContentItem> {
+        let stub = self.clone();
+        let path = path.into();
+        PageIterator::new(page_size, move |page| {
+            let stub = stub.clone();
+            let path = path.clone();
+            Box::pin(async move { stub.list_by_cursor(path, page).await })
+        })
+    }
 }
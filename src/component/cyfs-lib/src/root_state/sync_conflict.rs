@@ -0,0 +1,131 @@
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// dec通过dec_config设置的冲突处理策略的配置名，值为SyncConflictPolicyConfig的json
+pub const SYNC_CONFLICT_POLICY_CONFIG_NAME: &str = "sync_conflict_policy";
+
+// 设备和ood在两次同步之间修改了root_state的同一个路径时的处理策略
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncConflictPolicy {
+    // 保留设备上的修改，并写回ood
+    Ours,
+
+    // 使用ood上的值，和以前的行为一致
+    Theirs,
+
+    // 通过sync_conflict事件由dec合并，没有dec处理时保留ood上的值并记录为未解决
+    Merge,
+}
+
+// 路径前缀 -> 策略，按最长前缀匹配；路径是dec root_state内部的路径，比如/user/profile
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConflictPolicyConfig {
+    pub default: Option<SyncConflictPolicy>,
+    pub paths: BTreeMap<String, SyncConflictPolicy>,
+}
+
+impl SyncConflictPolicyConfig {
+    pub fn decode(value: &str) -> BuckyResult<Self> {
+        serde_json::from_str(value).map_err(|e| {
+            let msg = format!("invalid sync conflict policy config: {}, {}", value, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })
+    }
+
+    pub fn select(&self, path: &str) -> Option<SyncConflictPolicy> {
+        self.paths
+            .iter()
+            .filter(|(prefix, _)| Self::is_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, policy)| *policy)
+            .or(self.default)
+    }
+
+    // 按路径段匹配，/a是/a/b的前缀但不是/ab的前缀
+    fn is_prefix(prefix: &str, path: &str) -> bool {
+        let prefix = prefix.trim_end_matches('/');
+        if prefix.is_empty() {
+            return true;
+        }
+
+        match path.strip_prefix(prefix) {
+            Some(left) => left.is_empty() || left.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SyncConflictResolution {
+    Ours,
+    Theirs,
+
+    // dec合并后的新值
+    Merged(ObjectId),
+
+    // 没有策略也没有dec处理，暂时使用ood上的值，设备上的值保留在冲突记录里
+    Unresolved,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SyncConflictInfo {
+    pub id: String,
+
+    pub dec_id: ObjectId,
+    // dec root_state内部的路径
+    pub path: String,
+
+    // 上次同步时的值，以及设备和ood上各自修改后的值，None表示不存在或者被删除
+    pub base: Option<ObjectId>,
+    pub local: Option<ObjectId>,
+    pub remote: Option<ObjectId>,
+
+    pub policy: Option<SyncConflictPolicy>,
+    pub resolution: Option<SyncConflictResolution>,
+
+    // bucky time
+    pub detect_time: u64,
+}
+
+impl std::fmt::Display for SyncConflictInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "id={}, dec={}, path={}, base={:?}, local={:?}, remote={:?}, policy={:?}, resolution={:?}",
+            self.id,
+            self.dec_id,
+            self.path,
+            self.base,
+            self.local,
+            self.remote,
+            self.policy,
+            self.resolution
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_select_policy() {
+        let config = SyncConflictPolicyConfig::decode(
+            r#"{"default": "theirs", "paths": {"/a": "ours", "/a/b/": "merge"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.select("/a"), Some(SyncConflictPolicy::Ours));
+        assert_eq!(config.select("/a/c"), Some(SyncConflictPolicy::Ours));
+        assert_eq!(config.select("/a/b/c"), Some(SyncConflictPolicy::Merge));
+        assert_eq!(config.select("/ab"), Some(SyncConflictPolicy::Theirs));
+
+        let config = SyncConflictPolicyConfig::decode(r#"{}"#).unwrap();
+        assert_eq!(config.select("/a"), None);
+    }
+}
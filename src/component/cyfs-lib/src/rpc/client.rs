@@ -0,0 +1,71 @@
+use super::def::*;
+use crate::non::*;
+use crate::stack::*;
+use cyfs_base::*;
+
+// 调用目标dec的rpc方法，target为空时调用本zone的ood，跨zone时指定对方的device或者zone
+#[derive(Clone)]
+pub struct DecRpcClient {
+    stack: SharedCyfsStack,
+    target: Option<ObjectId>,
+    target_dec_id: ObjectId,
+}
+
+impl DecRpcClient {
+    pub fn new(stack: SharedCyfsStack, target: Option<ObjectId>, target_dec_id: ObjectId) -> Self {
+        Self {
+            stack,
+            target,
+            target_dec_id,
+        }
+    }
+
+    pub fn target(&self) -> Option<&ObjectId> {
+        self.target.as_ref()
+    }
+
+    pub fn target_dec_id(&self) -> &ObjectId {
+        &self.target_dec_id
+    }
+
+    pub async fn call<M: DecRpcMethod>(&self, params: &M::Params) -> BuckyResult<M::Result> {
+        DecRpcCodec::check_method_name(M::NAME)?;
+
+        let text = DecRpcCodec::encode_call::<M>(&self.target_dec_id, params)?;
+        let mut req = NONPostObjectOutputRequest::new_router(
+            self.target.clone(),
+            text.desc().calculate_id(),
+            text.to_vec()?,
+        );
+        req.common.req_path = Some(DecRpcCodec::req_path(&self.target_dec_id, M::NAME).to_string());
+
+        let resp = self
+            .stack
+            .non_service()
+            .post_object(req)
+            .await
+            .map_err(|e| {
+                warn!(
+                    "dec rpc call failed! target={:?}, dec={}, method={}, {}",
+                    self.target,
+                    self.target_dec_id,
+                    M::NAME,
+                    e
+                );
+                e
+            })?;
+
+        let object = resp.object.ok_or_else(|| {
+            let msg = format!(
+                "dec rpc call got empty response! target={:?}, dec={}, method={}",
+                self.target,
+                self.target_dec_id,
+                M::NAME
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        DecRpcCodec::decode_result::<M>(&object)
+    }
+}
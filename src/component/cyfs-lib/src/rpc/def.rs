@@ -0,0 +1,214 @@
+use crate::non::*;
+use crate::prelude::*;
+use cyfs_base::*;
+use cyfs_core::*;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// rpc请求和应答都使用Text对象承载: id区分请求和应答，header为方法名，value为参数或者结果的json
+pub const DEC_RPC_CALL_TEXT_ID: &str = "dec_rpc_call";
+pub const DEC_RPC_RESULT_TEXT_ID: &str = "dec_rpc_result";
+
+// 一个rpc方法的定义，参数和结果通过serde编码，客户端和服务端共用同一个定义
+pub trait DecRpcMethod: Send + Sync + 'static {
+    // 方法名，同时作为acl的路径: /.cyfs/api/rpc/{NAME}
+    const NAME: &'static str;
+
+    type Params: Serialize + DeserializeOwned + Send + Sync;
+    type Result: Serialize + DeserializeOwned + Send + Sync;
+}
+
+pub struct DecRpcCodec;
+
+impl DecRpcCodec {
+    pub fn check_method_name(name: &str) -> BuckyResult<()> {
+        if name.is_empty() || name.contains('/') {
+            let msg = format!("invalid dec rpc method name: {:?}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    // 方法在目标dec下的路径，用于router handler的req_path和rmeta的访问权限
+    pub fn method_path(method: &str) -> String {
+        format!("{}/{}", CYFS_RPC_VIRTUAL_PATH, method)
+    }
+
+    pub fn req_path(dec_id: &ObjectId, method: &str) -> RequestGlobalStatePath {
+        RequestGlobalStatePath::new(Some(dec_id.to_owned()), Some(Self::method_path(method)))
+    }
+
+    pub fn encode_call<M: DecRpcMethod>(
+        dec_id: &ObjectId,
+        params: &M::Params,
+    ) -> BuckyResult<Text> {
+        let value = Self::encode_value(M::NAME, params)?;
+        Ok(Text::build(DEC_RPC_CALL_TEXT_ID, M::NAME, value)
+            .dec_id(dec_id.to_owned())
+            .build())
+    }
+
+    pub fn decode_call<M: DecRpcMethod>(object: &NONObjectInfo) -> BuckyResult<M::Params> {
+        let text = Self::decode_text(object, DEC_RPC_CALL_TEXT_ID)?;
+        if text.header() != M::NAME {
+            let msg = format!(
+                "dec rpc method unmatched! expect={}, got={}",
+                M::NAME,
+                text.header()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+        }
+
+        Self::decode_value(M::NAME, text.value())
+    }
+
+    pub fn encode_result<M: DecRpcMethod>(result: &M::Result) -> BuckyResult<Text> {
+        let value = Self::encode_value(M::NAME, result)?;
+        Ok(Text::build(DEC_RPC_RESULT_TEXT_ID, M::NAME, value).build())
+    }
+
+    pub fn decode_result<M: DecRpcMethod>(object: &NONObjectInfo) -> BuckyResult<M::Result> {
+        let text = Self::decode_text(object, DEC_RPC_RESULT_TEXT_ID)?;
+        Self::decode_value(M::NAME, text.value())
+    }
+
+    fn decode_text(object: &NONObjectInfo, id: &str) -> BuckyResult<Text> {
+        let text = Text::clone_from_slice(&object.object_raw).map_err(|e| {
+            let msg = format!(
+                "invalid dec rpc object buffer! id={}, {}",
+                object.object_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        if text.id() != id {
+            let msg = format!("invalid dec rpc text id! expect={}, got={}", id, text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+
+        Ok(text)
+    }
+
+    fn encode_value<T: Serialize>(method: &str, value: &T) -> BuckyResult<String> {
+        serde_json::to_string(value).map_err(|e| {
+            let msg = format!("encode dec rpc value failed! method={}, {}", method, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })
+    }
+
+    fn decode_value<T: DeserializeOwned>(method: &str, value: &str) -> BuckyResult<T> {
+        serde_json::from_str(value).map_err(|e| {
+            let msg = format!(
+                "decode dec rpc value failed! method={}, value={}, {}",
+                method, value, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })
+    }
+}
+
+// 声明rpc方法:
+// declare_dec_rpc_method!(AddMethod, "add", AddParams, AddResult);
+#[macro_export]
+macro_rules! declare_dec_rpc_method {
+    ($method:ident, $name:expr, $params:ty, $result:ty) => {
+        pub struct $method;
+
+        impl $crate::DecRpcMethod for $method {
+            const NAME: &'static str = $name;
+
+            type Params = $params;
+            type Result = $result;
+        }
+    };
+}
+
+// 生成带类型的客户端，每个方法对应一个async函数:
+// declare_dec_rpc_client!(CalcClient { add: AddMethod, sub: SubMethod });
+#[macro_export]
+macro_rules! declare_dec_rpc_client {
+    ($client:ident { $($func:ident: $method:ty),* $(,)? }) => {
+        #[derive(Clone)]
+        pub struct $client($crate::DecRpcClient);
+
+        impl $client {
+            pub fn new(client: $crate::DecRpcClient) -> Self {
+                Self(client)
+            }
+
+            pub fn client(&self) -> &$crate::DecRpcClient {
+                &self.0
+            }
+
+            $(
+                pub async fn $func(
+                    &self,
+                    params: &<$method as $crate::DecRpcMethod>::Params,
+                ) -> cyfs_base::BuckyResult<<$method as $crate::DecRpcMethod>::Result> {
+                    self.0.call::<$method>(params).await
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct AddParams {
+        a: i32,
+        b: i32,
+    }
+
+    crate::declare_dec_rpc_method!(AddMethod, "add", AddParams, i32);
+    crate::declare_dec_rpc_method!(SubMethod, "sub", AddParams, i32);
+
+    fn to_object_info(text: &Text) -> NONObjectInfo {
+        NONObjectInfo::new(text.desc().calculate_id(), text.to_vec().unwrap(), None)
+    }
+
+    #[test]
+    fn test_codec() {
+        let dec_id = ObjectId::default();
+        assert_eq!(
+            DecRpcCodec::method_path(AddMethod::NAME),
+            "/.cyfs/api/rpc/add"
+        );
+        assert!(DecRpcCodec::check_method_name("a/b").is_err());
+
+        let params = AddParams { a: 1, b: 2 };
+        let call = DecRpcCodec::encode_call::<AddMethod>(&dec_id, &params).unwrap();
+        let object = to_object_info(&call);
+        assert_eq!(
+            DecRpcCodec::decode_call::<AddMethod>(&object).unwrap(),
+            params
+        );
+
+        // 方法名不匹配
+        let e = DecRpcCodec::decode_call::<SubMethod>(&object).unwrap_err();
+        assert_eq!(e.code(), BuckyErrorCode::UnSupport);
+
+        // 请求不能当作应答解码
+        assert!(DecRpcCodec::decode_result::<AddMethod>(&object).is_err());
+
+        let result = DecRpcCodec::encode_result::<AddMethod>(&3).unwrap();
+        let object = to_object_info(&result);
+        assert_eq!(DecRpcCodec::decode_result::<AddMethod>(&object).unwrap(), 3);
+
+        // 参数类型不匹配
+        let text = Text::build(DEC_RPC_CALL_TEXT_ID, "add", "{\"a\":1}").build();
+        let e = DecRpcCodec::decode_call::<AddMethod>(&to_object_info(&text)).unwrap_err();
+        assert_eq!(e.code(), BuckyErrorCode::InvalidParam);
+    }
+}
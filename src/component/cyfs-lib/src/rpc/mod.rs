@@ -0,0 +1,7 @@
+mod client;
+mod def;
+mod server;
+
+pub use client::*;
+pub use def::*;
+pub use server::*;
@@ -0,0 +1,174 @@
+use super::def::*;
+use crate::base::*;
+use crate::non::*;
+use crate::rmeta::*;
+use crate::router_handler::*;
+use crate::stack::*;
+use cyfs_base::*;
+use cyfs_util::*;
+
+use std::marker::PhantomData;
+
+// rpc方法的服务端实现，source为经过acl校验后的请求来源
+#[async_trait::async_trait]
+pub trait DecRpcHandler<M: DecRpcMethod>: Send + Sync + 'static {
+    async fn call(&self, source: &RequestSourceInfo, params: M::Params) -> BuckyResult<M::Result>;
+}
+
+struct DecRpcRoutine<M, H> {
+    handler: H,
+    _phantom: PhantomData<M>,
+}
+
+impl<M, H> DecRpcRoutine<M, H>
+where
+    M: DecRpcMethod,
+    H: DecRpcHandler<M>,
+{
+    async fn process(&self, param: &RouterHandlerPostObjectRequest) -> BuckyResult<NONObjectInfo> {
+        let params = DecRpcCodec::decode_call::<M>(&param.request.object)?;
+        let result = self
+            .handler
+            .call(&param.request.common.source, params)
+            .await?;
+
+        let text = DecRpcCodec::encode_result::<M>(&result)?;
+        Ok(NONObjectInfo::new(
+            text.desc().calculate_id(),
+            text.to_vec()?,
+            None,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<M, H> EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPostObjectResult>
+    for DecRpcRoutine<M, H>
+where
+    M: DecRpcMethod,
+    H: DecRpcHandler<M>,
+{
+    async fn call(
+        &self,
+        param: &RouterHandlerPostObjectRequest,
+    ) -> BuckyResult<RouterHandlerPostObjectResult> {
+        debug!(
+            "recv dec rpc call: method={}, source={}",
+            M::NAME,
+            param.request.common.source
+        );
+
+        // 业务错误通过应答返回给调用方，不能让handler本身失败
+        let response = self.process(param).await.map_err(|e| {
+            warn!(
+                "process dec rpc call failed! method={}, source={}, {}",
+                M::NAME,
+                param.request.common.source,
+                e
+            );
+            e
+        });
+
+        Ok(RouterHandlerPostObjectResult {
+            action: RouterHandlerAction::Response,
+            request: None,
+            response: Some(response.map(|object| NONPostObjectInputResponse {
+                object: Some(object),
+            })),
+        })
+    }
+}
+
+// 在当前dec下注册rpc方法，请求通过post_object分发到/.cyfs/api/rpc/{method}，
+// 本地和跨zone的路由以及acl校验都复用协议栈现有的post_object流程
+#[derive(Clone)]
+pub struct DecRpcServer {
+    stack: SharedCyfsStack,
+}
+
+impl DecRpcServer {
+    pub fn new(stack: SharedCyfsStack) -> Self {
+        Self { stack }
+    }
+
+    fn dec_id(&self) -> BuckyResult<ObjectId> {
+        self.stack.dec_id().cloned().ok_or_else(|| {
+            let msg = format!("dec rpc server should bind to dec!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })
+    }
+
+    fn handler_id(method: &str) -> String {
+        format!("dec_rpc_{}", method)
+    }
+
+    pub async fn register<M, H>(&self, handler: H) -> BuckyResult<()>
+    where
+        M: DecRpcMethod,
+        H: DecRpcHandler<M>,
+    {
+        DecRpcCodec::check_method_name(M::NAME)?;
+        let dec_id = self.dec_id()?;
+
+        let routine = DecRpcRoutine::<M, H> {
+            handler,
+            _phantom: PhantomData,
+        };
+
+        let req_path = DecRpcCodec::req_path(&dec_id, M::NAME);
+        self.stack
+            .router_handlers()
+            .post_object()
+            .add_handler(
+                RouterHandlerChain::Handler,
+                &Self::handler_id(M::NAME),
+                0,
+                None,
+                Some(req_path.to_string()),
+                RouterHandlerAction::Default,
+                Some(Box::new(routine)),
+            )
+            .await
+            .map_err(|e| {
+                error!(
+                    "register dec rpc method failed! dec={}, method={}, {}",
+                    dec_id,
+                    M::NAME,
+                    e
+                );
+                e
+            })?;
+
+        info!(
+            "register dec rpc method success! dec={}, method={}",
+            dec_id,
+            M::NAME
+        );
+        Ok(())
+    }
+
+    pub async fn unregister<M: DecRpcMethod>(&self) -> BuckyResult<bool> {
+        self.stack
+            .router_handlers()
+            .post_object()
+            .remove_handler(RouterHandlerChain::Handler, &Self::handler_id(M::NAME))
+            .await
+    }
+
+    // 默认只有同zone的同一个dec可以调用，其它dec或者zone需要显式开放Call权限
+    pub async fn open_access<M: DecRpcMethod>(&self, access: AccessString) -> BuckyResult<()> {
+        let item = GlobalStatePathAccessItem {
+            path: DecRpcCodec::method_path(M::NAME),
+            access: GlobalStatePathGroupAccess::Default(access.value()),
+            validity: None,
+        };
+
+        self.stack
+            .root_state_meta_stub(None, None)
+            .add_access(item)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use cyfs_base::*;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::fmt;
+use std::str::FromStr;
+
+// 标准的5段cron表达式: 分 时 日 月 周，按本地时区计算
+// 每段支持 *、n、a-b、a,b 以及 */n、a-b/n、a/n，周日可以用0或者7
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CronExpr {
+    expr: String,
+
+    // 每段允许的取值，按位保存
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+
+    // 日和周都做了限定时，按cron的惯例满足任意一个即可
+    day_any: bool,
+    weekday_any: bool,
+}
+
+// 向后查找的最大范围，超过后认为表达式不会再触发(比如2月30日)
+const CRON_SEARCH_DAYS: i64 = 366 * 5;
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> BuckyResult<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(Self::invalid(expr, "should have 5 fields"));
+        }
+
+        let minutes = Self::parse_field(expr, fields[0], 0, 59)?;
+        let hours = Self::parse_field(expr, fields[1], 0, 23)?;
+        let days = Self::parse_field(expr, fields[2], 1, 31)?;
+        let months = Self::parse_field(expr, fields[3], 1, 12)?;
+        let mut weekdays = Self::parse_field(expr, fields[4], 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expr: fields.join(" "),
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+            day_any: fields[2] == "*",
+            weekday_any: fields[4] == "*",
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    fn invalid(expr: &str, reason: &str) -> BuckyError {
+        let msg = format!("invalid cron expr: {:?}, {}", expr, reason);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+    }
+
+    fn parse_num(expr: &str, s: &str) -> BuckyResult<u32> {
+        s.parse::<u32>()
+            .map_err(|_| Self::invalid(expr, &format!("invalid number {:?}", s)))
+    }
+
+    fn parse_field(expr: &str, field: &str, min: u32, max: u32) -> BuckyResult<u64> {
+        let mut bits = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Self::parse_num(expr, step)?),
+                None => (part, 1),
+            };
+            if step == 0 {
+                return Err(Self::invalid(expr, "step should not be zero"));
+            }
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                (Self::parse_num(expr, a)?, Self::parse_num(expr, b)?)
+            } else {
+                let v = Self::parse_num(expr, range)?;
+                // a/n表示从a开始到最大值
+                if part.contains('/') {
+                    (v, max)
+                } else {
+                    (v, v)
+                }
+            };
+
+            if start < min || end > max || start > end {
+                return Err(Self::invalid(
+                    expr,
+                    &format!("{:?} out of range {}-{}", part, min, max),
+                ));
+            }
+
+            let mut v = start;
+            while v <= end {
+                bits |= 1 << v;
+                v += step;
+            }
+        }
+
+        Ok(bits)
+    }
+
+    fn match_day(&self, date: &NaiveDate) -> bool {
+        let dom = self.days & (1 << date.day()) != 0;
+        let dow = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        match (self.day_any, self.weekday_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    fn next_naive(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = from;
+        let limit = from + Duration::days(CRON_SEARCH_DAYS);
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !self.match_day(&t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+
+            if self.minutes & (1 << t.minute()) == 0 {
+                t = t + Duration::minutes(1);
+                continue;
+            }
+
+            return Some(t);
+        }
+
+        None
+    }
+
+    // 返回严格晚于time的下一次触发时间(bucky time)，不会再触发时返回None
+    pub fn next_after(&self, time: u64) -> Option<u64> {
+        let secs = (bucky_time_to_unix_time(time) / (1000 * 1000)) as i64;
+        let now = Local.timestamp_opt(secs, 0).single()?.naive_local();
+
+        // 从下一分钟开始
+        let mut t = now.date().and_hms_opt(now.hour(), now.minute(), 0)? + Duration::minutes(1);
+        loop {
+            t = self.next_naive(t)?;
+
+            // 夏令时切换时跳过的本地时间不存在，顺延到下一个匹配的时间
+            match Local.from_local_datetime(&t).earliest() {
+                Some(dt) => {
+                    return Some(unix_time_to_bucky_time(dt.timestamp() as u64 * 1000 * 1000));
+                }
+                None => t = t + Duration::minutes(1),
+            }
+        }
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn local_time(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(CronExpr::parse("* * * * *").is_ok());
+        assert!(CronExpr::parse("*/15 0-6,22 1 */2 1-5").is_ok());
+        assert!(CronExpr::parse("0 0 * * 7").is_ok());
+
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next() {
+        let cron = CronExpr::parse("30 3 * * *").unwrap();
+        let next = cron.next_naive(local_time(2023, 1, 1, 4, 0)).unwrap();
+        assert_eq!(next, local_time(2023, 1, 2, 3, 30));
+
+        let cron = CronExpr::parse("*/20 * * * *").unwrap();
+        let next = cron.next_naive(local_time(2023, 1, 1, 23, 41)).unwrap();
+        assert_eq!(next, local_time(2023, 1, 2, 0, 0));
+
+        // 2023-01-01是周日
+        let cron = CronExpr::parse("0 9 * * 0").unwrap();
+        let next = cron.next_naive(local_time(2023, 1, 1, 10, 0)).unwrap();
+        assert_eq!(next, local_time(2023, 1, 8, 9, 0));
+
+        // 日和周都限定时满足任意一个即可
+        let cron = CronExpr::parse("0 0 15 * 1").unwrap();
+        let next = cron.next_naive(local_time(2023, 1, 1, 1, 0)).unwrap();
+        assert_eq!(next, local_time(2023, 1, 2, 0, 0));
+
+        let cron = CronExpr::parse("0 0 30 2 *").unwrap();
+        assert!(cron.next_naive(local_time(2023, 1, 1, 0, 0)).is_none());
+    }
+}
@@ -0,0 +1,38 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// add_schedule_job
+pub struct ScheduleAddJobInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+    pub cron: String,
+    pub req_path: String,
+    pub payload_id: ObjectId,
+    pub payload_raw: Vec<u8>,
+}
+
+pub type ScheduleAddJobInputResponse = ScheduleAddJobOutputResponse;
+
+// remove_schedule_job
+pub struct ScheduleRemoveJobInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+}
+
+pub type ScheduleRemoveJobInputResponse = ScheduleRemoveJobOutputResponse;
+
+// get_schedule_jobs
+pub struct ScheduleGetJobsInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type ScheduleGetJobsInputResponse = ScheduleGetJobsOutputResponse;
+
+// get_schedule_job_history
+pub struct ScheduleGetJobHistoryInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+}
+
+pub type ScheduleGetJobHistoryInputResponse = ScheduleGetJobHistoryOutputResponse;
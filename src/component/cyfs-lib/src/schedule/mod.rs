@@ -0,0 +1,15 @@
+mod cron;
+mod input_request;
+mod output_request;
+mod output_request_codec;
+mod processor;
+mod request;
+mod requestor;
+
+pub use cron::*;
+pub use input_request::*;
+pub use output_request::*;
+pub use output_request_codec::*;
+pub use processor::*;
+pub use request::*;
+pub use requestor::*;
@@ -0,0 +1,194 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// dec注册的定时任务，由ood上的协议栈按cron表达式触发，触发时把payload对象post给目标dec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleJobInfo {
+    pub dec_id: ObjectId,
+    pub name: String,
+
+    // 5段cron表达式，按ood的本地时区计算
+    pub cron: String,
+
+    // 目标dec处理post_object的req_path，以及post到的设备
+    pub req_path: String,
+    pub device_id: DeviceId,
+
+    pub payload_id: ObjectId,
+
+    // bucky time
+    pub create_time: u64,
+    pub next_run_time: Option<u64>,
+    pub last_run_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleJobRunStatus {
+    Success,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleJobRun {
+    // 计划的触发时间，同一次触发失败重试时不变
+    pub schedule_time: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+
+    // 同一次触发的第几次尝试，从1开始
+    pub attempt: u32,
+    pub status: ScheduleJobRunStatus,
+    pub error: Option<String>,
+}
+
+// 定时任务属于发起请求的dec，同名任务会被替换
+#[derive(Debug, Clone)]
+pub struct ScheduleAddJobOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+    pub cron: String,
+    pub req_path: String,
+
+    pub payload_id: ObjectId,
+    pub payload_raw: Vec<u8>,
+}
+
+impl Display for ScheduleAddJobOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {}, cron: {}, req_path: {}, payload: {}",
+            self.common, self.name, self.cron, self.req_path, self.payload_id
+        )
+    }
+}
+
+impl ScheduleAddJobOutputRequest {
+    pub fn new(
+        name: impl Into<String>,
+        cron: impl Into<String>,
+        req_path: impl Into<String>,
+        payload_id: ObjectId,
+        payload_raw: Vec<u8>,
+    ) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+            cron: cron.into(),
+            req_path: req_path.into(),
+            payload_id,
+            payload_raw,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleAddJobOutputResponse {
+    pub job: ScheduleJobInfo,
+}
+
+impl Display for ScheduleAddJobOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "job: {:?}", self.job)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleRemoveJobOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+}
+
+impl Display for ScheduleRemoveJobOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, name: {}", self.common, self.name)
+    }
+}
+
+impl ScheduleRemoveJobOutputRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRemoveJobOutputResponse {
+    // 被移除的任务，不存在时为空
+    pub job: Option<ScheduleJobInfo>,
+}
+
+impl Display for ScheduleRemoveJobOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "job: {:?}", self.job)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleGetJobsOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for ScheduleGetJobsOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl ScheduleGetJobsOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleGetJobsOutputResponse {
+    pub list: Vec<ScheduleJobInfo>,
+}
+
+impl Display for ScheduleGetJobsOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleGetJobHistoryOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+}
+
+impl Display for ScheduleGetJobHistoryOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, name: {}", self.common, self.name)
+    }
+}
+
+impl ScheduleGetJobHistoryOutputRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+        }
+    }
+}
+
+// 按时间倒序，最近的在前
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleGetJobHistoryOutputResponse {
+    pub list: Vec<ScheduleJobRun>,
+}
+
+impl Display for ScheduleGetJobHistoryOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
@@ -0,0 +1,85 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ScheduleAddJobOutputRequest> for ScheduleAddJobOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        JsonCodecHelper::encode_string_field(&mut obj, "cron", &self.cron);
+        JsonCodecHelper::encode_string_field(&mut obj, "req_path", &self.req_path);
+        JsonCodecHelper::encode_string_field(&mut obj, "payload_id", &self.payload_id);
+        JsonCodecHelper::encode_string_field(
+            &mut obj,
+            "payload_raw",
+            &hex::encode(&self.payload_raw),
+        );
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ScheduleAddJobOutputRequest> {
+        let payload_raw: String = JsonCodecHelper::decode_string_field(obj, "payload_raw")?;
+        let payload_raw = hex::decode(&payload_raw).map_err(|e| {
+            let msg = format!("invalid schedule job payload_raw hex! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            cron: JsonCodecHelper::decode_string_field(obj, "cron")?,
+            req_path: JsonCodecHelper::decode_string_field(obj, "req_path")?,
+            payload_id: JsonCodecHelper::decode_string_field(obj, "payload_id")?,
+            payload_raw,
+        })
+    }
+}
+
+impl JsonCodec<ScheduleRemoveJobOutputRequest> for ScheduleRemoveJobOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ScheduleRemoveJobOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
+
+impl JsonCodec<ScheduleGetJobsOutputRequest> for ScheduleGetJobsOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ScheduleGetJobsOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+        })
+    }
+}
+
+impl JsonCodec<ScheduleGetJobHistoryOutputRequest> for ScheduleGetJobHistoryOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ScheduleGetJobHistoryOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
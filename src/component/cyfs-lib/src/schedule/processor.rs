@@ -0,0 +1,29 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ScheduleOutputProcessor: Sync + Send + 'static {
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobOutputRequest,
+    ) -> BuckyResult<ScheduleAddJobOutputResponse>;
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobOutputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobOutputResponse>;
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsOutputRequest,
+    ) -> BuckyResult<ScheduleGetJobsOutputResponse>;
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryOutputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryOutputResponse>;
+}
+
+pub type ScheduleOutputProcessorRef = Arc<dyn ScheduleOutputProcessor>;
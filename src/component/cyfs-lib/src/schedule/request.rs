@@ -0,0 +1,13 @@
+use super::output_request::*;
+
+pub type ScheduleAddJobRequest = ScheduleAddJobOutputRequest;
+pub type ScheduleAddJobResponse = ScheduleAddJobOutputResponse;
+
+pub type ScheduleRemoveJobRequest = ScheduleRemoveJobOutputRequest;
+pub type ScheduleRemoveJobResponse = ScheduleRemoveJobOutputResponse;
+
+pub type ScheduleGetJobsRequest = ScheduleGetJobsOutputRequest;
+pub type ScheduleGetJobsResponse = ScheduleGetJobsOutputResponse;
+
+pub type ScheduleGetJobHistoryRequest = ScheduleGetJobHistoryOutputRequest;
+pub type ScheduleGetJobHistoryResponse = ScheduleGetJobHistoryOutputResponse;
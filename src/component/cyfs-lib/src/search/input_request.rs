@@ -0,0 +1,13 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+
+// search_object
+pub struct SearchObjectInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub query: String,
+    pub filter: SearchObjectFilter,
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+pub type SearchObjectInputResponse = SearchObjectOutputResponse;
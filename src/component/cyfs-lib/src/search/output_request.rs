@@ -0,0 +1,69 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use std::fmt::{self, Display};
+
+// 搜索的过滤条件，都是可选的，多个条件之间是and关系
+#[derive(Debug, Clone, Default)]
+pub struct SearchObjectFilter {
+    pub obj_type: Option<u16>,
+    pub dec_id: Option<ObjectId>,
+    pub owner_id: Option<ObjectId>,
+
+    // 对象的create_time范围，[start, end)
+    pub create_time_start: Option<u64>,
+    pub create_time_end: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchObjectOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    // 全文检索的查询语句，为空则只按filter匹配
+    pub query: String,
+    pub filter: SearchObjectFilter,
+
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+impl Display for SearchObjectOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, query: {}, filter: {:?}, page_index: {}, page_size: {}",
+            self.common, self.query, self.filter, self.page_index, self.page_size
+        )
+    }
+}
+
+impl SearchObjectOutputRequest {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            query: query.into(),
+            filter: SearchObjectFilter::default(),
+            page_index: 0,
+            page_size: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchObjectItem {
+    pub object_id: ObjectId,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchObjectOutputResponse {
+    // 匹配的总数，用于分页
+    pub total: u64,
+    pub list: Vec<SearchObjectItem>,
+}
+
+impl Display for SearchObjectOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "total: {}, list: {:?}", self.total, self.list)
+    }
+}
@@ -0,0 +1,91 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<SearchObjectFilter> for SearchObjectFilter {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_option_number_field(&mut obj, "obj_type", self.obj_type);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_option_string_field(&mut obj, "owner_id", self.owner_id.as_ref());
+        JsonCodecHelper::encode_option_string_field(
+            &mut obj,
+            "create_time_start",
+            self.create_time_start.as_ref(),
+        );
+        JsonCodecHelper::encode_option_string_field(
+            &mut obj,
+            "create_time_end",
+            self.create_time_end.as_ref(),
+        );
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<SearchObjectFilter> {
+        Ok(Self {
+            obj_type: JsonCodecHelper::decode_option_int_field(obj, "obj_type")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            owner_id: JsonCodecHelper::decode_option_string_field(obj, "owner_id")?,
+            create_time_start: JsonCodecHelper::decode_option_string_field(
+                obj,
+                "create_time_start",
+            )?,
+            create_time_end: JsonCodecHelper::decode_option_string_field(obj, "create_time_end")?,
+        })
+    }
+}
+
+impl JsonCodec<SearchObjectOutputRequest> for SearchObjectOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "query", &self.query);
+        JsonCodecHelper::encode_field(&mut obj, "filter", &self.filter);
+        JsonCodecHelper::encode_number_field(&mut obj, "page_index", self.page_index);
+        JsonCodecHelper::encode_number_field(&mut obj, "page_size", self.page_size);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<SearchObjectOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            query: JsonCodecHelper::decode_string_field(obj, "query")?,
+            filter: JsonCodecHelper::decode_field(obj, "filter")?,
+            page_index: JsonCodecHelper::decode_int_field(obj, "page_index")?,
+            page_size: JsonCodecHelper::decode_int_field(obj, "page_size")?,
+        })
+    }
+}
+
+impl JsonCodec<SearchObjectItem> for SearchObjectItem {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "object_id", &self.object_id);
+        JsonCodecHelper::encode_string_field(&mut obj, "score", &self.score);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<SearchObjectItem> {
+        Ok(Self {
+            object_id: JsonCodecHelper::decode_string_field(obj, "object_id")?,
+            score: JsonCodecHelper::decode_string_field(obj, "score")?,
+        })
+    }
+}
+
+impl JsonCodec<SearchObjectOutputResponse> for SearchObjectOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "total", &self.total);
+        JsonCodecHelper::encode_as_list(&mut obj, "list", &self.list);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<SearchObjectOutputResponse> {
+        Ok(Self {
+            total: JsonCodecHelper::decode_string_field(obj, "total")?,
+            list: JsonCodecHelper::decode_array_field(obj, "list")?,
+        })
+    }
+}
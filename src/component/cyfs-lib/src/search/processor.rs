@@ -0,0 +1,14 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait SearchOutputProcessor: Sync + Send + 'static {
+    async fn search_object(
+        &self,
+        req: SearchObjectOutputRequest,
+    ) -> BuckyResult<SearchObjectOutputResponse>;
+}
+
+pub type SearchOutputProcessorRef = Arc<dyn SearchOutputProcessor>;
@@ -0,0 +1,4 @@
+use super::output_request::*;
+
+pub type SearchObjectRequest = SearchObjectOutputRequest;
+pub type SearchObjectResponse = SearchObjectOutputResponse;
@@ -0,0 +1,33 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// register_dec_service
+pub struct ServiceDiscoveryRegisterInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+    pub endpoints: Vec<DecServiceEndpoint>,
+    pub capabilities: Vec<String>,
+    pub ttl: u32,
+}
+
+pub type ServiceDiscoveryRegisterInputResponse = ServiceDiscoveryRegisterOutputResponse;
+
+// unregister_dec_service
+pub struct ServiceDiscoveryUnregisterInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: String,
+}
+
+pub type ServiceDiscoveryUnregisterInputResponse = ServiceDiscoveryUnregisterOutputResponse;
+
+// query_dec_services
+pub struct ServiceDiscoveryQueryInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub name: Option<String>,
+    pub dec_id: Option<ObjectId>,
+    pub capability: Option<String>,
+    pub include_expired: bool,
+}
+
+pub type ServiceDiscoveryQueryInputResponse = ServiceDiscoveryQueryOutputResponse;
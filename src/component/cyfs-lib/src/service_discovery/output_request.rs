@@ -0,0 +1,188 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// dec对外提供服务的端点
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DecServiceEndpoint {
+    // 注册设备上bdt协议栈的vport
+    VPort(u16),
+
+    // 注册设备上的http服务，path为路由前缀
+    Http { port: u16, path: String },
+}
+
+impl Display for DecServiceEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VPort(vport) => write!(f, "vport:{}", vport),
+            Self::Http { port, path } => write!(f, "http:{}{}", port, path),
+        }
+    }
+}
+
+// dec注册的服务，保存在ood的root_state系统dec下，随root_state同步到zone内的所有设备
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DecServiceInfo {
+    // 服务名，不同的dec可以提供同名的服务
+    pub name: String,
+
+    pub dec_id: ObjectId,
+
+    // 提供服务的设备，也就是发起注册的设备
+    pub device_id: DeviceId,
+
+    pub endpoints: Vec<DecServiceEndpoint>,
+
+    // 自定义的能力描述，比如支持的协议版本，查询时可以按能力过滤
+    pub capabilities: Vec<String>,
+
+    // 秒，超过ttl没有重新注册视为不可用
+    pub ttl: u32,
+
+    // bucky time
+    pub register_time: u64,
+    pub expire_time: u64,
+}
+
+impl DecServiceInfo {
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expire_time
+    }
+}
+
+// 注册或者刷新请求方dec在当前设备上的服务，需要在ttl内重复调用保持可用，请求会路由到ood处理
+#[derive(Debug, Clone)]
+pub struct ServiceDiscoveryRegisterOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+    pub endpoints: Vec<DecServiceEndpoint>,
+    pub capabilities: Vec<String>,
+
+    // 秒，为0使用默认值，服务端会限制在合理的范围内
+    pub ttl: u32,
+}
+
+impl Display for ServiceDiscoveryRegisterOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {}, endpoints: {:?}, capabilities: {:?}, ttl: {}",
+            self.common, self.name, self.endpoints, self.capabilities, self.ttl
+        )
+    }
+}
+
+impl ServiceDiscoveryRegisterOutputRequest {
+    pub fn new(name: impl Into<String>, endpoints: Vec<DecServiceEndpoint>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+            endpoints,
+            capabilities: vec![],
+            ttl: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDiscoveryRegisterOutputResponse {
+    pub info: DecServiceInfo,
+}
+
+impl Display for ServiceDiscoveryRegisterOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "name: {}, device: {}, expire_time: {}",
+            self.info.name, self.info.device_id, self.info.expire_time
+        )
+    }
+}
+
+// 移除请求方dec在当前设备上注册的服务
+#[derive(Debug, Clone)]
+pub struct ServiceDiscoveryUnregisterOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: String,
+}
+
+impl Display for ServiceDiscoveryUnregisterOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, name: {}", self.common, self.name)
+    }
+}
+
+impl ServiceDiscoveryUnregisterOutputRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDiscoveryUnregisterOutputResponse {
+    // 被移除的服务，不存在时为空
+    pub info: Option<DecServiceInfo>,
+}
+
+impl Display for ServiceDiscoveryUnregisterOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "removed: {}", self.info.is_some())
+    }
+}
+
+// 从当前设备同步的root_state查询，所有条件都为空时返回全部可用的服务
+#[derive(Debug, Clone)]
+pub struct ServiceDiscoveryQueryOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub name: Option<String>,
+    pub dec_id: Option<ObjectId>,
+    pub capability: Option<String>,
+
+    // 是否包括已经超过ttl的服务
+    pub include_expired: bool,
+}
+
+impl Display for ServiceDiscoveryQueryOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, name: {:?}, dec_id: {:?}, capability: {:?}, include_expired: {}",
+            self.common, self.name, self.dec_id, self.capability, self.include_expired
+        )
+    }
+}
+
+impl ServiceDiscoveryQueryOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            name: None,
+            dec_id: None,
+            capability: None,
+            include_expired: false,
+        }
+    }
+
+    pub fn new_name(name: impl Into<String>) -> Self {
+        let mut ret = Self::new();
+        ret.name = Some(name.into());
+        ret
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDiscoveryQueryOutputResponse {
+    pub list: Vec<DecServiceInfo>,
+}
+
+impl Display for ServiceDiscoveryQueryOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
@@ -0,0 +1,75 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ServiceDiscoveryRegisterOutputRequest> for ServiceDiscoveryRegisterOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj.insert(
+            "endpoints".to_owned(),
+            serde_json::to_value(&self.endpoints).unwrap(),
+        );
+        JsonCodecHelper::encode_str_array_field(&mut obj, "capabilities", &self.capabilities);
+        JsonCodecHelper::encode_number_field(&mut obj, "ttl", self.ttl);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ServiceDiscoveryRegisterOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+            endpoints: JsonCodecHelper::decode_serde_field(obj, "endpoints")?,
+            capabilities: JsonCodecHelper::decode_str_array_field(obj, "capabilities")?,
+            ttl: JsonCodecHelper::decode_int_field(obj, "ttl")?,
+        })
+    }
+}
+
+impl JsonCodec<ServiceDiscoveryUnregisterOutputRequest>
+    for ServiceDiscoveryUnregisterOutputRequest
+{
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "name", &self.name);
+        obj
+    }
+
+    fn decode_json(
+        obj: &Map<String, Value>,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_string_field(obj, "name")?,
+        })
+    }
+}
+
+impl JsonCodec<ServiceDiscoveryQueryOutputRequest> for ServiceDiscoveryQueryOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "name", self.name.as_ref());
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_option_string_field(
+            &mut obj,
+            "capability",
+            self.capability.as_ref(),
+        );
+        JsonCodecHelper::encode_bool_field(&mut obj, "include_expired", self.include_expired);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ServiceDiscoveryQueryOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            name: JsonCodecHelper::decode_option_string_field(obj, "name")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            capability: JsonCodecHelper::decode_option_string_field(obj, "capability")?,
+            include_expired: JsonCodecHelper::decode_bool_field(obj, "include_expired")?,
+        })
+    }
+}
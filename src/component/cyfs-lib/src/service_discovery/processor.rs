@@ -0,0 +1,24 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ServiceDiscoveryOutputProcessor: Sync + Send + 'static {
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterOutputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterOutputResponse>;
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterOutputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterOutputResponse>;
+
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryOutputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryOutputResponse>;
+}
+
+pub type ServiceDiscoveryOutputProcessorRef = Arc<dyn ServiceDiscoveryOutputProcessor>;
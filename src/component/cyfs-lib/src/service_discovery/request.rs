@@ -0,0 +1,10 @@
+use super::output_request::*;
+
+pub type ServiceDiscoveryRegisterRequest = ServiceDiscoveryRegisterOutputRequest;
+pub type ServiceDiscoveryRegisterResponse = ServiceDiscoveryRegisterOutputResponse;
+
+pub type ServiceDiscoveryUnregisterRequest = ServiceDiscoveryUnregisterOutputRequest;
+pub type ServiceDiscoveryUnregisterResponse = ServiceDiscoveryUnregisterOutputResponse;
+
+pub type ServiceDiscoveryQueryRequest = ServiceDiscoveryQueryOutputRequest;
+pub type ServiceDiscoveryQueryResponse = ServiceDiscoveryQueryOutputResponse;
@@ -0,0 +1,28 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+
+// create_share
+pub struct ShareCreateInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub target: ShareTarget,
+    pub mode: ShareMode,
+    pub expire_time: Option<u64>,
+    pub password: Option<String>,
+}
+
+pub type ShareCreateInputResponse = ShareCreateOutputResponse;
+
+// revoke_share
+pub struct ShareRevokeInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub id: String,
+}
+
+pub type ShareRevokeInputResponse = ShareRevokeOutputResponse;
+
+// get_shares
+pub struct ShareListInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type ShareListInputResponse = ShareListOutputResponse;
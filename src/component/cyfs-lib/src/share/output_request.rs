@@ -0,0 +1,200 @@
+use crate::UtilOutputRequestCommon;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// 分享的目标
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ShareTarget {
+    // 对象，比如file/dir/objectmap，inner_path为对象内部的路径
+    Object {
+        object_id: ObjectId,
+        inner_path: Option<String>,
+    },
+
+    // 创建分享的dec在root_state下的路径，访问时可以继续访问它的子路径
+    Path(String),
+}
+
+impl Display for ShareTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object {
+                object_id,
+                inner_path,
+            } => match inner_path {
+                Some(inner_path) => write!(f, "object: {}{}", object_id, inner_path),
+                None => write!(f, "object: {}", object_id),
+            },
+            Self::Path(path) => write!(f, "path: {}", path),
+        }
+    }
+}
+
+// 访问分享链接时返回的内容，和front协议的mode参数含义一致
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ShareMode {
+    Default,
+    Object,
+    Data,
+}
+
+impl Default for ShareMode {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+// 分享记录，在ood上创建和保存，通过front服务的/s/{id}链接提供给匿名的浏览器访问
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareRecord {
+    // 随机生成，同时作为访问链接的凭证
+    pub id: String,
+
+    // 创建分享的dec，访问时以该dec的身份读取目标
+    pub dec_id: ObjectId,
+
+    pub target: ShareTarget,
+    pub mode: ShareMode,
+
+    // 过期时间，bucky time，为None表示不过期
+    pub expire_time: Option<u64>,
+
+    // 访问时是否需要密码，密码只保存加盐后的hash
+    pub has_password: bool,
+
+    pub create_time: u64,
+
+    pub views: u64,
+    pub last_view_time: Option<u64>,
+}
+
+impl ShareRecord {
+    pub fn is_expired(&self, now: u64) -> bool {
+        match self.expire_time {
+            Some(expire_time) => now >= expire_time,
+            None => false,
+        }
+    }
+
+    // 相对于front服务的访问路径
+    pub fn url_path(&self) -> String {
+        format!("/s/{}", self.id)
+    }
+}
+
+// 创建分享，请求会路由到ood处理
+#[derive(Debug, Clone)]
+pub struct ShareCreateOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub target: ShareTarget,
+    pub mode: ShareMode,
+    pub expire_time: Option<u64>,
+    pub password: Option<String>,
+}
+
+impl Display for ShareCreateOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, target: {}, mode: {:?}, expire_time: {:?}, password: {}",
+            self.common,
+            self.target,
+            self.mode,
+            self.expire_time,
+            self.password.is_some()
+        )
+    }
+}
+
+impl ShareCreateOutputRequest {
+    pub fn new(target: ShareTarget, mode: ShareMode) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            target,
+            mode,
+            expire_time: None,
+            password: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareCreateOutputResponse {
+    pub share: ShareRecord,
+
+    // 访问路径，/s/{id}
+    pub url: String,
+}
+
+impl Display for ShareCreateOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "share: {:?}, url: {}", self.share, self.url)
+    }
+}
+
+// 撤销分享，只能撤销自己创建的分享，系统dec可以撤销所有的分享
+#[derive(Debug, Clone)]
+pub struct ShareRevokeOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub id: String,
+}
+
+impl Display for ShareRevokeOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, id: {}", self.common, self.id)
+    }
+}
+
+impl ShareRevokeOutputRequest {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            id: id.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareRevokeOutputResponse {
+    // 不存在时为None
+    pub share: Option<ShareRecord>,
+}
+
+impl Display for ShareRevokeOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "share: {:?}", self.share)
+    }
+}
+
+// 查询分享列表和访问计数，只返回调用方dec创建的分享，系统dec返回所有的分享
+#[derive(Debug, Clone)]
+pub struct ShareListOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for ShareListOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl ShareListOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareListOutputResponse {
+    pub list: Vec<ShareRecord>,
+}
+
+impl Display for ShareListOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
@@ -0,0 +1,63 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<ShareCreateOutputRequest> for ShareCreateOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        obj.insert(
+            "target".to_owned(),
+            serde_json::to_value(&self.target).unwrap(),
+        );
+        obj.insert("mode".to_owned(), serde_json::to_value(&self.mode).unwrap());
+        JsonCodecHelper::encode_option_string_field(
+            &mut obj,
+            "expire_time",
+            self.expire_time.as_ref(),
+        );
+        JsonCodecHelper::encode_option_string_field(&mut obj, "password", self.password.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ShareCreateOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            target: JsonCodecHelper::decode_serde_field(obj, "target")?,
+            mode: JsonCodecHelper::decode_option_serde_field(obj, "mode")?.unwrap_or_default(),
+            expire_time: JsonCodecHelper::decode_option_string_field(obj, "expire_time")?,
+            password: JsonCodecHelper::decode_option_string_field(obj, "password")?,
+        })
+    }
+}
+
+impl JsonCodec<ShareRevokeOutputRequest> for ShareRevokeOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "id", &self.id);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ShareRevokeOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            id: JsonCodecHelper::decode_string_field(obj, "id")?,
+        })
+    }
+}
+
+impl JsonCodec<ShareListOutputRequest> for ShareListOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<ShareListOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait ShareOutputProcessor: Sync + Send + 'static {
+    async fn create_share(
+        &self,
+        req: ShareCreateOutputRequest,
+    ) -> BuckyResult<ShareCreateOutputResponse>;
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeOutputRequest,
+    ) -> BuckyResult<ShareRevokeOutputResponse>;
+
+    async fn get_shares(&self, req: ShareListOutputRequest)
+        -> BuckyResult<ShareListOutputResponse>;
+}
+
+pub type ShareOutputProcessorRef = Arc<dyn ShareOutputProcessor>;
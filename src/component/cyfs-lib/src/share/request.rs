@@ -0,0 +1,10 @@
+use super::output_request::*;
+
+pub type ShareCreateRequest = ShareCreateOutputRequest;
+pub type ShareCreateResponse = ShareCreateOutputResponse;
+
+pub type ShareRevokeRequest = ShareRevokeOutputRequest;
+pub type ShareRevokeResponse = ShareRevokeOutputResponse;
+
+pub type ShareListRequest = ShareListOutputRequest;
+pub type ShareListResponse = ShareListOutputResponse;
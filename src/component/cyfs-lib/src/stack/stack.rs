@@ -53,6 +53,19 @@ pub(crate) struct ObjectServices {
     crypto_service: CryptoRequestor,
 
     util_service: UtilRequestor,
+    contacts_service: ContactsRequestor,
+    queue_service: QueueRequestor,
+    schedule_service: ScheduleRequestor,
+    trash_service: TrashRequestor,
+    dec_config_service: DecConfigRequestor,
+    admin_confirm_service: AdminConfirmRequestor,
+    service_discovery_service: ServiceDiscoveryRequestor,
+    bandwidth_service: BandwidthRequestor,
+    search_service: SearchRequestor,
+    object_refs_service: ObjectRefsRequestor,
+    share_service: ShareRequestor,
+    api_gateway_service: ApiGatewayRequestor,
+    object_history_service: ObjectHistoryRequestor,
     trans_service: TransRequestor,
     sync_service: SyncRequestor,
 
@@ -381,10 +394,23 @@ impl SharedCyfsStack {
             requestor_holder.select_requestor(&param, &param.requestor_config.trans_service);
         let trans_service = TransRequestor::new(Some(dec_id.clone()), requestor);
 
-        // util
+        // util，从util拆分出去的contacts等服务沿用util的requestor配置
         let requestor =
             requestor_holder.select_requestor(&param, &param.requestor_config.util_service);
-        let util_service = UtilRequestor::new(Some(dec_id.clone()), requestor);
+        let util_service = UtilRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let contacts_service = ContactsRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let queue_service = QueueRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let schedule_service = ScheduleRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let trash_service = TrashRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let dec_config_service = DecConfigRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let admin_confirm_service = AdminConfirmRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let service_discovery_service = ServiceDiscoveryRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let bandwidth_service = BandwidthRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let search_service = SearchRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let object_refs_service = ObjectRefsRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let share_service = ShareRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let api_gateway_service = ApiGatewayRequestor::new(Some(dec_id.clone()), requestor.clone());
+        let object_history_service = ObjectHistoryRequestor::new(Some(dec_id.clone()), requestor);
 
         // crypto
         let requestor =
@@ -437,6 +463,19 @@ impl SharedCyfsStack {
             crypto_service,
 
             util_service,
+            contacts_service,
+            queue_service,
+            schedule_service,
+            trash_service,
+            dec_config_service,
+            admin_confirm_service,
+            service_discovery_service,
+            bandwidth_service,
+            search_service,
+            object_refs_service,
+            share_service,
+            api_gateway_service,
+            object_history_service,
             trans_service,
             sync_service,
 
@@ -602,6 +641,58 @@ impl SharedCyfsStack {
         &self.services.util_service
     }
 
+    pub fn contacts(&self) -> &ContactsRequestor {
+        &self.services.contacts_service
+    }
+
+    pub fn queue(&self) -> &QueueRequestor {
+        &self.services.queue_service
+    }
+
+    pub fn schedule(&self) -> &ScheduleRequestor {
+        &self.services.schedule_service
+    }
+
+    pub fn trash(&self) -> &TrashRequestor {
+        &self.services.trash_service
+    }
+
+    pub fn dec_config(&self) -> &DecConfigRequestor {
+        &self.services.dec_config_service
+    }
+
+    pub fn admin_confirm(&self) -> &AdminConfirmRequestor {
+        &self.services.admin_confirm_service
+    }
+
+    pub fn service_discovery(&self) -> &ServiceDiscoveryRequestor {
+        &self.services.service_discovery_service
+    }
+
+    pub fn bandwidth(&self) -> &BandwidthRequestor {
+        &self.services.bandwidth_service
+    }
+
+    pub fn search(&self) -> &SearchRequestor {
+        &self.services.search_service
+    }
+
+    pub fn object_refs(&self) -> &ObjectRefsRequestor {
+        &self.services.object_refs_service
+    }
+
+    pub fn share(&self) -> &ShareRequestor {
+        &self.services.share_service
+    }
+
+    pub fn api_gateway(&self) -> &ApiGatewayRequestor {
+        &self.services.api_gateway_service
+    }
+
+    pub fn object_history(&self) -> &ObjectHistoryRequestor {
+        &self.services.object_history_service
+    }
+
     pub fn trans(&self) -> &TransRequestor {
         &self.services.trans_service
     }
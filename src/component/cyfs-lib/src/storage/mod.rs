@@ -4,9 +4,11 @@ mod state_storage;
 mod storage;
 mod collection;
 mod state_view;
+mod typed_collection;
 
 pub use remote_storage::*;
 pub use state_storage::*;
 pub use storage::*;
 pub use collection::*;
 pub use state_view::*;
+pub use typed_collection::*;
@@ -77,6 +77,7 @@ impl NamedObjectCache for RemoteNamedObjectCache {
                             context: None,
                             last_access_rpath: None,
                             access_string: 9,
+                            sign_verified_update_time: None,
                         }
                     },
                     object: Some(resp.object),
@@ -122,6 +123,7 @@ impl NamedObjectCache for RemoteNamedObjectCache {
                         context: None,
                         last_access_rpath: None,
                         access_string: 9,
+                        sign_verified_update_time: None,
                     };
 
                     Some(meta)
@@ -173,6 +175,20 @@ impl NamedObjectCache for RemoteNamedObjectCache {
         unimplemented!();
     }
 
+    async fn get_object_history(
+        &self,
+        _req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        unimplemented!();
+    }
+
+    async fn get_object_revision(
+        &self,
+        _req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        unimplemented!();
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         _object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
@@ -101,6 +101,7 @@ impl NOCStorageRawHelper {
             last_access_rpath: None,
             context: None,
             access_string: Some(AccessString::dec_default().value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -0,0 +1,1360 @@
+use super::collection::CollectionCodec;
+use crate::non::*;
+use crate::root_state::*;
+use crate::UniCyfsStackRef;
+use cyfs_base::*;
+use cyfs_core::*;
+
+use async_std::sync::Mutex as AsyncMutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/*
+基于root_state的带类型集合，值编码后以Storage对象保存，root_state里面只保存对象id
+修改先缓存在本地，调用flush或者开启自动flush后批量提交到root_state
+flush时如果发现对应的key已经被其它端修改，那么通过StateConflictResolver来决定保留哪一个值
+*/
+
+// flush时遇到commit失败(一般是root_state被并发修改)的最大重试次数
+const STATE_TYPED_FLUSH_RETRY: u32 = 3;
+
+pub enum StateConflictResolution<V> {
+    // 使用本地的值覆盖远端
+    KeepLocal,
+
+    // 放弃本地的修改，使用远端的值
+    KeepRemote,
+
+    // 使用合并后的值
+    Merge(V),
+}
+
+// local/remote为None表示对应的key已经被删除
+pub trait StateConflictResolver<V>: Send + Sync {
+    fn resolve(&self, key: &str, local: Option<&V>, remote: Option<&V>)
+        -> StateConflictResolution<V>;
+}
+
+pub type StateConflictResolverRef<V> = Arc<Box<dyn StateConflictResolver<V>>>;
+
+// 默认的冲突处理：后写入的覆盖先写入的
+pub struct StateLastWriteWins;
+
+impl<V> StateConflictResolver<V> for StateLastWriteWins {
+    fn resolve(
+        &self,
+        _key: &str,
+        _local: Option<&V>,
+        _remote: Option<&V>,
+    ) -> StateConflictResolution<V> {
+        StateConflictResolution::KeepLocal
+    }
+}
+
+#[derive(Clone)]
+struct TypedStateStore {
+    stub: GlobalStateStub,
+    non: NONOutputProcessorRef,
+    target: Option<ObjectId>,
+    path: String,
+}
+
+impl TypedStateStore {
+    fn new(
+        global_state: GlobalStateOutputProcessorRef,
+        non: NONOutputProcessorRef,
+        path: String,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> Self {
+        let stub = GlobalStateStub::new(global_state, target.clone(), dec_id);
+        Self {
+            stub,
+            non,
+            target,
+            path,
+        }
+    }
+
+    fn full_path(&self, key: &str) -> String {
+        format!("{}/{}", self.path.trim_end_matches('/'), key)
+    }
+
+    async fn load_value<V>(&self, object_id: &ObjectId) -> BuckyResult<V>
+    where
+        V: CollectionCodec<V>,
+    {
+        let mut req = NONGetObjectOutputRequest::new(NONAPILevel::NON, object_id.clone(), None);
+        req.common.target = self.target.clone();
+
+        let resp = self.non.get_object(req).await.map_err(|e| {
+            error!(
+                "load state value object failed! path={}, object={}, {}",
+                self.path, object_id, e
+            );
+            e
+        })?;
+
+        let storage = Storage::clone_from_slice(&resp.object.object_raw)?;
+        V::decode(&storage.into_value()).map_err(|e| {
+            error!(
+                "decode state value failed! path={}, object={}, {}",
+                self.path, object_id, e
+            );
+            e
+        })
+    }
+
+    async fn save_value<V>(&self, key: &str, value: &V) -> BuckyResult<ObjectId>
+    where
+        V: CollectionCodec<V>,
+    {
+        let buf = value.encode()?;
+        let storage = Storage::create_with_hash(&self.full_path(key), buf);
+        let object_id = storage.desc().calculate_id();
+
+        let mut req = NONPutObjectOutputRequest::new(
+            NONAPILevel::NON,
+            object_id.clone(),
+            storage.to_vec()?,
+        );
+        req.common.target = self.target.clone();
+
+        self.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save state value object failed! path={}, key={}, {}",
+                self.path, key, e
+            );
+            e
+        })?;
+
+        Ok(object_id)
+    }
+
+    async fn load_key<V>(&self, key: &str) -> BuckyResult<Option<(ObjectId, V)>>
+    where
+        V: CollectionCodec<V>,
+    {
+        let op_env = self.stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&self.path, key).await;
+        let _ = op_env.abort().await;
+
+        match ret {
+            Ok(Some(id)) => {
+                let value = self.load_value(&id).await?;
+                Ok(Some((id, value)))
+            }
+            Ok(None) => Ok(None),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // 在op_env里面写入一个key，base为上次读取到的值，None表示没有读取过
+    // 返回Ok(false)表示远端已经被修改，需要处理冲突
+    async fn write_key(
+        &self,
+        op_env: &PathOpEnvStub,
+        key: &str,
+        base: Option<Option<ObjectId>>,
+        value: Option<&ObjectId>,
+    ) -> BuckyResult<bool> {
+        let ret = match (value, base) {
+            (Some(id), None) => op_env
+                .set_with_key(&self.path, key, id, None, true)
+                .await
+                .map(|_| ()),
+            (Some(id), Some(None)) => op_env.insert_with_key(&self.path, key, id).await,
+            (Some(id), Some(Some(prev))) => op_env
+                .set_with_key(&self.path, key, id, Some(prev), false)
+                .await
+                .map(|_| ()),
+            (None, base) => op_env
+                .remove_with_key(&self.path, key, base.flatten())
+                .await
+                .map(|_| ()),
+        };
+
+        match ret {
+            Ok(_) => Ok(true),
+            Err(e) => match e.code() {
+                BuckyErrorCode::Unmatch
+                | BuckyErrorCode::AlreadyExists
+                | BuckyErrorCode::NotFound => {
+                    warn!(
+                        "write state key but remote changed! path={}, key={}, {}",
+                        self.path, key, e
+                    );
+                    Ok(false)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+struct TypedPendingItem<V> {
+    // 每次修改递增，flush完成后只移除没有再被修改过的项
+    seq: u64,
+
+    // None表示删除
+    value: Option<V>,
+}
+
+struct TypedCacheItem<V> {
+    id: Option<ObjectId>,
+    value: Option<V>,
+}
+
+struct StateTypedMapInner<V> {
+    store: TypedStateStore,
+    resolver: Mutex<StateConflictResolverRef<V>>,
+
+    // 已经和root_state同步过的值
+    cache: Mutex<HashMap<String, TypedCacheItem<V>>>,
+
+    // 还没有flush的修改
+    pending: Mutex<HashMap<String, TypedPendingItem<V>>>,
+    next_seq: AtomicU64,
+
+    flush_lock: AsyncMutex<()>,
+    auto_flush: AtomicBool,
+}
+
+// key为字符串，值为任意可编码类型的map
+#[derive(Clone)]
+pub struct StateTypedMap<V>(Arc<StateTypedMapInner<V>>);
+
+impl<V> StateTypedMap<V>
+where
+    V: CollectionCodec<V> + Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        global_state: GlobalStateOutputProcessorRef,
+        non: NONOutputProcessorRef,
+        path: impl Into<String>,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> Self {
+        let store = TypedStateStore::new(global_state, non, path.into(), target, dec_id);
+        let inner = StateTypedMapInner {
+            store,
+            resolver: Mutex::new(Arc::new(Box::new(StateLastWriteWins))),
+            cache: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            next_seq: AtomicU64::new(0),
+            flush_lock: AsyncMutex::new(()),
+            auto_flush: AtomicBool::new(false),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    pub fn new_with_stack(
+        stack: &UniCyfsStackRef,
+        category: GlobalStateCategory,
+        path: impl Into<String>,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> Self {
+        let global_state = match category {
+            GlobalStateCategory::RootState => stack.root_state().clone(),
+            GlobalStateCategory::LocalCache => stack.local_cache().clone(),
+        };
+
+        Self::new(
+            global_state,
+            stack.non_service().clone(),
+            path,
+            target,
+            dec_id,
+        )
+    }
+
+    pub fn set_resolver(&self, resolver: StateConflictResolverRef<V>) {
+        *self.0.resolver.lock().unwrap() = resolver;
+    }
+
+    pub fn path(&self) -> &str {
+        &self.0.store.path
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.0.pending.lock().unwrap().is_empty()
+    }
+
+    pub async fn get(&self, key: &str) -> BuckyResult<Option<V>> {
+        if let Some(item) = self.0.pending.lock().unwrap().get(key) {
+            return Ok(item.value.clone());
+        }
+
+        if let Some(item) = self.0.cache.lock().unwrap().get(key) {
+            return Ok(item.value.clone());
+        }
+
+        let ret = self.0.store.load_key::<V>(key).await?;
+        let value = ret.as_ref().map(|(_, v)| v.clone());
+
+        let item = match ret {
+            Some((id, value)) => TypedCacheItem {
+                id: Some(id),
+                value: Some(value),
+            },
+            None => TypedCacheItem {
+                id: None,
+                value: None,
+            },
+        };
+        self.0.cache.lock().unwrap().insert(key.to_owned(), item);
+
+        Ok(value)
+    }
+
+    pub fn set(&self, key: impl Into<String>, value: V) {
+        self.set_pending(key.into(), Some(value));
+    }
+
+    pub fn remove(&self, key: impl Into<String>) {
+        self.set_pending(key.into(), None);
+    }
+
+    fn set_pending(&self, key: String, value: Option<V>) {
+        let seq = self.0.next_seq.fetch_add(1, Ordering::SeqCst);
+        self.0
+            .pending
+            .lock()
+            .unwrap()
+            .insert(key, TypedPendingItem { seq, value });
+    }
+
+    // 丢弃所有没有flush的修改
+    pub fn discard(&self) {
+        self.0.pending.lock().unwrap().clear();
+    }
+
+    // 清空本地缓存，下次读取时从root_state重新加载
+    pub fn clear_cache(&self) {
+        self.0.cache.lock().unwrap().clear();
+    }
+
+    // 返回当前所有的key和值，包括还没有flush的修改
+    pub async fn list(&self) -> BuckyResult<Vec<(String, V)>> {
+        let op_env = self.0.store.stub.create_path_op_env().await?;
+        let ret = op_env.list(&self.0.store.path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => return Err(e),
+        };
+
+        let mut keys: Vec<String> = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Map((key, _)) => Some(key),
+                _ => None,
+            })
+            .collect();
+
+        for key in self.0.pending.lock().unwrap().keys() {
+            if !keys.contains(key) {
+                keys.push(key.to_owned());
+            }
+        }
+        keys.sort();
+
+        let mut result = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(value) = self.get(&key).await? {
+                result.push((key, value));
+            }
+        }
+
+        Ok(result)
+    }
+
+    // 提交所有的修改，返回提交的key的数量
+    pub async fn flush(&self) -> BuckyResult<usize> {
+        let _guard = self.0.flush_lock.lock().await;
+
+        let pending: Vec<(String, u64, Option<V>)> = self
+            .0
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.seq, v.value.clone()))
+            .collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let mut retry = 0;
+        let committed = loop {
+            match self.flush_once(&pending).await {
+                Ok(v) => break v,
+                Err(e) if retry < STATE_TYPED_FLUSH_RETRY => {
+                    warn!(
+                        "flush state typed map failed, now will retry! path={}, retry={}, {}",
+                        self.0.store.path, retry, e
+                    );
+                    retry += 1;
+                }
+                Err(e) => {
+                    error!(
+                        "flush state typed map failed! path={}, {}",
+                        self.0.store.path, e
+                    );
+                    return Err(e);
+                }
+            }
+        };
+
+        let count = committed.len();
+        {
+            let mut cache = self.0.cache.lock().unwrap();
+            let mut pending_list = self.0.pending.lock().unwrap();
+            for (key, seq, item) in committed {
+                match pending_list.get(&key) {
+                    Some(v) if v.seq == seq => {
+                        pending_list.remove(&key);
+                    }
+                    _ => {}
+                }
+                cache.insert(key, item);
+            }
+        }
+
+        info!(
+            "flush state typed map success! path={}, count={}",
+            self.0.store.path, count
+        );
+
+        Ok(count)
+    }
+
+    async fn flush_once(
+        &self,
+        pending: &[(String, u64, Option<V>)],
+    ) -> BuckyResult<Vec<(String, u64, TypedCacheItem<V>)>> {
+        let store = &self.0.store;
+
+        let mut values = Vec::with_capacity(pending.len());
+        for (key, _, value) in pending {
+            let id = match value {
+                Some(v) => Some(store.save_value(key, v).await?),
+                None => None,
+            };
+            values.push(id);
+        }
+
+        let op_env = store.stub.create_path_op_env().await?;
+        let ret = async {
+            let mut committed = Vec::with_capacity(pending.len());
+            for ((key, seq, value), id) in pending.iter().zip(values.into_iter()) {
+                let base = self.0.cache.lock().unwrap().get(key).map(|item| item.id.clone());
+                if store.write_key(&op_env, key, base, id.as_ref()).await? {
+                    let item = TypedCacheItem {
+                        id,
+                        value: value.clone(),
+                    };
+                    committed.push((key.clone(), *seq, item));
+                    continue;
+                }
+
+                let item = self.resolve(&op_env, key, value.as_ref()).await?;
+                committed.push((key.clone(), *seq, item));
+            }
+
+            Ok::<_, BuckyError>(committed)
+        }
+        .await;
+
+        match ret {
+            Ok(committed) => {
+                op_env.commit().await?;
+                Ok(committed)
+            }
+            Err(e) => {
+                let _ = op_env.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    // 远端已经被修改，读取远端的当前值后交给resolver处理
+    async fn resolve(
+        &self,
+        op_env: &PathOpEnvStub,
+        key: &str,
+        local: Option<&V>,
+    ) -> BuckyResult<TypedCacheItem<V>> {
+        let store = &self.0.store;
+
+        let remote_id = match op_env.get_by_key(&store.path, key).await {
+            Ok(v) => v,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let remote = match &remote_id {
+            Some(id) => Some(store.load_value::<V>(id).await?),
+            None => None,
+        };
+
+        let resolver = self.0.resolver.lock().unwrap().clone();
+        let value = match resolver.resolve(key, local, remote.as_ref()) {
+            StateConflictResolution::KeepLocal => local.cloned(),
+            StateConflictResolution::KeepRemote => {
+                info!("state key conflict, keep remote! path={}, key={}", store.path, key);
+                return Ok(TypedCacheItem {
+                    id: remote_id,
+                    value: remote,
+                });
+            }
+            StateConflictResolution::Merge(v) => Some(v),
+        };
+
+        let id = match &value {
+            Some(v) => Some(store.save_value(key, v).await?),
+            None => None,
+        };
+
+        // 基于刚读取到的远端值写入，同一个op_env内不会再冲突
+        if !store
+            .write_key(op_env, key, Some(remote_id), id.as_ref())
+            .await?
+        {
+            let msg = format!(
+                "write state key after resolve conflict failed! path={}, key={}",
+                store.path, key
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        Ok(TypedCacheItem { id, value })
+    }
+
+    pub fn start_flush(&self, dur: std::time::Duration) {
+        use async_std::prelude::*;
+
+        let ret = self
+            .0
+            .auto_flush
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire);
+        if ret.is_err() {
+            warn!("state typed map already in flushing state! path={}", self.path());
+            return;
+        }
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            let mut interval = async_std::stream::interval(dur);
+            while let Some(_) = interval.next().await {
+                if !this.0.auto_flush.load(Ordering::SeqCst) {
+                    warn!("state typed map auto flush stopped! path={}", this.path());
+                    break;
+                }
+
+                let _ = this.flush().await;
+            }
+        });
+    }
+
+    pub fn stop_flush(&self) {
+        if let Ok(_) =
+            self.0
+                .auto_flush
+                .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+        {
+            info!("stop state typed map auto flush! path={}", self.path());
+        }
+    }
+}
+
+struct StateCachedSetInner {
+    store: TypedStateStore,
+
+    cache: Mutex<Option<HashSet<ObjectId>>>,
+
+    // true为插入，false为删除
+    pending: Mutex<HashMap<ObjectId, (u64, bool)>>,
+    next_seq: AtomicU64,
+
+    flush_lock: AsyncMutex<()>,
+    auto_flush: AtomicBool,
+}
+
+// 带本地缓存的set，插入和删除可以交换顺序，所以flush时不需要处理冲突
+#[derive(Clone)]
+pub struct StateCachedSet(Arc<StateCachedSetInner>);
+
+impl StateCachedSet {
+    pub fn new(
+        global_state: GlobalStateOutputProcessorRef,
+        non: NONOutputProcessorRef,
+        path: impl Into<String>,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> Self {
+        let store = TypedStateStore::new(global_state, non, path.into(), target, dec_id);
+        let inner = StateCachedSetInner {
+            store,
+            cache: Mutex::new(None),
+            pending: Mutex::new(HashMap::new()),
+            next_seq: AtomicU64::new(0),
+            flush_lock: AsyncMutex::new(()),
+            auto_flush: AtomicBool::new(false),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    pub fn new_with_stack(
+        stack: &UniCyfsStackRef,
+        category: GlobalStateCategory,
+        path: impl Into<String>,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> Self {
+        let global_state = match category {
+            GlobalStateCategory::RootState => stack.root_state().clone(),
+            GlobalStateCategory::LocalCache => stack.local_cache().clone(),
+        };
+
+        Self::new(
+            global_state,
+            stack.non_service().clone(),
+            path,
+            target,
+            dec_id,
+        )
+    }
+
+    pub fn path(&self) -> &str {
+        &self.0.store.path
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.0.pending.lock().unwrap().is_empty()
+    }
+
+    // 从root_state重新加载，没有flush的修改会保留
+    pub async fn reload(&self) -> BuckyResult<()> {
+        let op_env = self.0.store.stub.create_path_op_env().await?;
+        let ret = op_env.list(&self.0.store.path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!(
+                    "load state cached set failed! path={}, {}",
+                    self.0.store.path, e
+                );
+                return Err(e);
+            }
+        };
+
+        let set = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Set(id) => Some(id),
+                _ => None,
+            })
+            .collect();
+        *self.0.cache.lock().unwrap() = Some(set);
+
+        Ok(())
+    }
+
+    async fn check_load(&self) -> BuckyResult<()> {
+        if self.0.cache.lock().unwrap().is_none() {
+            self.reload().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn contains(&self, object_id: &ObjectId) -> BuckyResult<bool> {
+        if let Some((_, insert)) = self.0.pending.lock().unwrap().get(object_id) {
+            return Ok(*insert);
+        }
+
+        self.check_load().await?;
+        let cache = self.0.cache.lock().unwrap();
+        Ok(cache.as_ref().unwrap().contains(object_id))
+    }
+
+    pub async fn list(&self) -> BuckyResult<Vec<ObjectId>> {
+        self.check_load().await?;
+
+        let mut set = self.0.cache.lock().unwrap().clone().unwrap();
+        for (id, (_, insert)) in self.0.pending.lock().unwrap().iter() {
+            if *insert {
+                set.insert(id.clone());
+            } else {
+                set.remove(id);
+            }
+        }
+
+        Ok(set.into_iter().collect())
+    }
+
+    pub fn insert(&self, object_id: &ObjectId) {
+        self.set_pending(object_id, true);
+    }
+
+    pub fn remove(&self, object_id: &ObjectId) {
+        self.set_pending(object_id, false);
+    }
+
+    fn set_pending(&self, object_id: &ObjectId, insert: bool) {
+        let seq = self.0.next_seq.fetch_add(1, Ordering::SeqCst);
+        self.0
+            .pending
+            .lock()
+            .unwrap()
+            .insert(object_id.clone(), (seq, insert));
+    }
+
+    pub fn discard(&self) {
+        self.0.pending.lock().unwrap().clear();
+    }
+
+    pub async fn flush(&self) -> BuckyResult<usize> {
+        let _guard = self.0.flush_lock.lock().await;
+
+        let pending: Vec<(ObjectId, u64, bool)> = self
+            .0
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, (seq, insert))| (id.clone(), *seq, *insert))
+            .collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let path = &self.0.store.path;
+        let mut retry = 0;
+        loop {
+            let op_env = self.0.store.stub.create_path_op_env().await?;
+            let ret = async {
+                for (id, _, insert) in &pending {
+                    if *insert {
+                        op_env.insert(path, id).await?;
+                    } else {
+                        match op_env.remove(path, id).await {
+                            Ok(_) => {}
+                            Err(e) if e.code() == BuckyErrorCode::NotFound => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+
+                op_env.update().await?;
+                Ok(())
+            }
+            .await;
+
+            let ret = match ret {
+                Ok(_) => op_env.commit().await.map(|_| ()),
+                Err(e) => {
+                    let _ = op_env.abort().await;
+                    Err(e)
+                }
+            };
+
+            match ret {
+                Ok(_) => break,
+                Err(e) if retry < STATE_TYPED_FLUSH_RETRY => {
+                    warn!(
+                        "flush state cached set failed, now will retry! path={}, retry={}, {}",
+                        path, retry, e
+                    );
+                    retry += 1;
+                }
+                Err(e) => {
+                    error!("flush state cached set failed! path={}, {}", path, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        {
+            let mut cache = self.0.cache.lock().unwrap();
+            let mut pending_list = self.0.pending.lock().unwrap();
+            for (id, seq, insert) in &pending {
+                if let Some(set) = cache.as_mut() {
+                    if *insert {
+                        set.insert(id.clone());
+                    } else {
+                        set.remove(id);
+                    }
+                }
+
+                match pending_list.get(id) {
+                    Some((v, _)) if v == seq => {
+                        pending_list.remove(id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        info!(
+            "flush state cached set success! path={}, count={}",
+            path,
+            pending.len()
+        );
+
+        Ok(pending.len())
+    }
+
+    pub fn start_flush(&self, dur: std::time::Duration) {
+        use async_std::prelude::*;
+
+        let ret = self
+            .0
+            .auto_flush
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire);
+        if ret.is_err() {
+            warn!("state cached set already in flushing state! path={}", self.path());
+            return;
+        }
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            let mut interval = async_std::stream::interval(dur);
+            while let Some(_) = interval.next().await {
+                if !this.0.auto_flush.load(Ordering::SeqCst) {
+                    warn!("state cached set auto flush stopped! path={}", this.path());
+                    break;
+                }
+
+                let _ = this.flush().await;
+            }
+        });
+    }
+
+    pub fn stop_flush(&self) {
+        if let Ok(_) =
+            self.0
+                .auto_flush
+                .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+        {
+            info!("stop state cached set auto flush! path={}", self.path());
+        }
+    }
+}
+
+// 单个文档，保存在path的父路径下，key为path的最后一段
+pub struct StateTypedDocument<T> {
+    map: StateTypedMap<T>,
+    key: String,
+}
+
+impl<T> StateTypedDocument<T>
+where
+    T: CollectionCodec<T> + Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        global_state: GlobalStateOutputProcessorRef,
+        non: NONOutputProcessorRef,
+        path: &str,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> BuckyResult<Self> {
+        let (parent, key) = Self::split_path(path)?;
+        let map = StateTypedMap::new(global_state, non, parent, target, dec_id);
+
+        Ok(Self { map, key })
+    }
+
+    pub fn new_with_stack(
+        stack: &UniCyfsStackRef,
+        category: GlobalStateCategory,
+        path: &str,
+        target: Option<ObjectId>,
+        dec_id: Option<ObjectId>,
+    ) -> BuckyResult<Self> {
+        let (parent, key) = Self::split_path(path)?;
+        let map = StateTypedMap::new_with_stack(stack, category, parent, target, dec_id);
+
+        Ok(Self { map, key })
+    }
+
+    fn split_path(path: &str) -> BuckyResult<(String, String)> {
+        let path = path.trim_end_matches('/');
+        match path.rsplit_once('/') {
+            Some((parent, key)) if !key.is_empty() => {
+                let parent = if parent.is_empty() { "/" } else { parent };
+                Ok((parent.to_owned(), key.to_owned()))
+            }
+            _ => {
+                let msg = format!("invalid state document path: {}", path);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg))
+            }
+        }
+    }
+
+    pub fn set_resolver(&self, resolver: StateConflictResolverRef<T>) {
+        self.map.set_resolver(resolver)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.map.is_dirty()
+    }
+
+    pub async fn get(&self) -> BuckyResult<Option<T>> {
+        self.map.get(&self.key).await
+    }
+
+    pub fn set(&self, doc: T) {
+        self.map.set(self.key.clone(), doc)
+    }
+
+    pub fn delete(&self) {
+        self.map.remove(self.key.clone())
+    }
+
+    // 在当前值(不存在时使用default)的基础上修改
+    pub async fn update<F>(&self, f: F) -> BuckyResult<T>
+    where
+        T: Default,
+        F: FnOnce(&mut T),
+    {
+        let mut doc = self.get().await?.unwrap_or_default();
+        f(&mut doc);
+        self.set(doc.clone());
+
+        Ok(doc)
+    }
+
+    pub async fn reload(&self) -> BuckyResult<Option<T>> {
+        self.map.0.cache.lock().unwrap().remove(&self.key);
+        self.map.get(&self.key).await
+    }
+
+    pub async fn flush(&self) -> BuckyResult<bool> {
+        Ok(self.map.flush().await? > 0)
+    }
+
+    pub fn start_flush(&self, dur: std::time::Duration) {
+        self.map.start_flush(dur)
+    }
+
+    pub fn stop_flush(&self) {
+        self.map.stop_flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 所有的值对象都保存在内存里
+    #[derive(Clone, Default)]
+    struct MemoryNON {
+        objects: Arc<Mutex<HashMap<ObjectId, Vec<u8>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl NONOutputProcessor for MemoryNON {
+        async fn put_object(
+            &self,
+            req: NONPutObjectOutputRequest,
+        ) -> BuckyResult<NONPutObjectOutputResponse> {
+            self.objects
+                .lock()
+                .unwrap()
+                .insert(req.object.object_id, req.object.object_raw);
+
+            Ok(NONPutObjectOutputResponse {
+                result: NONPutObjectResult::Accept,
+                object_update_time: None,
+                object_expires_time: None,
+            })
+        }
+
+        async fn get_object(
+            &self,
+            req: NONGetObjectOutputRequest,
+        ) -> BuckyResult<NONGetObjectOutputResponse> {
+            let object_raw = self.objects.lock().unwrap().get(&req.object_id).cloned();
+            match object_raw {
+                Some(object_raw) => Ok(NONGetObjectOutputResponse {
+                    object_update_time: None,
+                    object_expires_time: None,
+                    object: NONObjectInfo::new(req.object_id, object_raw, None),
+                    attr: None,
+                }),
+                None => Err(BuckyError::from(BuckyErrorCode::NotFound)),
+            }
+        }
+
+        async fn post_object(
+            &self,
+            _req: NONPostObjectOutputRequest,
+        ) -> BuckyResult<NONPostObjectOutputResponse> {
+            unreachable!();
+        }
+
+        async fn select_object(
+            &self,
+            _req: NONSelectObjectOutputRequest,
+        ) -> BuckyResult<NONSelectObjectOutputResponse> {
+            unreachable!();
+        }
+
+        async fn delete_object(
+            &self,
+            _req: NONDeleteObjectOutputRequest,
+        ) -> BuckyResult<NONDeleteObjectOutputResponse> {
+            unreachable!();
+        }
+    }
+
+    // 只有一个path的root_state，op_env在创建时复制一份，commit时整体替换
+    #[derive(Clone, Default)]
+    struct MemoryState {
+        map: Arc<Mutex<HashMap<String, ObjectId>>>,
+    }
+
+    struct MemoryOpEnv {
+        state: MemoryState,
+        map: Mutex<HashMap<String, ObjectId>>,
+    }
+
+    #[async_trait::async_trait]
+    impl GlobalStateOutputProcessor for MemoryState {
+        fn get_category(&self) -> GlobalStateCategory {
+            GlobalStateCategory::RootState
+        }
+
+        async fn get_current_root(
+            &self,
+            _req: RootStateGetCurrentRootOutputRequest,
+        ) -> BuckyResult<RootStateGetCurrentRootOutputResponse> {
+            unreachable!();
+        }
+
+        async fn create_op_env(
+            &self,
+            _req: RootStateCreateOpEnvOutputRequest,
+        ) -> BuckyResult<OpEnvOutputProcessorRef> {
+            let op_env = MemoryOpEnv {
+                state: self.clone(),
+                map: Mutex::new(self.map.lock().unwrap().clone()),
+            };
+
+            Ok(Arc::new(Box::new(op_env)))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl OpEnvOutputProcessor for MemoryOpEnv {
+        fn get_sid(&self) -> u64 {
+            1
+        }
+
+        fn get_category(&self) -> GlobalStateCategory {
+            GlobalStateCategory::RootState
+        }
+
+        async fn load(&self, _req: OpEnvLoadOutputRequest) -> BuckyResult<()> {
+            unreachable!();
+        }
+
+        async fn load_by_path(&self, _req: OpEnvLoadByPathOutputRequest) -> BuckyResult<()> {
+            unreachable!();
+        }
+
+        async fn create_new(&self, _req: OpEnvCreateNewOutputRequest) -> BuckyResult<()> {
+            unreachable!();
+        }
+
+        async fn get_current_root(
+            &self,
+            _req: OpEnvGetCurrentRootOutputRequest,
+        ) -> BuckyResult<OpEnvGetCurrentRootOutputResponse> {
+            unreachable!();
+        }
+
+        async fn lock(&self, _req: OpEnvLockOutputRequest) -> BuckyResult<()> {
+            unreachable!();
+        }
+
+        async fn commit(
+            &self,
+            _req: OpEnvCommitOutputRequest,
+        ) -> BuckyResult<OpEnvCommitOutputResponse> {
+            *self.state.map.lock().unwrap() = self.map.lock().unwrap().clone();
+
+            Ok(OpEnvCommitOutputResponse {
+                root: ObjectId::default(),
+                revision: 0,
+                dec_root: ObjectId::default(),
+            })
+        }
+
+        async fn abort(&self, _req: OpEnvAbortOutputRequest) -> BuckyResult<()> {
+            Ok(())
+        }
+
+        async fn metadata(
+            &self,
+            _req: OpEnvMetadataOutputRequest,
+        ) -> BuckyResult<OpEnvMetadataOutputResponse> {
+            unreachable!();
+        }
+
+        async fn get_by_key(
+            &self,
+            req: OpEnvGetByKeyOutputRequest,
+        ) -> BuckyResult<OpEnvGetByKeyOutputResponse> {
+            let value = self.map.lock().unwrap().get(&req.key).cloned();
+            Ok(OpEnvGetByKeyOutputResponse { value })
+        }
+
+        async fn insert_with_key(&self, req: OpEnvInsertWithKeyOutputRequest) -> BuckyResult<()> {
+            let mut map = self.map.lock().unwrap();
+            if map.contains_key(&req.key) {
+                return Err(BuckyError::from(BuckyErrorCode::AlreadyExists));
+            }
+
+            map.insert(req.key, req.value);
+            Ok(())
+        }
+
+        async fn set_with_key(
+            &self,
+            req: OpEnvSetWithKeyOutputRequest,
+        ) -> BuckyResult<OpEnvSetWithKeyOutputResponse> {
+            let mut map = self.map.lock().unwrap();
+            let current = map.get(&req.key).cloned();
+            if current.is_none() && !req.auto_insert {
+                return Err(BuckyError::from(BuckyErrorCode::NotFound));
+            }
+            if req.prev_value.is_some() && req.prev_value != current {
+                return Err(BuckyError::from(BuckyErrorCode::Unmatch));
+            }
+
+            map.insert(req.key, req.value);
+            Ok(OpEnvSetWithKeyOutputResponse {
+                prev_value: current,
+            })
+        }
+
+        async fn remove_with_key(
+            &self,
+            req: OpEnvRemoveWithKeyOutputRequest,
+        ) -> BuckyResult<OpEnvRemoveWithKeyOutputResponse> {
+            let mut map = self.map.lock().unwrap();
+            if req.prev_value.is_some() && req.prev_value != map.get(&req.key).cloned() {
+                return Err(BuckyError::from(BuckyErrorCode::Unmatch));
+            }
+
+            let value = map.remove(&req.key);
+            Ok(OpEnvRemoveWithKeyOutputResponse { value })
+        }
+
+        async fn contains(
+            &self,
+            _req: OpEnvContainsOutputRequest,
+        ) -> BuckyResult<OpEnvContainsOutputResponse> {
+            unreachable!();
+        }
+
+        async fn insert(
+            &self,
+            _req: OpEnvInsertOutputRequest,
+        ) -> BuckyResult<OpEnvInsertOutputResponse> {
+            unreachable!();
+        }
+
+        async fn remove(
+            &self,
+            _req: OpEnvRemoveOutputRequest,
+        ) -> BuckyResult<OpEnvRemoveOutputResponse> {
+            unreachable!();
+        }
+
+        async fn next(&self, _req: OpEnvNextOutputRequest) -> BuckyResult<OpEnvNextOutputResponse> {
+            unreachable!();
+        }
+
+        async fn reset(&self, _req: OpEnvResetOutputRequest) -> BuckyResult<()> {
+            unreachable!();
+        }
+
+        async fn list(&self, _req: OpEnvListOutputRequest) -> BuckyResult<OpEnvListOutputResponse> {
+            let list = self
+                .map
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(k, v)| ObjectMapContentItem::Map((k.clone(), v.clone())))
+                .collect();
+
+            Ok(OpEnvListOutputResponse { list })
+        }
+    }
+
+    struct KeepRemote;
+
+    impl StateConflictResolver<String> for KeepRemote {
+        fn resolve(
+            &self,
+            _key: &str,
+            _local: Option<&String>,
+            _remote: Option<&String>,
+        ) -> StateConflictResolution<String> {
+            StateConflictResolution::KeepRemote
+        }
+    }
+
+    fn new_map(state: &MemoryState, non: &MemoryNON) -> StateTypedMap<String> {
+        StateTypedMap::new(
+            Arc::new(Box::new(state.clone())),
+            Arc::new(Box::new(non.clone())),
+            "/test/typed/",
+            None,
+            None,
+        )
+    }
+
+    #[async_std::test]
+    async fn test_pending() {
+        let map = new_map(&MemoryState::default(), &MemoryNON::default());
+        assert_eq!(map.path(), "/test/typed/");
+        assert_eq!(map.0.store.full_path("a"), "/test/typed/a");
+
+        // 没有flush之前读取的是本地的修改
+        assert!(!map.is_dirty());
+        map.set("a", "1".to_owned());
+        map.set("b", "2".to_owned());
+        assert!(map.is_dirty());
+        assert_eq!(map.get("a").await.unwrap(), Some("1".to_owned()));
+
+        map.remove("a");
+        assert_eq!(map.get("a").await.unwrap(), None);
+        assert_eq!(
+            map.list().await.unwrap(),
+            vec![("b".to_owned(), "2".to_owned())]
+        );
+
+        map.discard();
+        assert!(!map.is_dirty());
+        assert_eq!(map.get("b").await.unwrap(), None);
+    }
+
+    #[async_std::test]
+    async fn test_flush() {
+        let state = MemoryState::default();
+        let non = MemoryNON::default();
+
+        let map = new_map(&state, &non);
+        map.set("a", "1".to_owned());
+        map.set("b", "2".to_owned());
+        assert_eq!(map.flush().await.unwrap(), 2);
+        assert!(!map.is_dirty());
+        assert_eq!(map.flush().await.unwrap(), 0);
+        assert_eq!(state.map.lock().unwrap().len(), 2);
+
+        // 其它实例从root_state加载
+        let other = new_map(&state, &non);
+        assert_eq!(other.get("a").await.unwrap(), Some("1".to_owned()));
+        assert_eq!(other.get("c").await.unwrap(), None);
+
+        map.set("a", "3".to_owned());
+        map.remove("b");
+        assert_eq!(map.flush().await.unwrap(), 2);
+        assert!(!state.map.lock().unwrap().contains_key("b"));
+
+        // 缓存的值不会自动更新，清空后重新加载
+        assert_eq!(other.get("a").await.unwrap(), Some("1".to_owned()));
+        other.clear_cache();
+        assert_eq!(other.get("a").await.unwrap(), Some("3".to_owned()));
+        assert_eq!(
+            other.list().await.unwrap(),
+            vec![("a".to_owned(), "3".to_owned())]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_conflict() {
+        let state = MemoryState::default();
+        let non = MemoryNON::default();
+
+        let map1 = new_map(&state, &non);
+        let map2 = new_map(&state, &non);
+        map1.set("a", "1".to_owned());
+        map1.flush().await.unwrap();
+        assert_eq!(map2.get("a").await.unwrap(), Some("1".to_owned()));
+
+        // 默认后写入的覆盖先写入的
+        map1.set("a", "2".to_owned());
+        map1.flush().await.unwrap();
+        map2.set("a", "3".to_owned());
+        assert_eq!(map2.flush().await.unwrap(), 1);
+        map1.clear_cache();
+        assert_eq!(map1.get("a").await.unwrap(), Some("3".to_owned()));
+
+        // 保留远端的值，本地的修改被丢弃
+        map2.set_resolver(Arc::new(Box::new(KeepRemote)));
+        map1.set("a", "4".to_owned());
+        map1.flush().await.unwrap();
+        map2.set("a", "5".to_owned());
+        assert_eq!(map2.flush().await.unwrap(), 1);
+        assert!(!map2.is_dirty());
+        assert_eq!(map2.get("a").await.unwrap(), Some("4".to_owned()));
+
+        // 远端插入了本地认为不存在的key
+        map1.set("b", "6".to_owned());
+        assert_eq!(map2.get("b").await.unwrap(), None);
+        map1.flush().await.unwrap();
+        map2.set("b", "7".to_owned());
+        map2.flush().await.unwrap();
+        assert_eq!(map2.get("b").await.unwrap(), Some("6".to_owned()));
+    }
+
+    #[async_std::test]
+    async fn test_document() {
+        let state = MemoryState::default();
+        let non = MemoryNON::default();
+
+        let ret = StateTypedDocument::<String>::split_path("/test/doc/");
+        assert_eq!(ret.unwrap(), ("/test".to_owned(), "doc".to_owned()));
+        let ret = StateTypedDocument::<String>::split_path("/doc");
+        assert_eq!(ret.unwrap(), ("/".to_owned(), "doc".to_owned()));
+        assert!(StateTypedDocument::<String>::split_path("/").is_err());
+
+        let doc = StateTypedDocument::<String>::new(
+            Arc::new(Box::new(state.clone())),
+            Arc::new(Box::new(non.clone())),
+            "/test/doc",
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(doc.get().await.unwrap(), None);
+        let value = doc.update(|v| v.push_str("abc")).await.unwrap();
+        assert_eq!(value, "abc");
+        assert!(doc.flush().await.unwrap());
+        assert!(!doc.flush().await.unwrap());
+        assert!(state.map.lock().unwrap().contains_key("doc"));
+
+        doc.delete();
+        assert_eq!(doc.get().await.unwrap(), None);
+        assert!(doc.flush().await.unwrap());
+        assert_eq!(doc.reload().await.unwrap(), None);
+    }
+}
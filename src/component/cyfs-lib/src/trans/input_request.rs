@@ -1,9 +1,10 @@
 use super::output_request::*;
-use crate::{NDNInputRequestCommon, TransTaskControlAction, TransTaskInfo, TransTaskStatus};
+use crate::{NDNInputRequestCommon, PageInfo, PageRequest, TransTaskControlAction, TransTaskInfo, TransTaskStatus};
 use cyfs_base::{*};
 use cyfs_core::TransContext;
 use cyfs_util::cache::FileDirRef;
 
+use async_std::io::Read;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -147,11 +148,33 @@ pub struct TransPublishFileInputRequest {
     pub dirs: Option<Vec<FileDirRef>>,
 }
 
+pub struct TransPublishStreamInputRequest {
+    pub common: NDNInputRequestCommon,
+    pub owner: ObjectId,
+    pub chunk_size: u32,
+    pub access: Option<AccessString>,
+    pub data: Box<dyn Read + Unpin + Send + Sync + 'static>,
+}
+
+impl std::fmt::Display for TransPublishStreamInputRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "common: {}", self.common)?;
+        write!(f, ", owner: {}", self.owner)?;
+        write!(f, ", chunk_size: {}", self.chunk_size)?;
+        write!(f, ", access: {:?}", self.access)
+    }
+}
+
+pub type TransPublishStreamInputResponse = TransPublishStreamOutputResponse;
+
 #[derive(Debug)]
 pub struct TransQueryTasksInputRequest {
     pub common: NDNInputRequestCommon,
     pub task_status: Option<TransTaskStatus>,
     pub range: Option<(u64, u32)>,
+
+    // cursor based pagination, range is ignored if specified
+    pub page: Option<PageRequest>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -166,6 +189,7 @@ pub struct TransCreateTaskInputResponse {
 
 pub struct TransQueryTasksInputResponse {
     pub task_list: Vec<TransTaskInfo>,
+    pub page: PageInfo,
 }
 
 // get task group state
@@ -189,3 +213,15 @@ pub struct TransControlTaskGroupInputRequest {
 }
 
 pub type TransControlTaskGroupInputResponse = TransControlTaskGroupOutputResponse;
+
+// verify local file
+#[derive(Debug)]
+pub struct TransVerifyFileInputRequest {
+    pub common: NDNInputRequestCommon,
+    pub file_id: ObjectId,
+    pub local_path: PathBuf,
+    pub repair: bool,
+    pub device_list: Vec<DeviceId>,
+}
+
+pub type TransVerifyFileInputResponse = TransVerifyFileOutputResponse;
@@ -2,6 +2,7 @@ use crate::*;
 use cyfs_base::*;
 
 use cyfs_bdt::{NdnTaskControlState, NdnTaskState};
+use async_std::io::Read;
 use cyfs_core::TransContext;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -15,6 +16,49 @@ pub struct TransTaskOnAirState {
     pub upload_speed: u32,
 }
 
+// 从单个源设备下载的累计流量
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransTaskSourceTraffic {
+    pub source: DeviceId,
+
+    // 收到的所有数据，包括损坏和重复的部分
+    pub received: u64,
+
+    // 写入的有效数据
+    pub downloaded: u64,
+
+    // 从收到第一个piece到最后一个piece的平均速度，bytes/s
+    pub average_speed: u32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TransTaskTrafficState {
+    pub received: u64,
+    pub downloaded: u64,
+    pub average_speed: u32,
+    pub sources: Vec<TransTaskSourceTraffic>,
+}
+
+impl From<&cyfs_bdt::DownloadTrafficStat> for TransTaskTrafficState {
+    fn from(stat: &cyfs_bdt::DownloadTrafficStat) -> Self {
+        Self {
+            received: stat.received(),
+            downloaded: stat.downloaded(),
+            average_speed: stat.average_speed(),
+            sources: stat
+                .sources
+                .iter()
+                .map(|source| TransTaskSourceTraffic {
+                    source: source.target.clone(),
+                    received: source.received,
+                    downloaded: source.downloaded,
+                    average_speed: source.average_speed(),
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TransTaskState {
     Pending,
@@ -177,6 +221,10 @@ pub struct TransGetTaskStateOutputRequest {
 pub struct TransGetTaskStateOutputResponse {
     pub state: TransTaskState,
     pub group: Option<String>,
+
+    // 当前下载会话的累计流量统计
+    #[serde(default)]
+    pub traffic: TransTaskTrafficState,
 }
 
 // query tasks
@@ -185,10 +233,14 @@ pub struct TransQueryTasksOutputRequest {
     pub common: NDNOutputRequestCommon,
     pub task_status: Option<TransTaskStatus>,
     pub range: Option<(u64, u32)>,
+
+    // cursor based pagination, range is ignored if specified
+    pub page: Option<PageRequest>,
 }
 
 pub struct TransQueryTasksOutputResponse {
     pub task_list: Vec<TransTaskInfo>,
+    pub page: PageInfo,
 }
 
 // publish file
@@ -222,6 +274,38 @@ pub struct TransPublishFileOutputResponse {
     pub file_id: ObjectId,
 }
 
+// publish file from stream
+pub struct TransPublishStreamOutputRequest {
+    pub common: NDNOutputRequestCommon,
+
+    // The owner of the file object to be generated
+    pub owner: ObjectId,
+
+    // The stream will be split into chunks of this size, the last chunk may be smaller
+    pub chunk_size: u32,
+
+    pub access: Option<AccessString>,
+
+    // The data stream with unknown length, the file object is generated when the stream ends
+    pub data: Box<dyn Read + Unpin + Send + Sync + 'static>,
+}
+
+impl std::fmt::Display for TransPublishStreamOutputRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "common: {}", self.common)?;
+        write!(f, ", owner: {}", self.owner)?;
+        write!(f, ", chunk_size: {}", self.chunk_size)?;
+        write!(f, ", access: {:?}", self.access)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TransPublishStreamOutputResponse {
+    pub file_id: ObjectId,
+    pub file: File,
+    pub chunk_list: Vec<ChunkId>,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[derive(serde_with::SerializeDisplay, serde_with::DeserializeFromStr)]
 pub enum TransTaskGroupType {
@@ -279,7 +363,11 @@ pub struct TransGetTaskGroupStateOutputResponse {
     pub speed: Option<u32>,
     pub cur_speed: u32,
     pub history_speed: u32, 
-    pub transfered: u64
+    pub transfered: u64,
+
+    // 组内所有任务的累计流量统计
+    #[serde(default)]
+    pub traffic: TransTaskTrafficState,
 }
 
 // control task group
@@ -305,6 +393,71 @@ pub struct TransControlTaskGroupOutputResponse {
     pub control_state: NdnTaskControlState,
 }
 
+// verify local file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransVerifyFileOutputRequest {
+    pub common: NDNOutputRequestCommon,
+
+    // The object_id of the file object, must be exists in NOC
+    pub file_id: ObjectId,
+
+    // The local file full path to be verified
+    pub local_path: PathBuf,
+
+    // If set, the mismatched chunks will be re-downloaded and written back to the same ranges of the local file
+    pub repair: bool,
+
+    // The source devices used for repair, if empty then will resolve the ood of the file's owner
+    pub device_list: Vec<DeviceId>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransVerifyChunkState {
+    Valid,
+    Mismatch,
+
+    // The local file is too short to contain the chunk
+    Missing,
+
+    Repaired,
+    RepairFailed,
+}
+
+impl TransVerifyChunkState {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Valid | Self::Repaired => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransVerifyChunkInfo {
+    pub index: u32,
+    pub chunk_id: ChunkId,
+
+    // The offset of the chunk in the file
+    pub offset: u64,
+    pub state: TransVerifyChunkState,
+
+    // Error info when verify or repair failed
+    pub msg: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransVerifyFileOutputResponse {
+    pub file_id: ObjectId,
+    pub file_len: u64,
+
+    // The local file len after verify(and repair)
+    pub local_len: u64,
+
+    // All chunks are valid or repaired, and the local file len matched
+    pub valid: bool,
+    pub chunks: Vec<TransVerifyChunkInfo>,
+}
+
 
 #[cfg(test)]
 mod test {
@@ -329,6 +482,7 @@ mod test {
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             group_type: TransTaskGroupType::Download,
             group: "test".to_owned(),
@@ -337,5 +491,52 @@ mod test {
 
         let s = serde_json::to_string(&req).unwrap();
         println!("{}", s);
+
+        let req = TransVerifyFileOutputRequest {
+            common: req.common,
+            file_id: ObjectId::default(),
+            local_path: PathBuf::from("/tmp/test"),
+            repair: true,
+            device_list: vec![],
+        };
+        let s = serde_json::to_string(&req).unwrap();
+        println!("{}", s);
+
+        let value: serde_json::Value = serde_json::from_str(&s).unwrap();
+        assert_eq!(value["local_path"], "/tmp/test");
+        assert_eq!(value["repair"], true);
+
+        assert!(TransVerifyChunkState::Repaired.is_valid());
+        assert!(!TransVerifyChunkState::Missing.is_valid());
+    }
+
+    #[test]
+    fn test_traffic() {
+        let source = DeviceId::default();
+        let mut stat = cyfs_bdt::DownloadTrafficStat::default();
+        stat.on_recv(&source, 1000, true, 1_000_000);
+        stat.on_recv(&source, 1000, false, 2_000_000);
+
+        let traffic = TransTaskTrafficState::from(&stat);
+        assert_eq!(traffic.received, 2000);
+        assert_eq!(traffic.downloaded, 1000);
+        assert_eq!(traffic.average_speed, 1000);
+        assert_eq!(traffic.sources.len(), 1);
+        assert_eq!(traffic.sources[0].source, source);
+        assert_eq!(traffic.sources[0].average_speed, 1000);
+
+        // 旧版本协议栈的应答里没有traffic字段
+        let resp = TransGetTaskStateOutputResponse {
+            state: TransTaskState::Pending,
+            group: None,
+            traffic,
+        };
+        let mut value = serde_json::to_value(&resp).unwrap();
+        assert_eq!(value["traffic"]["received"], 2000);
+
+        value.as_object_mut().unwrap().remove("traffic");
+        let resp: TransGetTaskStateOutputResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(resp.traffic.received, 0);
+        assert!(resp.traffic.sources.is_empty());
     }
 }
\ No newline at end of file
@@ -23,6 +23,10 @@ pub trait TransOutputProcessor: Send + Sync {
         &self,
         req: TransPublishFileOutputRequest,
     ) -> BuckyResult<TransPublishFileOutputResponse>;
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamOutputRequest,
+    ) -> BuckyResult<TransPublishStreamOutputResponse>;
 
     // task group
     async fn get_task_group_state(
@@ -33,6 +37,12 @@ pub trait TransOutputProcessor: Send + Sync {
         &self,
         req: TransControlTaskGroupOutputRequest,
     ) -> BuckyResult<TransControlTaskGroupOutputResponse>;
+
+    // verify local file
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileOutputRequest,
+    ) -> BuckyResult<TransVerifyFileOutputResponse>;
 }
 
 pub type TransOutputProcessorRef = Arc<dyn TransOutputProcessor>;
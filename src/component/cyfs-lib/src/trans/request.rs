@@ -19,8 +19,14 @@ pub type TransQueryTasksResponse = TransQueryTasksOutputResponse;
 pub type TransPublishFileRequest = TransPublishFileOutputRequest;
 pub type TransPublishFileResponse = TransPublishFileOutputResponse;
 
+pub type TransPublishStreamRequest = TransPublishStreamOutputRequest;
+pub type TransPublishStreamResponse = TransPublishStreamOutputResponse;
+
 pub type TransGetTaskGroupStateRequest = TransGetTaskGroupStateOutputRequest;
 pub type TransGetTaskGroupStateResponse = TransGetTaskGroupStateOutputResponse;
 
 pub type TransControlTaskGroupRequest = TransControlTaskGroupOutputRequest;
-pub type TransControlTaskGroupResponse = TransControlTaskGroupOutputResponse;
\ No newline at end of file
+pub type TransControlTaskGroupResponse = TransControlTaskGroupOutputResponse;
+
+pub type TransVerifyFileRequest = TransVerifyFileOutputRequest;
+pub type TransVerifyFileResponse = TransVerifyFileOutputResponse;
\ No newline at end of file
@@ -373,6 +373,30 @@ impl JsonCodec<TransPublishFileInputRequest> for TransPublishFileInputRequest {
 impl JsonCodecAutoWithSerde for TransPublishFileOutputResponse {}
 impl JsonCodecAutoWithSerde for TransPublishFileInputResponse {}
 
+impl JsonCodec<TransPublishStreamOutputResponse> for TransPublishStreamOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "file_id", &self.file_id);
+        JsonCodecHelper::encode_string_field(&mut obj, "file", &self.file.to_hex().unwrap());
+        JsonCodecHelper::encode_str_array_field(&mut obj, "chunk_list", &self.chunk_list);
+
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<Self> {
+        let file = File::clone_from_hex(
+            JsonCodecHelper::decode_string_field::<String>(obj, "file")?.as_str(),
+            &mut Vec::new(),
+        )?;
+
+        Ok(Self {
+            file_id: JsonCodecHelper::decode_string_field(obj, "file_id")?,
+            file,
+            chunk_list: JsonCodecHelper::decode_str_array_field(obj, "chunk_list")?,
+        })
+    }
+}
+
 impl JsonCodecAutoWithSerde for TransCreateTaskOutputResponse {}
 impl JsonCodecAutoWithSerde for TransCreateTaskInputResponse {}
 
@@ -412,12 +436,14 @@ impl JsonCodec<TransQueryTasksOutputResponse> for TransQueryTasksOutputResponse
     fn encode_json(&self) -> Map<String, Value> {
         let mut obj = Map::new();
         JsonCodecHelper::encode_as_list(&mut obj, "task_list", &self.task_list);
+        self.page.encode_json_fields(&mut obj);
         obj
     }
 
     fn decode_json(obj: &Map<String, Value>) -> BuckyResult<TransQueryTasksOutputResponse> {
         let task_list = JsonCodecHelper::decode_array_field(obj, "task_list")?;
-        Ok(TransQueryTasksOutputResponse { task_list })
+        let page = PageInfo::decode_json_fields(obj)?;
+        Ok(TransQueryTasksOutputResponse { task_list, page })
     }
 }
 
@@ -425,12 +451,14 @@ impl JsonCodec<TransQueryTasksInputResponse> for TransQueryTasksInputResponse {
     fn encode_json(&self) -> Map<String, Value> {
         let mut obj = Map::new();
         JsonCodecHelper::encode_as_list(&mut obj, "task_list", &self.task_list);
+        self.page.encode_json_fields(&mut obj);
         obj
     }
 
     fn decode_json(obj: &Map<String, Value>) -> BuckyResult<Self> {
         let task_list = JsonCodecHelper::decode_array_field(obj, "task_list")?;
-        Ok(Self { task_list })
+        let page = PageInfo::decode_json_fields(obj)?;
+        Ok(Self { task_list, page })
     }
 }
 
@@ -456,6 +484,9 @@ impl JsonCodec<TransQueryTasksOutputRequest> for TransQueryTasksOutputRequest {
                 &self.range.as_ref().unwrap().1,
             );
         }
+        if let Some(page) = &self.page {
+            page.encode_json_fields(&mut obj);
+        }
         obj
     }
 
@@ -485,6 +516,7 @@ impl JsonCodec<TransQueryTasksOutputRequest> for TransQueryTasksOutputRequest {
             common: JsonCodecHelper::decode_field(obj, "common")?,
             task_status,
             range,
+            page: PageRequest::decode_json_fields(obj)?,
         })
     }
 }
@@ -513,6 +545,9 @@ impl JsonCodec<TransQueryTasksInputRequest> for TransQueryTasksInputRequest {
                 &self.range.as_ref().unwrap().1,
             );
         }
+        if let Some(page) = &self.page {
+            page.encode_json_fields(&mut obj);
+        }
         obj
     }
 
@@ -542,6 +577,7 @@ impl JsonCodec<TransQueryTasksInputRequest> for TransQueryTasksInputRequest {
             common: JsonCodecHelper::decode_field(obj, "common")?,
             task_status,
             range,
+            page: PageRequest::decode_json_fields(obj)?,
         })
     }
 }
@@ -316,6 +316,29 @@ impl TransRequestor {
         }
     }
 
+    // 按分页自动读取所有符合条件的任务
+    pub fn query_tasks_iter(
+        &self,
+        common: NDNOutputRequestCommon,
+        task_status: Option<TransTaskStatus>,
+        page_size: u32,
+    ) -> PageIterator<TransTaskInfo> {
+        let this = self.clone();
+        PageIterator::new(page_size, move |page| {
+            let this = this.clone();
+            let req = TransQueryTasksOutputRequest {
+                common: common.clone(),
+                task_status: task_status.clone(),
+                range: None,
+                page: Some(page),
+            };
+            Box::pin(async move {
+                let resp = this.query_tasks(req).await?;
+                Ok((resp.task_list, resp.page))
+            }) as PageFetchFuture<TransTaskInfo>
+        })
+    }
+
     pub async fn publish_file(
         &self,
         req: TransPublishFileOutputRequest,
@@ -369,6 +392,70 @@ impl TransRequestor {
         }
     }
 
+    pub async fn publish_stream(
+        &self,
+        req: TransPublishStreamOutputRequest,
+    ) -> BuckyResult<TransPublishStreamOutputResponse> {
+        info!("will publish stream: {}", req);
+
+        let url = self.service_url.join("file/stream").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+
+        self.encode_common_headers(&req.common, &mut http_req);
+        http_req.insert_header(cyfs_base::CYFS_OWNER_ID, req.owner.to_string());
+        http_req.insert_header(cyfs_base::CYFS_CHUNK_SIZE, req.chunk_size.to_string());
+        if let Some(access) = &req.access {
+            http_req.insert_header(cyfs_base::CYFS_ACCESS, access.value().to_string());
+        }
+
+        // 长度未知，使用chunked方式发送
+        let reader = async_std::io::BufReader::new(req.data);
+        let body = tide::Body::from_reader(reader, None);
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+
+        match resp.status() {
+            code if code.is_success() => {
+                let body = resp.body_string().await.map_err(|e| {
+                    let msg = format!(
+                        "trans publish stream failed, read body string error! owner={}, {}",
+                        req.owner, e
+                    );
+                    error!("{}", msg);
+
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })?;
+
+                let resp = TransPublishStreamOutputResponse::decode_string(&body).map_err(|e| {
+                    error!(
+                        "decode trans publish stream resp from body string error: body={} {}",
+                        body, e,
+                    );
+                    e
+                })?;
+
+                info!(
+                    "trans publish stream success: file={}, len={}, chunks={}",
+                    resp.file_id,
+                    resp.file.len(),
+                    resp.chunk_list.len()
+                );
+
+                Ok(resp)
+            }
+            code @ _ => {
+                let e = RequestorHelper::error_from_resp(&mut resp).await;
+                error!(
+                    "trans publish stream failed: owner={}, status={}, {}",
+                    req.owner, code, e
+                );
+
+                Err(e)
+            }
+        }
+    }
+
     pub async fn get_task_group_state(
         &self,
         req: TransGetTaskGroupStateOutputRequest,
@@ -444,6 +531,53 @@ impl TransRequestor {
             Err(e)
         }
     }
+
+    pub async fn verify_file(
+        &self,
+        req: TransVerifyFileOutputRequest,
+    ) -> BuckyResult<TransVerifyFileOutputResponse> {
+        info!("will verify local file: {:?}", req);
+
+        let url = self.service_url.join("file/verify").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+
+        self.encode_common_headers(&req.common, &mut http_req);
+        http_req.set_body(serde_json::to_string(&req).unwrap());
+
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let resp: TransVerifyFileOutputResponse = resp.body_json().await.map_err(|e| {
+                let msg = format!(
+                    "trans verify file failed, read body string error! file={}, {}",
+                    req.file_id, e
+                );
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            info!(
+                "trans verify file success: file={}, local_path={}, valid={}",
+                req.file_id,
+                req.local_path.display(),
+                resp.valid
+            );
+
+            Ok(resp)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "trans verify file failed: file={}, local_path={}, status={}, {}",
+                req.file_id,
+                req.local_path.display(),
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -487,6 +621,13 @@ impl TransOutputProcessor for TransRequestor {
         Self::publish_file(self, req).await
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamOutputRequest,
+    ) -> BuckyResult<TransPublishStreamOutputResponse> {
+        Self::publish_stream(self, req).await
+    }
+
     async fn control_task(&self, req: TransControlTaskOutputRequest) -> BuckyResult<()> {
         Self::control_task(self, req).await
     }
@@ -504,6 +645,13 @@ impl TransOutputProcessor for TransRequestor {
     ) -> BuckyResult<TransControlTaskGroupOutputResponse> {
         Self::control_task_group(self, req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileOutputRequest,
+    ) -> BuckyResult<TransVerifyFileOutputResponse> {
+        Self::verify_file(self, req).await
+    }
 }
 /*
 struct TransHelper {
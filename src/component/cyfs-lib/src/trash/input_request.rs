@@ -0,0 +1,34 @@
+use super::output_request::*;
+use crate::UtilInputRequestCommon;
+use cyfs_base::*;
+
+// list_trash
+pub struct TrashListInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type TrashListInputResponse = TrashListOutputResponse;
+
+// restore_trash
+pub struct TrashRestoreInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub id: String,
+}
+
+pub type TrashRestoreInputResponse = TrashRestoreOutputResponse;
+
+// purge_trash
+pub struct TrashPurgeInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub id: Option<String>,
+}
+
+pub type TrashPurgeInputResponse = TrashPurgeOutputResponse;
+
+// set_trash_config
+pub struct TrashSetConfigInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub config: TrashConfig,
+}
+
+pub type TrashSetConfigInputResponse = TrashSetConfigOutputResponse;
@@ -0,0 +1,201 @@
+use crate::util::{deserialize_hex, serialize_hex};
+use crate::{GlobalStateCategory, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+// 回收站
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TrashItemKind {
+    // noc里删除的对象，保存了对象的原始数据和meta
+    Object,
+
+    // path_op_env里删除的path
+    Path,
+}
+
+// 回收站里的一条记录，属于发起删除的dec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashItem {
+    // 记录id，restore和purge时使用
+    pub id: String,
+
+    pub kind: TrashItemKind,
+    pub dec_id: ObjectId,
+
+    // 删除的对象或者path上的值
+    pub object_id: ObjectId,
+
+    // Object: 对象的原始数据和删除前的meta
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub object_raw: Vec<u8>,
+    pub create_dec_id: Option<ObjectId>,
+    pub access_string: Option<u32>,
+    pub storage_category: Option<u8>,
+    pub context: Option<String>,
+
+    // Path: 删除时所在的global state和完整路径
+    pub category: Option<GlobalStateCategory>,
+    pub target_dec_id: Option<ObjectId>,
+    pub path: Option<String>,
+
+    // bucky time
+    pub delete_time: u64,
+    pub purge_time: u64,
+}
+
+impl Display for TrashItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "id: {}, kind: {:?}, dec: {}, object: {}, path: {:?}, purge_time: {}",
+            self.id, self.kind, self.dec_id, self.object_id, self.path, self.purge_time
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashConfig {
+    // 放入回收站后多久被彻底清理
+    pub purge_delay_secs: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            purge_delay_secs: 3600 * 24 * 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashListOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for TrashListOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl TrashListOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+// 按删除时间倒序，最近的在前
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashListOutputResponse {
+    pub list: Vec<TrashItem>,
+}
+
+impl Display for TrashListOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashRestoreOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub id: String,
+}
+
+impl Display for TrashRestoreOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, id: {}", self.common, self.id)
+    }
+}
+
+impl TrashRestoreOutputRequest {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            id: id.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashRestoreOutputResponse {
+    pub item: TrashItem,
+}
+
+impl Display for TrashRestoreOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "item: {}", self.item)
+    }
+}
+
+// id为空表示清空当前dec的回收站
+#[derive(Debug, Clone)]
+pub struct TrashPurgeOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub id: Option<String>,
+}
+
+impl Display for TrashPurgeOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, id: {:?}", self.common, self.id)
+    }
+}
+
+impl TrashPurgeOutputRequest {
+    pub fn new(id: Option<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashPurgeOutputResponse {
+    pub count: u32,
+}
+
+impl Display for TrashPurgeOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "count: {}", self.count)
+    }
+}
+
+// 只影响之后放入回收站的记录
+#[derive(Debug, Clone)]
+pub struct TrashSetConfigOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub config: TrashConfig,
+}
+
+impl Display for TrashSetConfigOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, purge_delay_secs: {}",
+            self.common, self.config.purge_delay_secs
+        )
+    }
+}
+
+impl TrashSetConfigOutputRequest {
+    pub fn new(config: TrashConfig) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            config,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashSetConfigOutputResponse {}
+
+impl Display for TrashSetConfigOutputResponse {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use serde_json::{Map, Value};
+
+impl JsonCodec<TrashListOutputRequest> for TrashListOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<TrashListOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+        })
+    }
+}
+
+impl JsonCodec<TrashRestoreOutputRequest> for TrashRestoreOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "id", &self.id);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<TrashRestoreOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            id: JsonCodecHelper::decode_string_field(obj, "id")?,
+        })
+    }
+}
+
+impl JsonCodec<TrashPurgeOutputRequest> for TrashPurgeOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "id", self.id.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<TrashPurgeOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            id: JsonCodecHelper::decode_option_string_field(obj, "id")?,
+        })
+    }
+}
+
+impl JsonCodec<TrashSetConfigOutputRequest> for TrashSetConfigOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_number_field(
+            &mut obj,
+            "purge_delay_secs",
+            self.config.purge_delay_secs,
+        );
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<TrashSetConfigOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            config: TrashConfig {
+                purge_delay_secs: JsonCodecHelper::decode_int_field(obj, "purge_delay_secs")?,
+            },
+        })
+    }
+}
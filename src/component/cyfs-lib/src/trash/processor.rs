@@ -0,0 +1,27 @@
+use super::output_request::*;
+use cyfs_base::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait TrashOutputProcessor: Sync + Send + 'static {
+    async fn list_trash(&self, req: TrashListOutputRequest)
+        -> BuckyResult<TrashListOutputResponse>;
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreOutputRequest,
+    ) -> BuckyResult<TrashRestoreOutputResponse>;
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeOutputRequest,
+    ) -> BuckyResult<TrashPurgeOutputResponse>;
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigOutputRequest,
+    ) -> BuckyResult<TrashSetConfigOutputResponse>;
+}
+
+pub type TrashOutputProcessorRef = Arc<dyn TrashOutputProcessor>;
@@ -0,0 +1,13 @@
+use super::output_request::*;
+
+pub type TrashListRequest = TrashListOutputRequest;
+pub type TrashListResponse = TrashListOutputResponse;
+
+pub type TrashRestoreRequest = TrashRestoreOutputRequest;
+pub type TrashRestoreResponse = TrashRestoreOutputResponse;
+
+pub type TrashPurgeRequest = TrashPurgeOutputRequest;
+pub type TrashPurgeResponse = TrashPurgeOutputResponse;
+
+pub type TrashSetConfigRequest = TrashSetConfigOutputRequest;
+pub type TrashSetConfigResponse = TrashSetConfigOutputResponse;
@@ -0,0 +1,207 @@
+use super::output_request::*;
+use super::processor::*;
+use super::request::*;
+use crate::{base::*, requestor::*, SharedObjectStackDecID, UtilOutputRequestCommon};
+use cyfs_base::*;
+
+use http_types::{Method, Request, Url};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct TrashRequestor {
+    dec_id: Option<SharedObjectStackDecID>,
+    requestor: HttpRequestorRef,
+    service_url: Url,
+}
+
+impl TrashRequestor {
+    pub fn new(dec_id: Option<SharedObjectStackDecID>, requestor: HttpRequestorRef) -> Self {
+        let addr = requestor.remote_addr();
+
+        let url = format!("http://{}/trash/", addr);
+        let url = Url::parse(&url).unwrap();
+
+        Self {
+            dec_id,
+            requestor,
+            service_url: url,
+        }
+    }
+
+    pub fn into_processor(self) -> TrashOutputProcessorRef {
+        Arc::new(self)
+    }
+
+    pub fn clone_processor(&self) -> TrashOutputProcessorRef {
+        self.clone().into_processor()
+    }
+
+    fn encode_common_headers(&self, com_req: &UtilOutputRequestCommon, http_req: &mut Request) {
+        if let Some(dec_id) = &com_req.dec_id {
+            http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+        } else if let Some(dec_id) = &self.dec_id {
+            if let Some(dec_id) = dec_id.get() {
+                http_req.insert_header(cyfs_base::CYFS_DEC_ID, dec_id.to_string());
+            }
+        }
+
+        RequestorHelper::encode_opt_header_with_encoding(
+            http_req,
+            cyfs_base::CYFS_REQ_PATH,
+            com_req.req_path.as_deref(),
+        );
+
+        if let Some(target) = &com_req.target {
+            http_req.insert_header(cyfs_base::CYFS_TARGET, target.to_string());
+        }
+
+        http_req.insert_header(cyfs_base::CYFS_FLAGS, com_req.flags.to_string());
+    }
+
+    // list_trash
+    fn encode_list_trash_request(&self, req: TrashListRequest) -> Request {
+        let url = self.service_url.join("list").unwrap();
+        let mut http_req = Request::new(Method::Get, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+
+        http_req
+    }
+
+    pub async fn list_trash(&self, req: TrashListRequest) -> BuckyResult<TrashListResponse> {
+        let http_req = self.encode_list_trash_request(req);
+
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse list_trash resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("trash list_trash failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn restore_trash(
+        &self,
+        req: TrashRestoreRequest,
+    ) -> BuckyResult<TrashRestoreResponse> {
+        let url = self.service_url.join("restore").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse restore_trash resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "trash restore_trash failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn purge_trash(&self, req: TrashPurgeRequest) -> BuckyResult<TrashPurgeResponse> {
+        let url = self.service_url.join("purge").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse purge_trash resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("trash purge_trash failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn set_trash_config(
+        &self,
+        req: TrashSetConfigRequest,
+    ) -> BuckyResult<TrashSetConfigResponse> {
+        let url = self.service_url.join("config").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse set_trash_config resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "trash set_trash_config failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TrashOutputProcessor for TrashRequestor {
+    async fn list_trash(
+        &self,
+        req: TrashListOutputRequest,
+    ) -> BuckyResult<TrashListOutputResponse> {
+        Self::list_trash(self, req).await
+    }
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreOutputRequest,
+    ) -> BuckyResult<TrashRestoreOutputResponse> {
+        Self::restore_trash(self, req).await
+    }
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeOutputRequest,
+    ) -> BuckyResult<TrashPurgeOutputResponse> {
+        Self::purge_trash(self, req).await
+    }
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigOutputRequest,
+    ) -> BuckyResult<TrashSetConfigOutputResponse> {
+        Self::set_trash_config(self, req).await
+    }
+}
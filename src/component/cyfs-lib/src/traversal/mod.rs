@@ -2,7 +2,9 @@ mod object;
 mod traverser;
 mod adapter;
 mod local_loader;
+mod object_map_sync;
 
 pub use object::*;
 pub use traverser::*;
-pub use local_loader::*;
\ No newline at end of file
+pub use local_loader::*;
+pub use object_map_sync::*;
\ No newline at end of file
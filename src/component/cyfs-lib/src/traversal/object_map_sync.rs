@@ -0,0 +1,487 @@
+use crate::*;
+use cyfs_base::*;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const DEFAULT_SYNC_BATCH: usize = 32;
+
+#[derive(Clone, Debug, Default)]
+pub struct ObjectMapSyncProgress {
+    // 检查过的对象个数
+    pub checked: u64,
+
+    // 本地已经存在而跳过的对象(包括整棵子树)
+    pub skipped: u64,
+
+    // 从远端拉取并保存到本地的对象
+    pub pulled: u64,
+
+    // 已发现但还没有检查的对象
+    pub pending: u64,
+}
+
+pub trait ObjectMapSyncProgressHandler: Send + Sync {
+    fn on_progress(&self, progress: &ObjectMapSyncProgress);
+}
+
+pub type ObjectMapSyncProgressHandlerRef = Arc<Box<dyn ObjectMapSyncProgressHandler>>;
+
+// 收集一个objectmap节点直接引用的子对象
+struct ObjectMapChildCollector {
+    children: Vec<ObjectId>,
+}
+
+impl ObjectMapChildCollector {
+    fn add(&mut self, id: &ObjectId) {
+        // chunk和内嵌数据的id不是对象，不需要拉取
+        if id.is_data() || id.is_chunk_id() {
+            return;
+        }
+
+        self.children.push(id.to_owned());
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectMapVisitor for ObjectMapChildCollector {
+    async fn visit_hub_item(&mut self, item: &ObjectId) -> BuckyResult<()> {
+        self.add(item);
+        Ok(())
+    }
+
+    async fn visit_map_item(&mut self, _key: &str, item: &ObjectId) -> BuckyResult<()> {
+        self.add(item);
+        Ok(())
+    }
+
+    async fn visit_set_item(&mut self, item: &ObjectId) -> BuckyResult<()> {
+        self.add(item);
+        Ok(())
+    }
+
+    async fn visit_diff_map_item(
+        &mut self,
+        _key: &str,
+        item: &ObjectMapDiffMapItem,
+    ) -> BuckyResult<()> {
+        for id in [&item.prev, &item.altered, &item.diff] {
+            if let Some(id) = id {
+                self.add(id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn visit_diff_set_item(&mut self, item: &ObjectMapDiffSetItem) -> BuckyResult<()> {
+        for id in [&item.prev, &item.altered] {
+            if let Some(id) = id {
+                self.add(id);
+            }
+        }
+        Ok(())
+    }
+}
+
+// 把其它zone的一棵objectmap树增量同步到本地noc
+// 1. 对象是按内容寻址的，本地已经存在的objectmap节点，说明整棵子树在之前的同步里已经完成，直接跳过
+// 2. 按层分批检查和拉取，每批并发请求
+// 3. objectmap节点在所有子对象保存之后才写入本地，所以中断后重新调用sync就可以从断点继续
+pub struct ObjectMapSyncer {
+    non: NONOutputProcessorRef,
+    target: Option<ObjectId>,
+    dec_id: Option<ObjectId>,
+    batch: usize,
+    progress_handler: Option<ObjectMapSyncProgressHandlerRef>,
+}
+
+impl ObjectMapSyncer {
+    pub fn new(non: NONOutputProcessorRef, target: Option<ObjectId>) -> Self {
+        Self {
+            non,
+            target,
+            dec_id: None,
+            batch: DEFAULT_SYNC_BATCH,
+            progress_handler: None,
+        }
+    }
+
+    pub fn set_dec_id(&mut self, dec_id: Option<ObjectId>) {
+        self.dec_id = dec_id;
+    }
+
+    pub fn set_batch(&mut self, batch: usize) {
+        assert!(batch > 0);
+        self.batch = batch;
+    }
+
+    pub fn set_progress_handler(&mut self, handler: ObjectMapSyncProgressHandlerRef) {
+        self.progress_handler = Some(handler);
+    }
+
+    // remote_root: 远端objectmap的根；local_prev_root: 上一次同步到本地的根，可以为空
+    pub async fn sync(
+        &self,
+        remote_root: &ObjectId,
+        local_prev_root: Option<&ObjectId>,
+    ) -> BuckyResult<ObjectMapSyncProgress> {
+        if remote_root.obj_type_code() != ObjectTypeCode::ObjectMap {
+            let msg = format!("sync root is not objectmap! root={}", remote_root);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let mut progress = ObjectMapSyncProgress::default();
+        if local_prev_root == Some(remote_root) {
+            info!("objectmap already synced! root={}", remote_root);
+            return Ok(progress);
+        }
+
+        info!(
+            "will sync objectmap: target={:?}, root={}, prev={:?}",
+            self.target, remote_root, local_prev_root
+        );
+
+        let mut visited = HashSet::new();
+        visited.insert(remote_root.to_owned());
+
+        // 拉取到的objectmap节点，全部子对象保存后再逆序写入本地
+        let mut object_maps = vec![];
+
+        let mut current = vec![remote_root.to_owned()];
+        progress.pending = 1;
+
+        while !current.is_empty() {
+            let mut next = vec![];
+            for batch in current.chunks(self.batch) {
+                let children = self.sync_batch(batch, &mut object_maps, &mut progress).await?;
+                let mut count = 0;
+                for id in children {
+                    if visited.insert(id.clone()) {
+                        next.push(id);
+                        count += 1;
+                    }
+                }
+
+                progress.pending = progress.pending - batch.len() as u64 + count;
+                self.notify_progress(&progress);
+            }
+
+            current = next;
+        }
+
+        for object in object_maps.into_iter().rev() {
+            self.put_local(object).await?;
+            progress.pulled += 1;
+        }
+        self.notify_progress(&progress);
+
+        info!(
+            "sync objectmap complete! root={}, progress={:?}",
+            remote_root, progress
+        );
+
+        Ok(progress)
+    }
+
+    async fn sync_batch(
+        &self,
+        batch: &[ObjectId],
+        object_maps: &mut Vec<NONObjectInfo>,
+        progress: &mut ObjectMapSyncProgress,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        let exists = futures::future::join_all(batch.iter().map(|id| self.exists_local(id))).await;
+
+        let mut missing = vec![];
+        for (id, ret) in batch.iter().zip(exists) {
+            if !ret? {
+                missing.push(id);
+            }
+        }
+
+        progress.checked += batch.len() as u64;
+        progress.skipped += (batch.len() - missing.len()) as u64;
+
+        let objects =
+            futures::future::join_all(missing.into_iter().map(|id| self.get_remote(id))).await;
+
+        let mut children = vec![];
+        for object in objects {
+            let object = object?;
+            if object.object_id.obj_type_code() == ObjectTypeCode::ObjectMap {
+                let (object_map, _) = ObjectMap::raw_decode(&object.object_raw).map_err(|e| {
+                    error!("decode objectmap failed! id={}, {}", object.object_id, e);
+                    e
+                })?;
+
+                let mut collector = ObjectMapChildCollector { children: vec![] };
+                object_map.visit(&mut collector).await?;
+                children.append(&mut collector.children);
+
+                object_maps.push(object);
+            } else {
+                self.put_local(object).await?;
+                progress.pulled += 1;
+            }
+        }
+
+        Ok(children)
+    }
+
+    async fn exists_local(&self, object_id: &ObjectId) -> BuckyResult<bool> {
+        let mut req = NONGetObjectOutputRequest::new_noc(object_id.to_owned(), None);
+        req.common.dec_id = self.dec_id.clone();
+
+        match self.non.get_object(req).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => Ok(false),
+            Err(e) => {
+                error!("get object from local noc failed! id={}, {}", object_id, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_remote(&self, object_id: &ObjectId) -> BuckyResult<NONObjectInfo> {
+        let mut req =
+            NONGetObjectOutputRequest::new_router(self.target.clone(), object_id.to_owned(), None);
+        req.common.dec_id = self.dec_id.clone();
+
+        let resp = self.non.get_object(req).await.map_err(|e| {
+            error!(
+                "get object from remote failed! target={:?}, id={}, {}",
+                self.target, object_id, e
+            );
+            e
+        })?;
+
+        Ok(resp.object)
+    }
+
+    async fn put_local(&self, object: NONObjectInfo) -> BuckyResult<()> {
+        let object_id = object.object_id.clone();
+        let mut req = NONPutObjectOutputRequest::new_noc(object.object_id, object.object_raw);
+        req.common.dec_id = self.dec_id.clone();
+
+        self.non.put_object(req).await.map_err(|e| {
+            error!("put object to local noc failed! id={}, {}", object_id, e);
+            e
+        })?;
+
+        Ok(())
+    }
+
+    fn notify_progress(&self, progress: &ObjectMapSyncProgress) {
+        if let Some(handler) = &self.progress_handler {
+            handler.on_progress(progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // noc级别的请求访问本地，其它级别的请求访问远端
+    #[derive(Clone, Default)]
+    struct MemoryNON {
+        local: Arc<Mutex<HashMap<ObjectId, Vec<u8>>>>,
+        remote: Arc<Mutex<HashMap<ObjectId, Vec<u8>>>>,
+        put_order: Arc<Mutex<Vec<ObjectId>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl NONOutputProcessor for MemoryNON {
+        async fn put_object(
+            &self,
+            req: NONPutObjectOutputRequest,
+        ) -> BuckyResult<NONPutObjectOutputResponse> {
+            assert_eq!(req.common.level, NONAPILevel::NOC);
+            let object_id = req.object.object_id;
+            self.put_order.lock().unwrap().push(object_id.clone());
+            self.local
+                .lock()
+                .unwrap()
+                .insert(object_id, req.object.object_raw);
+
+            Ok(NONPutObjectOutputResponse {
+                result: NONPutObjectResult::Accept,
+                object_update_time: None,
+                object_expires_time: None,
+            })
+        }
+
+        async fn get_object(
+            &self,
+            req: NONGetObjectOutputRequest,
+        ) -> BuckyResult<NONGetObjectOutputResponse> {
+            let list = match req.common.level {
+                NONAPILevel::NOC => &self.local,
+                _ => &self.remote,
+            };
+
+            let object_raw = list.lock().unwrap().get(&req.object_id).cloned();
+            match object_raw {
+                Some(object_raw) => Ok(NONGetObjectOutputResponse {
+                    object_update_time: None,
+                    object_expires_time: None,
+                    object: NONObjectInfo::new(req.object_id, object_raw, None),
+                    attr: None,
+                }),
+                None => Err(BuckyError::from(BuckyErrorCode::NotFound)),
+            }
+        }
+
+        async fn post_object(
+            &self,
+            _req: NONPostObjectOutputRequest,
+        ) -> BuckyResult<NONPostObjectOutputResponse> {
+            unreachable!();
+        }
+
+        async fn select_object(
+            &self,
+            _req: NONSelectObjectOutputRequest,
+        ) -> BuckyResult<NONSelectObjectOutputResponse> {
+            unreachable!();
+        }
+
+        async fn delete_object(
+            &self,
+            _req: NONDeleteObjectOutputRequest,
+        ) -> BuckyResult<NONDeleteObjectOutputResponse> {
+            unreachable!();
+        }
+    }
+
+    struct TestTree {
+        root: ObjectId,
+        leaf_a: ObjectId,
+        leaf_b: ObjectId,
+        objects: HashMap<ObjectId, Vec<u8>>,
+    }
+
+    fn chunk_id(data: &[u8]) -> ObjectId {
+        ChunkId::calculate_sync(data).unwrap().object_id()
+    }
+
+    fn new_map(content_type: ObjectMapSimpleContentType) -> ObjectMap {
+        let owner = ObjectId::default();
+        ObjectMap::new(content_type, Some(owner.clone()), Some(owner))
+            .no_create_time()
+            .build()
+    }
+
+    // root(map) -> {a: leaf_a(set) -> [chunk], b: leaf_b(map) -> {x: chunk}}
+    async fn build_tree() -> TestTree {
+        let noc = ObjectMapMemoryNOCCache::new();
+        let root_cache = ObjectMapRootMemoryCache::new_default_ref(None, noc);
+        let cache = ObjectMapOpEnvMemoryCache::new_ref(root_cache);
+
+        let mut objects = HashMap::new();
+
+        let mut leaf_a = new_map(ObjectMapSimpleContentType::Set);
+        leaf_a.insert(&cache, &chunk_id(b"chunk a")).await.unwrap();
+        let leaf_a_id = leaf_a.flush_id();
+        objects.insert(leaf_a_id.clone(), leaf_a.to_vec().unwrap());
+
+        let mut leaf_b = new_map(ObjectMapSimpleContentType::Map);
+        leaf_b
+            .insert_with_key(&cache, "x", &chunk_id(b"chunk b"))
+            .await
+            .unwrap();
+        let leaf_b_id = leaf_b.flush_id();
+        objects.insert(leaf_b_id.clone(), leaf_b.to_vec().unwrap());
+
+        let mut root = new_map(ObjectMapSimpleContentType::Map);
+        root.insert_with_key(&cache, "a", &leaf_a_id).await.unwrap();
+        root.insert_with_key(&cache, "b", &leaf_b_id).await.unwrap();
+        let root_id = root.flush_id();
+        objects.insert(root_id.clone(), root.to_vec().unwrap());
+
+        TestTree {
+            root: root_id,
+            leaf_a: leaf_a_id,
+            leaf_b: leaf_b_id,
+            objects,
+        }
+    }
+
+    fn new_syncer(non: &MemoryNON) -> ObjectMapSyncer {
+        let processor: NONOutputProcessorRef = Arc::new(Box::new(non.clone()));
+        let mut syncer = ObjectMapSyncer::new(processor, None);
+        syncer.set_batch(1);
+        syncer
+    }
+
+    #[async_std::test]
+    async fn test_sync_full() {
+        let tree = build_tree().await;
+        let non = MemoryNON::default();
+        *non.remote.lock().unwrap() = tree.objects.clone();
+
+        let syncer = new_syncer(&non);
+        let progress = syncer.sync(&tree.root, None).await.unwrap();
+        assert_eq!(progress.checked, 3);
+        assert_eq!(progress.skipped, 0);
+        assert_eq!(progress.pulled, 3);
+        assert_eq!(progress.pending, 0);
+
+        // chunk不会被拉取，全部objectmap都写入了本地，并且根最后写入
+        assert_eq!(*non.local.lock().unwrap(), tree.objects);
+        assert_eq!(non.put_order.lock().unwrap().last(), Some(&tree.root));
+
+        // 和上一次同步的根相同，不需要任何请求
+        let progress = syncer.sync(&tree.root, Some(&tree.root)).await.unwrap();
+        assert_eq!(progress.checked, 0);
+        assert_eq!(progress.pulled, 0);
+    }
+
+    #[async_std::test]
+    async fn test_sync_resume() {
+        let tree = build_tree().await;
+        let non = MemoryNON::default();
+        {
+            let mut remote = non.remote.lock().unwrap();
+            *remote = tree.objects.clone();
+            remote.remove(&tree.leaf_b);
+        }
+
+        // 远端缺少leaf_b，同步中断，根节点不能写入本地
+        let syncer = new_syncer(&non);
+        let err = syncer.sync(&tree.root, None).await.unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::NotFound);
+        assert!(!non.local.lock().unwrap().contains_key(&tree.root));
+
+        // 重新同步时已经保存的子树直接跳过
+        non.remote
+            .lock()
+            .unwrap()
+            .insert(tree.leaf_b.clone(), tree.objects[&tree.leaf_b].clone());
+        non.local
+            .lock()
+            .unwrap()
+            .insert(tree.leaf_a.clone(), tree.objects[&tree.leaf_a].clone());
+
+        let progress = syncer.sync(&tree.root, None).await.unwrap();
+        assert_eq!(progress.checked, 3);
+        assert_eq!(progress.skipped, 1);
+        assert_eq!(progress.pulled, 2);
+        assert_eq!(*non.local.lock().unwrap(), tree.objects);
+    }
+
+    #[async_std::test]
+    async fn test_sync_invalid_root() {
+        let non = MemoryNON::default();
+        let syncer = new_syncer(&non);
+
+        let err = syncer
+            .sync(&chunk_id(b"not objectmap"), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+    }
+}
@@ -1,6 +1,7 @@
 use super::output_request::*;
 use crate::{base::*, TransPublishChunkMethod};
 use cyfs_base::*;
+use cyfs_core::ZoneEventCategory;
 use cyfs_util::SystemInfoUpdater;
 
 use std::path::PathBuf;
@@ -113,3 +114,90 @@ pub struct UtilBuildDirFromObjectMapInputRequest {
 }
 
 pub type UtilBuildDirFromObjectMapInputResponse = UtilBuildDirFromObjectMapOutputResponse;
+
+// pin_app_web_dir
+pub struct UtilPinAppWebDirInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: ObjectId,
+    pub version: String,
+}
+
+pub type UtilPinAppWebDirInputResponse = UtilPinAppWebDirOutputResponse;
+
+// unpin_app_web_dir
+pub struct UtilUnpinAppWebDirInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: ObjectId,
+    pub version: String,
+}
+
+pub type UtilUnpinAppWebDirInputResponse = UtilUnpinAppWebDirOutputResponse;
+
+// query_zone_events
+pub struct UtilQueryZoneEventsInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub category: Option<ZoneEventCategory>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+pub type UtilQueryZoneEventsInputResponse = UtilQueryZoneEventsOutputResponse;
+
+// get_zone_device_health
+pub struct UtilGetZoneDeviceHealthInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type UtilGetZoneDeviceHealthInputResponse = UtilGetZoneDeviceHealthOutputResponse;
+
+// get_ood_resolver_stats
+pub struct UtilGetOODResolverStatsInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type UtilGetOODResolverStatsInputResponse = UtilGetOODResolverStatsOutputResponse;
+
+// set_dec_resource_policy
+pub struct UtilSetDecResourcePolicyInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: ObjectId,
+    pub cpu_limit: Option<f32>,
+    pub memory_limit: Option<u64>,
+}
+
+pub type UtilSetDecResourcePolicyInputResponse = UtilSetDecResourcePolicyOutputResponse;
+
+// remove_dec_resource_policy
+pub struct UtilRemoveDecResourcePolicyInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: ObjectId,
+}
+
+pub type UtilRemoveDecResourcePolicyInputResponse = UtilRemoveDecResourcePolicyOutputResponse;
+
+// get_dec_resource_policies
+pub struct UtilGetDecResourcePoliciesInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+}
+
+pub type UtilGetDecResourcePoliciesInputResponse = UtilGetDecResourcePoliciesOutputResponse;
+
+// get_concurrency_stat
+pub struct UtilGetConcurrencyStatInputRequest {
+    pub common: UtilInputRequestCommon,
+}
+
+pub type UtilGetConcurrencyStatInputResponse = UtilGetConcurrencyStatOutputResponse;
+
+// get_object_access_stat
+pub struct UtilGetObjectAccessStatInputRequest {
+    pub common: UtilInputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+    pub days: u32,
+    pub top: u32,
+}
+
+pub type UtilGetObjectAccessStatInputResponse = UtilGetObjectAccessStatOutputResponse;
@@ -1,4 +1,4 @@
-use crate::{prelude::*, GlobalStateAccessMode, TransPublishChunkMethod};
+use crate::{prelude::*, GlobalStateAccessMode, GlobalStateCategory, TransPublishChunkMethod};
 use crate::zone::ZoneRole;
 use cyfs_base::*;
 use cyfs_core::ZoneId;
@@ -584,3 +584,537 @@ pub struct UtilBuildDirFromObjectMapOutputRequest {
 pub struct UtilBuildDirFromObjectMapOutputResponse {
     pub object_id: ObjectId,
 }
+
+// 预取并固定app某个版本的web dir的所有chunk
+#[derive(Debug, Clone)]
+pub struct UtilPinAppWebDirOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: ObjectId,
+    pub version: String,
+}
+
+impl Display for UtilPinAppWebDirOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {}, version: {}",
+            self.common, self.dec_id, self.version
+        )
+    }
+}
+
+impl UtilPinAppWebDirOutputRequest {
+    pub fn new(dec_id: ObjectId, version: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            version: version.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UtilPinAppWebDirOutputResponse {
+    pub dir_id: ObjectId,
+
+    // 固定的chunk总数，以及其中从远端拉取的个数
+    pub chunk_count: u32,
+    pub fetched_count: u32,
+}
+
+impl Display for UtilPinAppWebDirOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "dir_id: {}, chunk_count: {}, fetched_count: {}",
+            self.dir_id, self.chunk_count, self.fetched_count
+        )
+    }
+}
+
+// 解除固定，chunk本身不会被立即删除
+#[derive(Debug, Clone)]
+pub struct UtilUnpinAppWebDirOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: ObjectId,
+    pub version: String,
+}
+
+impl Display for UtilUnpinAppWebDirOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {}, version: {}",
+            self.common, self.dec_id, self.version
+        )
+    }
+}
+
+impl UtilUnpinAppWebDirOutputRequest {
+    pub fn new(dec_id: ObjectId, version: impl Into<String>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            version: version.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UtilUnpinAppWebDirOutputResponse {
+    pub dir_id: ObjectId,
+    pub chunk_count: u32,
+}
+
+impl Display for UtilUnpinAppWebDirOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dir_id: {}, chunk_count: {}", self.dir_id, self.chunk_count)
+    }
+}
+
+// 查询zone事件记录，按时间倒序返回
+#[derive(Debug, Clone)]
+pub struct UtilQueryZoneEventsOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    pub category: Option<ZoneEventCategory>,
+
+    // 时间范围，bucky time，左闭右开
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+
+    pub page_index: u32,
+    pub page_size: u32,
+}
+
+impl Display for UtilQueryZoneEventsOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, category: {:?}, start_time: {:?}, end_time: {:?}, page_index: {}, page_size: {}",
+            self.common,
+            self.category,
+            self.start_time,
+            self.end_time,
+            self.page_index,
+            self.page_size
+        )
+    }
+}
+
+impl UtilQueryZoneEventsOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            category: None,
+            start_time: None,
+            end_time: None,
+            page_index: 0,
+            page_size: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UtilQueryZoneEventsOutputResponse {
+    // ZoneEvent对象列表
+    pub list: Vec<NONObjectInfo>,
+}
+
+impl Display for UtilQueryZoneEventsOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+// 设备定时上报的运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealthInfo {
+    pub device_id: DeviceId,
+
+    pub stack_version: String,
+
+    // 上报时间，bucky time
+    pub update_time: u64,
+
+    pub cpu_usage: f32,
+
+    // memory size in bytes
+    pub total_memory: u64,
+    pub used_memory: u64,
+
+    // disk capacity of ssd and hdd, in bytes
+    pub disk_total: u64,
+    pub disk_avail: u64,
+
+    // Bytes transferred between each refresh cycle
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+
+    // 设置了资源策略的dec在本设备上的资源占用
+    #[serde(default)]
+    pub dec_usage: Vec<DecResourceUsage>,
+}
+
+// 汇总zone内所有设备最近一次上报的运行状态，没有指定target时默认路由到ood
+#[derive(Debug, Clone)]
+pub struct UtilGetZoneDeviceHealthOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for UtilGetZoneDeviceHealthOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl UtilGetZoneDeviceHealthOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilGetZoneDeviceHealthOutputResponse {
+    pub list: Vec<DeviceHealthInfo>,
+}
+
+impl Display for UtilGetZoneDeviceHealthOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+pub(crate) fn serialize_hex<S>(buf: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&hex::encode(buf))
+}
+
+pub(crate) fn deserialize_hex<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    hex::decode(&s).map_err(serde::de::Error::custom)
+}
+
+// get_ood_resolver_stats
+#[derive(Debug, Clone)]
+pub struct UtilGetOODResolverStatsOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for UtilGetOODResolverStatsOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl UtilGetOODResolverStatsOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+// ood解析缓存的命中统计，计数从协议栈启动开始累计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UtilGetOODResolverStatsOutputResponse {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidated: u64,
+}
+
+impl Display for UtilGetOODResolverStatsOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entries: {}, hits: {}, misses: {}, invalidated: {}",
+            self.entries, self.hits, self.misses, self.invalidated
+        )
+    }
+}
+
+// dec的资源限制策略，保存在ood的root_state系统dec下，随root_state同步到zone内的所有设备
+// 由app-manager启动的dec服务在linux上通过cgroup限制
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecResourcePolicy {
+    pub dec_id: ObjectId,
+
+    // cpu核数的上限，比如0.5表示最多使用半个核，为None表示不限制
+    pub cpu_limit: Option<f32>,
+
+    // 内存上限，单位字节，为None表示不限制
+    pub memory_limit: Option<u64>,
+
+    // bucky time
+    pub update_time: u64,
+}
+
+// dec在设备上的资源占用，随设备状态一起上报
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecResourceUsage {
+    pub dec_id: ObjectId,
+
+    // 最近一个统计周期的cpu占用，1.0表示一个核
+    pub cpu_usage: f32,
+
+    // memory size in bytes
+    pub memory_usage: u64,
+
+    pub cpu_limit: Option<f32>,
+    pub memory_limit: Option<u64>,
+
+    // 累计因为超过内存上限被杀掉的进程数
+    pub oom_kills: u64,
+}
+
+// 设置dec的资源策略，只有系统dec可以调用，请求会路由到ood处理
+#[derive(Debug, Clone)]
+pub struct UtilSetDecResourcePolicyOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: ObjectId,
+    pub cpu_limit: Option<f32>,
+    pub memory_limit: Option<u64>,
+}
+
+impl Display for UtilSetDecResourcePolicyOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {}, cpu_limit: {:?}, memory_limit: {:?}",
+            self.common, self.dec_id, self.cpu_limit, self.memory_limit
+        )
+    }
+}
+
+impl UtilSetDecResourcePolicyOutputRequest {
+    pub fn new(dec_id: ObjectId, cpu_limit: Option<f32>, memory_limit: Option<u64>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+            cpu_limit,
+            memory_limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilSetDecResourcePolicyOutputResponse {
+    pub policy: DecResourcePolicy,
+}
+
+impl Display for UtilSetDecResourcePolicyOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "policy: {:?}", self.policy)
+    }
+}
+
+// 移除dec的资源策略，已经运行的dec服务恢复为不限制
+#[derive(Debug, Clone)]
+pub struct UtilRemoveDecResourcePolicyOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: ObjectId,
+}
+
+impl Display for UtilRemoveDecResourcePolicyOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, dec_id: {}", self.common, self.dec_id)
+    }
+}
+
+impl UtilRemoveDecResourcePolicyOutputRequest {
+    pub fn new(dec_id: ObjectId) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilRemoveDecResourcePolicyOutputResponse {
+    // 不存在时为None
+    pub policy: Option<DecResourcePolicy>,
+}
+
+impl Display for UtilRemoveDecResourcePolicyOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "policy: {:?}", self.policy)
+    }
+}
+
+// 查询dec的资源策略，dec_id为None时返回所有的策略，默认查询本设备上同步的root_state
+#[derive(Debug, Clone)]
+pub struct UtilGetDecResourcePoliciesOutputRequest {
+    pub common: UtilOutputRequestCommon,
+    pub dec_id: Option<ObjectId>,
+}
+
+impl Display for UtilGetDecResourcePoliciesOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}, dec_id: {:?}", self.common, self.dec_id)
+    }
+}
+
+impl UtilGetDecResourcePoliciesOutputRequest {
+    pub fn new(dec_id: Option<ObjectId>) -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilGetDecResourcePoliciesOutputResponse {
+    pub list: Vec<DecResourcePolicy>,
+}
+
+impl Display for UtilGetDecResourcePoliciesOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+// 协议栈对外请求的并发限制和排队情况，每类请求一个限制器，比如non_get/non_put/ndn_get/root_state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyLimiterStat {
+    pub name: String,
+
+    // 0表示不限制
+    pub max_concurrent: u32,
+    pub max_queue: u32,
+
+    // 当前正在处理和排队中的请求数
+    pub running: u32,
+    pub queued: u32,
+
+    // 启动以来的累计请求数，被拒绝的请求数，排队长度的峰值
+    pub total: u64,
+    pub rejected: u64,
+    pub max_queued: u32,
+}
+
+// 查询当前设备上各类请求的并发和排队统计
+#[derive(Debug, Clone)]
+pub struct UtilGetConcurrencyStatOutputRequest {
+    pub common: UtilOutputRequestCommon,
+}
+
+impl Display for UtilGetConcurrencyStatOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "common: {}", self.common)
+    }
+}
+
+impl UtilGetConcurrencyStatOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilGetConcurrencyStatOutputResponse {
+    pub list: Vec<ConcurrencyLimiterStat>,
+}
+
+impl Display for UtilGetConcurrencyStatOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "list: {}", self.list.len())
+    }
+}
+
+// 对象访问统计
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ObjectAccessCategory {
+    Non,
+    Ndn,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ObjectAccessItem {
+    // 对象所属的dec，来自请求的req_path，没有时为请求方的dec
+    pub dec_id: ObjectId,
+
+    pub object_id: ObjectId,
+    pub category: ObjectAccessCategory,
+
+    // 按采样率折算后的访问次数，是估计值
+    pub count: u64,
+
+    // 最近一次被采样到的访问时间(bucky time)
+    pub last_access: u64,
+}
+
+// 查询目标设备(默认为当前设备)最近若干天里每个dec访问次数最多的对象
+#[derive(Debug, Clone)]
+pub struct UtilGetObjectAccessStatOutputRequest {
+    pub common: UtilOutputRequestCommon,
+
+    // 只返回指定dec的对象
+    pub dec_id: Option<ObjectId>,
+
+    // 统计的天数(UTC)，包括今天
+    pub days: u32,
+
+    // 每个dec最多返回的对象数
+    pub top: u32,
+}
+
+impl Display for UtilGetObjectAccessStatOutputRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "common: {}, dec_id: {:?}, days: {}, top: {}",
+            self.common, self.dec_id, self.days, self.top
+        )
+    }
+}
+
+impl UtilGetObjectAccessStatOutputRequest {
+    pub fn new() -> Self {
+        Self {
+            common: UtilOutputRequestCommon::default(),
+            dec_id: None,
+            days: 7,
+            top: 10,
+        }
+    }
+
+    pub fn new_dec(dec_id: ObjectId) -> Self {
+        let mut ret = Self::new();
+        ret.dec_id = Some(dec_id);
+        ret
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilGetObjectAccessStatOutputResponse {
+    // YYYY-MM-DD(UTC)，统计的起止日期
+    pub from_day: String,
+    pub to_day: String,
+
+    // 按dec分组，组内按访问次数从高到低
+    pub list: Vec<ObjectAccessItem>,
+}
+
+impl Display for UtilGetObjectAccessStatOutputResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "from_day: {}, to_day: {}, list: {}",
+            self.from_day,
+            self.to_day,
+            self.list.len()
+        )
+    }
+}
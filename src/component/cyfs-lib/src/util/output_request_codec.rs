@@ -338,3 +338,197 @@ impl JsonCodec<UtilBuildDirFromObjectMapOutputResponse>
         })
     }
 }
+
+impl JsonCodec<UtilPinAppWebDirOutputRequest> for UtilPinAppWebDirOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "dec_id", &self.dec_id);
+        JsonCodecHelper::encode_string_field(&mut obj, "version", &self.version);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilPinAppWebDirOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_string_field(obj, "dec_id")?,
+            version: JsonCodecHelper::decode_string_field(obj, "version")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilPinAppWebDirOutputResponse> for UtilPinAppWebDirOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "dir_id", &self.dir_id);
+        JsonCodecHelper::encode_number_field(&mut obj, "chunk_count", self.chunk_count);
+        JsonCodecHelper::encode_number_field(&mut obj, "fetched_count", self.fetched_count);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilPinAppWebDirOutputResponse> {
+        Ok(Self {
+            dir_id: JsonCodecHelper::decode_string_field(obj, "dir_id")?,
+            chunk_count: JsonCodecHelper::decode_int_field(obj, "chunk_count")?,
+            fetched_count: JsonCodecHelper::decode_int_field(obj, "fetched_count")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilUnpinAppWebDirOutputRequest> for UtilUnpinAppWebDirOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "dec_id", &self.dec_id);
+        JsonCodecHelper::encode_string_field(&mut obj, "version", &self.version);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilUnpinAppWebDirOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_string_field(obj, "dec_id")?,
+            version: JsonCodecHelper::decode_string_field(obj, "version")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilUnpinAppWebDirOutputResponse> for UtilUnpinAppWebDirOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_string_field(&mut obj, "dir_id", &self.dir_id);
+        JsonCodecHelper::encode_number_field(&mut obj, "chunk_count", self.chunk_count);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilUnpinAppWebDirOutputResponse> {
+        Ok(Self {
+            dir_id: JsonCodecHelper::decode_string_field(obj, "dir_id")?,
+            chunk_count: JsonCodecHelper::decode_int_field(obj, "chunk_count")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilQueryZoneEventsOutputRequest> for UtilQueryZoneEventsOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "category", self.category.as_ref());
+        JsonCodecHelper::encode_option_number_field(&mut obj, "start_time", self.start_time);
+        JsonCodecHelper::encode_option_number_field(&mut obj, "end_time", self.end_time);
+        JsonCodecHelper::encode_number_field(&mut obj, "page_index", self.page_index);
+        JsonCodecHelper::encode_number_field(&mut obj, "page_size", self.page_size);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilQueryZoneEventsOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            category: JsonCodecHelper::decode_option_string_field(obj, "category")?,
+            start_time: JsonCodecHelper::decode_option_int_field(obj, "start_time")?,
+            end_time: JsonCodecHelper::decode_option_int_field(obj, "end_time")?,
+            page_index: JsonCodecHelper::decode_int_field(obj, "page_index")?,
+            page_size: JsonCodecHelper::decode_int_field(obj, "page_size")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilQueryZoneEventsOutputResponse> for UtilQueryZoneEventsOutputResponse {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_as_list(&mut obj, "list", &self.list);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilQueryZoneEventsOutputResponse> {
+        Ok(Self {
+            list: JsonCodecHelper::decode_array_field(obj, "list")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilSetDecResourcePolicyOutputRequest> for UtilSetDecResourcePolicyOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "dec_id", &self.dec_id);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "cpu_limit", self.cpu_limit.as_ref());
+        JsonCodecHelper::encode_option_string_field(&mut obj, "memory_limit", self.memory_limit.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilSetDecResourcePolicyOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_string_field(obj, "dec_id")?,
+            cpu_limit: JsonCodecHelper::decode_option_string_field(obj, "cpu_limit")?,
+            memory_limit: JsonCodecHelper::decode_option_string_field(obj, "memory_limit")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilRemoveDecResourcePolicyOutputRequest> for UtilRemoveDecResourcePolicyOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_string_field(&mut obj, "dec_id", &self.dec_id);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilRemoveDecResourcePolicyOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_string_field(obj, "dec_id")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilGetDecResourcePoliciesOutputRequest> for UtilGetDecResourcePoliciesOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilGetDecResourcePoliciesOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilGetConcurrencyStatOutputRequest> for UtilGetConcurrencyStatOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilGetConcurrencyStatOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+        })
+    }
+}
+
+impl JsonCodec<UtilGetObjectAccessStatOutputRequest> for UtilGetObjectAccessStatOutputRequest {
+    fn encode_json(&self) -> Map<String, Value> {
+        let mut obj = Map::new();
+        JsonCodecHelper::encode_field(&mut obj, "common", &self.common);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_number_field(&mut obj, "days", self.days);
+        JsonCodecHelper::encode_number_field(&mut obj, "top", self.top);
+        obj
+    }
+
+    fn decode_json(obj: &Map<String, Value>) -> BuckyResult<UtilGetObjectAccessStatOutputRequest> {
+        Ok(Self {
+            common: JsonCodecHelper::decode_field(obj, "common")?,
+            dec_id: JsonCodecHelper::decode_option_string_field(obj, "dec_id")?,
+            days: JsonCodecHelper::decode_int_field(obj, "days")?,
+            top: JsonCodecHelper::decode_int_field(obj, "top")?,
+        })
+    }
+}
@@ -43,6 +43,36 @@ pub trait UtilOutputProcessor: Sync + Send + 'static {
 
     async fn build_dir_from_object_map(&self, req: UtilBuildDirFromObjectMapOutputRequest)
                                        -> BuckyResult<UtilBuildDirFromObjectMapOutputResponse>;
+
+    async fn pin_app_web_dir(&self, req: UtilPinAppWebDirOutputRequest)
+        -> BuckyResult<UtilPinAppWebDirOutputResponse>;
+
+    async fn unpin_app_web_dir(&self, req: UtilUnpinAppWebDirOutputRequest)
+        -> BuckyResult<UtilUnpinAppWebDirOutputResponse>;
+
+    async fn query_zone_events(&self, req: UtilQueryZoneEventsOutputRequest)
+        -> BuckyResult<UtilQueryZoneEventsOutputResponse>;
+
+    async fn get_zone_device_health(&self, req: UtilGetZoneDeviceHealthOutputRequest)
+        -> BuckyResult<UtilGetZoneDeviceHealthOutputResponse>;
+
+    async fn get_ood_resolver_stats(&self, req: UtilGetOODResolverStatsOutputRequest)
+        -> BuckyResult<UtilGetOODResolverStatsOutputResponse>;
+
+    async fn set_dec_resource_policy(&self, req: UtilSetDecResourcePolicyOutputRequest)
+        -> BuckyResult<UtilSetDecResourcePolicyOutputResponse>;
+
+    async fn remove_dec_resource_policy(&self, req: UtilRemoveDecResourcePolicyOutputRequest)
+        -> BuckyResult<UtilRemoveDecResourcePolicyOutputResponse>;
+
+    async fn get_dec_resource_policies(&self, req: UtilGetDecResourcePoliciesOutputRequest)
+        -> BuckyResult<UtilGetDecResourcePoliciesOutputResponse>;
+
+    async fn get_concurrency_stat(&self, req: UtilGetConcurrencyStatOutputRequest)
+        -> BuckyResult<UtilGetConcurrencyStatOutputResponse>;
+
+    async fn get_object_access_stat(&self, req: UtilGetObjectAccessStatOutputRequest)
+        -> BuckyResult<UtilGetObjectAccessStatOutputResponse>;
 }
 
 pub type UtilOutputProcessorRef = Arc<Box<dyn UtilOutputProcessor>>;
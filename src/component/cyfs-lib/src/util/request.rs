@@ -39,3 +39,28 @@ pub type UtilBuildFileResponse = UtilBuildFileOutputResponse;
 
 pub type UtilBuildDirFromObjectMapRequest = UtilBuildDirFromObjectMapOutputRequest;
 pub type UtilBuildDirFromObjectMapResponse = UtilBuildDirFromObjectMapOutputResponse;
+pub type UtilPinAppWebDirRequest = UtilPinAppWebDirOutputRequest;
+pub type UtilPinAppWebDirResponse = UtilPinAppWebDirOutputResponse;
+
+pub type UtilUnpinAppWebDirRequest = UtilUnpinAppWebDirOutputRequest;
+pub type UtilUnpinAppWebDirResponse = UtilUnpinAppWebDirOutputResponse;
+
+pub type UtilQueryZoneEventsRequest = UtilQueryZoneEventsOutputRequest;
+pub type UtilQueryZoneEventsResponse = UtilQueryZoneEventsOutputResponse;
+
+pub type UtilGetZoneDeviceHealthRequest = UtilGetZoneDeviceHealthOutputRequest;
+pub type UtilGetZoneDeviceHealthResponse = UtilGetZoneDeviceHealthOutputResponse;
+pub type UtilGetOODResolverStatsRequest = UtilGetOODResolverStatsOutputRequest;
+pub type UtilGetOODResolverStatsResponse = UtilGetOODResolverStatsOutputResponse;
+pub type UtilSetDecResourcePolicyRequest = UtilSetDecResourcePolicyOutputRequest;
+pub type UtilSetDecResourcePolicyResponse = UtilSetDecResourcePolicyOutputResponse;
+
+pub type UtilRemoveDecResourcePolicyRequest = UtilRemoveDecResourcePolicyOutputRequest;
+pub type UtilRemoveDecResourcePolicyResponse = UtilRemoveDecResourcePolicyOutputResponse;
+
+pub type UtilGetDecResourcePoliciesRequest = UtilGetDecResourcePoliciesOutputRequest;
+pub type UtilGetDecResourcePoliciesResponse = UtilGetDecResourcePoliciesOutputResponse;
+pub type UtilGetConcurrencyStatRequest = UtilGetConcurrencyStatOutputRequest;
+pub type UtilGetConcurrencyStatResponse = UtilGetConcurrencyStatOutputResponse;
+pub type UtilGetObjectAccessStatRequest = UtilGetObjectAccessStatOutputRequest;
+pub type UtilGetObjectAccessStatResponse = UtilGetObjectAccessStatOutputResponse;
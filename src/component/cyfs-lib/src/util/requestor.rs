@@ -1,3 +1,4 @@
+use super::output_request::*;
 use super::processor::*;
 use super::request::*;
 use crate::{
@@ -513,6 +514,301 @@ impl UtilRequestor {
             Err(e)
         }
     }
+
+    pub async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirOutputResponse> {
+        let url = self.service_url.join("pin_app_web_dir").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = RequestorHelper::decode_json_body(&mut resp)
+                .await
+                .map_err(|e| {
+                    let msg = format!("parse pin app web dir resp body error! err={}", e);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util pin_app_web_dir failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirOutputResponse> {
+        let url = self.service_url.join("unpin_app_web_dir").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = RequestorHelper::decode_json_body(&mut resp)
+                .await
+                .map_err(|e| {
+                    let msg = format!("parse unpin app web dir resp body error! err={}", e);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util unpin_app_web_dir failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsOutputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsOutputResponse> {
+        let url = self.service_url.join("query_zone_events").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = RequestorHelper::decode_json_body(&mut resp)
+                .await
+                .map_err(|e| {
+                    let msg = format!("parse query zone events resp body error! err={}", e);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util query_zone_events failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    // get_zone_device_health
+    fn encode_get_zone_device_health_request(
+        &self,
+        req: UtilGetZoneDeviceHealthRequest,
+    ) -> Request {
+        let url = self.service_url.join("zone_device_health").unwrap();
+        let mut http_req = Request::new(Method::Get, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+
+        http_req
+    }
+
+    pub async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthResponse> {
+        let http_req = self.encode_get_zone_device_health_request(req);
+
+        let mut resp = self.requestor.request(http_req).await?;
+
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_zone_device_health resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "util get_zone_device_health failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsResponse> {
+        let url = self.service_url.join("ood_resolver_stats").unwrap();
+        let mut http_req = Request::new(Method::Get, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_ood_resolver_stats resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util get_ood_resolver_stats failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyResponse> {
+        let url = self.service_url.join("set_dec_resource_policy").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse set_dec_resource_policy resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util set_dec_resource_policy failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyResponse> {
+        let url = self.service_url.join("remove_dec_resource_policy").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse remove_dec_resource_policy resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util remove_dec_resource_policy failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesResponse> {
+        let url = self.service_url.join("get_dec_resource_policies").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_dec_resource_policies resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("util get_dec_resource_policies failed: status={}, {}", resp.status(), e);
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatResponse> {
+        let url = self.service_url.join("get_concurrency_stat").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_concurrency_stat resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "util get_concurrency_stat failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
+
+    pub async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatResponse> {
+        let url = self.service_url.join("get_object_access_stat").unwrap();
+        let mut http_req = Request::new(Method::Post, url);
+        self.encode_common_headers(&req.common, &mut http_req);
+        let body = req.encode_string();
+        http_req.set_body(body);
+
+        let mut resp = self.requestor.request(http_req).await?;
+        if resp.status().is_success() {
+            let content = resp.body_json().await.map_err(|e| {
+                let msg = format!("parse get_object_access_stat resp body error! err={}", e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            Ok(content)
+        } else {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "util get_object_access_stat failed: status={}, {}",
+                resp.status(),
+                e
+            );
+
+            Err(e)
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -591,4 +887,74 @@ impl UtilOutputProcessor for UtilRequestor {
     ) -> BuckyResult<UtilBuildDirFromObjectMapOutputResponse> {
         Self::build_dir_from_object_map(self, req).await
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirOutputResponse> {
+        Self::pin_app_web_dir(self, req).await
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirOutputResponse> {
+        Self::unpin_app_web_dir(self, req).await
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsOutputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsOutputResponse> {
+        Self::query_zone_events(self, req).await
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthOutputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthOutputResponse> {
+        Self::get_zone_device_health(self, req).await
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsOutputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsOutputResponse> {
+        Self::get_ood_resolver_stats(self, req).await
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyOutputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyOutputResponse> {
+        Self::set_dec_resource_policy(self, req).await
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyOutputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyOutputResponse> {
+        Self::remove_dec_resource_policy(self, req).await
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesOutputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesOutputResponse> {
+        Self::get_dec_resource_policies(self, req).await
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatOutputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatOutputResponse> {
+        Self::get_concurrency_stat(self, req).await
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatOutputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatOutputResponse> {
+        Self::get_object_access_stat(self, req).await
+    }
 }
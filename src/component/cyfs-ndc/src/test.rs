@@ -399,6 +399,7 @@ async fn test_chunk(cache: &Box<dyn NamedDataCache>) {
         let req = SelectChunkRequest {
             filter: SelectChunkFilter {
                 state: Some(ChunkState::Ready),
+                last_access_before: None,
             },
             opt: SelectChunkOption::default(),
         };
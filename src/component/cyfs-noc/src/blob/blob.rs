@@ -17,10 +17,19 @@ pub struct BlobStorageDeleteObjectResponse {
 #[async_trait::async_trait]
 pub trait BlobStorage: Send + Sync {
     async fn put_object(&self, data: NONObjectInfo) -> BuckyResult<()>;
+
+    // 使用存储密钥加密之后保存，读取时自动解密
+    async fn put_object_encrypted(&self, data: NONObjectInfo) -> BuckyResult<()>;
     async fn get_object(&self, object_id: &ObjectId) -> BuckyResult<Option<NONObjectInfo>>;
     async fn delete_object(&self, object_id: &ObjectId, flags: u32) -> BuckyResult<BlobStorageDeleteObjectResponse>;
     async fn exists_object(&self, object_id: &ObjectId) -> BuckyResult<bool>;
     async fn stat(&self) -> BuckyResult<BlobStorageStat>;
+
+    // 列出所有的blob，用以一致性检查
+    async fn list_objects(&self) -> BuckyResult<Vec<ObjectId>>;
+
+    // 把blob移到隔离区，不再对外可见
+    async fn quarantine_object(&self, object_id: &ObjectId) -> BuckyResult<()>;
 }
 
 pub type BlobStorageRef = Arc<Box<dyn BlobStorage>>;
\ No newline at end of file
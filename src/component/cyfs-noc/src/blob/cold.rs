@@ -0,0 +1,189 @@
+use super::blob::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+use cyfs_util::{ColdPackStat, ColdPackStore};
+
+use async_std::sync::RwLock;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+// 在热存储之上叠加冷存储层，归档后的blob从热存储删除，只在冷存储里保留stub
+// 读取stub时自动水合回热存储，对上层的meta和noc透明
+#[derive(Clone)]
+pub(crate) struct ColdBlobStorage {
+    hot: BlobStorageRef,
+    cold: ColdPackStore,
+
+    // 归档和写入互斥，避免归档过程中写入的新数据被随后的删除覆盖
+    archive_lock: Arc<RwLock<()>>,
+}
+
+impl ColdBlobStorage {
+    pub fn new(hot: Box<dyn BlobStorage>, dir: PathBuf, readonly: bool) -> BuckyResult<Self> {
+        let cold = ColdPackStore::open(dir, readonly)?;
+
+        Ok(Self {
+            hot: Arc::new(hot),
+            cold,
+            archive_lock: Arc::new(RwLock::new(())),
+        })
+    }
+
+    pub fn contains(&self, object_id: &ObjectId) -> bool {
+        self.cold.contains(object_id)
+    }
+
+    pub fn stat(&self) -> ColdPackStat {
+        self.cold.stat()
+    }
+
+    pub fn set_hydrate_budget(&self, budget: Duration) {
+        self.cold.set_hydrate_budget(budget)
+    }
+
+    // 把热存储里的blob归档到一个新的pack，返回实际归档的数量
+    pub async fn archive(&self, list: &[ObjectId]) -> BuckyResult<usize> {
+        let _lock = self.archive_lock.write().await;
+
+        let mut items = Vec::with_capacity(list.len());
+        for object_id in list {
+            if self.cold.contains(object_id) {
+                continue;
+            }
+
+            match self.hot.get_object(object_id).await {
+                Ok(Some(data)) => items.push((object_id.to_owned(), data.object_raw)),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(
+                        "load object blob for archive failed! obj={}, {}",
+                        object_id, e
+                    );
+                }
+            }
+        }
+
+        let ids: Vec<ObjectId> = items.iter().map(|(id, _)| id.to_owned()).collect();
+        let count = self.cold.archive(items).await?;
+
+        // pack已经落盘，这里删除失败只会导致热存储里多一份数据
+        for object_id in &ids {
+            if let Err(e) = self.hot.delete_object(object_id, 0).await {
+                warn!(
+                    "remove archived object blob failed! obj={}, {}",
+                    object_id, e
+                );
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn hydrate(&self, object_id: &ObjectId) -> BuckyResult<Option<NONObjectInfo>> {
+        let hot = self.hot.clone();
+        let ret = self
+            .cold
+            .hydrate(object_id, move |object_raw, encrypted| async move {
+                let data = NONObjectInfo::new_from_object_raw(object_raw)?;
+                if encrypted {
+                    hot.put_object_encrypted(data).await
+                } else {
+                    hot.put_object(data).await
+                }
+            })
+            .await?;
+
+        match ret {
+            Some(object_raw) => Ok(Some(NONObjectInfo::new_from_object_raw(object_raw)?)),
+            None => Ok(None),
+        }
+    }
+
+    // 新写入的数据取代冷存储里的旧版本
+    async fn drop_stub(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        if self.cold.contains(object_id) {
+            self.cold.remove(object_id).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStorage for ColdBlobStorage {
+    async fn put_object(&self, data: NONObjectInfo) -> BuckyResult<()> {
+        let _lock = self.archive_lock.read().await;
+
+        let object_id = data.object_id.clone();
+        self.hot.put_object(data).await?;
+        self.drop_stub(&object_id).await
+    }
+
+    async fn put_object_encrypted(&self, data: NONObjectInfo) -> BuckyResult<()> {
+        let _lock = self.archive_lock.read().await;
+
+        let object_id = data.object_id.clone();
+        self.hot.put_object_encrypted(data).await?;
+        self.drop_stub(&object_id).await
+    }
+
+    async fn get_object(&self, object_id: &ObjectId) -> BuckyResult<Option<NONObjectInfo>> {
+        if let Some(data) = self.hot.get_object(object_id).await? {
+            return Ok(Some(data));
+        }
+
+        self.hydrate(object_id).await
+    }
+
+    async fn delete_object(
+        &self,
+        object_id: &ObjectId,
+        flags: u32,
+    ) -> BuckyResult<BlobStorageDeleteObjectResponse> {
+        let _lock = self.archive_lock.read().await;
+
+        let mut resp = self.hot.delete_object(object_id, flags).await?;
+        if self.cold.contains(object_id) {
+            if resp.object.is_none() && flags & CYFS_NOC_FLAG_DELETE_WITH_QUERY != 0 {
+                if let Ok(Some((object_raw, _))) = self.cold.load(object_id).await {
+                    resp.object = NONObjectInfo::new_from_object_raw(object_raw).ok();
+                }
+            }
+
+            if self.cold.remove(object_id).await? {
+                resp.delete_count = 1;
+            }
+        }
+
+        Ok(resp)
+    }
+
+    async fn exists_object(&self, object_id: &ObjectId) -> BuckyResult<bool> {
+        if self.cold.contains(object_id) {
+            return Ok(true);
+        }
+
+        self.hot.exists_object(object_id).await
+    }
+
+    async fn stat(&self) -> BuckyResult<BlobStorageStat> {
+        self.hot.stat().await
+    }
+
+    async fn list_objects(&self) -> BuckyResult<Vec<ObjectId>> {
+        let mut list = self.hot.list_objects().await?;
+        list.extend(self.cold.list());
+
+        Ok(list)
+    }
+
+    async fn quarantine_object(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        // 隔离区只针对热存储，先水合回来
+        if self.cold.contains(object_id) {
+            self.hydrate(object_id).await?;
+        }
+
+        self.hot.quarantine_object(object_id).await
+    }
+}
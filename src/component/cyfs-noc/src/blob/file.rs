@@ -1,25 +1,46 @@
 use super::blob::*;
+use crate::durability::*;
 use cyfs_base::*;
 use cyfs_lib::*;
+use cyfs_util::StorageCipher;
 
 use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "windows"))]
+use std::str::FromStr;
 
 pub struct FileBlobStorage {
     root: PathBuf,
+    coalescer: CommitCoalescer,
+
+    // 默认使用owner解锁后的全局密钥，测试时可以单独指定
+    cipher: Option<StorageCipher>,
     #[cfg(target_os = "windows")]
     upgrade: super::old_base36::FileBlobStorageUpgrade,
 }
 
 impl FileBlobStorage {
     pub fn new(root: PathBuf) -> Self {
+        Self::new_with_coalescer(root, CommitCoalescer::new(NamedObjectCacheDurability::Always))
+    }
+
+    pub(crate) fn new_with_coalescer(root: PathBuf, coalescer: CommitCoalescer) -> Self {
         Self {
             #[cfg(target_os = "windows")]
             upgrade: super::old_base36::FileBlobStorageUpgrade::new(root.clone()),
 
             root,
+            coalescer,
+            cipher: None,
         }
     }
 
+    // 存储密钥在owner解锁之后才会设置，所以每次使用时读取
+    fn cipher(&self) -> Option<&StorageCipher> {
+        self.cipher
+            .as_ref()
+            .or_else(|| cyfs_util::get_storage_cipher())
+    }
+
     async fn get_full_path(&self, object_id: &ObjectId, auto_create: bool) -> BuckyResult<PathBuf> {
         let hash_str;
         let len;
@@ -79,24 +100,133 @@ impl FileBlobStorage {
             BuckyError::new(BuckyErrorCode::IoError, msg)
         })?;
 
+        let object_raw = if StorageCipher::is_encrypted(&object_raw) {
+            self.decrypt(path, object_raw)?
+        } else {
+            object_raw
+        };
+
         let info = NONObjectInfo::new_from_object_raw(object_raw)?;
         Ok(info)
     }
 
-    fn write_sync<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> std::io::Result<()> {
+    // 加密时以文件名对应的object_id派生密钥
+    fn decrypt(&self, path: &Path, object_raw: Vec<u8>) -> BuckyResult<Vec<u8>> {
+        let cipher = self.cipher().ok_or_else(|| {
+            let msg = format!(
+                "object blob is encrypted but storage key not unlocked! path={}",
+                path.display()
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
+        })?;
+
+        let object_id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::parse_file_name)
+            .ok_or_else(|| {
+                let msg = format!(
+                    "decrypt object blob but invalid file name! path={}",
+                    path.display()
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+        cipher.decrypt(object_id.as_slice(), &object_raw)
+    }
+
+    async fn save_object(&self, data: &NONObjectInfo, contents: &[u8]) -> BuckyResult<()> {
+        let path = self.get_full_path(&data.object_id, true).await?;
+
+        let file = Self::write(&path, contents).await.map_err(|e| {
+            let msg = format!(
+                "save object blob to file error! path={}, size={}bytes, {}",
+                path.display(),
+                contents.len(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        self.coalescer.commit_file(file).await
+    }
+
+    fn parse_file_name(name: &str) -> Option<ObjectId> {
+        #[cfg(target_os = "windows")]
+        let ret = ObjectId::from_base36(name);
+        #[cfg(not(target_os = "windows"))]
+        let ret = ObjectId::from_str(name);
+
+        match ret {
+            Ok(id) => Some(id),
+            Err(_) => {
+                warn!("unknown file in blob storage! name={}", name);
+                None
+            }
+        }
+    }
+
+    // objects/{first}/{second}/{object_id}
+    fn list_objects_sync(root: &Path) -> std::io::Result<Vec<ObjectId>> {
+        let mut list = vec![];
+        for first in std::fs::read_dir(root)? {
+            let first = first?.path();
+            if !first.is_dir() {
+                continue;
+            }
+
+            for second in std::fs::read_dir(&first)? {
+                let second = second?.path();
+                if !second.is_dir() {
+                    continue;
+                }
+
+                for entry in std::fs::read_dir(&second)? {
+                    let entry = entry?;
+                    if !entry.file_type()?.is_file() {
+                        continue;
+                    }
+
+                    if let Some(id) = entry.file_name().to_str().and_then(Self::parse_file_name) {
+                        list.push(id);
+                    }
+                }
+            }
+        }
+
+        Ok(list)
+    }
+
+    fn quarantine_dir(&self) -> PathBuf {
+        match self.root.parent() {
+            Some(parent) => parent.join("quarantine"),
+            None => self.root.join("quarantine"),
+        }
+    }
+
+    // 只负责写入，是否以及何时fsync由持久化策略决定
+    fn write_sync<P: AsRef<Path>, C: AsRef<[u8]>>(
+        path: P,
+        contents: C,
+    ) -> std::io::Result<std::fs::File> {
         use std::fs::File;
         use std::io::Write;
 
-        fn inner(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        fn inner(path: &Path, contents: &[u8]) -> std::io::Result<File> {
             let mut file = File::create(path)?;
             file.write_all(contents)?;
-            file.sync_all()?;
-            Ok(())
+            Ok(file)
         }
         inner(path.as_ref(), contents.as_ref())
     }
 
-    async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> std::io::Result<()> {
+    async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(
+        path: P,
+        contents: C,
+    ) -> std::io::Result<std::fs::File> {
         let path = path.as_ref().to_owned();
         let contents = contents.as_ref().to_owned();
         async_std::task::spawn_blocking(move || Self::write_sync(&path, contents)).await
@@ -106,23 +236,34 @@ impl FileBlobStorage {
 #[async_trait::async_trait]
 impl BlobStorage for FileBlobStorage {
     async fn put_object(&self, data: NONObjectInfo) -> BuckyResult<()> {
-        let path = self.get_full_path(&data.object_id, true).await?;
+        self.save_object(&data, &data.object_raw).await?;
+
+        debug!(
+            "save object blob to file success! object={}, size={}bytes",
+            data.object_id,
+            data.object_raw.len(),
+        );
+        Ok(())
+    }
 
-        Self::write(&path, &data.object_raw).await.map_err(|e| {
+    async fn put_object_encrypted(&self, data: NONObjectInfo) -> BuckyResult<()> {
+        // 未解锁时不能退化为明文保存
+        let cipher = self.cipher().ok_or_else(|| {
             let msg = format!(
-                "save object blob to file error! path={}, size={}bytes, {}",
-                path.display(),
-                data.object_raw.len(),
-                e
+                "object blob should be encrypted but storage key not unlocked! object={}",
+                data.object_id
             );
             error!("{}", msg);
-            BuckyError::new(BuckyErrorCode::IoError, msg)
+            BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
         })?;
 
+        let contents = cipher.encrypt(data.object_id.as_slice(), &data.object_raw)?;
+        self.save_object(&data, &contents).await?;
+
         debug!(
-            "save object blob to file success! object={}, size={}bytes",
+            "save encrypted object blob to file success! object={}, size={}bytes",
             data.object_id,
-            data.object_raw.len(),
+            contents.len(),
         );
         Ok(())
     }
@@ -208,6 +349,57 @@ impl BlobStorage for FileBlobStorage {
 
         Ok(resp)
     }
+
+    async fn list_objects(&self) -> BuckyResult<Vec<ObjectId>> {
+        let root = self.root.clone();
+        async_std::task::spawn_blocking(move || Self::list_objects_sync(&root))
+            .await
+            .map_err(|e| {
+                let msg = format!(
+                    "list objects in blob storage error! root={}, {}",
+                    self.root.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })
+    }
+
+    async fn quarantine_object(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        let path = self.get_full_path(object_id, false).await?;
+        if !path.exists() {
+            let msg = format!("quarantine object but blob not found! object={}", object_id);
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        let dir = self.quarantine_dir();
+        async_std::fs::create_dir_all(&dir).await.map_err(|e| {
+            let msg = format!("create quarantine dir error! dir={}, {}", dir.display(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let target = dir.join(path.file_name().unwrap());
+        async_std::fs::rename(&path, &target).await.map_err(|e| {
+            let msg = format!(
+                "move object blob to quarantine error! path={}, target={}, {}",
+                path.display(),
+                target.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        warn!(
+            "object blob quarantined! object={}, path={}",
+            object_id,
+            target.display()
+        );
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +433,46 @@ mod test {
         println!("{}", obj);
     }
 
+    #[test]
+    fn test_encrypt() {
+        let root = cyfs_util::get_temp_path().join("test_blob_storage_encrypt");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut storage = FileBlobStorage::new(root.clone());
+
+        async_std::task::block_on(async move {
+            let obj = Text::create("test-encrypt", "private", "data");
+            let data = NONObjectInfo::new_from_object_raw(obj.to_vec().unwrap()).unwrap();
+
+            // 未解锁时拒绝写入，不会落盘明文
+            let e = storage
+                .put_object_encrypted(data.clone())
+                .await
+                .unwrap_err();
+            assert_eq!(e.code(), BuckyErrorCode::PermissionDenied);
+            let path = storage.get_full_path(&data.object_id, false).await.unwrap();
+            assert!(!path.exists());
+
+            storage.cipher = Some(StorageCipher::from_passphrase("test", b"salt"));
+            storage.put_object_encrypted(data.clone()).await.unwrap();
+
+            // 磁盘上不包含明文
+            let path = storage.get_full_path(&data.object_id, false).await.unwrap();
+            let raw = std::fs::read(&path).unwrap();
+            assert!(StorageCipher::is_encrypted(&raw));
+            assert!(raw.windows(7).all(|v| v != b"private"));
+
+            let ret = storage.get_object(&data.object_id).await.unwrap().unwrap();
+            assert_eq!(ret.object_raw, data.object_raw);
+
+            // 没有密钥无法读取
+            let other = FileBlobStorage::new(root);
+            let e = other.get_object(&data.object_id).await.unwrap_err();
+            assert_eq!(e.code(), BuckyErrorCode::PermissionDenied);
+        });
+    }
+
     #[test]
     fn main() {
         async_std::task::block_on(async move {
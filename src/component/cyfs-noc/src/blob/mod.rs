@@ -1,14 +1,25 @@
 mod blob;
+mod cold;
 mod file;
 mod old_base36;
 
 pub use blob::*;
+pub(crate) use cold::*;
 pub use file::*;
 
+use crate::durability::*;
 use cyfs_base::*;
 use std::path::Path;
 
 pub async fn create_blob_storage(root: &Path) -> BuckyResult<Box<dyn BlobStorage>> {
+    let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::Always);
+    create_blob_storage_with_coalescer(root, coalescer).await
+}
+
+pub(crate) async fn create_blob_storage_with_coalescer(
+    root: &Path,
+    coalescer: CommitCoalescer,
+) -> BuckyResult<Box<dyn BlobStorage>> {
     let dir = root.join("objects");
 
     if !dir.is_dir() {
@@ -24,7 +35,7 @@ pub async fn create_blob_storage(root: &Path) -> BuckyResult<Box<dyn BlobStorage
         }
     }
 
-    let blob = FileBlobStorage::new(dir);
+    let blob = FileBlobStorage::new_with_coalescer(dir, coalescer);
 
     Ok(Box::new(blob))
 }
\ No newline at end of file
@@ -246,6 +246,20 @@ impl NamedObjectCache for NamedObjectCacheMemoryCache {
         self.next.select_object(req).await
     }
 
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        self.next.get_object_revision(req).await
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
@@ -0,0 +1,274 @@
+use super::policy::*;
+use cyfs_base::*;
+
+use async_std::channel::Sender;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+enum CommitTarget {
+    File(std::fs::File),
+
+    // 按路径同步的文件，同一个窗口内只需要同步一次，比如meta数据库的wal文件
+    Path(PathBuf),
+}
+
+impl CommitTarget {
+    fn sync(&self) -> std::io::Result<()> {
+        match self {
+            Self::File(file) => file.sync_all(),
+            Self::Path(path) => {
+                // windows下需要写权限才能flush
+                match std::fs::OpenOptions::new().write(true).open(path) {
+                    Ok(file) => file.sync_all(),
+
+                    // wal文件在checkpoint之后可能已经被移除，此时数据已经同步到主库
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Self::File(_) => "file".to_owned(),
+            Self::Path(path) => path.display().to_string(),
+        }
+    }
+}
+
+struct CommitBatchItem {
+    target: CommitTarget,
+    waiters: Vec<Sender<BuckyResult<()>>>,
+}
+
+#[derive(Default)]
+struct CommitCoalescerState {
+    pending: Vec<CommitBatchItem>,
+
+    // 当前窗口是否已经安排了同步
+    scheduled: bool,
+}
+
+impl CommitCoalescerState {
+    fn add(&mut self, target: CommitTarget, waiter: Sender<BuckyResult<()>>) {
+        if let CommitTarget::Path(path) = &target {
+            let exists = self.pending.iter_mut().find(|item| match &item.target {
+                CommitTarget::Path(v) => v == path,
+                _ => false,
+            });
+
+            if let Some(item) = exists {
+                item.waiters.push(waiter);
+                return;
+            }
+        }
+
+        self.pending.push(CommitBatchItem {
+            target,
+            waiters: vec![waiter],
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommitCoalescerStat {
+    // 提交的写入次数
+    pub commits: u64,
+
+    // 实际执行的fsync次数
+    pub syncs: u64,
+
+    // group-commit模式下执行的批次
+    pub batches: u64,
+}
+
+// 按照持久化策略同步写入的数据，group-commit模式下把窗口期内的同步请求合并执行
+// meta和blob共用同一个实例，这样一个窗口内的数据库和文件写入可以一起落盘
+#[derive(Clone)]
+pub(crate) struct CommitCoalescer {
+    durability: NamedObjectCacheDurability,
+    state: Arc<Mutex<CommitCoalescerState>>,
+    stat: Arc<Mutex<CommitCoalescerStat>>,
+}
+
+impl CommitCoalescer {
+    pub fn new(durability: NamedObjectCacheDurability) -> Self {
+        Self {
+            durability,
+            state: Arc::new(Mutex::new(CommitCoalescerState::default())),
+            stat: Arc::new(Mutex::new(CommitCoalescerStat::default())),
+        }
+    }
+
+    pub fn durability(&self) -> &NamedObjectCacheDurability {
+        &self.durability
+    }
+
+    pub fn stat(&self) -> CommitCoalescerStat {
+        self.stat.lock().unwrap().clone()
+    }
+
+    // 文件内容写入之后调用，返回时按照策略已经完成了同步
+    pub async fn commit_file(&self, file: std::fs::File) -> BuckyResult<()> {
+        self.commit(CommitTarget::File(file)).await
+    }
+
+    pub async fn commit_path(&self, path: &Path) -> BuckyResult<()> {
+        self.commit(CommitTarget::Path(path.to_owned())).await
+    }
+
+    async fn commit(&self, target: CommitTarget) -> BuckyResult<()> {
+        self.stat.lock().unwrap().commits += 1;
+
+        match self.durability {
+            NamedObjectCacheDurability::Always => {
+                self.stat.lock().unwrap().syncs += 1;
+                async_std::task::spawn_blocking(move || {
+                    target.sync().map_err(|e| {
+                        let msg =
+                            format!("sync noc data error! target={}, {}", target.display(), e);
+                        error!("{}", msg);
+                        BuckyError::new(BuckyErrorCode::IoError, msg)
+                    })
+                })
+                .await
+            }
+            NamedObjectCacheDurability::GroupCommit(window) => self.submit(target, window).await,
+            NamedObjectCacheDurability::Relaxed => Ok(()),
+        }
+    }
+
+    async fn submit(&self, target: CommitTarget, window: u32) -> BuckyResult<()> {
+        let (tx, rx) = async_std::channel::bounded(1);
+        let schedule = {
+            let mut state = self.state.lock().unwrap();
+            state.add(target, tx);
+
+            let schedule = !state.scheduled;
+            state.scheduled = true;
+            schedule
+        };
+
+        // 窗口内的第一个写入负责安排同步
+        if schedule {
+            let this = self.clone();
+            async_std::task::spawn(async move {
+                async_std::task::sleep(Duration::from_millis(window as u64)).await;
+                this.flush().await;
+            });
+        }
+
+        rx.recv().await.map_err(|e| {
+            let msg = format!("wait for noc group commit error! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InternalError, msg)
+        })?
+    }
+
+    // 同步当前所有挂起的写入并唤醒等待者
+    pub async fn flush(&self) {
+        let batch = {
+            let mut state = self.state.lock().unwrap();
+            state.scheduled = false;
+            std::mem::take(&mut state.pending)
+        };
+
+        if batch.is_empty() {
+            return;
+        }
+
+        let count = batch.len();
+        let results = async_std::task::spawn_blocking(move || {
+            batch
+                .into_iter()
+                .map(|item| {
+                    let ret = item.target.sync().map_err(|e| {
+                        let msg = format!(
+                            "group commit sync noc data error! target={}, {}",
+                            item.target.display(),
+                            e
+                        );
+                        error!("{}", msg);
+                        BuckyError::new(BuckyErrorCode::IoError, msg)
+                    });
+                    (item.waiters, ret)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await;
+
+        {
+            let mut stat = self.stat.lock().unwrap();
+            stat.batches += 1;
+            stat.syncs += count as u64;
+        }
+
+        debug!("noc group commit complete! syncs={}", count);
+
+        for (waiters, ret) in results {
+            for waiter in waiters {
+                let _ = waiter.try_send(ret.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn commit_many(coalescer: &CommitCoalescer, path: &Path, count: usize) {
+        let list: Vec<_> = (0..count)
+            .map(|_| {
+                let coalescer = coalescer.clone();
+                let path = path.to_owned();
+                async_std::task::spawn(async move { coalescer.commit_path(&path).await })
+            })
+            .collect();
+
+        for task in list {
+            task.await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_coalescer() {
+        let dir = cyfs_util::get_temp_path().join("test_noc_commit_coalescer");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data");
+        std::fs::write(&path, b"test").unwrap();
+
+        async_std::task::block_on(async move {
+            let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::Always);
+            commit_many(&coalescer, &path, 8).await;
+            let stat = coalescer.stat();
+            assert_eq!(stat.commits, 8);
+            assert_eq!(stat.syncs, 8);
+
+            // 同一个窗口内对同一个文件的同步合并成一次
+            let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::GroupCommit(200));
+            commit_many(&coalescer, &path, 8).await;
+            let stat = coalescer.stat();
+            assert_eq!(stat.commits, 8);
+            assert!(stat.syncs < 8);
+            assert_eq!(stat.syncs, stat.batches);
+
+            let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+            coalescer.commit_file(file).await.unwrap();
+            assert_eq!(coalescer.stat().commits, 9);
+
+            let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::Relaxed);
+            commit_many(&coalescer, &path, 8).await;
+            assert_eq!(coalescer.stat().syncs, 0);
+
+            // 不存在的wal文件视为已经同步
+            let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::GroupCommit(10));
+            coalescer
+                .commit_path(&dir.join("not-exists"))
+                .await
+                .unwrap();
+        });
+    }
+}
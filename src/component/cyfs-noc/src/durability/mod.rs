@@ -0,0 +1,5 @@
+mod coalescer;
+mod policy;
+
+pub(crate) use coalescer::*;
+pub use policy::*;
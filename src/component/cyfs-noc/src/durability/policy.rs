@@ -0,0 +1,99 @@
+use cyfs_base::*;
+
+use std::str::FromStr;
+
+// group-commit未指定窗口时使用的默认合并窗口，单位ms
+pub const NOC_GROUP_COMMIT_DEFAULT_WINDOW: u32 = 10;
+
+// noc的meta数据库和blob文件写入的持久化策略
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NamedObjectCacheDurability {
+    // 每次写入都立即fsync，写入返回时数据一定已经落盘
+    Always,
+
+    // 窗口期(ms)内的写入合并后统一fsync，写入在所在窗口同步完成后才返回
+    GroupCommit(u32),
+
+    // 不主动fsync，由操作系统决定何时落盘；进程崩溃不会丢失数据，但系统掉电可能丢失最近的写入
+    Relaxed,
+}
+
+impl Default for NamedObjectCacheDurability {
+    fn default() -> Self {
+        Self::Always
+    }
+}
+
+impl NamedObjectCacheDurability {
+    // meta数据库在WAL模式下对应的synchronous级别
+    pub(crate) fn sqlite_synchronous(&self) -> &'static str {
+        match self {
+            Self::Always => "FULL",
+            Self::GroupCommit(_) | Self::Relaxed => "NORMAL",
+        }
+    }
+}
+
+impl std::fmt::Display for NamedObjectCacheDurability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::GroupCommit(window) => write!(f, "group-commit:{}", window),
+            Self::Relaxed => write!(f, "relaxed"),
+        }
+    }
+}
+
+// 支持always, relaxed, group-commit, group-commit:{window_ms}
+impl FromStr for NamedObjectCacheDurability {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        let ret = match s {
+            "always" => Self::Always,
+            "relaxed" => Self::Relaxed,
+            "group-commit" => Self::GroupCommit(NOC_GROUP_COMMIT_DEFAULT_WINDOW),
+            _ => {
+                let window = s
+                    .strip_prefix("group-commit:")
+                    .and_then(|v| u32::from_str(v).ok())
+                    .filter(|v| *v > 0);
+
+                match window {
+                    Some(window) => Self::GroupCommit(window),
+                    None => {
+                        let msg = format!("invalid noc durability mode: {}", s);
+                        error!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+                    }
+                }
+            }
+        };
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for mode in [
+            NamedObjectCacheDurability::Always,
+            NamedObjectCacheDurability::GroupCommit(20),
+            NamedObjectCacheDurability::Relaxed,
+        ] {
+            let s = mode.to_string();
+            assert_eq!(NamedObjectCacheDurability::from_str(&s).unwrap(), mode);
+        }
+
+        assert_eq!(
+            NamedObjectCacheDurability::from_str("group-commit").unwrap(),
+            NamedObjectCacheDurability::GroupCommit(NOC_GROUP_COMMIT_DEFAULT_WINDOW)
+        );
+        assert!(NamedObjectCacheDurability::from_str("group-commit:0").is_err());
+        assert!(NamedObjectCacheDurability::from_str("fsync").is_err());
+    }
+}
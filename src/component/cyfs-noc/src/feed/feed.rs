@@ -0,0 +1,167 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::{Arc, RwLock};
+
+#[derive(Clone)]
+pub enum NamedObjectCacheChangeEvent {
+    // 新增或者更新了对象
+    Put(NONObjectInfo),
+
+    // 删除了对象
+    Delete(ObjectId),
+}
+
+impl std::fmt::Display for NamedObjectCacheChangeEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Put(object) => write!(f, "put: {}", object.object_id),
+            Self::Delete(object_id) => write!(f, "delete: {}", object_id),
+        }
+    }
+}
+
+// 回调在noc的写操作路径上同步调用，实现方不能阻塞，耗时操作需要自己投递到队列里异步处理
+pub trait NamedObjectCacheChangeListener: Send + Sync {
+    fn on_change(&self, event: &NamedObjectCacheChangeEvent);
+}
+
+pub type NamedObjectCacheChangeListenerRef = Arc<Box<dyn NamedObjectCacheChangeListener>>;
+
+#[derive(Clone)]
+pub struct NamedObjectCacheChangeFeed {
+    listeners: Arc<RwLock<Vec<NamedObjectCacheChangeListenerRef>>>,
+}
+
+impl NamedObjectCacheChangeFeed {
+    fn new() -> Self {
+        Self {
+            listeners: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    pub fn subscribe(&self, listener: NamedObjectCacheChangeListenerRef) {
+        self.listeners.write().unwrap().push(listener);
+    }
+
+    fn emit(&self, event: NamedObjectCacheChangeEvent) {
+        let listeners = self.listeners.read().unwrap().clone();
+        for listener in listeners {
+            listener.on_change(&event);
+        }
+    }
+}
+
+// noc的变更通知层，只有真正改变了存储内容的写操作才会产生事件
+pub struct NamedObjectCacheChangeNotifier {
+    next: NamedObjectCacheRef,
+    feed: NamedObjectCacheChangeFeed,
+}
+
+impl NamedObjectCacheChangeNotifier {
+    pub fn new(next: NamedObjectCacheRef) -> Self {
+        Self {
+            next,
+            feed: NamedObjectCacheChangeFeed::new(),
+        }
+    }
+
+    pub fn feed(&self) -> &NamedObjectCacheChangeFeed {
+        &self.feed
+    }
+}
+
+#[async_trait::async_trait]
+impl NamedObjectCache for NamedObjectCacheChangeNotifier {
+    async fn put_object(
+        &self,
+        req: &NamedObjectCachePutObjectRequest,
+    ) -> BuckyResult<NamedObjectCachePutObjectResponse> {
+        let resp = self.next.put_object(req).await?;
+        match resp.result {
+            NamedObjectCachePutObjectResult::Accept
+            | NamedObjectCachePutObjectResult::Updated
+            | NamedObjectCachePutObjectResult::Merged => {
+                self.feed
+                    .emit(NamedObjectCacheChangeEvent::Put(req.object.clone()));
+            }
+            NamedObjectCachePutObjectResult::AlreadyExists => {}
+        }
+
+        Ok(resp)
+    }
+
+    async fn get_object_raw(
+        &self,
+        req: &NamedObjectCacheGetObjectRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRawData>> {
+        self.next.get_object_raw(req).await
+    }
+
+    async fn delete_object(
+        &self,
+        req: &NamedObjectCacheDeleteObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheDeleteObjectResponse> {
+        let resp = self.next.delete_object(req).await?;
+        if resp.deleted_count > 0 {
+            self.feed
+                .emit(NamedObjectCacheChangeEvent::Delete(req.object_id.clone()));
+        }
+
+        Ok(resp)
+    }
+
+    async fn exists_object(
+        &self,
+        req: &NamedObjectCacheExistsObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheExistsObjectResponse> {
+        self.next.exists_object(req).await
+    }
+
+    async fn update_object_meta(
+        &self,
+        req: &NamedObjectCacheUpdateObjectMetaRequest,
+    ) -> BuckyResult<()> {
+        self.next.update_object_meta(req).await
+    }
+
+    async fn check_object_access(
+        &self,
+        req: &NamedObjectCacheCheckObjectAccessRequest,
+    ) -> BuckyResult<Option<()>> {
+        self.next.check_object_access(req).await
+    }
+
+    async fn stat(&self) -> BuckyResult<NamedObjectCacheStat> {
+        self.next.stat().await
+    }
+
+    async fn select_object(
+        &self,
+        req: &NamedObjectCacheSelectObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheSelectObjectResponse> {
+        self.next.select_object(req).await
+    }
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        self.next.get_object_revision(req).await
+    }
+
+    fn bind_object_meta_access_provider(
+        &self,
+        object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
+    ) {
+        self.next
+            .bind_object_meta_access_provider(object_meta_access_provider)
+    }
+}
@@ -4,12 +4,22 @@ mod blob;
 mod meta;
 mod storage;
 mod cache;
+mod feed;
 mod noc;
 mod relation;
+mod refs;
+mod durability;
 
 pub use noc::*;
 pub use relation::*;
+pub use refs::{NamedObjectRefExtractor, NamedObjectRefGraph};
+pub use feed::*;
+pub use durability::{NamedObjectCacheDurability, NOC_GROUP_COMMIT_DEFAULT_WINDOW};
 pub use blob::{BlobStorage, create_blob_storage};
+pub use storage::{
+    NamedObjectColdTier, NamedObjectStorageCheckOption, NamedObjectStorageCheckResult,
+    NamedObjectStorageChecker, NamedObjectStorageOrphanBlobAction,
+};
 
 #[macro_use]
 extern crate log;
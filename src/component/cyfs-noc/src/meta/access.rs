@@ -32,6 +32,28 @@ impl NamedObjecAccessHelper {
         }
     }
 
+    // 查询对象在rmeta里配置的历史版本保留数量，按对象的create_dec查找
+    pub async fn get_object_versions(&self, data: &NamedObjectMetaData) -> u32 {
+        let object_meta = match self.object_meta_access_provider.get() {
+            Some(v) => v,
+            None => return 0,
+        };
+
+        match object_meta
+            .get_object_versions(&data.create_dec_id, data)
+            .await
+        {
+            Ok(ret) => ret.unwrap_or(0),
+            Err(e) => {
+                error!(
+                    "query object versions from object meta failed! obj={}, create_dec={}, {}",
+                    data.object_id, data.create_dec_id, e
+                );
+                0
+            }
+        }
+    }
+
     fn check_object_access(
         object_id: &ObjectId,
         access_string: u32,
@@ -158,6 +158,27 @@ impl NamedObjectMeta for NamedObjectMetaWithAccessCache {
     ) -> BuckyResult<NamedObjectMetaSelectObjectResponse> {
         self.next.select_object(req).await
     }
+
+    async fn put_object_version(
+        &self,
+        req: &NamedObjectMetaPutObjectVersionRequest,
+    ) -> BuckyResult<Option<u32>> {
+        self.next.put_object_version(req).await
+    }
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectMetaGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectMetaGetObjectHistoryResponse> {
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectMetaGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectMetaObjectRevisionData>> {
+        self.next.get_object_revision(req).await
+    }
     
     fn bind_object_meta_access_provider(
         &self,
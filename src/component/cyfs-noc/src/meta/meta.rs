@@ -154,6 +154,27 @@ pub struct NamedObjectMetaStat {
 pub type NamedObjectMetaSelectObjectRequest = NamedObjectCacheSelectObjectRequest;
 pub type NamedObjectMetaSelectObjectResponse = NamedObjectCacheSelectObjectResponse;
 
+// put_object_version
+#[derive(Clone, Debug)]
+pub struct NamedObjectMetaPutObjectVersionRequest {
+    pub object_id: ObjectId,
+
+    // 被替换掉的对象版本
+    pub object_update_time: Option<u64>,
+    pub object_raw: Vec<u8>,
+}
+
+// get_object_history & get_object_revision
+pub type NamedObjectMetaGetObjectHistoryRequest = NamedObjectCacheGetObjectHistoryRequest;
+pub type NamedObjectMetaGetObjectHistoryResponse = NamedObjectCacheGetObjectHistoryResponse;
+pub type NamedObjectMetaGetObjectRevisionRequest = NamedObjectCacheGetObjectRevisionRequest;
+
+#[derive(Clone, Debug)]
+pub struct NamedObjectMetaObjectRevisionData {
+    pub version: NamedObjectCacheObjectVersion,
+    pub object_raw: Vec<u8>,
+}
+
 #[async_trait::async_trait]
 pub trait NamedObjectMeta: Sync + Send {
     async fn put_object(
@@ -195,6 +216,22 @@ pub trait NamedObjectMeta: Sync + Send {
         req: &NamedObjectMetaSelectObjectRequest,
     ) -> BuckyResult<NamedObjectMetaSelectObjectResponse>;
 
+    // 对象的body被替换前调用，按rmeta里配置的保留数量归档旧版本并清理超出的部分，没有开启历史版本返回None
+    async fn put_object_version(
+        &self,
+        req: &NamedObjectMetaPutObjectVersionRequest,
+    ) -> BuckyResult<Option<u32>>;
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectMetaGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectMetaGetObjectHistoryResponse>;
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectMetaGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectMetaObjectRevisionData>>;
+
     fn bind_object_meta_access_provider(
         &self,
         object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
@@ -7,13 +7,17 @@ pub use meta::*;
 pub(crate) use access::*;
 
 
+use crate::durability::CommitCoalescer;
 use cyfs_base::BuckyResult;
 
 use std::path::Path;
 use std::sync::Arc;
 
-pub(crate) fn create_meta(root: &Path) -> BuckyResult<meta::NamedObjectMetaRef> {
-    let meta = sqlite::SqliteMetaStorage::new(root)?;
+pub(crate) fn create_meta(
+    root: &Path,
+    coalescer: CommitCoalescer,
+) -> BuckyResult<meta::NamedObjectMetaRef> {
+    let meta = sqlite::SqliteMetaStorage::new(root, coalescer)?;
     let meta = Arc::new(Box::new(meta) as Box<dyn NamedObjectMeta>);
 
     let meta_with_cache = cache::NamedObjectMetaWithAccessCache::new(meta);
@@ -21,3 +25,10 @@ pub(crate) fn create_meta(root: &Path) -> BuckyResult<meta::NamedObjectMetaRef>
 
     Ok(meta_with_cache)
 }
+
+pub(crate) fn create_meta_readonly(root: &Path) -> BuckyResult<meta::NamedObjectMetaRef> {
+    let meta = sqlite::SqliteMetaStorage::new_readonly(root)?;
+    let meta = Arc::new(Box::new(meta) as Box<dyn NamedObjectMeta>);
+
+    Ok(meta)
+}
@@ -203,6 +203,8 @@ pub(super) struct NamedObjectMetaDataRaw {
 
     pub last_access_rpath: Option<String>,
     pub access_string: u32,
+
+    pub sign_verified_update_time: Option<u64>,
 }
 
 impl TryFrom<&Row<'_>> for NamedObjectMetaDataRaw {
@@ -232,6 +234,9 @@ impl TryFrom<&Row<'_>> for NamedObjectMetaDataRaw {
             object_create_time: row.get(13)?,
             author: row.get(14)?,
             dec_id: row.get(15)?,
+
+            // version 3
+            sign_verified_update_time: row.get(21)?,
         })
     }
 }
@@ -276,6 +281,8 @@ impl TryInto<NamedObjectMetaData> for NamedObjectMetaDataRaw {
 
             last_access_rpath: self.last_access_rpath,
             access_string: self.access_string,
+
+            sign_verified_update_time: self.sign_verified_update_time,
         })
     }
 }
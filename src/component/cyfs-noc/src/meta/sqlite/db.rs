@@ -2,6 +2,7 @@ use super::super::access::*;
 use super::super::meta::*;
 use super::data::*;
 use super::sql::*;
+use crate::durability::*;
 use cyfs_base::*;
 use cyfs_lib::*;
 use cyfs_util::SqliteConnectionHolder;
@@ -19,6 +20,7 @@ pub struct UpdateObjectMetaRequest<'a> {
     pub context: Option<&'a String>,
     pub last_access_rpath: Option<&'a String>,
     pub access_string: Option<u32>,
+    pub sign_verified_update_time: Option<u64>,
 }
 
 impl<'a> UpdateObjectMetaRequest<'a> {
@@ -27,20 +29,23 @@ impl<'a> UpdateObjectMetaRequest<'a> {
             && self.context.is_none()
             && self.last_access_rpath.is_none()
             && self.access_string.is_none()
+            && self.sign_verified_update_time.is_none()
     }
 }
 
 pub(crate) struct SqliteMetaStorage {
     data_dir: PathBuf,
     data_file: PathBuf,
+    wal_file: PathBuf,
 
     access: NamedObjecAccessHelper,
 
     conn: SqliteConnectionHolder,
+    coalescer: CommitCoalescer,
 }
 
 impl SqliteMetaStorage {
-    pub fn new(root: &Path) -> BuckyResult<Self> {
+    pub fn new(root: &Path, coalescer: CommitCoalescer) -> BuckyResult<Self> {
         let data_file = root.join("meta.db");
 
         // 需要在开启connection之前调用
@@ -52,11 +57,19 @@ impl SqliteMetaStorage {
             file_exists
         );
 
+        // synchronous是连接级别的设置，每个写连接都需要设置
+        let init_sql = format!(
+            "PRAGMA synchronous={}",
+            coalescer.durability().sqlite_synchronous()
+        );
+
         let ret = Self {
             data_dir: root.to_owned(),
             data_file: data_file.clone(),
+            wal_file: root.join("meta.db-wal"),
             access: NamedObjecAccessHelper::new(),
-            conn: SqliteConnectionHolder::new(data_file),
+            conn: SqliteConnectionHolder::new_with_init_sql(data_file, init_sql),
+            coalescer,
         };
 
         let valid = if file_exists {
@@ -87,6 +100,81 @@ impl SqliteMetaStorage {
             ret.check_and_update()?;
         }
 
+        ret.enable_wal();
+
+        Ok(ret)
+    }
+
+    // 只读模式，用于其它进程在协议栈运行时访问，不做任何初始化和升级
+    pub fn new_readonly(root: &Path) -> BuckyResult<Self> {
+        let data_file = root.join("meta.db");
+        if !data_file.exists() {
+            let msg = format!(
+                "open noc sqlite meta db in readonly mode but not found! file={}",
+                data_file.display()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        let ret = Self {
+            data_dir: root.to_owned(),
+            data_file: data_file.clone(),
+            wal_file: root.join("meta.db-wal"),
+            access: NamedObjecAccessHelper::new(),
+            conn: SqliteConnectionHolder::new(data_file),
+            coalescer: CommitCoalescer::new(NamedObjectCacheDurability::default()),
+        };
+
+        let version = {
+            let (conn, _lock) = ret.conn.get_read_conn()?;
+            Self::get_db_version(&conn)?
+        };
+
+        if version < CURRENT_VERSION {
+            let msg = format!(
+                "noc sqlite meta db version is old, should be updated by stack first! file={}, db={}, current={}",
+                ret.data_file.display(),
+                version,
+                CURRENT_VERSION
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        info!(
+            "open noc sqlite meta db in readonly mode success! file={}, version={}",
+            ret.data_file.display(),
+            version
+        );
+
+        Ok(ret)
+    }
+
+    // WAL模式下其它进程的只读连接不会阻塞写入，并且每个读事务看到的是一致的快照
+    fn enable_wal(&self) {
+        let ret = self.conn.get_write_conn().and_then(|(conn, _lock)| {
+            conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0))
+                .map_err(|e| {
+                    let msg = format!("enable noc meta db wal mode error! {}", e);
+                    BuckyError::new(BuckyErrorCode::SqliteError, msg)
+                })
+        });
+
+        match ret {
+            Ok(mode) => info!("noc meta db journal mode: {}", mode),
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    // group-commit模式下sqlite提交时不会同步wal，需要等待所在窗口统一同步之后才返回；
+    // always模式由sqlite在每次提交时同步wal，relaxed模式不主动同步
+    async fn commit<T>(&self, ret: BuckyResult<T>) -> BuckyResult<T> {
+        let ret = ret?;
+        if let NamedObjectCacheDurability::GroupCommit(_) = self.coalescer.durability() {
+            self.coalescer.commit_path(&self.wal_file).await?;
+        }
+
         Ok(ret)
     }
 
@@ -550,6 +638,7 @@ impl SqliteMetaStorage {
                                 context: req.context.as_ref(),
                                 last_access_rpath: req.last_access_rpath.as_ref(),
                                 access_string: Some(req.access_string.clone()),
+                                sign_verified_update_time: None,
                             };
 
                             let count = self.update_existing_meta(meta_req, &current_info)?;
@@ -1017,7 +1106,7 @@ impl SqliteMetaStorage {
         let count = {
             let (conn, _lock) = self.conn.get_write_conn()?;
 
-            conn.execute(&DELETE_SQL, params).map_err(|e| {
+            let count = conn.execute(&DELETE_SQL, params).map_err(|e| {
                 let msg = format!(
                     "noc meta delete error: obj={}, create_dec={}, err={}",
                     object_id, access_info.create_dec_id, e
@@ -1025,7 +1114,14 @@ impl SqliteMetaStorage {
                 error!("{}", msg);
 
                 BuckyError::new(BuckyErrorCode::SqliteError, msg)
-            })?
+            })?;
+
+            // 对象删除后历史版本也一起清理
+            if count > 0 {
+                let _ = Self::delete_versions(&conn, object_id);
+            }
+
+            count
         };
 
         let ret = if count > 0 {
@@ -1188,6 +1284,7 @@ impl SqliteMetaStorage {
                 context: req.context.as_ref(),
                 last_access_rpath: req.last_access_rpath.as_ref(),
                 access_string: req.access_string.clone(),
+                sign_verified_update_time: req.sign_verified_update_time,
             };
 
             let ret = self.update_existing_meta(meta_req, &current_info)?;
@@ -1244,6 +1341,13 @@ impl SqliteMetaStorage {
             params.push((":access", access_string as &dyn ToSql));
             sqls.push("access = :access");
         }
+        if let Some(sign_verified_update_time) = &req.sign_verified_update_time {
+            params.push((
+                ":sign_verified_update_time",
+                sign_verified_update_time as &dyn ToSql,
+            ));
+            sqls.push("sign_verified_update_time = :sign_verified_update_time");
+        }
 
         assert!(sqls.len() > 0);
         let sql = UPDATE_SQL.replace("{}", &sqls.join(","));
@@ -1316,22 +1420,32 @@ impl SqliteMetaStorage {
             querys.push(query);
         }
 
+        if let Some(last_access_before) = req.filter.last_access_before {
+            params.push(Box::new(last_access_before as i64));
+
+            let query = format!("last_access_time<?{}", params.len());
+            querys.push(query);
+        }
+
         let sql = if querys.len() > 0 {
             "SELECT object_id FROM data_namedobject_meta WHERE ".to_owned() + &querys.join(" AND ")
         } else {
             "SELECT object_id FROM data_namedobject_meta ".to_owned()
         };
 
-        // Sort by insert_time, decrease
-        let sql = sql + " ORDER BY insert_time DESC ";
+        // Sort by insert_time, decrease; select by last access sort by last_access_time, increase
+        let sql = if req.filter.last_access_before.is_some() {
+            sql + " ORDER BY last_access_time ASC "
+        } else {
+            sql + " ORDER BY insert_time DESC "
+        };
 
-        // Add pagination
-        let sql = sql
-            + &format!(
-                " LIMIT {} OFFSET {}",
-                req.opt.page_size,
-                req.opt.page_size * req.opt.page_index
-            );
+        // Add pagination, cursor takes precedence over page_index
+        let offset = match &req.opt.cursor {
+            Some(cursor) => cursor.to_offset()?,
+            None => (req.opt.page_size * req.opt.page_index) as u64,
+        };
+        let sql = sql + &format!(" LIMIT {} OFFSET {}", req.opt.page_size, offset);
 
         info!(
             "will select from meta: sql={} filter={:?}, opt={:?}",
@@ -1382,10 +1496,272 @@ impl SqliteMetaStorage {
             list.push(data);
         }
 
-        let resp = NamedObjectMetaSelectObjectResponse { list };
+        let page = PageInfo::from_offset(offset, list.len(), req.opt.page_size as u32, None);
+        let resp = NamedObjectMetaSelectObjectResponse { list, page };
 
         Ok(resp)
     }
+
+    async fn put_version(
+        &self,
+        req: &NamedObjectMetaPutObjectVersionRequest,
+    ) -> BuckyResult<Option<u32>> {
+        let data = match self.get_raw(&req.object_id)? {
+            Some(data) => data,
+            None => {
+                warn!(
+                    "noc meta put object version but object not found! obj={}",
+                    req.object_id
+                );
+                return Ok(None);
+            }
+        };
+
+        let keep = self.access.get_object_versions(&data).await;
+        if keep == 0 {
+            return Ok(None);
+        }
+
+        let revision = self.insert_version(req, keep)?;
+        Ok(Some(revision))
+    }
+
+    fn insert_version(
+        &self,
+        req: &NamedObjectMetaPutObjectVersionRequest,
+        keep: u32,
+    ) -> BuckyResult<u32> {
+        const MAX_REVISION_SQL: &str = r#"
+            SELECT MAX(revision) FROM data_namedobject_version WHERE object_id=:object_id;
+        "#;
+
+        const INSERT_VERSION_SQL: &str = r#"
+            INSERT INTO data_namedobject_version (object_id, revision, object_update_time, archive_time, object_raw)
+            VALUES (:object_id, :revision, :object_update_time, :archive_time, :object_raw);
+        "#;
+
+        // 只保留最近的keep个版本，revision不会复用
+        const PRUNE_VERSION_SQL: &str = r#"
+            DELETE FROM data_namedobject_version WHERE object_id=:object_id AND revision<=:revision;
+        "#;
+
+        let object_id = req.object_id.to_string();
+
+        let (conn, _lock) = self.conn.get_write_conn()?;
+
+        let current: Option<u32> = conn
+            .query_row(
+                MAX_REVISION_SQL,
+                named_params! {
+                    ":object_id": object_id,
+                },
+                |row| row.get(0),
+            )
+            .map_err(|e| {
+                let msg = format!(
+                    "noc meta query object max revision error: obj={}, {}",
+                    req.object_id, e
+                );
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::SqliteError, msg)
+            })?;
+
+        let revision = current.unwrap_or(0) + 1;
+
+        let params = named_params! {
+            ":object_id": object_id,
+            ":revision": revision,
+            ":object_update_time": req.object_update_time.unwrap_or(0),
+            ":archive_time": bucky_time_now(),
+            ":object_raw": req.object_raw,
+        };
+
+        conn.execute(INSERT_VERSION_SQL, params).map_err(|e| {
+            let msg = format!(
+                "noc meta insert object version error: obj={}, revision={}, {}",
+                req.object_id, revision, e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::SqliteError, msg)
+        })?;
+
+        if revision > keep {
+            let params = named_params! {
+                ":object_id": object_id,
+                ":revision": revision - keep,
+            };
+
+            let count = conn.execute(PRUNE_VERSION_SQL, params).map_err(|e| {
+                let msg = format!(
+                    "noc meta prune object versions error: obj={}, keep={}, {}",
+                    req.object_id, keep, e
+                );
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::SqliteError, msg)
+            })?;
+
+            if count > 0 {
+                info!(
+                    "noc meta prune object versions: obj={}, keep={}, count={}",
+                    req.object_id, keep, count
+                );
+            }
+        }
+
+        info!(
+            "noc meta archive object version success! obj={}, revision={}, object_update_time={:?}",
+            req.object_id, revision, req.object_update_time
+        );
+
+        Ok(revision)
+    }
+
+    fn delete_versions(conn: &Connection, object_id: &ObjectId) -> BuckyResult<usize> {
+        const DELETE_VERSIONS_SQL: &str = r#"
+            DELETE FROM data_namedobject_version WHERE object_id=:object_id;
+        "#;
+
+        let params = named_params! {
+            ":object_id": object_id.to_string(),
+        };
+
+        conn.execute(DELETE_VERSIONS_SQL, params).map_err(|e| {
+            let msg = format!(
+                "noc meta delete object versions error: obj={}, {}",
+                object_id, e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::SqliteError, msg)
+        })
+    }
+
+    fn version_from_row(row: &rusqlite::Row) -> rusqlite::Result<NamedObjectCacheObjectVersion> {
+        let object_update_time: i64 = row.get(1)?;
+        let archive_time: i64 = row.get(2)?;
+
+        Ok(NamedObjectCacheObjectVersion {
+            revision: row.get(0)?,
+            object_update_time: match object_update_time {
+                0 => None,
+                v @ _ => Some(v as u64),
+            },
+            archive_time: archive_time as u64,
+            object_size: row.get(3)?,
+        })
+    }
+
+    async fn check_read_access(
+        &self,
+        object_id: &ObjectId,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<bool> {
+        let ret = self.query_update_info(object_id)?;
+        if ret.is_none() {
+            return Ok(false);
+        }
+
+        let current_info = ret.unwrap();
+        self.access
+            .check_access_with_meta_update_info(
+                object_id,
+                source,
+                &current_info,
+                &current_info.create_dec_id,
+                RequestOpType::Read,
+            )
+            .await?;
+
+        Ok(true)
+    }
+
+    async fn get_history(
+        &self,
+        req: &NamedObjectMetaGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectMetaGetObjectHistoryResponse> {
+        const GET_HISTORY_SQL: &str = r#"
+            SELECT revision, object_update_time, archive_time, length(object_raw) FROM data_namedobject_version 
+            WHERE object_id=:object_id ORDER BY revision DESC;
+        "#;
+
+        if !self.check_read_access(&req.object_id, &req.source).await? {
+            return Ok(NamedObjectMetaGetObjectHistoryResponse { list: vec![] });
+        }
+
+        let (conn, _lock) = self.conn.get_read_conn()?;
+        let mut stmt = conn.prepare(GET_HISTORY_SQL).map_err(|e| {
+            let msg = format!("prepare get object history sql error: {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::SqliteError, msg)
+        })?;
+
+        let params = named_params! {
+            ":object_id": req.object_id.to_string(),
+        };
+
+        let mut rows = stmt.query(params).map_err(|e| {
+            let msg = format!(
+                "noc meta get object history error: obj={}, {}",
+                req.object_id, e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::SqliteError, msg)
+        })?;
+
+        let mut list = Vec::new();
+        while let Some(row) = rows.next()? {
+            list.push(Self::version_from_row(row)?);
+        }
+
+        Ok(NamedObjectMetaGetObjectHistoryResponse { list })
+    }
+
+    async fn get_revision(
+        &self,
+        req: &NamedObjectMetaGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectMetaObjectRevisionData>> {
+        const GET_REVISION_SQL: &str = r#"
+            SELECT revision, object_update_time, archive_time, length(object_raw), object_raw FROM data_namedobject_version 
+            WHERE object_id=:object_id AND revision=:revision;
+        "#;
+
+        if !self.check_read_access(&req.object_id, &req.source).await? {
+            return Ok(None);
+        }
+
+        let params = named_params! {
+            ":object_id": req.object_id.to_string(),
+            ":revision": req.revision,
+        };
+
+        let (conn, _lock) = self.conn.get_read_conn()?;
+        let ret = conn
+            .query_row(GET_REVISION_SQL, params, |row| {
+                let version = Self::version_from_row(row)?;
+                let object_raw: Vec<u8> = row.get(4)?;
+                Ok(NamedObjectMetaObjectRevisionData {
+                    version,
+                    object_raw,
+                })
+            })
+            .optional()
+            .map_err(|e| {
+                let msg = format!(
+                    "noc meta get object revision error: obj={}, revision={}, {}",
+                    req.object_id, req.revision, e
+                );
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::SqliteError, msg)
+            })?;
+
+        Ok(ret)
+    }
 }
 
 #[async_trait::async_trait]
@@ -1394,7 +1770,9 @@ impl NamedObjectMeta for SqliteMetaStorage {
         &self,
         req: &NamedObjectMetaPutObjectRequest,
     ) -> BuckyResult<NamedObjectMetaPutObjectResponse> {
-        perf_scope_request!("noc.meta.put_object", { self.update(req).await })
+        perf_scope_request!("noc.meta.put_object", {
+            self.commit(self.update(req).await).await
+        })
     }
 
     async fn get_object(
@@ -1408,7 +1786,9 @@ impl NamedObjectMeta for SqliteMetaStorage {
         &self,
         req: &NamedObjectMetaDeleteObjectRequest,
     ) -> BuckyResult<NamedObjectMetaDeleteObjectResponse> {
-        perf_scope_request!("noc.meta.delete_object", { self.delete(req).await })
+        perf_scope_request!("noc.meta.delete_object", {
+            self.commit(self.delete(req).await).await
+        })
     }
 
     async fn exists_object(&self, req: &NamedObjectMetaExistsObjectRequest) -> BuckyResult<bool> {
@@ -1431,7 +1811,9 @@ impl NamedObjectMeta for SqliteMetaStorage {
         &self,
         req: &NamedObjectMetaUpdateObjectMetaRequest,
     ) -> BuckyResult<()> {
-        perf_scope_request!("noc.meta.update_object_meta", { Self::update_object_meta(&self, req).await })
+        perf_scope_request!("noc.meta.update_object_meta", {
+            self.commit(Self::update_object_meta(&self, req).await).await
+        })
     }
 
     async fn check_object_access(
@@ -1452,6 +1834,29 @@ impl NamedObjectMeta for SqliteMetaStorage {
         Self::select(self, req).await
     }
 
+    async fn put_object_version(
+        &self,
+        req: &NamedObjectMetaPutObjectVersionRequest,
+    ) -> BuckyResult<Option<u32>> {
+        perf_scope_request!("noc.meta.put_object_version", {
+            self.commit(self.put_version(req).await).await
+        })
+    }
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectMetaGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectMetaGetObjectHistoryResponse> {
+        self.get_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectMetaGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectMetaObjectRevisionData>> {
+        self.get_revision(req).await
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
@@ -1,6 +1,6 @@
 // 当前的数据库版本
-pub(super) const CURRENT_VERSION: i32 = 1;
-const SET_DB_VERSION: &'static str = concat!("PRAGMA USER_VERSION = ", 1);
+pub(super) const CURRENT_VERSION: i32 = 3;
+const SET_DB_VERSION: &'static str = concat!("PRAGMA USER_VERSION = ", 3);
 
 pub(super) const DATA_NAMEDOBJECT_META_INIT: &'static str = r#"
 CREATE TABLE IF NOT EXISTS data_namedobject_meta (
@@ -35,7 +35,10 @@ CREATE TABLE IF NOT EXISTS data_namedobject_meta (
     ref_objs BLOB,
 
     nonce BLOB,
-    difficulty INTEGER
+    difficulty INTEGER,
+
+    /* version 3 */
+    sign_verified_update_time INTEGER
 );"#;
 
 pub(super) const DATA_NAMEDOBJECT_META_INSERT_TIME_INDEX: &'static str = r#"
@@ -46,10 +49,26 @@ pub(super) const DATA_NAMEDOBJECT_META_INSERT_LAST_ACCESS_INDEX: &'static str =
 CREATE INDEX IF NOT EXISTS `data_namedobject_meta_last_access_time_index` on `data_namedobject_meta` (`last_access_time`);
 "#;
 
-pub(super) const INIT_NAMEDOBJECT_META_SQL_LIST: [&'static str; 4] = [
+/* version 2 */
+// 对象被替换前的历史版本，只对rmeta里配置了versions的对象生效
+pub(super) const DATA_NAMEDOBJECT_VERSION_INIT: &'static str = r#"
+CREATE TABLE IF NOT EXISTS data_namedobject_version (
+    object_id TEXT NOT NULL,
+    revision INTEGER NOT NULL,
+
+    object_update_time INTEGER,
+    archive_time INTEGER,
+
+    object_raw BLOB NOT NULL,
+
+    PRIMARY KEY (object_id, revision)
+);"#;
+
+pub(super) const INIT_NAMEDOBJECT_META_SQL_LIST: [&'static str; 5] = [
     DATA_NAMEDOBJECT_META_INIT,
     DATA_NAMEDOBJECT_META_INSERT_TIME_INDEX,
     DATA_NAMEDOBJECT_META_INSERT_LAST_ACCESS_INDEX,
+    DATA_NAMEDOBJECT_VERSION_INIT,
     SET_DB_VERSION,
 ];
 
@@ -66,7 +85,15 @@ ALTER TABLE `data_namedobject_meta` ADD COLUMN nonce BLOB DEFAULT NULL;
 ALTER TABLE `data_namedobject_meta` ADD COLUMN difficulty BLOB DEFAULT 0;
 "#;
 
+// version 3 alters
+// 对象签名校验通过时对应的object_update_time，对象更新后自动失效
+pub(super) const DATA_NAMEDOBJECT_META_UPDATE_3: &'static str = r#"
+ALTER TABLE `data_namedobject_meta` ADD COLUMN sign_verified_update_time INTEGER DEFAULT NULL;
+"#;
+
 // For all version upgrades, MAIN_TABLE_UPDATE_LIST[CURRENT_VERSION - 1] is the corresponding upgrade sql
 pub(super) const MAIN_TABLE_UPDATE_LIST: [[&'static str; 1]; CURRENT_VERSION as usize] = [
-    [DATA_NAMEDOBJECT_META_UPDATE_1]
+    [DATA_NAMEDOBJECT_META_UPDATE_1],
+    [DATA_NAMEDOBJECT_VERSION_INIT],
+    [DATA_NAMEDOBJECT_META_UPDATE_3],
 ];
@@ -1,4 +1,5 @@
 use super::db::*;
+use crate::durability::*;
 use crate::meta::*;
 use cyfs_base::*;
 use cyfs_lib::*;
@@ -9,7 +10,8 @@ async fn test_meta() {
         std::fs::create_dir_all(&dir).unwrap();
     }
 
-    let meta = SqliteMetaStorage::new(&dir).unwrap();
+    let coalescer = CommitCoalescer::new(NamedObjectCacheDurability::default());
+    let meta = SqliteMetaStorage::new(&dir, coalescer).unwrap();
 
     let req = NamedObjectMetaExistsObjectRequest {
         source: RequestSourceInfo::new_local_system(),
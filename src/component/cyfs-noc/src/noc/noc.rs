@@ -1,4 +1,5 @@
 use crate::cache::*;
+use crate::durability::*;
 use crate::storage::*;
 use cyfs_base::*;
 use cyfs_lib::*;
@@ -9,7 +10,38 @@ pub struct NamedObjectCacheManager;
 
 impl NamedObjectCacheManager {
     pub async fn create(isolate: &str) -> BuckyResult<NamedObjectCacheRef> {
-        let storage_raw = NamedObjectLocalStorage::new(isolate).await?;
+        Self::create_with_durability(isolate, NamedObjectCacheDurability::default()).await
+    }
+
+    pub async fn create_with_durability(
+        isolate: &str,
+        durability: NamedObjectCacheDurability,
+    ) -> BuckyResult<NamedObjectCacheRef> {
+        let (noc, _) = Self::create_with_checker(isolate, durability).await?;
+        Ok(noc)
+    }
+
+    // checker和noc共用同一个meta和blob存储，可以在线检查
+    pub async fn create_with_checker(
+        isolate: &str,
+        durability: NamedObjectCacheDurability,
+    ) -> BuckyResult<(NamedObjectCacheRef, NamedObjectStorageChecker)> {
+        let (noc, checker, _) = Self::create_with_cold_tier(isolate, durability).await?;
+        Ok((noc, checker))
+    }
+
+    // 同时返回冷存储层，由协议栈按配置定期归档
+    pub async fn create_with_cold_tier(
+        isolate: &str,
+        durability: NamedObjectCacheDurability,
+    ) -> BuckyResult<(
+        NamedObjectCacheRef,
+        NamedObjectStorageChecker,
+        NamedObjectColdTier,
+    )> {
+        let storage_raw = NamedObjectLocalStorage::new(isolate, durability).await?;
+        let checker = storage_raw.checker();
+        let cold_tier = storage_raw.cold_tier();
         let meta = storage_raw.meta().clone();
         let storage_raw = Arc::new(Box::new(storage_raw) as Box<dyn NamedObjectCache>);
         
@@ -20,6 +52,16 @@ impl NamedObjectCacheManager {
         let serial_cache = NamedObjectCacheSerializer::new(cache);
         let serial_cache = Arc::new(Box::new(serial_cache) as Box<dyn NamedObjectCache>);
 
-        Ok(serial_cache)
+        Ok((serial_cache, checker, cold_tier))
+    }
+
+    // 以只读快照模式打开noc，用于备份、索引等其它进程在协议栈运行时访问
+    // meta基于sqlite的WAL模式，每次读取都是一致的快照；blob只读访问，不做任何缓存
+    pub fn open_readonly(isolate: &str) -> BuckyResult<NamedObjectCacheRef> {
+        let storage_raw = NamedObjectLocalStorage::open_readonly(isolate)?;
+        let storage_raw = Arc::new(Box::new(storage_raw) as Box<dyn NamedObjectCache>);
+
+        let readonly = NamedObjectCacheReadOnly::new(storage_raw);
+        Ok(Arc::new(Box::new(readonly) as Box<dyn NamedObjectCache>))
     }
 }
@@ -36,6 +36,7 @@ async fn test_noc() {
         context: None,
         last_access_rpath: None,
         access_string: Some(access.value()),
+        precondition: None,
     };
 
     noc.put_object(&put_req).await.unwrap();
@@ -62,6 +63,7 @@ async fn test_noc() {
         context: None,
         last_access_rpath: None,
         access_string: None,
+        precondition: None,
     };
 
     if let Err(e) = noc.put_object(&put_req).await {
@@ -102,6 +104,7 @@ async fn test_noc() {
     let data = ret.unwrap();
     let got_update_time = data.object.object.as_ref().unwrap().update_time().unwrap();
     assert_eq!(got_update_time, update_time);
+    assert!(!data.meta.is_sign_verified(Some(update_time)));
 
     // update meta
     let mut access = AccessString::new(0);
@@ -118,6 +121,7 @@ async fn test_noc() {
         context: Some(context.clone()),
         last_access_rpath: Some(last_access_rpath.clone()),
         access_string: Some(access.value()),
+        sign_verified_update_time: Some(update_time),
     };
 
     noc.update_object_meta(&put_req).await.unwrap();
@@ -143,6 +147,8 @@ async fn test_noc() {
         NamedObjectStorageCategory::Cache
     );
     assert_eq!(data.meta.access_string, access.value());
+    assert!(data.meta.is_sign_verified(Some(update_time)));
+    assert!(!data.meta.is_sign_verified(Some(update_time + 1)));
 
     // get by unknown device
     let source = RequestSourceInfo {
@@ -200,7 +206,7 @@ async fn test_noc() {
 
     // select
     let select_req = NamedObjectCacheSelectObjectRequest {
-        filter: NamedObjectCacheSelectObjectFilter { obj_type: None },
+        filter: NamedObjectCacheSelectObjectFilter::default(),
         opt: NamedObjectCacheSelectObjectOption::default(),
     };
 
@@ -229,7 +235,7 @@ async fn test_noc() {
 
     // select
     let select_req = NamedObjectCacheSelectObjectRequest {
-        filter: NamedObjectCacheSelectObjectFilter { obj_type: None },
+        filter: NamedObjectCacheSelectObjectFilter::default(),
         opt: NamedObjectCacheSelectObjectOption::default(),
     };
 
@@ -237,6 +243,376 @@ async fn test_noc() {
     info!("select result: {:?}", resp);
 }
 
+async fn test_precondition() {
+    cyfs_base::init_simple_log("cyfs-noc-test", Some("debug"));
+
+    let noc = NamedObjectCacheManager::create("test-precondition").await.unwrap();
+
+    let mut text = Text::create("test-precondition", "", "v1");
+    let object = NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap();
+    let object_id = object.object_id.clone();
+    let update_time = text.body().as_ref().unwrap().update_time();
+
+    let mut put_req = NamedObjectCachePutObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object,
+        storage_category: NamedObjectStorageCategory::Storage,
+        context: None,
+        last_access_rpath: None,
+        access_string: None,
+        precondition: Some(NamedObjectCachePutPrecondition::NotExists),
+    };
+
+    // first put, and delete the stale one from the prev test
+    let req = NamedObjectCacheDeleteObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        flags: 0,
+    };
+    noc.delete_object(&req).await.unwrap();
+    noc.put_object(&put_req).await.unwrap();
+
+    // already exists
+    let e = noc.put_object(&put_req).await.unwrap_err();
+    assert_eq!(e.code(), BuckyErrorCode::Conflict);
+
+    // update with the right update_time
+    *text.value_mut() = "v2".to_owned();
+    text.body_mut()
+        .as_mut()
+        .unwrap()
+        .increase_update_time(update_time + 1);
+    put_req.object = NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap();
+    put_req.precondition = Some(NamedObjectCachePutPrecondition::UpdateTime(update_time));
+    noc.put_object(&put_req).await.unwrap();
+
+    // stale writer
+    *text.value_mut() = "v3".to_owned();
+    text.body_mut()
+        .as_mut()
+        .unwrap()
+        .increase_update_time(update_time + 2);
+    put_req.object = NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap();
+    let e = noc.put_object(&put_req).await.unwrap_err();
+    assert_eq!(e.code(), BuckyErrorCode::Conflict);
+}
+
+// 返回固定的历史版本保留数量
+struct TestVersionsProvider(Option<u32>);
+
+#[async_trait::async_trait]
+impl NamedObjectCacheObjectMetaAccessProvider for TestVersionsProvider {
+    async fn check_access(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+        _source: &RequestSourceInfo,
+        _permissions: AccessPermissions,
+    ) -> BuckyResult<Option<()>> {
+        Ok(None)
+    }
+
+    async fn get_object_versions(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<Option<u32>> {
+        Ok(self.0)
+    }
+
+    async fn get_object_encrypt(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<bool> {
+        Ok(false)
+    }
+}
+
+async fn test_versions() {
+    cyfs_base::init_simple_log("cyfs-noc-test", Some("debug"));
+
+    let noc = NamedObjectCacheManager::create("test-versions").await.unwrap();
+    noc.bind_object_meta_access_provider(std::sync::Arc::new(Box::new(TestVersionsProvider(Some(2)))));
+
+    let mut text = Text::create("test-versions", "", "v1");
+    let object_id = text.desc().calculate_id();
+    let update_time = text.body().as_ref().unwrap().update_time();
+
+    let req = NamedObjectCacheDeleteObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        flags: 0,
+    };
+    noc.delete_object(&req).await.unwrap();
+
+    for i in 1..=4 {
+        *text.value_mut() = format!("v{}", i);
+        text.body_mut()
+            .as_mut()
+            .unwrap()
+            .increase_update_time(update_time + i);
+
+        let put_req = NamedObjectCachePutObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object: NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap(),
+            storage_category: NamedObjectStorageCategory::Storage,
+            context: None,
+            last_access_rpath: None,
+            access_string: None,
+            precondition: None,
+        };
+        noc.put_object(&put_req).await.unwrap();
+    }
+
+    // only the last 2 replaced versions are kept
+    let history_req = NamedObjectCacheGetObjectHistoryRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+    };
+    let history = noc.get_object_history(&history_req).await.unwrap();
+    let revisions: Vec<u32> = history.list.iter().map(|item| item.revision).collect();
+    assert_eq!(revisions, vec![3, 2]);
+
+    let req = NamedObjectCacheGetObjectRevisionRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        revision: 3,
+    };
+    let data = noc.get_object_revision(&req).await.unwrap().unwrap();
+    let prev = Text::clone_from_slice(&data.object.object_raw).unwrap();
+    assert_eq!(prev.value(), "v3");
+
+    let req = NamedObjectCacheGetObjectRevisionRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        revision: 1,
+    };
+    assert!(noc.get_object_revision(&req).await.unwrap().is_none());
+
+    // history is removed with the object
+    let req = NamedObjectCacheDeleteObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        flags: 0,
+    };
+    noc.delete_object(&req).await.unwrap();
+    let history = noc.get_object_history(&history_req).await.unwrap();
+    assert!(history.list.is_empty());
+}
+
+// 没有配置或者配置为0时不保留历史版本
+async fn test_versions_disabled() {
+    cyfs_base::init_simple_log("cyfs-noc-test", Some("debug"));
+
+    for versions in [None, Some(0)] {
+        let noc = NamedObjectCacheManager::create("test-versions-disabled")
+            .await
+            .unwrap();
+        let provider = TestVersionsProvider(versions);
+        noc.bind_object_meta_access_provider(std::sync::Arc::new(Box::new(provider)));
+
+        let mut text = Text::create("test-versions-disabled", "", "v1");
+        let object_id = text.desc().calculate_id();
+        let update_time = text.body().as_ref().unwrap().update_time();
+
+        for i in 1..=2 {
+            *text.value_mut() = format!("v{}", i);
+            text.body_mut()
+                .as_mut()
+                .unwrap()
+                .increase_update_time(update_time + i);
+
+            let put_req = NamedObjectCachePutObjectRequest {
+                source: RequestSourceInfo::new_local_system(),
+                object: NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap(),
+                storage_category: NamedObjectStorageCategory::Storage,
+                context: None,
+                last_access_rpath: None,
+                access_string: None,
+                precondition: None,
+            };
+            noc.put_object(&put_req).await.unwrap();
+        }
+
+        let req = NamedObjectCacheGetObjectHistoryRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.clone(),
+        };
+        let history = noc.get_object_history(&req).await.unwrap();
+        assert!(history.list.is_empty());
+
+        let req = NamedObjectCacheGetObjectRevisionRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.clone(),
+            revision: 1,
+        };
+        assert!(noc.get_object_revision(&req).await.unwrap().is_none());
+
+        let req = NamedObjectCacheDeleteObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id,
+            flags: 0,
+        };
+        noc.delete_object(&req).await.unwrap();
+    }
+}
+
+// query_error为true时模拟rmeta查询失败，否则对象需要加密但存储密钥没有解锁
+struct TestEncryptProvider {
+    query_error: bool,
+}
+
+#[async_trait::async_trait]
+impl NamedObjectCacheObjectMetaAccessProvider for TestEncryptProvider {
+    async fn check_access(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+        _source: &RequestSourceInfo,
+        _permissions: AccessPermissions,
+    ) -> BuckyResult<Option<()>> {
+        Ok(None)
+    }
+
+    async fn get_object_versions(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<Option<u32>> {
+        Ok(None)
+    }
+
+    async fn get_object_encrypt(
+        &self,
+        _target_dec_id: &ObjectId,
+        _object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<bool> {
+        if self.query_error {
+            Err(BuckyError::new(BuckyErrorCode::Failed, "test query error"))
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+// 无法确定是否需要加密、或者需要加密但没有密钥时，写入必须失败，不能按明文保存
+async fn test_encrypt_fail_closed() {
+    for query_error in [true, false] {
+        let noc = NamedObjectCacheManager::create("test-encrypt-fail-closed")
+            .await
+            .unwrap();
+        noc.bind_object_meta_access_provider(std::sync::Arc::new(Box::new(TestEncryptProvider {
+            query_error,
+        })));
+
+        let object = new_object(&format!("test-encrypt-fail-closed-{}", query_error));
+        let object_id = object.object_id.clone();
+        let put_req = NamedObjectCachePutObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object,
+            storage_category: NamedObjectStorageCategory::Storage,
+            context: None,
+            last_access_rpath: None,
+            access_string: None,
+            precondition: None,
+        };
+        let e = noc.put_object(&put_req).await.unwrap_err();
+        if !query_error {
+            assert_eq!(e.code(), BuckyErrorCode::PermissionDenied);
+        }
+
+        let get_req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id,
+            last_access_rpath: None,
+            flags: 0,
+        };
+        let ret = noc.get_object(&get_req).await;
+        assert!(!matches!(ret, Ok(Some(_))));
+    }
+}
+
+async fn test_readonly() {
+    cyfs_base::init_simple_log("cyfs-noc-test", Some("debug"));
+
+    // 不存在的noc不能以只读模式打开
+    match NamedObjectCacheManager::open_readonly("test-readonly-not-exists") {
+        Err(e) => assert_eq!(e.code(), BuckyErrorCode::NotFound),
+        Ok(_) => unreachable!(),
+    }
+
+    let noc = NamedObjectCacheManager::create("test-readonly").await.unwrap();
+    let readonly = NamedObjectCacheManager::open_readonly("test-readonly").unwrap();
+
+    let put = |object: NONObjectInfo| NamedObjectCachePutObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object,
+        storage_category: NamedObjectStorageCategory::Storage,
+        context: None,
+        last_access_rpath: Some("/test/writer".to_owned()),
+        access_string: None,
+        precondition: None,
+    };
+    let get = |object_id: &ObjectId, last_access_rpath: Option<&str>| {
+        NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.to_owned(),
+            last_access_rpath: last_access_rpath.map(|v| v.to_owned()),
+            flags: 0,
+        }
+    };
+
+    // 只读实例打开之后写入的对象也可以读到
+    let object = new_object(&format!("test-readonly-{}", bucky_time_now()));
+    let object_id = object.object_id.clone();
+    noc.put_object(&put(object.clone())).await.unwrap();
+
+    let data = readonly
+        .get_object(&get(&object_id, Some("/test/reader")))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(data.object.object_raw, object.object_raw);
+
+    // 只读访问不会更新last_access
+    let data = noc.get_object(&get(&object_id, None)).await.unwrap().unwrap();
+    assert_eq!(data.meta.last_access_rpath.as_deref(), Some("/test/writer"));
+
+    // 所有写操作都被拒绝
+    let err = readonly.put_object(&put(object)).await.unwrap_err();
+    assert_eq!(err.code(), BuckyErrorCode::PermissionDenied);
+
+    let delete_req = NamedObjectCacheDeleteObjectRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        flags: 0,
+    };
+    let err = readonly.delete_object(&delete_req).await.unwrap_err();
+    assert_eq!(err.code(), BuckyErrorCode::PermissionDenied);
+
+    let update_req = NamedObjectCacheUpdateObjectMetaRequest {
+        source: RequestSourceInfo::new_local_system(),
+        object_id: object_id.clone(),
+        storage_category: None,
+        context: Some("test-readonly".to_owned()),
+        last_access_rpath: None,
+        access_string: None,
+        sign_verified_update_time: None,
+    };
+    let err = readonly.update_object_meta(&update_req).await.unwrap_err();
+    assert_eq!(err.code(), BuckyErrorCode::PermissionDenied);
+
+    // 写入方不受影响
+    let ret = noc.delete_object(&delete_req).await.unwrap();
+    assert_eq!(ret.deleted_count, 1);
+    assert!(readonly
+        .get_object(&get(&object_id, None))
+        .await
+        .unwrap()
+        .is_none());
+}
+
 async fn test_error_blob() {
     use std::str::FromStr;
 
@@ -271,10 +647,136 @@ async fn test_error_blob() {
     info!("test complete!");
 }
 
+// 崩溃测试的子进程通过环境变量获取持久化策略
+const DURABILITY_CRASH_CHILD_ENV: &str = "CYFS_NOC_DURABILITY_CRASH_CHILD";
+
+fn durability_crash_isolate(durability: &NamedObjectCacheDurability) -> String {
+    format!("test-durability-{}", durability).replace(':', "-")
+}
+
+// 崩溃测试的子进程：写入成功后输出object_id，然后直接abort，不做任何清理
+// 只在父进程指定了环境变量时执行，单独运行时直接返回
+#[test]
+fn test_durability_crash_child() {
+    use std::io::Write;
+    use std::str::FromStr;
+
+    let durability = match std::env::var(DURABILITY_CRASH_CHILD_ENV) {
+        Ok(v) => NamedObjectCacheDurability::from_str(&v).unwrap(),
+        Err(_) => return,
+    };
+
+    async_std::task::block_on(async move {
+        let isolate = durability_crash_isolate(&durability);
+        let noc = NamedObjectCacheManager::create_with_durability(&isolate, durability)
+            .await
+            .unwrap();
+
+        // 每次运行使用不同的对象，避免读到之前运行写入的数据
+        let now = bucky_time_now();
+        let mut tasks = vec![];
+        for i in 0..32 {
+            let noc = noc.clone();
+            let object = new_object(&format!("test-durability-{}-{}-{}", durability, now, i));
+            let task = async_std::task::spawn(async move {
+                let object_id = object.object_id.clone();
+                let put_req = NamedObjectCachePutObjectRequest {
+                    source: RequestSourceInfo::new_local_system(),
+                    object,
+                    storage_category: NamedObjectStorageCategory::Storage,
+                    context: None,
+                    last_access_rpath: None,
+                    access_string: None,
+                    precondition: None,
+                };
+
+                noc.put_object(&put_req).await.unwrap();
+
+                // 写入返回成功之后才输出，父进程只检查这些对象
+                let mut stdout = std::io::stdout();
+                writeln!(stdout, "durability-object={}", object_id).unwrap();
+                stdout.flush().unwrap();
+            });
+            tasks.push(task);
+        }
+
+        for task in tasks {
+            task.await;
+        }
+    });
+
+    std::process::abort();
+}
+
+// 在子进程里写入后直接abort模拟进程崩溃，重新打开后所有已经返回成功的写入都必须可以读到
+async fn test_durability_crash(durability: NamedObjectCacheDurability) {
+    use std::str::FromStr;
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "noc::test::test_durability_crash_child",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(DURABILITY_CRASH_CHILD_ENV, durability.to_string())
+        .output()
+        .unwrap();
+
+    // 子进程必须是异常退出的，否则说明没有执行到崩溃
+    assert!(!output.status.success());
+
+    let list: Vec<ObjectId> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("durability-object="))
+        .map(|v| ObjectId::from_str(v).unwrap())
+        .collect();
+    assert_eq!(list.len(), 32, "durability={}", durability);
+
+    let isolate = durability_crash_isolate(&durability);
+    let noc = NamedObjectCacheManager::create_with_durability(&isolate, durability)
+        .await
+        .unwrap();
+    for object_id in list {
+        let get_req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.clone(),
+            last_access_rpath: None,
+            flags: 0,
+        };
+
+        let ret = noc.get_object(&get_req).await.unwrap();
+        assert!(
+            ret.is_some(),
+            "object lost after crash! durability={}, object={}",
+            durability,
+            object_id
+        );
+    }
+}
+
+#[test]
+fn test_durability() {
+    cyfs_base::init_simple_log("cyfs-noc-test", Some("debug"));
+
+    async_std::task::block_on(async move {
+        test_durability_crash(NamedObjectCacheDurability::Always).await;
+        test_durability_crash(NamedObjectCacheDurability::GroupCommit(20)).await;
+
+        // relaxed模式不主动fsync，进程崩溃时数据已经在操作系统缓存里，同样不会丢失；掉电的情况无法在测试中模拟
+        test_durability_crash(NamedObjectCacheDurability::Relaxed).await;
+    });
+}
+
 #[test]
 fn main() {
     async_std::task::block_on(async move {
         // test_error_blob().await;
         test_noc().await;
+        test_precondition().await;
+        test_versions().await;
+        test_versions_disabled().await;
+        test_encrypt_fail_closed().await;
+        test_readonly().await;
     });
 }
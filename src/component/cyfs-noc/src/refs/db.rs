@@ -0,0 +1,266 @@
+use cyfs_base::*;
+use cyfs_util::SqliteConnectionHolder;
+
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const OBJECT_REF_INIT_SQL_LIST: [&'static str; 3] = [
+    r#"CREATE TABLE IF NOT EXISTS object_ref (
+        source TEXT NOT NULL,
+        target TEXT NOT NULL,
+        PRIMARY KEY(source, target)
+    );"#,
+    r#"CREATE INDEX IF NOT EXISTS object_ref_target_index ON object_ref (target);"#,
+    // 所有被引用过的对象，引用关系最后一次变化的时间，用来找出不再被引用的对象
+    r#"CREATE TABLE IF NOT EXISTS object_ref_target (
+        target TEXT PRIMARY KEY NOT NULL,
+        update_time INTEGER NOT NULL
+    );"#,
+];
+
+pub(super) struct SqliteObjectRefStorage {
+    data_file: PathBuf,
+    conn: SqliteConnectionHolder,
+}
+
+impl SqliteObjectRefStorage {
+    pub fn new(isolate: &str) -> BuckyResult<Self> {
+        let dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::Noc);
+        let dir = if isolate.len() > 0 {
+            dir.join(isolate)
+        } else {
+            dir
+        };
+        let dir = dir.join("named-object-cache");
+
+        if !dir.is_dir() {
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                let msg = format!(
+                    "create named object cache dir error! dir={}, err={}",
+                    dir.display(),
+                    e
+                );
+                error!("{}", msg);
+
+                return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+            }
+        }
+
+        let data_file = dir.join("refs.db");
+        info!(
+            "named object ref graph sqlite db file: {}",
+            data_file.display()
+        );
+
+        let ret = Self {
+            data_file: data_file.clone(),
+            conn: SqliteConnectionHolder::new(data_file),
+        };
+
+        ret.init_db()?;
+
+        Ok(ret)
+    }
+
+    fn init_db(&self) -> BuckyResult<()> {
+        let (conn, _lock) = self.conn.get_write_conn()?;
+
+        for sql in &OBJECT_REF_INIT_SQL_LIST {
+            conn.execute(&sql, []).map_err(|e| {
+                let msg = format!(
+                    "init object ref table error! sql={}, file={}, {}",
+                    sql,
+                    self.data_file.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::SqliteError, msg)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn map_sql_error(e: rusqlite::Error) -> BuckyError {
+        let msg = format!("exec object ref sql error: {}", e);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::SqliteError, msg)
+    }
+
+    pub fn is_empty(&self) -> BuckyResult<bool> {
+        let (conn, _lock) = self.conn.get_read_conn()?;
+        let ret: Option<i32> = conn
+            .query_row("SELECT 1 FROM object_ref LIMIT 1", [], |row| row.get(0))
+            .optional()
+            .map_err(Self::map_sql_error)?;
+
+        Ok(ret.is_none())
+    }
+
+    // 替换source的全部引用，新增和失去引用的对象都会更新时间
+    pub fn update(&self, source: &ObjectId, targets: &[ObjectId]) -> BuckyResult<()> {
+        let now = bucky_time_now() as i64;
+        let source = source.to_string();
+
+        let (mut conn, _lock) = self.conn.get_write_conn()?;
+        let tx = conn.transaction().map_err(Self::map_sql_error)?;
+
+        let old = Self::select_targets(&tx, &source)?;
+        let new: HashSet<String> = targets.iter().map(|id| id.to_string()).collect();
+
+        tx.execute("DELETE FROM object_ref WHERE source=?1", params![&source])
+            .map_err(Self::map_sql_error)?;
+
+        for target in &new {
+            tx.execute(
+                "INSERT OR IGNORE INTO object_ref (source, target) VALUES (?1, ?2)",
+                params![&source, target],
+            )
+            .map_err(Self::map_sql_error)?;
+        }
+
+        for target in new.symmetric_difference(&old) {
+            tx.execute(
+                "INSERT OR REPLACE INTO object_ref_target (target, update_time) VALUES (?1, ?2)",
+                params![target, now],
+            )
+            .map_err(Self::map_sql_error)?;
+        }
+
+        tx.commit().map_err(Self::map_sql_error)
+    }
+
+    // 对象从noc删除，移除它的所有引用，同时它本身也不再作为孤儿对象
+    pub fn remove(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        let now = bucky_time_now() as i64;
+        let object_id = object_id.to_string();
+
+        let (mut conn, _lock) = self.conn.get_write_conn()?;
+        let tx = conn.transaction().map_err(Self::map_sql_error)?;
+
+        let old = Self::select_targets(&tx, &object_id)?;
+        tx.execute(
+            "DELETE FROM object_ref WHERE source=?1",
+            params![&object_id],
+        )
+        .map_err(Self::map_sql_error)?;
+        for target in &old {
+            tx.execute(
+                "UPDATE object_ref_target SET update_time=?1 WHERE target=?2",
+                params![now, target],
+            )
+            .map_err(Self::map_sql_error)?;
+        }
+
+        tx.execute(
+            "DELETE FROM object_ref_target WHERE target=?1",
+            params![&object_id],
+        )
+        .map_err(Self::map_sql_error)?;
+
+        tx.commit().map_err(Self::map_sql_error)
+    }
+
+    fn select_targets(conn: &rusqlite::Connection, source: &str) -> BuckyResult<HashSet<String>> {
+        let mut stmt = conn
+            .prepare("SELECT target FROM object_ref WHERE source=?1")
+            .map_err(Self::map_sql_error)?;
+        let mut rows = stmt.query(params![source]).map_err(Self::map_sql_error)?;
+
+        let mut list = HashSet::new();
+        while let Some(row) = rows.next().map_err(Self::map_sql_error)? {
+            list.insert(row.get(0).map_err(Self::map_sql_error)?);
+        }
+
+        Ok(list)
+    }
+
+    fn query_list(
+        &self,
+        sql: &str,
+        param: Option<String>,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        let sql = format!(
+            "{} LIMIT {} OFFSET {}",
+            sql,
+            page_size,
+            page_index as u64 * page_size as u64
+        );
+
+        let (conn, _lock) = self.conn.get_read_conn()?;
+        let mut stmt = conn.prepare(&sql).map_err(Self::map_sql_error)?;
+        let mut rows = match &param {
+            Some(param) => stmt.query(params![param]),
+            None => stmt.query([]),
+        }
+        .map_err(Self::map_sql_error)?;
+
+        let mut list = Vec::new();
+        while let Some(row) = rows.next().map_err(Self::map_sql_error)? {
+            let id: String = row.get(0).map_err(Self::map_sql_error)?;
+            match ObjectId::from_str(&id) {
+                Ok(id) => list.push(id),
+                Err(e) => {
+                    error!("invalid object id in ref graph: {}, {}", id, e);
+                }
+            }
+        }
+
+        Ok(list)
+    }
+
+    pub fn referrers(
+        &self,
+        target: &ObjectId,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        self.query_list(
+            "SELECT source FROM object_ref WHERE target=?1 ORDER BY source",
+            Some(target.to_string()),
+            page_index,
+            page_size,
+        )
+    }
+
+    pub fn references(
+        &self,
+        source: &ObjectId,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        self.query_list(
+            "SELECT target FROM object_ref WHERE source=?1 ORDER BY target",
+            Some(source.to_string()),
+            page_index,
+            page_size,
+        )
+    }
+
+    // 被引用过但当前没有任何引用的对象，按失去引用的时间排序
+    pub fn orphans(&self, page_index: u32, page_size: u32) -> BuckyResult<Vec<ObjectId>> {
+        self.query_list(
+            r#"SELECT target FROM object_ref_target t 
+            WHERE NOT EXISTS (SELECT 1 FROM object_ref r WHERE r.target=t.target) 
+            ORDER BY update_time ASC"#,
+            None,
+            page_index,
+            page_size,
+        )
+    }
+
+    pub fn forget(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        let (conn, _lock) = self.conn.get_write_conn()?;
+        conn.execute(
+            "DELETE FROM object_ref_target WHERE target=?1",
+            params![object_id.to_string()],
+        )
+        .map_err(Self::map_sql_error)?;
+
+        Ok(())
+    }
+}
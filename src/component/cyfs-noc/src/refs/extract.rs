@@ -0,0 +1,131 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::collections::BTreeSet;
+
+// 从已知类型的对象里提取引用的对象和chunk
+pub struct NamedObjectRefExtractor;
+
+impl NamedObjectRefExtractor {
+    // 是否是可以提取引用的对象类型
+    pub fn is_known_type(object_id: &ObjectId) -> bool {
+        match object_id.obj_type_code() {
+            ObjectTypeCode::File | ObjectTypeCode::Dir | ObjectTypeCode::ObjectMap => true,
+            _ => false,
+        }
+    }
+
+    pub fn known_types() -> Vec<u16> {
+        vec![
+            ObjectTypeCode::File.to_u16(),
+            ObjectTypeCode::Dir.to_u16(),
+            ObjectTypeCode::ObjectMap.to_u16(),
+        ]
+    }
+
+    // 未知类型返回None
+    pub fn extract(object: &NONObjectInfo) -> BuckyResult<Option<Vec<ObjectId>>> {
+        let mut refs = BTreeSet::new();
+        match object.object_id.obj_type_code() {
+            ObjectTypeCode::File => {
+                let file = File::clone_from_slice(&object.object_raw)?;
+                Self::extract_file(&file, &mut refs);
+            }
+            ObjectTypeCode::Dir => {
+                let dir = Dir::clone_from_slice(&object.object_raw)?;
+                Self::extract_dir(&dir, &mut refs);
+            }
+            ObjectTypeCode::ObjectMap => {
+                let object_map = ObjectMap::clone_from_slice(&object.object_raw)?;
+                Self::extract_object_map(&object_map, &mut refs);
+            }
+            _ => return Ok(None),
+        }
+
+        // 不记录自引用
+        refs.remove(&object.object_id);
+
+        Ok(Some(refs.into_iter().collect()))
+    }
+
+    fn extract_file(file: &File, refs: &mut BTreeSet<ObjectId>) {
+        if let Some(body) = file.body() {
+            match body.content().chunk_list() {
+                ChunkList::ChunkInFile(file_id) => {
+                    refs.insert(file_id.object_id().to_owned());
+                }
+                list @ _ => {
+                    if let Some(chunks) = list.inner_chunk_list() {
+                        refs.extend(chunks.iter().map(|chunk_id| chunk_id.object_id()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn extract_dir(dir: &Dir, refs: &mut BTreeSet<ObjectId>) {
+        match dir.desc().content().obj_list() {
+            NDNObjectInfo::Chunk(chunk_id) => {
+                refs.insert(chunk_id.object_id());
+            }
+            NDNObjectInfo::ObjList(list) => {
+                if let Some(chunk_id) = list.parent_chunk() {
+                    refs.insert(chunk_id.object_id());
+                }
+
+                for (_, info) in list.object_map() {
+                    match info.node() {
+                        InnerNode::ObjId(id) => {
+                            refs.insert(id.to_owned());
+                        }
+                        InnerNode::Chunk(chunk_id) => {
+                            refs.insert(chunk_id.object_id());
+                        }
+                        InnerNode::IndexInParentChunk(_, _) => {}
+                    }
+                }
+            }
+        }
+
+        // 对象列表编码成chunk的情况
+        if let Some(body) = dir.body() {
+            if let DirBodyContent::Chunk(chunk_id) = body.content() {
+                refs.insert(chunk_id.object_id());
+            }
+        }
+    }
+
+    fn extract_object_map(object_map: &ObjectMap, refs: &mut BTreeSet<ObjectId>) {
+        match object_map.desc().content().content() {
+            ObjectMapContent::Simple(content) => match content.content() {
+                SimpleContent::Map(map) => {
+                    refs.extend(map.values().values().cloned());
+                }
+                SimpleContent::DiffMap(map) => {
+                    for item in map.values().values() {
+                        refs.extend(
+                            [&item.prev, &item.altered, &item.diff]
+                                .into_iter()
+                                .filter_map(|id| id.clone()),
+                        );
+                    }
+                }
+                SimpleContent::Set(set) => {
+                    refs.extend(set.values().iter().cloned());
+                }
+                SimpleContent::DiffSet(set) => {
+                    for item in set.values() {
+                        refs.extend(
+                            [&item.prev, &item.altered]
+                                .into_iter()
+                                .filter_map(|id| id.clone()),
+                        );
+                    }
+                }
+            },
+            ObjectMapContent::Hub(content) => {
+                refs.extend(content.subs().values().map(|sub| sub.id().to_owned()));
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use super::db::SqliteObjectRefStorage;
+use super::extract::NamedObjectRefExtractor;
+use crate::feed::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use async_std::channel::{Receiver, Sender};
+use std::sync::Arc;
+
+const REBUILD_PAGE_SIZE: usize = 256;
+
+// noc的变更回调在写路径上，这里只投递到队列，由后台任务更新引用关系
+struct NamedObjectRefChangeListener {
+    sender: Sender<NamedObjectCacheChangeEvent>,
+}
+
+impl NamedObjectCacheChangeListener for NamedObjectRefChangeListener {
+    fn on_change(&self, event: &NamedObjectCacheChangeEvent) {
+        if let NamedObjectCacheChangeEvent::Put(object) = event {
+            if !NamedObjectRefExtractor::is_known_type(&object.object_id) {
+                return;
+            }
+        }
+
+        if let Err(e) = self.sender.try_send(event.clone()) {
+            warn!(
+                "post noc change event to ref graph failed! {}, {}",
+                event, e
+            );
+        }
+    }
+}
+
+// 对象之间的引用关系图(对象 -> 引用的对象/chunk)，在对象写入noc时提取，用于gc和影响分析
+#[derive(Clone)]
+pub struct NamedObjectRefGraph {
+    storage: Arc<SqliteObjectRefStorage>,
+    noc: NamedObjectCacheRef,
+}
+
+impl NamedObjectRefGraph {
+    pub fn open(isolate: &str, noc: NamedObjectCacheRef) -> BuckyResult<Self> {
+        let storage = SqliteObjectRefStorage::new(isolate)?;
+
+        Ok(Self {
+            storage: Arc::new(storage),
+            noc,
+        })
+    }
+
+    pub fn start(&self, feed: &NamedObjectCacheChangeFeed) {
+        let (sender, receiver) = async_std::channel::unbounded();
+        let listener = NamedObjectRefChangeListener { sender };
+        feed.subscribe(Arc::new(Box::new(listener)));
+
+        // 首次开启时需要用noc里已有的对象建立引用关系，期间的变更在队列里等待
+        let need_rebuild = self.storage.is_empty().unwrap_or(false);
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            if need_rebuild {
+                if let Err(e) = this.rebuild().await {
+                    error!("rebuild object ref graph failed! {}", e);
+                }
+            }
+
+            this.run(receiver).await;
+        });
+    }
+
+    async fn run(&self, receiver: Receiver<NamedObjectCacheChangeEvent>) {
+        while let Ok(event) = receiver.recv().await {
+            let _ = match event {
+                NamedObjectCacheChangeEvent::Put(object) => self.add_object(&object),
+                NamedObjectCacheChangeEvent::Delete(object_id) => self.storage.remove(&object_id),
+            };
+        }
+
+        warn!("noc change feed closed, object ref graph stopped!");
+    }
+
+    // 返回对象是否是可以提取引用的类型
+    fn add_object(&self, object: &NONObjectInfo) -> BuckyResult<bool> {
+        let refs = NamedObjectRefExtractor::extract(object).map_err(|e| {
+            warn!(
+                "extract refs from object failed! object={}, {}",
+                object.object_id, e
+            );
+            e
+        })?;
+
+        match refs {
+            Some(refs) => {
+                self.storage.update(&object.object_id, &refs)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn rebuild(&self) -> BuckyResult<()> {
+        info!("will rebuild object ref graph from noc");
+
+        let mut count = 0;
+        for obj_type in NamedObjectRefExtractor::known_types() {
+            let mut opt = NamedObjectCacheSelectObjectOption::default();
+            opt.page_size = REBUILD_PAGE_SIZE;
+
+            loop {
+                let req = NamedObjectCacheSelectObjectRequest {
+                    filter: NamedObjectCacheSelectObjectFilter {
+                        obj_type: Some(obj_type),
+                        last_access_before: None,
+                    },
+                    opt: opt.clone(),
+                };
+
+                let resp = self.noc.select_object(&req).await?;
+                let len = resp.list.len();
+                for item in resp.list {
+                    let mut req = NamedObjectCacheGetObjectRequest {
+                        source: RequestSourceInfo::new_local_system(),
+                        object_id: item.object_id,
+                        last_access_rpath: None,
+                        flags: 0,
+                    };
+                    req.set_no_update_last_access();
+
+                    if let Some(data) = self.noc.get_object(&req).await? {
+                        if let Ok(true) = self.add_object(&data.object) {
+                            count += 1;
+                        }
+                    }
+                }
+
+                if len < opt.page_size {
+                    break;
+                }
+                opt.page_index += 1;
+            }
+        }
+
+        info!("rebuild object ref graph complete! count={}", count);
+
+        Ok(())
+    }
+
+    // 引用了object_id的对象
+    pub fn referrers(
+        &self,
+        object_id: &ObjectId,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        self.storage.referrers(object_id, page_index, page_size)
+    }
+
+    // object_id引用的对象和chunk
+    pub fn references(
+        &self,
+        object_id: &ObjectId,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<Vec<ObjectId>> {
+        self.storage.references(object_id, page_index, page_size)
+    }
+
+    // 曾经被引用但当前没有任何对象引用的对象和chunk
+    pub fn orphans(&self, page_index: u32, page_size: u32) -> BuckyResult<Vec<ObjectId>> {
+        self.storage.orphans(page_index, page_size)
+    }
+
+    // 孤儿对象被外部清理(比如gc删除了chunk)之后，不再记录
+    pub fn forget(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        self.storage.forget(object_id)
+    }
+}
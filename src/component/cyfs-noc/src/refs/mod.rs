@@ -0,0 +1,9 @@
+mod db;
+mod extract;
+mod graph;
+
+#[cfg(test)]
+mod test;
+
+pub use extract::*;
+pub use graph::*;
@@ -0,0 +1,60 @@
+use super::db::SqliteObjectRefStorage;
+use super::extract::NamedObjectRefExtractor;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+fn new_file(chunks: &[ChunkId]) -> NONObjectInfo {
+    let file = File::new(
+        ObjectId::default(),
+        0,
+        HashValue::default(),
+        ChunkList::ChunkInList(chunks.to_vec()),
+    )
+    .build();
+
+    NONObjectInfo::new_from_object_raw(file.to_vec().unwrap()).unwrap()
+}
+
+fn new_chunk(i: usize) -> ChunkId {
+    ChunkId::calculate_sync(i.to_string().as_bytes()).unwrap()
+}
+
+#[test]
+fn test_ref_graph() {
+    cyfs_base::init_simple_log("cyfs-noc-refs-test", Some("debug"));
+
+    let storage = SqliteObjectRefStorage::new("test-refs").unwrap();
+
+    let chunks: Vec<ChunkId> = (0..4).map(new_chunk).collect();
+    let file = new_file(&chunks[..3]);
+    let refs = NamedObjectRefExtractor::extract(&file).unwrap().unwrap();
+    assert_eq!(refs.len(), 3);
+
+    storage.update(&file.object_id, &refs).unwrap();
+    let list = storage.references(&file.object_id, 0, 10).unwrap();
+    assert_eq!(list.len(), 3);
+
+    let other = new_file(&chunks[2..]);
+    let other_refs = NamedObjectRefExtractor::extract(&other).unwrap().unwrap();
+    storage.update(&other.object_id, &other_refs).unwrap();
+
+    let shared = chunks[2].object_id();
+    let list = storage.referrers(&shared, 0, 10).unwrap();
+    assert_eq!(list.len(), 2);
+
+    // 删除第一个文件后，只被它引用的chunk成为孤儿
+    storage.remove(&file.object_id).unwrap();
+    let orphans = storage.orphans(0, 10).unwrap();
+    assert!(orphans.contains(&chunks[0].object_id()));
+    assert!(orphans.contains(&chunks[1].object_id()));
+    assert!(!orphans.contains(&shared));
+
+    storage.forget(&chunks[0].object_id()).unwrap();
+    let orphans = storage.orphans(0, 10).unwrap();
+    assert!(!orphans.contains(&chunks[0].object_id()));
+
+    storage.remove(&other.object_id).unwrap();
+    for chunk in &chunks {
+        storage.forget(&chunk.object_id()).unwrap();
+    }
+}
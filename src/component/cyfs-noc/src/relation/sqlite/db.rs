@@ -17,7 +17,7 @@ pub(crate) struct SqliteDBObjectRelationCache {
 
 impl SqliteDBObjectRelationCache {
     pub fn new(isolate: &str) -> BuckyResult<Self> {
-        let dir = cyfs_util::get_cyfs_root_path().join("data");
+        let dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::Noc);
         let dir = if isolate.len() > 0 {
             dir.join(isolate)
         } else {
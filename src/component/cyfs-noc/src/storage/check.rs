@@ -0,0 +1,277 @@
+use super::local::NamedObjectLocalStorage;
+use crate::blob::*;
+use crate::meta::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NamedObjectStorageOrphanBlobAction {
+    // 只报告，不处理
+    Keep,
+    // 用blob里的对象重建meta，对象无法解析时移到隔离区
+    RebuildMeta,
+    // 移到隔离区
+    Quarantine,
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedObjectStorageCheckOption {
+    // 删除blob已经不存在的meta记录
+    pub remove_dangling_meta: bool,
+
+    pub orphan_blob_action: NamedObjectStorageOrphanBlobAction,
+
+    // 每秒最多检查的对象个数，0表示不限制，在线检查时用以减少对正常读写的影响
+    pub ops_per_sec: u32,
+}
+
+impl Default for NamedObjectStorageCheckOption {
+    fn default() -> Self {
+        Self {
+            remove_dangling_meta: false,
+            orphan_blob_action: NamedObjectStorageOrphanBlobAction::Keep,
+            ops_per_sec: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NamedObjectStorageCheckResult {
+    pub meta_count: u64,
+    pub blob_count: u64,
+
+    // 有meta但是blob缺失
+    pub dangling_meta: Vec<ObjectId>,
+
+    // 有blob但是没有meta
+    pub orphan_blob: Vec<ObjectId>,
+
+    pub removed_meta: u64,
+    pub rebuilt_meta: u64,
+    pub quarantined_blob: u64,
+}
+
+impl std::fmt::Display for NamedObjectStorageCheckResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "meta={}, blob={}, dangling_meta={}, orphan_blob={}, removed_meta={}, rebuilt_meta={}, quarantined_blob={}",
+            self.meta_count,
+            self.blob_count,
+            self.dangling_meta.len(),
+            self.orphan_blob.len(),
+            self.removed_meta,
+            self.rebuilt_meta,
+            self.quarantined_blob,
+        )
+    }
+}
+
+struct RateLimiter {
+    ops_per_sec: u32,
+    count: u32,
+    window_start: Instant,
+}
+
+impl RateLimiter {
+    fn new(ops_per_sec: u32) -> Self {
+        Self {
+            ops_per_sec,
+            count: 0,
+            window_start: Instant::now(),
+        }
+    }
+
+    async fn acquire(&mut self) {
+        if self.ops_per_sec == 0 {
+            return;
+        }
+
+        self.count += 1;
+        if self.count < self.ops_per_sec {
+            return;
+        }
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed < Duration::from_secs(1) {
+            async_std::task::sleep(Duration::from_secs(1) - elapsed).await;
+        }
+
+        self.count = 0;
+        self.window_start = Instant::now();
+    }
+}
+
+// 交叉检查meta和blob，可以在noc正常运行时执行
+#[derive(Clone)]
+pub struct NamedObjectStorageChecker {
+    meta: NamedObjectMetaRef,
+    blob: BlobStorageRef,
+}
+
+impl NamedObjectStorageChecker {
+    pub(crate) fn new(meta: NamedObjectMetaRef, blob: BlobStorageRef) -> Self {
+        Self { meta, blob }
+    }
+
+    pub async fn check(
+        &self,
+        option: &NamedObjectStorageCheckOption,
+    ) -> BuckyResult<NamedObjectStorageCheckResult> {
+        info!("will check noc meta and blob: {:?}", option);
+
+        let mut result = NamedObjectStorageCheckResult::default();
+        let mut limiter = RateLimiter::new(option.ops_per_sec);
+
+        self.check_meta(option, &mut limiter, &mut result).await?;
+        self.check_blob(option, &mut limiter, &mut result).await?;
+
+        info!("check noc meta and blob complete! {}", result);
+
+        Ok(result)
+    }
+
+    // meta -> blob
+    async fn check_meta(
+        &self,
+        option: &NamedObjectStorageCheckOption,
+        limiter: &mut RateLimiter,
+        result: &mut NamedObjectStorageCheckResult,
+    ) -> BuckyResult<()> {
+        let mut dangling = vec![];
+        let mut opt = NamedObjectCacheSelectObjectOption::default();
+        loop {
+            let req = NamedObjectMetaSelectObjectRequest {
+                filter: NamedObjectCacheSelectObjectFilter::default(),
+                opt: opt.clone(),
+            };
+
+            let resp = self.meta.select_object(&req).await?;
+            let count = resp.list.len();
+            for item in resp.list {
+                limiter.acquire().await;
+
+                result.meta_count += 1;
+                if !self.blob.exists_object(&item.object_id).await? {
+                    warn!("noc object blob missing! object={}", item.object_id);
+                    dangling.push(item.object_id);
+                }
+            }
+
+            if count < opt.page_size {
+                break;
+            }
+            opt.page_index += 1;
+        }
+
+        // 全部检查完再删除，避免影响分页
+        if option.remove_dangling_meta {
+            for object_id in &dangling {
+                let req = NamedObjectMetaDeleteObjectRequest {
+                    source: RequestSourceInfo::new_local_system(),
+                    object_id: object_id.to_owned(),
+                    flags: 0,
+                };
+
+                match self.meta.delete_object(&req).await {
+                    Ok(resp) => result.removed_meta += resp.deleted_count as u64,
+                    Err(e) => {
+                        error!("remove dangling meta failed! object={}, {}", object_id, e);
+                    }
+                }
+            }
+        }
+
+        result.dangling_meta = dangling;
+
+        Ok(())
+    }
+
+    // blob -> meta
+    async fn check_blob(
+        &self,
+        option: &NamedObjectStorageCheckOption,
+        limiter: &mut RateLimiter,
+        result: &mut NamedObjectStorageCheckResult,
+    ) -> BuckyResult<()> {
+        let list = self.blob.list_objects().await?;
+        result.blob_count = list.len() as u64;
+
+        for object_id in list {
+            limiter.acquire().await;
+
+            let req = NamedObjectMetaExistsObjectRequest {
+                source: RequestSourceInfo::new_local_system(),
+                object_id: object_id.clone(),
+            };
+            if self.meta.exists_object(&req).await? {
+                continue;
+            }
+
+            warn!("noc object blob without meta! object={}", object_id);
+
+            match option.orphan_blob_action {
+                NamedObjectStorageOrphanBlobAction::Keep => {}
+                NamedObjectStorageOrphanBlobAction::RebuildMeta => {
+                    match self.rebuild_meta(&object_id).await {
+                        Ok(()) => result.rebuilt_meta += 1,
+                        Err(e) => {
+                            warn!(
+                                "rebuild meta from blob failed, now will quarantine! object={}, {}",
+                                object_id, e
+                            );
+                            if self.blob.quarantine_object(&object_id).await.is_ok() {
+                                result.quarantined_blob += 1;
+                            }
+                        }
+                    }
+                }
+                NamedObjectStorageOrphanBlobAction::Quarantine => {
+                    if self.blob.quarantine_object(&object_id).await.is_ok() {
+                        result.quarantined_blob += 1;
+                    }
+                }
+            }
+
+            result.orphan_blob.push(object_id);
+        }
+
+        Ok(())
+    }
+
+    async fn rebuild_meta(&self, object_id: &ObjectId) -> BuckyResult<()> {
+        let object = match self.blob.get_object(object_id).await? {
+            Some(object) => object,
+            None => {
+                let msg = format!("object blob removed during check! object={}", object_id);
+                return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+            }
+        };
+
+        if object.object_id != *object_id {
+            let msg = format!(
+                "object blob id unmatch! file={}, object={}",
+                object_id, object.object_id
+            );
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        let req = NamedObjectCachePutObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object,
+            storage_category: NamedObjectStorageCategory::Storage,
+            context: None,
+            last_access_rpath: None,
+            access_string: None,
+            precondition: None,
+        };
+        let meta_req = NamedObjectLocalStorage::gen_meta_put_request(&req)?;
+        self.meta.put_object(&meta_req).await?;
+
+        info!("rebuild noc meta from blob success! object={}", object_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::blob::*;
+use crate::meta::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+use cyfs_util::ColdPackStat;
+
+use std::time::Duration;
+
+const COLD_TIER_SELECT_PAGE_SIZE: usize = 256;
+
+// noc的冷存储层，按meta里的最近访问时间把长时间没有访问的对象归档
+#[derive(Clone)]
+pub struct NamedObjectColdTier {
+    meta: NamedObjectMetaRef,
+    blob: ColdBlobStorage,
+}
+
+impl NamedObjectColdTier {
+    pub(crate) fn new(meta: NamedObjectMetaRef, blob: ColdBlobStorage) -> Self {
+        Self { meta, blob }
+    }
+
+    pub fn stat(&self) -> ColdPackStat {
+        self.blob.stat()
+    }
+
+    pub fn set_hydrate_budget(&self, budget: Duration) {
+        self.blob.set_hydrate_budget(budget)
+    }
+
+    // 把最近访问时间早于idle_before的对象归档到冷存储，每次最多limit个，返回归档的数量
+    pub async fn archive_idle(&self, idle_before: u64, limit: usize) -> BuckyResult<usize> {
+        let mut opt = NamedObjectCacheSelectObjectOption::default();
+        opt.page_size = COLD_TIER_SELECT_PAGE_SIZE;
+
+        let mut list = vec![];
+        while list.len() < limit {
+            let req = NamedObjectMetaSelectObjectRequest {
+                filter: NamedObjectCacheSelectObjectFilter {
+                    obj_type: None,
+                    last_access_before: Some(idle_before),
+                },
+                opt: opt.clone(),
+            };
+
+            let resp = self.meta.select_object(&req).await?;
+            let count = resp.list.len();
+
+            // 已经在冷存储里的对象访问时间不会更新，会一直排在前面
+            for item in resp.list {
+                if list.len() >= limit {
+                    break;
+                }
+
+                if !self.blob.contains(&item.object_id) {
+                    list.push(item.object_id);
+                }
+            }
+
+            if count < opt.page_size {
+                break;
+            }
+            opt.page_index += 1;
+        }
+
+        if list.is_empty() {
+            return Ok(0);
+        }
+
+        let count = self.blob.archive(&list).await?;
+        info!(
+            "archive idle objects to cold tier: idle_before={}, count={}",
+            idle_before, count
+        );
+
+        Ok(count)
+    }
+}
@@ -1,25 +1,36 @@
+use super::cold::NamedObjectColdTier;
 use crate::blob::*;
+use crate::durability::*;
 use crate::meta::*;
 use cyfs_base::*;
 use cyfs_lib::*;
 
-use std::path::Path;
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 pub struct NamedObjectLocalStorage {
     meta: NamedObjectMetaRef,
-    blob: Box<dyn BlobStorage>,
+    blob: BlobStorageRef,
+    cold: ColdBlobStorage,
+
+    // 用以查询对象的blob是否需要加密存储
+    object_meta_access_provider: OnceCell<NamedObjectCacheObjectMetaAccessProviderRef>,
 }
 
 impl NamedObjectLocalStorage {
-    pub async fn new(isolate: &str) -> BuckyResult<Self> {
-        let dir = cyfs_util::get_cyfs_root_path().join("data");
+    fn get_dir(isolate: &str) -> PathBuf {
+        let dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::Noc);
         let dir = if isolate.len() > 0 {
             dir.join(isolate)
         } else {
             dir
         };
-        let dir = dir.join("named-object-cache");
+        dir.join("named-object-cache")
+    }
+
+    pub async fn new(isolate: &str, durability: NamedObjectCacheDurability) -> BuckyResult<Self> {
+        let dir = Self::get_dir(isolate);
 
         if !dir.is_dir() {
             if let Err(e) = std::fs::create_dir_all(&dir) {
@@ -30,27 +41,88 @@ impl NamedObjectLocalStorage {
             }
         }
 
-        // Init blob module
-        let blob = create_blob_storage(&dir).await?;
+        info!("noc durability mode: {}", durability);
+
+        // meta和blob共用同一个coalescer，group-commit模式下同一个窗口内的写入一起落盘
+        let coalescer = CommitCoalescer::new(durability);
+
+        // Init blob module, the cold tier is stacked on the file blob storage
+        let blob = create_blob_storage_with_coalescer(&dir, coalescer.clone()).await?;
+        let cold = ColdBlobStorage::new(blob, dir.join("cold"), false)?;
+        let blob = Arc::new(Box::new(cold.clone()) as Box<dyn BlobStorage>);
 
-        let meta = Self::init_meta(&dir)?;
+        let meta = Self::init_meta(&dir, coalescer)?;
 
-        Ok(Self { blob, meta })
+        Ok(Self {
+            blob,
+            cold,
+            meta,
+            object_meta_access_provider: OnceCell::new(),
+        })
+    }
+
+    // 只读打开已经存在的noc存储，不会创建目录和初始化数据库
+    pub fn open_readonly(isolate: &str) -> BuckyResult<Self> {
+        let dir = Self::get_dir(isolate);
+        if !dir.is_dir() {
+            let msg = format!("noc data dir not found! dir={}", dir.display());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        // 冷存储里的对象只读取，不会水合回热存储
+        let blob = FileBlobStorage::new(dir.join("objects"));
+        let cold = ColdBlobStorage::new(Box::new(blob), dir.join("cold"), true)?;
+        let blob = Arc::new(Box::new(cold.clone()) as Box<dyn BlobStorage>);
+
+        let meta = create_meta_readonly(&dir)?;
+
+        Ok(Self {
+            blob,
+            cold,
+            meta,
+            object_meta_access_provider: OnceCell::new(),
+        })
     }
 
     pub fn meta(&self) -> &NamedObjectMetaRef {
         &self.meta
     }
 
-    fn init_meta(root: &Path) -> BuckyResult<NamedObjectMetaRef> {
-        create_meta(root)
+    pub fn checker(&self) -> NamedObjectStorageChecker {
+        NamedObjectStorageChecker::new(self.meta.clone(), self.blob.clone())
+    }
+
+    pub fn cold_tier(&self) -> NamedObjectColdTier {
+        NamedObjectColdTier::new(self.meta.clone(), self.cold.clone())
+    }
+
+    fn init_meta(root: &Path, coalescer: CommitCoalescer) -> BuckyResult<NamedObjectMetaRef> {
+        create_meta(root, coalescer)
     }
 
     async fn put_object(
         &self,
         request: &NamedObjectCachePutObjectRequest,
     ) -> BuckyResult<NamedObjectCachePutObjectResponse> {
-        let meta_req = self.gen_meta_put_request(request)?;
+        // 写入前检查noc所在磁盘的剩余空间，只读模式下直接拒绝
+        cyfs_util::check_storage_space(
+            cyfs_util::StorageRootCategory::Noc,
+            request.object.object_raw.len() as u64,
+        )?;
+
+        let ret = self.put_object_inner(request).await;
+        cyfs_util::report_storage_write_result(cyfs_util::StorageRootCategory::Noc, &ret);
+
+        ret
+    }
+
+    async fn put_object_inner(
+        &self,
+        request: &NamedObjectCachePutObjectRequest,
+    ) -> BuckyResult<NamedObjectCachePutObjectResponse> {
+        let meta_req = Self::gen_meta_put_request(request)?;
+        let encrypt = self.check_encrypt(request).await?;
         let meta_ret = self.meta.put_object(&meta_req).await?;
 
         info!(
@@ -61,16 +133,20 @@ impl NamedObjectLocalStorage {
         let put_ret;
         match meta_ret.result {
             NamedObjectMetaPutObjectResult::Accept => {
-                self.blob.put_object(request.object.clone()).await?;
+                self.put_blob(request.object.clone(), encrypt).await?;
                 put_ret = NamedObjectCachePutObjectResult::Accept;
             }
             NamedObjectMetaPutObjectResult::AlreadyExists => {
                 let ret = self.blob.get_object(&request.object.object_id).await?;
                 match ret {
-                    Some(data) => {
+                    Some(mut data) => {
+                        let prev = Self::take_version(&mut data);
                         let ret = self.merge_body_and_signs(data, &request.object)?;
                         if let Some((result, data)) = ret {
-                            self.blob.put_object(data).await?;
+                            if let NamedObjectCachePutObjectResult::Updated = result {
+                                self.archive_version(prev).await;
+                            }
+                            self.put_blob(data, encrypt).await?;
                             put_ret = result;
                         } else {
                             put_ret = NamedObjectCachePutObjectResult::AlreadyExists;
@@ -81,7 +157,7 @@ impl NamedObjectLocalStorage {
                             "object not exists in blob storage, now will save! obj={}",
                             request.object.object_id
                         );
-                        self.blob.put_object(request.object.clone()).await?;
+                        self.put_blob(request.object.clone(), encrypt).await?;
 
                         put_ret = NamedObjectCachePutObjectResult::AlreadyExists;
                     }
@@ -90,10 +166,14 @@ impl NamedObjectLocalStorage {
             NamedObjectMetaPutObjectResult::Updated => {
                 let ret = self.blob.get_object(&request.object.object_id).await?;
                 match ret {
-                    Some(data) => {
+                    Some(mut data) => {
+                        let prev = Self::take_version(&mut data);
                         let ret = self.merge_body_and_signs(data, &request.object)?;
                         if let Some((result, data)) = ret {
-                            self.blob.put_object(data).await?;
+                            if let NamedObjectCachePutObjectResult::Updated = result {
+                                self.archive_version(prev).await;
+                            }
+                            self.put_blob(data, encrypt).await?;
                             put_ret = result;
                         } else {
                             put_ret = NamedObjectCachePutObjectResult::Updated;
@@ -104,7 +184,7 @@ impl NamedObjectLocalStorage {
                             "object not exists in blob storage, now will save! obj={}",
                             request.object.object_id
                         );
-                        self.blob.put_object(request.object.clone()).await?;
+                        self.put_blob(request.object.clone(), encrypt).await?;
 
                         put_ret = NamedObjectCachePutObjectResult::Updated;
                     }
@@ -121,8 +201,60 @@ impl NamedObjectLocalStorage {
         Ok(resp)
     }
 
-    fn gen_meta_put_request(
-        &self,
+    // 按rmeta里object meta的配置判断对象blob是否需要加密，按请求来源的dec查找
+    // 查询失败时无法确定是否需要加密，直接返回错误，避免按明文落盘
+    async fn check_encrypt(&self, request: &NamedObjectCachePutObjectRequest) -> BuckyResult<bool> {
+        let provider = match self.object_meta_access_provider.get() {
+            Some(v) => v,
+            None => return Ok(false),
+        };
+
+        let ret = if request.object.object.is_some() {
+            provider
+                .get_object_encrypt(&request.source.dec, &request.object)
+                .await
+        } else {
+            let mut data = request.object.clone();
+            data.decode()?;
+            provider.get_object_encrypt(&request.source.dec, &data).await
+        };
+
+        ret.map_err(|e| {
+            error!(
+                "query object encrypt from object meta failed! obj={}, dec={}, {}",
+                request.object.object_id, request.source.dec, e
+            );
+            e
+        })
+    }
+
+    async fn put_blob(&self, data: NONObjectInfo, encrypt: bool) -> BuckyResult<()> {
+        if encrypt {
+            self.blob.put_object_encrypted(data).await
+        } else {
+            self.blob.put_object(data).await
+        }
+    }
+
+    fn take_version(current: &mut NONObjectInfo) -> NamedObjectMetaPutObjectVersionRequest {
+        NamedObjectMetaPutObjectVersionRequest {
+            object_id: current.object_id.clone(),
+            object_update_time: current.object.as_ref().unwrap().update_time(),
+            object_raw: std::mem::take(&mut current.object_raw),
+        }
+    }
+
+    // body被替换前，按rmeta配置把当前版本归档到meta里，失败不影响本次put
+    async fn archive_version(&self, req: NamedObjectMetaPutObjectVersionRequest) {
+        if let Err(e) = self.meta.put_object_version(&req).await {
+            error!(
+                "archive object version before replace body failed! obj={}, {}",
+                req.object_id, e
+            );
+        }
+    }
+
+    pub(crate) fn gen_meta_put_request(
         request: &NamedObjectCachePutObjectRequest,
     ) -> BuckyResult<NamedObjectMetaPutObjectRequest> {
         let obj = request.object.object_if_none_then_decode()?;
@@ -481,6 +613,37 @@ impl NamedObjectLocalStorage {
     ) -> BuckyResult<NamedObjectCacheSelectObjectResponse> {
         self.meta.select_object(req).await
     }
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        self.meta.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        let ret = self.meta.get_object_revision(req).await?;
+        match ret {
+            Some(data) => {
+                let object = NONObjectInfo::new_from_object_raw(data.object_raw).map_err(|e| {
+                    error!(
+                        "decode object revision failed! obj={}, revision={}, {}",
+                        req.object_id, req.revision, e
+                    );
+                    e
+                })?;
+
+                Ok(Some(NamedObjectCacheObjectRevisionData {
+                    version: data.version,
+                    object,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -538,10 +701,31 @@ impl NamedObjectCache for NamedObjectLocalStorage {
         Self::select_object(self, req).await
     }
 
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        Self::get_object_history(self, req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        Self::get_object_revision(self, req).await
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
     ) {
+        if let Err(_) = self
+            .object_meta_access_provider
+            .set(object_meta_access_provider.clone())
+        {
+            unreachable!();
+        }
+
         self.meta.bind_object_meta_access_provider(object_meta_access_provider)
     }
 }
@@ -1,5 +1,11 @@
+mod check;
+mod cold;
 mod local;
 mod serial;
+mod readonly;
 
 pub use local::*;
 pub use serial::*;
+pub use readonly::*;
+pub use check::*;
+pub use cold::*;
@@ -0,0 +1,100 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+// 只读的noc，所有写操作都会被拒绝，读操作不会更新last_access
+pub struct NamedObjectCacheReadOnly {
+    next: NamedObjectCacheRef,
+}
+
+impl NamedObjectCacheReadOnly {
+    pub fn new(next: NamedObjectCacheRef) -> Self {
+        Self { next }
+    }
+
+    fn readonly_error(op: &str, object_id: &ObjectId) -> BuckyError {
+        let msg = format!("noc is opened in readonly mode! op={}, object={}", op, object_id);
+        warn!("{}", msg);
+        BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
+    }
+}
+
+#[async_trait::async_trait]
+impl NamedObjectCache for NamedObjectCacheReadOnly {
+    async fn put_object(
+        &self,
+        req: &NamedObjectCachePutObjectRequest,
+    ) -> BuckyResult<NamedObjectCachePutObjectResponse> {
+        Err(Self::readonly_error("put_object", &req.object.object_id))
+    }
+
+    async fn get_object_raw(
+        &self,
+        req: &NamedObjectCacheGetObjectRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRawData>> {
+        let mut req = req.clone();
+        req.set_no_update_last_access();
+
+        self.next.get_object_raw(&req).await
+    }
+
+    async fn delete_object(
+        &self,
+        req: &NamedObjectCacheDeleteObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheDeleteObjectResponse> {
+        Err(Self::readonly_error("delete_object", &req.object_id))
+    }
+
+    async fn exists_object(
+        &self,
+        req: &NamedObjectCacheExistsObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheExistsObjectResponse> {
+        self.next.exists_object(req).await
+    }
+
+    async fn update_object_meta(
+        &self,
+        req: &NamedObjectCacheUpdateObjectMetaRequest,
+    ) -> BuckyResult<()> {
+        Err(Self::readonly_error("update_object_meta", &req.object_id))
+    }
+
+    async fn check_object_access(
+        &self,
+        req: &NamedObjectCacheCheckObjectAccessRequest,
+    ) -> BuckyResult<Option<()>> {
+        self.next.check_object_access(req).await
+    }
+
+    async fn stat(&self) -> BuckyResult<NamedObjectCacheStat> {
+        self.next.stat().await
+    }
+
+    async fn select_object(
+        &self,
+        req: &NamedObjectCacheSelectObjectRequest,
+    ) -> BuckyResult<NamedObjectCacheSelectObjectResponse> {
+        self.next.select_object(req).await
+    }
+
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        self.next.get_object_revision(req).await
+    }
+
+    fn bind_object_meta_access_provider(
+        &self,
+        object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
+    ) {
+        self.next
+            .bind_object_meta_access_provider(object_meta_access_provider)
+    }
+}
@@ -77,6 +77,23 @@ impl NamedObjectCacheSerializer {
         lock
     }
 
+    // 需要在持有对象锁的情况下检查，保证检查和写入之间对象不会被修改
+    async fn check_precondition(
+        &self,
+        req: &NamedObjectCachePutObjectRequest,
+        precondition: &NamedObjectCachePutPrecondition,
+    ) -> BuckyResult<()> {
+        let get_req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: req.object.object_id.clone(),
+            last_access_rpath: None,
+            flags: 0,
+        };
+
+        let current = self.next.get_object_raw(&get_req).await?;
+        precondition.check(&req.object.object_id, current.as_ref().map(|v| &v.meta))
+    }
+
     fn leave_lock(&self, object_id: &ObjectId, lock: SerializeExecutorLockRef) {
         let ref_count = lock.release();
         if ref_count <= 0 {
@@ -105,7 +122,13 @@ impl NamedObjectCache for NamedObjectCacheSerializer {
         let lock = self.acquire_lock(&req.object.object_id);
         let ret = {
             let _guard = lock.lock.lock().await;
-            self.next.put_object(req).await
+            match &req.precondition {
+                Some(precondition) => match self.check_precondition(req, precondition).await {
+                    Ok(_) => self.next.put_object(req).await,
+                    Err(e) => Err(e),
+                },
+                None => self.next.put_object(req).await,
+            }
         };
 
         self.leave_lock(&req.object.object_id, lock);
@@ -199,6 +222,20 @@ impl NamedObjectCache for NamedObjectCacheSerializer {
         self.next.select_object(req).await
     }
 
+    async fn get_object_history(
+        &self,
+        req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        self.next.get_object_revision(req).await
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
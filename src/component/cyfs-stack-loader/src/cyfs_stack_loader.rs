@@ -1,6 +1,6 @@
 use crate::bdt_loader::*;
 use crate::ListenerUtil;
-use cyfs_base::{BuckyError, BuckyErrorCode, BuckyResult};
+use cyfs_base::{AesKey, BuckyError, BuckyErrorCode, BuckyResult};
 use cyfs_stack::CyfsStackParams;
 use cyfs_util::TomlHelper;
 
@@ -17,6 +17,9 @@ pub(crate) struct CyfsStackLoaderParams {
     pub id: String,
 
     pub shared_stack_stub: bool,
+
+    // 协议栈独立线程池的线程数
+    pub threads: usize,
 }
 
 impl Default for CyfsStackLoaderParams {
@@ -25,6 +28,7 @@ impl Default for CyfsStackLoaderParams {
             cyfs_stack_params: CyfsStackParams::new_default(),
             id: DEFAULT_BDT_STACK_ID.to_owned(),
             shared_stack_stub: false,
+            threads: 1,
         }
     }
 }
@@ -145,6 +149,10 @@ impl CyfsStackConfigLoader {
                     self.params.shared_stack_stub = TomlHelper::decode_from_boolean(v)?;
                 }
 
+                "threads" => {
+                    self.params.threads = TomlHelper::decode_to_int(v)?;
+                }
+
                 "sync_service" => {
                     self.params.cyfs_stack_params.config.sync_service =
                         TomlHelper::decode_from_boolean(v)?;
@@ -159,6 +167,18 @@ impl CyfsStackConfigLoader {
                     self.params.cyfs_stack_params.config.isolate = Some(v.as_str().unwrap().to_owned());
                 }
 
+                // 对象签名使用的硬件密钥，比如 sign_key = "pkcs11:token=cyfs;object=device"
+                // bdt协议栈仍然需要加载device.sec
+                "sign_key" => {
+                    self.params.cyfs_stack_params.config.sign_key =
+                        Some(TomlHelper::decode_from_string(v)?);
+                }
+
+                "preserve_file_metadata" => {
+                    self.params.cyfs_stack_params.config.preserve_file_metadata =
+                        TomlHelper::decode_from_boolean(v)?;
+                }
+
                 _ => {
                     warn!("unknown non stack.config field: {}", k.as_str());
                 }
@@ -203,8 +223,14 @@ impl CyfsStackConfigLoader {
     }
 
     fn load_noc(&mut self, node: &toml::value::Table) -> BuckyResult<()> {
-        for (k, _v) in node {
+        for (k, v) in node {
             match k.as_str() {
+                // 持久化策略: always, group-commit:{window_ms}, relaxed
+                "durability" => {
+                    self.params.cyfs_stack_params.noc.durability =
+                        TomlHelper::decode_from_string(v)?;
+                }
+
                 _ => {
                     warn!("unknown object stack noc field: {}", k.as_str());
                 }
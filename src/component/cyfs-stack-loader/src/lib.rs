@@ -7,6 +7,7 @@ mod listener_util;
 mod random_port;
 mod stack_info;
 mod stack_manager;
+mod stack_runtime;
 mod var_manager;
 
 pub use crate::cyfs_loader::*;
@@ -1,6 +1,7 @@
 use super::random_port::*;
 use crate::bdt_loader::*;
 use crate::cyfs_stack_loader::*;
+use crate::stack_runtime::StackRuntimeRef;
 use crate::{KNOWN_OBJECTS_MANAGER, VAR_MANAGER};
 use cyfs_base::*;
 use cyfs_bdt::StackGuard;
@@ -19,6 +20,8 @@ pub(crate) struct StackInfo {
 
     bdt_stack: Option<StackGuard>,
     cyfs_stack: Option<CyfsStack>,
+
+    runtime: Option<StackRuntimeRef>,
 }
 
 impl StackInfo {
@@ -31,6 +34,7 @@ impl StackInfo {
             bdt_params: BdtParams::default(),
 
             cyfs_stack: None,
+            runtime: None,
         }
     }
 
@@ -59,6 +63,19 @@ impl StackInfo {
         self.cyfs_stack.as_ref()
     }
 
+    pub fn threads(&self) -> usize {
+        self.stack_params.threads
+    }
+
+    pub fn runtime(&self) -> Option<&StackRuntimeRef> {
+        self.runtime.as_ref()
+    }
+
+    pub fn bind_runtime(&mut self, runtime: StackRuntimeRef) {
+        assert!(self.runtime.is_none());
+        self.runtime = Some(runtime);
+    }
+
     pub fn load(mut self, node: &toml::value::Table) -> BuckyResult<Self> {
         let mut loader = CyfsStackConfigLoader::new(self.stack_params, self.bdt_params);
         loader.load(node)?;
@@ -155,6 +172,7 @@ impl StackInfo {
             udp_sn_only: self.bdt_params.udp_sn_only,
             sn_mode: self.bdt_params.sn_mode,
             ping_interval: self.bdt_params.ping_interval,
+            session_resume: self.bdt_params.session_resume,
         };
 
         bdt_param
@@ -1,11 +1,34 @@
 use crate::stack_info::StackInfo;
+use crate::stack_runtime::StackRuntime;
 use cyfs_base::{BuckyError, BuckyErrorCode, BuckyResult, DeviceId};
 use cyfs_bdt::StackGuard;
 use cyfs_debug::Mutex;
+use cyfs_lib::NamedObjectCache;
 use cyfs_stack::CyfsStack;
 
 use lazy_static::lazy_static;
 use std::sync::Arc;
+use std::time::Duration;
+
+// 停止协议栈时等待处理中请求完成的最长时间
+const STACK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 单个协议栈的资源占用情况
+#[derive(Debug, Clone)]
+pub struct StackResourceStat {
+    pub id: String,
+    pub device_id: DeviceId,
+    pub isolate: Option<String>,
+
+    // 独立线程池
+    pub threads: usize,
+    pub pending_jobs: usize,
+    pub running_jobs: usize,
+
+    // noc占用
+    pub noc_object_count: u64,
+    pub noc_storage_size: u64,
+}
 
 pub(crate) struct StackManagerImpl {
     stack_list: Vec<StackInfo>,
@@ -147,6 +170,18 @@ impl StackManagerImpl {
         self.stack_list.iter().any(|item| item.id() == id)
     }
 
+    fn remove_stack(&mut self, id: &str) -> Option<StackInfo> {
+        let index = self.stack_list.iter().position(|item| item.id() == id)?;
+        Some(self.stack_list.remove(index))
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        self.stack_list
+            .iter()
+            .map(|item| item.id().to_owned())
+            .collect()
+    }
+
     pub fn get_stack(&self, id: &str) -> Option<&StackInfo> {
         for item in &self.stack_list {
             if item.id() == id {
@@ -187,37 +222,134 @@ impl<'a> IntoIterator for &'a StackManagerImpl {
     }
 }
 
-pub struct StackManager(Arc<Mutex<StackManagerImpl>>);
+pub struct StackManager(Arc<Mutex<StackManagerImpl>>, async_std::sync::Mutex<()>);
 
 impl StackManager {
     pub fn new() -> Self {
-        Self(Arc::new(Mutex::new(StackManagerImpl::new())))
+        Self(
+            Arc::new(Mutex::new(StackManagerImpl::new())),
+            async_std::sync::Mutex::new(()),
+        )
     }
 
     // 加载配置文件的stack根节点，支持列表和table两种模式，支持多个stack
     pub async fn load(&self, node: toml::Value) -> BuckyResult<()> {
         let list = StackManagerImpl::load(node)?;
 
-        for mut item in list {
-            // 避免调用栈过深，这里使用异步加载
-            let this = self.0.clone();
-            async_std::task::spawn(async move {
-                this.lock().unwrap().check_stack(&item)?;
+        // 同一时间只允许一个协议栈的启动或者停止
+        let _guard = self.1.lock().await;
+        for item in list {
+            self.start_item(item).await?;
+        }
 
+        Ok(())
+    }
+
+    // 运行时单独启动一个协议栈，格式和配置文件里面的单个stack节点一致
+    pub async fn start_stack(&self, node: toml::value::Table) -> BuckyResult<()> {
+        self.load(toml::Value::Table(node)).await
+    }
+
+    async fn start_item(&self, item: StackInfo) -> BuckyResult<()> {
+        self.0.lock().unwrap().check_stack(&item)?;
+
+        // 每个协议栈使用独立的线程池加载，同时也避免调用栈过深
+        let runtime = Arc::new(StackRuntime::new(item.id(), item.threads())?);
+        let ret = runtime
+            .run(async move {
+                let mut item = item;
                 if let Err(e) = item.init().await {
                     error!("init cyfs stack failed! id={}, {}", item.id(), e);
                     return Err(e);
                 }
 
-                this.lock().unwrap().stack_list.push(item);
-                Ok(())
+                Ok(item)
             })
-            .await?;
-        }
+            .await;
+
+        let mut item = match ret {
+            Ok(item) => item,
+            Err(e) => {
+                runtime.close().await;
+                return Err(e);
+            }
+        };
+
+        item.bind_runtime(runtime);
+        self.0.lock().unwrap().stack_list.push(item);
 
         Ok(())
     }
 
+    // 优雅停止并移除指定的协议栈，停止后可以使用相同的id重新启动
+    pub async fn stop_stack(&self, id: &str) -> BuckyResult<()> {
+        let _guard = self.1.lock().await;
+
+        let item = self.0.lock().unwrap().remove_stack(id);
+        let item = match item {
+            Some(item) => item,
+            None => {
+                let msg = format!("stop stack but not found! id={}", id);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+            }
+        };
+
+        if item.is_default() {
+            warn!("will stop the default stack! id={}", id);
+        }
+
+        let stack = item.cyfs_stack().unwrap().to_owned();
+        let runtime = item.runtime().unwrap().clone();
+        let ret = runtime
+            .run(async move { stack.shutdown(STACK_SHUTDOWN_TIMEOUT).await })
+            .await;
+        runtime.close().await;
+
+        match &ret {
+            Ok(()) => info!("stop stack success! id={}, device={}", id, item.device_id()),
+            Err(e) => error!("stop stack failed! id={}, {}", id, e),
+        }
+
+        ret
+    }
+
+    pub fn list_stacks(&self) -> Vec<String> {
+        self.0.lock().unwrap().list()
+    }
+
+    pub async fn get_stack_stat(&self, id: &str) -> BuckyResult<StackResourceStat> {
+        let (stack, runtime, isolate) = {
+            let inner = self.0.lock().unwrap();
+            let item = inner.get_stack(id).ok_or_else(|| {
+                let msg = format!("get stack stat but not found! id={}", id);
+                BuckyError::new(BuckyErrorCode::NotFound, msg)
+            })?;
+
+            (
+                item.cyfs_stack().unwrap().to_owned(),
+                item.runtime().unwrap().clone(),
+                item.stack_params.cyfs_stack_params.config.isolate.clone(),
+            )
+        };
+
+        let device_id = stack.local_device_id().to_owned();
+        let noc_stat = runtime
+            .run(async move { stack.noc_manager().stat().await })
+            .await?;
+
+        Ok(StackResourceStat {
+            id: id.to_owned(),
+            device_id,
+            isolate,
+            threads: runtime.threads(),
+            pending_jobs: runtime.pending_jobs(),
+            running_jobs: runtime.running_jobs(),
+            noc_object_count: noc_stat.count,
+            noc_storage_size: noc_stat.storage_size,
+        })
+    }
+
     pub fn exists(&self, id: &str) -> bool {
         self.0.lock().unwrap().exists(id)
     }
@@ -0,0 +1,133 @@
+use cyfs_base::*;
+
+use async_std::channel::{Receiver, Sender};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+type StackRuntimeJob = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+// 每个协议栈独立的线程池，由loader发起的加载、停止、统计等任务都在对应协议栈的线程池里面执行，
+// 一个协议栈的加载或者停止阻塞时不会影响到同进程的其余协议栈
+// 注意协议栈内部通过async_std::task::spawn创建的后台任务仍然使用全局的executor
+pub(crate) struct StackRuntime {
+    id: String,
+    threads: usize,
+
+    sender: Sender<StackRuntimeJob>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+
+    running: Arc<AtomicUsize>,
+}
+
+impl StackRuntime {
+    pub fn new(id: &str, threads: usize) -> BuckyResult<Self> {
+        let threads = std::cmp::max(threads, 1);
+        let (sender, receiver) = async_std::channel::unbounded();
+        let running = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::with_capacity(threads);
+        for index in 0..threads {
+            let receiver: Receiver<StackRuntimeJob> = receiver.clone();
+            let running = running.clone();
+
+            let handle = std::thread::Builder::new()
+                .name(format!("cyfs-stack-{}-{}", id, index))
+                .spawn(move || {
+                    async_std::task::block_on(async move {
+                        while let Ok(job) = receiver.recv().await {
+                            running.fetch_add(1, Ordering::SeqCst);
+                            job.await;
+                            running.fetch_sub(1, Ordering::SeqCst);
+                        }
+                    })
+                })
+                .map_err(|e| {
+                    let msg = format!(
+                        "create stack runtime thread failed! stack={}, index={}, {}",
+                        id, index, e
+                    );
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                });
+
+            match handle {
+                Ok(handle) => handles.push(handle),
+                Err(e) => {
+                    // 已经创建的线程在sender释放后会自动退出
+                    sender.close();
+                    return Err(e);
+                }
+            }
+        }
+
+        info!("create stack runtime success! stack={}, threads={}", id, threads);
+
+        Ok(Self {
+            id: id.to_owned(),
+            threads,
+            sender,
+            handles: Mutex::new(handles),
+            running,
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn pending_jobs(&self) -> usize {
+        self.sender.len()
+    }
+
+    pub fn running_jobs(&self) -> usize {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    // 在协议栈的线程池里面执行并等待结果
+    pub async fn run<F, T>(&self, fut: F) -> BuckyResult<T>
+    where
+        F: Future<Output = BuckyResult<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = async_std::channel::bounded(1);
+        let job = Box::pin(async move {
+            let ret = fut.await;
+            let _ = tx.send(ret).await;
+        });
+
+        if let Err(_) = self.sender.send(job).await {
+            let msg = format!("stack runtime already closed! stack={}", self.id);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::ErrorState, msg));
+        }
+
+        rx.recv().await.map_err(|_| {
+            let msg = format!("stack runtime job been dropped! stack={}", self.id);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::Interrupted, msg)
+        })?
+    }
+
+    // 关闭后不再接收新的任务，已经提交的任务执行完毕后线程退出
+    // 不能在当前线程池的任务里面调用，否则会等待自己退出
+    pub async fn close(&self) {
+        if !self.sender.close() {
+            return;
+        }
+
+        let handles: Vec<JoinHandle<()>> = self.handles.lock().unwrap().drain(..).collect();
+        async_std::task::spawn_blocking(move || {
+            for handle in handles {
+                let _ = handle.join();
+            }
+        })
+        .await;
+
+        info!("stack runtime closed! stack={}", self.id);
+    }
+}
+
+pub(crate) type StackRuntimeRef = Arc<StackRuntime>;
@@ -0,0 +1,182 @@
+use super::processor::*;
+use super::recorder::*;
+use super::storage::*;
+use crate::bandwidth::{day_of, format_day};
+use crate::config::{StackDynamicConfig, StackDynamicConfigApplier, StackGlobalConfig};
+use crate::ndn::NDNInputProcessorRef;
+use crate::non::NONInputProcessorRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const ACCESS_STAT_FLUSH_INTERVAL_SECS: u64 = 60;
+
+const ACCESS_STAT_MAX_TOP: u32 = 1000;
+
+struct ObjectAccessStatManagerInner {
+    config: StackGlobalConfig,
+    recorder: ObjectAccessRecorder,
+    storage: ObjectAccessStorage,
+
+    // 上次清理过期文件时所在的天，每天只清理一次
+    last_clean_day: Mutex<u32>,
+
+    // 定时落盘和查询时的落盘不能并发，否则同一天的文件可能被覆盖
+    flush_lock: async_std::sync::Mutex<()>,
+}
+
+// 对象访问统计，记录对外接口上non/ndn读取的对象，按dec和天汇总，用来查询每个dec最常被访问的对象
+// 访问按采样率记录并在内存里合并，定时批量落盘，不影响读取路径的性能
+#[derive(Clone)]
+pub(crate) struct ObjectAccessStatManager(Arc<ObjectAccessStatManagerInner>);
+
+impl ObjectAccessStatManager {
+    pub fn new(isolate: &str, config: &StackGlobalConfig) -> Self {
+        let inner = ObjectAccessStatManagerInner {
+            config: config.clone(),
+            recorder: ObjectAccessRecorder::new(),
+            storage: ObjectAccessStorage::new(isolate),
+            last_clean_day: Mutex::new(0),
+            flush_lock: async_std::sync::Mutex::new(()),
+        };
+
+        let ret = Self(Arc::new(inner));
+        config
+            .dynamic_config()
+            .register_applier("access_stat", Arc::new(Box::new(ret.clone())));
+
+        ret
+    }
+
+    pub fn wrap_non_processor(&self, processor: NONInputProcessorRef) -> NONInputProcessorRef {
+        NONAccessStatProcessor::new_raw(processor, self.0.recorder.clone())
+    }
+
+    pub fn wrap_ndn_processor(&self, processor: NDNInputProcessorRef) -> NDNInputProcessorRef {
+        NDNAccessStatProcessor::new_raw(processor, self.0.recorder.clone())
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run_flush().await;
+        });
+    }
+
+    async fn run_flush(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(ACCESS_STAT_FLUSH_INTERVAL_SECS));
+        while let Some(_) = interval.next().await {
+            if let Err(e) = self.flush().await {
+                warn!("flush object access stat failed! {}", e);
+            }
+
+            self.clean_expired();
+        }
+    }
+
+    async fn flush(&self) -> BuckyResult<()> {
+        let _guard = self.0.flush_lock.lock().await;
+
+        let records = self.0.recorder.take();
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut days: BTreeMap<u32, Vec<ObjectAccessItem>> = BTreeMap::new();
+        for (key, record) in records {
+            let item = ObjectAccessItem {
+                dec_id: key.dec_id,
+                object_id: key.object_id,
+                category: key.category,
+                count: record.count,
+                last_access: record.last_access,
+            };
+
+            days.entry(key.day).or_insert_with(Vec::new).push(item);
+        }
+
+        for (day, items) in days {
+            self.0.storage.merge(day, items).await?;
+        }
+
+        Ok(())
+    }
+
+    fn clean_expired(&self) {
+        let today = day_of(bucky_time_now());
+        {
+            let mut last_clean_day = self.0.last_clean_day.lock().unwrap();
+            if *last_clean_day == today {
+                return;
+            }
+            *last_clean_day = today;
+        }
+
+        let retention_days = self.0.config.dynamic_config().access_stat().retention_days;
+        if today >= retention_days {
+            self.0.storage.remove_before(today + 1 - retention_days);
+        }
+    }
+
+    pub async fn get_stat(
+        &self,
+        dec_id: Option<&ObjectId>,
+        days: u32,
+        top: u32,
+    ) -> BuckyResult<UtilGetObjectAccessStatOutputResponse> {
+        let retention_days = self.0.config.dynamic_config().access_stat().retention_days;
+        if days == 0 || days > retention_days || top == 0 || top > ACCESS_STAT_MAX_TOP {
+            let msg = format!(
+                "invalid object access stat query! days={}, top={}, should be 0 < days <= {}, 0 < top <= {}",
+                days, top, retention_days, ACCESS_STAT_MAX_TOP
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        // 先把内存里的统计落盘，查询结果包括到目前为止采样到的访问
+        if let Err(e) = self.flush().await {
+            warn!("flush object access stat before query failed! {}", e);
+        }
+
+        let to_day = day_of(bucky_time_now());
+        let from_day = to_day.saturating_sub(days - 1);
+
+        let mut list = vec![];
+        for day in from_day..=to_day {
+            let items: Vec<ObjectAccessItem> = self
+                .0
+                .storage
+                .load(day)
+                .await?
+                .into_iter()
+                .filter(|item| dec_id.is_none() || Some(&item.dec_id) == dec_id)
+                .collect();
+
+            merge_items(&mut list, items);
+        }
+
+        Ok(UtilGetObjectAccessStatOutputResponse {
+            from_day: format_day(from_day),
+            to_day: format_day(to_day),
+            list: top_items(list, top as usize),
+        })
+    }
+}
+
+impl StackDynamicConfigApplier for ObjectAccessStatManager {
+    fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()> {
+        let access_stat = &config.access_stat;
+        self.0
+            .recorder
+            .set_config(access_stat.enable, access_stat.sample_rate);
+
+        Ok(())
+    }
+}
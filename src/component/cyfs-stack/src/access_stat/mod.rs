@@ -0,0 +1,6 @@
+mod manager;
+mod processor;
+mod recorder;
+mod storage;
+
+pub(crate) use manager::*;
@@ -0,0 +1,124 @@
+use super::recorder::ObjectAccessRecorder;
+use crate::ndn::*;
+use crate::non::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+// 访问归属的dec，优先使用req_path里的目标dec，没有时为请求方的dec
+fn target_dec(req_path: Option<&String>, source: &RequestSourceInfo) -> ObjectId {
+    match req_path.and_then(|path| RequestGlobalStatePath::from_str(path).ok()) {
+        Some(path) => path.dec(source).to_owned(),
+        None => source.dec.clone(),
+    }
+}
+
+// 只记录成功的get_object，其余请求直接转发
+pub(crate) struct NONAccessStatProcessor {
+    next: NONInputProcessorRef,
+    recorder: ObjectAccessRecorder,
+}
+
+impl NONAccessStatProcessor {
+    pub fn new_raw(
+        next: NONInputProcessorRef,
+        recorder: ObjectAccessRecorder,
+    ) -> NONInputProcessorRef {
+        let ret = Self { next, recorder };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl NONInputProcessor for NONAccessStatProcessor {
+    async fn put_object(
+        &self,
+        req: NONPutObjectInputRequest,
+    ) -> BuckyResult<NONPutObjectInputResponse> {
+        self.next.put_object(req).await
+    }
+
+    async fn get_object(
+        &self,
+        req: NONGetObjectInputRequest,
+    ) -> BuckyResult<NONGetObjectInputResponse> {
+        let dec_id = target_dec(req.common.req_path.as_ref(), &req.common.source);
+        let resp = self.next.get_object(req).await?;
+
+        // 有inner_path时记录实际返回的对象
+        self.recorder
+            .record(ObjectAccessCategory::Non, &dec_id, &resp.object.object_id);
+
+        Ok(resp)
+    }
+
+    async fn post_object(
+        &self,
+        req: NONPostObjectInputRequest,
+    ) -> BuckyResult<NONPostObjectInputResponse> {
+        self.next.post_object(req).await
+    }
+
+    async fn select_object(
+        &self,
+        req: NONSelectObjectInputRequest,
+    ) -> BuckyResult<NONSelectObjectInputResponse> {
+        self.next.select_object(req).await
+    }
+
+    async fn delete_object(
+        &self,
+        req: NONDeleteObjectInputRequest,
+    ) -> BuckyResult<NONDeleteObjectInputResponse> {
+        self.next.delete_object(req).await
+    }
+}
+
+// 只记录成功的get_data，在返回数据流时计数，不关心数据是否读完
+pub(crate) struct NDNAccessStatProcessor {
+    next: NDNInputProcessorRef,
+    recorder: ObjectAccessRecorder,
+}
+
+impl NDNAccessStatProcessor {
+    pub fn new_raw(
+        next: NDNInputProcessorRef,
+        recorder: ObjectAccessRecorder,
+    ) -> NDNInputProcessorRef {
+        let ret = Self { next, recorder };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl NDNInputProcessor for NDNAccessStatProcessor {
+    async fn put_data(&self, req: NDNPutDataInputRequest) -> BuckyResult<NDNPutDataInputResponse> {
+        self.next.put_data(req).await
+    }
+
+    async fn get_data(&self, req: NDNGetDataInputRequest) -> BuckyResult<NDNGetDataInputResponse> {
+        let dec_id = target_dec(req.common.req_path.as_ref(), &req.common.source);
+        let resp = self.next.get_data(req).await?;
+
+        self.recorder
+            .record(ObjectAccessCategory::Ndn, &dec_id, &resp.object_id);
+
+        Ok(resp)
+    }
+
+    async fn delete_data(
+        &self,
+        req: NDNDeleteDataInputRequest,
+    ) -> BuckyResult<NDNDeleteDataInputResponse> {
+        self.next.delete_data(req).await
+    }
+
+    async fn query_file(
+        &self,
+        req: NDNQueryFileInputRequest,
+    ) -> BuckyResult<NDNQueryFileInputResponse> {
+        self.next.query_file(req).await
+    }
+}
@@ -0,0 +1,103 @@
+use crate::bandwidth::day_of;
+use cyfs_base::*;
+use cyfs_lib::ObjectAccessCategory;
+
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+// 两次落盘之间内存里最多保留的不同对象数，超出后新对象的访问直接丢弃
+const ACCESS_RECORD_MAX_PENDING: usize = 1024 * 64;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct ObjectAccessRecordKey {
+    // 距离unix epoch的天数(UTC)
+    pub day: u32,
+
+    pub dec_id: ObjectId,
+    pub object_id: ObjectId,
+    pub category: ObjectAccessCategory,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ObjectAccessRecord {
+    pub count: u64,
+    pub last_access: u64,
+}
+
+struct ObjectAccessRecorderInner {
+    enable: AtomicBool,
+    sample_rate: AtomicU32,
+
+    pending: Mutex<HashMap<ObjectAccessRecordKey, ObjectAccessRecord>>,
+
+    // 因为内存里的记录过多而丢弃的访问次数
+    dropped: AtomicU64,
+}
+
+// non/ndn读取路径上的对象访问，按采样率记录后先在内存里累加，由ObjectAccessStatManager定时取走落盘
+#[derive(Clone)]
+pub(crate) struct ObjectAccessRecorder(Arc<ObjectAccessRecorderInner>);
+
+impl ObjectAccessRecorder {
+    pub fn new() -> Self {
+        let inner = ObjectAccessRecorderInner {
+            enable: AtomicBool::new(true),
+            sample_rate: AtomicU32::new(1),
+            pending: Mutex::new(HashMap::new()),
+            dropped: AtomicU64::new(0),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    pub fn set_config(&self, enable: bool, sample_rate: u32) {
+        self.0.enable.store(enable, Ordering::SeqCst);
+        self.0
+            .sample_rate
+            .store(std::cmp::max(sample_rate, 1), Ordering::SeqCst);
+    }
+
+    pub fn record(&self, category: ObjectAccessCategory, dec_id: &ObjectId, object_id: &ObjectId) {
+        if !self.0.enable.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let sample_rate = self.0.sample_rate.load(Ordering::SeqCst);
+        if sample_rate > 1 && rand::thread_rng().gen_range(0..sample_rate) != 0 {
+            return;
+        }
+
+        let now = bucky_time_now();
+        let key = ObjectAccessRecordKey {
+            day: day_of(now),
+            dec_id: dec_id.to_owned(),
+            object_id: object_id.to_owned(),
+            category,
+        };
+
+        let mut pending = self.0.pending.lock().unwrap();
+        if pending.len() >= ACCESS_RECORD_MAX_PENDING && !pending.contains_key(&key) {
+            let dropped = self.0.dropped.fetch_add(1, Ordering::SeqCst);
+            if dropped % 1000 == 0 {
+                warn!(
+                    "too many pending object access records, now will drop! object={}, dropped={}",
+                    object_id,
+                    dropped + 1
+                );
+            }
+            return;
+        }
+
+        let record = pending
+            .entry(key)
+            .or_insert_with(ObjectAccessRecord::default);
+        record.count += sample_rate as u64;
+        record.last_access = now;
+    }
+
+    pub fn take(&self) -> HashMap<ObjectAccessRecordKey, ObjectAccessRecord> {
+        std::mem::take(&mut *self.0.pending.lock().unwrap())
+    }
+}
@@ -0,0 +1,219 @@
+use crate::bandwidth::format_day;
+use cyfs_base::*;
+use cyfs_lib::{ObjectAccessCategory, ObjectAccessItem};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+type ObjectAccessItemKey = (ObjectId, ObjectId, ObjectAccessCategory);
+
+// 把items合并到list，同一个dec下的同一个对象累加次数，访问时间取最近的
+pub(crate) fn merge_items(list: &mut Vec<ObjectAccessItem>, items: Vec<ObjectAccessItem>) {
+    let mut index: HashMap<ObjectAccessItemKey, usize> = HashMap::new();
+    for (i, item) in list.iter().enumerate() {
+        index.insert(key_of(item), i);
+    }
+
+    for item in items {
+        match index.get(&key_of(&item)) {
+            Some(i) => {
+                let exists = &mut list[*i];
+                exists.count += item.count;
+                exists.last_access = std::cmp::max(exists.last_access, item.last_access);
+            }
+            None => {
+                index.insert(key_of(&item), list.len());
+                list.push(item);
+            }
+        }
+    }
+}
+
+fn key_of(item: &ObjectAccessItem) -> ObjectAccessItemKey {
+    (item.dec_id.clone(), item.object_id.clone(), item.category)
+}
+
+// 每个dec只保留访问次数最多的top个，结果按dec分组，组内按次数从高到低
+pub(crate) fn top_items(list: Vec<ObjectAccessItem>, top: usize) -> Vec<ObjectAccessItem> {
+    let mut decs: HashMap<ObjectId, Vec<ObjectAccessItem>> = HashMap::new();
+    for item in list {
+        decs.entry(item.dec_id.clone())
+            .or_insert_with(Vec::new)
+            .push(item);
+    }
+
+    let mut decs: Vec<(ObjectId, Vec<ObjectAccessItem>)> = decs.into_iter().collect();
+    decs.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let mut result = vec![];
+    for (_, mut items) in decs {
+        items.sort_by(|left, right| {
+            right
+                .count
+                .cmp(&left.count)
+                .then_with(|| right.last_access.cmp(&left.last_access))
+        });
+        items.truncate(top);
+        result.append(&mut items);
+    }
+
+    result
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ObjectAccessDayData {
+    list: Vec<ObjectAccessItem>,
+}
+
+// 按天保存的对象访问统计，每天一个文件
+// {cyfs_root}/data/{isolate}/access_stat/{YYYY-MM-DD}.json
+pub(crate) struct ObjectAccessStorage {
+    dir: PathBuf,
+}
+
+impl ObjectAccessStorage {
+    pub fn new(isolate: &str) -> Self {
+        let mut dir = cyfs_util::get_cyfs_root_path();
+        dir.push("data");
+        if isolate.len() > 0 {
+            dir.push(isolate);
+        }
+        dir.push("access_stat");
+
+        Self { dir }
+    }
+
+    fn day_file(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("{}.json", format_day(day)))
+    }
+
+    pub async fn load(&self, day: u32) -> BuckyResult<Vec<ObjectAccessItem>> {
+        let file = self.day_file(day);
+        if !file.exists() {
+            return Ok(vec![]);
+        }
+
+        let value = async_std::fs::read_to_string(&file).await.map_err(|e| {
+            let msg = format!(
+                "load object access stat error! file={}, {}",
+                file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let data: ObjectAccessDayData = serde_json::from_str(&value).map_err(|e| {
+            let msg = format!(
+                "invalid object access stat file! file={}, {}",
+                file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Ok(data.list)
+    }
+
+    // 累加到当天的文件里，先写临时文件再替换
+    pub async fn merge(&self, day: u32, items: Vec<ObjectAccessItem>) -> BuckyResult<()> {
+        let mut list = self.load(day).await?;
+        merge_items(&mut list, items);
+
+        if !self.dir.is_dir() {
+            if let Err(e) = async_std::fs::create_dir_all(&self.dir).await {
+                let msg = format!(
+                    "create object access stat dir error! dir={}, {}",
+                    self.dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+            }
+        }
+
+        let file = self.day_file(day);
+        let tmp_file = self.dir.join(format!("{}.json.tmp", format_day(day)));
+        let data = serde_json::to_string(&ObjectAccessDayData { list }).unwrap();
+        async_std::fs::write(&tmp_file, &data)
+            .await
+            .and_then(|_| std::fs::rename(&tmp_file, &file))
+            .map_err(|e| {
+                let msg = format!(
+                    "write object access stat file error! file={}, {}",
+                    file.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        debug!("save object access stat success! file={}", file.display());
+
+        Ok(())
+    }
+
+    // 删除day之前的文件，文件名是YYYY-MM-DD，可以直接按字符串比较
+    pub fn remove_before(&self, day: u32) {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        let limit = format_day(day);
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stem = name.split('.').next().unwrap_or("");
+            if stem.len() == limit.len() && stem < limit.as_str() {
+                match std::fs::remove_file(entry.path()) {
+                    Ok(_) => info!("remove expired object access stat file: {}", name),
+                    Err(e) => warn!(
+                        "remove expired object access stat file failed! file={}, {}",
+                        name, e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge_top() {
+        let dec1 = ObjectId::default();
+        let dec2 = ChunkId::calculate_sync(b"dec2").unwrap().object_id();
+        let object = |name: &[u8]| ChunkId::calculate_sync(name).unwrap().object_id();
+        let item = |dec_id: &ObjectId, name: &[u8], count, last_access| ObjectAccessItem {
+            dec_id: dec_id.clone(),
+            object_id: object(name),
+            category: ObjectAccessCategory::Ndn,
+            count,
+            last_access,
+        };
+
+        let mut list = vec![item(&dec1, b"a", 3, 1), item(&dec1, b"b", 5, 1)];
+        merge_items(
+            &mut list,
+            vec![
+                item(&dec1, b"a", 4, 2),
+                item(&dec1, b"c", 1, 1),
+                item(&dec2, b"a", 1, 1),
+            ],
+        );
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(list[0].count, 7);
+        assert_eq!(list[0].last_access, 2);
+
+        let top = top_items(list, 2);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].object_id, object(b"a"));
+        assert_eq!(top[1].object_id, object(b"b"));
+        assert_eq!(top[2].dec_id, dec2);
+    }
+}
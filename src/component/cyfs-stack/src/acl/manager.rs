@@ -2,6 +2,7 @@ use super::config::AclConfig;
 use super::loader::AclFileLoader;
 use super::loader::AclLoader;
 use super::zone_cache::*;
+use crate::config::StackGlobalConfig;
 use crate::resolver::DeviceCache;
 use crate::rmeta_api::GlobalStateMetaLocalService;
 use crate::zone::ZoneManagerRef;
@@ -77,7 +78,9 @@ pub struct AclManager {
 
     local_zone_cache: LocalZoneCache,
 
+    // 从acl配置文件加载的配置，运行时可以被stack config的[acl]段覆盖
     config: OnceCell<AclConfig>,
+    stack_config: StackGlobalConfig,
 }
 
 impl AclManager {
@@ -87,6 +90,7 @@ impl AclManager {
         noc: NamedObjectCacheRef,
         config_isolate: Option<String>,
         zone_manager: ZoneManagerRef,
+        stack_config: StackGlobalConfig,
     ) -> Self {
         let local_zone_cache = LocalZoneCache::new(zone_manager.clone(), noc.clone());
 
@@ -99,6 +103,7 @@ impl AclManager {
             file_loader,
             local_zone_cache,
             config: OnceCell::new(),
+            stack_config,
         }
     }
 
@@ -135,8 +140,18 @@ impl AclManager {
         &self.zone_manager
     }
 
-    pub fn config(&self) -> &AclConfig {
-        self.config.get().unwrap()
+    pub fn config(&self) -> AclConfig {
+        let mut config = self.config.get().unwrap().clone();
+
+        let dynamic = self.stack_config.dynamic_config().acl();
+        if let Some(v) = dynamic.read_bypass_ood {
+            config.read_bypass_ood = v;
+        }
+        if let Some(v) = dynamic.write_bypass_ood {
+            config.write_bypass_ood = v;
+        }
+
+        config
     }
 
     pub fn global_state_meta(&self) -> &GlobalStateMetaLocalService {
@@ -0,0 +1,804 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// owner注册的认证器保存在root_state系统dec下，随root_state同步到zone内的其它设备:
+// /.cyfs/admin/webauthn/{credential_id} -> 认证器text_id
+
+// Text对象id，header为credential_id，value为WebAuthnCredential的json
+const ADMIN_WEBAUTHN_TEXT_ID: &str = "admin_webauthn";
+
+// challenge和确认结果的有效期，微秒
+const ADMIN_CONFIRM_CHALLENGE_TIMEOUT: u64 = 1000 * 1000 * 60 * 2;
+const ADMIN_CONFIRM_TIMEOUT: u64 = 1000 * 1000 * 60;
+
+// 同时等待确认的challenge上限
+const ADMIN_CONFIRM_MAX_PENDING: usize = 64;
+
+// authenticator data: rpIdHash(32) + flags(1) + signCount(4)
+const AUTH_DATA_MIN_LEN: usize = 37;
+const AUTH_DATA_FLAG_UP: u8 = 0x01;
+const AUTH_DATA_FLAG_UV: u8 = 0x04;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WebAuthnCredential {
+    pub credential_id: String,
+
+    // hex编码的P-256公钥
+    pub public_key: String,
+    pub rp_id: String,
+
+    // 认证器的签名计数，用来发现被复制的认证器
+    pub sign_count: u32,
+    pub register_time: u64,
+}
+
+fn decode_base64url(name: &str, value: &str) -> BuckyResult<Vec<u8>> {
+    base64::decode_config(value.trim_end_matches('='), base64::URL_SAFE_NO_PAD).map_err(|e| {
+        let msg = format!("invalid webauthn {}, not base64url! {}", name, e);
+        warn!("{}", msg);
+        BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+    })
+}
+
+fn encode_base64url(value: &[u8]) -> String {
+    base64::encode_config(value, base64::URL_SAFE_NO_PAD)
+}
+
+fn invalid_assertion(reason: String) -> BuckyError {
+    let msg = format!("invalid webauthn assertion! {}", reason);
+    warn!("{}", msg);
+    BuckyError::new(BuckyErrorCode::InvalidSignature, msg)
+}
+
+impl WebAuthnCredential {
+    fn verifying_key(&self) -> BuckyResult<VerifyingKey> {
+        let buf = hex::decode(&self.public_key).map_err(|e| {
+            let msg = format!("invalid webauthn public key, not hex! {}", e);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        VerifyingKey::from_sec1_bytes(&buf).map_err(|e| {
+            let msg = format!("invalid webauthn public key, only P-256 supported! {}", e);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })
+    }
+
+    fn check_origin(&self, origin: &str) -> bool {
+        let host = match origin.split_once("://") {
+            Some((_, v)) => v,
+            None => return false,
+        };
+        let host = host.split(|c| c == ':' || c == '/').next().unwrap_or("");
+
+        host == self.rp_id || host.ends_with(&format!(".{}", self.rp_id))
+    }
+
+    // 校验assertion的签名和内容，返回clientDataJSON里的challenge和认证器的签名计数
+    fn verify_assertion(&self, assertion: &WebAuthnAssertion) -> BuckyResult<(String, u32)> {
+        let client_data = decode_base64url("client_data_json", &assertion.client_data_json)?;
+        let auth_data = decode_base64url("authenticator_data", &assertion.authenticator_data)?;
+        let signature = decode_base64url("signature", &assertion.signature)?;
+
+        let client: serde_json::Value = serde_json::from_slice(&client_data)
+            .map_err(|e| invalid_assertion(format!("client data not json: {}", e)))?;
+
+        let ty = client.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        if ty != "webauthn.get" {
+            return Err(invalid_assertion(format!(
+                "unexpected client data type: {}",
+                ty
+            )));
+        }
+
+        let origin = client.get("origin").and_then(|v| v.as_str()).unwrap_or("");
+        if !self.check_origin(origin) {
+            return Err(invalid_assertion(format!(
+                "origin not match rp id: origin={}, rp_id={}",
+                origin, self.rp_id
+            )));
+        }
+
+        let challenge = client
+            .get("challenge")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid_assertion("challenge missing".to_owned()))?;
+
+        if auth_data.len() < AUTH_DATA_MIN_LEN {
+            return Err(invalid_assertion(format!(
+                "authenticator data too short: {}",
+                auth_data.len()
+            )));
+        }
+
+        let rp_id_hash = sha2::Sha256::digest(self.rp_id.as_bytes());
+        if auth_data[..32] != rp_id_hash[..] {
+            return Err(invalid_assertion(format!(
+                "rp id hash not match: rp_id={}",
+                self.rp_id
+            )));
+        }
+
+        // 敏感操作要求用户在场并且通过了认证器的用户验证(pin/生物识别)
+        let flags = auth_data[32];
+        if flags & AUTH_DATA_FLAG_UP == 0 || flags & AUTH_DATA_FLAG_UV == 0 {
+            return Err(invalid_assertion(format!(
+                "user not present or not verified: flags={:#x}",
+                flags
+            )));
+        }
+
+        let mut count = [0u8; 4];
+        count.copy_from_slice(&auth_data[33..37]);
+        let sign_count = u32::from_be_bytes(count);
+
+        let signature = Signature::from_der(&signature)
+            .map_err(|e| invalid_assertion(format!("signature not der: {}", e)))?;
+
+        let mut msg = auth_data;
+        msg.extend_from_slice(&sha2::Sha256::digest(&client_data));
+
+        self.verifying_key()?
+            .verify(&msg, &signature)
+            .map_err(|e| invalid_assertion(format!("signature not match: {}", e)))?;
+
+        Ok((challenge.to_owned(), sign_count))
+    }
+}
+
+// 申请challenge的请求者，确认结果只能被同一个请求者使用
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct AdminConfirmRequester {
+    device: Option<DeviceId>,
+    dec: ObjectId,
+}
+
+impl From<&RequestSourceInfo> for AdminConfirmRequester {
+    fn from(source: &RequestSourceInfo) -> Self {
+        Self {
+            device: source.zone.device.clone(),
+            dec: source.dec.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for AdminConfirmRequester {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "device={:?}, dec={}", self.device, self.dec)
+    }
+}
+
+struct AdminConfirmChallenge {
+    action: AdminConfirmAction,
+    target: String,
+    requester: AdminConfirmRequester,
+    expire_time: u64,
+}
+
+#[derive(Default)]
+struct AdminConfirmState {
+    // challenge -> 等待确认的操作
+    challenges: HashMap<String, AdminConfirmChallenge>,
+
+    // 已确认的操作 -> 过期时间，执行一次后移除
+    confirmed: HashMap<(AdminConfirmAction, String, AdminConfirmRequester), u64>,
+
+    // 本设备上看到过的签名计数
+    sign_counts: HashMap<String, u32>,
+}
+
+impl AdminConfirmState {
+    fn clear_expired(&mut self, now: u64) {
+        self.challenges.retain(|_, v| v.expire_time > now);
+        self.confirmed.retain(|_, expire_time| *expire_time > now);
+    }
+
+    fn take_confirmed(
+        &mut self,
+        now: u64,
+        action: AdminConfirmAction,
+        target: &str,
+        requester: AdminConfirmRequester,
+    ) -> bool {
+        self.clear_expired(now);
+        self.confirmed
+            .remove(&(action, target.to_owned(), requester))
+            .is_some()
+    }
+}
+
+struct AdminConfirmManagerInner {
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+
+    state: Mutex<AdminConfirmState>,
+}
+
+// 敏感操作的二次确认，owner注册了WebAuthn认证器之后开启
+// challenge和确认结果只保存在处理请求的设备上，所以确认请求和要执行的操作需要发到同一个设备(ood)
+#[derive(Clone)]
+pub(crate) struct AdminConfirmManager(Arc<AdminConfirmManagerInner>);
+
+impl AdminConfirmManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = AdminConfirmManagerInner {
+            non,
+            root_state_stub,
+            state: Mutex::new(AdminConfirmState::default()),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    // 注册或者移除认证器，由owner签名的admin命令触发
+    // 已经有认证器时需要先用已有的认证器确认，避免泄露的owner密钥直接替换掉认证器
+    pub async fn update_credential(
+        &self,
+        source: &RequestSourceInfo,
+        data: &AdminWebAuthnCredentialData,
+    ) -> BuckyResult<()> {
+        let credential_id =
+            encode_base64url(&decode_base64url("credential_id", &data.credential_id)?);
+        if credential_id.is_empty() {
+            let msg = format!("webauthn credential id is empty!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        self.check(source, AdminConfirmAction::CredentialChange, &credential_id)
+            .await?;
+
+        let current = self.load_credential(&credential_id).await?;
+        let prev = current.as_ref().map(|(id, _)| id.clone());
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let mut text_id = None;
+        let ret = if data.remove {
+            if prev.is_none() {
+                warn!(
+                    "remove webauthn credential but not found! id={}",
+                    credential_id
+                );
+                let _ = op_env.abort().await;
+                return Ok(());
+            }
+
+            op_env
+                .remove_with_key(CYFS_ADMIN_WEBAUTHN_PATH, &credential_id, prev.clone())
+                .await
+                .map(|_| ())
+        } else {
+            if data.rp_id.is_empty() {
+                let _ = op_env.abort().await;
+                let msg = format!("webauthn credential rp id is empty! id={}", credential_id);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+
+            let credential = WebAuthnCredential {
+                credential_id: credential_id.clone(),
+                public_key: data.public_key.to_lowercase(),
+                rp_id: data.rp_id.clone(),
+                sign_count: 0,
+                register_time: bucky_time_now(),
+            };
+            if let Err(e) = credential.verifying_key() {
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+
+            let id = self.save_credential(&credential).await?;
+            text_id = Some(id.clone());
+            op_env
+                .set_with_key(
+                    CYFS_ADMIN_WEBAUTHN_PATH,
+                    &credential_id,
+                    &id,
+                    prev.clone(),
+                    prev.is_none(),
+                )
+                .await
+                .map(|_| ())
+        };
+
+        if let Err(e) = ret {
+            error!(
+                "update webauthn credential failed! id={}, remove={}, {}",
+                credential_id, data.remove, e
+            );
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        info!(
+            "update webauthn credential success! id={}, rp_id={}, remove={}",
+            credential_id, data.rp_id, data.remove
+        );
+
+        if let Some(prev) = prev {
+            if Some(&prev) != text_id.as_ref() {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        self.0
+            .state
+            .lock()
+            .unwrap()
+            .sign_counts
+            .remove(&credential_id);
+
+        Ok(())
+    }
+
+    pub async fn create_challenge(
+        &self,
+        source: &RequestSourceInfo,
+        action: AdminConfirmAction,
+        target: &str,
+    ) -> BuckyResult<AdminConfirmCreateChallengeOutputResponse> {
+        let list = self.list_credentials().await?;
+        if list.is_empty() {
+            let msg = format!("admin confirm not enabled, no webauthn credential registered!");
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+        }
+
+        let rp_id = list[0].rp_id.clone();
+        let credential_ids = list
+            .into_iter()
+            .filter(|v| v.rp_id == rp_id)
+            .map(|v| v.credential_id)
+            .collect();
+
+        let now = bucky_time_now();
+        let challenge = encode_base64url(&rand::random::<[u8; 32]>());
+        let expire_time = now + ADMIN_CONFIRM_CHALLENGE_TIMEOUT;
+
+        {
+            let mut state = self.0.state.lock().unwrap();
+            state.clear_expired(now);
+            if state.challenges.len() >= ADMIN_CONFIRM_MAX_PENDING {
+                let msg = format!(
+                    "too many pending admin confirm challenges! limit={}",
+                    ADMIN_CONFIRM_MAX_PENDING
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            state.challenges.insert(
+                challenge.clone(),
+                AdminConfirmChallenge {
+                    action,
+                    target: target.to_owned(),
+                    requester: source.into(),
+                    expire_time,
+                },
+            );
+        }
+
+        info!(
+            "create admin confirm challenge: action={}, target={}, challenge={}, source={}",
+            action, target, challenge, source
+        );
+
+        Ok(AdminConfirmCreateChallengeOutputResponse {
+            challenge,
+            rp_id,
+            credential_ids,
+            expire_time,
+        })
+    }
+
+    pub async fn confirm(
+        &self,
+        source: &RequestSourceInfo,
+        assertion: &WebAuthnAssertion,
+    ) -> BuckyResult<AdminConfirmOutputResponse> {
+        let credential_id = encode_base64url(&decode_base64url(
+            "credential_id",
+            &assertion.credential_id,
+        )?);
+        let credential = match self.load_credential(&credential_id).await? {
+            Some((_, v)) => v,
+            None => {
+                let msg = format!("webauthn credential not registered! id={}", credential_id);
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+            }
+        };
+
+        let (challenge, sign_count) = credential.verify_assertion(assertion)?;
+
+        let now = bucky_time_now();
+        let resp = {
+            let mut state = self.0.state.lock().unwrap();
+            state.clear_expired(now);
+
+            // 计数为0表示认证器不支持签名计数
+            let last = std::cmp::max(
+                credential.sign_count,
+                state.sign_counts.get(&credential_id).cloned().unwrap_or(0),
+            );
+            if (sign_count != 0 || last != 0) && sign_count <= last {
+                let msg = format!(
+                    "webauthn sign count not increased, the authenticator may be cloned! id={}, count={}, last={}",
+                    credential_id, sign_count, last
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidSignature, msg));
+            }
+
+            let requester = AdminConfirmRequester::from(source);
+            match state.challenges.get(&challenge) {
+                Some(item) if item.requester != requester => {
+                    let msg = format!(
+                        "admin confirm challenge requested by other source! challenge={}, requester={}, source={}",
+                        challenge, item.requester, requester
+                    );
+                    warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+                }
+                Some(_) => {}
+                None => {
+                    let msg = format!(
+                        "admin confirm challenge not found or expired! challenge={}",
+                        challenge
+                    );
+                    warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+                }
+            }
+            let item = state.challenges.remove(&challenge).unwrap();
+
+            state.sign_counts.insert(credential_id.clone(), sign_count);
+
+            let expire_time = now + ADMIN_CONFIRM_TIMEOUT;
+            state.confirmed.insert(
+                (item.action, item.target.clone(), item.requester),
+                expire_time,
+            );
+
+            AdminConfirmOutputResponse {
+                action: item.action,
+                target: item.target,
+                expire_time,
+            }
+        };
+
+        info!(
+            "admin confirm success! credential={}, {}",
+            credential_id, resp
+        );
+
+        // 只有ood可以写root_state，其它设备上只在内存里记录
+        let mut credential = credential;
+        credential.sign_count = sign_count;
+        if let Err(e) = self.update_sign_count(&credential).await {
+            warn!(
+                "save webauthn sign count failed! id={}, {}",
+                credential_id, e
+            );
+        }
+
+        Ok(resp)
+    }
+
+    // 执行敏感操作前调用，已确认的操作只能由申请确认的请求者执行一次
+    pub async fn check(
+        &self,
+        source: &RequestSourceInfo,
+        action: AdminConfirmAction,
+        target: &str,
+    ) -> BuckyResult<()> {
+        if self.list_credentials().await?.is_empty() {
+            return Ok(());
+        }
+
+        let now = bucky_time_now();
+        let mut state = self.0.state.lock().unwrap();
+        if state.take_confirmed(now, action, target, source.into()) {
+            info!(
+                "admin confirmed action will execute: action={}, target={}, source={}",
+                action, target, source
+            );
+            return Ok(());
+        }
+
+        let msg = format!(
+            "action need admin confirm with webauthn! action={}, target={}, source={}",
+            action, target, source
+        );
+        warn!("{}", msg);
+        Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
+    }
+
+    async fn list_credentials(&self) -> BuckyResult<Vec<WebAuthnCredential>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(CYFS_ADMIN_WEBAUTHN_PATH).await;
+        let _ = op_env.abort().await;
+
+        let items = match ret {
+            Ok(v) => v,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(vec![]),
+            Err(e) => {
+                error!("list webauthn credentials failed! {}", e);
+                return Err(e);
+            }
+        };
+
+        let mut list = vec![];
+        for item in items {
+            if let ObjectMapContentItem::Map((_, text_id)) = item {
+                list.push(self.load_text(&text_id).await?);
+            }
+        }
+
+        Ok(list)
+    }
+
+    async fn load_credential(
+        &self,
+        credential_id: &str,
+    ) -> BuckyResult<Option<(ObjectId, WebAuthnCredential)>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env
+            .get_by_key(CYFS_ADMIN_WEBAUTHN_PATH, credential_id)
+            .await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(v)) => v,
+            Ok(None) => return Ok(None),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(None),
+            Err(e) => {
+                error!(
+                    "load webauthn credential failed! id={}, {}",
+                    credential_id, e
+                );
+                return Err(e);
+            }
+        };
+
+        let credential = self.load_text(&text_id).await?;
+        Ok(Some((text_id, credential)))
+    }
+
+    async fn load_text(&self, text_id: &ObjectId) -> BuckyResult<WebAuthnCredential> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await.map_err(|e| {
+            error!(
+                "load webauthn credential text from noc failed! text={}, {}",
+                text_id, e
+            );
+            e
+        })?;
+
+        let text = Text::clone_from_slice(&resp.object.object_raw)?;
+        if text.id() != ADMIN_WEBAUTHN_TEXT_ID {
+            let msg = format!(
+                "invalid webauthn credential text id: expect={}, got={}",
+                ADMIN_WEBAUTHN_TEXT_ID,
+                text.id()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!(
+                "invalid webauthn credential text value! text={}, {}",
+                text_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn save_credential(&self, credential: &WebAuthnCredential) -> BuckyResult<ObjectId> {
+        let value = serde_json::to_string(credential).unwrap();
+        let text = Text::build(ADMIN_WEBAUTHN_TEXT_ID, &credential.credential_id, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save webauthn credential to noc failed! id={}, {}",
+                credential.credential_id, e
+            );
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn update_sign_count(&self, credential: &WebAuthnCredential) -> BuckyResult<()> {
+        let text_id = self.save_credential(credential).await?;
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env
+            .set_with_key(
+                CYFS_ADMIN_WEBAUTHN_PATH,
+                &credential.credential_id,
+                &text_id,
+                None,
+                false,
+            )
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!(
+                "remove old webauthn credential text from noc failed! text={}, {}",
+                text_id, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use p256::ecdsa::{signature::Signer, SigningKey};
+
+    fn sign_assertion(
+        key: &SigningKey,
+        rp_id: &str,
+        origin: &str,
+        flags: u8,
+        sign_count: u32,
+    ) -> WebAuthnAssertion {
+        let client_data = format!(
+            r#"{{"type":"webauthn.get","challenge":"Y2hhbGxlbmdl","origin":"{}"}}"#,
+            origin
+        );
+
+        let mut auth_data = sha2::Sha256::digest(rp_id.as_bytes()).to_vec();
+        auth_data.push(flags);
+        auth_data.extend_from_slice(&sign_count.to_be_bytes());
+
+        let mut msg = auth_data.clone();
+        msg.extend_from_slice(&sha2::Sha256::digest(client_data.as_bytes()));
+        let signature: Signature = key.sign(&msg);
+
+        WebAuthnAssertion {
+            credential_id: "AAECAwQFBgc".to_owned(),
+            client_data_json: encode_base64url(client_data.as_bytes()),
+            authenticator_data: encode_base64url(&auth_data),
+            signature: encode_base64url(signature.to_der().as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_confirmed_bind_requester() {
+        let device = DeviceId::default();
+        let requester = AdminConfirmRequester {
+            device: Some(device.clone()),
+            dec: cyfs_core::get_system_dec_app().to_owned(),
+        };
+        let other = AdminConfirmRequester {
+            device: Some(device),
+            dec: cyfs_core::get_anonymous_dec_app().to_owned(),
+        };
+
+        let mut state = AdminConfirmState::default();
+        state.confirmed.insert(
+            (
+                AdminConfirmAction::PurgeTrash,
+                "*".to_owned(),
+                requester.clone(),
+            ),
+            100,
+        );
+
+        // 其它dec和其它操作都不能使用这个确认
+        assert!(!state.take_confirmed(1, AdminConfirmAction::PurgeTrash, "*", other));
+        assert!(!state.take_confirmed(1, AdminConfirmAction::Restore, "*", requester.clone()));
+        assert!(state.take_confirmed(1, AdminConfirmAction::PurgeTrash, "*", requester.clone()));
+
+        // 只能使用一次
+        assert!(!state.take_confirmed(1, AdminConfirmAction::PurgeTrash, "*", requester.clone()));
+
+        // 过期之后不能使用
+        state.confirmed.insert(
+            (
+                AdminConfirmAction::PurgeTrash,
+                "*".to_owned(),
+                requester.clone(),
+            ),
+            100,
+        );
+        assert!(!state.take_confirmed(100, AdminConfirmAction::PurgeTrash, "*", requester));
+    }
+
+    #[test]
+    fn test_verify_assertion() {
+        let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let public_key = key.verifying_key().to_encoded_point(false);
+
+        let credential = WebAuthnCredential {
+            credential_id: "AAECAwQFBgc".to_owned(),
+            public_key: hex::encode(public_key.as_bytes()),
+            rp_id: "cyfs.com".to_owned(),
+            sign_count: 0,
+            register_time: 0,
+        };
+
+        let flags = AUTH_DATA_FLAG_UP | AUTH_DATA_FLAG_UV;
+        let assertion = sign_assertion(&key, "cyfs.com", "https://www.cyfs.com", flags, 3);
+        let (challenge, count) = credential.verify_assertion(&assertion).unwrap();
+        assert_eq!(challenge, "Y2hhbGxlbmdl");
+        assert_eq!(count, 3);
+
+        // 签名的内容和认证器数据不一致
+        let mut other = assertion.clone();
+        other.authenticator_data =
+            sign_assertion(&key, "cyfs.com", "https://cyfs.com", flags, 4).authenticator_data;
+        assert!(credential.verify_assertion(&other).is_err());
+
+        // rp id和origin不匹配
+        let assertion = sign_assertion(&key, "other.com", "https://www.cyfs.com", flags, 3);
+        assert!(credential.verify_assertion(&assertion).is_err());
+        let assertion = sign_assertion(&key, "cyfs.com", "https://evilcyfs.com", flags, 3);
+        assert!(credential.verify_assertion(&assertion).is_err());
+
+        // 没有通过用户验证
+        let assertion = sign_assertion(&key, "cyfs.com", "https://cyfs.com", AUTH_DATA_FLAG_UP, 3);
+        assert!(credential.verify_assertion(&assertion).is_err());
+
+        // 其它的密钥签名
+        let other_key = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let assertion = sign_assertion(&other_key, "cyfs.com", "https://cyfs.com", flags, 3);
+        assert!(credential.verify_assertion(&assertion).is_err());
+    }
+}
@@ -1,10 +1,13 @@
+use super::confirm::AdminConfirmManager;
 use crate::config::StackGlobalConfig;
 use crate::crypto_api::*;
 use crate::zone::ZoneRoleManager;
 use cyfs_base::*;
 use cyfs_lib::*;
+use cyfs_noc::*;
 use cyfs_util::*;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 struct OnAdminCommandWatcher {
@@ -52,6 +55,8 @@ pub struct AdminManager {
     role_manager: ZoneRoleManager,
     obj_verifier: Arc<ObjectVerifier>,
     config: StackGlobalConfig,
+    noc_checker: NamedObjectStorageChecker,
+    confirm: AdminConfirmManager,
 }
 
 impl AdminManager {
@@ -59,11 +64,15 @@ impl AdminManager {
         role_manager: ZoneRoleManager,
         obj_verifier: Arc<ObjectVerifier>,
         config: StackGlobalConfig,
+        noc_checker: NamedObjectStorageChecker,
+        confirm: AdminConfirmManager,
     ) -> Self {
         Self {
             role_manager,
             obj_verifier,
             config,
+            noc_checker,
+            confirm,
         }
     }
 
@@ -151,7 +160,7 @@ impl AdminManager {
             })?;
 
         let cmd = admin_object.into_command();
-        self.process_command(cmd).await
+        self.process_command(source, cmd).await
     }
 
     async fn verfiy_people_signs(
@@ -199,12 +208,67 @@ impl AdminManager {
         }
     }
 
-    async fn process_command(&self, cmd: AdminCommand) -> BuckyResult<()> {
+    async fn process_command(
+        &self,
+        source: &RequestSourceInfo,
+        cmd: AdminCommand,
+    ) -> BuckyResult<()> {
         match cmd {
             AdminCommand::GlobalStateAccessMode(access_mode) => {
                 self.process_access_mode(access_mode).await
             }
+            AdminCommand::NOCCheck(data) => self.process_noc_check(data).await,
+            AdminCommand::ReloadConfig(data) => self.process_reload_config(data).await,
+            AdminCommand::WebAuthnCredential(data) => {
+                self.process_webauthn_credential(source, data).await
+            }
+            AdminCommand::UnlockStorage(data) => self.process_unlock_storage(data).await,
+        }
+    }
+
+    async fn process_webauthn_credential(
+        &self,
+        source: &RequestSourceInfo,
+        data: AdminWebAuthnCredentialData,
+    ) -> BuckyResult<()> {
+        info!(
+            "admin will update webauthn credential: id={}, rp_id={}, remove={}",
+            data.credential_id, data.rp_id, data.remove
+        );
+
+        self.confirm.update_credential(source, &data).await
+    }
+
+    // 口令校验文件和noc等数据放在同一个目录下，只保存salt和校验数据
+    fn storage_check_file(&self) -> PathBuf {
+        let mut dir = get_cyfs_root_path().join("data");
+        if let Some(isolate) = &self.config.get_stack_params().config.isolate {
+            if !isolate.is_empty() {
+                dir.push(isolate);
+            }
         }
+
+        dir.join("storage-cipher.check")
+    }
+
+    async fn process_unlock_storage(&self, data: AdminUnlockStorageData) -> BuckyResult<()> {
+        let check_file = self.storage_check_file();
+        let cipher = async_std::task::spawn_blocking(move || {
+            StorageCipher::unlock(&data.passphrase, &check_file)
+        })
+        .await?;
+
+        info!("admin unlock local storage success");
+        init_storage_cipher(cipher);
+
+        Ok(())
+    }
+
+    async fn process_reload_config(&self, data: AdminReloadConfigData) -> BuckyResult<()> {
+        info!("admin will reload stack config: {}", data.config);
+
+        self.config.dynamic_config().reload(&data.config)?;
+        Ok(())
     }
 
     async fn process_access_mode(
@@ -214,4 +278,39 @@ impl AdminManager {
         self.config.change_access_mode(access_mode.category, access_mode.access_mode);
         Ok(())
     }
+
+    // 检查可能耗时很长，在后台执行，结果输出到日志
+    async fn process_noc_check(&self, data: AdminNOCCheckData) -> BuckyResult<()> {
+        let orphan_blob_action = match data.orphan_blob_action {
+            AdminNOCOrphanBlobAction::Keep => NamedObjectStorageOrphanBlobAction::Keep,
+            AdminNOCOrphanBlobAction::RebuildMeta => NamedObjectStorageOrphanBlobAction::RebuildMeta,
+            AdminNOCOrphanBlobAction::Quarantine => NamedObjectStorageOrphanBlobAction::Quarantine,
+        };
+
+        let option = NamedObjectStorageCheckOption {
+            remove_dangling_meta: data.remove_dangling_meta,
+            orphan_blob_action,
+            ops_per_sec: data.ops_per_sec,
+        };
+
+        let checker = self.noc_checker.clone();
+        async_std::task::spawn(async move {
+            match checker.check(&option).await {
+                Ok(ret) => {
+                    info!("admin noc check complete! {}", ret);
+                    for id in &ret.dangling_meta {
+                        warn!("noc dangling meta: {}", id);
+                    }
+                    for id in &ret.orphan_blob {
+                        warn!("noc orphan blob: {}", id);
+                    }
+                }
+                Err(e) => {
+                    error!("admin noc check failed! {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
 }
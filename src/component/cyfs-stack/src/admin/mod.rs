@@ -1,3 +1,9 @@
+mod confirm;
 mod manager;
+mod processor;
+mod transform;
 
-pub use manager::*;
\ No newline at end of file
+pub(crate) use confirm::*;
+pub use manager::*;
+pub(crate) use processor::*;
+pub(crate) use transform::*;
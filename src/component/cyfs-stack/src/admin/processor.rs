@@ -0,0 +1,19 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait AdminConfirmInputProcessor: Sync + Send + 'static {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse>;
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse>;
+}
+
+pub(crate) type AdminConfirmInputProcessorRef = Arc<dyn AdminConfirmInputProcessor>;
@@ -0,0 +1,79 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct AdminConfirmInputTransformer {
+    processor: AdminConfirmOutputProcessorRef,
+}
+
+impl AdminConfirmInputTransformer {
+    pub fn new(processor: AdminConfirmOutputProcessorRef) -> AdminConfirmInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        let out_req = AdminConfirmCreateChallengeOutputRequest {
+            common: Self::convert_common(req.common),
+            action: req.action,
+            target: req.target,
+        };
+
+        let out_resp = self
+            .processor
+            .create_admin_confirm_challenge(out_req)
+            .await?;
+        Ok(out_resp)
+    }
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        let out_req = AdminConfirmOutputRequest {
+            common: Self::convert_common(req.common),
+            assertion: req.assertion,
+        };
+
+        let out_resp = self.processor.admin_confirm(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminConfirmInputProcessor for AdminConfirmInputTransformer {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        Self::create_admin_confirm_challenge(&self, req).await
+    }
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        Self::admin_confirm(&self, req).await
+    }
+}
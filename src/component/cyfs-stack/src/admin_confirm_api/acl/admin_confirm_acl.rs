@@ -0,0 +1,62 @@
+use crate::admin::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct AdminConfirmAclInnerInputProcessor {
+    next: AdminConfirmInputProcessorRef,
+}
+
+impl AdminConfirmAclInnerInputProcessor {
+    pub(crate) fn new(next: AdminConfirmInputProcessorRef) -> AdminConfirmInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminConfirmInputProcessor for AdminConfirmAclInnerInputProcessor {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        self.check_local_zone_permit(
+            "admin_confirm.create_admin_confirm_challenge",
+            &req.common.source,
+        )?;
+
+        self.next.create_admin_confirm_challenge(req).await
+    }
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        self.check_local_zone_permit("admin_confirm.admin_confirm", &req.common.source)?;
+
+        self.next.admin_confirm(req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod admin_confirm_acl;
+
+pub(crate) use admin_confirm_acl::*;
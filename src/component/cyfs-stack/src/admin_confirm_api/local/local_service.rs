@@ -0,0 +1,57 @@
+use crate::admin::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalAdminConfirmService {
+    admin_confirm_manager: AdminConfirmManager,
+}
+
+impl LocalAdminConfirmService {
+    pub(crate) fn new(admin_confirm_manager: AdminConfirmManager) -> Self {
+        Self {
+            admin_confirm_manager,
+        }
+    }
+
+    pub fn clone_processor(&self) -> AdminConfirmInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        self.admin_confirm_manager
+            .create_challenge(&req.common.source, req.action, &req.target)
+            .await
+    }
+
+    pub async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        self.admin_confirm_manager
+            .confirm(&req.common.source, &req.assertion)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminConfirmInputProcessor for LocalAdminConfirmService {
+    async fn create_admin_confirm_challenge(
+        &self,
+        req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        Self::create_admin_confirm_challenge(self, req).await
+    }
+
+    async fn admin_confirm(
+        &self,
+        req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        Self::admin_confirm(self, req).await
+    }
+}
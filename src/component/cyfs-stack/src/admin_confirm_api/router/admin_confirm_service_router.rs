@@ -0,0 +1,118 @@
+use super::super::acl::AdminConfirmAclInnerInputProcessor;
+use crate::admin::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct AdminConfirmServiceRouter {
+    processor: AdminConfirmInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl AdminConfirmServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: AdminConfirmInputProcessorRef,
+    ) -> AdminConfirmInputProcessorRef {
+        // 限定同zone
+        let processor = AdminConfirmAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<AdminConfirmInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = AdminConfirmRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = AdminConfirmInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<AdminConfirmInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!(
+                "admin confirm target resolved: {:?} -> {}",
+                target, device_id
+            );
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AdminConfirmInputProcessor for AdminConfirmServiceRouter {
+    // 需要确认的操作都在ood上执行，确认也发到ood
+    async fn create_admin_confirm_challenge(
+        &self,
+        mut req: AdminConfirmCreateChallengeInputRequest,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.create_admin_confirm_challenge(req).await
+    }
+
+    async fn admin_confirm(
+        &self,
+        mut req: AdminConfirmInputRequest,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.admin_confirm(req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod admin_confirm_service_router;
+
+pub(crate) use admin_confirm_service_router::*;
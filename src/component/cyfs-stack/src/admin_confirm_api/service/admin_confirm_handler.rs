@@ -0,0 +1,105 @@
+use crate::admin::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct AdminConfirmRequestHandler {
+    processor: AdminConfirmInputProcessorRef,
+}
+
+impl AdminConfirmRequestHandler {
+    pub fn new(processor: AdminConfirmInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // create_admin_confirm_challenge
+    pub async fn process_create_admin_confirm_challenge_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_create_admin_confirm_challenge_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_create_admin_confirm_challenge_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<AdminConfirmCreateChallengeInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!(
+                "create admin confirm challenge failed, read body bytes error! {}",
+                e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = AdminConfirmCreateChallengeOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = AdminConfirmCreateChallengeInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            action: out_req.action,
+            target: out_req.target,
+        };
+        self.processor.create_admin_confirm_challenge(in_req).await
+    }
+
+    // admin_confirm
+    pub async fn process_admin_confirm_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_admin_confirm_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_admin_confirm_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<AdminConfirmInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("admin confirm failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = AdminConfirmOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = AdminConfirmInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            assertion: out_req.assertion,
+        };
+        self.processor.admin_confirm(in_req).await
+    }
+}
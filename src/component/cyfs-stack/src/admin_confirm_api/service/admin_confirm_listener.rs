@@ -0,0 +1,100 @@
+use super::admin_confirm_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum AdminConfirmRequestType {
+    CreateAdminConfirmChallenge,
+    AdminConfirm,
+}
+
+pub(crate) struct AdminConfirmRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: AdminConfirmRequestType,
+    handler: AdminConfirmRequestHandler,
+}
+
+impl AdminConfirmRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: AdminConfirmRequestType,
+        handler: AdminConfirmRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            AdminConfirmRequestType::CreateAdminConfirmChallenge => {
+                self.handler
+                    .process_create_admin_confirm_challenge_request(req)
+                    .await
+            }
+            AdminConfirmRequestType::AdminConfirm => {
+                self.handler.process_admin_confirm_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &AdminConfirmRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // admin confirm
+        server.at("/admin_confirm/challenge").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            AdminConfirmRequestType::CreateAdminConfirmChallenge,
+            handler.clone(),
+        ));
+
+        server.at("/admin_confirm/challenge/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            AdminConfirmRequestType::CreateAdminConfirmChallenge,
+            handler.clone(),
+        ));
+
+        server.at("/admin_confirm/confirm").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            AdminConfirmRequestType::AdminConfirm,
+            handler.clone(),
+        ));
+
+        server.at("/admin_confirm/confirm/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            AdminConfirmRequestType::AdminConfirm,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for AdminConfirmRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::local::LocalAdminConfirmService;
+use super::super::router::AdminConfirmServiceRouter;
+use crate::admin::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct AdminConfirmService {
+    router: AdminConfirmInputProcessorRef,
+}
+
+impl AdminConfirmService {
+    pub(crate) fn new(
+        admin_confirm_manager: AdminConfirmManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalAdminConfirmService::new(admin_confirm_manager);
+        let router =
+            AdminConfirmServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> AdminConfirmInputProcessorRef {
+        self.router.clone()
+    }
+}
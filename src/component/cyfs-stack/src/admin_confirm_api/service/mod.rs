@@ -0,0 +1,7 @@
+mod admin_confirm_handler;
+mod admin_confirm_listener;
+mod admin_confirm_service;
+
+pub(crate) use admin_confirm_handler::*;
+pub(crate) use admin_confirm_listener::*;
+pub(crate) use admin_confirm_service::*;
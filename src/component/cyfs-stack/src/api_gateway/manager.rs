@@ -0,0 +1,405 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::{Request, Response, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+// 每个dec在当前设备上最多注册的路由数
+const API_GATEWAY_MAX_ROUTES_PER_DEC: usize = 32;
+
+// 请求来源由网关填写，转发前先移除客户端携带的同名头和协议栈使用的身份头，
+// 避免dec服务或者本地协议栈信任客户端伪造的来源
+const API_GATEWAY_SOURCE_HEADERS: &[&str] = &[
+    CYFS_SOURCE_DEVICE,
+    CYFS_SOURCE_ZONE_CATEGORY,
+    CYFS_SOURCE_DEC_ID,
+    CYFS_SOURCE,
+    CYFS_DEC_ID,
+    CYFS_TARGET_DEC_ID,
+    CYFS_FILTER_DEC_ID,
+    CYFS_TARGET,
+    CYFS_REQ_PATH,
+    CYFS_REMOTE_DEVICE,
+    CYFS_REMOTE_VPORT,
+];
+
+// 协议栈和系统服务使用的本地端口，不允许作为dec的路由目标
+const API_GATEWAY_RESERVED_PORTS: &[u16] = &[
+    CHUNK_MANAGER_PORT,
+    FILE_MANAGER_PORT,
+    ACC_SERVICE_PORT,
+    GATEWAY_CONTROL_PORT,
+    NON_STACK_HTTP_PORT,
+    NON_STACK_WS_PORT,
+    OOD_DAEMON_CONTROL_PORT,
+    CYFS_RUNTIME_DAEMON_CONTROL_PORT,
+    CYFS_RUNTIME_NON_STACK_HTTP_PORT,
+    CYFS_RUNTIME_NON_STACK_WS_PORT,
+    OOD_INSTALLER_CONTROL_PORT,
+    OOD_DAEMON_LOCAL_STATUS_PORT,
+    OOD_BACKUP_TOOL_SERVICE_PORT,
+];
+
+// dec的api路由，front服务把/a/{dec}/api/*的请求反向代理到dec在当前设备上的本地端口
+// 路由只保存在内存里，dec启动后需要重新注册
+#[derive(Clone)]
+pub(crate) struct ApiGatewayManager {
+    routes: Arc<RwLock<HashMap<ObjectId, Vec<DecApiRoute>>>>,
+}
+
+impl ApiGatewayManager {
+    pub fn new() -> Self {
+        Self {
+            routes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    // 统一成以/开头并且不以/结尾的形式，根路由为/
+    fn fix_path(path: &str) -> BuckyResult<String> {
+        if !path.starts_with('/') || path.split('/').any(|seg| seg == "..") {
+            let msg = format!("invalid api route path: {}", path);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let path = path.trim_end_matches('/');
+        if path.is_empty() {
+            Ok("/".to_owned())
+        } else {
+            Ok(path.to_owned())
+        }
+    }
+
+    pub fn register(
+        &self,
+        source: &RequestSourceInfo,
+        path: &str,
+        port: u16,
+        strip_prefix: bool,
+        access: Option<AccessString>,
+    ) -> BuckyResult<DecApiRoute> {
+        // 只转发到127.0.0.1，所以只允许当前设备上的dec注册
+        let dec_id = match source.get_opt_dec() {
+            Some(dec_id) if source.is_current_device() => dec_id.to_owned(),
+            _ => {
+                let msg = format!(
+                    "register api route only allowed for dec on current device! source={}",
+                    source
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+            }
+        };
+
+        let path = Self::fix_path(path)?;
+        if port == 0 || API_GATEWAY_RESERVED_PORTS.contains(&port) {
+            let msg = format!(
+                "invalid api route port! dec={}, path={}, port={}",
+                dec_id, path, port
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let route = DecApiRoute {
+            dec_id: dec_id.clone(),
+            path,
+            port,
+            strip_prefix,
+            access: access.unwrap_or_else(AccessString::dec_default).value(),
+            register_time: bucky_time_now(),
+        };
+
+        let mut routes = self.routes.write().unwrap();
+
+        // 其它dec已经注册的端口是其它dec的服务，不能转发过去
+        if let Some(other) = routes
+            .iter()
+            .filter(|(id, _)| **id != dec_id)
+            .find_map(|(id, list)| list.iter().find(|item| item.port == port).map(|_| id))
+        {
+            let msg = format!(
+                "api route port already registered by other dec! dec={}, port={}, other={}",
+                dec_id, port, other
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+        }
+
+        let list = routes.entry(dec_id).or_insert_with(Vec::new);
+        match list.iter_mut().find(|item| item.path == route.path) {
+            Some(item) => *item = route.clone(),
+            None => {
+                if list.len() >= API_GATEWAY_MAX_ROUTES_PER_DEC {
+                    let msg = format!(
+                        "register api route but out of limit! dec={}, limit={}",
+                        route.dec_id, API_GATEWAY_MAX_ROUTES_PER_DEC
+                    );
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+                }
+
+                list.push(route.clone());
+            }
+        }
+
+        info!(
+            "register api route: dec={}, path={}, port={}, access={}",
+            route.dec_id,
+            route.path,
+            route.port,
+            AccessString::new(route.access)
+        );
+
+        Ok(route)
+    }
+
+    pub fn unregister(
+        &self,
+        source: &RequestSourceInfo,
+        path: Option<&str>,
+    ) -> BuckyResult<Vec<DecApiRoute>> {
+        let path = match path {
+            Some(path) => Some(Self::fix_path(path)?),
+            None => None,
+        };
+
+        let mut routes = self.routes.write().unwrap();
+        let list = match routes.get_mut(&source.dec) {
+            Some(list) => list,
+            None => return Ok(vec![]),
+        };
+
+        let removed = match &path {
+            Some(path) => {
+                let (removed, left): (Vec<_>, Vec<_>) =
+                    list.drain(..).partition(|item| item.path == *path);
+                *list = left;
+                removed
+            }
+            None => std::mem::take(list),
+        };
+
+        if list.is_empty() {
+            routes.remove(&source.dec);
+        }
+
+        info!(
+            "unregister api route: dec={}, path={:?}, removed={}",
+            source.dec,
+            path,
+            removed.len()
+        );
+
+        Ok(removed)
+    }
+
+    pub fn list(&self, dec_id: Option<&ObjectId>) -> Vec<DecApiRoute> {
+        let routes = self.routes.read().unwrap();
+        match dec_id {
+            Some(dec_id) => routes.get(dec_id).cloned().unwrap_or_default(),
+            None => routes.values().flatten().cloned().collect(),
+        }
+    }
+
+    // 按最长前缀匹配，返回路由和转发给dec服务的路径
+    fn select(&self, dec_id: &ObjectId, path: &str) -> Option<(DecApiRoute, String)> {
+        let routes = self.routes.read().unwrap();
+        routes
+            .get(dec_id)?
+            .iter()
+            .filter_map(|route| route.match_path(path).map(|target| (route, target)))
+            .max_by_key(|(route, _)| route.path.len())
+            .map(|(route, target)| (route.clone(), target))
+    }
+
+    fn prepare_request(
+        source: &RequestSourceInfo,
+        addr: &str,
+        target_path: &str,
+        req: &mut Request,
+    ) {
+        let mut url = Url::parse(&format!("http://{}", addr)).unwrap();
+        url.set_path(target_path);
+        url.set_query(req.url().query());
+        *req.url_mut() = url;
+
+        // host由转发的地址重新生成
+        req.remove_header(http_types::headers::HOST);
+        for name in API_GATEWAY_SOURCE_HEADERS {
+            req.remove_header(*name);
+        }
+
+        if let Some(device) = &source.zone.device {
+            req.insert_header(CYFS_SOURCE_DEVICE, device.to_string());
+        }
+        req.insert_header(
+            CYFS_SOURCE_ZONE_CATEGORY,
+            source.zone.zone_category.as_str(),
+        );
+        req.insert_header(CYFS_SOURCE_DEC_ID, source.dec.to_string());
+    }
+
+    pub async fn proxy(
+        &self,
+        source: &RequestSourceInfo,
+        dec_id: &ObjectId,
+        path: &str,
+        mut req: Request,
+    ) -> BuckyResult<Response> {
+        let (route, target_path) = self.select(dec_id, path).ok_or_else(|| {
+            let msg = format!("api route not found! dec={}, path={}", dec_id, path);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        let mask = source.mask(dec_id, AccessPermissions::CallOnly);
+        if route.access & mask != mask {
+            let msg = format!(
+                "api route reject by access! dec={}, route={}, source={}, access={}",
+                dec_id,
+                route.path,
+                source,
+                AccessString::new(route.access)
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let addr = format!("127.0.0.1:{}", route.port);
+        Self::prepare_request(source, &addr, &target_path, &mut req);
+
+        debug!(
+            "will proxy api request: dec={}, route={}, url={}, source={}",
+            dec_id,
+            route.path,
+            req.url(),
+            source
+        );
+
+        let requestor = TcpHttpRequestor::new(&addr);
+        requestor.request(req).await.map_err(|e| {
+            let msg = format!(
+                "proxy api request to dec service failed! dec={}, route={}, port={}, {}",
+                dec_id, route.path, route.port, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::ConnectFailed, msg)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::{DecApp, DecAppObj};
+
+    #[test]
+    fn test_select() {
+        let dec_id = cyfs_core::get_system_dec_app().to_owned();
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id.clone()));
+
+        let manager = ApiGatewayManager::new();
+        assert!(manager.register(&source, "v1", 8000, false, None).is_err());
+        assert!(manager.register(&source, "/", 0, false, None).is_err());
+
+        manager.register(&source, "/", 8000, false, None).unwrap();
+        manager.register(&source, "/v1/", 8001, true, None).unwrap();
+
+        let (route, path) = manager.select(&dec_id, "/v1/users").unwrap();
+        assert_eq!(route.port, 8001);
+        assert_eq!(path, "/users");
+
+        let (route, path) = manager.select(&dec_id, "/v1").unwrap();
+        assert_eq!(route.port, 8001);
+        assert_eq!(path, "/");
+
+        let (route, path) = manager.select(&dec_id, "/v10/users").unwrap();
+        assert_eq!(route.port, 8000);
+        assert_eq!(path, "/v10/users");
+
+        // 其它zone的设备不能注册
+        let other = RequestSourceInfo::new_other_zone_dec(Some(dec_id.clone()));
+        assert!(manager.register(&other, "/v2", 8002, false, None).is_err());
+
+        let removed = manager.unregister(&source, Some("/v1")).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(manager.list(Some(&dec_id)).len(), 1);
+
+        manager.unregister(&source, None).unwrap();
+        assert!(manager.select(&dec_id, "/v1/users").is_none());
+    }
+
+    #[test]
+    fn test_reserved_port() {
+        let dec_id = cyfs_core::get_system_dec_app().to_owned();
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id.clone()));
+
+        let manager = ApiGatewayManager::new();
+        for port in [
+            NON_STACK_HTTP_PORT,
+            CYFS_RUNTIME_NON_STACK_HTTP_PORT,
+            NON_STACK_WS_PORT,
+        ] {
+            let err = manager
+                .register(&source, "/", port, false, None)
+                .unwrap_err();
+            assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+        }
+
+        // 其它dec的服务端口
+        manager.register(&source, "/", 8000, false, None).unwrap();
+        let other_dec = DecApp::generate_id(ObjectId::default(), "test-api-gateway");
+        let other = RequestSourceInfo::new_local_dec(Some(other_dec));
+        let err = manager
+            .register(&other, "/", 8000, false, None)
+            .unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::AlreadyExists);
+        manager.register(&other, "/", 8001, false, None).unwrap();
+
+        // 同一个dec可以在多个路由上复用端口
+        manager.register(&source, "/v1", 8000, true, None).unwrap();
+    }
+
+    #[test]
+    fn test_strip_headers() {
+        let dec_id = cyfs_core::get_system_dec_app().to_owned();
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id.clone()));
+
+        let mut req = Request::new(
+            http_types::Method::Post,
+            Url::parse("http://127.0.0.1:1318/a/test/api/v1/users?page=1").unwrap(),
+        );
+        let fake = ObjectId::default().to_string();
+        for name in API_GATEWAY_SOURCE_HEADERS {
+            req.insert_header(*name, &fake);
+        }
+        req.insert_header("x-app-header", "value");
+
+        ApiGatewayManager::prepare_request(&source, "127.0.0.1:8000", "/users", &mut req);
+        assert_eq!(req.url().as_str(), "http://127.0.0.1:8000/users?page=1");
+
+        for name in [
+            CYFS_DEC_ID,
+            CYFS_TARGET,
+            CYFS_REQ_PATH,
+            CYFS_SOURCE,
+            CYFS_REMOTE_DEVICE,
+        ] {
+            assert!(req.header(name).is_none(), "header {} not removed", name);
+        }
+        assert_eq!(
+            req.header(CYFS_SOURCE_DEC_ID).unwrap().last().as_str(),
+            dec_id.to_string()
+        );
+        assert_eq!(
+            req.header(CYFS_SOURCE_ZONE_CATEGORY)
+                .unwrap()
+                .last()
+                .as_str(),
+            source.zone.zone_category.as_str()
+        );
+        assert_eq!(req.header("x-app-header").unwrap().last().as_str(), "value");
+    }
+}
@@ -0,0 +1,24 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ApiGatewayInputProcessor: Sync + Send + 'static {
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse>;
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse>;
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse>;
+}
+
+pub(crate) type ApiGatewayInputProcessorRef = Arc<dyn ApiGatewayInputProcessor>;
@@ -0,0 +1,98 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ApiGatewayInputTransformer {
+    processor: ApiGatewayOutputProcessorRef,
+}
+
+impl ApiGatewayInputTransformer {
+    pub fn new(processor: ApiGatewayOutputProcessorRef) -> ApiGatewayInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        let out_req = ApiGatewayRegisterRouteOutputRequest {
+            common: Self::convert_common(req.common),
+            path: req.path,
+            port: req.port,
+            strip_prefix: req.strip_prefix,
+            access: req.access,
+        };
+
+        let out_resp = self.processor.register_api_route(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        let out_req = ApiGatewayUnregisterRouteOutputRequest {
+            common: Self::convert_common(req.common),
+            path: req.path,
+        };
+
+        let out_resp = self.processor.unregister_api_route(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        let out_req = ApiGatewayGetRoutesOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+        };
+
+        let out_resp = self.processor.get_api_routes(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiGatewayInputProcessor for ApiGatewayInputTransformer {
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        Self::register_api_route(&self, req).await
+    }
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        Self::unregister_api_route(&self, req).await
+    }
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        Self::get_api_routes(&self, req).await
+    }
+}
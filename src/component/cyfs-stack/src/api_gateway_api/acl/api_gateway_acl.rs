@@ -0,0 +1,68 @@
+use crate::api_gateway::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ApiGatewayAclInnerInputProcessor {
+    next: ApiGatewayInputProcessorRef,
+}
+
+impl ApiGatewayAclInnerInputProcessor {
+    pub(crate) fn new(next: ApiGatewayInputProcessorRef) -> ApiGatewayInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiGatewayInputProcessor for ApiGatewayAclInnerInputProcessor {
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        self.check_local_zone_permit("api_gateway.register_api_route", &req.common.source)?;
+
+        self.next.register_api_route(req).await
+    }
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        self.check_local_zone_permit("api_gateway.unregister_api_route", &req.common.source)?;
+
+        self.next.unregister_api_route(req).await
+    }
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        self.check_local_zone_permit("api_gateway.get_api_routes", &req.common.source)?;
+
+        self.next.get_api_routes(req).await
+    }
+}
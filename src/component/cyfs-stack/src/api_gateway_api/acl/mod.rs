@@ -0,0 +1,3 @@
+mod api_gateway_acl;
+
+pub(crate) use api_gateway_acl::*;
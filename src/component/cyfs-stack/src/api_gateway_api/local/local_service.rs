@@ -0,0 +1,81 @@
+use crate::api_gateway::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalApiGatewayService {
+    api_gateway_manager: ApiGatewayManager,
+}
+
+impl LocalApiGatewayService {
+    pub(crate) fn new(api_gateway_manager: ApiGatewayManager) -> Self {
+        Self {
+            api_gateway_manager,
+        }
+    }
+
+    pub fn clone_processor(&self) -> ApiGatewayInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        let route = self.api_gateway_manager.register(
+            &req.common.source,
+            &req.path,
+            req.port,
+            req.strip_prefix,
+            req.access,
+        )?;
+
+        Ok(ApiGatewayRegisterRouteInputResponse { route })
+    }
+
+    pub async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        let list = self
+            .api_gateway_manager
+            .unregister(&req.common.source, req.path.as_deref())?;
+
+        Ok(ApiGatewayUnregisterRouteInputResponse { list })
+    }
+
+    pub async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        let list = self.api_gateway_manager.list(req.dec_id.as_ref());
+
+        Ok(ApiGatewayGetRoutesInputResponse { list })
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiGatewayInputProcessor for LocalApiGatewayService {
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        Self::register_api_route(self, req).await
+    }
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        Self::unregister_api_route(self, req).await
+    }
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        Self::get_api_routes(self, req).await
+    }
+}
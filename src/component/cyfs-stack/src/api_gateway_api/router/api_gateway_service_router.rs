@@ -0,0 +1,109 @@
+use super::super::acl::ApiGatewayAclInnerInputProcessor;
+use crate::api_gateway::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct ApiGatewayServiceRouter {
+    processor: ApiGatewayInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl ApiGatewayServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: ApiGatewayInputProcessorRef,
+    ) -> ApiGatewayInputProcessorRef {
+        // 限定同zone
+        let processor = ApiGatewayAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<ApiGatewayInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = ApiGatewayRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = ApiGatewayInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<ApiGatewayInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("api gateway target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiGatewayInputProcessor for ApiGatewayServiceRouter {
+    // api路由按设备区分，由路由指向的dec服务所在的设备处理
+    async fn register_api_route(
+        &self,
+        req: ApiGatewayRegisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.register_api_route(req).await
+    }
+
+    async fn unregister_api_route(
+        &self,
+        req: ApiGatewayUnregisterRouteInputRequest,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.unregister_api_route(req).await
+    }
+
+    async fn get_api_routes(
+        &self,
+        req: ApiGatewayGetRoutesInputRequest,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_api_routes(req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod api_gateway_service_router;
+
+pub(crate) use api_gateway_service_router::*;
@@ -0,0 +1,141 @@
+use crate::api_gateway::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ApiGatewayRequestHandler {
+    processor: ApiGatewayInputProcessorRef,
+}
+
+impl ApiGatewayRequestHandler {
+    pub fn new(processor: ApiGatewayInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // register_api_route
+    pub async fn process_register_api_route_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_register_api_route_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_register_api_route_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ApiGatewayRegisterRouteInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("register api route failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ApiGatewayRegisterRouteOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ApiGatewayRegisterRouteInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            path: out_req.path,
+            port: out_req.port,
+            strip_prefix: out_req.strip_prefix,
+            access: out_req.access,
+        };
+        self.processor.register_api_route(in_req).await
+    }
+
+    // unregister_api_route
+    pub async fn process_unregister_api_route_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_unregister_api_route_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_unregister_api_route_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ApiGatewayUnregisterRouteInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("unregister api route failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ApiGatewayUnregisterRouteOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ApiGatewayUnregisterRouteInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            path: out_req.path,
+        };
+        self.processor.unregister_api_route(in_req).await
+    }
+
+    // get_api_routes
+    pub async fn process_get_api_routes_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_api_routes_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_api_routes_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ApiGatewayGetRoutesInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get api routes failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ApiGatewayGetRoutesOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ApiGatewayGetRoutesInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+        };
+        self.processor.get_api_routes(in_req).await
+    }
+}
@@ -0,0 +1,115 @@
+use super::api_gateway_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ApiGatewayRequestType {
+    RegisterApiRoute,
+    UnregisterApiRoute,
+    GetApiRoutes,
+}
+
+pub(crate) struct ApiGatewayRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ApiGatewayRequestType,
+    handler: ApiGatewayRequestHandler,
+}
+
+impl ApiGatewayRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ApiGatewayRequestType,
+        handler: ApiGatewayRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ApiGatewayRequestType::RegisterApiRoute => {
+                self.handler.process_register_api_route_request(req).await
+            }
+            ApiGatewayRequestType::UnregisterApiRoute => {
+                self.handler.process_unregister_api_route_request(req).await
+            }
+            ApiGatewayRequestType::GetApiRoutes => {
+                self.handler.process_get_api_routes_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ApiGatewayRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        server.at("/api_gateway/register").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::RegisterApiRoute,
+            handler.clone(),
+        ));
+
+        server.at("/api_gateway/register/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::RegisterApiRoute,
+            handler.clone(),
+        ));
+
+        server.at("/api_gateway/unregister").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::UnregisterApiRoute,
+            handler.clone(),
+        ));
+
+        server.at("/api_gateway/routes").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::GetApiRoutes,
+            handler.clone(),
+        ));
+
+        server.at("/api_gateway/routes/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::GetApiRoutes,
+            handler.clone(),
+        ));
+
+        server.at("/api_gateway/unregister/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ApiGatewayRequestType::UnregisterApiRoute,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ApiGatewayRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
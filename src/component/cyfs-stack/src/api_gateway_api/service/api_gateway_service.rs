@@ -0,0 +1,27 @@
+use super::super::local::LocalApiGatewayService;
+use super::super::router::ApiGatewayServiceRouter;
+use crate::api_gateway::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ApiGatewayService {
+    router: ApiGatewayInputProcessorRef,
+}
+
+impl ApiGatewayService {
+    pub(crate) fn new(
+        api_gateway_manager: ApiGatewayManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalApiGatewayService::new(api_gateway_manager);
+        let router =
+            ApiGatewayServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ApiGatewayInputProcessorRef {
+        self.router.clone()
+    }
+}
@@ -0,0 +1,7 @@
+mod api_gateway_handler;
+mod api_gateway_listener;
+mod api_gateway_service;
+
+pub(crate) use api_gateway_handler::*;
+pub(crate) use api_gateway_listener::*;
+pub(crate) use api_gateway_service::*;
@@ -1,3 +1,5 @@
+use crate::config::{StackDynamicConfig, StackDynamicConfigApplier, StackFrontDynamicConfig};
+use super::dependency::AppDependenciesStatus;
 use crate::front::FrontARequestVersion;
 use cyfs_base::*;
 
@@ -11,28 +13,40 @@ struct AppVersionCacheKey {
 }
 
 pub struct AppCacheInner {
+    config: StackFrontDynamicConfig,
+
     name: LruCache<String, (ObjectId, u64)>,
     name_not_exists: LruCache<String, u64>,
 
     version: LruCache<AppVersionCacheKey, (Option<ObjectId>, u64)>,
+
+    // 依赖app的运行状态变化比较快，只做短时间缓存
+    dependencies: LruCache<ObjectId, AppDependenciesStatus>,
 }
 
 impl AppCacheInner {
-    pub fn new() -> Self {
+    pub fn new(config: StackFrontDynamicConfig) -> Self {
         Self {
             name: LruCache::with_expiry_duration_and_capacity(
                 std::time::Duration::from_secs(3600 * 24),
-                256,
+                config.app_name_cache_size,
             ),
             name_not_exists: LruCache::with_expiry_duration_and_capacity(
                 std::time::Duration::from_secs(60 * 10),
-                512,
+                config.app_name_not_exists_cache_size,
             ),
 
             version: LruCache::with_expiry_duration_and_capacity(
                 std::time::Duration::from_secs(60 * 10),
-                256,
+                config.app_version_cache_size,
+            ),
+
+            dependencies: LruCache::with_expiry_duration_and_capacity(
+                std::time::Duration::from_secs(10),
+                config.app_version_cache_size,
             ),
+
+            config,
         }
     }
 
@@ -115,14 +129,42 @@ impl AppCacheInner {
             }
         }
     }
+
+    fn get_dependencies(&mut self, dec_id: &ObjectId) -> Option<AppDependenciesStatus> {
+        let _ = self.dependencies.iter();
+
+        self.dependencies.peek(dec_id).cloned()
+    }
+
+    fn cache_dependencies(&mut self, status: AppDependenciesStatus) {
+        self.dependencies.insert(status.dec_id.clone(), status);
+    }
+
+    fn clear_dependencies(&mut self, dec_id: &ObjectId) {
+        self.dependencies.remove(dec_id);
+    }
 }
 
 #[derive(Clone)]
 pub struct AppCache(Arc<Mutex<AppCacheInner>>);
 
 impl AppCache {
-    pub fn new() -> Self {
-        Self(Arc::new(Mutex::new(AppCacheInner::new())))
+    pub fn new(config: StackFrontDynamicConfig) -> Self {
+        Self(Arc::new(Mutex::new(AppCacheInner::new(config))))
+    }
+
+    // LruCache不支持调整容量，大小变化后直接重建，之前的缓存会被清空
+    pub fn resize(&self, config: &StackFrontDynamicConfig) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.config == *config {
+            return;
+        }
+
+        info!(
+            "app cache size changed, now will rebuild! {:?} -> {:?}",
+            inner.config, config
+        );
+        *inner = AppCacheInner::new(config.clone());
     }
 
     pub fn get_app_by_name(&self, name: &str) -> Option<Option<ObjectId>> {
@@ -156,4 +198,23 @@ impl AppCache {
     pub fn clear_dir(&self, dec_id: &ObjectId, ver: &FrontARequestVersion) {
         self.0.lock().unwrap().clear_dir(dec_id, ver)
     }
+
+    pub fn get_dependencies(&self, dec_id: &ObjectId) -> Option<AppDependenciesStatus> {
+        self.0.lock().unwrap().get_dependencies(dec_id)
+    }
+
+    pub fn cache_dependencies(&self, status: AppDependenciesStatus) {
+        self.0.lock().unwrap().cache_dependencies(status)
+    }
+
+    pub fn clear_dependencies(&self, dec_id: &ObjectId) {
+        self.0.lock().unwrap().clear_dependencies(dec_id)
+    }
+}
+
+impl StackDynamicConfigApplier for AppCache {
+    fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()> {
+        self.resize(&config.front);
+        Ok(())
+    }
 }
@@ -0,0 +1,126 @@
+use cyfs_base::*;
+use cyfs_core::{AppLocalStatusCode, SemVerHelper};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppDependencyStatus {
+    pub dec_id: ObjectId,
+
+    // 哪个app声明的依赖，间接依赖时不是请求的app本身
+    pub required_by: ObjectId,
+    pub req_version: String,
+
+    // 没有安装时为None
+    pub version: Option<String>,
+    pub status: Option<AppLocalStatusCode>,
+
+    pub ready: bool,
+}
+
+impl AppDependencyStatus {
+    pub fn new(
+        dec_id: ObjectId,
+        required_by: ObjectId,
+        req_version: String,
+        version: Option<String>,
+        status: Option<AppLocalStatusCode>,
+    ) -> Self {
+        let ready = status == Some(AppLocalStatusCode::Running)
+            && Self::is_version_matched(version.as_deref(), &req_version);
+
+        Self {
+            dec_id,
+            required_by,
+            req_version,
+            version,
+            status,
+            ready,
+        }
+    }
+
+    fn is_version_matched(version: Option<&str>, req_version: &str) -> bool {
+        let req_version = req_version.trim();
+        if req_version.is_empty() || req_version == "*" {
+            return true;
+        }
+
+        let version = match version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        let req = match semver::VersionReq::parse(req_version) {
+            Ok(req) => req,
+            Err(e) => {
+                warn!(
+                    "invalid app dependency version requirement! req={}, {}",
+                    req_version, e
+                );
+                return false;
+            }
+        };
+
+        match semver::Version::parse(&SemVerHelper::fix_semver(version)) {
+            Ok(mut version) => {
+                version.pre = semver::Prerelease::EMPTY;
+                req.matches(&version)
+            }
+            Err(e) => {
+                warn!("invalid app version! version={}, {}", version, e);
+                false
+            }
+        }
+    }
+}
+
+// app所有依赖(包括间接依赖)的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct AppDependenciesStatus {
+    pub dec_id: ObjectId,
+    pub ready: bool,
+    pub dependencies: Vec<AppDependencyStatus>,
+}
+
+impl AppDependenciesStatus {
+    pub fn new(dec_id: ObjectId, dependencies: Vec<AppDependencyStatus>) -> Self {
+        let ready = dependencies.iter().all(|item| item.ready);
+        Self {
+            dec_id,
+            ready,
+            dependencies,
+        }
+    }
+
+    pub fn not_ready(&self) -> impl Iterator<Item = &AppDependencyStatus> {
+        self.dependencies.iter().filter(|item| !item.ready)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dependency_version() {
+        let dec_id = ObjectId::default();
+        let item = |version: Option<&str>, req: &str, status| {
+            AppDependencyStatus::new(
+                dec_id.clone(),
+                dec_id.clone(),
+                req.to_owned(),
+                version.map(|v| v.to_owned()),
+                status,
+            )
+            .ready
+        };
+
+        let running = Some(AppLocalStatusCode::Running);
+        assert!(item(Some("1.0.2"), "*", running));
+        assert!(item(Some("1.2.0.5"), "^1.2", running));
+        assert!(!item(Some("1.2.0.5"), ">=1.3", running));
+        assert!(!item(None, "^1.0", running));
+        assert!(!item(Some("1.0.2"), "*", Some(AppLocalStatusCode::Stop)));
+        assert!(!item(None, "*", None));
+    }
+}
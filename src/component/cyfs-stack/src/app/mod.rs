@@ -2,6 +2,10 @@ mod service;
 mod controller;
 mod state_storage;
 mod cache;
+mod pin;
+mod dependency;
 
 pub use service::*;
-pub(crate) use controller::*;
\ No newline at end of file
+pub use dependency::*;
+pub(crate) use controller::*;
+pub(crate) use pin::*;
\ No newline at end of file
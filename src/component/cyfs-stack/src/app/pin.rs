@@ -0,0 +1,535 @@
+use crate::ndn::NDNInputProcessorRef;
+use crate::ndn_api::DirLoader;
+use crate::non::NONInputProcessorRef;
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_bdt_ext::ChunkStoreReader;
+use cyfs_core::{DecApp, DecAppObj};
+use cyfs_lib::*;
+
+use async_std::io::ReadExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// 已经固定的web dir记录在local_cache: /app/pinned/{dec_id}/{version} -> dir_id
+const APP_PINNED_PATH: &str = "/app/pinned";
+
+struct AppWebDirChunks {
+    list: Vec<ChunkId>,
+    set: HashSet<ChunkId>,
+}
+
+impl AppWebDirChunks {
+    fn new() -> Self {
+        Self {
+            list: vec![],
+            set: HashSet::new(),
+        }
+    }
+
+    fn add(&mut self, chunk_id: &ChunkId) {
+        if self.set.insert(chunk_id.to_owned()) {
+            self.list.push(chunk_id.to_owned());
+        }
+    }
+}
+
+struct AppWebDirPinManagerInner {
+    non: NONInputProcessorRef,
+    ndn: NDNInputProcessorRef,
+    ndc: Box<dyn NamedDataCache>,
+    dir_loader: DirLoader,
+    root_state_stub: GlobalStateStub,
+    local_cache_stub: GlobalStateStub,
+}
+
+// app安装后预取web dir的所有chunk到ood，并在ndc里添加Pinned引用，避免首次打开页面时从远端拉取
+#[derive(Clone)]
+pub(crate) struct AppWebDirPinManager(Arc<AppWebDirPinManagerInner>);
+
+impl AppWebDirPinManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        ndn: NDNInputProcessorRef,
+        ndc: Box<dyn NamedDataCache>,
+        chunk_reader: ChunkStoreReader,
+        root_state: GlobalStateInputProcessorRef,
+        local_cache: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let info = zone_manager.get_current_info().await?;
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source.clone());
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            Some(info.zone_device_ood_id.object_id().clone()),
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let processor = GlobalStateOutputTransformer::new(local_cache, source);
+        let local_cache_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = AppWebDirPinManagerInner {
+            non,
+            ndn,
+            ndc,
+            dir_loader: DirLoader::new(chunk_reader),
+            root_state_stub,
+            local_cache_stub,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    fn pinned_path(dec_id: &ObjectId, version: &str) -> String {
+        format!("{}/{}/{}", APP_PINNED_PATH, dec_id, version)
+    }
+
+    pub async fn pin(
+        &self,
+        dec_id: &ObjectId,
+        version: &str,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        let dir_id = self.get_app_web_dir(dec_id, version).await?;
+        self.verify_dec_app(dec_id, version).await?;
+
+        let dir = self.load_dir(&dir_id).await?;
+
+        // dir自身的描述数据可能放在chunk里，需要先拉取到本地才能展开
+        let mut chunks = AppWebDirChunks::new();
+        Self::collect_dir_meta_chunks(&dir, &mut chunks);
+
+        let mut fetched_count = 0;
+        for chunk_id in &chunks.list {
+            if self.prefetch_chunk(&dir_id, chunk_id).await? {
+                fetched_count += 1;
+            }
+        }
+
+        let meta_count = chunks.list.len();
+        self.collect_file_chunks(&dir_id, &dir, &mut chunks).await?;
+
+        for chunk_id in &chunks.list[meta_count..] {
+            if self.prefetch_chunk(&dir_id, chunk_id).await? {
+                fetched_count += 1;
+            }
+        }
+
+        for chunk_id in &chunks.list {
+            self.update_chunk_ref(&dir_id, chunk_id, true).await?;
+        }
+
+        let path = Self::pinned_path(dec_id, version);
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        op_env.set_with_path(&path, &dir_id, None, true).await?;
+        op_env.commit().await?;
+
+        info!(
+            "pin app web dir success! dec={}, ver={}, dir={}, chunks={}, fetched={}",
+            dec_id,
+            version,
+            dir_id,
+            chunks.list.len(),
+            fetched_count
+        );
+
+        Ok(UtilPinAppWebDirInputResponse {
+            dir_id,
+            chunk_count: chunks.list.len() as u32,
+            fetched_count,
+        })
+    }
+
+    pub async fn unpin(
+        &self,
+        dec_id: &ObjectId,
+        version: &str,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        let path = Self::pinned_path(dec_id, version);
+
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_path(&path).await;
+        let _ = op_env.abort().await;
+
+        let dir_id = match ret? {
+            Some(id) => id,
+            None => {
+                let msg = format!(
+                    "app web dir not pinned! dec={}, ver={}, path={}",
+                    dec_id, version, path
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+            }
+        };
+
+        let dir = self.load_dir(&dir_id).await?;
+
+        let mut chunks = AppWebDirChunks::new();
+        Self::collect_dir_meta_chunks(&dir, &mut chunks);
+        self.collect_file_chunks(&dir_id, &dir, &mut chunks).await?;
+
+        for chunk_id in &chunks.list {
+            self.update_chunk_ref(&dir_id, chunk_id, false).await?;
+        }
+
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        op_env.remove_with_path(&path, Some(dir_id.clone())).await?;
+        op_env.commit().await?;
+
+        info!(
+            "unpin app web dir success! dec={}, ver={}, dir={}, chunks={}",
+            dec_id,
+            version,
+            dir_id,
+            chunks.list.len()
+        );
+
+        Ok(UtilUnpinAppWebDirInputResponse {
+            dir_id,
+            chunk_count: chunks.list.len() as u32,
+        })
+    }
+
+    // app-manager安装完成后记录的web dir: /app/{dec_id}/versions/{version}
+    async fn get_app_web_dir(&self, dec_id: &ObjectId, version: &str) -> BuckyResult<ObjectId> {
+        let path = format!("/app/{}/versions/{}", dec_id, version);
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_path(&path).await;
+        let _ = op_env.abort().await;
+
+        match ret? {
+            Some(dir_id) => Ok(dir_id),
+            None => {
+                let msg = format!(
+                    "app web dir not found! dec={}, ver={}, path={}",
+                    dec_id, version, path
+                );
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::NotFound, msg))
+            }
+        }
+    }
+
+    // 版本必须是DecApp对象里发布过的
+    async fn verify_dec_app(&self, dec_id: &ObjectId, version: &str) -> BuckyResult<()> {
+        let object = self.get_object(dec_id).await?;
+        let dec_app = DecApp::clone_from_slice(&object.object_raw)?;
+
+        dec_app.find_source(version).map_err(|e| {
+            let msg = format!(
+                "app version not exists in dec app object! dec={}, ver={}, {}",
+                dec_id, version, e
+            );
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::Unmatch, msg)
+        })?;
+
+        Ok(())
+    }
+
+    async fn load_dir(&self, dir_id: &ObjectId) -> BuckyResult<Dir> {
+        let object = self.get_object(dir_id).await?;
+        let dir = Dir::clone_from_slice(&object.object_raw)?;
+
+        let id = dir.desc().calculate_id();
+        if id != *dir_id {
+            let msg = format!("app web dir id unmatch! expect={}, got={}", dir_id, id);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        Ok(dir)
+    }
+
+    fn collect_dir_meta_chunks(dir: &Dir, chunks: &mut AppWebDirChunks) {
+        match dir.desc().content().obj_list() {
+            NDNObjectInfo::Chunk(id) => chunks.add(id),
+            NDNObjectInfo::ObjList(list) => {
+                if let Some(id) = &list.parent_chunk {
+                    chunks.add(id);
+                }
+            }
+        }
+
+        if let Some(body) = dir.body() {
+            if let DirBodyContent::Chunk(id) = body.content() {
+                chunks.add(id);
+            }
+        }
+    }
+
+    async fn collect_file_chunks(
+        &self,
+        dir_id: &ObjectId,
+        dir: &Dir,
+        chunks: &mut AppWebDirChunks,
+    ) -> BuckyResult<()> {
+        let (obj_list, body) = self.0.dir_loader.load_desc_and_body(dir_id, dir).await?;
+
+        if let Some(id) = &obj_list.parent_chunk {
+            chunks.add(id);
+        }
+
+        for (path, info) in obj_list.object_map.iter() {
+            match info.node() {
+                InnerNode::Chunk(id) => chunks.add(id),
+                InnerNode::IndexInParentChunk(_, _) => {}
+                InnerNode::ObjId(id) => {
+                    if id.obj_type_code() != ObjectTypeCode::File {
+                        warn!(
+                            "app web dir item is not file, now will ignore! dir={}, path={}, id={}",
+                            dir_id, path, id
+                        );
+                        continue;
+                    }
+
+                    let file = match body.as_ref().and_then(|body| body.get(id)) {
+                        Some(buf) => File::clone_from_slice(buf)?,
+                        None => {
+                            let object = self.get_object(id).await?;
+                            File::clone_from_slice(&object.object_raw)?
+                        }
+                    };
+
+                    let file_id = file.desc().calculate_id();
+                    if file_id != *id {
+                        let msg = format!(
+                            "app web dir file id unmatch! dir={}, path={}, expect={}, got={}",
+                            dir_id, path, id, file_id
+                        );
+                        error!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+                    }
+
+                    match file.body_expect("").content().inner_chunk_list() {
+                        Some(list) => {
+                            for chunk_id in list {
+                                chunks.add(chunk_id);
+                            }
+                        }
+                        None => {
+                            warn!(
+                                "app web dir file's chunk list not support! dir={}, path={}, file={}",
+                                dir_id, path, id
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // 本地已经存在返回false，从远端拉取返回true
+    async fn prefetch_chunk(&self, dir_id: &ObjectId, chunk_id: &ChunkId) -> BuckyResult<bool> {
+        let req = GetChunkRequest {
+            chunk_id: chunk_id.to_owned(),
+            flags: 0,
+        };
+        if let Some(data) = self.0.ndc.get_chunk(&req).await? {
+            if data.state == ChunkState::Ready {
+                return Ok(false);
+            }
+        }
+
+        let mut common = Self::new_ndn_common(NDNAPILevel::Router);
+        common.referer_object = vec![NDNDataRefererObject {
+            target: None,
+            object_id: dir_id.to_owned(),
+            inner_path: None,
+        }];
+
+        let req = NDNGetDataInputRequest {
+            common,
+            object_id: chunk_id.object_id(),
+            data_type: NDNDataType::Mem,
+            range: None,
+            inner_path: None,
+            context: None,
+            group: None,
+        };
+
+        let mut resp = self.0.ndn.get_data(req).await.map_err(|e| {
+            error!(
+                "prefetch app web dir chunk failed! dir={}, chunk={}, {}",
+                dir_id, chunk_id, e
+            );
+            e
+        })?;
+
+        let mut data = Vec::with_capacity(chunk_id.len());
+        resp.data.read_to_end(&mut data).await.map_err(|e| {
+            let msg = format!(
+                "read app web dir chunk failed! dir={}, chunk={}, {}",
+                dir_id, chunk_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let req = NDNPutDataInputRequest {
+            common: Self::new_ndn_common(NDNAPILevel::NDC),
+            object_id: chunk_id.object_id(),
+            data_type: NDNDataType::Mem,
+            length: data.len() as u64,
+            data: Box::new(async_std::io::Cursor::new(data)),
+        };
+        self.0.ndn.put_data(req).await.map_err(|e| {
+            error!(
+                "save app web dir chunk failed! dir={}, chunk={}, {}",
+                dir_id, chunk_id, e
+            );
+            e
+        })?;
+
+        debug!("prefetch app web dir chunk: dir={}, chunk={}", dir_id, chunk_id);
+
+        Ok(true)
+    }
+
+    async fn update_chunk_ref(
+        &self,
+        dir_id: &ObjectId,
+        chunk_id: &ChunkId,
+        pin: bool,
+    ) -> BuckyResult<()> {
+        let r = ChunkObjectRef {
+            object_id: dir_id.to_owned(),
+            relation: ChunkObjectRelation::Pinned,
+        };
+
+        let mut req = UpdateChunkRefsRequest {
+            chunk_id: chunk_id.to_owned(),
+            add_list: vec![],
+            remove_list: vec![],
+        };
+        if pin {
+            req.add_list.push(r);
+        } else {
+            req.remove_list.push(r);
+        }
+
+        self.0.ndc.update_chunk_ref_objects(&req).await.map_err(|e| {
+            error!(
+                "update app web dir chunk ref failed! dir={}, chunk={}, pin={}, {}",
+                dir_id, chunk_id, pin, e
+            );
+            e
+        })
+    }
+
+    async fn get_object(&self, object_id: &ObjectId) -> BuckyResult<NONObjectInfo> {
+        let req = NONGetObjectInputRequest {
+            common: NONInputRequestCommon {
+                req_path: None,
+                source: RequestSourceInfo::new_local_system(),
+                level: NONAPILevel::Router,
+                target: None,
+                flags: 0,
+                deadline: None,
+                request_id: None,
+            },
+            object_id: object_id.to_owned(),
+            inner_path: None,
+        };
+
+        let resp = self.0.non.get_object(req).await.map_err(|e| {
+            error!("get object for app web dir failed! id={}, {}", object_id, e);
+            e
+        })?;
+
+        Ok(resp.object)
+    }
+
+    fn new_ndn_common(level: NDNAPILevel) -> NDNInputRequestCommon {
+        NDNInputRequestCommon {
+            req_path: None,
+            source: RequestSourceInfo::new_local_system(),
+            level,
+            referer_object: vec![],
+            target: None,
+            flags: 0,
+            deadline: None,
+            user_data: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn chunk(data: &str) -> ChunkId {
+        ChunkId::calculate_sync(data.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_chunks_dedup() {
+        let mut chunks = AppWebDirChunks::new();
+        chunks.add(&chunk("a"));
+        chunks.add(&chunk("b"));
+        chunks.add(&chunk("a"));
+        chunks.add(&chunk("c"));
+        chunks.add(&chunk("b"));
+
+        // 去重之后保持第一次出现的顺序
+        assert_eq!(chunks.list, vec![chunk("a"), chunk("b"), chunk("c")]);
+    }
+
+    #[test]
+    fn test_pinned_path() {
+        let dec_id = ObjectId::default();
+        assert_eq!(
+            AppWebDirPinManager::pinned_path(&dec_id, "1.0.1"),
+            format!("/app/pinned/{}/1.0.1", dec_id)
+        );
+    }
+
+    #[test]
+    fn test_collect_dir_meta_chunks() {
+        let attr = Attributes::new(0);
+
+        // desc和body都内置，没有需要预取的chunk
+        let list = NDNObjectList::new(None);
+        let dir = Dir::new(attr.clone(), NDNObjectInfo::ObjList(list), HashMap::new())
+            .no_create_time()
+            .build();
+        let mut chunks = AppWebDirChunks::new();
+        AppWebDirPinManager::collect_dir_meta_chunks(&dir, &mut chunks);
+        assert!(chunks.list.is_empty());
+
+        // 打包小文件的parent chunk
+        let list = NDNObjectList::new(Some(chunk("parent")));
+        let dir = Dir::new(attr.clone(), NDNObjectInfo::ObjList(list), HashMap::new())
+            .no_create_time()
+            .build();
+        let mut chunks = AppWebDirChunks::new();
+        AppWebDirPinManager::collect_dir_meta_chunks(&dir, &mut chunks);
+        assert_eq!(chunks.list, vec![chunk("parent")]);
+
+        // desc的对象列表和body都放在chunk里
+        let dir = Dir::new_with_chunk_body(
+            attr,
+            NDNObjectInfo::Chunk(chunk("desc")),
+            chunk("body"),
+        )
+        .no_create_time()
+        .build();
+        let mut chunks = AppWebDirChunks::new();
+        AppWebDirPinManager::collect_dir_meta_chunks(&dir, &mut chunks);
+        assert_eq!(chunks.list, vec![chunk("desc"), chunk("body")]);
+    }
+}
@@ -1,10 +1,17 @@
 use super::cache::AppCache;
+use super::dependency::*;
+use crate::config::StackGlobalConfig;
 use crate::front::*;
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
 use crate::root_state::GlobalStateInputProcessorRef;
 use crate::root_state::GlobalStateOutputTransformer;
 use crate::ZoneManagerRef;
 use cyfs_base::*;
-use cyfs_lib::GlobalStateStub;
+use cyfs_core::*;
+use cyfs_lib::{GlobalStateStub, NONGetObjectOutputRequest};
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
 
 pub enum AppInstallStatus {
     Installed((ObjectId, ObjectId)),
@@ -15,17 +22,21 @@ pub enum AppInstallStatus {
 pub struct AppService {
     cache: AppCache,
     root_state_stub: GlobalStateStub,
+    non: NONInputProcessorRef,
 }
 
 impl AppService {
     pub async fn new(
         zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
         root_state: GlobalStateInputProcessorRef,
+        config: &StackGlobalConfig,
     ) -> BuckyResult<Self> {
         let info = zone_manager.get_current_info().await?;
         let source = zone_manager
             .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
             .await?;
+        let non = NONOutputTransformer::new(non, source.clone());
         let processor = GlobalStateOutputTransformer::new(root_state, source);
         let root_state_stub = GlobalStateStub::new(
             processor,
@@ -33,9 +44,15 @@ impl AppService {
             Some(cyfs_core::get_system_dec_app().to_owned()),
         );
 
+        let cache = AppCache::new(config.dynamic_config().front());
+        config
+            .dynamic_config()
+            .register_applier("front-app-cache", Arc::new(Box::new(cache.clone())));
+
         Ok(Self {
             root_state_stub,
-            cache: AppCache::new(),
+            cache,
+            non,
         })
     }
 
@@ -81,7 +98,123 @@ impl AppService {
         Ok(status)
     }
 
-    async fn get_app(&self, dec: &FrontARequestDec) -> BuckyResult<Option<ObjectId>> {
+    // 获取app声明的所有依赖(包括间接依赖)的安装和运行状态
+    pub async fn get_app_dependencies(
+        &self,
+        dec_id: &ObjectId,
+        flush_cache: bool,
+    ) -> BuckyResult<AppDependenciesStatus> {
+        if flush_cache {
+            self.cache.clear_dependencies(dec_id);
+        } else if let Some(cache) = self.cache.get_dependencies(dec_id) {
+            return Ok(cache);
+        }
+
+        let status = self.search_app_dependencies(dec_id).await?;
+        if !status.ready {
+            warn!(
+                "app dependencies not ready! dec={}, not ready={:?}",
+                dec_id,
+                status.not_ready().map(|v| &v.dec_id).collect::<Vec<_>>()
+            );
+        }
+
+        self.cache.cache_dependencies(status.clone());
+
+        Ok(status)
+    }
+
+    async fn search_app_dependencies(&self, dec_id: &ObjectId) -> BuckyResult<AppDependenciesStatus> {
+        let mut dependencies = vec![];
+
+        // 按层遍历依赖，同一个app被多个app依赖时每个要求都需要满足
+        let mut visited = HashSet::new();
+        visited.insert(dec_id.to_owned());
+        let mut pending = VecDeque::new();
+        pending.push_back(dec_id.to_owned());
+
+        while let Some(current) = pending.pop_front() {
+            let dec_app = match self.load_dec_app(&current).await? {
+                Some(dec_app) => dec_app,
+                None => continue,
+            };
+
+            let mut list: Vec<_> = dec_app.dependencies().iter().collect();
+            list.sort_by(|a, b| a.0.cmp(b.0));
+            for (dep_id, req_version) in list {
+                let (version, status) = match self.load_local_status(dep_id).await? {
+                    Some(local_status) => (
+                        local_status.version().map(|v| v.to_owned()),
+                        Some(local_status.status()),
+                    ),
+                    None => (None, None),
+                };
+
+                dependencies.push(AppDependencyStatus::new(
+                    dep_id.to_owned(),
+                    current.clone(),
+                    req_version.to_owned(),
+                    version,
+                    status,
+                ));
+
+                if visited.insert(dep_id.to_owned()) {
+                    pending.push_back(dep_id.to_owned());
+                }
+            }
+        }
+
+        Ok(AppDependenciesStatus::new(dec_id.to_owned(), dependencies))
+    }
+
+    async fn load_dec_app(&self, dec_id: &ObjectId) -> BuckyResult<Option<DecApp>> {
+        let req = NONGetObjectOutputRequest::new_noc(dec_id.clone(), None);
+        let resp = match self.non.get_object(req).await {
+            Ok(resp) => resp,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                warn!("load dec app from noc but not found! dec={}", dec_id);
+                return Ok(None);
+            }
+            Err(e) => {
+                error!("load dec app from noc failed! dec={}, {}", dec_id, e);
+                return Err(e);
+            }
+        };
+
+        let dec_app = DecApp::clone_from_slice(&resp.object.object_raw)?;
+        Ok(Some(dec_app))
+    }
+
+    async fn load_local_status(&self, dec_id: &ObjectId) -> BuckyResult<Option<AppLocalStatus>> {
+        let local_status_id = match self.search_local_status(dec_id).await? {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let req = NONGetObjectOutputRequest::new_noc(local_status_id.clone(), None);
+        let resp = match self.non.get_object(req).await {
+            Ok(resp) => resp,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                warn!(
+                    "load app local_status from noc but not found! dec={}, local_status={}",
+                    dec_id, local_status_id
+                );
+                return Ok(None);
+            }
+            Err(e) => {
+                error!(
+                    "load app local_status from noc failed! dec={}, local_status={}, {}",
+                    dec_id, local_status_id, e
+                );
+                return Err(e);
+            }
+        };
+
+        let local_status = AppLocalStatus::clone_from_slice(&resp.object.object_raw)?;
+        Ok(Some(local_status))
+    }
+
+    pub async fn get_app(&self, dec: &FrontARequestDec) -> BuckyResult<Option<ObjectId>> {
         let dec_id = match dec {
             FrontARequestDec::DecID(dec_id) => Some(dec_id.to_owned()),
             FrontARequestDec::Name(name) => self.get_app_by_name(name).await?,
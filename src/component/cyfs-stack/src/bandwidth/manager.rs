@@ -0,0 +1,211 @@
+use super::recorder::*;
+use super::storage::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_bdt::StackGuard;
+use cyfs_lib::*;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const BANDWIDTH_FLUSH_INTERVAL_SECS: u64 = 60;
+
+const MINUTE: u64 = 60 * 1000 * 1000;
+
+struct BandwidthManagerInner {
+    zone_manager: ZoneManagerRef,
+    bdt_stack: StackGuard,
+    recorder: BandwidthRecorder,
+    storage: BandwidthStorage,
+
+    // tunnel统计已经收集到的分钟(不含)，bdt的统计只在内存里，协议栈重启后从0开始
+    last_minute: Mutex<u64>,
+
+    // 定时落盘和查询时的落盘不能并发，否则tunnel统计可能被重复收集
+    flush_lock: async_std::sync::Mutex<()>,
+}
+
+// 流量统计，按方向、接口、对端zone和dec汇总到天，每个月保存为一个文件
+// bdt的流量来自tunnel统计，是设备上的全部流量；http和ndn的流量来自BandwidthRecorder，是其中可以归属到dec的部分
+#[derive(Clone)]
+pub(crate) struct BandwidthManager(Arc<BandwidthManagerInner>);
+
+impl BandwidthManager {
+    pub fn new(
+        isolate: &str,
+        zone_manager: &ZoneManagerRef,
+        bdt_stack: StackGuard,
+        recorder: BandwidthRecorder,
+    ) -> Self {
+        let inner = BandwidthManagerInner {
+            zone_manager: zone_manager.clone(),
+            bdt_stack,
+            recorder,
+            storage: BandwidthStorage::new(isolate),
+            last_minute: Mutex::new(0),
+            flush_lock: async_std::sync::Mutex::new(()),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run_flush().await;
+        });
+    }
+
+    async fn run_flush(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(BANDWIDTH_FLUSH_INTERVAL_SECS));
+        while let Some(_) = interval.next().await {
+            if let Err(e) = self.flush().await {
+                warn!("flush bandwidth stats failed! {}", e);
+            }
+        }
+    }
+
+    async fn flush(&self) -> BuckyResult<()> {
+        let _guard = self.0.flush_lock.lock().await;
+
+        let mut records = self.0.recorder.take();
+        self.collect_tunnel_stats(&mut records);
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut months: BTreeMap<String, Vec<BandwidthUsageItem>> = BTreeMap::new();
+        for (key, bytes) in records {
+            let peer_zone = match &key.peer {
+                Some(device_id) => self.peer_zone(device_id).await,
+                None => None,
+            };
+
+            let item = BandwidthUsageItem {
+                day: Some(format_day(key.day)),
+                direction: key.direction,
+                interface: key.interface,
+                peer_zone,
+                dec_id: key.dec_id,
+                bytes,
+            };
+
+            months
+                .entry(format_month(key.day))
+                .or_insert_with(Vec::new)
+                .push(item);
+        }
+
+        for (month, items) in months {
+            let mut list = vec![];
+            merge_items(&mut list, items);
+            self.0.storage.merge(&month, list).await?;
+        }
+
+        Ok(())
+    }
+
+    // 只收集已经结束的分钟，当前这一分钟留到下一次
+    fn collect_tunnel_stats(&self, records: &mut HashMap<BandwidthRecordKey, u64>) {
+        let current_minute = bucky_time_now() / MINUTE * MINUTE;
+        let since = {
+            let mut last_minute = self.0.last_minute.lock().unwrap();
+            std::mem::replace(&mut *last_minute, current_minute)
+        };
+
+        let mut add = |day, direction, peer: &DeviceId, bytes| {
+            if bytes == 0 {
+                return;
+            }
+
+            let key = BandwidthRecordKey {
+                day,
+                direction,
+                interface: BandwidthInterface::Bdt,
+                peer: Some(peer.to_owned()),
+                dec_id: None,
+            };
+            *records.entry(key).or_insert(0) += bytes;
+        };
+
+        for (remote, stats) in self.0.bdt_stack.tunnel_manager().all_stats() {
+            for sample in stats.history(Some(since)) {
+                if sample.minute >= current_minute {
+                    continue;
+                }
+
+                let day = day_of(sample.minute);
+                add(day, BandwidthDirection::Upload, &remote, sample.send_bytes);
+                add(
+                    day,
+                    BandwidthDirection::Download,
+                    &remote,
+                    sample.recv_bytes,
+                );
+            }
+        }
+    }
+
+    // 对端设备所在zone的owner，zone_manager内部有缓存
+    async fn peer_zone(&self, device_id: &DeviceId) -> Option<ObjectId> {
+        match self.0.zone_manager.get_zone(device_id, None).await {
+            Ok(zone) => Some(zone.owner().to_owned()),
+            Err(e) => {
+                warn!(
+                    "get zone of bandwidth peer failed! device={}, {}",
+                    device_id, e
+                );
+                None
+            }
+        }
+    }
+
+    pub async fn get_report(
+        &self,
+        month: Option<&str>,
+        dec_id: Option<&ObjectId>,
+        by_day: bool,
+    ) -> BuckyResult<BandwidthGetReportOutputResponse> {
+        let month = match month {
+            Some(month) => {
+                check_month(month)?;
+                month.to_owned()
+            }
+            None => format_month(day_of(bucky_time_now())),
+        };
+
+        // 先把内存里的统计落盘，查询结果包括到上一分钟为止的流量
+        if let Err(e) = self.flush().await {
+            warn!("flush bandwidth stats before report failed! {}", e);
+        }
+
+        let items: Vec<BandwidthUsageItem> = self
+            .0
+            .storage
+            .load(&month)
+            .await?
+            .into_iter()
+            .filter(|item| dec_id.is_none() || item.dec_id.as_ref() == dec_id)
+            .map(|mut item| {
+                if !by_day {
+                    item.day = None;
+                }
+                item
+            })
+            .collect();
+
+        let mut list = vec![];
+        merge_items(&mut list, items);
+        list.sort_by(|left, right| {
+            left.day
+                .cmp(&right.day)
+                .then_with(|| right.bytes.cmp(&left.bytes))
+        });
+
+        Ok(BandwidthGetReportOutputResponse { month, list })
+    }
+}
@@ -0,0 +1,11 @@
+mod manager;
+mod processor;
+mod recorder;
+mod storage;
+mod transform;
+
+pub(crate) use manager::*;
+pub(crate) use processor::*;
+pub(crate) use recorder::*;
+pub(crate) use storage::{day_of, format_day};
+pub(crate) use transform::*;
@@ -0,0 +1,14 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait BandwidthInputProcessor: Sync + Send + 'static {
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse>;
+}
+
+pub(crate) type BandwidthInputProcessorRef = Arc<dyn BandwidthInputProcessor>;
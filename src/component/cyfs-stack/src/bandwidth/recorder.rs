@@ -0,0 +1,54 @@
+use super::storage::day_of;
+use cyfs_base::*;
+use cyfs_lib::{BandwidthDirection, BandwidthInterface};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct BandwidthRecordKey {
+    // 距离unix epoch的天数(UTC)
+    pub day: u32,
+
+    pub direction: BandwidthDirection,
+    pub interface: BandwidthInterface,
+    pub peer: Option<DeviceId>,
+    pub dec_id: Option<ObjectId>,
+}
+
+// http和ndn接口上可以归属到dec的流量，先在内存里累加，由BandwidthManager定时取走落盘
+#[derive(Clone)]
+pub struct BandwidthRecorder(Arc<Mutex<HashMap<BandwidthRecordKey, u64>>>);
+
+impl BandwidthRecorder {
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(HashMap::new())))
+    }
+
+    pub fn record(
+        &self,
+        direction: BandwidthDirection,
+        interface: BandwidthInterface,
+        peer: Option<&DeviceId>,
+        dec_id: Option<&ObjectId>,
+        bytes: u64,
+    ) {
+        if bytes == 0 {
+            return;
+        }
+
+        let key = BandwidthRecordKey {
+            day: day_of(bucky_time_now()),
+            direction,
+            interface,
+            peer: peer.cloned(),
+            dec_id: dec_id.cloned(),
+        };
+
+        *self.0.lock().unwrap().entry(key).or_insert(0) += bytes;
+    }
+
+    pub(crate) fn take(&self) -> HashMap<BandwidthRecordKey, u64> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
@@ -0,0 +1,232 @@
+use cyfs_base::*;
+use cyfs_lib::{BandwidthDirection, BandwidthInterface, BandwidthUsageItem};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const DAY_SECS: u64 = 60 * 60 * 24;
+
+// bucky time所在的天，距离unix epoch的天数(UTC)
+pub(crate) fn day_of(time: u64) -> u32 {
+    (bucky_time_to_unix_time(time) / 1000 / 1000 / DAY_SECS) as u32
+}
+
+// 天数转换为(年, 月, 日)
+fn civil_from_days(days: u32) -> (u32, u32, u32) {
+    let z = days as u64 + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    (y as u32, m as u32, d as u32)
+}
+
+// YYYY-MM-DD
+pub(crate) fn format_day(days: u32) -> String {
+    let (y, m, d) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// YYYY-MM
+pub(crate) fn format_month(days: u32) -> String {
+    let (y, m, _) = civil_from_days(days);
+    format!("{:04}-{:02}", y, m)
+}
+
+pub(crate) fn check_month(month: &str) -> BuckyResult<()> {
+    let valid = month.len() == 7
+        && month.as_bytes()[4] == b'-'
+        && month[..4].parse::<u32>().is_ok()
+        && month[5..]
+            .parse::<u32>()
+            .map(|m| m >= 1 && m <= 12)
+            .unwrap_or(false);
+
+    if !valid {
+        let msg = format!(
+            "invalid bandwidth report month, should be YYYY-MM: {}",
+            month
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+    }
+
+    Ok(())
+}
+
+type BandwidthItemKey = (
+    Option<String>,
+    BandwidthDirection,
+    BandwidthInterface,
+    Option<ObjectId>,
+    Option<ObjectId>,
+);
+
+// 把items合并到list，维度相同的累加
+pub(crate) fn merge_items(list: &mut Vec<BandwidthUsageItem>, items: Vec<BandwidthUsageItem>) {
+    let mut index: HashMap<BandwidthItemKey, usize> = HashMap::new();
+    for (i, item) in list.iter().enumerate() {
+        index.insert(key_of(item), i);
+    }
+
+    for item in items {
+        match index.get(&key_of(&item)) {
+            Some(i) => list[*i].bytes += item.bytes,
+            None => {
+                index.insert(key_of(&item), list.len());
+                list.push(item);
+            }
+        }
+    }
+}
+
+fn key_of(item: &BandwidthUsageItem) -> BandwidthItemKey {
+    (
+        item.day.clone(),
+        item.direction,
+        item.interface,
+        item.peer_zone.clone(),
+        item.dec_id.clone(),
+    )
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct BandwidthMonthData {
+    list: Vec<BandwidthUsageItem>,
+}
+
+// 按月保存的流量统计，每个月一个文件，里面是按天汇总的记录
+// {cyfs_root}/data/{isolate}/bandwidth/{YYYY-MM}.json
+pub(crate) struct BandwidthStorage {
+    dir: PathBuf,
+}
+
+impl BandwidthStorage {
+    pub fn new(isolate: &str) -> Self {
+        let mut dir = cyfs_util::get_cyfs_root_path();
+        dir.push("data");
+        if isolate.len() > 0 {
+            dir.push(isolate);
+        }
+        dir.push("bandwidth");
+
+        Self { dir }
+    }
+
+    fn month_file(&self, month: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", month))
+    }
+
+    pub async fn load(&self, month: &str) -> BuckyResult<Vec<BandwidthUsageItem>> {
+        let file = self.month_file(month);
+        if !file.exists() {
+            return Ok(vec![]);
+        }
+
+        let value = async_std::fs::read_to_string(&file).await.map_err(|e| {
+            let msg = format!("load bandwidth stats error! file={}, {}", file.display(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let data: BandwidthMonthData = serde_json::from_str(&value).map_err(|e| {
+            let msg = format!(
+                "invalid bandwidth stats file! file={}, {}",
+                file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Ok(data.list)
+    }
+
+    // 累加到月文件里，先写临时文件再替换，避免写到一半时退出导致整个月的数据损坏
+    pub async fn merge(&self, month: &str, items: Vec<BandwidthUsageItem>) -> BuckyResult<()> {
+        let mut list = self.load(month).await?;
+        merge_items(&mut list, items);
+
+        if !self.dir.is_dir() {
+            if let Err(e) = async_std::fs::create_dir_all(&self.dir).await {
+                let msg = format!(
+                    "create bandwidth stats dir error! dir={}, {}",
+                    self.dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+            }
+        }
+
+        let file = self.month_file(month);
+        let tmp_file = self.dir.join(format!("{}.json.tmp", month));
+        let data = serde_json::to_string(&BandwidthMonthData { list }).unwrap();
+        async_std::fs::write(&tmp_file, &data)
+            .await
+            .and_then(|_| std::fs::rename(&tmp_file, &file))
+            .map_err(|e| {
+                let msg = format!(
+                    "write bandwidth stats file error! file={}, {}",
+                    file.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        debug!("save bandwidth stats success! file={}", file.display());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(11016), "2000-02-29");
+        assert_eq!(format_day(19782), "2024-02-29");
+        assert_eq!(format_month(19783), "2024-03");
+
+        assert!(check_month("2024-03").is_ok());
+        assert!(check_month("2024-13").is_err());
+        assert!(check_month("2024/03").is_err());
+        assert!(check_month("24-03").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let item = |day: &str, direction, bytes| BandwidthUsageItem {
+            day: Some(day.to_owned()),
+            direction,
+            interface: BandwidthInterface::Bdt,
+            peer_zone: None,
+            dec_id: None,
+            bytes,
+        };
+
+        let mut list = vec![item("2024-03-01", BandwidthDirection::Upload, 10)];
+        merge_items(
+            &mut list,
+            vec![
+                item("2024-03-01", BandwidthDirection::Upload, 5),
+                item("2024-03-01", BandwidthDirection::Download, 7),
+                item("2024-03-02", BandwidthDirection::Upload, 1),
+            ],
+        );
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].bytes, 15);
+        assert_eq!(list[1].bytes, 7);
+    }
+}
@@ -0,0 +1,57 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct BandwidthInputTransformer {
+    processor: BandwidthOutputProcessorRef,
+}
+
+impl BandwidthInputTransformer {
+    pub fn new(processor: BandwidthOutputProcessorRef) -> BandwidthInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        let out_req = BandwidthGetReportOutputRequest {
+            common: Self::convert_common(req.common),
+            month: req.month,
+            dec_id: req.dec_id,
+            by_day: req.by_day,
+        };
+
+        let out_resp = self.processor.get_bandwidth_report(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl BandwidthInputProcessor for BandwidthInputTransformer {
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        Self::get_bandwidth_report(&self, req).await
+    }
+}
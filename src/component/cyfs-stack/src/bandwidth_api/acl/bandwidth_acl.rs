@@ -0,0 +1,50 @@
+use crate::bandwidth::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct BandwidthAclInnerInputProcessor {
+    next: BandwidthInputProcessorRef,
+}
+
+impl BandwidthAclInnerInputProcessor {
+    pub(crate) fn new(next: BandwidthInputProcessorRef) -> BandwidthInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl BandwidthInputProcessor for BandwidthAclInnerInputProcessor {
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        self.check_local_zone_permit("bandwidth.get_bandwidth_report", &req.common.source)?;
+
+        self.next.get_bandwidth_report(req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod bandwidth_acl;
+
+pub(crate) use bandwidth_acl::*;
@@ -0,0 +1,39 @@
+use crate::bandwidth::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalBandwidthService {
+    bandwidth_manager: BandwidthManager,
+}
+
+impl LocalBandwidthService {
+    pub(crate) fn new(bandwidth_manager: BandwidthManager) -> Self {
+        Self { bandwidth_manager }
+    }
+
+    pub fn clone_processor(&self) -> BandwidthInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        self.bandwidth_manager
+            .get_report(req.month.as_deref(), req.dec_id.as_ref(), req.by_day)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl BandwidthInputProcessor for LocalBandwidthService {
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        Self::get_bandwidth_report(self, req).await
+    }
+}
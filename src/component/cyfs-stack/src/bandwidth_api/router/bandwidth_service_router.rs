@@ -0,0 +1,93 @@
+use super::super::acl::BandwidthAclInnerInputProcessor;
+use crate::bandwidth::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct BandwidthServiceRouter {
+    processor: BandwidthInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl BandwidthServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: BandwidthInputProcessorRef,
+    ) -> BandwidthInputProcessorRef {
+        // 限定同zone
+        let processor = BandwidthAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<BandwidthInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = BandwidthRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = BandwidthInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<BandwidthInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("bandwidth target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BandwidthInputProcessor for BandwidthServiceRouter {
+    // 流量按设备统计，查询zone内其它设备时需要指定target
+    async fn get_bandwidth_report(
+        &self,
+        req: BandwidthGetReportInputRequest,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_bandwidth_report(req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod bandwidth_service_router;
+
+pub(crate) use bandwidth_service_router::*;
@@ -0,0 +1,66 @@
+use crate::bandwidth::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct BandwidthRequestHandler {
+    processor: BandwidthInputProcessorRef,
+}
+
+impl BandwidthRequestHandler {
+    pub fn new(processor: BandwidthInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // get_bandwidth_report
+    pub async fn process_get_bandwidth_report_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_bandwidth_report_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_bandwidth_report_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<BandwidthGetReportInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get bandwidth report failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = BandwidthGetReportOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = BandwidthGetReportInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            month: out_req.month,
+            dec_id: out_req.dec_id,
+            by_day: out_req.by_day,
+        };
+        self.processor.get_bandwidth_report(in_req).await
+    }
+}
@@ -0,0 +1,79 @@
+use super::bandwidth_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum BandwidthRequestType {
+    GetBandwidthReport,
+}
+
+pub(crate) struct BandwidthRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: BandwidthRequestType,
+    handler: BandwidthRequestHandler,
+}
+
+impl BandwidthRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: BandwidthRequestType,
+        handler: BandwidthRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            BandwidthRequestType::GetBandwidthReport => {
+                self.handler.process_get_bandwidth_report_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &BandwidthRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        server.at("/bandwidth/report").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            BandwidthRequestType::GetBandwidthReport,
+            handler.clone(),
+        ));
+
+        server.at("/bandwidth/report/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            BandwidthRequestType::GetBandwidthReport,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for BandwidthRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::local::LocalBandwidthService;
+use super::super::router::BandwidthServiceRouter;
+use crate::bandwidth::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct BandwidthService {
+    router: BandwidthInputProcessorRef,
+}
+
+impl BandwidthService {
+    pub(crate) fn new(
+        bandwidth_manager: BandwidthManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalBandwidthService::new(bandwidth_manager);
+        let router =
+            BandwidthServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> BandwidthInputProcessorRef {
+        self.router.clone()
+    }
+}
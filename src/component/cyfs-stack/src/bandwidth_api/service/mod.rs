@@ -0,0 +1,7 @@
+mod bandwidth_handler;
+mod bandwidth_listener;
+mod bandwidth_service;
+
+pub(crate) use bandwidth_handler::*;
+pub(crate) use bandwidth_listener::*;
+pub(crate) use bandwidth_service::*;
@@ -0,0 +1,276 @@
+use crate::config::StackConcurrencyLimitConfig;
+use cyfs_base::*;
+use cyfs_lib::ConcurrencyLimiterStat;
+
+use async_std::channel::Sender;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+struct ConcurrencyLimiterState {
+    limit: StackConcurrencyLimitConfig,
+
+    running: u32,
+
+    // 按到达顺序排队的等待者，空出位置后直接转交给队首
+    queue: VecDeque<(u64, Sender<()>)>,
+    next_id: u64,
+
+    total: u64,
+    rejected: u64,
+    max_queued: u32,
+}
+
+impl ConcurrencyLimiterState {
+    fn has_capacity(&self) -> bool {
+        self.limit.max_concurrent == 0 || self.running < self.limit.max_concurrent
+    }
+
+    // 有空闲位置时按顺序唤醒排队的请求
+    fn dispatch(&mut self) {
+        while self.has_capacity() {
+            match self.queue.pop_front() {
+                Some((_, tx)) => {
+                    if tx.try_send(()).is_ok() {
+                        self.running += 1;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+struct ConcurrencyLimiterInner {
+    name: String,
+    state: Mutex<ConcurrencyLimiterState>,
+}
+
+// 带公平排队的信号量，超出并发上限的请求按先来先到排队，队列满了直接拒绝
+#[derive(Clone)]
+pub(crate) struct ConcurrencyLimiter(Arc<ConcurrencyLimiterInner>);
+
+impl ConcurrencyLimiter {
+    pub fn new(name: &str, limit: StackConcurrencyLimitConfig) -> Self {
+        let state = ConcurrencyLimiterState {
+            limit,
+            running: 0,
+            queue: VecDeque::new(),
+            next_id: 0,
+            total: 0,
+            rejected: 0,
+            max_queued: 0,
+        };
+
+        Self(Arc::new(ConcurrencyLimiterInner {
+            name: name.to_owned(),
+            state: Mutex::new(state),
+        }))
+    }
+
+    // 上限放宽后立即唤醒排队中的请求，收紧后已经在处理中的请求不受影响
+    pub fn set_limit(&self, limit: StackConcurrencyLimitConfig) {
+        let mut state = self.0.state.lock().unwrap();
+        if state.limit == limit {
+            return;
+        }
+
+        info!(
+            "concurrency limit changed: name={}, {:?} -> {:?}",
+            self.0.name, state.limit, limit
+        );
+        state.limit = limit;
+        state.dispatch();
+    }
+
+    pub async fn acquire(&self) -> BuckyResult<ConcurrencyPermit> {
+        let (id, rx) = {
+            let mut state = self.0.state.lock().unwrap();
+            state.total += 1;
+
+            // 已经有排队的请求时新请求也必须排队，保证先到先得
+            if state.has_capacity() && state.queue.is_empty() {
+                state.running += 1;
+                return Ok(ConcurrencyPermit {
+                    limiter: self.clone(),
+                });
+            }
+
+            let max_queue = state.limit.max_queue;
+            if max_queue > 0 && state.queue.len() >= max_queue as usize {
+                state.rejected += 1;
+                let msg = format!(
+                    "concurrency queue is full! name={}, running={}, queued={}",
+                    self.0.name,
+                    state.running,
+                    state.queue.len()
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            let id = state.next_id;
+            state.next_id += 1;
+
+            let (tx, rx) = async_std::channel::bounded(1);
+            state.queue.push_back((id, tx));
+
+            let queued = state.queue.len() as u32;
+            if queued > state.max_queued {
+                state.max_queued = queued;
+            }
+
+            (id, rx)
+        };
+
+        let mut waiter = ConcurrencyWaiter {
+            limiter: self.clone(),
+            id,
+            acquired: false,
+        };
+
+        // 发送端只会在转交位置时被移出队列，所以这里的结果不需要判断
+        let _ = rx.recv().await;
+        waiter.acquired = true;
+
+        Ok(ConcurrencyPermit {
+            limiter: self.clone(),
+        })
+    }
+
+    pub fn stat(&self) -> ConcurrencyLimiterStat {
+        let state = self.0.state.lock().unwrap();
+        ConcurrencyLimiterStat {
+            name: self.0.name.clone(),
+            max_concurrent: state.limit.max_concurrent,
+            max_queue: state.limit.max_queue,
+            running: state.running,
+            queued: state.queue.len() as u32,
+            total: state.total,
+            rejected: state.rejected,
+            max_queued: state.max_queued,
+        }
+    }
+
+    fn release(&self) {
+        let mut state = self.0.state.lock().unwrap();
+        state.running -= 1;
+        state.dispatch();
+    }
+
+    // 排队中的请求被取消
+    fn cancel(&self, id: u64) {
+        let mut state = self.0.state.lock().unwrap();
+        match state.queue.iter().position(|(v, _)| *v == id) {
+            Some(index) => {
+                state.queue.remove(index);
+            }
+            None => {
+                // 取消前已经被转交了位置，需要继续转交给下一个
+                state.running -= 1;
+                state.dispatch();
+            }
+        }
+    }
+}
+
+// 持有期间占用一个并发位置，drop后转交给下一个排队的请求
+pub(crate) struct ConcurrencyPermit {
+    limiter: ConcurrencyLimiter,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
+
+struct ConcurrencyWaiter {
+    limiter: ConcurrencyLimiter,
+    id: u64,
+    acquired: bool,
+}
+
+impl Drop for ConcurrencyWaiter {
+    fn drop(&mut self) {
+        if !self.acquired {
+            self.limiter.cancel(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn limit(max_concurrent: u32, max_queue: u32) -> StackConcurrencyLimitConfig {
+        StackConcurrencyLimitConfig {
+            max_concurrent,
+            max_queue,
+        }
+    }
+
+    async fn wait_queued(limiter: &ConcurrencyLimiter, count: u32) {
+        while limiter.stat().queued < count {
+            async_std::task::yield_now().await;
+        }
+    }
+
+    #[async_std::test]
+    async fn test_limiter() {
+        let limiter = ConcurrencyLimiter::new("test", limit(1, 2));
+
+        let permit = limiter.acquire().await.unwrap();
+        assert_eq!(limiter.stat().running, 1);
+
+        // 排队的请求按到达顺序获得位置
+        let order = Arc::new(Mutex::new(vec![]));
+        let mut tasks = vec![];
+        for i in 0..2 {
+            let limiter = limiter.clone();
+            let order = order.clone();
+            tasks.push(async_std::task::spawn(async move {
+                let _permit = limiter.acquire().await.unwrap();
+                order.lock().unwrap().push(i);
+            }));
+            wait_queued(&limiter, i + 1).await;
+        }
+
+        // 队列已满
+        let e = limiter.acquire().await.err().unwrap();
+        assert_eq!(e.code(), BuckyErrorCode::OutOfLimit);
+
+        drop(permit);
+        for task in tasks {
+            task.await;
+        }
+        assert_eq!(*order.lock().unwrap(), vec![0, 1]);
+
+        let stat = limiter.stat();
+        assert_eq!(stat.running, 0);
+        assert_eq!(stat.queued, 0);
+        assert_eq!(stat.total, 4);
+        assert_eq!(stat.rejected, 1);
+        assert_eq!(stat.max_queued, 2);
+
+        // 排队中被取消的请求不占用位置
+        let permit = limiter.acquire().await.unwrap();
+        let ret = async_std::future::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(ret.is_err());
+        assert_eq!(limiter.stat().queued, 0);
+
+        // 放宽上限后排队的请求立即获得位置
+        let task = {
+            let limiter = limiter.clone();
+            async_std::task::spawn(async move { limiter.acquire().await.unwrap() })
+        };
+        wait_queued(&limiter, 1).await;
+        limiter.set_limit(limit(0, 0));
+        let permit2 = task.await;
+        assert_eq!(limiter.stat().running, 2);
+
+        drop(permit);
+        drop(permit2);
+        assert_eq!(limiter.stat().running, 0);
+    }
+}
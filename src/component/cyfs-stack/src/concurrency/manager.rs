@@ -0,0 +1,87 @@
+use super::limiter::ConcurrencyLimiter;
+use super::processor::*;
+use crate::config::{StackDynamicConfig, StackDynamicConfigApplier, StackGlobalConfig};
+use crate::ndn::NDNInputProcessorRef;
+use crate::non::NONInputProcessorRef;
+use crate::root_state::{GlobalStateInputProcessorRef, OpEnvInputProcessorRef};
+use cyfs_base::*;
+use cyfs_lib::ConcurrencyLimiterStat;
+
+use std::sync::Arc;
+
+struct ConcurrencyManagerInner {
+    non_get: ConcurrencyLimiter,
+    non_put: ConcurrencyLimiter,
+    ndn_get: ConcurrencyLimiter,
+    root_state: ConcurrencyLimiter,
+}
+
+// 对外请求的并发限制，所有协议的listener共用同一组限制器，配置来自[concurrency]
+#[derive(Clone)]
+pub(crate) struct ConcurrencyManager(Arc<ConcurrencyManagerInner>);
+
+impl ConcurrencyManager {
+    pub fn new(config: &StackGlobalConfig) -> Self {
+        let current = config.dynamic_config().concurrency();
+        let inner = ConcurrencyManagerInner {
+            non_get: ConcurrencyLimiter::new("non_get", current.non_get),
+            non_put: ConcurrencyLimiter::new("non_put", current.non_put),
+            ndn_get: ConcurrencyLimiter::new("ndn_get", current.ndn_get),
+            root_state: ConcurrencyLimiter::new("root_state", current.root_state),
+        };
+
+        let ret = Self(Arc::new(inner));
+        config
+            .dynamic_config()
+            .register_applier("concurrency", Arc::new(Box::new(ret.clone())));
+
+        ret
+    }
+
+    pub fn wrap_non_processor(&self, processor: NONInputProcessorRef) -> NONInputProcessorRef {
+        NONConcurrencyLimitProcessor::new_raw(
+            processor,
+            self.0.non_get.clone(),
+            self.0.non_put.clone(),
+        )
+    }
+
+    pub fn wrap_ndn_processor(&self, processor: NDNInputProcessorRef) -> NDNInputProcessorRef {
+        NDNConcurrencyLimitProcessor::new_raw(processor, self.0.ndn_get.clone())
+    }
+
+    pub fn wrap_global_state_processor(
+        &self,
+        processor: GlobalStateInputProcessorRef,
+    ) -> GlobalStateInputProcessorRef {
+        GlobalStateConcurrencyLimitProcessor::new_raw(processor, self.0.root_state.clone())
+    }
+
+    pub fn wrap_op_env_processor(
+        &self,
+        processor: OpEnvInputProcessorRef,
+    ) -> OpEnvInputProcessorRef {
+        OpEnvConcurrencyLimitProcessor::new_raw(processor, self.0.root_state.clone())
+    }
+
+    pub fn stat(&self) -> Vec<ConcurrencyLimiterStat> {
+        vec![
+            self.0.non_get.stat(),
+            self.0.non_put.stat(),
+            self.0.ndn_get.stat(),
+            self.0.root_state.stat(),
+        ]
+    }
+}
+
+impl StackDynamicConfigApplier for ConcurrencyManager {
+    fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()> {
+        let concurrency = &config.concurrency;
+        self.0.non_get.set_limit(concurrency.non_get);
+        self.0.non_put.set_limit(concurrency.non_put);
+        self.0.ndn_get.set_limit(concurrency.ndn_get);
+        self.0.root_state.set_limit(concurrency.root_state);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod limiter;
+mod manager;
+mod processor;
+
+pub(crate) use manager::*;
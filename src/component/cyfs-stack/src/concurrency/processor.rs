@@ -0,0 +1,263 @@
+use super::limiter::ConcurrencyLimiter;
+use crate::ndn::*;
+use crate::non::*;
+use crate::root_state::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// non请求的get和put分别限制并发，其余请求直接转发
+pub(crate) struct NONConcurrencyLimitProcessor {
+    next: NONInputProcessorRef,
+    get: ConcurrencyLimiter,
+    put: ConcurrencyLimiter,
+}
+
+impl NONConcurrencyLimitProcessor {
+    pub fn new_raw(
+        next: NONInputProcessorRef,
+        get: ConcurrencyLimiter,
+        put: ConcurrencyLimiter,
+    ) -> NONInputProcessorRef {
+        let ret = Self { next, get, put };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl NONInputProcessor for NONConcurrencyLimitProcessor {
+    async fn put_object(
+        &self,
+        req: NONPutObjectInputRequest,
+    ) -> BuckyResult<NONPutObjectInputResponse> {
+        let _permit = self.put.acquire().await?;
+        self.next.put_object(req).await
+    }
+
+    async fn get_object(
+        &self,
+        req: NONGetObjectInputRequest,
+    ) -> BuckyResult<NONGetObjectInputResponse> {
+        let _permit = self.get.acquire().await?;
+        self.next.get_object(req).await
+    }
+
+    async fn post_object(
+        &self,
+        req: NONPostObjectInputRequest,
+    ) -> BuckyResult<NONPostObjectInputResponse> {
+        self.next.post_object(req).await
+    }
+
+    async fn select_object(
+        &self,
+        req: NONSelectObjectInputRequest,
+    ) -> BuckyResult<NONSelectObjectInputResponse> {
+        self.next.select_object(req).await
+    }
+
+    async fn delete_object(
+        &self,
+        req: NONDeleteObjectInputRequest,
+    ) -> BuckyResult<NONDeleteObjectInputResponse> {
+        self.next.delete_object(req).await
+    }
+}
+
+// ndn的get_data只限制到返回数据流为止，数据流的读取不占用位置
+pub(crate) struct NDNConcurrencyLimitProcessor {
+    next: NDNInputProcessorRef,
+    get: ConcurrencyLimiter,
+}
+
+impl NDNConcurrencyLimitProcessor {
+    pub fn new_raw(next: NDNInputProcessorRef, get: ConcurrencyLimiter) -> NDNInputProcessorRef {
+        let ret = Self { next, get };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl NDNInputProcessor for NDNConcurrencyLimitProcessor {
+    async fn put_data(&self, req: NDNPutDataInputRequest) -> BuckyResult<NDNPutDataInputResponse> {
+        self.next.put_data(req).await
+    }
+
+    async fn get_data(&self, req: NDNGetDataInputRequest) -> BuckyResult<NDNGetDataInputResponse> {
+        let _permit = self.get.acquire().await?;
+        self.next.get_data(req).await
+    }
+
+    async fn delete_data(
+        &self,
+        req: NDNDeleteDataInputRequest,
+    ) -> BuckyResult<NDNDeleteDataInputResponse> {
+        self.next.delete_data(req).await
+    }
+
+    async fn query_file(
+        &self,
+        req: NDNQueryFileInputRequest,
+    ) -> BuckyResult<NDNQueryFileInputResponse> {
+        self.next.query_file(req).await
+    }
+}
+
+// root_state只限制create_op_env和commit，op_env内部的读写操作都在内存里完成
+pub(crate) struct GlobalStateConcurrencyLimitProcessor {
+    next: GlobalStateInputProcessorRef,
+    limiter: ConcurrencyLimiter,
+}
+
+impl GlobalStateConcurrencyLimitProcessor {
+    pub fn new_raw(
+        next: GlobalStateInputProcessorRef,
+        limiter: ConcurrencyLimiter,
+    ) -> GlobalStateInputProcessorRef {
+        let ret = Self { next, limiter };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl GlobalStateInputProcessor for GlobalStateConcurrencyLimitProcessor {
+    fn create_op_env_processor(&self) -> OpEnvInputProcessorRef {
+        OpEnvConcurrencyLimitProcessor::new_raw(
+            self.next.create_op_env_processor(),
+            self.limiter.clone(),
+        )
+    }
+
+    fn get_category(&self) -> GlobalStateCategory {
+        self.next.get_category()
+    }
+
+    async fn get_current_root(
+        &self,
+        req: RootStateGetCurrentRootInputRequest,
+    ) -> BuckyResult<RootStateGetCurrentRootInputResponse> {
+        self.next.get_current_root(req).await
+    }
+
+    async fn create_op_env(
+        &self,
+        req: RootStateCreateOpEnvInputRequest,
+    ) -> BuckyResult<RootStateCreateOpEnvInputResponse> {
+        let _permit = self.limiter.acquire().await?;
+        self.next.create_op_env(req).await
+    }
+}
+
+pub(crate) struct OpEnvConcurrencyLimitProcessor {
+    next: OpEnvInputProcessorRef,
+    limiter: ConcurrencyLimiter,
+}
+
+impl OpEnvConcurrencyLimitProcessor {
+    pub fn new_raw(
+        next: OpEnvInputProcessorRef,
+        limiter: ConcurrencyLimiter,
+    ) -> OpEnvInputProcessorRef {
+        let ret = Self { next, limiter };
+        Arc::new(Box::new(ret))
+    }
+}
+
+#[async_trait::async_trait]
+impl OpEnvInputProcessor for OpEnvConcurrencyLimitProcessor {
+    fn get_category(&self) -> GlobalStateCategory {
+        self.next.get_category()
+    }
+
+    async fn load(&self, req: OpEnvLoadInputRequest) -> BuckyResult<()> {
+        self.next.load(req).await
+    }
+
+    async fn load_by_path(&self, req: OpEnvLoadByPathInputRequest) -> BuckyResult<()> {
+        self.next.load_by_path(req).await
+    }
+
+    async fn create_new(&self, req: OpEnvCreateNewInputRequest) -> BuckyResult<()> {
+        self.next.create_new(req).await
+    }
+
+    async fn get_current_root(
+        &self,
+        req: OpEnvGetCurrentRootInputRequest,
+    ) -> BuckyResult<OpEnvGetCurrentRootInputResponse> {
+        self.next.get_current_root(req).await
+    }
+
+    async fn lock(&self, req: OpEnvLockInputRequest) -> BuckyResult<()> {
+        self.next.lock(req).await
+    }
+
+    async fn commit(&self, req: OpEnvCommitInputRequest) -> BuckyResult<OpEnvCommitInputResponse> {
+        let _permit = self.limiter.acquire().await?;
+        self.next.commit(req).await
+    }
+
+    async fn abort(&self, req: OpEnvAbortInputRequest) -> BuckyResult<()> {
+        self.next.abort(req).await
+    }
+
+    async fn get_by_key(
+        &self,
+        req: OpEnvGetByKeyInputRequest,
+    ) -> BuckyResult<OpEnvGetByKeyInputResponse> {
+        self.next.get_by_key(req).await
+    }
+
+    async fn insert_with_key(&self, req: OpEnvInsertWithKeyInputRequest) -> BuckyResult<()> {
+        self.next.insert_with_key(req).await
+    }
+
+    async fn set_with_key(
+        &self,
+        req: OpEnvSetWithKeyInputRequest,
+    ) -> BuckyResult<OpEnvSetWithKeyInputResponse> {
+        self.next.set_with_key(req).await
+    }
+
+    async fn remove_with_key(
+        &self,
+        req: OpEnvRemoveWithKeyInputRequest,
+    ) -> BuckyResult<OpEnvRemoveWithKeyInputResponse> {
+        self.next.remove_with_key(req).await
+    }
+
+    async fn contains(
+        &self,
+        req: OpEnvContainsInputRequest,
+    ) -> BuckyResult<OpEnvContainsInputResponse> {
+        self.next.contains(req).await
+    }
+
+    async fn insert(&self, req: OpEnvInsertInputRequest) -> BuckyResult<OpEnvInsertInputResponse> {
+        self.next.insert(req).await
+    }
+
+    async fn remove(&self, req: OpEnvRemoveInputRequest) -> BuckyResult<OpEnvRemoveInputResponse> {
+        self.next.remove(req).await
+    }
+
+    async fn next(&self, req: OpEnvNextInputRequest) -> BuckyResult<OpEnvNextInputResponse> {
+        self.next.next(req).await
+    }
+
+    async fn reset(&self, req: OpEnvResetInputRequest) -> BuckyResult<()> {
+        self.next.reset(req).await
+    }
+
+    async fn list(&self, req: OpEnvListInputRequest) -> BuckyResult<OpEnvListInputResponse> {
+        self.next.list(req).await
+    }
+
+    async fn metadata(
+        &self,
+        req: OpEnvMetadataInputRequest,
+    ) -> BuckyResult<OpEnvMetadataInputResponse> {
+        self.next.metadata(req).await
+    }
+}
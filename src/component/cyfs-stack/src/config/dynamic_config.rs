@@ -0,0 +1,567 @@
+use cyfs_base::*;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::{Arc, Mutex, RwLock};
+
+// 可以在运行时重新加载的配置，只在当前进程周期内有效，重启后恢复默认值
+// 通过admin的ReloadConfig命令下发toml格式的配置片段，每个段整体替换，没有出现的段保持不变，比如
+// [bdt]
+// max_concurrent_interest = 64
+//
+// [sync]
+// retry_min_interval_secs = 5
+// retry_max_interval_secs = 120
+//
+// [local_cache]
+// dec_quota = 100000
+//
+// [ndn]
+// replica_read = true
+// replica_read_min_size = 4194304
+//
+// [cold]
+// enable = true
+// idle_days = 30
+//
+// [concurrency]
+// non_get = { max_concurrent = 64, max_queue = 1024 }
+// root_state = { max_concurrent = 16, max_queue = 256 }
+//
+// [access_stat]
+// sample_rate = 10
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackBdtDynamicConfig {
+    // 同时在处理中的ndn interest上限，超出后直接回复OutOfLimit，0表示不限制
+    pub max_concurrent_interest: u32,
+}
+
+impl Default for StackBdtDynamicConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_interest: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackAclDynamicConfig {
+    // 为空表示使用acl配置文件里面的值
+    pub read_bypass_ood: Option<bool>,
+    pub write_bypass_ood: Option<bool>,
+}
+
+impl Default for StackAclDynamicConfig {
+    fn default() -> Self {
+        Self {
+            read_bypass_ood: None,
+            write_bypass_ood: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackFrontDynamicConfig {
+    // front模块里面app相关的缓存大小
+    pub app_name_cache_size: usize,
+    pub app_name_not_exists_cache_size: usize,
+    pub app_version_cache_size: usize,
+}
+
+impl Default for StackFrontDynamicConfig {
+    fn default() -> Self {
+        Self {
+            app_name_cache_size: 256,
+            app_name_not_exists_cache_size: 512,
+            app_version_cache_size: 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackSyncDynamicConfig {
+    // sync失败后的重试间隔，每次翻倍直到最大值
+    pub retry_min_interval_secs: u64,
+    pub retry_max_interval_secs: u64,
+}
+
+impl Default for StackSyncDynamicConfig {
+    fn default() -> Self {
+        Self {
+            retry_min_interval_secs: 10,
+            retry_max_interval_secs: 60 * 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackLocalCacheDynamicConfig {
+    // 每个dec在local_cache里面可以保存的条目上限，超出后拒绝写入，0表示不限制
+    pub dec_quota: u64,
+
+    // dec根状态变化后，用量统计的最小刷新间隔
+    pub usage_refresh_interval_secs: u64,
+}
+
+impl Default for StackLocalCacheDynamicConfig {
+    fn default() -> Self {
+        Self {
+            dec_quota: 0,
+            usage_refresh_interval_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackNdnDynamicConfig {
+    // zone内多个设备持有同一个chunk时，是否按负载从其它设备读取
+    pub replica_read: bool,
+
+    // 只有不小于这个长度的chunk才会从其它设备读取
+    pub replica_read_min_size: u64,
+}
+
+impl Default for StackNdnDynamicConfig {
+    fn default() -> Self {
+        Self {
+            replica_read: true,
+            replica_read_min_size: 1024 * 1024 * 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackColdDynamicConfig {
+    // 是否把长时间没有访问的noc对象和chunk归档到冷存储
+    pub enable: bool,
+
+    // 最近访问时间超过多少天的才会归档
+    pub idle_days: u32,
+
+    pub scan_interval_secs: u64,
+
+    // 每轮noc对象和chunk分别最多归档的数量
+    pub batch_size: usize,
+
+    // 访问冷存储里的数据时水合的延迟预算，超出后返回Timeout，水合在后台继续
+    pub hydrate_budget_ms: u64,
+}
+
+impl Default for StackColdDynamicConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            idle_days: 30,
+            scan_interval_secs: 3600,
+            batch_size: 256,
+            hydrate_budget_ms: 5000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackConcurrencyLimitConfig {
+    // 同时处理中的请求上限，0表示不限制
+    pub max_concurrent: u32,
+
+    // 超出并发上限后按到达顺序排队，队列满了直接返回OutOfLimit，0表示不限制队列长度
+    pub max_queue: u32,
+}
+
+// 对外请求按类型分别限制并发，避免大量的对象读取请求把root_state的提交饿死
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackConcurrencyDynamicConfig {
+    pub non_get: StackConcurrencyLimitConfig,
+    pub non_put: StackConcurrencyLimitConfig,
+    pub ndn_get: StackConcurrencyLimitConfig,
+
+    // root_state的create_op_env和commit
+    pub root_state: StackConcurrencyLimitConfig,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StackAccessStatDynamicConfig {
+    // 是否统计对外接口上non/ndn读取的对象访问次数
+    pub enable: bool,
+
+    // 平均每sample_rate次访问记录一次，计数按sample_rate折算，1表示全部记录
+    pub sample_rate: u32,
+
+    // 按天保存的统计保留的天数
+    pub retention_days: u32,
+}
+
+impl Default for StackAccessStatDynamicConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            sample_rate: 1,
+            retention_days: 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StackDynamicConfig {
+    pub bdt: StackBdtDynamicConfig,
+    pub acl: StackAclDynamicConfig,
+    pub front: StackFrontDynamicConfig,
+    pub sync: StackSyncDynamicConfig,
+    pub local_cache: StackLocalCacheDynamicConfig,
+    pub ndn: StackNdnDynamicConfig,
+    pub cold: StackColdDynamicConfig,
+    pub concurrency: StackConcurrencyDynamicConfig,
+    pub access_stat: StackAccessStatDynamicConfig,
+}
+
+const FRONT_CACHE_MAX_SIZE: usize = 1024 * 64;
+const SYNC_RETRY_MAX_INTERVAL_LIMIT_SECS: u64 = 3600;
+const LOCAL_CACHE_USAGE_REFRESH_MAX_INTERVAL_SECS: u64 = 3600;
+const COLD_SCAN_MIN_INTERVAL_SECS: u64 = 60;
+const COLD_BATCH_MAX_SIZE: usize = 4096;
+const CONCURRENCY_MAX_QUEUE_LIMIT: u32 = 1024 * 64;
+const ACCESS_STAT_MAX_SAMPLE_RATE: u32 = 10000;
+const ACCESS_STAT_MAX_RETENTION_DAYS: u32 = 366;
+
+impl StackDynamicConfig {
+    fn parse_section<T: DeserializeOwned>(name: &str, value: toml::Value) -> BuckyResult<T> {
+        value.try_into().map_err(|e| {
+            let msg = format!("invalid stack config section! section={}, {}", name, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })
+    }
+
+    // 在当前配置的基础上，用value里面出现的段替换，返回新的配置和替换的段
+    pub fn merge(&self, value: &str) -> BuckyResult<(Self, Vec<String>)> {
+        let table: toml::value::Table = toml::from_str(value).map_err(|e| {
+            let msg = format!("invalid stack config! value={}, {}", value, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let mut config = self.clone();
+        let mut sections = vec![];
+        for (k, v) in table {
+            match k.as_str() {
+                "bdt" => config.bdt = Self::parse_section(&k, v)?,
+                "acl" => config.acl = Self::parse_section(&k, v)?,
+                "front" => config.front = Self::parse_section(&k, v)?,
+                "sync" => config.sync = Self::parse_section(&k, v)?,
+                "local_cache" => config.local_cache = Self::parse_section(&k, v)?,
+                "ndn" => config.ndn = Self::parse_section(&k, v)?,
+                "cold" => config.cold = Self::parse_section(&k, v)?,
+                "concurrency" => config.concurrency = Self::parse_section(&k, v)?,
+                "access_stat" => config.access_stat = Self::parse_section(&k, v)?,
+                _ => {
+                    let msg = format!("unknown or unsupported reload stack config section: {}", k);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+                }
+            }
+
+            sections.push(k);
+        }
+
+        if sections.is_empty() {
+            let msg = format!("stack config has no section to reload! value={}", value);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        config.check()?;
+
+        Ok((config, sections))
+    }
+
+    pub fn check(&self) -> BuckyResult<()> {
+        let front = &self.front;
+        for (name, size) in [
+            ("app_name_cache_size", front.app_name_cache_size),
+            ("app_name_not_exists_cache_size", front.app_name_not_exists_cache_size),
+            ("app_version_cache_size", front.app_version_cache_size),
+        ] {
+            if size == 0 || size > FRONT_CACHE_MAX_SIZE {
+                let msg = format!(
+                    "invalid stack config [front] {}: {}, should be in [1, {}]",
+                    name, size, FRONT_CACHE_MAX_SIZE
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        let sync = &self.sync;
+        if sync.retry_min_interval_secs == 0
+            || sync.retry_min_interval_secs > sync.retry_max_interval_secs
+            || sync.retry_max_interval_secs > SYNC_RETRY_MAX_INTERVAL_LIMIT_SECS
+        {
+            let msg = format!(
+                "invalid stack config [sync] retry interval: min={}, max={}, should be 0 < min <= max <= {}",
+                sync.retry_min_interval_secs,
+                sync.retry_max_interval_secs,
+                SYNC_RETRY_MAX_INTERVAL_LIMIT_SECS
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let local_cache = &self.local_cache;
+        if local_cache.usage_refresh_interval_secs > LOCAL_CACHE_USAGE_REFRESH_MAX_INTERVAL_SECS {
+            let msg = format!(
+                "invalid stack config [local_cache] usage_refresh_interval_secs: {}, should be <= {}",
+                local_cache.usage_refresh_interval_secs, LOCAL_CACHE_USAGE_REFRESH_MAX_INTERVAL_SECS
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let cold = &self.cold;
+        if cold.idle_days == 0
+            || cold.scan_interval_secs < COLD_SCAN_MIN_INTERVAL_SECS
+            || cold.batch_size == 0
+            || cold.batch_size > COLD_BATCH_MAX_SIZE
+            || cold.hydrate_budget_ms == 0
+        {
+            let msg = format!(
+                "invalid stack config [cold]: {:?}, should be idle_days > 0, scan_interval_secs >= {}, 0 < batch_size <= {}, hydrate_budget_ms > 0",
+                cold, COLD_SCAN_MIN_INTERVAL_SECS, COLD_BATCH_MAX_SIZE
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let concurrency = &self.concurrency;
+        for (name, limit) in [
+            ("non_get", &concurrency.non_get),
+            ("non_put", &concurrency.non_put),
+            ("ndn_get", &concurrency.ndn_get),
+            ("root_state", &concurrency.root_state),
+        ] {
+            if limit.max_queue > CONCURRENCY_MAX_QUEUE_LIMIT {
+                let msg = format!(
+                    "invalid stack config [concurrency] {}.max_queue: {}, should be <= {}",
+                    name, limit.max_queue, CONCURRENCY_MAX_QUEUE_LIMIT
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        let access_stat = &self.access_stat;
+        if access_stat.sample_rate == 0
+            || access_stat.sample_rate > ACCESS_STAT_MAX_SAMPLE_RATE
+            || access_stat.retention_days == 0
+            || access_stat.retention_days > ACCESS_STAT_MAX_RETENTION_DAYS
+        {
+            let msg = format!(
+                "invalid stack config [access_stat]: {:?}, should be 0 < sample_rate <= {}, 0 < retention_days <= {}",
+                access_stat, ACCESS_STAT_MAX_SAMPLE_RATE, ACCESS_STAT_MAX_RETENTION_DAYS
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+}
+
+// 配置变化后需要主动更新内部状态的模块，比如需要重建缓存
+// 其余模块使用时直接从StackDynamicConfigManager读取当前值即可
+pub trait StackDynamicConfigApplier: Send + Sync {
+    fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()>;
+}
+
+pub type StackDynamicConfigApplierRef = Arc<Box<dyn StackDynamicConfigApplier>>;
+
+pub struct StackDynamicConfigManager {
+    current: RwLock<StackDynamicConfig>,
+
+    // 同时也用来保证reload是串行的
+    appliers: Mutex<Vec<(String, StackDynamicConfigApplierRef)>>,
+}
+
+impl StackDynamicConfigManager {
+    pub fn new() -> Self {
+        Self {
+            current: RwLock::new(StackDynamicConfig::default()),
+            appliers: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn get(&self) -> StackDynamicConfig {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn bdt(&self) -> StackBdtDynamicConfig {
+        self.current.read().unwrap().bdt.clone()
+    }
+
+    pub fn acl(&self) -> StackAclDynamicConfig {
+        self.current.read().unwrap().acl.clone()
+    }
+
+    pub fn front(&self) -> StackFrontDynamicConfig {
+        self.current.read().unwrap().front.clone()
+    }
+
+    pub fn sync(&self) -> StackSyncDynamicConfig {
+        self.current.read().unwrap().sync.clone()
+    }
+
+    pub fn local_cache(&self) -> StackLocalCacheDynamicConfig {
+        self.current.read().unwrap().local_cache.clone()
+    }
+
+    pub fn ndn(&self) -> StackNdnDynamicConfig {
+        self.current.read().unwrap().ndn.clone()
+    }
+
+    pub fn cold(&self) -> StackColdDynamicConfig {
+        self.current.read().unwrap().cold.clone()
+    }
+
+    pub fn concurrency(&self) -> StackConcurrencyDynamicConfig {
+        self.current.read().unwrap().concurrency.clone()
+    }
+
+    pub fn access_stat(&self) -> StackAccessStatDynamicConfig {
+        self.current.read().unwrap().access_stat.clone()
+    }
+
+    // 注册后会立即用当前配置apply一次
+    pub fn register_applier(&self, name: &str, applier: StackDynamicConfigApplierRef) {
+        let mut appliers = self.appliers.lock().unwrap();
+        if let Err(e) = applier.apply(&self.current.read().unwrap()) {
+            error!(
+                "apply current stack config on register failed! applier={}, {}",
+                name, e
+            );
+        }
+
+        appliers.push((name.to_owned(), applier));
+    }
+
+    // 先校验，再依次apply，任何一个apply失败都会回滚到之前的配置
+    pub fn reload(&self, value: &str) -> BuckyResult<StackDynamicConfig> {
+        let appliers = self.appliers.lock().unwrap();
+
+        let old = self.get();
+        let (config, sections) = old.merge(value)?;
+        if config == old {
+            info!("reload stack config but not changed! sections={:?}", sections);
+            return Ok(config);
+        }
+
+        for (index, (name, applier)) in appliers.iter().enumerate() {
+            if let Err(e) = applier.apply(&config) {
+                error!(
+                    "apply stack config failed, now will rollback! applier={}, sections={:?}, {}",
+                    name, sections, e
+                );
+
+                for (name, applier) in appliers.iter().take(index + 1) {
+                    if let Err(e) = applier.apply(&old) {
+                        error!("rollback stack config failed! applier={}, {}", name, e);
+                    }
+                }
+
+                return Err(e);
+            }
+        }
+
+        *self.current.write().unwrap() = config.clone();
+
+        info!(
+            "reload stack config success! sections={:?}, {:?} -> {:?}",
+            sections, old, config
+        );
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct FailedApplier;
+
+    impl StackDynamicConfigApplier for FailedApplier {
+        fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()> {
+            if config.bdt.max_concurrent_interest == 1 {
+                return Err(BuckyError::from(BuckyErrorCode::Failed));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reload() {
+        let manager = StackDynamicConfigManager::new();
+        manager.register_applier("test", Arc::new(Box::new(FailedApplier)));
+
+        let config = manager
+            .reload("[front]\napp_name_cache_size = 1024\n\n[sync]\nretry_min_interval_secs = 5\n")
+            .unwrap();
+        assert_eq!(config.front.app_name_cache_size, 1024);
+        assert_eq!(config.front.app_version_cache_size, 256);
+        assert_eq!(config.sync.retry_min_interval_secs, 5);
+        assert_eq!(config.sync.retry_max_interval_secs, 60 * 5);
+
+        // 校验失败
+        assert!(manager.reload("[sync]\nretry_min_interval_secs = 0\n").is_err());
+        assert!(manager.reload("[front]\nunknown = 1\n").is_err());
+        assert!(manager.reload("[noc]\nx = 1\n").is_err());
+
+        // apply失败后回滚
+        assert!(manager.reload("[bdt]\nmax_concurrent_interest = 1\n").is_err());
+        assert_eq!(manager.get(), config);
+
+        manager.reload("[bdt]\nmax_concurrent_interest = 64\n").unwrap();
+        assert_eq!(manager.bdt().max_concurrent_interest, 64);
+        assert_eq!(manager.front().app_name_cache_size, 1024);
+
+        manager.reload("[local_cache]\ndec_quota = 1000\n").unwrap();
+        assert_eq!(manager.local_cache().dec_quota, 1000);
+        assert_eq!(manager.local_cache().usage_refresh_interval_secs, 10);
+        assert!(manager
+            .reload("[local_cache]\nusage_refresh_interval_secs = 7200\n")
+            .is_err());
+
+        manager.reload("[ndn]\nreplica_read = false\n").unwrap();
+        assert!(!manager.ndn().replica_read);
+        assert_eq!(manager.ndn().replica_read_min_size, 1024 * 1024 * 4);
+
+        manager.reload("[cold]\nenable = true\nidle_days = 7\n").unwrap();
+        assert!(manager.cold().enable);
+        assert_eq!(manager.cold().idle_days, 7);
+        assert_eq!(manager.cold().hydrate_budget_ms, 5000);
+        assert!(manager.reload("[cold]\nscan_interval_secs = 1\n").is_err());
+
+        manager
+            .reload("[concurrency]\nnon_get = { max_concurrent = 64, max_queue = 1024 }\n")
+            .unwrap();
+        assert_eq!(manager.concurrency().non_get.max_concurrent, 64);
+        assert_eq!(manager.concurrency().root_state.max_concurrent, 0);
+        assert!(manager
+            .reload("[concurrency]\nndn_get = { max_queue = 1000000 }\n")
+            .is_err());
+
+        manager.reload("[access_stat]\nsample_rate = 10\n").unwrap();
+        assert!(manager.access_stat().enable);
+        assert_eq!(manager.access_stat().sample_rate, 10);
+        assert!(manager.reload("[access_stat]\nsample_rate = 0\n").is_err());
+    }
+}
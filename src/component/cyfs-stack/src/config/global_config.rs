@@ -1,4 +1,6 @@
-use crate::stack::CyfsStackParams;
+use super::dynamic_config::StackDynamicConfigManager;
+use crate::bandwidth::BandwidthRecorder;
+use crate::stack::{CyfsStackParams, StackRequestGate};
 use cyfs_bdt_ext::BdtStackParams;
 use cyfs_lib::*;
 
@@ -13,6 +15,15 @@ pub struct StackGlobalConfigInner {
     // global state access mode
     root_state_access_mode: AtomicCell<GlobalStateAccessMode>,
     local_cache_access_mode: AtomicCell<GlobalStateAccessMode>,
+
+    // 可以运行时重新加载的配置
+    dynamic_config: StackDynamicConfigManager,
+
+    // 退出前的请求drain控制
+    request_gate: StackRequestGate,
+
+    // 可以归属到dec的流量统计
+    bandwidth_recorder: BandwidthRecorder,
 }
 
 impl StackGlobalConfigInner {
@@ -22,6 +33,9 @@ impl StackGlobalConfigInner {
             bdt_params,
             root_state_access_mode: AtomicCell::new(GlobalStateAccessMode::Read),
             local_cache_access_mode: AtomicCell::new(GlobalStateAccessMode::Write),
+            dynamic_config: StackDynamicConfigManager::new(),
+            request_gate: StackRequestGate::new(),
+            bandwidth_recorder: BandwidthRecorder::new(),
         }
     }
 
@@ -33,6 +47,18 @@ impl StackGlobalConfigInner {
         &self.bdt_params
     }
 
+    pub fn dynamic_config(&self) -> &StackDynamicConfigManager {
+        &self.dynamic_config
+    }
+
+    pub fn request_gate(&self) -> &StackRequestGate {
+        &self.request_gate
+    }
+
+    pub fn bandwidth_recorder(&self) -> &BandwidthRecorder {
+        &self.bandwidth_recorder
+    }
+
     pub fn get_access_mode(&self, category: GlobalStateCategory) -> GlobalStateAccessMode {
         let state = match category {
             GlobalStateCategory::RootState => &self.root_state_access_mode,
@@ -1,5 +1,7 @@
+mod dynamic_config;
 mod global_config;
 mod sn_config;
 
+pub use dynamic_config::*;
 pub use global_config::*;
 pub use sn_config::*;
@@ -302,6 +302,7 @@ impl SNConfigManager {
             context: None,
             last_access_rpath: None,
             access_string: None,
+            precondition: None,
         };
 
         if let Err(e) = self.noc.put_object(&put_req).await {
@@ -0,0 +1,902 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// 联系人保存在ood的root_state系统dec下: /user/contacts/{owner_id} -> text_id
+// Text对象id，header为对方的owner_id，value为ContactRecord的json
+const CONTACT_TEXT_ID: &str = "contact";
+
+// 握手用的Text对象id，header为动作，value为ContactHandshake的json
+const CONTACT_HANDSHAKE_TEXT_ID: &str = "contact_handshake";
+
+const HANDSHAKE_REQUEST: &str = "request";
+const HANDSHAKE_ACCEPT: &str = "accept";
+const HANDSHAKE_REJECT: &str = "reject";
+const HANDSHAKE_REMOVE: &str = "remove";
+
+const CONTACTS_HANDLER_ID: &str = "system_contacts_handshake";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ContactRecord {
+    info: ContactInfo,
+
+    // 本地发出的随机数，对方accept时必须原样带回
+    nonce: Option<String>,
+
+    // 对方请求里的随机数，本地accept时带回
+    peer_nonce: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ContactHandshake {
+    owner_id: ObjectId,
+    ood_id: DeviceId,
+
+    // 发送方owner对象的hex编码，接收方用来校验owner_id和zone
+    owner: String,
+
+    nonce: String,
+    reply_nonce: Option<String>,
+}
+
+struct OnContactHandshakeWatcher {
+    owner: ContactManager,
+}
+
+#[async_trait::async_trait]
+impl EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPostObjectResult>
+    for OnContactHandshakeWatcher
+{
+    async fn call(
+        &self,
+        param: &RouterHandlerPostObjectRequest,
+    ) -> BuckyResult<RouterHandlerPostObjectResult> {
+        debug!(
+            "recv contact handshake: {}, source={}",
+            param.request.object.object_id, param.request.common.source
+        );
+
+        let ret = self
+            .owner
+            .on_handshake(&param.request.common.source, &param.request.object)
+            .await
+            .map(|object| NONPostObjectInputResponse { object });
+
+        let resp = RouterHandlerPostObjectResult {
+            action: RouterHandlerAction::Response,
+            request: None,
+            response: Some(ret),
+        };
+
+        Ok(resp)
+    }
+}
+
+struct ContactManagerInner {
+    zone_manager: ZoneManagerRef,
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+}
+
+// 跨zone的联系人管理，只在ood上保存
+// 双方ood之间通过握手交换owner对象，并用随机数确认双方都持有对应zone的ood身份
+#[derive(Clone)]
+pub(crate) struct ContactManager(Arc<ContactManagerInner>);
+
+impl ContactManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = ContactManagerInner {
+            zone_manager: zone_manager.clone(),
+            non,
+            root_state_stub,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub async fn init(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if info.zone_role.is_ood_device() {
+            self.register_router_handler(router_handlers).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn register_router_handler(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let routine = OnContactHandshakeWatcher {
+            owner: self.clone(),
+        };
+
+        let req_path =
+            RequestGlobalStatePath::new_system_dec(Some(CYFS_SYSTEM_CONTACTS_VIRTUAL_PATH));
+        if let Err(e) = router_handlers
+            .post_object()
+            .add_handler(
+                RouterHandlerChain::Handler,
+                CONTACTS_HANDLER_ID,
+                1,
+                None,
+                Some(req_path.to_string()),
+                RouterHandlerAction::Default,
+                Some(Box::new(routine)),
+            )
+            .await
+        {
+            error!("add contacts post handler error! {}", e);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    pub async fn list(&self) -> BuckyResult<Vec<ContactInfo>> {
+        let list = self.load_all().await?;
+        Ok(list.into_iter().map(|record| record.info).collect())
+    }
+
+    pub async fn add(&self, card: ContactCard) -> BuckyResult<ContactInfo> {
+        let zone_info = self.0.zone_manager.get_current_info().await?;
+        if card.owner_id == zone_info.owner_id {
+            let msg = format!("can't add current zone owner as contact! owner={}", card.owner_id);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let now = bucky_time_now();
+        let mut record = match self.load(&card.owner_id).await? {
+            Some(record) => match record.info.status {
+                ContactStatus::Verified => return Ok(record.info),
+
+                // 对方已经发起过请求，直接确认
+                ContactStatus::Pending => return self.reply(&card.owner_id, true).await,
+                _ => record,
+            },
+            None => ContactRecord {
+                info: ContactInfo {
+                    owner_id: card.owner_id.clone(),
+                    ood_id: None,
+                    name: None,
+                    status: ContactStatus::Requesting,
+                    create_time: now,
+                    update_time: now,
+                },
+                nonce: None,
+                peer_nonce: None,
+            },
+        };
+
+        let ood_id = self.resolve_ood(&card).await?;
+        let handshake = self.build_handshake(None).await?;
+
+        if card.name.is_some() {
+            record.info.name = card.name;
+        }
+        record.info.ood_id = Some(ood_id.clone());
+        record.info.status = ContactStatus::Requesting;
+        record.info.update_time = now;
+        record.nonce = Some(handshake.nonce.clone());
+        record.peer_nonce = None;
+
+        // 先保存再发请求，避免对方的accept先于本地记录到达
+        self.save(&record).await?;
+
+        let resp = self
+            .post_handshake(&ood_id, HANDSHAKE_REQUEST, &handshake)
+            .await?;
+
+        // 双方同时添加对方时，对方会直接回复accept
+        if let Some(object) = resp {
+            let peer = self
+                .check_accept_reply(&card.owner_id, &handshake.nonce, &object)
+                .await?;
+
+            record.info.ood_id = Some(peer.ood_id);
+            record.info.status = ContactStatus::Verified;
+            record.info.update_time = bucky_time_now();
+            record.nonce = None;
+            self.save(&record).await?;
+
+            info!("contact verified on request! owner={}", card.owner_id);
+        }
+
+        Ok(record.info)
+    }
+
+    pub async fn reply(&self, owner_id: &ObjectId, accept: bool) -> BuckyResult<ContactInfo> {
+        let mut record = self.load(owner_id).await?.ok_or_else(|| {
+            let msg = format!("contact not found! owner={}", owner_id);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        if record.info.status != ContactStatus::Pending {
+            let msg = format!(
+                "contact is not pending! owner={}, status={:?}",
+                owner_id, record.info.status
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::ErrorState, msg));
+        }
+
+        let ood_id = record.info.ood_id.clone().ok_or_else(|| {
+            let msg = format!("contact ood is unknown! owner={}", owner_id);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::ErrorState, msg)
+        })?;
+
+        let handshake = self.build_handshake(record.peer_nonce.take()).await?;
+
+        if !accept {
+            record.info.status = ContactStatus::Rejected;
+            record.info.update_time = bucky_time_now();
+            self.save(&record).await?;
+
+            // 通知对方只是尽力而为
+            let _ = self
+                .post_handshake(&ood_id, HANDSHAKE_REJECT, &handshake)
+                .await;
+
+            return Ok(record.info);
+        }
+
+        let resp = self
+            .post_handshake(&ood_id, HANDSHAKE_ACCEPT, &handshake)
+            .await?;
+        let object = resp.ok_or_else(|| {
+            let msg = format!("contact accept got empty response! owner={}", owner_id);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        let peer = self
+            .check_accept_reply(owner_id, &handshake.nonce, &object)
+            .await?;
+
+        record.info.ood_id = Some(peer.ood_id);
+        record.info.status = ContactStatus::Verified;
+        record.info.update_time = bucky_time_now();
+        record.nonce = None;
+        self.save(&record).await?;
+
+        info!("contact verified on accept! owner={}", owner_id);
+
+        Ok(record.info)
+    }
+
+    pub async fn remove(&self, owner_id: &ObjectId) -> BuckyResult<Option<ContactInfo>> {
+        let record = match self.load(owner_id).await? {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+
+        self.delete(owner_id).await?;
+
+        match record.info.status {
+            ContactStatus::Verified | ContactStatus::Requesting => {
+                if let Some(ood_id) = &record.info.ood_id {
+                    match self.build_handshake(None).await {
+                        Ok(handshake) => {
+                            let _ = self
+                                .post_handshake(ood_id, HANDSHAKE_REMOVE, &handshake)
+                                .await;
+                        }
+                        Err(e) => {
+                            warn!("build contact remove handshake failed! owner={}, {}", owner_id, e);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(Some(record.info))
+    }
+
+    // 对方的ood优先取名片上的，没有的话从zone里面解析
+    async fn resolve_ood(&self, card: &ContactCard) -> BuckyResult<DeviceId> {
+        if let Some(ood_id) = &card.ood_id {
+            return Ok(ood_id.to_owned());
+        }
+
+        let zone = self
+            .0
+            .zone_manager
+            .get_zone_by_owner(&card.owner_id, None)
+            .await
+            .map_err(|e| {
+                error!("resolve contact zone failed! owner={}, {}", card.owner_id, e);
+                e
+            })?;
+
+        Ok(zone.ood().to_owned())
+    }
+
+    fn gen_nonce() -> String {
+        hex::encode(rand::random::<[u8; 16]>())
+    }
+
+    async fn build_handshake(&self, reply_nonce: Option<String>) -> BuckyResult<ContactHandshake> {
+        let zone_info = self.0.zone_manager.get_current_info().await?;
+
+        let req = NONGetObjectOutputRequest::new_router(None, zone_info.owner_id.clone(), None);
+        let resp = self.0.non.get_object(req).await.map_err(|e| {
+            error!(
+                "load current zone owner object failed! owner={}, {}",
+                zone_info.owner_id, e
+            );
+            e
+        })?;
+
+        Ok(ContactHandshake {
+            owner_id: zone_info.owner_id.clone(),
+            ood_id: self.0.zone_manager.get_current_device_id().to_owned(),
+            owner: hex::encode(&resp.object.object_raw),
+            nonce: Self::gen_nonce(),
+            reply_nonce,
+        })
+    }
+
+    fn encode_handshake(action: &str, handshake: &ContactHandshake) -> BuckyResult<NONObjectInfo> {
+        let value = serde_json::to_string(handshake).unwrap();
+        let text = Text::build(CONTACT_HANDSHAKE_TEXT_ID, action, value)
+            .owner(handshake.owner_id.clone())
+            .no_create_time()
+            .build();
+
+        Ok(NONObjectInfo::new(
+            text.desc().calculate_id(),
+            text.to_vec()?,
+            None,
+        ))
+    }
+
+    fn decode_handshake(object: &NONObjectInfo) -> BuckyResult<(String, ContactHandshake)> {
+        let text = Text::clone_from_slice(&object.object_raw).map_err(|e| {
+            let msg = format!(
+                "invalid contact handshake object buffer! id={}, {}",
+                object.object_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        if text.id() != CONTACT_HANDSHAKE_TEXT_ID {
+            let msg = format!("invalid contact handshake text id: {}", text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let handshake: ContactHandshake = serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!("invalid contact handshake value! action={}, {}", text.header(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        Ok((text.header().to_owned(), handshake))
+    }
+
+    async fn post_handshake(
+        &self,
+        ood_id: &DeviceId,
+        action: &str,
+        handshake: &ContactHandshake,
+    ) -> BuckyResult<Option<NONObjectInfo>> {
+        let object = Self::encode_handshake(action, handshake)?;
+        let mut req = NONPostObjectOutputRequest::new_router(
+            Some(ood_id.object_id().to_owned()),
+            object.object_id,
+            object.object_raw,
+        );
+        req.common.req_path = Some(CYFS_SYSTEM_CONTACTS_VIRTUAL_PATH.to_owned());
+
+        match self.0.non.post_object(req).await {
+            Ok(resp) => Ok(resp.object),
+            Err(e) if e.code() == BuckyErrorCode::Ok => Ok(None),
+            Err(e) => {
+                warn!(
+                    "post contact handshake failed! ood={}, action={}, {}",
+                    ood_id, action, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    // 校验对方的owner对象和ood，并把owner对象缓存到本地
+    async fn check_peer(&self, peer: &ContactHandshake) -> BuckyResult<()> {
+        let raw = hex::decode(&peer.owner).map_err(|e| {
+            let msg = format!("invalid contact owner object hex! owner={}, {}", peer.owner_id, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let owner = AnyNamedObject::clone_from_slice(&raw)?;
+        let id = owner.calculate_id();
+        if id != peer.owner_id {
+            let msg = format!(
+                "contact owner object not match! expect={}, got={}",
+                peer.owner_id, id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let zone = self
+            .0
+            .zone_manager
+            .get_zone_by_owner(&peer.owner_id, Some(owner))
+            .await?;
+        if !zone.is_ood(&peer.ood_id) {
+            let msg = format!(
+                "contact device is not ood of the owner's zone! owner={}, device={}",
+                peer.owner_id, peer.ood_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let req = NONPutObjectOutputRequest::new_noc(id, raw);
+        if let Err(e) = self.0.non.put_object(req).await {
+            warn!("save contact owner object to noc failed! owner={}, {}", peer.owner_id, e);
+        }
+
+        Ok(())
+    }
+
+    async fn check_accept_reply(
+        &self,
+        owner_id: &ObjectId,
+        nonce: &str,
+        object: &NONObjectInfo,
+    ) -> BuckyResult<ContactHandshake> {
+        let (action, peer) = Self::decode_handshake(object)?;
+        if action != HANDSHAKE_ACCEPT
+            || peer.owner_id != *owner_id
+            || peer.reply_nonce.as_deref() != Some(nonce)
+        {
+            let msg = format!(
+                "invalid contact accept reply! owner={}, action={}, reply owner={}",
+                owner_id, action, peer.owner_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        self.check_peer(&peer).await?;
+
+        Ok(peer)
+    }
+
+    async fn on_handshake(
+        &self,
+        source: &RequestSourceInfo,
+        object: &NONObjectInfo,
+    ) -> BuckyResult<Option<NONObjectInfo>> {
+        if source.is_current_zone() {
+            let msg = format!("contact handshake from current zone! source={}", source);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let (action, peer) = Self::decode_handshake(object)?;
+
+        // 握手只能由对方zone的ood自己发起
+        let matched = source.zone.zone.as_ref() == Some(&peer.owner_id)
+            && source.zone.device.as_ref() == Some(&peer.ood_id);
+        if !matched {
+            let msg = format!(
+                "contact handshake not match the source! source={}, owner={}, ood={}",
+                source, peer.owner_id, peer.ood_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.check_peer(&peer).await?;
+
+        info!(
+            "recv contact handshake: action={}, owner={}, ood={}",
+            action, peer.owner_id, peer.ood_id
+        );
+
+        match action.as_str() {
+            HANDSHAKE_REQUEST => self.on_request(peer).await,
+            HANDSHAKE_ACCEPT => self.on_accept(peer).await,
+            HANDSHAKE_REJECT => self.on_reject(peer).await.map(|_| None),
+            HANDSHAKE_REMOVE => self.on_remove(peer).await.map(|_| None),
+            _ => {
+                let msg = format!("unknown contact handshake action: {}", action);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::UnSupport, msg))
+            }
+        }
+    }
+
+    async fn on_request(&self, peer: ContactHandshake) -> BuckyResult<Option<NONObjectInfo>> {
+        let now = bucky_time_now();
+        let record = self.load(&peer.owner_id).await?;
+
+        match record {
+            // 双方同时发起，对方已经通过了来源校验，直接完成验证
+            Some(mut record) if record.info.status == ContactStatus::Requesting => {
+                record.info.ood_id = Some(peer.ood_id.clone());
+                record.info.status = ContactStatus::Verified;
+                record.info.update_time = now;
+                record.nonce = None;
+                self.save(&record).await?;
+
+                self.reply_accept(peer).await.map(Some)
+            }
+
+            // 对方可能丢失了记录，重新回复accept
+            Some(record) if record.info.status == ContactStatus::Verified => {
+                self.reply_accept(peer).await.map(Some)
+            }
+
+            Some(record) if record.info.status == ContactStatus::Rejected => {
+                let msg = format!("contact request already rejected! owner={}", peer.owner_id);
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
+            }
+
+            record => {
+                let mut record = record.unwrap_or_else(|| ContactRecord {
+                    info: ContactInfo {
+                        owner_id: peer.owner_id.clone(),
+                        ood_id: None,
+                        name: None,
+                        status: ContactStatus::Pending,
+                        create_time: now,
+                        update_time: now,
+                    },
+                    nonce: None,
+                    peer_nonce: None,
+                });
+
+                record.info.ood_id = Some(peer.ood_id);
+                record.info.status = ContactStatus::Pending;
+                record.info.update_time = now;
+                record.peer_nonce = Some(peer.nonce);
+                self.save(&record).await?;
+
+                Ok(None)
+            }
+        }
+    }
+
+    async fn reply_accept(&self, peer: ContactHandshake) -> BuckyResult<NONObjectInfo> {
+        let handshake = self.build_handshake(Some(peer.nonce)).await?;
+        Self::encode_handshake(HANDSHAKE_ACCEPT, &handshake)
+    }
+
+    async fn on_accept(&self, peer: ContactHandshake) -> BuckyResult<Option<NONObjectInfo>> {
+        let mut record = self.load(&peer.owner_id).await?.ok_or_else(|| {
+            let msg = format!("contact accept but request not found! owner={}", peer.owner_id);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        if record.info.status != ContactStatus::Requesting
+            || record.nonce.is_none()
+            || record.nonce != peer.reply_nonce
+        {
+            let msg = format!(
+                "contact accept not match the request! owner={}, status={:?}",
+                peer.owner_id, record.info.status
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        record.info.ood_id = Some(peer.ood_id.clone());
+        record.info.status = ContactStatus::Verified;
+        record.info.update_time = bucky_time_now();
+        record.nonce = None;
+        self.save(&record).await?;
+
+        info!("contact verified by peer accept! owner={}", peer.owner_id);
+
+        self.reply_accept(peer).await.map(Some)
+    }
+
+    async fn on_reject(&self, peer: ContactHandshake) -> BuckyResult<()> {
+        let record = match self.load(&peer.owner_id).await? {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+
+        if record.info.status != ContactStatus::Requesting || record.nonce != peer.reply_nonce {
+            warn!(
+                "contact reject not match the request, now will ignore! owner={}, status={:?}",
+                peer.owner_id, record.info.status
+            );
+            return Ok(());
+        }
+
+        info!("contact request rejected by peer! owner={}", peer.owner_id);
+        self.delete(&peer.owner_id).await
+    }
+
+    async fn on_remove(&self, peer: ContactHandshake) -> BuckyResult<()> {
+        if self.load(&peer.owner_id).await?.is_none() {
+            return Ok(());
+        }
+
+        info!("contact removed by peer! owner={}", peer.owner_id);
+        self.delete(&peer.owner_id).await
+    }
+
+    fn decode_record(text: &Text) -> BuckyResult<ContactRecord> {
+        if text.id() != CONTACT_TEXT_ID {
+            let msg = format!("invalid contact text id: {}", text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!("invalid contact value! owner={}, {}", text.header(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn load_record(&self, text_id: &ObjectId) -> BuckyResult<ContactRecord> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        let text = Text::clone_from_slice(&resp.object.object_raw)?;
+        Self::decode_record(&text)
+    }
+
+    async fn load(&self, owner_id: &ObjectId) -> BuckyResult<Option<ContactRecord>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env
+            .get_by_key(CYFS_CONTACTS_PATH, owner_id.to_string())
+            .await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(text_id)) => text_id,
+            Ok(None) => return Ok(None),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(None),
+            Err(e) => {
+                error!("load contact index failed! owner={}, {}", owner_id, e);
+                return Err(e);
+            }
+        };
+
+        self.load_record(&text_id).await.map(Some)
+    }
+
+    async fn load_all(&self) -> BuckyResult<Vec<ContactRecord>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(CYFS_CONTACTS_PATH).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list contacts failed! {}", e);
+                return Err(e);
+            }
+        };
+
+        let mut result = Vec::with_capacity(list.len());
+        for item in list {
+            let (owner, text_id) = match item {
+                ObjectMapContentItem::Map(v) => v,
+                _ => continue,
+            };
+
+            match self.load_record(&text_id).await {
+                Ok(record) => result.push(record),
+                Err(e) => {
+                    warn!("load contact failed! owner={}, text={}, {}", owner, text_id, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn save(&self, record: &ContactRecord) -> BuckyResult<()> {
+        let owner_id = &record.info.owner_id;
+        let zone_info = self.0.zone_manager.get_current_info().await?;
+
+        let value = serde_json::to_string(record).unwrap();
+        let text = Text::build(CONTACT_TEXT_ID, owner_id.to_string(), value)
+            .owner(zone_info.owner_id.clone())
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save contact to noc failed! owner={}, {}", owner_id, e);
+            e
+        })?;
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env
+            .set_with_key(CYFS_CONTACTS_PATH, owner_id.to_string(), &text_id, None, true)
+            .await
+        {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("save contact index failed! owner={}, {}", owner_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        debug!(
+            "save contact success! owner={}, status={:?}, text={}",
+            owner_id, record.info.status, text_id
+        );
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, owner_id: &ObjectId) -> BuckyResult<()> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env
+            .remove_with_key(CYFS_CONTACTS_PATH, owner_id.to_string(), None)
+            .await
+        {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("remove contact index failed! owner={}, {}", owner_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        info!("remove contact success! owner={}", owner_id);
+
+        if let Some(prev) = prev {
+            self.remove_text(&prev).await;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!("remove old contact from noc failed! text={}, {}", text_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_handshake(reply_nonce: Option<String>) -> ContactHandshake {
+        ContactHandshake {
+            owner_id: ObjectId::default(),
+            ood_id: DeviceId::default(),
+            owner: hex::encode(b"owner"),
+            nonce: ContactManager::gen_nonce(),
+            reply_nonce,
+        }
+    }
+
+    #[test]
+    fn test_handshake_codec() {
+        let handshake = new_handshake(Some(ContactManager::gen_nonce()));
+        let object = ContactManager::encode_handshake(HANDSHAKE_ACCEPT, &handshake).unwrap();
+
+        let (action, ret) = ContactManager::decode_handshake(&object).unwrap();
+        assert_eq!(action, HANDSHAKE_ACCEPT);
+        assert_eq!(ret.owner_id, handshake.owner_id);
+        assert_eq!(ret.ood_id, handshake.ood_id);
+        assert_eq!(ret.owner, handshake.owner);
+        assert_eq!(ret.nonce, handshake.nonce);
+        assert_eq!(ret.reply_nonce, handshake.reply_nonce);
+
+        // 同一个动作的握手每次的nonce不同，对象id也不同
+        let other =
+            ContactManager::encode_handshake(HANDSHAKE_ACCEPT, &new_handshake(None)).unwrap();
+        assert_ne!(other.object_id, object.object_id);
+    }
+
+    #[test]
+    fn test_handshake_invalid() {
+        let text = Text::build("other", HANDSHAKE_REQUEST, "{}")
+            .no_create_time()
+            .build();
+        let object = NONObjectInfo::new(text.desc().calculate_id(), text.to_vec().unwrap(), None);
+        let err = ContactManager::decode_handshake(&object).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        let text = Text::build(CONTACT_HANDSHAKE_TEXT_ID, HANDSHAKE_REQUEST, "{}")
+            .no_create_time()
+            .build();
+        let object = NONObjectInfo::new(text.desc().calculate_id(), text.to_vec().unwrap(), None);
+        let err = ContactManager::decode_handshake(&object).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        let object = NONObjectInfo::new(ObjectId::default(), vec![1, 2, 3], None);
+        let err = ContactManager::decode_handshake(&object).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidFormat);
+    }
+
+    #[test]
+    fn test_gen_nonce() {
+        let nonce = ContactManager::gen_nonce();
+        assert_eq!(nonce.len(), 32);
+        assert!(hex::decode(&nonce).is_ok());
+        assert_ne!(nonce, ContactManager::gen_nonce());
+    }
+
+    #[test]
+    fn test_decode_record() {
+        let record = ContactRecord {
+            info: ContactInfo {
+                owner_id: ObjectId::default(),
+                ood_id: Some(DeviceId::default()),
+                name: Some("friend".to_owned()),
+                status: ContactStatus::Requesting,
+                create_time: bucky_time_now(),
+                update_time: bucky_time_now(),
+            },
+            nonce: Some(ContactManager::gen_nonce()),
+            peer_nonce: None,
+        };
+        let value = serde_json::to_string(&record).unwrap();
+
+        let text = Text::build(CONTACT_TEXT_ID, record.info.owner_id.to_string(), &value)
+            .no_create_time()
+            .build();
+        let ret = ContactManager::decode_record(&text).unwrap();
+        assert_eq!(ret.info.name, record.info.name);
+        assert_eq!(ret.nonce, record.nonce);
+        assert!(ret.peer_nonce.is_none());
+
+        let text = Text::build(CONTACT_HANDSHAKE_TEXT_ID, HANDSHAKE_REQUEST, &value)
+            .no_create_time()
+            .build();
+        let err = ContactManager::decode_record(&text).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+    }
+}
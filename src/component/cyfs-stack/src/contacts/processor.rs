@@ -0,0 +1,29 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ContactsInputProcessor: Sync + Send + 'static {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse>;
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse>;
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse>;
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse>;
+}
+
+pub(crate) type ContactsInputProcessorRef = Arc<dyn ContactsInputProcessor>;
@@ -0,0 +1,115 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ContactsInputTransformer {
+    processor: ContactsOutputProcessorRef,
+}
+
+impl ContactsInputTransformer {
+    pub fn new(processor: ContactsOutputProcessorRef) -> ContactsInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn get_contacts(
+        &self,
+        req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        let out_req = ContactsGetOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_contacts(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        let out_req = ContactsAddOutputRequest {
+            common: Self::convert_common(req.common),
+            card: req.card,
+        };
+
+        let out_resp = self.processor.add_contact(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        let out_req = ContactsReplyOutputRequest {
+            common: Self::convert_common(req.common),
+            owner_id: req.owner_id,
+            accept: req.accept,
+        };
+
+        let out_resp = self.processor.reply_contact(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        let out_req = ContactsRemoveOutputRequest {
+            common: Self::convert_common(req.common),
+            owner_id: req.owner_id,
+        };
+
+        let out_resp = self.processor.remove_contact(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ContactsInputProcessor for ContactsInputTransformer {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        Self::get_contacts(&self, req).await
+    }
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        Self::add_contact(&self, req).await
+    }
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        Self::reply_contact(&self, req).await
+    }
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        Self::remove_contact(&self, req).await
+    }
+}
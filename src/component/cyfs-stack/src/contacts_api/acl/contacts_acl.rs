@@ -0,0 +1,77 @@
+use crate::contacts::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ContactsAclInnerInputProcessor {
+    next: ContactsInputProcessorRef,
+}
+
+impl ContactsAclInnerInputProcessor {
+    pub(crate) fn new(next: ContactsInputProcessorRef) -> ContactsInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ContactsInputProcessor for ContactsAclInnerInputProcessor {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        self.check_local_zone_permit("contacts.get_contacts", &req.common.source)?;
+
+        self.next.get_contacts(req).await
+    }
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        self.check_local_zone_permit("contacts.add_contact", &req.common.source)?;
+
+        self.next.add_contact(req).await
+    }
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        self.check_local_zone_permit("contacts.reply_contact", &req.common.source)?;
+
+        self.next.reply_contact(req).await
+    }
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        self.check_local_zone_permit("contacts.remove_contact", &req.common.source)?;
+
+        self.next.remove_contact(req).await
+    }
+}
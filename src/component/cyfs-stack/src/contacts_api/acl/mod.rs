@@ -0,0 +1,3 @@
+mod contacts_acl;
+
+pub(crate) use contacts_acl::*;
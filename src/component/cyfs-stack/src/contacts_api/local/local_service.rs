@@ -0,0 +1,90 @@
+use crate::contacts::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalContactsService {
+    contact_manager: ContactManager,
+}
+
+impl LocalContactsService {
+    pub(crate) fn new(contact_manager: ContactManager) -> Self {
+        Self { contact_manager }
+    }
+
+    pub fn clone_processor(&self) -> ContactsInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn get_contacts(
+        &self,
+        _req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        let list = self.contact_manager.list().await?;
+
+        Ok(ContactsGetInputResponse { list })
+    }
+
+    pub async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        let contact = self.contact_manager.add(req.card).await?;
+
+        Ok(ContactsAddInputResponse { contact })
+    }
+
+    pub async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        let contact = self
+            .contact_manager
+            .reply(&req.owner_id, req.accept)
+            .await?;
+
+        Ok(ContactsReplyInputResponse { contact })
+    }
+
+    pub async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        let contact = self.contact_manager.remove(&req.owner_id).await?;
+
+        Ok(ContactsRemoveInputResponse { contact })
+    }
+}
+
+#[async_trait::async_trait]
+impl ContactsInputProcessor for LocalContactsService {
+    async fn get_contacts(
+        &self,
+        req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        Self::get_contacts(self, req).await
+    }
+
+    async fn add_contact(
+        &self,
+        req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        Self::add_contact(self, req).await
+    }
+
+    async fn reply_contact(
+        &self,
+        req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        Self::reply_contact(self, req).await
+    }
+
+    async fn remove_contact(
+        &self,
+        req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        Self::remove_contact(self, req).await
+    }
+}
@@ -0,0 +1,134 @@
+use super::super::acl::ContactsAclInnerInputProcessor;
+use crate::contacts::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct ContactsServiceRouter {
+    processor: ContactsInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl ContactsServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: ContactsInputProcessorRef,
+    ) -> ContactsInputProcessorRef {
+        // 限定同zone
+        let processor = ContactsAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<ContactsInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = ContactsRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = ContactsInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<ContactsInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("contacts target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ContactsInputProcessor for ContactsServiceRouter {
+    async fn get_contacts(
+        &self,
+        mut req: ContactsGetInputRequest,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_contacts(req).await
+    }
+
+    async fn add_contact(
+        &self,
+        mut req: ContactsAddInputRequest,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.add_contact(req).await
+    }
+
+    async fn reply_contact(
+        &self,
+        mut req: ContactsReplyInputRequest,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.reply_contact(req).await
+    }
+
+    async fn remove_contact(
+        &self,
+        mut req: ContactsRemoveInputRequest,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.remove_contact(req).await
+    }
+}
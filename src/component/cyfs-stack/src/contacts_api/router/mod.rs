@@ -0,0 +1,3 @@
+mod contacts_service_router;
+
+pub(crate) use contacts_service_router::*;
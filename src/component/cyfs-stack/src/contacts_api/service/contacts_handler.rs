@@ -0,0 +1,187 @@
+use crate::contacts::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ContactsRequestHandler {
+    processor: ContactsInputProcessorRef,
+}
+
+impl ContactsRequestHandler {
+    pub fn new(processor: ContactsInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn decode_common_headers<State>(
+        req: &NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilInputRequestCommon> {
+        // req_path
+        let req_path = RequestorHelper::decode_optional_header_with_utf8_decoding(
+            &req.request,
+            cyfs_base::CYFS_REQ_PATH,
+        )?;
+
+        // 尝试提取flags
+        let flags: Option<u32> =
+            RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_FLAGS)?;
+
+        // 尝试提取target字段
+        let target = RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_TARGET)?;
+
+        let ret = UtilInputRequestCommon {
+            req_path,
+            source: req.source.clone(),
+            target,
+            flags: flags.unwrap_or(0),
+        };
+
+        Ok(ret)
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // get_contacts
+    pub async fn process_get_contacts_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_contacts_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_contacts_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ContactsGetInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+        let req = ContactsGetInputRequest { common };
+
+        self.processor.get_contacts(req).await
+    }
+
+    // add_contact
+    pub async fn process_add_contact_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_add_contact_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_add_contact_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ContactsAddInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("add contact failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ContactsAddOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ContactsAddInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            card: out_req.card,
+        };
+        self.processor.add_contact(in_req).await
+    }
+
+    // reply_contact
+    pub async fn process_reply_contact_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_reply_contact_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_reply_contact_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ContactsReplyInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("reply contact failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ContactsReplyOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ContactsReplyInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            owner_id: out_req.owner_id,
+            accept: out_req.accept,
+        };
+        self.processor.reply_contact(in_req).await
+    }
+
+    // remove_contact
+    pub async fn process_remove_contact_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_remove_contact_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_remove_contact_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ContactsRemoveInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("remove contact failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ContactsRemoveOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ContactsRemoveInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            owner_id: out_req.owner_id,
+        };
+        self.processor.remove_contact(in_req).await
+    }
+}
@@ -0,0 +1,132 @@
+use super::contacts_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ContactsRequestType {
+    GetContacts,
+    AddContact,
+    ReplyContact,
+    RemoveContact,
+}
+
+pub(crate) struct ContactsRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ContactsRequestType,
+    handler: ContactsRequestHandler,
+}
+
+impl ContactsRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ContactsRequestType,
+        handler: ContactsRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ContactsRequestType::GetContacts => {
+                self.handler.process_get_contacts_request(req).await
+            }
+            ContactsRequestType::AddContact => self.handler.process_add_contact_request(req).await,
+            ContactsRequestType::ReplyContact => {
+                self.handler.process_reply_contact_request(req).await
+            }
+            ContactsRequestType::RemoveContact => {
+                self.handler.process_remove_contact_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ContactsRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // contacts
+        server.at("/contacts/list").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::GetContacts,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/list/").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::GetContacts,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/add").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::AddContact,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/add/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::AddContact,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/reply").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::ReplyContact,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/reply/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::ReplyContact,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/remove").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::RemoveContact,
+            handler.clone(),
+        ));
+
+        server.at("/contacts/remove/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ContactsRequestType::RemoveContact,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ContactsRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::local::LocalContactsService;
+use super::super::router::ContactsServiceRouter;
+use crate::contacts::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ContactsService {
+    router: ContactsInputProcessorRef,
+}
+
+impl ContactsService {
+    pub(crate) fn new(
+        contact_manager: ContactManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalContactsService::new(contact_manager);
+        let router =
+            ContactsServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ContactsInputProcessorRef {
+        self.router.clone()
+    }
+}
@@ -0,0 +1,7 @@
+mod contacts_handler;
+mod contacts_listener;
+mod contacts_service;
+
+pub(crate) use contacts_handler::*;
+pub(crate) use contacts_listener::*;
+pub(crate) use contacts_service::*;
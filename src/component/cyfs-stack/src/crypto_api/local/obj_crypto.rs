@@ -19,6 +19,7 @@ pub struct ObjectCrypto {
 
 impl ObjectCrypto {
     pub(crate) fn new(
+        signer: RsaCPUObjectSigner,
         verifier: Arc<ObjectVerifier>,
         zone_manager: ZoneManagerRef,
         device_manager: Box<dyn DeviceCache>,
@@ -28,6 +29,7 @@ impl ObjectCrypto {
             zone_manager.clone(),
             device_manager.clone_cache(),
             &bdt_stack,
+            signer,
         );
 
         let signer = Arc::new(signer);
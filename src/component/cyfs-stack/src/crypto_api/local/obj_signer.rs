@@ -35,8 +35,9 @@ impl ObjectSigner {
         zone_manager: ZoneManagerRef,
         device_manager: Box<dyn DeviceCache>,
         bdt_stack: &StackGuard,
+        signer: RsaCPUObjectSigner,
     ) -> Self {
-        let (sign_object_id, signer, verifier) = Self::new_local_device_signer(&bdt_stack);
+        let (sign_object_id, signer, verifier) = Self::new_local_device_signer(&bdt_stack, signer);
 
         Self {
             zone_manager,
@@ -48,13 +49,12 @@ impl ObjectSigner {
         }
     }
 
+    // signer可能是硬件密钥，公钥和bdt协议栈的device一致
     fn new_local_device_signer(
         bdt_stack: &StackGuard,
+        signer: RsaCPUObjectSigner,
     ) -> (ObjectId, Box<dyn Signer>, Box<dyn Verifier>) {
-        let sk = bdt_stack.keystore().private_key();
         let pk = bdt_stack.keystore().public_key();
-
-        let signer = RsaCPUObjectSigner::new(pk.clone(), sk.clone());
         let verifier = RsaCPUObjectVerifier::new(pk.clone());
 
         (
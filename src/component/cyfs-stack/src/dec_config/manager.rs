@@ -0,0 +1,340 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+// 配置文档保存在root_state系统dec下，随root_state同步到zone内的其它设备:
+// /.cyfs/config/dec/{dec_id}/{name} -> 配置text_id
+
+// Text对象id，header为配置名，value为DecConfigDocument的json
+const DEC_CONFIG_TEXT_ID: &str = "dec_config";
+
+// 单个配置文档(value+schema)的大小上限
+const DEC_CONFIG_MAX_SIZE: usize = 1024 * 64;
+
+// watch检查变化的间隔，以及默认和最大的等待时间
+const DEC_CONFIG_WATCH_INTERVAL_MS: u64 = 1000;
+const DEC_CONFIG_WATCH_DEFAULT_TIMEOUT_MS: u64 = 1000 * 30;
+const DEC_CONFIG_WATCH_MAX_TIMEOUT_MS: u64 = 1000 * 60;
+
+struct DecConfigManagerInner {
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+}
+
+// dec的配置中心，取代各个dec自己定义的配置对象
+// 写入只在ood上进行，读取和watch使用本设备上同步的root_state
+#[derive(Clone)]
+pub(crate) struct DecConfigManager(Arc<DecConfigManagerInner>);
+
+impl DecConfigManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = DecConfigManagerInner {
+            non,
+            root_state_stub,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    fn check_name(name: &str) -> BuckyResult<()> {
+        if name.is_empty() || name.contains('/') {
+            let msg = format!("invalid dec config name: {:?}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    fn dec_path(dec_id: &ObjectId) -> String {
+        format!("{}/{}", CYFS_DEC_CONFIG_PATH, dec_id)
+    }
+
+    // schema为空字符串表示移除已有的schema
+    pub async fn set(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        value: String,
+        schema: Option<String>,
+        prev_version: Option<u64>,
+    ) -> BuckyResult<DecConfigDocument> {
+        Self::check_name(name)?;
+
+        let current = self.load(dec_id, name).await?;
+        let version = current.as_ref().map(|(_, doc)| doc.version).unwrap_or(0);
+        if let Some(prev_version) = prev_version {
+            if prev_version != version {
+                let msg = format!(
+                    "dec config version not match! dec={}, name={}, expect={}, current={}",
+                    dec_id, name, prev_version, version
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::Conflict, msg));
+            }
+        }
+
+        let schema = match schema {
+            Some(schema) if schema.is_empty() => None,
+            Some(schema) => Some(schema),
+            None => current.as_ref().and_then(|(_, doc)| doc.schema.clone()),
+        };
+
+        let size = value.len() + schema.as_ref().map(|v| v.len()).unwrap_or(0);
+        if size > DEC_CONFIG_MAX_SIZE {
+            let msg = format!(
+                "dec config size out of limit! dec={}, name={}, size={}, limit={}",
+                dec_id, name, size, DEC_CONFIG_MAX_SIZE
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
+
+        match &schema {
+            Some(schema) => {
+                DecConfigSchema::parse(schema)?.validate_str(&value)?;
+            }
+            None => {
+                DecConfigSchema::parse_value(&value)?;
+            }
+        }
+
+        let doc = DecConfigDocument {
+            dec_id: dec_id.to_owned(),
+            name: name.to_owned(),
+            value,
+            schema,
+            version: version + 1,
+            update_time: bucky_time_now(),
+        };
+        let text_id = self.save_doc(&doc).await?;
+
+        // 基于读取时的text_id更新，期间被其它请求修改的话这里会失败
+        let prev = current.as_ref().map(|(id, _)| id.clone());
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        if let Err(e) = op_env
+            .set_with_key(
+                &Self::dec_path(dec_id),
+                name,
+                &text_id,
+                prev.clone(),
+                prev.is_none(),
+            )
+            .await
+        {
+            error!("save dec config failed! dec={}, name={}, {}", dec_id, name, e);
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        info!(
+            "set dec config success! dec={}, name={}, version={}",
+            dec_id, name, doc.version
+        );
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(doc)
+    }
+
+    pub async fn get(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<Option<DecConfigDocument>> {
+        Self::check_name(name)?;
+
+        let ret = self.load(dec_id, name).await?;
+        Ok(ret.map(|(_, doc)| doc))
+    }
+
+    pub async fn remove(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+    ) -> BuckyResult<Option<DecConfigDocument>> {
+        Self::check_name(name)?;
+
+        let (text_id, doc) = match self.load(dec_id, name).await? {
+            Some(v) => v,
+            None => {
+                warn!("remove dec config but not found! dec={}, name={}", dec_id, name);
+                return Ok(None);
+            }
+        };
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = match op_env
+            .remove_with_key(&Self::dec_path(dec_id), name, Some(text_id.clone()))
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error!("remove dec config failed! dec={}, name={}, {}", dec_id, name, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        info!(
+            "remove dec config success! dec={}, name={}, version={}",
+            dec_id, name, doc.version
+        );
+
+        self.remove_text(&text_id).await;
+
+        Ok(Some(doc))
+    }
+
+    // 返回(是否变化, 当前的配置)，超时没有变化返回false
+    pub async fn watch(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        version: u64,
+        timeout: Option<u64>,
+    ) -> BuckyResult<(bool, Option<DecConfigDocument>)> {
+        Self::check_name(name)?;
+
+        let timeout = std::cmp::min(
+            timeout.unwrap_or(DEC_CONFIG_WATCH_DEFAULT_TIMEOUT_MS),
+            DEC_CONFIG_WATCH_MAX_TIMEOUT_MS,
+        );
+        let deadline = bucky_time_now() + timeout * 1000;
+
+        let mut last_root = None;
+        let mut doc = None;
+        loop {
+            // 系统dec的root没有变化时不需要重新加载
+            let root = match self.0.root_state_stub.get_dec_root().await {
+                Ok(info) => Some(info.dec_root),
+                Err(_) => None,
+            };
+
+            if root.is_none() || root != last_root {
+                last_root = root;
+                doc = self.load(dec_id, name).await?.map(|(_, doc)| doc);
+
+                let current = doc.as_ref().map(|doc| doc.version).unwrap_or(0);
+                if current != version {
+                    return Ok((true, doc));
+                }
+            }
+
+            let now = bucky_time_now();
+            if now >= deadline {
+                return Ok((false, doc));
+            }
+
+            let wait = std::cmp::min((deadline - now) / 1000, DEC_CONFIG_WATCH_INTERVAL_MS);
+            async_std::task::sleep(Duration::from_millis(wait)).await;
+        }
+    }
+
+    async fn load(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+    ) -> BuckyResult<Option<(ObjectId, DecConfigDocument)>> {
+        let path = Self::dec_path(dec_id);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&path, name).await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(v)) => v,
+            Ok(None) => return Ok(None),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(None),
+            Err(e) => {
+                error!("load dec config failed! dec={}, name={}, {}", dec_id, name, e);
+                return Err(e);
+            }
+        };
+
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await.map_err(|e| {
+            error!(
+                "load dec config text from noc failed! dec={}, name={}, text={}, {}",
+                dec_id, name, text_id, e
+            );
+            e
+        })?;
+
+        let text = Text::clone_from_slice(&resp.object.object_raw)?;
+        if text.id() != DEC_CONFIG_TEXT_ID {
+            let msg = format!(
+                "invalid dec config text id: expect={}, got={}",
+                DEC_CONFIG_TEXT_ID,
+                text.id()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let doc: DecConfigDocument = serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!(
+                "invalid dec config text value! dec={}, name={}, {}",
+                dec_id, name, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        Ok(Some((text_id, doc)))
+    }
+
+    async fn save_doc(&self, doc: &DecConfigDocument) -> BuckyResult<ObjectId> {
+        let value = serde_json::to_string(doc).unwrap();
+        let text = Text::build(DEC_CONFIG_TEXT_ID, &doc.name, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save dec config to noc failed! dec={}, name={}, {}",
+                doc.dec_id, doc.name, e
+            );
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!("remove old dec config text from noc failed! text={}, {}", text_id, e);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait DecConfigInputProcessor: Sync + Send + 'static {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse>;
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse>;
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse>;
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse>;
+}
+
+pub(crate) type DecConfigInputProcessorRef = Arc<dyn DecConfigInputProcessor>;
@@ -0,0 +1,124 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct DecConfigInputTransformer {
+    processor: DecConfigOutputProcessorRef,
+}
+
+impl DecConfigInputTransformer {
+    pub fn new(processor: DecConfigOutputProcessorRef) -> DecConfigInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        let out_req = DecConfigSetOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            name: req.name,
+            value: req.value,
+            schema: req.schema,
+            prev_version: req.prev_version,
+        };
+
+        let out_resp = self.processor.set_dec_config(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        let out_req = DecConfigGetOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            name: req.name,
+        };
+
+        let out_resp = self.processor.get_dec_config(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        let out_req = DecConfigRemoveOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            name: req.name,
+        };
+
+        let out_resp = self.processor.remove_dec_config(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        let out_req = DecConfigWatchOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            name: req.name,
+            version: req.version,
+            timeout: req.timeout,
+        };
+
+        let out_resp = self.processor.watch_dec_config(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl DecConfigInputProcessor for DecConfigInputTransformer {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        Self::set_dec_config(&self, req).await
+    }
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        Self::get_dec_config(&self, req).await
+    }
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        Self::remove_dec_config(&self, req).await
+    }
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        Self::watch_dec_config(&self, req).await
+    }
+}
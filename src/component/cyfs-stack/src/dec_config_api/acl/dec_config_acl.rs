@@ -0,0 +1,128 @@
+use crate::dec_config::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct DecConfigAclInnerInputProcessor {
+    next: DecConfigInputProcessorRef,
+}
+
+impl DecConfigAclInnerInputProcessor {
+    pub(crate) fn new(next: DecConfigInputProcessorRef) -> DecConfigInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+
+    // dec配置只允许本zone的系统dec修改
+    fn check_dec_config_write_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        self.check_local_zone_permit(service, source)?;
+
+        if !source.is_system_dec() {
+            let msg = format!(
+                "{} service valid only for system dec! source={}",
+                service, source
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+
+    // dec只能读取自己的配置，系统dec可以读取所有dec的配置
+    fn check_dec_config_read_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+        dec_id: Option<&ObjectId>,
+    ) -> BuckyResult<()> {
+        self.check_local_zone_permit(service, source)?;
+
+        if !source.check_target_dec_permission2(dec_id) {
+            let msg = format!(
+                "{} service valid only for the same dec! source={}, dec={:?}",
+                service, source, dec_id
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl DecConfigInputProcessor for DecConfigAclInnerInputProcessor {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        self.check_dec_config_write_permit("dec_config.set_dec_config", &req.common.source)?;
+
+        self.next.set_dec_config(req).await
+    }
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        self.check_dec_config_read_permit(
+            "dec_config.get_dec_config",
+            &req.common.source,
+            req.dec_id.as_ref(),
+        )?;
+
+        self.next.get_dec_config(req).await
+    }
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        self.check_dec_config_write_permit("dec_config.remove_dec_config", &req.common.source)?;
+
+        self.next.remove_dec_config(req).await
+    }
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        self.check_dec_config_read_permit(
+            "dec_config.watch_dec_config",
+            &req.common.source,
+            req.dec_id.as_ref(),
+        )?;
+
+        self.next.watch_dec_config(req).await
+    }
+}
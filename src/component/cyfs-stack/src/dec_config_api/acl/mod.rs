@@ -0,0 +1,3 @@
+mod dec_config_acl;
+
+pub(crate) use dec_config_acl::*;
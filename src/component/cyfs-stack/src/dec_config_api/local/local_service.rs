@@ -0,0 +1,97 @@
+use crate::dec_config::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalDecConfigService {
+    dec_config_manager: DecConfigManager,
+}
+
+impl LocalDecConfigService {
+    pub(crate) fn new(dec_config_manager: DecConfigManager) -> Self {
+        Self { dec_config_manager }
+    }
+
+    pub fn clone_processor(&self) -> DecConfigInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        let dec_id = req.dec_id.unwrap_or(req.common.source.dec);
+        let doc = self
+            .dec_config_manager
+            .set(&dec_id, &req.name, req.value, req.schema, req.prev_version)
+            .await?;
+
+        Ok(DecConfigSetInputResponse { doc })
+    }
+
+    pub async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        let dec_id = req.dec_id.unwrap_or(req.common.source.dec);
+        let doc = self.dec_config_manager.get(&dec_id, &req.name).await?;
+
+        Ok(DecConfigGetInputResponse { doc })
+    }
+
+    pub async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        let dec_id = req.dec_id.unwrap_or(req.common.source.dec);
+        let doc = self.dec_config_manager.remove(&dec_id, &req.name).await?;
+
+        Ok(DecConfigRemoveInputResponse { doc })
+    }
+
+    pub async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        let dec_id = req.dec_id.unwrap_or(req.common.source.dec);
+        let (changed, doc) = self
+            .dec_config_manager
+            .watch(&dec_id, &req.name, req.version, req.timeout)
+            .await?;
+
+        Ok(DecConfigWatchInputResponse { changed, doc })
+    }
+}
+
+#[async_trait::async_trait]
+impl DecConfigInputProcessor for LocalDecConfigService {
+    async fn set_dec_config(
+        &self,
+        req: DecConfigSetInputRequest,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        Self::set_dec_config(self, req).await
+    }
+
+    async fn get_dec_config(
+        &self,
+        req: DecConfigGetInputRequest,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        Self::get_dec_config(self, req).await
+    }
+
+    async fn remove_dec_config(
+        &self,
+        req: DecConfigRemoveInputRequest,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        Self::remove_dec_config(self, req).await
+    }
+
+    async fn watch_dec_config(
+        &self,
+        req: DecConfigWatchInputRequest,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        Self::watch_dec_config(self, req).await
+    }
+}
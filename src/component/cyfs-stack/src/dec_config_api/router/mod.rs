@@ -0,0 +1,3 @@
+mod dec_config_service_router;
+
+pub(crate) use dec_config_service_router::*;
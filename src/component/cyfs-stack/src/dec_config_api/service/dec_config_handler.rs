@@ -0,0 +1,184 @@
+use crate::dec_config::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct DecConfigRequestHandler {
+    processor: DecConfigInputProcessorRef,
+}
+
+impl DecConfigRequestHandler {
+    pub fn new(processor: DecConfigInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // set_dec_config
+    pub async fn process_set_dec_config_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_set_dec_config_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_set_dec_config_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<DecConfigSetInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("set dec config failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = DecConfigSetOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = DecConfigSetInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            name: out_req.name,
+            value: out_req.value,
+            schema: out_req.schema,
+            prev_version: out_req.prev_version,
+        };
+        self.processor.set_dec_config(in_req).await
+    }
+
+    // get_dec_config
+    pub async fn process_get_dec_config_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_dec_config_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_dec_config_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<DecConfigGetInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get dec config failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = DecConfigGetOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = DecConfigGetInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            name: out_req.name,
+        };
+        self.processor.get_dec_config(in_req).await
+    }
+
+    // remove_dec_config
+    pub async fn process_remove_dec_config_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_remove_dec_config_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_remove_dec_config_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<DecConfigRemoveInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("remove dec config failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = DecConfigRemoveOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = DecConfigRemoveInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            name: out_req.name,
+        };
+        self.processor.remove_dec_config(in_req).await
+    }
+
+    // watch_dec_config
+    pub async fn process_watch_dec_config_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_watch_dec_config_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_watch_dec_config_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<DecConfigWatchInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("watch dec config failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = DecConfigWatchOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = DecConfigWatchInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            name: out_req.name,
+            version: out_req.version,
+            timeout: out_req.timeout,
+        };
+        self.processor.watch_dec_config(in_req).await
+    }
+}
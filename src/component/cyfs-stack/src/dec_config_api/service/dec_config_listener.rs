@@ -0,0 +1,134 @@
+use super::dec_config_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum DecConfigRequestType {
+    SetDecConfig,
+    GetDecConfig,
+    RemoveDecConfig,
+    WatchDecConfig,
+}
+
+pub(crate) struct DecConfigRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: DecConfigRequestType,
+    handler: DecConfigRequestHandler,
+}
+
+impl DecConfigRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: DecConfigRequestType,
+        handler: DecConfigRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            DecConfigRequestType::SetDecConfig => {
+                self.handler.process_set_dec_config_request(req).await
+            }
+            DecConfigRequestType::GetDecConfig => {
+                self.handler.process_get_dec_config_request(req).await
+            }
+            DecConfigRequestType::RemoveDecConfig => {
+                self.handler.process_remove_dec_config_request(req).await
+            }
+            DecConfigRequestType::WatchDecConfig => {
+                self.handler.process_watch_dec_config_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &DecConfigRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // dec config
+        server.at("/dec_config/set").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::SetDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/set/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::SetDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/get").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::GetDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/get/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::GetDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/remove").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::RemoveDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/remove/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::RemoveDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/watch").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::WatchDecConfig,
+            handler.clone(),
+        ));
+
+        server.at("/dec_config/watch/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            DecConfigRequestType::WatchDecConfig,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for DecConfigRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
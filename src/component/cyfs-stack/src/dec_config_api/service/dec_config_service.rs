@@ -0,0 +1,27 @@
+use super::super::local::LocalDecConfigService;
+use super::super::router::DecConfigServiceRouter;
+use crate::dec_config::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct DecConfigService {
+    router: DecConfigInputProcessorRef,
+}
+
+impl DecConfigService {
+    pub(crate) fn new(
+        dec_config_manager: DecConfigManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalDecConfigService::new(dec_config_manager);
+        let router =
+            DecConfigServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> DecConfigInputProcessorRef {
+        self.router.clone()
+    }
+}
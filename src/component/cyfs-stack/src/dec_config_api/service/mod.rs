@@ -0,0 +1,7 @@
+mod dec_config_handler;
+mod dec_config_listener;
+mod dec_config_service;
+
+pub(crate) use dec_config_handler::*;
+pub(crate) use dec_config_listener::*;
+pub(crate) use dec_config_service::*;
@@ -0,0 +1,383 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+use cyfs_util::{DecCgroup, DecCgroupUsage};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 资源策略保存在ood的root_state系统dec下，随root_state同步到zone内的其它设备:
+// /.cyfs/dec_resource/{dec_id} -> 策略text_id
+
+// Text对象id，header为dec_id，value为DecResourcePolicy的json
+const DEC_RESOURCE_POLICY_TEXT_ID: &str = "dec_resource_policy";
+
+// 各设备定时根据同步过来的策略更新本地cgroup的限制
+const DEC_RESOURCE_APPLY_INTERVAL_SECS: u64 = 60;
+
+// 内存上限不能太小，否则dec服务无法启动
+const DEC_RESOURCE_MIN_MEMORY_LIMIT: u64 = 1024 * 1024 * 16;
+
+struct DecResourceManagerInner {
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+
+    // 上次应用到本地cgroup的策略，策略被删除后需要恢复为不限制
+    applied: Mutex<HashMap<ObjectId, DecResourcePolicy>>,
+
+    // 上次采样的cpu累计时间和采样时间，用来计算cpu占用
+    last_samples: Mutex<HashMap<ObjectId, (u64, u64)>>,
+}
+
+// dec的资源策略，策略的修改只在ood上进行，每个设备根据策略更新app-manager启动的dec服务所在的cgroup
+// 并通过设备状态上报各dec的资源占用
+#[derive(Clone)]
+pub(crate) struct DecResourceManager(Arc<DecResourceManagerInner>);
+
+impl DecResourceManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = DecResourceManagerInner {
+            non,
+            root_state_stub,
+            applied: Mutex::new(HashMap::new()),
+            last_samples: Mutex::new(HashMap::new()),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub fn start(&self) {
+        if !DecCgroup::is_supported() {
+            info!("cgroup v2 not supported on current system, dec resource limits will not be applied");
+            return;
+        }
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run_apply().await;
+        });
+    }
+
+    fn check_policy(cpu_limit: Option<f32>, memory_limit: Option<u64>) -> BuckyResult<()> {
+        if let Some(cpu_limit) = cpu_limit {
+            if !(cpu_limit > 0.0) {
+                let msg = format!("invalid dec resource cpu limit: {}", cpu_limit);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        if let Some(memory_limit) = memory_limit {
+            if memory_limit < DEC_RESOURCE_MIN_MEMORY_LIMIT {
+                let msg = format!(
+                    "dec resource memory limit too small: {}, min={}",
+                    memory_limit, DEC_RESOURCE_MIN_MEMORY_LIMIT
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_policy(
+        &self,
+        dec_id: ObjectId,
+        cpu_limit: Option<f32>,
+        memory_limit: Option<u64>,
+    ) -> BuckyResult<DecResourcePolicy> {
+        Self::check_policy(cpu_limit, memory_limit)?;
+
+        let policy = DecResourcePolicy {
+            dec_id,
+            cpu_limit,
+            memory_limit,
+            update_time: bucky_time_now(),
+        };
+
+        let text_id = self.save_policy(&policy).await?;
+
+        let key = policy.dec_id.to_string();
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env
+            .set_with_key(CYFS_DEC_RESOURCE_POLICY_PATH, &key, &text_id, None, true)
+            .await
+        {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!(
+                    "save dec resource policy failed! dec={}, {}",
+                    policy.dec_id, e
+                );
+                let _ = op_env.abort().await;
+                self.remove_text(&text_id).await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        info!("set dec resource policy success! {:?}", policy);
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(policy)
+    }
+
+    pub async fn remove_policy(&self, dec_id: &ObjectId) -> BuckyResult<Option<DecResourcePolicy>> {
+        let key = dec_id.to_string();
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = match op_env
+            .remove_with_key(CYFS_DEC_RESOURCE_POLICY_PATH, &key, None)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error!("remove dec resource policy failed! dec={}, {}", dec_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        let text_id = match ret {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let policy = self.load_policy(&text_id).await.ok();
+        self.remove_text(&text_id).await;
+
+        info!("remove dec resource policy success! dec={}", dec_id);
+
+        Ok(policy)
+    }
+
+    pub async fn get_policies(
+        &self,
+        dec_id: Option<&ObjectId>,
+    ) -> BuckyResult<Vec<DecResourcePolicy>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret: BuckyResult<Vec<ObjectId>> = match dec_id {
+            Some(dec_id) => op_env
+                .get_by_key(CYFS_DEC_RESOURCE_POLICY_PATH, &dec_id.to_string())
+                .await
+                .map(|v| v.into_iter().collect()),
+            None => op_env
+                .list(CYFS_DEC_RESOURCE_POLICY_PATH)
+                .await
+                .map(|list| {
+                    list.into_iter()
+                        .filter_map(|item| match item {
+                            ObjectMapContentItem::Map((_, v)) => Some(v),
+                            _ => None,
+                        })
+                        .collect()
+                }),
+        };
+        let _ = op_env.abort().await;
+
+        let list: Vec<ObjectId> = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("load dec resource policies failed! {}", e);
+                return Err(e);
+            }
+        };
+
+        let mut result = vec![];
+        for text_id in list {
+            match self.load_policy(&text_id).await {
+                Ok(policy) => result.push(policy),
+                Err(e) => {
+                    warn!("load dec resource policy failed! text={}, {}", text_id, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // 收集本设备上所有存在cgroup的dec的资源占用
+    pub async fn collect_usage(&self) -> Vec<DecResourceUsage> {
+        let policies: HashMap<ObjectId, DecResourcePolicy> = match self.get_policies(None).await {
+            Ok(list) => list.into_iter().map(|v| (v.dec_id.clone(), v)).collect(),
+            Err(_) => HashMap::new(),
+        };
+
+        let now = bucky_time_now();
+        let mut result = vec![];
+        let mut last_samples = self.0.last_samples.lock().unwrap();
+        for dec_id in DecCgroup::list() {
+            let usage = match DecCgroup::new(&dec_id).usage() {
+                Some(usage) => usage,
+                None => continue,
+            };
+
+            let cpu_usage = Self::calc_cpu_usage(last_samples.get(&dec_id), &usage, now);
+            last_samples.insert(dec_id.clone(), (usage.cpu_usage_usec, now));
+
+            let policy = policies.get(&dec_id);
+            result.push(DecResourceUsage {
+                dec_id,
+                cpu_usage,
+                memory_usage: usage.memory_current,
+                cpu_limit: policy.and_then(|v| v.cpu_limit),
+                memory_limit: policy.and_then(|v| v.memory_limit),
+                oom_kills: usage.oom_kills,
+            });
+        }
+
+        result
+    }
+
+    // 第一次采样时没有基准，cpu占用记为0
+    fn calc_cpu_usage(last: Option<&(u64, u64)>, usage: &DecCgroupUsage, now: u64) -> f32 {
+        match last {
+            Some((last_usec, last_time)) if now > *last_time => {
+                let used = usage.cpu_usage_usec.saturating_sub(*last_usec);
+                used as f32 / (now - last_time) as f32
+            }
+            _ => 0.0,
+        }
+    }
+
+    async fn run_apply(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(DEC_RESOURCE_APPLY_INTERVAL_SECS));
+        loop {
+            if let Err(e) = self.apply_all().await {
+                warn!("apply dec resource policies failed! {}", e);
+            }
+
+            let _ = interval.next().await;
+        }
+    }
+
+    // 只更新已经存在的cgroup，cgroup由app-manager启动dec服务时创建
+    async fn apply_all(&self) -> BuckyResult<()> {
+        let policies = self.get_policies(None).await?;
+
+        let mut applied = self.0.applied.lock().unwrap();
+        let mut current = HashMap::new();
+        for policy in policies {
+            let cgroup = DecCgroup::new(&policy.dec_id);
+            if cgroup.exists() && applied.get(&policy.dec_id) != Some(&policy) {
+                if let Err(e) = cgroup.apply_limits(policy.cpu_limit, policy.memory_limit) {
+                    warn!(
+                        "apply dec resource policy failed! dec={}, {}",
+                        policy.dec_id, e
+                    );
+                    continue;
+                }
+            }
+
+            current.insert(policy.dec_id.clone(), policy);
+        }
+
+        for dec_id in applied.keys() {
+            if current.contains_key(dec_id) {
+                continue;
+            }
+
+            let cgroup = DecCgroup::new(dec_id);
+            if cgroup.exists() {
+                info!(
+                    "dec resource policy removed, will reset limits: dec={}",
+                    dec_id
+                );
+                let _ = cgroup.apply_limits(None, None);
+            }
+        }
+
+        *applied = current;
+
+        Ok(())
+    }
+
+    async fn save_policy(&self, policy: &DecResourcePolicy) -> BuckyResult<ObjectId> {
+        let value = serde_json::to_string(policy).unwrap();
+        let text = Text::build(
+            DEC_RESOURCE_POLICY_TEXT_ID,
+            policy.dec_id.to_string(),
+            value,
+        )
+        .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save dec resource policy to noc failed! dec={}, {}",
+                policy.dec_id, e
+            );
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn load_policy(&self, text_id: &ObjectId) -> BuckyResult<DecResourcePolicy> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        let text = Text::clone_from_slice(&resp.object.object_raw)?;
+        if text.id() != DEC_RESOURCE_POLICY_TEXT_ID {
+            let msg = format!(
+                "invalid dec resource policy text id: expect={}, got={}",
+                DEC_RESOURCE_POLICY_TEXT_ID,
+                text.id()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!(
+                "invalid dec resource policy text value! dec={}, {}",
+                text.header(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!(
+                "remove old dec resource policy text from noc failed! text={}, {}",
+                text_id, e
+            );
+        }
+    }
+}
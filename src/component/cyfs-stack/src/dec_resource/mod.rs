@@ -0,0 +1,3 @@
+mod manager;
+
+pub(crate) use manager::*;
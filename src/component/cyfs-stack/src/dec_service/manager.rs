@@ -0,0 +1,506 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+// 服务注册信息保存在ood的root_state系统dec下，随root_state同步到zone内的其它设备:
+// /.cyfs/service/{name}/{dec_id}_{device_id} -> 服务text_id
+
+// Text对象id，header为服务名，value为DecServiceInfo的json
+const DEC_SERVICE_TEXT_ID: &str = "dec_service";
+
+// ttl的默认值和范围，单位秒
+const DEC_SERVICE_DEFAULT_TTL_SECS: u32 = 60;
+const DEC_SERVICE_MIN_TTL_SECS: u32 = 10;
+const DEC_SERVICE_MAX_TTL_SECS: u32 = 3600;
+
+// 过期的服务保留一段时间再清理，方便查询最近下线的服务
+const DEC_SERVICE_EXPIRED_KEEP_SECS: u64 = 60 * 10;
+const DEC_SERVICE_CLEANUP_INTERVAL_SECS: u64 = 60;
+
+const DEC_SERVICE_MAX_ENDPOINTS: usize = 16;
+const DEC_SERVICE_MAX_CAPABILITIES: usize = 64;
+
+struct DecServiceManagerInner {
+    zone_manager: ZoneManagerRef,
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+}
+
+// dec服务的注册中心，dec在启动后注册自己提供的端点并定时刷新，其它dec按服务名查询
+// 注册只在ood上进行，查询使用本设备上同步的root_state
+#[derive(Clone)]
+pub(crate) struct DecServiceManager(Arc<DecServiceManagerInner>);
+
+impl DecServiceManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = DecServiceManagerInner {
+            zone_manager: zone_manager.clone(),
+            non,
+            root_state_stub,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    // 只在ood上定时清理过期的服务
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            match this.0.zone_manager.get_current_info().await {
+                Ok(info) if info.zone_role.is_ood_device() => {}
+                _ => return,
+            }
+
+            this.run_cleanup().await;
+        });
+    }
+
+    fn check_name(name: &str) -> BuckyResult<()> {
+        if name.is_empty() || name.contains('/') {
+            let msg = format!("invalid dec service name: {:?}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    fn service_path(name: &str) -> String {
+        format!("{}/{}", CYFS_DEC_SERVICE_PATH, name)
+    }
+
+    fn service_key(dec_id: &ObjectId, device_id: &DeviceId) -> String {
+        format!("{}_{}", dec_id, device_id)
+    }
+
+    fn fix_ttl(ttl: u32) -> u32 {
+        if ttl == 0 {
+            return DEC_SERVICE_DEFAULT_TTL_SECS;
+        }
+
+        std::cmp::min(
+            std::cmp::max(ttl, DEC_SERVICE_MIN_TTL_SECS),
+            DEC_SERVICE_MAX_TTL_SECS,
+        )
+    }
+
+    // 同一个dec在同一个设备上的同名服务只保留一份，重复注册等同于刷新
+    pub async fn register(
+        &self,
+        source: &RequestSourceInfo,
+        name: &str,
+        endpoints: Vec<DecServiceEndpoint>,
+        capabilities: Vec<String>,
+        ttl: u32,
+    ) -> BuckyResult<DecServiceInfo> {
+        Self::check_name(name)?;
+
+        if endpoints.is_empty()
+            || endpoints.len() > DEC_SERVICE_MAX_ENDPOINTS
+            || capabilities.len() > DEC_SERVICE_MAX_CAPABILITIES
+        {
+            let msg = format!(
+                "invalid dec service endpoints or capabilities! name={}, endpoints={}, capabilities={}",
+                name,
+                endpoints.len(),
+                capabilities.len()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let device_id = match &source.zone.device {
+            Some(device_id) => device_id.to_owned(),
+            None => self.0.zone_manager.get_current_device_id().to_owned(),
+        };
+
+        let ttl = Self::fix_ttl(ttl);
+        let now = bucky_time_now();
+        let info = DecServiceInfo {
+            name: name.to_owned(),
+            dec_id: source.dec.clone(),
+            device_id,
+            endpoints,
+            capabilities,
+            ttl,
+            register_time: now,
+            expire_time: now + ttl as u64 * 1000 * 1000,
+        };
+
+        let text_id = self.save_info(&info).await?;
+
+        let path = Self::service_path(name);
+        let key = Self::service_key(&info.dec_id, &info.device_id);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env.set_with_key(&path, &key, &text_id, None, true).await {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!(
+                    "save dec service failed! name={}, dec={}, device={}, {}",
+                    name, info.dec_id, info.device_id, e
+                );
+                let _ = op_env.abort().await;
+                self.remove_text(&text_id).await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        debug!(
+            "register dec service success! name={}, dec={}, device={}, ttl={}",
+            name, info.dec_id, info.device_id, ttl
+        );
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(info)
+    }
+
+    pub async fn unregister(
+        &self,
+        source: &RequestSourceInfo,
+        name: &str,
+    ) -> BuckyResult<Option<DecServiceInfo>> {
+        Self::check_name(name)?;
+
+        let device_id = match &source.zone.device {
+            Some(device_id) => device_id.to_owned(),
+            None => self.0.zone_manager.get_current_device_id().to_owned(),
+        };
+
+        let key = Self::service_key(&source.dec, &device_id);
+        let ret = self.remove(name, &key, None).await?;
+        if ret.is_some() {
+            info!(
+                "unregister dec service success! name={}, dec={}, device={}",
+                name, source.dec, device_id
+            );
+        }
+
+        Ok(ret)
+    }
+
+    async fn remove(
+        &self,
+        name: &str,
+        key: &str,
+        prev: Option<ObjectId>,
+    ) -> BuckyResult<Option<DecServiceInfo>> {
+        let path = Self::service_path(name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = match op_env.remove_with_key(&path, key, prev).await {
+            Ok(v) => v,
+            Err(e) => {
+                error!(
+                    "remove dec service failed! name={}, key={}, {}",
+                    name, key, e
+                );
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        let text_id = match ret {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let info = self.load_info(&text_id).await.ok();
+        self.remove_text(&text_id).await;
+
+        Ok(info)
+    }
+
+    pub async fn query(
+        &self,
+        name: Option<&str>,
+        dec_id: Option<&ObjectId>,
+        capability: Option<&str>,
+        include_expired: bool,
+    ) -> BuckyResult<Vec<DecServiceInfo>> {
+        let names = match name {
+            Some(name) => {
+                Self::check_name(name)?;
+                vec![name.to_owned()]
+            }
+            None => self
+                .list(CYFS_DEC_SERVICE_PATH)
+                .await?
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect(),
+        };
+
+        let now = bucky_time_now();
+        let mut result = vec![];
+        for name in names {
+            for (key, text_id) in self.list(&Self::service_path(&name)).await? {
+                if let Some(dec_id) = dec_id {
+                    if !key.starts_with(&dec_id.to_string()) {
+                        continue;
+                    }
+                }
+
+                let info = match self.load_info(&text_id).await {
+                    Ok(info) => info,
+                    Err(e) => {
+                        warn!("load dec service failed! name={}, key={}, {}", name, key, e);
+                        continue;
+                    }
+                };
+
+                if Self::check_info(&info, capability, include_expired, now) {
+                    result.push(info);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn check_info(
+        info: &DecServiceInfo,
+        capability: Option<&str>,
+        include_expired: bool,
+        now: u64,
+    ) -> bool {
+        if !include_expired && info.is_expired(now) {
+            return false;
+        }
+
+        match capability {
+            Some(capability) => info.capabilities.iter().any(|v| v == capability),
+            None => true,
+        }
+    }
+
+    async fn run_cleanup(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(DEC_SERVICE_CLEANUP_INTERVAL_SECS));
+        while let Some(_) = interval.next().await {
+            if let Err(e) = self.cleanup().await {
+                warn!("cleanup expired dec services failed! {}", e);
+            }
+        }
+    }
+
+    async fn cleanup(&self) -> BuckyResult<()> {
+        let now = bucky_time_now();
+        let list = self.query(None, None, None, true).await?;
+        for info in list {
+            if !info.is_expired(now.saturating_sub(DEC_SERVICE_EXPIRED_KEEP_SECS * 1000 * 1000)) {
+                continue;
+            }
+
+            info!(
+                "will remove expired dec service: name={}, dec={}, device={}, expire_time={}",
+                info.name, info.dec_id, info.device_id, info.expire_time
+            );
+
+            let key = Self::service_key(&info.dec_id, &info.device_id);
+            if let Err(e) = self.remove(&info.name, &key, None).await {
+                warn!(
+                    "remove expired dec service failed! name={}, key={}, {}",
+                    info.name, key, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn list(&self, path: &str) -> BuckyResult<Vec<(String, ObjectId)>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list dec services failed! path={}, {}", path, e);
+                return Err(e);
+            }
+        };
+
+        let result = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Map(v) => Some(v),
+                _ => None,
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn save_info(&self, info: &DecServiceInfo) -> BuckyResult<ObjectId> {
+        // 每次注册的过期时间不同，保留create_time保证text_id随之变化，root_state才会同步到其它设备
+        let value = serde_json::to_string(info).unwrap();
+        let text = Text::build(DEC_SERVICE_TEXT_ID, &info.name, value).build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save dec service to noc failed! name={}, dec={}, {}",
+                info.name, info.dec_id, e
+            );
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn load_info(&self, text_id: &ObjectId) -> BuckyResult<DecServiceInfo> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        let text = Text::clone_from_slice(&resp.object.object_raw)?;
+        if text.id() != DEC_SERVICE_TEXT_ID {
+            let msg = format!(
+                "invalid dec service text id: expect={}, got={}",
+                DEC_SERVICE_TEXT_ID,
+                text.id()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!(
+                "invalid dec service text value! name={}, {}",
+                text.header(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!(
+                "remove old dec service text from noc failed! text={}, {}",
+                text_id, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn new_info(expire_time: u64) -> DecServiceInfo {
+        DecServiceInfo {
+            name: "test-service".to_owned(),
+            dec_id: cyfs_core::get_system_dec_app().to_owned(),
+            device_id: DeviceId::from_str("5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty").unwrap(),
+            endpoints: vec![
+                DecServiceEndpoint::VPort(1000),
+                DecServiceEndpoint::Http {
+                    port: 1318,
+                    path: "/test".to_owned(),
+                },
+            ],
+            capabilities: vec!["v1".to_owned(), "v2".to_owned()],
+            ttl: 60,
+            register_time: expire_time.saturating_sub(60 * 1000 * 1000),
+            expire_time,
+        }
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert!(DecServiceManager::check_name("test-service").is_ok());
+        for name in ["", "a/b", "/"] {
+            let err = DecServiceManager::check_name(name).unwrap_err();
+            assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+        }
+
+        let info = new_info(0);
+        assert_eq!(
+            DecServiceManager::service_path(&info.name),
+            format!("{}/test-service", CYFS_DEC_SERVICE_PATH)
+        );
+
+        // 同一个dec在不同设备上的服务使用不同的key，查询时按dec_id前缀过滤
+        let key = DecServiceManager::service_key(&info.dec_id, &info.device_id);
+        assert!(key.starts_with(&info.dec_id.to_string()));
+        let other = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        assert_ne!(key, DecServiceManager::service_key(&info.dec_id, &other));
+    }
+
+    #[test]
+    fn test_fix_ttl() {
+        assert_eq!(DecServiceManager::fix_ttl(0), DEC_SERVICE_DEFAULT_TTL_SECS);
+        assert_eq!(DecServiceManager::fix_ttl(1), DEC_SERVICE_MIN_TTL_SECS);
+        assert_eq!(DecServiceManager::fix_ttl(30), 30);
+        assert_eq!(
+            DecServiceManager::fix_ttl(u32::MAX),
+            DEC_SERVICE_MAX_TTL_SECS
+        );
+    }
+
+    #[test]
+    fn test_check_info() {
+        let now = bucky_time_now();
+        let info = new_info(now + 1000);
+
+        assert!(DecServiceManager::check_info(&info, None, false, now));
+        assert!(DecServiceManager::check_info(&info, Some("v2"), false, now));
+        assert!(!DecServiceManager::check_info(
+            &info,
+            Some("v3"),
+            false,
+            now
+        ));
+
+        // 过期的服务只在include_expired时返回
+        let info = new_info(now);
+        assert!(!DecServiceManager::check_info(&info, None, false, now));
+        assert!(DecServiceManager::check_info(&info, None, true, now));
+        assert!(!DecServiceManager::check_info(&info, Some("v3"), true, now));
+    }
+
+    #[test]
+    fn test_info_codec() {
+        let info = new_info(bucky_time_now());
+        let value = serde_json::to_string(&info).unwrap();
+        let ret: DecServiceInfo = serde_json::from_str(&value).unwrap();
+        assert_eq!(ret, info);
+    }
+}
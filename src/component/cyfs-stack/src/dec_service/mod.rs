@@ -0,0 +1,7 @@
+mod manager;
+mod processor;
+mod transform;
+
+pub(crate) use manager::*;
+pub(crate) use processor::*;
+pub(crate) use transform::*;
@@ -0,0 +1,24 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ServiceDiscoveryInputProcessor: Sync + Send + 'static {
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse>;
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse>;
+
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse>;
+}
+
+pub(crate) type ServiceDiscoveryInputProcessorRef = Arc<dyn ServiceDiscoveryInputProcessor>;
@@ -0,0 +1,101 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ServiceDiscoveryInputTransformer {
+    processor: ServiceDiscoveryOutputProcessorRef,
+}
+
+impl ServiceDiscoveryInputTransformer {
+    pub fn new(processor: ServiceDiscoveryOutputProcessorRef) -> ServiceDiscoveryInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        let out_req = ServiceDiscoveryRegisterOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            endpoints: req.endpoints,
+            capabilities: req.capabilities,
+            ttl: req.ttl,
+        };
+
+        let out_resp = self.processor.register_dec_service(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        let out_req = ServiceDiscoveryUnregisterOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+        };
+
+        let out_resp = self.processor.unregister_dec_service(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        let out_req = ServiceDiscoveryQueryOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            dec_id: req.dec_id,
+            capability: req.capability,
+            include_expired: req.include_expired,
+        };
+
+        let out_resp = self.processor.query_dec_services(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceDiscoveryInputProcessor for ServiceDiscoveryInputTransformer {
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        Self::register_dec_service(&self, req).await
+    }
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        Self::unregister_dec_service(&self, req).await
+    }
+
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        Self::query_dec_services(&self, req).await
+    }
+}
@@ -7,6 +7,10 @@ use std::sync::Arc;
 pub struct RouterEventsContainer {
     pub test_event: OnceCell<RouterEvents<TestEventRequest, TestEventResponse>>,
     pub zone_role_changed_event: OnceCell<RouterEvents<ZoneRoleChangedEventRequest, ZoneRoleChangedEventResponse>>,
+    pub desc_renew_failed_event: OnceCell<RouterEvents<DescRenewFailedEventRequest, DescRenewFailedEventResponse>>,
+    pub zone_event_recorded_event: OnceCell<RouterEvents<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse>>,
+    pub storage_mode_changed_event: OnceCell<RouterEvents<StorageModeChangedEventRequest, StorageModeChangedEventResponse>>,
+    pub sync_conflict_event: OnceCell<RouterEvents<SyncConflictEventRequest, SyncConflictEventResponse>>,
 }
 
 pub type RouterEventsContainerRef = Arc<RouterEventsContainer>;
@@ -16,6 +20,10 @@ impl RouterEventsContainer {
         Self {
             test_event: OnceCell::new(),
             zone_role_changed_event: OnceCell::new(),
+            desc_renew_failed_event: OnceCell::new(),
+            zone_event_recorded_event: OnceCell::new(),
+            storage_mode_changed_event: OnceCell::new(),
+            sync_conflict_event: OnceCell::new(),
         }
     }
 
@@ -36,6 +44,42 @@ impl RouterEventsContainer {
         self.zone_role_changed_event.get()
     }
 
+    pub fn desc_renew_failed_event(&self) -> &RouterEvents<DescRenewFailedEventRequest, DescRenewFailedEventResponse> {
+        self.desc_renew_failed_event
+            .get_or_init(|| RouterEvents::<DescRenewFailedEventRequest, DescRenewFailedEventResponse>::new())
+    }
+
+    pub fn try_desc_renew_failed_event(&self) -> Option<&RouterEvents<DescRenewFailedEventRequest, DescRenewFailedEventResponse>> {
+        self.desc_renew_failed_event.get()
+    }
+
+    pub fn zone_event_recorded_event(&self) -> &RouterEvents<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse> {
+        self.zone_event_recorded_event
+            .get_or_init(|| RouterEvents::<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse>::new())
+    }
+
+    pub fn try_zone_event_recorded_event(&self) -> Option<&RouterEvents<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse>> {
+        self.zone_event_recorded_event.get()
+    }
+
+    pub fn storage_mode_changed_event(&self) -> &RouterEvents<StorageModeChangedEventRequest, StorageModeChangedEventResponse> {
+        self.storage_mode_changed_event
+            .get_or_init(|| RouterEvents::<StorageModeChangedEventRequest, StorageModeChangedEventResponse>::new())
+    }
+
+    pub fn try_storage_mode_changed_event(&self) -> Option<&RouterEvents<StorageModeChangedEventRequest, StorageModeChangedEventResponse>> {
+        self.storage_mode_changed_event.get()
+    }
+
+    pub fn sync_conflict_event(&self) -> &RouterEvents<SyncConflictEventRequest, SyncConflictEventResponse> {
+        self.sync_conflict_event
+            .get_or_init(|| RouterEvents::<SyncConflictEventRequest, SyncConflictEventResponse>::new())
+    }
+
+    pub fn try_sync_conflict_event(&self) -> Option<&RouterEvents<SyncConflictEventRequest, SyncConflictEventResponse>> {
+        self.sync_conflict_event.get()
+    }
+
 }
 
 #[derive(Clone)]
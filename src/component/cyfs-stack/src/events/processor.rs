@@ -36,6 +36,10 @@ macro_rules! declare_router_event_processor {
 // non events
 declare_router_event_processor!(TestEventRequest, TestEventResponse, test_event);
 declare_router_event_processor!(ZoneRoleChangedEventRequest, ZoneRoleChangedEventResponse, zone_role_changed_event);
+declare_router_event_processor!(DescRenewFailedEventRequest, DescRenewFailedEventResponse, desc_renew_failed_event);
+declare_router_event_processor!(ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse, zone_event_recorded_event);
+declare_router_event_processor!(StorageModeChangedEventRequest, StorageModeChangedEventResponse, storage_mode_changed_event);
+declare_router_event_processor!(SyncConflictEventRequest, SyncConflictEventResponse, sync_conflict_event);
 
 impl RouterEventManagerProcessor for RouterEventsManager {
     fn test_event(&self) -> &dyn RouterEventProcessor<TestEventRequest, TestEventResponse> {
@@ -45,4 +49,20 @@ impl RouterEventManagerProcessor for RouterEventsManager {
     fn zone_role_changed_event(&self) -> &dyn RouterEventProcessor<ZoneRoleChangedEventRequest, ZoneRoleChangedEventResponse> {
         self
     }
+
+    fn desc_renew_failed_event(&self) -> &dyn RouterEventProcessor<DescRenewFailedEventRequest, DescRenewFailedEventResponse> {
+        self
+    }
+
+    fn zone_event_recorded_event(&self) -> &dyn RouterEventProcessor<ZoneEventRecordedEventRequest, ZoneEventRecordedEventResponse> {
+        self
+    }
+
+    fn storage_mode_changed_event(&self) -> &dyn RouterEventProcessor<StorageModeChangedEventRequest, StorageModeChangedEventResponse> {
+        self
+    }
+
+    fn sync_conflict_event(&self) -> &dyn RouterEventProcessor<SyncConflictEventRequest, SyncConflictEventResponse> {
+        self
+    }
 }
@@ -73,6 +73,46 @@ impl RouterEventWSProcessor {
                     .zone_role_changed_event()
                     .add_event(event)
             }
+            RouterEventCategory::DescRenewFailed => {
+                let event = Self::create_event::<
+                    DescRenewFailedEventRequest,
+                    DescRenewFailedEventResponse,
+                >(session_requestor, &req)?;
+                self.manager
+                    .events()
+                    .desc_renew_failed_event()
+                    .add_event(event)
+            }
+            RouterEventCategory::ZoneEventRecorded => {
+                let event = Self::create_event::<
+                    ZoneEventRecordedEventRequest,
+                    ZoneEventRecordedEventResponse,
+                >(session_requestor, &req)?;
+                self.manager
+                    .events()
+                    .zone_event_recorded_event()
+                    .add_event(event)
+            }
+            RouterEventCategory::StorageModeChanged => {
+                let event = Self::create_event::<
+                    StorageModeChangedEventRequest,
+                    StorageModeChangedEventResponse,
+                >(session_requestor, &req)?;
+                self.manager
+                    .events()
+                    .storage_mode_changed_event()
+                    .add_event(event)
+            }
+            RouterEventCategory::SyncConflict => {
+                let event = Self::create_event::<
+                    SyncConflictEventRequest,
+                    SyncConflictEventResponse,
+                >(session_requestor, &req)?;
+                self.manager
+                    .events()
+                    .sync_conflict_event()
+                    .add_event(event)
+            }
         }
     }
 
@@ -88,6 +128,26 @@ impl RouterEventWSProcessor {
                 .events()
                 .zone_role_changed_event()
                 .remove_event(&req.id, req.dec_id),
+            RouterEventCategory::DescRenewFailed => self
+                .manager
+                .events()
+                .desc_renew_failed_event()
+                .remove_event(&req.id, req.dec_id),
+            RouterEventCategory::ZoneEventRecorded => self
+                .manager
+                .events()
+                .zone_event_recorded_event()
+                .remove_event(&req.id, req.dec_id),
+            RouterEventCategory::StorageModeChanged => self
+                .manager
+                .events()
+                .storage_mode_changed_event()
+                .remove_event(&req.id, req.dec_id),
+            RouterEventCategory::SyncConflict => self
+                .manager
+                .events()
+                .sync_conflict_event()
+                .remove_event(&req.id, req.dec_id),
         };
 
         Ok(ret)
@@ -1,4 +1,5 @@
 use cyfs_base::*;
+use cyfs_lib::ShareMode;
 
 use std::str::FromStr;
 
@@ -44,6 +45,16 @@ impl FromStr for FrontRequestGetMode {
     }
 }
 
+impl From<ShareMode> for FrontRequestGetMode {
+    fn from(mode: ShareMode) -> Self {
+        match mode {
+            ShareMode::Default => Self::Default,
+            ShareMode::Object => Self::Object,
+            ShareMode::Data => Self::Data,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Copy, Eq, PartialEq)]
 pub enum FrontRequestObjectFormat {
     Default,
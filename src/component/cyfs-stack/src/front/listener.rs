@@ -1,5 +1,5 @@
 use super::http_request::FrontInputHttpRequest;
-use super::protocol::FrontProtocolHandlerRef;
+use super::protocol::{parse_front_object_host, FrontProtocolHandlerRef};
 use crate::zone::ZoneManagerRef;
 use cyfs_lib::*;
 
@@ -12,6 +12,15 @@ pub enum FrontRequestType {
     L,
     A,
 
+    // dec api gateway, /a/{dec}/api/*
+    Api,
+
+    // share link
+    S,
+
+    // multipart form upload
+    Upload,
+
     // treat as o protocol
     Any,
 }
@@ -39,12 +48,18 @@ impl FrontRequestHandlerEndpoint {
     }
 
     async fn process_request<State: Send>(&self, req: tide::Request<State>) -> tide::Response {
+        // <object_id>.o.<zone-domain>和o.<zone-domain>形式的host，不管path是什么都按照o协议处理
+        let object_host = req.url().host_str().and_then(parse_front_object_host);
+
         let req = match FrontInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
             Ok(v) => v,
             Err(resp) => return resp,
         };
 
-        self.handler.process_request(self.req_type, req).await
+        match object_host {
+            Some(host) => self.handler.process_object_host_request(host, req).await,
+            None => self.handler.process_request(self.req_type, req).await,
+        }
     }
 
     pub fn register_server(
@@ -83,6 +98,46 @@ impl FrontRequestHandlerEndpoint {
             handler.clone(),
         ));
 
+        // a/{dec}/api，反向代理到dec注册的本地服务，需要支持get以外的method
+        for path in ["/a/:dec/api", "/a/:dec/api/*must"] {
+            let mut route = server.at(path);
+            for method in [
+                http_types::Method::Get,
+                http_types::Method::Post,
+                http_types::Method::Put,
+                http_types::Method::Delete,
+                http_types::Method::Patch,
+            ] {
+                route.method(
+                    method,
+                    FrontRequestHandlerEndpoint::new(
+                        zone_manager.clone(),
+                        protocol.to_owned(),
+                        FrontRequestType::Api,
+                        handler.clone(),
+                    ),
+                );
+            }
+        }
+
+        // share
+        server.at("/s/*must").get(FrontRequestHandlerEndpoint::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            FrontRequestType::S,
+            handler.clone(),
+        ));
+
+        // upload
+        server
+            .at("/upload/*must")
+            .post(FrontRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                FrontRequestType::Upload,
+                handler.clone(),
+            ));
+
         // any
         server
             .at("/:name/*must")
@@ -99,6 +154,14 @@ impl FrontRequestHandlerEndpoint {
             FrontRequestType::Any,
             handler.clone(),
         ));
+
+        // <object_id>.o.<zone-domain>/
+        server.at("/").get(FrontRequestHandlerEndpoint::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            FrontRequestType::Any,
+            handler.clone(),
+        ));
     }
 }
 
@@ -1,5 +1,6 @@
 mod def;
 mod listener;
+mod multipart;
 mod protocol;
 mod request;
 mod service;
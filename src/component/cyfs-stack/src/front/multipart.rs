@@ -0,0 +1,324 @@
+use cyfs_base::*;
+
+use async_std::io::{ErrorKind, Read, ReadExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// part头部的最大长度
+const MULTIPART_MAX_HEADER_SIZE: usize = 1024 * 16;
+
+const MULTIPART_READ_BUF_SIZE: usize = 1024 * 16;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MultipartPart {
+    pub name: Option<String>,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+}
+
+// 从content-type里面提取boundary
+pub(crate) fn parse_multipart_boundary(content_type: &str) -> BuckyResult<String> {
+    let mut params = content_type.split(';');
+    let mime = params.next().unwrap_or("").trim();
+    if !mime.eq_ignore_ascii_case("multipart/form-data") {
+        let msg = format!("invalid multipart content type: {}", content_type);
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+    }
+
+    for param in params {
+        if let Some((k, v)) = param.split_once('=') {
+            if k.trim().eq_ignore_ascii_case("boundary") {
+                let v = v.trim().trim_matches('"');
+                if !v.is_empty() && v.len() <= 70 {
+                    return Ok(v.to_owned());
+                }
+            }
+        }
+    }
+
+    let msg = format!("multipart boundary missing or invalid: {}", content_type);
+    error!("{}", msg);
+    Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum MultipartState {
+    Begin,
+    Body,
+    PartEnd,
+    Done,
+}
+
+// multipart/form-data的流式读取，每次next_part成功之后，reader本身就是当前part的数据流
+// https://www.rfc-editor.org/rfc/rfc7578
+pub(crate) struct MultipartFormReader<R> {
+    inner: R,
+
+    // \r\n--{boundary}
+    delimiter: Vec<u8>,
+
+    buf: Vec<u8>,
+    eof: bool,
+    state: MultipartState,
+}
+
+impl<R> MultipartFormReader<R>
+where
+    R: Read + Unpin,
+{
+    pub fn new(inner: R, boundary: &str) -> Self {
+        Self {
+            inner,
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+
+            // body以--{boundary}开头，前面补上\r\n，这样所有的分隔符可以统一查找
+            buf: b"\r\n".to_vec(),
+            eof: false,
+            state: MultipartState::Begin,
+        }
+    }
+
+    fn find(buf: &[u8], pattern: &[u8]) -> Option<usize> {
+        buf.windows(pattern.len()).position(|w| w == pattern)
+    }
+
+    fn unexpected_eof(&self) -> BuckyError {
+        let msg = format!("multipart body ended unexpectedly! state={:?}", self.state);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::InvalidData, msg)
+    }
+
+    // 读到流结束时返回false
+    async fn fill_buf(&mut self) -> BuckyResult<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+
+        let mut tmp = vec![0u8; MULTIPART_READ_BUF_SIZE];
+        let len = loop {
+            match self.inner.read(&mut tmp).await {
+                Ok(len) => break len,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let msg = format!("read multipart body error! {}", e);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+                }
+            }
+        };
+
+        if len == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+
+        self.buf.extend_from_slice(&tmp[..len]);
+        Ok(true)
+    }
+
+    // 跳过当前part剩余的数据，读取下一个part的头部，没有更多的part时返回None
+    pub async fn next_part(&mut self) -> BuckyResult<Option<MultipartPart>> {
+        if self.state == MultipartState::Done {
+            return Ok(None);
+        }
+
+        let pos = loop {
+            if let Some(pos) = Self::find(&self.buf, &self.delimiter) {
+                break pos;
+            }
+
+            // 末尾可能是分隔符的开头，需要保留
+            let keep = self.delimiter.len() - 1;
+            if self.buf.len() > keep {
+                self.buf.drain(..self.buf.len() - keep);
+            }
+
+            if !self.fill_buf().await? {
+                return Err(self.unexpected_eof());
+            }
+        };
+        self.buf.drain(..pos + self.delimiter.len());
+
+        while self.buf.len() < 2 {
+            if !self.fill_buf().await? {
+                return Err(self.unexpected_eof());
+            }
+        }
+
+        // 最后一个分隔符后面紧跟--
+        if self.buf.starts_with(b"--") {
+            self.state = MultipartState::Done;
+            return Ok(None);
+        }
+
+        if !self.buf.starts_with(b"\r\n") {
+            let msg = "invalid multipart delimiter line!".to_owned();
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        // 头部从分隔符之后的\r\n开始，到空行结束，没有头部时空行紧跟在分隔符后面
+        let end = loop {
+            if let Some(pos) = Self::find(&self.buf, b"\r\n\r\n") {
+                break pos;
+            }
+
+            if self.buf.len() > MULTIPART_MAX_HEADER_SIZE {
+                let msg = "multipart part headers too large!".to_owned();
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            if !self.fill_buf().await? {
+                return Err(self.unexpected_eof());
+            }
+        };
+
+        let part = if end == 0 {
+            MultipartPart::default()
+        } else {
+            Self::parse_part_headers(&String::from_utf8_lossy(&self.buf[2..end]))
+        };
+        self.buf.drain(..end + 4);
+        self.state = MultipartState::Body;
+
+        Ok(Some(part))
+    }
+
+    fn parse_part_headers(headers: &str) -> MultipartPart {
+        let mut part = MultipartPart::default();
+        for line in headers.split("\r\n") {
+            let (k, v) = match line.split_once(':') {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let k = k.trim();
+            if k.eq_ignore_ascii_case("content-disposition") {
+                for param in v.split(';').skip(1) {
+                    if let Some((pk, pv)) = param.split_once('=') {
+                        let pv = pv.trim().trim_matches('"').to_owned();
+                        match pk.trim().to_ascii_lowercase().as_str() {
+                            "name" => part.name = Some(pv),
+                            "filename" => part.filename = Some(pv),
+                            _ => {}
+                        }
+                    }
+                }
+            } else if k.eq_ignore_ascii_case("content-type") {
+                part.content_type = Some(v.trim().to_owned());
+            }
+        }
+
+        part
+    }
+}
+
+impl<R> Read for MultipartFormReader<R>
+where
+    R: Read + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        if this.state != MultipartState::Body {
+            return Poll::Ready(Ok(0));
+        }
+
+        loop {
+            // 分隔符之前的数据都属于当前part，没有找到分隔符时末尾可能是分隔符的开头，需要等待更多的数据
+            let avail = match Self::find(&this.buf, &this.delimiter) {
+                Some(0) => {
+                    this.state = MultipartState::PartEnd;
+                    return Poll::Ready(Ok(0));
+                }
+                Some(pos) => pos,
+                None => this.buf.len().saturating_sub(this.delimiter.len() - 1),
+            };
+
+            if avail > 0 {
+                let len = std::cmp::min(avail, out.len());
+                out[..len].copy_from_slice(&this.buf[..len]);
+                this.buf.drain(..len);
+                return Poll::Ready(Ok(len));
+            }
+
+            if this.eof {
+                let e = std::io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "multipart part body ended without delimiter",
+                );
+                return Poll::Ready(Err(e));
+            }
+
+            let mut tmp = [0u8; MULTIPART_READ_BUF_SIZE];
+            match Pin::new(&mut this.inner).poll_read(cx, &mut tmp) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(len)) => this.buf.extend_from_slice(&tmp[..len]),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 每次最多返回3个字节
+    struct SlowReader(async_std::io::Cursor<Vec<u8>>);
+
+    impl Read for SlowReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let len = std::cmp::min(buf.len(), 3);
+            Pin::new(&mut self.0).poll_read(cx, &mut buf[..len])
+        }
+    }
+
+    #[async_std::test]
+    async fn test_multipart_reader() {
+        let boundary = parse_multipart_boundary("multipart/form-data; boundary=\"XYZ\"").unwrap();
+        assert_eq!(boundary, "XYZ");
+
+        let body = "preamble\r\n--XYZ\r\n\
+            Content-Disposition: form-data; name=\"desc\"\r\n\r\n\
+            hello\r\n\
+            --XYZ\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            line1\r\n--XY\r\nline2\r\n\
+            --XYZ--\r\n";
+
+        // 按很小的块读取，覆盖分隔符跨越读取边界的情况
+        let mut reader = MultipartFormReader::new(
+            SlowReader(async_std::io::Cursor::new(body.as_bytes().to_vec())),
+            &boundary,
+        );
+
+        // 不读取第一个part的数据，直接跳到第二个part
+        let part = reader.next_part().await.unwrap().unwrap();
+        assert_eq!(part.name.as_deref(), Some("desc"));
+        assert!(part.filename.is_none());
+
+        let part = reader.next_part().await.unwrap().unwrap();
+        assert_eq!(part.name.as_deref(), Some("file"));
+        assert_eq!(part.filename.as_deref(), Some("a.txt"));
+        assert_eq!(part.content_type.as_deref(), Some("text/plain"));
+
+        let mut data = vec![];
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"line1\r\n--XY\r\nline2");
+
+        assert!(reader.next_part().await.unwrap().is_none());
+        assert!(parse_multipart_boundary("text/plain").is_err());
+    }
+}
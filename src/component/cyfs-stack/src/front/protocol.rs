@@ -1,8 +1,10 @@
 use super::def::*;
 use super::http_request::FrontInputHttpRequest;
 use super::listener::FrontRequestType;
+use super::multipart::parse_multipart_boundary;
 use super::request::*;
 use super::service::*;
+use crate::media::MediaTransformParam;
 use crate::name::*;
 use crate::ndn_api::NDNRequestHandler;
 use crate::non_api::NONRequestHandler;
@@ -33,8 +35,13 @@ const KNOWN_ROOTS: &[&str] = &[
     "r",
     "l",
     "a",
+    "s",
+    "upload",
 ];
 
+// 分享链接的密码，也可以通过pwd参数传递
+const SHARE_PASSWORD_HEADER: &str = "cyfs-share-password";
+
 pub(crate) fn parse_front_host_with_dec_id(
     host: &str,
 ) -> BuckyResult<Option<(FrontRequestType, ObjectId)>> {
@@ -109,6 +116,43 @@ pub(crate) fn parse_front_host(host: &str) -> Option<FrontRequestType> {
     Some(ft)
 }
 
+// 通过host访问对象，每个对象可以有独立的origin，方便浏览器隔离不可信的内容
+// <object_id>.o.<zone-domain>/[inner_path] -> /o/<object_id>/[inner_path]
+// o.<zone-domain>/<object_id>/[inner_path] -> /o/<object_id>/[inner_path]
+// 域名不区分大小写，子域名里面的object_id需要使用base36编码
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum FrontObjectHost {
+    Zone,
+    Object(ObjectId),
+}
+
+pub(crate) fn parse_front_object_host(host: &str) -> Option<FrontObjectHost> {
+    // ipv6地址不会是上面的形式
+    if host.starts_with('[') {
+        return None;
+    }
+    let host = host.split(':').next().unwrap();
+
+    if let Some(zone) = host.strip_prefix("o.") {
+        // o.{dec_id}和o.system是dec的host形式，不在这里处理
+        if zone.is_empty() || zone == "system" || ObjectId::from_str(zone).is_ok() {
+            return None;
+        }
+        return Some(FrontObjectHost::Zone);
+    }
+
+    let (label, zone) = host.split_once('.')?;
+    match zone.strip_prefix("o.") {
+        Some(zone) if !zone.is_empty() && OBJECT_ID_BASE36_RANGE.contains(&label.len()) => {
+            match ObjectId::from_base36(label) {
+                Ok(id) => Some(FrontObjectHost::Object(id)),
+                Err(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub(crate) fn parse_front_host_with_anonymous_dec_id(
     host: &str,
 ) -> Option<(FrontRequestType, ObjectId)> {
@@ -166,21 +210,23 @@ impl FrontProtocolHandler {
         req: &tide::Request<State>,
     ) -> BuckyResult<Option<String>> {
         match req.param("must") {
-            Ok(v) => {
-                // 对url里面的以%编码的unicode字符进行解码
-                let decoded_value = percent_encoding::percent_decode_str(&v);
-                let value = decoded_value.decode_utf8().map_err(|e| {
-                    let msg = format!("invalid utf8 url format! param={}, {}", v, e);
-                    error!("{}", msg);
-                    BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
-                })?;
-
-                Ok(Some(value.into_owned()))
-            }
+            Ok(v) => Ok(Some(Self::decode_url_param(v)?)),
             Err(_) => Ok(None),
         }
     }
 
+    // 对url里面的以%编码的unicode字符进行解码
+    fn decode_url_param(v: &str) -> BuckyResult<String> {
+        let decoded_value = percent_encoding::percent_decode_str(v);
+        let value = decoded_value.decode_utf8().map_err(|e| {
+            let msg = format!("invalid utf8 url format! param={}, {}", v, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        Ok(value.into_owned())
+    }
+
     // 直接解析一个seg是不是object_id
     fn parse_object_seg(seg: &str) -> Option<ObjectId> {
         // 只对合适的字符串才尝试解析是不是object
@@ -271,6 +317,13 @@ impl FrontProtocolHandler {
         }
     }
 
+    fn media_from_request(url: &http_types::Url) -> BuckyResult<Option<MediaTransformParam>> {
+        let size = RequestorHelper::value_from_querys("size", url)?;
+        let quality = RequestorHelper::value_from_querys("quality", url)?;
+
+        MediaTransformParam::new(size, quality)
+    }
+
     fn range_from_request(req: &http_types::Request) -> BuckyResult<Option<NDNDataRequestRange>> {
         // first extract dec_id from headers
         let s: Option<String> = match RequestorHelper::decode_optional_header(req, "Range")? {
@@ -363,6 +416,39 @@ impl FrontProtocolHandler {
         }
     }
 
+    fn deadline_from_request(req: &http_types::Request) -> BuckyResult<Option<u64>> {
+        // first extract deadline from headers
+        if let Some(deadline) = RequestorHelper::decode_optional_header(req, cyfs_base::CYFS_DEADLINE)? {
+            return Ok(Some(deadline));
+        }
+
+        // try extract deadline from query pairs
+        match RequestorHelper::value_from_querys("deadline", req.url()) {
+            Ok(ret) => Ok(ret),
+            Err(e) => {
+                let msg = format!("invalid request url deadline query param! {}, {}", req.url(), e);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg))
+            }
+        }
+    }
+
+    fn share_password_from_request(req: &http_types::Request) -> BuckyResult<Option<String>> {
+        if let Some(password) = RequestorHelper::decode_optional_header(req, SHARE_PASSWORD_HEADER)?
+        {
+            return Ok(Some(password));
+        }
+
+        match RequestorHelper::value_from_querys_with_utf8_decoding("pwd", req.url()) {
+            Ok(ret) => Ok(ret),
+            Err(e) => {
+                let msg = format!("invalid request url pwd query param! {}, {}", req.url(), e);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg))
+            }
+        }
+    }
+
     fn is_cyfs_browser(req: &http_types::Request) -> bool {
         let ret: BuckyResult<Option<String>> =
             RequestorHelper::decode_optional_header(req, http_types::headers::USER_AGENT);
@@ -443,6 +529,20 @@ impl FrontProtocolHandler {
                 let http_resp = self.encode_a_response(resp, format, is_cyfs_browser).await;
                 Ok(http_resp)
             }
+            FrontRequestType::Api => self.process_api_request(req).await,
+            FrontRequestType::S => {
+                let route_param = Self::extract_route_param(&req.request)?;
+                self.process_s_request(req, route_param, format).await
+            }
+            FrontRequestType::Upload => {
+                let route_param = Self::extract_route_param(&req.request)?;
+                let resp = self.process_upload_request(req, route_param).await?;
+
+                let mut http_resp = RequestorHelper::new_response(http_types::StatusCode::Ok);
+                http_resp.set_body(serde_json::to_string(&resp).unwrap());
+                http_resp.set_content_type(tide::http::mime::JSON);
+                Ok(http_resp.into())
+            }
             FrontRequestType::Any => {
                 let route_param = Self::extract_option_route_param(&req.request)?;
                 self.process_any_request(req, route_param, format).await
@@ -450,6 +550,57 @@ impl FrontProtocolHandler {
         }
     }
 
+    pub async fn process_object_host_request<State>(
+        &self,
+        host: FrontObjectHost,
+        req: FrontInputHttpRequest<State>,
+    ) -> tide::Response {
+        match self.process_object_host_request_inner(host, req).await {
+            Ok(resp) => resp,
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    // 整个path都作为o协议的参数
+    async fn process_object_host_request_inner<State>(
+        &self,
+        host: FrontObjectHost,
+        req: FrontInputHttpRequest<State>,
+    ) -> BuckyResult<tide::Response> {
+        let format = Self::object_format_from_request(req.request.url())?;
+        let path = Self::decode_url_param(req.request.url().path())?;
+        let path = path.trim_matches('/');
+
+        let route_param = match &host {
+            FrontObjectHost::Object(id) => {
+                if path.is_empty() {
+                    id.to_string()
+                } else {
+                    format!("{}/{}", id, path)
+                }
+            }
+            FrontObjectHost::Zone => {
+                if path.is_empty() {
+                    let msg = format!("request url object_id missing! {}", req.request.url());
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+                }
+                path.to_owned()
+            }
+        };
+
+        debug!(
+            "will process front request by host: url={}, host={:?}, route={}",
+            req.request.url(),
+            host,
+            route_param
+        );
+
+        let resp = self.process_o_request(req, route_param, format).await?;
+        let http_resp = self.encode_o_response(resp, format).await;
+        Ok(http_resp)
+    }
+
     async fn process_any_request<State>(
         &self,
         mut req: FrontInputHttpRequest<State>,
@@ -532,7 +683,10 @@ impl FrontProtocolHandler {
                 let http_resp = self.encode_r_response(resp, format).await;
                 Ok(http_resp)
             }
-            FrontRequestType::Any => {
+            FrontRequestType::Api
+            | FrontRequestType::S
+            | FrontRequestType::Upload
+            | FrontRequestType::Any => {
                 unreachable!()
             }
         }
@@ -557,6 +711,7 @@ impl FrontProtocolHandler {
 
         let mode = Self::mode_from_request(url)?;
         let flags = Self::flags_from_request(url)?;
+        let media = Self::media_from_request(url)?;
 
         let range = Self::range_from_request(req.request.as_ref())?;
 
@@ -564,6 +719,7 @@ impl FrontProtocolHandler {
         let context = Self::context_from_request(&url)?;
         let group = Self::group_from_request(&url)?;
         let req_path = Self::req_path_from_request(&url)?;
+        let deadline = Self::deadline_from_request(req.request.as_ref())?;
 
         /*
         /object_id
@@ -604,12 +760,14 @@ impl FrontProtocolHandler {
 
                     mode,
                     format,
+                    media: media.clone(),
 
                     referer_objects,
                     context,
                     group,
 
                     flags,
+                    deadline,
                 }
             }
             None => {
@@ -639,12 +797,14 @@ impl FrontProtocolHandler {
 
                     mode,
                     format,
+                    media: media.clone(),
 
                     referer_objects,
                     context,
                     group,
 
                     flags,
+                    deadline,
                 }
             }
         };
@@ -652,6 +812,160 @@ impl FrontProtocolHandler {
         self.service.process_o_request(o_req).await
     }
 
+    fn join_share_path(base: Option<String>, sub_path: Option<String>) -> Option<String> {
+        match (base, sub_path) {
+            (Some(base), Some(sub_path)) => {
+                Some(format!("{}{}", base.trim_end_matches('/'), sub_path))
+            }
+            (base, None) => base,
+            (None, sub_path) => sub_path,
+        }
+    }
+
+    /*
+    /s/{share_id}
+    /s/{share_id}/{sub_path}
+    */
+    async fn process_s_request<State>(
+        &self,
+        mut req: FrontInputHttpRequest<State>,
+        route_param: String,
+        format: FrontRequestObjectFormat,
+    ) -> BuckyResult<tide::Response> {
+        let segs = Self::parse_url_segs(&route_param)?;
+        let url = req.request.url();
+
+        // 子路径不能跳出分享的范围
+        if segs[1..].iter().any(|seg| *seg == "..") {
+            let msg = format!("invalid share url sub path! {}", url);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let sub_path = if segs.len() > 1 {
+            Some(Self::gen_inner_path(&segs[1..]))
+        } else {
+            None
+        };
+
+        let media = Self::media_from_request(url)?;
+        let range = Self::range_from_request(req.request.as_ref())?;
+        let password = Self::share_password_from_request(req.request.as_ref())?;
+
+        let share = self
+            .service
+            .open_share(segs[0], password.as_deref())
+            .await?;
+
+        // 匿名的访问者以创建分享的dec的身份读取目标
+        req.source.set_dec(share.dec_id.clone());
+
+        match share.target {
+            ShareTarget::Object {
+                object_id,
+                inner_path,
+            } => {
+                let o_req = FrontORequest {
+                    source: req.source,
+
+                    req_path: None,
+                    target: vec![],
+
+                    object_id,
+                    inner_path: Self::join_share_path(inner_path, sub_path),
+                    range,
+
+                    mode: share.mode.into(),
+                    format,
+                    media,
+
+                    referer_objects: vec![],
+                    context: None,
+                    group: None,
+
+                    flags: 0,
+                    deadline: None,
+                };
+
+                let resp = self.service.process_o_request(o_req).await?;
+                let http_resp = self.encode_o_response(resp, format).await;
+                Ok(http_resp)
+            }
+            ShareTarget::Path(path) => {
+                let r_req = FrontRRequest {
+                    source: req.source,
+
+                    category: GlobalStateCategory::RootState,
+
+                    target: None,
+                    target_dec_id: Some(share.dec_id),
+
+                    action: GlobalStateAccessorAction::GetObjectByPath,
+                    inner_path: Self::join_share_path(Some(path), sub_path),
+                    range,
+                    page_index: None,
+                    page_size: None,
+
+                    mode: share.mode.into(),
+                    media,
+                    context: None,
+                    group: None,
+
+                    flags: 0,
+                    deadline: None,
+                };
+
+                let resp = self.service.process_r_request(r_req).await?;
+                let http_resp = self.encode_r_response(resp, format).await;
+                Ok(http_resp)
+            }
+        }
+    }
+
+    async fn process_upload_request<State>(
+        &self,
+        mut req: FrontInputHttpRequest<State>,
+        route_param: String,
+    ) -> BuckyResult<FrontUploadResponse> {
+        /*
+        /upload/{dec_id}/{dir_path}
+        dec_id: system或者dec的object_id，root_state的根不支持直接写入
+        */
+        let segs = Self::parse_url_segs(&route_param)?;
+        let url = req.request.url();
+
+        let dec_id = match Self::parse_dec_seg(url, &segs, 0)? {
+            Some(dec_id) => dec_id,
+            None => {
+                let msg = format!("upload to root of global state not supported! {}", url);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+            }
+        };
+
+        if segs[1..].iter().any(|seg| *seg == "." || *seg == "..") {
+            let msg = format!("invalid upload url path! {}", url);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let path = Self::gen_inner_path(&segs[1..]);
+
+        let content_type: String =
+            RequestorHelper::decode_header(&req.request, http_types::headers::CONTENT_TYPE)?;
+        let boundary = parse_multipart_boundary(&content_type)?;
+
+        let upload_req = FrontUploadRequest {
+            source: req.source,
+            dec_id,
+            path,
+            boundary,
+            data: Box::new(req.request.take_body()),
+        };
+
+        self.service.process_upload_request(upload_req).await
+    }
+
     fn parse_dec_seg(
         url: &http_types::Url,
         segs: &Vec<&str>,
@@ -806,6 +1120,7 @@ impl FrontProtocolHandler {
         };
 
         let range = Self::range_from_request(req.request.as_ref())?;
+        let deadline = Self::deadline_from_request(req.request.as_ref())?;
 
         // let mode = Self::mode_from_request(url)?;
         // let flags = Self::flags_from_request(url)?;
@@ -818,6 +1133,8 @@ impl FrontProtocolHandler {
         let mut flags = 0;
         let mut context = None;
         let mut group = None;
+        let mut size = None;
+        let mut quality = None;
 
         let pairs = req.request.url().query_pairs();
         let mut user_pairs = vec![];
@@ -826,7 +1143,7 @@ impl FrontProtocolHandler {
                 "mode" => {
                     mode = FrontRequestGetMode::from_str(v.as_ref())?;
                 }
-                "format" => { /* ignore */ }
+                "format" | "deadline" => { /* ignore */ }
                 "flags" => {
                     flags = u32::from_str(v.as_ref()).map_err(|e| {
                         let msg = format!(
@@ -863,6 +1180,22 @@ impl FrontProtocolHandler {
                 "group" => {
                     group = Some(RequestorHelper::decode_url_param_with_utf8_decoding(k, v)?);
                 }
+                "size" => {
+                    let v = v.as_ref().parse().map_err(|e| {
+                        let msg = format!("invalid size param: {}, {}", v, e);
+                        error!("{}", msg);
+                        BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+                    })?;
+                    size = Some(v);
+                }
+                "quality" => {
+                    let v = v.as_ref().parse().map_err(|e| {
+                        let msg = format!("invalid quality param: {}, {}", v, e);
+                        error!("{}", msg);
+                        BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+                    })?;
+                    quality = Some(v);
+                }
                 _ => {
                     debug!("user global state access url query: {}={}", k, v);
                     user_pairs.push(format!("{}={}", k, v));
@@ -897,10 +1230,12 @@ impl FrontProtocolHandler {
             page_size,
 
             mode,
+            media: MediaTransformParam::new(size, quality)?,
             context,
             group,
 
             flags,
+            deadline,
         };
 
         self.service.process_r_request(r_req).await
@@ -911,7 +1246,42 @@ impl FrontProtocolHandler {
     cyfs://a/{dec-id}/{dir-id}/{inner-path}
     cyfs://a/{dec-id}/{x.x.x}/{inner-path}
     cyfs://a/{dec-id}/local_status
+    cyfs://a/{dec-id}/dependencies
     */
+    // /a/{dec}/api/*，按dec注册的路由反向代理到当前设备上的本地服务
+    async fn process_api_request<State>(
+        &self,
+        req: FrontInputHttpRequest<State>,
+    ) -> BuckyResult<tide::Response> {
+        let dec = req.request.param("dec").map_err(|e| {
+            let msg = format!(
+                "invalid api request dec param! {}, {}",
+                req.request.url(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+        let dec = match Self::parse_object_seg(dec) {
+            Some(id) => FrontARequestDec::DecID(id),
+            None => FrontARequestDec::Name(dec.to_owned()),
+        };
+
+        // 保持原始的url编码转发
+        let path = match req.request.param("must") {
+            Ok(v) => format!("/{}", v),
+            Err(_) => "/".to_owned(),
+        };
+
+        let FrontInputHttpRequest { request, source } = req;
+        let resp = self
+            .service
+            .process_api_request(&source, &dec, &path, request.into())
+            .await?;
+
+        Ok(resp.into())
+    }
+
     async fn process_a_request<State>(
         &self,
         req: FrontInputHttpRequest<State>,
@@ -934,6 +1304,7 @@ impl FrontProtocolHandler {
 
         let goal = match segs[1] {
             "local_status" => FrontARequestGoal::LocalStatus,
+            "dependencies" => FrontARequestGoal::Dependencies,
             _ => {
                 let mut inner_path_pos = 2;
                 let version = match Self::parse_object_seg(segs[1]) {
@@ -970,6 +1341,7 @@ impl FrontProtocolHandler {
         let referer_objects = Self::referer_objects_from_request(url)?;
         let context = Self::context_from_request(url)?;
         let group = Self::group_from_request(url)?;
+        let deadline = Self::deadline_from_request(req.request.as_ref())?;
 
         // TODO now target always be current zone's ood
         let target = self
@@ -1007,6 +1379,7 @@ impl FrontProtocolHandler {
             group,
 
             flags,
+            deadline,
 
             flush_cache,
         };
@@ -1086,6 +1459,12 @@ impl FrontProtocolHandler {
                 }
                 tide::Redirect::new(url).into()
             }
+            FrontAResponse::Json(body) => {
+                let mut http_resp = RequestorHelper::new_response(http_types::StatusCode::Ok);
+                http_resp.set_body(body);
+                http_resp.set_content_type(tide::http::mime::JSON);
+                http_resp.into()
+            }
         }
     }
 }
@@ -1104,4 +1483,27 @@ mod tests {
         let value: String = RequestorHelper::value_from_querys_with_utf8_decoding("req_path", &url).unwrap().unwrap();
         assert_eq!(value, "/a/b?token=xxx&id=xxx");
     }
+
+    #[test]
+    fn test_object_host() {
+        let id = ObjectId::from_str("9tGpLNnSzxs7kX2pbe27adjNjGQTgFzMCR9pDQ4rHRpM").unwrap();
+
+        assert_eq!(parse_front_object_host("o.zone.cyfs.com"), Some(FrontObjectHost::Zone));
+        assert_eq!(parse_front_object_host("o.localhost:1318"), Some(FrontObjectHost::Zone));
+
+        let host = format!("{}.o.zone.cyfs.com", id.to_base36());
+        assert_eq!(parse_front_object_host(&host), Some(FrontObjectHost::Object(id.clone())));
+        let host = format!("{}.o.localhost:1318", id.to_base36());
+        assert_eq!(parse_front_object_host(&host), Some(FrontObjectHost::Object(id.clone())));
+
+        // dec_id的host形式不受影响
+        assert_eq!(parse_front_object_host(&format!("o.{}", id)), None);
+        assert_eq!(parse_front_object_host("o.system"), None);
+
+        assert_eq!(parse_front_object_host("127.0.0.1:1318"), None);
+        assert_eq!(parse_front_object_host("localhost"), None);
+        assert_eq!(parse_front_object_host("www.o.zone.cyfs.com"), None);
+        assert_eq!(parse_front_object_host(&format!("{}.o", id.to_base36())), None);
+        assert_eq!(parse_front_object_host("[::1]:1318"), None);
+    }
 }
\ No newline at end of file
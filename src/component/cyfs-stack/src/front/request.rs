@@ -1,4 +1,5 @@
 use super::def::*;
+use crate::media::MediaTransformParam;
 use cyfs_base::*;
 use cyfs_lib::*;
 
@@ -21,7 +22,11 @@ pub struct FrontORequest {
     pub mode: FrontRequestGetMode,
     pub format: FrontRequestObjectFormat,
 
+    // 媒体文件的缩放和转码参数，只对data模式的file有效
+    pub media: Option<MediaTransformParam>,
+
     pub flags: u32,
+    pub deadline: Option<u64>,
 }
 
 pub struct FrontOResponse {
@@ -51,7 +56,10 @@ pub struct FrontRRequest {
     pub group: Option<String>,
 
     pub mode: FrontRequestGetMode,
+    pub media: Option<MediaTransformParam>,
+
     pub flags: u32,
+    pub deadline: Option<u64>,
 }
 
 pub struct FrontRResponse {
@@ -81,6 +89,7 @@ pub struct FrontNDNRequest {
     pub group: Option<String>,
 
     pub flags: u32,
+    pub deadline: Option<u64>,
 }
 
 impl FrontNDNRequest {
@@ -97,6 +106,7 @@ impl FrontNDNRequest {
             context: req.context,
             group: req.group,
             flags: req.flags,
+            deadline: req.deadline,
         }
     }
 
@@ -128,6 +138,7 @@ impl FrontNDNRequest {
             context: req.context,
             group: req.group,
             flags: req.flags,
+            deadline: req.deadline,
         }
     }
 
@@ -150,6 +161,7 @@ impl FrontNDNRequest {
             context: req.context,
             group: req.group,
             flags: req.flags,
+            deadline: req.deadline,
         }
     }
 }
@@ -193,6 +205,7 @@ pub struct FrontARequestWeb {
 pub enum FrontARequestGoal {
     Web(FrontARequestWeb),
     LocalStatus,
+    Dependencies,
 }
 
 #[derive(Debug)]
@@ -215,6 +228,7 @@ pub struct FrontARequest {
     pub group: Option<String>,
 
     pub flags: u32,
+    pub deadline: Option<u64>,
 
     pub flush_cache: bool,
 }
@@ -222,4 +236,33 @@ pub struct FrontARequest {
 pub enum FrontAResponse {
     Response(FrontOResponse),
     Redirect(String),
+    Json(String),
+}
+
+// multipart/form-data上传，每个带filename的part保存为一个File对象，挂到root_state的path下
+pub struct FrontUploadRequest {
+    pub source: RequestSourceInfo,
+
+    pub dec_id: ObjectId,
+    pub path: String,
+
+    pub boundary: String,
+    pub data: Box<dyn async_std::io::Read + Unpin + Send + Sync>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FrontUploadFile {
+    // 表单字段名
+    pub field: Option<String>,
+    pub filename: String,
+
+    // 在root_state里的完整路径
+    pub path: String,
+    pub object_id: ObjectId,
+    pub len: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FrontUploadResponse {
+    pub files: Vec<FrontUploadFile>,
 }
@@ -1,15 +1,29 @@
 use super::def::*;
+use super::multipart::MultipartFormReader;
 use super::request::*;
-use crate::app::AppInstallStatus;
+use crate::api_gateway::ApiGatewayManager;
+use crate::app::{AppDependenciesStatus, AppInstallStatus};
 use crate::app::AppService;
+use crate::media::{MediaService, MediaTransformParam};
 use crate::ndn::NDNInputProcessorRef;
-use crate::ndn_api::NDNForwardObjectData;
+use crate::ndn_api::{ChunkStreamBuilder, NDNForwardObjectData, STREAM_CHUNK_SIZE};
 use crate::non::NONInputProcessorRef;
 use crate::resolver::OodResolver;
-use crate::root_state::GlobalStateAccessorInputProcessorRef;
+use crate::rmeta_api::GlobalStateMetaLocalService;
+use crate::root_state::{
+    GlobalStateAccessorInputProcessorRef, GlobalStateInputProcessorRef,
+    GlobalStateOutputTransformer,
+};
+use crate::share::ShareManager;
 use cyfs_base::*;
 use cyfs_lib::*;
 
+use std::str::FromStr;
+use std::sync::Arc;
+
+// 单次上传的文件数上限
+const FRONT_UPLOAD_MAX_FILES: usize = 256;
+
 enum GlobalStateResponse {
     Object(RootStateAccessorGetObjectByPathInputResponse),
     List(RootStateAccessorListInputResponse),
@@ -22,9 +36,20 @@ pub(crate) struct FrontService {
     root_state: GlobalStateAccessorInputProcessorRef,
     local_cache: GlobalStateAccessorInputProcessorRef,
 
+    // 上传时写root_state，经过acl和rmeta的检查
+    root_state_processor: GlobalStateInputProcessorRef,
+
     app: AppService,
 
     ood_resolver: OodResolver,
+
+    global_state_meta: GlobalStateMetaLocalService,
+
+    media: MediaService,
+
+    share: ShareManager,
+
+    api_gateway: ApiGatewayManager,
 }
 
 impl FrontService {
@@ -33,26 +58,239 @@ impl FrontService {
         ndn: NDNInputProcessorRef,
         root_state: GlobalStateAccessorInputProcessorRef,
         local_cache: GlobalStateAccessorInputProcessorRef,
+        root_state_processor: GlobalStateInputProcessorRef,
         app: AppService,
         ood_resolver: OodResolver,
+        global_state_meta: GlobalStateMetaLocalService,
+        media: MediaService,
+        share: ShareManager,
+        api_gateway: ApiGatewayManager,
     ) -> Self {
         Self {
             non,
             ndn,
             root_state,
             local_cache,
+            root_state_processor,
             app,
             ood_resolver,
+            global_state_meta,
+            media,
+            share,
+            api_gateway,
+        }
+    }
+
+    // 分享链接，校验通过后返回分享记录，由调用方以分享的dec身份访问目标
+    pub async fn open_share(&self, id: &str, password: Option<&str>) -> BuckyResult<ShareRecord> {
+        info!("will open share: id={}", id);
+
+        self.share.open(id, password).await
+    }
+
+    pub async fn process_upload_request(
+        &self,
+        req: FrontUploadRequest,
+    ) -> BuckyResult<FrontUploadResponse> {
+        info!(
+            "will process upload request: source={}, dec={}, path={}",
+            req.source, req.dec_id, req.path
+        );
+
+        // 先创建op_env，写权限在读取body之前就完成检查
+        let processor = GlobalStateOutputTransformer::new(
+            self.root_state_processor.clone(),
+            req.source.clone(),
+        );
+        let stub = GlobalStateStub::new(processor, None, Some(req.dec_id.clone()));
+        let access = RootStateOpEnvAccess::new(&req.path, AccessPermissions::WriteOnly);
+        let op_env = stub.create_path_op_env_with_access(Some(access)).await?;
+
+        let mut reader = MultipartFormReader::new(req.data, &req.boundary);
+        match self
+            .upload_parts(&req.source, &req.path, &mut reader, &op_env)
+            .await
+        {
+            Ok(files) => {
+                op_env.commit().await?;
+
+                info!(
+                    "upload complete! dec={}, path={}, files={}",
+                    req.dec_id,
+                    req.path,
+                    files.len()
+                );
+                Ok(FrontUploadResponse { files })
+            }
+            Err(e) => {
+                let _ = op_env.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn upload_parts(
+        &self,
+        source: &RequestSourceInfo,
+        path: &str,
+        reader: &mut MultipartFormReader<Box<dyn async_std::io::Read + Unpin + Send + Sync>>,
+        op_env: &PathOpEnvStub,
+    ) -> BuckyResult<Vec<FrontUploadFile>> {
+        let mut files = vec![];
+        while let Some(part) = reader.next_part().await? {
+            // 普通的表单字段直接忽略
+            let filename = match Self::upload_filename(part.filename.as_deref())? {
+                Some(filename) => filename,
+                None => {
+                    debug!("upload ignore form field without filename: {:?}", part.name);
+                    continue;
+                }
+            };
+
+            if files.len() >= FRONT_UPLOAD_MAX_FILES {
+                let msg = format!(
+                    "upload files exceed limit! path={}, limit={}",
+                    path, FRONT_UPLOAD_MAX_FILES
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            let file = self.upload_file(source, reader).await?;
+            let file_id = file.desc().calculate_id();
+            op_env
+                .set_with_key(path, &filename, &file_id, None, true)
+                .await?;
+
+            let file_path = format!("{}/{}", path.trim_end_matches('/'), filename);
+            info!(
+                "upload file: field={:?}, path={}, file={}, len={}",
+                part.name,
+                file_path,
+                file_id,
+                file.len()
+            );
+
+            files.push(FrontUploadFile {
+                field: part.name,
+                filename,
+                path: file_path,
+                object_id: file_id,
+                len: file.len(),
+            });
+        }
+
+        Ok(files)
+    }
+
+    // 把当前part的数据切成chunk保存，生成的File对象也保存到noc
+    async fn upload_file(
+        &self,
+        source: &RequestSourceInfo,
+        reader: &mut MultipartFormReader<Box<dyn async_std::io::Read + Unpin + Send + Sync>>,
+    ) -> BuckyResult<File> {
+        let common = NDNInputRequestCommon {
+            req_path: None,
+            source: source.clone(),
+            level: NDNAPILevel::Router,
+            referer_object: vec![],
+            target: None,
+            flags: 0,
+            deadline: None,
+            user_data: None,
+        };
+
+        let mut builder = ChunkStreamBuilder::new(STREAM_CHUNK_SIZE);
+        while let Some((chunk_id, buf)) = builder.next_chunk(reader).await? {
+            let length = buf.len() as u64;
+            let put_req = NDNPutDataInputRequest {
+                common: common.clone(),
+                object_id: chunk_id.object_id(),
+
+                data_type: NDNDataType::Mem,
+                length,
+                data: Box::new(async_std::io::Cursor::new(buf)),
+            };
+
+            self.ndn.put_data(put_req).await.map_err(|e| {
+                error!("upload put chunk failed! chunk={}, {}", chunk_id, e);
+                e
+            })?;
         }
+
+        let file = builder.finish(None);
+        let file_id = file.desc().calculate_id();
+
+        let object_raw = file.to_vec()?;
+        let object = Arc::new(AnyNamedObject::Standard(StandardObject::File(file.clone())));
+        let put_req = NONPutObjectInputRequest {
+            common: NONInputRequestCommon {
+                req_path: None,
+                source: source.clone(),
+                level: NONAPILevel::Router,
+                target: None,
+                flags: 0,
+                deadline: None,
+                request_id: None,
+            },
+            object: NONObjectInfo::new(file_id, object_raw, Some(object)),
+            access: None,
+            precondition: None,
+        };
+
+        self.non.put_object(put_req).await.map_err(|e| {
+            error!("upload put file object failed! file={}, {}", file_id, e);
+            e
+        })?;
+
+        Ok(file)
     }
 
-    pub async fn process_o_request(&self, req: FrontORequest) -> BuckyResult<FrontOResponse> {
+    // 浏览器可能带上客户端的完整路径，只保留最后一段
+    fn upload_filename(filename: Option<&str>) -> BuckyResult<Option<String>> {
+        let filename = match filename {
+            Some(v) => v
+                .rsplit(|c| c == '/' || c == '\\')
+                .next()
+                .unwrap_or("")
+                .trim(),
+            None => return Ok(None),
+        };
+
+        if filename.is_empty() {
+            return Ok(None);
+        }
+
+        if filename == "." || filename == ".." {
+            let msg = format!("invalid upload filename: {}", filename);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(Some(filename.to_owned()))
+    }
+
+    pub async fn process_o_request(&self, mut req: FrontORequest) -> BuckyResult<FrontOResponse> {
         info!("will process o request: {:?}", req);
 
+        // the links only valid on current stack, the remote target will resolve by itself
+        if req.target.is_empty() {
+            if let Some(req_path) = &req.req_path {
+                let mut req_path = RequestGlobalStatePath::from_str(req_path)?;
+                if self
+                    .global_state_meta
+                    .resolve_req_path(&req.source, &mut req_path)
+                    .await?
+                {
+                    req.req_path = Some(req_path.format_string());
+                }
+            }
+        }
+
         let resp = match req.object_id.obj_type_code() {
             ObjectTypeCode::Chunk => {
                 // verify the mode
-                let mode = Self::select_mode(&req.mode, &req.object_id)?;
+                let mode = Self::select_mode(&req.mode, &req.format, &req.object_id)?;
                 assert_eq!(mode, FrontRequestGetMode::Data);
 
                 let ndn_req = FrontNDNRequest::new_o_chunk(req);
@@ -67,7 +305,7 @@ impl FrontService {
                 let non_resp = self.process_get_object(req.clone()).await?;
 
                 // decide the mode
-                let mode = Self::select_mode(&req.mode, &non_resp.object.object_id)?;
+                let mode = Self::select_mode(&req.mode, &req.format, &non_resp.object.object_id)?;
 
                 match mode {
                     FrontRequestGetMode::Object => FrontOResponse {
@@ -75,8 +313,9 @@ impl FrontService {
                         data: None,
                     },
                     FrontRequestGetMode::Data => {
+                        let media = req.media.clone();
                         let ndn_req = FrontNDNRequest::new_o_file(req, non_resp.object.clone());
-                        let ndn_resp = self.process_get_file(ndn_req).await?;
+                        let ndn_resp = self.process_get_file_data(ndn_req, media).await?;
 
                         FrontOResponse {
                             object: Some(non_resp),
@@ -115,6 +354,8 @@ impl FrontService {
             level: NONAPILevel::Router,
             target,
             flags: req.flags,
+            deadline: req.deadline,
+            request_id: None,
         };
 
         let non_req = NONGetObjectInputRequest {
@@ -145,6 +386,7 @@ impl FrontService {
             referer_object: vec![],
             target,
             flags: req.flags,
+            deadline: req.deadline,
             user_data: None,
         };
 
@@ -161,6 +403,35 @@ impl FrontService {
         self.ndn.get_data(ndn_req).await
     }
 
+    // 带媒体参数时返回转换后的衍生数据，否则返回原始数据
+    async fn process_get_file_data(
+        &self,
+        req: FrontNDNRequest,
+        media: Option<MediaTransformParam>,
+    ) -> BuckyResult<NDNGetDataInputResponse> {
+        let media = match media {
+            Some(media) => media,
+            None => return self.process_get_file(req).await,
+        };
+
+        if req.range.is_some() {
+            let msg = format!(
+                "range not support with media params! file={}",
+                req.object.object_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+        }
+
+        let file_id = req.object.object_id.clone();
+        if let Some(resp) = self.media.get_cached(&file_id, &media).await? {
+            return Ok(resp);
+        }
+
+        let source = self.process_get_file(req).await?;
+        self.media.transcode(&file_id, source, &media).await
+    }
+
     async fn process_get_file(&self, req: FrontNDNRequest) -> BuckyResult<NDNGetDataInputResponse> {
         assert_eq!(req.object.object_id.obj_type_code(), ObjectTypeCode::File);
 
@@ -191,6 +462,7 @@ impl FrontService {
             referer_object: vec![],
             target,
             flags: req.flags,
+            deadline: req.deadline,
             user_data: Some(data.to_any()),
         };
 
@@ -292,8 +564,22 @@ impl FrontService {
 
     fn select_mode(
         mode: &FrontRequestGetMode,
+        format: &FrontRequestObjectFormat,
         object_id: &ObjectId,
     ) -> BuckyResult<FrontRequestGetMode> {
+        // json格式只能返回对象本身，file对象默认也不再返回数据
+        let mode = match (format, mode) {
+            (FrontRequestObjectFormat::Json, FrontRequestGetMode::Default) => {
+                &FrontRequestGetMode::Object
+            }
+            (FrontRequestObjectFormat::Json, FrontRequestGetMode::Data) => {
+                let msg = format!("json format not support data mode! object={}", object_id);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+            }
+            _ => mode,
+        };
+
         let mode = match mode {
             FrontRequestGetMode::Object => {
                 if object_id.obj_type_code() == ObjectTypeCode::Chunk {
@@ -336,9 +622,13 @@ impl FrontService {
         }
     }
 
-    pub async fn process_r_request(&self, req: FrontRRequest) -> BuckyResult<FrontRResponse> {
+    pub async fn process_r_request(&self, mut req: FrontRRequest) -> BuckyResult<FrontRResponse> {
         info!("will process r request: {:?}", req);
 
+        if req.target.is_none() {
+            self.resolve_r_request_link(&mut req).await?;
+        }
+
         let state_resp = self.process_global_state_request(req.clone()).await?;
 
         let resp = match state_resp {
@@ -346,8 +636,11 @@ impl FrontService {
                 match state_resp.object.object.object_id.obj_type_code() {
                     ObjectTypeCode::Chunk => {
                         // verify the mode
-                        let mode =
-                            Self::select_mode(&req.mode, &state_resp.object.object.object_id)?;
+                        let mode = Self::select_mode(
+                            &req.mode,
+                            &req.format,
+                            &state_resp.object.object.object_id,
+                        )?;
                         assert_eq!(mode, FrontRequestGetMode::Data);
 
                         let ndn_req =
@@ -364,8 +657,11 @@ impl FrontService {
                     }
                     _ => {
                         // decide the mode
-                        let mode =
-                            Self::select_mode(&req.mode, &state_resp.object.object.object_id)?;
+                        let mode = Self::select_mode(
+                            &req.mode,
+                            &req.format,
+                            &state_resp.object.object.object_id,
+                        )?;
 
                         match mode {
                             FrontRequestGetMode::Object => FrontRResponse {
@@ -376,11 +672,12 @@ impl FrontService {
                                 list: None,
                             },
                             FrontRequestGetMode::Data => {
+                                let media = req.media.clone();
                                 let ndn_req = FrontNDNRequest::new_r_resp(
                                     req,
                                     &state_resp
                                 );
-                                let ndn_resp = self.process_get_file(ndn_req).await?;
+                                let ndn_resp = self.process_get_file_data(ndn_req, media).await?;
 
                                 FrontRResponse {
                                     object: Some(state_resp.object),
@@ -407,6 +704,28 @@ impl FrontService {
         Ok(resp)
     }
 
+    async fn resolve_r_request_link(&self, req: &mut FrontRRequest) -> BuckyResult<()> {
+        let inner_path = match &req.inner_path {
+            Some(path) if path.starts_with('/') && path != "/" => path,
+            _ => return Ok(()),
+        };
+
+        let target_dec_id = req.target_dec_id.as_ref().unwrap_or(&req.source.dec);
+        let ret = self
+            .global_state_meta
+            .resolve_link(target_dec_id, req.category, inner_path)
+            .await?;
+        if let Some(dest) = ret {
+            info!(
+                "resolve r request path link: dec={}, {} -> {}",
+                target_dec_id, inner_path, dest
+            );
+            req.inner_path = Some(dest);
+        }
+
+        Ok(())
+    }
+
     async fn process_global_state_request(
         &self,
         req: FrontRRequest,
@@ -442,6 +761,7 @@ impl FrontService {
 
                     page_index: req.page_index,
                     page_size: req.page_size,
+                    cursor: None,
                 };
 
                 processor
@@ -452,6 +772,22 @@ impl FrontService {
         }
     }
 
+    pub async fn process_api_request(
+        &self,
+        source: &RequestSourceInfo,
+        dec: &FrontARequestDec,
+        path: &str,
+        req: http_types::Request,
+    ) -> BuckyResult<http_types::Response> {
+        let dec_id = self.app.get_app(dec).await?.ok_or_else(|| {
+            let msg = format!("api request but dec not found! dec={:?}", dec);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        self.api_gateway.proxy(source, &dec_id, path, req).await
+    }
+
     pub async fn process_a_request(&self, req: FrontARequest) -> BuckyResult<FrontAResponse> {
         info!("will process a request: {:?}", req);
 
@@ -464,7 +800,19 @@ impl FrontService {
             FrontARequestGoal::Web(web_req) => {
                 let ret = self.app.get_app_web_dir(&req.dec, &web_req.version, req.flush_cache).await?;
                 match ret {
-                    AppInstallStatus::Installed((_dec_id, dir_id)) => {
+                    AppInstallStatus::Installed((dec_id, dir_id)) => {
+                        // 依赖的app没有安装或者没有运行时，跳转到app详情页
+                        let dependencies = self.app.get_app_dependencies(&dec_id, req.flush_cache).await?;
+                        if !dependencies.ready {
+                            let url = self.gen_app_dependency_not_ready_redirect_url(
+                                &dependencies,
+                                req.dec.as_name(),
+                                &web_req.version,
+                                &req.origin_url,
+                            );
+                            return Ok(FrontAResponse::Redirect(url));
+                        }
+
                         let o_req = FrontORequest {
                             source: req.source,
                             req_path: None,
@@ -476,12 +824,14 @@ impl FrontService {
 
                             mode: req.mode,
                             format: req.format,
+                            media: None,
 
                             referer_objects: req.referer_objects,
                             context: req.context,
                             group: req.group,
 
                             flags: req.flags,
+                            deadline: req.deadline,
                         };
 
                         // let url = self.gen_o_redirect_url(&o_req, &req.origin_url);
@@ -493,10 +843,12 @@ impl FrontService {
                         let dec_id = dec.as_dec_id().or(req.dec.as_dec_id());
                         let name = dec.as_name().or(req.dec.as_name());
 
-                        let url = self.gen_app_not_installed_redirect_url(
+                        let url = self.gen_app_detail_redirect_url(
+                            "not_installed",
                             dec_id,
                             name,
                             Some(&web_req.version),
+                            vec![],
                             &req.origin_url,
                         );
                         FrontAResponse::Redirect(url)
@@ -518,12 +870,14 @@ impl FrontService {
 
                             mode: req.mode,
                             format: req.format,
+                            media: None,
 
                             referer_objects: req.referer_objects,
                             context: req.context,
                             group: req.group,
                             
                             flags: req.flags,
+                            deadline: req.deadline,
                         };
 
                         let o_resp = self.process_o_request(o_req).await?;
@@ -533,10 +887,31 @@ impl FrontService {
                         let dec_id = dec.as_dec_id().or(req.dec.as_dec_id());
                         let name = dec.as_name().or(req.dec.as_name());
 
-                        let url = self.gen_app_not_installed_redirect_url(
+                        let url = self.gen_app_detail_redirect_url(
+                            "not_installed",
                             dec_id,
                             name,
                             None,
+                            vec![],
+                            &req.origin_url,
+                        );
+                        FrontAResponse::Redirect(url)
+                    }
+                }
+            }
+            FrontARequestGoal::Dependencies => {
+                match self.app.get_app(&req.dec).await? {
+                    Some(dec_id) => {
+                        let status = self.app.get_app_dependencies(&dec_id, req.flush_cache).await?;
+                        FrontAResponse::Json(serde_json::to_string(&status).unwrap())
+                    }
+                    None => {
+                        let url = self.gen_app_detail_redirect_url(
+                            "not_installed",
+                            req.dec.as_dec_id(),
+                            req.dec.as_name(),
+                            None,
+                            vec![],
                             &req.origin_url,
                         );
                         FrontAResponse::Redirect(url)
@@ -604,17 +979,41 @@ impl FrontService {
         url
     }
 
-    fn gen_app_not_installed_redirect_url(
+    // 依赖没有就绪时，把没有就绪的依赖列表通过dependencies参数带给详情页
+    fn gen_app_dependency_not_ready_redirect_url(
+        &self,
+        status: &AppDependenciesStatus,
+        name: Option<&str>,
+        version: &FrontARequestVersion,
+        origin_url: &http_types::Url,
+    ) -> String {
+        let mut list: Vec<String> = status.not_ready().map(|v| v.dec_id.to_string()).collect();
+        list.sort();
+        list.dedup();
+
+        self.gen_app_detail_redirect_url(
+            "dependency_not_ready",
+            Some(&status.dec_id),
+            name,
+            Some(version),
+            vec![format!("dependencies={}", list.join(","))],
+            origin_url,
+        )
+    }
+
+    fn gen_app_detail_redirect_url(
         &self,
+        error: &str,
         dec_id: Option<&ObjectId>,
         name: Option<&str>,
         version: Option<&FrontARequestVersion>,
+        extra_querys: Vec<String>,
         origin_url: &http_types::Url,
     ) -> String {
         let mut querys = vec![];
 
         // FIXME distinguish between not installed and version unmatch
-        querys.push("error=not_installed".to_owned());
+        querys.push(format!("error={}", error));
 
         if let Some(dec_id) = dec_id {
             querys.push(format!("dec_id={}", dec_id));
@@ -640,6 +1039,8 @@ impl FrontService {
             }
         }
 
+        querys.extend(extra_querys);
+
         // merge origin query pairs
         for (key, value) in origin_url.query_pairs() {
             let s = format!("{}=", key);
@@ -59,11 +59,18 @@ impl BrowserSanboxHttpServer {
     cyfs://o/
     cyfs://{object-id}
     cyfs://{name}
+    {object-id}.o.{zone-domain}
+    o.{zone-domain}
     */
     fn parse_host(host: &str) -> BuckyResult<RequestSource> {
         if host == "static" {
             return Ok(RequestSource::System);
         }
+
+        // Per-object origins serve untrusted content, always treat as anonymous dec_id
+        if crate::front::parse_front_object_host(host).is_some() {
+            return Ok(RequestSource::Dec(cyfs_core::get_anonymous_dec_app().to_owned()));
+        }
         
         // Parse host in a|o|r|l.dec_id mode
         if let Some((_, dec_id)) = crate::front::parse_front_host_with_dec_id(host)? {
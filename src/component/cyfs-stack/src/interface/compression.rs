@@ -0,0 +1,200 @@
+use async_compression::futures::bufread::{GzipEncoder, ZstdEncoder};
+use async_std::io::BufReader;
+use http_types::headers::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, VARY};
+use http_types::{Body, Method};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HttpContentEncoding {
+    Gzip,
+    Zstd,
+}
+
+impl HttpContentEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpCompressionConfig {
+    pub enable: bool,
+
+    // 小于这个大小的响应不压缩，长度未知的流式响应总是压缩
+    pub threshold: usize,
+
+    // 服务端优先使用的编码顺序，和请求的accept-encoding取交集
+    pub encodings: Vec<HttpContentEncoding>,
+
+    // 允许压缩的content-type，支持text/*的形式
+    pub mime_types: Vec<String>,
+
+    // 只对这些路径前缀下的响应压缩
+    pub paths: Vec<String>,
+}
+
+impl Default for HttpCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            threshold: 1024,
+            encodings: vec![HttpContentEncoding::Zstd, HttpContentEncoding::Gzip],
+            mime_types: vec![
+                "text/*".to_owned(),
+                "application/json".to_owned(),
+                "application/javascript".to_owned(),
+                "application/xml".to_owned(),
+                "image/svg+xml".to_owned(),
+            ],
+            paths: vec!["/o/".to_owned(), "/r/".to_owned(), "/non".to_owned()],
+        }
+    }
+}
+
+impl HttpCompressionConfig {
+    // 从accept-encoding里面选择服务端优先级最高的编码，q=0的表示不接受
+    fn select_encoding(&self, accept: &str) -> Option<HttpContentEncoding> {
+        let mut accepted = vec![];
+        for item in accept.split(',') {
+            let mut parts = item.split(';');
+            let name = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+            let q = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .next()
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if !name.is_empty() {
+                accepted.push((name, q));
+            }
+        }
+
+        let quality = |name: &str| {
+            accepted
+                .iter()
+                .find(|(v, _)| v == name)
+                .or_else(|| accepted.iter().find(|(v, _)| v == "*"))
+                .map(|(_, q)| *q)
+                .unwrap_or(0.0)
+        };
+
+        self.encodings
+            .iter()
+            .find(|encoding| quality(encoding.as_str()) > 0.0)
+            .cloned()
+    }
+
+    fn is_mime_allowed(&self, essence: &str) -> bool {
+        self.mime_types.iter().any(|v| match v.strip_suffix("/*") {
+            Some(prefix) => essence
+                .split('/')
+                .next()
+                .map(|t| t == prefix)
+                .unwrap_or(false),
+            None => v == essence,
+        })
+    }
+
+    fn is_path_allowed(&self, path: &str) -> bool {
+        self.paths.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
+
+// 根据请求的accept-encoding对响应做流式压缩
+pub(crate) struct HttpCompressionMiddleware {
+    config: HttpCompressionConfig,
+}
+
+impl HttpCompressionMiddleware {
+    pub fn new(config: HttpCompressionConfig) -> Self {
+        Self { config }
+    }
+
+    fn check_response(&self, resp: &tide::Response) -> bool {
+        if !resp.status().is_success()
+            || resp.status() == http_types::StatusCode::NoContent
+            || resp.header(CONTENT_ENCODING).is_some()
+            || resp.header(CONTENT_RANGE).is_some()
+        {
+            return false;
+        }
+
+        if let Some(len) = resp.len() {
+            if len < self.config.threshold {
+                return false;
+            }
+        }
+
+        match resp.content_type() {
+            Some(mime) => self.config.is_mime_allowed(mime.essence()),
+            None => false,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> tide::Middleware<State> for HttpCompressionMiddleware {
+    async fn handle(&self, req: tide::Request<State>, next: tide::Next<'_, State>) -> tide::Result {
+        let encoding = if req.method() != Method::Head && self.config.is_path_allowed(req.url().path()) {
+            req.header(ACCEPT_ENCODING)
+                .and_then(|v| self.config.select_encoding(v.last().as_str()))
+        } else {
+            None
+        };
+
+        let mut resp = next.run(req).await;
+        let encoding = match encoding {
+            Some(encoding) if self.check_response(&resp) => encoding,
+            _ => return Ok(resp),
+        };
+
+        let body = resp.take_body();
+        let mime = body.mime().clone();
+        let mut body = match encoding {
+            HttpContentEncoding::Gzip => Body::from_reader(BufReader::new(GzipEncoder::new(body)), None),
+            HttpContentEncoding::Zstd => Body::from_reader(BufReader::new(ZstdEncoder::new(body)), None),
+        };
+        body.set_mime(mime);
+
+        resp.set_body(body);
+        resp.remove_header(CONTENT_LENGTH);
+        resp.insert_header(CONTENT_ENCODING, encoding.as_str());
+        resp.append_header(VARY, ACCEPT_ENCODING.as_str());
+
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_select_encoding() {
+        let config = HttpCompressionConfig::default();
+        assert_eq!(
+            config.select_encoding("gzip, deflate, br"),
+            Some(HttpContentEncoding::Gzip)
+        );
+        assert_eq!(
+            config.select_encoding("gzip;q=0.5, zstd"),
+            Some(HttpContentEncoding::Zstd)
+        );
+        assert_eq!(
+            config.select_encoding("zstd;q=0, gzip"),
+            Some(HttpContentEncoding::Gzip)
+        );
+        assert_eq!(config.select_encoding("*;q=0"), None);
+        assert_eq!(config.select_encoding("identity"), None);
+        assert_eq!(config.select_encoding("*"), Some(HttpContentEncoding::Zstd));
+
+        assert!(config.is_mime_allowed("text/html"));
+        assert!(config.is_mime_allowed("application/json"));
+        assert!(!config.is_mime_allowed("image/png"));
+
+        assert!(config.is_path_allowed("/non/object"));
+        assert!(config.is_path_allowed("/o/5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty"));
+        assert!(!config.is_path_allowed("/ndn/chunk"));
+    }
+}
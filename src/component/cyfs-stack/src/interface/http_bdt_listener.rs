@@ -1,4 +1,5 @@
 use super::http_server::{HttpRequestSource, HttpServerHandlerRef};
+use super::limits::HttpConnectionLimiter;
 use super::ObjectListener;
 use cyfs_base::{BuckyError, BuckyResult};
 use cyfs_lib::RequestProtocol;
@@ -8,6 +9,7 @@ use async_std::stream::StreamExt;
 use async_std::task;
 use async_trait::async_trait;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 pub(super) struct ObjectHttpBdtListenerImpl {
@@ -16,16 +18,25 @@ pub(super) struct ObjectHttpBdtListenerImpl {
     listen: String,
 
     server: HttpServerHandlerRef,
+
+    // 为None时不限制连接，zone内部的同步接口使用
+    limiter: Option<HttpConnectionLimiter>,
 }
 
 impl ObjectHttpBdtListenerImpl {
-    pub fn new(bdt_stack: StackGuard, vport: u16, server: HttpServerHandlerRef) -> Self {
+    pub fn new(
+        bdt_stack: StackGuard,
+        vport: u16,
+        server: HttpServerHandlerRef,
+        limiter: Option<HttpConnectionLimiter>,
+    ) -> Self {
         let listen = format!("{}:{}", bdt_stack.local_device_id().to_string(), vport);
         Self {
             bdt_stack,
             vport,
             listen,
             server,
+            limiter,
         }
     }
 }
@@ -59,8 +70,13 @@ impl ObjectListener for ObjectHttpBdtListener {
 }
 
 impl ObjectHttpBdtListener {
-    pub fn new(bdt_stack: StackGuard, vport: u16, server: HttpServerHandlerRef) -> Self {
-        let inner = ObjectHttpBdtListenerImpl::new(bdt_stack, vport, server);
+    pub fn new(
+        bdt_stack: StackGuard,
+        vport: u16,
+        server: HttpServerHandlerRef,
+        limiter: Option<HttpConnectionLimiter>,
+    ) -> Self {
+        let inner = ObjectHttpBdtListenerImpl::new(bdt_stack, vport, server, limiter);
         Self(Arc::new(inner))
     }
 
@@ -146,6 +162,13 @@ impl ObjectHttpBdtListener {
             self.0.listen, remote_addr, seq,
         );
 
+        // 超出连接上限或者被封禁的来源直接断开，不再确认连接
+        let conn_source = HttpRequestSource::Remote(remote_addr.clone());
+        let guard = match &self.0.limiter {
+            Some(limiter) => Some(limiter.acquire(&conn_source)?),
+            None => None,
+        };
+
         if let Err(e) = stream.confirm(&vec![]).await {
             error!(
                 "bdt stream confirm error! remote={:?}, seq={:?}, {}",
@@ -159,10 +182,16 @@ impl ObjectHttpBdtListener {
         let device_id = remote_addr.0.to_string();
         let device_id_str = device_id.as_str();
         let remote_addr_ref = &remote_addr;
+        let requests = AtomicU64::new(0);
+        let requests_ref = &requests;
     
-        let opts = async_h1::ServerOptions::default();
+        let opts = match &self.0.limiter {
+            Some(limiter) => limiter.config().server_options(),
+            None => async_h1::ServerOptions::default(),
+        };
         let ret = async_h1::accept_with_opts(stream, |mut req| async move {
             let begin = std::time::Instant::now();
+            requests_ref.fetch_add(1, Ordering::SeqCst);
 
             info!(
                 "recv bdt http request: source={}, seq={:?}, method={}, url={}, len={:?}",
@@ -216,6 +245,10 @@ impl ObjectHttpBdtListener {
         }, opts)
         .await;
 
+        if let Some(guard) = guard {
+            guard.check_header_timeout(&conn_source, requests.load(Ordering::SeqCst));
+        }
+
         if let Err(e) = ret {
             error!(
                 "bdt http accept error, err={}, addr={}, remote={:?}, seq={:?}, during={}",
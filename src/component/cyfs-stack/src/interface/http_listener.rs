@@ -1,18 +1,36 @@
+use super::compression::{HttpCompressionConfig, HttpCompressionMiddleware};
 use crate::acl::AclManagerRef;
+use crate::admin_confirm_api::{AdminConfirmRequestHandler, AdminConfirmRequestHandlerEndpoint};
+use crate::api_gateway_api::{ApiGatewayRequestHandler, ApiGatewayRequestHandlerEndpoint};
+use crate::bandwidth_api::{BandwidthRequestHandler, BandwidthRequestHandlerEndpoint};
+use crate::contacts_api::{ContactsRequestHandler, ContactsRequestHandlerEndpoint};
 use crate::crypto_api::*;
+use crate::dec_config_api::{DecConfigRequestHandler, DecConfigRequestHandlerEndpoint};
 use crate::front::{FrontProtocolHandler, FrontRequestHandlerEndpoint};
 use crate::group_api::{GroupRequestHandler, GroupRequestHandlerEndpoint, GroupService};
+use crate::migration::{ZoneMigrationRequestHandler, ZoneMigrationRequestHandlerEndpoint};
 use crate::name::NameResolver;
 use crate::ndn_api::*;
 use crate::non_api::*;
+use crate::object_history_api::{ObjectHistoryRequestHandler, ObjectHistoryRequestHandlerEndpoint};
+use crate::object_pack::{ObjectPackRequestHandler, ObjectPackRequestHandlerEndpoint};
+use crate::object_refs_api::{ObjectRefsRequestHandler, ObjectRefsRequestHandlerEndpoint};
+use crate::queue_api::{QueueRequestHandler, QueueRequestHandlerEndpoint};
 use crate::rmeta_api::*;
 use crate::root_state_api::*;
 use crate::router_handler::{
     RouterHandlerHttpHandler, RouterHandlerRequestHandlerEndpoint, RouterHandlersManager,
 };
+use crate::schedule_api::{ScheduleRequestHandler, ScheduleRequestHandlerEndpoint};
+use crate::search_api::{SearchRequestHandler, SearchRequestHandlerEndpoint};
+use crate::service_discovery_api::{
+    ServiceDiscoveryRequestHandler, ServiceDiscoveryRequestHandlerEndpoint,
+};
+use crate::share_api::{ShareRequestHandler, ShareRequestHandlerEndpoint};
 use crate::stack::ObjectServices;
 use crate::sync::*;
 use crate::trans_api::{TransRequestHandler, TransRequestHandlerEndpoint};
+use crate::trash_api::{TrashRequestHandler, TrashRequestHandlerEndpoint};
 use crate::util_api::*;
 use crate::zone::ZoneManagerRef;
 use cyfs_base::*;
@@ -20,7 +38,7 @@ use cyfs_lib::{GlobalStateCategory, RequestProtocol, RequestorHelper};
 
 use std::sync::Arc;
 
-fn new_server() -> ::tide::Server<()> {
+fn new_server(compression: Option<&HttpCompressionConfig>) -> ::tide::Server<()> {
     use http_types::headers::HeaderValue;
     use tide::security::{CorsMiddleware, Origin};
 
@@ -38,6 +56,12 @@ fn new_server() -> ::tide::Server<()> {
         .expose_headers("*".parse::<HeaderValue>().unwrap());
     server.with(cors);
 
+    if let Some(compression) = compression {
+        if compression.enable {
+            server.with(HttpCompressionMiddleware::new(compression.clone()));
+        }
+    }
+
     server
 }
 
@@ -72,13 +96,14 @@ impl ObjectHttpListener {
         sync_server: Option<&Arc<ZoneSyncServer>>,
         sync_client: Option<&Arc<DeviceSyncClient>>,
         root_state: &GlobalStateService,
-        local_cache: &GlobalStateLocalService,
+        local_cache: &LocalCacheNamespaceManager,
         global_state_meta: &GlobalStateMetaService,
         name_resolver: &NameResolver,
         zone_manager: &ZoneManagerRef,
         group_service: &GroupService,
+        compression: &HttpCompressionConfig,
     ) -> Self {
-        let mut server = new_server();
+        let mut server = new_server(Some(compression));
 
         default_handler(&mut server);
 
@@ -104,6 +129,17 @@ impl ObjectHttpListener {
                     &mut server,
                 );
             }
+
+            // object pack import/export
+            let handler = ObjectPackRequestHandler::new(services.object_pack_manager.clone());
+            ObjectPackRequestHandlerEndpoint::register_server(&handler, &mut server);
+
+            // ood迁移
+            let handler = ZoneMigrationRequestHandler::new(
+                services.migration_manager.clone(),
+                zone_manager.clone(),
+            );
+            ZoneMigrationRequestHandlerEndpoint::register_server(&handler, &mut server);
         }
 
         // sync提供的对外服务
@@ -117,7 +153,10 @@ impl ObjectHttpListener {
         }
 
         // root_state
-        let handler = GlobalStateRequestHandler::new(root_state.clone_global_state_processor());
+        let concurrency = &services.concurrency_manager;
+        let handler = GlobalStateRequestHandler::new(
+            concurrency.wrap_global_state_processor(root_state.clone_global_state_processor()),
+        );
         GlobalStateRequestHandlerEndpoint::register_server(
             zone_manager,
             &protocol,
@@ -126,7 +165,9 @@ impl ObjectHttpListener {
             &mut server,
         );
 
-        let handler = OpEnvRequestHandler::new(root_state.clone_op_env_processor());
+        let handler = OpEnvRequestHandler::new(
+            concurrency.wrap_op_env_processor(root_state.clone_op_env_processor()),
+        );
         OpEnvRequestHandlerEndpoint::register_server(
             zone_manager,
             &protocol,
@@ -207,17 +248,143 @@ impl ObjectHttpListener {
         );
 
         // non
-        let handler = NONRequestHandler::new(services.non_service.clone_processor());
+        let access_stat = &services.access_stat_manager;
+        let handler = NONRequestHandler::new(concurrency.wrap_non_processor(
+            access_stat.wrap_non_processor(services.non_service.clone_processor()),
+        ));
         NONRequestHandlerEndpoint::register_server(zone_manager, &protocol, &handler, &mut server);
 
         // ndn
-        let handler = NDNRequestHandler::new(services.ndn_service.clone_processor());
+        let handler = NDNRequestHandler::new(concurrency.wrap_ndn_processor(
+            access_stat.wrap_ndn_processor(services.ndn_service.clone_processor()),
+        ));
         NDNRequestHandlerEndpoint::register_server(zone_manager, &protocol, &handler, &mut server);
 
         // util
         let handler = UtilRequestHandler::new(services.util_service.clone_processor());
         UtilRequestHandlerEndpoint::register_server(zone_manager, &protocol, &handler, &mut server);
 
+        // contacts
+        let handler = ContactsRequestHandler::new(services.contacts_service.clone_processor());
+        ContactsRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // queue
+        let handler = QueueRequestHandler::new(services.queue_service.clone_processor());
+        QueueRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // schedule
+        let handler = ScheduleRequestHandler::new(services.schedule_service.clone_processor());
+        ScheduleRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // trash
+        let handler = TrashRequestHandler::new(services.trash_service.clone_processor());
+        TrashRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // dec_config
+        let handler = DecConfigRequestHandler::new(services.dec_config_service.clone_processor());
+        DecConfigRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // admin_confirm
+        let handler =
+            AdminConfirmRequestHandler::new(services.admin_confirm_service.clone_processor());
+        AdminConfirmRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // service_discovery
+        let handler = ServiceDiscoveryRequestHandler::new(
+            services.service_discovery_service.clone_processor(),
+        );
+        ServiceDiscoveryRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // bandwidth
+        let handler = BandwidthRequestHandler::new(services.bandwidth_service.clone_processor());
+        BandwidthRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // search
+        let handler = SearchRequestHandler::new(services.search_service.clone_processor());
+        SearchRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // object_refs
+        let handler = ObjectRefsRequestHandler::new(services.object_refs_service.clone_processor());
+        ObjectRefsRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // share
+        let handler = ShareRequestHandler::new(services.share_service.clone_processor());
+        ShareRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // api_gateway
+        let handler = ApiGatewayRequestHandler::new(services.api_gateway_service.clone_processor());
+        ApiGatewayRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
+        // object_history
+        let handler =
+            ObjectHistoryRequestHandler::new(services.object_history_service.clone_processor());
+        ObjectHistoryRequestHandlerEndpoint::register_server(
+            zone_manager,
+            &protocol,
+            &handler,
+            &mut server,
+        );
+
         // trans service
         let handler = TransRequestHandler::new(services.trans_service.clone_processor());
         TransRequestHandlerEndpoint::register_server(
@@ -254,7 +421,7 @@ impl SyncHttpListener {
         sync_server: Option<&Arc<ZoneSyncServer>>,
         sync_client: Option<&Arc<DeviceSyncClient>>,
     ) -> Self {
-        let mut server = new_server();
+        let mut server = new_server(None);
 
         // sync只支持bdt协议
         match protocol {
@@ -1,4 +1,6 @@
 use super::auth::InterfaceAuth;
+use crate::bandwidth::BandwidthRecorder;
+use crate::stack::{recent_requests, StackRequestGate};
 use cyfs_base::*;
 use cyfs_lib::*;
 
@@ -100,16 +102,22 @@ impl HttpServerHandler for RawHttpServer {
 pub(crate) struct DefaultHttpServer {
     handler: HttpServerHandlerRef,
     default_handler: HttpDefaultHandler,
+    gate: StackRequestGate,
+    bandwidth_recorder: BandwidthRecorder,
 }
 
 impl DefaultHttpServer {
     pub(crate) fn new(
         handler: HttpServerHandlerRef,
         default_handler: HttpDefaultHandler,
+        gate: StackRequestGate,
+        bandwidth_recorder: BandwidthRecorder,
     ) -> Self {
         Self {
             handler,
             default_handler,
+            gate,
+            bandwidth_recorder,
         }
     }
 
@@ -130,7 +138,58 @@ impl HttpServerHandler for DefaultHttpServer {
             return Ok(resp);
         }
 
-        self.handler.respond(source, req).await
+        // 协议栈正在退出，不再接收新的请求
+        let _guard = match self.gate.enter() {
+            Some(guard) => guard,
+            None => {
+                warn!(
+                    "stack is draining, reject request! source={:?}, url={}",
+                    source,
+                    req.url()
+                );
+                return Ok(RequestorHelper::new_response(
+                    http_types::StatusCode::ServiceUnavailable,
+                ));
+            }
+        };
+
+        // 只记录method和path，不记录query和body
+        let path = req.url().path();
+        let processor = path.trim_start_matches('/').split('/').next().unwrap_or("");
+        recent_requests().record(processor, format!("{} {} {:?}", req.method(), path, source));
+
+        // 远程请求按dec统计流量，只统计body长度，chunked等未知长度的忽略
+        let remote = match &source {
+            HttpRequestSource::Remote((device_id, _)) => {
+                let dec_id: Option<ObjectId> =
+                    RequestorHelper::decode_optional_header(&req, cyfs_base::CYFS_DEC_ID)
+                        .unwrap_or(None);
+                Some((device_id.to_owned(), dec_id, req.len().unwrap_or(0)))
+            }
+            HttpRequestSource::Local(_) => None,
+        };
+
+        let resp = self.handler.respond(source, req).await?;
+
+        if let Some((device_id, dec_id, recv_len)) = remote {
+            let recorder = &self.bandwidth_recorder;
+            recorder.record(
+                BandwidthDirection::Download,
+                BandwidthInterface::Http,
+                Some(&device_id),
+                dec_id.as_ref(),
+                recv_len as u64,
+            );
+            recorder.record(
+                BandwidthDirection::Upload,
+                BandwidthInterface::Http,
+                Some(&device_id),
+                dec_id.as_ref(),
+                resp.len().unwrap_or(0) as u64,
+            );
+        }
+
+        Ok(resp)
     }
 }
 
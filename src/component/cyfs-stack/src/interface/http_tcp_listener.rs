@@ -1,4 +1,5 @@
 use super::http_server::{HttpRequestSource, HttpServerHandlerRef};
+use super::limits::HttpConnectionLimiter;
 use super::ObjectListener;
 use cyfs_base::*;
 use cyfs_lib::{BaseTcpListener, BaseTcpListenerHandler, RequestProtocol};
@@ -6,7 +7,7 @@ use cyfs_lib::{BaseTcpListener, BaseTcpListenerHandler, RequestProtocol};
 use async_std::net::TcpStream;
 use async_trait::async_trait;
 use std::net::SocketAddr;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -19,6 +20,7 @@ pub(super) struct ObjectHttpTcpListener {
     device_id: DeviceId,
 
     server: HttpServerHandlerRef,
+    limiter: HttpConnectionLimiter,
 
     seq: Arc<AtomicU64>,
 }
@@ -49,13 +51,19 @@ impl ObjectListener for ObjectHttpTcpListener {
 }
 
 impl ObjectHttpTcpListener {
-    pub fn new(addr: SocketAddr, device_id: DeviceId, server: HttpServerHandlerRef) -> Self {
+    pub fn new(
+        addr: SocketAddr,
+        device_id: DeviceId,
+        server: HttpServerHandlerRef,
+        limiter: HttpConnectionLimiter,
+    ) -> Self {
         let listen_url = format!("http://{}", addr);
         let ret = Self {
             tcp_server: BaseTcpListener::new(addr),
             listen_url,
             device_id,
             server,
+            limiter,
             seq: Arc::new(AtomicU64::new(0)),
         };
 
@@ -66,7 +74,7 @@ impl ObjectHttpTcpListener {
     }
 
     fn next_seq(&self) -> u64 {
-        self.seq.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        self.seq.fetch_add(1, Ordering::SeqCst)
     }
 
     pub fn get_listen(&self) -> String {
@@ -80,14 +88,21 @@ impl ObjectHttpTcpListener {
             self.listen_url, &peer_addr,
         );
 
+        // 超出连接上限或者被封禁的来源直接断开
+        let conn_source = HttpRequestSource::Local(peer_addr.clone());
+        let guard = self.limiter.acquire(&conn_source)?;
+
         let stream_begin = std::time::Instant::now();
+        let requests = AtomicU64::new(0);
+        let requests_ref = &requests;
 
-        let opts = async_h1::ServerOptions::default();
+        let opts = self.limiter.config().server_options();
         let ret = async_h1::accept_with_opts(
             stream,
             |mut req| async move {
                 let begin = std::time::Instant::now();
                 let seq = self.next_seq();
+                requests_ref.fetch_add(1, Ordering::SeqCst);
 
                 info!(
                     "recv tcp http request: url={}, method={}, len={:?}, peer={}, seq={}",
@@ -143,6 +158,8 @@ impl ObjectHttpTcpListener {
         )
         .await;
 
+        guard.check_header_timeout(&conn_source, requests.load(Ordering::SeqCst));
+
         if let Err(e) = ret {
             warn!(
                 "tcp http accept error, err={}, addr={}, peer={}, during={}ms",
@@ -0,0 +1,570 @@
+use super::http_server::{HttpRequestSource, HttpServerHandler, HttpServerHandlerRef};
+use cyfs_base::*;
+use cyfs_lib::RequestorHelper;
+
+use async_std::io::BufReader;
+use futures::io::AsyncRead;
+use http_types::{Body, StatusCode};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct HttpLimitsConfig {
+    pub enable: bool,
+
+    // 请求body的默认上限，单位字节
+    pub max_body_size: u64,
+
+    // 按路径前缀单独设置的body上限，匹配最长的前缀；None表示不限制，用于边读边处理的流式接口，
+    // 由接口自己限制单次读取的大小
+    pub route_body_limits: Vec<(String, Option<u64>)>,
+
+    // 读取请求头的超时，防止慢速发送请求头的连接长期占用服务
+    pub header_timeout: Duration,
+
+    // 所有来源的并发连接上限和单个来源的并发连接上限，本机回环地址不受单来源上限限制
+    pub max_connections: usize,
+    pub max_connections_per_source: usize,
+
+    // 同一来源在ban_window内违规达到ban_threshold次，在ban_duration内拒绝它的所有连接
+    pub ban_threshold: u32,
+    pub ban_window: Duration,
+    pub ban_duration: Duration,
+}
+
+impl Default for HttpLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            max_body_size: 1024 * 1024 * 16,
+            route_body_limits: vec![
+                // put_data的body长度由chunk_id里的长度决定，ndn服务按chunk长度读取
+                ("/ndn".to_owned(), None),
+                ("/trans".to_owned(), Some(1024 * 1024)),
+                // publish_stream按chunk_size分块读取，总长度不限
+                ("/trans/file/stream".to_owned(), None),
+                ("/util".to_owned(), Some(1024 * 1024)),
+                ("/contacts".to_owned(), Some(1024 * 1024)),
+                ("/queue".to_owned(), Some(1024 * 1024)),
+                ("/schedule".to_owned(), Some(1024 * 1024)),
+                ("/trash".to_owned(), Some(1024 * 1024)),
+                ("/dec_config".to_owned(), Some(1024 * 1024)),
+                ("/admin_confirm".to_owned(), Some(1024 * 1024)),
+                ("/service_discovery".to_owned(), Some(1024 * 1024)),
+                ("/bandwidth".to_owned(), Some(1024 * 1024)),
+                ("/search".to_owned(), Some(1024 * 1024)),
+                ("/object_refs".to_owned(), Some(1024 * 1024)),
+                ("/share".to_owned(), Some(1024 * 1024)),
+                ("/api_gateway".to_owned(), Some(1024 * 1024)),
+                ("/object_history".to_owned(), Some(1024 * 1024)),
+            ],
+            header_timeout: Duration::from_secs(30),
+            max_connections: 4096,
+            max_connections_per_source: 256,
+            ban_threshold: 10,
+            ban_window: Duration::from_secs(60),
+            ban_duration: Duration::from_secs(60 * 10),
+        }
+    }
+}
+
+impl HttpLimitsConfig {
+    pub fn body_limit(&self, path: &str) -> Option<u64> {
+        self.route_body_limits
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, limit)| *limit)
+            .unwrap_or(Some(self.max_body_size))
+    }
+
+    pub fn server_options(&self) -> async_h1::ServerOptions {
+        let opts = async_h1::ServerOptions::default();
+        if self.enable {
+            opts.with_headers_timeout(self.header_timeout)
+        } else {
+            opts
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HttpLimitViolation {
+    BodyTooLarge,
+    HeaderTimeout,
+    TooManyConnections,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HttpLimitsStat {
+    pub active_connections: usize,
+    pub rejected_connections: u64,
+    pub rejected_bodies: u64,
+    pub header_timeouts: u64,
+    pub banned_sources: usize,
+    pub total_bans: u64,
+}
+
+struct SourceState {
+    connections: usize,
+    violations: Vec<Instant>,
+    banned_until: Option<Instant>,
+}
+
+impl SourceState {
+    fn new() -> Self {
+        Self {
+            connections: 0,
+            violations: vec![],
+            banned_until: None,
+        }
+    }
+
+    fn is_banned(&self, now: Instant) -> bool {
+        match self.banned_until {
+            Some(until) => until > now,
+            None => false,
+        }
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        self.connections == 0 && self.violations.is_empty() && !self.is_banned(now)
+    }
+}
+
+struct HttpLimiterState {
+    connections: usize,
+    sources: HashMap<String, SourceState>,
+}
+
+struct HttpLimiterInner {
+    config: HttpLimitsConfig,
+    state: Mutex<HttpLimiterState>,
+
+    rejected_connections: AtomicU64,
+    rejected_bodies: AtomicU64,
+    header_timeouts: AtomicU64,
+    total_bans: AtomicU64,
+}
+
+// http接口的并发连接和违规来源管理，tcp和bdt的listener共用一个实例
+// tcp的来源为对端ip，bdt的来源为对端device_id
+#[derive(Clone)]
+pub(crate) struct HttpConnectionLimiter(Arc<HttpLimiterInner>);
+
+impl HttpConnectionLimiter {
+    pub fn new(config: HttpLimitsConfig) -> Self {
+        let inner = HttpLimiterInner {
+            config,
+            state: Mutex::new(HttpLimiterState {
+                connections: 0,
+                sources: HashMap::new(),
+            }),
+            rejected_connections: AtomicU64::new(0),
+            rejected_bodies: AtomicU64::new(0),
+            header_timeouts: AtomicU64::new(0),
+            total_bans: AtomicU64::new(0),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    pub fn config(&self) -> &HttpLimitsConfig {
+        &self.0.config
+    }
+
+    pub fn source_key(source: &HttpRequestSource) -> String {
+        match source {
+            HttpRequestSource::Remote((device_id, _)) => device_id.to_string(),
+            HttpRequestSource::Local(addr) => addr.ip().to_string(),
+        }
+    }
+
+    // 本机发起的连接不受单来源上限和封禁的限制
+    fn is_exempt(source: &HttpRequestSource) -> bool {
+        match source {
+            HttpRequestSource::Local(addr) => Self::is_loopback(&addr.ip()),
+            HttpRequestSource::Remote(_) => false,
+        }
+    }
+
+    fn is_loopback(ip: &IpAddr) -> bool {
+        match ip {
+            IpAddr::V4(ip) => ip.is_loopback(),
+            IpAddr::V6(ip) => {
+                ip.is_loopback() || ip.to_ipv4().map(|v| v.is_loopback()).unwrap_or(false)
+            }
+        }
+    }
+
+    pub fn acquire(&self, source: &HttpRequestSource) -> BuckyResult<HttpConnectionGuard> {
+        let key = Self::source_key(source);
+        if !self.0.config.enable {
+            return Ok(HttpConnectionGuard::new(self.clone(), key, false));
+        }
+
+        let exempt = Self::is_exempt(source);
+        let now = Instant::now();
+
+        let ret = {
+            let mut guard = self.0.state.lock().unwrap();
+            let state = &mut *guard;
+            if state.connections >= self.0.config.max_connections {
+                Err(format!(
+                    "too many http connections! source={}, count={}",
+                    key, state.connections
+                ))
+            } else if exempt {
+                state.connections += 1;
+                Ok(())
+            } else {
+                let item = state
+                    .sources
+                    .entry(key.clone())
+                    .or_insert_with(SourceState::new);
+                if item.is_banned(now) {
+                    Err(format!("http source is banned! source={}", key))
+                } else if item.connections >= self.0.config.max_connections_per_source {
+                    Err(format!(
+                        "too many http connections from source! source={}, count={}",
+                        key, item.connections
+                    ))
+                } else {
+                    item.connections += 1;
+                    state.connections += 1;
+                    Ok(())
+                }
+            }
+        };
+
+        match ret {
+            Ok(()) => Ok(HttpConnectionGuard::new(self.clone(), key, !exempt)),
+            Err(msg) => {
+                warn!("{}", msg);
+                self.0.rejected_connections.fetch_add(1, Ordering::SeqCst);
+                if !exempt {
+                    self.record_violation(source, HttpLimitViolation::TooManyConnections);
+                }
+                Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg))
+            }
+        }
+    }
+
+    fn release(&self, key: &str, per_source: bool) {
+        let mut state = self.0.state.lock().unwrap();
+        state.connections = state.connections.saturating_sub(1);
+        if per_source {
+            if let Some(item) = state.sources.get_mut(key) {
+                item.connections = item.connections.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn record_violation(&self, source: &HttpRequestSource, violation: HttpLimitViolation) {
+        match violation {
+            HttpLimitViolation::BodyTooLarge => {
+                self.0.rejected_bodies.fetch_add(1, Ordering::SeqCst);
+            }
+            HttpLimitViolation::HeaderTimeout => {
+                self.0.header_timeouts.fetch_add(1, Ordering::SeqCst);
+            }
+            HttpLimitViolation::TooManyConnections => {}
+        }
+
+        if !self.0.config.enable || Self::is_exempt(source) {
+            return;
+        }
+
+        let key = Self::source_key(source);
+        let now = Instant::now();
+        let config = &self.0.config;
+
+        let mut state = self.0.state.lock().unwrap();
+        let item = state
+            .sources
+            .entry(key.clone())
+            .or_insert_with(SourceState::new);
+        if item.is_banned(now) {
+            return;
+        }
+
+        item.violations
+            .retain(|t| now.duration_since(*t) < config.ban_window);
+        item.violations.push(now);
+
+        if item.violations.len() as u32 >= config.ban_threshold {
+            warn!(
+                "http source will be banned for too many violations! source={}, last={:?}, duration={}s",
+                key,
+                violation,
+                config.ban_duration.as_secs()
+            );
+            item.violations.clear();
+            item.banned_until = Some(now + config.ban_duration);
+            self.0.total_bans.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // 顺便清理已经失效的来源，避免来源表无限增长
+        if state.sources.len() > config.max_connections {
+            let window = config.ban_window;
+            state.sources.retain(|_, v| {
+                v.violations.retain(|t| now.duration_since(*t) < window);
+                !v.is_idle(now)
+            });
+        }
+    }
+
+    // 单个请求的body上限，为None表示不限制
+    pub fn body_limit(&self, path: &str) -> Option<u64> {
+        if self.0.config.enable {
+            self.0.config.body_limit(path)
+        } else {
+            None
+        }
+    }
+
+    pub fn stat(&self) -> HttpLimitsStat {
+        let now = Instant::now();
+        let state = self.0.state.lock().unwrap();
+        HttpLimitsStat {
+            active_connections: state.connections,
+            rejected_connections: self.0.rejected_connections.load(Ordering::SeqCst),
+            rejected_bodies: self.0.rejected_bodies.load(Ordering::SeqCst),
+            header_timeouts: self.0.header_timeouts.load(Ordering::SeqCst),
+            banned_sources: state.sources.values().filter(|v| v.is_banned(now)).count(),
+            total_bans: self.0.total_bans.load(Ordering::SeqCst),
+        }
+    }
+}
+
+// 连接结束时释放占用的连接数
+pub(crate) struct HttpConnectionGuard {
+    limiter: HttpConnectionLimiter,
+    key: String,
+    per_source: bool,
+    begin: Instant,
+}
+
+impl HttpConnectionGuard {
+    fn new(limiter: HttpConnectionLimiter, key: String, per_source: bool) -> Self {
+        Self {
+            limiter,
+            key,
+            per_source,
+            begin: Instant::now(),
+        }
+    }
+
+    // 连接上一个请求都没有处理完成，并且持续时间超过了请求头超时，认为是慢速攻击
+    pub fn check_header_timeout(&self, source: &HttpRequestSource, requests: u64) {
+        let config = self.limiter.config();
+        if config.enable && requests == 0 && self.begin.elapsed() >= config.header_timeout {
+            warn!(
+                "http connection closed without any request! source={:?}, during={}ms",
+                source,
+                self.begin.elapsed().as_millis()
+            );
+            self.limiter
+                .record_violation(source, HttpLimitViolation::HeaderTimeout);
+        }
+    }
+}
+
+impl Drop for HttpConnectionGuard {
+    fn drop(&mut self) {
+        self.limiter.release(&self.key, self.per_source);
+    }
+}
+
+// 读取超过上限时返回错误，用于长度未知的chunked请求
+struct BodyLimitReader {
+    body: Body,
+    remain: u64,
+}
+
+impl AsyncRead for BodyLimitReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        // 多读一个字节用来判断是否超出上限
+        let max = std::cmp::min(buf.len() as u64, self.remain + 1) as usize;
+        let ret = Pin::new(&mut self.body).poll_read(cx, &mut buf[..max]);
+        if let Poll::Ready(Ok(len)) = &ret {
+            if *len as u64 > self.remain {
+                return Poll::Ready(Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "request body too large",
+                )));
+            }
+            self.remain -= *len as u64;
+        }
+
+        ret
+    }
+}
+
+// 按路径检查请求body的大小，超出上限的请求直接返回413
+pub(crate) struct HttpLimitedServer {
+    handler: HttpServerHandlerRef,
+    limiter: HttpConnectionLimiter,
+}
+
+impl HttpLimitedServer {
+    pub fn new(handler: HttpServerHandlerRef, limiter: HttpConnectionLimiter) -> Self {
+        Self { handler, limiter }
+    }
+
+    pub fn into(self) -> HttpServerHandlerRef {
+        Arc::new(Box::new(self))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerHandler for HttpLimitedServer {
+    async fn respond(
+        &self,
+        source: HttpRequestSource,
+        mut req: http_types::Request,
+    ) -> http_types::Result<http_types::Response> {
+        let limit = match self.limiter.body_limit(req.url().path()) {
+            Some(limit) => limit,
+            None => return self.handler.respond(source, req).await,
+        };
+
+        match req.len() {
+            Some(len) => {
+                if len as u64 > limit {
+                    let msg = format!(
+                        "request body too large! source={:?}, path={}, len={}, limit={}",
+                        source,
+                        req.url().path(),
+                        len,
+                        limit
+                    );
+                    warn!("{}", msg);
+                    self.limiter
+                        .record_violation(&source, HttpLimitViolation::BodyTooLarge);
+
+                    let mut resp = RequestorHelper::new_response(StatusCode::PayloadTooLarge);
+                    resp.set_body(msg);
+                    return Ok(resp);
+                }
+            }
+            None => {
+                let body = req.take_body();
+                let mime = body.mime().clone();
+                let reader = BodyLimitReader {
+                    body,
+                    remain: limit,
+                };
+                let mut body = Body::from_reader(BufReader::new(reader), None);
+                body.set_mime(mime);
+                req.set_body(body);
+            }
+        }
+
+        self.handler.respond(source, req).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let config = HttpLimitsConfig {
+            max_connections_per_source: 2,
+            ban_threshold: 2,
+            ..Default::default()
+        };
+        assert_eq!(config.body_limit("/ndn/chunk"), None);
+        assert_eq!(config.body_limit("/trans/file"), Some(1024 * 1024));
+        assert_eq!(config.body_limit("/trans/file/stream"), None);
+        assert_eq!(config.body_limit("/non/object"), Some(1024 * 1024 * 16));
+
+        let limiter = HttpConnectionLimiter::new(config);
+        let remote = HttpRequestSource::Local("192.168.1.2:1234".parse().unwrap());
+        let local = HttpRequestSource::Local("127.0.0.1:1234".parse().unwrap());
+
+        let g1 = limiter.acquire(&remote).unwrap();
+        let _g2 = limiter.acquire(&remote).unwrap();
+        assert!(limiter.acquire(&remote).is_err());
+        assert_eq!(limiter.stat().active_connections, 2);
+
+        let _l: Vec<_> = (0..4).map(|_| limiter.acquire(&local).unwrap()).collect();
+        assert_eq!(limiter.stat().active_connections, 6);
+
+        // 第二次违规之后被封禁，已经释放的连接也无法重新建立
+        drop(g1);
+        limiter.record_violation(&remote, HttpLimitViolation::BodyTooLarge);
+        assert!(limiter.acquire(&remote).is_err());
+        assert_eq!(limiter.stat().banned_sources, 1);
+        assert_eq!(limiter.stat().rejected_connections, 2);
+
+        limiter.record_violation(&local, HttpLimitViolation::BodyTooLarge);
+        limiter.record_violation(&local, HttpLimitViolation::BodyTooLarge);
+        assert!(limiter.acquire(&local).is_ok());
+    }
+
+    // 读取整个body并返回读取到的长度
+    struct ReadAllHandler;
+
+    #[async_trait::async_trait]
+    impl HttpServerHandler for ReadAllHandler {
+        async fn respond(
+            &self,
+            _source: HttpRequestSource,
+            mut req: http_types::Request,
+        ) -> http_types::Result<http_types::Response> {
+            let body = req.body_bytes().await?;
+            let mut resp = RequestorHelper::new_response(StatusCode::Ok);
+            resp.set_body(body.len().to_string());
+            Ok(resp)
+        }
+    }
+
+    async fn post_chunked(
+        server: &HttpLimitedServer,
+        path: &str,
+        len: usize,
+    ) -> http_types::Result<http_types::Response> {
+        let url = http_types::Url::parse(&format!("http://127.0.0.1{}", path)).unwrap();
+        let mut req = http_types::Request::new(http_types::Method::Post, url);
+
+        // 长度未知，和publish_stream一样使用chunked方式
+        let data = async_std::io::Cursor::new(vec![0u8; len]);
+        req.set_body(Body::from_reader(BufReader::new(data), None));
+
+        let source = HttpRequestSource::Local("192.168.1.2:1234".parse().unwrap());
+        server.respond(source, req).await
+    }
+
+    #[test]
+    fn test_stream_body_limit() {
+        let limiter = HttpConnectionLimiter::new(HttpLimitsConfig::default());
+        let server = HttpLimitedServer::new(Arc::new(Box::new(ReadAllHandler)), limiter);
+
+        async_std::task::block_on(async move {
+            // 超出/trans的上限，但是流式发布不受限制
+            let len = 1024 * 1024 * 4;
+            let mut resp = post_chunked(&server, "/trans/file/stream", len)
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::Ok);
+            assert_eq!(resp.body_string().await.unwrap(), len.to_string());
+
+            assert!(post_chunked(&server, "/trans/file", len).await.is_err());
+
+            let mut resp = post_chunked(&server, "/trans/file", 1024).await.unwrap();
+            assert_eq!(resp.body_string().await.unwrap(), "1024");
+        });
+    }
+}
@@ -2,11 +2,13 @@ use super::auth::InterfaceAuth;
 use super::browser_server::{BrowserSanboxHttpServer, DisableBrowserRequestHttpServer};
 use super::http_server::*;
 use super::{
-    ObjectHttpBdtListener, ObjectHttpListener, ObjectHttpTcpListener, ObjectListener,
-    WebSocketEventInterface,
+    HttpCompressionConfig, HttpConnectionLimiter, HttpLimitedServer, HttpLimitsConfig,
+    HttpLimitsStat, ObjectHttpBdtListener, ObjectHttpListener, ObjectHttpTcpListener,
+    ObjectListener, ReplayProtectedHttpServer, WebSocketEventInterface,
 };
 use crate::acl::AclManagerRef;
 use crate::app::AuthenticatedAppList;
+use crate::bandwidth::BandwidthRecorder;
 use crate::config::StackGlobalConfig;
 use crate::events::RouterEventsManager;
 use crate::group_api::GroupService;
@@ -15,7 +17,7 @@ use crate::name::NameResolver;
 use crate::rmeta_api::GlobalStateMetaService;
 use crate::root_state_api::*;
 use crate::router_handler::RouterHandlersManager;
-use crate::stack::ObjectServices;
+use crate::stack::{ObjectServices, StackRequestGate};
 use crate::zone::ZoneRoleManager;
 use cyfs_base::*;
 use cyfs_bdt::StackGuard;
@@ -36,6 +38,14 @@ pub struct ObjectListenerManagerParams {
 
     // websocket
     pub ws_listener: Option<SocketAddr>,
+
+    // 其它zone的写请求是否必须带防重放签名
+    pub require_request_sign: bool,
+
+    pub compression: HttpCompressionConfig,
+
+    // 请求body大小、请求头超时和并发连接的限制
+    pub limits: HttpLimitsConfig,
 }
 
 struct AuthenticatedServerInfo {
@@ -64,6 +74,9 @@ pub struct ObjectListenerManager {
     authenticated_server: Mutex<Option<AuthenticatedServerInfo>>,
 
     default_handler: Option<HttpDefaultHandler>,
+    request_gate: Option<StackRequestGate>,
+    bandwidth_recorder: Option<BandwidthRecorder>,
+    limiter: Option<HttpConnectionLimiter>,
 }
 
 pub type ObjectListenerManagerRef = Arc<ObjectListenerManager>;
@@ -82,6 +95,9 @@ impl ObjectListenerManager {
             http_auth_raw_server: None,
             authenticated_server: Mutex::new(None),
             default_handler: None,
+            request_gate: None,
+            bandwidth_recorder: None,
+            limiter: None,
         }
     }
 
@@ -119,6 +135,10 @@ impl ObjectListenerManager {
         self.http_bdt_server.as_ref().unwrap().clone()
     }
 
+    pub fn get_http_limits_stat(&self) -> Option<HttpLimitsStat> {
+        self.limiter.as_ref().map(|limiter| limiter.stat())
+    }
+
     pub(crate) fn init(
         &mut self,
         params: ObjectListenerManagerParams,
@@ -130,13 +150,14 @@ impl ObjectListenerManager {
         acl: &AclManagerRef,
         role_manager: &ZoneRoleManager,
         root_state: &GlobalStateService,
-        local_cache: &GlobalStateLocalService,
+        local_cache: &LocalCacheNamespaceManager,
         global_state_meta: &GlobalStateMetaService,
         group_service: &GroupService,
     ) {
         assert!(self.listeners.is_empty());
 
         let default_handler = HttpDefaultHandler::default();
+        let limiter = HttpConnectionLimiter::new(params.limits.clone());
 
         // 首先初始化三个基础的http_server
         {
@@ -154,10 +175,22 @@ impl ObjectListenerManager {
                 name_resolver,
                 role_manager.zone_manager(),
                 group_service,
+                &params.compression,
             );
 
             let raw_handler = RawHttpServer::new(server.into_server());
-            let http_server = DefaultHttpServer::new(raw_handler.into(), default_handler.clone());
+            let http_server = DefaultHttpServer::new(
+                raw_handler.into(),
+                default_handler.clone(),
+                config.request_gate().clone(),
+                config.bandwidth_recorder().clone(),
+            );
+            let http_server = ReplayProtectedHttpServer::new(
+                http_server.into(),
+                role_manager.zone_manager().clone(),
+                params.require_request_sign,
+            );
+            let http_server = HttpLimitedServer::new(http_server.into(), limiter.clone());
             self.http_bdt_server = Some(http_server.into());
         }
 
@@ -176,15 +209,22 @@ impl ObjectListenerManager {
                 name_resolver,
                 role_manager.zone_manager(),
                 group_service,
+                &params.compression,
             );
 
             let raw_handler = RawHttpServer::new(server.into_server());
-            let http_server = DefaultHttpServer::new(raw_handler.into(), default_handler.clone());
+            let http_server = DefaultHttpServer::new(
+                raw_handler.into(),
+                default_handler.clone(),
+                config.request_gate().clone(),
+                config.bandwidth_recorder().clone(),
+            );
             let http_server = match config.get_stack_params().front.browser_mode {
                 BrowserSanboxMode::None => http_server.into(),
                 mode @ _ => BrowserSanboxHttpServer::new(http_server.into(), mode).into(),
             };
-            self.http_tcp_server = Some(http_server);
+            let http_server = HttpLimitedServer::new(http_server, limiter.clone());
+            self.http_tcp_server = Some(http_server.into());
         }
 
         {
@@ -202,6 +242,7 @@ impl ObjectListenerManager {
                 name_resolver,
                 role_manager.zone_manager(),
                 group_service,
+                &params.compression,
             );
 
             let raw_handler = RawHttpServer::new(server.into_server());
@@ -212,13 +253,20 @@ impl ObjectListenerManager {
         // save default_handler for dynamic auth interface
         assert!(self.default_handler.is_none());
         self.default_handler = Some(default_handler);
+        self.request_gate = Some(config.request_gate().clone());
+        self.bandwidth_recorder = Some(config.bandwidth_recorder().clone());
+        self.limiter = Some(limiter.clone());
 
         // init all listeners
         for vport in params.bdt_listeners {
             let http_server = self.http_bdt_server.as_ref().unwrap().clone();
 
-            let bdt_listener =
-                ObjectHttpBdtListener::new(params.bdt_stack.clone(), vport, http_server);
+            let bdt_listener = ObjectHttpBdtListener::new(
+                params.bdt_stack.clone(),
+                vport,
+                http_server,
+                Some(limiter.clone()),
+            );
             let bdt_listener = Box::new(bdt_listener) as Box<dyn ObjectListener>;
             self.listeners.push(bdt_listener);
         }
@@ -226,8 +274,12 @@ impl ObjectListenerManager {
         for addr in params.tcp_listeners {
             let http_server = self.http_tcp_server.as_ref().unwrap().clone();
 
-            let tcp_listener =
-                ObjectHttpTcpListener::new(addr, self.device_id.clone(), http_server);
+            let tcp_listener = ObjectHttpTcpListener::new(
+                addr,
+                self.device_id.clone(),
+                http_server,
+                limiter.clone(),
+            );
             let tcp_listener = Box::new(tcp_listener) as Box<dyn ObjectListener>;
             self.listeners.push(tcp_listener);
         }
@@ -278,6 +330,25 @@ impl ObjectListenerManager {
         Ok(())
     }
 
+    // 停止所有的监听，单个listener停止失败不影响其余的
+    pub async fn stop(&self) -> BuckyResult<()> {
+        for listener in &self.listeners {
+            if let Err(e) = listener.stop().await {
+                error!(
+                    "stop object listener error: addr={}, {}",
+                    listener.get_addr(),
+                    e
+                );
+            }
+        }
+
+        if let Some(ws_event_interface) = &self.ws_event_interface {
+            ws_event_interface.stop().await;
+        }
+
+        self.stop_authenticated_interface().await
+    }
+
     pub async fn restart(&self) -> BuckyResult<()> {
         for listener in &self.listeners {
             if let Err(e) = listener.restart().await {
@@ -351,8 +422,11 @@ impl ObjectListenerManager {
         let server = DefaultHttpServer::new(
             auth_http_server.into(),
             self.default_handler.as_ref().unwrap().clone(),
-        )
-        .into();
+            self.request_gate.as_ref().unwrap().clone(),
+            self.bandwidth_recorder.as_ref().unwrap().clone(),
+        );
+        let limiter = self.limiter.as_ref().unwrap().clone();
+        let server = HttpLimitedServer::new(server.into(), limiter.clone()).into();
 
         let addr = format!("{}:{}", gateway_ip, 0);
         let mut sock_addr: SocketAddr = addr.parse().map_err(|e| {
@@ -365,8 +439,12 @@ impl ObjectListenerManager {
 
         // http interface
         sock_addr.set_port(NON_STACK_HTTP_PORT);
-        let listener =
-            ObjectHttpTcpListener::new(sock_addr.clone(), self.device_id.clone(), server.clone());
+        let listener = ObjectHttpTcpListener::new(
+            sock_addr.clone(),
+            self.device_id.clone(),
+            server.clone(),
+            limiter,
+        );
 
         let listener = Box::new(listener) as Box<dyn ObjectListener>;
         listener.start().await?;
@@ -1,20 +1,26 @@
 mod auth;
+mod compression;
 mod http_bdt_listener;
 mod http_listener;
 mod http_server;
 mod http_tcp_listener;
 mod http_ws_listener;
+mod limits;
 mod listener_manager;
+mod replay;
 mod sync_interface;
 mod ws_interface;
 mod browser_server;
 
 pub(crate) use auth::InterfaceAuth;
+pub use compression::*;
 use http_bdt_listener::*;
 use http_listener::*;
 pub use http_server::*;
 use http_tcp_listener::*;
+pub use limits::*;
 pub(crate) use listener_manager::*;
+use replay::*;
 pub(crate) use sync_interface::*;
 use ws_interface::*;
 
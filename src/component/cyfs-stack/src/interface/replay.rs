@@ -0,0 +1,303 @@
+use super::http_server::{HttpRequestSource, HttpServerHandler, HttpServerHandlerRef};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_bdt::DeviceCache;
+use cyfs_lib::*;
+
+use async_std::io::BufReader;
+use futures::io::AsyncRead;
+use http_types::Body;
+use sha2::Digest;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+// 请求时间戳允许的偏差，窗口期内的nonce只能使用一次
+const REQUEST_REPLAY_WINDOW: u64 = 1000 * 1000 * 60 * 5;
+
+// 窗口期内每个设备最多缓存的nonce数量，超出后拒绝该设备新的请求，不影响其它设备
+const REQUEST_REPLAY_MAX_NONCES: usize = 1024 * 64;
+
+// 缓存的设备数量超过这个值时，清理所有nonce都已经过期的设备
+const REQUEST_REPLAY_SWEEP_DEVICES: usize = 1024;
+
+pub(crate) struct RequestReplayWindow {
+    window: u64,
+    max_nonces: usize,
+
+    // device_id -> (nonce -> timestamp)
+    nonces: Mutex<HashMap<DeviceId, HashMap<String, u64>>>,
+}
+
+impl RequestReplayWindow {
+    pub fn new(window: u64, max_nonces: usize) -> Self {
+        Self {
+            window,
+            max_nonces,
+            nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn check(
+        &self,
+        device_id: &DeviceId,
+        nonce: &str,
+        timestamp: u64,
+        now: u64,
+    ) -> BuckyResult<()> {
+        let delta = if now > timestamp {
+            now - timestamp
+        } else {
+            timestamp - now
+        };
+        if delta > self.window {
+            let msg = format!(
+                "request timestamp out of replay window! device={}, timestamp={}, now={}",
+                device_id, timestamp, now
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Expired, msg));
+        }
+
+        let window = self.window;
+        let mut all = self.nonces.lock().unwrap();
+
+        if all.len() >= REQUEST_REPLAY_SWEEP_DEVICES && !all.contains_key(device_id) {
+            all.retain(|_, nonces| {
+                nonces.retain(|_, timestamp| *timestamp + window >= now);
+                !nonces.is_empty()
+            });
+        }
+
+        let nonces = all.entry(device_id.to_owned()).or_insert_with(HashMap::new);
+        if nonces.contains_key(nonce) {
+            let msg = format!(
+                "request replayed! device={}, nonce={}, timestamp={}",
+                device_id, nonce, timestamp
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+        }
+
+        if nonces.len() >= self.max_nonces {
+            // 超出窗口期的nonce对应的请求会被时间戳检查拒绝，不需要再保存
+            nonces.retain(|_, timestamp| *timestamp + window >= now);
+
+            if nonces.len() >= self.max_nonces {
+                let msg = format!(
+                    "too many requests in replay window! device={}, count={}",
+                    device_id,
+                    nonces.len()
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+        }
+
+        nonces.insert(nonce.to_owned(), timestamp);
+        Ok(())
+    }
+}
+
+// 读取body的同时计算hash，读取结束时和签名里的body_hash不一致返回错误
+struct BodyHashVerifyReader {
+    body: Body,
+    hasher: sha2::Sha256,
+    expect: HashValue,
+    done: bool,
+}
+
+impl AsyncRead for BodyHashVerifyReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let ret = Pin::new(&mut self.body).poll_read(cx, buf);
+        if let Poll::Ready(Ok(len)) = &ret {
+            let len = *len;
+            if len > 0 {
+                self.hasher.input(&buf[..len]);
+            } else if !buf.is_empty() && !self.done {
+                self.done = true;
+                let hash = HashValue::from(self.hasher.clone().result());
+                if hash != self.expect {
+                    warn!(
+                        "request body unmatch the signed hash! expect={}, got={}",
+                        self.expect, hash
+                    );
+                    return Poll::Ready(Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "request body unmatch the signed hash",
+                    )));
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+// 对其它zone发起的写请求做签名和防重放检查
+// require_sign=false时兼容没有签名的旧版本请求，带了签名的请求仍然需要通过检查
+pub(crate) struct ReplayProtectedHttpServer {
+    handler: HttpServerHandlerRef,
+    zone_manager: ZoneManagerRef,
+    require_sign: bool,
+    window: RequestReplayWindow,
+}
+
+impl ReplayProtectedHttpServer {
+    pub fn new(
+        handler: HttpServerHandlerRef,
+        zone_manager: ZoneManagerRef,
+        require_sign: bool,
+    ) -> Self {
+        Self {
+            handler,
+            zone_manager,
+            require_sign,
+            window: RequestReplayWindow::new(REQUEST_REPLAY_WINDOW, REQUEST_REPLAY_MAX_NONCES),
+        }
+    }
+
+    pub fn into(self) -> HttpServerHandlerRef {
+        Arc::new(Box::new(self))
+    }
+
+    // 返回签名里的body_hash，没有签名的请求返回None
+    async fn check_request(
+        &self,
+        device_id: &DeviceId,
+        req: &http_types::Request,
+    ) -> BuckyResult<Option<HashValue>> {
+        let source = self.zone_manager.resolve_source_info(&None, device_id.to_owned()).await?;
+        if source.is_current_zone() {
+            return Ok(None);
+        }
+
+        let device = self.zone_manager.device_manager().search(device_id).await?;
+        let info = match RequestSignHelper::verify(req, device.desc().public_key())? {
+            Some(info) => info,
+            None => {
+                if self.require_sign {
+                    let msg = format!(
+                        "request from other zone without sign! device={}, method={}, url={}",
+                        device_id,
+                        req.method(),
+                        req.url()
+                    );
+                    warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+                }
+
+                return Ok(None);
+            }
+        };
+
+        self.window
+            .check(device_id, &info.nonce, info.timestamp, bucky_time_now())?;
+
+        Ok(Some(info.body_hash))
+    }
+
+    fn verify_body(req: &mut http_types::Request, body_hash: HashValue) {
+        let body = req.take_body();
+        let mime = body.mime().clone();
+        let len = body.len();
+        let reader = BodyHashVerifyReader {
+            body,
+            hasher: sha2::Sha256::new(),
+            expect: body_hash,
+            done: false,
+        };
+
+        let mut body = Body::from_reader(BufReader::new(reader), len);
+        body.set_mime(mime);
+        req.set_body(body);
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpServerHandler for ReplayProtectedHttpServer {
+    async fn respond(
+        &self,
+        source: HttpRequestSource,
+        mut req: http_types::Request,
+    ) -> http_types::Result<http_types::Response> {
+        if let HttpRequestSource::Remote((device_id, _)) = &source {
+            if RequestSignHelper::is_mutating(req.method()) {
+                match self.check_request(device_id, &req).await {
+                    Ok(Some(body_hash)) => Self::verify_body(&mut req, body_hash),
+                    Ok(None) => {}
+                    Err(e) => return Ok(RequestorHelper::trans_error(e)),
+                }
+            }
+        }
+
+        self.handler.respond(source, req).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_replay_window() {
+        let window = RequestReplayWindow::new(1000, 2);
+        let device_id =
+            DeviceId::from_str("5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty").unwrap();
+
+        assert!(window.check(&device_id, "1", 10000, 10000).is_ok());
+        assert_eq!(
+            window.check(&device_id, "1", 10000, 10500).unwrap_err().code(),
+            BuckyErrorCode::AlreadyExists
+        );
+        assert_eq!(
+            window.check(&device_id, "2", 8000, 10000).unwrap_err().code(),
+            BuckyErrorCode::Expired
+        );
+        assert!(window.check(&device_id, "2", 10500, 10500).is_ok());
+        assert_eq!(
+            window.check(&device_id, "3", 10500, 10800).unwrap_err().code(),
+            BuckyErrorCode::OutOfLimit
+        );
+
+        // 其它设备不受影响
+        let other = DeviceId::from_str("5aSixgLkHa2NR4vSKJLYLPo5Av6CY3RJeFJegtF5iR1g").unwrap();
+        assert!(window.check(&other, "3", 10500, 10800).is_ok());
+
+        // 过期的nonce被清理之后可以接收新的请求
+        assert!(window.check(&device_id, "3", 11600, 11600).is_ok());
+    }
+
+    fn read_body(body: &[u8], expect: HashValue) -> std::io::Result<Vec<u8>> {
+        use async_std::io::ReadExt;
+
+        let mut reader = BodyHashVerifyReader {
+            body: Body::from_bytes(body.to_vec()),
+            hasher: sha2::Sha256::new(),
+            expect,
+            done: false,
+        };
+
+        async_std::task::block_on(async move {
+            let mut buf = vec![];
+            reader.read_to_end(&mut buf).await?;
+            Ok(buf)
+        })
+    }
+
+    #[test]
+    fn test_body_hash() {
+        let body = b"signed request body";
+        assert_eq!(read_body(body, hash_data(body)).unwrap(), body);
+
+        // body被替换之后读取失败
+        let e = read_body(b"replaced request body", hash_data(body)).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
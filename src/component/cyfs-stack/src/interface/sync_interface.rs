@@ -1,5 +1,7 @@
 use super::http_server::{DefaultHttpServer, HttpDefaultHandler, RawHttpServer};
 use super::{ObjectHttpBdtListener, ObjectListener, SyncHttpListener};
+use crate::bandwidth::BandwidthRecorder;
+use crate::stack::StackRequestGate;
 use crate::sync::*;
 use cyfs_base::BuckyResult;
 use cyfs_bdt::StackGuard;
@@ -12,6 +14,9 @@ pub struct SyncListenerManagerParams {
 
     // bdt协议栈监听的vport列表
     pub bdt_listeners: Vec<u16>,
+
+    pub request_gate: StackRequestGate,
+    pub bandwidth_recorder: BandwidthRecorder,
 }
 
 pub(crate) struct SyncListenerManager {
@@ -39,10 +44,19 @@ impl SyncListenerManager {
             info!("new http-bdt sync bdt listener: vport={}", vport);
             let server = SyncHttpListener::new(RequestProtocol::HttpBdt, sync_server, sync_client);
             let handler = RawHttpServer::new(server.into_server()).into();
-            let http_server = DefaultHttpServer::new(handler, default_handler.clone());
+            let http_server = DefaultHttpServer::new(
+                handler,
+                default_handler.clone(),
+                params.request_gate.clone(),
+                params.bandwidth_recorder.clone(),
+            );
 
-            let bdt_listener =
-                ObjectHttpBdtListener::new(params.bdt_stack.clone(), vport, http_server.into());
+            let bdt_listener = ObjectHttpBdtListener::new(
+                params.bdt_stack.clone(),
+                vport,
+                http_server.into(),
+                None,
+            );
             let bdt_listener = Box::new(bdt_listener) as Box<dyn ObjectListener>;
             self.listeners.push(bdt_listener);
         }
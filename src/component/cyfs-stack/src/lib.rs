@@ -1,11 +1,33 @@
+mod access_stat;
 mod admin;
+mod admin_confirm_api;
+mod api_gateway;
+mod api_gateway_api;
+mod bandwidth;
+mod bandwidth_api;
+mod concurrency;
+mod contacts;
+mod contacts_api;
 mod crypto;
 mod crypto_api;
+mod dec_config;
+mod dec_config_api;
+mod dec_resource;
+mod dec_service;
 mod interface;
 pub mod meta;
 pub mod name;
+mod media;
+mod migration;
+mod object_history;
+mod object_history_api;
+mod object_refs;
+mod object_refs_api;
 //mod default_app;
 mod router_handler;
+mod search;
+mod search_api;
+mod service_discovery_api;
 mod stack;
 mod storage;
 mod sync;
@@ -14,6 +36,8 @@ mod trans_api;
 mod util;
 mod util_api;
 mod zone;
+mod zone_event;
+mod zone_health;
 mod acl;
 mod app;
 mod forward;
@@ -21,7 +45,16 @@ mod ndn;
 pub mod ndn_api;
 mod non;
 mod non_api;
+mod object_pack;
+mod queue;
+mod queue_api;
 mod resolver;
+mod schedule;
+mod schedule_api;
+mod share;
+mod share_api;
+mod trash;
+mod trash_api;
 mod events;
 mod root_state;
 mod root_state_api;
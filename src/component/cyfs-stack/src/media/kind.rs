@@ -0,0 +1,56 @@
+// 识别媒体类型需要的头部字节数
+pub(crate) const MEDIA_SNIFF_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+impl MediaKind {
+    // File对象不带mime信息，只能通过数据头部的magic来识别
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0xFF, 0xD8, 0xFF])
+            || head.starts_with(&[0x89, b'P', b'N', b'G'])
+            || head.starts_with(b"GIF8")
+            || head.starts_with(b"BM")
+            || (head.starts_with(b"RIFF") && head.len() >= 12 && &head[8..12] == b"WEBP")
+        {
+            return Some(Self::Image);
+        }
+
+        if (head.len() >= 8 && &head[4..8] == b"ftyp")
+            || head.starts_with(&[0x1A, 0x45, 0xDF, 0xA3])
+            || (head.starts_with(b"RIFF") && head.len() >= 12 && &head[8..12] == b"AVI ")
+        {
+            return Some(Self::Video);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sniff_media_kind() {
+        assert_eq!(
+            MediaKind::sniff(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0x10]),
+            Some(MediaKind::Image)
+        );
+        assert_eq!(
+            MediaKind::sniff(b"\x89PNG\r\n\x1a\n"),
+            Some(MediaKind::Image)
+        );
+        assert_eq!(MediaKind::sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(MediaKind::Image));
+        assert_eq!(
+            MediaKind::sniff(b"\0\0\0\x20ftypisom"),
+            Some(MediaKind::Video)
+        );
+        assert_eq!(MediaKind::sniff(b"RIFF\0\0\0\0AVI LIST"), Some(MediaKind::Video));
+        assert_eq!(MediaKind::sniff(b"hello world"), None);
+        assert_eq!(MediaKind::sniff(b""), None);
+    }
+}
@@ -0,0 +1,8 @@
+mod kind;
+mod param;
+mod service;
+mod transcode;
+
+pub use kind::*;
+pub use param::*;
+pub(crate) use service::*;
@@ -0,0 +1,80 @@
+use cyfs_base::*;
+
+// 输出尺寸的上限，避免请求方用过大的尺寸消耗资源
+const MAX_MEDIA_SIZE: u32 = 4096;
+
+const DEFAULT_MEDIA_QUALITY: u8 = 80;
+
+// /o和/r请求上的?size=&quality=参数
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MediaTransformParam {
+    // 输出的最长边，单位像素，为空则保持原尺寸
+    pub size: Option<u32>,
+
+    // 输出质量，1-100
+    pub quality: Option<u8>,
+}
+
+impl MediaTransformParam {
+    // 两个参数都没有则不需要转换，返回None
+    pub fn new(size: Option<u32>, quality: Option<u8>) -> BuckyResult<Option<Self>> {
+        if size.is_none() && quality.is_none() {
+            return Ok(None);
+        }
+
+        if let Some(size) = size {
+            if size == 0 || size > MAX_MEDIA_SIZE {
+                let msg = format!(
+                    "invalid media size param! size={}, max={}",
+                    size, MAX_MEDIA_SIZE
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        if let Some(quality) = quality {
+            if quality == 0 || quality > 100 {
+                let msg = format!("invalid media quality param! quality={}", quality);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        Ok(Some(Self { size, quality }))
+    }
+
+    pub fn quality(&self) -> u8 {
+        self.quality.unwrap_or(DEFAULT_MEDIA_QUALITY)
+    }
+
+    // 衍生数据的缓存key，相同的输出参数总是得到相同的key
+    pub fn key(&self) -> String {
+        format!("s{}_q{}", self.size.unwrap_or(0), self.quality())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_media_param() {
+        assert!(MediaTransformParam::new(None, None).unwrap().is_none());
+        assert!(MediaTransformParam::new(Some(0), None).is_err());
+        assert!(MediaTransformParam::new(Some(MAX_MEDIA_SIZE + 1), None).is_err());
+        assert!(MediaTransformParam::new(None, Some(101)).is_err());
+
+        let param = MediaTransformParam::new(Some(256), None).unwrap().unwrap();
+        assert_eq!(param.key(), "s256_q80");
+
+        // 显式指定默认质量和不指定得到同一个衍生数据
+        let param2 = MediaTransformParam::new(Some(256), Some(DEFAULT_MEDIA_QUALITY))
+            .unwrap()
+            .unwrap();
+        assert_eq!(param.key(), param2.key());
+
+        let param = MediaTransformParam::new(None, Some(30)).unwrap().unwrap();
+        assert_eq!(param.key(), "s0_q30");
+    }
+}
@@ -0,0 +1,257 @@
+use super::kind::*;
+use super::param::MediaTransformParam;
+use super::transcode::MediaTranscoder;
+use crate::ndn::NDNInputProcessorRef;
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use async_std::io::{ReadExt, WriteExt};
+use std::sync::Arc;
+
+// 衍生数据在local_cache里的记录: /media/derivative/{source_file_id}/{param_key} -> chunk_id
+const MEDIA_DERIVATIVE_PATH: &str = "/media/derivative";
+
+// 图片需要整个读到内存里解码
+const MAX_IMAGE_SOURCE_SIZE: u64 = 1024 * 1024 * 64;
+
+// 按需生成并缓存媒体文件的缩略图或者低码率版本，衍生数据作为独立的chunk保存
+#[derive(Clone)]
+pub(crate) struct MediaService {
+    ndn: NDNInputProcessorRef,
+    local_cache_stub: GlobalStateStub,
+    transcoder: Arc<MediaTranscoder>,
+}
+
+impl MediaService {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        ndn: NDNInputProcessorRef,
+        local_cache: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+        let processor = GlobalStateOutputTransformer::new(local_cache, source);
+        let local_cache_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        Ok(Self {
+            ndn,
+            local_cache_stub,
+            transcoder: Arc::new(MediaTranscoder::new()),
+        })
+    }
+
+    fn derivative_path(file_id: &ObjectId, param: &MediaTransformParam) -> String {
+        format!("{}/{}/{}", MEDIA_DERIVATIVE_PATH, file_id, param.key())
+    }
+
+    // 查找已经生成过的衍生数据
+    pub async fn get_cached(
+        &self,
+        file_id: &ObjectId,
+        param: &MediaTransformParam,
+    ) -> BuckyResult<Option<NDNGetDataInputResponse>> {
+        let path = Self::derivative_path(file_id, param);
+
+        let op_env = self.local_cache_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_path(&path).await;
+        let _ = op_env.abort().await;
+
+        let chunk_id = match ret? {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let req = NDNGetDataInputRequest {
+            common: Self::new_local_common(),
+            object_id: chunk_id.clone(),
+            data_type: NDNDataType::Mem,
+            range: None,
+            inner_path: None,
+            context: None,
+            group: None,
+        };
+
+        match self.ndn.get_data(req).await {
+            Ok(resp) => {
+                info!(
+                    "got media derivative from cache: file={}, param={}, chunk={}",
+                    file_id,
+                    param.key(),
+                    chunk_id
+                );
+                Ok(Some(resp))
+            }
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                // 记录还在但chunk已经被清理，需要重新生成
+                warn!(
+                    "media derivative chunk missing, now will regenerate! file={}, chunk={}",
+                    file_id, chunk_id
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // 用源文件的数据生成衍生数据，保存后返回
+    pub async fn transcode(
+        &self,
+        file_id: &ObjectId,
+        mut source: NDNGetDataInputResponse,
+        param: &MediaTransformParam,
+    ) -> BuckyResult<NDNGetDataInputResponse> {
+        let mut head = Vec::with_capacity(MEDIA_SNIFF_LEN);
+        (&mut source.data)
+            .take(MEDIA_SNIFF_LEN as u64)
+            .read_to_end(&mut head)
+            .await
+            .map_err(|e| {
+                let msg = format!("read media source failed! file={}, {}", file_id, e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        let kind = MediaKind::sniff(&head).ok_or_else(|| {
+            let msg = format!("file is not supported media! file={}", file_id);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotSupport, msg)
+        })?;
+
+        info!(
+            "will transcode media: file={}, kind={:?}, len={}, param={:?}",
+            file_id, kind, source.length, param
+        );
+
+        let data = match kind {
+            MediaKind::Image => {
+                if source.length > MAX_IMAGE_SOURCE_SIZE {
+                    let msg = format!(
+                        "image file too large to transcode! file={}, len={}, max={}",
+                        file_id, source.length, MAX_IMAGE_SOURCE_SIZE
+                    );
+                    warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+                }
+
+                let mut data = head;
+                source.data.read_to_end(&mut data).await.map_err(|e| {
+                    let msg = format!("read media source failed! file={}, {}", file_id, e);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                })?;
+
+                self.transcoder.transcode_image(data, param).await?
+            }
+            MediaKind::Video => {
+                let tmp = self
+                    .transcoder
+                    .gen_tmp_file(&format!("{}_{}.src", file_id, param.key()))?;
+
+                let ret = match Self::save_to_file(&tmp, &head, &mut source.data).await {
+                    Ok(()) => self.transcoder.transcode_video(&tmp, param).await,
+                    Err(e) => Err(e),
+                };
+                let _ = async_std::fs::remove_file(&tmp).await;
+
+                ret?
+            }
+        };
+
+        let chunk_id = self.save_derivative(file_id, param, data.clone()).await?;
+
+        Ok(NDNGetDataInputResponse {
+            object_id: chunk_id.object_id(),
+            owner_id: source.owner_id,
+            attr: None,
+            range: None,
+            group: None,
+            length: data.len() as u64,
+            data: Box::new(async_std::io::Cursor::new(data)),
+        })
+    }
+
+    async fn save_to_file(
+        file: &std::path::Path,
+        head: &[u8],
+        data: &mut (dyn async_std::io::Read + Unpin + Send + Sync),
+    ) -> BuckyResult<()> {
+        let mut writer = async_std::fs::File::create(file).await.map_err(|e| {
+            let msg = format!("create media tmp file failed! file={}, {}", file.display(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let ret = async {
+            writer.write_all(head).await?;
+            async_std::io::copy(data, &mut writer).await?;
+            writer.flush().await
+        }
+        .await;
+
+        ret.map_err(|e: std::io::Error| {
+            let msg = format!("write media tmp file failed! file={}, {}", file.display(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })
+    }
+
+    async fn save_derivative(
+        &self,
+        file_id: &ObjectId,
+        param: &MediaTransformParam,
+        data: Vec<u8>,
+    ) -> BuckyResult<ChunkId> {
+        let chunk_id = ChunkId::calculate_sync(&data)?;
+
+        let req = NDNPutDataInputRequest {
+            common: Self::new_local_common(),
+            object_id: chunk_id.object_id(),
+            data_type: NDNDataType::Mem,
+            length: data.len() as u64,
+            data: Box::new(async_std::io::Cursor::new(data)),
+        };
+        self.ndn.put_data(req).await.map_err(|e| {
+            error!(
+                "save media derivative chunk failed! file={}, chunk={}, {}",
+                file_id, chunk_id, e
+            );
+            e
+        })?;
+
+        let path = Self::derivative_path(file_id, param);
+        let op_env = self.local_cache_stub.create_path_op_env().await?;
+        op_env
+            .set_with_path(&path, &chunk_id.object_id(), None, true)
+            .await?;
+        op_env.commit().await?;
+
+        info!(
+            "save media derivative success! file={}, param={}, chunk={}",
+            file_id,
+            param.key(),
+            chunk_id
+        );
+
+        Ok(chunk_id)
+    }
+
+    fn new_local_common() -> NDNInputRequestCommon {
+        NDNInputRequestCommon {
+            req_path: None,
+            source: RequestSourceInfo::new_local_system(),
+            level: NDNAPILevel::NDC,
+            referer_object: vec![],
+            target: None,
+            flags: 0,
+            deadline: None,
+            user_data: None,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::param::MediaTransformParam;
+use cyfs_base::*;
+
+use std::path::{Path, PathBuf};
+
+pub(crate) struct MediaTranscoder {
+    tmp_dir: PathBuf,
+}
+
+impl MediaTranscoder {
+    pub fn new() -> Self {
+        let tmp_dir = cyfs_util::get_cyfs_root_path().join("tmp").join("media");
+
+        Self { tmp_dir }
+    }
+
+    pub fn gen_tmp_file(&self, name: &str) -> BuckyResult<PathBuf> {
+        if !self.tmp_dir.is_dir() {
+            std::fs::create_dir_all(&self.tmp_dir).map_err(|e| {
+                let msg = format!(
+                    "create media tmp dir failed! dir={}, {}",
+                    self.tmp_dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+        }
+
+        Ok(self.tmp_dir.join(name))
+    }
+
+    // 图片缩放后统一输出为jpeg
+    #[cfg(feature = "media")]
+    pub async fn transcode_image(
+        &self,
+        data: Vec<u8>,
+        param: &MediaTransformParam,
+    ) -> BuckyResult<Vec<u8>> {
+        let size = param.size;
+        let quality = param.quality();
+
+        async_std::task::spawn_blocking(move || {
+            let image = image::load_from_memory(&data).map_err(|e| {
+                let msg = format!("decode image failed! {}", e);
+                warn!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?;
+
+            let image = match size {
+                Some(size) if image.width() > size || image.height() > size => {
+                    image.thumbnail(size, size)
+                }
+                _ => image,
+            };
+
+            let mut buf = vec![];
+            let mut encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality);
+            encoder
+                .encode_image(&image.to_rgb8())
+                .map_err(|e| {
+                    let msg = format!("encode image failed! {}", e);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::Failed, msg)
+                })?;
+
+            Ok(buf)
+        })
+        .await
+    }
+
+    #[cfg(not(feature = "media"))]
+    pub async fn transcode_image(
+        &self,
+        _data: Vec<u8>,
+        _param: &MediaTransformParam,
+    ) -> BuckyResult<Vec<u8>> {
+        let msg = format!("image transcode not support, cyfs-stack is built without media feature!");
+        warn!("{}", msg);
+        Err(BuckyError::new(BuckyErrorCode::NotSupport, msg))
+    }
+
+    // 视频依赖系统里的ffmpeg，输出h264的mp4
+    pub async fn transcode_video(
+        &self,
+        source: &Path,
+        param: &MediaTransformParam,
+    ) -> BuckyResult<Vec<u8>> {
+        let target = source.with_extension("out.mp4");
+
+        // quality映射到crf: 100 -> 18, 1 -> 51
+        let crf = 18 + (100 - param.quality() as u32) * 33 / 99;
+
+        let mut cmd = async_std::process::Command::new("ffmpeg");
+        cmd.arg("-y")
+            .arg("-loglevel")
+            .arg("error")
+            .arg("-i")
+            .arg(source);
+        if let Some(size) = param.size {
+            // 限制最长边，另一边按比例缩放并保持偶数
+            let scale = format!(
+                "scale='if(gte(iw,ih),min({size},iw),-2)':'if(gte(iw,ih),-2,min({size},ih))'",
+                size = size
+            );
+            cmd.arg("-vf").arg(scale);
+        }
+        cmd.arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-crf")
+            .arg(crf.to_string())
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-movflags")
+            .arg("+faststart")
+            .arg(&target);
+
+        let output = cmd.output().await.map_err(|e| {
+            let code = if e.kind() == std::io::ErrorKind::NotFound {
+                BuckyErrorCode::NotSupport
+            } else {
+                BuckyErrorCode::IoError
+            };
+            let msg = format!("run ffmpeg for video transcode failed! {}", e);
+            error!("{}", msg);
+            BuckyError::new(code, msg)
+        })?;
+
+        let ret = if output.status.success() {
+            async_std::fs::read(&target).await.map_err(|e| {
+                let msg = format!(
+                    "read transcoded video failed! file={}, {}",
+                    target.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })
+        } else {
+            let msg = format!(
+                "ffmpeg transcode video failed! status={}, {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            error!("{}", msg);
+            Err(BuckyError::new(BuckyErrorCode::Failed, msg))
+        };
+
+        let _ = async_std::fs::remove_file(&target).await;
+
+        ret
+    }
+}
@@ -0,0 +1,217 @@
+use crate::events::RouterEventsManager;
+use crate::resolver::DeviceInfoManager;
+use cyfs_base::*;
+use cyfs_base_meta::SavedMetaObject;
+use cyfs_lib::*;
+use cyfs_meta_lib::{MetaClient, MetaMinerTarget};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+const DESC_RENEW_CHECK_INTERVAL_IN_SECS: u64 = 60 * 60;
+
+// 距离过期不足7天就开始告警，bucky_time的单位是微秒
+const DESC_EXPIRE_ALERT_AHEAD: u64 = 1000 * 1000 * 60 * 60 * 24 * 7;
+
+// 定期检查本地device和owner在meta链上的desc:
+// 1. 链上device的body落后于本地时，使用本地私钥重新签名并update_desc，保持链上记录有效
+// 2. desc的expired_time参与object_id的计算，无法原地延长，临近过期或者已经过期只能告警，由用户重新签发
+// 任何一步失败都会触发desc_renew_failed事件
+#[derive(Clone)]
+pub(crate) struct DescRenewManager {
+    device_manager: DeviceInfoManager,
+    meta_client: Arc<MetaClient>,
+    signer: RsaCPUObjectSigner,
+    secret: PrivateKey,
+    event_manager: RouterEventsManager,
+}
+
+impl DescRenewManager {
+    pub fn new(
+        target: MetaMinerTarget,
+        device_manager: DeviceInfoManager,
+        signer: RsaCPUObjectSigner,
+        secret: PrivateKey,
+        event_manager: RouterEventsManager,
+    ) -> Self {
+        let meta_client =
+            MetaClient::new_target(target).with_timeout(Duration::from_secs(60 * 2));
+
+        Self {
+            device_manager,
+            meta_client: Arc::new(meta_client),
+            signer,
+            secret,
+            event_manager,
+        }
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            loop {
+                this.check_once().await;
+                async_std::task::sleep(Duration::from_secs(DESC_RENEW_CHECK_INTERVAL_IN_SECS))
+                    .await;
+            }
+        });
+    }
+
+    async fn check_once(&self) {
+        let device = self.device_manager.local_device();
+
+        if let Err(e) = self.renew_device(device.clone()).await {
+            self.on_renew_failed(device.desc().object_id(), device.desc().expired_time(), e)
+                .await;
+        }
+
+        if let Some(owner) = device.desc().owner() {
+            if let Err((expired_time, e)) = self.check_owner(owner).await {
+                self.on_renew_failed(owner.to_owned(), expired_time, e).await;
+            }
+        }
+    }
+
+    async fn renew_device(&self, mut device: Device) -> BuckyResult<()> {
+        let device_id = device.desc().object_id();
+        Self::check_expired(device.desc().expired_time(), bucky_time_now())?;
+
+        let chain_device = match self.meta_client.get_desc(&device_id).await {
+            Ok(SavedMetaObject::Device(chain_device)) => chain_device,
+            Ok(_) => {
+                let msg = format!(
+                    "desc on meta chain is not device! device={}",
+                    device_id
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                // 没有上链的device不需要续期
+                debug!("device not found on meta chain, renew skipped! device={}", device_id);
+                return Ok(());
+            }
+            Err(e) => {
+                error!("get device desc from meta chain failed! device={}, {}", device_id, e);
+                return Err(e);
+            }
+        };
+
+        let chain_update_time = chain_device.body().as_ref().map_or(0, |b| b.update_time());
+        let local_update_time = device.body().as_ref().map_or(0, |b| b.update_time());
+        if chain_update_time >= local_update_time {
+            return Ok(());
+        }
+
+        info!(
+            "device desc on meta chain is outdated, now will renew! device={}, chain={}, local={}",
+            device_id, chain_update_time, local_update_time
+        );
+
+        device
+            .body_mut()
+            .as_mut()
+            .unwrap()
+            .increase_update_time(bucky_time_now());
+        sign_and_set_named_object_body(
+            &self.signer,
+            &mut device,
+            &SignatureSource::RefIndex(SIGNATURE_SOURCE_REFINDEX_SELF),
+        )
+        .await?;
+
+        let tx_id = self
+            .meta_client
+            .update_desc(
+                &StandardObject::Device(device.clone()),
+                &SavedMetaObject::Device(device.clone()),
+                None,
+                None,
+                &self.secret,
+            )
+            .await
+            .map_err(|e| {
+                error!("renew device desc on meta chain failed! device={}, {}", device_id, e);
+                e
+            })?;
+
+        info!("renew device desc on meta chain success! device={}, tx={}", device_id, tx_id);
+        self.device_manager.update_local_device(&device);
+
+        Ok(())
+    }
+
+    // 本地没有people的私钥，只能检查链上的状态
+    async fn check_owner(&self, owner: &ObjectId) -> Result<(), (Option<u64>, BuckyError)> {
+        let people = match self.meta_client.get_desc(owner).await {
+            Ok(SavedMetaObject::People(people)) => people,
+            Ok(_) => {
+                // owner也可能是其它类型的对象，这里只检查people
+                return Ok(());
+            }
+            Err(e) => {
+                error!("get owner desc from meta chain failed! owner={}, {}", owner, e);
+                return Err((None, e));
+            }
+        };
+
+        let expired_time = people.desc().expired_time();
+        Self::check_expired(expired_time, bucky_time_now()).map_err(|e| (expired_time, e))
+    }
+
+    fn check_expired(expired_time: Option<u64>, now: u64) -> BuckyResult<()> {
+        match expired_time {
+            Some(expired_time) if expired_time <= now + DESC_EXPIRE_ALERT_AHEAD => {
+                let msg = if expired_time <= now {
+                    format!("desc already expired! expired_time={}", expired_time)
+                } else {
+                    format!("desc will expire soon! expired_time={}", expired_time)
+                };
+                warn!("{}", msg);
+
+                Err(BuckyError::new(BuckyErrorCode::Expired, msg))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn on_renew_failed(&self, object_id: ObjectId, expired_time: Option<u64>, error: BuckyError) {
+        error!(
+            "desc renew failed! object={}, expired_time={:?}, {}",
+            object_id, expired_time, error
+        );
+
+        let event = self.event_manager.events().try_desc_renew_failed_event();
+        if event.is_none() {
+            return;
+        }
+
+        let param = DescRenewFailedEventRequest {
+            object_id,
+            expired_time,
+            error,
+        };
+
+        let mut emitter = event.unwrap().emitter();
+        let resp = emitter.emit(param).await;
+        info!("desc renew failed event resp: {}", resp);
+    }
+}
+
+#[cfg(test)]
+mod desc_renew_tests {
+    use super::*;
+
+    #[test]
+    fn test_check_expired() {
+        let now = bucky_time_now();
+        assert!(DescRenewManager::check_expired(None, now).is_ok());
+        assert!(DescRenewManager::check_expired(Some(now + DESC_EXPIRE_ALERT_AHEAD * 2), now).is_ok());
+
+        let e = DescRenewManager::check_expired(Some(now + 1000), now).unwrap_err();
+        assert_eq!(e.code(), BuckyErrorCode::Expired);
+
+        let e = DescRenewManager::check_expired(Some(now - 1000), now).unwrap_err();
+        assert_eq!(e.code(), BuckyErrorCode::Expired);
+    }
+}
@@ -0,0 +1,193 @@
+use super::MetaCacheRef;
+use crate::resolver::{DeviceCache, DeviceInfoManager};
+use cyfs_base::*;
+use cyfs_bdt::StackGuard;
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+const DEVICE_REFRESH_CHECK_INTERVAL_IN_SECS: u64 = 60 * 5;
+
+// 缓存里的device超过一个小时没有刷新就重新获取，bucky_time的单位是微秒
+const DEVICE_DESC_TTL: u64 = 1000 * 1000 * 60 * 60;
+
+// 每轮最多刷新的device数量，连接失败的优先
+const DEVICE_REFRESH_MAX_PER_ROUND: usize = 32;
+
+const SN_QUERY_TIMEOUT_IN_SECS: u64 = 10;
+
+// 后台定期刷新device cache里过期的desc和连接失败的device:
+// 1. 先从meta链刷新，链上有更新的版本时替换缓存
+// 2. 连接失败的device再通过sn查询，对端更换了endpoint但是没有上链时也可以拿到最新的desc
+// desc有变化时同步到bdt，重置没有连通的tunnel，下次建立时使用新的endpoint
+#[derive(Clone)]
+pub(crate) struct DeviceDescRefresher {
+    meta_cache: MetaCacheRef,
+    device_manager: DeviceInfoManager,
+    bdt_stack: StackGuard,
+}
+
+impl DeviceDescRefresher {
+    pub fn new(
+        meta_cache: MetaCacheRef,
+        device_manager: DeviceInfoManager,
+        bdt_stack: StackGuard,
+    ) -> Self {
+        Self {
+            meta_cache,
+            device_manager,
+            bdt_stack,
+        }
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            loop {
+                async_std::task::sleep(Duration::from_secs(DEVICE_REFRESH_CHECK_INTERVAL_IN_SECS))
+                    .await;
+                this.refresh_once().await;
+            }
+        });
+    }
+
+    async fn refresh_once(&self) {
+        let failed: HashSet<DeviceId> = self
+            .bdt_stack
+            .tunnel_manager()
+            .dead_remotes()
+            .into_iter()
+            .collect();
+
+        let mut list: Vec<DeviceId> = failed.iter().cloned().collect();
+        for device_id in self.device_manager.stale_devices(DEVICE_DESC_TTL) {
+            if !failed.contains(&device_id) {
+                list.push(device_id);
+            }
+        }
+        list.truncate(DEVICE_REFRESH_MAX_PER_ROUND);
+
+        if list.is_empty() {
+            return;
+        }
+
+        info!(
+            "will refresh device descs, count={}, failed={}",
+            list.len(),
+            failed.len()
+        );
+
+        let mut updated = 0;
+        for device_id in list {
+            let is_failed = failed.contains(&device_id);
+            match self.refresh_device(&device_id, is_failed).await {
+                Ok(true) => updated += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("refresh device desc failed! device={}, {}", device_id, e);
+                }
+            }
+        }
+
+        info!("refresh device descs complete, updated={}", updated);
+    }
+
+    // 返回desc是否有更新
+    async fn refresh_device(&self, device_id: &DeviceId, is_failed: bool) -> BuckyResult<bool> {
+        let old = self.device_manager.get_device(device_id).await;
+        let old_update_time = old.as_ref().map_or(0, |d| d.latest_update_time());
+
+        match self.meta_cache.flush_object(device_id.object_id()).await {
+            Ok(true) => {
+                info!("flush device from meta and changed! device={}", device_id);
+                self.device_manager.flush(device_id).await;
+            }
+            Ok(false) => {
+                debug!("flush device from meta and unchanged! device={}", device_id);
+            }
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                debug!("device not found on meta chain! device={}", device_id);
+            }
+            Err(e) => {
+                warn!("flush device from meta failed! device={}, {}", device_id, e);
+            }
+        }
+
+        if is_failed {
+            if let Err(e) = self.query_from_sn(device_id, old.as_ref()).await {
+                warn!("query device from sn failed! device={}, {}", device_id, e);
+            }
+        }
+
+        self.device_manager.mark_refreshed(device_id);
+
+        let latest = match self.device_manager.get_device(device_id).await {
+            Some(device) => device,
+            None => {
+                let msg = format!("device not found after refresh! device={}", device_id);
+                return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+            }
+        };
+
+        let changed = match &old {
+            Some(old) => {
+                latest.latest_update_time() > old_update_time
+                    || latest.connect_info().endpoints() != old.connect_info().endpoints()
+            }
+            None => true,
+        };
+
+        if changed {
+            info!(
+                "device desc refreshed! device={}, update_time {} -> {}, endpoints={:?}",
+                device_id,
+                old_update_time,
+                latest.latest_update_time(),
+                latest.connect_info().endpoints()
+            );
+            self.bdt_stack
+                .tunnel_manager()
+                .update_remote_device(&latest);
+        }
+
+        Ok(changed)
+    }
+
+    async fn query_from_sn(&self, device_id: &DeviceId, old: Option<&Device>) -> BuckyResult<()> {
+        let mut sn_list = old
+            .map(|d| d.connect_info().sn_list().clone())
+            .unwrap_or_default();
+        if sn_list.is_empty() {
+            sn_list = self.bdt_stack.sn_client().cache().known_list();
+        }
+        if sn_list.is_empty() {
+            let msg = format!("no sn to query device! device={}", device_id);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        let device = async_std::future::timeout(
+            Duration::from_secs(SN_QUERY_TIMEOUT_IN_SECS),
+            self.bdt_stack.sn_client().query_device(device_id, &sn_list),
+        )
+        .await
+        .map_err(|_| {
+            let msg = format!("query device from sn timeout! device={}", device_id);
+            BuckyError::new(BuckyErrorCode::Timeout, msg)
+        })??;
+
+        if device.desc().device_id() != *device_id {
+            let msg = format!(
+                "query device from sn but unmatch! device={}, got={}",
+                device_id,
+                device.desc().device_id()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        // 只有更新时间比缓存里新的才会替换
+        DeviceCache::add(&self.device_manager, device_id, device).await;
+
+        Ok(())
+    }
+}
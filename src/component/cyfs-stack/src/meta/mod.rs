@@ -3,7 +3,11 @@ mod raw_meta;
 mod fail_handler;
 mod fail_cache;
 mod cache;
+mod desc_renew;
+mod device_refresh;
 
 pub use meta_cache::*;
 pub use raw_meta::*;
-pub(crate) use fail_handler::*;
\ No newline at end of file
+pub(crate) use fail_handler::*;
+pub(crate) use desc_renew::*;
+pub(crate) use device_refresh::*;
\ No newline at end of file
@@ -104,6 +104,7 @@ impl RawMetaCache {
             last_access_rpath: None,
             object,
             access_string: None,
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -0,0 +1,116 @@
+use super::manager::*;
+use super::status::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ZoneMigrationRequestHandler {
+    manager: ZoneMigrationManager,
+    zone_manager: ZoneManagerRef,
+}
+
+impl ZoneMigrationRequestHandler {
+    pub fn new(manager: ZoneMigrationManager, zone_manager: ZoneManagerRef) -> Self {
+        Self {
+            manager,
+            zone_manager,
+        }
+    }
+
+    // 本地http接口上的请求来源，用来匹配WebAuthn确认的申请者
+    async fn source<State: Send>(
+        &self,
+        req: &tide::Request<State>,
+    ) -> BuckyResult<RequestSourceInfo> {
+        let dec_id: Option<ObjectId> =
+            RequestorHelper::decode_optional_header(req, cyfs_base::CYFS_DEC_ID)?;
+
+        self.zone_manager.get_current_source_info(&dec_id).await
+    }
+
+    fn encode_status_response(status: &ZoneMigrationStatus) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(status).unwrap());
+
+        http_resp.into()
+    }
+
+    fn encode_result(ret: BuckyResult<ZoneMigrationStatus>) -> Response {
+        match ret {
+            Ok(status) => Self::encode_status_response(&status),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    fn task_id<State: Send>(req: &tide::Request<State>) -> BuckyResult<String> {
+        let id: Option<String> =
+            RequestorHelper::value_from_querys_with_utf8_decoding("id", req.url())?;
+
+        id.ok_or_else(|| {
+            let msg = format!(
+                "zone migration request but id param missing! url={}",
+                req.url()
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })
+    }
+
+    // start
+    pub async fn process_start_request<State: Send>(&self, req: tide::Request<State>) -> Response {
+        Self::encode_result(self.on_start(req).await)
+    }
+
+    async fn on_start<State: Send>(
+        &self,
+        mut req: tide::Request<State>,
+    ) -> BuckyResult<ZoneMigrationStatus> {
+        let params: ZoneMigrationParams = req.body_json().await.map_err(|e| {
+            let msg = format!("read zone migration params from body failed! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        let source = self.source(&req).await?;
+        self.manager.start(&source, params).await
+    }
+
+    // continue
+    pub async fn process_continue_request<State: Send>(
+        &self,
+        req: tide::Request<State>,
+    ) -> Response {
+        let ret = match Self::task_id(&req) {
+            Ok(id) => match self.source(&req).await {
+                Ok(source) => self.manager.continue_step(&source, &id).await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        Self::encode_result(ret)
+    }
+
+    // abort
+    pub async fn process_abort_request<State: Send>(&self, req: tide::Request<State>) -> Response {
+        let ret = match Self::task_id(&req) {
+            Ok(id) => self.manager.abort(&id).await,
+            Err(e) => Err(e),
+        };
+        Self::encode_result(ret)
+    }
+
+    // status
+    pub async fn process_get_status_request<State: Send>(
+        &self,
+        req: tide::Request<State>,
+    ) -> Response {
+        let ret = Self::task_id(&req).and_then(|id| self.manager.get_status(&id));
+        Self::encode_result(ret)
+    }
+}
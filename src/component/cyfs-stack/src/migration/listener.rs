@@ -0,0 +1,62 @@
+use super::handler::ZoneMigrationRequestHandler;
+
+enum ZoneMigrationRequestType {
+    Start,
+    Continue,
+    Abort,
+    GetStatus,
+}
+
+pub(crate) struct ZoneMigrationRequestHandlerEndpoint {
+    req_type: ZoneMigrationRequestType,
+    handler: ZoneMigrationRequestHandler,
+}
+
+impl ZoneMigrationRequestHandlerEndpoint {
+    fn new(req_type: ZoneMigrationRequestType, handler: ZoneMigrationRequestHandler) -> Self {
+        Self { req_type, handler }
+    }
+
+    async fn process_request<State: Send>(&self, req: tide::Request<State>) -> tide::Response {
+        match self.req_type {
+            ZoneMigrationRequestType::Start => self.handler.process_start_request(req).await,
+            ZoneMigrationRequestType::Continue => self.handler.process_continue_request(req).await,
+            ZoneMigrationRequestType::Abort => self.handler.process_abort_request(req).await,
+            ZoneMigrationRequestType::GetStatus => {
+                self.handler.process_get_status_request(req).await
+            }
+        }
+    }
+
+    // 只在本地的http协议上开放，由管理员在当前ood上发起迁移
+    pub fn register_server(handler: &ZoneMigrationRequestHandler, server: &mut ::tide::Server<()>) {
+        server
+            .at("/migration/start")
+            .post(Self::new(ZoneMigrationRequestType::Start, handler.clone()));
+
+        server.at("/migration/continue").post(Self::new(
+            ZoneMigrationRequestType::Continue,
+            handler.clone(),
+        ));
+
+        server
+            .at("/migration/abort")
+            .post(Self::new(ZoneMigrationRequestType::Abort, handler.clone()));
+
+        server.at("/migration/status").get(Self::new(
+            ZoneMigrationRequestType::GetStatus,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait::async_trait]
+impl<State> tide::Endpoint<State> for ZoneMigrationRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
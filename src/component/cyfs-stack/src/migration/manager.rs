@@ -0,0 +1,768 @@
+use super::remote::ZoneMigrationRemote;
+use super::status::*;
+use crate::admin::AdminConfirmManager;
+use crate::object_pack::{ObjectPackExportFilter, ObjectPackImportState, ObjectPackManager};
+use crate::NamedDataComponents;
+use cyfs_backup::{BackupManager, UniBackupTask};
+use cyfs_backup_lib::*;
+use cyfs_base::*;
+use cyfs_base_meta::SavedMetaObject;
+use cyfs_bdt::StackGuard;
+use cyfs_lib::*;
+use cyfs_meta_lib::{MetaClient, MetaMinerTarget};
+
+use futures::future::{AbortHandle, Abortable};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 查询新ood上恢复和导入任务进度的间隔
+const ZONE_MIGRATION_POLL_INTERVAL_SECS: u64 = 5;
+
+// 恢复完成后等待新ood协议栈上线的最长时间
+const ZONE_MIGRATION_STACK_ONLINE_TIMEOUT_SECS: u64 = 60 * 10;
+
+// 每次执行回放步骤最多的轮数，增量足够小或者没有新增对象就结束
+const ZONE_MIGRATION_REPLAY_MAX_ROUNDS: u32 = 8;
+
+struct ZoneMigrationTaskState {
+    status: ZoneMigrationStatus,
+
+    // 快照使用的备份任务，用以查询备份进度
+    backup: Option<(String, Arc<BackupManager>)>,
+
+    abort_handle: Option<AbortHandle>,
+}
+
+struct ZoneMigrationTaskInner {
+    params: ZoneMigrationParams,
+    remote: ZoneMigrationRemote,
+    state: Mutex<ZoneMigrationTaskState>,
+}
+
+#[derive(Clone)]
+struct ZoneMigrationTask(Arc<ZoneMigrationTaskInner>);
+
+impl ZoneMigrationTask {
+    fn new(params: ZoneMigrationParams) -> BuckyResult<Self> {
+        let remote = ZoneMigrationRemote::new(&params)?;
+        let state = ZoneMigrationTaskState {
+            status: ZoneMigrationStatus::new(params.id.clone()),
+            backup: None,
+            abort_handle: None,
+        };
+
+        Ok(Self(Arc::new(ZoneMigrationTaskInner {
+            params,
+            remote,
+            state: Mutex::new(state),
+        })))
+    }
+
+    fn id(&self) -> &str {
+        &self.0.params.id
+    }
+
+    fn status(&self) -> ZoneMigrationStatus {
+        let state = self.0.state.lock().unwrap();
+        let mut status = state.status.clone();
+        if let Some((id, manager)) = &state.backup {
+            status.backup = manager.get_task_status(id).ok();
+        }
+
+        status
+    }
+
+    fn update_status(&self, f: impl FnOnce(&mut ZoneMigrationStatus)) {
+        let mut state = self.0.state.lock().unwrap();
+        f(&mut state.status);
+        state.status.last_update_time = bucky_time_now();
+    }
+}
+
+struct ZoneMigrationManagerInner {
+    device_id: DeviceId,
+    isolate: String,
+
+    noc: NamedObjectCacheRef,
+    named_data_components: NamedDataComponents,
+    object_pack: ObjectPackManager,
+    bdt_stack: StackGuard,
+
+    meta_client: MetaClient,
+    signer: RsaCPUObjectSigner,
+    secret: PrivateKey,
+
+    // 恢复到新ood和停止当前ood的注册需要owner的WebAuthn确认
+    confirm: AdminConfirmManager,
+
+    // 同一时间只允许一个迁移任务
+    task: Mutex<Option<ZoneMigrationTask>>,
+}
+
+// 把当前zone的ood迁移到新的硬件上，新ood使用同样的身份:
+// 快照 -> 新ood裸机恢复 -> 回放快照之后的增量 -> 切换sn和链上注册 -> 校验
+// 每一步完成后默认停下来等待确认，失败的步骤确认后会重新执行
+#[derive(Clone)]
+pub(crate) struct ZoneMigrationManager(Arc<ZoneMigrationManagerInner>);
+
+impl ZoneMigrationManager {
+    pub fn new(
+        isolate: &str,
+        noc: NamedObjectCacheRef,
+        named_data_components: NamedDataComponents,
+        object_pack: ObjectPackManager,
+        meta_target: MetaMinerTarget,
+        signer: RsaCPUObjectSigner,
+        secret: PrivateKey,
+        confirm: AdminConfirmManager,
+    ) -> Self {
+        let bdt_stack = named_data_components.bdt_stack().clone();
+        let meta_client =
+            MetaClient::new_target(meta_target).with_timeout(Duration::from_secs(60 * 2));
+
+        let inner = ZoneMigrationManagerInner {
+            device_id: bdt_stack.local_device_id().to_owned(),
+            isolate: isolate.to_owned(),
+            noc,
+            named_data_components,
+            object_pack,
+            bdt_stack,
+            meta_client,
+            signer,
+            secret,
+            confirm,
+            task: Mutex::new(None),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    fn get_task(&self, id: &str) -> BuckyResult<ZoneMigrationTask> {
+        let task = self.0.task.lock().unwrap();
+        match task.as_ref() {
+            Some(task) if task.id() == id => Ok(task.clone()),
+            _ => {
+                let msg = format!("zone migration task not found! id={}", id);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::NotFound, msg))
+            }
+        }
+    }
+
+    pub fn get_status(&self, id: &str) -> BuckyResult<ZoneMigrationStatus> {
+        Ok(self.get_task(id)?.status())
+    }
+
+    pub async fn start(
+        &self,
+        source: &RequestSourceInfo,
+        params: ZoneMigrationParams,
+    ) -> BuckyResult<ZoneMigrationStatus> {
+        if params.id.is_empty() || params.archive_url.is_empty() {
+            let msg = format!(
+                "invalid zone migration params! id={}, archive_url={}",
+                params.id, params.archive_url
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        // 自动执行的任务不会在切换注册之前停下来，所以启动时一并确认
+        if params.auto {
+            self.check_device_revoke(source).await?;
+        }
+        self.0
+            .confirm
+            .check(source, AdminConfirmAction::BackupRestore, &params.id)
+            .await?;
+
+        let task = {
+            let mut current = self.0.task.lock().unwrap();
+            if let Some(task) = current.as_ref() {
+                let status = task.status();
+                if !status.state.is_finished() {
+                    let msg = format!(
+                        "zone migration task already exists! id={}, step={:?}, state={:?}",
+                        status.id, status.step, status.state
+                    );
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+                }
+            }
+
+            let task = ZoneMigrationTask::new(params)?;
+            *current = Some(task.clone());
+            task
+        };
+
+        info!(
+            "will start zone migration: id={}, target ood-control={}, target stack={}, archive={}",
+            task.id(),
+            task.0.params.target_ood_control,
+            task.0.params.target_stack,
+            task.0.params.archive_url
+        );
+
+        self.run(task.clone());
+
+        Ok(task.status())
+    }
+
+    async fn check_device_revoke(&self, source: &RequestSourceInfo) -> BuckyResult<()> {
+        self.0
+            .confirm
+            .check(
+                source,
+                AdminConfirmAction::DeviceRevoke,
+                &self.0.device_id.to_string(),
+            )
+            .await
+    }
+
+    // 确认执行等待中的步骤，或者重新执行失败的步骤
+    pub async fn continue_step(
+        &self,
+        source: &RequestSourceInfo,
+        id: &str,
+    ) -> BuckyResult<ZoneMigrationStatus> {
+        let task = self.get_task(id)?;
+
+        let step = task.0.state.lock().unwrap().status.step;
+        if step == ZoneMigrationStep::SwitchRegistration && !task.0.params.auto {
+            self.check_device_revoke(source).await?;
+        }
+
+        {
+            let mut state = task.0.state.lock().unwrap();
+            match state.status.state {
+                ZoneMigrationState::WaitConfirm | ZoneMigrationState::Failed => {
+                    state.status.state = ZoneMigrationState::Running;
+                }
+                _ => {
+                    let msg = format!(
+                        "zone migration task is not waiting for confirm! id={}, step={:?}, state={:?}",
+                        id, state.status.step, state.status.state
+                    );
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::ErrorState, msg));
+                }
+            }
+        }
+
+        self.run(task.clone());
+
+        Ok(task.status())
+    }
+
+    pub async fn abort(&self, id: &str) -> BuckyResult<ZoneMigrationStatus> {
+        let task = self.get_task(id)?;
+
+        let step = {
+            let mut state = task.0.state.lock().unwrap();
+            if state.status.state.is_finished() {
+                return Ok(state.status.clone());
+            }
+
+            if let Some(handle) = state.abort_handle.take() {
+                handle.abort();
+            }
+
+            state.status.state = ZoneMigrationState::Aborted;
+            state.status.last_update_time = bucky_time_now();
+            state.status.step
+        };
+
+        warn!("zone migration aborted! id={}, step={:?}", id, step);
+
+        match step {
+            ZoneMigrationStep::Restore => {
+                // 新ood上的恢复任务需要单独取消
+                if let Err(e) = task.0.remote.abort_restore(id).await {
+                    error!("abort restore task on target ood failed! id={}, {}", id, e);
+                }
+            }
+            ZoneMigrationStep::SwitchRegistration | ZoneMigrationStep::Verify => {
+                warn!(
+                    "zone migration aborted after sn ping stopped, restart the stack to register again! id={}",
+                    id
+                );
+            }
+            _ => {}
+        }
+
+        Ok(task.status())
+    }
+
+    fn run(&self, task: ZoneMigrationTask) {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        {
+            let mut state = task.0.state.lock().unwrap();
+            state.abort_handle = Some(abort_handle);
+            state.status.state = ZoneMigrationState::Running;
+            state.status.error = None;
+            state.status.last_update_time = bucky_time_now();
+        }
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            let fut = Abortable::new(this.run_steps(task.clone()), abort_registration);
+            if let Err(futures::future::Aborted) = fut.await {
+                warn!("zone migration task aborted! id={}", task.id());
+            }
+        });
+    }
+
+    async fn run_steps(&self, task: ZoneMigrationTask) {
+        loop {
+            let step = task.0.state.lock().unwrap().status.step;
+            info!(
+                "zone migration step begin: id={}, step={:?}",
+                task.id(),
+                step
+            );
+
+            if let Err(e) = self.run_step(&task, step).await {
+                error!(
+                    "zone migration step failed! id={}, step={:?}, {}",
+                    task.id(),
+                    step,
+                    e
+                );
+                task.update_status(|status| {
+                    if status.state == ZoneMigrationState::Running {
+                        status.state = ZoneMigrationState::Failed;
+                        status.error = Some(e);
+                    }
+                });
+                break;
+            }
+
+            let next = step.next();
+            info!(
+                "zone migration step complete: id={}, step={:?}, next={:?}",
+                task.id(),
+                step,
+                next
+            );
+
+            let auto = task.0.params.auto;
+            let mut aborted = false;
+            task.update_status(|status| {
+                // 取消和步骤完成同时发生时保持取消的状态
+                if status.state != ZoneMigrationState::Running {
+                    aborted = true;
+                    return;
+                }
+
+                status.step = next;
+                if next == ZoneMigrationStep::Complete {
+                    status.state = ZoneMigrationState::Complete;
+                } else if !auto {
+                    status.state = ZoneMigrationState::WaitConfirm;
+                }
+            });
+
+            if aborted || next == ZoneMigrationStep::Complete || !auto {
+                break;
+            }
+        }
+    }
+
+    async fn run_step(&self, task: &ZoneMigrationTask, step: ZoneMigrationStep) -> BuckyResult<()> {
+        match step {
+            ZoneMigrationStep::Snapshot => self.snapshot(task).await,
+            ZoneMigrationStep::Provision => self.provision(task).await,
+            ZoneMigrationStep::Restore => self.restore(task).await,
+            ZoneMigrationStep::ReplayDelta => self.replay_delta(task).await,
+            ZoneMigrationStep::SwitchRegistration => self.switch_registration(task).await,
+            ZoneMigrationStep::Verify => self.verify(task).await,
+            ZoneMigrationStep::Complete => Ok(()),
+        }
+    }
+
+    async fn snapshot(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        let params = &task.0.params;
+        let backup_params = UniBackupParams {
+            id: params.id.clone(),
+            isolate: self.0.isolate.clone(),
+            password: params.password.clone(),
+            target_file: LocalFileBackupParam {
+                dir: params.archive_dir.clone(),
+                ..Default::default()
+            },
+            key_data_filters: vec![],
+        };
+
+        // 上一次失败留下的快照目录需要清除，备份要求目标目录为空
+        let archive_dir: PathBuf = UniBackupTask::backup_dir(&backup_params).into_owned();
+        let retry = task.0.state.lock().unwrap().status.archive_dir.is_some();
+        if retry && archive_dir.is_dir() {
+            warn!(
+                "will remove the snapshot dir of the last failed attempt! dir={}",
+                archive_dir.display()
+            );
+            async_std::fs::remove_dir_all(&archive_dir)
+                .await
+                .map_err(|e| {
+                    let msg = format!(
+                        "remove last snapshot dir failed! dir={}, {}",
+                        archive_dir.display(),
+                        e
+                    );
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                })?;
+        }
+
+        // 快照时间之后写入的对象都需要回放
+        let snapshot_time = bucky_time_now();
+
+        let ndc = Arc::new(self.0.named_data_components.ndc.clone());
+        let chunk_reader = Arc::new(self.0.named_data_components.new_chunk_reader());
+        let backup_manager = Arc::new(BackupManager::new(self.0.noc.clone(), ndc, chunk_reader));
+
+        {
+            let mut state = task.0.state.lock().unwrap();
+            state.backup = Some((params.id.clone(), backup_manager.clone()));
+            state.status.snapshot_time = Some(snapshot_time);
+            state.status.archive_dir = Some(archive_dir.clone());
+            state.status.replay = ZoneMigrationReplayStat {
+                next_begin: snapshot_time,
+                ..Default::default()
+            };
+        }
+
+        backup_manager.run_uni_backup(backup_params).await?;
+
+        info!(
+            "zone migration snapshot complete! id={}, dir={}, time={}",
+            params.id,
+            archive_dir.display(),
+            snapshot_time
+        );
+
+        Ok(())
+    }
+
+    async fn provision(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        let params = &task.0.params;
+
+        // 重试时恢复任务可能已经提交过了，ood-control同时只允许一个恢复任务
+        if task.0.remote.get_restore_status(&params.id).await.is_ok() {
+            info!(
+                "restore task already exists on target ood! id={}",
+                params.id
+            );
+            return Ok(());
+        }
+
+        if task.0.remote.is_target_activated().await? {
+            let msg = format!(
+                "target ood already activated, migration need a brand-new one! ood-control={}",
+                params.target_ood_control
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
+        }
+
+        // 裸机恢复会在最后恢复身份，新ood和当前ood是同一个device
+        let mut restore_params = RemoteRestoreParams::new(&params.id, &params.archive_url);
+        restore_params.password = params.password.clone();
+        restore_params.bare_metal = true;
+        if !self.0.isolate.is_empty() {
+            restore_params.isolate = Some(self.0.isolate.clone());
+        }
+
+        task.0.remote.start_restore(&restore_params).await?;
+
+        info!(
+            "zone migration provision target ood success! id={}, archive={}",
+            params.id, params.archive_url
+        );
+
+        Ok(())
+    }
+
+    async fn restore(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        let id = task.id().to_owned();
+        loop {
+            let status = task.0.remote.get_restore_status(&id).await?;
+            let phase = status.phase;
+            let result = status.result.clone();
+            task.update_status(|s| s.restore = Some(status));
+
+            if phase == RemoteRestoreTaskPhase::Complete {
+                match result {
+                    Some(Err(e)) => return Err(e),
+                    _ => break,
+                }
+            }
+
+            async_std::task::sleep(Duration::from_secs(ZONE_MIGRATION_POLL_INTERVAL_SECS)).await;
+        }
+
+        info!(
+            "restore on target ood complete, now will wait target stack online! id={}",
+            id
+        );
+
+        // 恢复完成后新ood会被激活并启动协议栈
+        let begin = std::time::Instant::now();
+        loop {
+            match task.0.remote.get_device().await {
+                Ok(resp) => {
+                    if resp.device_id != self.0.device_id {
+                        let msg = format!(
+                            "target stack is not the same device! current={}, target={}",
+                            self.0.device_id, resp.device_id
+                        );
+                        error!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+                    }
+
+                    info!("target stack online! id={}, device={}", id, resp.device_id);
+                    break Ok(());
+                }
+                Err(e) => {
+                    if begin.elapsed().as_secs() >= ZONE_MIGRATION_STACK_ONLINE_TIMEOUT_SECS {
+                        let msg = format!(
+                            "wait target stack online timeout! stack={}, {}",
+                            task.0.params.target_stack, e
+                        );
+                        error!("{}", msg);
+                        break Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+                    }
+                }
+            }
+
+            async_std::task::sleep(Duration::from_secs(ZONE_MIGRATION_POLL_INTERVAL_SECS)).await;
+        }
+    }
+
+    // 导出[next_begin, now)之间写入noc的对象并导入到新ood，返回本轮导出的对象数
+    // chunk不在对象包里面，增量文件的数据由新ood按需从zone内获取
+    async fn replay_round(&self, task: &ZoneMigrationTask) -> BuckyResult<u64> {
+        let begin = task.0.state.lock().unwrap().status.replay.next_begin;
+        let end = bucky_time_now();
+
+        let filter = ObjectPackExportFilter {
+            insert_time_begin: Some(begin),
+            insert_time_end: Some(end),
+            ..Default::default()
+        };
+
+        let file = self.0.object_pack.export(filter).await?;
+        let count = file.count();
+
+        if count > 0 {
+            let mut status = task.0.remote.import_object_pack(file).await?;
+            while status.state == ObjectPackImportState::Running {
+                async_std::task::sleep(Duration::from_secs(ZONE_MIGRATION_POLL_INTERVAL_SECS))
+                    .await;
+                status = task.0.remote.get_import_status(&status.id).await?;
+            }
+
+            if status.state == ObjectPackImportState::Failed {
+                let msg = format!(
+                    "import delta objects to target ood failed! pack={}, {:?}",
+                    status.id, status.error
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::Failed, msg));
+            }
+
+            task.update_status(|s| {
+                s.replay.imported += status.imported;
+                s.replay.exists += status.exists;
+                s.replay.skipped += status.skipped;
+            });
+        }
+
+        task.update_status(|s| {
+            s.replay.rounds += 1;
+            s.replay.next_begin = end;
+            s.replay.exported += count;
+        });
+
+        info!(
+            "zone migration replay round complete! id={}, range=[{}, {}), count={}",
+            task.id(),
+            begin,
+            end,
+            count
+        );
+
+        Ok(count)
+    }
+
+    async fn replay_delta(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        for _ in 0..ZONE_MIGRATION_REPLAY_MAX_ROUNDS {
+            if self.replay_round(task).await? == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn switch_registration(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        let resp = task.0.remote.get_device().await?;
+        if resp.device_id != self.0.device_id {
+            let msg = format!(
+                "target stack is not the same device! current={}, target={}",
+                self.0.device_id, resp.device_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        // 当前ood停止在sn上的注册，之后zone外的请求都会通过sn找到新ood
+        warn!(
+            "zone migration will stop sn ping of current ood! device={}",
+            self.0.device_id
+        );
+        self.0.bdt_stack.sn_client().reset_sn_list(vec![]);
+
+        // 停止注册之后再回放一轮，补上最后的增量
+        self.replay_round(task).await?;
+
+        // 链上的device更新为新ood的body(endpoints等)
+        let tx = self.update_meta_device(resp.device).await?;
+        task.update_status(|s| s.meta_tx = tx);
+
+        Ok(())
+    }
+
+    async fn update_meta_device(&self, mut device: Device) -> BuckyResult<Option<String>> {
+        let device_id = device.desc().object_id();
+        let chain_device = match self.0.meta_client.get_desc(&device_id).await {
+            Ok(SavedMetaObject::Device(device)) => device,
+            Ok(_) => {
+                let msg = format!("desc on meta chain is not device! device={}", device_id);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+            Err(e) if e.code() == BuckyErrorCode::NotFound => {
+                info!(
+                    "device not found on meta chain, update skipped! device={}",
+                    device_id
+                );
+                return Ok(None);
+            }
+            Err(e) => {
+                error!(
+                    "get device desc from meta chain failed! device={}, {}",
+                    device_id, e
+                );
+                return Err(e);
+            }
+        };
+
+        let chain_update_time = chain_device.body().as_ref().map_or(0, |b| b.update_time());
+        device
+            .body_mut()
+            .as_mut()
+            .unwrap()
+            .increase_update_time(std::cmp::max(bucky_time_now(), chain_update_time + 1));
+        sign_and_set_named_object_body(
+            &self.0.signer,
+            &mut device,
+            &SignatureSource::RefIndex(SIGNATURE_SOURCE_REFINDEX_SELF),
+        )
+        .await?;
+
+        let tx_id = self
+            .0
+            .meta_client
+            .update_desc(
+                &StandardObject::Device(device.clone()),
+                &SavedMetaObject::Device(device),
+                None,
+                None,
+                &self.0.secret,
+            )
+            .await
+            .map_err(|e| {
+                error!(
+                    "update device desc on meta chain failed! device={}, {}",
+                    device_id, e
+                );
+                e
+            })?;
+
+        info!(
+            "update device desc on meta chain for migration success! device={}, tx={}",
+            device_id, tx_id
+        );
+
+        Ok(Some(tx_id.to_string()))
+    }
+
+    async fn verify(&self, task: &ZoneMigrationTask) -> BuckyResult<()> {
+        let resp = task.0.remote.get_device().await?;
+        if resp.device_id != self.0.device_id {
+            let msg = format!(
+                "verify target device failed! current={}, target={}",
+                self.0.device_id, resp.device_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        // 新ood上可能有自己产生的对象，只要求不少于当前ood
+        let local = self.0.noc.stat().await?;
+        let target = task.0.remote.get_noc_stat().await?;
+        if target.count < local.count {
+            let msg = format!(
+                "verify target object count failed! current={}, target={}",
+                local.count, target.count
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        let meta_update_time = if task.status().meta_tx.is_some() {
+            let device_id = self.0.device_id.object_id();
+            match self.0.meta_client.get_desc(device_id).await? {
+                SavedMetaObject::Device(device) => {
+                    let update_time = device.body().as_ref().map_or(0, |b| b.update_time());
+                    let target_update_time =
+                        resp.device.body().as_ref().map_or(0, |b| b.update_time());
+                    if update_time <= target_update_time {
+                        let msg = format!(
+                            "device on meta chain not updated yet! device={}, chain={}, target={}",
+                            device_id, update_time, target_update_time
+                        );
+                        warn!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::ErrorState, msg));
+                    }
+                    Some(update_time)
+                }
+                _ => {
+                    let msg = format!("desc on meta chain is not device! device={}", device_id);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+                }
+            }
+        } else {
+            None
+        };
+
+        let result = ZoneMigrationVerifyResult {
+            device_id: self.0.device_id.clone(),
+            local_object_count: local.count,
+            target_object_count: target.count,
+            meta_update_time,
+        };
+
+        info!(
+            "zone migration verify success! id={}, result={:?}",
+            task.id(),
+            result
+        );
+        task.update_status(|s| s.verify = Some(result));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+mod handler;
+mod listener;
+mod manager;
+mod remote;
+mod status;
+
+pub(crate) use handler::*;
+pub(crate) use listener::*;
+pub(crate) use manager::*;
+pub(crate) use status::*;
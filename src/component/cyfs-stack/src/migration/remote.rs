@@ -0,0 +1,221 @@
+use super::status::ZoneMigrationParams;
+use crate::object_pack::{ObjectPackImportStatus, ObjectPackTempFile};
+use cyfs_backup_lib::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use async_std::io::BufReader;
+use http_types::{Method, Request, Url};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// 访问新ood上的ood-control和协议栈
+pub(super) struct ZoneMigrationRemote {
+    ood_control: HttpRequestorRef,
+    access_token: Option<String>,
+
+    stack: HttpRequestorRef,
+    util: UtilRequestor,
+}
+
+impl ZoneMigrationRemote {
+    pub fn new(params: &ZoneMigrationParams) -> BuckyResult<Self> {
+        let ood_control = Self::new_requestor(&params.target_ood_control)?;
+        let stack = Self::new_requestor(&params.target_stack)?;
+        let util = UtilRequestor::new(None, stack.clone());
+
+        Ok(Self {
+            ood_control,
+            access_token: params.target_access_token.clone(),
+            stack,
+            util,
+        })
+    }
+
+    fn new_requestor(addr: &str) -> BuckyResult<HttpRequestorRef> {
+        if let Err(e) = SocketAddr::from_str(addr) {
+            let msg = format!("invalid migration target address! addr={}, {}", addr, e);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(Arc::new(Box::new(TcpHttpRequestor::new(addr))))
+    }
+
+    fn ood_control_url(&self, path: &str) -> Url {
+        let mut url = Url::parse(&format!(
+            "http://{}/{}",
+            self.ood_control.remote_addr(),
+            path
+        ))
+        .unwrap();
+
+        if let Some(token) = &self.access_token {
+            url.query_pairs_mut().append_pair("access_token", token);
+        }
+
+        url
+    }
+
+    fn stack_url(&self, path: &str) -> Url {
+        Url::parse(&format!("http://{}/{}", self.stack.remote_addr(), path)).unwrap()
+    }
+
+    async fn request_json<T: serde::de::DeserializeOwned>(
+        requestor: &HttpRequestorRef,
+        req: Request,
+    ) -> BuckyResult<T> {
+        let url = req.url().to_string();
+        let mut resp = requestor.request(req).await?;
+        if !resp.status().is_success() {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!("migration request failed! url={}, {}", url, e);
+            return Err(e);
+        }
+
+        resp.body_json().await.map_err(|e| {
+            let msg = format!("parse migration response failed! url={}, {}", url, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    // 新ood是否已经绑定了身份
+    pub async fn is_target_activated(&self) -> BuckyResult<bool> {
+        let req = Request::new(Method::Get, self.ood_control_url("check"));
+        let resp: serde_json::Value = Self::request_json(&self.ood_control, req).await?;
+
+        match resp.get("activation").and_then(|v| v.as_bool()) {
+            Some(v) => Ok(v),
+            None => {
+                let msg = format!("invalid ood-control check response! {}", resp);
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::InvalidData, msg))
+            }
+        }
+    }
+
+    pub async fn start_restore(&self, params: &RemoteRestoreParams) -> BuckyResult<()> {
+        let mut req = Request::new(Method::Post, self.ood_control_url("restore"));
+        req.set_body(serde_json::to_string(params).unwrap());
+        req.set_content_type(http_types::mime::JSON);
+
+        let mut resp = self.ood_control.request(req).await?;
+        if !resp.status().is_success() {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            error!(
+                "start remote restore on target ood failed! task={}, {}",
+                params.id, e
+            );
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_restore_status(&self, id: &str) -> BuckyResult<RemoteRestoreStatus> {
+        let req = Request::new(
+            Method::Get,
+            self.ood_control_url(&format!("restore/{}", id)),
+        );
+        Self::request_json(&self.ood_control, req).await
+    }
+
+    pub async fn abort_restore(&self, id: &str) -> BuckyResult<()> {
+        let req = Request::new(
+            Method::Delete,
+            self.ood_control_url(&format!("restore/{}", id)),
+        );
+
+        let mut resp = self.ood_control.request(req).await?;
+        if !resp.status().is_success() {
+            let e = RequestorHelper::error_from_resp(&mut resp).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    pub async fn import_object_pack(
+        &self,
+        file: ObjectPackTempFile,
+    ) -> BuckyResult<ObjectPackImportStatus> {
+        let mut req = Request::new(Method::Post, self.stack_url("object_pack/import"));
+        let len = file.len() as usize;
+        req.set_body(http_types::Body::from_reader(
+            BufReader::new(file),
+            Some(len),
+        ));
+
+        Self::request_json(&self.stack, req).await
+    }
+
+    pub async fn get_import_status(&self, id: &str) -> BuckyResult<ObjectPackImportStatus> {
+        let mut url = self.stack_url("object_pack/import/status");
+        url.query_pairs_mut().append_pair("id", id);
+
+        let req = Request::new(Method::Get, url);
+        Self::request_json(&self.stack, req).await
+    }
+
+    pub async fn get_device(&self) -> BuckyResult<UtilGetDeviceResponse> {
+        self.util.get_device(UtilGetDeviceRequest::new()).await
+    }
+
+    pub async fn get_noc_stat(&self) -> BuckyResult<NamedObjectCacheStat> {
+        let resp = self.util.get_noc_info(UtilGetNOCInfoRequest::new()).await?;
+        Ok(resp.stat)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_params(target_ood_control: &str, target_stack: &str) -> ZoneMigrationParams {
+        ZoneMigrationParams {
+            id: "test-migration".to_owned(),
+            target_ood_control: target_ood_control.to_owned(),
+            target_access_token: None,
+            target_stack: target_stack.to_owned(),
+            archive_dir: None,
+            archive_url: "http://192.168.100.1:8887/test-migration".to_owned(),
+            password: None,
+            auto: false,
+        }
+    }
+
+    #[test]
+    fn test_address() {
+        for (ood_control, stack) in [
+            ("192.168.100.2", "127.0.0.1:11318"),
+            ("192.168.100.2:1320", "localhost:11318"),
+            ("", "127.0.0.1:11318"),
+        ] {
+            let err = ZoneMigrationRemote::new(&new_params(ood_control, stack))
+                .err()
+                .unwrap();
+            assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+        }
+
+        let mut params = new_params("192.168.100.2:1320", "127.0.0.1:11318");
+        let remote = ZoneMigrationRemote::new(&params).unwrap();
+        assert_eq!(
+            remote.ood_control_url("check").as_str(),
+            "http://192.168.100.2:1320/check"
+        );
+        assert_eq!(
+            remote.stack_url("object_pack/import").as_str(),
+            "http://127.0.0.1:11318/object_pack/import"
+        );
+
+        // 配置了access_token时ood-control的请求都带上token
+        params.target_access_token = Some("test-token".to_owned());
+        let remote = ZoneMigrationRemote::new(&params).unwrap();
+        assert_eq!(
+            remote.ood_control_url("check").as_str(),
+            "http://192.168.100.2:1320/check?access_token=test-token"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use cyfs_backup_lib::*;
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// 迁移的步骤，按顺序执行
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneMigrationStep {
+    // 对当前ood做一次完整备份，记录快照时间
+    Snapshot,
+
+    // 检查新ood处于未激活状态，并提交使用快照的裸机恢复任务
+    Provision,
+
+    // 等待新ood恢复完成并且协议栈上线
+    Restore,
+
+    // 把快照之后写入的对象回放到新ood
+    ReplayDelta,
+
+    // 停止当前ood在sn上的注册，回放最后的增量，然后更新链上的device
+    SwitchRegistration,
+
+    // 校验新ood的身份、对象数和链上记录
+    Verify,
+
+    Complete,
+}
+
+impl ZoneMigrationStep {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Snapshot => Self::Provision,
+            Self::Provision => Self::Restore,
+            Self::Restore => Self::ReplayDelta,
+            Self::ReplayDelta => Self::SwitchRegistration,
+            Self::SwitchRegistration => Self::Verify,
+            Self::Verify | Self::Complete => Self::Complete,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneMigrationState {
+    Running,
+
+    // 当前步骤还没有执行，等待管理员确认
+    WaitConfirm,
+
+    // 当前步骤失败，确认后会重新执行当前步骤
+    Failed,
+
+    Aborted,
+    Complete,
+}
+
+impl ZoneMigrationState {
+    // 结束状态的迁移任务可以被新的任务替换
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Aborted | Self::Complete)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoneMigrationParams {
+    // 任务id，同时用作备份和恢复任务的id
+    pub id: String,
+
+    // 新ood的ood-control服务地址，ip:port
+    pub target_ood_control: String,
+    pub target_access_token: Option<String>,
+
+    // 新ood上协议栈的本地http接口地址，ip:port，需要从当前ood可以访问(比如通过端口转发)
+    pub target_stack: String,
+
+    // 快照的存放目录，默认是{cyfs}/data/backup/{isolate}/{id}
+    pub archive_dir: Option<PathBuf>,
+
+    // 新ood下载快照使用的地址，需要指向上面的快照目录
+    pub archive_url: String,
+
+    pub password: Option<ProtectedPassword>,
+
+    // 每一步完成后自动进入下一步，默认需要确认
+    #[serde(default)]
+    pub auto: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ZoneMigrationReplayStat {
+    pub rounds: u32,
+
+    // 下一轮回放的起始时间，初始是快照时间
+    pub next_begin: u64,
+
+    pub exported: u64,
+    pub imported: u64,
+    pub exists: u64,
+    pub skipped: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoneMigrationVerifyResult {
+    pub device_id: DeviceId,
+    pub local_object_count: u64,
+    pub target_object_count: u64,
+
+    // 链上device的body更新时间，没有上链的device为空
+    pub meta_update_time: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoneMigrationStatus {
+    pub id: String,
+    pub step: ZoneMigrationStep,
+    pub state: ZoneMigrationState,
+    pub last_update_time: u64,
+
+    pub snapshot_time: Option<u64>,
+    pub archive_dir: Option<PathBuf>,
+    pub backup: Option<BackupStatus>,
+
+    pub restore: Option<RemoteRestoreStatus>,
+
+    pub replay: ZoneMigrationReplayStat,
+
+    pub meta_tx: Option<String>,
+    pub verify: Option<ZoneMigrationVerifyResult>,
+
+    pub error: Option<BuckyError>,
+}
+
+impl ZoneMigrationStatus {
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            step: ZoneMigrationStep::Snapshot,
+            state: ZoneMigrationState::Running,
+            last_update_time: bucky_time_now(),
+
+            snapshot_time: None,
+            archive_dir: None,
+            backup: None,
+            restore: None,
+            replay: ZoneMigrationReplayStat::default(),
+            meta_tx: None,
+            verify: None,
+            error: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let mut steps = vec![ZoneMigrationStep::Snapshot];
+        while *steps.last().unwrap() != ZoneMigrationStep::Complete {
+            steps.push(steps.last().unwrap().next());
+        }
+
+        assert_eq!(
+            steps,
+            vec![
+                ZoneMigrationStep::Snapshot,
+                ZoneMigrationStep::Provision,
+                ZoneMigrationStep::Restore,
+                ZoneMigrationStep::ReplayDelta,
+                ZoneMigrationStep::SwitchRegistration,
+                ZoneMigrationStep::Verify,
+                ZoneMigrationStep::Complete,
+            ]
+        );
+        assert_eq!(
+            ZoneMigrationStep::Complete.next(),
+            ZoneMigrationStep::Complete
+        );
+
+        // 只有结束状态的任务可以被替换
+        assert!(ZoneMigrationState::Aborted.is_finished());
+        assert!(ZoneMigrationState::Complete.is_finished());
+        assert!(!ZoneMigrationState::Running.is_finished());
+        assert!(!ZoneMigrationState::WaitConfirm.is_finished());
+        assert!(!ZoneMigrationState::Failed.is_finished());
+    }
+
+    #[test]
+    fn test_status_codec() {
+        let mut status = ZoneMigrationStatus::new("test-migration".to_owned());
+        status.step = ZoneMigrationStep::SwitchRegistration;
+        status.state = ZoneMigrationState::WaitConfirm;
+        status.snapshot_time = Some(bucky_time_now());
+        status.replay.rounds = 2;
+        status.replay.exported = 10;
+        status.error = Some(BuckyError::new(BuckyErrorCode::Timeout, "restore timeout"));
+
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["step"], "switch_registration");
+        assert_eq!(value["state"], "wait_confirm");
+
+        let ret: ZoneMigrationStatus = serde_json::from_value(value).unwrap();
+        assert_eq!(ret.id, status.id);
+        assert_eq!(ret.step, status.step);
+        assert_eq!(ret.state, status.state);
+        assert_eq!(ret.snapshot_time, status.snapshot_time);
+        assert_eq!(ret.replay.rounds, 2);
+        assert_eq!(ret.replay.exported, 10);
+        assert_eq!(ret.error.unwrap().code(), BuckyErrorCode::Timeout);
+    }
+
+    #[test]
+    fn test_params_codec() {
+        // auto没有指定时默认需要确认
+        let value = serde_json::json!({
+            "id": "test-migration",
+            "target_ood_control": "192.168.100.2:1320",
+            "target_access_token": null,
+            "target_stack": "127.0.0.1:11318",
+            "archive_dir": null,
+            "archive_url": "http://192.168.100.1:8887/test-migration",
+            "password": null,
+        });
+        let params: ZoneMigrationParams = serde_json::from_value(value).unwrap();
+        assert_eq!(params.id, "test-migration");
+        assert!(!params.auto);
+    }
+}
@@ -32,6 +32,7 @@ impl NDNInputTransformer {
             flags: common.flags,
 
             referer_object: common.referer_object,
+            deadline: common.deadline,
         }
     }
 
@@ -227,6 +228,7 @@ impl NDNOutputTransformer {
 
             source,
             user_data: None,
+            deadline: common.deadline,
         }
     }
 
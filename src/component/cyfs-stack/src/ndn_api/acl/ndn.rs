@@ -85,7 +85,7 @@ impl NDNAclInputProcessor {
 
     async fn on_get_chunk(
         &self,
-        req: NDNGetDataInputRequest,
+        mut req: NDNGetDataInputRequest,
     ) -> BuckyResult<NDNGetDataInputRequest> {
         debug!("will check get_chunk access: req={}", req,);
 
@@ -93,7 +93,21 @@ impl NDNAclInputProcessor {
 
         if req.common.referer_object.is_empty() {
             let req_path = match &req.common.req_path {
-                Some(req_path) => Some(RequestGlobalStatePath::from_str(req_path)?),
+                Some(req_path) => {
+                    let mut req_path = RequestGlobalStatePath::from_str(req_path)?;
+
+                    // req_path maybe an alias of another path, resolve the links first
+                    if self
+                        .acl
+                        .global_state_meta()
+                        .resolve_req_path(&req.common.source, &mut req_path)
+                        .await?
+                    {
+                        req.common.req_path = Some(req_path.format_string());
+                    }
+
+                    Some(req_path)
+                }
                 None => None,
             };
             
@@ -128,6 +128,7 @@ impl BdtNDNDataAclProcessor {
                 target: None,
                 flags: 0,
                 user_data: None,
+                deadline: None,
             },
             object_id: req.object_id,
             data_type: NDNDataType::Mem,
@@ -2,6 +2,7 @@ use super::acl::BdtNDNDataAclProcessor;
 
 use crate::{
     acl::*,
+    config::StackGlobalConfig,
     non::NONInputProcessorRef,
     non_api::NONHandlerCaller,
     router_handler::{RouterHandlers, RouterHandlersManager},
@@ -19,11 +20,18 @@ use cyfs_bdt::{
 use cyfs_lib::*;
 use cyfs_util::acl::*;
 
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub(crate) struct BdtNDNEventHandler {
     acl: BdtNDNDataAclProcessor,
     handlers: RouterHandlersManager,
+
+    config: StackGlobalConfig,
+
+    // 当前正在处理中的interest数量
+    pending_interest: Arc<AtomicU32>,
 }
 
 impl BdtNDNEventHandler {
@@ -32,6 +40,7 @@ impl BdtNDNEventHandler {
         acl: AclManagerRef,
         handlers: RouterHandlersManager,
         named_data_components: &NamedDataComponents,
+        config: StackGlobalConfig,
     ) -> Self {
         Self {
             acl: BdtNDNDataAclProcessor::new(
@@ -41,6 +50,8 @@ impl BdtNDNEventHandler {
                 named_data_components.new_chunk_store_reader(),
             ),
             handlers,
+            config,
+            pending_interest: Arc::new(AtomicU32::new(0)),
         }
     }
 
@@ -75,6 +86,20 @@ impl BdtNDNEventHandler {
                         encoder)?;
                     
                     let _ = stack.ndn().root_task().upload().add_task(vec![], &session)?;
+
+                    // 按请求的chunk长度计入referer里的dec，上传中途取消的部分不扣除
+                    let dec_id = interest
+                        .referer
+                        .as_ref()
+                        .and_then(|referer| BdtDataRefererInfo::decode_string(referer).ok())
+                        .and_then(|referer| referer.dec_id);
+                    self.config.bandwidth_recorder().record(
+                        BandwidthDirection::Upload,
+                        BandwidthInterface::Ndn,
+                        Some(from.tunnel().remote()),
+                        dec_id.as_ref(),
+                        interest.chunk.len() as u64,
+                    );
                     Ok(())
                 } else {
                     from.resp_interest(RespInterest {
@@ -178,6 +203,65 @@ impl NdnEventHandler for BdtNDNEventHandler {
         stack: &Stack,
         interest: &Interest,
         from: &Channel,
+    ) -> BuckyResult<()> {
+        // 协议栈正在退出，不再处理新的interest
+        let _guard = match self.config.request_gate().enter() {
+            Some(guard) => guard,
+            None => {
+                warn!(
+                    "stack is draining, reject interest! chunk={}, from={}",
+                    interest.chunk,
+                    from.tunnel().remote(),
+                );
+                from.resp_interest(RespInterest {
+                    session_id: interest.session_id.clone(),
+                    chunk: interest.chunk.clone(),
+                    err: BuckyErrorCode::Interrupted,
+                    redirect: None,
+                    redirect_referer: None,
+                    to: None,
+                });
+
+                return Ok(());
+            }
+        };
+
+        let limit = self.config.dynamic_config().bdt().max_concurrent_interest;
+        let count = self.pending_interest.fetch_add(1, Ordering::SeqCst) + 1;
+        if limit > 0 && count > limit {
+            self.pending_interest.fetch_sub(1, Ordering::SeqCst);
+
+            warn!(
+                "concurrent interest out of limit! chunk={}, from={}, limit={}",
+                interest.chunk,
+                from.tunnel().remote(),
+                limit
+            );
+            from.resp_interest(RespInterest {
+                session_id: interest.session_id.clone(),
+                chunk: interest.chunk.clone(),
+                err: BuckyErrorCode::OutOfLimit,
+                redirect: None,
+                redirect_referer: None,
+                to: None,
+            });
+
+            return Ok(());
+        }
+
+        let ret = self.on_newly_interest_impl(stack, interest, from).await;
+        self.pending_interest.fetch_sub(1, Ordering::SeqCst);
+
+        ret
+    }
+}
+
+impl BdtNDNEventHandler {
+    async fn on_newly_interest_impl(
+        &self,
+        stack: &Stack,
+        interest: &Interest,
+        from: &Channel,
     ) -> BuckyResult<()> {
         let handler = self
             .handlers
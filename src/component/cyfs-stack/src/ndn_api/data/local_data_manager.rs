@@ -59,7 +59,7 @@ impl LocalDataManager {
         Option<String>,
     )> {
         self.target_data_manager()
-            .get_file(source, file_obj, group, ranges)
+            .get_file(source, file_obj, group, ranges, None)
             .await
     }
 
@@ -68,13 +68,21 @@ impl LocalDataManager {
         chunk_id: &ChunkId,
         chunk: Box<dyn Chunk>,
         referer_object: Vec<NDNDataRefererObject>,
+        encrypt: bool,
     ) -> BuckyResult<()> {
         assert!(chunk_id.len() == chunk.get_len());
 
-        self.named_data_components
-            .chunk_manager
-            .put_chunk(chunk_id, chunk)
-            .await?;
+        if encrypt {
+            self.named_data_components
+                .chunk_manager
+                .put_chunk_encrypted(chunk_id, chunk)
+                .await?;
+        } else {
+            self.named_data_components
+                .chunk_manager
+                .put_chunk(chunk_id, chunk)
+                .await?;
+        }
 
         self.named_data_components
             .ndc
@@ -172,7 +180,7 @@ impl LocalDataManager {
         Option<String>,
     )> {
         self.target_data_manager()
-            .get_chunk(source, chunk_id, group, ranges)
+            .get_chunk(source, chunk_id, group, ranges, None)
             .await
     }
 
@@ -82,7 +82,13 @@ impl NDNForwardDataOutputProcessor {
 
         let (data, length, group) = if need_process {
             self.data_manager
-                .get_file(&req.common.source, &file, req.group.as_deref(), ranges)
+                .get_file(
+                    &req.common.source,
+                    &file,
+                    req.group.as_deref(),
+                    ranges,
+                    req.common.deadline,
+                )
                 .await?
         } else {
             (zero_bytes_reader(), 0, None)
@@ -130,7 +136,13 @@ impl NDNForwardDataOutputProcessor {
 
         let (data, length, group) = if need_process {
             self.data_manager
-                .get_chunk(&req.common.source, &chunk_id, req.group.as_deref(), ranges)
+                .get_chunk(
+                    &req.common.source,
+                    &chunk_id,
+                    req.group.as_deref(),
+                    ranges,
+                    req.common.deadline,
+                )
                 .await
                 .map_err(|e| {
                     error!(
@@ -6,10 +6,12 @@ mod forward;
 mod handler;
 mod ndc;
 mod ndn;
+mod replica;
 mod router;
 mod service;
 
 pub(crate) use bdt::*;
 pub(crate) use common::*;
 pub(crate) use forward::*;
+pub(crate) use replica::*;
 pub(crate) use service::*;
\ No newline at end of file
@@ -109,6 +109,31 @@ impl NDNObjectLoader {
         }
     }
 
+    // 从本地加载chunk的关联对象
+    pub async fn get_referer_object(
+        &self,
+        common: &NDNInputRequestCommon,
+        referer_object: &NDNDataRefererObject,
+    ) -> BuckyResult<NONObjectInfo> {
+        let get_req = NONGetObjectInputRequest {
+            common: NONInputRequestCommon {
+                req_path: common.req_path.clone(),
+                source: common.source.clone(),
+                level: NONAPILevel::NOC,
+                target: None,
+                flags: 0,
+                deadline: common.deadline,
+                request_id: None,
+            },
+
+            object_id: referer_object.object_id.clone(),
+            inner_path: referer_object.inner_path.clone(),
+        };
+
+        let resp = self.non_processor.get_object(get_req).await?;
+        Ok(resp.object)
+    }
+
     async fn get_object_with_referer(
         &self,
         req: &NDNGetDataInputRequest,
@@ -181,6 +206,8 @@ impl NDNObjectLoader {
                 // should not pass the ndn flags to non loader request!
                 // flags: req.common.flags,
                 flags: 0,
+                deadline: req.common.deadline,
+                request_id: None,
             },
 
             object_id: req_object,
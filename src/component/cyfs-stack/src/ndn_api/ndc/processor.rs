@@ -6,6 +6,7 @@ use crate::ndn::*;
 use crate::ndn_api::acl::NDNAclInputProcessor;
 use crate::ndn_api::NDNForwardObjectData;
 use crate::non::*;
+use crate::rmeta_api::GlobalStateMetaLocalService;
 use cyfs_base::*;
 use cyfs_bdt_ext::{zero_bytes_reader, NamedDataComponentsRef};
 use cyfs_chunk_cache::MemChunk;
@@ -16,10 +17,15 @@ use futures::AsyncReadExt;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+const NDN_SHARED_CHUNK_META_MAX_LEN: u64 = 1024 * 64;
+
 pub(crate) struct NDCLevelInputProcessor {
     data_manager: LocalDataManager,
 
     object_loader: NDNObjectLoader,
+
+    // 用以查询chunk是否需要加密存储
+    rmeta: GlobalStateMetaLocalService,
 }
 
 impl NDCLevelInputProcessor {
@@ -33,6 +39,7 @@ impl NDCLevelInputProcessor {
         let ret = Self {
             data_manager: LocalDataManager::new(named_data_components.clone()),
             object_loader: NDNObjectLoader::new(non_processor.clone()),
+            rmeta: acl.global_state_meta().clone(),
         };
 
         let raw_processor = Arc::new(Box::new(ret) as Box<dyn NDNInputProcessor>);
@@ -76,15 +83,36 @@ impl NDCLevelInputProcessor {
             });
         }
 
+        let encrypt = self.check_chunk_encrypt(&req).await?;
+
+        // http接口对ndn的body不做长度限制，这里按chunk长度读取，多读一个字节用来判断是否超出
+        let max_len = match req.data_type {
+            NDNDataType::Mem => chunk_id.len() as u64,
+            // 共享内存方式只传递chunk的描述信息
+            NDNDataType::SharedMem => NDN_SHARED_CHUNK_META_MAX_LEN,
+        };
+
         let mut chunk_raw = vec![];
-        let len = req.data.read_to_end(&mut chunk_raw).await.map_err(|e| {
+        let len = (&mut req.data)
+            .take(max_len + 1)
+            .read_to_end(&mut chunk_raw)
+            .await
+            .map_err(|e| {
+                let msg = format!(
+                    "read chunk buffer from request error! chunk={}, {}",
+                    chunk_id, e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+        if len as u64 > max_len {
             let msg = format!(
-                "read chunk buffer from request error! chunk={}, {}",
-                chunk_id, e
+                "chunk buffer exceeds the chunk length! chunk={}, max={}",
+                chunk_id, max_len,
             );
             error!("{}", msg);
-            BuckyError::new(BuckyErrorCode::IoError, msg)
-        })?;
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
 
         match req.data_type {
             NDNDataType::Mem => {
@@ -104,6 +132,7 @@ impl NDCLevelInputProcessor {
                         &chunk_id,
                         Box::new(MemChunk::from(chunk_raw)),
                         req.common.referer_object,
+                        encrypt,
                     )
                     .await?;
             }
@@ -112,7 +141,7 @@ impl NDCLevelInputProcessor {
                     .to_chunk()
                     .await?;
                 self.data_manager
-                    .put_chunk(&chunk_id, chunk, req.common.referer_object)
+                    .put_chunk(&chunk_id, chunk, req.common.referer_object, encrypt)
                     .await?;
             }
         }
@@ -121,6 +150,41 @@ impl NDCLevelInputProcessor {
         })
     }
 
+    // chunk的关联对象在rmeta的object meta里配置了加密，那么chunk也需要加密保存
+    // 任何一个关联对象无法确定时都返回错误，避免按明文落盘；存储密钥未解锁时由chunk cache拒绝写入
+    async fn check_chunk_encrypt(&self, req: &NDNPutDataInputRequest) -> BuckyResult<bool> {
+        for referer_object in &req.common.referer_object {
+            let object = self
+                .object_loader
+                .get_referer_object(&req.common, referer_object)
+                .await
+                .map_err(|e| {
+                    error!(
+                        "load chunk's referer object failed! chunk={}, referer={}, {}",
+                        req.object_id, referer_object, e
+                    );
+                    e
+                })?;
+
+            let encrypt = self
+                .rmeta
+                .get_object_encrypt(&req.common.source.dec, &object)
+                .await
+                .map_err(|e| {
+                    error!(
+                        "query chunk's referer object encrypt failed! chunk={}, referer={}, {}",
+                        req.object_id, referer_object, e
+                    );
+                    e
+                })?;
+            if encrypt {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     // put_data目前只支持chunk
     async fn put_data(&self, req: NDNPutDataInputRequest) -> BuckyResult<NDNPutDataInputResponse> {
         match req.object_id.obj_type_code() {
@@ -244,8 +244,11 @@ impl NDNLevelInputProcessor {
     async fn get_data(&self, req: NDNGetDataInputRequest) -> BuckyResult<NDNGetDataInputResponse> {
         debug!("will get data from ndn: {}", req);
 
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
         let processor = self.get_data_processor(&req).await?;
-        processor.get_data(req).await
+        RequestDeadline::run(deadline, processor.get_data(req)).await
     }
 
     // put_data目前只支持chunk
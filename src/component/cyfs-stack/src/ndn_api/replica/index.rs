@@ -0,0 +1,138 @@
+use cyfs_base::*;
+
+use std::collections::{BTreeMap, HashMap};
+
+// chunk所在设备的索引，只保存在内存里，超出上限时淘汰最早更新的chunk
+// 每次更新都会分配递增的序号，设备可以按序号增量获取
+pub(super) struct ChunkReplicaIndex {
+    capacity: usize,
+    seq: u64,
+
+    // chunk -> (最近一次更新的序号, 持有的设备)
+    chunks: HashMap<ChunkId, (u64, Vec<DeviceId>)>,
+
+    // 序号 -> chunk
+    order: BTreeMap<u64, ChunkId>,
+}
+
+impl ChunkReplicaIndex {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seq: 0,
+            chunks: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.order.clear();
+    }
+
+    // 设备已经在列表里时不更新序号，避免重复下发
+    pub fn add(&mut self, chunk_id: &ChunkId, device_id: &DeviceId) -> bool {
+        let seq = self.seq + 1;
+        match self.chunks.get_mut(chunk_id) {
+            Some((prev, list)) => {
+                if list.contains(device_id) {
+                    return false;
+                }
+
+                list.push(device_id.to_owned());
+                self.order.remove(prev);
+                *prev = seq;
+            }
+            None => {
+                self.chunks
+                    .insert(chunk_id.to_owned(), (seq, vec![device_id.to_owned()]));
+            }
+        }
+
+        self.seq = seq;
+        self.order.insert(seq, chunk_id.to_owned());
+
+        while self.chunks.len() > self.capacity {
+            let first = *self.order.keys().next().unwrap();
+            let chunk_id = self.order.remove(&first).unwrap();
+            self.chunks.remove(&chunk_id);
+        }
+
+        true
+    }
+
+    pub fn devices(&self, chunk_id: &ChunkId) -> &[DeviceId] {
+        match self.chunks.get(chunk_id) {
+            Some((_, list)) => list.as_slice(),
+            None => &[],
+        }
+    }
+
+    // 返回序号在seq之后更新过的chunk位置，以及本次返回的最后一个序号
+    pub fn since(&self, seq: u64, limit: usize) -> (u64, Vec<(ChunkId, DeviceId)>) {
+        let mut last = seq;
+        let mut result = vec![];
+        for (item_seq, chunk_id) in self.order.range(seq + 1..) {
+            let (_, list) = self.chunks.get(chunk_id).unwrap();
+            if !result.is_empty() && result.len() + list.len() > limit {
+                break;
+            }
+
+            for device_id in list {
+                result.push((chunk_id.to_owned(), device_id.to_owned()));
+            }
+            last = *item_seq;
+        }
+
+        if result.is_empty() {
+            last = self.seq;
+        }
+
+        (last, result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn chunk_id(v: u8) -> ChunkId {
+        ChunkId::calculate_sync(&[v; 16]).unwrap()
+    }
+
+    #[test]
+    fn test_replica_index() {
+        let mut index = ChunkReplicaIndex::new(2);
+        let (a, b, c) = (chunk_id(1), chunk_id(2), chunk_id(3));
+        let d1 = DeviceId::from_str("5aSixgLtjoYcAFH9isc6KCqDgKfTJ8jpgASAoiRz5NLk").unwrap();
+        let d2 = DeviceId::from_str("5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty").unwrap();
+
+        assert!(index.add(&a, &d1));
+        assert!(!index.add(&a, &d1));
+        assert!(index.add(&b, &d1));
+        assert!(index.add(&a, &d2));
+        assert_eq!(index.seq(), 3);
+        assert_eq!(index.devices(&a), &[d1.clone(), d2.clone()]);
+
+        let (seq, list) = index.since(0, 16);
+        assert_eq!(seq, 3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0], (b.clone(), d1.clone()));
+
+        let (seq, list) = index.since(3, 16);
+        assert_eq!(seq, 3);
+        assert!(list.is_empty());
+
+        // b最早更新，超出上限后被淘汰
+        assert!(index.add(&c, &d2));
+        assert!(index.devices(&b).is_empty());
+        let (seq, list) = index.since(2, 1);
+        assert_eq!(seq, 3);
+        assert_eq!(list.len(), 2);
+    }
+}
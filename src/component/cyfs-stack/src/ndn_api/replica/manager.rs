@@ -0,0 +1,254 @@
+use super::index::ChunkReplicaIndex;
+use crate::config::StackGlobalConfig;
+use crate::NamedDataComponents;
+use cyfs_base::*;
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+// 保存的chunk位置上限，ood上是整个zone的索引，设备上是从ood获取的副本
+const REPLICA_INDEX_CAPACITY: usize = 1024 * 16;
+
+// 一次ping上报和下发的chunk位置上限
+const REPLICA_REPORT_MAX_CHUNKS: usize = 256;
+
+// 超过这个时间没有上报负载的设备不再作为读取的候选
+const REPLICA_LOAD_TIMEOUT: u64 = 1000 * 1000 * 60 * 5;
+
+// 设备通过sync ping上报给ood的负载和新增的chunk
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct NDNReplicaReport {
+    // 当前ndn的上传速度，bytes/s
+    pub load: u32,
+
+    // 设备已经获取到的ood索引序号
+    pub seq: u64,
+
+    pub chunks: Vec<ChunkId>,
+}
+
+// ood在ping应答里下发的各设备负载和新增的chunk位置
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct NDNReplicaHints {
+    pub seq: u64,
+
+    // 为true时设备需要清空已有的chunk位置，比如ood重启后序号重新开始
+    pub reset: bool,
+
+    // (device_id, load)
+    pub loads: Vec<(ObjectId, u32)>,
+
+    // (chunk_id, device_id)
+    pub chunks: Vec<(ChunkId, ObjectId)>,
+}
+
+struct NDNReplicaState {
+    index: ChunkReplicaIndex,
+
+    // device -> (load, update_time)
+    loads: HashMap<DeviceId, (u32, u64)>,
+
+    // 设备上还没有上报给ood的chunk
+    pending: VecDeque<ChunkId>,
+
+    // 设备上已经从ood获取到的索引序号
+    remote_seq: u64,
+}
+
+// zone内多个设备持有同一个chunk时，读取不再总是访问ood，而是在持有的设备里按负载分摊
+// 设备通过sync ping上报负载和同步到本地的chunk，ood汇总后在ping应答里增量下发
+#[derive(Clone)]
+pub(crate) struct NDNReplicaManager {
+    device_id: DeviceId,
+    named_data_components: NamedDataComponents,
+    config: StackGlobalConfig,
+
+    state: Arc<Mutex<NDNReplicaState>>,
+}
+
+impl NDNReplicaManager {
+    pub fn new(
+        device_id: DeviceId,
+        named_data_components: NamedDataComponents,
+        config: StackGlobalConfig,
+    ) -> Self {
+        let state = NDNReplicaState {
+            index: ChunkReplicaIndex::new(REPLICA_INDEX_CAPACITY),
+            loads: HashMap::new(),
+            pending: VecDeque::new(),
+            remote_seq: 0,
+        };
+
+        Self {
+            device_id,
+            named_data_components,
+            config,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    fn current_load(&self) -> u32 {
+        match self.named_data_components.bdt_stack.get() {
+            Some(stack) => stack.ndn().root_task().upload().cur_speed(),
+            None => 0,
+        }
+    }
+
+    // 设备上新同步到本地的chunk，等待下次ping时上报
+    pub fn on_local_chunks(&self, chunks: &[ChunkId]) {
+        if chunks.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        for chunk_id in chunks {
+            state.pending.push_back(chunk_id.to_owned());
+        }
+
+        while state.pending.len() > REPLICA_INDEX_CAPACITY {
+            state.pending.pop_front();
+        }
+    }
+
+    pub fn take_report(&self) -> NDNReplicaReport {
+        let load = self.current_load();
+
+        let mut state = self.state.lock().unwrap();
+        let count = std::cmp::min(state.pending.len(), REPLICA_REPORT_MAX_CHUNKS);
+        let chunks = state.pending.drain(..count).collect();
+
+        NDNReplicaReport {
+            load,
+            seq: state.remote_seq,
+            chunks,
+        }
+    }
+
+    // ping失败后放回去等待下次上报
+    pub fn restore_report(&self, report: NDNReplicaReport) {
+        let mut state = self.state.lock().unwrap();
+        for chunk_id in report.chunks.into_iter().rev() {
+            state.pending.push_front(chunk_id);
+        }
+    }
+
+    // 设备上处理ood下发的信息
+    pub fn on_hints(&self, hints: NDNReplicaHints) {
+        let now = bucky_time_now();
+
+        let mut state = self.state.lock().unwrap();
+        if hints.reset {
+            state.index.clear();
+        }
+
+        for (chunk_id, device_id) in hints.chunks {
+            if let Ok(device_id) = DeviceId::try_from(device_id) {
+                state.index.add(&chunk_id, &device_id);
+            }
+        }
+
+        state.loads = hints
+            .loads
+            .into_iter()
+            .filter_map(|(device_id, load)| {
+                DeviceId::try_from(device_id)
+                    .ok()
+                    .map(|device_id| (device_id, (load, now)))
+            })
+            .collect();
+        state.remote_seq = hints.seq;
+    }
+
+    // ood上处理设备的上报，返回需要下发给设备的信息
+    pub fn on_report(&self, device_id: &DeviceId, report: NDNReplicaReport) -> NDNReplicaHints {
+        let now = bucky_time_now();
+        let ood_load = self.current_load();
+
+        let mut state = self.state.lock().unwrap();
+        state.loads.insert(device_id.to_owned(), (report.load, now));
+        for chunk_id in &report.chunks {
+            state.index.add(chunk_id, device_id);
+        }
+
+        let (seq, reset) = if report.seq == 0 || report.seq > state.index.seq() {
+            (0, true)
+        } else {
+            (report.seq, false)
+        };
+        let (seq, chunks) = state.index.since(seq, REPLICA_REPORT_MAX_CHUNKS);
+
+        state
+            .loads
+            .retain(|_, (_, update_time)| *update_time + REPLICA_LOAD_TIMEOUT >= now);
+        let mut loads: Vec<(ObjectId, u32)> = state
+            .loads
+            .iter()
+            .map(|(device_id, (load, _))| (device_id.object_id().to_owned(), *load))
+            .collect();
+        loads.push((self.device_id.object_id().to_owned(), ood_load));
+
+        NDNReplicaHints {
+            seq,
+            reset,
+            loads,
+            chunks: chunks
+                .into_iter()
+                .map(|(chunk_id, device_id)| (chunk_id, device_id.object_id().to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn on_device_offline(&self, device_id: &DeviceId) {
+        self.state.lock().unwrap().loads.remove(device_id);
+    }
+
+    // 在持有chunk的设备和ood里随机选两个，使用负载较低的那个；返回None表示直接读取ood
+    pub fn select(&self, chunk_id: &ChunkId, ood_id: &DeviceId) -> Option<DeviceId> {
+        let config = self.config.dynamic_config().ndn();
+        if !config.replica_read || (chunk_id.len() as u64) < config.replica_read_min_size {
+            return None;
+        }
+
+        let now = bucky_time_now();
+        let state = self.state.lock().unwrap();
+        let load_of = |device_id: &DeviceId| -> Option<u32> {
+            state
+                .loads
+                .get(device_id)
+                .filter(|(_, update_time)| *update_time + REPLICA_LOAD_TIMEOUT >= now)
+                .map(|(load, _)| *load)
+        };
+
+        let mut candidates: Vec<(DeviceId, u32)> = state
+            .index
+            .devices(chunk_id)
+            .iter()
+            .filter(|device_id| **device_id != self.device_id && *device_id != ood_id)
+            .filter_map(|device_id| load_of(device_id).map(|load| (device_id.to_owned(), load)))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        candidates.push((ood_id.to_owned(), load_of(ood_id).unwrap_or(0)));
+
+        let mut rng = rand::thread_rng();
+        let (device_id, load) = candidates
+            .choose_multiple(&mut rng, 2)
+            .min_by_key(|(_, load)| *load)
+            .unwrap();
+
+        if device_id == ood_id {
+            return None;
+        }
+
+        debug!(
+            "select ndn read replica: chunk={}, device={}, load={}",
+            chunk_id, device_id, load
+        );
+        Some(device_id.to_owned())
+    }
+}
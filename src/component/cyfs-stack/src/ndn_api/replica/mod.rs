@@ -0,0 +1,4 @@
+mod index;
+mod manager;
+
+pub(crate) use manager::*;
@@ -1,6 +1,7 @@
 use super::super::forward::*;
 use super::super::handler::*;
 use super::super::ndc::*;
+use super::super::replica::NDNReplicaManager;
 use crate::acl::*;
 use crate::forward::ForwardProcessorManager;
 use crate::meta::ObjectFailHandler;
@@ -19,6 +20,9 @@ pub(crate) struct NDNRouter {
 
     named_data_components: NamedDataComponentsRef,
 
+    // 本zone内chunk的副本位置和各设备的负载
+    ndn_replica: NDNReplicaManager,
+
     // local ndn
     ndc_processor: NDNInputProcessorRef,
 
@@ -38,6 +42,7 @@ impl NDNRouter {
     fn new(
         acl: AclManagerRef,
         named_data_components: &NamedDataComponentsRef,
+        ndn_replica: NDNReplicaManager,
         non_router: NONInputProcessorRef,
         zone_manager: ZoneManagerRef,
         router_handlers: RouterHandlersManager,
@@ -54,6 +59,7 @@ impl NDNRouter {
         let ret = Self {
             acl,
             named_data_components: named_data_components.clone(),
+            ndn_replica,
             object_loader,
             ndc_processor,
             zone_manager,
@@ -68,6 +74,7 @@ impl NDNRouter {
     pub(crate) fn new_acl(
         acl: AclManagerRef,
         named_data_components: &NamedDataComponentsRef,
+        ndn_replica: NDNReplicaManager,
         non_router: NONInputProcessorRef,
         zone_manager: ZoneManagerRef,
         router_handlers: RouterHandlersManager,
@@ -78,6 +85,7 @@ impl NDNRouter {
         let processor = Self::new(
             acl.clone(),
             named_data_components,
+            ndn_replica,
             non_router,
             zone_manager,
             router_handlers,
@@ -131,7 +139,11 @@ impl NDNRouter {
 
     // NDN resolve target logic is same as NON
     // is target = current_device, return NONE
-    async fn resolve_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+    async fn resolve_target(
+        &self,
+        source: &RequestSourceInfo,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<Option<DeviceId>> {
         let info = self
             .zone_manager
             .target_zone_manager()
@@ -140,6 +152,13 @@ impl NDNRouter {
         let ret = if info.target_device == *self.acl.get_current_device_id() {
             None
         } else {
+            if !info.is_current_zone && source.is_current_zone() {
+                self.acl
+                    .global_state_meta()
+                    .check_egress(source, info.target_owner.as_ref(), &info.target_device)
+                    .await?;
+            }
+
             Some(info.target_device)
         };
 
@@ -169,7 +188,10 @@ impl NDNRouter {
                 Ok(processor)
             }
             None => {
-                if let Some(device_id) = self.resolve_target(req.common.target.as_ref()).await? {
+                if let Some(device_id) = self
+                    .resolve_target(&req.common.source, req.common.target.as_ref())
+                    .await?
+                {
                     let referer = BdtDataRefererInfo::from(req).encode_string();
                     let context = self
                         .named_data_components
@@ -185,17 +207,80 @@ impl NDNRouter {
         }
     }
 
+    // 读取本zone的ood上的chunk时，如果其它设备也持有并且负载更低，那么从该设备读取
+    async fn select_replica(&self, req: &NDNGetDataInputRequest) -> Option<DeviceId> {
+        if req.context.is_some() || req.object_id.obj_type_code() != ObjectTypeCode::Chunk {
+            return None;
+        }
+
+        let chunk_id = ChunkId::try_from(&req.object_id).ok()?;
+        let target = self
+            .resolve_target(&req.common.source, req.common.target.as_ref())
+            .await
+            .ok()??;
+
+        let info = self.zone_manager.get_current_info().await.ok()?;
+        if target != info.zone_device_ood_id {
+            return None;
+        }
+
+        self.ndn_replica.select(&chunk_id, &target)
+    }
+
+    async fn get_data_from_replica(
+        &self,
+        device_id: DeviceId,
+        req: NDNGetDataInputRequest,
+    ) -> BuckyResult<NDNGetDataInputResponse> {
+        let referer = BdtDataRefererInfo::from(&req).encode_string();
+        let context = self
+            .named_data_components
+            .context_manager
+            .create_download_context_from_target(referer, device_id)
+            .await?;
+        let processor = self.get_data_forward(context).await?;
+        processor.get_data(req).await
+    }
+
     async fn get_data(&self, req: NDNGetDataInputRequest) -> BuckyResult<NDNGetDataInputResponse> {
         debug!("will get data from ndn: {}", req);
 
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+
+        // 从副本读取失败后再从ood读取
+        if let Some(device_id) = self.select_replica(&req).await {
+            let ret = RequestDeadline::run(
+                deadline,
+                self.get_data_from_replica(device_id.clone(), req.clone()),
+            )
+            .await;
+            match ret {
+                Ok(resp) => return Ok(resp),
+                Err(e) => {
+                    warn!(
+                        "get data from replica failed, now will retry with ood! chunk={}, replica={}, {}",
+                        req.object_id, device_id, e
+                    );
+                    req.common.check_deadline()?;
+                }
+            }
+        }
+
         let processor = self.get_data_processor(&req).await?;
-        processor.get_data(req).await
+        RequestDeadline::run(deadline, processor.get_data(req)).await
     }
 
     async fn put_data(&self, req: NDNPutDataInputRequest) -> BuckyResult<NDNPutDataInputResponse> {
         debug!("will put data to ndn: {}", req,);
 
-        if let Some(device_id) = self.resolve_target(req.common.target.as_ref()).await? {
+        req.common.check_deadline()?;
+
+        if let Some(device_id) = self
+            .resolve_target(&req.common.source, req.common.target.as_ref())
+            .await?
+        {
             let msg = format!(
                 "ndn put_data to target not support! chunk={}, target={}",
                 req.object_id, device_id,
@@ -235,8 +320,12 @@ impl NDNRouter {
         Ok(post_processor)
     }
 
-    async fn get_processor(&self, target: Option<&ObjectId>) -> BuckyResult<NDNInputProcessorRef> {
-        if let Some(device_id) = self.resolve_target(target).await? {
+    async fn get_processor(
+        &self,
+        source: &RequestSourceInfo,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<NDNInputProcessorRef> {
+        if let Some(device_id) = self.resolve_target(source, target).await? {
             let processor = self.get_forward(device_id).await?;
             Ok(processor)
         } else {
@@ -250,7 +339,11 @@ impl NDNRouter {
     ) -> BuckyResult<NDNDeleteDataInputResponse> {
         debug!("will delete data from ndn: {}", req,);
 
-        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        req.common.check_deadline()?;
+
+        let processor = self
+            .get_processor(&req.common.source, req.common.target.as_ref())
+            .await?;
         processor.delete_data(req).await
     }
 
@@ -260,7 +353,11 @@ impl NDNRouter {
     ) -> BuckyResult<NDNQueryFileInputResponse> {
         debug!("will query file from ndn: {}", req);
 
-        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        req.common.check_deadline()?;
+
+        let processor = self
+            .get_processor(&req.common.source, req.common.target.as_ref())
+            .await?;
         processor.query_file(req).await
     }
 }
@@ -0,0 +1,118 @@
+use cyfs_base::*;
+
+use async_std::io::{ErrorKind, ReadExt};
+use sha2::Digest;
+
+// 未知长度的数据流按固定大小切分chunk
+pub(crate) const STREAM_CHUNK_SIZE: usize = 1024 * 1024 * 4;
+
+// 调用方指定chunk_size时的上限，切分时整个chunk都缓存在内存里
+pub(crate) const STREAM_MAX_CHUNK_SIZE: usize = 1024 * 1024 * 64;
+
+// 滚动的chunk构造器，每凑满一个chunk_size就输出一个chunk，流结束时输出最后一个不满的chunk
+pub(crate) struct ChunkStreamBuilder {
+    chunk_size: usize,
+    buf: Vec<u8>,
+    sha256: sha2::Sha256,
+    len: u64,
+    chunk_list: Vec<ChunkId>,
+}
+
+impl ChunkStreamBuilder {
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0);
+
+        Self {
+            chunk_size,
+            buf: Vec::with_capacity(chunk_size),
+            sha256: sha2::Sha256::new(),
+            len: 0,
+            chunk_list: vec![],
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    // 从reader读取数据，凑满一个chunk后返回，读到流结束时返回最后一个不满的chunk，之后返回None
+    pub async fn next_chunk(
+        &mut self,
+        reader: &mut (impl ReadExt + Unpin),
+    ) -> BuckyResult<Option<(ChunkId, Vec<u8>)>> {
+        let mut tmp = vec![0u8; 1024 * 64];
+        loop {
+            let left = self.chunk_size - self.buf.len();
+            if left == 0 {
+                break;
+            }
+
+            let max = std::cmp::min(left, tmp.len());
+            match reader.read(&mut tmp[..max]).await {
+                Ok(0) => break,
+                Ok(size) => {
+                    self.buf.extend_from_slice(&tmp[..size]);
+                }
+                Err(e) => {
+                    if let ErrorKind::Interrupted = e.kind() {
+                        continue;
+                    }
+                    let msg = format!("read chunked stream error! len={}, {}", self.len, e);
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+                }
+            }
+        }
+
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+
+        let data = std::mem::replace(&mut self.buf, Vec::with_capacity(self.chunk_size));
+        self.sha256.input(&data);
+        self.len += data.len() as u64;
+
+        let chunk_id = ChunkId::calculate_sync(&data)?;
+        self.chunk_list.push(chunk_id.clone());
+
+        Ok(Some((chunk_id, data)))
+    }
+
+    // 流结束后生成File对象
+    pub fn finish(self, owner: Option<ObjectId>) -> File {
+        let hash: HashValue = self.sha256.result().into();
+        let chunk_list = ChunkList::ChunkInList(self.chunk_list);
+        let builder = match owner {
+            Some(owner) => File::new(owner, self.len, hash, chunk_list),
+            None => File::new_no_owner(self.len, hash, chunk_list),
+        };
+
+        builder.no_create_time().build()
+    }
+}
+
+#[cfg(test)]
+mod chunked_tests {
+    use super::*;
+
+    #[async_std::test]
+    async fn test_chunk_stream_builder() {
+        let data: Vec<u8> = (0..2500u32).map(|i| i as u8).collect();
+        let mut reader = async_std::io::Cursor::new(data.clone());
+
+        let mut builder = ChunkStreamBuilder::new(1024);
+        let mut chunks = vec![];
+        while let Some((chunk_id, buf)) = builder.next_chunk(&mut reader).await.unwrap() {
+            assert_eq!(chunk_id.len(), buf.len());
+            chunks.push(chunk_id);
+        }
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].len(), 2500 - 1024 * 2);
+        assert_eq!(builder.len(), 2500);
+
+        let file = builder.finish(None);
+        assert_eq!(file.len(), 2500);
+        assert_eq!(file.hash(), &hash_data(&data));
+        assert_eq!(file.body_expect("").content().inner_chunk_list().unwrap(), &chunks);
+    }
+}
@@ -1,3 +1,4 @@
+use super::chunked::*;
 use crate::ndn::*;
 use crate::non::NONInputHttpRequest;
 use cyfs_base::*;
@@ -5,6 +6,7 @@ use cyfs_lib::*;
 
 use async_std::io::BufReader;
 use http_types::StatusCode;
+use std::str::FromStr;
 use tide::Response;
 
 // 目前ndn使用non同样的http request
@@ -62,6 +64,7 @@ impl NDNRequestHandler {
         let mut object_id = None;
         let mut context = None;
         let mut group = None;
+        let mut deadline = None;
 
         for (k, v) in req.request.url().query_pairs() {
             match k.as_ref() {
@@ -99,6 +102,9 @@ impl NDNRequestHandler {
                 cyfs_base::CYFS_TASK_GROUP => {
                     group = Some(RequestorHelper::decode_url_param_with_utf8_decoding(k, v)?);
                 }
+                cyfs_base::CYFS_DEADLINE => {
+                    deadline = Some(RequestorHelper::decode_url_param(k, v)?);
+                }
                 _ => {
                     warn!("unknown ndn url param: {}={}", k, v);
                 }
@@ -131,6 +137,7 @@ impl NDNRequestHandler {
             referer_object,
 
             flags: flags.unwrap_or(0),
+            deadline,
 
             user_data: None,
         };
@@ -175,6 +182,10 @@ impl NDNRequestHandler {
                 cyfs_base::CYFS_REFERER_OBJECT,
             )?;
 
+        // 请求的截止时间
+        let deadline =
+            RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_DEADLINE)?;
+
         let ret = NDNInputRequestCommon {
             req_path,
 
@@ -185,6 +196,7 @@ impl NDNRequestHandler {
             referer_object: referer_object.unwrap_or(vec![]),
 
             flags: flags.unwrap_or(0),
+            deadline,
 
             user_data: None,
         };
@@ -205,6 +217,15 @@ impl NDNRequestHandler {
         &self,
         req: NDNInputHttpRequest<State>,
     ) -> Response {
+        // 没有content-length的请求(Transfer-Encoding: chunked)，按流式切分chunk处理
+        if req.request.len().is_none() {
+            let ret = self.on_put_stream_data(req).await;
+            return match ret {
+                Ok(file) => Self::encode_put_stream_data_response(file),
+                Err(e) => RequestorHelper::trans_error(e),
+            };
+        }
+
         let ret = self.on_put_data(req).await;
         match ret {
             Ok(resp) => Self::encode_put_data_response(resp),
@@ -212,6 +233,88 @@ impl NDNRequestHandler {
         }
     }
 
+    // 流式put_data的结果是生成的File对象，id放在header里，对象编码后作为body返回
+    fn encode_put_stream_data_response(file: File) -> Response {
+        let buf = match file.to_vec() {
+            Ok(buf) => buf,
+            Err(e) => return RequestorHelper::trans_error(e),
+        };
+
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.insert_header(cyfs_base::CYFS_NDN_ACTION, &NDNAction::PutData.to_string());
+        RequestorHelper::encode_header(
+            &mut http_resp,
+            cyfs_base::CYFS_RESULT,
+            &NDNPutDataResult::Accept,
+        );
+        http_resp.insert_header(cyfs_base::CYFS_OBJECT_ID, file.desc().calculate_id().to_string());
+        http_resp.set_content_type(::tide::http::mime::BYTE_STREAM);
+        http_resp.set_body(buf);
+
+        http_resp.into()
+    }
+
+    async fn on_put_stream_data<State>(
+        &self,
+        mut req: NDNInputHttpRequest<State>,
+    ) -> BuckyResult<File> {
+        let action = Self::decode_action(&req, NDNAction::PutData)?;
+        let data_type = match action {
+            NDNAction::PutData => NDNDataType::Mem,
+            NDNAction::PutSharedData => NDNDataType::SharedMem,
+            _ => {
+                let msg = format!("invalid ndn put_data action! {:?}", action);
+                error!("{}", msg);
+
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+        };
+
+        let common = Self::decode_common_headers(&req)?;
+
+        // 流式上传时数据长度未知，不需要指定object_id，最终生成的file可以指定owner
+        let owner = RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_OWNER_ID)?;
+
+        info!(
+            "recv put_data stream request: common={}, owner={:?}",
+            common, owner
+        );
+
+        let mut data = req.request.take_body();
+        let mut builder = ChunkStreamBuilder::new(STREAM_CHUNK_SIZE);
+        while let Some((chunk_id, buf)) = builder.next_chunk(&mut data).await? {
+            let length = buf.len() as u64;
+            let put_req = NDNPutDataInputRequest {
+                common: common.clone(),
+                object_id: chunk_id.object_id(),
+
+                data_type,
+                length,
+                data: Box::new(async_std::io::Cursor::new(buf)),
+            };
+
+            self.processor.put_data(put_req).await.map_err(|e| {
+                error!(
+                    "put chunk of stream data failed! chunk={}, pos={}, {}",
+                    chunk_id,
+                    builder.len() - length,
+                    e
+                );
+                e
+            })?;
+        }
+
+        let file = builder.finish(owner);
+        info!(
+            "put_data stream complete! file={}, len={}",
+            file.desc().calculate_id(),
+            file.len()
+        );
+
+        Ok(file)
+    }
+
     async fn on_put_data<State>(
         &self,
         mut req: NDNInputHttpRequest<State>,
@@ -297,9 +400,24 @@ impl NDNRequestHandler {
         );
 
         if http_resp.status().is_success() {
-            let reader = BufReader::new(resp.data);
-            let body = tide::Body::from_reader(reader, Some(resp.length as usize));
-            http_resp.set_body(body);
+            match &resp.range {
+                // 多个range，按照multipart/byteranges返回
+                Some(NDNDataResponseRange::Range((ranges, size))) if ranges.len() > 1 => {
+                    let reader = MultipartByteRangesReader::new(ranges, *size, resp.data);
+                    let mime = ::tide::http::Mime::from_str(&reader.content_type()).unwrap();
+                    let len = reader.len();
+
+                    let mut body =
+                        tide::Body::from_reader(BufReader::new(reader), Some(len as usize));
+                    body.set_mime(mime);
+                    http_resp.set_body(body);
+                }
+                _ => {
+                    let reader = BufReader::new(resp.data);
+                    let body = tide::Body::from_reader(reader, Some(resp.length as usize));
+                    http_resp.set_body(body);
+                }
+            }
         }
 
         http_resp.into()
@@ -1,8 +1,10 @@
 mod service;
 mod handler;
 mod listener;
+mod chunked;
 
 
 pub(crate) use service::*;
 pub(crate) use handler::*;
-pub(crate) use listener::*;
\ No newline at end of file
+pub(crate) use listener::*;
+pub(crate) use chunked::*;
\ No newline at end of file
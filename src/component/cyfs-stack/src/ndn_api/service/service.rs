@@ -1,5 +1,6 @@
 use super::super::ndc::*;
 use super::super::ndn::*;
+use super::super::replica::NDNReplicaManager;
 use super::super::router::*;
 use crate::NamedDataComponents;
 use crate::acl::AclManagerRef;
@@ -25,6 +26,7 @@ impl NDNService {
     pub(crate) fn new(
         acl: AclManagerRef,
         named_data_components: &NamedDataComponents,
+        ndn_replica: NDNReplicaManager,
 
         zone_manager: ZoneManagerRef,
         router_handlers: RouterHandlersManager,
@@ -55,6 +57,7 @@ impl NDNService {
         let router = NDNRouter::new_acl(
             acl,
             &named_data_components,
+            ndn_replica,
             non_router,
             zone_manager,
             router_handlers,
@@ -33,6 +33,8 @@ impl NONInputTransformer {
             target: common.target,
 
             flags: common.flags,
+            deadline: common.deadline,
+            request_id: common.request_id,
         }
     }
 
@@ -45,6 +47,7 @@ impl NONInputTransformer {
 
             object: req.object,
             access: req.access,
+            precondition: req.precondition,
         };
 
         let out_resp = self.processor.put_object(out_req).await?;
@@ -213,6 +216,8 @@ impl NONOutputTransformer {
             target: common.target,
 
             flags: common.flags,
+            deadline: common.deadline,
+            request_id: common.request_id,
         }
     }
 
@@ -228,6 +233,7 @@ impl NONOutputTransformer {
 
             object: req.object,
             access: req.access,
+            precondition: req.precondition,
         };
 
         let in_resp = self.processor.put_object(in_req).await?;
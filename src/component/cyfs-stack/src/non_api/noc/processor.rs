@@ -108,6 +108,7 @@ impl NOCLevelInputProcessor {
             context: None,
             last_access_rpath: None,
             access_string: req.access.as_ref().map(|v| v.value()),
+            precondition: req.precondition,
         };
 
         let resp = match self.noc.put_object(&noc_req).await {
@@ -196,6 +197,7 @@ impl NOCLevelInputProcessor {
             context: None,
             last_access_rpath: None,
             access_string: req.access.as_ref().map(|v| v.value()),
+            sign_verified_update_time: None,
         };
 
         self.noc.update_object_meta(&noc_req).await?;
@@ -2,6 +2,7 @@ use super::super::acl::*;
 use super::super::handler::*;
 use super::def::*;
 use crate::acl::*;
+use crate::crypto_api::*;
 use crate::forward::ForwardProcessorManager;
 use crate::meta::*;
 use crate::non::*;
@@ -34,6 +35,10 @@ pub(crate) struct NONRouter {
     router_handlers: RouterHandlersManager,
 
     fail_handler: ObjectFailHandler,
+
+    // 用以校验从其它zone获取的对象签名，校验结果记录在noc的meta里
+    noc: NamedObjectCacheRef,
+    verifier: Arc<ObjectVerifier>,
 }
 
 impl NONRouter {
@@ -52,6 +57,8 @@ impl NONRouter {
 
         meta_processor: NONInputProcessorRef,
         fail_handler: ObjectFailHandler,
+        noc: NamedObjectCacheRef,
+        verifier: Arc<ObjectVerifier>,
     ) -> NONInputProcessorRef {
         let noc_acl_processor =
             NOCLevelInputProcessor::new_rmeta_acl(acl.clone(), noc_raw_processor.clone());
@@ -73,6 +80,9 @@ impl NONRouter {
 
             meta_processor,
             fail_handler,
+
+            noc,
+            verifier,
         };
 
         Arc::new(Box::new(ret))
@@ -91,6 +101,8 @@ impl NONRouter {
         router_handlers: RouterHandlersManager,
         meta_processor: NONInputProcessorRef,
         fail_handler: ObjectFailHandler,
+        noc: NamedObjectCacheRef,
+        verifier: Arc<ObjectVerifier>,
     ) -> NONInputProcessorRef {
         // router processor with rmeta acl and valdiate
         let rmeta_validate_router = Self::new(
@@ -102,6 +114,8 @@ impl NONRouter {
             router_handlers.clone(),
             meta_processor,
             fail_handler,
+            noc,
+            verifier,
         );
 
         // Request from local rpc call or other stacks requests via bdt protocol: input->acl->pre_router->router->post_router
@@ -162,6 +176,18 @@ impl NONRouter {
             if target_zone_info.is_current_zone {
                 direction = Some(ZoneDirection::LocalToLocal);
             } else {
+                // Requests from current zone to other zone should been checked by the source dec's egress policy
+                if source.is_current_zone() {
+                    self.acl
+                        .global_state_meta()
+                        .check_egress(
+                            source,
+                            target_zone_info.target_owner.as_ref(),
+                            &target_zone_info.target_device,
+                        )
+                        .await?;
+                }
+
                 direction = Some(ZoneDirection::LocalToRemote);
             }
         }
@@ -560,6 +586,14 @@ impl NONRouter {
                 e
             });
 
+        // 从其它zone获取的对象，需要根据来源dec的配置校验签名，校验失败的对象不会缓存也不会返回
+        let sign_verified = match &ret {
+            Ok(resp) if router_info.direction == Some(ZoneDirection::LocalToRemote) => {
+                self.verify_remote_object(&req, &resp.object).await?
+            }
+            _ => None,
+        };
+
         // Try to cache relation
         if req.is_with_inner_path_relation() {
             let cache_key = NamedObjectRelationCacheKey {
@@ -599,14 +633,131 @@ impl NONRouter {
                 common: req.common.clone(),
                 object: ret.as_ref().unwrap().object.clone(),
                 access: None,
+                precondition: None,
             };
 
             let _r = self.noc_raw_processor.put_object(put_req).await;
+
+            if let Some(update_time) = sign_verified {
+                self.save_sign_verified(&ret.as_ref().unwrap().object.object_id, update_time)
+                    .await;
+            }
         }
 
         ret
     }
 
+    // 使用对象owner的公钥校验desc和body签名，返回Some(update_time)表示本次校验通过，需要保存校验结果
+    async fn verify_remote_object(
+        &self,
+        req: &NONGetObjectInputRequest,
+        object: &NONObjectInfo,
+    ) -> BuckyResult<Option<u64>> {
+        let verify = self
+            .acl
+            .global_state_meta()
+            .check_verify_sign(&req.common.source, req.common.req_path.as_deref())
+            .await?;
+        if !verify {
+            return Ok(None);
+        }
+
+        let obj = match &object.object {
+            Some(obj) => obj.clone(),
+            None => Arc::new(object.object_if_none_then_decode()?.into_owned()),
+        };
+
+        let owner = match obj.owner() {
+            Some(owner) => owner.to_owned(),
+            None => {
+                debug!(
+                    "object from other zone has no owner, now will ignore verify sign! obj={}",
+                    object.object_id
+                );
+                return Ok(None);
+            }
+        };
+
+        // noc里面已经有同一版本对象的校验结果，不需要再次校验
+        let update_time = obj.update_time();
+        let noc_req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object.object_id.clone(),
+            last_access_rpath: None,
+            flags: NAMED_OBJECT_CACHE_GET_OBJECT_FLAG_NO_UPDATE_LAST_ACCESS,
+        };
+        if let Ok(Some(data)) = self.noc.get_object_raw(&noc_req).await {
+            if data.meta.is_sign_verified(update_time) {
+                debug!(
+                    "object from other zone's sign already verified! obj={}, owner={}",
+                    object.object_id, owner
+                );
+                return Ok(None);
+            }
+        }
+
+        let sign_type = if obj.has_body()? {
+            VerifySignType::Both
+        } else {
+            VerifySignType::Desc
+        };
+
+        let verify_req = VerifyObjectInnerRequest {
+            sign_type: sign_type.clone(),
+            object: ObjectInfo {
+                object_id: object.object_id.clone(),
+                object: obj,
+            },
+            sign_object: VerifyObjectType::Owner,
+        };
+
+        let ret = match self.verifier.verify_object_inner(verify_req).await {
+            Ok(ret) => ret.valid,
+            Err(e) => {
+                warn!(
+                    "verify object from other zone with owner's sign error! obj={}, owner={}, {}",
+                    object.object_id, owner, e
+                );
+                false
+            }
+        };
+
+        if !ret {
+            let msg = format!(
+                "verify object from other zone with owner's sign failed! obj={}, owner={}, source={}",
+                object.object_id, owner, req.common.source,
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidSignature, msg));
+        }
+
+        info!(
+            "verify object from other zone with owner's sign success! obj={}, owner={}, sign_type={:?}",
+            object.object_id, owner, sign_type
+        );
+
+        Ok(Some(update_time.unwrap_or(0)))
+    }
+
+    async fn save_sign_verified(&self, object_id: &ObjectId, update_time: u64) {
+        let req = NamedObjectCacheUpdateObjectMetaRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.to_owned(),
+            storage_category: None,
+            context: None,
+            last_access_rpath: None,
+            access_string: None,
+            sign_verified_update_time: Some(update_time),
+        };
+
+        if let Err(e) = self.noc.update_object_meta(&req).await {
+            warn!(
+                "save object's sign verified state to noc failed! obj={}, {}",
+                object_id, e
+            );
+        }
+    }
+
     pub async fn get_object(
         &self,
         req: NONGetObjectInputRequest,
@@ -774,34 +925,49 @@ impl NONInputProcessor for NONRouter {
         &self,
         req: NONPutObjectInputRequest,
     ) -> BuckyResult<NONPutObjectInputResponse> {
-        NONRouter::put_object(&self, req).await
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+        RequestDeadline::run(deadline, NONRouter::put_object(&self, req)).await
     }
 
     async fn get_object(
         &self,
         req: NONGetObjectInputRequest,
     ) -> BuckyResult<NONGetObjectInputResponse> {
-        NONRouter::get_object(&self, req).await
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+        RequestDeadline::run(deadline, NONRouter::get_object(&self, req)).await
     }
 
     async fn post_object(
         &self,
         req: NONPostObjectInputRequest,
     ) -> BuckyResult<NONPostObjectInputResponse> {
-        NONRouter::post_object(&self, req).await
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+        RequestDeadline::run(deadline, NONRouter::post_object(&self, req)).await
     }
 
     async fn select_object(
         &self,
         req: NONSelectObjectInputRequest,
     ) -> BuckyResult<NONSelectObjectInputResponse> {
-        NONRouter::select_object(&self, req).await
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+        RequestDeadline::run(deadline, NONRouter::select_object(&self, req)).await
     }
 
     async fn delete_object(
         &self,
         req: NONDeleteObjectInputRequest,
     ) -> BuckyResult<NONDeleteObjectInputResponse> {
-        NONRouter::delete_object(&self, req).await
+        req.common.check_deadline()?;
+
+        let deadline = req.common.deadline;
+        RequestDeadline::run(deadline, NONRouter::delete_object(&self, req)).await
     }
 }
@@ -0,0 +1,199 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+// 请求id的结果保留时间，需要覆盖客户端的所有重试
+const NON_REQUEST_DEDUP_EXPIRE_SECS: u64 = 60 * 10;
+
+// 最多保留的请求id数量，超出后新的请求不再去重
+const NON_REQUEST_DEDUP_MAX_COUNT: usize = 1024 * 16;
+
+struct DedupEntry {
+    create_time: u64,
+
+    // 第一次执行期间一直持有锁，并发到达的重试请求会等待第一次的结果
+    // 只保存成功的结果，失败后重试的请求会重新执行
+    result: async_std::sync::Mutex<Option<Box<dyn Any + Send + Sync>>>,
+}
+
+// 对带有请求id的put/delete请求去重，客户端超时重试时直接返回第一次的结果，避免重复写入
+#[derive(Clone)]
+pub(crate) struct NONRequestDedupCache {
+    entries: Arc<Mutex<HashMap<String, Arc<DedupEntry>>>>,
+}
+
+impl NONRequestDedupCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // 同一个请求id只在同一个来源设备和dec内有效
+    // target为请求操作的对象，复用了请求id的不同请求不会拿到其它请求的结果
+    pub fn key(action: &str, common: &NONInputRequestCommon, target: &str) -> Option<String> {
+        let request_id = common.request_id.as_ref()?;
+        let device = match &common.source.zone.device {
+            Some(device) => device.to_string(),
+            None => "local".to_owned(),
+        };
+
+        Some(format!(
+            "{}/{}/{}/{}/{}",
+            action, device, common.source.dec, request_id, target
+        ))
+    }
+
+    fn entry(&self, key: &str) -> Option<Arc<DedupEntry>> {
+        let now = bucky_time_now();
+        let expire = NON_REQUEST_DEDUP_EXPIRE_SECS * 1000 * 1000;
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get(key) {
+            if entry.create_time + expire > now {
+                return Some(entry.clone());
+            }
+
+            // 过期的结果不再复用，当作新的请求重新执行
+            entries.remove(key);
+        }
+
+        if entries.len() >= NON_REQUEST_DEDUP_MAX_COUNT {
+            entries.retain(|_, entry| entry.create_time + expire > now);
+
+            if entries.len() >= NON_REQUEST_DEDUP_MAX_COUNT {
+                warn!(
+                    "too many request ids in dedup cache, now will skip! key={}, count={}",
+                    key,
+                    entries.len()
+                );
+                return None;
+            }
+        }
+
+        let entry = Arc::new(DedupEntry {
+            create_time: now,
+            result: async_std::sync::Mutex::new(None),
+        });
+        entries.insert(key.to_owned(), entry.clone());
+
+        Some(entry)
+    }
+
+    pub async fn process<T, F>(&self, key: Option<String>, fut: F) -> BuckyResult<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Future<Output = BuckyResult<T>>,
+    {
+        let entry = match key.as_ref().and_then(|key| self.entry(key)) {
+            Some(entry) => entry,
+            None => return fut.await,
+        };
+
+        let mut result = entry.result.lock().await;
+        if let Some(prev) = result.as_ref().and_then(|v| v.downcast_ref::<T>()) {
+            info!(
+                "duplicate request id, return the previous result: key={}",
+                key.unwrap()
+            );
+            return Ok(prev.clone());
+        }
+
+        let ret = fut.await;
+        if let Ok(v) = &ret {
+            *result = Some(Box::new(v.clone()));
+        }
+
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[async_std::test]
+    async fn test_dedup() {
+        let cache = NONRequestDedupCache::new();
+        let count = Arc::new(AtomicU32::new(0));
+
+        let put = |key: Option<&str>, fail: bool| {
+            let cache = cache.clone();
+            let count = count.clone();
+            let key = key.map(|v| v.to_owned());
+            async move {
+                cache
+                    .process(key, async move {
+                        let v = count.fetch_add(1, Ordering::SeqCst);
+                        async_std::task::sleep(std::time::Duration::from_millis(20)).await;
+                        if fail {
+                            Err(BuckyError::from(BuckyErrorCode::Timeout))
+                        } else {
+                            Ok(v)
+                        }
+                    })
+                    .await
+            }
+        };
+
+        // 失败的结果不保存
+        assert!(put(Some("1"), true).await.is_err());
+
+        // 并发的重复请求只执行一次
+        let (a, b) = futures::join!(put(Some("1"), false), put(Some("1"), false));
+        assert_eq!(a.unwrap(), 1);
+        assert_eq!(b.unwrap(), 1);
+        assert_eq!(put(Some("1"), false).await.unwrap(), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        // 没有请求id或者不同的请求id都会执行
+        assert_eq!(put(None, false).await.unwrap(), 2);
+        assert_eq!(put(Some("2"), false).await.unwrap(), 3);
+
+        // 过期的结果不再返回，重新执行
+        let expired = Arc::new(DedupEntry {
+            create_time: bucky_time_now() - (NON_REQUEST_DEDUP_EXPIRE_SECS + 1) * 1000 * 1000,
+            result: async_std::sync::Mutex::new(Some(Box::new(1u32))),
+        });
+        cache
+            .entries
+            .lock()
+            .unwrap()
+            .insert("1".to_owned(), expired);
+        assert_eq!(put(Some("1"), false).await.unwrap(), 4);
+        assert_eq!(put(Some("1"), false).await.unwrap(), 4);
+    }
+
+    #[test]
+    fn test_key() {
+        let mut common = NONInputRequestCommon {
+            req_path: None,
+            source: RequestSourceInfo::new_local_system(),
+            level: NONAPILevel::NOC,
+            target: None,
+            flags: 0,
+            deadline: None,
+            request_id: None,
+        };
+        assert!(NONRequestDedupCache::key("put_object", &common, "a").is_none());
+
+        common.request_id = Some("1".to_owned());
+        let a = NONRequestDedupCache::key("put_object", &common, "a").unwrap();
+        assert_eq!(
+            a,
+            NONRequestDedupCache::key("put_object", &common, "a").unwrap()
+        );
+        assert_ne!(
+            a,
+            NONRequestDedupCache::key("put_object", &common, "b").unwrap()
+        );
+        assert_ne!(
+            a,
+            NONRequestDedupCache::key("delete_object", &common, "a").unwrap()
+        );
+    }
+}
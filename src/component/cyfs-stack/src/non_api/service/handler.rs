@@ -47,12 +47,33 @@ impl NONRequestHandler {
         // 尝试提取target字段
         let target = RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_TARGET)?;
 
+        // 请求的截止时间
+        let deadline =
+            RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_DEADLINE)?;
+
+        // 客户端生成的请求id，用以对重试的请求去重
+        let request_id: Option<String> =
+            RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_REQUEST_ID)?;
+        if let Some(request_id) = &request_id {
+            if request_id.is_empty() || request_id.len() > CYFS_REQUEST_ID_MAX_LEN {
+                let msg = format!(
+                    "invalid request id length: {}, should be in [1, {}]",
+                    request_id.len(),
+                    CYFS_REQUEST_ID_MAX_LEN
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
         let ret = NONInputRequestCommon {
             req_path,
             source: req.source.clone(),
             level: level.unwrap_or_default(),
             target,
             flags: flags.unwrap_or(0),
+            deadline,
+            request_id,
         };
 
         Ok(ret)
@@ -122,7 +143,17 @@ impl NONRequestHandler {
         let access: Option<u32>= RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_ACCESS)?;
         let access = access.map(|v| AccessString::new(v));
 
-        let put_req = NONPutObjectInputRequest { common, object, access };
+        let precondition = RequestorHelper::decode_optional_header(
+            &req.request,
+            cyfs_base::CYFS_PUT_PRECONDITION,
+        )?;
+
+        let put_req = NONPutObjectInputRequest {
+            common,
+            object,
+            access,
+            precondition,
+        };
 
         info!("recv put_object request: {}", put_req);
 
@@ -162,9 +193,16 @@ impl NONRequestHandler {
     }
 
     pub fn encode_get_object_response(
-        resp: NONGetObjectInputResponse,
+        mut resp: NONGetObjectInputResponse,
         format: FrontRequestObjectFormat,
     ) -> Response {
+        // json格式需要通过解码器把对象转换为结构化的json
+        if format == FrontRequestObjectFormat::Json {
+            if let Err(e) = resp.object.try_decode() {
+                return RequestorHelper::trans_error(e);
+            }
+        }
+
         let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
 
         Self::encode_get_object_response_times(&mut http_resp, &resp);
@@ -1,3 +1,4 @@
+mod dedup;
 mod handler;
 mod listener;
 mod service;
@@ -2,10 +2,13 @@ use super::super::meta::MetaInputProcessor;
 use super::super::noc::*;
 use super::super::non::*;
 use super::super::router::*;
+use super::dedup::NONRequestDedupCache;
+use crate::crypto_api::ObjectVerifier;
 use crate::forward::ForwardProcessorManager;
 use crate::meta::{MetaCacheRef, ObjectFailHandler};
 use crate::ndn_api::*;
 use crate::router_handler::RouterHandlersManager;
+use crate::trash::{NONTrashInputProcessor, TrashManagerHolder};
 use crate::zone::ZoneManagerRef;
 use crate::NamedDataComponents;
 use crate::{acl::*, non::*};
@@ -21,6 +24,9 @@ pub struct NONService {
     rmeta_noc_processor: NONInputProcessorRef,
     non: NONInputProcessorRef,
     router: NONInputProcessorRef,
+
+    // 带有请求id的put/delete请求去重
+    dedup: NONRequestDedupCache,
 }
 
 impl NONService {
@@ -28,12 +34,15 @@ impl NONService {
         noc: NamedObjectCacheRef,
         noc_relation: NamedObjectRelationCacheRef,
         named_data_components: &NamedDataComponents,
+        ndn_replica: NDNReplicaManager,
         forward_manager: ForwardProcessorManager,
         acl: AclManagerRef,
         zone_manager: ZoneManagerRef,
         router_handlers: RouterHandlersManager,
         meta_cache: MetaCacheRef,
         fail_handler: ObjectFailHandler,
+        trash: TrashManagerHolder,
+        verifier: Arc<ObjectVerifier>,
     ) -> (NONService, NDNService) {
         // raw service with inner_path service support
         let raw_noc_processor = NOCLevelInputProcessor::new_with_inner_path_service(
@@ -44,6 +53,9 @@ impl NONService {
             zone_manager.clone(),
         );
 
+        // 回收站，删除时带有CYFS_REQUEST_FLAG_DELETE_TO_TRASH的对象先放入回收站
+        let raw_noc_processor = NONTrashInputProcessor::new(raw_noc_processor, noc.clone(), trash);
+
         // meta处理器，从mete和noc处理get_object请求
         let meta_processor = MetaInputProcessor::new_with_inner_path_service(
             None,
@@ -80,6 +92,8 @@ impl NONService {
             router_handlers.clone(),
             meta_processor,
             fail_handler.clone(),
+            noc.clone(),
+            verifier,
         );
 
         let non_service = Self {
@@ -88,12 +102,14 @@ impl NONService {
             rmeta_noc_processor,
             non: non_processor.clone(),
             router: router.clone(),
+            dedup: NONRequestDedupCache::new(),
         };
 
         // 同时初始化ndn
         let ndn_service = NDNService::new(
             acl,
             named_data_components,
+            ndn_replica,
             zone_manager,
             router_handlers.clone(),
             router,
@@ -133,7 +149,13 @@ impl NONService {
         req: NONPutObjectInputRequest,
     ) -> BuckyResult<NONPutObjectInputResponse> {
         let processor = self.get_api(&req.common.level);
-        processor.put_object(req).await
+        let target = format!(
+            "{}/{}",
+            req.object.object_id,
+            hash_data(&req.object.object_raw)
+        );
+        let key = NONRequestDedupCache::key("put_object", &req.common, &target);
+        self.dedup.process(key, processor.put_object(req)).await
     }
 
     async fn get_object(
@@ -165,7 +187,13 @@ impl NONService {
         req: NONDeleteObjectInputRequest,
     ) -> BuckyResult<NONDeleteObjectInputResponse> {
         let processor = self.get_api(&req.common.level);
-        processor.delete_object(req).await
+        let target = format!(
+            "{}/{}",
+            req.object_id,
+            req.inner_path.as_deref().unwrap_or("")
+        );
+        let key = NONRequestDedupCache::key("delete_object", &req.common, &target);
+        self.dedup.process(key, processor.delete_object(req)).await
     }
 }
 
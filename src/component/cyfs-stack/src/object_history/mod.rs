@@ -0,0 +1,5 @@
+mod processor;
+mod transform;
+
+pub(crate) use processor::*;
+pub(crate) use transform::*;
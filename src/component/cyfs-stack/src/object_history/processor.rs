@@ -0,0 +1,19 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ObjectHistoryInputProcessor: Sync + Send + 'static {
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse>;
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse>;
+}
+
+pub(crate) type ObjectHistoryInputProcessorRef = Arc<dyn ObjectHistoryInputProcessor>;
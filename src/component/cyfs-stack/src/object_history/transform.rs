@@ -0,0 +1,76 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ObjectHistoryInputTransformer {
+    processor: ObjectHistoryOutputProcessorRef,
+}
+
+impl ObjectHistoryInputTransformer {
+    pub fn new(processor: ObjectHistoryOutputProcessorRef) -> ObjectHistoryInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        let out_req = ObjectHistoryListOutputRequest {
+            common: Self::convert_common(req.common),
+            object_id: req.object_id,
+        };
+
+        let out_resp = self.processor.get_object_history(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        let out_req = ObjectHistoryGetRevisionOutputRequest {
+            common: Self::convert_common(req.common),
+            object_id: req.object_id,
+            revision: req.revision,
+        };
+
+        let out_resp = self.processor.get_object_revision(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectHistoryInputProcessor for ObjectHistoryInputTransformer {
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        Self::get_object_history(&self, req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        Self::get_object_revision(&self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod object_history_acl;
+
+pub(crate) use object_history_acl::*;
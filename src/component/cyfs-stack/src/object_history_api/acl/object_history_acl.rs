@@ -0,0 +1,59 @@
+use crate::object_history::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ObjectHistoryAclInnerInputProcessor {
+    next: ObjectHistoryInputProcessorRef,
+}
+
+impl ObjectHistoryAclInnerInputProcessor {
+    pub(crate) fn new(next: ObjectHistoryInputProcessorRef) -> ObjectHistoryInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectHistoryInputProcessor for ObjectHistoryAclInnerInputProcessor {
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        self.check_local_zone_permit("object_history.get_object_history", &req.common.source)?;
+
+        self.next.get_object_history(req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        self.check_local_zone_permit("object_history.get_object_revision", &req.common.source)?;
+
+        self.next.get_object_revision(req).await
+    }
+}
@@ -0,0 +1,78 @@
+use crate::object_history::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalObjectHistoryService {
+    noc: NamedObjectCacheRef,
+}
+
+impl LocalObjectHistoryService {
+    pub(crate) fn new(noc: NamedObjectCacheRef) -> Self {
+        Self { noc }
+    }
+
+    pub fn clone_processor(&self) -> ObjectHistoryInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        let noc_req = NamedObjectCacheGetObjectHistoryRequest {
+            source: req.common.source,
+            object_id: req.object_id,
+        };
+
+        let resp = self.noc.get_object_history(&noc_req).await?;
+
+        Ok(ObjectHistoryListInputResponse { list: resp.list })
+    }
+
+    pub async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        let noc_req = NamedObjectCacheGetObjectRevisionRequest {
+            source: req.common.source,
+            object_id: req.object_id.clone(),
+            revision: req.revision,
+        };
+
+        match self.noc.get_object_revision(&noc_req).await? {
+            Some(data) => Ok(ObjectHistoryGetRevisionInputResponse {
+                version: data.version,
+                object_id: data.object.object_id,
+                object_raw: data.object.object_raw,
+            }),
+            None => {
+                let msg = format!(
+                    "object revision not found! obj={}, revision={}",
+                    req.object_id, req.revision
+                );
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::NotFound, msg))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectHistoryInputProcessor for LocalObjectHistoryService {
+    async fn get_object_history(
+        &self,
+        req: ObjectHistoryListInputRequest,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        Self::get_object_history(self, req).await
+    }
+
+    async fn get_object_revision(
+        &self,
+        req: ObjectHistoryGetRevisionInputRequest,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        Self::get_object_revision(self, req).await
+    }
+}
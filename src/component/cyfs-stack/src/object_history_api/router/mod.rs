@@ -0,0 +1,3 @@
+mod object_history_service_router;
+
+pub(crate) use object_history_service_router::*;
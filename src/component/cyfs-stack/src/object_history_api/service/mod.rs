@@ -0,0 +1,7 @@
+mod object_history_handler;
+mod object_history_listener;
+mod object_history_service;
+
+pub(crate) use object_history_handler::*;
+pub(crate) use object_history_listener::*;
+pub(crate) use object_history_service::*;
@@ -0,0 +1,102 @@
+use crate::non::NONInputHttpRequest;
+use crate::object_history::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ObjectHistoryRequestHandler {
+    processor: ObjectHistoryInputProcessorRef,
+}
+
+impl ObjectHistoryRequestHandler {
+    pub fn new(processor: ObjectHistoryInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // get_object_history
+    pub async fn process_get_object_history_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_object_history_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_object_history_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ObjectHistoryListInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get object history failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ObjectHistoryListOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ObjectHistoryListInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            object_id: out_req.object_id,
+        };
+        self.processor.get_object_history(in_req).await
+    }
+
+    // get_object_revision
+    pub async fn process_get_object_revision_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_object_revision_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_object_revision_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ObjectHistoryGetRevisionInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get object revision failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ObjectHistoryGetRevisionOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ObjectHistoryGetRevisionInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            object_id: out_req.object_id,
+            revision: out_req.revision,
+        };
+        self.processor.get_object_revision(in_req).await
+    }
+}
@@ -0,0 +1,98 @@
+use super::object_history_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ObjectHistoryRequestType {
+    GetObjectHistory,
+    GetObjectRevision,
+}
+
+pub(crate) struct ObjectHistoryRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ObjectHistoryRequestType,
+    handler: ObjectHistoryRequestHandler,
+}
+
+impl ObjectHistoryRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ObjectHistoryRequestType,
+        handler: ObjectHistoryRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ObjectHistoryRequestType::GetObjectHistory => {
+                self.handler.process_get_object_history_request(req).await
+            }
+            ObjectHistoryRequestType::GetObjectRevision => {
+                self.handler.process_get_object_revision_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ObjectHistoryRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // object history
+        server.at("/object_history/list").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectHistoryRequestType::GetObjectHistory,
+            handler.clone(),
+        ));
+
+        server.at("/object_history/list/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectHistoryRequestType::GetObjectHistory,
+            handler.clone(),
+        ));
+
+        server.at("/object_history/revision").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectHistoryRequestType::GetObjectRevision,
+            handler.clone(),
+        ));
+
+        server.at("/object_history/revision/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectHistoryRequestType::GetObjectRevision,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ObjectHistoryRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
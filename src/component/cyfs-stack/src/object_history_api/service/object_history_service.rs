@@ -0,0 +1,27 @@
+use super::super::local::LocalObjectHistoryService;
+use super::super::router::ObjectHistoryServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::object_history::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ObjectHistoryService {
+    router: ObjectHistoryInputProcessorRef,
+}
+
+impl ObjectHistoryService {
+    pub(crate) fn new(
+        noc: NamedObjectCacheRef,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalObjectHistoryService::new(noc);
+        let router =
+            ObjectHistoryServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ObjectHistoryInputProcessorRef {
+        self.router.clone()
+    }
+}
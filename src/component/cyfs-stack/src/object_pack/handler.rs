@@ -0,0 +1,103 @@
+use super::manager::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use async_std::io::BufReader;
+use http_types::StatusCode;
+use serde::Serialize;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ObjectPackRequestHandler {
+    manager: ObjectPackManager,
+}
+
+impl ObjectPackRequestHandler {
+    pub fn new(manager: ObjectPackManager) -> Self {
+        Self { manager }
+    }
+
+    fn encode_json_response<T: Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // export
+    pub async fn process_export_request<State: Send>(&self, req: tide::Request<State>) -> Response {
+        match self.on_export(req).await {
+            Ok(file) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                let len = file.len();
+                let mut body = tide::Body::from_reader(BufReader::new(file), Some(len as usize));
+                body.set_mime(::tide::http::Mime::from("application/zip"));
+                http_resp.set_body(body);
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_export<State: Send>(
+        &self,
+        mut req: tide::Request<State>,
+    ) -> BuckyResult<ObjectPackTempFile> {
+        let filter: ObjectPackExportFilter = req.body_json().await.map_err(|e| {
+            let msg = format!("read export object pack request from body failed! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        self.manager.export(filter).await
+    }
+
+    // import
+    pub async fn process_import_request<State: Send>(&self, req: tide::Request<State>) -> Response {
+        match self.on_import(req).await {
+            Ok(status) => Self::encode_json_response(&status),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_import<State: Send>(
+        &self,
+        mut req: tide::Request<State>,
+    ) -> BuckyResult<ObjectPackImportStatus> {
+        let body = req.take_body();
+        self.manager.import(body).await
+    }
+
+    // import status
+    pub async fn process_get_import_status_request<State: Send>(
+        &self,
+        req: tide::Request<State>,
+    ) -> Response {
+        match self.on_get_import_status(req) {
+            Ok(status) => Self::encode_json_response(&status),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    fn on_get_import_status<State: Send>(
+        &self,
+        req: tide::Request<State>,
+    ) -> BuckyResult<ObjectPackImportStatus> {
+        let id: Option<String> =
+            RequestorHelper::value_from_querys_with_utf8_decoding("id", req.url())?;
+        if id.is_none() {
+            let msg = format!(
+                "query object pack import status but id param missing! url={}",
+                req.url()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        self.manager.get_import_status(&id.unwrap())
+    }
+}
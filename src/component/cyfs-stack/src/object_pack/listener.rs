@@ -0,0 +1,55 @@
+use super::handler::ObjectPackRequestHandler;
+
+enum ObjectPackRequestType {
+    Export,
+    Import,
+    GetImportStatus,
+}
+
+pub(crate) struct ObjectPackRequestHandlerEndpoint {
+    req_type: ObjectPackRequestType,
+    handler: ObjectPackRequestHandler,
+}
+
+impl ObjectPackRequestHandlerEndpoint {
+    fn new(req_type: ObjectPackRequestType, handler: ObjectPackRequestHandler) -> Self {
+        Self { req_type, handler }
+    }
+
+    async fn process_request<State: Send>(&self, req: tide::Request<State>) -> tide::Response {
+        match self.req_type {
+            ObjectPackRequestType::Export => self.handler.process_export_request(req).await,
+            ObjectPackRequestType::Import => self.handler.process_import_request(req).await,
+            ObjectPackRequestType::GetImportStatus => {
+                self.handler.process_get_import_status_request(req).await
+            }
+        }
+    }
+
+    // 只在本地的http协议上开放，用于管理员在协议栈之间迁移数据
+    pub fn register_server(handler: &ObjectPackRequestHandler, server: &mut ::tide::Server<()>) {
+        server
+            .at("/object_pack/export")
+            .post(Self::new(ObjectPackRequestType::Export, handler.clone()));
+
+        server
+            .at("/object_pack/import")
+            .post(Self::new(ObjectPackRequestType::Import, handler.clone()));
+
+        server.at("/object_pack/import/status").get(Self::new(
+            ObjectPackRequestType::GetImportStatus,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait::async_trait]
+impl<State> tide::Endpoint<State> for ObjectPackRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,725 @@
+use cyfs_backup::{ArchiveInnerFileMeta, ObjectPackFactory};
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use async_std::io::Read as AsyncRead;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+// 保留最近完成的导入任务状态，用以查询结果
+const OBJECT_PACK_IMPORT_TASK_MAX_KEEP: usize = 16;
+
+// 导出的过滤条件，所有条件之间是与的关系
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ObjectPackExportFilter {
+    // 指定对象列表，不为空时只导出列表里面的对象
+    pub object_list: Option<Vec<ObjectId>>,
+
+    pub obj_type: Option<u16>,
+    pub dec_id: Option<ObjectId>,
+    pub owner_id: Option<ObjectId>,
+    pub create_dec_id: Option<ObjectId>,
+
+    // 对象写入noc的时间范围[begin, end)
+    pub insert_time_begin: Option<u64>,
+    pub insert_time_end: Option<u64>,
+
+    // 最多导出的对象数
+    pub limit: Option<u64>,
+}
+
+impl ObjectPackExportFilter {
+    fn check(&self, meta: &NamedObjectMetaData) -> bool {
+        if let Some(obj_type) = self.obj_type {
+            if meta.object_type != obj_type {
+                return false;
+            }
+        }
+
+        if self.dec_id.is_some() && meta.dec_id != self.dec_id {
+            return false;
+        }
+
+        if self.owner_id.is_some() && meta.owner_id != self.owner_id {
+            return false;
+        }
+
+        if let Some(create_dec_id) = &self.create_dec_id {
+            if meta.create_dec_id != *create_dec_id {
+                return false;
+            }
+        }
+
+        if let Some(begin) = self.insert_time_begin {
+            if meta.insert_time < begin {
+                return false;
+            }
+        }
+
+        if let Some(end) = self.insert_time_end {
+            if meta.insert_time >= end {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectPackImportState {
+    Running,
+    Complete,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObjectPackImportStatus {
+    pub id: String,
+    pub state: ObjectPackImportState,
+
+    // 包里面的文件数，包括跳过的
+    pub total: u64,
+    pub imported: u64,
+    pub exists: u64,
+
+    // 校验失败或者不支持的数据(比如chunk)
+    pub skipped: u64,
+
+    pub error: Option<String>,
+}
+
+impl ObjectPackImportStatus {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            state: ObjectPackImportState::Running,
+            total: 0,
+            imported: 0,
+            exists: 0,
+            skipped: 0,
+            error: None,
+        }
+    }
+}
+
+// 导出时先写入临时文件，读取完毕后自动删除
+pub(crate) struct ObjectPackTempFile {
+    path: PathBuf,
+    file: async_std::fs::File,
+    len: u64,
+    count: u64,
+}
+
+impl ObjectPackTempFile {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    // 包里面的对象数
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl AsyncRead for ObjectPackTempFile {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.file).poll_read(cx, buf)
+    }
+}
+
+impl Drop for ObjectPackTempFile {
+    fn drop(&mut self) {
+        ObjectPackManager::remove_file(&self.path);
+    }
+}
+
+struct ObjectPackManagerInner {
+    noc: NamedObjectCacheRef,
+    temp_dir: PathBuf,
+    next_seq: AtomicU64,
+    tasks: Mutex<Vec<ObjectPackImportStatus>>,
+}
+
+// 对象包的导入和导出，使用和cyfs-backup一样的pack格式(zip)，用于在不同协议栈之间批量迁移对象
+#[derive(Clone)]
+pub(crate) struct ObjectPackManager(Arc<ObjectPackManagerInner>);
+
+impl ObjectPackManager {
+    pub fn new(noc: NamedObjectCacheRef) -> Self {
+        let temp_dir = cyfs_util::get_temp_path().join("object_pack");
+
+        let inner = ObjectPackManagerInner {
+            noc,
+            temp_dir,
+            next_seq: AtomicU64::new(0),
+            tasks: Mutex::new(vec![]),
+        };
+
+        Self(Arc::new(inner))
+    }
+
+    fn new_temp_file(&self, prefix: &str) -> BuckyResult<(String, PathBuf)> {
+        if !self.0.temp_dir.is_dir() {
+            std::fs::create_dir_all(&self.0.temp_dir).map_err(|e| {
+                let msg = format!(
+                    "create object pack temp dir failed! dir={}, {}",
+                    self.0.temp_dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+        }
+
+        let seq = self.0.next_seq.fetch_add(1, Ordering::SeqCst);
+        let id = format!("{}-{}-{}", prefix, bucky_time_now(), seq);
+        let path = self.0.temp_dir.join(format!("{}.zip", id));
+
+        Ok((id, path))
+    }
+
+    fn remove_file(path: &Path) {
+        if let Err(e) = std::fs::remove_file(path) {
+            warn!(
+                "remove object pack temp file failed! file={}, {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    pub async fn export(&self, filter: ObjectPackExportFilter) -> BuckyResult<ObjectPackTempFile> {
+        let (id, path) = self.new_temp_file("export")?;
+
+        info!("will export object pack: id={}, filter={:?}", id, filter);
+
+        let count = match self.export_to_file(&filter, &path).await {
+            Ok(count) => {
+                info!("export object pack complete! id={}, count={}", id, count);
+                count
+            }
+            Err(e) => {
+                error!("export object pack failed! id={}, {}", id, e);
+                Self::remove_file(&path);
+                return Err(e);
+            }
+        };
+
+        let file = async_std::fs::File::open(&path).await.map_err(|e| {
+            let msg = format!(
+                "open object pack file failed! file={}, {}",
+                path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let len = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+
+        Ok(ObjectPackTempFile {
+            path,
+            file,
+            len,
+            count,
+        })
+    }
+
+    async fn export_to_file(
+        &self,
+        filter: &ObjectPackExportFilter,
+        path: &Path,
+    ) -> BuckyResult<u64> {
+        let mut writer = ObjectPackFactory::create_zip_writer(path.to_owned(), None);
+        writer.open().await?;
+
+        let limit = filter.limit.unwrap_or(u64::MAX);
+        let mut count = 0;
+
+        match &filter.object_list {
+            Some(list) => {
+                for object_id in list {
+                    if count >= limit {
+                        break;
+                    }
+                    if self.export_object(filter, object_id, &mut writer).await? {
+                        count += 1;
+                    }
+                }
+            }
+            None => {
+                let mut opt = NamedObjectCacheSelectObjectOption::default();
+                let noc_filter = NamedObjectCacheSelectObjectFilter {
+                    obj_type: filter.obj_type,
+                    last_access_before: None,
+                };
+
+                'outer: loop {
+                    let req = NamedObjectCacheSelectObjectRequest {
+                        filter: noc_filter.clone(),
+                        opt: opt.clone(),
+                    };
+
+                    let resp = self.0.noc.select_object(&req).await?;
+                    let page_count = resp.list.len();
+
+                    for item in resp.list {
+                        if count >= limit {
+                            break 'outer;
+                        }
+                        if self
+                            .export_object(filter, &item.object_id, &mut writer)
+                            .await?
+                        {
+                            count += 1;
+                        }
+                    }
+
+                    if page_count < opt.page_size {
+                        break;
+                    }
+
+                    opt.page_index += 1;
+                }
+            }
+        }
+
+        writer.finish().await?;
+
+        Ok(count)
+    }
+
+    async fn export_object(
+        &self,
+        filter: &ObjectPackExportFilter,
+        object_id: &ObjectId,
+        writer: &mut Box<dyn cyfs_backup::ObjectPackWriter>,
+    ) -> BuckyResult<bool> {
+        let req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: object_id.to_owned(),
+            last_access_rpath: None,
+            flags: 0,
+        };
+
+        let data = match self.0.noc.get_object(&req).await? {
+            Some(data) => data,
+            None => {
+                warn!("export object but not found in noc! id={}", object_id);
+                return Ok(false);
+            }
+        };
+
+        if !filter.check(&data.meta) {
+            return Ok(false);
+        }
+
+        let meta = ArchiveInnerFileMeta::from(&data.meta).to_vec()?;
+        writer
+            .add_data_buf(object_id, &data.object.object_raw, Some(meta))
+            .await??;
+
+        Ok(true)
+    }
+
+    // 上传的数据先保存到临时文件，然后在后台导入，通过返回的id查询进度
+    pub async fn import(
+        &self,
+        data: impl AsyncRead + Unpin,
+    ) -> BuckyResult<ObjectPackImportStatus> {
+        let (id, path) = self.new_temp_file("import")?;
+
+        if let Err(e) = Self::save_to_file(data, &path).await {
+            Self::remove_file(&path);
+            return Err(e);
+        }
+
+        let status = ObjectPackImportStatus::new(id.clone());
+        {
+            let mut tasks = self.0.tasks.lock().unwrap();
+            if tasks.len() >= OBJECT_PACK_IMPORT_TASK_MAX_KEEP {
+                if let Some(pos) = tasks
+                    .iter()
+                    .position(|task| task.state != ObjectPackImportState::Running)
+                {
+                    tasks.remove(pos);
+                }
+            }
+            tasks.push(status.clone());
+        }
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            info!("will import object pack: id={}", id);
+
+            let ret = this.import_from_file(&id, &path).await;
+            Self::remove_file(&path);
+
+            this.update_status(&id, |status| match ret {
+                Ok(()) => {
+                    info!("import object pack complete! status={:?}", status);
+                    status.state = ObjectPackImportState::Complete;
+                }
+                Err(e) => {
+                    error!("import object pack failed! id={}, {}", status.id, e);
+                    status.state = ObjectPackImportState::Failed;
+                    status.error = Some(e.to_string());
+                }
+            });
+        });
+
+        Ok(status)
+    }
+
+    pub fn get_import_status(&self, id: &str) -> BuckyResult<ObjectPackImportStatus> {
+        let tasks = self.0.tasks.lock().unwrap();
+        match tasks.iter().find(|task| task.id == id) {
+            Some(status) => Ok(status.clone()),
+            None => {
+                let msg = format!("object pack import task not found! id={}", id);
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::NotFound, msg))
+            }
+        }
+    }
+
+    fn update_status(&self, id: &str, f: impl FnOnce(&mut ObjectPackImportStatus)) {
+        let mut tasks = self.0.tasks.lock().unwrap();
+        if let Some(status) = tasks.iter_mut().find(|task| task.id == id) {
+            f(status);
+        }
+    }
+
+    async fn save_to_file(mut data: impl AsyncRead + Unpin, path: &Path) -> BuckyResult<()> {
+        let mut file = async_std::fs::File::create(path).await.map_err(|e| {
+            let msg = format!(
+                "create object pack file failed! file={}, {}",
+                path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let len = async_std::io::copy(&mut data, &mut file)
+            .await
+            .map_err(|e| {
+                let msg = format!(
+                    "save object pack to file failed! file={}, {}",
+                    path.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        use async_std::io::WriteExt;
+        file.flush().await.map_err(|e| {
+            let msg = format!(
+                "flush object pack file failed! file={}, {}",
+                path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        info!(
+            "save object pack to file success! file={}, len={}",
+            path.display(),
+            len
+        );
+
+        Ok(())
+    }
+
+    async fn import_from_file(&self, id: &str, path: &Path) -> BuckyResult<()> {
+        let mut reader = ObjectPackFactory::create_zip_reader(path.to_owned(), None);
+        reader.open().await?;
+
+        loop {
+            let ret = reader.next_data().await?;
+            if ret.is_none() {
+                break;
+            }
+
+            let (object_id, file) = ret.unwrap();
+            let ret = self.import_object(&object_id, file).await;
+            self.update_status(id, |status| {
+                status.total += 1;
+                match ret {
+                    Ok(NamedObjectCachePutObjectResult::AlreadyExists) => status.exists += 1,
+                    Ok(_) => status.imported += 1,
+                    Err(_) => status.skipped += 1,
+                }
+            });
+        }
+
+        reader.close().await?;
+
+        Ok(())
+    }
+
+    async fn import_object(
+        &self,
+        object_id: &ObjectId,
+        file: cyfs_backup::ObjectPackInnerFile,
+    ) -> BuckyResult<NamedObjectCachePutObjectResult> {
+        if object_id.obj_type_code() == ObjectTypeCode::Chunk {
+            let msg = format!(
+                "import chunk from object pack not support! id={}",
+                object_id
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+        }
+
+        let meta = match &file.meta {
+            Some(meta) => Some(ArchiveInnerFileMeta::clone_from_slice(meta).map_err(|e| {
+                let msg = format!(
+                    "decode object meta from pack failed! id={}, {}",
+                    object_id, e
+                );
+                warn!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidData, msg)
+            })?),
+            None => None,
+        };
+
+        let object_raw = file.data.into_buffer().await?;
+
+        // 校验对象编码以及对象id
+        let mut object = NONObjectInfo::new(object_id.to_owned(), object_raw, None);
+        object.decode_and_verify().map_err(|e| {
+            warn!("invalid object in pack! id={}, {}", object_id, e);
+            e
+        })?;
+
+        let (source, storage_category, context, access_string) = match meta {
+            Some(meta) => (
+                RequestSourceInfo::new_local_dec(Some(meta.create_dec_id)),
+                meta.storage_category,
+                meta.context,
+                Some(meta.access),
+            ),
+            None => (
+                RequestSourceInfo::new_local_system(),
+                NamedObjectStorageCategory::default(),
+                None,
+                None,
+            ),
+        };
+
+        let req = NamedObjectCachePutObjectRequest {
+            source,
+            object,
+            storage_category,
+            context,
+            last_access_rpath: None,
+            access_string,
+            precondition: None,
+        };
+
+        let resp = self.0.noc.put_object(&req).await.map_err(|e| {
+            error!("import object to noc failed! id={}, {}", object_id, e);
+            e
+        })?;
+
+        Ok(resp.result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::*;
+
+    fn new_meta(
+        object_type: u16,
+        create_dec_id: &ObjectId,
+        insert_time: u64,
+    ) -> NamedObjectMetaData {
+        NamedObjectMetaData {
+            object_id: ObjectId::default(),
+            object_type,
+            owner_id: None,
+            create_dec_id: create_dec_id.clone(),
+            insert_time,
+            update_time: insert_time,
+            object_create_time: None,
+            object_update_time: None,
+            object_expired_time: None,
+            author: None,
+            dec_id: None,
+            storage_category: NamedObjectStorageCategory::default(),
+            context: None,
+            last_access_rpath: None,
+            access_string: AccessString::default().value(),
+            sign_verified_update_time: None,
+        }
+    }
+
+    async fn put_text(noc: &NamedObjectCacheRef, value: &str) -> ObjectId {
+        let text = Text::create("test-object-pack", "", value);
+        let object = NONObjectInfo::new_from_object_raw(text.to_vec().unwrap()).unwrap();
+        let object_id = object.object_id.clone();
+
+        let req = NamedObjectCachePutObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object,
+            storage_category: NamedObjectStorageCategory::Storage,
+            context: None,
+            last_access_rpath: None,
+            access_string: None,
+            precondition: None,
+        };
+        noc.put_object(&req).await.unwrap();
+
+        object_id
+    }
+
+    async fn wait_import(manager: &ObjectPackManager, id: &str) -> ObjectPackImportStatus {
+        loop {
+            let status = manager.get_import_status(id).unwrap();
+            if status.state != ObjectPackImportState::Running {
+                break status;
+            }
+
+            async_std::task::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let text_type = CoreObjectType::Text as u16;
+        let dec_id = cyfs_core::get_system_dec_app().to_owned();
+        let meta = new_meta(text_type, &dec_id, 100);
+
+        assert!(ObjectPackExportFilter::default().check(&meta));
+
+        let mut filter = ObjectPackExportFilter::default();
+        filter.obj_type = Some(text_type);
+        filter.create_dec_id = Some(dec_id.clone());
+        assert!(filter.check(&meta));
+
+        filter.obj_type = Some(CoreObjectType::Storage as u16);
+        assert!(!filter.check(&meta));
+        filter.obj_type = None;
+
+        filter.create_dec_id = Some(ObjectId::default());
+        assert!(!filter.check(&meta));
+        filter.create_dec_id = None;
+
+        // 对象没有dec_id和owner时不匹配
+        filter.dec_id = Some(dec_id.clone());
+        assert!(!filter.check(&meta));
+        filter.dec_id = None;
+        filter.owner_id = Some(dec_id.clone());
+        assert!(!filter.check(&meta));
+        filter.owner_id = None;
+
+        // 写入时间的范围为[begin, end)
+        filter.insert_time_begin = Some(100);
+        filter.insert_time_end = Some(101);
+        assert!(filter.check(&meta));
+        filter.insert_time_begin = Some(101);
+        assert!(!filter.check(&meta));
+        filter.insert_time_begin = None;
+        filter.insert_time_end = Some(100);
+        assert!(!filter.check(&meta));
+    }
+
+    #[async_std::test]
+    async fn test_export_and_import() {
+        let noc = cyfs_noc::NamedObjectCacheManager::create("test-object-pack-export")
+            .await
+            .unwrap();
+        let id1 = put_text(&noc, "value1").await;
+        let id2 = put_text(&noc, "value2").await;
+        let id3 = put_text(&noc, "value3").await;
+        let missing = Text::create("test-object-pack", "", "missing")
+            .desc()
+            .calculate_id();
+
+        let manager = ObjectPackManager::new(noc);
+
+        // 不存在的对象和超过limit的对象被忽略
+        let mut filter = ObjectPackExportFilter::default();
+        filter.object_list = Some(vec![id1.clone(), missing, id2.clone(), id3.clone()]);
+        filter.limit = Some(2);
+        let mut file = manager.export(filter).await.unwrap();
+        assert_eq!(file.count(), 2);
+        assert!(file.len() > 0);
+
+        let mut data = vec![];
+        async_std::io::ReadExt::read_to_end(&mut file, &mut data)
+            .await
+            .unwrap();
+        let path = file.path.clone();
+        drop(file);
+        assert!(!path.exists());
+
+        let mut filter = ObjectPackExportFilter::default();
+        filter.object_list = Some(vec![id1.clone()]);
+        filter.obj_type = Some(CoreObjectType::Storage as u16);
+        assert_eq!(manager.export(filter).await.unwrap().count(), 0);
+
+        let target = cyfs_noc::NamedObjectCacheManager::create("test-object-pack-import")
+            .await
+            .unwrap();
+        for id in [&id1, &id2] {
+            let req = NamedObjectCacheDeleteObjectRequest {
+                source: RequestSourceInfo::new_local_system(),
+                object_id: id.clone(),
+                flags: 0,
+            };
+            target.delete_object(&req).await.unwrap();
+        }
+        let importer = ObjectPackManager::new(target.clone());
+
+        let status = importer.import(data.as_slice()).await.unwrap();
+        assert_eq!(status.state, ObjectPackImportState::Running);
+        let status = wait_import(&importer, &status.id).await;
+        assert_eq!(status.state, ObjectPackImportState::Complete);
+        assert_eq!((status.total, status.imported, status.exists), (2, 2, 0));
+
+        for id in [&id1, &id2] {
+            let req = NamedObjectCacheGetObjectRequest {
+                source: RequestSourceInfo::new_local_system(),
+                object_id: id.clone(),
+                last_access_rpath: None,
+                flags: 0,
+            };
+            let data = target.get_object(&req).await.unwrap().unwrap();
+            assert_eq!(
+                data.meta.storage_category,
+                NamedObjectStorageCategory::Storage
+            );
+        }
+
+        // 重复导入
+        let status = importer.import(data.as_slice()).await.unwrap();
+        let status = wait_import(&importer, &status.id).await;
+        assert_eq!((status.total, status.imported, status.exists), (2, 0, 2));
+
+        // 无效的包
+        let status = importer.import(&b"invalid pack"[..]).await.unwrap();
+        let status = wait_import(&importer, &status.id).await;
+        assert_eq!(status.state, ObjectPackImportState::Failed);
+        assert!(status.error.is_some());
+
+        let err = importer.get_import_status("not-exists").unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::NotFound);
+    }
+}
@@ -0,0 +1,7 @@
+mod handler;
+mod listener;
+mod manager;
+
+pub(crate) use handler::*;
+pub(crate) use listener::*;
+pub(crate) use manager::*;
@@ -0,0 +1,14 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ObjectRefsInputProcessor: Sync + Send + 'static {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse>;
+}
+
+pub(crate) type ObjectRefsInputProcessorRef = Arc<dyn ObjectRefsInputProcessor>;
@@ -0,0 +1,58 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ObjectRefsInputTransformer {
+    processor: ObjectRefsOutputProcessorRef,
+}
+
+impl ObjectRefsInputTransformer {
+    pub fn new(processor: ObjectRefsOutputProcessorRef) -> ObjectRefsInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        let out_req = ObjectRefsQueryOutputRequest {
+            common: Self::convert_common(req.common),
+            query_type: req.query_type,
+            object_id: req.object_id,
+            page_index: req.page_index,
+            page_size: req.page_size,
+        };
+
+        let out_resp = self.processor.query_object_refs(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectRefsInputProcessor for ObjectRefsInputTransformer {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        Self::query_object_refs(&self, req).await
+    }
+}
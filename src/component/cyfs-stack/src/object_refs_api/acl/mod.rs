@@ -0,0 +1,3 @@
+mod object_refs_acl;
+
+pub(crate) use object_refs_acl::*;
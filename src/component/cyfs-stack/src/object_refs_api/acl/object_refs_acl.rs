@@ -0,0 +1,124 @@
+use crate::object_refs::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ObjectRefsAclInnerInputProcessor {
+    next: ObjectRefsInputProcessorRef,
+}
+
+impl ObjectRefsAclInnerInputProcessor {
+    pub(crate) fn new(next: ObjectRefsInputProcessorRef) -> ObjectRefsInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectRefsInputProcessor for ObjectRefsAclInnerInputProcessor {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        self.check_local_zone_permit("object_refs.query_object_refs", &req.common.source)?;
+
+        // orphans是整个noc范围的查询，只允许系统dec
+        if req.query_type == ObjectRefsQueryType::Orphans && !req.common.source.is_system_dec() {
+            let msg = format!(
+                "query orphan objects only allowed for system dec! source={}",
+                req.common.source
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.query_object_refs(req).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::{DecApp, DecAppObj};
+
+    struct DummyObjectRefs;
+
+    #[async_trait::async_trait]
+    impl ObjectRefsInputProcessor for DummyObjectRefs {
+        async fn query_object_refs(
+            &self,
+            _req: ObjectRefsQueryInputRequest,
+        ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+            Ok(ObjectRefsQueryInputResponse { list: vec![] })
+        }
+    }
+
+    async fn query(
+        source: RequestSourceInfo,
+        query_type: ObjectRefsQueryType,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        let acl = ObjectRefsAclInnerInputProcessor::new(Arc::new(DummyObjectRefs));
+
+        let req = ObjectRefsQueryInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: None,
+                source,
+                target: None,
+                flags: 0,
+            },
+            query_type,
+            object_id: Some(ObjectId::default()),
+            page_index: 0,
+            page_size: 32,
+        };
+
+        acl.query_object_refs(req).await
+    }
+
+    #[async_std::test]
+    async fn test_orphans_acl() {
+        let dec_id = DecApp::generate_id(ObjectId::default(), "object-refs-dec");
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id));
+
+        let ret = query(source.clone(), ObjectRefsQueryType::Orphans).await;
+        assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::PermissionDenied);
+
+        query(source.clone(), ObjectRefsQueryType::Referrers)
+            .await
+            .unwrap();
+        query(source, ObjectRefsQueryType::References)
+            .await
+            .unwrap();
+
+        query(
+            RequestSourceInfo::new_local_system(),
+            ObjectRefsQueryType::Orphans,
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+use crate::object_refs::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+use cyfs_noc::NamedObjectRefGraph;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalObjectRefsService {
+    noc: NamedObjectCacheRef,
+    ref_graph: Option<NamedObjectRefGraph>,
+}
+
+impl LocalObjectRefsService {
+    pub(crate) fn new(noc: NamedObjectCacheRef, ref_graph: Option<NamedObjectRefGraph>) -> Self {
+        Self { noc, ref_graph }
+    }
+
+    pub fn clone_processor(&self) -> ObjectRefsInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        let graph = self.ref_graph.as_ref().ok_or_else(|| {
+            let msg = format!("object ref graph not enabled!");
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotSupport, msg)
+        })?;
+
+        let list = match req.query_type {
+            ObjectRefsQueryType::Orphans => graph.orphans(req.page_index, req.page_size)?,
+            query_type => {
+                let object_id = req.object_id.as_ref().ok_or_else(|| {
+                    let msg = format!("query object {} but object_id not specified!", query_type);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+                })?;
+
+                self.check_object_access(&req.common.source, object_id)
+                    .await?;
+
+                match query_type {
+                    ObjectRefsQueryType::Referrers => {
+                        graph.referrers(object_id, req.page_index, req.page_size)?
+                    }
+                    _ => graph.references(object_id, req.page_index, req.page_size)?,
+                }
+            }
+        };
+
+        Ok(ObjectRefsQueryInputResponse { list })
+    }
+
+    // 查询某个对象的引用关系，需要来源对该对象有读权限
+    async fn check_object_access(
+        &self,
+        source: &RequestSourceInfo,
+        object_id: &ObjectId,
+    ) -> BuckyResult<()> {
+        if source.is_system_dec() {
+            return Ok(());
+        }
+
+        let req = NamedObjectCacheCheckObjectAccessRequest {
+            source: source.clone(),
+            object_id: object_id.clone(),
+            required_access: AccessPermissions::ReadOnly,
+        };
+
+        let ret = self.noc.check_object_access(&req).await?;
+        if ret.is_none() {
+            let msg = format!(
+                "query object refs but object not found! object={}, source={}",
+                object_id, source
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectRefsInputProcessor for LocalObjectRefsService {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        Self::query_object_refs(self, req).await
+    }
+}
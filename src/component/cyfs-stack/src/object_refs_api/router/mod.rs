@@ -0,0 +1,3 @@
+mod object_refs_service_router;
+
+pub(crate) use object_refs_service_router::*;
@@ -0,0 +1,92 @@
+use super::super::acl::ObjectRefsAclInnerInputProcessor;
+use crate::forward::ForwardProcessorManager;
+use crate::object_refs::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct ObjectRefsServiceRouter {
+    processor: ObjectRefsInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl ObjectRefsServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: ObjectRefsInputProcessorRef,
+    ) -> ObjectRefsInputProcessorRef {
+        // 限定同zone
+        let processor = ObjectRefsAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<ObjectRefsInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = ObjectRefsRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = ObjectRefsInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<ObjectRefsInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("object refs target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectRefsInputProcessor for ObjectRefsServiceRouter {
+    async fn query_object_refs(
+        &self,
+        req: ObjectRefsQueryInputRequest,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.query_object_refs(req).await
+    }
+}
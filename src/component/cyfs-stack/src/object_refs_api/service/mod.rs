@@ -0,0 +1,7 @@
+mod object_refs_handler;
+mod object_refs_listener;
+mod object_refs_service;
+
+pub(crate) use object_refs_handler::*;
+pub(crate) use object_refs_listener::*;
+pub(crate) use object_refs_service::*;
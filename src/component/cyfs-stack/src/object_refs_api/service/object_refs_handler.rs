@@ -0,0 +1,65 @@
+use crate::non::NONInputHttpRequest;
+use crate::object_refs::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ObjectRefsRequestHandler {
+    processor: ObjectRefsInputProcessorRef,
+}
+
+impl ObjectRefsRequestHandler {
+    pub fn new(processor: ObjectRefsInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    // query_object_refs
+    pub async fn process_query_object_refs_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        match self.on_query_object_refs_request(req).await {
+            Ok(resp) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(resp.encode_string());
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_query_object_refs_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ObjectRefsQueryInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("query object refs failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ObjectRefsQueryOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ObjectRefsQueryInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+
+            query_type: out_req.query_type,
+            object_id: out_req.object_id,
+            page_index: out_req.page_index,
+            page_size: out_req.page_size,
+        };
+        self.processor.query_object_refs(in_req).await
+    }
+}
@@ -0,0 +1,79 @@
+use super::object_refs_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ObjectRefsRequestType {
+    QueryObjectRefs,
+}
+
+pub(crate) struct ObjectRefsRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ObjectRefsRequestType,
+    handler: ObjectRefsRequestHandler,
+}
+
+impl ObjectRefsRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ObjectRefsRequestType,
+        handler: ObjectRefsRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ObjectRefsRequestType::QueryObjectRefs => {
+                self.handler.process_query_object_refs_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ObjectRefsRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        server.at("/object_refs/query").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectRefsRequestType::QueryObjectRefs,
+            handler.clone(),
+        ));
+
+        server.at("/object_refs/query/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ObjectRefsRequestType::QueryObjectRefs,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ObjectRefsRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,30 @@
+use super::super::local::LocalObjectRefsService;
+use super::super::router::ObjectRefsServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::object_refs::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+use cyfs_noc::NamedObjectRefGraph;
+
+pub(crate) struct ObjectRefsService {
+    router: ObjectRefsInputProcessorRef,
+}
+
+impl ObjectRefsService {
+    pub(crate) fn new(
+        noc: NamedObjectCacheRef,
+        ref_graph: Option<NamedObjectRefGraph>,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalObjectRefsService::new(noc, ref_graph);
+        let router =
+            ObjectRefsServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ObjectRefsInputProcessorRef {
+        self.router.clone()
+    }
+}
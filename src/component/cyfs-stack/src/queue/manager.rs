@@ -0,0 +1,911 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// 队列保存在ood的root_state系统dec下:
+// /queue/{dec_id}/{name}/config -> 配置text_id
+// /queue/{dec_id}/{name}/items/{key} -> 消息text_id
+// /queue/{dec_id}/{name}/dead/{key} -> 死信text_id
+const QUEUE_ROOT_PATH: &str = "/queue";
+
+// Text对象id，header为队列路径，value为QueueConfig的json
+const QUEUE_CONFIG_TEXT_ID: &str = "queue_config";
+
+// Text对象id，header为消息key，value为QueueItem的json，消息对象本身保存在noc
+const QUEUE_ITEM_TEXT_ID: &str = "queue_item";
+
+// 跨zone推送用的Text对象id，header为队列名，value为RemotePush的json
+const QUEUE_PUSH_TEXT_ID: &str = "queue_push";
+
+// 跨zone推送的回复，header为消息key
+const QUEUE_PUSHED_TEXT_ID: &str = "queue_pushed";
+
+const QUEUE_HANDLER_ID: &str = "system_queue_remote_push";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct QueueItem {
+    object_id: ObjectId,
+    source_zone: Option<ObjectId>,
+    source_dec: ObjectId,
+    push_time: u64,
+    attempts: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RemotePush {
+    dec_id: ObjectId,
+    name: String,
+    source_dec: ObjectId,
+    object_id: ObjectId,
+
+    // 消息对象的hex编码
+    object_raw: String,
+}
+
+struct OnQueueRemotePushWatcher {
+    owner: QueueManager,
+}
+
+#[async_trait::async_trait]
+impl EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPostObjectResult>
+    for OnQueueRemotePushWatcher
+{
+    async fn call(
+        &self,
+        param: &RouterHandlerPostObjectRequest,
+    ) -> BuckyResult<RouterHandlerPostObjectResult> {
+        debug!(
+            "recv queue remote push: {}, source={}",
+            param.request.object.object_id, param.request.common.source
+        );
+
+        let ret = self
+            .owner
+            .on_remote_push(&param.request.common.source, &param.request.object)
+            .await
+            .map(|object| NONPostObjectInputResponse {
+                object: Some(object),
+            });
+
+        let resp = RouterHandlerPostObjectResult {
+            action: RouterHandlerAction::Response,
+            request: None,
+            response: Some(ret),
+        };
+
+        Ok(resp)
+    }
+}
+
+// 消息key按时间递增，同一微秒内的推送依次加一
+struct QueueKeyGenerator {
+    last: Mutex<u64>,
+}
+
+impl QueueKeyGenerator {
+    fn new() -> Self {
+        Self {
+            last: Mutex::new(0),
+        }
+    }
+
+    fn next(&self, now: u64) -> String {
+        let mut last = self.last.lock().unwrap();
+        *last = std::cmp::max(*last + 1, now);
+
+        format!("{:020}", *last)
+    }
+}
+
+// 已经被pull还没有ack的消息: {dec_id}/{name}/{key} -> 租约到期时间
+struct QueueLeases {
+    list: Mutex<HashMap<String, u64>>,
+}
+
+impl QueueLeases {
+    fn new() -> Self {
+        Self {
+            list: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 成功占用租约返回true，已经被其它pull占用时返回false
+    fn try_lease(&self, lease_key: String, now: u64, deadline: u64) -> bool {
+        let mut list = self.list.lock().unwrap();
+        if list.len() > 1024 {
+            list.retain(|_, v| *v > now);
+        }
+
+        match list.get(&lease_key) {
+            Some(v) if *v > now => false,
+            _ => {
+                list.insert(lease_key, deadline);
+                true
+            }
+        }
+    }
+
+    fn release(&self, lease_key: &str) {
+        self.list.lock().unwrap().remove(lease_key);
+    }
+}
+
+struct QueueManagerInner {
+    zone_manager: ZoneManagerRef,
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+
+    keys: QueueKeyGenerator,
+    leases: QueueLeases,
+}
+
+// dec之间的持久化消息队列，只在ood上保存
+// 队列属于创建它的dec，同zone的dec通过util接口推送，其它zone通过router post到本zone的ood
+#[derive(Clone)]
+pub(crate) struct QueueManager(Arc<QueueManagerInner>);
+
+impl QueueManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = QueueManagerInner {
+            zone_manager: zone_manager.clone(),
+            non,
+            root_state_stub,
+            keys: QueueKeyGenerator::new(),
+            leases: QueueLeases::new(),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub async fn init(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if info.zone_role.is_ood_device() {
+            self.register_router_handler(router_handlers).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn register_router_handler(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let routine = OnQueueRemotePushWatcher {
+            owner: self.clone(),
+        };
+
+        let req_path =
+            RequestGlobalStatePath::new_system_dec(Some(CYFS_SYSTEM_QUEUE_VIRTUAL_PATH));
+        if let Err(e) = router_handlers
+            .post_object()
+            .add_handler(
+                RouterHandlerChain::Handler,
+                QUEUE_HANDLER_ID,
+                1,
+                None,
+                Some(req_path.to_string()),
+                RouterHandlerAction::Default,
+                Some(Box::new(routine)),
+            )
+            .await
+        {
+            error!("add queue post handler error! {}", e);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn check_name(name: &str) -> BuckyResult<()> {
+        if name.is_empty() || name.contains('/') {
+            let msg = format!("invalid queue name: {:?}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    fn queue_path(dec_id: &ObjectId, name: &str) -> String {
+        format!("{}/{}/{}", QUEUE_ROOT_PATH, dec_id, name)
+    }
+
+    fn items_path(dec_id: &ObjectId, name: &str) -> String {
+        format!("{}/items", Self::queue_path(dec_id, name))
+    }
+
+    fn dead_path(dec_id: &ObjectId, name: &str) -> String {
+        format!("{}/dead", Self::queue_path(dec_id, name))
+    }
+
+    fn lease_key(dec_id: &ObjectId, name: &str, key: &str) -> String {
+        format!("{}/{}/{}", dec_id, name, key)
+    }
+
+    fn next_key(&self) -> String {
+        self.0.keys.next(bucky_time_now())
+    }
+
+    pub async fn create(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        config: QueueConfig,
+    ) -> BuckyResult<QueueConfig> {
+        Self::check_name(name)?;
+
+        if config.max_attempts == 0 {
+            let msg = format!("queue max_attempts should not be zero! name={}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let path = Self::queue_path(dec_id, name);
+        let value = serde_json::to_string(&config).unwrap();
+        let text = Text::build(QUEUE_CONFIG_TEXT_ID, &path, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save queue config to noc failed! dec={}, name={}, {}", dec_id, name, e);
+            e
+        })?;
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env.set_with_key(&path, "config", &text_id, None, true).await {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("save queue config failed! dec={}, name={}, {}", dec_id, name, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        info!("create queue success! dec={}, name={}, config={:?}", dec_id, name, config);
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(config)
+    }
+
+    async fn load_config(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<QueueConfig> {
+        Self::check_name(name)?;
+
+        let path = Self::queue_path(dec_id, name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&path, "config").await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(v) => v,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => None,
+            Err(e) => {
+                error!("load queue config failed! dec={}, name={}, {}", dec_id, name, e);
+                return Err(e);
+            }
+        }
+        .ok_or_else(|| {
+            let msg = format!("queue not found! dec={}, name={}", dec_id, name);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        let text = self.load_text(&text_id).await?;
+        Self::decode_text(&text, QUEUE_CONFIG_TEXT_ID)
+    }
+
+    pub async fn push(
+        &self,
+        source: &RequestSourceInfo,
+        zone: Option<&ObjectId>,
+        dec_id: &ObjectId,
+        name: &str,
+        object_id: ObjectId,
+        object_raw: Vec<u8>,
+    ) -> BuckyResult<String> {
+        if let Some(zone) = zone {
+            let info = self
+                .0
+                .zone_manager
+                .target_zone_manager()
+                .resolve_target(Some(zone))
+                .await?;
+            if !info.is_current_zone {
+                return self
+                    .push_remote(&info.target_ood, &source.dec, dec_id, name, object_id, object_raw)
+                    .await;
+            }
+        }
+
+        self.push_local(None, &source.dec, dec_id, name, object_id, object_raw, false)
+            .await
+    }
+
+    async fn push_local(
+        &self,
+        source_zone: Option<ObjectId>,
+        source_dec: &ObjectId,
+        dec_id: &ObjectId,
+        name: &str,
+        object_id: ObjectId,
+        object_raw: Vec<u8>,
+        remote: bool,
+    ) -> BuckyResult<String> {
+        let config = self.load_config(dec_id, name).await?;
+        if remote && !config.allow_remote {
+            let msg = format!(
+                "queue not allow remote push! dec={}, name={}, source={:?}",
+                dec_id, name, source_zone
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let object = AnyNamedObject::clone_from_slice(&object_raw)?;
+        let id = object.calculate_id();
+        if id != object_id {
+            let msg = format!(
+                "queue message object id not match! expect={}, got={}",
+                object_id, id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let req = NONPutObjectOutputRequest::new_noc(object_id.clone(), object_raw);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save queue message to noc failed! object={}, {}", object_id, e);
+            e
+        })?;
+
+        let key = self.next_key();
+        let item = QueueItem {
+            object_id,
+            source_zone,
+            source_dec: source_dec.to_owned(),
+            push_time: bucky_time_now(),
+            attempts: 0,
+        };
+        let text_id = self.save_item(&key, &item).await?;
+
+        let path = Self::items_path(dec_id, name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        if let Err(e) = op_env.set_with_key(&path, &key, &text_id, None, true).await {
+            error!("push queue item failed! dec={}, name={}, {}", dec_id, name, e);
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        debug!(
+            "push queue success! dec={}, name={}, key={}, object={}",
+            dec_id, name, key, item.object_id
+        );
+
+        Ok(key)
+    }
+
+    async fn push_remote(
+        &self,
+        ood_id: &DeviceId,
+        source_dec: &ObjectId,
+        dec_id: &ObjectId,
+        name: &str,
+        object_id: ObjectId,
+        object_raw: Vec<u8>,
+    ) -> BuckyResult<String> {
+        let push = RemotePush {
+            dec_id: dec_id.to_owned(),
+            name: name.to_owned(),
+            source_dec: source_dec.to_owned(),
+            object_id,
+            object_raw: hex::encode(&object_raw),
+        };
+
+        let zone_info = self.0.zone_manager.get_current_info().await?;
+        let value = serde_json::to_string(&push).unwrap();
+        let text = Text::build(QUEUE_PUSH_TEXT_ID, name, value)
+            .owner(zone_info.owner_id.clone())
+            .no_create_time()
+            .build();
+
+        let mut req = NONPostObjectOutputRequest::new_router(
+            Some(ood_id.object_id().to_owned()),
+            text.desc().calculate_id(),
+            text.to_vec()?,
+        );
+        req.common.req_path = Some(CYFS_SYSTEM_QUEUE_VIRTUAL_PATH.to_owned());
+
+        let resp = self.0.non.post_object(req).await.map_err(|e| {
+            warn!(
+                "push queue to remote failed! ood={}, dec={}, name={}, {}",
+                ood_id, dec_id, name, e
+            );
+            e
+        })?;
+
+        let object = resp.object.ok_or_else(|| {
+            let msg = format!("push queue to remote got empty response! ood={}", ood_id);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        let text = Text::clone_from_slice(&object.object_raw)?;
+        if text.id() != QUEUE_PUSHED_TEXT_ID {
+            let msg = format!("invalid queue push response text id: {}", text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        Ok(text.header().to_owned())
+    }
+
+    async fn on_remote_push(
+        &self,
+        source: &RequestSourceInfo,
+        object: &NONObjectInfo,
+    ) -> BuckyResult<NONObjectInfo> {
+        let text = Text::clone_from_slice(&object.object_raw).map_err(|e| {
+            let msg = format!(
+                "invalid queue push object buffer! id={}, {}",
+                object.object_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let push: RemotePush = Self::decode_text(&text, QUEUE_PUSH_TEXT_ID)?;
+        let object_raw = hex::decode(&push.object_raw).map_err(|e| {
+            let msg = format!("invalid queue push object hex! {}", e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let key = self
+            .push_local(
+                source.zone.zone.clone(),
+                &push.source_dec,
+                &push.dec_id,
+                &push.name,
+                push.object_id,
+                object_raw,
+                !source.is_current_zone(),
+            )
+            .await?;
+
+        let text = Text::build(QUEUE_PUSHED_TEXT_ID, key, "")
+            .no_create_time()
+            .build();
+
+        Ok(NONObjectInfo::new(
+            text.desc().calculate_id(),
+            text.to_vec()?,
+            None,
+        ))
+    }
+
+    fn try_lease(&self, lease_key: String, now: u64, deadline: u64) -> bool {
+        self.0.leases.try_lease(lease_key, now, deadline)
+    }
+
+    fn release_lease(&self, lease_key: &str) {
+        self.0.leases.release(lease_key)
+    }
+
+    pub async fn pull(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        max_count: u32,
+    ) -> BuckyResult<Vec<QueueMessage>> {
+        let config = self.load_config(dec_id, name).await?;
+        let list = self.list_items(&Self::items_path(dec_id, name)).await?;
+
+        let now = bucky_time_now();
+        let deadline = now + config.lease_secs as u64 * 1000 * 1000;
+
+        let mut result = vec![];
+        for (key, text_id) in list {
+            if result.len() >= max_count as usize {
+                break;
+            }
+
+            let lease_key = Self::lease_key(dec_id, name, &key);
+            if !self.try_lease(lease_key.clone(), now, deadline) {
+                continue;
+            }
+
+            match self
+                .deliver(dec_id, name, &config, &key, &text_id)
+                .await
+            {
+                Ok(Some(msg)) => result.push(msg),
+                Ok(None) => self.release_lease(&lease_key),
+                Err(e) => {
+                    warn!("deliver queue item failed! dec={}, name={}, key={}, {}", dec_id, name, key, e);
+                    self.release_lease(&lease_key);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // 投递次数用完或者消息对象丢失时移入死信，返回None
+    async fn deliver(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        config: &QueueConfig,
+        key: &str,
+        text_id: &ObjectId,
+    ) -> BuckyResult<Option<QueueMessage>> {
+        let text = self.load_text(text_id).await?;
+        let mut item: QueueItem = Self::decode_text(&text, QUEUE_ITEM_TEXT_ID)?;
+
+        if item.attempts >= config.max_attempts {
+            info!(
+                "queue item exceed max attempts, now will move to dead! dec={}, name={}, key={}, attempts={}",
+                dec_id, name, key, item.attempts
+            );
+            self.move_to_dead(dec_id, name, key, text_id).await?;
+            return Ok(None);
+        }
+
+        let req = NONGetObjectOutputRequest::new_noc(item.object_id.clone(), None);
+        let object_raw = match self.0.non.get_object(req).await {
+            Ok(resp) => resp.object.object_raw,
+            Err(e) => {
+                warn!(
+                    "load queue message object failed, now will move to dead! dec={}, name={}, key={}, object={}, {}",
+                    dec_id, name, key, item.object_id, e
+                );
+                self.move_to_dead(dec_id, name, key, text_id).await?;
+                return Ok(None);
+            }
+        };
+
+        // 先记录投递次数再返回，避免重启后无限重投
+        item.attempts += 1;
+        let new_text_id = self.save_item(key, &item).await?;
+
+        let path = Self::items_path(dec_id, name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        if let Err(e) = op_env
+            .set_with_key(&path, key, &new_text_id, Some(text_id.to_owned()), false)
+            .await
+        {
+            // 可能已经被ack
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        if new_text_id != *text_id {
+            self.remove_text(text_id).await;
+        }
+
+        Ok(Some(QueueMessage {
+            key: key.to_owned(),
+            object_id: item.object_id,
+            object_raw,
+            source_zone: item.source_zone,
+            source_dec: item.source_dec,
+            push_time: item.push_time,
+            attempts: item.attempts,
+        }))
+    }
+
+    async fn move_to_dead(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+        key: &str,
+        text_id: &ObjectId,
+    ) -> BuckyResult<()> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = async {
+            op_env
+                .remove_with_key(&Self::items_path(dec_id, name), key, Some(text_id.to_owned()))
+                .await?;
+            op_env
+                .set_with_key(&Self::dead_path(dec_id, name), key, text_id, None, true)
+                .await
+        }
+        .await;
+
+        if let Err(e) = ret {
+            error!("move queue item to dead failed! dec={}, name={}, key={}, {}", dec_id, name, key, e);
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn ack(&self, dec_id: &ObjectId, name: &str, keys: &[String]) -> BuckyResult<u32> {
+        self.load_config(dec_id, name).await?;
+
+        let items_path = Self::items_path(dec_id, name);
+        let dead_path = Self::dead_path(dec_id, name);
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let mut removed = vec![];
+        for key in keys {
+            let ret = match op_env.remove_with_key(&items_path, key, None).await {
+                Ok(Some(v)) => Ok(Some(v)),
+                Ok(None) => op_env.remove_with_key(&dead_path, key, None).await,
+                Err(e) => Err(e),
+            };
+
+            match ret {
+                Ok(Some(text_id)) => removed.push((key, text_id)),
+                Ok(None) => {
+                    warn!("ack queue item but not found! dec={}, name={}, key={}", dec_id, name, key);
+                }
+                Err(e) if e.code() == BuckyErrorCode::NotFound => {}
+                Err(e) => {
+                    error!("ack queue item failed! dec={}, name={}, key={}, {}", dec_id, name, key, e);
+                    let _ = op_env.abort().await;
+                    return Err(e);
+                }
+            }
+        }
+        op_env.commit().await?;
+
+        for (key, text_id) in &removed {
+            self.release_lease(&Self::lease_key(dec_id, name, key));
+            self.remove_text(text_id).await;
+        }
+
+        debug!("ack queue items: dec={}, name={}, count={}", dec_id, name, removed.len());
+
+        Ok(removed.len() as u32)
+    }
+
+    pub async fn dead_letters(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<Vec<QueueMessage>> {
+        self.load_config(dec_id, name).await?;
+
+        let list = self.list_items(&Self::dead_path(dec_id, name)).await?;
+
+        let mut result = Vec::with_capacity(list.len());
+        for (key, text_id) in list {
+            let item: QueueItem = match self.load_text(&text_id).await.and_then(|text| Self::decode_text(&text, QUEUE_ITEM_TEXT_ID)) {
+                Ok(item) => item,
+                Err(e) => {
+                    warn!("load queue dead item failed! dec={}, name={}, key={}, {}", dec_id, name, key, e);
+                    continue;
+                }
+            };
+
+            // 死信的消息对象可能已经丢失
+            let req = NONGetObjectOutputRequest::new_noc(item.object_id.clone(), None);
+            let object_raw = match self.0.non.get_object(req).await {
+                Ok(resp) => resp.object.object_raw,
+                Err(_) => vec![],
+            };
+
+            result.push(QueueMessage {
+                key,
+                object_id: item.object_id,
+                object_raw,
+                source_zone: item.source_zone,
+                source_dec: item.source_dec,
+                push_time: item.push_time,
+                attempts: item.attempts,
+            });
+        }
+
+        Ok(result)
+    }
+
+    // 按key排序，也就是按推送的先后顺序
+    async fn list_items(&self, path: &str) -> BuckyResult<Vec<(String, ObjectId)>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list queue items failed! path={}, {}", path, e);
+                return Err(e);
+            }
+        };
+
+        let mut result: Vec<(String, ObjectId)> = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Map(v) => Some(v),
+                _ => None,
+            })
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(result)
+    }
+
+    async fn save_item(&self, key: &str, item: &QueueItem) -> BuckyResult<ObjectId> {
+        let value = serde_json::to_string(item).unwrap();
+        let text = Text::build(QUEUE_ITEM_TEXT_ID, key, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save queue item to noc failed! key={}, {}", key, e);
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn load_text(&self, text_id: &ObjectId) -> BuckyResult<Text> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        Text::clone_from_slice(&resp.object.object_raw)
+    }
+
+    fn decode_text<T>(text: &Text, id: &str) -> BuckyResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if text.id() != id {
+            let msg = format!("invalid queue text id: expect={}, got={}", id, text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!("invalid queue text value! id={}, header={}, {}", id, text.header(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!("remove old queue text from noc failed! text={}, {}", text_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_next_key() {
+        let keys = QueueKeyGenerator::new();
+
+        // 同一时间的推送依次加一，时钟回退时也不会重复
+        let now = bucky_time_now();
+        let list = vec![
+            keys.next(now),
+            keys.next(now),
+            keys.next(now - 1000),
+            keys.next(now + 1000),
+        ];
+        assert_eq!(list[0], format!("{:020}", now));
+        assert_eq!(list[1], format!("{:020}", now + 1));
+        assert_eq!(list[2], format!("{:020}", now + 2));
+        assert_eq!(list[3], format!("{:020}", now + 1000));
+
+        // 定长的key按字符串排序就是推送顺序
+        let mut sorted = list.clone();
+        sorted.sort();
+        assert_eq!(sorted, list);
+    }
+
+    #[test]
+    fn test_lease() {
+        let leases = QueueLeases::new();
+        let key = QueueManager::lease_key(&ObjectId::default(), "test", "00000000000000000001");
+
+        assert!(leases.try_lease(key.clone(), 100, 200));
+
+        // 租约到期之前不能被其它pull占用
+        assert!(!leases.try_lease(key.clone(), 150, 250));
+
+        // 到期之后重新投递
+        assert!(leases.try_lease(key.clone(), 200, 300));
+
+        // ack之后立即释放
+        leases.release(&key);
+        assert!(leases.try_lease(key, 210, 310));
+
+        // 超过上限时清理过期的租约
+        for i in 0..1100 {
+            leases.try_lease(format!("expired-{}", i), 100, 150);
+        }
+        assert!(leases.try_lease("new".to_owned(), 1000, 1100));
+        assert!(leases.list.lock().unwrap().len() <= 2);
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert!(QueueManager::check_name("events").is_ok());
+
+        let err = QueueManager::check_name("").unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+        let err = QueueManager::check_name("a/b").unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidParam);
+
+        let dec_id = ObjectId::default();
+        assert_eq!(
+            QueueManager::items_path(&dec_id, "events"),
+            format!("/queue/{}/events/items", dec_id)
+        );
+        assert_eq!(
+            QueueManager::dead_path(&dec_id, "events"),
+            format!("/queue/{}/events/dead", dec_id)
+        );
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let item = QueueItem {
+            object_id: ObjectId::default(),
+            source_zone: None,
+            source_dec: ObjectId::default(),
+            push_time: bucky_time_now(),
+            attempts: 2,
+        };
+        let value = serde_json::to_string(&item).unwrap();
+
+        let text = Text::build(QUEUE_ITEM_TEXT_ID, "key", &value)
+            .no_create_time()
+            .build();
+        let ret: QueueItem = QueueManager::decode_text(&text, QUEUE_ITEM_TEXT_ID).unwrap();
+        assert_eq!(ret.push_time, item.push_time);
+        assert_eq!(ret.attempts, 2);
+
+        let err = QueueManager::decode_text::<QueueItem>(&text, QUEUE_PUSH_TEXT_ID).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        let text = Text::build(QUEUE_ITEM_TEXT_ID, "key", "{}")
+            .no_create_time()
+            .build();
+        let err = QueueManager::decode_text::<QueueItem>(&text, QUEUE_ITEM_TEXT_ID).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+    }
+}
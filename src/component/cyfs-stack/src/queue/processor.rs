@@ -0,0 +1,25 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait QueueInputProcessor: Sync + Send + 'static {
+    async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse>;
+
+    async fn push_queue(&self, req: QueuePushInputRequest) -> BuckyResult<QueuePushInputResponse>;
+
+    async fn pull_queue(&self, req: QueuePullInputRequest) -> BuckyResult<QueuePullInputResponse>;
+
+    async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse>;
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse>;
+}
+
+pub(crate) type QueueInputProcessorRef = Arc<dyn QueueInputProcessor>;
@@ -0,0 +1,124 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct QueueInputTransformer {
+    processor: QueueOutputProcessorRef,
+}
+
+impl QueueInputTransformer {
+    pub fn new(processor: QueueOutputProcessorRef) -> QueueInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        let out_req = QueueCreateOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            config: req.config,
+        };
+
+        let out_resp = self.processor.create_queue(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn push_queue(&self, req: QueuePushInputRequest) -> BuckyResult<QueuePushInputResponse> {
+        let out_req = QueuePushOutputRequest {
+            common: Self::convert_common(req.common),
+            zone: req.zone,
+            dec_id: req.dec_id,
+            name: req.name,
+            object_id: req.object_id,
+            object_raw: req.object_raw,
+        };
+
+        let out_resp = self.processor.push_queue(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn pull_queue(&self, req: QueuePullInputRequest) -> BuckyResult<QueuePullInputResponse> {
+        let out_req = QueuePullOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            max_count: req.max_count,
+        };
+
+        let out_resp = self.processor.pull_queue(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        let out_req = QueueAckOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            keys: req.keys,
+        };
+
+        let out_resp = self.processor.ack_queue(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        let out_req = QueueGetDeadLettersOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+        };
+
+        let out_resp = self.processor.get_queue_dead_letters(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueInputProcessor for QueueInputTransformer {
+    async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        Self::create_queue(&self, req).await
+    }
+
+    async fn push_queue(&self, req: QueuePushInputRequest) -> BuckyResult<QueuePushInputResponse> {
+        Self::push_queue(&self, req).await
+    }
+
+    async fn pull_queue(&self, req: QueuePullInputRequest) -> BuckyResult<QueuePullInputResponse> {
+        Self::pull_queue(&self, req).await
+    }
+
+    async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        Self::ack_queue(&self, req).await
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        Self::get_queue_dead_letters(&self, req).await
+    }
+}
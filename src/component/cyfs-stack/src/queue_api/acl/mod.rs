@@ -0,0 +1,3 @@
+mod queue_acl;
+
+pub(crate) use queue_acl::*;
@@ -0,0 +1,77 @@
+use crate::queue::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct QueueAclInnerInputProcessor {
+    next: QueueInputProcessorRef,
+}
+
+impl QueueAclInnerInputProcessor {
+    pub(crate) fn new(next: QueueInputProcessorRef) -> QueueInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueInputProcessor for QueueAclInnerInputProcessor {
+    async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        self.check_local_zone_permit("queue.create_queue", &req.common.source)?;
+
+        self.next.create_queue(req).await
+    }
+
+    async fn push_queue(&self, req: QueuePushInputRequest) -> BuckyResult<QueuePushInputResponse> {
+        self.check_local_zone_permit("queue.push_queue", &req.common.source)?;
+
+        self.next.push_queue(req).await
+    }
+
+    async fn pull_queue(&self, req: QueuePullInputRequest) -> BuckyResult<QueuePullInputResponse> {
+        self.check_local_zone_permit("queue.pull_queue", &req.common.source)?;
+
+        self.next.pull_queue(req).await
+    }
+
+    async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        self.check_local_zone_permit("queue.ack_queue", &req.common.source)?;
+
+        self.next.ack_queue(req).await
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        self.check_local_zone_permit("queue.get_queue_dead_letters", &req.common.source)?;
+
+        self.next.get_queue_dead_letters(req).await
+    }
+}
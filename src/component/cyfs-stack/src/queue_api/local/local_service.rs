@@ -0,0 +1,113 @@
+use crate::queue::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalQueueService {
+    queue_manager: QueueManager,
+}
+
+impl LocalQueueService {
+    pub(crate) fn new(queue_manager: QueueManager) -> Self {
+        Self { queue_manager }
+    }
+
+    pub fn clone_processor(&self) -> QueueInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        let config = self
+            .queue_manager
+            .create(&req.common.source.dec, &req.name, req.config)
+            .await?;
+
+        Ok(QueueCreateInputResponse { config })
+    }
+
+    pub async fn push_queue(
+        &self,
+        req: QueuePushInputRequest,
+    ) -> BuckyResult<QueuePushInputResponse> {
+        let key = self
+            .queue_manager
+            .push(
+                &req.common.source,
+                req.zone.as_ref(),
+                &req.dec_id,
+                &req.name,
+                req.object_id,
+                req.object_raw,
+            )
+            .await?;
+
+        Ok(QueuePushInputResponse { key })
+    }
+
+    pub async fn pull_queue(
+        &self,
+        req: QueuePullInputRequest,
+    ) -> BuckyResult<QueuePullInputResponse> {
+        let list = self
+            .queue_manager
+            .pull(&req.common.source.dec, &req.name, req.max_count)
+            .await?;
+
+        Ok(QueuePullInputResponse { list })
+    }
+
+    pub async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        let acked = self
+            .queue_manager
+            .ack(&req.common.source.dec, &req.name, &req.keys)
+            .await?;
+
+        Ok(QueueAckInputResponse { acked })
+    }
+
+    pub async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        let list = self
+            .queue_manager
+            .dead_letters(&req.common.source.dec, &req.name)
+            .await?;
+
+        Ok(QueueGetDeadLettersInputResponse { list })
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueInputProcessor for LocalQueueService {
+    async fn create_queue(
+        &self,
+        req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        Self::create_queue(self, req).await
+    }
+
+    async fn push_queue(&self, req: QueuePushInputRequest) -> BuckyResult<QueuePushInputResponse> {
+        Self::push_queue(self, req).await
+    }
+
+    async fn pull_queue(&self, req: QueuePullInputRequest) -> BuckyResult<QueuePullInputResponse> {
+        Self::pull_queue(self, req).await
+    }
+
+    async fn ack_queue(&self, req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        Self::ack_queue(self, req).await
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        Self::get_queue_dead_letters(self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod queue_service_router;
+
+pub(crate) use queue_service_router::*;
@@ -0,0 +1,141 @@
+use super::super::acl::QueueAclInnerInputProcessor;
+use crate::forward::ForwardProcessorManager;
+use crate::queue::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct QueueServiceRouter {
+    processor: QueueInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl QueueServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: QueueInputProcessorRef,
+    ) -> QueueInputProcessorRef {
+        // 限定同zone
+        let processor = QueueAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<QueueInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = QueueRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = QueueInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<QueueInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("queue target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueInputProcessor for QueueServiceRouter {
+    async fn create_queue(
+        &self,
+        mut req: QueueCreateInputRequest,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.create_queue(req).await
+    }
+
+    async fn push_queue(
+        &self,
+        mut req: QueuePushInputRequest,
+    ) -> BuckyResult<QueuePushInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.push_queue(req).await
+    }
+
+    async fn pull_queue(
+        &self,
+        mut req: QueuePullInputRequest,
+    ) -> BuckyResult<QueuePullInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.pull_queue(req).await
+    }
+
+    async fn ack_queue(&self, mut req: QueueAckInputRequest) -> BuckyResult<QueueAckInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.ack_queue(req).await
+    }
+
+    async fn get_queue_dead_letters(
+        &self,
+        mut req: QueueGetDeadLettersInputRequest,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_queue_dead_letters(req).await
+    }
+}
@@ -0,0 +1,7 @@
+mod queue_handler;
+mod queue_listener;
+mod queue_service;
+
+pub(crate) use queue_handler::*;
+pub(crate) use queue_listener::*;
+pub(crate) use queue_service::*;
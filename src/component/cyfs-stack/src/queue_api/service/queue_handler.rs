@@ -0,0 +1,222 @@
+use crate::non::NONInputHttpRequest;
+use crate::queue::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct QueueRequestHandler {
+    processor: QueueInputProcessorRef,
+}
+
+impl QueueRequestHandler {
+    pub fn new(processor: QueueInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // create_queue
+    pub async fn process_create_queue_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_create_queue_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_create_queue_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<QueueCreateInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("create queue failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = QueueCreateOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = QueueCreateInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            config: out_req.config,
+        };
+        self.processor.create_queue(in_req).await
+    }
+
+    // push_queue
+    pub async fn process_push_queue_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_push_queue_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_push_queue_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<QueuePushInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("push queue failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = QueuePushOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = QueuePushInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            zone: out_req.zone,
+            dec_id: out_req.dec_id,
+            name: out_req.name,
+            object_id: out_req.object_id,
+            object_raw: out_req.object_raw,
+        };
+        self.processor.push_queue(in_req).await
+    }
+
+    // pull_queue
+    pub async fn process_pull_queue_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_pull_queue_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_pull_queue_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<QueuePullInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("pull queue failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = QueuePullOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = QueuePullInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            max_count: out_req.max_count,
+        };
+        self.processor.pull_queue(in_req).await
+    }
+
+    // ack_queue
+    pub async fn process_ack_queue_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_ack_queue_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_ack_queue_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<QueueAckInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("ack queue failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = QueueAckOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = QueueAckInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            keys: out_req.keys,
+        };
+        self.processor.ack_queue(in_req).await
+    }
+
+    // get_queue_dead_letters
+    pub async fn process_get_queue_dead_letters_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_queue_dead_letters_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_queue_dead_letters_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<QueueGetDeadLettersInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!(
+                "get queue dead letters failed, read body bytes error! {}",
+                e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = QueueGetDeadLettersOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = QueueGetDeadLettersInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+        };
+        self.processor.get_queue_dead_letters(in_req).await
+    }
+}
@@ -0,0 +1,146 @@
+use super::queue_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum QueueRequestType {
+    CreateQueue,
+    PushQueue,
+    PullQueue,
+    AckQueue,
+    GetQueueDeadLetters,
+}
+
+pub(crate) struct QueueRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: QueueRequestType,
+    handler: QueueRequestHandler,
+}
+
+impl QueueRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: QueueRequestType,
+        handler: QueueRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            QueueRequestType::CreateQueue => self.handler.process_create_queue_request(req).await,
+            QueueRequestType::PushQueue => self.handler.process_push_queue_request(req).await,
+            QueueRequestType::PullQueue => self.handler.process_pull_queue_request(req).await,
+            QueueRequestType::AckQueue => self.handler.process_ack_queue_request(req).await,
+            QueueRequestType::GetQueueDeadLetters => {
+                self.handler
+                    .process_get_queue_dead_letters_request(req)
+                    .await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &QueueRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // queue
+        server.at("/queue/create").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::CreateQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/create/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::CreateQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/push").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::PushQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/push/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::PushQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/pull").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::PullQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/pull/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::PullQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/ack").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::AckQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/ack/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::AckQueue,
+            handler.clone(),
+        ));
+
+        server.at("/queue/dead_letters").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::GetQueueDeadLetters,
+            handler.clone(),
+        ));
+
+        server.at("/queue/dead_letters/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            QueueRequestType::GetQueueDeadLetters,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for QueueRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
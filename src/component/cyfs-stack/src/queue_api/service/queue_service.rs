@@ -0,0 +1,27 @@
+use super::super::local::LocalQueueService;
+use super::super::router::QueueServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::queue::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct QueueService {
+    router: QueueInputProcessorRef,
+}
+
+impl QueueService {
+    pub(crate) fn new(
+        queue_manager: QueueManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalQueueService::new(queue_manager);
+        let router =
+            QueueServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> QueueInputProcessorRef {
+        self.router.clone()
+    }
+}
@@ -20,6 +20,9 @@ pub(crate) struct DeviceInfoManagerImpl {
     // 内存缓存
     list: RwLock<HashMap<DeviceId, Device>>,
 
+    // 缓存里的device最近一次加入或者刷新的时间，用来检测过期的desc
+    refresh_times: RwLock<HashMap<DeviceId, u64>>,
+
     // used for search from meta chain
     obj_searcher: ObjectSearcherRef,
 }
@@ -39,6 +42,7 @@ impl DeviceInfoManagerImpl {
             obj_searcher,
             local_device: RwLock::new(local_device),
             list: RwLock::new(HashMap::new()),
+            refresh_times: RwLock::new(HashMap::new()),
         }
     }
 
@@ -72,6 +76,30 @@ impl DeviceInfoManagerImpl {
     async fn flush_device(&self, device_id: &DeviceId) {
         let mut list = self.list.write().unwrap();
         list.remove(device_id);
+        self.refresh_times.write().unwrap().remove(device_id);
+    }
+
+    pub fn mark_refreshed(&self, device_id: &DeviceId) {
+        self.refresh_times
+            .write()
+            .unwrap()
+            .insert(device_id.to_owned(), bucky_time_now());
+    }
+
+    // 超过ttl没有刷新过的device，按刷新时间从旧到新排序
+    pub fn stale_devices(&self, ttl: u64) -> Vec<DeviceId> {
+        let now = bucky_time_now();
+        let cache = self.list.read().unwrap();
+        let times = self.refresh_times.read().unwrap();
+        let mut list: Vec<(DeviceId, u64)> = cache
+            .keys()
+            .filter(|id| **id != self.local_device_id)
+            .map(|id| (id.to_owned(), times.get(id).cloned().unwrap_or(0)))
+            .filter(|(_, time)| now >= *time + ttl)
+            .collect();
+        list.sort_by_key(|(_, time)| *time);
+
+        list.into_iter().map(|(id, _)| id).collect()
     }
 
     // 本地和网络查找
@@ -237,11 +265,13 @@ impl DeviceInfoManagerImpl {
         let mut changed = false;
         {
             let mut cache = self.list.write().unwrap();
+            let mut times = self.refresh_times.write().unwrap();
             match cache.entry(device_id.clone()) {
                 Entry::Vacant(v) => {
                     info!("new device in cache: {}", device_id);
 
                     v.insert(device.clone());
+                    times.insert(device_id.clone(), bucky_time_now());
                     changed = true;
                 }
                 Entry::Occupied(mut o) => {
@@ -253,6 +283,7 @@ impl DeviceInfoManagerImpl {
                             device_id, old_time, new_time
                         );
                         o.insert(device.clone());
+                        times.insert(device_id.clone(), bucky_time_now());
                         changed = true;
                     }
                 }
@@ -292,6 +323,7 @@ impl DeviceInfoManagerImpl {
             last_access_rpath: None,
             context: None,
             access_string: Some(AccessString::full_except_write().value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&info).await {
@@ -439,6 +471,14 @@ impl DeviceInfoManager {
     pub async fn search_device(&self, device_id: &DeviceId) -> BuckyResult<Device> {
         self.0.search_device(device_id).await
     }
+
+    pub fn mark_refreshed(&self, device_id: &DeviceId) {
+        self.0.mark_refreshed(device_id)
+    }
+
+    pub fn stale_devices(&self, ttl: u64) -> Vec<DeviceId> {
+        self.0.stale_devices(ttl)
+    }
 }
 
 #[async_trait]
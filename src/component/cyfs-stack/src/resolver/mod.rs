@@ -1,6 +1,7 @@
 mod device_manager;
 mod obj_searcher;
 mod ood_resolver;
+mod ood_cache;
 
 pub(crate) use device_manager::*;
 pub(crate) use obj_searcher::*;
@@ -249,6 +249,8 @@ impl ZoneSearcher {
                 target: Some(zone_info.zone_device_ood_id.object_id().clone()),
 
                 flags: 0,
+                deadline: None,
+                request_id: None,
             },
             object_id: object_id.to_owned(),
             inner_path: None,
@@ -273,6 +275,7 @@ impl ZoneSearcher {
             context: None,
             last_access_rpath: None,
             access_string: Some(AccessString::full_except_write().value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&info).await {
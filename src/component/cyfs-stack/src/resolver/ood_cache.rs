@@ -0,0 +1,207 @@
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::{ZoneId, ZoneObj};
+use cyfs_lib::UtilGetOODResolverStatsOutputResponse;
+use cyfs_util::EventListenerSyncRoutine;
+
+use lru_time_cache::LruCache;
+use std::sync::{Arc, Mutex};
+
+// 解析结果的缓存时间，owner的ood_list变化时通过zone改变事件主动失效
+const OOD_RESOLVER_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 10);
+const OOD_RESOLVER_CACHE_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct OodResolverCacheKey {
+    object_id: ObjectId,
+    owner_id: Option<ObjectId>,
+}
+
+#[derive(Clone)]
+struct OodResolverCacheItem {
+    device_list: Vec<DeviceId>,
+
+    // 解析过程中经过的对象和owner，其中任何一个改变都需要让缓存失效
+    chain: Vec<ObjectId>,
+}
+
+struct OodResolverCacheInner {
+    list: LruCache<OodResolverCacheKey, OodResolverCacheItem>,
+
+    hits: u64,
+    misses: u64,
+    invalidated: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct OodResolverCache(Arc<Mutex<OodResolverCacheInner>>);
+
+impl OodResolverCache {
+    pub fn new() -> Self {
+        Self::new_with_ttl(OOD_RESOLVER_CACHE_TTL, OOD_RESOLVER_CACHE_CAPACITY)
+    }
+
+    fn new_with_ttl(ttl: std::time::Duration, capacity: usize) -> Self {
+        let inner = OodResolverCacheInner {
+            list: LruCache::with_expiry_duration_and_capacity(ttl, capacity),
+            hits: 0,
+            misses: 0,
+            invalidated: 0,
+        };
+
+        Self(Arc::new(Mutex::new(inner)))
+    }
+
+    pub fn get(&self, object_id: &ObjectId, owner_id: &Option<ObjectId>) -> Option<Vec<DeviceId>> {
+        let key = OodResolverCacheKey {
+            object_id: object_id.to_owned(),
+            owner_id: owner_id.to_owned(),
+        };
+
+        let mut inner = self.0.lock().unwrap();
+
+        // 用peek避免访问时刷新过期时间，iter会先清除已经过期的条目
+        let _ = inner.list.iter();
+        match inner.list.peek(&key).map(|item| item.device_list.clone()) {
+            Some(list) => {
+                inner.hits += 1;
+                Some(list)
+            }
+            None => {
+                inner.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn cache(
+        &self,
+        object_id: &ObjectId,
+        owner_id: &Option<ObjectId>,
+        device_list: Vec<DeviceId>,
+        mut chain: Vec<ObjectId>,
+    ) {
+        let key = OodResolverCacheKey {
+            object_id: object_id.to_owned(),
+            owner_id: owner_id.to_owned(),
+        };
+
+        chain.push(object_id.to_owned());
+        if let Some(owner_id) = owner_id {
+            chain.push(owner_id.to_owned());
+        }
+
+        let item = OodResolverCacheItem { device_list, chain };
+        self.0.lock().unwrap().list.insert(key, item);
+    }
+
+    // 移除所有解析链上包含id的缓存
+    pub fn invalidate(&self, id: &ObjectId) -> usize {
+        let mut inner = self.0.lock().unwrap();
+
+        let keys: Vec<_> = inner
+            .list
+            .peek_iter()
+            .filter(|(_, item)| item.chain.iter().any(|v| v == id))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &keys {
+            inner.list.remove(key);
+        }
+        inner.invalidated += keys.len() as u64;
+
+        if !keys.is_empty() {
+            info!("invalidate ood resolver cache: id={}, count={}", id, keys.len());
+        }
+
+        keys.len()
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let count = inner.list.len();
+        inner.list.clear();
+        inner.invalidated += count as u64;
+
+        info!("clear ood resolver cache: count={}", count);
+    }
+
+    pub fn stats(&self) -> UtilGetOODResolverStatsOutputResponse {
+        let mut inner = self.0.lock().unwrap();
+        let _ = inner.list.iter();
+
+        UtilGetOODResolverStatsOutputResponse {
+            entries: inner.list.len(),
+            hits: inner.hits,
+            misses: inner.misses,
+            invalidated: inner.invalidated,
+        }
+    }
+
+    // zone改变说明owner对象或者其ood_list有变化，相关的缓存需要失效
+    pub fn bind_zone_manager(&self, zone_manager: &ZoneManagerRef) {
+        let notify = OodResolverZoneChangedNotify {
+            cache: self.clone(),
+            zone_manager: zone_manager.clone(),
+        };
+
+        zone_manager.zone_changed_event().on(Box::new(notify));
+    }
+}
+
+struct OodResolverZoneChangedNotify {
+    cache: OodResolverCache,
+    zone_manager: ZoneManagerRef,
+}
+
+impl EventListenerSyncRoutine<ZoneId, ()> for OodResolverZoneChangedNotify {
+    fn call(&self, param: &ZoneId) -> BuckyResult<()> {
+        match self.zone_manager.query(param) {
+            Some(zone) => {
+                self.cache.invalidate(zone.owner());
+                for device_id in zone.ood_list() {
+                    self.cache.invalidate(device_id.object_id());
+                }
+            }
+            None => {
+                // 找不到对应的zone，无法确定影响范围，直接清空
+                self.cache.clear();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_ood_resolver_cache() {
+        let cache = OodResolverCache::new_with_ttl(std::time::Duration::from_secs(60), 16);
+
+        let device_id =
+            DeviceId::from_str("5aSixgPXvhR4puWzFCHqvUXrjFWjxbq4y3thJVgZg6ty").unwrap();
+        let object_id = ObjectId::default();
+        let owner_id = device_id.object_id().to_owned();
+
+        assert!(cache.get(&object_id, &None).is_none());
+        cache.cache(&object_id, &None, vec![device_id.clone()], vec![owner_id.clone()]);
+        assert_eq!(cache.get(&object_id, &None).unwrap(), vec![device_id.clone()]);
+
+        // 指定不同owner的解析结果单独缓存
+        assert!(cache.get(&object_id, &Some(owner_id.clone())).is_none());
+
+        assert_eq!(cache.invalidate(&owner_id), 1);
+        assert!(cache.get(&object_id, &None).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.invalidated, 1);
+    }
+}
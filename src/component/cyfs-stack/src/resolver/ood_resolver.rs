@@ -1,6 +1,9 @@
 use super::obj_searcher::*;
+use super::ood_cache::OodResolverCache;
+use crate::zone::ZoneManagerRef;
 use cyfs_base::*;
 use cyfs_bdt::DeviceCache;
+use cyfs_lib::UtilGetOODResolverStatsOutputResponse;
 
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -13,6 +16,7 @@ pub(crate) struct OodResolver {
     device_id: DeviceId,
     device_cache: Arc<Box<dyn DeviceCache>>,
     searcher: ObjectSearcherRef,
+    cache: OodResolverCache,
 }
 
 // 依赖下面几个核心要素
@@ -21,14 +25,27 @@ pub(crate) struct OodResolver {
 // 目前认为People也没有owner
 // People,SimpleGroup 对象存在ood_list
 impl OodResolver {
-    pub(crate) fn new(local_device_id: DeviceId, device_cache: Box<dyn DeviceCache>, searcher: ObjectSearcherRef) -> Self {
+    pub(crate) fn new(
+        local_device_id: DeviceId,
+        device_cache: Box<dyn DeviceCache>,
+        searcher: ObjectSearcherRef,
+        zone_manager: &ZoneManagerRef,
+    ) -> Self {
+        let cache = OodResolverCache::new();
+        cache.bind_zone_manager(zone_manager);
+
         Self {
             device_id: local_device_id,
             device_cache: Arc::new(device_cache),
             searcher,
+            cache,
         }
     }
 
+    pub fn cache_stats(&self) -> UtilGetOODResolverStatsOutputResponse {
+        self.cache.stats()
+    }
+
     pub fn device_id(&self) -> &DeviceId {
         &self.device_id
     }
@@ -38,6 +55,11 @@ impl OodResolver {
         object_id: &ObjectId,
         owner_id: Option<ObjectId>,
     ) -> BuckyResult<Vec<DeviceId>> {
+        if let Some(list) = self.cache.get(object_id, &owner_id) {
+            debug!("resolve ood from cache: obj={}, list={:?}", object_id, list);
+            return Ok(list);
+        }
+
         let mut object = None;
 
         // 首先本地查询此对象
@@ -59,13 +81,32 @@ impl OodResolver {
             }
         };
 
-        if object.is_some() {
-            self.get_ood_by_object(object_id.clone(), owner_id, object.unwrap())
-                .await
+        let mut chain = vec![];
+        let ret = if object.is_some() {
+            Self::get_ood_by_object_impl(
+                self.searcher.clone(),
+                object_id.clone(),
+                owner_id.clone(),
+                object.unwrap(),
+                &mut chain,
+            )
+            .await
         } else {
-            self.get_ood_by_object_with_owner(object_id.clone(), owner_id)
-                .await
+            Self::get_ood_by_object_with_owner_impl(
+                self.searcher.clone(),
+                object_id.clone(),
+                owner_id.clone(),
+                &mut chain,
+            )
+            .await
+        };
+
+        // 只缓存成功的结果，失败的下次重新解析
+        if let Ok(list) = &ret {
+            self.cache.cache(object_id, &owner_id, list.clone(), chain);
         }
+
+        ret
     }
 
     pub async fn get_ood_by_object(
@@ -74,7 +115,25 @@ impl OodResolver {
         owner_id: Option<ObjectId>,
         object: Arc<AnyNamedObject>,
     ) -> BuckyResult<Vec<DeviceId>> {
-        Self::get_ood_by_object_impl(self.searcher.clone(), object_id, owner_id, object).await
+        if let Some(list) = self.cache.get(&object_id, &owner_id) {
+            return Ok(list);
+        }
+
+        let mut chain = vec![];
+        let ret = Self::get_ood_by_object_impl(
+            self.searcher.clone(),
+            object_id.clone(),
+            owner_id.clone(),
+            object,
+            &mut chain,
+        )
+        .await;
+
+        if let Ok(list) = &ret {
+            self.cache.cache(&object_id, &owner_id, list.clone(), chain);
+        }
+
+        ret
     }
 
     pub async fn ensure_device_list(&self, list: &[DeviceId]) -> BuckyResult<()> {
@@ -85,14 +144,6 @@ impl OodResolver {
         Ok(())
     }
 
-    async fn get_ood_by_object_with_owner(
-        &self,
-        object_id: ObjectId,
-        owner_id: Option<ObjectId>,
-    ) -> BuckyResult<Vec<DeviceId>> {
-        Self::get_ood_by_object_with_owner_impl(self.searcher.clone(), object_id, owner_id).await
-    }
-
     fn append_device_id(device_list: &mut Vec<DeviceId>, device_id: DeviceId) {
         if !device_list.iter().any(|id| device_id == *id) {
             device_list.push(device_id);
@@ -109,6 +160,7 @@ impl OodResolver {
         mut object_id: ObjectId,
         mut owner_id: Option<ObjectId>,
         mut object: Arc<AnyNamedObject>,
+        chain: &mut Vec<ObjectId>,
     ) -> BuckyResult<Vec<DeviceId>> {
         let mut device_list: Vec<DeviceId> = Vec::new();
         let ret = loop {
@@ -177,6 +229,7 @@ impl OodResolver {
                     object_id, cur_owner_id
                 );
 
+                chain.push(cur_owner_id.clone());
                 match searcher.search(None, &cur_owner_id).await {
                     Ok(ret) => {
                         // 递归调用会出很多问题，这里改用循环替代
@@ -233,6 +286,7 @@ impl OodResolver {
         searcher: ObjectSearcherRef,
         mut object_id: ObjectId,
         mut owner_id: Option<ObjectId>,
+        chain: &mut Vec<ObjectId>,
     ) -> BuckyResult<Vec<DeviceId>> {
         let mut object: Option<Arc<AnyNamedObject>> = None;
 
@@ -299,6 +353,7 @@ impl OodResolver {
                         object_id, cur_owner_id
                     );
 
+                    chain.push(cur_owner_id.clone());
                     match searcher.search(None, &cur_owner_id).await {
                         Ok(ret) => {
                             object_id = cur_owner_id;
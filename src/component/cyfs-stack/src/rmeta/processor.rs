@@ -67,6 +67,38 @@ pub trait GlobalStateMetaInputProcessor: Sync + Send + 'static {
         &self,
         req: GlobalStateMetaClearPathConfigInputRequest,
     ) -> BuckyResult<GlobalStateMetaClearPathConfigInputResponse>;
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse>;
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse>;
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse>;
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse>;
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse>;
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse>;
 }
 
 pub type GlobalStateMetaInputProcessorRef = Arc<Box<dyn GlobalStateMetaInputProcessor>>;
@@ -178,6 +178,79 @@ impl GlobalStateMetaOutputProcessor for GlobalStateMetaOutputTransformer {
 
         self.processor.clear_path_config(in_req).await
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressOutputResponse> {
+        let in_req = GlobalStateMetaAddEgressInputRequest {
+            common: self.convert_common(req.common),
+            item: req.item,
+        };
+
+        self.processor.add_egress(in_req).await
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressOutputResponse> {
+        let in_req = GlobalStateMetaRemoveEgressInputRequest {
+            common: self.convert_common(req.common),
+            item: req.item,
+        };
+
+        self.processor.remove_egress(in_req).await
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressOutputResponse> {
+        let in_req = GlobalStateMetaClearEgressInputRequest {
+            common: self.convert_common(req.common),
+        };
+
+        self.processor.clear_egress(in_req).await
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessOutputResponse> {
+        let in_req = GlobalStateMetaListPendingAccessInputRequest {
+            common: self.convert_common(req.common),
+            page: req.page,
+        };
+
+        self.processor.list_pending_access(in_req).await
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessOutputResponse> {
+        let in_req = GlobalStateMetaApprovePendingAccessInputRequest {
+            common: self.convert_common(req.common),
+            id: req.id,
+        };
+
+        self.processor.approve_pending_access(in_req).await
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessOutputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessOutputResponse> {
+        let in_req = GlobalStateMetaRejectPendingAccessInputRequest {
+            common: self.convert_common(req.common),
+            id: req.id,
+        };
+
+        self.processor.reject_pending_access(in_req).await
+    }
 }
 
 ///////////////////////////////////////////////////
@@ -347,4 +420,77 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaInputTransformer {
 
         self.processor.clear_path_config(in_req).await
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse> {
+        let in_req = GlobalStateMetaAddEgressOutputRequest {
+            common: self.convert_common(req.common),
+            item: req.item,
+        };
+
+        self.processor.add_egress(in_req).await
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse> {
+        let in_req = GlobalStateMetaRemoveEgressOutputRequest {
+            common: self.convert_common(req.common),
+            item: req.item,
+        };
+
+        self.processor.remove_egress(in_req).await
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse> {
+        let in_req = GlobalStateMetaClearEgressOutputRequest {
+            common: self.convert_common(req.common),
+        };
+
+        self.processor.clear_egress(in_req).await
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse> {
+        let in_req = GlobalStateMetaListPendingAccessOutputRequest {
+            common: self.convert_common(req.common),
+            page: req.page,
+        };
+
+        self.processor.list_pending_access(in_req).await
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse> {
+        let in_req = GlobalStateMetaApprovePendingAccessOutputRequest {
+            common: self.convert_common(req.common),
+            id: req.id,
+        };
+
+        self.processor.approve_pending_access(in_req).await
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse> {
+        let in_req = GlobalStateMetaRejectPendingAccessOutputRequest {
+            common: self.convert_common(req.common),
+            id: req.id,
+        };
+
+        self.processor.reject_pending_access(in_req).await
+    }
 }
@@ -1,3 +1,4 @@
+use crate::admin::AdminConfirmManager;
 use crate::rmeta::*;
 use cyfs_base::*;
 use cyfs_lib::*;
@@ -7,15 +8,39 @@ use std::sync::Arc;
 // 限定在同zone内操作
 pub(crate) struct GlobalStateMetaAclInnerInputProcessor {
     next: GlobalStateMetaInputProcessorRef,
+
+    // root_state的权限修改需要owner确认，local_cache的权限只影响本设备，不需要确认
+    admin_confirm: Option<AdminConfirmManager>,
 }
 
 impl GlobalStateMetaAclInnerInputProcessor {
-    pub(crate) fn new(next: GlobalStateMetaInputProcessorRef) -> GlobalStateMetaInputProcessorRef {
-        let ret = Self { next };
+    pub(crate) fn new(
+        next: GlobalStateMetaInputProcessorRef,
+        admin_confirm: Option<AdminConfirmManager>,
+    ) -> GlobalStateMetaInputProcessorRef {
+        let ret = Self {
+            next,
+            admin_confirm,
+        };
 
         Arc::new(Box::new(ret))
     }
 
+    async fn check_admin_confirm(&self, common: &MetaInputRequestCommon) -> BuckyResult<()> {
+        if let Some(admin_confirm) = &self.admin_confirm {
+            let dec_id = common.target_dec_id.as_ref().unwrap_or(&common.source.dec);
+            admin_confirm
+                .check(
+                    &common.source,
+                    AdminConfirmAction::AclChange,
+                    &dec_id.to_string(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
     fn check_access(&self, service: &str, common: &MetaInputRequestCommon) -> BuckyResult<()> {
         common.source.check_current_zone(service)?;
 
@@ -34,6 +59,23 @@ impl GlobalStateMetaAclInnerInputProcessor {
 
         Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
     }
+
+    // egress list can only been modified by the system dec in current zone, the dec itself can't change it
+    fn check_admin_access(&self, service: &str, common: &MetaInputRequestCommon) -> BuckyResult<()> {
+        common.source.check_current_zone(service)?;
+
+        if common.source.is_system_dec() {
+            return Ok(());
+        }
+
+        let msg = format!(
+            "global state meta {} only allowed for system dec! source={}, target={:?}",
+            service, common.source, common.target_dec_id,
+        );
+        error!("{}", msg);
+
+        Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
+    }
 }
 
 #[async_trait::async_trait]
@@ -43,6 +85,7 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaAclInnerInputProcessor {
         req: GlobalStateMetaAddAccessInputRequest,
     ) -> BuckyResult<GlobalStateMetaAddAccessInputResponse> {
         self.check_access("global_state.meta.add_access", &req.common)?;
+        self.check_admin_confirm(&req.common).await?;
 
         self.next.add_access(req).await
     }
@@ -52,6 +95,7 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaAclInnerInputProcessor {
         req: GlobalStateMetaRemoveAccessInputRequest,
     ) -> BuckyResult<GlobalStateMetaRemoveAccessInputResponse> {
         self.check_access("global_state.meta.remove_access", &req.common)?;
+        self.check_admin_confirm(&req.common).await?;
 
         self.next.remove_access(req).await
     }
@@ -61,6 +105,7 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaAclInnerInputProcessor {
         req: GlobalStateMetaClearAccessInputRequest,
     ) -> BuckyResult<GlobalStateMetaClearAccessInputResponse> {
         self.check_access("global_state.meta.clear_access", &req.common)?;
+        self.check_admin_confirm(&req.common).await?;
 
         self.next.clear_access(req).await
     }
@@ -147,4 +192,60 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaAclInnerInputProcessor {
 
         self.next.clear_path_config(req).await
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse> {
+        self.check_admin_access("global_state.meta.add_egress", &req.common)?;
+
+        self.next.add_egress(req).await
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse> {
+        self.check_admin_access("global_state.meta.remove_egress", &req.common)?;
+
+        self.next.remove_egress(req).await
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse> {
+        self.check_admin_access("global_state.meta.clear_egress", &req.common)?;
+
+        self.next.clear_egress(req).await
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse> {
+        self.check_admin_access("global_state.meta.list_pending_access", &req.common)?;
+
+        self.next.list_pending_access(req).await
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse> {
+        self.check_admin_access("global_state.meta.approve_pending_access", &req.common)?;
+
+        self.next.approve_pending_access(req).await
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse> {
+        self.check_admin_access("global_state.meta.reject_pending_access", &req.common)?;
+
+        self.next.reject_pending_access(req).await
+    }
 }
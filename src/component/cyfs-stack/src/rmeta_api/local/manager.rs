@@ -349,6 +349,132 @@ impl GlobalStateMetaInputProcessor for GlobalStatePathMetaManager {
         let resp = GlobalStateMetaClearPathConfigInputResponse { count };
         Ok(resp)
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse> {
+        let meta = self
+            .get_global_state_meta(Self::get_dec_id(&req.common), true)
+            .await?;
+        let updated = meta.add_egress(req.item).await?;
+
+        let resp = GlobalStateMetaAddEgressInputResponse { updated };
+        Ok(resp)
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse> {
+        let ret = self
+            .get_option_global_state_meta(Self::get_dec_id(&req.common), false)
+            .await?;
+        if ret.is_none() {
+            let resp = GlobalStateMetaRemoveEgressInputResponse { item: None };
+
+            return Ok(resp);
+        }
+
+        let meta = ret.unwrap();
+        let item = meta.remove_egress(req.item).await?;
+
+        let resp = GlobalStateMetaRemoveEgressInputResponse { item };
+
+        Ok(resp)
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse> {
+        let ret = self
+            .get_option_global_state_meta(Self::get_dec_id(&req.common), false)
+            .await?;
+        if ret.is_none() {
+            let resp = GlobalStateMetaClearEgressInputResponse { count: 0 };
+            return Ok(resp);
+        }
+
+        let meta = ret.unwrap();
+        let count = meta.clear_egress().await? as u32;
+
+        let resp = GlobalStateMetaClearEgressInputResponse { count };
+        Ok(resp)
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse> {
+        let ret = self
+            .get_option_global_state_meta(Self::get_dec_id(&req.common), false)
+            .await?;
+        let list = match ret {
+            Some(meta) => meta.list_pending_access().await,
+            None => vec![],
+        };
+
+        // 待审批列表在内存里，直接按偏移量分页
+        let (list, page) = match req.page {
+            Some(page) => {
+                let offset = page.offset()?;
+                let page_size = page.page_size();
+                let total = list.len() as u64;
+                let list: Vec<_> = list
+                    .into_iter()
+                    .skip(offset as usize)
+                    .take(page_size as usize)
+                    .collect();
+                let info = PageInfo::from_offset(offset, list.len(), page_size, Some(total));
+                (list, info)
+            }
+            None => (list, PageInfo::default()),
+        };
+
+        let resp = GlobalStateMetaListPendingAccessInputResponse { list, page };
+        Ok(resp)
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse> {
+        let ret = self
+            .get_option_global_state_meta(Self::get_dec_id(&req.common), false)
+            .await?;
+        if ret.is_none() {
+            let resp = GlobalStateMetaApprovePendingAccessInputResponse { item: None };
+            return Ok(resp);
+        }
+
+        let meta = ret.unwrap();
+        let item = meta.approve_pending_access(req.id).await?;
+
+        let resp = GlobalStateMetaApprovePendingAccessInputResponse { item };
+        Ok(resp)
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse> {
+        let ret = self
+            .get_option_global_state_meta(Self::get_dec_id(&req.common), false)
+            .await?;
+        if ret.is_none() {
+            let resp = GlobalStateMetaRejectPendingAccessInputResponse { item: None };
+            return Ok(resp);
+        }
+
+        let meta = ret.unwrap();
+        let item = meta.reject_pending_access(req.id).await?;
+
+        let resp = GlobalStateMetaRejectPendingAccessInputResponse { item };
+        Ok(resp)
+    }
 }
 
 pub type GlobalStatePathMetaManagerRef = Arc<GlobalStatePathMetaManager>;
@@ -14,6 +14,12 @@ pub(crate) struct ObjectMeta {
 
     // Object referer's depth, default is 1
     pub depth: Option<u8>,
+
+    // Object history versions to keep in noc
+    pub versions: Option<u32>,
+
+    // Encrypt object blob at rest in noc
+    pub encrypt: Option<bool>,
 }
 
 impl ObjectMeta {
@@ -29,6 +35,8 @@ impl ObjectMeta {
             selector,
             access: item.access,
             depth: item.depth,
+            versions: item.versions,
+            encrypt: item.encrypt,
         })
     }
 
@@ -38,6 +46,8 @@ impl ObjectMeta {
             selector,
             access: item.access,
             depth: item.depth,
+            versions: item.versions,
+            encrypt: item.encrypt,
         }
     }
 
@@ -50,10 +60,12 @@ impl std::fmt::Display for ObjectMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "({}, {}, {:?})",
+            "({}, {}, {:?}, {:?}, {:?})",
             self.selector.exp(),
             self.access,
-            self.depth
+            self.depth,
+            self.versions,
+            self.encrypt
         )
     }
 }
@@ -225,3 +237,58 @@ impl GlobalStateObjectMetaList {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::*;
+
+    fn new_item(obj_type: CoreObjectType, versions: Option<u32>) -> GlobalStateObjectMetaItem {
+        GlobalStateObjectMetaItem {
+            selector: format!("obj_type == {}", obj_type as u16),
+            access: GlobalStatePathGroupAccess::Default(AccessString::default().value()),
+            depth: None,
+            versions,
+            encrypt: None,
+        }
+    }
+
+    fn new_object(obj_type: CoreObjectType) -> NONObjectInfo {
+        let raw = match obj_type {
+            CoreObjectType::Text => Text::create("test", "", "value").to_vec().unwrap(),
+            _ => Storage::create("test", vec![1, 2, 3]).to_vec().unwrap(),
+        };
+
+        NONObjectInfo::new_from_object_raw(raw).unwrap()
+    }
+
+    #[test]
+    fn test_versions() {
+        let mut item = new_item(CoreObjectType::Text, None);
+        item.access = GlobalStatePathGroupAccess::Handler;
+        let err = ObjectMeta::new(item).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::NotSupport);
+
+        let text_meta = ObjectMeta::new(new_item(CoreObjectType::Text, Some(3))).unwrap();
+        let storage_meta = ObjectMeta::new(new_item(CoreObjectType::Storage, None)).unwrap();
+
+        let mut list = GlobalStateObjectMetaList::new();
+        assert!(list.add(text_meta));
+        assert!(list.add(storage_meta));
+        assert!(!list.add(ObjectMeta::new(new_item(CoreObjectType::Text, Some(3))).unwrap()));
+
+        // 按选择器匹配到的配置决定保留的历史版本数量
+        let text = new_object(CoreObjectType::Text);
+        let ret = list.query_object_meta(&text).unwrap();
+        assert_eq!(ret.versions, Some(3));
+
+        let storage = new_object(CoreObjectType::Storage);
+        let ret = list.query_object_meta(&storage).unwrap();
+        assert_eq!(ret.versions, None);
+
+        let text_meta = ObjectMeta::new(new_item(CoreObjectType::Text, Some(3))).unwrap();
+        assert!(list.remove(&text_meta).is_some());
+        assert!(list.query_object_meta(&text).is_none());
+        assert!(list.query_object_meta(&storage).is_some());
+    }
+}
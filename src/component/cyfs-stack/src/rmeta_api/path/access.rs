@@ -70,6 +70,21 @@ impl GlobalStatePathAccessList {
         self.list.clone()
     }
 
+    // remove the items which validity has ended, return the removed count
+    pub fn remove_expired(&mut self, now: u64) -> usize {
+        let count = self.list.len();
+        self.list.retain(|item| {
+            if item.is_expired(now) {
+                info!("raccess remove expired item: {}", item);
+                false
+            } else {
+                true
+            }
+        });
+
+        count - self.list.len()
+    }
+
     pub async fn check<'d, 'a, 'b>(
         &self,
         req: GlobalStateAccessRequest<'d, 'a, 'b>,
@@ -82,8 +97,15 @@ impl GlobalStatePathAccessList {
             Cow::Owned(format!("{}/", req.path))
         };
 
+        let now = bucky_time_now();
         for item in &self.list {
             if req_path.starts_with(item.path.as_str()) {
+                // the item out of the valid time window is treated as not exists
+                if !item.is_active(now) {
+                    debug!("raccess skip inactive item: req={}, access={}", req, item);
+                    continue;
+                }
+
                 match &item.access {
                     GlobalStatePathGroupAccess::Default(access) => {
                         let mask = req.source.mask(&req.dec, req.permissions);
@@ -295,6 +317,30 @@ mod test_path_access {
 
         list.check(ret, &current_device_id, &handler).await.unwrap_err();
 
+        // expired item is ignored and removed
+        let now = bucky_time_now();
+        let item = GlobalStatePathAccessItem::new_group(
+            "/e/a",
+            None,
+            None,
+            Some(dec.clone()),
+            AccessPermissions::ReadOnly as u8,
+        )
+        .with_validity(GlobalStateAccessValidity::new_expire(now - 1));
+        list.add(item);
+
+        let ret = GlobalStateAccessRequest {
+            path: Cow::Owned("/e/a/c/".to_owned()),
+            query_string: None,
+            dec: Cow::Owned(owner_dec.clone()),
+            source: Cow::Borrowed(&source),
+            permissions: AccessPermissions::ReadOnly,
+        };
+
+        list.check(ret, &current_device_id, &handler).await.unwrap_err();
+        assert_eq!(list.remove_expired(now), 1);
+        assert_eq!(list.remove_expired(now), 0);
+
         // test remove
         let device = DeviceId::default();
         let item = GlobalStatePathAccessItem::new_group(
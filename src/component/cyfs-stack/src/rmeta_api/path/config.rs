@@ -87,7 +87,7 @@ impl GlobalStatePathConfigList {
             assert!(item.path.len() <= current_len);
             current_len = item.path.len();
 
-            if path.len() > item.path.len() {
+            if path.len() < item.path.len() {
                 continue;
             }
 
@@ -99,3 +99,38 @@ impl GlobalStatePathConfigList {
         None
     }
 }
+
+
+#[cfg(test)]
+mod test_path_config {
+    use super::*;
+
+    fn new_item(path: &str, verify_sign: Option<bool>) -> GlobalStatePathConfigItem {
+        GlobalStatePathConfigItem {
+            path: path.to_owned(),
+            storage_state: None,
+            depth: None,
+            verify_sign,
+            pending_access: None,
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        let mut list = GlobalStatePathConfigList::default();
+        assert!(list.query("/a").is_none());
+
+        assert!(list.add(new_item("/", Some(false))));
+        assert!(list.add(new_item("/a/b", Some(true))));
+        assert!(!list.add(new_item("/a/b/", Some(true))));
+
+        // 匹配最长的前缀路径
+        assert_eq!(list.query("/a/b/c").unwrap().path, "/a/b/");
+        assert_eq!(list.query("/a/b").unwrap().verify_sign, Some(true));
+        assert_eq!(list.query("/a/bc").unwrap().path, "/");
+        assert_eq!(list.query("/").unwrap().verify_sign, Some(false));
+
+        assert!(list.remove(new_item("/", None)).is_some());
+        assert!(list.query("/x").is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use serde::{Deserialize, Serialize};
+
+// dec出站请求的目标白名单，为空表示不做限制
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStateEgressList {
+    list: Vec<GlobalStateEgressItem>,
+}
+
+impl Default for GlobalStateEgressList {
+    fn default() -> Self {
+        Self { list: vec![] }
+    }
+}
+
+impl GlobalStateEgressList {
+    pub fn add(&mut self, item: GlobalStateEgressItem) -> bool {
+        if self.list.iter().any(|v| *v == item) {
+            warn!("egress item already exists! {}", item);
+            return false;
+        }
+
+        info!("new egress item: {}", item);
+        self.list.push(item);
+
+        true
+    }
+
+    pub fn remove(&mut self, item: &GlobalStateEgressItem) -> Option<GlobalStateEgressItem> {
+        match self.list.iter().position(|v| v == item) {
+            Some(index) => {
+                let item = self.list.remove(index);
+                info!("remove egress item: {}", item);
+                Some(item)
+            }
+            None => {
+                warn!("remove egress item but not found! {}", item);
+                None
+            }
+        }
+    }
+
+    pub fn clear(&mut self) -> usize {
+        if self.list.is_empty() {
+            return 0;
+        }
+
+        let count = self.list.len();
+        self.list.clear();
+        count
+    }
+
+    pub fn check(&self, target_owner: Option<&ObjectId>, target_device: &DeviceId) -> bool {
+        if self.list.is_empty() {
+            return true;
+        }
+
+        let target_device = target_device.object_id();
+        self.list
+            .iter()
+            .any(|item| item.target == *target_device || Some(&item.target) == target_owner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_check() {
+        let mut list = GlobalStateEgressList::default();
+
+        let device = DeviceId::from_str("5aSixgLtjoYcAFH9isc6KCqDgKfTJ8jpgASAoiRz5NLk").unwrap();
+        let owner = ObjectId::from_str("5r4MYfF7qVAbn1gdNy9JaNQUW5DfFM8yD3pnwFWY8nn4").unwrap();
+
+        // empty list means no limit
+        assert!(list.check(Some(&owner), &device));
+
+        let other = ObjectId::from_str("5r4MYfF8wo73agKvNjPu7ENuJKABYEFDZ4xi6efweF9D").unwrap();
+        assert!(list.add(GlobalStateEgressItem::new(other.clone())));
+        assert!(!list.add(GlobalStateEgressItem::new(other.clone())));
+        assert!(!list.check(Some(&owner), &device));
+        assert!(list.check(Some(&other), &device));
+
+        assert!(list.add(GlobalStateEgressItem::new(device.object_id().to_owned())));
+        assert!(list.check(None, &device));
+
+        assert_eq!(list.clear(), 2);
+        assert!(list.check(None, &device));
+    }
+}
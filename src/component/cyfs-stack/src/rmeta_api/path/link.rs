@@ -126,14 +126,24 @@ impl GlobalStatePathLinkList {
         assert!(source.ends_with('/'));
 
         let mut ret = Cow::Borrowed(source);
-        let mut depth = 0;
+        let mut visited = vec![];
         loop {
             match self.translate_once(&ret) {
                 Some(dest) => {
                     info!("resolve path link: {} -> {}", ret, dest);
-                    ret = Cow::Owned(dest);
-                    depth += 1;
-                    if depth >= GLOBAL_STATE_PATH_LINK_MAX_DEPTH {
+
+                    // 链接成环
+                    if dest == source || visited.contains(&dest) {
+                        let msg = format!(
+                            "resolve path link but loop detected! source={}, dest={}",
+                            source, dest
+                        );
+                        error!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+                    }
+
+                    visited.push(dest.clone());
+                    if visited.len() >= GLOBAL_STATE_PATH_LINK_MAX_DEPTH as usize {
                         let msg = format!(
                             "resolve path link extend max depth limit! source={}",
                             source
@@ -141,6 +151,8 @@ impl GlobalStatePathLinkList {
                         error!("{}", msg);
                         return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
                     }
+
+                    ret = Cow::Owned(dest);
                 }
                 None => {
                     break;
@@ -154,6 +166,21 @@ impl GlobalStatePathLinkList {
             Ok(Some(ret.to_string()))
         }
     }
+
+    // path maybe not end with '/', and the result keep the same style as the origin path
+    pub fn resolve_path(&self, path: &str) -> BuckyResult<Option<String>> {
+        if path.ends_with('/') {
+            return self.resolve(path);
+        }
+
+        let source = format!("{}/", path);
+        let ret = self.resolve(&source)?.map(|mut dest| {
+            dest.pop();
+            dest
+        });
+
+        Ok(ret)
+    }
 }
 
 #[cfg(test)]
@@ -172,4 +199,28 @@ mod test_path_link {
         let ret=  links.resolve("/x/b/c/").unwrap();
         println!("{:?}", ret);
     }
+
+    #[test]
+    fn test_resolve() {
+        let mut links = GlobalStatePathLinkList::default();
+
+        links.add("/x", "/a/c/e").unwrap();
+        links.add("/a/c", "/b").unwrap();
+
+        let ret = links.resolve_path("/x/f").unwrap();
+        assert_eq!(ret.as_deref(), Some("/b/e/f"));
+
+        let ret = links.resolve_path("/x/f/").unwrap();
+        assert_eq!(ret.as_deref(), Some("/b/e/f/"));
+
+        let ret = links.resolve_path("/y/f").unwrap();
+        assert!(ret.is_none());
+
+        // loop
+        let mut links = GlobalStatePathLinkList::default();
+        links.add("/a", "/b").unwrap();
+        links.add("/b", "/a").unwrap();
+        let err = links.resolve_path("/a/f").unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+    }
 }
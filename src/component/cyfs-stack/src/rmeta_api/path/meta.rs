@@ -1,7 +1,9 @@
 use super::super::object::*;
 use super::access::*;
 use super::config::*;
+use super::egress::*;
 use super::link::*;
+use super::pending::*;
 use super::storage::*;
 use cyfs_base::*;
 use cyfs_lib::*;
@@ -17,6 +19,12 @@ pub struct GlobalStatePathMeta {
 
     #[serde(default)]
     object: GlobalStateObjectMetaList,
+
+    #[serde(default)]
+    egress: GlobalStateEgressList,
+
+    #[serde(default)]
+    pending_access: GlobalStatePendingAccessList,
 }
 
 impl Default for GlobalStatePathMeta {
@@ -26,6 +34,8 @@ impl Default for GlobalStatePathMeta {
             link: GlobalStatePathLinkList::default(),
             config: GlobalStatePathConfigList::default(),
             object: GlobalStateObjectMetaList::default(),
+            egress: GlobalStateEgressList::default(),
+            pending_access: GlobalStatePendingAccessList::default(),
         }
     }
 }
@@ -77,20 +87,24 @@ impl GlobalStatePathMetaSyncCollection {
             return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
         }
 
-        {
+        let ret = {
             let mut meta = self.meta.coll().write().await;
+
+            // clean up the expired items by the way, they are ignored on check already
+            let expired = meta.access.remove_expired(bucky_time_now());
             let ret = meta.access.add(item);
-            if !ret {
+            if !ret && expired == 0 {
                 return Ok(false);
             }
-        }
+            ret
+        };
 
         self.meta.set_dirty(true);
         self.meta.save().await?;
 
         self.dump().await;
 
-        Ok(true)
+        Ok(ret)
     }
 
     pub async fn remove_access(
@@ -200,7 +214,7 @@ impl GlobalStatePathMetaSyncCollection {
 
     pub async fn resolve_link(&self, source: &str) -> BuckyResult<Option<String>> {
         let meta = self.meta.coll().read().await;
-        meta.link.resolve(source)
+        meta.link.resolve_path(source)
     }
 
     // object meta
@@ -253,6 +267,8 @@ impl GlobalStatePathMetaSyncCollection {
             selector: item.selector.into_exp(),
             access: item.access,
             depth: item.depth,
+            versions: item.versions,
+            encrypt: item.encrypt,
         };
 
         Ok(Some(ret))
@@ -303,6 +319,8 @@ impl GlobalStatePathMetaSyncCollection {
             .map(|ret| GlobalStateObjectMetaConfigItemValue {
                 access: ret.access.clone(),
                 depth: ret.depth,
+                versions: ret.versions,
+                encrypt: ret.encrypt,
             })
     }
 
@@ -370,6 +388,8 @@ impl GlobalStatePathMetaSyncCollection {
                 Some(item) => Some(GlobalStatePathConfigItemValue {
                     storage_state: item.storage_state,
                     depth: item.depth,
+                    verify_sign: item.verify_sign,
+                    pending_access: item.pending_access,
                 }),
                 None => None,
             }
@@ -377,6 +397,163 @@ impl GlobalStatePathMetaSyncCollection {
 
         ret
     }
+
+    // egress
+    pub async fn add_egress(&self, item: GlobalStateEgressItem) -> BuckyResult<bool> {
+        if !item.check_valid() {
+            let msg = format!("invalid egress item! {}", item);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        {
+            let mut meta = self.meta.coll().write().await;
+            let ret = meta.egress.add(item);
+            if !ret {
+                return Ok(false);
+            }
+        }
+
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        self.dump().await;
+
+        Ok(true)
+    }
+
+    pub async fn remove_egress(
+        &self,
+        item: GlobalStateEgressItem,
+    ) -> BuckyResult<Option<GlobalStateEgressItem>> {
+        let ret = {
+            let mut meta = self.meta.coll().write().await;
+            meta.egress.remove(&item)
+        };
+
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        self.dump().await;
+
+        Ok(ret)
+    }
+
+    pub async fn clear_egress(&self) -> BuckyResult<usize> {
+        let ret = {
+            let mut meta = self.meta.coll().write().await;
+            meta.egress.clear()
+        };
+
+        if ret == 0 {
+            return Ok(ret);
+        }
+
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        self.dump().await;
+
+        Ok(ret)
+    }
+
+    pub async fn check_egress(
+        &self,
+        target_owner: Option<&ObjectId>,
+        target_device: &DeviceId,
+    ) -> bool {
+        let meta = self.meta.coll().read().await;
+        meta.egress.check(target_owner, target_device)
+    }
+
+    // pending access
+    pub async fn record_pending_access(
+        &self,
+        path: &str,
+        source_zone: &ObjectId,
+        source_dec: &ObjectId,
+        access: AccessPermissions,
+    ) -> BuckyResult<bool> {
+        let ret = {
+            let mut meta = self.meta.coll().write().await;
+            meta.pending_access
+                .record(path, source_zone, source_dec, access)
+        };
+
+        // 重复的请求也需要更新次数和时间
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        if ret {
+            self.dump().await;
+        }
+
+        Ok(ret)
+    }
+
+    pub async fn list_pending_access(&self) -> Vec<GlobalStatePendingAccessItem> {
+        let meta = self.meta.coll().read().await;
+        meta.pending_access.list()
+    }
+
+    // 批准后移除待审批请求，并为请求来源添加对应路径的访问规则
+    pub async fn approve_pending_access(
+        &self,
+        id: u64,
+    ) -> BuckyResult<Option<GlobalStatePendingAccessItem>> {
+        let ret = {
+            let mut meta = self.meta.coll().write().await;
+            match meta.pending_access.take(id) {
+                Some(item) => {
+                    let access_item = item.to_access_item();
+                    info!(
+                        "approve pending access: {}, access item={}",
+                        item, access_item
+                    );
+                    meta.access.add(access_item);
+                    Some(item)
+                }
+                None => None,
+            }
+        };
+
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        self.dump().await;
+
+        Ok(ret)
+    }
+
+    pub async fn reject_pending_access(
+        &self,
+        id: u64,
+    ) -> BuckyResult<Option<GlobalStatePendingAccessItem>> {
+        let ret = {
+            let mut meta = self.meta.coll().write().await;
+            meta.pending_access.take(id)
+        };
+
+        match &ret {
+            Some(item) => info!("reject pending access: {}", item),
+            None => return Ok(None),
+        }
+
+        self.meta.set_dirty(true);
+        self.meta.save().await?;
+
+        self.dump().await;
+
+        Ok(ret)
+    }
 }
 
 #[async_trait::async_trait]
@@ -472,4 +649,24 @@ impl GlobalStateMetaRawProcessor for GlobalStatePathMetaSyncCollection {
     async fn query_path_config(&self, path: &str) -> Option<GlobalStatePathConfigItemValue> {
         Self::query_path_config(self, path).await
     }
+
+    // egress
+    async fn add_egress(&self, item: GlobalStateEgressItem) -> BuckyResult<bool> {
+        Self::add_egress(self, item).await
+    }
+
+    async fn remove_egress(
+        &self,
+        item: GlobalStateEgressItem,
+    ) -> BuckyResult<Option<GlobalStateEgressItem>> {
+        Self::remove_egress(self, item).await
+    }
+
+    async fn clear_egress(&self) -> BuckyResult<usize> {
+        Self::clear_egress(self).await
+    }
+
+    async fn check_egress(&self, target_owner: Option<&ObjectId>, target_device: &DeviceId) -> bool {
+        Self::check_egress(self, target_owner, target_device).await
+    }
 }
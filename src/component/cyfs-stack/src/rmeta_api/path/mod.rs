@@ -1,8 +1,10 @@
 mod access;
 mod config;
 mod dec_manager;
+mod egress;
 mod link;
 mod meta;
+mod pending;
 mod storage;
 mod handler;
 
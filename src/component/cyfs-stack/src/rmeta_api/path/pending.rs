@@ -0,0 +1,123 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use serde::{Deserialize, Serialize};
+
+// 每个dec最多保留的待审批请求，超出后丢弃最久没有更新的
+const PENDING_ACCESS_MAX_COUNT: usize = 256;
+
+// 其它zone被拒绝的访问请求，等待zone owner审批
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GlobalStatePendingAccessList {
+    next_id: u64,
+    list: Vec<GlobalStatePendingAccessItem>,
+}
+
+impl Default for GlobalStatePendingAccessList {
+    fn default() -> Self {
+        Self {
+            next_id: 1,
+            list: vec![],
+        }
+    }
+}
+
+impl GlobalStatePendingAccessList {
+    // 同一个来源对同一路径的请求合并成一条，返回是否新增
+    pub fn record(
+        &mut self,
+        path: &str,
+        source_zone: &ObjectId,
+        source_dec: &ObjectId,
+        access: AccessPermissions,
+    ) -> bool {
+        let path = GlobalStatePathHelper::fix_path(path);
+        let now = bucky_time_now();
+
+        if let Some(item) = self.list.iter_mut().find(|item| {
+            item.path == path && item.source_zone == *source_zone && item.source_dec == *source_dec
+        }) {
+            item.access |= access as u8;
+            item.update_time = now;
+            item.count = item.count.saturating_add(1);
+            return false;
+        }
+
+        if self.list.len() >= PENDING_ACCESS_MAX_COUNT {
+            let index = self
+                .list
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, item)| item.update_time)
+                .map(|(index, _)| index)
+                .unwrap();
+            let item = self.list.remove(index);
+            warn!(
+                "pending access list is full, now will drop the oldest one! {}",
+                item
+            );
+        }
+
+        let item = GlobalStatePendingAccessItem {
+            id: self.next_id,
+            path: path.to_string(),
+            source_zone: source_zone.to_owned(),
+            source_dec: source_dec.to_owned(),
+            access: access as u8,
+            create_time: now,
+            update_time: now,
+            count: 1,
+        };
+        self.next_id += 1;
+
+        info!("new pending access item: {}", item);
+        self.list.push(item);
+
+        true
+    }
+
+    pub fn list(&self) -> Vec<GlobalStatePendingAccessItem> {
+        self.list.clone()
+    }
+
+    pub fn take(&mut self, id: u64) -> Option<GlobalStatePendingAccessItem> {
+        match self.list.iter().position(|item| item.id == id) {
+            Some(index) => Some(self.list.remove(index)),
+            None => {
+                warn!("pending access item not found! id={}", id);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pending {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_record() {
+        let mut list = GlobalStatePendingAccessList::default();
+
+        let zone = ObjectId::from_str("5r4MYfF7qVAbn1gdNy9JaNQUW5DfFM8yD3pnwFWY8nn4").unwrap();
+        let dec = ObjectId::from_str("5r4MYfF8wo73agKvNjPu7ENuJKABYEFDZ4xi6efweF9D").unwrap();
+
+        assert!(list.record("/a/b", &zone, &dec, AccessPermissions::ReadOnly));
+        assert!(!list.record("/a/b/", &zone, &dec, AccessPermissions::WriteOnly));
+        assert!(list.record("/a", &zone, &dec, AccessPermissions::ReadOnly));
+
+        let items = list.list();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].count, 2);
+        assert_eq!(items[0].access, AccessPermissions::ReadAndWrite as u8);
+
+        let item = list.take(items[0].id).unwrap();
+        assert_eq!(item.to_access_item().path, "/a/b/");
+        assert!(list.take(items[0].id).is_none());
+
+        // 已经处理过的id不会被复用
+        assert!(list.record("/a/b", &zone, &dec, AccessPermissions::ReadOnly));
+        assert_eq!(list.list()[1].id, 3);
+    }
+}
@@ -1,4 +1,5 @@
 use super::super::acl::GlobalStateMetaAclInnerInputProcessor;
+use crate::admin::AdminConfirmManager;
 use crate::forward::ForwardProcessorManager;
 use crate::meta::ObjectFailHandler;
 use crate::rmeta::*;
@@ -35,8 +36,9 @@ impl GlobalStateMetaServiceRouter {
         zone_manager: ZoneManagerRef,
         fail_handler: ObjectFailHandler,
         processor: GlobalStateMetaInputProcessorRef,
+        admin_confirm: Option<AdminConfirmManager>,
     ) -> Self {
-        let processor = GlobalStateMetaAclInnerInputProcessor::new(processor);
+        let processor = GlobalStateMetaAclInnerInputProcessor::new(processor, admin_confirm);
         Self {
             category,
             processor,
@@ -197,4 +199,54 @@ impl GlobalStateMetaInputProcessor for GlobalStateMetaServiceRouter {
         let processor = self.get_processor(req.common.target.as_ref()).await?;
         processor.clear_path_config(req).await
     }
+
+    // egress
+    async fn add_egress(
+        &self,
+        req: GlobalStateMetaAddEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.add_egress(req).await
+    }
+
+    async fn remove_egress(
+        &self,
+        req: GlobalStateMetaRemoveEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.remove_egress(req).await
+    }
+
+    async fn clear_egress(
+        &self,
+        req: GlobalStateMetaClearEgressInputRequest,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.clear_egress(req).await
+    }
+
+    // pending access
+    async fn list_pending_access(
+        &self,
+        req: GlobalStateMetaListPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.list_pending_access(req).await
+    }
+
+    async fn approve_pending_access(
+        &self,
+        req: GlobalStateMetaApprovePendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.approve_pending_access(req).await
+    }
+
+    async fn reject_pending_access(
+        &self,
+        req: GlobalStateMetaRejectPendingAccessInputRequest,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.reject_pending_access(req).await
+    }
 }
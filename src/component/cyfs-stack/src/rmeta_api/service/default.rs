@@ -27,6 +27,7 @@ impl GlobalStateDefaultMetas {
                 dec: Some(cyfs_core::get_system_dec_app().to_owned()),
                 access: AccessPermissions::CallOnly as u8,
             }),
+            validity: None,
         };
 
         meta.add_access(item).await?;
@@ -40,6 +41,7 @@ impl GlobalStateDefaultMetas {
                 dec: Some(cyfs_core::get_system_dec_app().to_owned()),
                 access: AccessPermissions::CallOnly as u8,
             }),
+            validity: None,
         };
 
         meta.add_access(item).await?;
@@ -53,6 +55,7 @@ impl GlobalStateDefaultMetas {
                 dec: Some(cyfs_core::get_system_dec_app().to_owned()),
                 access: AccessPermissions::CallOnly as u8,
             }),
+            validity: None,
         };
 
         meta.add_access(item).await?;
@@ -69,6 +72,7 @@ impl GlobalStateDefaultMetas {
         let item = GlobalStatePathAccessItem {
             path,
             access: GlobalStatePathGroupAccess::Default(permissions.value()),
+            validity: None,
         };
 
         meta.add_access(item).await?;
@@ -77,6 +81,30 @@ impl GlobalStateDefaultMetas {
         let item = GlobalStatePathAccessItem {
             path,
             access: GlobalStatePathGroupAccess::Default(permissions.value()),
+            validity: None,
+        };
+
+        meta.add_access(item).await?;
+
+        // allow contacts handshake from other zone's system dec
+        let mut permissions = AccessString::new(0);
+        permissions.set_group_permission(AccessGroup::FriendZone, AccessPermission::Call);
+        permissions.set_group_permission(AccessGroup::OthersZone, AccessPermission::Call);
+        permissions.set_group_permission(AccessGroup::OwnerDec, AccessPermission::Call);
+
+        let item = GlobalStatePathAccessItem {
+            path: CYFS_SYSTEM_CONTACTS_VIRTUAL_PATH.to_owned(),
+            access: GlobalStatePathGroupAccess::Default(permissions.value()),
+            validity: None,
+        };
+
+        meta.add_access(item).await?;
+
+        // allow queue remote push from other zone's system dec, each queue checks allow_remote itself
+        let item = GlobalStatePathAccessItem {
+            path: CYFS_SYSTEM_QUEUE_VIRTUAL_PATH.to_owned(),
+            access: GlobalStatePathGroupAccess::Default(permissions.value()),
+            validity: None,
         };
 
         meta.add_access(item).await?;
@@ -645,4 +645,320 @@ impl GlobalStateMetaRequestHandler {
 
         self.processor.clear_path_config(clear_request).await
     }
+
+    // add_egress
+    pub fn encode_add_egress_response(resp: GlobalStateMetaAddEgressInputResponse) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_add_egress_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_add_egress(req).await;
+        match ret {
+            Ok(resp) => Self::encode_add_egress_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_add_egress<State: Send>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaAddEgressInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateAddEgress)?;
+        if action != MetaAction::GlobalStateAddEgress {
+            let msg = format!("invalid global state meta add egress action! {:?}", action);
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+
+        let req: GlobalStateMetaAddEgressOutputRequest =
+            RequestorHelper::decode_serde_json_body(&mut req.request).await?;
+
+        let add_request = GlobalStateMetaAddEgressInputRequest {
+            common,
+            item: req.item,
+        };
+
+        info!(
+            "recv global state meta add egress request: {:?}",
+            add_request
+        );
+
+        self.processor.add_egress(add_request).await
+    }
+
+    // remove_egress
+    pub fn encode_remove_egress_response(
+        resp: GlobalStateMetaRemoveEgressInputResponse,
+    ) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_remove_egress_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_remove_egress(req).await;
+        match ret {
+            Ok(resp) => Self::encode_remove_egress_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_remove_egress<State: Send>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaRemoveEgressInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateRemoveEgress)?;
+        if action != MetaAction::GlobalStateRemoveEgress {
+            let msg = format!(
+                "invalid global state meta remove egress action! {:?}",
+                action
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+
+        let req: GlobalStateMetaRemoveEgressOutputRequest =
+            RequestorHelper::decode_serde_json_body(&mut req.request).await?;
+
+        let remove_request = GlobalStateMetaRemoveEgressInputRequest {
+            common,
+            item: req.item,
+        };
+
+        info!(
+            "recv global state meta remove egress request: {:?}",
+            remove_request
+        );
+
+        self.processor.remove_egress(remove_request).await
+    }
+
+    // clear_egress
+    pub fn encode_clear_egress_response(resp: GlobalStateMetaClearEgressInputResponse) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_clear_egress_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_clear_egress(req).await;
+        match ret {
+            Ok(resp) => Self::encode_clear_egress_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_clear_egress<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaClearEgressInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateClearEgress)?;
+        if action != MetaAction::GlobalStateClearEgress {
+            let msg = format!(
+                "invalid global state meta clear egress action! {:?}",
+                action
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+        let clear_request = GlobalStateMetaClearEgressInputRequest { common };
+
+        info!(
+            "recv global state meta clear egress request: {:?}",
+            clear_request
+        );
+
+        self.processor.clear_egress(clear_request).await
+    }
+
+    // list_pending_access
+    pub fn encode_list_pending_access_response(
+        resp: GlobalStateMetaListPendingAccessInputResponse,
+    ) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_list_pending_access_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_list_pending_access(req).await;
+        match ret {
+            Ok(resp) => Self::encode_list_pending_access_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_list_pending_access<State: Send>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaListPendingAccessInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateListPendingAccess)?;
+        if action != MetaAction::GlobalStateListPendingAccess {
+            let msg = format!(
+                "invalid global state meta list pending access action! {:?}",
+                action
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+
+        let req: GlobalStateMetaListPendingAccessOutputRequest =
+            RequestorHelper::decode_serde_json_body(&mut req.request).await?;
+
+        let list_request = GlobalStateMetaListPendingAccessInputRequest {
+            common,
+            page: req.page,
+        };
+
+        info!(
+            "recv global state meta list pending access request: {:?}",
+            list_request
+        );
+
+        self.processor.list_pending_access(list_request).await
+    }
+
+    // approve_pending_access
+    pub fn encode_approve_pending_access_response(
+        resp: GlobalStateMetaApprovePendingAccessInputResponse,
+    ) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_approve_pending_access_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_approve_pending_access(req).await;
+        match ret {
+            Ok(resp) => Self::encode_approve_pending_access_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_approve_pending_access<State: Send>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaApprovePendingAccessInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateApprovePendingAccess)?;
+        if action != MetaAction::GlobalStateApprovePendingAccess {
+            let msg = format!(
+                "invalid global state meta approve pending access action! {:?}",
+                action
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+
+        let req: GlobalStateMetaApprovePendingAccessOutputRequest =
+            RequestorHelper::decode_serde_json_body(&mut req.request).await?;
+
+        let approve_request = GlobalStateMetaApprovePendingAccessInputRequest {
+            common,
+            id: req.id,
+        };
+
+        info!(
+            "recv global state meta approve pending access request: {:?}",
+            approve_request
+        );
+
+        self.processor.approve_pending_access(approve_request).await
+    }
+
+    // reject_pending_access
+    pub fn encode_reject_pending_access_response(
+        resp: GlobalStateMetaRejectPendingAccessInputResponse,
+    ) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+        http_resp.into()
+    }
+
+    pub async fn process_reject_pending_access_request<State: Send>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_reject_pending_access(req).await;
+        match ret {
+            Ok(resp) => Self::encode_reject_pending_access_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_reject_pending_access<State: Send>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<GlobalStateMetaRejectPendingAccessInputResponse> {
+        // 检查action
+        let action = Self::decode_action(&req, MetaAction::GlobalStateRejectPendingAccess)?;
+        if action != MetaAction::GlobalStateRejectPendingAccess {
+            let msg = format!(
+                "invalid global state meta reject pending access action! {:?}",
+                action
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let common = Self::decode_common_headers(&req)?;
+
+        let req: GlobalStateMetaRejectPendingAccessOutputRequest =
+            RequestorHelper::decode_serde_json_body(&mut req.request).await?;
+
+        let reject_request = GlobalStateMetaRejectPendingAccessInputRequest {
+            common,
+            id: req.id,
+        };
+
+        info!(
+            "recv global state meta reject pending access request: {:?}",
+            reject_request
+        );
+
+        self.processor.reject_pending_access(reject_request).await
+    }
 }
@@ -22,6 +22,14 @@ enum GlobalStateMetaRequestType {
     AddPathConfig,
     RemovePathConfig,
     ClearPathConfig,
+
+    AddEgress,
+    RemoveEgress,
+    ClearEgress,
+
+    ListPendingAccess,
+    ApprovePendingAccess,
+    RejectPendingAccess,
 }
 
 pub(crate) struct GlobalStateMetaRequestHandlerEndpoint {
@@ -90,6 +98,26 @@ impl GlobalStateMetaRequestHandlerEndpoint {
             GlobalStateMetaRequestType::ClearPathConfig => {
                 self.handler.process_clear_path_config_request(req).await
             }
+
+            GlobalStateMetaRequestType::AddEgress => {
+                self.handler.process_add_egress_request(req).await
+            }
+            GlobalStateMetaRequestType::RemoveEgress => {
+                self.handler.process_remove_egress_request(req).await
+            }
+            GlobalStateMetaRequestType::ClearEgress => {
+                self.handler.process_clear_egress_request(req).await
+            }
+
+            GlobalStateMetaRequestType::ListPendingAccess => {
+                self.handler.process_list_pending_access_request(req).await
+            }
+            GlobalStateMetaRequestType::ApprovePendingAccess => {
+                self.handler.process_approve_pending_access_request(req).await
+            }
+            GlobalStateMetaRequestType::RejectPendingAccess => {
+                self.handler.process_reject_pending_access_request(req).await
+            }
         }
     }
 
@@ -229,6 +257,70 @@ impl GlobalStateMetaRequestHandlerEndpoint {
                 GlobalStateMetaRequestType::ClearPathConfig,
                 handler.clone(),
             ));
+
+        let path = format!("/{}/meta/egress", root_seg);
+        // add_egress
+        server
+            .at(&path)
+            .put(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::AddEgress,
+                handler.clone(),
+            ));
+
+        // remove_egress
+        server
+            .at(&path)
+            .delete(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::RemoveEgress,
+                handler.clone(),
+            ));
+
+        // clear_egress
+        let path = format!("/{}/meta/egresses", root_seg);
+        server
+            .at(&path)
+            .delete(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::ClearEgress,
+                handler.clone(),
+            ));
+
+        // list_pending_access
+        let path = format!("/{}/meta/pending-accesses", root_seg);
+        server
+            .at(&path)
+            .post(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::ListPendingAccess,
+                handler.clone(),
+            ));
+
+        let path = format!("/{}/meta/pending-access", root_seg);
+        // approve_pending_access
+        server
+            .at(&path)
+            .put(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::ApprovePendingAccess,
+                handler.clone(),
+            ));
+
+        // reject_pending_access
+        server
+            .at(&path)
+            .delete(GlobalStateMetaRequestHandlerEndpoint::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                GlobalStateMetaRequestType::RejectPendingAccess,
+                handler.clone(),
+            ));
     }
 }
 
@@ -1,6 +1,7 @@
 use super::super::local::*;
 use super::super::router::GlobalStateMetaServiceRouter;
 use super::default::GlobalStateDefaultMetas;
+use crate::admin::AdminConfirmManager;
 use crate::forward::ForwardProcessorManager;
 use crate::meta::ObjectFailHandler;
 use crate::rmeta::*;
@@ -8,11 +9,13 @@ use crate::rmeta_api::{AclHandlerWrapper, GlobalStatePathMetaSyncCollection};
 use crate::root_state_api::GlobalStateLocalService;
 use crate::router_handler::RouterHandlersManager;
 use crate::zone::ZoneManagerRef;
+use crate::zone_event::ZoneEventRecorder;
 use cyfs_base::*;
 use cyfs_lib::*;
 
 use once_cell::sync::OnceCell;
 use std::borrow::Cow;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -23,6 +26,9 @@ pub struct GlobalStateMetaLocalService {
     local_cache_meta: GlobalStatePathMetaManagerRef,
 
     acl_handler: Arc<OnceCell<GlobalStatePathHandlerRef>>,
+
+    // 拒绝访问时记录zone事件
+    zone_event_recorder: Arc<OnceCell<ZoneEventRecorder>>,
 }
 
 impl GlobalStateMetaLocalService {
@@ -62,6 +68,7 @@ impl GlobalStateMetaLocalService {
             root_state_meta,
             local_cache_meta,
             acl_handler: Arc::new(OnceCell::new()),
+            zone_event_recorder: Arc::new(OnceCell::new()),
         }
     }
 
@@ -75,6 +82,12 @@ impl GlobalStateMetaLocalService {
         }
     }
 
+    pub(crate) fn bind_zone_event_recorder(&self, recorder: ZoneEventRecorder) {
+        if let Err(_) = self.zone_event_recorder.set(recorder) {
+            unreachable!();
+        }
+    }
+
     pub(crate) async fn init(&self) -> BuckyResult<()> {
         GlobalStateDefaultMetas::init(&self).await
     }
@@ -115,6 +128,49 @@ impl GlobalStateMetaLocalService {
             .await
     }
 
+    pub async fn resolve_link(
+        &self,
+        target_dec_id: &ObjectId,
+        category: GlobalStateCategory,
+        path: &str,
+    ) -> BuckyResult<Option<String>> {
+        let ret = self.get_dec_meta(target_dec_id, category).await?;
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        let dec_rmeta = ret.unwrap();
+        dec_rmeta.resolve_link(path).await
+    }
+
+    // resolve the path links of req_path, return true if the req_path was been changed
+    pub async fn resolve_req_path(
+        &self,
+        source: &RequestSourceInfo,
+        req_path: &mut RequestGlobalStatePath,
+    ) -> BuckyResult<bool> {
+        let path = match &req_path.req_path {
+            Some(path) if path.starts_with('/') && path != "/" => path,
+            _ => return Ok(false),
+        };
+
+        let target_dec_id = req_path.dec(source).to_owned();
+        let ret = self
+            .resolve_link(&target_dec_id, req_path.category(), path)
+            .await?;
+        match ret {
+            Some(dest) => {
+                info!(
+                    "resolve req_path link: dec={}, {} -> {}",
+                    target_dec_id, path, dest
+                );
+                req_path.req_path = Some(dest);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     pub async fn check_access(
         &self,
         source: &RequestSourceInfo,
@@ -155,12 +211,135 @@ impl GlobalStateMetaLocalService {
                 req_path,
                 permissions.as_str()
             );
+
+            if let Some(recorder) = self.zone_event_recorder.get() {
+                recorder.record_local(
+                    cyfs_core::ZoneEventCategory::AclDenied,
+                    Some(source.dec.clone()),
+                    format!(
+                        "source={}, req_path={}, permissions={}",
+                        source,
+                        req_path,
+                        permissions.as_str()
+                    ),
+                );
+            }
+
+            if !source.is_current_zone() {
+                self.try_record_pending_access(&dec_rmeta, source, req_path, permissions)
+                    .await;
+            }
+
             return Err(e);
         }
 
         Ok(())
     }
 
+    // 其它zone的请求被拒绝时，如果目标路径配置了pending_access，那么记录为待审批请求，等待zone owner处理
+    async fn try_record_pending_access(
+        &self,
+        dec_rmeta: &GlobalStatePathMetaSyncCollection,
+        source: &RequestSourceInfo,
+        req_path: &RequestGlobalStatePath,
+        permissions: AccessPermissions,
+    ) {
+        let path = req_path.req_path();
+        let enable = match dec_rmeta.query_path_config(&path).await {
+            Some(item) => item.pending_access.unwrap_or(false),
+            None => false,
+        };
+        if !enable {
+            return;
+        }
+
+        let source_zone = match &source.zone.zone {
+            Some(zone) => zone.to_owned(),
+            None => match &source.zone.device {
+                Some(device) => device.object_id().to_owned(),
+                None => {
+                    warn!(
+                        "record pending access but source zone and device not specified! source={}, req_path={}",
+                        source, req_path
+                    );
+                    return;
+                }
+            },
+        };
+
+        if let Err(e) = dec_rmeta
+            .record_pending_access(&path, &source_zone, &source.dec, permissions)
+            .await
+        {
+            error!(
+                "record pending access failed! source={}, req_path={}, {}",
+                source, req_path, e
+            );
+        }
+    }
+
+    // check if the source dec's output request can reach the target zone, with the egress list configed by the zone admin
+    pub async fn check_egress(
+        &self,
+        source: &RequestSourceInfo,
+        target_owner: Option<&ObjectId>,
+        target_device: &DeviceId,
+    ) -> BuckyResult<()> {
+        if source.is_system_dec() {
+            return Ok(());
+        }
+
+        let rmeta = self.get_meta_manager(GlobalStateCategory::RootState);
+        let ret = rmeta
+            .get_option_global_state_meta(&source.dec, false)
+            .await?;
+        if ret.is_none() {
+            return Ok(());
+        }
+
+        let dec_rmeta = ret.unwrap();
+        if !dec_rmeta.check_egress(target_owner, target_device).await {
+            let msg = format!(
+                "dec output request been rejected by egress policy! source={}, target_owner={:?}, target_device={}",
+                source, target_owner, target_device,
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+
+    // check if the objects got from other zones should be verified with their owner's signs, configed by the source dec's path config
+    pub async fn check_verify_sign(
+        &self,
+        source: &RequestSourceInfo,
+        req_path: Option<&str>,
+    ) -> BuckyResult<bool> {
+        let path = match req_path {
+            Some(req_path) => RequestGlobalStatePath::from_str(req_path)?
+                .req_path()
+                .to_string(),
+            None => "/".to_owned(),
+        };
+
+        let rmeta = self.get_meta_manager(GlobalStateCategory::RootState);
+        let ret = rmeta
+            .get_option_global_state_meta(&source.dec, false)
+            .await?;
+        if ret.is_none() {
+            return Ok(false);
+        }
+
+        let dec_rmeta = ret.unwrap();
+        let ret = match dec_rmeta.query_path_config(&path).await {
+            Some(item) => item.verify_sign.unwrap_or(false),
+            None => false,
+        };
+
+        Ok(ret)
+    }
+
     pub async fn check_object_access(
         &self,
         target_dec_id: &ObjectId,
@@ -210,6 +389,42 @@ impl NamedObjectCacheObjectMetaAccessProvider for GlobalStateMetaLocalService {
         self.check_object_access(target_dec_id, source, object_data, permissions)
             .await
     }
+
+    async fn get_object_versions(
+        &self,
+        target_dec_id: &ObjectId,
+        object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<Option<u32>> {
+        let rmeta = self.get_meta_manager(GlobalStateCategory::RootState);
+
+        let ret = rmeta
+            .get_option_global_state_meta(target_dec_id, false)
+            .await?;
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        let ret = ret.unwrap().query_object_meta(object_data).await;
+        Ok(ret.and_then(|item| item.versions))
+    }
+
+    async fn get_object_encrypt(
+        &self,
+        target_dec_id: &ObjectId,
+        object_data: &dyn ObjectSelectorDataProvider,
+    ) -> BuckyResult<bool> {
+        let rmeta = self.get_meta_manager(GlobalStateCategory::RootState);
+
+        let ret = rmeta
+            .get_option_global_state_meta(target_dec_id, false)
+            .await?;
+        if ret.is_none() {
+            return Ok(false);
+        }
+
+        let ret = ret.unwrap().query_object_meta(object_data).await;
+        Ok(ret.and_then(|item| item.encrypt).unwrap_or(false))
+    }
 }
 
 #[async_trait::async_trait]
@@ -243,6 +458,7 @@ impl GlobalStateMetaService {
         forward: ForwardProcessorManager,
         zone_manager: ZoneManagerRef,
         fail_handler: ObjectFailHandler,
+        admin_confirm: AdminConfirmManager,
     ) -> Self {
         // root-state
         let root_state_meta_router = GlobalStateMetaServiceRouter::new(
@@ -251,6 +467,7 @@ impl GlobalStateMetaService {
             zone_manager.clone(),
             fail_handler.clone(),
             local_service.clone_processor(GlobalStateCategory::RootState),
+            Some(admin_confirm),
         );
         let root_state_meta_router = Arc::new(root_state_meta_router);
 
@@ -261,6 +478,7 @@ impl GlobalStateMetaService {
             zone_manager,
             fail_handler,
             local_service.clone_processor(GlobalStateCategory::LocalCache),
+            None,
         );
         let local_cache_meta_router = Arc::new(local_cache_meta_router);
 
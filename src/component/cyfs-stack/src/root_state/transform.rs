@@ -721,6 +721,7 @@ impl GlobalStateAccessorOutputProcessor for GlobalStateAccessorOutputTransformer
             common: self.convert_common(req.common),
             page_index: req.page_index,
             page_size: req.page_size,
+            cursor: req.cursor,
             inner_path: req.inner_path,
         };
 
@@ -787,6 +788,7 @@ impl GlobalStateAccessorInputProcessor for GlobalStateAccessorInputTransformer {
             common: self.convert_common(req.common),
             page_index: req.page_index,
             page_size: req.page_size,
+            cursor: req.cursor,
             inner_path: req.inner_path,
         };
 
@@ -43,6 +43,7 @@ impl MemoryNOC {
             context: req.context.clone(),
             last_access_rpath: req.last_access_rpath.clone(),
             access_string,
+            sign_verified_update_time: None,
         };
 
         NamedObjectCacheObjectRawData {
@@ -159,6 +160,20 @@ impl NamedObjectCache for MemoryNOC {
         unreachable!();
     }
 
+    async fn get_object_history(
+        &self,
+        _req: &NamedObjectCacheGetObjectHistoryRequest,
+    ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+        unreachable!();
+    }
+
+    async fn get_object_revision(
+        &self,
+        _req: &NamedObjectCacheGetObjectRevisionRequest,
+    ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+        unreachable!();
+    }
+
     fn bind_object_meta_access_provider(
         &self,
         _object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef,
@@ -199,6 +214,7 @@ async fn create_global_state_manager() -> GlobalStateManager {
         udp_sn_only: None,
         sn_mode: SNMode::Normal,
         ping_interval: None,
+        session_resume: None,
     };
     let config = StackGlobalConfig::new(params, bdt_params);
 
@@ -193,18 +193,23 @@ impl GlobalStateAccessorService {
         }
         let obj = ret.unwrap();
 
-        let page_index = req.page_index.unwrap_or(0) as usize;
-        let page_size = req.page_size.unwrap_or(1024) as usize;
+        let page_size = std::cmp::min(req.page_size.unwrap_or(CYFS_PAGE_SIZE_MAX), CYFS_PAGE_SIZE_MAX) as usize;
         if page_size == 0 {
             return Ok(RootStateAccessorListInputResponse {
                 list: vec![],
                 root: root_info.0,
                 revision: root_info.1,
+                page: PageInfo::default(),
             });
         }
 
-        let begin = page_size * page_index;
+        // 有游标时按游标里的偏移读取，忽略page_index
+        let begin = match &req.cursor {
+            Some(cursor) => cursor.to_offset()? as usize,
+            None => page_size * req.page_index.unwrap_or(0) as usize,
+        };
         let obj = obj.lock().await;
+        let total = obj.count();
 
         // TODO it maybe cached during next incoming list request with inc page_index
         let op_env_cache = ObjectMapOpEnvMemoryCache::new_ref(root_cache);
@@ -216,6 +221,10 @@ impl GlobalStateAccessorService {
                     list: vec![],
                     root: root_info.0,
                     revision: root_info.1,
+                    page: PageInfo {
+                        next_cursor: None,
+                        total_hint: Some(total),
+                    },
                 });
             }
 
@@ -225,11 +234,13 @@ impl GlobalStateAccessorService {
         };
 
         let list = it.next(&obj, page_size).await?;
+        let page = PageInfo::from_offset(begin as u64, list.list.len(), page_size as u32, Some(total));
 
         Ok(RootStateAccessorListInputResponse {
             list: list.list,
             root: root_info.0,
             revision: root_info.1,
+            page,
         })
     }
 }
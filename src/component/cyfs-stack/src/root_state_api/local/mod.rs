@@ -1,5 +1,7 @@
 mod accessor_service;
+mod namespace;
 mod service;
 
 pub use accessor_service::*;
+pub use namespace::*;
 pub use service::*;
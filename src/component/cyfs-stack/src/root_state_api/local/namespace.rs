@@ -0,0 +1,627 @@
+use super::super::core::*;
+use super::service::GlobalStateLocalService;
+use crate::acl::AclManagerRef;
+use crate::config::StackGlobalConfig;
+use crate::root_state::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// dec在local_cache里面的用量信息
+#[derive(Clone, Debug)]
+pub struct LocalCacheNamespaceInfo {
+    pub dec_id: ObjectId,
+    pub dec_root: ObjectId,
+
+    // dec根下面所有叶子条目的数量，不包括中间的objectmap
+    pub items: u64,
+
+    // 0表示不限制
+    pub quota: u64,
+}
+
+// 统计dec根下面的叶子条目数量
+struct LocalCacheUsageCounter {
+    cache: ObjectMapRootCacheRef,
+    items: u64,
+}
+
+impl LocalCacheUsageCounter {
+    async fn count(cache: ObjectMapRootCacheRef, dec_root: &ObjectId) -> BuckyResult<u64> {
+        let counter = Self { cache, items: 0 };
+
+        let mut visitor = ObjectMapPathVisitor::new(Box::new(counter));
+        visitor.visit(dec_root).await?;
+
+        let counter = visitor
+            .into_provider()
+            .into_any()
+            .downcast::<Self>()
+            .unwrap();
+        Ok(counter.items)
+    }
+
+    fn on_item(&mut self, item: &ObjectId) {
+        if item.obj_type_code() != ObjectTypeCode::ObjectMap {
+            self.items += 1;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectMapVisitor for LocalCacheUsageCounter {
+    async fn visit_hub_item(&mut self, _item: &ObjectId) -> BuckyResult<()> {
+        Ok(())
+    }
+
+    async fn visit_map_item(&mut self, _key: &str, item: &ObjectId) -> BuckyResult<()> {
+        self.on_item(item);
+        Ok(())
+    }
+
+    async fn visit_set_item(&mut self, item: &ObjectId) -> BuckyResult<()> {
+        self.on_item(item);
+        Ok(())
+    }
+
+    async fn visit_diff_map_item(
+        &mut self,
+        _key: &str,
+        _item: &ObjectMapDiffMapItem,
+    ) -> BuckyResult<()> {
+        Ok(())
+    }
+
+    async fn visit_diff_set_item(&mut self, _item: &ObjectMapDiffSetItem) -> BuckyResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectMapVisitLoader for LocalCacheUsageCounter {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    async fn get_object_map(&mut self, id: &ObjectId) -> BuckyResult<Option<ObjectMapRef>> {
+        self.cache.get_object_map(id).await
+    }
+}
+
+impl ObjectMapVisitorProvider for LocalCacheUsageCounter {}
+
+struct LocalCacheUsageItem {
+    dec_root: ObjectId,
+    items: u64,
+    update_at: Timestamp,
+}
+
+struct LocalCacheNamespaceManagerInner {
+    local_service: GlobalStateLocalService,
+    acl: AclManagerRef,
+    config: StackGlobalConfig,
+
+    // dec_id -> 最近一次统计的用量
+    usage: Mutex<HashMap<ObjectId, LocalCacheUsageItem>>,
+}
+
+// local_cache按dec隔离：每个dec只能操作自己的根，跨dec访问需要local_cache的rmeta授权
+// 每个dec的写入受dynamic_config里面的配额限制，防止一个dec耗尽整个local_cache
+// 只作用于对外的http接口，协议栈内部模块直接使用GlobalStateLocalService
+#[derive(Clone)]
+pub struct LocalCacheNamespaceManager(Arc<LocalCacheNamespaceManagerInner>);
+
+impl LocalCacheNamespaceManager {
+    pub(crate) fn new(
+        local_service: GlobalStateLocalService,
+        acl: AclManagerRef,
+        config: StackGlobalConfig,
+    ) -> Self {
+        assert_eq!(
+            local_service.state().category(),
+            GlobalStateCategory::LocalCache
+        );
+
+        Self(Arc::new(LocalCacheNamespaceManagerInner {
+            local_service,
+            acl,
+            config,
+            usage: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    fn state(&self) -> &GlobalStateRef {
+        self.0.local_service.state()
+    }
+
+    fn quota(&self) -> u64 {
+        self.0.config.dynamic_config().local_cache().dec_quota
+    }
+
+    pub fn clone_global_state_processor(&self) -> GlobalStateInputProcessorRef {
+        let processor = LocalCacheIsolateInputProcessor {
+            manager: self.clone(),
+            next: self.0.local_service.clone_global_state_processor(),
+        };
+
+        Arc::new(Box::new(processor))
+    }
+
+    pub fn clone_op_env_processor(&self) -> OpEnvInputProcessorRef {
+        OpEnvLocalCacheIsolateInputProcessor::new(
+            self.clone(),
+            self.0.local_service.clone_op_env_processor(),
+        )
+    }
+
+    pub fn clone_accessor_processor(&self) -> GlobalStateAccessorInputProcessorRef {
+        let processor = LocalCacheAccessorIsolateInputProcessor {
+            manager: self.clone(),
+            next: self.0.local_service.clone_accessor_processor(),
+        };
+
+        Arc::new(Box::new(processor))
+    }
+
+    // 同dec和system dec直接放行，跨dec需要目标dec在local_cache上的rmeta授权
+    async fn check_access(
+        &self,
+        source: &RequestSourceInfo,
+        target_dec_id: &Option<ObjectId>,
+        path: &str,
+        permissions: impl Into<AccessPermissions>,
+    ) -> BuckyResult<()> {
+        if source.check_target_dec_permission(target_dec_id) {
+            return Ok(());
+        }
+
+        let (req_path, req_query_string) =
+            RequestGlobalStatePath::parse_req_path_with_query_string_owned(path);
+        let global_state = RequestGlobalStatePath {
+            global_state_category: Some(GlobalStateCategory::LocalCache),
+            global_state_root: None,
+            dec_id: target_dec_id.clone(),
+            req_path: Some(req_path),
+            req_query_string,
+        };
+
+        self.0
+            .acl
+            .global_state_meta()
+            .check_access(source, &global_state, permissions)
+            .await
+    }
+
+    async fn dec_root_manager(
+        &self,
+        dec_id: &ObjectId,
+    ) -> BuckyResult<Option<ObjectMapRootManagerRef>> {
+        match self.state().get_dec_root_manager(dec_id, false).await {
+            Ok(manager) => Ok(Some(manager)),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn check_quota(&self, service: &str, dec_id: &ObjectId) -> BuckyResult<()> {
+        let quota = self.quota();
+        if quota == 0 || dec_id == cyfs_core::get_system_dec_app() {
+            return Ok(());
+        }
+
+        let items = match self.usage(dec_id, false).await? {
+            Some((_, items)) => items,
+            None => return Ok(()),
+        };
+
+        if items >= quota {
+            let msg = format!(
+                "local cache quota of dec exceeded! service={}, dec={}, items={}, quota={}",
+                service, dec_id, items, quota
+            );
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
+
+        Ok(())
+    }
+
+    // 返回(dec_root, items)，dec还没有创建根状态则返回None
+    // dec根变化后按刷新间隔重新统计，force为true时忽略刷新间隔
+    async fn usage(&self, dec_id: &ObjectId, force: bool) -> BuckyResult<Option<(ObjectId, u64)>> {
+        let dec_root_manager = match self.dec_root_manager(dec_id).await? {
+            Some(manager) => manager,
+            None => return Ok(None),
+        };
+        let dec_root = dec_root_manager.get_current_root();
+
+        let now = bucky_time_now();
+        {
+            let usage = self.0.usage.lock().unwrap();
+            if let Some(item) = usage.get(dec_id) {
+                if item.dec_root == dec_root {
+                    return Ok(Some((dec_root, item.items)));
+                }
+
+                let interval = self
+                    .0
+                    .config
+                    .dynamic_config()
+                    .local_cache()
+                    .usage_refresh_interval_secs;
+                if !force && now < item.update_at + interval * 1000 * 1000 {
+                    return Ok(Some((item.dec_root.clone(), item.items)));
+                }
+            }
+        }
+
+        let items = LocalCacheUsageCounter::count(dec_root_manager.root_cache().clone(), &dec_root)
+            .await
+            .map_err(|e| {
+                error!(
+                    "count local cache usage of dec failed! dec={}, dec_root={}, {}",
+                    dec_id, dec_root, e
+                );
+                e
+            })?;
+
+        debug!(
+            "count local cache usage of dec: dec={}, dec_root={}, items={}",
+            dec_id, dec_root, items
+        );
+
+        self.0.usage.lock().unwrap().insert(
+            dec_id.to_owned(),
+            LocalCacheUsageItem {
+                dec_root: dec_root.clone(),
+                items,
+                update_at: now,
+            },
+        );
+
+        Ok(Some((dec_root, items)))
+    }
+
+    // 列举local_cache里面所有dec的用量
+    pub async fn list(&self) -> BuckyResult<Vec<LocalCacheNamespaceInfo>> {
+        let quota = self.quota();
+        let root_info = self.state().get_dec_root_info_list().await?;
+
+        let mut list = Vec::with_capacity(root_info.dec_list.len());
+        for item in root_info.dec_list {
+            let (dec_root, items) = match self.usage(&item.dec_id, true).await? {
+                Some(ret) => ret,
+                None => (item.dec_root, 0),
+            };
+
+            list.push(LocalCacheNamespaceInfo {
+                dec_id: item.dec_id,
+                dec_root,
+                items,
+                quota,
+            });
+        }
+
+        Ok(list)
+    }
+
+    pub async fn get(&self, dec_id: &ObjectId) -> BuckyResult<Option<LocalCacheNamespaceInfo>> {
+        let ret =
+            self.usage(dec_id, true)
+                .await?
+                .map(|(dec_root, items)| LocalCacheNamespaceInfo {
+                    dec_id: dec_id.to_owned(),
+                    dec_root,
+                    items,
+                    quota: self.quota(),
+                });
+
+        Ok(ret)
+    }
+
+    // 清空dec在local_cache里面的所有内容，返回移除的顶层条目数
+    pub async fn clear(&self, dec_id: &ObjectId) -> BuckyResult<usize> {
+        let dec_root_manager = match self.dec_root_manager(dec_id).await? {
+            Some(manager) => manager,
+            None => {
+                info!("clear local cache of dec but not exists! dec={}", dec_id);
+                return Ok(0);
+            }
+        };
+        let env = dec_root_manager.create_op_env(None)?;
+
+        let list = env.list("/").await?;
+        let mut count = 0;
+        for item in list.list {
+            match item {
+                ObjectMapContentItem::Map((key, _)) => {
+                    env.remove_with_key("/", &key, &None).await?;
+                    count += 1;
+                }
+                v @ _ => {
+                    error!(
+                        "invalid local cache dec root value type: dec={}, {:?}",
+                        dec_id,
+                        v.content_type()
+                    );
+                }
+            }
+        }
+
+        let dec_root = env.commit().await.map_err(|e| {
+            error!(
+                "clear local cache of dec but commit failed! dec={}, {}",
+                dec_id, e
+            );
+            e
+        })?;
+
+        self.0.usage.lock().unwrap().remove(dec_id);
+
+        info!(
+            "clear local cache of dec success! dec={}, removed={}, dec_root={}",
+            dec_id, count, dec_root
+        );
+
+        Ok(count)
+    }
+}
+
+struct LocalCacheIsolateInputProcessor {
+    manager: LocalCacheNamespaceManager,
+    next: GlobalStateInputProcessorRef,
+}
+
+#[async_trait::async_trait]
+impl GlobalStateInputProcessor for LocalCacheIsolateInputProcessor {
+    fn create_op_env_processor(&self) -> OpEnvInputProcessorRef {
+        OpEnvLocalCacheIsolateInputProcessor::new(
+            self.manager.clone(),
+            self.next.create_op_env_processor(),
+        )
+    }
+
+    fn get_category(&self) -> GlobalStateCategory {
+        self.next.get_category()
+    }
+
+    async fn get_current_root(
+        &self,
+        req: RootStateGetCurrentRootInputRequest,
+    ) -> BuckyResult<RootStateGetCurrentRootInputResponse> {
+        self.manager
+            .check_access(
+                &req.common.source,
+                &req.common.target_dec_id,
+                "/",
+                RequestOpType::Read,
+            )
+            .await?;
+
+        self.next.get_current_root(req).await
+    }
+
+    async fn create_op_env(
+        &self,
+        req: RootStateCreateOpEnvInputRequest,
+    ) -> BuckyResult<RootStateCreateOpEnvInputResponse> {
+        if !req
+            .common
+            .source
+            .check_target_dec_permission(&req.common.target_dec_id)
+        {
+            if req.access.is_none() {
+                let msg = format!(
+                    "local cache op_env between different dec should specified the access param! source={}, target={:?}",
+                    req.common.source, req.common.target_dec_id,
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+            }
+
+            let access = req.access.as_ref().unwrap();
+            self.manager
+                .check_access(
+                    &req.common.source,
+                    &req.common.target_dec_id,
+                    &access.path,
+                    access.access,
+                )
+                .await?;
+        }
+
+        self.next.create_op_env(req).await
+    }
+}
+
+struct OpEnvLocalCacheIsolateInputProcessor {
+    manager: LocalCacheNamespaceManager,
+    next: OpEnvInputProcessorRef,
+}
+
+impl OpEnvLocalCacheIsolateInputProcessor {
+    fn new(
+        manager: LocalCacheNamespaceManager,
+        next: OpEnvInputProcessorRef,
+    ) -> OpEnvInputProcessorRef {
+        let ret = Self { manager, next };
+
+        Arc::new(Box::new(ret))
+    }
+
+    // op_env在创建时已经校验过访问权限，并且只能被创建者使用，这里只需要检查写入配额
+    async fn check_quota(
+        &self,
+        service: &str,
+        common: &OpEnvInputRequestCommon,
+    ) -> BuckyResult<()> {
+        let dec_id = GlobalStateLocalService::get_op_env_target_dec_id(common)?;
+        self.manager.check_quota(service, dec_id).await
+    }
+}
+
+#[async_trait::async_trait]
+impl OpEnvInputProcessor for OpEnvLocalCacheIsolateInputProcessor {
+    fn get_category(&self) -> GlobalStateCategory {
+        self.next.get_category()
+    }
+
+    // single_op_env methods
+    async fn load(&self, req: OpEnvLoadInputRequest) -> BuckyResult<()> {
+        self.next.load(req).await
+    }
+
+    async fn load_by_path(&self, req: OpEnvLoadByPathInputRequest) -> BuckyResult<()> {
+        self.manager
+            .check_access(
+                &req.common.source,
+                &req.common.target_dec_id,
+                &req.path,
+                RequestOpType::Read,
+            )
+            .await?;
+
+        self.next.load_by_path(req).await
+    }
+
+    async fn create_new(&self, req: OpEnvCreateNewInputRequest) -> BuckyResult<()> {
+        self.check_quota("op_env.create_new", &req.common).await?;
+
+        self.next.create_new(req).await
+    }
+
+    // lock
+    async fn lock(&self, req: OpEnvLockInputRequest) -> BuckyResult<()> {
+        self.next.lock(req).await
+    }
+
+    // get_current_root
+    async fn get_current_root(
+        &self,
+        req: OpEnvGetCurrentRootInputRequest,
+    ) -> BuckyResult<OpEnvGetCurrentRootInputResponse> {
+        self.next.get_current_root(req).await
+    }
+
+    // transcation
+    async fn commit(&self, req: OpEnvCommitInputRequest) -> BuckyResult<OpEnvCommitInputResponse> {
+        self.next.commit(req).await
+    }
+
+    async fn abort(&self, req: OpEnvAbortInputRequest) -> BuckyResult<()> {
+        self.next.abort(req).await
+    }
+
+    // map methods
+    async fn get_by_key(
+        &self,
+        req: OpEnvGetByKeyInputRequest,
+    ) -> BuckyResult<OpEnvGetByKeyInputResponse> {
+        self.next.get_by_key(req).await
+    }
+
+    async fn insert_with_key(&self, req: OpEnvInsertWithKeyInputRequest) -> BuckyResult<()> {
+        self.check_quota("op_env.insert_with_key", &req.common)
+            .await?;
+
+        self.next.insert_with_key(req).await
+    }
+
+    async fn set_with_key(
+        &self,
+        req: OpEnvSetWithKeyInputRequest,
+    ) -> BuckyResult<OpEnvSetWithKeyInputResponse> {
+        self.check_quota("op_env.set_with_key", &req.common).await?;
+
+        self.next.set_with_key(req).await
+    }
+
+    async fn remove_with_key(
+        &self,
+        req: OpEnvRemoveWithKeyInputRequest,
+    ) -> BuckyResult<OpEnvRemoveWithKeyInputResponse> {
+        self.next.remove_with_key(req).await
+    }
+
+    // set methods
+    async fn contains(
+        &self,
+        req: OpEnvContainsInputRequest,
+    ) -> BuckyResult<OpEnvContainsInputResponse> {
+        self.next.contains(req).await
+    }
+
+    async fn insert(&self, req: OpEnvInsertInputRequest) -> BuckyResult<OpEnvInsertInputResponse> {
+        self.check_quota("op_env.insert", &req.common).await?;
+
+        self.next.insert(req).await
+    }
+
+    async fn remove(&self, req: OpEnvRemoveInputRequest) -> BuckyResult<OpEnvRemoveInputResponse> {
+        self.next.remove(req).await
+    }
+
+    // iterator methods
+    async fn next(&self, req: OpEnvNextInputRequest) -> BuckyResult<OpEnvNextInputResponse> {
+        self.next.next(req).await
+    }
+
+    async fn reset(&self, req: OpEnvResetInputRequest) -> BuckyResult<()> {
+        self.next.reset(req).await
+    }
+
+    async fn list(&self, req: OpEnvListInputRequest) -> BuckyResult<OpEnvListInputResponse> {
+        self.next.list(req).await
+    }
+
+    // metadata
+    async fn metadata(
+        &self,
+        req: OpEnvMetadataInputRequest,
+    ) -> BuckyResult<OpEnvMetadataInputResponse> {
+        self.next.metadata(req).await
+    }
+}
+
+struct LocalCacheAccessorIsolateInputProcessor {
+    manager: LocalCacheNamespaceManager,
+    next: GlobalStateAccessorInputProcessorRef,
+}
+
+#[async_trait::async_trait]
+impl GlobalStateAccessorInputProcessor for LocalCacheAccessorIsolateInputProcessor {
+    async fn get_object_by_path(
+        &self,
+        req: RootStateAccessorGetObjectByPathInputRequest,
+    ) -> BuckyResult<RootStateAccessorGetObjectByPathInputResponse> {
+        self.manager
+            .check_access(
+                &req.common.source,
+                &req.common.target_dec_id,
+                &req.inner_path,
+                RequestOpType::Read,
+            )
+            .await?;
+
+        self.next.get_object_by_path(req).await
+    }
+
+    async fn list(
+        &self,
+        req: RootStateAccessorListInputRequest,
+    ) -> BuckyResult<RootStateAccessorListInputResponse> {
+        self.manager
+            .check_access(
+                &req.common.source,
+                &req.common.target_dec_id,
+                &req.inner_path,
+                RequestOpType::Read,
+            )
+            .await?;
+
+        self.next.list(req).await
+    }
+}
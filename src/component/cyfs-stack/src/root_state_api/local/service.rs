@@ -1,6 +1,7 @@
 use super::super::core::*;
 use super::accessor_service::GlobalStateAccessorService;
 use crate::root_state::*;
+use crate::trash::TrashManagerHolder;
 use cyfs_base::*;
 use cyfs_lib::*;
 
@@ -12,6 +13,8 @@ pub struct GlobalStateLocalService {
 
     // only valid for global_state category
     accessor_service: Arc<GlobalStateAccessorService>,
+
+    trash: TrashManagerHolder,
 }
 
 impl GlobalStateLocalService {
@@ -21,21 +24,27 @@ impl GlobalStateLocalService {
         device_id: &DeviceId,
         owner: Option<ObjectId>,
         noc: NamedObjectCacheRef,
+        trash: TrashManagerHolder,
     ) -> BuckyResult<Self> {
         let global_state = global_state_manager
             .load_global_state(category, device_id.object_id(), owner, true)
             .await?
             .unwrap();
 
-        Ok(Self::new(global_state, noc))
+        Ok(Self::new(global_state, noc, trash))
     }
 
-    fn new(global_state: GlobalStateRef, noc: NamedObjectCacheRef) -> Self {
+    fn new(
+        global_state: GlobalStateRef,
+        noc: NamedObjectCacheRef,
+        trash: TrashManagerHolder,
+    ) -> Self {
         let accessor_service = GlobalStateAccessorService::new(global_state.clone(), noc);
 
         Self {
             global_state,
             accessor_service: Arc::new(accessor_service),
+            trash,
         }
     }
 
@@ -621,7 +630,23 @@ impl OpEnvInputProcessor for GlobalStateLocalService {
             .get_dec_root_manager(dec_id, false)
             .await?;
 
-        let value = match OpEnvSessionIDHelper::get_type(req.common.sid)? {
+        let to_trash = req.common.flags & CYFS_REQUEST_FLAG_DELETE_TO_TRASH != 0;
+        let op_env_type = OpEnvSessionIDHelper::get_type(req.common.sid)?;
+        if to_trash && op_env_type != ObjectMapOpEnvType::Path {
+            let msg = format!(
+                "remove to trash only support on path_op_env! sid={}, type={:?}",
+                req.common.sid, op_env_type
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+        }
+
+        let full_path = match &req.path {
+            Some(path) => format!("{}/{}", path.trim_end_matches('/'), req.key),
+            None => req.key.clone(),
+        };
+
+        let value = match op_env_type {
             ObjectMapOpEnvType::Path => {
                 let op_env = dec_root_manager
                     .managed_envs()
@@ -656,6 +681,22 @@ impl OpEnvInputProcessor for GlobalStateLocalService {
             }
         };
 
+        // 放入回收站失败时返回错误，调用方可以abort当前的op_env
+        if to_trash {
+            if let Some(value) = &value {
+                self.trash
+                    .get()?
+                    .trash_path(
+                        &req.common.source,
+                        self.global_state.category(),
+                        dec_id,
+                        &full_path,
+                        value,
+                    )
+                    .await?;
+            }
+        }
+
         let resp = OpEnvRemoveWithKeyInputResponse { value };
 
         Ok(resp)
@@ -40,9 +40,12 @@ impl GlobalStateAccessorCacheProcessor {
                 level: NONAPILevel::NOC,
                 target: None,
                 flags: 0,
+                deadline: None,
+                request_id: None,
             },
             object: object.clone(),
             access: None,
+            precondition: None,
         };
 
         let _r = self.noc.put_object(put_req).await;
@@ -993,6 +993,7 @@ impl GlobalStateAccessorRequestHandler {
         // extract params from url querys
         let mut page_index: Option<u32> = None;
         let mut page_size: Option<u32> = None;
+        let mut cursor: Option<PageCursor> = None;
         let mut action = GlobalStateAccessorAction::GetObjectByPath;
 
         let pairs = req.request.url().query_pairs();
@@ -1018,6 +1019,9 @@ impl GlobalStateAccessorRequestHandler {
                     })?;
                     page_size = Some(v);
                 }
+                "cursor" => {
+                    cursor = Some(PageCursor::from_str(v.as_ref())?);
+                }
                 _ => {
                     user_pairs.push(format!("{}={}", k, v));
                 }
@@ -1053,6 +1057,7 @@ impl GlobalStateAccessorRequestHandler {
                     inner_path,
                     page_index,
                     page_size,
+                    cursor,
                 };
                 self.on_list(req).await
             }
@@ -1092,6 +1097,12 @@ impl GlobalStateAccessorRequestHandler {
         http_resp.set_content_type(tide::http::mime::JSON);
         http_resp.insert_header(cyfs_base::CYFS_ROOT, resp.root.to_string());
         http_resp.insert_header(cyfs_base::CYFS_REVISION, resp.revision.to_string());
+        if let Some(next_cursor) = &resp.page.next_cursor {
+            http_resp.insert_header(cyfs_base::CYFS_NEXT_CURSOR, next_cursor.as_str());
+        }
+        if let Some(total_hint) = &resp.page.total_hint {
+            http_resp.insert_header(cyfs_base::CYFS_TOTAL_HINT, total_hint.to_string());
+        }
 
         Ok(http_resp.into())
     }
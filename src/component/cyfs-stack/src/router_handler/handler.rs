@@ -18,6 +18,9 @@ where
 {
     pub index: i32,
 
+    // 注册顺序，在添加到handler列表时分配，index相同的按此排序
+    pub seq: u64,
+
     pub id: String,
 
     pub dec_id: Option<ObjectId>,
@@ -26,6 +29,9 @@ where
 
     pub filter: Option<ExpEvaluator>,
 
+    // filter表达式的必要条件，在完整求值前用以快速排除
+    pub filter_conditions: Vec<ExpRequiredCondition>,
+
     pub default_action: RouterHandlerAction,
 
     pub routine: Option<
@@ -97,6 +103,8 @@ where
             None => None,
         };
 
+        let filter_conditions = Self::compile_filter(&filter);
+
         let id = id.into();
         if req_path.is_none() && filter.is_none() {
             let msg = format!(
@@ -111,7 +119,9 @@ where
             id,
             dec_id,
             index,
+            seq: 0,
             filter,
+            filter_conditions,
             req_path,
             default_action,
             routine,
@@ -119,6 +129,89 @@ where
 
         Ok(handler)
     }
+
+    fn compile_filter(filter: &Option<ExpEvaluator>) -> Vec<ExpRequiredCondition> {
+        match filter {
+            Some(filter) => {
+                let conditions = filter.required_conditions();
+                trace!(
+                    "compile router handler filter: filter={}, conditions={:?}",
+                    filter,
+                    conditions
+                );
+                conditions
+            }
+            None => vec![],
+        }
+    }
+
+    // 优先级由index决定，index相同的按照注册顺序，先注册的优先
+    fn cmp_priority(&self, other: &Self) -> std::cmp::Ordering {
+        self.index
+            .cmp(&other.index)
+            .then_with(|| self.seq.cmp(&other.seq))
+    }
+
+    // 判断请求是否命中handler，至少需要匹配一个条件
+    fn is_match(
+        &self,
+        req_path: &Option<RequestGlobalStatePath>,
+        translator: &impl ExpReservedTokenTranslator,
+    ) -> bool {
+        let mut exec = false;
+
+        // first match req_path
+        if let Some(handler_req_path) = &self.req_path {
+            if let Some(req_path) = req_path {
+                if !handler_req_path.match_target(req_path) {
+                    return false;
+                }
+                exec = true;
+            }
+        }
+
+        if let Some(filter) = &self.filter {
+            // then match the compiled conditions, and the dynamic filter at last
+            if !self
+                .filter_conditions
+                .iter()
+                .all(|c| c.is_match(translator))
+            {
+                return false;
+            }
+
+            if !filter.eval(translator).unwrap() {
+                return false;
+            }
+            exec = true;
+        }
+
+        exec
+    }
+}
+
+// dry-run的匹配结果
+#[derive(Debug, Clone)]
+pub struct RouterHandlerMatchInfo {
+    pub id: String,
+    pub dec_id: Option<ObjectId>,
+    pub index: i32,
+    pub default_action: RouterHandlerAction,
+    pub has_routine: bool,
+}
+
+impl JsonCodec<RouterHandlerMatchInfo> for RouterHandlerMatchInfo {
+    fn encode_json(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut obj = serde_json::Map::new();
+
+        JsonCodecHelper::encode_string_field(&mut obj, "id", &self.id);
+        JsonCodecHelper::encode_option_string_field(&mut obj, "dec_id", self.dec_id.as_ref());
+        JsonCodecHelper::encode_number_field(&mut obj, "index", self.index);
+        JsonCodecHelper::encode_string_field(&mut obj, "default_action", &self.default_action);
+        JsonCodecHelper::encode_bool_field(&mut obj, "has_routine", self.has_routine);
+
+        obj
+    }
 }
 
 pub(crate) struct RouterHandlersImpl<REQ, RESP>
@@ -129,6 +222,9 @@ where
 {
     chain: RouterHandlerChain,
     handler_list: Vec<Arc<RouterHandler<REQ, RESP>>>,
+
+    // 下一个注册的handler的seq
+    next_seq: u64,
 }
 
 impl<REQ, RESP> RouterHandlersImpl<REQ, RESP>
@@ -141,6 +237,7 @@ where
         Self {
             chain,
             handler_list: Vec::new(),
+            next_seq: 0,
         }
     }
 
@@ -152,13 +249,14 @@ where
         RouterHandlerRequest::<REQ, RESP>::category()
     }
 
-    pub fn add_handler(&mut self, handler: RouterHandler<REQ, RESP>) -> BuckyResult<bool> {
-        let handler = Arc::new(handler);
-
+    pub fn add_handler(&mut self, mut handler: RouterHandler<REQ, RESP>) -> BuckyResult<bool> {
         let changed = (|| {
             for i in 0..self.handler_list.len() {
                 let cur = &self.handler_list[i];
                 if cur.compare_id(&handler) {
+                    // 替换的handler保持原有的注册顺序
+                    handler.seq = cur.seq;
+
                     // 比较是否相同
                     let changed;
                     if cur.eq(&handler) {
@@ -186,7 +284,7 @@ where
                         changed = true;
                     }
                     // 无论是否相同，都直接替换，因为routine可能变化了
-                    self.handler_list[i] = handler;
+                    self.handler_list[i] = Arc::new(handler);
                     return changed;
                 }
             }
@@ -203,15 +301,16 @@ where
                 handler.default_action,
                 handler.routine.is_some(),
             );
-            self.handler_list.push(handler);
+            handler.seq = self.next_seq;
+            self.next_seq += 1;
+            self.handler_list.push(Arc::new(handler));
 
             true
         })();
 
         if changed {
-            // 按照index排序，必须是稳定算法
-            self.handler_list
-                .sort_by(|a, b| a.index.partial_cmp(&b.index).unwrap());
+            // 按照优先级排序
+            self.handler_list.sort_by(|a, b| a.cmp_priority(b));
         }
 
         Ok(changed)
@@ -320,6 +419,7 @@ where
 
             let data = RouterHandlerSavedData {
                 index: item.index,
+                seq: item.seq,
                 dec_id: item.dec_id.clone(),
                 filter: item.filter.as_ref().map(|v| v.exp().to_owned()),
                 req_path: item.req_path.as_ref().map(|v| v.format_string()),
@@ -332,6 +432,10 @@ where
     }
 
     pub(crate) fn load_data(&mut self, list: BTreeMap<String, RouterHandlerSavedData>) {
+        // 按保存的注册顺序重新添加，保持index相同的handler之间的顺序
+        let mut list: Vec<_> = list.into_iter().collect();
+        list.sort_by(|a, b| a.1.seq.cmp(&b.1.seq));
+
         for (id, item) in list.into_iter() {
             if let Err(e) = self.add_handler_from_saved_data(id, item) {
                 error!(
@@ -377,11 +481,15 @@ where
             None => None,
         };
 
+        let filter_conditions = RouterHandler::<REQ, RESP>::compile_filter(&filter);
+
         let handler = RouterHandler::<REQ, RESP> {
             id,
             dec_id: data.dec_id,
             index: data.index,
+            seq: 0,
             filter,
+            filter_conditions,
             req_path,
             default_action: RouterHandlerAction::from_str(&data.default_action)?,
             routine: None,
@@ -459,6 +567,37 @@ where
         RouterHandlerEmitter::<REQ, RESP>::new_with_specified(self, id)
     }
 
+    // 按优先级返回会命中请求的所有handler，不触发handler的routine
+    // dec_id不为空时只计算该dec注册的handler
+    pub fn dry_run(
+        &self,
+        dec_id: Option<&ObjectId>,
+        req_path: &Option<RequestGlobalStatePath>,
+        param: &RouterHandlerRequest<REQ, RESP>,
+    ) -> Vec<RouterHandlerMatchInfo> {
+        let handler_list = {
+            let inner = self.handlers.lock().unwrap();
+            inner.handler_list.clone()
+        };
+
+        let translator = ExpReservedTokenCachedTranslator::new(param);
+        handler_list
+            .iter()
+            .filter(|handler| match dec_id {
+                Some(dec_id) => handler.dec_id.as_ref() == Some(dec_id),
+                None => true,
+            })
+            .filter(|handler| handler.is_match(req_path, &translator))
+            .map(|handler| RouterHandlerMatchInfo {
+                id: handler.id.clone(),
+                dec_id: handler.dec_id.clone(),
+                index: handler.index,
+                default_action: handler.default_action.clone(),
+                has_routine: handler.routine.is_some(),
+            })
+            .collect()
+    }
+
     pub(crate) fn dump_data(&self) -> Option<BTreeMap<String, RouterHandlerSavedData>> {
         let inner = self.handlers.lock().unwrap();
         inner.dump_data()
@@ -527,6 +666,7 @@ where
         req_path: &Option<RequestGlobalStatePath>,
         param: &RouterHandlerRequest<REQ, RESP>,
     ) -> Option<Arc<RouterHandler<REQ, RESP>>> {
+        let translator = ExpReservedTokenCachedTranslator::new(param);
         while self.next_index < self.handler_list.len() {
             let handler = &self.handler_list[self.next_index];
             self.next_index += 1;
@@ -541,27 +681,7 @@ where
             );
 
             // execute at least one condition!!
-            let mut exec = false;
-
-            // first match req_path
-            if let Some(handler_req_path) = &handler.req_path {
-                if let Some(req_path) = req_path {
-                    if !handler_req_path.match_target(req_path) {
-                        continue;
-                    }
-                    exec = true;
-                }
-            }
-
-            if let Some(filter) = &handler.filter {
-                // then match the dynamic filter
-                if !filter.eval(param).unwrap() {
-                    continue;
-                }
-                exec = true;
-            }
-
-            if exec {
+            if handler.is_match(req_path, &translator) {
                 debug!(
                     "router handler select filter: chain={}, category={}, param={}, handler={}",
                     self.chain, self.category, param, handler.id
@@ -593,3 +713,56 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::{DecApp, DecAppObj};
+
+    type TestHandler = RouterHandler<NONPutObjectInputRequest, NONPutObjectInputResponse>;
+    type TestHandlers = RouterHandlersImpl<NONPutObjectInputRequest, NONPutObjectInputResponse>;
+
+    fn new_handler(id: &str, dec_id: &ObjectId, index: i32) -> TestHandler {
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id.clone()));
+        RouterHandler::new(
+            &source,
+            id,
+            Some(dec_id.clone()),
+            index,
+            None,
+            Some("/test".to_owned()),
+            RouterHandlerAction::Default,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn ids(handlers: &TestHandlers) -> Vec<&str> {
+        handlers
+            .handler_list
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_priority() {
+        let dec_id = DecApp::generate_id(ObjectId::default(), "test-handler");
+        let mut handlers = TestHandlers::new(RouterHandlerChain::PreNOC);
+
+        handlers.add_handler(new_handler("c", &dec_id, 0)).unwrap();
+        handlers.add_handler(new_handler("a", &dec_id, 0)).unwrap();
+        handlers.add_handler(new_handler("b", &dec_id, -1)).unwrap();
+        assert_eq!(ids(&handlers), vec!["b", "c", "a"]);
+
+        // 替换不改变注册顺序
+        handlers.add_handler(new_handler("c", &dec_id, 0)).unwrap();
+        assert_eq!(ids(&handlers), vec!["b", "c", "a"]);
+
+        // 重新加载后保持顺序
+        let data = handlers.dump_data().unwrap();
+        let mut loaded = TestHandlers::new(RouterHandlerChain::PreNOC);
+        loaded.load_data(data);
+        assert_eq!(ids(&loaded), vec!["b", "c", "a"]);
+    }
+}
@@ -1,9 +1,11 @@
-use super::super::RouterHandlersManager;
+use super::super::{RouterHandlerMatchInfo, RouterHandlersManager};
 use super::processor::*;
 use crate::zone::ZoneManagerRef;
 use cyfs_base::*;
 use cyfs_lib::*;
 
+use std::str::FromStr;
+
 #[derive(Clone)]
 pub(crate) struct RouterHandlerHttpHandler {
     protocol: RequestProtocol,
@@ -76,6 +78,25 @@ impl RouterHandlerHttpHandler {
         }
     }
 
+    pub async fn process_dry_run<State>(
+        &self,
+        req: tide::Request<State>,
+        body: String,
+    ) -> tide::Response {
+        match self.on_dry_run_request(req, body).await {
+            Ok(list) => {
+                let mut http_resp = RequestorHelper::new_response(tide::StatusCode::Ok);
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(JsonCodecHelper::encode_to_array(&list).to_string());
+                http_resp.into()
+            }
+            Err(e) => {
+                error!("router handler dry-run error: {}", e);
+                RequestorHelper::trans_error(e)
+            }
+        }
+    }
+
     pub async fn process_remove_handler<State>(
         &self,
         req: tide::Request<State>,
@@ -131,6 +152,73 @@ impl RouterHandlerHttpHandler {
         self.processor.on_add_handler_request(source, add_req).await
     }
 
+    // body: {"req_path": "...", "request": {...}, "response": {...}}
+    async fn on_dry_run_request<State>(
+        &self,
+        req: tide::Request<State>,
+        body: String,
+    ) -> BuckyResult<Vec<RouterHandlerMatchInfo>> {
+        let chain: RouterHandlerChain = req
+            .param("handler_chain")
+            .map_err(|e| {
+                let msg = format!("invalid handler_chain: {}", e);
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+            })?
+            .parse()?;
+
+        let category: RouterHandlerCategory = req
+            .param("handler_category")
+            .map_err(|e| {
+                let msg = format!("invalid handler_category: {}", e);
+                error!("{}", msg);
+
+                BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+            })?
+            .parse()?;
+
+        // extrac source dec_id
+        let dec_id = RequestorHelper::decode_optional_header(&req, cyfs_base::CYFS_DEC_ID)?;
+
+        let mut source = self.zone_manager.get_current_source_info(&dec_id).await?;
+        source.protocol = self.protocol;
+
+        let value: serde_json::Value = serde_json::from_str(&body).map_err(|e| {
+            let msg = format!("invalid router handler dry-run body: {}, {}", body, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+        let req_path: Option<String> = match value.as_object() {
+            Some(obj) => JsonCodecHelper::decode_option_string_field(obj, "req_path")?,
+            None => None,
+        };
+        let req_path = match req_path {
+            Some(v) => {
+                let mut req_path = RequestGlobalStatePath::from_str(&v)?;
+                if req_path.dec_id.is_none() {
+                    req_path.dec_id = Some(source.dec.clone());
+                }
+                Some(req_path)
+            }
+            None => None,
+        };
+
+        info!(
+            "recv router handler dry-run request: chain={}, category={}, source={}, req_path={:?}",
+            chain, category, source, req_path
+        );
+
+        let dry_run_req = RouterDryRunHandlerRequest {
+            chain,
+            category,
+            req_path,
+            body,
+        };
+
+        self.processor.on_dry_run_request(source, dry_run_req).await
+    }
+
     async fn on_remove_handler_request<State>(
         &self,
         req: tide::Request<State>,
@@ -7,6 +7,7 @@ use tide::{Response, StatusCode};
 enum RouterHandlerRequestType {
     AddHandler,
     RemoveHandler,
+    DryRun,
 }
 
 pub(crate) struct RouterHandlerRequestHandlerEndpoint {
@@ -26,6 +27,7 @@ impl RouterHandlerRequestHandlerEndpoint {
                 RouterHandlerRequestType::RemoveHandler => {
                     self.handler.process_remove_handler(req, body).await
                 }
+                RouterHandlerRequestType::DryRun => self.handler.process_dry_run(req, body).await,
             },
 
             Err(e) => {
@@ -49,6 +51,11 @@ impl RouterHandlerRequestHandlerEndpoint {
                 handler.clone(),
             ),
         );
+
+        // dry_run
+        server.at("/handler/dryrun/:handler_chain/:handler_category").post(
+            RouterHandlerRequestHandlerEndpoint::new(RouterHandlerRequestType::DryRun, handler.clone()),
+        );
     }
 }
 
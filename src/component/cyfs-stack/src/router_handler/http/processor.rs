@@ -1,4 +1,4 @@
-use super::super::{RouterHandler, RouterHandlersManager};
+use super::super::{RouterHandler, RouterHandlerMatchInfo, RouterHandlers, RouterHandlersManager};
 use super::http_routine::RouterHandlerHttpRoutine;
 use cyfs_base::*;
 use cyfs_lib::*;
@@ -36,6 +36,16 @@ pub(crate) struct RouterRemoveHandlerRequest {
     pub dec_id: Option<ObjectId>,
 }
 
+pub(crate) struct RouterDryRunHandlerRequest {
+    pub chain: RouterHandlerChain,
+    pub category: RouterHandlerCategory,
+
+    pub req_path: Option<RequestGlobalStatePath>,
+
+    // RouterHandlerRequest的json
+    pub body: String,
+}
+
 #[derive(Clone)]
 pub(crate) struct RouterHandlerHttpProcessor {
     manager: RouterHandlersManager,
@@ -260,6 +270,130 @@ impl RouterHandlerHttpProcessor {
         }
     }
 
+    fn dry_run_handlers<REQ, RESP>(
+        handlers: &RouterHandlers<REQ, RESP>,
+        dec_id: Option<&ObjectId>,
+        req_path: &Option<RequestGlobalStatePath>,
+        body: &str,
+    ) -> BuckyResult<Vec<RouterHandlerMatchInfo>>
+    where
+        REQ:
+            Send + Sync + 'static + ExpReservedTokenTranslator + JsonCodec<REQ> + std::fmt::Display,
+        RESP: Send
+            + Sync
+            + 'static
+            + ExpReservedTokenTranslator
+            + JsonCodec<RESP>
+            + std::fmt::Display,
+        RouterHandlerRequest<REQ, RESP>: ExpReservedTokenTranslator + RouterHandlerCategoryInfo,
+    {
+        let param = RouterHandlerRequest::<REQ, RESP>::decode_string(body)?;
+
+        Ok(handlers.dry_run(dec_id, req_path, &param))
+    }
+
+    // 只计算会命中的handler列表，不会触发handler
+    pub async fn on_dry_run_request(
+        &self,
+        source: RequestSourceInfo,
+        req: RouterDryRunHandlerRequest,
+    ) -> BuckyResult<Vec<RouterHandlerMatchInfo>> {
+        // handler列表包含了其它dec的信息，非系统dec只能计算自己注册的handler
+        let dec_id = if source.is_system_dec() {
+            None
+        } else {
+            Some(&source.dec)
+        };
+
+        let handlers = self.manager.handlers(&req.chain);
+        match req.category {
+            RouterHandlerCategory::PutObject => Self::dry_run_handlers::<NONPutObjectInputRequest, NONPutObjectInputResponse>(
+                handlers.put_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::GetObject => Self::dry_run_handlers::<NONGetObjectInputRequest, NONGetObjectInputResponse>(
+                handlers.get_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::PostObject => Self::dry_run_handlers::<NONPostObjectInputRequest, NONPostObjectInputResponse>(
+                handlers.post_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::SelectObject => Self::dry_run_handlers::<NONSelectObjectInputRequest, NONSelectObjectInputResponse>(
+                handlers.select_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::DeleteObject => Self::dry_run_handlers::<NONDeleteObjectInputRequest, NONDeleteObjectInputResponse>(
+                handlers.delete_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::GetData => Self::dry_run_handlers::<NDNGetDataInputRequest, NDNGetDataInputResponse>(
+                handlers.get_data(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::PutData => Self::dry_run_handlers::<NDNPutDataInputRequest, NDNPutDataInputResponse>(
+                handlers.put_data(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::DeleteData => Self::dry_run_handlers::<NDNDeleteDataInputRequest, NDNDeleteDataInputResponse>(
+                handlers.delete_data(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::SignObject => Self::dry_run_handlers::<CryptoSignObjectInputRequest, CryptoSignObjectInputResponse>(
+                handlers.sign_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::VerifyObject => Self::dry_run_handlers::<CryptoVerifyObjectInputRequest, CryptoVerifyObjectInputResponse>(
+                handlers.verify_object(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::EncryptData => Self::dry_run_handlers::<CryptoEncryptDataInputRequest, CryptoEncryptDataInputResponse>(
+                handlers.encrypt_data(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::DecryptData => Self::dry_run_handlers::<CryptoDecryptDataInputRequest, CryptoDecryptDataInputResponse>(
+                handlers.decrypt_data(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::Acl => Self::dry_run_handlers::<AclHandlerRequest, AclHandlerResponse>(
+                handlers.acl(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+            RouterHandlerCategory::Interest => Self::dry_run_handlers::<InterestHandlerRequest, InterestHandlerResponse>(
+                handlers.interest(),
+                dec_id,
+                &req.req_path,
+                &req.body,
+            ),
+        }
+    }
+
     pub async fn on_remove_handler_request(
         &self,
         req: RouterRemoveHandlerRequest,
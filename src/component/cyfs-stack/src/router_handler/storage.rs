@@ -8,6 +8,10 @@ use cyfs_debug::Mutex;
 pub(crate) struct RouterHandlerSavedData {
     pub index: i32,
 
+    // 注册顺序，旧数据没有该字段的按id顺序加载
+    #[serde(default)]
+    pub seq: u64,
+
     pub filter: Option<String>,
 
     pub req_path: Option<String>,
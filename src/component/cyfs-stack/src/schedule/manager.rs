@@ -0,0 +1,616 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+// 定时任务保存在ood的root_state系统dec下:
+// /schedule/{dec_id}/{name}/job -> 任务text_id
+// /schedule/{dec_id}/{name}/history/{start_time} -> 执行记录text_id
+const SCHEDULE_ROOT_PATH: &str = "/schedule";
+
+// Text对象id，header为任务名，value为JobRecord的json
+const SCHEDULE_JOB_TEXT_ID: &str = "schedule_job";
+
+// Text对象id，header为任务名，value为ScheduleJobRun的json
+const SCHEDULE_RUN_TEXT_ID: &str = "schedule_run";
+
+// 检查到期任务的间隔
+const SCHEDULE_CHECK_INTERVAL_SECS: u64 = 30;
+
+// 单次触发post的超时时间
+const SCHEDULE_RUN_TIMEOUT_SECS: u64 = 60;
+
+// 同一次触发最多尝试的次数，全部失败后跳到下一次触发
+const SCHEDULE_MAX_ATTEMPTS: u32 = 5;
+
+// 失败重试的退避时间，按次数翻倍
+const SCHEDULE_RETRY_MIN_SECS: u64 = 60;
+const SCHEDULE_RETRY_MAX_SECS: u64 = 60 * 60;
+
+// 每个任务保留的执行记录条数
+const SCHEDULE_HISTORY_MAX: usize = 50;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct JobRecord {
+    info: ScheduleJobInfo,
+
+    // 当前这次触发已经失败的次数
+    attempts: u32,
+
+    // 失败后的重试时间，为空表示按next_run_time触发
+    retry_time: Option<u64>,
+}
+
+struct ScheduleManagerInner {
+    zone_manager: ZoneManagerRef,
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+}
+
+// dec注册的定时任务，持久化在ood上，由active ood的协议栈负责触发
+// 触发时把payload对象post到目标设备上dec的req_path，失败会退避重试，保证至少执行一次
+#[derive(Clone)]
+pub(crate) struct ScheduleManager(Arc<ScheduleManagerInner>);
+
+impl ScheduleManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = ScheduleManagerInner {
+            zone_manager: zone_manager.clone(),
+            non,
+            root_state_stub,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run().await;
+        });
+    }
+
+    async fn run(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(SCHEDULE_CHECK_INTERVAL_SECS));
+        loop {
+            let _ = interval.next().await;
+
+            // 只有active ood负责触发，避免多个ood重复执行
+            match self.0.zone_manager.get_current_info().await {
+                Ok(info) if info.zone_role.is_active_ood() => {}
+                _ => continue,
+            }
+
+            if let Err(e) = self.run_once().await {
+                error!("check schedule jobs failed! {}", e);
+            }
+        }
+    }
+
+    fn check_name(name: &str) -> BuckyResult<()> {
+        if name.is_empty() || name.contains('/') {
+            let msg = format!("invalid schedule job name: {:?}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    fn dec_path(dec_id: &ObjectId) -> String {
+        format!("{}/{}", SCHEDULE_ROOT_PATH, dec_id)
+    }
+
+    fn job_path(dec_id: &ObjectId, name: &str) -> String {
+        format!("{}/{}/{}", SCHEDULE_ROOT_PATH, dec_id, name)
+    }
+
+    fn history_path(dec_id: &ObjectId, name: &str) -> String {
+        format!("{}/history", Self::job_path(dec_id, name))
+    }
+
+    pub async fn add(
+        &self,
+        source: &RequestSourceInfo,
+        name: &str,
+        cron: &str,
+        req_path: &str,
+        payload_id: ObjectId,
+        payload_raw: Vec<u8>,
+    ) -> BuckyResult<ScheduleJobInfo> {
+        Self::check_name(name)?;
+
+        let cron = CronExpr::parse(cron)?;
+        if req_path.is_empty() {
+            let msg = format!("schedule job req_path should not be empty! name={}", name);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let object = AnyNamedObject::clone_from_slice(&payload_raw)?;
+        let id = object.calculate_id();
+        if id != payload_id {
+            let msg = format!(
+                "schedule job payload id not match! expect={}, got={}",
+                payload_id, id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let now = bucky_time_now();
+        let next_run_time = cron.next_after(now);
+        if next_run_time.is_none() {
+            let msg = format!("schedule job cron will never fire! cron={}", cron);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let req = NONPutObjectOutputRequest::new_noc(payload_id.clone(), payload_raw);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save schedule job payload to noc failed! object={}, {}", payload_id, e);
+            e
+        })?;
+
+        // 任务在发起请求的设备上执行
+        let device_id = match &source.zone.device {
+            Some(device) => device.to_owned(),
+            None => self.0.zone_manager.get_current_device_id().to_owned(),
+        };
+
+        let info = ScheduleJobInfo {
+            dec_id: source.dec.clone(),
+            name: name.to_owned(),
+            cron: cron.to_string(),
+            req_path: req_path.to_owned(),
+            device_id,
+            payload_id,
+            create_time: now,
+            next_run_time,
+            last_run_time: None,
+        };
+
+        let record = JobRecord {
+            info: info.clone(),
+            attempts: 0,
+            retry_time: None,
+        };
+        let text_id = self.save_record(&record).await?;
+
+        let path = Self::job_path(&source.dec, name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let prev = match op_env.set_with_key(&path, "job", &text_id, None, true).await {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("save schedule job failed! dec={}, name={}, {}", source.dec, name, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        info!(
+            "add schedule job success! dec={}, name={}, cron={}, next={:?}",
+            source.dec, name, info.cron, info.next_run_time
+        );
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(info)
+    }
+
+    pub async fn remove(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<Option<ScheduleJobInfo>> {
+        Self::check_name(name)?;
+
+        let (text_id, record) = match self.load_record(dec_id, name).await? {
+            Some(v) => v,
+            None => {
+                warn!("remove schedule job but not found! dec={}, name={}", dec_id, name);
+                return Ok(None);
+            }
+        };
+
+        let history = self.list_items(&Self::history_path(dec_id, name)).await?;
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = match op_env.remove_with_key(&Self::dec_path(dec_id), name, None).await {
+            Ok(v) => v,
+            Err(e) => {
+                error!("remove schedule job failed! dec={}, name={}, {}", dec_id, name, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        if ret.is_none() {
+            return Ok(None);
+        }
+
+        info!("remove schedule job success! dec={}, name={}", dec_id, name);
+
+        self.remove_text(&text_id).await;
+        for (_, text_id) in &history {
+            self.remove_text(text_id).await;
+        }
+
+        Ok(Some(record.info))
+    }
+
+    pub async fn list(&self, dec_id: &ObjectId) -> BuckyResult<Vec<ScheduleJobInfo>> {
+        let names = self.list_items(&Self::dec_path(dec_id)).await?;
+
+        let mut result = Vec::with_capacity(names.len());
+        for (name, _) in names {
+            match self.load_record(dec_id, &name).await {
+                Ok(Some((_, record))) => result.push(record.info),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("load schedule job failed! dec={}, name={}, {}", dec_id, name, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // 最近的执行记录在前
+    pub async fn history(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<Vec<ScheduleJobRun>> {
+        Self::check_name(name)?;
+
+        if self.load_record(dec_id, name).await?.is_none() {
+            let msg = format!("schedule job not found! dec={}, name={}", dec_id, name);
+            warn!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::NotFound, msg));
+        }
+
+        let list = self.list_items(&Self::history_path(dec_id, name)).await?;
+
+        let mut result = Vec::with_capacity(list.len());
+        for (key, text_id) in list.into_iter().rev() {
+            match self
+                .load_text(&text_id)
+                .await
+                .and_then(|text| Self::decode_text(&text, SCHEDULE_RUN_TEXT_ID))
+            {
+                Ok(run) => result.push(run),
+                Err(e) => {
+                    warn!("load schedule job run failed! dec={}, name={}, key={}, {}", dec_id, name, key, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn run_once(&self) -> BuckyResult<()> {
+        let decs = self.list_items(SCHEDULE_ROOT_PATH).await?;
+        for (dec, _) in decs {
+            let dec_id = match ObjectId::from_str(&dec) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            for (name, _) in self.list_items(&Self::dec_path(&dec_id)).await? {
+                if let Err(e) = self.check_job(&dec_id, &name).await {
+                    warn!("check schedule job failed! dec={}, name={}, {}", dec_id, name, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_job(&self, dec_id: &ObjectId, name: &str) -> BuckyResult<()> {
+        let (text_id, mut record) = match self.load_record(dec_id, name).await? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let schedule_time = match record.info.next_run_time {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        // 错过的触发(比如ood停机期间)在恢复后只补执行一次
+        let now = bucky_time_now();
+        if record.retry_time.unwrap_or(schedule_time) > now {
+            return Ok(());
+        }
+
+        record.attempts += 1;
+        let ret = self.execute(&record.info).await;
+        let end_time = bucky_time_now();
+
+        let run = ScheduleJobRun {
+            schedule_time,
+            start_time: now,
+            end_time,
+            attempt: record.attempts,
+            status: match ret {
+                Ok(_) => ScheduleJobRunStatus::Success,
+                Err(_) => ScheduleJobRunStatus::Failed,
+            },
+            error: ret.as_ref().err().map(|e| e.to_string()),
+        };
+
+        match &ret {
+            Ok(_) => {
+                info!(
+                    "run schedule job success! dec={}, name={}, attempt={}",
+                    dec_id, name, record.attempts
+                );
+                record.info.last_run_time = Some(end_time);
+                record.info.next_run_time = Self::next_run_time(&record.info.cron, end_time);
+                record.attempts = 0;
+                record.retry_time = None;
+            }
+            Err(e) if record.attempts >= SCHEDULE_MAX_ATTEMPTS => {
+                error!(
+                    "run schedule job failed, now will skip to next schedule! dec={}, name={}, attempts={}, {}",
+                    dec_id, name, record.attempts, e
+                );
+                record.info.next_run_time = Self::next_run_time(&record.info.cron, end_time);
+                record.attempts = 0;
+                record.retry_time = None;
+            }
+            Err(e) => {
+                let secs = std::cmp::min(
+                    SCHEDULE_RETRY_MIN_SECS << (record.attempts - 1),
+                    SCHEDULE_RETRY_MAX_SECS,
+                );
+                warn!(
+                    "run schedule job failed, will retry after {}s! dec={}, name={}, attempt={}, {}",
+                    secs, dec_id, name, record.attempts, e
+                );
+                record.retry_time = Some(end_time + secs * 1000 * 1000);
+            }
+        }
+
+        self.add_history(dec_id, name, &run).await?;
+
+        // 执行期间任务可能被替换或者删除，这时候不再覆盖
+        let new_text_id = self.save_record(&record).await?;
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        if let Err(e) = op_env
+            .set_with_key(
+                &Self::job_path(dec_id, name),
+                "job",
+                &new_text_id,
+                Some(text_id.clone()),
+                false,
+            )
+            .await
+        {
+            warn!("update schedule job failed! dec={}, name={}, {}", dec_id, name, e);
+            let _ = op_env.abort().await;
+            return Ok(());
+        }
+        op_env.commit().await?;
+
+        if new_text_id != text_id {
+            self.remove_text(&text_id).await;
+        }
+
+        Ok(())
+    }
+
+    fn next_run_time(cron: &str, time: u64) -> Option<u64> {
+        match CronExpr::parse(cron) {
+            Ok(cron) => cron.next_after(time),
+            Err(_) => None,
+        }
+    }
+
+    async fn execute(&self, info: &ScheduleJobInfo) -> BuckyResult<()> {
+        let req = NONGetObjectOutputRequest::new_noc(info.payload_id.clone(), None);
+        let resp = self.0.non.get_object(req).await.map_err(|e| {
+            error!(
+                "load schedule job payload failed! dec={}, name={}, payload={}, {}",
+                info.dec_id, info.name, info.payload_id, e
+            );
+            e
+        })?;
+
+        let mut req = NONPostObjectOutputRequest::new_router(
+            Some(info.device_id.object_id().to_owned()),
+            info.payload_id.clone(),
+            resp.object.object_raw,
+        );
+        req.common.req_path = Some(
+            RequestGlobalStatePath::new(Some(info.dec_id.clone()), Some(info.req_path.clone()))
+                .to_string(),
+        );
+        req.common.deadline = Some(bucky_time_now() + SCHEDULE_RUN_TIMEOUT_SECS * 1000 * 1000);
+
+        self.0.non.post_object(req).await?;
+
+        Ok(())
+    }
+
+    async fn add_history(&self, dec_id: &ObjectId, name: &str, run: &ScheduleJobRun) -> BuckyResult<()> {
+        let value = serde_json::to_string(run).unwrap();
+        let text = Text::build(SCHEDULE_RUN_TEXT_ID, name, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save schedule job run to noc failed! dec={}, name={}, {}", dec_id, name, e);
+            e
+        })?;
+
+        let path = Self::history_path(dec_id, name);
+        let list = self.list_items(&path).await?;
+
+        let key = format!("{:020}", run.start_time);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = async {
+            op_env.set_with_key(&path, &key, &text_id, None, true).await?;
+
+            // 只保留最近的记录
+            let mut removed = vec![];
+            if list.len() >= SCHEDULE_HISTORY_MAX {
+                for (key, _) in &list[..list.len() + 1 - SCHEDULE_HISTORY_MAX] {
+                    if let Some(id) = op_env.remove_with_key(&path, key, None).await? {
+                        removed.push(id);
+                    }
+                }
+            }
+
+            Ok::<_, BuckyError>(removed)
+        }
+        .await;
+
+        let removed = match ret {
+            Ok(v) => v,
+            Err(e) => {
+                error!("save schedule job run failed! dec={}, name={}, {}", dec_id, name, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        for id in &removed {
+            self.remove_text(id).await;
+        }
+
+        Ok(())
+    }
+
+    async fn load_record(
+        &self,
+        dec_id: &ObjectId,
+        name: &str,
+    ) -> BuckyResult<Option<(ObjectId, JobRecord)>> {
+        let path = Self::job_path(dec_id, name);
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&path, "job").await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(v)) => v,
+            Ok(None) => return Ok(None),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(None),
+            Err(e) => {
+                error!("load schedule job failed! dec={}, name={}, {}", dec_id, name, e);
+                return Err(e);
+            }
+        };
+
+        let text = self.load_text(&text_id).await?;
+        let record = Self::decode_text(&text, SCHEDULE_JOB_TEXT_ID)?;
+
+        Ok(Some((text_id, record)))
+    }
+
+    async fn save_record(&self, record: &JobRecord) -> BuckyResult<ObjectId> {
+        let value = serde_json::to_string(record).unwrap();
+        let text = Text::build(SCHEDULE_JOB_TEXT_ID, &record.info.name, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save schedule job to noc failed! dec={}, name={}, {}",
+                record.info.dec_id, record.info.name, e
+            );
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    // 按key排序
+    async fn list_items(&self, path: &str) -> BuckyResult<Vec<(String, ObjectId)>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list schedule items failed! path={}, {}", path, e);
+                return Err(e);
+            }
+        };
+
+        let mut result: Vec<(String, ObjectId)> = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Map(v) => Some(v),
+                _ => None,
+            })
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(result)
+    }
+
+    async fn load_text(&self, text_id: &ObjectId) -> BuckyResult<Text> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        Text::clone_from_slice(&resp.object.object_raw)
+    }
+
+    fn decode_text<T>(text: &Text, id: &str) -> BuckyResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if text.id() != id {
+            let msg = format!("invalid schedule text id: expect={}, got={}", id, text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!("invalid schedule text value! id={}, header={}, {}", id, text.header(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!("remove old schedule text from noc failed! text={}, {}", text_id, e);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ScheduleInputProcessor: Sync + Send + 'static {
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse>;
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse>;
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse>;
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse>;
+}
+
+pub(crate) type ScheduleInputProcessorRef = Arc<dyn ScheduleInputProcessor>;
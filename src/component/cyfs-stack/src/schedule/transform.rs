@@ -0,0 +1,118 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ScheduleInputTransformer {
+    processor: ScheduleOutputProcessorRef,
+}
+
+impl ScheduleInputTransformer {
+    pub fn new(processor: ScheduleOutputProcessorRef) -> ScheduleInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        let out_req = ScheduleAddJobOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+            cron: req.cron,
+            req_path: req.req_path,
+            payload_id: req.payload_id,
+            payload_raw: req.payload_raw,
+        };
+
+        let out_resp = self.processor.add_schedule_job(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        let out_req = ScheduleRemoveJobOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+        };
+
+        let out_resp = self.processor.remove_schedule_job(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        let out_req = ScheduleGetJobsOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_schedule_jobs(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        let out_req = ScheduleGetJobHistoryOutputRequest {
+            common: Self::convert_common(req.common),
+            name: req.name,
+        };
+
+        let out_resp = self.processor.get_schedule_job_history(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ScheduleInputProcessor for ScheduleInputTransformer {
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        Self::add_schedule_job(&self, req).await
+    }
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        Self::remove_schedule_job(&self, req).await
+    }
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        Self::get_schedule_jobs(&self, req).await
+    }
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        Self::get_schedule_job_history(&self, req).await
+    }
+}
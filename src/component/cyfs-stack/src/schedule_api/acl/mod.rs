@@ -0,0 +1,3 @@
+mod schedule_acl;
+
+pub(crate) use schedule_acl::*;
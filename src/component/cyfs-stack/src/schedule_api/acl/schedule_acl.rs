@@ -0,0 +1,77 @@
+use crate::schedule::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ScheduleAclInnerInputProcessor {
+    next: ScheduleInputProcessorRef,
+}
+
+impl ScheduleAclInnerInputProcessor {
+    pub(crate) fn new(next: ScheduleInputProcessorRef) -> ScheduleInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ScheduleInputProcessor for ScheduleAclInnerInputProcessor {
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        self.check_local_zone_permit("schedule.add_schedule_job", &req.common.source)?;
+
+        self.next.add_schedule_job(req).await
+    }
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        self.check_local_zone_permit("schedule.remove_schedule_job", &req.common.source)?;
+
+        self.next.remove_schedule_job(req).await
+    }
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        self.check_local_zone_permit("schedule.get_schedule_jobs", &req.common.source)?;
+
+        self.next.get_schedule_jobs(req).await
+    }
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        self.check_local_zone_permit("schedule.get_schedule_job_history", &req.common.source)?;
+
+        self.next.get_schedule_job_history(req).await
+    }
+}
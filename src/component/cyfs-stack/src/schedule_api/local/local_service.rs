@@ -0,0 +1,103 @@
+use crate::schedule::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalScheduleService {
+    schedule_manager: ScheduleManager,
+}
+
+impl LocalScheduleService {
+    pub(crate) fn new(schedule_manager: ScheduleManager) -> Self {
+        Self { schedule_manager }
+    }
+
+    pub fn clone_processor(&self) -> ScheduleInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        let job = self
+            .schedule_manager
+            .add(
+                &req.common.source,
+                &req.name,
+                &req.cron,
+                &req.req_path,
+                req.payload_id,
+                req.payload_raw,
+            )
+            .await?;
+
+        Ok(ScheduleAddJobInputResponse { job })
+    }
+
+    pub async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        let job = self
+            .schedule_manager
+            .remove(&req.common.source.dec, &req.name)
+            .await?;
+
+        Ok(ScheduleRemoveJobInputResponse { job })
+    }
+
+    pub async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        let list = self.schedule_manager.list(&req.common.source.dec).await?;
+
+        Ok(ScheduleGetJobsInputResponse { list })
+    }
+
+    pub async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        let list = self
+            .schedule_manager
+            .history(&req.common.source.dec, &req.name)
+            .await?;
+
+        Ok(ScheduleGetJobHistoryInputResponse { list })
+    }
+}
+
+#[async_trait::async_trait]
+impl ScheduleInputProcessor for LocalScheduleService {
+    async fn add_schedule_job(
+        &self,
+        req: ScheduleAddJobInputRequest,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        Self::add_schedule_job(self, req).await
+    }
+
+    async fn remove_schedule_job(
+        &self,
+        req: ScheduleRemoveJobInputRequest,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        Self::remove_schedule_job(self, req).await
+    }
+
+    async fn get_schedule_jobs(
+        &self,
+        req: ScheduleGetJobsInputRequest,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        Self::get_schedule_jobs(self, req).await
+    }
+
+    async fn get_schedule_job_history(
+        &self,
+        req: ScheduleGetJobHistoryInputRequest,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        Self::get_schedule_job_history(self, req).await
+    }
+}
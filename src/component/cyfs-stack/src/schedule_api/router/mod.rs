@@ -0,0 +1,3 @@
+mod schedule_service_router;
+
+pub(crate) use schedule_service_router::*;
@@ -0,0 +1,7 @@
+mod schedule_handler;
+mod schedule_listener;
+mod schedule_service;
+
+pub(crate) use schedule_handler::*;
+pub(crate) use schedule_listener::*;
+pub(crate) use schedule_service::*;
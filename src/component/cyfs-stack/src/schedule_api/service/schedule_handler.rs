@@ -0,0 +1,181 @@
+use crate::non::NONInputHttpRequest;
+use crate::schedule::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ScheduleRequestHandler {
+    processor: ScheduleInputProcessorRef,
+}
+
+impl ScheduleRequestHandler {
+    pub fn new(processor: ScheduleInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // add_schedule_job
+    pub async fn process_add_schedule_job_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_add_schedule_job_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_add_schedule_job_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ScheduleAddJobInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("add schedule job failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ScheduleAddJobOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ScheduleAddJobInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            cron: out_req.cron,
+            req_path: out_req.req_path,
+            payload_id: out_req.payload_id,
+            payload_raw: out_req.payload_raw,
+        };
+        self.processor.add_schedule_job(in_req).await
+    }
+
+    // remove_schedule_job
+    pub async fn process_remove_schedule_job_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_remove_schedule_job_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_remove_schedule_job_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ScheduleRemoveJobInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("remove schedule job failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ScheduleRemoveJobOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ScheduleRemoveJobInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+        };
+        self.processor.remove_schedule_job(in_req).await
+    }
+
+    // get_schedule_jobs
+    pub async fn process_get_schedule_jobs_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_schedule_jobs_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_schedule_jobs_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ScheduleGetJobsInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get schedule jobs failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ScheduleGetJobsOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ScheduleGetJobsInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+        };
+        self.processor.get_schedule_jobs(in_req).await
+    }
+
+    // get_schedule_job_history
+    pub async fn process_get_schedule_job_history_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_schedule_job_history_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_schedule_job_history_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ScheduleGetJobHistoryInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!(
+                "get schedule job history failed, read body bytes error! {}",
+                e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ScheduleGetJobHistoryOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ScheduleGetJobHistoryInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+        };
+        self.processor.get_schedule_job_history(in_req).await
+    }
+}
@@ -0,0 +1,136 @@
+use super::schedule_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ScheduleRequestType {
+    AddScheduleJob,
+    RemoveScheduleJob,
+    GetScheduleJobs,
+    GetScheduleJobHistory,
+}
+
+pub(crate) struct ScheduleRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ScheduleRequestType,
+    handler: ScheduleRequestHandler,
+}
+
+impl ScheduleRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ScheduleRequestType,
+        handler: ScheduleRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ScheduleRequestType::AddScheduleJob => {
+                self.handler.process_add_schedule_job_request(req).await
+            }
+            ScheduleRequestType::RemoveScheduleJob => {
+                self.handler.process_remove_schedule_job_request(req).await
+            }
+            ScheduleRequestType::GetScheduleJobs => {
+                self.handler.process_get_schedule_jobs_request(req).await
+            }
+            ScheduleRequestType::GetScheduleJobHistory => {
+                self.handler
+                    .process_get_schedule_job_history_request(req)
+                    .await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ScheduleRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // schedule
+        server.at("/schedule/add_job").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::AddScheduleJob,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/add_job/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::AddScheduleJob,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/remove_job").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::RemoveScheduleJob,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/remove_job/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::RemoveScheduleJob,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/jobs").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::GetScheduleJobs,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/jobs/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::GetScheduleJobs,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/job_history").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::GetScheduleJobHistory,
+            handler.clone(),
+        ));
+
+        server.at("/schedule/job_history/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ScheduleRequestType::GetScheduleJobHistory,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ScheduleRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
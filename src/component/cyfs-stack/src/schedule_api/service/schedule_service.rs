@@ -0,0 +1,27 @@
+use super::super::local::LocalScheduleService;
+use super::super::router::ScheduleServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::schedule::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ScheduleService {
+    router: ScheduleInputProcessorRef,
+}
+
+impl ScheduleService {
+    pub(crate) fn new(
+        schedule_manager: ScheduleManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalScheduleService::new(schedule_manager);
+        let router =
+            ScheduleServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ScheduleInputProcessorRef {
+        self.router.clone()
+    }
+}
@@ -0,0 +1,128 @@
+use cyfs_base::*;
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Deserialize)]
+struct ObjectSearchDecConfig {
+    // 为空表示不带dec_id的对象
+    dec_id: Option<String>,
+    obj_types: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ObjectSearchConfigData {
+    #[serde(default)]
+    dec: Vec<ObjectSearchDecConfig>,
+}
+
+// 需要建立索引的对象，按dec配置对象类型，例如
+// [[dec]]
+// dec_id = "9tGpLNnAAYE9Dd4ooNiSjtP5MeL9CNLf9Rxu6AFEc12M"
+// obj_types = [41, 32769]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ObjectSearchConfig {
+    rules: HashMap<Option<ObjectId>, HashSet<u16>>,
+}
+
+impl ObjectSearchConfig {
+    fn config_file(isolate: &str) -> PathBuf {
+        let mut file = cyfs_util::get_cyfs_root_path().join("etc");
+        if isolate.len() > 0 {
+            file.push(isolate);
+        }
+        file.push("search.toml");
+        file
+    }
+
+    // 配置文件不存在返回None，表示不开启搜索服务
+    pub fn load(isolate: &str) -> BuckyResult<Option<Self>> {
+        let file = Self::config_file(isolate);
+        if !file.is_file() {
+            return Ok(None);
+        }
+
+        let value = std::fs::read_to_string(&file).map_err(|e| {
+            let msg = format!(
+                "load search config error! file={}, {}",
+                file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        info!("will load search config: file={}, {}", file.display(), value);
+
+        let config = Self::parse(&value)?;
+        Ok(Some(config))
+    }
+
+    pub fn parse(value: &str) -> BuckyResult<Self> {
+        let data: ObjectSearchConfigData = toml::from_str(value).map_err(|e| {
+            let msg = format!("invalid search config! {}, {}", value, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let mut rules: HashMap<Option<ObjectId>, HashSet<u16>> = HashMap::new();
+        for item in data.dec {
+            let dec_id = match item.dec_id {
+                Some(v) => Some(ObjectId::from_str(&v)?),
+                None => None,
+            };
+
+            rules.entry(dec_id).or_default().extend(item.obj_types);
+        }
+
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.values().all(|list| list.is_empty())
+    }
+
+    pub fn should_index(&self, dec_id: Option<&ObjectId>, obj_type: u16) -> bool {
+        match self.rules.get(&dec_id.cloned()) {
+            Some(list) => list.contains(&obj_type),
+            None => false,
+        }
+    }
+
+    // 所有需要索引的对象类型，用以重建索引
+    pub fn obj_types(&self) -> HashSet<u16> {
+        self.rules.values().flatten().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_config() {
+        let value = r#"
+        [[dec]]
+        dec_id = "9tGpLNnAAYE9Dd4ooNiSjtP5MeL9CNLf9Rxu6AFEc12M"
+        obj_types = [41, 32769]
+
+        [[dec]]
+        obj_types = [1]
+        "#;
+
+        let config = ObjectSearchConfig::parse(value).unwrap();
+        assert!(!config.is_empty());
+
+        let dec_id = ObjectId::from_str("9tGpLNnAAYE9Dd4ooNiSjtP5MeL9CNLf9Rxu6AFEc12M").unwrap();
+        assert!(config.should_index(Some(&dec_id), 41));
+        assert!(!config.should_index(Some(&dec_id), 1));
+        assert!(config.should_index(None, 1));
+        assert!(!config.should_index(None, 41));
+        assert_eq!(config.obj_types().len(), 3);
+
+        let config = ObjectSearchConfig::parse("").unwrap();
+        assert!(config.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+// 从对象里提取出的可搜索内容
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectSearchDocument {
+    pub object_id: ObjectId,
+    pub obj_type: u16,
+    pub dec_id: Option<ObjectId>,
+    pub owner_id: Option<ObjectId>,
+    pub create_time: u64,
+
+    // 全文检索的内容，不支持的对象类型为空，只能通过属性匹配
+    pub text: String,
+}
+
+impl ObjectSearchDocument {
+    pub fn extract(object: &NONObjectInfo) -> BuckyResult<Self> {
+        let any = object.object_if_none_then_decode()?;
+
+        let text = if any.obj_type() == CoreObjectType::Text as u16 {
+            let text = Text::clone_from_slice(&object.object_raw)?;
+            format!("{}\n{}\n{}", text.id(), text.header(), text.value())
+        } else {
+            String::new()
+        };
+
+        Ok(Self {
+            object_id: object.object_id.clone(),
+            obj_type: any.obj_type(),
+            dec_id: any.dec_id().clone(),
+            owner_id: any.owner().clone(),
+            create_time: any.create_time(),
+            text,
+        })
+    }
+}
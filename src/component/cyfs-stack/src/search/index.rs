@@ -0,0 +1,265 @@
+use super::extract::ObjectSearchDocument;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tantivy::collector::{Count, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::*;
+use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
+use tantivy::{Document, Index, IndexReader, IndexWriter, ReloadPolicy, Term};
+
+// 中文没有分词，使用ngram来支持任意子串的匹配
+const SEARCH_TOKENIZER: &str = "cyfs_ngram";
+
+const INDEX_WRITER_HEAP_SIZE: usize = 1024 * 1024 * 32;
+
+const MAX_PAGE_SIZE: u32 = 1024;
+
+struct ObjectSearchFields {
+    object_id: Field,
+    obj_type: Field,
+    dec_id: Field,
+    owner_id: Field,
+    create_time: Field,
+    text: Field,
+}
+
+pub(crate) struct ObjectSearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: ObjectSearchFields,
+}
+
+impl ObjectSearchIndex {
+    pub fn open(dir: &Path) -> BuckyResult<Self> {
+        if !dir.is_dir() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                let msg = format!(
+                    "create search index dir failed! dir={}, {}",
+                    dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+        }
+
+        let (schema, fields) = Self::schema();
+
+        let directory = MmapDirectory::open(dir).map_err(|e| {
+            Self::index_error(format!("open search index dir failed! dir={}", dir.display()), e)
+        })?;
+        let index = Index::open_or_create(directory, schema).map_err(|e| {
+            Self::index_error(format!("open search index failed! dir={}", dir.display()), e)
+        })?;
+
+        let tokenizer = TextAnalyzer::from(NgramTokenizer::new(1, 2, false)).filter(LowerCaser);
+        index.tokenizers().register(SEARCH_TOKENIZER, tokenizer);
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommit)
+            .try_into()
+            .map_err(|e| Self::index_error("create search index reader failed!".to_owned(), e))?;
+
+        let writer = index
+            .writer_with_num_threads(1, INDEX_WRITER_HEAP_SIZE)
+            .map_err(|e| Self::index_error("create search index writer failed!".to_owned(), e))?;
+
+        info!("open search index success! dir={}", dir.display());
+
+        Ok(Self {
+            index,
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    fn schema() -> (Schema, ObjectSearchFields) {
+        let mut builder = Schema::builder();
+
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(SEARCH_TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+
+        let fields = ObjectSearchFields {
+            object_id: builder.add_text_field("object_id", STRING | STORED),
+            obj_type: builder.add_u64_field("obj_type", INDEXED),
+            dec_id: builder.add_text_field("dec_id", STRING),
+            owner_id: builder.add_text_field("owner_id", STRING),
+            create_time: builder.add_u64_field("create_time", INDEXED | FAST),
+            text: builder.add_text_field("text", text_options),
+        };
+
+        (builder.build(), fields)
+    }
+
+    fn index_error(msg: String, e: impl std::fmt::Display) -> BuckyError {
+        let msg = format!("{} {}", msg, e);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::Failed, msg)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reader.searcher().num_docs() == 0
+    }
+
+    // 同一个对象重复添加会覆盖之前的索引
+    pub fn add(&self, doc: &ObjectSearchDocument) -> BuckyResult<()> {
+        let mut document = Document::new();
+        document.add_text(self.fields.object_id, doc.object_id.to_string());
+        document.add_u64(self.fields.obj_type, doc.obj_type as u64);
+        if let Some(dec_id) = &doc.dec_id {
+            document.add_text(self.fields.dec_id, dec_id.to_string());
+        }
+        if let Some(owner_id) = &doc.owner_id {
+            document.add_text(self.fields.owner_id, owner_id.to_string());
+        }
+        document.add_u64(self.fields.create_time, doc.create_time);
+        if !doc.text.is_empty() {
+            document.add_text(self.fields.text, &doc.text);
+        }
+
+        let writer = self.writer.lock().unwrap();
+        writer.delete_term(self.object_id_term(&doc.object_id));
+        writer.add_document(document).map_err(|e| {
+            Self::index_error(format!("add search index failed! object={}", doc.object_id), e)
+        })?;
+
+        Ok(())
+    }
+
+    pub fn remove(&self, object_id: &ObjectId) {
+        let writer = self.writer.lock().unwrap();
+        writer.delete_term(self.object_id_term(object_id));
+    }
+
+    pub fn commit(&self) -> BuckyResult<()> {
+        self.writer
+            .lock()
+            .unwrap()
+            .commit()
+            .map_err(|e| Self::index_error("commit search index failed!".to_owned(), e))?;
+
+        Ok(())
+    }
+
+    fn object_id_term(&self, object_id: &ObjectId) -> Term {
+        Term::from_field_text(self.fields.object_id, &object_id.to_string())
+    }
+
+    pub fn search(
+        &self,
+        query: &str,
+        filter: &SearchObjectFilter,
+        page_index: u32,
+        page_size: u32,
+    ) -> BuckyResult<SearchObjectOutputResponse> {
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            let msg = format!(
+                "invalid search page size! size={}, max={}",
+                page_size, MAX_PAGE_SIZE
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let query = self.build_query(query, filter)?;
+
+        let searcher = self.reader.searcher();
+        let collector = (
+            TopDocs::with_limit(page_size as usize)
+                .and_offset(page_index as usize * page_size as usize),
+            Count,
+        );
+        let (top_docs, total) = searcher
+            .search(&query, &collector)
+            .map_err(|e| Self::index_error("search index failed!".to_owned(), e))?;
+
+        let mut list = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let doc = searcher
+                .doc(address)
+                .map_err(|e| Self::index_error("load search doc failed!".to_owned(), e))?;
+
+            let object_id = doc
+                .get_first(self.fields.object_id)
+                .and_then(|v| v.as_text())
+                .ok_or_else(|| {
+                    let msg = format!("object_id field missing in search doc! {:?}", address);
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::InvalidData, msg)
+                })?;
+
+            list.push(SearchObjectItem {
+                object_id: ObjectId::from_str(object_id)?,
+                score,
+            });
+        }
+
+        Ok(SearchObjectOutputResponse {
+            total: total as u64,
+            list,
+        })
+    }
+
+    fn build_query(
+        &self,
+        query: &str,
+        filter: &SearchObjectFilter,
+    ) -> BuckyResult<Box<dyn Query>> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![];
+
+        let query = query.trim();
+        if !query.is_empty() {
+            let parser = QueryParser::for_index(&self.index, vec![self.fields.text]);
+            let query = parser.parse_query(query).map_err(|e| {
+                let msg = format!("invalid search query! query={}, {}", query, e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+            })?;
+            clauses.push((Occur::Must, query));
+        }
+
+        if let Some(obj_type) = filter.obj_type {
+            let term = Term::from_field_u64(self.fields.obj_type, obj_type as u64);
+            clauses.push((Occur::Must, Self::term_query(term)));
+        }
+        if let Some(dec_id) = &filter.dec_id {
+            let term = Term::from_field_text(self.fields.dec_id, &dec_id.to_string());
+            clauses.push((Occur::Must, Self::term_query(term)));
+        }
+        if let Some(owner_id) = &filter.owner_id {
+            let term = Term::from_field_text(self.fields.owner_id, &owner_id.to_string());
+            clauses.push((Occur::Must, Self::term_query(term)));
+        }
+        if filter.create_time_start.is_some() || filter.create_time_end.is_some() {
+            let range = filter.create_time_start.unwrap_or(0)
+                ..filter.create_time_end.unwrap_or(u64::MAX);
+            clauses.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_u64(self.fields.create_time, range)),
+            ));
+        }
+
+        let query: Box<dyn Query> = if clauses.is_empty() {
+            Box::new(AllQuery)
+        } else {
+            Box::new(BooleanQuery::new(clauses))
+        };
+
+        Ok(query)
+    }
+
+    fn term_query(term: Term) -> Box<dyn Query> {
+        Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+    }
+}
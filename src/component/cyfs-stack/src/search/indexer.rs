@@ -0,0 +1,197 @@
+use super::config::ObjectSearchConfig;
+use super::extract::ObjectSearchDocument;
+use super::index::ObjectSearchIndex;
+use super::searcher::ObjectSearcher;
+use cyfs_base::*;
+use cyfs_lib::*;
+use cyfs_noc::*;
+
+use async_std::channel::{Receiver, Sender};
+use std::sync::Arc;
+
+// 每次提交索引最多合并的变更个数
+const INDEX_COMMIT_BATCH: usize = 1024;
+
+const REBUILD_PAGE_SIZE: usize = 256;
+
+// noc的变更回调在写路径上，这里只投递到队列，由后台任务更新索引
+struct ObjectSearchChangeListener {
+    sender: Sender<NamedObjectCacheChangeEvent>,
+}
+
+impl NamedObjectCacheChangeListener for ObjectSearchChangeListener {
+    fn on_change(&self, event: &NamedObjectCacheChangeEvent) {
+        if let Err(e) = self.sender.try_send(event.clone()) {
+            warn!("post noc change event to search indexer failed! {}, {}", event, e);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ObjectSearchIndexer {
+    config: Arc<ObjectSearchConfig>,
+    index: Arc<ObjectSearchIndex>,
+    noc: NamedObjectCacheRef,
+}
+
+impl ObjectSearchIndexer {
+    pub fn open(
+        isolate: &str,
+        config: ObjectSearchConfig,
+        noc: NamedObjectCacheRef,
+    ) -> BuckyResult<Self> {
+        let mut dir = cyfs_util::get_cyfs_root_path();
+        dir.push("data");
+        if isolate.len() > 0 {
+            dir.push(isolate)
+        }
+        dir.push("search-index");
+
+        let index = ObjectSearchIndex::open(&dir)?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            index: Arc::new(index),
+            noc,
+        })
+    }
+
+    pub fn start(&self, feed: &NamedObjectCacheChangeFeed) {
+        let (sender, receiver) = async_std::channel::unbounded();
+        let listener = ObjectSearchChangeListener { sender };
+        feed.subscribe(Arc::new(Box::new(listener)));
+
+        // 首次开启时索引为空，需要用noc里已有的对象建立索引，期间的变更在队列里等待
+        let need_rebuild = self.index.is_empty();
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            if need_rebuild {
+                if let Err(e) = this.rebuild().await {
+                    error!("rebuild search index failed! {}", e);
+                }
+            }
+
+            this.run(receiver).await;
+        });
+    }
+
+    async fn run(&self, receiver: Receiver<NamedObjectCacheChangeEvent>) {
+        while let Ok(event) = receiver.recv().await {
+            self.on_change(event);
+
+            let mut count = 1;
+            while count < INDEX_COMMIT_BATCH {
+                match receiver.try_recv() {
+                    Ok(event) => {
+                        self.on_change(event);
+                        count += 1;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let _ = self.commit().await;
+        }
+
+        warn!("noc change feed closed, search indexer stopped!");
+    }
+
+    fn on_change(&self, event: NamedObjectCacheChangeEvent) {
+        match event {
+            NamedObjectCacheChangeEvent::Put(object) => {
+                let _ = self.index_object(&object);
+            }
+            NamedObjectCacheChangeEvent::Delete(object_id) => {
+                // 不在索引里的对象删除也没有影响
+                self.index.remove(&object_id);
+            }
+        }
+    }
+
+    // 返回对象是否需要索引
+    fn index_object(&self, object: &NONObjectInfo) -> BuckyResult<bool> {
+        let doc = ObjectSearchDocument::extract(object).map_err(|e| {
+            warn!(
+                "extract search doc from object failed! object={}, {}",
+                object.object_id, e
+            );
+            e
+        })?;
+
+        if !self
+            .config
+            .should_index(doc.dec_id.as_ref(), doc.obj_type)
+        {
+            return Ok(false);
+        }
+
+        self.index.add(&doc)?;
+        Ok(true)
+    }
+
+    async fn commit(&self) -> BuckyResult<()> {
+        let index = self.index.clone();
+        async_std::task::spawn_blocking(move || index.commit()).await
+    }
+
+    async fn rebuild(&self) -> BuckyResult<()> {
+        info!("will rebuild search index from noc");
+
+        let mut count = 0;
+        for obj_type in self.config.obj_types() {
+            let mut opt = NamedObjectCacheSelectObjectOption::default();
+            opt.page_size = REBUILD_PAGE_SIZE;
+
+            loop {
+                let req = NamedObjectCacheSelectObjectRequest {
+                    filter: NamedObjectCacheSelectObjectFilter {
+                        obj_type: Some(obj_type),
+                        last_access_before: None,
+                    },
+                    opt: opt.clone(),
+                };
+
+                let resp = self.noc.select_object(&req).await?;
+                let len = resp.list.len();
+                for item in resp.list {
+                    let mut req = NamedObjectCacheGetObjectRequest {
+                        source: RequestSourceInfo::new_local_system(),
+                        object_id: item.object_id,
+                        last_access_rpath: None,
+                        flags: 0,
+                    };
+                    req.set_no_update_last_access();
+
+                    if let Some(data) = self.noc.get_object(&req).await? {
+                        if let Ok(true) = self.index_object(&data.object) {
+                            count += 1;
+                        }
+                    }
+                }
+
+                self.commit().await?;
+
+                if len < opt.page_size {
+                    break;
+                }
+                opt.page_index += 1;
+            }
+        }
+
+        info!("rebuild search index complete! count={}", count);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectSearcher for ObjectSearchIndexer {
+    async fn search(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        self.index
+            .search(&req.query, &req.filter, req.page_index, req.page_size)
+    }
+}
@@ -0,0 +1,19 @@
+#[cfg(feature = "search")]
+mod config;
+#[cfg(feature = "search")]
+mod extract;
+#[cfg(feature = "search")]
+mod index;
+#[cfg(feature = "search")]
+mod indexer;
+mod processor;
+mod searcher;
+mod transform;
+
+#[cfg(feature = "search")]
+pub(crate) use config::*;
+#[cfg(feature = "search")]
+pub(crate) use indexer::*;
+pub(crate) use processor::*;
+pub(crate) use searcher::*;
+pub(crate) use transform::*;
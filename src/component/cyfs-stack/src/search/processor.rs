@@ -0,0 +1,14 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait SearchInputProcessor: Sync + Send + 'static {
+    async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse>;
+}
+
+pub(crate) type SearchInputProcessorRef = Arc<dyn SearchInputProcessor>;
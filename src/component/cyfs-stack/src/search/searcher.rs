@@ -0,0 +1,12 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ObjectSearcher: Send + Sync {
+    async fn search(&self, req: SearchObjectInputRequest)
+        -> BuckyResult<SearchObjectInputResponse>;
+}
+
+pub(crate) type ObjectSearcherRef = Arc<Box<dyn ObjectSearcher>>;
@@ -0,0 +1,58 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct SearchInputTransformer {
+    processor: SearchOutputProcessorRef,
+}
+
+impl SearchInputTransformer {
+    pub fn new(processor: SearchOutputProcessorRef) -> SearchInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        let out_req = SearchObjectOutputRequest {
+            common: Self::convert_common(req.common),
+            query: req.query,
+            filter: req.filter,
+            page_index: req.page_index,
+            page_size: req.page_size,
+        };
+
+        let out_resp = self.processor.search_object(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchInputProcessor for SearchInputTransformer {
+    async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        Self::search_object(&self, req).await
+    }
+}
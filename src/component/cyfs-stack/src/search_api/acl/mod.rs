@@ -0,0 +1,3 @@
+mod search_acl;
+
+pub(crate) use search_acl::*;
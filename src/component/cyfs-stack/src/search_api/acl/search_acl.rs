@@ -0,0 +1,174 @@
+use crate::search::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct SearchAclInnerInputProcessor {
+    next: SearchInputProcessorRef,
+}
+
+impl SearchAclInnerInputProcessor {
+    pub(crate) fn new(next: SearchInputProcessorRef) -> SearchInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+
+    // 除了系统dec，只能搜索自己dec的对象
+    fn limit_filter(source: &RequestSourceInfo, filter: &mut SearchObjectFilter) {
+        if source.is_system_dec() {
+            return;
+        }
+
+        if let Some(dec_id) = &filter.dec_id {
+            if *dec_id != source.dec {
+                warn!(
+                    "search other dec's objects not allowed, will limit to source dec! source={}, filter dec={}",
+                    source, dec_id
+                );
+            }
+        }
+
+        filter.dec_id = Some(source.dec.clone());
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchInputProcessor for SearchAclInnerInputProcessor {
+    async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        self.check_local_zone_permit("search.search_object", &req.common.source)?;
+
+        let mut req = req;
+        Self::limit_filter(&req.common.source, &mut req.filter);
+
+        self.next.search_object(req).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cyfs_core::{DecApp, DecAppObj};
+    use std::sync::Mutex;
+
+    struct FilterRecorder {
+        filter: Mutex<Option<SearchObjectFilter>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchInputProcessor for FilterRecorder {
+        async fn search_object(
+            &self,
+            req: SearchObjectInputRequest,
+        ) -> BuckyResult<SearchObjectInputResponse> {
+            *self.filter.lock().unwrap() = Some(req.filter);
+            Ok(SearchObjectInputResponse {
+                total: 0,
+                list: vec![],
+            })
+        }
+    }
+
+    async fn search(
+        source: RequestSourceInfo,
+        filter: SearchObjectFilter,
+    ) -> BuckyResult<SearchObjectFilter> {
+        let recorder = Arc::new(FilterRecorder {
+            filter: Mutex::new(None),
+        });
+        let acl = SearchAclInnerInputProcessor::new(recorder.clone());
+
+        let req = SearchObjectInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: None,
+                source,
+                target: None,
+                flags: 0,
+            },
+            query: "test".to_owned(),
+            filter,
+            page_index: 0,
+            page_size: 32,
+        };
+        acl.search_object(req).await?;
+
+        let filter = recorder.filter.lock().unwrap().take().unwrap();
+        Ok(filter)
+    }
+
+    #[async_std::test]
+    async fn test_search_acl() {
+        let dec_id = DecApp::generate_id(ObjectId::default(), "search-dec");
+        let other_dec_id = DecApp::generate_id(ObjectId::default(), "search-other-dec");
+
+        // 普通dec只能搜索自己的对象
+        let filter = search(
+            RequestSourceInfo::new_local_dec(Some(dec_id.clone())),
+            SearchObjectFilter::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(filter.dec_id, Some(dec_id.clone()));
+
+        let mut filter = SearchObjectFilter::default();
+        filter.dec_id = Some(other_dec_id.clone());
+        filter.obj_type = Some(41);
+        let filter = search(
+            RequestSourceInfo::new_local_dec(Some(dec_id.clone())),
+            filter,
+        )
+        .await
+        .unwrap();
+        assert_eq!(filter.dec_id, Some(dec_id.clone()));
+        assert_eq!(filter.obj_type, Some(41));
+
+        // 系统dec不受限制
+        let filter = search(
+            RequestSourceInfo::new_local_system(),
+            SearchObjectFilter::default(),
+        )
+        .await
+        .unwrap();
+        assert!(filter.dec_id.is_none());
+
+        let mut filter = SearchObjectFilter::default();
+        filter.dec_id = Some(other_dec_id.clone());
+        let filter = search(RequestSourceInfo::new_local_system(), filter)
+            .await
+            .unwrap();
+        assert_eq!(filter.dec_id, Some(other_dec_id));
+
+        // 其它zone的请求直接拒绝
+        let ret = search(
+            RequestSourceInfo::new_other_zone_dec(Some(dec_id)),
+            SearchObjectFilter::default(),
+        )
+        .await;
+        assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::PermissionDenied);
+    }
+}
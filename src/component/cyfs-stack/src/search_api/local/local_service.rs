@@ -0,0 +1,44 @@
+use crate::search::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalSearchService {
+    searcher: Option<ObjectSearcherRef>,
+}
+
+impl LocalSearchService {
+    pub(crate) fn new(searcher: Option<ObjectSearcherRef>) -> Self {
+        Self { searcher }
+    }
+
+    pub fn clone_processor(&self) -> SearchInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        match &self.searcher {
+            Some(searcher) => searcher.search(req).await,
+            None => {
+                let msg = format!("object search service not enabled!");
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::NotSupport, msg))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchInputProcessor for LocalSearchService {
+    async fn search_object(
+        &self,
+        req: SearchObjectInputRequest,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        Self::search_object(self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod local_service;
+
+pub(crate) use local_service::*;
@@ -0,0 +1,3 @@
+mod search_service_router;
+
+pub(crate) use search_service_router::*;
@@ -0,0 +1,7 @@
+mod search_handler;
+mod search_listener;
+mod search_service;
+
+pub(crate) use search_handler::*;
+pub(crate) use search_listener::*;
+pub(crate) use search_service::*;
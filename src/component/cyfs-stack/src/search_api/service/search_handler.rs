@@ -0,0 +1,64 @@
+use crate::non::NONInputHttpRequest;
+use crate::search::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct SearchRequestHandler {
+    processor: SearchInputProcessorRef,
+}
+
+impl SearchRequestHandler {
+    pub fn new(processor: SearchInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    pub async fn process_search_object_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        match self.on_search_object_request(req).await {
+            Ok(resp) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(resp.encode_string());
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_search_object_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<SearchObjectInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("search object failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = SearchObjectOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = SearchObjectInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+
+            query: out_req.query,
+            filter: out_req.filter,
+            page_index: out_req.page_index,
+            page_size: out_req.page_size,
+        };
+        self.processor.search_object(in_req).await
+    }
+}
@@ -0,0 +1,79 @@
+use super::search_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum SearchRequestType {
+    SearchObject,
+}
+
+pub(crate) struct SearchRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: SearchRequestType,
+    handler: SearchRequestHandler,
+}
+
+impl SearchRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: SearchRequestType,
+        handler: SearchRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            SearchRequestType::SearchObject => {
+                self.handler.process_search_object_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &SearchRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        server.at("/search/object").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            SearchRequestType::SearchObject,
+            handler.clone(),
+        ));
+
+        server.at("/search/object/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            SearchRequestType::SearchObject,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for SearchRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::local::LocalSearchService;
+use super::super::router::SearchServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::search::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct SearchService {
+    router: SearchInputProcessorRef,
+}
+
+impl SearchService {
+    pub(crate) fn new(
+        searcher: Option<ObjectSearcherRef>,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalSearchService::new(searcher);
+        let router =
+            SearchServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> SearchInputProcessorRef {
+        self.router.clone()
+    }
+}
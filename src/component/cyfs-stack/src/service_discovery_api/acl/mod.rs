@@ -0,0 +1,3 @@
+mod service_discovery_acl;
+
+pub(crate) use service_discovery_acl::*;
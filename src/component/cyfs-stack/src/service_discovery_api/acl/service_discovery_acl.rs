@@ -0,0 +1,74 @@
+use crate::dec_service::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ServiceDiscoveryAclInnerInputProcessor {
+    next: ServiceDiscoveryInputProcessorRef,
+}
+
+impl ServiceDiscoveryAclInnerInputProcessor {
+    pub(crate) fn new(
+        next: ServiceDiscoveryInputProcessorRef,
+    ) -> ServiceDiscoveryInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceDiscoveryInputProcessor for ServiceDiscoveryAclInnerInputProcessor {
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        self.check_local_zone_permit("service_discovery.register_dec_service", &req.common.source)?;
+
+        self.next.register_dec_service(req).await
+    }
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        self.check_local_zone_permit(
+            "service_discovery.unregister_dec_service",
+            &req.common.source,
+        )?;
+
+        self.next.unregister_dec_service(req).await
+    }
+
+    // zone内的所有dec都可以查询
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        self.check_local_zone_permit("service_discovery.query_dec_services", &req.common.source)?;
+
+        self.next.query_dec_services(req).await
+    }
+}
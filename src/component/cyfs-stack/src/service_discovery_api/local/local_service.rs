@@ -0,0 +1,93 @@
+use crate::dec_service::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalServiceDiscoveryService {
+    dec_service_manager: DecServiceManager,
+}
+
+impl LocalServiceDiscoveryService {
+    pub(crate) fn new(dec_service_manager: DecServiceManager) -> Self {
+        Self {
+            dec_service_manager,
+        }
+    }
+
+    pub fn clone_processor(&self) -> ServiceDiscoveryInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        let info = self
+            .dec_service_manager
+            .register(
+                &req.common.source,
+                &req.name,
+                req.endpoints,
+                req.capabilities,
+                req.ttl,
+            )
+            .await?;
+
+        Ok(ServiceDiscoveryRegisterInputResponse { info })
+    }
+
+    pub async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        let info = self
+            .dec_service_manager
+            .unregister(&req.common.source, &req.name)
+            .await?;
+
+        Ok(ServiceDiscoveryUnregisterInputResponse { info })
+    }
+
+    pub async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        let list = self
+            .dec_service_manager
+            .query(
+                req.name.as_deref(),
+                req.dec_id.as_ref(),
+                req.capability.as_deref(),
+                req.include_expired,
+            )
+            .await?;
+
+        Ok(ServiceDiscoveryQueryInputResponse { list })
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceDiscoveryInputProcessor for LocalServiceDiscoveryService {
+    async fn register_dec_service(
+        &self,
+        req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        Self::register_dec_service(self, req).await
+    }
+
+    async fn unregister_dec_service(
+        &self,
+        req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        Self::unregister_dec_service(self, req).await
+    }
+
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        Self::query_dec_services(self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod service_discovery_service_router;
+
+pub(crate) use service_discovery_service_router::*;
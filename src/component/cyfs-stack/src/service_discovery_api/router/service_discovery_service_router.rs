@@ -0,0 +1,129 @@
+use super::super::acl::ServiceDiscoveryAclInnerInputProcessor;
+use crate::dec_service::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct ServiceDiscoveryServiceRouter {
+    processor: ServiceDiscoveryInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl ServiceDiscoveryServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: ServiceDiscoveryInputProcessorRef,
+    ) -> ServiceDiscoveryInputProcessorRef {
+        // 限定同zone
+        let processor = ServiceDiscoveryAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(
+        &self,
+        target: DeviceId,
+    ) -> BuckyResult<ServiceDiscoveryInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = ServiceDiscoveryRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = ServiceDiscoveryInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<ServiceDiscoveryInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!(
+                "service discovery target resolved: {:?} -> {}",
+                target, device_id
+            );
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceDiscoveryInputProcessor for ServiceDiscoveryServiceRouter {
+    async fn register_dec_service(
+        &self,
+        mut req: ServiceDiscoveryRegisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.register_dec_service(req).await
+    }
+
+    async fn unregister_dec_service(
+        &self,
+        mut req: ServiceDiscoveryUnregisterInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.unregister_dec_service(req).await
+    }
+
+    // 默认查询本设备上同步的root_state
+    async fn query_dec_services(
+        &self,
+        req: ServiceDiscoveryQueryInputRequest,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.query_dec_services(req).await
+    }
+}
@@ -0,0 +1,7 @@
+mod service_discovery_handler;
+mod service_discovery_listener;
+mod service_discovery_service;
+
+pub(crate) use service_discovery_handler::*;
+pub(crate) use service_discovery_listener::*;
+pub(crate) use service_discovery_service::*;
@@ -0,0 +1,147 @@
+use crate::dec_service::*;
+use crate::non::NONInputHttpRequest;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ServiceDiscoveryRequestHandler {
+    processor: ServiceDiscoveryInputProcessorRef,
+}
+
+impl ServiceDiscoveryRequestHandler {
+    pub fn new(processor: ServiceDiscoveryInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // register_dec_service
+    pub async fn process_register_dec_service_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_register_dec_service_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_register_dec_service_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ServiceDiscoveryRegisterInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("register dec service failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ServiceDiscoveryRegisterOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ServiceDiscoveryRegisterInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            endpoints: out_req.endpoints,
+            capabilities: out_req.capabilities,
+            ttl: out_req.ttl,
+        };
+        self.processor.register_dec_service(in_req).await
+    }
+
+    // unregister_dec_service
+    pub async fn process_unregister_dec_service_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_unregister_dec_service_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_unregister_dec_service_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ServiceDiscoveryUnregisterInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!(
+                "unregister dec service failed, read body bytes error! {}",
+                e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ServiceDiscoveryUnregisterOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ServiceDiscoveryUnregisterInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+        };
+        self.processor.unregister_dec_service(in_req).await
+    }
+
+    // query_dec_services
+    pub async fn process_query_dec_services_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_query_dec_services_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_query_dec_services_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ServiceDiscoveryQueryInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("query dec services failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ServiceDiscoveryQueryOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ServiceDiscoveryQueryInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            name: out_req.name,
+            dec_id: out_req.dec_id,
+            capability: out_req.capability,
+            include_expired: out_req.include_expired,
+        };
+        self.processor.query_dec_services(in_req).await
+    }
+}
@@ -0,0 +1,122 @@
+use super::service_discovery_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ServiceDiscoveryRequestType {
+    RegisterDecService,
+    UnregisterDecService,
+    QueryDecServices,
+}
+
+pub(crate) struct ServiceDiscoveryRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ServiceDiscoveryRequestType,
+    handler: ServiceDiscoveryRequestHandler,
+}
+
+impl ServiceDiscoveryRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ServiceDiscoveryRequestType,
+        handler: ServiceDiscoveryRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ServiceDiscoveryRequestType::RegisterDecService => {
+                self.handler.process_register_dec_service_request(req).await
+            }
+            ServiceDiscoveryRequestType::UnregisterDecService => {
+                self.handler
+                    .process_unregister_dec_service_request(req)
+                    .await
+            }
+            ServiceDiscoveryRequestType::QueryDecServices => {
+                self.handler.process_query_dec_services_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ServiceDiscoveryRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // dec service
+        server.at("/service_discovery/register").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ServiceDiscoveryRequestType::RegisterDecService,
+            handler.clone(),
+        ));
+
+        server
+            .at("/service_discovery/register/*must")
+            .post(Self::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                ServiceDiscoveryRequestType::RegisterDecService,
+                handler.clone(),
+            ));
+
+        server.at("/service_discovery/unregister").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ServiceDiscoveryRequestType::UnregisterDecService,
+            handler.clone(),
+        ));
+
+        server
+            .at("/service_discovery/unregister/*must")
+            .post(Self::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                ServiceDiscoveryRequestType::UnregisterDecService,
+                handler.clone(),
+            ));
+
+        server.at("/service_discovery/query").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ServiceDiscoveryRequestType::QueryDecServices,
+            handler.clone(),
+        ));
+
+        server.at("/service_discovery/query/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ServiceDiscoveryRequestType::QueryDecServices,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ServiceDiscoveryRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
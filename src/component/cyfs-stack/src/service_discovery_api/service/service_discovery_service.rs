@@ -0,0 +1,30 @@
+use super::super::local::LocalServiceDiscoveryService;
+use super::super::router::ServiceDiscoveryServiceRouter;
+use crate::dec_service::*;
+use crate::forward::ForwardProcessorManager;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ServiceDiscoveryService {
+    router: ServiceDiscoveryInputProcessorRef,
+}
+
+impl ServiceDiscoveryService {
+    pub(crate) fn new(
+        dec_service_manager: DecServiceManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalServiceDiscoveryService::new(dec_service_manager);
+        let router = ServiceDiscoveryServiceRouter::new(
+            forward,
+            zone_manager,
+            local_service.clone_processor(),
+        );
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ServiceDiscoveryInputProcessorRef {
+        self.router.clone()
+    }
+}
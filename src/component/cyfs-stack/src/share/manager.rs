@@ -0,0 +1,495 @@
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// 分享记录保存在ood的local_cache系统dec下: /.cyfs/share
+const SHARE_LIST_ID: &str = "cyfs-share-list";
+
+// 访问计数只在内存里累加，定期保存
+const SHARE_SAVE_INTERVAL_SECS: u64 = 30;
+
+// 分享id的随机字节数，hex编码后作为链接的一部分
+const SHARE_ID_BYTES: usize = 12;
+
+// 每个dec最多同时存在的分享数量
+const SHARE_MAX_PER_DEC: usize = 1024;
+
+// 分享密码使用pbkdf2派生后保存，增加暴力破解的代价
+const SHARE_PASSWORD_KDF_ROUNDS: u32 = 100_000;
+
+// 每个分享在一个时间窗口内允许的密码错误次数，超过后在窗口结束前拒绝继续尝试
+const SHARE_PASSWORD_MAX_FAILED: u32 = 5;
+const SHARE_PASSWORD_FAILED_WINDOW_SECS: u64 = 60 * 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SharePassword {
+    salt: String,
+    hash: String,
+
+    // pbkdf2的轮数，0表示旧版本的sha256(salt+password)
+    #[serde(default)]
+    rounds: u32,
+}
+
+impl SharePassword {
+    fn new(password: &str) -> Self {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let salt = hex::encode(salt);
+        let rounds = SHARE_PASSWORD_KDF_ROUNDS;
+        let hash = Self::hash(&salt, password, rounds);
+
+        Self { salt, hash, rounds }
+    }
+
+    fn hash(salt: &str, password: &str, rounds: u32) -> String {
+        if rounds == 0 {
+            let mut buf = Vec::with_capacity(salt.len() + password.len());
+            buf.extend_from_slice(salt.as_bytes());
+            buf.extend_from_slice(password.as_bytes());
+
+            return hash_data(&buf).to_hex_string();
+        }
+
+        cyfs_util::derive_key_from_passphrase(password, salt.as_bytes(), rounds).to_hex_string()
+    }
+
+    fn verify(&self, password: &str) -> bool {
+        Self::hash(&self.salt, password, self.rounds) == self.hash
+    }
+}
+
+struct ShareFailedAttempts {
+    count: u32,
+    window_start: u64,
+}
+
+// 访问分享时密码错误的计数，只保存在内存里
+#[derive(Default)]
+struct SharePasswordThrottle {
+    list: HashMap<String, ShareFailedAttempts>,
+}
+
+impl SharePasswordThrottle {
+    fn window() -> u64 {
+        SHARE_PASSWORD_FAILED_WINDOW_SECS * 1000 * 1000
+    }
+
+    fn check(&mut self, id: &str, now: u64) -> BuckyResult<()> {
+        if let Some(item) = self.list.get(id) {
+            if now >= item.window_start + Self::window() {
+                self.list.remove(id);
+            } else if item.count >= SHARE_PASSWORD_MAX_FAILED {
+                let msg = format!(
+                    "too many failed password attempts for share! id={}, count={}",
+                    id, item.count
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_failed(&mut self, id: &str, now: u64) {
+        let item = self
+            .list
+            .entry(id.to_owned())
+            .or_insert(ShareFailedAttempts {
+                count: 0,
+                window_start: now,
+            });
+        item.count += 1;
+    }
+
+    fn on_success(&mut self, id: &str) {
+        self.list.remove(id);
+    }
+
+    fn clear_expired(&mut self, now: u64) {
+        let window = Self::window();
+        self.list.retain(|_, item| now < item.window_start + window);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ShareItem {
+    record: ShareRecord,
+    password: Option<SharePassword>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct ShareList {
+    list: BTreeMap<String, ShareItem>,
+}
+
+declare_collection_codec_for_serde!(ShareList);
+
+struct ShareManagerInner {
+    data: NOCCollectionRWAsync<ShareList>,
+    throttle: Mutex<SharePasswordThrottle>,
+}
+
+// 对象和路径的分享链接，分享记录在ood上创建，由ood的front服务通过/s/{id}提供给匿名的浏览器访问
+// 访问时以创建分享的dec的身份读取目标，所以分享的内容不会超出该dec自身的权限
+#[derive(Clone)]
+pub(crate) struct ShareManager(Arc<ShareManagerInner>);
+
+impl ShareManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        noc: NamedObjectCacheRef,
+        local_cache: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let processor = GlobalStateOutputTransformer::new(local_cache, source);
+        let data = NOCCollectionRWAsync::<ShareList>::new_global_state(
+            processor,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+            CYFS_SHARE_PATH.to_owned(),
+            None,
+            SHARE_LIST_ID,
+            noc,
+        );
+
+        let inner = ShareManagerInner {
+            data,
+            throttle: Mutex::new(SharePasswordThrottle::default()),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub async fn init(&self) -> BuckyResult<()> {
+        self.0.data.load().await.map_err(|e| {
+            error!("load share list failed! {}", e);
+            e
+        })?;
+
+        self.0
+            .data
+            .start_save(Duration::from_secs(SHARE_SAVE_INTERVAL_SECS));
+
+        Ok(())
+    }
+
+    fn gen_id() -> String {
+        let mut buf = [0u8; SHARE_ID_BYTES];
+        rand::thread_rng().fill_bytes(&mut buf);
+        hex::encode(buf)
+    }
+
+    // 路径不能包含..，避免访问时跳出分享的范围
+    fn check_path(path: &str) -> BuckyResult<()> {
+        if !path.starts_with('/') || path.split('/').any(|seg| seg == "..") {
+            let msg = format!("invalid share path: {}", path);
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        Ok(())
+    }
+
+    fn check_target(target: &ShareTarget) -> BuckyResult<()> {
+        match target {
+            ShareTarget::Object { inner_path, .. } => match inner_path {
+                Some(inner_path) => Self::check_path(inner_path),
+                None => Ok(()),
+            },
+            ShareTarget::Path(path) => Self::check_path(path),
+        }
+    }
+
+    pub async fn create(
+        &self,
+        dec_id: &ObjectId,
+        target: ShareTarget,
+        mode: ShareMode,
+        expire_time: Option<u64>,
+        password: Option<String>,
+    ) -> BuckyResult<ShareRecord> {
+        Self::check_target(&target)?;
+
+        let now = bucky_time_now();
+        if let Some(expire_time) = &expire_time {
+            if *expire_time <= now {
+                let msg = format!("share expire time already passed: {}", expire_time);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        let password = match password {
+            Some(password) if password.is_empty() => {
+                let msg = format!("share password should not be empty!");
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+            Some(password) => Some(SharePassword::new(&password)),
+            None => None,
+        };
+
+        let record = ShareRecord {
+            id: Self::gen_id(),
+            dec_id: dec_id.to_owned(),
+            target,
+            mode,
+            expire_time,
+            has_password: password.is_some(),
+            create_time: now,
+            views: 0,
+            last_view_time: None,
+        };
+
+        {
+            let mut data = self.0.data.coll().write().await;
+
+            // 顺便清除已经过期的分享
+            data.list.retain(|_, item| !item.record.is_expired(now));
+
+            let count = data
+                .list
+                .values()
+                .filter(|item| item.record.dec_id == *dec_id)
+                .count();
+            if count >= SHARE_MAX_PER_DEC {
+                let msg = format!("too many shares for dec! dec={}, count={}", dec_id, count);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+            }
+
+            let item = ShareItem {
+                record: record.clone(),
+                password,
+            };
+            data.list.insert(record.id.clone(), item);
+        }
+
+        self.0.data.set_dirty(true);
+        self.0.data.save().await?;
+
+        info!(
+            "new share created! id={}, dec={}, target={}, mode={:?}, expire={:?}",
+            record.id, record.dec_id, record.target, record.mode, record.expire_time
+        );
+
+        Ok(record)
+    }
+
+    // 只能撤销自己dec创建的分享，系统dec可以撤销所有的分享
+    pub async fn revoke(
+        &self,
+        source: &RequestSourceInfo,
+        id: &str,
+    ) -> BuckyResult<Option<ShareRecord>> {
+        let item = {
+            let mut data = self.0.data.coll().write().await;
+            match data.list.get(id) {
+                Some(item) => {
+                    if item.record.dec_id != source.dec && !source.is_system_dec() {
+                        let msg = format!(
+                            "revoke share but dec not match! id={}, dec={}, source={}",
+                            id, item.record.dec_id, source.dec
+                        );
+                        error!("{}", msg);
+                        return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+                    }
+                }
+                None => {
+                    warn!("revoke share but not found! id={}", id);
+                    return Ok(None);
+                }
+            }
+
+            data.list.remove(id).unwrap()
+        };
+
+        self.0.data.set_dirty(true);
+        self.0.data.save().await?;
+
+        info!("share revoked! id={}, dec={}", id, item.record.dec_id);
+
+        Ok(Some(item.record))
+    }
+
+    // 只能查看自己dec创建的分享，系统dec可以查看所有的分享
+    fn is_visible(record: &ShareRecord, source: &RequestSourceInfo) -> bool {
+        source.is_system_dec() || record.dec_id == source.dec
+    }
+
+    pub async fn list(&self, source: &RequestSourceInfo) -> Vec<ShareRecord> {
+        let data = self.0.data.coll().read().await;
+        data.list
+            .values()
+            .filter(|item| Self::is_visible(&item.record, source))
+            .map(|item| item.record.clone())
+            .collect()
+    }
+
+    // front服务访问分享链接，校验有效期和密码，并累加访问计数
+    pub async fn open(&self, id: &str, password: Option<&str>) -> BuckyResult<ShareRecord> {
+        let now = bucky_time_now();
+
+        let record = {
+            let mut data = self.0.data.coll().write().await;
+            let item = data.list.get_mut(id).ok_or_else(|| {
+                let msg = format!("share not found! id={}", id);
+                warn!("{}", msg);
+                BuckyError::new(BuckyErrorCode::NotFound, msg)
+            })?;
+
+            if item.record.is_expired(now) {
+                let msg = format!(
+                    "share already expired! id={}, expire={:?}",
+                    id, item.record.expire_time
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::Expired, msg));
+            }
+
+            if let Some(stored) = &item.password {
+                self.0.throttle.lock().unwrap().check(id, now)?;
+
+                let matched = match password {
+                    Some(password) => stored.verify(password),
+                    None => false,
+                };
+
+                let mut throttle = self.0.throttle.lock().unwrap();
+                if !matched {
+                    throttle.on_failed(id, now);
+                    throttle.clear_expired(now);
+
+                    let msg = format!("share password missing or not match! id={}", id);
+                    warn!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+                }
+
+                throttle.on_success(id);
+            }
+
+            item.record.views += 1;
+            item.record.last_view_time = Some(now);
+            item.record.clone()
+        };
+
+        self.0.data.set_dirty(true);
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_password() {
+        let password = SharePassword::new("123456");
+        assert!(password.verify("123456"));
+        assert!(!password.verify("1234567"));
+        assert!(!password.verify(""));
+
+        // 同样的密码每次生成的salt不同
+        let other = SharePassword::new("123456");
+        assert_ne!(password.hash, other.hash);
+
+        assert!(ShareManager::check_path("/a/b").is_ok());
+        assert!(ShareManager::check_path("a/b").is_err());
+        assert!(ShareManager::check_path("/a/../b").is_err());
+    }
+
+    #[test]
+    fn test_legacy_password() {
+        let salt = "0011223344556677";
+        let legacy = SharePassword {
+            salt: salt.to_owned(),
+            hash: SharePassword::hash(salt, "123456", 0),
+            rounds: 0,
+        };
+        assert!(legacy.verify("123456"));
+        assert!(!legacy.verify("654321"));
+
+        // 旧版本保存的数据没有rounds字段
+        let value = serde_json::json!({ "salt": legacy.salt, "hash": legacy.hash });
+        let loaded: SharePassword = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.rounds, 0);
+        assert!(loaded.verify("123456"));
+
+        let password = SharePassword::new("123456");
+        assert_eq!(password.rounds, SHARE_PASSWORD_KDF_ROUNDS);
+        assert_ne!(
+            password.hash,
+            SharePassword::hash(&password.salt, "123456", 0)
+        );
+    }
+
+    #[test]
+    fn test_password_throttle() {
+        let mut throttle = SharePasswordThrottle::default();
+        let now = bucky_time_now();
+
+        for _ in 0..SHARE_PASSWORD_MAX_FAILED {
+            throttle.check("s1", now).unwrap();
+            throttle.on_failed("s1", now);
+        }
+
+        let ret = throttle.check("s1", now + 1);
+        assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::OutOfLimit);
+
+        // 其它分享不受影响
+        throttle.check("s2", now).unwrap();
+
+        // 窗口结束后可以继续尝试
+        let later = now + SharePasswordThrottle::window();
+        throttle.check("s1", later).unwrap();
+
+        throttle.on_failed("s2", now);
+        throttle.on_success("s2");
+        assert!(throttle.list.get("s2").is_none());
+
+        throttle.on_failed("s3", now);
+        throttle.clear_expired(later);
+        assert!(throttle.list.is_empty());
+    }
+
+    #[test]
+    fn test_visible() {
+        use cyfs_core::{DecApp, DecAppObj};
+
+        let dec_id = DecApp::generate_id(ObjectId::default(), "share-dec");
+        let other_dec_id = DecApp::generate_id(ObjectId::default(), "share-other-dec");
+
+        let record = ShareRecord {
+            id: ShareManager::gen_id(),
+            dec_id: dec_id.clone(),
+            target: ShareTarget::Path("/a".to_owned()),
+            mode: ShareMode::Default,
+            expire_time: None,
+            has_password: false,
+            create_time: bucky_time_now(),
+            views: 0,
+            last_view_time: None,
+        };
+
+        let source = RequestSourceInfo::new_local_dec(Some(dec_id));
+        assert!(ShareManager::is_visible(&record, &source));
+
+        let source = RequestSourceInfo::new_local_dec(Some(other_dec_id));
+        assert!(!ShareManager::is_visible(&record, &source));
+
+        let source = RequestSourceInfo::new_local_system();
+        assert!(ShareManager::is_visible(&record, &source));
+    }
+}
@@ -0,0 +1,21 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait ShareInputProcessor: Sync + Send + 'static {
+    async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse>;
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse>;
+
+    async fn get_shares(&self, req: ShareListInputRequest) -> BuckyResult<ShareListInputResponse>;
+}
+
+pub(crate) type ShareInputProcessorRef = Arc<dyn ShareInputProcessor>;
@@ -0,0 +1,91 @@
+use super::processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct ShareInputTransformer {
+    processor: ShareOutputProcessorRef,
+}
+
+impl ShareInputTransformer {
+    pub fn new(processor: ShareOutputProcessorRef) -> ShareInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        let out_req = ShareCreateOutputRequest {
+            common: Self::convert_common(req.common),
+            target: req.target,
+            mode: req.mode,
+            expire_time: req.expire_time,
+            password: req.password,
+        };
+
+        let out_resp = self.processor.create_share(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        let out_req = ShareRevokeOutputRequest {
+            common: Self::convert_common(req.common),
+            id: req.id,
+        };
+
+        let out_resp = self.processor.revoke_share(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_shares(&self, req: ShareListInputRequest) -> BuckyResult<ShareListInputResponse> {
+        let out_req = ShareListOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_shares(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl ShareInputProcessor for ShareInputTransformer {
+    async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        Self::create_share(&self, req).await
+    }
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        Self::revoke_share(&self, req).await
+    }
+
+    async fn get_shares(&self, req: ShareListInputRequest) -> BuckyResult<ShareListInputResponse> {
+        Self::get_shares(&self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod share_acl;
+
+pub(crate) use share_acl::*;
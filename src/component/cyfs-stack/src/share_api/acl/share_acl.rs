@@ -0,0 +1,72 @@
+use crate::share::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct ShareAclInnerInputProcessor {
+    next: ShareInputProcessorRef,
+}
+
+impl ShareAclInnerInputProcessor {
+    pub(crate) fn new(next: ShareInputProcessorRef) -> ShareInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ShareInputProcessor for ShareAclInnerInputProcessor {
+    // 分享以调用方dec的身份对外提供访问，匿名dec不能创建
+    async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        self.check_local_zone_permit("share.create_share", &req.common.source)?;
+
+        if req.common.source.is_anonymous_dec_app() {
+            let msg = format!("share.create_share not valid for anonymous dec!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.create_share(req).await
+    }
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        self.check_local_zone_permit("share.revoke_share", &req.common.source)?;
+
+        self.next.revoke_share(req).await
+    }
+
+    async fn get_shares(&self, req: ShareListInputRequest) -> BuckyResult<ShareListInputResponse> {
+        self.check_local_zone_permit("share.get_shares", &req.common.source)?;
+
+        self.next.get_shares(req).await
+    }
+}
@@ -0,0 +1,81 @@
+use crate::share::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalShareService {
+    share_manager: ShareManager,
+}
+
+impl LocalShareService {
+    pub(crate) fn new(share_manager: ShareManager) -> Self {
+        Self { share_manager }
+    }
+
+    pub fn clone_processor(&self) -> ShareInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        let share = self
+            .share_manager
+            .create(
+                &req.common.source.dec,
+                req.target,
+                req.mode,
+                req.expire_time,
+                req.password,
+            )
+            .await?;
+
+        let url = share.url_path();
+        Ok(ShareCreateInputResponse { share, url })
+    }
+
+    pub async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        let share = self
+            .share_manager
+            .revoke(&req.common.source, &req.id)
+            .await?;
+
+        Ok(ShareRevokeInputResponse { share })
+    }
+
+    pub async fn get_shares(
+        &self,
+        req: ShareListInputRequest,
+    ) -> BuckyResult<ShareListInputResponse> {
+        let list = self.share_manager.list(&req.common.source).await;
+
+        Ok(ShareListInputResponse { list })
+    }
+}
+
+#[async_trait::async_trait]
+impl ShareInputProcessor for LocalShareService {
+    async fn create_share(
+        &self,
+        req: ShareCreateInputRequest,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        Self::create_share(self, req).await
+    }
+
+    async fn revoke_share(
+        &self,
+        req: ShareRevokeInputRequest,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        Self::revoke_share(self, req).await
+    }
+
+    async fn get_shares(&self, req: ShareListInputRequest) -> BuckyResult<ShareListInputResponse> {
+        Self::get_shares(self, req).await
+    }
+}
@@ -0,0 +1,3 @@
+mod share_service_router;
+
+pub(crate) use share_service_router::*;
@@ -0,0 +1,7 @@
+mod share_handler;
+mod share_listener;
+mod share_service;
+
+pub(crate) use share_handler::*;
+pub(crate) use share_listener::*;
+pub(crate) use share_service::*;
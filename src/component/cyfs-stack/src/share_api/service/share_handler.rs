@@ -0,0 +1,140 @@
+use crate::non::NONInputHttpRequest;
+use crate::share::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct ShareRequestHandler {
+    processor: ShareInputProcessorRef,
+}
+
+impl ShareRequestHandler {
+    pub fn new(processor: ShareInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // create_share
+    pub async fn process_create_share_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_create_share_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_create_share_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ShareCreateInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("create share failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ShareCreateOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ShareCreateInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            target: out_req.target,
+            mode: out_req.mode,
+            expire_time: out_req.expire_time,
+            password: out_req.password,
+        };
+        self.processor.create_share(in_req).await
+    }
+
+    // revoke_share
+    pub async fn process_revoke_share_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_revoke_share_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_revoke_share_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ShareRevokeInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("revoke share failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ShareRevokeOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ShareRevokeInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            id: out_req.id,
+        };
+        self.processor.revoke_share(in_req).await
+    }
+
+    // get_shares
+    pub async fn process_get_shares_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_shares_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_shares_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<ShareListInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get shares failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = ShareListOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = ShareListInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+        };
+        self.processor.get_shares(in_req).await
+    }
+}
@@ -0,0 +1,110 @@
+use super::share_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum ShareRequestType {
+    CreateShare,
+    RevokeShare,
+    GetShares,
+}
+
+pub(crate) struct ShareRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: ShareRequestType,
+    handler: ShareRequestHandler,
+}
+
+impl ShareRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: ShareRequestType,
+        handler: ShareRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            ShareRequestType::CreateShare => self.handler.process_create_share_request(req).await,
+            ShareRequestType::RevokeShare => self.handler.process_revoke_share_request(req).await,
+            ShareRequestType::GetShares => self.handler.process_get_shares_request(req).await,
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &ShareRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // share
+        server.at("/share/create").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::CreateShare,
+            handler.clone(),
+        ));
+
+        server.at("/share/create/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::CreateShare,
+            handler.clone(),
+        ));
+
+        server.at("/share/revoke").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::RevokeShare,
+            handler.clone(),
+        ));
+
+        server.at("/share/revoke/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::RevokeShare,
+            handler.clone(),
+        ));
+
+        server.at("/share/list").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::GetShares,
+            handler.clone(),
+        ));
+
+        server.at("/share/list/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            ShareRequestType::GetShares,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for ShareRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::local::LocalShareService;
+use super::super::router::ShareServiceRouter;
+use crate::forward::ForwardProcessorManager;
+use crate::share::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct ShareService {
+    router: ShareInputProcessorRef,
+}
+
+impl ShareService {
+    pub(crate) fn new(
+        share_manager: ShareManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalShareService::new(share_manager);
+        let router =
+            ShareServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> ShareInputProcessorRef {
+        self.router.clone()
+    }
+}
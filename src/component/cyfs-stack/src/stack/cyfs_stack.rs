@@ -1,9 +1,14 @@
 // use super::dsg::{DSGService, DSGServiceOptions};
+use super::panic_snapshot::StackPanicSnapshot;
 use super::params::*;
 use super::uni_stack::*;
+use crate::access_stat::ObjectAccessStatManager;
 use crate::acl::{AclManager, AclManagerRef};
-use crate::admin::AdminManager;
-use crate::app::{AppController, AppService};
+use crate::admin::{AdminConfirmManager, AdminManager};
+use crate::api_gateway::ApiGatewayManager;
+use crate::app::{AppController, AppService, AppWebDirPinManager};
+use crate::bandwidth::BandwidthManager;
+use crate::concurrency::ConcurrencyManager;
 use crate::config::*;
 use crate::crypto::CryptoOutputTransformer;
 use crate::crypto_api::{CryptoService, ObjectCrypto, ObjectVerifier};
@@ -14,25 +19,55 @@ use crate::group_api::GroupService;
 use crate::interface::{
     ObjectListenerManager, ObjectListenerManagerParams, ObjectListenerManagerRef,
 };
+use crate::media::MediaService;
 use crate::meta::*;
+use crate::migration::ZoneMigrationManager;
 use crate::name::NameResolver;
 use crate::ndn::NDNOutputTransformer;
-use crate::ndn_api::{BdtNDNEventHandler, NDNService};
+use crate::ndn_api::{BdtNDNEventHandler, NDNReplicaManager, NDNService};
 use crate::non::NONOutputTransformer;
 use crate::non_api::NONService;
+use crate::object_pack::ObjectPackManager;
 use crate::resolver::{CompoundObjectSearcher, DeviceInfoManager, OodResolver};
 use crate::rmeta::GlobalStateMetaOutputTransformer;
 use crate::rmeta_api::{GlobalStateMetaLocalService, GlobalStateMetaService};
 use crate::root_state::{GlobalStateAccessorOutputTransformer, GlobalStateOutputTransformer};
 use crate::root_state_api::{
     GlobalStateLocalService, GlobalStateManager, GlobalStateService, GlobalStateValidatorManager,
+    LocalCacheNamespaceManager,
 };
 use crate::router_handler::RouterHandlersManager;
+use crate::search::ObjectSearcherRef;
+use crate::storage::{ColdTierManager, StorageDegradationNotifier};
+use crate::sync::SyncConflictResolver;
 use crate::trans::TransOutputTransformer;
 use crate::trans_api::{create_trans_store, TransService};
 use crate::util::UtilOutputTransformer;
 use crate::util_api::UtilService;
 use crate::zone::{ZoneManager, ZoneManagerRef, ZoneRoleManager};
+use crate::zone_event::{ZoneEventManager, ZoneEventRecorder};
+use crate::zone_health::DeviceHealthManager;
+use crate::contacts::ContactManager;
+use crate::contacts_api::ContactsService;
+use crate::queue_api::QueueService;
+use crate::schedule_api::ScheduleService;
+use crate::trash_api::TrashService;
+use crate::dec_config_api::DecConfigService;
+use crate::admin_confirm_api::AdminConfirmService;
+use crate::service_discovery_api::ServiceDiscoveryService;
+use crate::bandwidth_api::BandwidthService;
+use crate::search_api::SearchService;
+use crate::object_refs_api::ObjectRefsService;
+use crate::share_api::ShareService;
+use crate::api_gateway_api::ApiGatewayService;
+use crate::object_history_api::ObjectHistoryService;
+use crate::dec_config::DecConfigManager;
+use crate::dec_resource::DecResourceManager;
+use crate::dec_service::DecServiceManager;
+use crate::queue::QueueManager;
+use crate::schedule::ScheduleManager;
+use crate::share::ShareManager;
+use crate::trash::{TrashManager, TrashManagerHolder};
 use crate::GroupNONDriver;
 use cyfs_base::*;
 
@@ -54,8 +89,32 @@ pub(crate) struct ObjectServices {
     pub crypto_service: Arc<CryptoService>,
     pub util_service: Arc<UtilService>,
     pub trans_service: Arc<TransService>,
+    pub contacts_service: Arc<ContactsService>,
+    pub queue_service: Arc<QueueService>,
+    pub schedule_service: Arc<ScheduleService>,
+    pub trash_service: Arc<TrashService>,
+    pub dec_config_service: Arc<DecConfigService>,
+    pub admin_confirm_service: Arc<AdminConfirmService>,
+    pub service_discovery_service: Arc<ServiceDiscoveryService>,
+    pub bandwidth_service: Arc<BandwidthService>,
+    pub search_service: Arc<SearchService>,
+    pub object_refs_service: Arc<ObjectRefsService>,
+    pub share_service: Arc<ShareService>,
+    pub api_gateway_service: Arc<ApiGatewayService>,
+    pub object_history_service: Arc<ObjectHistoryService>,
 
     pub front_service: Option<Arc<FrontService>>,
+
+    pub object_pack_manager: ObjectPackManager,
+
+    // 迁移当前ood到新的硬件
+    pub migration_manager: ZoneMigrationManager,
+
+    // 对外接口的non/ndn/root_state请求共用的并发限制
+    pub concurrency_manager: ConcurrencyManager,
+
+    // 对外接口的non/ndn读取请求的对象访问统计
+    pub access_stat_manager: ObjectAccessStatManager,
 }
 
 pub struct CyfsStackImpl {
@@ -102,11 +161,19 @@ pub struct CyfsStackImpl {
     // local_cache
     local_cache: GlobalStateLocalService,
 
+    // 对外接口使用的按dec隔离的local_cache
+    local_cache_namespace: LocalCacheNamespaceManager,
+
     // global_state_meta
     global_state_meta: GlobalStateMetaService,
 
     // group
     group_service: GroupService,
+
+    // trans等持久化任务
+    task_manager: Arc<TaskManager>,
+
+    panic_snapshot: StackPanicSnapshot,
 }
 
 impl CyfsStackImpl {
@@ -129,12 +196,23 @@ impl CyfsStackImpl {
             None => "",
         };
 
-        let noc = Self::init_raw_noc(isolate, known_objects).await?;
+        // 数据根目录可能映射到不同的磁盘，后台监控每个磁盘的剩余空间
+        cyfs_util::get_storage_layout().start_monitor();
+
+        let (noc, noc_checker, noc_cold_tier) = Self::init_raw_noc(isolate, param.noc.durability, known_objects).await?;
+
+        // noc的变更通知，用以更新搜索索引和对象引用关系
+        let noc_notifier = NamedObjectCacheChangeNotifier::new(noc);
+        let noc_feed = noc_notifier.feed().clone();
+        let noc = Arc::new(Box::new(noc_notifier) as Box<dyn NamedObjectCache>);
+
+        let searcher = Self::init_search(isolate, noc.clone(), &noc_feed);
+        let ref_graph = Self::init_ref_graph(isolate, noc.clone(), &noc_feed);
         let noc_relation = NamedObjectRelationCacheManager::create(isolate)
         .await?;
 
         // meta with cache
-        let raw_meta_cache = RawMetaCache::new(param.meta.target, noc.clone());
+        let raw_meta_cache = RawMetaCache::new(param.meta.target.clone(), noc.clone());
 
         // 名字解析服务
         let name_resolver = NameResolver::new(raw_meta_cache.clone(), noc.clone());
@@ -148,6 +226,9 @@ impl CyfsStackImpl {
             BuckyError::new(e.code(), msg)
         })?;
 
+        // 回收站在noc和global state服务之后创建，先创建holder
+        let trash_holder = TrashManagerHolder::new();
+
         // load current zone's global_state
         let (local_root_state, local_cache) = Self::load_global_state(
             &global_state_manager,
@@ -155,6 +236,7 @@ impl CyfsStackImpl {
             &device,
             noc.clone(),
             &config,
+            &trash_holder,
         )
         .await?;
 
@@ -208,6 +290,13 @@ impl CyfsStackImpl {
         )
         .await?;
 
+        // noc对象和chunk的冷存储层
+        let cold_tier_manager = ColdTierManager::new(
+            noc_cold_tier,
+            named_data_components.clone(),
+            config.clone(),
+        );
+
         let fail_handler =
             ObjectFailHandler::new(raw_meta_cache.clone(), device_manager.clone_cache());
 
@@ -259,6 +348,7 @@ impl CyfsStackImpl {
             noc.clone(),
             param.config.isolate.clone(),
             zone_manager.clone(),
+            config.clone(),
         ));
 
         // handlers
@@ -269,10 +359,28 @@ impl CyfsStackImpl {
         }
 
         local_global_state_meta.init_acl_handler(&router_handlers);
+
+        let local_cache_namespace = LocalCacheNamespaceManager::new(
+            local_cache.clone(),
+            acl_manager.clone(),
+            config.clone(),
+        );
         
         // events
         let router_events = RouterEventsManager::new();
 
+        // zone事件记录，存储服务在后面root_state加载后再启动
+        let (zone_event_recorder, zone_event_receiver) =
+            ZoneEventRecorder::new(device_id.clone(), device.desc().owner().clone());
+        local_global_state_meta.bind_zone_event_recorder(zone_event_recorder.clone());
+
+        // zone内ndn读取的副本位置和负载，通过sync ping在设备和ood之间交换
+        let ndn_replica = NDNReplicaManager::new(
+            device_id.clone(),
+            named_data_components.clone(),
+            config.clone(),
+        );
+
         // role manager
         let zone_role_manager = ZoneRoleManager::new(
             device_id.clone(),
@@ -281,14 +389,28 @@ impl CyfsStackImpl {
             raw_meta_cache.clone(),
             acl_manager.clone(),
             router_events.clone(),
+            zone_event_recorder.clone(),
+            ndn_replica.clone(),
             config.clone(),
         );
 
-        let signer = RsaCPUObjectSigner::new(
-            bdt_param.device.desc().public_key().clone(),
+        // 存储进入或者退出只读模式时通知订阅者
+        StorageDegradationNotifier::bind(router_events.clone());
+
+        let signer = Self::init_signer(&param.config, &bdt_param)?;
+
+        // 链上device/people desc的续期和过期告警
+        let desc_renew_manager = DescRenewManager::new(
+            param.meta.target.clone(),
+            device_manager.clone(),
+            signer.clone(),
             bdt_param.secret.clone(),
+            router_events.clone(),
         );
 
+        // 迁移到新ood后需要使用当前的身份更新链上的device
+        let migration_secret = bdt_param.secret.clone();
+
         // 初始化bdt协议栈
         let (bdt_stack, bdt_event) = Self::init_bdt_stack(
             zone_manager.clone(),
@@ -299,11 +421,19 @@ impl CyfsStackImpl {
             &named_data_components,
             router_handlers.clone(),
             &sn_config_manager,
+            config.clone(),
         )
         .await?;
 
         named_data_components.bind_bdt_stack(bdt_stack.clone());
 
+        // 定期刷新device cache里过期或者连接失败的device desc
+        let device_refresher = DeviceDescRefresher::new(
+            raw_meta_cache.clone(),
+            device_manager.clone(),
+            bdt_stack.clone(),
+        );
+
         // enable the zone search ablity for obj_searcher
         obj_searcher.init_zone_searcher(zone_manager.clone(), noc.clone(), bdt_stack.clone());
 
@@ -317,11 +447,13 @@ impl CyfsStackImpl {
             device_id.clone(),
             device_manager.clone_cache(),
             obj_searcher.clone().into_ref(),
+            &zone_manager,
         );
 
         // crypto
         let crypto = ObjectCrypto::new(
-            verifier,
+            signer.clone(),
+            verifier.clone(),
             zone_manager.clone(),
             device_manager.clone_cache(),
             bdt_stack.clone(),
@@ -352,12 +484,15 @@ impl CyfsStackImpl {
             noc.clone(),
             noc_relation,
             &named_data_components,
+            ndn_replica,
             forward_manager.clone(),
             acl_manager.clone(),
             zone_manager.clone(),
             router_handlers.clone(),
             raw_meta_cache.clone(),
             fail_handler.clone(),
+            trash_holder.clone(),
+            verifier,
         );
 
         bdt_event.bind_non_processor(non_service.rmeta_noc_processor().clone());
@@ -373,6 +508,7 @@ impl CyfsStackImpl {
             zone_manager.clone(),
             fail_handler.clone(),
             trans_store,
+            param.config.preserve_file_metadata,
         );
 
         let non_service = Arc::new(non_service);
@@ -382,7 +518,7 @@ impl CyfsStackImpl {
 
         // load root-state service
         let root_state = Self::load_root_state_service(
-            local_root_state,
+            local_root_state.clone(),
             acl_manager.clone(),
             forward_manager.clone(),
             zone_manager.clone(),
@@ -392,31 +528,266 @@ impl CyfsStackImpl {
         )
         .await?;
 
+        // 敏感操作的WebAuthn确认，认证器保存在root_state
+        let admin_confirm_manager = AdminConfirmManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let admin_confirm_service = AdminConfirmService::new(
+            admin_confirm_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
         // load global state meta service
         let global_state_meta = Self::load_global_state_meta_service(
             local_global_state_meta,
             forward_manager.clone(),
             zone_manager.clone(),
             fail_handler.clone(),
+            admin_confirm_manager.clone(),
+        );
+
+        let app_pin_manager = AppWebDirPinManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            ndn_service.clone_processor(),
+            named_data_components.ndc.clone(),
+            named_data_components.new_chunk_store_reader(),
+            root_state.clone_global_state_processor(),
+            local_cache.clone_global_state_processor(),
+        )
+        .await?;
+        util_service
+            .local_service()
+            .bind_app_pin_manager(app_pin_manager);
+
+        let zone_event_manager = ZoneEventManager::new(
+            &zone_manager,
+            zone_event_recorder,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+            router_events.clone(),
+        )
+        .await?;
+        util_service
+            .local_service()
+            .bind_zone_event_manager(zone_event_manager.clone());
+
+        let dec_resource_manager = DecResourceManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        util_service
+            .local_service()
+            .bind_dec_resource_manager(dec_resource_manager.clone());
+
+        let share_manager = ShareManager::new(
+            &zone_manager,
+            noc.clone(),
+            local_cache.clone_global_state_processor(),
+        )
+        .await?;
+        let share_service = ShareService::new(
+            share_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let device_health_manager = DeviceHealthManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            local_cache.clone_global_state_processor(),
+            dec_resource_manager.clone(),
+        )
+        .await?;
+        util_service
+            .local_service()
+            .bind_device_health_manager(device_health_manager.clone());
+
+        let contact_manager = ContactManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let contacts_service = ContactsService::new(
+            contact_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let queue_manager = QueueManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let queue_service = QueueService::new(
+            queue_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let schedule_manager = ScheduleManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let schedule_service = ScheduleService::new(
+            schedule_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let trash_manager = TrashManager::new(
+            &zone_manager,
+            noc.clone(),
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+            local_cache.clone_global_state_processor(),
+        )
+        .await?;
+        trash_holder.bind(trash_manager.clone());
+        let trash_service = TrashService::new(
+            trash_manager.clone(),
+            admin_confirm_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let dec_config_manager = DecConfigManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let dec_config_service = DecConfigService::new(
+            dec_config_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        // 设备上root_state的同步冲突处理
+        let sync_conflict_resolver = SyncConflictResolver::new(
+            &zone_manager,
+            local_root_state.clone(),
+            local_cache.clone_global_state_processor(),
+            non_service.raw_noc_processor().clone(),
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+            router_events.clone(),
+            dec_config_manager,
+            zone_event_recorder.clone(),
+        )
+        .await?;
+        zone_role_manager.bind_sync_conflict_resolver(sync_conflict_resolver);
+
+        let dec_service_manager = DecServiceManager::new(
+            &zone_manager,
+            non_service.clone_processor(),
+            root_state.clone_global_state_processor(),
+        )
+        .await?;
+        let service_discovery_service = ServiceDiscoveryService::new(
+            dec_service_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let bandwidth_manager = BandwidthManager::new(
+            isolate,
+            &zone_manager,
+            bdt_stack.clone(),
+            config.bandwidth_recorder().clone(),
+        );
+        let bandwidth_service = BandwidthService::new(
+            bandwidth_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let search_service =
+            SearchService::new(searcher, forward_manager.clone(), zone_manager.clone());
+
+        let object_refs_service = ObjectRefsService::new(
+            noc.clone(),
+            ref_graph,
+            forward_manager.clone(),
+            zone_manager.clone(),
+        );
+
+        let object_history_service =
+            ObjectHistoryService::new(noc.clone(), forward_manager.clone(), zone_manager.clone());
+
+        let concurrency_manager = ConcurrencyManager::new(&config);
+        util_service
+            .local_service()
+            .bind_concurrency_manager(concurrency_manager.clone());
+
+        let access_stat_manager = ObjectAccessStatManager::new(isolate, &config);
+        util_service
+            .local_service()
+            .bind_access_stat_manager(access_stat_manager.clone());
+
+        let api_gateway_manager = ApiGatewayManager::new();
+        let api_gateway_service = ApiGatewayService::new(
+            api_gateway_manager.clone(),
+            forward_manager.clone(),
+            zone_manager.clone(),
         );
 
         let front_service = if param.front.enable {
-            let app_service =
-                AppService::new(&zone_manager, root_state.clone_global_state_processor()).await?;
+            let app_service = AppService::new(
+                &zone_manager,
+                non_service.clone_processor(),
+                root_state.clone_global_state_processor(),
+                &config,
+            )
+            .await?;
+
+            let media_service = MediaService::new(
+                &zone_manager,
+                ndn_service.clone_processor(),
+                local_cache.clone_global_state_processor(),
+            )
+            .await?;
 
             let front_service = FrontService::new(
                 non_service.clone_processor(),
                 ndn_service.clone_processor(),
                 root_state.clone_accessor_processor(),
                 local_cache.clone_accessor_processor(),
+                root_state.clone_global_state_processor(),
                 app_service,
                 ood_resoler.clone(),
+                acl_manager.global_state_meta().clone(),
+                media_service,
+                share_manager.clone(),
+                api_gateway_manager,
             );
             Some(Arc::new(front_service))
         } else {
             None
         };
 
+        let object_pack_manager = ObjectPackManager::new(noc.clone());
+        let migration_manager = ZoneMigrationManager::new(
+            isolate,
+            noc.clone(),
+            named_data_components.clone(),
+            object_pack_manager.clone(),
+            param.meta.target.clone(),
+            signer.clone(),
+            migration_secret,
+            admin_confirm_manager.clone(),
+        );
+
         let services = ObjectServices {
             ndn_service,
             non_service: non_service.clone(),
@@ -425,14 +796,35 @@ impl CyfsStackImpl {
             util_service,
 
             trans_service: Arc::new(trans_service),
+            contacts_service: Arc::new(contacts_service),
+            queue_service: Arc::new(queue_service),
+            schedule_service: Arc::new(schedule_service),
+            trash_service: Arc::new(trash_service),
+            dec_config_service: Arc::new(dec_config_service),
+            admin_confirm_service: Arc::new(admin_confirm_service),
+            service_discovery_service: Arc::new(service_discovery_service),
+            bandwidth_service: Arc::new(bandwidth_service),
+            search_service: Arc::new(search_service),
+            object_refs_service: Arc::new(object_refs_service),
+            share_service: Arc::new(share_service),
+            api_gateway_service: Arc::new(api_gateway_service),
+            object_history_service: Arc::new(object_history_service),
 
             front_service,
+
+            object_pack_manager,
+            migration_manager,
+
+            concurrency_manager,
+            access_stat_manager: access_stat_manager.clone(),
         };
 
         let admin_manager = AdminManager::new(
             zone_role_manager.clone(),
             services.crypto_service.local_service().verifier().clone(),
             config.clone(),
+            noc_checker,
+            admin_confirm_manager,
         );
 
         let group_manager = GroupManager::new(
@@ -447,6 +839,9 @@ impl CyfsStackImpl {
         let group_service =
             GroupService::new(forward_manager.clone(), zone_manager.clone(), group_manager);
 
+        // panic时输出任务和tunnel的状态
+        let panic_snapshot = StackPanicSnapshot::register(&device_id, &task_manager, &bdt_stack);
+
         let mut stack = Self {
             config,
 
@@ -457,6 +852,7 @@ impl CyfsStackImpl {
             global_state_manager,
             root_state,
             local_cache,
+            local_cache_namespace,
 
             global_state_meta,
 
@@ -484,6 +880,10 @@ impl CyfsStackImpl {
             acl_manager,
 
             group_service,
+
+            task_manager: task_manager.clone(),
+
+            panic_snapshot,
         };
 
         // init an system-dec router-handler processor for later use
@@ -543,6 +943,23 @@ impl CyfsStackImpl {
         // init admin manager
         stack.admin_manager.init(&system_router_handlers).await?;
 
+        zone_event_manager.init(&system_router_handlers).await?;
+        zone_event_manager.start(zone_event_receiver);
+
+        device_health_manager.init(&system_router_handlers).await?;
+        device_health_manager.start();
+        dec_resource_manager.start();
+        share_manager.init().await?;
+
+        contact_manager.init(&system_router_handlers).await?;
+        queue_manager.init(&system_router_handlers).await?;
+        schedule_manager.start();
+        trash_manager.start();
+        cold_tier_manager.start();
+        dec_service_manager.start();
+        bandwidth_manager.start();
+        access_stat_manager.start();
+
         // bind bdt stack and start sync
         sn_config_manager.bind_bdt_stack(stack.bdt_stack.clone());
 
@@ -553,6 +970,9 @@ impl CyfsStackImpl {
             bdt_listeners: param.interface.bdt_listeners,
             tcp_listeners: Vec::new(),
             ws_listener: None,
+            require_request_sign: param.interface.require_request_sign,
+            compression: param.interface.compression,
+            limits: param.interface.limits,
         };
 
         // If the local shared_stack is turned on, then need to initialize the TCP-HTTP interface
@@ -571,7 +991,7 @@ impl CyfsStackImpl {
             &stack.acl_manager,
             &stack.zone_role_manager,
             &stack.root_state,
-            &stack.local_cache,
+            &stack.local_cache_namespace,
             &stack.global_state_meta,
             &stack.group_service,
         );
@@ -596,6 +1016,9 @@ impl CyfsStackImpl {
         // start rust's task thread pool and process dead lock checking
         cyfs_debug::ProcessDeadHelper::instance().start_check();
 
+        desc_renew_manager.start();
+        device_refresher.start();
+
         // try resume all tasks
         async_std::task::spawn(async move {
             if let Err(e) = task_manager.resume_task().await {
@@ -616,6 +1039,7 @@ impl CyfsStackImpl {
         device: &Device,
         noc: NamedObjectCacheRef,
         config: &StackGlobalConfig,
+        trash: &TrashManagerHolder,
     ) -> BuckyResult<(GlobalStateLocalService, GlobalStateLocalService)> {
         let owner = match device.desc().owner() {
             Some(owner) => owner.to_owned(),
@@ -635,6 +1059,7 @@ impl CyfsStackImpl {
             device_id,
             Some(owner.clone()),
             noc.clone(),
+            trash.clone(),
         )
         .await?;
 
@@ -653,6 +1078,7 @@ impl CyfsStackImpl {
             device_id,
             Some(owner),
             noc,
+            trash.clone(),
         )
         .await?;
 
@@ -722,13 +1148,50 @@ impl CyfsStackImpl {
         forward: ForwardProcessorManager,
         zone_manager: ZoneManagerRef,
         fail_handler: ObjectFailHandler,
+        admin_confirm: AdminConfirmManager,
     ) -> GlobalStateMetaService {
-        let global_state_meta =
-            GlobalStateMetaService::new(local_service, forward, zone_manager, fail_handler);
+        let global_state_meta = GlobalStateMetaService::new(
+            local_service,
+            forward,
+            zone_manager,
+            fail_handler,
+            admin_confirm,
+        );
 
         global_state_meta
     }
 
+    // 对象签名和group投票使用的签名器，配置了硬件密钥时签名不需要用到device.sec里的私钥
+    // bdt协议栈的keystore仍然加载device.sec，用于tunnel密钥交换和按device解密，这部分不经过签名器
+    fn init_signer(
+        config: &CyfsStackConfigParams,
+        bdt_param: &BdtStackParams,
+    ) -> BuckyResult<RsaCPUObjectSigner> {
+        let public_key = bdt_param.device.desc().public_key();
+
+        let signer = match &config.sign_key {
+            Some(handle) => {
+                let key = open_hardware_key(handle)?;
+                if key.public_key() != public_key {
+                    let msg = format!(
+                        "hardware key not match current device's public key! key={}, device={}",
+                        handle,
+                        bdt_param.device.desc().device_id()
+                    );
+                    error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::NotMatch, msg));
+                }
+
+                info!("will use hardware key for object sign: {}", handle);
+                warn!("hardware key only used for object sign and group vote, bdt key exchange and device decrypt still use the private key in device.sec! key={}", handle);
+                RsaCPUObjectSigner::new_with_hardware_key(key)
+            }
+            None => RsaCPUObjectSigner::new(public_key.clone(), bdt_param.secret.clone()),
+        };
+
+        Ok(signer)
+    }
+
     async fn init_bdt_stack(
         zone_manager: ZoneManagerRef,
         acl: AclManagerRef,
@@ -738,9 +1201,15 @@ impl CyfsStackImpl {
         named_data_components: &NamedDataComponents,
         router_handlers: RouterHandlersManager,
         sn_config_manager: &SNConfigManager,
+        config: StackGlobalConfig,
     ) -> BuckyResult<(StackGuard, BdtNDNEventHandler)> {
-        let event =
-            BdtNDNEventHandler::new(zone_manager, acl, router_handlers, named_data_components);
+        let event = BdtNDNEventHandler::new(
+            zone_manager,
+            acl,
+            router_handlers,
+            named_data_components,
+            config,
+        );
 
         // priority: params sn(always loaded from config dir) > sn config manager(always loaded from meta) > buildin sn
         if params.known_sn.is_empty() {
@@ -767,16 +1236,21 @@ impl CyfsStackImpl {
 
     async fn init_raw_noc(
         isolate: &str,
+        durability: NamedObjectCacheDurability,
         known_objects: CyfsStackKnownObjects,
-    ) -> BuckyResult<NamedObjectCacheRef> {
+    ) -> BuckyResult<(
+        NamedObjectCacheRef,
+        NamedObjectStorageChecker,
+        NamedObjectColdTier,
+    )> {
         let isolate = isolate.to_owned();
 
         // 这里切换线程同步初始化，否则debug下可能会导致主线程调用栈过深
-        let noc = async_std::task::spawn(async move {
-            match NamedObjectCacheManager::create(&isolate).await {
-                Ok(noc) => {
+        let (noc, checker, cold_tier) = async_std::task::spawn(async move {
+            match NamedObjectCacheManager::create_with_cold_tier(&isolate, durability).await {
+                Ok(ret) => {
                     info!("init named object cache manager success!");
-                    Ok(noc)
+                    Ok(ret)
                 }
                 Err(e) => {
                     error!("init named object cache manager failed: {}", e);
@@ -798,6 +1272,7 @@ impl CyfsStackImpl {
                     context: None,
                     last_access_rpath: None,
                     access_string: None,
+                    precondition: None,
                 };
                 let _ = noc2.put_object(&req).await;
             }
@@ -807,7 +1282,70 @@ impl CyfsStackImpl {
             task.await;
         }
 
-        Ok(noc)
+        Ok((noc, checker, cold_tier))
+    }
+
+    // 搜索服务是可选的，初始化失败不影响协议栈
+    #[cfg(feature = "search")]
+    fn init_search(
+        isolate: &str,
+        noc: NamedObjectCacheRef,
+        noc_feed: &NamedObjectCacheChangeFeed,
+    ) -> Option<ObjectSearcherRef> {
+        use crate::search::{ObjectSearchConfig, ObjectSearchIndexer};
+
+        let config = match ObjectSearchConfig::load(isolate) {
+            Ok(Some(config)) if !config.is_empty() => config,
+            Ok(_) => {
+                info!("search config not found or empty, object search service will be disabled!");
+                return None;
+            }
+            Err(e) => {
+                error!("load search config failed, object search service will be disabled! {}", e);
+                return None;
+            }
+        };
+
+        match ObjectSearchIndexer::open(isolate, config, noc) {
+            Ok(indexer) => {
+                indexer.start(noc_feed);
+                Some(Arc::new(Box::new(indexer)))
+            }
+            Err(e) => {
+                error!("init search indexer failed, object search service will be disabled! {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "search"))]
+    fn init_search(
+        _isolate: &str,
+        _noc: NamedObjectCacheRef,
+        _noc_feed: &NamedObjectCacheChangeFeed,
+    ) -> Option<ObjectSearcherRef> {
+        None
+    }
+
+    // 引用关系只用于查询和gc，打开失败不影响协议栈
+    fn init_ref_graph(
+        isolate: &str,
+        noc: NamedObjectCacheRef,
+        noc_feed: &NamedObjectCacheChangeFeed,
+    ) -> Option<NamedObjectRefGraph> {
+        match NamedObjectRefGraph::open(isolate, noc) {
+            Ok(graph) => {
+                graph.start(noc_feed);
+                Some(graph)
+            }
+            Err(e) => {
+                error!(
+                    "open object ref graph failed, ref queries will be disabled! {}",
+                    e
+                );
+                None
+            }
+        }
     }
 
     fn init_ndc(isolate: &str) -> BuckyResult<Box<dyn NamedDataCache>> {
@@ -823,8 +1361,7 @@ impl CyfsStackImpl {
     }
 
     async fn create_task_manager(isolate: &str) -> BuckyResult<Arc<TaskManager>> {
-        let mut base_dir = cyfs_util::get_cyfs_root_path();
-        base_dir.push("data");
+        let mut base_dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::TransTmp);
         if isolate.len() > 0 {
             base_dir.push(isolate)
         }
@@ -904,6 +1441,72 @@ impl CyfsStackImpl {
         Ok(())
     }
 
+    // 停止对外的服务接口并关闭bdt协议栈，用于同一进程内托管多个协议栈时单独停止某一个
+    // 内部的后台任务会在协议栈对象释放后逐步退出
+    pub async fn stop(&self) -> BuckyResult<()> {
+        info!(
+            "will stop cyfs stack: device={}",
+            self.bdt_stack.local_device_id()
+        );
+
+        if let Some(interface) = self.interface.get() {
+            interface.stop().await?;
+        }
+
+        self.bdt_stack.close();
+        self.panic_snapshot.unregister();
+
+        Ok(())
+    }
+
+    // 优雅退出，依次执行：
+    // 1. 进入drain状态，拒绝新的http请求和ndn interest
+    // 2. 等待处理中的请求完成，最多等待timeout
+    // 3. 对trans等任务做checkpoint，暂停下载并保存进度，避免退出时chunk写入一半
+    // 4. 保存同步状态
+    // 5. 停止对外接口并关闭bdt协议栈
+    pub async fn shutdown(&self, timeout: std::time::Duration) -> BuckyResult<()> {
+        let device_id = self.bdt_stack.local_device_id().clone();
+        info!(
+            "will shutdown cyfs stack: device={}, timeout={:?}",
+            device_id, timeout
+        );
+
+        let gate = self.config.request_gate();
+        gate.begin_drain();
+        if !gate.wait_idle(timeout).await {
+            warn!(
+                "shutdown cyfs stack with requests still in flight! device={}, in_flight={}",
+                device_id,
+                gate.in_flight()
+            );
+        }
+
+        let failed = self.task_manager.checkpoint_all().await;
+        if failed > 0 {
+            warn!(
+                "checkpoint tasks on shutdown partially failed! device={}, failed={}",
+                device_id, failed
+            );
+        }
+
+        // ping下线需要和ood交互，这里同样限制最长等待时间
+        if let Err(_) =
+            async_std::future::timeout(timeout, self.zone_role_manager.stop_sync()).await
+        {
+            warn!(
+                "stop sync on shutdown but timeout! device={}, timeout={:?}",
+                device_id, timeout
+            );
+        }
+
+        self.stop().await?;
+
+        info!("shutdown cyfs stack complete! device={}", device_id);
+
+        Ok(())
+    }
+
     pub fn prepare_shared_object_stack_param(
         &self,
         dec_id: Option<ObjectId>,
@@ -963,13 +1566,7 @@ impl CyfsStackImpl {
     }
 
     fn register_custom_objects_format() {
-        use std::sync::atomic::{AtomicBool, Ordering};
-
-        static INIT_DONE: AtomicBool = AtomicBool::new(false);
-        if !INIT_DONE.swap(true, Ordering::SeqCst) {
-            cyfs_core::register_core_objects_format();
-            cyfs_lib::register_core_objects_format();
-        }
+        cyfs_lib::register_all_objects_format();
     }
 
     pub async fn open_uni_stack(&self, dec_id: &Option<ObjectId>) -> UniCyfsStackRef {
@@ -1205,6 +1802,11 @@ impl CyfsStack {
         &self.stack.local_cache
     }
 
+    // 按dec列举和清理local_cache
+    pub fn local_cache_namespace(&self) -> &LocalCacheNamespaceManager {
+        &self.stack.local_cache_namespace
+    }
+
     // use system dec as default dec
     pub async fn local_cache_stub(&self, target_dec_id: Option<ObjectId>) -> GlobalStateStub {
         let source = self
@@ -1233,6 +1835,14 @@ impl CyfsStack {
         self.stack.restart_interface().await
     }
 
+    pub async fn stop(&self) -> BuckyResult<()> {
+        self.stack.stop().await
+    }
+
+    pub async fn shutdown(&self, timeout: std::time::Duration) -> BuckyResult<()> {
+        self.stack.shutdown(timeout).await
+    }
+
     pub fn prepare_shared_object_stack_param(
         &self,
         dec_id: Option<ObjectId>,
@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+struct StackRequestGateInner {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+// 协议栈对外请求的闸门，用于退出前的drain：进入drain状态后拒绝新的请求，并等待处理中的请求完成
+// http接口(NON/NDN等)和bdt的ndn interest处理都通过这里计数
+#[derive(Clone)]
+pub struct StackRequestGate(Arc<StackRequestGateInner>);
+
+impl StackRequestGate {
+    pub fn new() -> Self {
+        Self(Arc::new(StackRequestGateInner {
+            draining: AtomicBool::new(false),
+            in_flight: AtomicUsize::new(0),
+        }))
+    }
+
+    // 已经在drain状态则返回None，调用方需要直接拒绝请求；否则返回的guard释放时请求结束
+    pub fn enter(&self) -> Option<StackRequestGuard> {
+        if self.is_draining() {
+            return None;
+        }
+
+        self.0.in_flight.fetch_add(1, Ordering::SeqCst);
+
+        // 和begin_drain之间存在竞争，增加计数后需要再检查一次
+        if self.is_draining() {
+            self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+
+        Some(StackRequestGuard(self.0.clone()))
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.0.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.0.in_flight.load(Ordering::SeqCst)
+    }
+
+    pub fn begin_drain(&self) {
+        if !self.0.draining.swap(true, Ordering::SeqCst) {
+            info!(
+                "stack begin drain, will reject new requests! in_flight={}",
+                self.in_flight()
+            );
+        }
+    }
+
+    // 等待处理中的请求全部完成，超时返回false
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let begin = std::time::Instant::now();
+        loop {
+            let count = self.in_flight();
+            if count == 0 {
+                return true;
+            }
+
+            if begin.elapsed() >= timeout {
+                warn!(
+                    "wait in-flight requests complete but timeout! in_flight={}, timeout={:?}",
+                    count, timeout
+                );
+                return false;
+            }
+
+            async_std::task::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+pub struct StackRequestGuard(Arc<StackRequestGateInner>);
+
+impl Drop for StackRequestGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[async_std::test]
+    async fn test_drain() {
+        let gate = StackRequestGate::new();
+
+        let guard = gate.enter().unwrap();
+        assert_eq!(gate.in_flight(), 1);
+
+        gate.begin_drain();
+        assert!(gate.enter().is_none());
+        assert!(!gate.wait_idle(Duration::from_millis(200)).await);
+
+        drop(guard);
+        assert_eq!(gate.in_flight(), 0);
+        assert!(gate.wait_idle(Duration::from_millis(200)).await);
+    }
+}
@@ -58,6 +58,8 @@ impl GroupNONDriver {
 
                     target: from.map(|remote| remote.clone()),
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object_id: object_id.clone(),
                 inner_path: None,
@@ -98,9 +100,12 @@ impl GroupNONDriver {
 
                     target: None,
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object: obj,
                 access: Some(AccessString::full()), // TODO access
+                precondition: None,
             })
             .await
             .map(|_| ())
@@ -136,6 +141,8 @@ impl GroupNONDriver {
 
                     target: to.cloned(),
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object: obj,
             })
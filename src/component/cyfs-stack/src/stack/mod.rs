@@ -1,9 +1,13 @@
 mod cyfs_stack;
+mod drain;
 mod group_non_driver;
+mod panic_snapshot;
 mod params;
 mod uni_stack;
 
 pub use cyfs_stack::*;
+pub use drain::*;
 pub(crate) use group_non_driver::*;
+pub(crate) use panic_snapshot::recent_requests;
 pub use params::*;
 pub use cyfs_bdt_ext::NamedDataComponents;
@@ -0,0 +1,104 @@
+use cyfs_base::*;
+use cyfs_bdt::tunnel::TunnelStatsFormat;
+use cyfs_debug::{PanicSnapshotManager, PanicSnapshotProvider, RecentRequestRecorder};
+use cyfs_task_manager::TaskManager;
+
+use std::sync::{Arc, Weak};
+
+// 每个processor保留的最近请求数
+const RECENT_REQUESTS_CAPACITY: usize = 32;
+
+// tunnel统计只导出最近一段时间的
+const TUNNEL_STATS_SNAPSHOT_DURATION: u64 = 1000 * 1000 * 60;
+
+// 同一进程内的多个协议栈共用一个请求记录
+pub(crate) fn recent_requests() -> &'static RecentRequestRecorder {
+    use once_cell::sync::OnceCell;
+    static S_INSTANCE: OnceCell<RecentRequestRecorder> = OnceCell::new();
+    S_INSTANCE.get_or_init(|| {
+        let recorder = RecentRequestRecorder::new(RECENT_REQUESTS_CAPACITY);
+        PanicSnapshotManager::get_instance().register("requests", Arc::new(recorder.clone()));
+        recorder
+    })
+}
+
+struct TaskSnapshotProvider(Weak<TaskManager>);
+
+impl PanicSnapshotProvider for TaskSnapshotProvider {
+    fn snapshot(&self) -> serde_json::Value {
+        let task_manager = match self.0.upgrade() {
+            Some(v) => v,
+            None => return serde_json::Value::Null,
+        };
+
+        match task_manager.try_list_tasks() {
+            Some(list) => {
+                let list: Vec<serde_json::Value> = list
+                    .into_iter()
+                    .map(|(task_id, task_type, category)| {
+                        serde_json::json!({
+                            "id": task_id.to_string(),
+                            "type": task_type.to_string(),
+                            "category": category.to_string(),
+                        })
+                    })
+                    .collect();
+                serde_json::Value::Array(list)
+            }
+            None => serde_json::Value::String("[locked]".to_owned()),
+        }
+    }
+}
+
+struct TunnelSnapshotProvider(cyfs_bdt::Stack);
+
+impl PanicSnapshotProvider for TunnelSnapshotProvider {
+    fn snapshot(&self) -> serde_json::Value {
+        let since = bucky_time_now().saturating_sub(TUNNEL_STATS_SNAPSHOT_DURATION);
+        let stats = self
+            .0
+            .tunnel_manager()
+            .export_stats(TunnelStatsFormat::Json, Some(since));
+        serde_json::from_str(&stats).unwrap_or(serde_json::Value::String(stats))
+    }
+}
+
+// 协议栈在panic时需要输出的状态，按device区分，协议栈停止时注销
+pub(crate) struct StackPanicSnapshot {
+    device_id: DeviceId,
+}
+
+impl StackPanicSnapshot {
+    pub fn register(
+        device_id: &DeviceId,
+        task_manager: &Arc<TaskManager>,
+        bdt_stack: &cyfs_bdt::Stack,
+    ) -> Self {
+        let manager = PanicSnapshotManager::get_instance();
+        manager.register(
+            &Self::name(device_id, "tasks"),
+            Arc::new(TaskSnapshotProvider(Arc::downgrade(task_manager))),
+        );
+        manager.register(
+            &Self::name(device_id, "tunnels"),
+            Arc::new(TunnelSnapshotProvider(bdt_stack.clone())),
+        );
+
+        // 确保请求记录已经注册
+        let _ = recent_requests();
+
+        Self {
+            device_id: device_id.to_owned(),
+        }
+    }
+
+    pub fn unregister(&self) {
+        let manager = PanicSnapshotManager::get_instance();
+        manager.unregister(&Self::name(&self.device_id, "tasks"));
+        manager.unregister(&Self::name(&self.device_id, "tunnels"));
+    }
+
+    fn name(device_id: &DeviceId, category: &str) -> String {
+        format!("stack.{}.{}", device_id, category)
+    }
+}
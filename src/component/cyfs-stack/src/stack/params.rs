@@ -1,5 +1,8 @@
+use crate::interface::{HttpCompressionConfig, HttpLimitsConfig};
+use cyfs_base::HardwareKeyHandle;
 use cyfs_lib::*;
 use cyfs_meta_lib::MetaMinerTarget;
+use cyfs_noc::NamedObjectCacheDurability;
 
 use async_std::net::SocketAddr;
 
@@ -16,6 +19,15 @@ pub struct CyfsStackConfigParams {
 
     // Whether to enable perf_service
     pub perf_service: bool,
+
+    // 对象签名使用的硬件密钥，比如 pkcs11:token=cyfs;object=device，为空时使用device.sec里的私钥签名；
+    // 硬件密钥的公钥必须和当前device的公钥一致
+    // 只覆盖对象签名和group投票；bdt的密钥交换、crypto接口的按device解密以及跨zone请求签名仍然需要device.sec里的私钥，
+    // 硬件密钥只提供摘要签名，不支持这些操作
+    pub sign_key: Option<HardwareKeyHandle>,
+
+    // 发布本地文件时把mtime、权限和扩展属性保存到File对象的body里，下载到本地路径后恢复，默认关闭
+    pub preserve_file_metadata: bool,
 }
 
 impl Default for CyfsStackConfigParams {
@@ -25,6 +37,8 @@ impl Default for CyfsStackConfigParams {
             sync_service: true,
             shared_stack: true,
             perf_service: true,
+            sign_key: None,
+            preserve_file_metadata: false,
         }
     }
 }
@@ -61,11 +75,16 @@ impl Default for CyfsStackMetaParams {
 }
 
 #[derive(Debug, Clone)]
-pub struct CyfsStackNOCParams {}
+pub struct CyfsStackNOCParams {
+    // meta数据库和blob写入的持久化策略，默认每次写入都fsync
+    pub durability: NamedObjectCacheDurability,
+}
 
 impl Default for CyfsStackNOCParams {
     fn default() -> Self {
-        Self {}
+        Self {
+            durability: NamedObjectCacheDurability::default(),
+        }
     }
 }
 
@@ -79,6 +98,15 @@ pub struct CyfsStackInterfaceParams {
 
     // ws event服务地址
     pub ws_listener: Option<SocketAddr>,
+
+    // 其它zone通过bdt发起的写请求是否必须带防重放签名，关闭时兼容旧版本的请求
+    pub require_request_sign: bool,
+
+    // http接口的响应压缩
+    pub compression: HttpCompressionConfig,
+
+    // http接口的请求body大小、请求头超时、并发连接限制和违规来源的临时封禁
+    pub limits: HttpLimitsConfig,
 }
 
 impl Default for CyfsStackInterfaceParams {
@@ -99,6 +127,9 @@ impl Default for CyfsStackInterfaceParams {
             tcp_listeners,
             bdt_listeners,
             ws_listener: Some(ws_listener),
+            require_request_sign: false,
+            compression: HttpCompressionConfig::default(),
+            limits: HttpLimitsConfig::default(),
         }
     }
 }
@@ -109,6 +140,9 @@ impl CyfsStackInterfaceParams {
             tcp_listeners: Vec::new(),
             bdt_listeners: Vec::new(),
             ws_listener: None,
+            require_request_sign: false,
+            compression: HttpCompressionConfig::default(),
+            limits: HttpLimitsConfig::default(),
         }
     }
 }
@@ -0,0 +1,149 @@
+use crate::config::{StackDynamicConfig, StackDynamicConfigApplier, StackGlobalConfig};
+use cyfs_base::*;
+use cyfs_bdt_ext::NamedDataComponents;
+use cyfs_noc::NamedObjectColdTier;
+use cyfs_util::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+const COLD_TIER_SELECT_CHUNK_PAGE_SIZE: usize = 256;
+
+struct ColdTierManagerInner {
+    noc: NamedObjectColdTier,
+    named_data_components: NamedDataComponents,
+    config: StackGlobalConfig,
+}
+
+// 按[cold]配置定期把长时间没有访问的noc对象和chunk归档到冷存储
+// 访问时的水合由noc和chunk manager自己完成，这里只负责归档、延迟预算和统计
+#[derive(Clone)]
+pub(crate) struct ColdTierManager(Arc<ColdTierManagerInner>);
+
+impl ColdTierManager {
+    pub fn new(
+        noc: NamedObjectColdTier,
+        named_data_components: NamedDataComponents,
+        config: StackGlobalConfig,
+    ) -> Self {
+        let inner = ColdTierManagerInner {
+            noc,
+            named_data_components,
+            config,
+        };
+
+        let ret = Self(Arc::new(inner));
+        ret.0
+            .config
+            .dynamic_config()
+            .register_applier("cold-tier", Arc::new(Box::new(ret.clone())));
+
+        ret
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run().await;
+        });
+    }
+
+    async fn run(&self) {
+        loop {
+            let config = self.0.config.dynamic_config().cold();
+            async_std::task::sleep(Duration::from_secs(config.scan_interval_secs)).await;
+
+            // 配置可能在等待期间被修改
+            let config = self.0.config.dynamic_config().cold();
+            if !config.enable {
+                continue;
+            }
+
+            let idle_before =
+                bucky_time_now().saturating_sub(config.idle_days as u64 * 24 * 3600 * 1000 * 1000);
+            self.archive_once(idle_before, config.batch_size).await;
+        }
+    }
+
+    async fn archive_once(&self, idle_before: u64, limit: usize) {
+        match self.0.noc.archive_idle(idle_before, limit).await {
+            Ok(count) => {
+                info!("archive idle noc objects to cold tier: count={}", count);
+            }
+            Err(e) => {
+                error!("archive idle noc objects to cold tier failed! {}", e);
+            }
+        }
+
+        match self.archive_idle_chunks(idle_before, limit).await {
+            Ok(count) => {
+                info!("archive idle chunks to cold tier: count={}", count);
+            }
+            Err(e) => {
+                error!("archive idle chunks to cold tier failed! {}", e);
+            }
+        }
+
+        info!(
+            "cold tier stat: noc={:?}, chunk={:?}",
+            self.0.noc.stat(),
+            self.0.named_data_components.chunk_manager.cold_stat()
+        );
+    }
+
+    async fn archive_idle_chunks(&self, idle_before: u64, limit: usize) -> BuckyResult<usize> {
+        let chunk_manager = &self.0.named_data_components.chunk_manager;
+
+        let mut opt = SelectChunkOption::default();
+        opt.page_size = COLD_TIER_SELECT_CHUNK_PAGE_SIZE;
+
+        let mut list = vec![];
+        while list.len() < limit {
+            let req = SelectChunkRequest {
+                filter: SelectChunkFilter {
+                    state: Some(ChunkState::Ready),
+                    last_access_before: Some(idle_before),
+                },
+                opt: opt.clone(),
+            };
+
+            let resp = self.0.named_data_components.ndc.select_chunk(&req).await?;
+            let count = resp.list.len();
+
+            // 已经归档的chunk访问时间不会更新，会一直排在前面
+            for item in resp.list {
+                if list.len() >= limit {
+                    break;
+                }
+
+                if !chunk_manager.is_archived(&item.chunk_id) {
+                    list.push(item.chunk_id);
+                }
+            }
+
+            if count < opt.page_size {
+                break;
+            }
+            opt.page_index += 1;
+        }
+
+        if list.is_empty() {
+            return Ok(0);
+        }
+
+        chunk_manager.archive_chunks(&list).await
+    }
+}
+
+impl StackDynamicConfigApplier for ColdTierManager {
+    fn apply(&self, config: &StackDynamicConfig) -> BuckyResult<()> {
+        let budget = Duration::from_millis(config.cold.hydrate_budget_ms);
+        self.0.noc.set_hydrate_budget(budget);
+        self.0
+            .named_data_components
+            .chunk_manager
+            .set_hydrate_budget(budget);
+
+        Ok(())
+    }
+}
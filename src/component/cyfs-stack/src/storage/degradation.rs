@@ -0,0 +1,50 @@
+use crate::events::RouterEventsManager;
+use cyfs_base::*;
+use cyfs_lib::*;
+use cyfs_util::{EventListenerSyncRoutine, StorageDegradationEvent};
+
+// 把存储根目录只读模式的切换转发为storage_mode_changed事件
+pub(crate) struct StorageDegradationNotifier {
+    event_manager: RouterEventsManager,
+}
+
+impl StorageDegradationNotifier {
+    pub fn bind(event_manager: RouterEventsManager) {
+        let notifier = Self { event_manager };
+
+        cyfs_util::get_storage_layout()
+            .degradation()
+            .event()
+            .on(Box::new(notifier));
+    }
+
+    async fn emit(event_manager: RouterEventsManager, param: StorageModeChangedEventRequest) {
+        let event = event_manager.events().try_storage_mode_changed_event();
+        if event.is_none() {
+            return;
+        }
+
+        let mut emitter = event.unwrap().emitter();
+        let resp = emitter.emit(param).await;
+        info!("storage mode changed event resp: {}", resp);
+    }
+}
+
+impl EventListenerSyncRoutine<StorageDegradationEvent, ()> for StorageDegradationNotifier {
+    fn call(&self, param: &StorageDegradationEvent) -> BuckyResult<()> {
+        let param = StorageModeChangedEventRequest {
+            root: param.category.to_string(),
+            read_only: param.read_only,
+            reason: param.reason.map(|v| v.to_string()),
+            msg: param.msg.clone(),
+        };
+
+        // 事件在写入路径上同步触发，这里异步投递避免阻塞
+        let event_manager = self.event_manager.clone();
+        async_std::task::spawn(async move {
+            Self::emit(event_manager, param).await;
+        });
+
+        Ok(())
+    }
+}
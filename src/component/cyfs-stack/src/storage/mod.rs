@@ -1,3 +1,7 @@
 mod local_storage;
+mod degradation;
+mod cold;
 
-pub use local_storage::*;
\ No newline at end of file
+pub use local_storage::*;
+pub(crate) use degradation::*;
+pub(crate) use cold::*;
\ No newline at end of file
@@ -0,0 +1,590 @@
+use crate::dec_config::DecConfigManager;
+use crate::events::RouterEventsManager;
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::root_state_api::GlobalStateLocalService;
+use crate::zone::ZoneManagerRef;
+use crate::zone_event::ZoneEventRecorder;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+// 同步的基准和冲突记录保存在本设备local_cache的系统dec下:
+// /.cyfs/sync/base -> 上次同步完成时的root
+// /.cyfs/sync/conflict/{id}/info -> 冲突信息text_id
+// /.cyfs/sync/conflict/{id}/local -> 设备上的值
+// /.cyfs/sync/conflict/{id}/remote -> ood上的值
+const SYNC_BASE_PATH: &str = "/.cyfs/sync/base";
+const SYNC_CONFLICT_PATH: &str = "/.cyfs/sync/conflict";
+
+// Text对象id，header为冲突id，value为SyncConflictInfo的json
+const SYNC_CONFLICT_TEXT_ID: &str = "sync_conflict";
+
+// 一次同步最多处理的冲突数和对比的深度，超出的部分按原来的方式使用ood上的值
+const SYNC_CONFLICT_MAX_COUNT: usize = 64;
+const SYNC_CONFLICT_MAX_DEPTH: usize = 16;
+
+struct DiffNode {
+    path: String,
+    base: Option<ObjectId>,
+    local: Option<ObjectId>,
+    remote: Option<ObjectId>,
+    depth: usize,
+}
+
+impl DiffNode {
+    fn is_conflict(&self) -> bool {
+        self.local != self.base && self.remote != self.base && self.local != self.remote
+    }
+}
+
+// 待写回ood的处理结果，merged为dec合并后提供的对象
+pub(crate) struct SyncConflictOutput {
+    conflict: SyncConflictInfo,
+    merged: Option<NONObjectInfo>,
+}
+
+struct SyncConflictResolverInner {
+    zone_manager: ZoneManagerRef,
+    root_state: GlobalStateLocalService,
+
+    // 本地noc，用来读取设备上的值和保存冲突记录
+    non: NONOutputProcessorRef,
+    local_cache_stub: GlobalStateStub,
+
+    // 通过router访问ood上的non和root_state
+    router_non: NONOutputProcessorRef,
+    router_root_state: GlobalStateOutputProcessorRef,
+
+    event_manager: RouterEventsManager,
+    dec_config_manager: DecConfigManager,
+    zone_event_recorder: ZoneEventRecorder,
+
+    // 缓存的同步基准，避免每次都读取local_cache
+    base: Mutex<Option<ObjectId>>,
+}
+
+// 设备和ood在两次同步之间修改了同一个路径时，不再直接使用ood的值覆盖，而是按照dec的策略或者事件处理
+// 基于上次同步的root做三方对比，只对比object_map的结构，叶子节点的值不同即认为冲突
+#[derive(Clone)]
+pub(crate) struct SyncConflictResolver(Arc<SyncConflictResolverInner>);
+
+impl SyncConflictResolver {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        root_state: GlobalStateLocalService,
+        local_cache: GlobalStateInputProcessorRef,
+        non: NONInputProcessorRef,
+        router_non: NONInputProcessorRef,
+        router_root_state: GlobalStateInputProcessorRef,
+        event_manager: RouterEventsManager,
+        dec_config_manager: DecConfigManager,
+        zone_event_recorder: ZoneEventRecorder,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let local_cache = GlobalStateOutputTransformer::new(local_cache, source.clone());
+        let local_cache_stub = GlobalStateStub::new(
+            local_cache,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = SyncConflictResolverInner {
+            zone_manager: zone_manager.clone(),
+            root_state,
+            non: NONOutputTransformer::new(non, source.clone()),
+            local_cache_stub,
+            router_non: NONOutputTransformer::new(router_non, source.clone()),
+            router_root_state: GlobalStateOutputTransformer::new(router_root_state, source),
+            event_manager,
+            dec_config_manager,
+            zone_event_recorder,
+            base: Mutex::new(None),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    // 同步完成或者状态已经一致时更新基准
+    pub async fn update_base(&self, root: &ObjectId) {
+        if self.load_base().await.as_ref() == Some(root) {
+            return;
+        }
+
+        let ret = async {
+            let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+            if let Err(e) = op_env.set_with_path(SYNC_BASE_PATH, root, None, true).await {
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+            op_env.commit().await
+        }
+        .await;
+
+        match ret {
+            Ok(_) => {
+                info!("update sync base root: {}", root);
+                *self.0.base.lock().unwrap() = Some(root.to_owned());
+            }
+            Err(e) => {
+                error!("update sync base root failed! root={}, {}", root, e);
+            }
+        }
+    }
+
+    async fn load_base(&self) -> Option<ObjectId> {
+        if let Some(base) = self.0.base.lock().unwrap().clone() {
+            return Some(base);
+        }
+
+        let ret = async {
+            let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+            let ret = op_env.get_by_path(SYNC_BASE_PATH).await;
+            let _ = op_env.abort().await;
+            ret
+        }
+        .await;
+
+        match ret {
+            Ok(Some(base)) => {
+                *self.0.base.lock().unwrap() = Some(base.clone());
+                Some(base)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                if e.code() != BuckyErrorCode::NotFound {
+                    error!("load sync base root failed! {}", e);
+                }
+                None
+            }
+        }
+    }
+
+    // 在使用ood的root覆盖本地之前调用，返回需要在覆盖之后写回ood的处理结果
+    pub async fn detect(&self, local: &ObjectId, remote: &ObjectId) -> Vec<SyncConflictOutput> {
+        let base = match self.load_base().await {
+            Some(base) => base,
+            None => {
+                info!("sync base root not exists yet, conflict detection will be skipped!");
+                return vec![];
+            }
+        };
+
+        // 设备在上次同步后没有修改
+        if base == *local || local == remote {
+            return vec![];
+        }
+
+        let list = match self.diff(&base, local, remote).await {
+            Ok(list) => list,
+            Err(e) => {
+                error!(
+                    "detect sync conflict failed! base={}, local={}, remote={}, {}",
+                    base, local, remote, e
+                );
+                return vec![];
+            }
+        };
+
+        if list.is_empty() {
+            return vec![];
+        }
+
+        warn!(
+            "detect sync conflicts: base={}, local={}, remote={}, count={}",
+            base,
+            local,
+            remote,
+            list.len()
+        );
+
+        let detect_time = bucky_time_now();
+        let mut policies = HashMap::new();
+        let mut result = vec![];
+        for (index, node) in list.into_iter().enumerate() {
+            // 第一段是dec_id，后面的是dec内部的路径
+            let mut parts = node.path.trim_start_matches('/').splitn(2, '/');
+            let dec_id = match parts.next().map(|v| ObjectId::from_str(v)) {
+                Some(Ok(v)) => v,
+                _ => {
+                    warn!(
+                        "sync conflict path not in dec root state! path={}",
+                        node.path
+                    );
+                    continue;
+                }
+            };
+            let path = format!("/{}", parts.next().unwrap_or(""));
+
+            if !policies.contains_key(&dec_id) {
+                let config = self.load_policy_config(&dec_id).await;
+                policies.insert(dec_id.clone(), config);
+            }
+            let policy = policies
+                .get(&dec_id)
+                .unwrap()
+                .as_ref()
+                .and_then(|config| config.select(&path));
+
+            let conflict = SyncConflictInfo {
+                id: format!("{}_{}", detect_time, index),
+                dec_id,
+                path,
+                base: node.base,
+                local: node.local,
+                remote: node.remote,
+                policy,
+                resolution: None,
+                detect_time,
+            };
+
+            result.push(self.resolve(conflict).await);
+        }
+
+        result
+    }
+
+    // 覆盖本地root之后调用，更新基准并把需要保留的设备修改写回ood
+    pub async fn apply(&self, remote: &ObjectId, list: Vec<SyncConflictOutput>) {
+        self.update_base(remote).await;
+
+        if list.is_empty() {
+            return;
+        }
+
+        let ood_id = match self.0.zone_manager.get_current_info().await {
+            Ok(info) => info.zone_device_ood_id.object_id().to_owned(),
+            Err(e) => {
+                error!(
+                    "get current zone info failed, sync conflicts will not be applied! {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        for output in list {
+            let conflict = &output.conflict;
+            let value = match &conflict.resolution {
+                Some(SyncConflictResolution::Ours) => conflict.local.clone(),
+                Some(SyncConflictResolution::Merged(id)) => Some(id.to_owned()),
+                _ => continue,
+            };
+
+            if let Err(e) = self
+                .write_back(&ood_id, conflict, value, output.merged)
+                .await
+            {
+                error!("apply sync conflict to ood failed! {}, {}", conflict, e);
+                self.0.zone_event_recorder.record_local(
+                    ZoneEventCategory::SyncConflict,
+                    Some(conflict.dec_id.clone()),
+                    format!("apply failed: {}, {}", conflict, e),
+                );
+            } else {
+                info!("apply sync conflict to ood success! {}", conflict);
+            }
+        }
+    }
+
+    async fn diff(
+        &self,
+        base: &ObjectId,
+        local: &ObjectId,
+        remote: &ObjectId,
+    ) -> BuckyResult<Vec<DiffNode>> {
+        let cache =
+            ObjectMapOpEnvMemoryCache::new_ref(self.0.root_state.state().root_cache().clone());
+
+        let mut result = vec![];
+        let mut pending = vec![DiffNode {
+            path: String::new(),
+            base: Some(base.to_owned()),
+            local: Some(local.to_owned()),
+            remote: Some(remote.to_owned()),
+            depth: 0,
+        }];
+
+        while let Some(node) = pending.pop() {
+            if !node.is_conflict() {
+                continue;
+            }
+
+            // 都是map类型的object_map时继续对比子节点，否则认为这个路径冲突
+            if node.depth < SYNC_CONFLICT_MAX_DEPTH {
+                let base = Self::load_map(&cache, &node.base).await?;
+                let local = Self::load_map(&cache, &node.local).await?;
+                let remote = Self::load_map(&cache, &node.remote).await?;
+
+                if let (Some(base), Some(local), Some(remote)) = (base, local, remote) {
+                    let keys: BTreeSet<&String> = base
+                        .keys()
+                        .chain(local.keys())
+                        .chain(remote.keys())
+                        .collect();
+                    for key in keys {
+                        pending.push(DiffNode {
+                            path: format!("{}/{}", node.path, key),
+                            base: base.get(key).cloned(),
+                            local: local.get(key).cloned(),
+                            remote: remote.get(key).cloned(),
+                            depth: node.depth + 1,
+                        });
+                    }
+                    continue;
+                }
+            }
+
+            result.push(node);
+            if result.len() >= SYNC_CONFLICT_MAX_COUNT {
+                warn!(
+                    "sync conflicts out of limit, the rest will use the ood's value! limit={}",
+                    SYNC_CONFLICT_MAX_COUNT
+                );
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+
+    // 不存在的路径当作空的map，非map类型或者本地缺失的object_map返回None
+    async fn load_map(
+        cache: &ObjectMapOpEnvCacheRef,
+        id: &Option<ObjectId>,
+    ) -> BuckyResult<Option<HashMap<String, ObjectId>>> {
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(Some(HashMap::new())),
+        };
+
+        if id.obj_type_code() != ObjectTypeCode::ObjectMap {
+            return Ok(None);
+        }
+
+        let obj = match cache.get_object_map(id).await? {
+            Some(obj) => obj,
+            None => {
+                warn!("sync conflict object_map not found! id={}", id);
+                return Ok(None);
+            }
+        };
+
+        let obj = obj.lock().await;
+        if obj.content_type() != ObjectMapSimpleContentType::Map {
+            return Ok(None);
+        }
+
+        let list = obj.list_direct(cache).await?;
+        let map = list
+            .list
+            .into_iter()
+            .map(|item| item.into_map_item())
+            .collect();
+
+        Ok(Some(map))
+    }
+
+    async fn load_policy_config(&self, dec_id: &ObjectId) -> Option<SyncConflictPolicyConfig> {
+        let doc = match self
+            .0
+            .dec_config_manager
+            .get(dec_id, SYNC_CONFLICT_POLICY_CONFIG_NAME)
+            .await
+        {
+            Ok(Some(doc)) => doc,
+            Ok(None) => return None,
+            Err(e) => {
+                error!("load sync conflict policy failed! dec={}, {}", dec_id, e);
+                return None;
+            }
+        };
+
+        SyncConflictPolicyConfig::decode(&doc.value).ok()
+    }
+
+    // 优先使用dec通过事件给出的处理结果，其次是配置的策略
+    async fn resolve(&self, mut conflict: SyncConflictInfo) -> SyncConflictOutput {
+        let (resolution, mut merged) = self.emit(&conflict).await;
+
+        let resolution = match resolution {
+            Some(SyncConflictResolution::Merged(id)) => {
+                match &merged {
+                    Some(object) if object.object_id != id => {
+                        warn!(
+                            "sync conflict merged object not match! expect={}, got={}",
+                            id, object.object_id
+                        );
+                        merged = None;
+                    }
+                    _ => {}
+                }
+                SyncConflictResolution::Merged(id)
+            }
+            Some(v) => v,
+            None => match conflict.policy {
+                Some(SyncConflictPolicy::Ours) => SyncConflictResolution::Ours,
+                Some(SyncConflictPolicy::Theirs) => SyncConflictResolution::Theirs,
+                Some(SyncConflictPolicy::Merge) | None => SyncConflictResolution::Unresolved,
+            },
+        };
+        conflict.resolution = Some(resolution);
+
+        warn!("sync conflict resolved: {}", conflict);
+
+        if let Err(e) = self.record(&conflict).await {
+            error!("record sync conflict failed! {}, {}", conflict, e);
+        }
+
+        self.0.zone_event_recorder.record_local(
+            ZoneEventCategory::SyncConflict,
+            Some(conflict.dec_id.clone()),
+            conflict.to_string(),
+        );
+
+        SyncConflictOutput { conflict, merged }
+    }
+
+    async fn emit(
+        &self,
+        conflict: &SyncConflictInfo,
+    ) -> (Option<SyncConflictResolution>, Option<NONObjectInfo>) {
+        let event = self.0.event_manager.events().try_sync_conflict_event();
+        if event.is_none() {
+            return (None, None);
+        }
+
+        let param = SyncConflictEventRequest {
+            conflict: conflict.clone(),
+        };
+
+        let mut emitter = event.unwrap().emitter();
+        let resp = emitter.emit(param).await;
+        info!("sync conflict event resp: {}", resp);
+
+        match resp.response {
+            Some(Ok(resp)) => (resp.resolution, resp.object),
+            Some(Err(e)) => {
+                warn!("sync conflict event handler failed! {}, {}", conflict, e);
+                (None, None)
+            }
+            None => (None, None),
+        }
+    }
+
+    async fn record(&self, conflict: &SyncConflictInfo) -> BuckyResult<()> {
+        let value = serde_json::to_string(conflict).unwrap();
+        let text = Text::build(SYNC_CONFLICT_TEXT_ID, &conflict.id, value)
+            .no_create_time()
+            .build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await?;
+
+        let path = format!("{}/{}", SYNC_CONFLICT_PATH, conflict.id);
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        let ret = async {
+            op_env
+                .set_with_key(&path, "info", &text_id, None, true)
+                .await?;
+            if let Some(local) = &conflict.local {
+                op_env
+                    .set_with_key(&path, "local", local, None, true)
+                    .await?;
+            }
+            if let Some(remote) = &conflict.remote {
+                op_env
+                    .set_with_key(&path, "remote", remote, None, true)
+                    .await?;
+            }
+            Ok::<(), BuckyError>(())
+        }
+        .await;
+
+        if let Err(e) = ret {
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        Ok(())
+    }
+
+    async fn write_back(
+        &self,
+        ood_id: &ObjectId,
+        conflict: &SyncConflictInfo,
+        value: Option<ObjectId>,
+        merged: Option<NONObjectInfo>,
+    ) -> BuckyResult<()> {
+        if let Some(value) = &value {
+            // object_map需要整棵树同步，这里不支持
+            if value.obj_type_code() == ObjectTypeCode::ObjectMap {
+                let msg = format!(
+                    "write back object_map to ood not supported! dec={}, path={}, value={}",
+                    conflict.dec_id, conflict.path, value
+                );
+                warn!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+            }
+
+            // chunk_id和data类型的id没有对应的对象
+            if !value.is_data() && value.obj_type_code() != ObjectTypeCode::Chunk {
+                let object_raw = match merged {
+                    Some(object) => object.object_raw,
+                    None => {
+                        let req = NONGetObjectOutputRequest::new_noc(value.clone(), None);
+                        self.0.non.get_object(req).await?.object.object_raw
+                    }
+                };
+
+                let req = NONPutObjectOutputRequest::new_router(
+                    Some(ood_id.to_owned()),
+                    value.clone(),
+                    object_raw,
+                );
+                self.0.router_non.put_object(req).await?;
+            }
+        }
+
+        let stub = GlobalStateStub::new(
+            self.0.router_root_state.clone(),
+            Some(ood_id.to_owned()),
+            Some(conflict.dec_id.clone()),
+        );
+
+        // 以ood上冲突时的值为前提，期间被再次修改的话会失败，等待下次同步再处理
+        let op_env = stub.create_path_op_env().await?;
+        let ret = match &value {
+            Some(value) => op_env
+                .set_with_path(
+                    &conflict.path,
+                    value,
+                    conflict.remote.clone(),
+                    conflict.remote.is_none(),
+                )
+                .await
+                .map(|_| ()),
+            None => op_env
+                .remove_with_path(&conflict.path, conflict.remote.clone())
+                .await
+                .map(|_| ()),
+        };
+
+        if let Err(e) = ret {
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        Ok(())
+    }
+}
@@ -133,6 +133,22 @@ impl DeviceStateManager {
         self.state.start_save(interval);
     }
 
+    // 停止定时保存，并立即保存一次当前状态，用于协议栈退出
+    pub async fn flush(&self) {
+        self.state.stop_save();
+        match self.state.save().await {
+            Ok(_) => {
+                info!("device sync state save success! device={}", self.device_id);
+            }
+            Err(e) => {
+                error!(
+                    "device sync state save error! device={}, {}",
+                    self.device_id, e
+                );
+            }
+        }
+    }
+
     // get current device's local state dynamically
     pub async fn get_device_state(&self) -> BuckyResult<DeviceState> {
         let (root_state, root_state_revision) = self.root_state.state().get_current_root();
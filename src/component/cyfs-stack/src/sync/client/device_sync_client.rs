@@ -3,6 +3,7 @@ use super::device_state::*;
 use super::object_sync_client::*;
 use super::ping_client::SyncPingClient;
 use super::requestor::SyncClientRequestor;
+use super::conflict::SyncConflictResolver;
 use crate::NamedDataComponents;
 use crate::acl::AclManagerRef;
 use crate::root_state_api::GlobalStateLocalService;
@@ -96,6 +97,7 @@ impl DeviceSyncClient {
         raw_noc: NamedObjectCacheRef,
         acl_manager: AclManagerRef,
         named_data_components: NamedDataComponents,
+        conflict_resolver: Option<SyncConflictResolver>,
     ) -> BuckyResult<Self> {
         let zone_info = zone_manager.get_current_info().await?;
         let device_id = zone_info.device_id.clone();
@@ -126,6 +128,10 @@ impl DeviceSyncClient {
             raw_noc,
             bdt_stack.clone(),
             named_data_components,
+            role_manager.ndn_replica().clone(),
+            role_manager.zone_event_recorder().clone(),
+            conflict_resolver,
+            role_manager.config().clone(),
         );
         let sync_client = Arc::new(sync_client);
 
@@ -216,6 +222,12 @@ impl DeviceSyncClient {
         self.ping_client.stop().await
     }
 
+    // 协议栈退出前调用，停止ping并保存同步状态
+    pub async fn stop(&self) {
+        self.stop_ping().await;
+        self.state_manager.flush().await;
+    }
+
     pub fn wakeup_ping(&self) {
         self.ping_client.wakeup_ping();
     }
@@ -1,3 +1,4 @@
+mod conflict;
 mod device_state;
 mod ping_client;
 mod requestor;
@@ -8,6 +9,7 @@ mod listener;
 mod ping_status;
 
 
+pub(crate) use conflict::SyncConflictResolver;
 pub(crate) use device_sync_client::DeviceSyncClient;
 pub(crate) use listener::DeviceSyncRequestHandlerEndpoint;
 pub(crate) use handler::DeviceSyncRequestHandler;
@@ -1,8 +1,12 @@
 use super::super::global_state::*;
 use super::super::protocol::*;
+use super::conflict::SyncConflictResolver;
 use super::device_state::*;
 use super::requestor::SyncClientRequestor;
+use crate::config::StackGlobalConfig;
+use crate::ndn_api::NDNReplicaManager;
 use crate::NamedDataComponents;
+use crate::zone_event::ZoneEventRecorder;
 use crate::root_state_api::{GlobalStateLocalService, RootInfo};
 use cyfs_base::*;
 use cyfs_bdt::StackGuard;
@@ -15,10 +19,6 @@ use std::sync::{
     Arc,
 };
 
-// sync的重试间隔
-const SYNC_RETRY_MIN_INTERVAL_SECS: u64 = 10;
-const SYNC_RETRY_MAX_INTERVAL_SECS: u64 = 60 * 5;
-
 pub(super) struct ObjectSyncClient {
     state_manager: Arc<DeviceStateManager>,
 
@@ -35,6 +35,15 @@ pub(super) struct ObjectSyncClient {
 
     bdt_stack: StackGuard,
     named_data_components: NamedDataComponents,
+    ndn_replica: NDNReplicaManager,
+
+    zone_event_recorder: ZoneEventRecorder,
+
+    // 设备和ood同时修改同一路径时的冲突处理，没有绑定时直接使用ood的值
+    conflict_resolver: Option<SyncConflictResolver>,
+
+    // sync的重试间隔从stack config的[sync]段读取
+    config: StackGlobalConfig,
 }
 
 impl ObjectSyncClient {
@@ -46,6 +55,10 @@ impl ObjectSyncClient {
         noc: NamedObjectCacheRef,
         bdt_stack: StackGuard,
         named_data_components: NamedDataComponents,
+        ndn_replica: NDNReplicaManager,
+        zone_event_recorder: ZoneEventRecorder,
+        conflict_resolver: Option<SyncConflictResolver>,
+        config: StackGlobalConfig,
     ) -> Self {
         let state_sync_helper = GlobalStateSyncHelper::new(root_state, device_id, noc);
 
@@ -59,6 +72,10 @@ impl ObjectSyncClient {
             state_cache,
             bdt_stack,
             named_data_components,
+            ndn_replica,
+            zone_event_recorder,
+            conflict_resolver,
+            config,
 
             during: AtomicBool::new(false),
             enable: AtomicBool::new(false),
@@ -117,7 +134,7 @@ impl ObjectSyncClient {
         }
 
         // 重试间隔
-        let mut retry_interval = SYNC_RETRY_MIN_INTERVAL_SECS;
+        let mut retry_interval = self.config.dynamic_config().sync().retry_min_interval_secs;
 
         let device_state = loop {
             match self.sync_impl().await {
@@ -131,7 +148,7 @@ impl ObjectSyncClient {
                     // 等待重试，并允许被提前唤醒
                     let (abort_handle, abort_registration) = AbortHandle::new_pair();
                     let fut = Abortable::new(
-                        async_std::task::sleep(std::time::Duration::from_secs(retry_interval)),
+                        bucky_sleep(std::time::Duration::from_secs(retry_interval)),
                         abort_registration,
                     );
 
@@ -149,9 +166,11 @@ impl ObjectSyncClient {
                         }
                     };
 
-                    retry_interval *= 2;
-                    if retry_interval >= SYNC_RETRY_MAX_INTERVAL_SECS {
-                        retry_interval = SYNC_RETRY_MAX_INTERVAL_SECS;
+                    // 每次都读取当前配置，运行时修改后可以立即生效
+                    let window = self.config.dynamic_config().sync();
+                    retry_interval = std::cmp::max(retry_interval * 2, window.retry_min_interval_secs);
+                    if retry_interval >= window.retry_max_interval_secs {
+                        retry_interval = window.retry_max_interval_secs;
                     }
                 }
             }
@@ -159,6 +178,12 @@ impl ObjectSyncClient {
 
         info!("sync complete, current device state: {}", device_state);
 
+        self.zone_event_recorder.record_local(
+            cyfs_core::ZoneEventCategory::SyncComplete,
+            None,
+            format!("{}", device_state),
+        );
+
         assert!(self.is_during_sync());
         self.leave_during_sync();
     }
@@ -188,6 +213,10 @@ impl ObjectSyncClient {
                     device_state,
                     zone_state
                 );
+
+                if let Some(resolver) = &self.conflict_resolver {
+                    resolver.update_base(&device_state.root_state).await;
+                }
                 break Ok(device_state);
             }
 
@@ -224,6 +253,7 @@ impl ObjectSyncClient {
             self.state_cache.clone(),
             self.bdt_stack.clone(),
             self.named_data_components.clone(),
+            self.ndn_replica.clone(),
         );
         let (had_saved_error, result) = client.sync(req).await?;
 
@@ -233,8 +263,14 @@ impl ObjectSyncClient {
 
         match result.target {
             Some(target) => {
+                // 覆盖本地的root之前检测设备上同时修改的路径
+                let conflicts = match &self.conflict_resolver {
+                    Some(resolver) => resolver.detect(&device_state.root_state, &target).await,
+                    None => vec![],
+                };
+
                 let root = RootInfo {
-                    root_state: Some(target),
+                    root_state: Some(target.clone()),
                     revision: result.revision,
                 };
 
@@ -242,7 +278,13 @@ impl ObjectSyncClient {
                     .global_state()
                     .state()
                     .direct_set_root_state(root, None)
-                    .await
+                    .await?;
+
+                if let Some(resolver) = &self.conflict_resolver {
+                    resolver.apply(&target, conflicts).await;
+                }
+
+                Ok(())
             }
             None => {
                 warn!("sync root_state but target is empty!");
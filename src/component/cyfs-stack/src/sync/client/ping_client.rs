@@ -199,10 +199,7 @@ impl SyncPingClient {
             waker.abort();
         }
 
-        let fut = Abortable::new(
-            async_std::task::sleep(SYNC_PING_TIMEOUT_IN_SECS),
-            abort_registration,
-        );
+        let fut = Abortable::new(bucky_sleep(SYNC_PING_TIMEOUT_IN_SECS), abort_registration);
 
         match fut.await {
             Ok(_) => {
@@ -272,8 +269,7 @@ impl SyncPingClient {
                 abort_registration = state.ping_waiter.take().unwrap();
             }
 
-            let fut =
-                Abortable::new(async_std::task::sleep(interval_in_secs), abort_registration);
+            let fut = Abortable::new(bucky_sleep(interval_in_secs), abort_registration);
             match fut.await {
                 Ok(_) => {
                     trace!("ping wait timeout, now will ping once");
@@ -286,21 +282,20 @@ impl SyncPingClient {
     }
 
     async fn ping_once(&self) -> BuckyErrorCode {
-        let err =
-            match async_std::future::timeout(SYNC_PING_TIMEOUT_IN_SECS, self.ping_impl()).await {
-                Ok(ret) => {
-                    if let Err(e) = ret {
-                        error!("device sync ping failed! {}", e);
-                        e.code()
-                    } else {
-                        BuckyErrorCode::Ok
-                    }
-                }
-                Err(async_std::future::TimeoutError { .. }) => {
-                    error!("device sync ping timeout!");
-                    BuckyErrorCode::Timeout
+        let err = match bucky_timeout(SYNC_PING_TIMEOUT_IN_SECS, self.ping_impl()).await {
+            Ok(ret) => {
+                if let Err(e) = ret {
+                    error!("device sync ping failed! {}", e);
+                    e.code()
+                } else {
+                    BuckyErrorCode::Ok
                 }
-            };
+            }
+            Err(_) => {
+                error!("device sync ping timeout!");
+                BuckyErrorCode::Timeout
+            }
+        };
 
         // 更新ping结果
         let notify_list;
@@ -350,9 +345,23 @@ impl SyncPingClient {
             root_state_revision: state.root_state_revision,
             state: self.state(),
             owner_update_time: state.owner_update_time,
+            replica: Some(self.role_manager.ndn_replica().take_report()),
         };
 
-        let resp = self.requestor.ping(req, &self.ping_status).await?;
+        let report = req.replica.clone();
+        let resp = match self.requestor.ping(req, &self.ping_status).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                if let Some(report) = report {
+                    self.role_manager.ndn_replica().restore_report(report);
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(hints) = resp.replica {
+            self.role_manager.ndn_replica().on_hints(hints);
+        }
 
         if let Some(object_raw) = resp.owner {
             let _ = self.update_owner(object_raw).await;
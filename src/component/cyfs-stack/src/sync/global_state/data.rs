@@ -2,6 +2,7 @@ use super::super::client::SyncClientRequestor;
 use super::super::protocol::SyncChunksRequest;
 use super::cache::SyncObjectsStateCache;
 use super::dir_sync::DirListSync;
+use crate::ndn_api::NDNReplicaManager;
 use crate::NamedDataComponents;
 use cyfs_base::*;
 use cyfs_bdt::*;
@@ -188,6 +189,7 @@ impl ChunksCollector {
 pub(super) struct DataSync {
     bdt_stack: StackGuard,
     named_data_components: NamedDataComponents,
+    ndn_replica: NDNReplicaManager,
     ood_device_id: DeviceId,
     requestor: Arc<SyncClientRequestor>,
     state_cache: SyncObjectsStateCache,
@@ -197,6 +199,7 @@ impl DataSync {
     pub fn new(
         bdt_stack: StackGuard,
         named_data_components: NamedDataComponents,
+        ndn_replica: NDNReplicaManager,
         requestor: Arc<SyncClientRequestor>,
         state_cache: SyncObjectsStateCache,
     ) -> Self {
@@ -205,6 +208,7 @@ impl DataSync {
         Self {
             bdt_stack,
             named_data_components,
+            ndn_replica,
             ood_device_id,
             requestor,
             state_cache,
@@ -317,7 +321,17 @@ impl DataSync {
 
     pub async fn sync_chunks(&self, chunk_list: Vec<ChunkId>) -> BuckyResult<()> {
         // remove the chunks already exists in local
+        let all = chunk_list.clone();
         let chunk_list = self.filter_exists_chunks(chunk_list).await?;
+
+        // 本地已经存在的chunk也可以作为zone内的读取副本
+        let missing: HashSet<&ChunkId> = chunk_list.iter().collect();
+        let exists: Vec<ChunkId> = all
+            .into_iter()
+            .filter(|chunk_id| !missing.contains(chunk_id))
+            .collect();
+        self.ndn_replica.on_local_chunks(&exists);
+
         if chunk_list.is_empty() {
             return Ok(());
         }
@@ -381,6 +395,7 @@ impl DataSync {
         match adapter.run().await {
             Ok(()) => {
                 info!("sync single chunk success! chunk={}, task={}", chunk_id, id);
+                self.ndn_replica.on_local_chunks(&[chunk_id.to_owned()]);
                 Ok(())
             }
             Err(e) => {
@@ -398,7 +413,7 @@ impl DataSync {
         let count = chunk_list.len();
 
         // create a bundle file to download the chunks
-        let file = Self::create_file(chunk_list);
+        let file = Self::create_file(chunk_list.clone());
         let file_id = file.desc().calculate_id();
 
         info!(
@@ -430,6 +445,7 @@ impl DataSync {
         match adapter.run().await {
             Ok(()) => {
                 info!("sync chunks success! file={}, task={}", file_id, id);
+                self.ndn_replica.on_local_chunks(&chunk_list);
                 Ok(())
             }
             Err(e) => {
@@ -214,7 +214,7 @@ impl ObjectMapSync {
                     // 等待重试，并允许被提前唤醒
                     let (abort_handle, abort_registration) = AbortHandle::new_pair();
                     let fut = Abortable::new(
-                        async_std::task::sleep(std::time::Duration::from_secs(retry_interval)),
+                        bucky_sleep(std::time::Duration::from_secs(retry_interval)),
                         abort_registration,
                     );
 
@@ -387,6 +387,7 @@ impl ObjectMapSync {
             context: meta.context,
             last_access_rpath: meta.last_access_rpath,
             access_string: meta.access_string,
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -450,7 +451,7 @@ impl ObjectMapSync {
                     // 等待重试，并允许被提前唤醒
                     let (abort_handle, abort_registration) = AbortHandle::new_pair();
                     let fut = Abortable::new(
-                        async_std::task::sleep(std::time::Duration::from_secs(retry_interval)),
+                        bucky_sleep(std::time::Duration::from_secs(retry_interval)),
                         abort_registration,
                     );
 
@@ -1,3 +1,4 @@
+use crate::ndn_api::NDNReplicaManager;
 use crate::NamedDataComponents;
 
 use super::super::client::*;
@@ -25,6 +26,7 @@ pub(crate) struct GlobalStateSyncClient {
     state_cache: SyncObjectsStateCache,
     bdt_stack: StackGuard,
     named_data_components: NamedDataComponents,
+    ndn_replica: NDNReplicaManager,
 }
 
 impl GlobalStateSyncClient {
@@ -34,6 +36,7 @@ impl GlobalStateSyncClient {
         state_cache: SyncObjectsStateCache,
         bdt_stack: StackGuard,
         named_data_components: NamedDataComponents,
+        ndn_replica: NDNReplicaManager,
     ) -> Self {
         Self {
             requestor,
@@ -41,6 +44,7 @@ impl GlobalStateSyncClient {
             state_cache,
             bdt_stack,
             named_data_components,
+            ndn_replica,
         }
     }
 
@@ -99,6 +103,7 @@ impl GlobalStateSyncClient {
         let data_sync = DataSync::new(
             self.bdt_stack.clone(),
             self.named_data_components.clone(),
+            self.ndn_replica.clone(),
             self.requestor.clone(),
             self.state_cache.clone(),
         );
@@ -23,6 +23,10 @@ impl JsonCodec<SyncPingRequest> for SyncPingRequest {
             &self.owner_update_time,
         );
 
+        if let Some(replica) = &self.replica {
+            obj.insert("replica".to_owned(), serde_json::to_value(replica).unwrap());
+        }
+
         obj
     }
 
@@ -35,6 +39,7 @@ impl JsonCodec<SyncPingRequest> for SyncPingRequest {
             state: JsonCodecHelper::decode_string_field(obj, "state")?,
             owner_update_time: JsonCodecHelper::decode_option_int_field(obj, "owner_update_time")?
                 .unwrap_or(0),
+            replica: JsonCodecHelper::decode_option_serde_field(obj, "replica")?,
         })
     }
 }
@@ -59,6 +64,10 @@ impl JsonCodec<SyncPingResponse> for SyncPingResponse {
 
         JsonCodecHelper::encode_option_string_field(&mut obj, "owner", owner.as_ref());
 
+        if let Some(replica) = &self.replica {
+            obj.insert("replica".to_owned(), serde_json::to_value(replica).unwrap());
+        }
+
         obj
     }
 
@@ -88,6 +97,7 @@ impl JsonCodec<SyncPingResponse> for SyncPingResponse {
             zone_role: JsonCodecHelper::decode_string_field(obj, "zone_role")?,
             ood_work_mode: JsonCodecHelper::decode_string_field(obj, "ood_work_mode")?,
             owner,
+            replica: JsonCodecHelper::decode_option_serde_field(obj, "replica")?,
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::ndn_api::{NDNReplicaHints, NDNReplicaReport};
 use cyfs_base::*;
 use cyfs_lib::*;
 
@@ -61,6 +62,9 @@ pub(crate) struct SyncPingRequest {
 
     // local owner's body update time
     pub owner_update_time: u64,
+
+    // ndn load and local chunks for read replica routing
+    pub replica: Option<NDNReplicaReport>,
 }
 
 #[derive(Debug, Clone)]
@@ -70,6 +74,8 @@ pub(crate) struct SyncPingResponse {
     pub zone_role: ZoneRole,
     pub ood_work_mode: OODWorkMode,
     pub owner: Option<Vec<u8>>,
+
+    pub replica: Option<NDNReplicaHints>,
 }
 
 #[derive(Debug, Clone)]
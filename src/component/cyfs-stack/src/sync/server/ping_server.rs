@@ -2,6 +2,7 @@ use super::super::protocol::*;
 use super::zone_state::ZoneStateManager;
 use crate::zone::ZoneRoleManager;
 use cyfs_base::*;
+use cyfs_core::ZoneEventCategory;
 use cyfs_debug::Mutex;
 use cyfs_lib::ZoneRole;
 
@@ -148,6 +149,13 @@ impl SyncPingServer {
                                     ping_req.device_id, ping_req.zone_role
                                 );
 
+                                self.role_manager.zone_event_recorder().record(
+                                    ZoneEventCategory::DeviceOnline,
+                                    ping_req.device_id.clone(),
+                                    None,
+                                    format!("zone_role={}", ping_req.zone_role),
+                                );
+
                                 let device_state = DevicePingState {
                                     latest_ping: bucky_time_now(),
                                     state: ping_req.state.clone(),
@@ -191,6 +199,17 @@ impl SyncPingServer {
                     }
                 }
 
+                self.role_manager.zone_event_recorder().record(
+                    ZoneEventCategory::DeviceOffline,
+                    ping_req.device_id.clone(),
+                    None,
+                    format!("zone_role={}", ping_req.zone_role),
+                );
+
+                self.role_manager
+                    .ndn_replica()
+                    .on_device_offline(&ping_req.device_id);
+
                 // 下线操作
                 self.zone_state.device_offline(&ping_req)?
             }
@@ -204,8 +223,19 @@ impl SyncPingServer {
             ood_work_mode: zone_state.ood_work_mode,
             zone_role: zone_state.zone_role,
             owner: None,
+            replica: None,
         };
 
+        if ping_req.state != DeviceSyncState::Offline {
+            if let Some(report) = &ping_req.replica {
+                let hints = self
+                    .role_manager
+                    .ndn_replica()
+                    .on_report(&ping_req.device_id, report.clone());
+                resp.replica = Some(hints);
+            }
+        }
+
         
         if resp.zone_role != ZoneRole::ActiveOOD {
             warn!(
@@ -267,8 +297,20 @@ impl SyncPingServer {
                         root_state_revision: device_state.root_state_revision,
                         state: DeviceSyncState::Offline,
                         owner_update_time: 0,
+                        replica: None,
                     };
 
+                    self.role_manager
+                        .ndn_replica()
+                        .on_device_offline(&req.device_id);
+
+                    self.role_manager.zone_event_recorder().record(
+                        ZoneEventCategory::DeviceOffline,
+                        req.device_id.clone(),
+                        None,
+                        format!("zone_role={}, ping timeout", req.zone_role),
+                    );
+
                     let _r = self.zone_state.device_offline(&req);
                 }
                 None => {
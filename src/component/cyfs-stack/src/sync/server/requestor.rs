@@ -105,7 +105,7 @@ impl SyncServerRequestor {
                         }
                     }
 
-                    async_std::task::sleep(std::time::Duration::from_secs(retry_interval)).await;
+                    bucky_sleep(std::time::Duration::from_secs(retry_interval)).await;
 
                     retry_interval *= 2;
                     count += 1;
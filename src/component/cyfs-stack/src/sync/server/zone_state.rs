@@ -168,6 +168,12 @@ impl ZoneStateManager {
         self.state.start_save(interval);
     }
 
+    // 停止定时保存，并立即保存一次当前状态，用于协议栈退出
+    pub async fn flush(&self) {
+        self.state.stop_save();
+        self.save().await;
+    }
+
     pub async fn verify_source(&self, source: &DeviceId) -> BuckyResult<()> {
         let zone = self.zone_manager.get_zone(source, None).await?;
         if zone.zone_id() != self.zone_id {
@@ -77,6 +77,11 @@ impl ZoneSyncServer {
         self.ping_server.start();
     }
 
+    // 协议栈退出前调用，保存zone的同步状态
+    pub async fn stop(&self) {
+        self.zone_state.flush().await;
+    }
+
     // zone的noc插入了新可同步object，seq发生了更新;或者启动时候，从noc获取最新的seq
     // 注意seq是可能发生回滚的，比如机器时间回调等
     pub fn notify_device_zone_state_changed(&self, state: ZoneState, owner_changed: bool) {
@@ -23,6 +23,10 @@ pub trait TransInputProcessor: Send + Sync {
         &self,
         req: TransPublishFileInputRequest,
     ) -> BuckyResult<TransPublishFileInputResponse>;
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse>;
 
     // task group
     async fn get_task_group_state(
@@ -33,5 +37,11 @@ pub trait TransInputProcessor: Send + Sync {
         &self,
         req: TransControlTaskGroupInputRequest,
     ) -> BuckyResult<TransControlTaskGroupInputResponse>;
+
+    // verify local file
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse>;
 }
 pub type TransInputProcessorRef = Arc<Box<dyn TransInputProcessor>>;
@@ -21,6 +21,7 @@ impl TransInputTransformer {
             target: common.target,
             referer_object: common.referer_object,
             flags: common.flags,
+            deadline: common.deadline,
         }
     }
 }
@@ -85,10 +86,12 @@ impl TransInputProcessor for TransInputTransformer {
             common: Self::convert_common(req.common),
             task_status: req.task_status,
             range: req.range,
+            page: req.page,
         };
         let out_resp = self.processor.query_tasks(out_req).await?;
         Ok(TransQueryTasksInputResponse {
             task_list: out_resp.task_list,
+            page: out_resp.page,
         })
     }
 
@@ -126,6 +129,21 @@ impl TransInputProcessor for TransInputTransformer {
         })
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        let out_req = TransPublishStreamOutputRequest {
+            common: Self::convert_common(req.common),
+            owner: req.owner,
+            chunk_size: req.chunk_size,
+            access: req.access,
+            data: req.data,
+        };
+
+        self.processor.publish_stream(out_req).await
+    }
+
     async fn get_task_group_state(
         &self,
         req: TransGetTaskGroupStateInputRequest,
@@ -153,6 +171,21 @@ impl TransInputProcessor for TransInputTransformer {
 
         self.processor.control_task_group(out_req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        let out_req = TransVerifyFileOutputRequest {
+            common: Self::convert_common(req.common),
+            file_id: req.file_id,
+            local_path: req.local_path,
+            repair: req.repair,
+            device_list: req.device_list,
+        };
+
+        self.processor.verify_file(out_req).await
+    }
 }
 
 pub(crate) struct TransOutputTransformer {
@@ -182,6 +215,7 @@ impl TransOutputTransformer {
             target: common.target,
             flags: common.flags,
             user_data: None,
+            deadline: common.deadline,
         }
     }
 }
@@ -245,6 +279,21 @@ impl TransOutputProcessor for TransOutputTransformer {
         })
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamOutputRequest,
+    ) -> BuckyResult<TransPublishStreamOutputResponse> {
+        let in_req = TransPublishStreamInputRequest {
+            common: self.convert_common(req.common),
+            owner: req.owner,
+            chunk_size: req.chunk_size,
+            access: req.access,
+            data: req.data,
+        };
+
+        self.processor.publish_stream(in_req).await
+    }
+
     async fn create_task(
         &self,
         req: TransCreateTaskOutputRequest,
@@ -273,10 +322,12 @@ impl TransOutputProcessor for TransOutputTransformer {
             common: self.convert_common(req.common),
             task_status: req.task_status,
             range: req.range,
+            page: req.page,
         };
         let in_resp = self.processor.query_tasks(in_req).await?;
         Ok(TransQueryTasksOutputResponse {
             task_list: in_resp.task_list,
+            page: in_resp.page,
         })
     }
 
@@ -317,4 +368,19 @@ impl TransOutputProcessor for TransOutputTransformer {
 
         self.processor.control_task_group(in_req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileOutputRequest,
+    ) -> BuckyResult<TransVerifyFileOutputResponse> {
+        let in_req = TransVerifyFileInputRequest {
+            common: self.convert_common(req.common),
+            file_id: req.file_id,
+            local_path: req.local_path,
+            repair: req.repair,
+            device_list: req.device_list,
+        };
+
+        self.processor.verify_file(in_req).await
+    }
 }
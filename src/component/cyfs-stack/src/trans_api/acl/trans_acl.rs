@@ -67,6 +67,14 @@ impl TransInputProcessor for TransAclInnerInputProcessor {
         self.next.publish_file(req).await
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        self.check_local_zone_permit("trans.publish_stream", &req.common.source)?;
+        self.next.publish_stream(req).await
+    }
+
     async fn create_task(
         &self,
         req: TransCreateTaskInputRequest,
@@ -98,4 +106,12 @@ impl TransInputProcessor for TransAclInnerInputProcessor {
         self.check_local_zone_permit("trans.control_task_group", &req.common.source)?;
         self.next.control_task_group(req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        self.check_local_zone_permit("trans.verify_file", &req.common.source)?;
+        self.next.verify_file(req).await
+    }
 }
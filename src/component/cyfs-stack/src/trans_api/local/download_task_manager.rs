@@ -4,8 +4,9 @@ use crate::trans_api::{DownloadTaskTracker, TransStore};
 use crate::NamedDataComponents;
 use cyfs_base::*;
 use cyfs_bdt::StackGuard;
-use cyfs_lib::TransTaskInfo;
+use cyfs_lib::{TransTaskInfo, TransTaskSourceTraffic, TransTaskTrafficState};
 use cyfs_task_manager::*;
+use cyfs_util::StorageRootCategory;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -20,12 +21,45 @@ pub struct DownloadTaskState {
     pub downloaded_progress: u64,
     pub sum_size: u64,
     pub group: Option<String>,
+
+    // bdt下载session收到的piece的数据质量统计，用来区分网络丢包和恶意的源
+    pub integrity: DownloadTaskIntegrityState,
+
+    // 当前下载会话的累计流量，按源设备统计
+    pub traffic: TransTaskTrafficState,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadTaskIntegrityState {
+    pub corrupt_pieces: u64,
+    pub duplicate_pieces: u64,
+    pub out_of_window_pieces: u64,
+}
+
+impl From<&cyfs_bdt::DownloadIntegrityStat> for DownloadTaskIntegrityState {
+    fn from(stat: &cyfs_bdt::DownloadIntegrityStat) -> Self {
+        Self {
+            corrupt_pieces: stat.corrupt,
+            duplicate_pieces: stat.duplicate,
+            out_of_window_pieces: stat.out_of_window,
+        }
+    }
 }
 
 impl ProtobufTransform<super::trans_proto::DownloadTaskState> for DownloadTaskState {
     fn transform(
         value: crate::trans_api::local::trans_proto::DownloadTaskState,
     ) -> BuckyResult<Self> {
+        let mut sources = Vec::with_capacity(value.sources.len());
+        for item in value.sources {
+            sources.push(TransTaskSourceTraffic {
+                source: DeviceId::clone_from_slice(&item.source)?,
+                received: item.received,
+                downloaded: item.downloaded,
+                average_speed: item.average_speed,
+            });
+        }
+
         Ok(Self {
             task_status: TaskStatus::try_from(value.task_status)?,
             err_code: value.err_code.map(|v| BuckyErrorCode::from(v)),
@@ -34,12 +68,33 @@ impl ProtobufTransform<super::trans_proto::DownloadTaskState> for DownloadTaskSt
             downloaded_progress: value.download_progress,
             sum_size: value.sum_size,
             group: value.group,
+            integrity: DownloadTaskIntegrityState {
+                corrupt_pieces: value.corrupt_pieces,
+                duplicate_pieces: value.duplicate_pieces,
+                out_of_window_pieces: value.out_of_window_pieces,
+            },
+            traffic: TransTaskTrafficState {
+                received: value.received,
+                downloaded: value.downloaded,
+                average_speed: value.average_speed,
+                sources,
+            },
         })
     }
 }
 
 impl ProtobufTransform<&DownloadTaskState> for super::trans_proto::DownloadTaskState {
     fn transform(value: &DownloadTaskState) -> BuckyResult<Self> {
+        let mut sources = Vec::with_capacity(value.traffic.sources.len());
+        for item in &value.traffic.sources {
+            sources.push(super::trans_proto::DownloadSourceTraffic {
+                source: item.source.to_vec()?,
+                received: item.received,
+                downloaded: item.downloaded,
+                average_speed: item.average_speed,
+            });
+        }
+
         Ok(Self {
             task_status: value.task_status.into(),
             err_code: value.err_code.map(|v| v.into()),
@@ -48,6 +103,13 @@ impl ProtobufTransform<&DownloadTaskState> for super::trans_proto::DownloadTaskS
             download_progress: value.downloaded_progress,
             sum_size: value.sum_size,
             group: value.group.clone(),
+            corrupt_pieces: value.integrity.corrupt_pieces,
+            duplicate_pieces: value.integrity.duplicate_pieces,
+            out_of_window_pieces: value.integrity.out_of_window_pieces,
+            received: value.traffic.received,
+            downloaded: value.traffic.downloaded,
+            average_speed: value.traffic.average_speed,
+            sources,
         })
     }
 }
@@ -65,6 +127,7 @@ impl DownloadTaskManager {
         named_data_components: &NamedDataComponents,
         task_manager: Arc<TaskManager>,
         trans_store: Arc<TransStore>,
+        preserve_file_metadata: bool,
     ) -> Self {
         task_manager
             .register_task_factory(DownloadChunkTaskFactory::new(
@@ -78,6 +141,7 @@ impl DownloadTaskManager {
                 stack.clone(),
                 named_data_components.clone(),
                 trans_store.clone(),
+                preserve_file_metadata,
             ))
             .unwrap();
 
@@ -100,6 +164,16 @@ impl DownloadTaskManager {
     }
     */
 
+    // 任务记录保存在trans-tmp目录，没有指定本地路径时数据保存在chunk目录，需要在创建任务前检查磁盘空间
+    fn check_storage_space(local_path: &Option<String>, size: u64) -> BuckyResult<()> {
+        cyfs_util::check_storage_space(StorageRootCategory::TransTmp, 0)?;
+        if local_path.is_none() {
+            cyfs_util::check_storage_space(StorageRootCategory::Chunk, size)?;
+        }
+
+        Ok(())
+    }
+
     pub async fn create_file_task(
         &self,
         source: DeviceId,
@@ -127,6 +201,8 @@ impl DownloadTaskManager {
             );
         }
 
+        Self::check_storage_space(&local_path, file.desc().content().len())?;
+
         let group = TaskGroupHelper::new_opt_with_dec(&dec_id, group.as_deref());
         let params = DownloadFileParam {
             dec_id: dec_id.clone(),
@@ -176,6 +252,8 @@ impl DownloadTaskManager {
             );
         }
 
+        Self::check_storage_space(&local_path, chunk_id.len() as u64)?;
+
         let group = TaskGroupHelper::new_opt_with_dec(&dec_id, group.as_deref());
         let params = DownloadChunkParam {
             dec_id: dec_id.clone(),
@@ -284,3 +362,44 @@ impl DownloadTaskManager {
         Ok(task_info_list)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_state_codec() {
+        let source = DeviceId::default();
+        let mut stat = cyfs_bdt::DownloadTrafficStat::default();
+        stat.on_recv(&source, 1000, true, 1_000_000);
+        stat.on_recv(&source, 1000, true, 2_000_000);
+
+        let state = DownloadTaskState {
+            task_status: TaskStatus::Running,
+            err_code: None,
+            speed: 1000,
+            upload_speed: 0,
+            downloaded_progress: 50,
+            sum_size: 4000,
+            group: Some("/test/group".to_owned()),
+            integrity: DownloadTaskIntegrityState {
+                corrupt_pieces: 1,
+                duplicate_pieces: 2,
+                out_of_window_pieces: 3,
+            },
+            traffic: TransTaskTrafficState::from(&stat),
+        };
+
+        let buf = state.to_vec().unwrap();
+        let ret = DownloadTaskState::clone_from_slice(&buf).unwrap();
+        assert_eq!(ret.task_status, TaskStatus::Running);
+        assert_eq!(ret.group, state.group);
+        assert_eq!(ret.integrity, state.integrity);
+        assert_eq!(ret.traffic.received, 2000);
+        assert_eq!(ret.traffic.downloaded, 2000);
+        assert_eq!(ret.traffic.average_speed, 2000);
+        assert_eq!(ret.traffic.sources.len(), 1);
+        assert_eq!(ret.traffic.sources[0].source, source);
+        assert_eq!(ret.traffic.sources[0].received, 2000);
+    }
+}
@@ -189,11 +189,22 @@ impl FileRecorder {
         chunk_method: TransPublishChunkMethod, 
         access: Option<AccessString>,
     ) -> BuckyResult<()> {
-        let file_id = file.desc().file_id();
-
         self.record_file_chunk_list(source, file, chunk_method).await?;
 
         // 添加到noc
+        self.put_file_to_noc(file, access).await?;
+
+        // 添加到ndc的file管理
+        self.add_file_to_ndc(file, dirs).await
+    }
+
+    pub async fn put_file_to_noc(
+        &self,
+        file: &File,
+        access: Option<AccessString>,
+    ) -> BuckyResult<()> {
+        let file_id = file.desc().file_id();
+
         let object_raw = file.to_vec()?;
         let object = Arc::new(AnyNamedObject::Standard(StandardObject::File(file.clone())));
         let object = NONObjectInfo::new(file_id.object_id().to_owned(), object_raw, Some(object));
@@ -205,6 +216,7 @@ impl FileRecorder {
             context: None,
             last_access_rpath: None,
             access_string: access.map(|v| v.value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -226,8 +238,7 @@ impl FileRecorder {
             }
         }
 
-        // 添加到ndc的file管理
-        self.add_file_to_ndc(file, dirs).await
+        Ok(())
     }
 
     pub async fn add_file_to_ndc(
@@ -468,6 +479,7 @@ impl FileRecorder {
             context: None,
             last_access_rpath: None,
             access_string: access.map(|v| v.value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
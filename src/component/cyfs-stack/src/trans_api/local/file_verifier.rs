@@ -0,0 +1,285 @@
+use cyfs_base::*;
+use cyfs_bdt::{self, StackGuard};
+use cyfs_bdt_ext::TransContextHolder;
+use cyfs_lib::*;
+
+use async_std::io::prelude::{SeekExt, WriteExt};
+use async_std::io::ReadExt;
+use sha2::Digest;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+
+// 按照file对象的chunk划分重新校验本地文件，可选对不匹配的chunk重新下载并写回对应的区间
+pub(crate) struct LocalFileVerifier {
+    bdt_stack: StackGuard,
+    file_id: ObjectId,
+    file: File,
+    local_path: PathBuf,
+}
+
+impl LocalFileVerifier {
+    pub fn new(bdt_stack: StackGuard, file: File, local_path: PathBuf) -> Self {
+        let file_id = file.desc().calculate_id();
+        Self {
+            bdt_stack,
+            file_id,
+            file,
+            local_path,
+        }
+    }
+
+    fn chunk_list(&self) -> BuckyResult<&Vec<ChunkId>> {
+        let body = self.file.body().as_ref().ok_or_else(|| {
+            let msg = format!(
+                "verify file but file object has no body! file={}",
+                self.file_id
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })?;
+
+        body.content().inner_chunk_list().ok_or_else(|| {
+            let msg = format!(
+                "verify file with chunk list in file not support yet! file={}",
+                self.file_id
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::UnSupport, msg)
+        })
+    }
+
+    async fn local_len(&self) -> BuckyResult<u64> {
+        match async_std::fs::metadata(&self.local_path).await {
+            Ok(meta) => Ok(meta.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => {
+                let msg = format!(
+                    "verify file but get local file metadata failed! file={}, local_path={}, {}",
+                    self.file_id,
+                    self.local_path.display(),
+                    e
+                );
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::IoError, msg))
+            }
+        }
+    }
+
+    pub async fn verify(&self) -> BuckyResult<TransVerifyFileInputResponse> {
+        let chunk_list = self.chunk_list()?;
+        let file_len = self.file.desc().content().len();
+        let local_len = self.local_len().await?;
+
+        info!(
+            "will verify local file: file={}, local_path={}, len={}, local_len={}, chunks={}",
+            self.file_id,
+            self.local_path.display(),
+            file_len,
+            local_len,
+            chunk_list.len()
+        );
+
+        let mut local_file = if local_len > 0 {
+            let file = async_std::fs::File::open(&self.local_path)
+                .await
+                .map_err(|e| {
+                    let msg = format!(
+                        "verify file but open local file failed! file={}, local_path={}, {}",
+                        self.file_id,
+                        self.local_path.display(),
+                        e
+                    );
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                })?;
+            Some(file)
+        } else {
+            None
+        };
+
+        let mut buf = vec![0u8; 1024 * 64];
+        let mut chunks = Vec::with_capacity(chunk_list.len());
+        let mut offset = 0;
+        for (index, chunk_id) in chunk_list.iter().enumerate() {
+            let len = chunk_id.len() as u64;
+            let (state, msg) = match &mut local_file {
+                Some(file) if offset + len <= local_len => {
+                    match Self::verify_chunk(file, offset, chunk_id, &mut buf).await {
+                        Ok(real_chunk_id) if real_chunk_id == *chunk_id => {
+                            (TransVerifyChunkState::Valid, None)
+                        }
+                        Ok(real_chunk_id) => {
+                            let msg = format!("got unmatched chunk {}", real_chunk_id);
+                            (TransVerifyChunkState::Mismatch, Some(msg))
+                        }
+                        Err(e) => (TransVerifyChunkState::Mismatch, Some(e.to_string())),
+                    }
+                }
+                _ => (TransVerifyChunkState::Missing, None),
+            };
+
+            if !state.is_valid() {
+                warn!(
+                    "verify local file chunk not valid! file={}, index={}, chunk={}, offset={}, state={:?}, msg={:?}",
+                    self.file_id, index, chunk_id, offset, state, msg
+                );
+            }
+
+            chunks.push(TransVerifyChunkInfo {
+                index: index as u32,
+                chunk_id: chunk_id.to_owned(),
+                offset,
+                state,
+                msg,
+            });
+
+            offset += len;
+        }
+
+        let valid = local_len == file_len && chunks.iter().all(|item| item.state.is_valid());
+
+        info!(
+            "verify local file complete! file={}, local_path={}, valid={}",
+            self.file_id,
+            self.local_path.display(),
+            valid
+        );
+
+        Ok(TransVerifyFileInputResponse {
+            file_id: self.file_id.clone(),
+            file_len,
+            local_len,
+            valid,
+            chunks,
+        })
+    }
+
+    async fn verify_chunk(
+        file: &mut async_std::fs::File,
+        offset: u64,
+        chunk_id: &ChunkId,
+        buf: &mut [u8],
+    ) -> BuckyResult<ChunkId> {
+        file.seek(SeekFrom::Start(offset)).await?;
+
+        let len = chunk_id.len();
+        let mut reader = file.clone().take(len as u64);
+        let mut sha256 = sha2::Sha256::new();
+        let mut read_len = 0;
+        loop {
+            let size = reader.read(buf).await?;
+            if size == 0 {
+                break;
+            }
+            sha256.input(&buf[0..size]);
+            read_len += size;
+        }
+
+        if read_len != len {
+            let msg = format!(
+                "read chunk from local file but len unmatched! chunk={}, got={}",
+                chunk_id, read_len
+            );
+            return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+        }
+
+        let hash: HashValue = sha256.result().into();
+        Ok(ChunkId::new(&hash, len as u32))
+    }
+
+    // 只重新下载校验失败的chunk，写回到本地文件的对应位置
+    pub async fn repair(
+        &self,
+        resp: &mut TransVerifyFileInputResponse,
+        context: TransContextHolder,
+        group: Option<String>,
+    ) -> BuckyResult<()> {
+        let mut local_file = async_std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&self.local_path)
+            .await
+            .map_err(|e| {
+                let msg = format!(
+                    "repair file but open local file failed! file={}, local_path={}, {}",
+                    self.file_id,
+                    self.local_path.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        for item in resp.chunks.iter_mut() {
+            if item.state.is_valid() {
+                continue;
+            }
+
+            match self
+                .repair_chunk(&mut local_file, item, context.clone(), group.clone())
+                .await
+            {
+                Ok(()) => {
+                    info!(
+                        "repair local file chunk success! file={}, index={}, chunk={}, offset={}",
+                        self.file_id, item.index, item.chunk_id, item.offset
+                    );
+                    item.state = TransVerifyChunkState::Repaired;
+                    item.msg = None;
+                }
+                Err(e) => {
+                    error!(
+                        "repair local file chunk failed! file={}, index={}, chunk={}, offset={}, {}",
+                        self.file_id, item.index, item.chunk_id, item.offset, e
+                    );
+                    item.state = TransVerifyChunkState::RepairFailed;
+                    item.msg = Some(e.to_string());
+                }
+            }
+        }
+
+        // 本地文件比file长的部分需要截掉
+        if resp.local_len > resp.file_len {
+            local_file.set_len(resp.file_len).await?;
+        }
+
+        local_file.sync_all().await?;
+
+        resp.local_len = self.local_len().await?;
+        resp.valid =
+            resp.local_len == resp.file_len && resp.chunks.iter().all(|item| item.state.is_valid());
+
+        Ok(())
+    }
+
+    async fn repair_chunk(
+        &self,
+        local_file: &mut async_std::fs::File,
+        item: &TransVerifyChunkInfo,
+        context: TransContextHolder,
+        group: Option<String>,
+    ) -> BuckyResult<()> {
+        let len = item.chunk_id.len();
+        let (_, reader) =
+            cyfs_bdt::download_chunk(&self.bdt_stack, item.chunk_id.clone(), group, context)
+                .await?;
+
+        let mut data = Vec::with_capacity(len);
+        reader.take(len as u64).read_to_end(&mut data).await?;
+
+        let real_chunk_id = ChunkId::calculate(&data).await?;
+        if real_chunk_id != item.chunk_id {
+            let msg = format!(
+                "download chunk for repair but unmatched! chunk={}, got={}",
+                item.chunk_id, real_chunk_id
+            );
+            return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+        }
+
+        local_file.seek(SeekFrom::Start(item.offset)).await?;
+        local_file.write_all(&data).await?;
+        local_file.flush().await?;
+
+        Ok(())
+    }
+}
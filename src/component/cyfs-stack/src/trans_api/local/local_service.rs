@@ -1,4 +1,5 @@
 use cyfs_bdt_ext::TaskGroupHelper;
+use crate::ndn_api::{ChunkStreamBuilder, STREAM_MAX_CHUNK_SIZE};
 use crate::resolver::OodResolver;
 use crate::NamedDataComponents;
 use cyfs_base::*;
@@ -6,9 +7,10 @@ use cyfs_bdt::{StackGuard, self};
 use cyfs_lib::*;
 
 use crate::trans::{TransInputProcessor, TransInputProcessorRef};
-use crate::trans_api::local::FileRecorder;
+use crate::trans_api::local::{FileRecorder, LocalFileVerifier};
 use crate::trans_api::{DownloadTaskManager, PublishManager, TransStore};
 use cyfs_base::File;
+use cyfs_chunk_cache::MemChunk;
 use cyfs_task_manager::{TaskId, TaskManager, TaskStatus};
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -64,12 +66,14 @@ impl LocalTransService {
         ood_resolver: OodResolver,
         task_manager: Arc<TaskManager>,
         trans_store: Arc<TransStore>,
+        preserve_file_metadata: bool,
     ) -> Self {
         let tasks = DownloadTaskManager::new(
             bdt_stack.clone(),
             named_data_components,
             task_manager.clone(),
             trans_store,
+            preserve_file_metadata,
         );
         let publish_manager = PublishManager::new(
             task_manager.clone(),
@@ -187,6 +191,86 @@ impl LocalTransService {
         }
     }
 
+    // 从数据流切分chunk并保存，流结束后生成file对象并添加到noc和ndc
+    pub async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        info!("trans recv publish stream request: {}", req);
+
+        // 和publish_file一样，chunk_size不能太小；切分时每个chunk都要先缓存在内存里，所以也不能太大
+        if req.chunk_size < 1024 || req.chunk_size as usize > STREAM_MAX_CHUNK_SIZE {
+            let msg = format!(
+                "trans publish stream but chunk size should in [1024, {}]! chunk_size={}",
+                STREAM_MAX_CHUNK_SIZE, req.chunk_size
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+        }
+
+        let writer = self.named_data_components.new_chunk_writer();
+        let mut builder = ChunkStreamBuilder::new(req.chunk_size as usize);
+        let mut data = req.data;
+        loop {
+            let ret = match builder.next_chunk(&mut data).await {
+                Ok(Some((chunk_id, buf))) => writer
+                    .write(&chunk_id, Box::new(MemChunk::from(buf)))
+                    .await
+                    .map(|_| true),
+                Ok(None) => Ok(false),
+                Err(e) => Err(e),
+            };
+
+            match ret {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(e) => {
+                    error!(
+                        "trans publish stream failed! owner={}, pos={}, {}",
+                        req.owner,
+                        builder.len(),
+                        e
+                    );
+                    let _ = writer.err(&e).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        writer.finish().await?;
+
+        let file = builder.finish(Some(req.owner.clone()));
+        let file_id = file.desc().calculate_id();
+        let chunk_list = file
+            .body_expect("invalid file object body")
+            .content()
+            .inner_chunk_list()
+            .unwrap()
+            .to_owned();
+
+        let file_recorder = FileRecorder::new(
+            self.named_data_components.ndc.clone(),
+            self.named_data_components.tracker.clone(),
+            self.noc.clone(),
+            req.common.source.dec.clone(),
+        );
+        file_recorder.put_file_to_noc(&file, req.access).await?;
+        file_recorder.add_file_to_ndc(&file, None).await?;
+
+        info!(
+            "trans publish stream success! file={}, len={}, chunks={}",
+            file_id,
+            file.len(),
+            chunk_list.len()
+        );
+
+        Ok(TransPublishStreamInputResponse {
+            file_id,
+            file,
+            chunk_list,
+        })
+    }
+
     async fn add_file_impl(
         &self,
         req: TransPublishFileInputRequest,
@@ -460,6 +544,7 @@ impl LocalTransService {
         let resp = TransGetTaskStateInputResponse {
             state,
             group: task_state.group,
+            traffic: task_state.traffic,
         };
 
         Ok(resp)
@@ -474,16 +559,31 @@ impl LocalTransService {
         } else {
             None
         };
+
+        // 指定了分页时忽略range
+        let page = match &req.page {
+            Some(page) => Some((page.offset()?, page.page_size())),
+            None => None,
+        };
+        let range = page.or(req.range);
+
         let task_list = self
             .download_tasks
             .get_tasks(
                 req.common.source.zone.device.as_ref().unwrap(),
                 &req.common.source.dec,
                 task_status,
-                req.range,
+                range,
             )
             .await?;
-        Ok(TransQueryTasksInputResponse { task_list })
+
+        let page = match page {
+            Some((offset, page_size)) => {
+                PageInfo::from_offset(offset, task_list.len(), page_size, None)
+            }
+            None => PageInfo::default(),
+        };
+        Ok(TransQueryTasksInputResponse { task_list, page })
     }
 
     async fn get_task_group_state(
@@ -492,9 +592,10 @@ impl LocalTransService {
     ) -> BuckyResult<TransGetTaskGroupStateInputResponse> {
         let group = TaskGroupHelper::check_and_fix(&req.common.source.dec, req.group);
 
-        let task = match req.group_type {
-            TransTaskGroupType::Download => self.bdt_stack.ndn().root_task().download().sub_task(&group).map(|task| task.clone_as_task()), 
-            TransTaskGroupType::Upload => self.bdt_stack.ndn().root_task().upload().sub_task(&group).map(|task| task.clone_as_task()), 
+        // 流量统计目前只有下载任务组支持
+        let (task, traffic) = match req.group_type {
+            TransTaskGroupType::Download => self.bdt_stack.ndn().root_task().download().sub_task(&group).map(|task| (task.clone_as_task(), TransTaskTrafficState::from(&task.traffic_stat()))), 
+            TransTaskGroupType::Upload => self.bdt_stack.ndn().root_task().upload().sub_task(&group).map(|task| (task.clone_as_task(), TransTaskTrafficState::default())), 
         }.ok_or_else(|| {
             let msg = format!("get task group but ot found! group={}", group);
             error!("{}", msg);
@@ -507,7 +608,8 @@ impl LocalTransService {
             speed: None,
             cur_speed: task.cur_speed(),
             history_speed: task.history_speed(),
-            transfered: task.transfered()
+            transfered: task.transfered(),
+            traffic,
         };
 
         if let Some(_tm) = req.speed_when {
@@ -546,6 +648,73 @@ impl LocalTransService {
         Ok(resp)
     }
 
+    // 按照file对象校验本地文件，需要修复的话只重新下载不匹配的chunk
+    pub async fn verify_file(
+        &self,
+        mut req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        info!("trans recv verify file request: {:?}", req);
+
+        let object = self
+            .get_object_from_noc(&req.common.source, &req.file_id)
+            .await?;
+        let file = match object.as_ref() {
+            AnyNamedObject::Standard(StandardObject::File(file)) => file.clone(),
+            _ => {
+                let msg = format!(
+                    "trans verify file but not file object! file_id={}",
+                    req.file_id
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        };
+
+        let verifier =
+            LocalFileVerifier::new(self.bdt_stack.clone(), file, req.local_path.clone());
+        let mut resp = verifier.verify().await?;
+        if resp.valid || !req.repair {
+            return Ok(resp);
+        }
+
+        if req.device_list.is_empty() {
+            req.device_list = self.resolve_ood(&req.file_id).await?;
+        } else {
+            self.ood_resolver.ensure_device_list(&req.device_list).await?;
+        }
+
+        let referer = BdtDataRefererInfo {
+            target: None,
+            object_id: req.file_id.clone(),
+            inner_path: None,
+            dec_id: Some(req.common.source.dec.clone()),
+            req_path: req.common.req_path.clone(),
+            referer_object: req.common.referer_object.clone(),
+            flags: req.common.flags,
+        };
+
+        let context = self
+            .named_data_components
+            .context_manager
+            .create_download_context_from_target(
+                referer.encode_string(),
+                req.device_list[0].clone(),
+            )
+            .await?;
+        let group = TaskGroupHelper::new_opt_with_dec(&req.common.source.dec, None);
+
+        verifier.repair(&mut resp, context, group).await?;
+
+        info!(
+            "trans repair local file complete! file={}, local_path={}, valid={}",
+            req.file_id,
+            req.local_path.display(),
+            resp.valid
+        );
+
+        Ok(resp)
+    }
+
     async fn get_object_from_noc(
         &self,
         source: &RequestSourceInfo,
@@ -646,6 +815,13 @@ impl TransInputProcessor for LocalTransService {
         Self::publish_file(self, req).await
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        Self::publish_stream(self, req).await
+    }
+
     async fn create_task(
         &self,
         req: TransCreateTaskInputRequest,
@@ -673,4 +849,11 @@ impl TransInputProcessor for LocalTransService {
     ) -> BuckyResult<TransControlTaskGroupInputResponse> {
         Self::control_task_group(self, req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        Self::verify_file(self, req).await
+    }
 }
@@ -6,6 +6,7 @@ mod trans_store;
 mod db_helper;
 mod download_task_tracker;
 mod task;
+mod file_verifier;
 mod trans_proto {
     include!(concat!(env!("OUT_DIR"), "/trans_proto.rs"));
 }
@@ -17,3 +18,4 @@ pub(crate) use local_service::*;
 pub(crate) use trans_store::*;
 pub(crate) use db_helper::*;
 pub(crate) use download_task_tracker::*;
+pub(crate) use file_verifier::*;
@@ -1,4 +1,4 @@
-use super::super::download_task_manager::DownloadTaskState;
+use super::super::download_task_manager::{DownloadTaskIntegrityState, DownloadTaskState};
 use super::chunk_task::DownloadChunkParam;
 use super::file_task::DownloadFileParam;
 use super::verify_file_task::*;
@@ -10,6 +10,7 @@ use cyfs_bdt_ext::{
     ChunkListReaderAdapter, ChunkWriter, LocalChunkWriter, LocalFileWriter, NDNTaskCancelStrategy,
     TransContextHolder,
 };
+use cyfs_lib::TransTaskTrafficState;
 use cyfs_task_manager::*;
 
 use async_std::sync::Mutex as AsyncMutex;
@@ -30,10 +31,13 @@ pub(super) struct DownloadFileTaskParams {
     pub save_path: Option<String>,
     pub group: Option<String>,
     pub context: Option<String>,
+
+    // 下载到本地路径后恢复File对象里保存的文件元数据
+    pub preserve_metadata: bool,
 }
 
 impl DownloadFileTaskParams {
-    pub fn new_file(param: DownloadFileParam) -> Self {
+    pub fn new_file(param: DownloadFileParam, preserve_metadata: bool) -> Self {
         Self {
             task_type: DOWNLOAD_FILE_TASK,
 
@@ -45,6 +49,7 @@ impl DownloadFileTaskParams {
             save_path: param.save_path,
             group: param.group,
             context: param.context,
+            preserve_metadata,
         }
     }
 
@@ -60,6 +65,7 @@ impl DownloadFileTaskParams {
             save_path: param.save_path,
             group: param.group,
             context: param.context,
+            preserve_metadata: false,
         }
     }
 
@@ -311,6 +317,7 @@ impl DownloadFileTask {
             self.params.file.clone(),
             self.params.chunk_id.clone(),
             self.params.save_path.clone(),
+            self.params.preserve_metadata,
         ));
         vtask.start_task().await?;
 
@@ -332,6 +339,8 @@ impl DownloadFileTask {
                 downloaded_progress: 100,
                 sum_size: self.params.len(),
                 group: self.params.group.clone(),
+                integrity: Default::default(),
+                traffic: Default::default(),
             },
             TaskStatus::Finished => {
                 let ret =
@@ -346,6 +355,8 @@ impl DownloadFileTask {
                         downloaded_progress: 100,
                         sum_size: self.params.len(),
                         group: self.params.group.clone(),
+                        integrity: Default::default(),
+                        traffic: Default::default(),
                     }
                 } else {
                     let msg = format!(
@@ -362,6 +373,8 @@ impl DownloadFileTask {
                         downloaded_progress: 100,
                         sum_size: self.params.len(),
                         group: self.params.group.clone(),
+                        integrity: Default::default(),
+                        traffic: Default::default(),
                     }
                 }
             }
@@ -377,6 +390,8 @@ impl DownloadFileTask {
                     downloaded_progress: 100,
                     sum_size: self.params.len(),
                     group: self.params.group.clone(),
+                    integrity: Default::default(),
+                    traffic: Default::default(),
                 }
             }
             TaskStatus::Stopped => {
@@ -395,6 +410,8 @@ impl DownloadFileTask {
                     downloaded_progress: 100,
                     sum_size: self.params.len(),
                     group: self.params.group.clone(),
+                    integrity: Default::default(),
+                    traffic: Default::default(),
                 }
             }
         };
@@ -548,11 +565,54 @@ impl Task for DownloadFileTask {
         Ok(())
     }
 
+    async fn checkpoint(&self) -> BuckyResult<()> {
+        let mut task_status = self.task_status.lock().await;
+        if task_status.status != TaskStatus::Running {
+            return Ok(());
+        }
+
+        // 暂停下载，避免退出过程中还在写chunk，状态仍然保存为Running，重启后会自动恢复
+        if let Some(session) = self.get_session().await {
+            if let Err(e) = session.pause() {
+                warn!(
+                    "pause download task on checkpoint failed! task={}, group={:?}, {}",
+                    self.task_id,
+                    session.abs_group_path(),
+                    e
+                );
+            }
+
+            let len = self.params.len();
+            if len > 0 {
+                let progress = ((session.transfered() as f32 / len as f32) * 100.0) as u64;
+                task_status.state.set_download_progress(progress);
+            }
+        }
+
+        self.save_task_status(&task_status).await?;
+
+        info!(
+            "checkpoint download task success! task={}, progress={}",
+            self.task_id,
+            task_status.state.download_progress()
+        );
+
+        Ok(())
+    }
+
     async fn get_task_detail_status(&self) -> BuckyResult<Vec<u8>> {
         let mut task_status = self.task_status.lock().await;
 
         let session = self.get_session().await;
-        let ret = if let Some(session) = session {
+
+        // session结束之后沿用最后一次的统计
+        if let Some(session) = &session {
+            let integrity = DownloadTaskIntegrityState::from(&session.integrity_stat());
+            task_status.state.set_integrity(&integrity);
+            task_status.traffic = TransTaskTrafficState::from(&session.traffic_stat());
+        }
+
+        let mut ret = if let Some(session) = session {
             let len = self.params.len();
             let progress = if len > 0 {
                 ((session.transfered() as f32 / len as f32) * 100.0) as u64
@@ -582,6 +642,8 @@ impl Task for DownloadFileTask {
                         downloaded_progress: progress,
                         sum_size: len,
                         group: self.params.group.clone(),
+                        integrity: Default::default(),
+                        traffic: Default::default(),
                     }
                 }
                 cyfs_bdt::NdnTaskState::Paused => {
@@ -597,6 +659,8 @@ impl Task for DownloadFileTask {
                         downloaded_progress: progress,
                         sum_size: len,
                         group: self.params.group.clone(),
+                        integrity: Default::default(),
+                        traffic: Default::default(),
                     }
                 }
                 cyfs_bdt::NdnTaskState::Finished => {
@@ -626,6 +690,8 @@ impl Task for DownloadFileTask {
                             downloaded_progress: 100,
                             sum_size: len,
                             group: self.params.group.clone(),
+                            integrity: Default::default(),
+                            traffic: Default::default(),
                         }
                     } else {
                         error!("download session finished but task state is not running or paused! task={}, task state={:?}", self.task_id, task_status.status);
@@ -637,6 +703,8 @@ impl Task for DownloadFileTask {
                             downloaded_progress: 100,
                             sum_size: len,
                             group: self.params.group.clone(),
+                            integrity: Default::default(),
+                            traffic: Default::default(),
                         }
                     }
                 }
@@ -652,6 +720,8 @@ impl Task for DownloadFileTask {
                             downloaded_progress: 100,
                             sum_size: len,
                             group: self.params.group.clone(),
+                            integrity: Default::default(),
+                            traffic: Default::default(),
                         }
                     } else {
                         DownloadTaskState {
@@ -662,6 +732,8 @@ impl Task for DownloadFileTask {
                             downloaded_progress: 0,
                             sum_size: len,
                             group: self.params.group.clone(),
+                            integrity: Default::default(),
+                            traffic: Default::default(),
                         }
                     }
                 }
@@ -696,10 +768,15 @@ impl Task for DownloadFileTask {
                     downloaded_progress: task_status.state.download_progress,
                     sum_size: self.params.len(),
                     group: self.params.group.clone(),
+                    integrity: Default::default(),
+                    traffic: Default::default(),
                 }
             }
         };
 
+        ret.integrity = task_status.state.integrity();
+        ret.traffic = task_status.traffic.clone();
+
         let mut changed = false;
         if task_status.status != ret.task_status {
             info!(
@@ -738,11 +815,19 @@ impl Task for DownloadFileTask {
 #[cyfs_protobuf_type(super::super::trans_proto::DownloadFileTaskState)]
 pub(super) struct DownloadFileTaskState {
     download_progress: u64,
+    corrupt_pieces: u64,
+    duplicate_pieces: u64,
+    out_of_window_pieces: u64,
 }
 
 impl DownloadFileTaskState {
     pub fn new(download_progress: u64) -> Self {
-        DownloadFileTaskState { download_progress }
+        DownloadFileTaskState {
+            download_progress,
+            corrupt_pieces: 0,
+            duplicate_pieces: 0,
+            out_of_window_pieces: 0,
+        }
     }
 
     pub fn download_progress(&self) -> u64 {
@@ -754,12 +839,29 @@ impl DownloadFileTaskState {
             self.download_progress = download_progress;
         }
     }
+
+    pub fn integrity(&self) -> DownloadTaskIntegrityState {
+        DownloadTaskIntegrityState {
+            corrupt_pieces: self.corrupt_pieces,
+            duplicate_pieces: self.duplicate_pieces,
+            out_of_window_pieces: self.out_of_window_pieces,
+        }
+    }
+
+    pub fn set_integrity(&mut self, integrity: &DownloadTaskIntegrityState) {
+        self.corrupt_pieces = integrity.corrupt_pieces;
+        self.duplicate_pieces = integrity.duplicate_pieces;
+        self.out_of_window_pieces = integrity.out_of_window_pieces;
+    }
 }
 
 pub(super) struct DownloadFileTaskStatus {
     status: TaskStatus,
     state: DownloadFileTaskState,
     err_code: Option<BuckyErrorCode>,
+
+    // 流量统计只在内存里保留，任务重新加载后从零开始
+    traffic: TransTaskTrafficState,
 }
 
 impl DownloadFileTaskStatus {
@@ -768,6 +870,7 @@ impl DownloadFileTaskStatus {
             status: TaskStatus::Stopped,
             state: DownloadFileTaskState::new(0),
             err_code: None,
+            traffic: TransTaskTrafficState::default(),
         }
     }
 
@@ -777,6 +880,7 @@ impl DownloadFileTaskStatus {
                 status: TaskStatus::Stopped,
                 state: DownloadFileTaskState::clone_from_slice(data)?,
                 err_code: None,
+                traffic: TransTaskTrafficState::default(),
             }
         } else {
             Self::new()
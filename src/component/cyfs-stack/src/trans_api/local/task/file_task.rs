@@ -63,6 +63,7 @@ pub(crate) struct DownloadFileTaskFactory {
     named_data_components: NamedDataComponents,
     stack: StackGuard,
     trans_store: Arc<TransStore>,
+    preserve_metadata: bool,
 }
 
 impl DownloadFileTaskFactory {
@@ -70,11 +71,13 @@ impl DownloadFileTaskFactory {
         stack: StackGuard,
         named_data_components: NamedDataComponents,
         trans_store: Arc<TransStore>,
+        preserve_metadata: bool,
     ) -> Self {
         Self {
             stack,
             named_data_components,
             trans_store,
+            preserve_metadata,
         }
     }
 }
@@ -87,7 +90,7 @@ impl TaskFactory for DownloadFileTaskFactory {
 
     async fn create(&self, params: &[u8]) -> BuckyResult<Box<dyn Task>> {
         let params = DownloadFileParam::clone_from_slice(params)?;
-        let params = DownloadFileTaskParams::new_file(params);
+        let params = DownloadFileTaskParams::new_file(params, self.preserve_metadata);
 
         let task = DownloadFileTask::new(
             self.named_data_components.clone(),
@@ -106,7 +109,7 @@ impl TaskFactory for DownloadFileTaskFactory {
         data: &[u8],
     ) -> BuckyResult<Box<dyn Task>> {
         let params = DownloadFileParam::clone_from_slice(params)?;
-        let params = DownloadFileTaskParams::new_file(params);
+        let params = DownloadFileTaskParams::new_file(params, self.preserve_metadata);
 
         let status = DownloadFileTaskStatus::load(data)?;
 
@@ -2,6 +2,7 @@ use cyfs_base::*;
 use cyfs_chunk_cache::{CachedFile, ChunkManager, ChunkType};
 use cyfs_chunk_lib::ChunkRead;
 use cyfs_task_manager::*;
+use cyfs_util::FileMetadata;
 
 use async_std::io::prelude::SeekExt;
 use async_std::io::{Read, ReadExt};
@@ -18,6 +19,7 @@ pub struct VerifyFileRunnable {
     file: Option<File>,
     chunk_id: Option<ChunkId>,
     save_path: Option<String>,
+    preserve_metadata: bool,
     verify_result: Mutex<bool>,
 }
 
@@ -28,6 +30,7 @@ impl VerifyFileRunnable {
         file: Option<File>,
         chunk_id: Option<ChunkId>,
         save_path: Option<String>,
+        preserve_metadata: bool,
     ) -> Self {
         assert!(file.is_some() || chunk_id.is_some());
 
@@ -37,6 +40,7 @@ impl VerifyFileRunnable {
             file,
             chunk_id,
             save_path,
+            preserve_metadata,
             verify_result: Mutex::new(false),
         }
     }
@@ -350,6 +354,35 @@ impl VerifyFileRunnable {
 
         Ok(())
     }
+
+    // 校验通过后恢复发布时保存在File对象里的mtime、权限和扩展属性
+    fn restore_metadata(&self) {
+        if !self.preserve_metadata {
+            return;
+        }
+
+        let (file, save_path) = match (&self.file, &self.save_path) {
+            (Some(file), Some(save_path)) if !save_path.is_empty() => (file, save_path),
+            _ => return,
+        };
+
+        if let Some(metadata) = FileMetadata::decode_from_file(file) {
+            match metadata.apply(Path::new(save_path)) {
+                Ok(()) => {
+                    info!(
+                        "restore local file metadata success! task={}, local_file={}",
+                        self.task_id, save_path
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        "restore local file metadata failed! task={}, local_file={}, {}",
+                        self.task_id, save_path, e
+                    );
+                }
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -371,6 +404,7 @@ impl Runnable for VerifyFileRunnable {
     async fn run(&self) -> BuckyResult<()> {
         match self.verify().await {
             Ok(()) => {
+                self.restore_metadata();
                 *self.verify_result.lock().unwrap() = true;
             }
             Err(_) => {
@@ -55,8 +55,7 @@ impl TransStore {
 }
 
 pub async fn create_trans_store(isolate: &str) -> BuckyResult<Arc<TransStore>> {
-    let mut base_dir = cyfs_util::get_cyfs_root_path();
-    base_dir.push("data");
+    let mut base_dir = cyfs_util::get_storage_root(cyfs_util::StorageRootCategory::TransTmp);
     base_dir.push(isolate);
     base_dir.push("tracker-cache");
 
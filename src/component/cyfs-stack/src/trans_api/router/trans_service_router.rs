@@ -8,6 +8,7 @@ use crate::forward::ForwardProcessorManager;
 use crate::trans::{TransInputProcessor, TransInputProcessorRef, TransInputTransformer};
 use crate::trans_api::TransAclInnerInputProcessor;
 use crate::zone::ZoneManagerRef;
+use crate::AclManagerRef;
 
 #[derive(Clone)]
 pub struct TransServiceRouter {
@@ -15,12 +16,14 @@ pub struct TransServiceRouter {
     forward: ForwardProcessorManager,
     fail_handler: ObjectFailHandler,
     zone_manager: ZoneManagerRef,
+    acl: AclManagerRef,
 }
 
 impl TransServiceRouter {
     pub(crate) fn new(
         forward: ForwardProcessorManager,
         zone_manager: ZoneManagerRef,
+        acl: AclManagerRef,
         fail_handler: ObjectFailHandler,
         processor: TransInputProcessorRef,
     ) -> TransInputProcessorRef {
@@ -28,6 +31,7 @@ impl TransServiceRouter {
         let ret = Self {
             processor,
             zone_manager,
+            acl,
             forward,
             fail_handler,
         };
@@ -75,10 +79,40 @@ impl TransServiceRouter {
         }
     }
 
+    // Requests from current zone to other zone should been checked by the source dec's egress policy
+    async fn check_egress(&self, source: &RequestSourceInfo, target: &ObjectId) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            return Ok(());
+        }
+
+        let info = self
+            .zone_manager
+            .target_zone_manager()
+            .resolve_target(Some(target))
+            .await?;
+        if info.is_current_zone {
+            return Ok(());
+        }
+
+        self.acl
+            .global_state_meta()
+            .check_egress(source, info.target_owner.as_ref(), &info.target_device)
+            .await
+    }
+
     pub async fn create_task(
         &self,
         req: TransCreateTaskInputRequest,
     ) -> BuckyResult<TransCreateTaskInputResponse> {
+        // 任务的target和下载源device都需要检查
+        if let Some(target) = &req.common.target {
+            self.check_egress(&req.common.source, target).await?;
+        }
+        for device_id in &req.device_list {
+            self.check_egress(&req.common.source, device_id.object_id())
+                .await?;
+        }
+
         let processor = self.get_processor(req.common.target.as_ref()).await?;
         processor.create_task(req).await
     }
@@ -117,6 +151,19 @@ impl TransServiceRouter {
         self.processor.publish_file(req).await
     }
 
+    pub async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        if req.common.target.is_some() {
+            let msg = format!("target not support for trans.publish_stream!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+        }
+
+        self.processor.publish_stream(req).await
+    }
+
     async fn get_task_group_state(
         &self,
         req: TransGetTaskGroupStateInputRequest,
@@ -132,6 +179,19 @@ impl TransServiceRouter {
         let processor = self.get_processor(req.common.target.as_ref()).await?;
         processor.control_task_group(req).await
     }
+
+    pub async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        if req.common.target.is_some() {
+            let msg = format!("target not support for trans.verify_file!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::UnSupport, msg));
+        }
+
+        self.processor.verify_file(req).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -178,6 +238,13 @@ impl TransInputProcessor for TransServiceRouter {
         Self::publish_file(self, req).await
     }
 
+    async fn publish_stream(
+        &self,
+        req: TransPublishStreamInputRequest,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        Self::publish_stream(self, req).await
+    }
+
     async fn get_task_group_state(
         &self,
         req: TransGetTaskGroupStateInputRequest,
@@ -191,4 +258,11 @@ impl TransInputProcessor for TransServiceRouter {
     ) -> BuckyResult<TransControlTaskGroupInputResponse> {
         Self::control_task_group(self, req).await
     }
+
+    async fn verify_file(
+        &self,
+        req: TransVerifyFileInputRequest,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        Self::verify_file(self, req).await
+    }
 }
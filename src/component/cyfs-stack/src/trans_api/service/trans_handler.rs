@@ -41,7 +41,8 @@ impl TransRequestHandler {
             referer_object,
             target,
             flags: flags.unwrap_or(0),
-            user_data: None
+            user_data: None,
+            deadline: None,
         };
 
         Ok(ret)
@@ -100,6 +101,20 @@ impl TransRequestHandler {
         }
     }
 
+    pub async fn process_publish_stream<State>(&self, req: NONInputHttpRequest<State>) -> tide::Response {
+        match self.on_publish_stream(req).await {
+            Ok(resp) => {
+                let mut http_resp: tide::Response = RequestorHelper::new_ok_response();
+
+                let body = resp.encode_string();
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(body);
+                http_resp
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
     pub async fn process_get_context<State>(&self, req: NONInputHttpRequest<State>) -> tide::Response {
         match self.on_get_context(req).await {
             Ok(resp) => {
@@ -239,6 +254,31 @@ impl TransRequestHandler {
         self.processor.publish_file(req).await
     }
 
+    async fn on_publish_stream<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TransPublishStreamInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+
+        let owner = RequestorHelper::decode_header(&req.request, cyfs_base::CYFS_OWNER_ID)?;
+        let chunk_size = RequestorHelper::decode_header(&req.request, cyfs_base::CYFS_CHUNK_SIZE)?;
+        let access: Option<u32> = RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_ACCESS)?;
+        let access = access.map(|v| AccessString::new(v));
+
+        // 数据长度未知，body直接作为流交给后续处理
+        let data = req.request.take_body();
+
+        let req = TransPublishStreamInputRequest {
+            common,
+            owner,
+            chunk_size,
+            access,
+            data: Box::new(data),
+        };
+
+        self.processor.publish_stream(req).await
+    }
+
     async fn on_get_context<State>(&self, mut req: NONInputHttpRequest<State>) -> BuckyResult<TransGetContextInputResponse> {
         let common = Self::decode_common_headers(&req)?;
 
@@ -298,7 +338,8 @@ impl TransRequestHandler {
         let req = TransQueryTasksInputRequest {
             common,
             task_status: JsonCodecHelper::decode_option_string_field(&body, "task_status")?,
-            range
+            range,
+            page: PageRequest::decode_json_fields(&body)?,
         };
         self.processor.query_tasks(req).await
     }
@@ -376,4 +417,44 @@ impl TransRequestHandler {
 
         self.processor.get_task_group_state(req).await
     }
+
+    // verify local file
+    pub async fn process_verify_file<State>(&self, req: NONInputHttpRequest<State>) -> tide::Response {
+        match self.on_verify_file(req).await {
+            Ok(resp) => {
+                let mut http_resp: tide::Response = RequestorHelper::new_ok_response();
+
+                let body = serde_json::to_string(&resp).unwrap();
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(body);
+
+                http_resp
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_verify_file<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TransVerifyFileInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+
+        let body = req.request.body_json().await.map_err(|e| {
+            let msg = format!("trans verify file failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let req = TransVerifyFileInputRequest {
+            common,
+            file_id: JsonCodecHelper::decode_string_field(&body, "file_id")?,
+            local_path: JsonCodecHelper::decode_string_field(&body, "local_path")?,
+            repair: JsonCodecHelper::decode_bool_field(&body, "repair")?,
+            device_list: JsonCodecHelper::decode_str_array_field(&body, "device_list")?,
+        };
+
+        self.processor.verify_file(req).await
+    }
 }
@@ -11,12 +11,15 @@ enum TransRequestType {
     ControlTask,
     GetTaskState,
     PublishFile,
+    PublishStream,
     GetContext,
     PutContext,
     QueryTasks,
 
     ControlTaskGroup,
     GetTaskGroupState,
+
+    VerifyFile,
 }
 
 pub(crate) struct TransRequestHandlerEndpoint {
@@ -45,12 +48,15 @@ impl TransRequestHandlerEndpoint {
             TransRequestType::ControlTask => self.handler.process_control_task(req).await,
             TransRequestType::GetTaskState => self.handler.process_get_task_state(req).await,
             TransRequestType::PublishFile => self.handler.process_publish_file(req).await,
+            TransRequestType::PublishStream => self.handler.process_publish_stream(req).await,
             TransRequestType::GetContext => self.handler.process_get_context(req).await,
             TransRequestType::PutContext => self.handler.process_put_context(req).await,
             TransRequestType::QueryTasks => self.handler.process_query_tasks_context(req).await,
 
             TransRequestType::ControlTaskGroup => self.handler.process_control_task_group(req).await,
             TransRequestType::GetTaskGroupState => self.handler.process_get_task_group_state(req).await,
+
+            TransRequestType::VerifyFile => self.handler.process_verify_file(req).await,
         }
     }
 
@@ -85,6 +91,10 @@ impl TransRequestHandlerEndpoint {
             .at("/trans/file")
             .post(Self::new(zone_manager.clone(), protocol.to_owned(), TransRequestType::PublishFile, handler.clone()));
 
+        server
+            .at("/trans/file/stream")
+            .post(Self::new(zone_manager.clone(), protocol.to_owned(), TransRequestType::PublishStream, handler.clone()));
+
         server
             .at("/trans/tasks")
             .post(Self::new(zone_manager.clone(), protocol.to_owned(), TransRequestType::QueryTasks, handler.clone()));
@@ -99,6 +109,11 @@ impl TransRequestHandlerEndpoint {
             .at("/trans/task_group")
             .put(Self::new(zone_manager.clone(), protocol.to_owned(), TransRequestType::ControlTaskGroup, handler.clone()));
 
+        // verify local file
+        server
+            .at("/trans/file/verify")
+            .post(Self::new(zone_manager.clone(), protocol.to_owned(), TransRequestType::VerifyFile, handler.clone()));
+
     }
 }
 
@@ -24,11 +24,12 @@ impl TransService {
         named_data_components: &NamedDataComponents,
         ood_resolver: OodResolver,
         task_manager: Arc<TaskManager>,
-        _acl: AclManagerRef,
+        acl: AclManagerRef,
         forward: ForwardProcessorManager,
         zone_manager: ZoneManagerRef,
         fail_handler: ObjectFailHandler,
         trans_store: Arc<TransStore>,
+        preserve_file_metadata: bool,
     ) -> Self {
         let local_service = LocalTransService::new(
             noc.clone(),
@@ -37,10 +38,12 @@ impl TransService {
             ood_resolver.clone(),
             task_manager.clone(),
             trans_store,
+            preserve_file_metadata,
         );
         let router = TransServiceRouter::new(
             forward,
             zone_manager,
+            acl,
             fail_handler,
             local_service.clone_processor(),
         );
@@ -0,0 +1,26 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub(crate) trait TrashInputProcessor: Sync + Send + 'static {
+    async fn list_trash(&self, req: TrashListInputRequest) -> BuckyResult<TrashListInputResponse>;
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse>;
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse>;
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse>;
+}
+
+pub(crate) type TrashInputProcessorRef = Arc<dyn TrashInputProcessor>;
@@ -0,0 +1,635 @@
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+// 回收站保存在当前设备local_cache的系统dec下，和被删除的noc对象在同一个设备上:
+// /trash/{dec_id}/items/{id} -> 记录text_id
+// /trash/{dec_id}/config -> 配置text_id
+const TRASH_ROOT_PATH: &str = "/trash";
+
+// Text对象id，header为dec_id，value为TrashItem的json
+const TRASH_ITEM_TEXT_ID: &str = "trash_item";
+
+// Text对象id，header为dec_id，value为TrashConfig的json
+const TRASH_CONFIG_TEXT_ID: &str = "trash_config";
+
+// 检查到期记录的间隔
+const TRASH_PURGE_INTERVAL_SECS: u64 = 60 * 10;
+
+struct TrashManagerInner {
+    noc: NamedObjectCacheRef,
+    non: NONOutputProcessorRef,
+    index_stub: GlobalStateStub,
+
+    root_state: GlobalStateInputProcessorRef,
+    local_cache: GlobalStateInputProcessorRef,
+}
+
+// dec删除的noc对象和root_state路径，在清理前可以恢复
+// 记录id以清理时间开头，按key排序即为清理顺序
+#[derive(Clone)]
+pub(crate) struct TrashManager(Arc<TrashManagerInner>);
+
+impl TrashManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        noc: NamedObjectCacheRef,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+        local_cache: GlobalStateInputProcessorRef,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(local_cache.clone(), source);
+        let index_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = TrashManagerInner {
+            noc,
+            non,
+            index_stub,
+            root_state,
+            local_cache,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run().await;
+        });
+    }
+
+    async fn run(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(TRASH_PURGE_INTERVAL_SECS));
+        loop {
+            let _ = interval.next().await;
+
+            if let Err(e) = self.purge_expired().await {
+                error!("purge expired trash items failed! {}", e);
+            }
+        }
+    }
+
+    fn dec_path(dec_id: &ObjectId) -> String {
+        format!("{}/{}", TRASH_ROOT_PATH, dec_id)
+    }
+
+    fn items_path(dec_id: &ObjectId) -> String {
+        format!("{}/items", Self::dec_path(dec_id))
+    }
+
+    fn item_id(purge_time: u64, object_id: &ObjectId) -> String {
+        format!("{:020}-{}", purge_time, object_id)
+    }
+
+    fn parse_purge_time(id: &str) -> Option<u64> {
+        id.split_once('-').and_then(|(time, _)| time.parse().ok())
+    }
+
+    pub async fn get_config(&self, dec_id: &ObjectId) -> BuckyResult<TrashConfig> {
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&Self::dec_path(dec_id), "config").await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(v)) => v,
+            Ok(None) => return Ok(TrashConfig::default()),
+            Err(e) if e.code() == BuckyErrorCode::NotFound => return Ok(TrashConfig::default()),
+            Err(e) => {
+                error!("load trash config failed! dec={}, {}", dec_id, e);
+                return Err(e);
+            }
+        };
+
+        let text = self.load_text(&text_id).await?;
+        Self::decode_text(&text, TRASH_CONFIG_TEXT_ID)
+    }
+
+    pub async fn set_config(&self, dec_id: &ObjectId, config: &TrashConfig) -> BuckyResult<()> {
+        let value = serde_json::to_string(config).unwrap();
+        let text_id = self
+            .save_text(TRASH_CONFIG_TEXT_ID, &dec_id.to_string(), value)
+            .await?;
+
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        let prev = match op_env
+            .set_with_key(&Self::dec_path(dec_id), "config", &text_id, None, true)
+            .await
+        {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("save trash config failed! dec={}, {}", dec_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        info!(
+            "set trash config success! dec={}, purge_delay_secs={}",
+            dec_id, config.purge_delay_secs
+        );
+
+        if let Some(prev) = prev {
+            if prev != text_id {
+                self.remove_text(&prev).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    // noc里被删除的对象，data为删除时返回的对象和meta
+    pub async fn trash_object(
+        &self,
+        source: &RequestSourceInfo,
+        object: &NONObjectInfo,
+        meta: &NamedObjectMetaData,
+    ) -> BuckyResult<TrashItem> {
+        let mut item = self.new_item(source, TrashItemKind::Object, &object.object_id).await?;
+        item.object_raw = object.object_raw.clone();
+        item.create_dec_id = Some(meta.create_dec_id.clone());
+        item.access_string = Some(meta.access_string);
+        item.storage_category = Some(meta.storage_category.as_u8());
+        item.context = meta.context.clone();
+
+        self.add_item(&item).await?;
+        Ok(item)
+    }
+
+    // path_op_env里被删除的路径
+    pub async fn trash_path(
+        &self,
+        source: &RequestSourceInfo,
+        category: GlobalStateCategory,
+        target_dec_id: &ObjectId,
+        full_path: &str,
+        value: &ObjectId,
+    ) -> BuckyResult<TrashItem> {
+        let mut item = self.new_item(source, TrashItemKind::Path, value).await?;
+        item.category = Some(category);
+        item.target_dec_id = Some(target_dec_id.clone());
+        item.path = Some(full_path.to_owned());
+
+        self.add_item(&item).await?;
+        Ok(item)
+    }
+
+    async fn new_item(
+        &self,
+        source: &RequestSourceInfo,
+        kind: TrashItemKind,
+        object_id: &ObjectId,
+    ) -> BuckyResult<TrashItem> {
+        let config = self.get_config(&source.dec).await?;
+
+        let delete_time = bucky_time_now();
+        let purge_time = delete_time + config.purge_delay_secs * 1000 * 1000;
+
+        Ok(TrashItem {
+            id: Self::item_id(purge_time, object_id),
+            kind,
+            dec_id: source.dec.clone(),
+            object_id: object_id.clone(),
+            object_raw: vec![],
+            create_dec_id: None,
+            access_string: None,
+            storage_category: None,
+            context: None,
+            category: None,
+            target_dec_id: None,
+            path: None,
+            delete_time,
+            purge_time,
+        })
+    }
+
+    async fn add_item(&self, item: &TrashItem) -> BuckyResult<()> {
+        let value = serde_json::to_string(item).unwrap();
+        let text_id = self
+            .save_text(TRASH_ITEM_TEXT_ID, &item.dec_id.to_string(), value)
+            .await?;
+
+        let path = Self::items_path(&item.dec_id);
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        if let Err(e) = op_env.set_with_key(&path, &item.id, &text_id, None, true).await {
+            error!("save trash item failed! item={}, {}", item, e);
+            let _ = op_env.abort().await;
+            self.remove_text(&text_id).await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        info!("move to trash success! item={}", item);
+
+        Ok(())
+    }
+
+    // 最近删除的在前
+    pub async fn list(&self, dec_id: &ObjectId) -> BuckyResult<Vec<TrashItem>> {
+        let ids = self.list_items(&Self::items_path(dec_id)).await?;
+
+        let mut result = Vec::with_capacity(ids.len());
+        for (id, text_id) in ids {
+            match self
+                .load_text(&text_id)
+                .await
+                .and_then(|text| Self::decode_text::<TrashItem>(&text, TRASH_ITEM_TEXT_ID))
+            {
+                Ok(item) => result.push(item),
+                Err(e) => {
+                    warn!("load trash item failed! dec={}, id={}, {}", dec_id, id, e);
+                }
+            }
+        }
+
+        result.sort_by(|a, b| b.delete_time.cmp(&a.delete_time));
+
+        Ok(result)
+    }
+
+    async fn load_item(&self, dec_id: &ObjectId, id: &str) -> BuckyResult<(ObjectId, TrashItem)> {
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        let ret = op_env.get_by_key(&Self::items_path(dec_id), id).await;
+        let _ = op_env.abort().await;
+
+        let text_id = match ret {
+            Ok(Some(v)) => v,
+            Ok(None) => None,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => None,
+            Err(e) => {
+                error!("load trash item failed! dec={}, id={}, {}", dec_id, id, e);
+                return Err(e);
+            }
+        }
+        .ok_or_else(|| {
+            let msg = format!("trash item not found! dec={}, id={}", dec_id, id);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotFound, msg)
+        })?;
+
+        let text = self.load_text(&text_id).await?;
+        let item = Self::decode_text(&text, TRASH_ITEM_TEXT_ID)?;
+
+        Ok((text_id, item))
+    }
+
+    // 把记录放回原来的位置，成功后从回收站移除
+    pub async fn restore(
+        &self,
+        source: &RequestSourceInfo,
+        id: &str,
+    ) -> BuckyResult<TrashItem> {
+        let (text_id, item) = self.load_item(&source.dec, id).await?;
+
+        match item.kind {
+            TrashItemKind::Object => self.restore_object(&item).await?,
+            TrashItemKind::Path => self.restore_path(source, &item).await?,
+        }
+
+        self.remove_item(&item.dec_id, &item.id, &text_id).await?;
+
+        info!("restore trash item success! item={}", item);
+
+        Ok(item)
+    }
+
+    async fn restore_object(&self, item: &TrashItem) -> BuckyResult<()> {
+        let object = NONObjectInfo::new_from_object_raw(item.object_raw.clone())?;
+        if object.object_id != item.object_id {
+            let msg = format!(
+                "trash item object id not match! item={}, got={}",
+                item, object.object_id
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        let storage_category = match item.storage_category {
+            Some(v) => NamedObjectStorageCategory::try_from(v)?,
+            None => NamedObjectStorageCategory::default(),
+        };
+
+        // 按删除前的meta重新写入，create_dec保持不变
+        let req = NamedObjectCachePutObjectRequest {
+            source: RequestSourceInfo::new_local_dec(item.create_dec_id.clone()),
+            object,
+            storage_category,
+            context: item.context.clone(),
+            last_access_rpath: None,
+            access_string: item.access_string,
+            precondition: None,
+        };
+
+        self.0.noc.put_object(&req).await.map_err(|e| {
+            error!("restore trash object to noc failed! item={}, {}", item, e);
+            e
+        })?;
+
+        Ok(())
+    }
+
+    async fn restore_path(&self, source: &RequestSourceInfo, item: &TrashItem) -> BuckyResult<()> {
+        let (category, path) = match (&item.category, &item.path) {
+            (Some(category), Some(path)) => (category, path),
+            _ => {
+                let msg = format!("invalid trash path item! item={}", item);
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+            }
+        };
+
+        let processor = match category {
+            GlobalStateCategory::RootState => self.0.root_state.clone(),
+            GlobalStateCategory::LocalCache => self.0.local_cache.clone(),
+        };
+
+        // 使用发起恢复的请求方身份，和删除时一样走正常的权限检查
+        let stub = GlobalStateStub::new(
+            GlobalStateOutputTransformer::new(processor, source.clone()),
+            None,
+            item.target_dec_id.clone(),
+        );
+
+        // 原路径已经被重新占用时返回AlreadyExists，不会覆盖
+        let op_env = stub.create_path_op_env().await?;
+        if let Err(e) = op_env.insert_with_path(path, &item.object_id).await {
+            error!("restore trash path failed! item={}, {}", item, e);
+            let _ = op_env.abort().await;
+            return Err(e);
+        }
+        op_env.commit().await?;
+
+        Ok(())
+    }
+
+    // id为空时清空dec的全部记录，返回清理的条数
+    pub async fn purge(&self, dec_id: &ObjectId, id: Option<&str>) -> BuckyResult<u32> {
+        let list = match id {
+            Some(id) => {
+                let (text_id, _) = self.load_item(dec_id, id).await?;
+                vec![(id.to_owned(), text_id)]
+            }
+            None => self.list_items(&Self::items_path(dec_id)).await?,
+        };
+
+        let mut count = 0;
+        for (id, text_id) in list {
+            if self.remove_item(dec_id, &id, &text_id).await? {
+                count += 1;
+            }
+        }
+
+        info!("purge trash items success! dec={}, count={}", dec_id, count);
+
+        Ok(count)
+    }
+
+    async fn purge_expired(&self) -> BuckyResult<()> {
+        let now = bucky_time_now();
+
+        let decs = self.list_items(TRASH_ROOT_PATH).await?;
+        for (dec, _) in decs {
+            let dec_id = match ObjectId::from_str(&dec) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            for (id, text_id) in self.list_items(&Self::items_path(&dec_id)).await? {
+                match Self::parse_purge_time(&id) {
+                    Some(time) if time > now => break,
+                    _ => {}
+                }
+
+                if let Err(e) = self.remove_item(&dec_id, &id, &text_id).await {
+                    warn!("purge expired trash item failed! dec={}, id={}, {}", dec_id, id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_item(&self, dec_id: &ObjectId, id: &str, text_id: &ObjectId) -> BuckyResult<bool> {
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        let ret = match op_env
+            .remove_with_key(&Self::items_path(dec_id), id, Some(text_id.clone()))
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                error!("remove trash item failed! dec={}, id={}, {}", dec_id, id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        if ret.is_none() {
+            return Ok(false);
+        }
+
+        self.remove_text(text_id).await;
+
+        Ok(true)
+    }
+
+    // 按key排序
+    async fn list_items(&self, path: &str) -> BuckyResult<Vec<(String, ObjectId)>> {
+        let op_env = self.0.index_stub.create_path_op_env().await?;
+        let ret = op_env.list(path).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list trash items failed! path={}, {}", path, e);
+                return Err(e);
+            }
+        };
+
+        let mut result: Vec<(String, ObjectId)> = list
+            .into_iter()
+            .filter_map(|item| match item {
+                ObjectMapContentItem::Map(v) => Some(v),
+                _ => None,
+            })
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(result)
+    }
+
+    async fn save_text(&self, id: &str, header: &str, value: String) -> BuckyResult<ObjectId> {
+        let text = Text::build(id, header, value).no_create_time().build();
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!("save trash text to noc failed! id={}, header={}, {}", id, header, e);
+            e
+        })?;
+
+        Ok(text_id)
+    }
+
+    async fn load_text(&self, text_id: &ObjectId) -> BuckyResult<Text> {
+        let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+        let resp = self.0.non.get_object(req).await?;
+
+        Text::clone_from_slice(&resp.object.object_raw)
+    }
+
+    fn decode_text<T>(text: &Text, id: &str) -> BuckyResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if text.id() != id {
+            let msg = format!("invalid trash text id: expect={}, got={}", id, text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!("invalid trash text value! id={}, header={}, {}", id, text.header(), e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn remove_text(&self, text_id: &ObjectId) {
+        let req = NONDeleteObjectOutputRequest::new_noc(text_id.clone(), None);
+        if let Err(e) = self.0.non.delete_object(req).await {
+            warn!("remove trash text from noc failed! text={}, {}", text_id, e);
+        }
+    }
+}
+
+// noc和global state的服务在回收站之前创建，通过holder延迟绑定
+#[derive(Clone)]
+pub struct TrashManagerHolder(Arc<OnceCell<TrashManager>>);
+
+impl TrashManagerHolder {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(OnceCell::new()))
+    }
+
+    pub(crate) fn bind(&self, manager: TrashManager) {
+        if let Err(_) = self.0.set(manager) {
+            unreachable!();
+        }
+    }
+
+    pub(crate) fn get(&self) -> BuckyResult<&TrashManager> {
+        self.0.get().ok_or_else(|| {
+            let msg = format!("trash manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_item(object_id: &ObjectId) -> TrashItem {
+        TrashItem {
+            id: TrashManager::item_id(1000, object_id),
+            kind: TrashItemKind::Object,
+            dec_id: cyfs_core::get_system_dec_app().to_owned(),
+            object_id: object_id.clone(),
+            object_raw: vec![1, 2, 3],
+            create_dec_id: None,
+            access_string: Some(AccessString::default().value()),
+            storage_category: Some(NamedObjectStorageCategory::Storage.as_u8()),
+            context: None,
+            category: None,
+            target_dec_id: None,
+            path: None,
+            delete_time: 100,
+            purge_time: 1000,
+        }
+    }
+
+    #[test]
+    fn test_item_id() {
+        let object_id = Text::create("test", "", "value").desc().calculate_id();
+
+        // 按key排序即为清理时间的顺序
+        let id1 = TrashManager::item_id(999, &object_id);
+        let id2 = TrashManager::item_id(1000, &object_id);
+        let id3 = TrashManager::item_id(u64::MAX, &object_id);
+        assert!(id1 < id2 && id2 < id3);
+
+        assert_eq!(TrashManager::parse_purge_time(&id1), Some(999));
+        assert_eq!(TrashManager::parse_purge_time(&id3), Some(u64::MAX));
+        assert_eq!(TrashManager::parse_purge_time("invalid"), None);
+        assert_eq!(TrashManager::parse_purge_time("abc-def"), None);
+
+        let dec_id = cyfs_core::get_system_dec_app().to_owned();
+        assert_eq!(
+            TrashManager::items_path(&dec_id),
+            format!("/trash/{}/items", dec_id)
+        );
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let object_id = Text::create("test", "", "value").desc().calculate_id();
+        let item = new_item(&object_id);
+
+        let value = serde_json::to_string(&item).unwrap();
+        let text = Text::build(TRASH_ITEM_TEXT_ID, &item.dec_id.to_string(), value)
+            .no_create_time()
+            .build();
+        let ret: TrashItem = TrashManager::decode_text(&text, TRASH_ITEM_TEXT_ID).unwrap();
+        assert_eq!(ret.id, item.id);
+        assert_eq!(ret.object_id, object_id);
+        assert_eq!(ret.object_raw, vec![1, 2, 3]);
+        assert_eq!(ret.access_string, item.access_string);
+        assert_eq!(ret.purge_time, 1000);
+
+        // 配置和记录使用不同的text id，不能混用
+        let ret = TrashManager::decode_text::<TrashConfig>(&text, TRASH_CONFIG_TEXT_ID);
+        assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::InvalidData);
+
+        let text = Text::build(TRASH_CONFIG_TEXT_ID, "", "{}").no_create_time().build();
+        let ret = TrashManager::decode_text::<TrashConfig>(&text, TRASH_CONFIG_TEXT_ID);
+        assert_eq!(ret.unwrap_err().code(), BuckyErrorCode::InvalidData);
+
+        let config = TrashConfig {
+            purge_delay_secs: 60,
+        };
+        let value = serde_json::to_string(&config).unwrap();
+        let text = Text::build(TRASH_CONFIG_TEXT_ID, "", value)
+            .no_create_time()
+            .build();
+        let ret: TrashConfig = TrashManager::decode_text(&text, TRASH_CONFIG_TEXT_ID).unwrap();
+        assert_eq!(ret.purge_delay_secs, 60);
+    }
+}
@@ -0,0 +1,9 @@
+mod input_processor;
+mod manager;
+mod processor;
+mod transform;
+
+pub(crate) use input_processor::*;
+pub(crate) use manager::*;
+pub(crate) use processor::*;
+pub(crate) use transform::*;
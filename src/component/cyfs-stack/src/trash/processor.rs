@@ -0,0 +1,129 @@
+use super::manager::TrashManagerHolder;
+use crate::non::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 处理带有CYFS_REQUEST_FLAG_DELETE_TO_TRASH标志的delete_object，删除成功后把对象和meta放入回收站
+pub(crate) struct NONTrashInputProcessor {
+    next: NONInputProcessorRef,
+    noc: NamedObjectCacheRef,
+    trash: TrashManagerHolder,
+}
+
+impl NONTrashInputProcessor {
+    pub fn new(
+        next: NONInputProcessorRef,
+        noc: NamedObjectCacheRef,
+        trash: TrashManagerHolder,
+    ) -> NONInputProcessorRef {
+        let ret = Self { next, noc, trash };
+        Arc::new(Box::new(ret))
+    }
+
+    async fn delete_object_to_trash(
+        &self,
+        mut req: NONDeleteObjectInputRequest,
+    ) -> BuckyResult<NONDeleteObjectInputResponse> {
+        let trash = self.trash.get()?;
+
+        // 先取出删除前的meta，删除本身仍然走完整的处理链和权限检查
+        let mut get_req = NamedObjectCacheGetObjectRequest {
+            source: RequestSourceInfo::new_local_system(),
+            object_id: req.object_id.clone(),
+            last_access_rpath: None,
+            flags: 0,
+        };
+        get_req.set_no_update_last_access();
+
+        let meta = match self.noc.get_object(&get_req).await? {
+            Some(data) => data.meta,
+            None => return self.next.delete_object(req).await,
+        };
+
+        let source = req.common.source.clone();
+        let with_query = req.common.flags & CYFS_REQUEST_FLAG_DELETE_WITH_QUERY != 0;
+        req.common.flags |= CYFS_REQUEST_FLAG_DELETE_WITH_QUERY;
+
+        let mut resp = self.next.delete_object(req).await?;
+        let object = match &resp.object {
+            Some(object) => object.clone(),
+            None => return Ok(resp),
+        };
+
+        if let Err(e) = trash.trash_object(&source, &object, &meta).await {
+            error!(
+                "move deleted object to trash failed, now will put it back! object={}, {}",
+                object.object_id, e
+            );
+
+            let put_req = NamedObjectCachePutObjectRequest {
+                source: RequestSourceInfo::new_local_dec(Some(meta.create_dec_id.clone())),
+                object,
+                storage_category: meta.storage_category,
+                context: meta.context.clone(),
+                last_access_rpath: meta.last_access_rpath.clone(),
+                access_string: Some(meta.access_string),
+                precondition: None,
+            };
+            if let Err(e) = self.noc.put_object(&put_req).await {
+                error!(
+                    "put back deleted object failed! object={}, {}",
+                    meta.object_id, e
+                );
+            }
+
+            return Err(e);
+        }
+
+        if !with_query {
+            resp.object = None;
+        }
+
+        Ok(resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl NONInputProcessor for NONTrashInputProcessor {
+    async fn put_object(
+        &self,
+        req: NONPutObjectInputRequest,
+    ) -> BuckyResult<NONPutObjectInputResponse> {
+        self.next.put_object(req).await
+    }
+
+    async fn get_object(
+        &self,
+        req: NONGetObjectInputRequest,
+    ) -> BuckyResult<NONGetObjectInputResponse> {
+        self.next.get_object(req).await
+    }
+
+    async fn post_object(
+        &self,
+        req: NONPostObjectInputRequest,
+    ) -> BuckyResult<NONPostObjectInputResponse> {
+        self.next.post_object(req).await
+    }
+
+    async fn select_object(
+        &self,
+        req: NONSelectObjectInputRequest,
+    ) -> BuckyResult<NONSelectObjectInputResponse> {
+        self.next.select_object(req).await
+    }
+
+    async fn delete_object(
+        &self,
+        req: NONDeleteObjectInputRequest,
+    ) -> BuckyResult<NONDeleteObjectInputResponse> {
+        // inner_path删除的是dir/objectmap内部的内容，不支持放入回收站
+        if req.common.flags & CYFS_REQUEST_FLAG_DELETE_TO_TRASH == 0 || req.inner_path.is_some() {
+            return self.next.delete_object(req).await;
+        }
+
+        self.delete_object_to_trash(req).await
+    }
+}
@@ -0,0 +1,108 @@
+use super::input_processor::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 实现从input到output的转换
+pub(crate) struct TrashInputTransformer {
+    processor: TrashOutputProcessorRef,
+}
+
+impl TrashInputTransformer {
+    pub fn new(processor: TrashOutputProcessorRef) -> TrashInputProcessorRef {
+        let ret = Self { processor };
+        Arc::new(ret)
+    }
+
+    fn convert_common(common: UtilInputRequestCommon) -> UtilOutputRequestCommon {
+        UtilOutputRequestCommon {
+            // 请求路径，可为空
+            req_path: common.req_path,
+
+            // 来源DEC
+            dec_id: common.source.get_opt_dec().cloned(),
+
+            // 用以处理默认行为
+            target: common.target,
+
+            flags: common.flags,
+        }
+    }
+
+    async fn list_trash(&self, req: TrashListInputRequest) -> BuckyResult<TrashListInputResponse> {
+        let out_req = TrashListOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.list_trash(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        let out_req = TrashRestoreOutputRequest {
+            common: Self::convert_common(req.common),
+            id: req.id,
+        };
+
+        let out_resp = self.processor.restore_trash(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        let out_req = TrashPurgeOutputRequest {
+            common: Self::convert_common(req.common),
+            id: req.id,
+        };
+
+        let out_resp = self.processor.purge_trash(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        let out_req = TrashSetConfigOutputRequest {
+            common: Self::convert_common(req.common),
+            config: req.config,
+        };
+
+        let out_resp = self.processor.set_trash_config(out_req).await?;
+        Ok(out_resp)
+    }
+}
+
+#[async_trait::async_trait]
+impl TrashInputProcessor for TrashInputTransformer {
+    async fn list_trash(&self, req: TrashListInputRequest) -> BuckyResult<TrashListInputResponse> {
+        Self::list_trash(&self, req).await
+    }
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        Self::restore_trash(&self, req).await
+    }
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        Self::purge_trash(&self, req).await
+    }
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        Self::set_trash_config(&self, req).await
+    }
+}
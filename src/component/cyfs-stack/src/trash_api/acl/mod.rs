@@ -0,0 +1,3 @@
+mod trash_acl;
+
+pub(crate) use trash_acl::*;
@@ -0,0 +1,74 @@
+use crate::trash::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+// 限定在同zone内操作
+pub(crate) struct TrashAclInnerInputProcessor {
+    next: TrashInputProcessorRef,
+}
+
+impl TrashAclInnerInputProcessor {
+    pub(crate) fn new(next: TrashInputProcessorRef) -> TrashInputProcessorRef {
+        let ret = Self { next };
+
+        Arc::new(ret)
+    }
+
+    fn check_local_zone_permit(
+        &self,
+        service: &str,
+        source: &RequestSourceInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "{} service valid only in current zone! source={:?}, category={}",
+                service,
+                source.zone.device,
+                source.zone.zone_category.as_str()
+            );
+            error!("{}", msg);
+
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TrashInputProcessor for TrashAclInnerInputProcessor {
+    async fn list_trash(&self, req: TrashListInputRequest) -> BuckyResult<TrashListInputResponse> {
+        self.check_local_zone_permit("trash.list_trash", &req.common.source)?;
+
+        self.next.list_trash(req).await
+    }
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        self.check_local_zone_permit("trash.restore_trash", &req.common.source)?;
+
+        self.next.restore_trash(req).await
+    }
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        self.check_local_zone_permit("trash.purge_trash", &req.common.source)?;
+
+        self.next.purge_trash(req).await
+    }
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        self.check_local_zone_permit("trash.set_trash_config", &req.common.source)?;
+
+        self.next.set_trash_config(req).await
+    }
+}
@@ -0,0 +1,109 @@
+use crate::admin::AdminConfirmManager;
+use crate::trash::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct LocalTrashService {
+    trash_manager: TrashManager,
+    admin_confirm_manager: AdminConfirmManager,
+}
+
+impl LocalTrashService {
+    pub(crate) fn new(
+        trash_manager: TrashManager,
+        admin_confirm_manager: AdminConfirmManager,
+    ) -> Self {
+        Self {
+            trash_manager,
+            admin_confirm_manager,
+        }
+    }
+
+    pub fn clone_processor(&self) -> TrashInputProcessorRef {
+        Arc::new(self.clone())
+    }
+
+    pub async fn list_trash(
+        &self,
+        req: TrashListInputRequest,
+    ) -> BuckyResult<TrashListInputResponse> {
+        let list = self.trash_manager.list(&req.common.source.dec).await?;
+
+        Ok(TrashListInputResponse { list })
+    }
+
+    pub async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        self.admin_confirm_manager
+            .check(&req.common.source, AdminConfirmAction::Restore, &req.id)
+            .await?;
+
+        let item = self
+            .trash_manager
+            .restore(&req.common.source, &req.id)
+            .await?;
+
+        Ok(TrashRestoreInputResponse { item })
+    }
+
+    pub async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        let target = req.id.as_deref().unwrap_or("*");
+        self.admin_confirm_manager
+            .check(&req.common.source, AdminConfirmAction::PurgeTrash, target)
+            .await?;
+
+        let count = self
+            .trash_manager
+            .purge(&req.common.source.dec, req.id.as_deref())
+            .await?;
+
+        Ok(TrashPurgeInputResponse { count })
+    }
+
+    pub async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        self.trash_manager
+            .set_config(&req.common.source.dec, &req.config)
+            .await?;
+
+        Ok(TrashSetConfigInputResponse {})
+    }
+}
+
+#[async_trait::async_trait]
+impl TrashInputProcessor for LocalTrashService {
+    async fn list_trash(&self, req: TrashListInputRequest) -> BuckyResult<TrashListInputResponse> {
+        Self::list_trash(self, req).await
+    }
+
+    async fn restore_trash(
+        &self,
+        req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        Self::restore_trash(self, req).await
+    }
+
+    async fn purge_trash(
+        &self,
+        req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        Self::purge_trash(self, req).await
+    }
+
+    async fn set_trash_config(
+        &self,
+        req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        Self::set_trash_config(self, req).await
+    }
+}
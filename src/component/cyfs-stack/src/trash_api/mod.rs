@@ -0,0 +1,9 @@
+mod acl;
+mod local;
+mod router;
+mod service;
+
+pub(crate) use acl::*;
+pub(crate) use local::*;
+pub(crate) use router::*;
+pub(crate) use service::*;
@@ -0,0 +1,3 @@
+mod trash_service_router;
+
+pub(crate) use trash_service_router::*;
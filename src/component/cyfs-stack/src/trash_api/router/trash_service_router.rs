@@ -0,0 +1,134 @@
+use super::super::acl::TrashAclInnerInputProcessor;
+use crate::forward::ForwardProcessorManager;
+use crate::trash::*;
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub(crate) struct TrashServiceRouter {
+    processor: TrashInputProcessorRef,
+
+    zone_manager: ZoneManagerRef,
+
+    forward: ForwardProcessorManager,
+}
+
+impl TrashServiceRouter {
+    pub(crate) fn new(
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+        processor: TrashInputProcessorRef,
+    ) -> TrashInputProcessorRef {
+        // 限定同zone
+        let processor = TrashAclInnerInputProcessor::new(processor);
+
+        let ret = Self {
+            processor,
+            zone_manager,
+            forward,
+        };
+
+        Arc::new(ret)
+    }
+
+    async fn get_forward(&self, target: DeviceId) -> BuckyResult<TrashInputProcessorRef> {
+        // 获取到目标的processor
+        let requestor = self.forward.get(&target).await?;
+        let processor = TrashRequestor::new(None, requestor).into_processor();
+
+        // 转换为input processor
+        let input_processor = TrashInputTransformer::new(processor);
+
+        Ok(input_processor)
+    }
+
+    // 如果target为空，那么表示本地device
+    async fn get_target(&self, target: Option<&ObjectId>) -> BuckyResult<Option<DeviceId>> {
+        let ret = match target {
+            Some(object_id) => {
+                let info = self
+                    .zone_manager
+                    .target_zone_manager()
+                    .resolve_target(Some(object_id))
+                    .await?;
+                if info.target_device == *self.zone_manager.get_current_device_id() {
+                    None
+                } else {
+                    Some(info.target_device)
+                }
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+
+    async fn get_processor(
+        &self,
+        target: Option<&ObjectId>,
+    ) -> BuckyResult<TrashInputProcessorRef> {
+        if let Some(device_id) = self.get_target(target).await? {
+            debug!("trash target resolved: {:?} -> {}", target, device_id);
+            let processor = self.get_forward(device_id).await?;
+            Ok(processor)
+        } else {
+            Ok(self.processor.clone())
+        }
+    }
+
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TrashInputProcessor for TrashServiceRouter {
+    async fn list_trash(
+        &self,
+        mut req: TrashListInputRequest,
+    ) -> BuckyResult<TrashListInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.list_trash(req).await
+    }
+
+    async fn restore_trash(
+        &self,
+        mut req: TrashRestoreInputRequest,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.restore_trash(req).await
+    }
+
+    async fn purge_trash(
+        &self,
+        mut req: TrashPurgeInputRequest,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.purge_trash(req).await
+    }
+
+    async fn set_trash_config(
+        &self,
+        mut req: TrashSetConfigInputRequest,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.set_trash_config(req).await
+    }
+}
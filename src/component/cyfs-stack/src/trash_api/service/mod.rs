@@ -0,0 +1,7 @@
+mod trash_handler;
+mod trash_listener;
+mod trash_service;
+
+pub(crate) use trash_handler::*;
+pub(crate) use trash_listener::*;
+pub(crate) use trash_service::*;
@@ -0,0 +1,186 @@
+use crate::non::NONInputHttpRequest;
+use crate::trash::*;
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use http_types::StatusCode;
+use tide::Response;
+
+#[derive(Clone)]
+pub(crate) struct TrashRequestHandler {
+    processor: TrashInputProcessorRef,
+}
+
+impl TrashRequestHandler {
+    pub fn new(processor: TrashInputProcessorRef) -> Self {
+        Self { processor }
+    }
+
+    fn decode_common_headers<State>(
+        req: &NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilInputRequestCommon> {
+        // req_path
+        let req_path = RequestorHelper::decode_optional_header_with_utf8_decoding(
+            &req.request,
+            cyfs_base::CYFS_REQ_PATH,
+        )?;
+
+        // 尝试提取flags
+        let flags: Option<u32> =
+            RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_FLAGS)?;
+
+        // 尝试提取target字段
+        let target = RequestorHelper::decode_optional_header(&req.request, cyfs_base::CYFS_TARGET)?;
+
+        let ret = UtilInputRequestCommon {
+            req_path,
+            source: req.source.clone(),
+            target,
+            flags: flags.unwrap_or(0),
+        };
+
+        Ok(ret)
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // list_trash
+    pub async fn process_list_trash_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_list_trash_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_list_trash_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TrashListInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+        let req = TrashListInputRequest { common };
+
+        self.processor.list_trash(req).await
+    }
+
+    // restore_trash
+    pub async fn process_restore_trash_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_restore_trash_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_restore_trash_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TrashRestoreInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("restore trash failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = TrashRestoreOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = TrashRestoreInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            id: out_req.id,
+        };
+        self.processor.restore_trash(in_req).await
+    }
+
+    // purge_trash
+    pub async fn process_purge_trash_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_purge_trash_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_purge_trash_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TrashPurgeInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("purge trash failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = TrashPurgeOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = TrashPurgeInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            id: out_req.id,
+        };
+        self.processor.purge_trash(in_req).await
+    }
+
+    // set_trash_config
+    pub async fn process_set_trash_config_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_set_trash_config_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_set_trash_config_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<TrashSetConfigInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("set trash config failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = TrashSetConfigOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = TrashSetConfigInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            config: out_req.config,
+        };
+        self.processor.set_trash_config(in_req).await
+    }
+}
@@ -0,0 +1,128 @@
+use super::trash_handler::*;
+use crate::non::NONInputHttpRequest;
+use crate::zone::ZoneManagerRef;
+use cyfs_lib::*;
+
+use async_trait::async_trait;
+use tide::Response;
+
+enum TrashRequestType {
+    ListTrash,
+    RestoreTrash,
+    PurgeTrash,
+    SetTrashConfig,
+}
+
+pub(crate) struct TrashRequestHandlerEndpoint {
+    zone_manager: ZoneManagerRef,
+    protocol: RequestProtocol,
+    req_type: TrashRequestType,
+    handler: TrashRequestHandler,
+}
+
+impl TrashRequestHandlerEndpoint {
+    fn new(
+        zone_manager: ZoneManagerRef,
+        protocol: RequestProtocol,
+        req_type: TrashRequestType,
+        handler: TrashRequestHandler,
+    ) -> Self {
+        Self {
+            zone_manager,
+            protocol,
+            req_type,
+            handler,
+        }
+    }
+
+    async fn process_request<State>(&self, req: ::tide::Request<State>) -> Response {
+        let req = match NONInputHttpRequest::new(&self.zone_manager, &self.protocol, req).await {
+            Ok(v) => v,
+            Err(resp) => return resp,
+        };
+
+        match self.req_type {
+            TrashRequestType::ListTrash => self.handler.process_list_trash_request(req).await,
+            TrashRequestType::RestoreTrash => self.handler.process_restore_trash_request(req).await,
+            TrashRequestType::PurgeTrash => self.handler.process_purge_trash_request(req).await,
+            TrashRequestType::SetTrashConfig => {
+                self.handler.process_set_trash_config_request(req).await
+            }
+        }
+    }
+
+    pub fn register_server(
+        zone_manager: &ZoneManagerRef,
+        protocol: &RequestProtocol,
+        handler: &TrashRequestHandler,
+        server: &mut ::tide::Server<()>,
+    ) {
+        // trash
+        server.at("/trash/list").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::ListTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/list/").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::ListTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/restore").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::RestoreTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/restore/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::RestoreTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/purge").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::PurgeTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/purge/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::PurgeTrash,
+            handler.clone(),
+        ));
+
+        server.at("/trash/config").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::SetTrashConfig,
+            handler.clone(),
+        ));
+
+        server.at("/trash/config/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            TrashRequestType::SetTrashConfig,
+            handler.clone(),
+        ));
+    }
+}
+
+#[async_trait]
+impl<State> tide::Endpoint<State> for TrashRequestHandlerEndpoint
+where
+    State: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, req: ::tide::Request<State>) -> tide::Result {
+        let resp = self.process_request(req).await;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,29 @@
+use super::super::local::LocalTrashService;
+use super::super::router::TrashServiceRouter;
+use crate::admin::AdminConfirmManager;
+use crate::forward::ForwardProcessorManager;
+use crate::trash::*;
+use crate::zone::ZoneManagerRef;
+
+pub(crate) struct TrashService {
+    router: TrashInputProcessorRef,
+}
+
+impl TrashService {
+    pub(crate) fn new(
+        trash_manager: TrashManager,
+        admin_confirm_manager: AdminConfirmManager,
+        forward: ForwardProcessorManager,
+        zone_manager: ZoneManagerRef,
+    ) -> Self {
+        let local_service = LocalTrashService::new(trash_manager, admin_confirm_manager);
+        let router =
+            TrashServiceRouter::new(forward, zone_manager, local_service.clone_processor());
+
+        Self { router }
+    }
+
+    pub fn clone_processor(&self) -> TrashInputProcessorRef {
+        self.router.clone()
+    }
+}
@@ -43,6 +43,36 @@ pub trait UtilInputProcessor: Sync + Send + 'static {
 
     async fn build_dir_from_object_map(&self, req: UtilBuildDirFromObjectMapInputRequest)
         -> BuckyResult<UtilBuildDirFromObjectMapInputResponse>;
+
+    async fn pin_app_web_dir(&self, req: UtilPinAppWebDirInputRequest)
+        -> BuckyResult<UtilPinAppWebDirInputResponse>;
+
+    async fn unpin_app_web_dir(&self, req: UtilUnpinAppWebDirInputRequest)
+        -> BuckyResult<UtilUnpinAppWebDirInputResponse>;
+
+    async fn query_zone_events(&self, req: UtilQueryZoneEventsInputRequest)
+        -> BuckyResult<UtilQueryZoneEventsInputResponse>;
+
+    async fn get_zone_device_health(&self, req: UtilGetZoneDeviceHealthInputRequest)
+        -> BuckyResult<UtilGetZoneDeviceHealthInputResponse>;
+
+    async fn get_ood_resolver_stats(&self, req: UtilGetOODResolverStatsInputRequest)
+        -> BuckyResult<UtilGetOODResolverStatsInputResponse>;
+
+    async fn set_dec_resource_policy(&self, req: UtilSetDecResourcePolicyInputRequest)
+        -> BuckyResult<UtilSetDecResourcePolicyInputResponse>;
+
+    async fn remove_dec_resource_policy(&self, req: UtilRemoveDecResourcePolicyInputRequest)
+        -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse>;
+
+    async fn get_dec_resource_policies(&self, req: UtilGetDecResourcePoliciesInputRequest)
+        -> BuckyResult<UtilGetDecResourcePoliciesInputResponse>;
+
+    async fn get_concurrency_stat(&self, req: UtilGetConcurrencyStatInputRequest)
+        -> BuckyResult<UtilGetConcurrencyStatInputResponse>;
+
+    async fn get_object_access_stat(&self, req: UtilGetObjectAccessStatInputRequest)
+        -> BuckyResult<UtilGetObjectAccessStatInputResponse>;
 }
 
 pub type UtilInputProcessorRef = Arc<Box<dyn UtilInputProcessor>>;
@@ -234,6 +234,143 @@ impl UtilInputTransformer {
         let out_resp = self.processor.build_dir_from_object_map(out_req).await?;
         Ok(out_resp)
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        let out_req = UtilPinAppWebDirOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            version: req.version,
+        };
+
+        let out_resp = self.processor.pin_app_web_dir(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        let out_req = UtilUnpinAppWebDirOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            version: req.version,
+        };
+
+        let out_resp = self.processor.unpin_app_web_dir(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        let out_req = UtilQueryZoneEventsOutputRequest {
+            common: Self::convert_common(req.common),
+            category: req.category,
+            start_time: req.start_time,
+            end_time: req.end_time,
+            page_index: req.page_index,
+            page_size: req.page_size,
+        };
+
+        let out_resp = self.processor.query_zone_events(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        let out_req = UtilGetZoneDeviceHealthOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_zone_device_health(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        let out_req = UtilGetOODResolverStatsOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_ood_resolver_stats(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        let out_req = UtilSetDecResourcePolicyOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            cpu_limit: req.cpu_limit,
+            memory_limit: req.memory_limit,
+        };
+
+        let out_resp = self.processor.set_dec_resource_policy(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        let out_req = UtilRemoveDecResourcePolicyOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+        };
+
+        let out_resp = self.processor.remove_dec_resource_policy(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        let out_req = UtilGetDecResourcePoliciesOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+        };
+
+        let out_resp = self.processor.get_dec_resource_policies(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        let out_req = UtilGetConcurrencyStatOutputRequest {
+            common: Self::convert_common(req.common),
+        };
+
+        let out_resp = self.processor.get_concurrency_stat(out_req).await?;
+        Ok(out_resp)
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        let out_req = UtilGetObjectAccessStatOutputRequest {
+            common: Self::convert_common(req.common),
+            dec_id: req.dec_id,
+            days: req.days,
+            top: req.top,
+        };
+
+        let out_resp = self.processor.get_object_access_stat(out_req).await?;
+        Ok(out_resp)
+    }
 }
 
 #[async_trait::async_trait]
@@ -318,6 +455,76 @@ impl UtilInputProcessor for UtilInputTransformer {
     ) -> BuckyResult<UtilBuildDirFromObjectMapInputResponse> {
         Self::build_dir_from_object_map(&self, req).await
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        Self::pin_app_web_dir(&self, req).await
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        Self::unpin_app_web_dir(&self, req).await
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        Self::query_zone_events(&self, req).await
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        Self::get_zone_device_health(&self, req).await
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        Self::get_ood_resolver_stats(&self, req).await
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        Self::set_dec_resource_policy(&self, req).await
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        Self::remove_dec_resource_policy(&self, req).await
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        Self::get_dec_resource_policies(&self, req).await
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        Self::get_concurrency_stat(&self, req).await
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        Self::get_object_access_stat(&self, req).await
+    }
 }
 
 pub(crate) struct UtilOutputTransformer {
@@ -520,4 +727,138 @@ impl UtilOutputProcessor for UtilOutputTransformer {
         let resp = self.processor.build_dir_from_object_map(in_req).await?;
         Ok(resp)
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirOutputResponse> {
+        let in_req = UtilPinAppWebDirInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+            version: req.version,
+        };
+        let resp = self.processor.pin_app_web_dir(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirOutputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirOutputResponse> {
+        let in_req = UtilUnpinAppWebDirInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+            version: req.version,
+        };
+        let resp = self.processor.unpin_app_web_dir(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsOutputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsOutputResponse> {
+        let in_req = UtilQueryZoneEventsInputRequest {
+            common: self.convert_common(req.common),
+            category: req.category,
+            start_time: req.start_time,
+            end_time: req.end_time,
+            page_index: req.page_index,
+            page_size: req.page_size,
+        };
+        let resp = self.processor.query_zone_events(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthOutputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthOutputResponse> {
+        let in_req = UtilGetZoneDeviceHealthInputRequest {
+            common: self.convert_common(req.common),
+        };
+
+        let resp = self.processor.get_zone_device_health(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsOutputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsOutputResponse> {
+        let in_req = UtilGetOODResolverStatsInputRequest {
+            common: self.convert_common(req.common),
+        };
+
+        let resp = self.processor.get_ood_resolver_stats(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyOutputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyOutputResponse> {
+        let in_req = UtilSetDecResourcePolicyInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+            cpu_limit: req.cpu_limit,
+            memory_limit: req.memory_limit,
+        };
+
+        let resp = self.processor.set_dec_resource_policy(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyOutputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyOutputResponse> {
+        let in_req = UtilRemoveDecResourcePolicyInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+        };
+
+        let resp = self.processor.remove_dec_resource_policy(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesOutputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesOutputResponse> {
+        let in_req = UtilGetDecResourcePoliciesInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+        };
+
+        let resp = self.processor.get_dec_resource_policies(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatOutputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatOutputResponse> {
+        let in_req = UtilGetConcurrencyStatInputRequest {
+            common: self.convert_common(req.common),
+        };
+
+        let resp = self.processor.get_concurrency_stat(in_req).await?;
+        Ok(resp)
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatOutputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatOutputResponse> {
+        let in_req = UtilGetObjectAccessStatInputRequest {
+            common: self.convert_common(req.common),
+            dec_id: req.dec_id,
+            days: req.days,
+            top: req.top,
+        };
+
+        let resp = self.processor.get_object_access_stat(in_req).await?;
+        Ok(resp)
+    }
 }
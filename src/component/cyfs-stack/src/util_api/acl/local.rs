@@ -152,4 +152,119 @@ impl UtilInputProcessor for UtilAclInnerInputProcessor {
 
         self.next.build_dir_from_object_map(req).await
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        self.check_local_zone_permit("util.pin_app_web_dir", &req.common.source)?;
+
+        if !req.common.source.is_system_dec() {
+            let msg = format!("util.pin_app_web_dir only valid for system dec!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.pin_app_web_dir(req).await
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        self.check_local_zone_permit("util.unpin_app_web_dir", &req.common.source)?;
+
+        if !req.common.source.is_system_dec() {
+            let msg = format!("util.unpin_app_web_dir only valid for system dec!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.unpin_app_web_dir(req).await
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        self.check_local_zone_permit("util.query_zone_events", &req.common.source)?;
+
+        self.next.query_zone_events(req).await
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        self.check_local_zone_permit("util.get_zone_device_health", &req.common.source)?;
+
+        self.next.get_zone_device_health(req).await
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        self.check_local_zone_permit("util.get_ood_resolver_stats", &req.common.source)?;
+
+        self.next.get_ood_resolver_stats(req).await
+    }
+
+    // 资源策略影响整个设备，只有系统dec可以修改
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        self.check_local_zone_permit("util.set_dec_resource_policy", &req.common.source)?;
+
+        if !req.common.source.is_system_dec() {
+            let msg = format!("util.set_dec_resource_policy only valid for system dec!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.set_dec_resource_policy(req).await
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        self.check_local_zone_permit("util.remove_dec_resource_policy", &req.common.source)?;
+
+        if !req.common.source.is_system_dec() {
+            let msg = format!("util.remove_dec_resource_policy only valid for system dec!");
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        self.next.remove_dec_resource_policy(req).await
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        self.check_local_zone_permit("util.get_dec_resource_policies", &req.common.source)?;
+
+        self.next.get_dec_resource_policies(req).await
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        self.check_local_zone_permit("util.get_concurrency_stat", &req.common.source)?;
+
+        self.next.get_concurrency_stat(req).await
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        self.check_local_zone_permit("util.get_object_access_stat", &req.common.source)?;
+
+        self.next.get_object_access_stat(req).await
+    }
 }
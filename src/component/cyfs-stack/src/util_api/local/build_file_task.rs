@@ -3,6 +3,7 @@ use cyfs_lib::*;
 use cyfs_task_manager::*;
 use cyfs_util::*;
 use sha2::Digest;
+use std::path::Path;
 use std::sync::Arc;
 
 #[derive(Clone, ProtobufEncode, ProtobufDecode, ProtobufTransformType)]
@@ -48,11 +49,20 @@ impl ProtobufTransform<&BuildFileParams> for super::util_proto::BuildFileParams
 pub struct BuildFileTaskFactory {
     noc: NamedObjectCacheRef,
     ndc: Box<dyn NamedDataCache>,
+    preserve_metadata: bool,
 }
 
 impl BuildFileTaskFactory {
-    pub fn new(noc: NamedObjectCacheRef, ndc: Box<dyn NamedDataCache>) -> Self {
-        Self { noc, ndc }
+    pub fn new(
+        noc: NamedObjectCacheRef,
+        ndc: Box<dyn NamedDataCache>,
+        preserve_metadata: bool,
+    ) -> Self {
+        Self {
+            noc,
+            ndc,
+            preserve_metadata,
+        }
     }
 }
 
@@ -73,6 +83,7 @@ impl TaskFactory for BuildFileTaskFactory {
             params.access,
             self.noc.clone(),
             self.ndc.clone(),
+            self.preserve_metadata,
         ))))
     }
 
@@ -99,6 +110,7 @@ impl TaskFactory for BuildFileTaskFactory {
             task_state,
             self.noc.clone(),
             self.ndc.clone(),
+            self.preserve_metadata,
         ))))
     }
 }
@@ -228,6 +240,7 @@ pub struct BuildFileTask {
     access: Option<u32>,
     noc: NamedObjectCacheRef,
     ndc: Box<dyn NamedDataCache>,
+    preserve_metadata: bool,
 }
 
 impl BuildFileTask {
@@ -240,6 +253,7 @@ impl BuildFileTask {
         access: Option<u32>,
         noc: NamedObjectCacheRef,
         ndc: Box<dyn NamedDataCache>,
+        preserve_metadata: bool,
     ) -> Self {
         let mut sha2 = sha2::Sha256::new();
         sha2.input(local_path.as_bytes());
@@ -264,6 +278,7 @@ impl BuildFileTask {
             access,
             noc,
             ndc,
+            preserve_metadata,
         }
     }
 
@@ -277,6 +292,7 @@ impl BuildFileTask {
         task_state: FileTaskState,
         noc: NamedObjectCacheRef,
         ndc: Box<dyn NamedDataCache>,
+        preserve_metadata: bool,
     ) -> Self {
         let mut sha2 = sha2::Sha256::new();
         sha2.input(local_path.as_bytes());
@@ -298,6 +314,7 @@ impl BuildFileTask {
             access,
             noc,
             ndc,
+            preserve_metadata,
         }
     }
 
@@ -309,12 +326,29 @@ impl BuildFileTask {
             self.chunk_size,
             None,
         );
-        let file = match builder.build().await {
+        let mut file = match builder.build().await {
             Ok(file) => file,
             Err(e) => {
                 return Err(e);
             }
         };
+
+        let metadata = if self.preserve_metadata {
+            match FileMetadata::load(Path::new(&self.local_path)) {
+                Ok(metadata) => Some(metadata),
+                Err(e) => {
+                    warn!(
+                        "load local file metadata failed, will be ignored! file={}, {}",
+                        self.local_path, e
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut exists = None;
         let query_ret = self
             .ndc
             .get_file_by_hash(&GetFileByHashRequest {
@@ -334,11 +368,27 @@ impl BuildFileTask {
                 .await?;
             if let Some(file) = file {
                 if let Ok(file) = File::clone_from_slice(file.object.object_raw.as_slice()) {
-                    return Ok(file);
+                    exists = Some(file);
                 }
             }
         }
 
+        let file = match exists {
+            Some(mut exists) => {
+                // 已经存在的文件对象，只有元数据变化了才需要更新body重新保存
+                match &metadata {
+                    Some(metadata) if metadata.encode_to_file(&mut exists) => exists,
+                    _ => return Ok(exists),
+                }
+            }
+            None => {
+                if let Some(metadata) = &metadata {
+                    metadata.encode_to_file(&mut file);
+                }
+                file
+            }
+        };
+
         let object_raw = file.to_vec()?;
         let object = NONObjectInfo::new_from_object_raw(object_raw)?;
 
@@ -349,6 +399,7 @@ impl BuildFileTask {
             context: None,
             last_access_rpath: None,
             access_string: self.access,
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -495,6 +546,20 @@ mod build_file_task_test {
             todo!();    
         }
 
+        async fn get_object_history(
+            &self,
+            _req: &NamedObjectCacheGetObjectHistoryRequest,
+        ) -> BuckyResult<NamedObjectCacheGetObjectHistoryResponse> {
+            todo!();
+        }
+
+        async fn get_object_revision(
+            &self,
+            _req: &NamedObjectCacheGetObjectRevisionRequest,
+        ) -> BuckyResult<Option<NamedObjectCacheObjectRevisionData>> {
+            todo!();
+        }
+
         fn bind_object_meta_access_provider(&self, _object_meta_access_provider: NamedObjectCacheObjectMetaAccessProviderRef) {
 
         }
@@ -637,7 +702,7 @@ mod build_file_task_test {
             let ndc = Box::new(MemoryNDC {});
             let task_manager = create_test_task_manager().await.unwrap();
             task_manager
-                .register_task_factory(BuildFileTaskFactory::new(noc, ndc))
+                .register_task_factory(BuildFileTaskFactory::new(noc, ndc, false))
                 .unwrap();
 
             let dec_id = cyfs_core::get_system_dec_app();
@@ -43,6 +43,7 @@ impl DirHelper {
             context: None,
             last_access_rpath: None,
             access_string: Some(access.value()),
+            precondition: None,
         };
 
         noc.put_object(&req).await.map_err(|e| {
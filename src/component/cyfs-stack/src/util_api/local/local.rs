@@ -1,10 +1,16 @@
 use super::bdt_access_info::BdtNetworkAccessInfoManager;
 use super::dir_helper::*;
+use crate::access_stat::ObjectAccessStatManager;
+use crate::app::AppWebDirPinManager;
+use crate::concurrency::ConcurrencyManager;
 use crate::config::StackGlobalConfig;
+use crate::dec_resource::DecResourceManager;
 use crate::resolver::OodResolver;
 use crate::sync::DeviceSyncClient;
 use crate::util::*;
 use crate::zone::*;
+use crate::zone_event::ZoneEventManager;
+use crate::zone_health::DeviceHealthManager;
 use cyfs_base::*;
 use cyfs_bdt::StackGuard;
 use cyfs_core::ZoneObj;
@@ -34,6 +40,13 @@ pub(crate) struct UtilLocalService {
     task_manager: Arc<TaskManager>,
 
     config: StackGlobalConfig,
+
+    app_pin_manager: Arc<OnceCell<AppWebDirPinManager>>,
+    zone_event_manager: Arc<OnceCell<ZoneEventManager>>,
+    device_health_manager: Arc<OnceCell<DeviceHealthManager>>,
+    dec_resource_manager: Arc<OnceCell<DecResourceManager>>,
+    concurrency_manager: Arc<OnceCell<ConcurrencyManager>>,
+    access_stat_manager: Arc<OnceCell<ObjectAccessStatManager>>,
 }
 
 impl Clone for UtilLocalService {
@@ -47,6 +60,12 @@ impl Clone for UtilLocalService {
             access_info_manager: self.access_info_manager.clone(),
             task_manager: self.task_manager.clone(),
             config: self.config.clone(),
+            app_pin_manager: self.app_pin_manager.clone(),
+            zone_event_manager: self.zone_event_manager.clone(),
+            device_health_manager: self.device_health_manager.clone(),
+            dec_resource_manager: self.dec_resource_manager.clone(),
+            concurrency_manager: self.concurrency_manager.clone(),
+            access_stat_manager: self.access_stat_manager.clone(),
         }
     }
 }
@@ -64,7 +83,11 @@ impl UtilLocalService {
         let access_info_manager = BdtNetworkAccessInfoManager::new(bdt_stack.clone());
 
         task_manager
-            .register_task_factory(BuildFileTaskFactory::new(noc.clone(), ndc))
+            .register_task_factory(BuildFileTaskFactory::new(
+                noc.clone(),
+                ndc,
+                config.get_stack_params().config.preserve_file_metadata,
+            ))
             .unwrap();
         task_manager
             .register_task_factory(BuildDirTaskFactory::new(
@@ -82,6 +105,12 @@ impl UtilLocalService {
             access_info_manager,
             task_manager,
             config,
+            app_pin_manager: Arc::new(OnceCell::new()),
+            zone_event_manager: Arc::new(OnceCell::new()),
+            device_health_manager: Arc::new(OnceCell::new()),
+            dec_resource_manager: Arc::new(OnceCell::new()),
+            concurrency_manager: Arc::new(OnceCell::new()),
+            access_stat_manager: Arc::new(OnceCell::new()),
         }
     }
 
@@ -95,6 +124,74 @@ impl UtilLocalService {
         }
     }
 
+    pub(crate) fn bind_app_pin_manager(&self, app_pin_manager: AppWebDirPinManager) {
+        if let Err(_) = self.app_pin_manager.set(app_pin_manager) {
+            unreachable!();
+        }
+    }
+
+    pub(crate) fn bind_zone_event_manager(&self, zone_event_manager: ZoneEventManager) {
+        if let Err(_) = self.zone_event_manager.set(zone_event_manager) {
+            unreachable!();
+        }
+    }
+
+    pub(crate) fn bind_device_health_manager(&self, device_health_manager: DeviceHealthManager) {
+        if let Err(_) = self.device_health_manager.set(device_health_manager) {
+            unreachable!();
+        }
+    }
+
+    pub(crate) fn bind_access_stat_manager(&self, access_stat_manager: ObjectAccessStatManager) {
+        if let Err(_) = self.access_stat_manager.set(access_stat_manager) {
+            unreachable!();
+        }
+    }
+
+    fn access_stat_manager(&self) -> BuckyResult<&ObjectAccessStatManager> {
+        self.access_stat_manager.get().ok_or_else(|| {
+            let msg = format!("object access stat manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })
+    }
+
+    pub(crate) fn bind_dec_resource_manager(&self, dec_resource_manager: DecResourceManager) {
+        if let Err(_) = self.dec_resource_manager.set(dec_resource_manager) {
+            unreachable!();
+        }
+    }
+
+    fn dec_resource_manager(&self) -> BuckyResult<&DecResourceManager> {
+        self.dec_resource_manager.get().ok_or_else(|| {
+            let msg = format!("dec resource manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })
+    }
+
+    pub(crate) fn bind_concurrency_manager(&self, concurrency_manager: ConcurrencyManager) {
+        if let Err(_) = self.concurrency_manager.set(concurrency_manager) {
+            unreachable!();
+        }
+    }
+
+    fn concurrency_manager(&self) -> BuckyResult<&ConcurrencyManager> {
+        self.concurrency_manager.get().ok_or_else(|| {
+            let msg = format!("concurrency manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })
+    }
+
+    fn app_pin_manager(&self) -> BuckyResult<&AppWebDirPinManager> {
+        self.app_pin_manager.get().ok_or_else(|| {
+            let msg = format!("app web dir pin manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })
+    }
+
     async fn get_device(
         &self,
         _req: UtilGetDeviceInputRequest,
@@ -407,6 +504,109 @@ impl UtilLocalService {
         .await?;
         Ok(UtilBuildDirFromObjectMapInputResponse { object_id: dir_id })
     }
+
+    pub async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        self.app_pin_manager()?.pin(&req.dec_id, &req.version).await
+    }
+
+    pub async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        self.app_pin_manager()?.unpin(&req.dec_id, &req.version).await
+    }
+
+    pub async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        let zone_event_manager = self.zone_event_manager.get().ok_or_else(|| {
+            let msg = format!("zone event manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })?;
+
+        zone_event_manager.query(req).await
+    }
+
+    pub async fn get_zone_device_health(
+        &self,
+        _req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        let device_health_manager = self.device_health_manager.get().ok_or_else(|| {
+            let msg = format!("device health manager not initialized yet!");
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotInit, msg)
+        })?;
+
+        let list = device_health_manager.load_all().await?;
+
+        Ok(UtilGetZoneDeviceHealthInputResponse { list })
+    }
+
+    pub async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        let policy = self
+            .dec_resource_manager()?
+            .set_policy(req.dec_id, req.cpu_limit, req.memory_limit)
+            .await?;
+
+        Ok(UtilSetDecResourcePolicyInputResponse { policy })
+    }
+
+    pub async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        let policy = self
+            .dec_resource_manager()?
+            .remove_policy(&req.dec_id)
+            .await?;
+
+        Ok(UtilRemoveDecResourcePolicyInputResponse { policy })
+    }
+
+    pub async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        let list = self
+            .dec_resource_manager()?
+            .get_policies(req.dec_id.as_ref())
+            .await?;
+
+        Ok(UtilGetDecResourcePoliciesInputResponse { list })
+    }
+
+    pub async fn get_concurrency_stat(
+        &self,
+        _req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        let list = self.concurrency_manager()?.stat();
+
+        Ok(UtilGetConcurrencyStatInputResponse { list })
+    }
+
+    pub async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        self.access_stat_manager()?
+            .get_stat(req.dec_id.as_ref(), req.days, req.top)
+            .await
+    }
+
+    pub async fn get_ood_resolver_stats(
+        &self,
+        _req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        Ok(self.ood_resolver.cache_stats())
+    }
 }
 
 #[async_trait::async_trait]
@@ -494,4 +694,74 @@ impl UtilInputProcessor for UtilLocalService {
     ) -> BuckyResult<UtilBuildDirFromObjectMapInputResponse> {
         Self::build_dir_from_object_map(self, req).await
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        Self::pin_app_web_dir(self, req).await
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        Self::unpin_app_web_dir(self, req).await
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        Self::query_zone_events(self, req).await
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        Self::get_zone_device_health(self, req).await
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        Self::set_dec_resource_policy(self, req).await
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        Self::remove_dec_resource_policy(self, req).await
+    }
+
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        Self::get_dec_resource_policies(self, req).await
+    }
+
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        Self::get_concurrency_stat(self, req).await
+    }
+
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        Self::get_object_access_stat(self, req).await
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        Self::get_ood_resolver_stats(self, req).await
+    }
 }
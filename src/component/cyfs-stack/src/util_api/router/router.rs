@@ -89,6 +89,16 @@ impl UtilRouter {
         }
     }
 
+    // 只保存在ood上的数据，没有指定target时默认路由到当前zone的ood
+    async fn route_to_ood(&self, common: &mut UtilInputRequestCommon) -> BuckyResult<()> {
+        if common.target.is_none() {
+            let info = self.zone_manager.get_current_info().await?;
+            common.target = Some(info.zone_device_ood_id.object_id().to_owned());
+        }
+
+        Ok(())
+    }
+
     async fn get_device(
         &self,
         req: UtilGetDeviceInputRequest,
@@ -264,4 +274,93 @@ impl UtilInputProcessor for UtilRouter {
         let processor = self.get_processor(req.common.target.as_ref()).await?;
         processor.build_dir_from_object_map(req).await
     }
+
+    async fn pin_app_web_dir(
+        &self,
+        req: UtilPinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.pin_app_web_dir(req).await
+    }
+
+    async fn unpin_app_web_dir(
+        &self,
+        req: UtilUnpinAppWebDirInputRequest,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.unpin_app_web_dir(req).await
+    }
+
+    async fn query_zone_events(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.query_zone_events(req).await
+    }
+
+    async fn get_zone_device_health(
+        &self,
+        mut req: UtilGetZoneDeviceHealthInputRequest,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_zone_device_health(req).await
+    }
+
+    async fn get_ood_resolver_stats(
+        &self,
+        req: UtilGetOODResolverStatsInputRequest,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_ood_resolver_stats(req).await
+    }
+
+    async fn set_dec_resource_policy(
+        &self,
+        mut req: UtilSetDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.set_dec_resource_policy(req).await
+    }
+
+    async fn remove_dec_resource_policy(
+        &self,
+        mut req: UtilRemoveDecResourcePolicyInputRequest,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        self.route_to_ood(&mut req.common).await?;
+
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.remove_dec_resource_policy(req).await
+    }
+
+    // 默认查询本设备上同步的root_state
+    async fn get_dec_resource_policies(
+        &self,
+        req: UtilGetDecResourcePoliciesInputRequest,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_dec_resource_policies(req).await
+    }
+
+    // 并发统计按设备区分，查询zone内其它设备时需要指定target
+    async fn get_concurrency_stat(
+        &self,
+        req: UtilGetConcurrencyStatInputRequest,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_concurrency_stat(req).await
+    }
+
+    // 访问统计按设备区分，查询zone内其它设备时需要指定target
+    async fn get_object_access_stat(
+        &self,
+        req: UtilGetObjectAccessStatInputRequest,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        let processor = self.get_processor(req.common.target.as_ref()).await?;
+        processor.get_object_access_stat(req).await
+    }
 }
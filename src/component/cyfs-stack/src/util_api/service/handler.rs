@@ -482,4 +482,394 @@ impl UtilRequestHandler {
         };
         self.processor.build_dir_from_object_map(in_req).await
     }
+
+    pub async fn process_pin_app_web_dir_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        match self.on_pin_app_web_dir_request(req).await {
+            Ok(resp) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(resp.encode_string());
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_pin_app_web_dir_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilPinAppWebDirInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("pin app web dir failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilPinAppWebDirOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilPinAppWebDirInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+
+            dec_id: out_req.dec_id,
+            version: out_req.version,
+        };
+        self.processor.pin_app_web_dir(in_req).await
+    }
+
+    pub async fn process_unpin_app_web_dir_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        match self.on_unpin_app_web_dir_request(req).await {
+            Ok(resp) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(resp.encode_string());
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_unpin_app_web_dir_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilUnpinAppWebDirInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("unpin app web dir failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilUnpinAppWebDirOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilUnpinAppWebDirInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+
+            dec_id: out_req.dec_id,
+            version: out_req.version,
+        };
+        self.processor.unpin_app_web_dir(in_req).await
+    }
+
+    pub async fn process_query_zone_events_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        match self.on_query_zone_events_request(req).await {
+            Ok(resp) => {
+                let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+                http_resp.set_content_type(::tide::http::mime::JSON);
+                http_resp.set_body(resp.encode_string());
+
+                http_resp.into()
+            }
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_query_zone_events_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("query zone events failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilQueryZoneEventsOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilQueryZoneEventsInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+
+            category: out_req.category,
+            start_time: out_req.start_time,
+            end_time: out_req.end_time,
+            page_index: out_req.page_index,
+            page_size: out_req.page_size,
+        };
+        self.processor.query_zone_events(in_req).await
+    }
+
+    // get_zone_device_health
+    fn encode_get_zone_device_health_response(
+        resp: UtilGetZoneDeviceHealthInputResponse,
+    ) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(&resp).unwrap());
+
+        http_resp.into()
+    }
+
+    pub async fn process_get_zone_device_health_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_zone_device_health_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_get_zone_device_health_response(resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_zone_device_health_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilGetZoneDeviceHealthInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+        let req = UtilGetZoneDeviceHealthInputRequest { common };
+
+        self.processor.get_zone_device_health(req).await
+    }
+
+    fn encode_json_response<T: serde::Serialize>(resp: &T) -> Response {
+        let mut http_resp = RequestorHelper::new_response(StatusCode::Ok);
+
+        http_resp.set_content_type(::tide::http::mime::JSON);
+        http_resp.set_body(serde_json::to_string(resp).unwrap());
+
+        http_resp.into()
+    }
+
+    // get_ood_resolver_stats
+    pub async fn process_get_ood_resolver_stats_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_ood_resolver_stats_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_ood_resolver_stats_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilGetOODResolverStatsInputResponse> {
+        let common = Self::decode_common_headers(&req)?;
+        let req = UtilGetOODResolverStatsInputRequest { common };
+
+        self.processor.get_ood_resolver_stats(req).await
+    }
+
+    // set_dec_resource_policy
+    pub async fn process_set_dec_resource_policy_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_set_dec_resource_policy_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_set_dec_resource_policy_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilSetDecResourcePolicyInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("set dec resource policy failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilSetDecResourcePolicyOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilSetDecResourcePolicyInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            cpu_limit: out_req.cpu_limit,
+            memory_limit: out_req.memory_limit,
+        };
+        self.processor.set_dec_resource_policy(in_req).await
+    }
+
+    // remove_dec_resource_policy
+    pub async fn process_remove_dec_resource_policy_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_remove_dec_resource_policy_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_remove_dec_resource_policy_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilRemoveDecResourcePolicyInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("remove dec resource policy failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilRemoveDecResourcePolicyOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilRemoveDecResourcePolicyInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+        };
+        self.processor.remove_dec_resource_policy(in_req).await
+    }
+
+    // get_dec_resource_policies
+    pub async fn process_get_dec_resource_policies_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_dec_resource_policies_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_dec_resource_policies_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilGetDecResourcePoliciesInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get dec resource policies failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilGetDecResourcePoliciesOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilGetDecResourcePoliciesInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+        };
+        self.processor.get_dec_resource_policies(in_req).await
+    }
+
+    // get_concurrency_stat
+    pub async fn process_get_concurrency_stat_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_concurrency_stat_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_concurrency_stat_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilGetConcurrencyStatInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!("get concurrency stat failed, read body bytes error! {}", e);
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilGetConcurrencyStatOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilGetConcurrencyStatInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+        };
+        self.processor.get_concurrency_stat(in_req).await
+    }
+
+    // get_object_access_stat
+    pub async fn process_get_object_access_stat_request<State>(
+        &self,
+        req: NONInputHttpRequest<State>,
+    ) -> Response {
+        let ret = self.on_get_object_access_stat_request(req).await;
+        match ret {
+            Ok(resp) => Self::encode_json_response(&resp),
+            Err(e) => RequestorHelper::trans_error(e),
+        }
+    }
+
+    async fn on_get_object_access_stat_request<State>(
+        &self,
+        mut req: NONInputHttpRequest<State>,
+    ) -> BuckyResult<UtilGetObjectAccessStatInputResponse> {
+        let body = req.request.body_string().await.map_err(|e| {
+            let msg = format!(
+                "get object access stat failed, read body bytes error! {}",
+                e
+            );
+            error!("{}", msg);
+
+            BuckyError::new(BuckyErrorCode::InvalidParam, msg)
+        })?;
+
+        let out_req = UtilGetObjectAccessStatOutputRequest::decode_string(body.as_str())?;
+
+        let in_req = UtilGetObjectAccessStatInputRequest {
+            common: UtilInputRequestCommon {
+                req_path: out_req.common.req_path,
+                source: req.source,
+                target: out_req.common.target,
+                flags: out_req.common.flags,
+            },
+            dec_id: out_req.dec_id,
+            days: out_req.days,
+            top: out_req.top,
+        };
+        self.processor.get_object_access_stat(in_req).await
+    }
 }
@@ -19,6 +19,16 @@ enum UtilRequestType {
     GetVersionInfo,
     BuildFile,
     BuildDirFromObjectMap,
+    PinAppWebDir,
+    UnpinAppWebDir,
+    QueryZoneEvents,
+    GetZoneDeviceHealth,
+    GetOODResolverStats,
+    SetDecResourcePolicy,
+    RemoveDecResourcePolicy,
+    GetDecResourcePolicies,
+    GetConcurrencyStat,
+    GetObjectAccessStat,
 }
 
 pub(crate) struct UtilRequestHandlerEndpoint {
@@ -82,6 +92,38 @@ impl UtilRequestHandlerEndpoint {
                     .process_build_dir_from_object_map_request(req)
                     .await
             }
+            UtilRequestType::PinAppWebDir => {
+                self.handler.process_pin_app_web_dir_request(req).await
+            }
+            UtilRequestType::UnpinAppWebDir => {
+                self.handler.process_unpin_app_web_dir_request(req).await
+            }
+            UtilRequestType::QueryZoneEvents => {
+                self.handler.process_query_zone_events_request(req).await
+            }
+            UtilRequestType::GetZoneDeviceHealth => {
+                self.handler.process_get_zone_device_health_request(req).await
+            }
+            UtilRequestType::GetOODResolverStats => {
+                self.handler.process_get_ood_resolver_stats_request(req).await
+            }
+            UtilRequestType::SetDecResourcePolicy => {
+                self.handler.process_set_dec_resource_policy_request(req).await
+            }
+            UtilRequestType::RemoveDecResourcePolicy => {
+                self.handler.process_remove_dec_resource_policy_request(req).await
+            }
+            UtilRequestType::GetDecResourcePolicies => {
+                self.handler.process_get_dec_resource_policies_request(req).await
+            }
+            UtilRequestType::GetConcurrencyStat => {
+                self.handler.process_get_concurrency_stat_request(req).await
+            }
+            UtilRequestType::GetObjectAccessStat => {
+                self.handler
+                    .process_get_object_access_stat_request(req)
+                    .await
+            }
         }
     }
 
@@ -302,6 +344,138 @@ impl UtilRequestHandlerEndpoint {
                 UtilRequestType::BuildDirFromObjectMap,
                 handler.clone(),
             ));
+        server.at("/util/pin_app_web_dir").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::PinAppWebDir,
+            handler.clone(),
+        ));
+        server.at("/util/pin_app_web_dir/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::PinAppWebDir,
+            handler.clone(),
+        ));
+
+        server.at("/util/unpin_app_web_dir").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::UnpinAppWebDir,
+            handler.clone(),
+        ));
+        server.at("/util/unpin_app_web_dir/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::UnpinAppWebDir,
+            handler.clone(),
+        ));
+
+        server.at("/util/query_zone_events").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::QueryZoneEvents,
+            handler.clone(),
+        ));
+        server.at("/util/query_zone_events/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::QueryZoneEvents,
+            handler.clone(),
+        ));
+
+        // get_zone_device_health
+        server.at("/util/zone_device_health").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetZoneDeviceHealth,
+            handler.clone(),
+        ));
+        server.at("/util/zone_device_health/").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetZoneDeviceHealth,
+            handler.clone(),
+        ));
+        // get_ood_resolver_stats
+        server.at("/util/ood_resolver_stats").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetOODResolverStats,
+            handler.clone(),
+        ));
+        server.at("/util/ood_resolver_stats/").get(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetOODResolverStats,
+            handler.clone(),
+        ));
+        // dec resource policy
+        server.at("/util/set_dec_resource_policy").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::SetDecResourcePolicy,
+            handler.clone(),
+        ));
+        server.at("/util/set_dec_resource_policy/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::SetDecResourcePolicy,
+            handler.clone(),
+        ));
+
+        server.at("/util/remove_dec_resource_policy").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::RemoveDecResourcePolicy,
+            handler.clone(),
+        ));
+        server.at("/util/remove_dec_resource_policy/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::RemoveDecResourcePolicy,
+            handler.clone(),
+        ));
+
+        server.at("/util/get_dec_resource_policies").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetDecResourcePolicies,
+            handler.clone(),
+        ));
+        server.at("/util/get_dec_resource_policies/*must").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetDecResourcePolicies,
+            handler.clone(),
+        ));
+        server.at("/util/get_concurrency_stat").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetConcurrencyStat,
+            handler.clone(),
+        ));
+        server
+            .at("/util/get_concurrency_stat/*must")
+            .post(Self::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                UtilRequestType::GetConcurrencyStat,
+                handler.clone(),
+            ));
+        server.at("/util/get_object_access_stat").post(Self::new(
+            zone_manager.clone(),
+            protocol.to_owned(),
+            UtilRequestType::GetObjectAccessStat,
+            handler.clone(),
+        ));
+        server
+            .at("/util/get_object_access_stat/*must")
+            .post(Self::new(
+                zone_manager.clone(),
+                protocol.to_owned(),
+                UtilRequestType::GetObjectAccessStat,
+                handler.clone(),
+            ));
     }
 }
 
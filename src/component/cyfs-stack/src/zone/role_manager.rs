@@ -4,9 +4,11 @@ use crate::config::StackGlobalConfig;
 use crate::events::RouterEventsManager;
 use crate::interface::{SyncListenerManager, SyncListenerManagerParams};
 use crate::meta::MetaCacheRef;
+use crate::ndn_api::NDNReplicaManager;
 use crate::root_state_api::GlobalStateLocalService;
 use crate::{sync::*, NamedDataComponents};
 use crate::util_api::UtilService;
+use crate::zone_event::ZoneEventRecorder;
 use cyfs_base::*;
 use cyfs_bdt::{DeviceCache, StackGuard};
 use cyfs_core::*;
@@ -64,9 +66,14 @@ pub struct ZoneRoleManager {
     sync_server: Arc<OnceCell<Arc<ZoneSyncServer>>>,
     sync_client: Arc<OnceCell<Arc<DeviceSyncClient>>>,
     sync_interface: Arc<OnceCell<SyncListenerManager>>,
+    sync_conflict_resolver: Arc<OnceCell<SyncConflictResolver>>,
 
     // events
     event_manager: RouterEventsManager,
+    zone_event_recorder: ZoneEventRecorder,
+
+    // ndn读取在zone内多个设备之间分摊
+    ndn_replica: NDNReplicaManager,
 }
 
 impl ZoneRoleManager {
@@ -77,6 +84,8 @@ impl ZoneRoleManager {
         raw_meta_cache: MetaCacheRef,
         acl_manager: AclManagerRef,
         event_manager: RouterEventsManager,
+        zone_event_recorder: ZoneEventRecorder,
+        ndn_replica: NDNReplicaManager,
         config: StackGlobalConfig,
     ) -> Self {
         Self {
@@ -86,12 +95,22 @@ impl ZoneRoleManager {
             raw_meta_cache,
             acl_manager,
             event_manager,
+            zone_event_recorder,
+            ndn_replica,
 
             config,
 
             sync_server: Arc::new(OnceCell::new()),
             sync_client: Arc::new(OnceCell::new()),
             sync_interface: Arc::new(OnceCell::new()),
+            sync_conflict_resolver: Arc::new(OnceCell::new()),
+        }
+    }
+
+    // 需要在init之前绑定，否则启动的sync client不会处理冲突
+    pub(crate) fn bind_sync_conflict_resolver(&self, resolver: SyncConflictResolver) {
+        if let Err(_) = self.sync_conflict_resolver.set(resolver) {
+            unreachable!();
         }
     }
 
@@ -99,6 +118,18 @@ impl ZoneRoleManager {
         &self.zone_manager
     }
 
+    pub(crate) fn zone_event_recorder(&self) -> &ZoneEventRecorder {
+        &self.zone_event_recorder
+    }
+
+    pub(crate) fn ndn_replica(&self) -> &NDNReplicaManager {
+        &self.ndn_replica
+    }
+
+    pub(crate) fn config(&self) -> &StackGlobalConfig {
+        &self.config
+    }
+
     pub(crate) fn sync_server(&self) -> Option<&Arc<ZoneSyncServer>> {
         self.sync_server.get()
     }
@@ -106,6 +137,17 @@ impl ZoneRoleManager {
         self.sync_client.get()
     }
 
+    // 协议栈退出前停止同步并保存同步状态
+    pub(crate) async fn stop_sync(&self) {
+        if let Some(client) = self.sync_client.get() {
+            client.stop().await;
+        }
+
+        if let Some(server) = self.sync_server.get() {
+            server.stop().await;
+        }
+    }
+
     pub(crate) async fn init_root_state_access_mode(&self) -> BuckyResult<()> {
         let current_zone_info = self.zone_manager.get_current_info().await?;
         let access_mode = match current_zone_info.zone_role {
@@ -186,6 +228,7 @@ impl ZoneRoleManager {
             context: None,
             last_access_rpath: None,
             access_string: Some(AccessString::full_except_write().value()),
+            precondition: None,
         };
 
         match self.noc.put_object(&req).await {
@@ -622,6 +665,7 @@ impl ZoneRoleManager {
             self.noc.clone(),
             self.acl_manager.clone(),
             named_data_components,
+            self.sync_conflict_resolver.get().cloned(),
         )
         .await?;
 
@@ -649,6 +693,8 @@ impl ZoneRoleManager {
         let params = SyncListenerManagerParams {
             bdt_stack: bdt_stack.to_owned(),
             bdt_listeners: vec![cyfs_base::NON_STACK_SYNC_BDT_VPORT],
+            request_gate: self.config.request_gate().clone(),
+            bandwidth_recorder: self.config.bandwidth_recorder().clone(),
         };
 
         let mut interface = SyncListenerManager::new();
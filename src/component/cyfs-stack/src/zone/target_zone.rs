@@ -8,6 +8,9 @@ pub struct TargetZoneInfo {
     pub is_current_zone: bool,
     pub target_device: DeviceId,
     pub target_ood: DeviceId,
+
+    // target zone's owner, none if target device is an orphan device
+    pub target_owner: Option<ObjectId>,
 }
 
 pub struct TargetZoneManager {
@@ -94,6 +97,7 @@ impl TargetZoneManager {
                 is_current_zone: true,
                 target_device: info.zone_device_ood_id.clone(),
                 target_ood: info.zone_device_ood_id.clone(),
+                target_owner: Some(info.owner_id.clone()),
             });
         }
 
@@ -137,6 +141,7 @@ impl TargetZoneManager {
                                 is_current_zone: true,
                                 target_device: device_id.clone(),
                                 target_ood: device_id,
+                                target_owner: None,
                             };
                             Ok(ret)
                         } else {
@@ -144,6 +149,7 @@ impl TargetZoneManager {
                                 is_current_zone: false,
                                 target_device: device_id.clone(),
                                 target_ood: device_id,
+                                target_owner: None,
                             };
                             Ok(ret)
                         }
@@ -154,6 +160,7 @@ impl TargetZoneManager {
                                 is_current_zone: true,
                                 target_device: device_id.clone(),
                                 target_ood: info.zone_device_ood_id.clone(),
+                                target_owner: Some(owner.clone()),
                             };
 
                             Ok(ret)
@@ -171,6 +178,7 @@ impl TargetZoneManager {
                                 is_current_zone: false,
                                 target_device: device_id.clone(),
                                 target_ood: ood_list.remove(0),
+                                target_owner: Some(owner.clone()),
                             };
                             Ok(ret)
                         }
@@ -184,6 +192,7 @@ impl TargetZoneManager {
                         is_current_zone: true,
                         target_device: info.zone_device_ood_id.clone(),
                         target_ood: info.zone_device_ood_id.clone(),
+                        target_owner: Some(target.clone()),
                     };
 
                     Ok(ret)
@@ -201,6 +210,7 @@ impl TargetZoneManager {
                         is_current_zone: false,
                         target_device: ood.clone(),
                         target_ood: ood,
+                        target_owner: Some(target.clone()),
                     };
                     Ok(ret)
                 }
@@ -219,6 +229,7 @@ impl TargetZoneManager {
                         is_current_zone: true,
                         target_device: info.zone_device_ood_id.clone(),
                         target_ood: info.zone_device_ood_id.clone(),
+                        target_owner: Some(info.owner_id.clone()),
                     };
 
                     Ok(ret)
@@ -228,6 +239,7 @@ impl TargetZoneManager {
                             is_current_zone: false,
                             target_device: zone.ood().clone(),
                             target_ood: zone.ood().clone(),
+                            target_owner: Some(zone.owner().clone()),
                         };
                         Ok(ret)
                     } else {
@@ -419,6 +419,7 @@ impl ZoneContainer {
             context: None,
             last_access_rpath: None,
             access_string: None,
+            precondition: None,
         };
 
         match self.noc.put_object(&info).await {
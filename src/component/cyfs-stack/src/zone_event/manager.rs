@@ -0,0 +1,487 @@
+use super::recorder::*;
+use crate::events::RouterEventsManager;
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+// 事件记录在root_state的系统dec下: /zone/events/{time}_{category}_{event_id} -> event_id
+const ZONE_EVENTS_PATH: &str = "/zone/events";
+
+// 保留策略，超过条数或者超过时长的事件会被清理
+const ZONE_EVENTS_MAX_COUNT: usize = 1024 * 10;
+const ZONE_EVENTS_MAX_AGE: u64 = 1000 * 1000 * 3600 * 24 * 30;
+const ZONE_EVENTS_PRUNE_INTERVAL_SECS: u64 = 60 * 60;
+
+// 一次提交的最大事件个数
+const ZONE_EVENTS_BATCH: usize = 64;
+
+const ZONE_EVENT_HANDLER_ID: &str = "system_zone_event_recorder";
+
+struct ZoneEventIndexItem {
+    key: String,
+    time: u64,
+    category: ZoneEventCategory,
+    event_id: ObjectId,
+}
+
+impl ZoneEventIndexItem {
+    fn new(event_id: &ObjectId, event: &ZoneEvent) -> Self {
+        let time = event.time();
+        let category = event.category();
+        let key = format!("{:020}_{}_{}", time, category as u8, event_id);
+
+        Self {
+            key,
+            time,
+            category,
+            event_id: event_id.to_owned(),
+        }
+    }
+
+    fn parse(key: String, event_id: ObjectId) -> BuckyResult<Self> {
+        let invalid = || {
+            let msg = format!("invalid zone event index key: {}", key);
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        };
+
+        let mut parts = key.splitn(3, '_');
+        let time = parts
+            .next()
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(invalid)?;
+        let category = parts
+            .next()
+            .and_then(|v| v.parse::<u8>().ok())
+            .and_then(|v| ZoneEventCategory::try_from(v).ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Self {
+            key,
+            time,
+            category,
+            event_id,
+        })
+    }
+
+    fn full_path(&self) -> String {
+        format!("{}/{}", ZONE_EVENTS_PATH, self.key)
+    }
+}
+
+struct OnZoneEventPostWatcher {
+    owner: ZoneEventManager,
+}
+
+#[async_trait::async_trait]
+impl EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPostObjectResult>
+    for OnZoneEventPostWatcher
+{
+    async fn call(
+        &self,
+        param: &RouterHandlerPostObjectRequest,
+    ) -> BuckyResult<RouterHandlerPostObjectResult> {
+        debug!(
+            "recv zone event post request: {}",
+            param.request.object.object_id
+        );
+
+        let ret = match self
+            .owner
+            .on_post_event(&param.request.common.source, &param.request.object)
+        {
+            Ok(()) => Ok(NONPostObjectInputResponse { object: None }),
+            Err(e) => Err(e),
+        };
+
+        let resp = RouterHandlerPostObjectResult {
+            action: RouterHandlerAction::Response,
+            request: None,
+            response: Some(ret),
+        };
+
+        Ok(resp)
+    }
+}
+
+struct ZoneEventManagerInner {
+    zone_manager: ZoneManagerRef,
+    recorder: ZoneEventRecorder,
+    non: NONOutputProcessorRef,
+    root_state_stub: GlobalStateStub,
+    event_manager: RouterEventsManager,
+}
+
+// zone级别的事件时间线，只有active ood负责存储，其余设备把事件转发给ood
+#[derive(Clone)]
+pub(crate) struct ZoneEventManager(Arc<ZoneEventManagerInner>);
+
+impl ZoneEventManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        recorder: ZoneEventRecorder,
+        non: NONInputProcessorRef,
+        root_state: GlobalStateInputProcessorRef,
+        event_manager: RouterEventsManager,
+    ) -> BuckyResult<Self> {
+        let info = zone_manager.get_current_info().await?;
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(root_state, source);
+        let root_state_stub = GlobalStateStub::new(
+            processor,
+            Some(info.zone_device_ood_id.object_id().clone()),
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = ZoneEventManagerInner {
+            zone_manager: zone_manager.clone(),
+            recorder,
+            non,
+            root_state_stub,
+            event_manager,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub fn recorder(&self) -> &ZoneEventRecorder {
+        &self.0.recorder
+    }
+
+    pub async fn init(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if info.zone_role.is_ood_device() {
+            self.register_router_handler(router_handlers).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn start(&self, receiver: ZoneEventReceiver) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run(receiver).await;
+        });
+
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run_prune().await;
+        });
+    }
+
+    async fn register_router_handler(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let routine = OnZoneEventPostWatcher {
+            owner: self.clone(),
+        };
+
+        let req_path =
+            RequestGlobalStatePath::new_system_dec(Some(CYFS_SYSTEM_ZONE_EVENT_VIRTUAL_PATH));
+        if let Err(e) = router_handlers
+            .post_object()
+            .add_handler(
+                RouterHandlerChain::Handler,
+                ZONE_EVENT_HANDLER_ID,
+                1,
+                None,
+                Some(req_path.to_string()),
+                RouterHandlerAction::Default,
+                Some(Box::new(routine)),
+            )
+            .await
+        {
+            error!("add zone event post handler error! {}", e);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    // 同zone设备转发的事件，以及app-manager/backup等系统服务提交的事件
+    fn on_post_event(&self, source: &RequestSourceInfo, object: &NONObjectInfo) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "zone event source device is not in current zone! source={}",
+                source
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let event = ZoneEvent::clone_from_slice(&object.object_raw).map_err(|e| {
+            let msg = format!(
+                "invalid zone event object buffer! id={}, {}",
+                object.object_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        self.0.recorder.post(event);
+
+        Ok(())
+    }
+
+    async fn run(&self, receiver: ZoneEventReceiver) {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(_) => {
+                    warn!("zone event queue closed!");
+                    break;
+                }
+            };
+
+            let mut list = vec![event];
+            while list.len() < ZONE_EVENTS_BATCH {
+                match receiver.try_recv() {
+                    Ok(event) => list.push(event),
+                    Err(_) => break,
+                }
+            }
+
+            if let Err(e) = self.on_events(list).await {
+                error!("process zone events failed! {}", e);
+            }
+        }
+    }
+
+    async fn on_events(&self, list: Vec<ZoneEvent>) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if info.zone_role.is_active_ood() {
+            self.store(list).await
+        } else {
+            for event in list {
+                let _ = self.forward(&info.zone_device_ood_id, event).await;
+            }
+
+            Ok(())
+        }
+    }
+
+    async fn store(&self, list: Vec<ZoneEvent>) -> BuckyResult<()> {
+        let mut items = Vec::with_capacity(list.len());
+        for event in list {
+            let event_id = event.desc().calculate_id();
+            let object_raw = event.to_vec()?;
+
+            let req = NONPutObjectOutputRequest::new_noc(event_id.clone(), object_raw.clone());
+            if let Err(e) = self.0.non.put_object(req).await {
+                error!("save zone event to noc failed! event={}, {}", event_id, e);
+                continue;
+            }
+
+            let index = ZoneEventIndexItem::new(&event_id, &event);
+            items.push((index, NONObjectInfo::new(event_id, object_raw, None)));
+        }
+
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        for (index, _) in &items {
+            if let Err(e) = op_env
+                .set_with_path(index.full_path(), &index.event_id, None, true)
+                .await
+            {
+                error!("save zone event index failed! event={}, {}", index.event_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        }
+        op_env.commit().await?;
+
+        info!("save zone events success! count={}", items.len());
+
+        for (index, event) in items {
+            self.emit_event(index.event_id, event).await;
+        }
+
+        Ok(())
+    }
+
+    async fn forward(&self, ood_id: &DeviceId, event: ZoneEvent) -> BuckyResult<()> {
+        let event_id = event.desc().calculate_id();
+        let mut req = NONPostObjectOutputRequest::new_router(
+            Some(ood_id.object_id().to_owned()),
+            event_id.clone(),
+            event.to_vec()?,
+        );
+        req.common.req_path = Some(CYFS_SYSTEM_ZONE_EVENT_VIRTUAL_PATH.to_owned());
+
+        match self.0.non.post_object(req).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == BuckyErrorCode::Ok => Ok(()),
+            Err(e) => {
+                warn!(
+                    "forward zone event to ood failed! event={}, ood={}, {}",
+                    event_id, ood_id, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    async fn emit_event(&self, event_id: ObjectId, event: NONObjectInfo) {
+        let events = self.0.event_manager.events().try_zone_event_recorded_event();
+        if events.is_none() {
+            return;
+        }
+
+        let param = ZoneEventRecordedEventRequest { event_id, event };
+
+        let mut emitter = events.unwrap().emitter();
+        let resp = emitter.emit(param).await;
+        debug!("zone event recorded event resp: {}", resp);
+    }
+
+    async fn load_index(&self) -> BuckyResult<Vec<ZoneEventIndexItem>> {
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        let ret = op_env.list(ZONE_EVENTS_PATH).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list zone events index failed! {}", e);
+                return Err(e);
+            }
+        };
+
+        let mut result = Vec::with_capacity(list.len());
+        for item in list {
+            if let ObjectMapContentItem::Map((key, event_id)) = item {
+                if let Ok(index) = ZoneEventIndexItem::parse(key, event_id) {
+                    result.push(index);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub async fn query(
+        &self,
+        req: UtilQueryZoneEventsInputRequest,
+    ) -> BuckyResult<UtilQueryZoneEventsInputResponse> {
+        let mut list = self.load_index().await?;
+        list.retain(|item| {
+            if let Some(category) = &req.category {
+                if item.category != *category {
+                    return false;
+                }
+            }
+            if let Some(start_time) = req.start_time {
+                if item.time < start_time {
+                    return false;
+                }
+            }
+            if let Some(end_time) = req.end_time {
+                if item.time >= end_time {
+                    return false;
+                }
+            }
+            true
+        });
+
+        // 最新的事件在前
+        list.sort_by(|left, right| right.key.cmp(&left.key));
+
+        let start = req.page_index as usize * req.page_size as usize;
+        let ood_id = self.0.zone_manager.get_current_info().await?.zone_device_ood_id;
+
+        let mut result = vec![];
+        for item in list.iter().skip(start).take(req.page_size as usize) {
+            let get_req = NONGetObjectOutputRequest::new_router(
+                Some(ood_id.object_id().to_owned()),
+                item.event_id.clone(),
+                None,
+            );
+            match self.0.non.get_object(get_req).await {
+                Ok(resp) => result.push(resp.object),
+                Err(e) => {
+                    warn!("load zone event object failed! event={}, {}", item.event_id, e);
+                }
+            }
+        }
+
+        Ok(UtilQueryZoneEventsInputResponse { list: result })
+    }
+
+    async fn run_prune(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(ZONE_EVENTS_PRUNE_INTERVAL_SECS));
+        while let Some(_) = interval.next().await {
+            if let Err(e) = self.prune().await {
+                error!("prune zone events failed! {}", e);
+            }
+        }
+    }
+
+    async fn prune(&self) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if !info.zone_role.is_active_ood() {
+            return Ok(());
+        }
+
+        let mut list = self.load_index().await?;
+        list.sort_by(|left, right| left.key.cmp(&right.key));
+
+        let min_time = bucky_time_now().saturating_sub(ZONE_EVENTS_MAX_AGE);
+        let over = list.len().saturating_sub(ZONE_EVENTS_MAX_COUNT);
+        let expired: Vec<ZoneEventIndexItem> = list
+            .into_iter()
+            .enumerate()
+            .filter(|(i, item)| *i < over || item.time < min_time)
+            .map(|(_, item)| item)
+            .collect();
+
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        let op_env = self.0.root_state_stub.create_path_op_env().await?;
+        for item in &expired {
+            if let Err(e) = op_env
+                .remove_with_path(item.full_path(), Some(item.event_id.clone()))
+                .await
+            {
+                error!("remove zone event index failed! event={}, {}", item.event_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        }
+        op_env.commit().await?;
+
+        for item in &expired {
+            let req = NONDeleteObjectOutputRequest::new_noc(item.event_id.clone(), None);
+            if let Err(e) = self.0.non.delete_object(req).await {
+                warn!("remove zone event from noc failed! event={}, {}", item.event_id, e);
+            }
+        }
+
+        info!("prune zone events complete! count={}", expired.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod manager;
+mod recorder;
+
+pub(crate) use manager::*;
+pub(crate) use recorder::*;
@@ -0,0 +1,106 @@
+use cyfs_base::*;
+use cyfs_core::*;
+
+use async_std::channel::{Receiver, Sender};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// 高频事件的最小记录间隔，同一设备同一dec在间隔内只记录一次
+const ZONE_EVENT_THROTTLE_INTERVAL: u64 = 1000 * 1000 * 60;
+
+pub(crate) type ZoneEventReceiver = Receiver<ZoneEvent>;
+
+// 事件的记录入口，只负责生成对象并投递，存储由ZoneEventManager异步完成
+#[derive(Clone)]
+pub(crate) struct ZoneEventRecorder {
+    device_id: DeviceId,
+    owner: Option<ObjectId>,
+    sender: Sender<ZoneEvent>,
+
+    // (category, device, dec) -> last record time
+    throttle: Arc<Mutex<HashMap<(ZoneEventCategory, DeviceId, Option<ObjectId>), u64>>>,
+}
+
+impl ZoneEventRecorder {
+    pub fn new(device_id: DeviceId, owner: Option<ObjectId>) -> (Self, ZoneEventReceiver) {
+        let (sender, receiver) = async_std::channel::unbounded();
+
+        let ret = Self {
+            device_id,
+            owner,
+            sender,
+            throttle: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        (ret, receiver)
+    }
+
+    // 记录当前设备上发生的事件
+    pub fn record_local(
+        &self,
+        category: ZoneEventCategory,
+        dec_id: Option<ObjectId>,
+        detail: impl Into<String>,
+    ) {
+        self.record(category, self.device_id.clone(), dec_id, detail)
+    }
+
+    pub fn record(
+        &self,
+        category: ZoneEventCategory,
+        device: DeviceId,
+        dec_id: Option<ObjectId>,
+        detail: impl Into<String>,
+    ) {
+        if Self::need_throttle(category) && !self.check_throttle(category, &device, &dec_id) {
+            return;
+        }
+
+        let event = ZoneEvent::create(self.owner.clone(), category, device, dec_id, detail.into());
+        self.post(event);
+    }
+
+    // 投递外部提交的事件对象
+    pub fn post(&self, event: ZoneEvent) {
+        if let Err(e) = self.sender.try_send(event) {
+            error!("post zone event to queue failed! {}", e);
+        }
+    }
+
+    fn need_throttle(category: ZoneEventCategory) -> bool {
+        match category {
+            ZoneEventCategory::SyncComplete | ZoneEventCategory::AclDenied => true,
+            _ => false,
+        }
+    }
+
+    fn check_throttle(
+        &self,
+        category: ZoneEventCategory,
+        device: &DeviceId,
+        dec_id: &Option<ObjectId>,
+    ) -> bool {
+        let now = bucky_time_now();
+        let mut throttle = self.throttle.lock().unwrap();
+
+        // 顺便清理过期的项，避免无限增长
+        if throttle.len() > 1024 {
+            throttle.retain(|_, last| now < *last + ZONE_EVENT_THROTTLE_INTERVAL);
+        }
+
+        let key = (category, device.to_owned(), dec_id.to_owned());
+        match throttle.get_mut(&key) {
+            Some(last) => {
+                if now < *last + ZONE_EVENT_THROTTLE_INTERVAL {
+                    return false;
+                }
+                *last = now;
+            }
+            None => {
+                throttle.insert(key, now);
+            }
+        }
+
+        true
+    }
+}
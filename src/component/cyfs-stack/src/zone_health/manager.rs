@@ -0,0 +1,424 @@
+use crate::dec_resource::DecResourceManager;
+use crate::non::{NONInputProcessorRef, NONOutputTransformer};
+use crate::root_state::{GlobalStateInputProcessorRef, GlobalStateOutputTransformer};
+use crate::zone::ZoneManagerRef;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+use cyfs_util::SYSTEM_INFO_MANAGER;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+// 各设备的状态保存在local_cache的系统dec下: /zone/device_health/{device_id} -> text_id
+const DEVICE_HEALTH_PATH: &str = "/zone/device_health";
+
+// 上报用的Text对象id，header为device_id，value为DeviceHealthInfo的json
+const DEVICE_HEALTH_TEXT_ID: &str = "device_health";
+
+const DEVICE_HEALTH_REPORT_INTERVAL_SECS: u64 = 60 * 5;
+
+const DEVICE_HEALTH_HANDLER_ID: &str = "system_device_health_reporter";
+
+struct OnDeviceHealthPostWatcher {
+    owner: DeviceHealthManager,
+}
+
+#[async_trait::async_trait]
+impl EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPostObjectResult>
+    for OnDeviceHealthPostWatcher
+{
+    async fn call(
+        &self,
+        param: &RouterHandlerPostObjectRequest,
+    ) -> BuckyResult<RouterHandlerPostObjectResult> {
+        debug!(
+            "recv device health report: {}",
+            param.request.object.object_id
+        );
+
+        let ret = match self
+            .owner
+            .on_post_report(&param.request.common.source, &param.request.object)
+            .await
+        {
+            Ok(()) => Ok(NONPostObjectInputResponse { object: None }),
+            Err(e) => Err(e),
+        };
+
+        let resp = RouterHandlerPostObjectResult {
+            action: RouterHandlerAction::Response,
+            request: None,
+            response: Some(ret),
+        };
+
+        Ok(resp)
+    }
+}
+
+struct DeviceHealthManagerInner {
+    zone_manager: ZoneManagerRef,
+    non: NONOutputProcessorRef,
+    local_cache_stub: GlobalStateStub,
+    dec_resource_manager: DecResourceManager,
+}
+
+// 每个设备定时把自身的运行状态写入本地local_cache，并上报给ood汇总
+#[derive(Clone)]
+pub(crate) struct DeviceHealthManager(Arc<DeviceHealthManagerInner>);
+
+impl DeviceHealthManager {
+    pub async fn new(
+        zone_manager: &ZoneManagerRef,
+        non: NONInputProcessorRef,
+        local_cache: GlobalStateInputProcessorRef,
+        dec_resource_manager: DecResourceManager,
+    ) -> BuckyResult<Self> {
+        let source = zone_manager
+            .get_current_source_info(&Some(cyfs_core::get_system_dec_app().to_owned()))
+            .await?;
+
+        let non = NONOutputTransformer::new(non, source.clone());
+
+        let processor = GlobalStateOutputTransformer::new(local_cache, source);
+        let local_cache_stub = GlobalStateStub::new(
+            processor,
+            None,
+            Some(cyfs_core::get_system_dec_app().to_owned()),
+        );
+
+        let inner = DeviceHealthManagerInner {
+            zone_manager: zone_manager.clone(),
+            non,
+            local_cache_stub,
+            dec_resource_manager,
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    pub async fn init(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let info = self.0.zone_manager.get_current_info().await?;
+        if info.zone_role.is_ood_device() {
+            self.register_router_handler(router_handlers).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn start(&self) {
+        let this = self.clone();
+        async_std::task::spawn(async move {
+            this.run().await;
+        });
+    }
+
+    async fn register_router_handler(
+        &self,
+        router_handlers: &RouterHandlerManagerProcessorRef,
+    ) -> BuckyResult<()> {
+        let routine = OnDeviceHealthPostWatcher {
+            owner: self.clone(),
+        };
+
+        let req_path =
+            RequestGlobalStatePath::new_system_dec(Some(CYFS_SYSTEM_DEVICE_HEALTH_VIRTUAL_PATH));
+        if let Err(e) = router_handlers
+            .post_object()
+            .add_handler(
+                RouterHandlerChain::Handler,
+                DEVICE_HEALTH_HANDLER_ID,
+                1,
+                None,
+                Some(req_path.to_string()),
+                RouterHandlerAction::Default,
+                Some(Box::new(routine)),
+            )
+            .await
+        {
+            error!("add device health post handler error! {}", e);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    async fn run(&self) {
+        use async_std::prelude::*;
+
+        let mut interval =
+            async_std::stream::interval(Duration::from_secs(DEVICE_HEALTH_REPORT_INTERVAL_SECS));
+        loop {
+            if let Err(e) = self.report().await {
+                error!("report device health failed! {}", e);
+            }
+
+            let _ = interval.next().await;
+        }
+    }
+
+    async fn collect(&self) -> DeviceHealthInfo {
+        let info = SYSTEM_INFO_MANAGER.get_system_info().await;
+
+        DeviceHealthInfo {
+            device_id: self.0.zone_manager.get_current_device_id().to_owned(),
+            stack_version: cyfs_base::get_version().to_owned(),
+            update_time: bucky_time_now(),
+            cpu_usage: info.cpu_usage,
+            total_memory: info.total_memory,
+            used_memory: info.used_memory,
+            disk_total: info.ssd_disk_total + info.hdd_disk_total,
+            disk_avail: info.ssd_disk_avail + info.hdd_disk_avail,
+            received_bytes: info.received_bytes,
+            transmitted_bytes: info.transmitted_bytes,
+            dec_usage: self.0.dec_resource_manager.collect_usage().await,
+        }
+    }
+
+    async fn report(&self) -> BuckyResult<()> {
+        let health = self.collect().await;
+        let value = serde_json::to_string(&health).unwrap();
+
+        let zone_info = self.0.zone_manager.get_current_info().await?;
+        let text = Text::build(DEVICE_HEALTH_TEXT_ID, health.device_id.to_string(), value)
+            .owner(zone_info.owner_id.clone())
+            .no_create_time()
+            .build();
+
+        // 本地总是保存一份，非active ood设备再上报给ood
+        self.save(&health.device_id, &text).await?;
+
+        if !zone_info.zone_role.is_active_ood() {
+            self.post_to_ood(&zone_info.zone_device_ood_id, &text).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn post_to_ood(&self, ood_id: &DeviceId, text: &Text) -> BuckyResult<()> {
+        let text_id = text.desc().calculate_id();
+        let mut req = NONPostObjectOutputRequest::new_router(
+            Some(ood_id.object_id().to_owned()),
+            text_id.clone(),
+            text.to_vec()?,
+        );
+        req.common.req_path = Some(CYFS_SYSTEM_DEVICE_HEALTH_VIRTUAL_PATH.to_owned());
+
+        match self.0.non.post_object(req).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == BuckyErrorCode::Ok => Ok(()),
+            Err(e) => {
+                warn!("post device health to ood failed! ood={}, {}", ood_id, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn on_post_report(
+        &self,
+        source: &RequestSourceInfo,
+        object: &NONObjectInfo,
+    ) -> BuckyResult<()> {
+        if !source.is_current_zone() {
+            let msg = format!(
+                "device health source device is not in current zone! source={}",
+                source
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        let text = Text::clone_from_slice(&object.object_raw).map_err(|e| {
+            let msg = format!(
+                "invalid device health object buffer! id={}, {}",
+                object.object_id, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let health = Self::decode(&text)?;
+
+        // 设备只能上报自己的状态
+        if let Some(device) = &source.zone.device {
+            if *device != health.device_id {
+                let msg = format!(
+                    "device health report not match the source device! source={}, device={}",
+                    device, health.device_id
+                );
+                error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+            }
+        }
+
+        self.save(&health.device_id, &text).await
+    }
+
+    fn decode(text: &Text) -> BuckyResult<DeviceHealthInfo> {
+        if text.id() != DEVICE_HEALTH_TEXT_ID {
+            let msg = format!("invalid device health text id: {}", text.id());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidData, msg));
+        }
+
+        serde_json::from_str(text.value()).map_err(|e| {
+            let msg = format!(
+                "invalid device health value! device={}, {}",
+                text.header(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+
+    async fn save(&self, device_id: &DeviceId, text: &Text) -> BuckyResult<()> {
+        let text_id = text.desc().calculate_id();
+
+        let req = NONPutObjectOutputRequest::new_noc(text_id.clone(), text.to_vec()?);
+        self.0.non.put_object(req).await.map_err(|e| {
+            error!(
+                "save device health to noc failed! device={}, {}",
+                device_id, e
+            );
+            e
+        })?;
+
+        let path = format!("{}/{}", DEVICE_HEALTH_PATH, device_id);
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        let prev = match op_env.set_with_path(&path, &text_id, None, true).await {
+            Ok(prev) => prev,
+            Err(e) => {
+                error!("save device health index failed! device={}, {}", device_id, e);
+                let _ = op_env.abort().await;
+                return Err(e);
+            }
+        };
+        op_env.commit().await?;
+
+        debug!("save device health success! device={}, text={}", device_id, text_id);
+
+        // 旧的上报对象已经没用了
+        if let Some(prev) = prev {
+            if prev != text_id {
+                let req = NONDeleteObjectOutputRequest::new_noc(prev.clone(), None);
+                if let Err(e) = self.0.non.delete_object(req).await {
+                    warn!("remove old device health from noc failed! text={}, {}", prev, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn load_all(&self) -> BuckyResult<Vec<DeviceHealthInfo>> {
+        let op_env = self.0.local_cache_stub.create_path_op_env().await?;
+        let ret = op_env.list(DEVICE_HEALTH_PATH).await;
+        let _ = op_env.abort().await;
+
+        let list = match ret {
+            Ok(list) => list,
+            Err(e) if e.code() == BuckyErrorCode::NotFound => vec![],
+            Err(e) => {
+                error!("list device health failed! {}", e);
+                return Err(e);
+            }
+        };
+
+        let mut result = Vec::with_capacity(list.len());
+        for item in list {
+            let (device, text_id) = match item {
+                ObjectMapContentItem::Map(v) => v,
+                _ => continue,
+            };
+
+            let req = NONGetObjectOutputRequest::new_noc(text_id.clone(), None);
+            let resp = match self.0.non.get_object(req).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    warn!(
+                        "load device health object failed! device={}, text={}, {}",
+                        device, text_id, e
+                    );
+                    continue;
+                }
+            };
+
+            let ret = Text::clone_from_slice(&resp.object.object_raw)
+                .and_then(|text| Self::decode(&text));
+            match ret {
+                Ok(health) => result.push(health),
+                Err(e) => {
+                    warn!("decode device health failed! device={}, {}", device, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_health() -> DeviceHealthInfo {
+        DeviceHealthInfo {
+            device_id: DeviceId::default(),
+            stack_version: "1.1.0".to_owned(),
+            update_time: bucky_time_now(),
+            cpu_usage: 12.5,
+            total_memory: 1024 * 1024 * 1024,
+            used_memory: 512 * 1024 * 1024,
+            disk_total: 1024 * 1024 * 1024 * 100,
+            disk_avail: 1024 * 1024 * 1024 * 10,
+            received_bytes: 1024,
+            transmitted_bytes: 2048,
+            dec_usage: vec![],
+        }
+    }
+
+    fn new_text(id: &str, value: String) -> Text {
+        Text::build(id, DeviceId::default().to_string(), value)
+            .no_create_time()
+            .build()
+    }
+
+    #[test]
+    fn test_decode() {
+        let health = new_health();
+        let text = new_text(
+            DEVICE_HEALTH_TEXT_ID,
+            serde_json::to_string(&health).unwrap(),
+        );
+
+        let ret = DeviceHealthManager::decode(&text).unwrap();
+        assert_eq!(ret.device_id, health.device_id);
+        assert_eq!(ret.stack_version, health.stack_version);
+        assert_eq!(ret.update_time, health.update_time);
+        assert_eq!(ret.used_memory, health.used_memory);
+        assert_eq!(ret.disk_avail, health.disk_avail);
+
+        // 旧版本上报的数据没有dec_usage字段
+        let mut value = serde_json::to_value(&health).unwrap();
+        value.as_object_mut().unwrap().remove("dec_usage");
+        let text = new_text(DEVICE_HEALTH_TEXT_ID, value.to_string());
+        let ret = DeviceHealthManager::decode(&text).unwrap();
+        assert!(ret.dec_usage.is_empty());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let value = serde_json::to_string(&new_health()).unwrap();
+        let text = new_text("other", value);
+        let err = DeviceHealthManager::decode(&text).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+
+        let text = new_text(DEVICE_HEALTH_TEXT_ID, "{}".to_owned());
+        let err = DeviceHealthManager::decode(&text).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::InvalidData);
+    }
+}
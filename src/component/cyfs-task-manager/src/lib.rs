@@ -2,6 +2,7 @@ mod condvar_helper;
 mod db_helper;
 mod object_locker;
 mod task;
+mod task_dependency;
 mod task_manager;
 mod task_store;
 
@@ -10,6 +11,7 @@ pub use task_manager::*;
 pub(crate) use db_helper::*;
 pub(crate) use object_locker::*;
 pub use task::*;
+pub(crate) use task_dependency::*;
 pub use task_store::*;
 
 #[macro_use]
@@ -180,6 +180,71 @@ pub trait Task: Send + Sync {
         }
     }
     async fn get_task_detail_status(&self) -> BuckyResult<Vec<u8>>;
+    // 进程退出前调用，暂停底层的传输并保存当前进度，任务状态保持不变，下次启动时通过resume_task恢复
+    async fn checkpoint(&self) -> BuckyResult<()> {
+        Ok(())
+    }
+}
+
+// 任务失败后的自动重试策略，两次执行之间按指数退避等待
+#[derive(Clone, Debug)]
+pub struct TaskRetryPolicy {
+    // 包括第一次执行在内的最大执行次数
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for TaskRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2,
+        }
+    }
+}
+
+impl TaskRetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    // 第attempt次(从1开始)执行失败后，到下一次执行之前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let mut delay = self.initial_delay;
+        for _ in 1..attempt {
+            delay = delay.saturating_mul(self.multiplier);
+            if delay >= self.max_delay {
+                return self.max_delay;
+            }
+        }
+
+        std::cmp::min(delay, self.max_delay)
+    }
+
+    // 参数错误、不支持以及被取消之类的错误重试也不会成功
+    pub fn should_retry(&self, attempt: u32, err: &BuckyError) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match err.code() {
+            BuckyErrorCode::InvalidParam
+            | BuckyErrorCode::InvalidFormat
+            | BuckyErrorCode::NotSupport
+            | BuckyErrorCode::UnSupport
+            | BuckyErrorCode::PermissionDenied
+            | BuckyErrorCode::Aborted
+            | BuckyErrorCode::UserCanceled => false,
+            _ => true,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -194,6 +259,35 @@ pub trait Runnable: Send + Sync {
     async fn set_task_store(&mut self, task_store: Arc<dyn TaskStore>);
     async fn run(&self) -> BuckyResult<()>;
     async fn get_task_detail_status(&self) -> BuckyResult<Vec<u8>>;
+    // 返回Some时run失败后由RunnableTask按策略自动重试，不需要在run里自己实现重试
+    fn retry_policy(&self) -> Option<TaskRetryPolicy> {
+        None
+    }
+}
+
+async fn run_with_retry(runnable: &dyn Runnable) -> BuckyResult<()> {
+    let policy = match runnable.retry_policy() {
+        Some(policy) => policy,
+        None => return runnable.run().await,
+    };
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let err = match runnable.run().await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        if !policy.should_retry(attempt, &err) {
+            return Err(err);
+        }
+
+        let delay = policy.delay(attempt);
+        warn!("task run failed, will retry! task={}, attempt={}/{}, delay={:?}, {}",
+            runnable.get_task_id(), attempt, policy.max_attempts, delay, err);
+        bucky_sleep(delay).await;
+    }
 }
 
 struct RunnableTaskData {
@@ -278,7 +372,7 @@ impl<R: 'static + Runnable> Task for RunnableTask<R> {
         }
 
         let (ft, handle) = futures::future::abortable(async move {
-            runnable.run().await
+            run_with_retry(runnable.as_ref()).await
         });
 
         {
@@ -427,8 +521,8 @@ pub trait TaskFactory: 'static + Send + Sync {
 mod test_task {
     use std::sync::Arc;
     use std::time::Duration;
-    use cyfs_base::BuckyResult;
-    use crate::{Runnable, RunnableTask, Task, TaskCategory, TaskId, TaskStatus, TaskStore, TaskType};
+    use cyfs_base::{BuckyError, BuckyErrorCode, BuckyResult};
+    use crate::{Runnable, RunnableTask, Task, TaskCategory, TaskId, TaskRetryPolicy, TaskStatus, TaskStore, TaskType};
 
     struct TestRunnable {
 
@@ -461,6 +555,26 @@ mod test_task {
             todo!()
         }
     }
+    #[test]
+    fn test_retry_policy() {
+        let policy = TaskRetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            multiplier: 2,
+        };
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(4), Duration::from_secs(5));
+        assert_eq!(policy.delay(100), Duration::from_secs(5));
+
+        let err = BuckyError::from(BuckyErrorCode::Timeout);
+        assert!(policy.should_retry(1, &err));
+        assert!(!policy.should_retry(5, &err));
+        assert!(!policy.should_retry(1, &BuckyError::from(BuckyErrorCode::InvalidParam)));
+    }
+
     #[test]
     fn test_runnable() {
         async_std::task::block_on(async {
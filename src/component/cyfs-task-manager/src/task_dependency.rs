@@ -0,0 +1,100 @@
+use std::collections::{HashMap, HashSet};
+use cyfs_base::*;
+use crate::TaskId;
+
+// 任务之间的依赖关系，只保存在内存里，必须是有向无环图
+#[derive(Default)]
+pub(crate) struct TaskDependencyGraph {
+    // task -> 该任务依赖的任务列表
+    deps: HashMap<TaskId, HashSet<TaskId>>,
+}
+
+impl TaskDependencyGraph {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // 增加依赖，会产生环的依赖整体拒绝
+    pub fn add(&mut self, task_id: &TaskId, depends_on: &[TaskId]) -> BuckyResult<()> {
+        for dep in depends_on {
+            if dep == task_id || self.is_reachable(dep, task_id) {
+                let msg = format!("task dependency will cause cycle! task={}, depends_on={}", task_id, dep);
+                log::error!("{}", msg);
+                return Err(BuckyError::new(BuckyErrorCode::InvalidParam, msg));
+            }
+        }
+
+        let list = self.deps.entry(task_id.clone()).or_insert_with(HashSet::new);
+        for dep in depends_on {
+            list.insert(dep.clone());
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, task_id: &TaskId) {
+        self.deps.remove(task_id);
+    }
+
+    pub fn dependencies(&self, task_id: &TaskId) -> Vec<TaskId> {
+        match self.deps.get(task_id) {
+            Some(list) => list.iter().cloned().collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn dependents(&self, task_id: &TaskId) -> Vec<TaskId> {
+        self.deps.iter()
+            .filter(|(_, list)| list.contains(task_id))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    // from是否直接或者间接依赖to
+    fn is_reachable(&self, from: &TaskId, to: &TaskId) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from.clone()];
+        while let Some(id) = stack.pop() {
+            if &id == to {
+                return true;
+            }
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            if let Some(list) = self.deps.get(&id) {
+                stack.extend(list.iter().cloned());
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn task_id(v: u8) -> TaskId {
+        TaskId::from([v; 32].as_slice())
+    }
+
+    #[test]
+    fn test_dependency_graph() {
+        let mut graph = TaskDependencyGraph::new();
+        let (a, b, c) = (task_id(1), task_id(2), task_id(3));
+
+        // c -> b -> a
+        graph.add(&b, &[a]).unwrap();
+        graph.add(&c, &[b]).unwrap();
+        assert_eq!(graph.dependencies(&c), vec![b]);
+        assert_eq!(graph.dependents(&a), vec![b]);
+
+        assert!(graph.add(&a, &[a]).is_err());
+        assert!(graph.add(&a, &[c]).is_err());
+        assert!(graph.dependencies(&a).is_empty());
+
+        graph.remove(&b);
+        graph.add(&a, &[c]).unwrap();
+        assert_eq!(graph.dependencies(&a), vec![c]);
+    }
+}
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use cyfs_base::*;
-use crate::{Locker, Task, TaskCategory, TaskFactory, TaskId, TaskManagerStore, TaskStatus, TaskStore, TaskType};
+use futures::future::AbortHandle;
+use crate::{Locker, Task, TaskCategory, TaskDependencyGraph, TaskFactory, TaskId, TaskManagerStore, TaskStatus, TaskStore, TaskType};
 
 struct TaskInfo {
     pub task: Arc<Box<dyn Task>>,
@@ -54,6 +55,9 @@ pub struct TaskManager {
     task_manager_store: Arc<dyn TaskManagerStore>,
     task_store: Arc<dyn TaskStore>,
     task_map: async_std::sync::Mutex<HashMap<TaskId, TaskInfo>>,
+    dependencies: Mutex<TaskDependencyGraph>,
+    // 等待依赖完成后再启动的任务
+    pending_starts: Arc<Mutex<HashMap<TaskId, AbortHandle>>>,
 }
 
 impl TaskManager {
@@ -62,7 +66,9 @@ impl TaskManager {
             task_factory_map: Mutex::new(Default::default()),
             task_store,
             task_manager_store,
-            task_map: async_std::sync::Mutex::new(Default::default())
+            task_map: async_std::sync::Mutex::new(Default::default()),
+            dependencies: Mutex::new(TaskDependencyGraph::new()),
+            pending_starts: Arc::new(Mutex::new(HashMap::new())),
         });
 
         // task_manager.task_manager_store.clear_can_delete_task().await?;
@@ -250,8 +256,125 @@ impl TaskManager {
         }
     }
 
+    // 声明task_id需要在depends_on里的任务都完成之后才能启动，依赖关系不持久化，重启后需要重新声明
+    pub fn add_task_dependency(&self, task_id: &TaskId, depends_on: &[TaskId]) -> BuckyResult<()> {
+        self.dependencies.lock().unwrap().add(task_id, depends_on)
+    }
+
+    pub fn get_task_dependencies(&self, task_id: &TaskId) -> Vec<TaskId> {
+        self.dependencies.lock().unwrap().dependencies(task_id)
+    }
+
+    async fn get_or_load_task(&self, task_id: &TaskId) -> BuckyResult<Arc<Box<dyn Task>>> {
+        {
+            let task_map = self.task_map.lock().await;
+            if let Some(task_info) = task_map.get(task_id) {
+                return Ok(task_info.task.clone());
+            }
+        }
+
+        let (_task_category, task_type, task_status, task_param, task_data) = self.task_manager_store.get_task(task_id).await?;
+        let factory = self.get_task_factory(&task_type).ok_or_else(|| {
+            let msg = format!("task factory not found! task={}, type={}", task_id, task_type);
+            log::error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::NotSupport, msg)
+        })?;
+
+        let dec_list = self.task_manager_store.get_dec_list(task_id).await?;
+        let mut task = factory.restore(task_status, task_param.as_slice(), task_data.as_slice()).await?;
+        task.set_task_store(self.task_store.clone()).await;
+
+        let mut task_map = self.task_map.lock().await;
+        if !task_map.contains_key(task_id) {
+            task_map.insert(task_id.clone(), TaskInfo::new(Arc::new(task), dec_list));
+        }
+        Ok(task_map.get(task_id).unwrap().task.clone())
+    }
+
+    // 依赖都已完成时直接启动，有依赖失败时返回错误，否则在后台等待依赖完成后再启动
+    async fn start_task_after(&self, task_id: &TaskId, deps: Vec<TaskId>) -> BuckyResult<()> {
+        let task = self.get_or_load_task(task_id).await?;
+
+        let mut waiting = vec![];
+        for dep_id in deps {
+            let dep = self.get_or_load_task(&dep_id).await.map_err(|e| {
+                let msg = format!("load task dependency failed! task={}, dep={}, {}", task_id, dep_id, e);
+                log::error!("{}", msg);
+                BuckyError::new(e.code(), msg)
+            })?;
+
+            match dep.get_task_status().await {
+                TaskStatus::Finished => {}
+                TaskStatus::Failed => {
+                    let msg = format!("task dependency failed! task={}, dep={}", task_id, dep_id);
+                    log::error!("{}", msg);
+                    return Err(BuckyError::new(BuckyErrorCode::ErrorState, msg));
+                }
+                _ => waiting.push((dep_id, dep)),
+            }
+        }
+
+        if waiting.is_empty() {
+            return task.start_task().await;
+        }
+
+        log::info!("task will start after dependencies finished: task={}, waiting={}", task_id, waiting.len());
+
+        let id = task_id.clone();
+        let (ft, handle) = futures::future::abortable(async move {
+            for (dep_id, dep) in waiting {
+                loop {
+                    match dep.get_task_status().await {
+                        TaskStatus::Finished => break,
+                        TaskStatus::Failed => {
+                            log::error!("task dependency failed, cancel start! task={}, dep={}", id, dep_id);
+                            return;
+                        }
+                        _ => async_std::task::sleep(Duration::from_secs(1)).await,
+                    }
+                }
+            }
+
+            if let Err(e) = task.start_task().await {
+                log::error!("start task after dependencies finished failed! task={}, {}", id, e);
+            }
+        });
+
+        if let Some(prev) = self.pending_starts.lock().unwrap().insert(task_id.clone(), handle) {
+            prev.abort();
+        }
+
+        let id = task_id.clone();
+        let pending_starts = self.pending_starts.clone();
+        async_std::task::spawn(async move {
+            if ft.await.is_ok() {
+                pending_starts.lock().unwrap().remove(&id);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn cancel_pending_start(&self, task_id: &TaskId) {
+        if let Some(handle) = self.pending_starts.lock().unwrap().remove(task_id) {
+            log::info!("cancel pending start task: task={}", task_id);
+            handle.abort();
+        }
+    }
+
+    fn remove_task_dependency(&self, task_id: &TaskId) {
+        self.cancel_pending_start(task_id);
+        self.dependencies.lock().unwrap().remove(task_id);
+    }
+
     pub async fn start_task(&self, task_id: &TaskId) -> BuckyResult<()> {
         log::info!("start_task {}", task_id.to_string());
+        let deps = self.get_task_dependencies(task_id);
+        if !deps.is_empty() {
+            let _locker = Locker::get_locker(format!("task_manager_{}", task_id.to_string())).await;
+            return self.start_task_after(task_id, deps).await;
+        }
+
         let _locker = Locker::get_locker(format!("task_manager_{}", task_id.to_string())).await;
         let task = {
             let task_map = self.task_map.lock().await;
@@ -349,6 +472,7 @@ impl TaskManager {
     pub async fn pause_task(&self, task_id: &TaskId) -> BuckyResult<()> {
         log::info!("will pause_task {}", task_id);
         let _locker = Locker::get_locker(format!("task_manager_{}", task_id.to_string())).await;
+        self.cancel_pending_start(task_id);
         let task = {
             let task_map = self.task_map.lock().await;
             match task_map.get(task_id) {
@@ -361,9 +485,31 @@ impl TaskManager {
         task.pause_task().await
     }
 
+    // 对所有已加载的任务做checkpoint，单个任务失败不影响其余的，返回失败的任务数
+    pub async fn checkpoint_all(&self) -> usize {
+        let tasks: Vec<Arc<Box<dyn Task>>> = {
+            let task_map = self.task_map.lock().await;
+            task_map.values().map(|info| info.task.clone()).collect()
+        };
+
+        let mut failed = 0;
+        for task in tasks {
+            let task_id = task.get_task_id();
+            let _locker = Locker::get_locker(format!("task_manager_{}", task_id)).await;
+            if let Err(e) = task.checkpoint().await {
+                log::error!("checkpoint task failed! task={}, {}", task_id, e);
+                failed += 1;
+            }
+        }
+
+        log::info!("checkpoint all tasks complete! failed={}", failed);
+        failed
+    }
+
     pub async fn stop_task(&self, task_id: &TaskId) -> BuckyResult<()> {
         log::info!("will stop_task {}", task_id);
         let _locker = Locker::get_locker(format!("task_manager_{}", task_id)).await;
+        self.cancel_pending_start(task_id);
         let task = {
             let mut task_map = self.task_map.lock().await;
             task_map.remove(task_id)
@@ -394,6 +540,7 @@ impl TaskManager {
                 if dec_list.len() == 0 {
                     self.task_manager_store.delete_task(task_id).await?;
                     task_map.remove(task_id);
+                    self.remove_task_dependency(task_id);
                 }
             }
             Some(info) => {
@@ -407,6 +554,7 @@ impl TaskManager {
                         self.task_manager_store.delete_task(task_id).await?;
                     }
                     task_map.remove(task_id);
+                    self.remove_task_dependency(task_id);
                 }
             }
         }
@@ -417,6 +565,7 @@ impl TaskManager {
     pub async fn remove_task_by_task_id(&self, task_id: &TaskId) -> BuckyResult<()> {
         log::info!("remove_task task_id {}", task_id.to_string());
         let _locker = Locker::get_locker(format!("task_manager_{}", task_id.to_string())).await;
+        self.remove_task_dependency(task_id);
 
         let mut task_map = self.task_map.lock().await;
         match task_map.get_mut(task_id) {
@@ -434,6 +583,15 @@ impl TaskManager {
         Ok(())
     }
 
+    // 同步获取当前已加载的任务列表，锁被占用时返回None，用于panic时的状态快照
+    pub fn try_list_tasks(&self) -> Option<Vec<(TaskId, TaskType, TaskCategory)>> {
+        let task_map = self.task_map.try_lock()?;
+        let list = task_map.iter().map(|(task_id, info)| {
+            (task_id.clone(), info.task.get_task_type(), info.task.get_task_category())
+        }).collect();
+        Some(list)
+    }
+
     pub async fn get_tasks_by_task_id(&self, task_id_list: &[TaskId]) -> BuckyResult<Vec<(TaskId, TaskType, TaskStatus, Vec<u8>, Vec<u8>)>> {
         self.task_manager_store.get_tasks_by_task_id(task_id_list).await
     }
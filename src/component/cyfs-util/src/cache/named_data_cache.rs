@@ -105,6 +105,8 @@ pub enum ChunkObjectRelation {
     Unknown = 0,
     FileBody = 1,
     DirMeta = 2,
+    // 被对象固定，不应该被回收
+    Pinned = 3,
 }
 
 impl Into<u8> for ChunkObjectRelation {
@@ -224,6 +226,9 @@ pub struct GetChunkRefObjectsResponse {
 #[derive(Clone, Debug)]
 pub struct SelectChunkFilter {
     pub state: Option<ChunkState>,
+
+    // 只选择最近访问时间早于指定时间的chunk，结果按访问时间从早到晚排序
+    pub last_access_before: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,666 @@
+use crate::StorageCipher;
+use cyfs_base::*;
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// 每条记录的格式: id(32) + flags(1) + len(4) + data
+const COLD_PACK_ENTRY_HEADER_LEN: usize = OBJECT_ID_LEN + 1 + 4;
+const COLD_PACK_FLAG_ENCRYPTED: u8 = 0x01;
+
+// 已经移除的记录: id(32) + pack(4)
+const COLD_PACK_REMOVED_RECORD_LEN: usize = OBJECT_ID_LEN + 4;
+const COLD_PACK_REMOVED_FILE: &str = "removed";
+
+pub const COLD_PACK_DEFAULT_HYDRATE_BUDGET: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug)]
+struct ColdPackLocation {
+    pack: u32,
+    offset: u64,
+    len: u32,
+    encrypted: bool,
+}
+
+struct ColdPackState {
+    // 冷存储里的stub，id -> 所在的pack和位置
+    index: HashMap<ObjectId, ColdPackLocation>,
+
+    // pack -> 仍然有效的记录数，为0时删除整个pack文件
+    live: HashMap<u32, usize>,
+
+    next_pack: u32,
+}
+
+#[derive(Default)]
+struct ColdPackCounters {
+    archived: AtomicU64,
+    hydrated: AtomicU64,
+    hydrate_timeout: AtomicU64,
+    hydrate_failed: AtomicU64,
+
+    // 微秒
+    hydrate_total_time: AtomicU64,
+    hydrate_max_time: AtomicU64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColdPackStat {
+    pub stub_count: u64,
+    pub pack_count: u64,
+
+    pub archived: u64,
+    pub hydrated: u64,
+    pub hydrate_timeout: u64,
+    pub hydrate_failed: u64,
+
+    // 水合耗时，微秒
+    pub hydrate_avg_time: u64,
+    pub hydrate_max_time: u64,
+}
+
+struct ColdPackStoreInner {
+    dir: PathBuf,
+    readonly: bool,
+
+    // 微秒
+    hydrate_budget: AtomicU64,
+
+    state: Mutex<ColdPackState>,
+    counters: ColdPackCounters,
+}
+
+// 冷存储层，很少访问的对象/chunk批量归档到pack文件里，原位置只保留内存里的stub索引
+// 访问stub时透明的水合回热存储，超出延迟预算时先返回超时，水合在后台继续完成
+// noc和chunk manager共用
+#[derive(Clone)]
+pub struct ColdPackStore(Arc<ColdPackStoreInner>);
+
+impl ColdPackStore {
+    pub fn open(dir: PathBuf, readonly: bool) -> BuckyResult<Self> {
+        if !dir.is_dir() && !readonly {
+            std::fs::create_dir_all(&dir).map_err(|e| {
+                let msg = format!("create cold pack dir error! dir={}, {}", dir.display(), e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+        }
+
+        let state = if dir.is_dir() {
+            Self::load_state(&dir, readonly).map_err(|e| {
+                let msg = format!("load cold pack index error! dir={}, {}", dir.display(), e);
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?
+        } else {
+            ColdPackState {
+                index: HashMap::new(),
+                live: HashMap::new(),
+                next_pack: 0,
+            }
+        };
+
+        info!(
+            "open cold pack store: dir={}, stubs={}, packs={}",
+            dir.display(),
+            state.index.len(),
+            state.live.len()
+        );
+
+        let inner = ColdPackStoreInner {
+            dir,
+            readonly,
+            hydrate_budget: AtomicU64::new(COLD_PACK_DEFAULT_HYDRATE_BUDGET.as_micros() as u64),
+            state: Mutex::new(state),
+            counters: ColdPackCounters::default(),
+        };
+
+        Ok(Self(Arc::new(inner)))
+    }
+
+    // 存储密钥在owner解锁之后才会设置，所以每次使用时读取
+    fn cipher(&self) -> Option<&'static StorageCipher> {
+        crate::get_storage_cipher()
+    }
+
+    fn pack_path(dir: &Path, pack: u32) -> PathBuf {
+        dir.join(format!("{:08}.pack", pack))
+    }
+
+    fn parse_pack_name(name: &str) -> Option<u32> {
+        name.strip_suffix(".pack")
+            .and_then(|v| v.parse::<u32>().ok())
+    }
+
+    fn load_removed(dir: &Path) -> std::io::Result<HashSet<(ObjectId, u32)>> {
+        let path = dir.join(COLD_PACK_REMOVED_FILE);
+        let mut removed = HashSet::new();
+        if !path.exists() {
+            return Ok(removed);
+        }
+
+        let data = std::fs::read(&path)?;
+        for record in data.chunks_exact(COLD_PACK_REMOVED_RECORD_LEN) {
+            let id = ObjectId::clone_from_slice(&record[..OBJECT_ID_LEN]).unwrap();
+            let pack = u32::from_le_bytes(record[OBJECT_ID_LEN..].try_into().unwrap());
+            removed.insert((id, pack));
+        }
+
+        Ok(removed)
+    }
+
+    fn load_state(dir: &Path, readonly: bool) -> std::io::Result<ColdPackState> {
+        let removed = Self::load_removed(dir)?;
+
+        let mut packs = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if let Some(pack) = entry.file_name().to_str().and_then(Self::parse_pack_name) {
+                packs.push(pack);
+            }
+        }
+        packs.sort();
+
+        // 同一个id在后面的pack里再次归档时以后面的为准
+        let mut index: HashMap<ObjectId, ColdPackLocation> = HashMap::new();
+        let mut live: HashMap<u32, usize> = HashMap::new();
+        for pack in &packs {
+            live.insert(*pack, 0);
+            for (id, loc) in Self::scan_pack(&Self::pack_path(dir, *pack), *pack)? {
+                if removed.contains(&(id, *pack)) {
+                    continue;
+                }
+
+                *live.get_mut(pack).unwrap() += 1;
+                if let Some(prev) = index.insert(id, loc) {
+                    *live.get_mut(&prev.pack).unwrap() -= 1;
+                }
+            }
+        }
+
+        let next_pack = packs.last().map(|v| v + 1).unwrap_or(0);
+
+        if !readonly {
+            live.retain(|pack, count| {
+                if *count > 0 {
+                    return true;
+                }
+
+                let path = Self::pack_path(dir, *pack);
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!(
+                        "remove empty cold pack error! path={}, {}",
+                        path.display(),
+                        e
+                    );
+                }
+                false
+            });
+
+            // 只保留仍然存在的pack的移除记录
+            let mut data = vec![];
+            for (id, pack) in removed.iter().filter(|(_, pack)| live.contains_key(pack)) {
+                data.extend_from_slice(id.as_slice());
+                data.extend_from_slice(&pack.to_le_bytes());
+            }
+            let tmp = dir.join(format!("{}.tmp", COLD_PACK_REMOVED_FILE));
+            std::fs::write(&tmp, &data)?;
+            std::fs::rename(&tmp, dir.join(COLD_PACK_REMOVED_FILE))?;
+        }
+
+        Ok(ColdPackState {
+            index,
+            live,
+            next_pack,
+        })
+    }
+
+    fn scan_pack(path: &Path, pack: u32) -> std::io::Result<Vec<(ObjectId, ColdPackLocation)>> {
+        let mut file = std::fs::File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut list = vec![];
+        let mut offset = 0;
+        let mut header = [0u8; COLD_PACK_ENTRY_HEADER_LEN];
+        while offset + COLD_PACK_ENTRY_HEADER_LEN as u64 <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut header)?;
+
+            let id = ObjectId::clone_from_slice(&header[..OBJECT_ID_LEN]).unwrap();
+            let flags = header[OBJECT_ID_LEN];
+            let len = u32::from_le_bytes(header[OBJECT_ID_LEN + 1..].try_into().unwrap());
+
+            let data_offset = offset + COLD_PACK_ENTRY_HEADER_LEN as u64;
+            if data_offset + len as u64 > file_len {
+                warn!(
+                    "cold pack entry out of range, the pack may be broken! path={}, offset={}",
+                    path.display(),
+                    offset
+                );
+                break;
+            }
+
+            let loc = ColdPackLocation {
+                pack,
+                offset: data_offset,
+                len,
+                encrypted: flags & COLD_PACK_FLAG_ENCRYPTED != 0,
+            };
+            list.push((id, loc));
+            offset = data_offset + len as u64;
+        }
+
+        Ok(list)
+    }
+
+    pub fn readonly(&self) -> bool {
+        self.0.readonly
+    }
+
+    pub fn hydrate_budget(&self) -> Duration {
+        Duration::from_micros(self.0.hydrate_budget.load(Ordering::SeqCst))
+    }
+
+    pub fn set_hydrate_budget(&self, budget: Duration) {
+        self.0
+            .hydrate_budget
+            .store(budget.as_micros() as u64, Ordering::SeqCst);
+    }
+
+    pub fn contains(&self, id: &ObjectId) -> bool {
+        self.0.state.lock().unwrap().index.contains_key(id)
+    }
+
+    pub fn list(&self) -> Vec<ObjectId> {
+        self.0.state.lock().unwrap().index.keys().cloned().collect()
+    }
+
+    pub fn stat(&self) -> ColdPackStat {
+        let (stub_count, pack_count) = {
+            let state = self.0.state.lock().unwrap();
+            (state.index.len() as u64, state.live.len() as u64)
+        };
+
+        let counters = &self.0.counters;
+        let hydrated = counters.hydrated.load(Ordering::SeqCst);
+        let total = counters.hydrate_total_time.load(Ordering::SeqCst);
+
+        ColdPackStat {
+            stub_count,
+            pack_count,
+            archived: counters.archived.load(Ordering::SeqCst),
+            hydrated,
+            hydrate_timeout: counters.hydrate_timeout.load(Ordering::SeqCst),
+            hydrate_failed: counters.hydrate_failed.load(Ordering::SeqCst),
+            hydrate_avg_time: if hydrated > 0 { total / hydrated } else { 0 },
+            hydrate_max_time: counters.hydrate_max_time.load(Ordering::SeqCst),
+        }
+    }
+
+    fn check_writable(&self) -> BuckyResult<()> {
+        if self.0.readonly {
+            let msg = format!("cold pack store is readonly! dir={}", self.0.dir.display());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg));
+        }
+
+        Ok(())
+    }
+
+    fn write_pack(path: &Path, entries: &[(ObjectId, u8, Vec<u8>)]) -> std::io::Result<Vec<u64>> {
+        let tmp = path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+
+        let mut offsets = Vec::with_capacity(entries.len());
+        let mut offset = 0;
+        for (id, flags, data) in entries {
+            file.write_all(id.as_slice())?;
+            file.write_all(&[*flags])?;
+            file.write_all(&(data.len() as u32).to_le_bytes())?;
+            file.write_all(data)?;
+
+            offset += COLD_PACK_ENTRY_HEADER_LEN as u64;
+            offsets.push(offset);
+            offset += data.len() as u64;
+        }
+        file.sync_all()?;
+        drop(file);
+
+        std::fs::rename(&tmp, path)?;
+        Ok(offsets)
+    }
+
+    // 把一批数据写入一个新的pack，返回后才允许调用者删除热存储里的数据
+    pub async fn archive(&self, items: Vec<(ObjectId, Vec<u8>)>) -> BuckyResult<usize> {
+        self.check_writable()?;
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        // 有存储密钥时总是加密保存
+        let mut entries = Vec::with_capacity(items.len());
+        for (id, data) in items {
+            match self.cipher() {
+                Some(cipher) => {
+                    let data = cipher.encrypt(id.as_slice(), &data)?;
+                    entries.push((id, COLD_PACK_FLAG_ENCRYPTED, data));
+                }
+                None => entries.push((id, 0, data)),
+            }
+        }
+
+        let pack = {
+            let mut state = self.0.state.lock().unwrap();
+            let pack = state.next_pack;
+            state.next_pack += 1;
+            pack
+        };
+
+        let path = Self::pack_path(&self.0.dir, pack);
+        let (entries, offsets) = async_std::task::spawn_blocking(move || {
+            let ret = Self::write_pack(&path, &entries);
+            (entries, ret)
+        })
+        .await;
+
+        let offsets = offsets.map_err(|e| {
+            let msg = format!(
+                "write cold pack error! dir={}, pack={}, {}",
+                self.0.dir.display(),
+                pack,
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let count = entries.len();
+        let mut empty = vec![];
+        {
+            let mut state = self.0.state.lock().unwrap();
+            state.live.insert(pack, count);
+            for ((id, flags, data), offset) in entries.into_iter().zip(offsets) {
+                let loc = ColdPackLocation {
+                    pack,
+                    offset,
+                    len: data.len() as u32,
+                    encrypted: flags & COLD_PACK_FLAG_ENCRYPTED != 0,
+                };
+
+                if let Some(prev) = state.index.insert(id, loc) {
+                    let live = state.live.get_mut(&prev.pack).unwrap();
+                    *live -= 1;
+                    if *live == 0 {
+                        state.live.remove(&prev.pack);
+                        empty.push(prev.pack);
+                    }
+                }
+            }
+        }
+
+        self.remove_packs(empty).await;
+        self.0
+            .counters
+            .archived
+            .fetch_add(count as u64, Ordering::SeqCst);
+
+        info!(
+            "archive to cold pack success! dir={}, pack={}, count={}",
+            self.0.dir.display(),
+            pack,
+            count
+        );
+
+        Ok(count)
+    }
+
+    async fn remove_packs(&self, packs: Vec<u32>) {
+        for pack in packs {
+            let path = Self::pack_path(&self.0.dir, pack);
+            match async_std::fs::remove_file(&path).await {
+                Ok(()) => info!("remove empty cold pack: {}", path.display()),
+                Err(e) => warn!(
+                    "remove empty cold pack error! path={}, {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    // 读取冷存储里的数据，返回(data, 是否加密保存)
+    pub async fn load(&self, id: &ObjectId) -> BuckyResult<Option<(Vec<u8>, bool)>> {
+        let loc = match self.0.state.lock().unwrap().index.get(id) {
+            Some(loc) => loc.to_owned(),
+            None => return Ok(None),
+        };
+
+        let path = Self::pack_path(&self.0.dir, loc.pack);
+        let ret = async_std::task::spawn_blocking(move || -> std::io::Result<Vec<u8>> {
+            let mut file = std::fs::File::open(&path)?;
+            file.seek(SeekFrom::Start(loc.offset))?;
+
+            let mut data = vec![0u8; loc.len as usize];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        })
+        .await;
+
+        let data = ret.map_err(|e| {
+            let msg = format!(
+                "read cold pack error! id={}, pack={}, offset={}, {}",
+                id, loc.pack, loc.offset, e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        if !loc.encrypted {
+            return Ok(Some((data, false)));
+        }
+
+        let cipher = self.cipher().ok_or_else(|| {
+            let msg = format!(
+                "cold pack entry is encrypted but storage key not unlocked! id={}",
+                id
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::PermissionDenied, msg)
+        })?;
+
+        let data = cipher.decrypt(id.as_slice(), &data)?;
+        Ok(Some((data, true)))
+    }
+
+    // 移除stub，pack里的数据在整个pack都失效后才会真正删除
+    pub async fn remove(&self, id: &ObjectId) -> BuckyResult<bool> {
+        self.check_writable()?;
+
+        let loc = match self.0.state.lock().unwrap().index.remove(id) {
+            Some(loc) => loc,
+            None => return Ok(false),
+        };
+
+        let mut record = Vec::with_capacity(COLD_PACK_REMOVED_RECORD_LEN);
+        record.extend_from_slice(id.as_slice());
+        record.extend_from_slice(&loc.pack.to_le_bytes());
+
+        let path = self.0.dir.join(COLD_PACK_REMOVED_FILE);
+        let ret = async_std::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?;
+            file.write_all(&record)?;
+            file.sync_data()
+        })
+        .await;
+
+        if let Err(e) = ret {
+            let msg = format!("append cold pack removed record error! id={}, {}", id, e);
+            error!("{}", msg);
+
+            self.0
+                .state
+                .lock()
+                .unwrap()
+                .index
+                .insert(id.to_owned(), loc);
+            return Err(BuckyError::new(BuckyErrorCode::IoError, msg));
+        }
+
+        let empty = {
+            let mut state = self.0.state.lock().unwrap();
+            let live = state.live.get_mut(&loc.pack).unwrap();
+            *live -= 1;
+            if *live == 0 {
+                state.live.remove(&loc.pack);
+                vec![loc.pack]
+            } else {
+                vec![]
+            }
+        };
+        self.remove_packs(empty).await;
+
+        Ok(true)
+    }
+
+    // 把stub对应的数据水合回热存储，restore负责写回；只读模式下只读取不写回
+    // 超出延迟预算时返回Timeout，水合任务在后台继续，下次访问时直接命中热存储
+    pub async fn hydrate<F, Fut>(&self, id: &ObjectId, restore: F) -> BuckyResult<Option<Vec<u8>>>
+    where
+        F: FnOnce(Vec<u8>, bool) -> Fut + Send + 'static,
+        Fut: Future<Output = BuckyResult<()>> + Send + 'static,
+    {
+        if !self.contains(id) {
+            return Ok(None);
+        }
+
+        let budget = self.hydrate_budget();
+        let this = self.clone();
+        let task_id = id.to_owned();
+        let task =
+            async_std::task::spawn(async move { this.hydrate_inner(&task_id, restore).await });
+
+        match async_std::future::timeout(budget, task).await {
+            Ok(ret) => ret,
+            Err(_) => {
+                self.0
+                    .counters
+                    .hydrate_timeout
+                    .fetch_add(1, Ordering::SeqCst);
+
+                let msg = format!(
+                    "hydrate from cold pack out of latency budget! id={}, budget={:?}",
+                    id, budget
+                );
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::Timeout, msg))
+            }
+        }
+    }
+
+    async fn hydrate_inner<F, Fut>(&self, id: &ObjectId, restore: F) -> BuckyResult<Option<Vec<u8>>>
+    where
+        F: FnOnce(Vec<u8>, bool) -> Fut,
+        Fut: Future<Output = BuckyResult<()>>,
+    {
+        let tick = Instant::now();
+        let counters = &self.0.counters;
+
+        let (data, encrypted) = match self.load(id).await {
+            Ok(Some(ret)) => ret,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                counters.hydrate_failed.fetch_add(1, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+
+        if !self.0.readonly {
+            if let Err(e) = restore(data.clone(), encrypted).await {
+                error!("restore hydrated data error! id={}, {}", id, e);
+                counters.hydrate_failed.fetch_add(1, Ordering::SeqCst);
+                return Err(e);
+            }
+
+            // 数据已经回到热存储，移除失败只会导致下次重复水合
+            if let Err(e) = self.remove(id).await {
+                warn!("remove cold stub after hydrate error! id={}, {}", id, e);
+            }
+        }
+
+        let used = tick.elapsed().as_micros() as u64;
+        counters.hydrated.fetch_add(1, Ordering::SeqCst);
+        counters
+            .hydrate_total_time
+            .fetch_add(used, Ordering::SeqCst);
+        counters.hydrate_max_time.fetch_max(used, Ordering::SeqCst);
+
+        info!(
+            "hydrate from cold pack success! id={}, len={}, used={}us",
+            id,
+            data.len(),
+            used
+        );
+
+        Ok(Some(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_id(v: u8) -> ObjectId {
+        ObjectId::clone_from_slice(&[v; OBJECT_ID_LEN]).unwrap()
+    }
+
+    #[test]
+    fn test_cold_pack() {
+        let dir = crate::get_temp_path().join("test_cold_pack");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        async_std::task::block_on(async move {
+            let store = ColdPackStore::open(dir.clone(), false).unwrap();
+            let (a, b) = (test_id(1), test_id(2));
+            let count = store
+                .archive(vec![
+                    (a.clone(), b"aaa".to_vec()),
+                    (b.clone(), b"bbbb".to_vec()),
+                ])
+                .await
+                .unwrap();
+            assert_eq!(count, 2);
+            assert!(store.contains(&a));
+            assert_eq!(store.load(&b).await.unwrap().unwrap().0, b"bbbb".to_vec());
+
+            let ret = store
+                .hydrate(&a, |data, _| async move {
+                    assert_eq!(data, b"aaa".to_vec());
+                    Ok(())
+                })
+                .await
+                .unwrap();
+            assert_eq!(ret, Some(b"aaa".to_vec()));
+            assert!(!store.contains(&a));
+            assert_eq!(store.stat().hydrated, 1);
+
+            // 重新打开后stub保持一致
+            let store = ColdPackStore::open(dir.clone(), false).unwrap();
+            assert!(!store.contains(&a));
+            assert!(store.contains(&b));
+
+            // pack里的记录全部移除后删除pack文件
+            assert!(store.remove(&b).await.unwrap());
+            assert_eq!(store.stat().pack_count, 0);
+            assert!(!ColdPackStore::pack_path(&dir, 0).exists());
+        });
+    }
+}
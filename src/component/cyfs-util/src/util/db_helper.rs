@@ -13,6 +13,9 @@ pub struct SqliteConnectionHolder {
     read_conn: ThreadLocal<RefCell<Connection>>,
     write_conn: ThreadLocal<RefCell<Connection>>,
     conn_rw_lock: RwLock<u32>,
+
+    // 每个写连接创建后执行的初始化语句，比如连接级别的pragma
+    write_init_sql: Option<String>,
 }
 
 impl SqliteConnectionHolder {
@@ -22,9 +25,16 @@ impl SqliteConnectionHolder {
             read_conn: ThreadLocal::new(),
             write_conn: ThreadLocal::new(),
             conn_rw_lock: RwLock::new(0),
+            write_init_sql: None,
         }
     }
 
+    pub fn new_with_init_sql(data_file: PathBuf, write_init_sql: String) -> Self {
+        let mut ret = Self::new(data_file);
+        ret.write_init_sql = Some(write_init_sql);
+        ret
+    }
+
     pub fn get_write_conn(
         &self,
     ) -> BuckyResult<(std::cell::RefMut<Connection>, RwLockWriteGuard<u32>)> {
@@ -76,6 +86,22 @@ impl SqliteConnectionHolder {
             error!("init sqlite busy_timeout error! {}", e);
         }
 
+        if !read_only {
+            if let Some(sql) = &self.write_init_sql {
+                conn.execute_batch(sql).map_err(|e| {
+                    let msg = format!(
+                        "init db conn failed, db={}, sql={}, {}",
+                        self.data_file.display(),
+                        sql,
+                        e
+                    );
+                    error!("{}", msg);
+
+                    BuckyError::new(BuckyErrorCode::SqliteError, msg)
+                })?;
+            }
+        }
+
         Ok(conn)
     }
 }
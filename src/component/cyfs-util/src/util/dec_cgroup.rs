@@ -0,0 +1,233 @@
+use cyfs_base::*;
+
+use std::path::PathBuf;
+
+// cgroup v2的挂载点，所有dec的cgroup都放在同一个父节点下
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const DEC_CGROUP_PARENT: &str = "cyfs-dec";
+
+// cpu.max的统计周期，单位微秒
+const CPU_MAX_PERIOD_USEC: u64 = 100000;
+
+#[derive(Clone, Debug, Default)]
+pub struct DecCgroupUsage {
+    // 累计的cpu时间，单位微秒
+    pub cpu_usage_usec: u64,
+
+    // 当前的内存占用，单位字节
+    pub memory_current: u64,
+
+    // 累计被oom killer杀掉的进程数
+    pub oom_kills: u64,
+}
+
+// 每个dec一个cgroup: /sys/fs/cgroup/cyfs-dec/{dec_id}
+// app-manager启动dec服务后把进程加入对应的cgroup，协议栈根据资源策略更新限制并读取占用
+// 只支持linux上的cgroup v2，其它平台上所有操作都是空操作
+pub struct DecCgroup {
+    dec_id: ObjectId,
+    path: PathBuf,
+}
+
+impl DecCgroup {
+    pub fn new(dec_id: &ObjectId) -> Self {
+        let path = PathBuf::from(CGROUP_ROOT)
+            .join(DEC_CGROUP_PARENT)
+            .join(dec_id.to_string());
+
+        Self {
+            dec_id: dec_id.to_owned(),
+            path,
+        }
+    }
+
+    pub fn is_supported() -> bool {
+        cfg!(target_os = "linux")
+            && PathBuf::from(CGROUP_ROOT)
+                .join("cgroup.controllers")
+                .is_file()
+    }
+
+    // 当前已经创建了cgroup的所有dec
+    pub fn list() -> Vec<ObjectId> {
+        if !Self::is_supported() {
+            return vec![];
+        }
+
+        let parent = PathBuf::from(CGROUP_ROOT).join(DEC_CGROUP_PARENT);
+        let dir = match std::fs::read_dir(&parent) {
+            Ok(dir) => dir,
+            Err(_) => return vec![],
+        };
+
+        dir.filter_map(|entry| {
+            let entry = entry.ok()?;
+            if !entry.path().is_dir() {
+                return None;
+            }
+
+            entry.file_name().to_str()?.parse().ok()
+        })
+        .collect()
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.is_dir()
+    }
+
+    // cpu_limit: cpu核数，比如0.5表示最多使用半个核; memory_limit: 字节
+    // 为None表示不限制
+    pub fn apply_limits(
+        &self,
+        cpu_limit: Option<f32>,
+        memory_limit: Option<u64>,
+    ) -> BuckyResult<()> {
+        if !Self::is_supported() {
+            return Ok(());
+        }
+
+        self.create()?;
+
+        self.write("cpu.max", &Self::format_cpu_max(cpu_limit))?;
+
+        let memory_max = match memory_limit {
+            Some(limit) => limit.to_string(),
+            None => "max".to_owned(),
+        };
+        self.write("memory.max", &memory_max)?;
+
+        info!(
+            "apply dec cgroup limits: dec={}, cpu={:?}, memory={:?}",
+            self.dec_id, cpu_limit, memory_limit
+        );
+
+        Ok(())
+    }
+
+    // 把进程加入cgroup，之后由它创建的子进程都会在同一个cgroup内
+    pub fn attach(&self, pid: u32) -> BuckyResult<()> {
+        if !Self::is_supported() {
+            return Ok(());
+        }
+
+        self.create()?;
+        self.write("cgroup.procs", &pid.to_string())?;
+
+        info!(
+            "attach process to dec cgroup: dec={}, pid={}",
+            self.dec_id, pid
+        );
+        Ok(())
+    }
+
+    pub fn usage(&self) -> Option<DecCgroupUsage> {
+        if !Self::is_supported() || !self.exists() {
+            return None;
+        }
+
+        let mut usage = DecCgroupUsage::default();
+        if let Some(v) = self.read("cpu.stat") {
+            usage.cpu_usage_usec = Self::parse_keyed(&v, "usage_usec").unwrap_or(0);
+        }
+        if let Some(v) = self.read("memory.current") {
+            usage.memory_current = v.trim().parse().unwrap_or(0);
+        }
+        if let Some(v) = self.read("memory.events") {
+            usage.oom_kills = Self::parse_keyed(&v, "oom_kill").unwrap_or(0);
+        }
+
+        Some(usage)
+    }
+
+    // cgroup内还有进程时删除会失败，只在dec卸载后调用
+    pub fn remove(&self) -> BuckyResult<()> {
+        if !self.exists() {
+            return Ok(());
+        }
+
+        std::fs::remove_dir(&self.path).map_err(|e| {
+            let msg = format!(
+                "remove dec cgroup failed! dec={}, path={}, {}",
+                self.dec_id,
+                self.path.display(),
+                e
+            );
+            warn!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })
+    }
+
+    fn create(&self) -> BuckyResult<()> {
+        if self.exists() {
+            return Ok(());
+        }
+
+        // 父节点需要打开cpu和memory控制器，子节点才会有对应的接口文件
+        let parent = PathBuf::from(CGROUP_ROOT).join(DEC_CGROUP_PARENT);
+        if !parent.is_dir() {
+            Self::enable_controllers(&PathBuf::from(CGROUP_ROOT))?;
+            std::fs::create_dir(&parent).map_err(|e| Self::io_error(&parent, e))?;
+        }
+        Self::enable_controllers(&parent)?;
+
+        std::fs::create_dir(&self.path).map_err(|e| Self::io_error(&self.path, e))
+    }
+
+    fn enable_controllers(dir: &PathBuf) -> BuckyResult<()> {
+        let file = dir.join("cgroup.subtree_control");
+        std::fs::write(&file, "+cpu +memory").map_err(|e| Self::io_error(&file, e))
+    }
+
+    fn write(&self, name: &str, value: &str) -> BuckyResult<()> {
+        let file = self.path.join(name);
+        std::fs::write(&file, value).map_err(|e| Self::io_error(&file, e))
+    }
+
+    fn read(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(self.path.join(name)).ok()
+    }
+
+    fn io_error(path: &PathBuf, e: std::io::Error) -> BuckyError {
+        let msg = format!("write dec cgroup failed! path={}, {}", path.display(), e);
+        error!("{}", msg);
+        BuckyError::new(BuckyErrorCode::IoError, msg)
+    }
+
+    fn format_cpu_max(cpu_limit: Option<f32>) -> String {
+        match cpu_limit {
+            Some(cores) if cores > 0.0 => {
+                // 内核要求quota不小于1ms
+                let quota = std::cmp::max((cores as f64 * CPU_MAX_PERIOD_USEC as f64) as u64, 1000);
+                format!("{} {}", quota, CPU_MAX_PERIOD_USEC)
+            }
+            _ => format!("max {}", CPU_MAX_PERIOD_USEC),
+        }
+    }
+
+    // cpu.stat和memory.events的格式为每行一个"key value"
+    fn parse_keyed(content: &str, key: &str) -> Option<u64> {
+        content.lines().find_map(|line| {
+            let mut it = line.split_whitespace();
+            match (it.next(), it.next()) {
+                (Some(k), Some(v)) if k == key => v.parse().ok(),
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(DecCgroup::format_cpu_max(None), "max 100000");
+        assert_eq!(DecCgroup::format_cpu_max(Some(0.5)), "50000 100000");
+        assert_eq!(DecCgroup::format_cpu_max(Some(0.001)), "1000 100000");
+
+        let stat = "usage_usec 12345\nuser_usec 10000\nsystem_usec 2345\n";
+        assert_eq!(DecCgroup::parse_keyed(stat, "usage_usec"), Some(12345));
+        assert_eq!(DecCgroup::parse_keyed(stat, "oom_kill"), None);
+    }
+}
@@ -0,0 +1,333 @@
+use cyfs_base::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// 本地文件系统的元数据，发布时从本地文件读取，下载到本地路径后再恢复
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    // 最后修改时间，bucky time
+    pub mtime: Option<u64>,
+
+    // unix下的权限位，比如0o644，其它平台只保存readonly
+    pub mode: Option<u32>,
+    pub readonly: bool,
+
+    // 扩展属性，只保存user命名空间，值使用hex编码
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub xattrs: BTreeMap<String, String>,
+}
+
+// 以{"cyfs_file_metadata": {...}}的格式保存在File对象body的user_data里，不影响FileId
+#[derive(Serialize, Deserialize)]
+struct FileMetadataUserData {
+    cyfs_file_metadata: FileMetadata,
+}
+
+impl FileMetadata {
+    pub fn load(path: &Path) -> BuckyResult<Self> {
+        let metadata = std::fs::metadata(path).map_err(|e| {
+            let msg = format!(
+                "read local file metadata failed! file={}, {}",
+                path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        let mtime = metadata
+            .modified()
+            .ok()
+            .map(|t| system_time_to_bucky_time(&t));
+
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(metadata.permissions().mode() & 0o7777)
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+
+        Ok(Self {
+            mtime,
+            mode,
+            readonly: metadata.permissions().readonly(),
+            xattrs: Self::load_xattrs(path),
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn load_xattrs(path: &Path) -> BTreeMap<String, String> {
+        let mut xattrs = BTreeMap::new();
+        match xattr::list(path) {
+            Ok(list) => {
+                for (name, value) in list {
+                    xattrs.insert(name, hex::encode(value));
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "read local file xattrs failed! file={}, {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+
+        xattrs
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn load_xattrs(_path: &Path) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    // 扩展属性和修改时间恢复失败只输出警告，权限恢复失败返回错误
+    pub fn apply(&self, path: &Path) -> BuckyResult<()> {
+        #[cfg(target_os = "linux")]
+        for (name, value) in &self.xattrs {
+            let value = match hex::decode(value) {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("invalid xattr value! name={}, {}", name, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = xattr::set(path, name, &value) {
+                warn!(
+                    "set local file xattr failed! file={}, name={}, {}",
+                    path.display(),
+                    name,
+                    e
+                );
+            }
+        }
+
+        // 修改时间需要在设置只读权限之前更新
+        if let Some(mtime) = self.mtime {
+            let ret = std::fs::OpenOptions::new()
+                .write(true)
+                .open(path)
+                .and_then(|f| f.set_modified(bucky_time_to_system_time(mtime)));
+            if let Err(e) = ret {
+                warn!(
+                    "set local file mtime failed! file={}, mtime={}, {}",
+                    path.display(),
+                    mtime,
+                    e
+                );
+            }
+        }
+
+        let mut permissions = std::fs::metadata(path)
+            .map_err(|e| {
+                let msg = format!(
+                    "read local file metadata failed! file={}, {}",
+                    path.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?
+            .permissions();
+
+        #[cfg(unix)]
+        match self.mode {
+            Some(mode) => {
+                use std::os::unix::fs::PermissionsExt;
+                permissions.set_mode(mode);
+            }
+            None => permissions.set_readonly(self.readonly),
+        }
+        #[cfg(not(unix))]
+        permissions.set_readonly(self.readonly);
+
+        std::fs::set_permissions(path, permissions).map_err(|e| {
+            let msg = format!(
+                "set local file permissions failed! file={}, {}",
+                path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })
+    }
+
+    pub fn decode_from_file(file: &File) -> Option<Self> {
+        let user_data = file.body().as_ref()?.user_data().as_ref()?;
+        match serde_json::from_slice::<FileMetadataUserData>(user_data) {
+            Ok(v) => Some(v.cyfs_file_metadata),
+            Err(_) => None,
+        }
+    }
+
+    // 只会覆盖空的或者之前保存的元数据，不会覆盖应用自己设置的user_data
+    pub fn encode_to_file(&self, file: &mut File) -> bool {
+        let body = match file.body_mut() {
+            Some(body) => body,
+            None => return false,
+        };
+
+        if let Some(user_data) = body.user_data() {
+            match serde_json::from_slice::<FileMetadataUserData>(user_data) {
+                Ok(v) => {
+                    if v.cyfs_file_metadata == *self {
+                        return false;
+                    }
+                }
+                Err(_) => {
+                    warn!("file body already has user data, metadata will not be saved!");
+                    return false;
+                }
+            }
+        }
+
+        let value = serde_json::to_vec(&FileMetadataUserData {
+            cyfs_file_metadata: self.clone(),
+        })
+        .unwrap();
+        body.set_userdata(&value);
+
+        true
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod xattr {
+    use std::ffi::CString;
+    use std::io::{Error, ErrorKind, Result};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    fn path_to_cstr(path: &Path) -> Result<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+    }
+
+    // 只读取user命名空间的属性，其它命名空间依赖特权或者本机的安全策略
+    pub fn list(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+        let path = path_to_cstr(path)?;
+
+        let len = unsafe { libc::listxattr(path.as_ptr(), std::ptr::null_mut(), 0) };
+        if len < 0 {
+            return Err(Error::last_os_error());
+        }
+        if len == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut names = vec![0u8; len as usize];
+        let len = unsafe {
+            libc::listxattr(
+                path.as_ptr(),
+                names.as_mut_ptr() as *mut libc::c_char,
+                names.len(),
+            )
+        };
+        if len < 0 {
+            return Err(Error::last_os_error());
+        }
+        names.truncate(len as usize);
+
+        let mut list = vec![];
+        for name in names.split(|c| *c == 0) {
+            if !name.starts_with(b"user.") {
+                continue;
+            }
+
+            let name_str = CString::new(name).unwrap();
+            let len = unsafe {
+                libc::getxattr(path.as_ptr(), name_str.as_ptr(), std::ptr::null_mut(), 0)
+            };
+            if len < 0 {
+                continue;
+            }
+
+            let mut value = vec![0u8; len as usize];
+            let len = unsafe {
+                libc::getxattr(
+                    path.as_ptr(),
+                    name_str.as_ptr(),
+                    value.as_mut_ptr() as *mut libc::c_void,
+                    value.len(),
+                )
+            };
+            if len < 0 {
+                continue;
+            }
+            value.truncate(len as usize);
+
+            list.push((String::from_utf8_lossy(name).to_string(), value));
+        }
+
+        Ok(list)
+    }
+
+    pub fn set(path: &Path, name: &str, value: &[u8]) -> Result<()> {
+        let path = path_to_cstr(path)?;
+        let name = CString::new(name).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+        let ret = unsafe {
+            libc::setxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr() as *const libc::c_void,
+                value.len(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_metadata() {
+        let path = std::env::temp_dir().join("test_file_metadata");
+        std::fs::write(&path, b"file metadata").unwrap();
+
+        let mut metadata = FileMetadata::load(&path).unwrap();
+        assert!(metadata.mtime.is_some());
+
+        let chunk_id = ChunkId::calculate_sync(b"file metadata").unwrap();
+        let mut file = File::new(
+            ObjectId::default(),
+            chunk_id.len() as u64,
+            hash_data(b"file metadata"),
+            ChunkList::ChunkInList(vec![chunk_id]),
+        )
+        .build();
+        let file_id = file.desc().file_id();
+
+        assert!(metadata.encode_to_file(&mut file));
+        assert!(!metadata.encode_to_file(&mut file));
+        assert_eq!(file.desc().file_id(), file_id);
+
+        let file = File::clone_from_slice(&file.to_vec().unwrap()).unwrap();
+        assert_eq!(FileMetadata::decode_from_file(&file).unwrap(), metadata);
+
+        // 恢复到修改过的文件上
+        metadata.mtime = Some(metadata.mtime.unwrap() - 3600 * 1000 * 1000);
+        std::fs::write(&path, b"file metadata").unwrap();
+        metadata.apply(&path).unwrap();
+
+        let loaded = FileMetadata::load(&path).unwrap();
+        assert_eq!(
+            loaded.mtime.unwrap() / 1000000,
+            metadata.mtime.unwrap() / 1000000
+        );
+        assert_eq!(loaded.mode, metadata.mode);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
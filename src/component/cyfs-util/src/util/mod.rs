@@ -16,6 +16,12 @@ mod dir_loader;
 mod sn_dir;
 mod local_device_manager;
 mod db_helper;
+mod storage_layout;
+mod storage_degradation;
+mod storage_cipher;
+mod cold_pack;
+mod dec_cgroup;
+mod file_metadata;
 
 pub use bdt_util::*;
 pub use condvar_helper::*;
@@ -34,4 +40,10 @@ pub use read_seek::*;
 pub use dir_loader::*;
 pub use sn_dir::*;
 pub use local_device_manager::*;
-pub use db_helper::*;
\ No newline at end of file
+pub use db_helper::*;
+pub use storage_layout::*;
+pub use storage_degradation::*;
+pub use storage_cipher::*;
+pub use cold_pack::*;
+pub use dec_cgroup::*;
+pub use file_metadata::*;
\ No newline at end of file
@@ -0,0 +1,260 @@
+use cyfs_base::*;
+
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+use aes_gcm::Aes256Gcm;
+use hmac::Hmac;
+use once_cell::sync::OnceCell;
+use std::path::Path;
+
+// 加密后的数据格式: magic(8) + nonce(12) + aes256-gcm密文 + tag(16)
+const STORAGE_CIPHER_MAGIC: &[u8; 8] = b"CYFSENC2";
+const STORAGE_CIPHER_NONCE_LEN: usize = 12;
+const STORAGE_CIPHER_TAG_LEN: usize = 16;
+const STORAGE_CIPHER_HEADER_LEN: usize = STORAGE_CIPHER_MAGIC.len() + STORAGE_CIPHER_NONCE_LEN;
+
+// 口令派生主密钥的参数，salt每个设备随机生成，和校验数据一起保存在校验文件里
+const STORAGE_CIPHER_SALT_LEN: usize = 16;
+const STORAGE_CIPHER_KDF_ROUNDS: u32 = 100_000;
+const STORAGE_CIPHER_CHECK_ID: &[u8] = b"cyfs-storage-check";
+const STORAGE_CIPHER_CHECK_DATA: &[u8] = b"cyfs storage cipher";
+
+// 基于pbkdf2-hmac-sha256的口令派生，也可以用于只需要校验口令的场景
+pub fn derive_key_from_passphrase(passphrase: &str, salt: &[u8], rounds: u32) -> HashValue {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2::<Hmac<sha2::Sha256>>(passphrase.as_bytes(), salt, rounds, &mut key);
+
+    HashValue::from(&key)
+}
+
+// 本地存储的静态加密，主密钥由owner在运行时通过口令解锁，每个对象/chunk使用按id派生的独立密钥
+// 主密钥和口令都不会落盘，磁盘上只保存salt和校验数据
+#[derive(Clone)]
+pub struct StorageCipher {
+    master: HashValue,
+}
+
+// 避免在日志里输出密钥
+impl std::fmt::Debug for StorageCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StorageCipher(***)")
+    }
+}
+
+impl StorageCipher {
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Self {
+        Self {
+            master: derive_key_from_passphrase(passphrase, salt, STORAGE_CIPHER_KDF_ROUNDS),
+        }
+    }
+
+    // 用owner提供的口令解锁，校验文件不存在说明是第一次启用，生成salt和校验数据
+    // 校验文件存在时必须能用派生的主密钥解密校验数据，否则说明口令错误
+    pub fn unlock(passphrase: &str, check_file: &Path) -> BuckyResult<Self> {
+        if !check_file.is_file() {
+            let salt: [u8; STORAGE_CIPHER_SALT_LEN] = rand::random();
+            let cipher = Self::from_passphrase(passphrase, &salt);
+
+            let mut buf = salt.to_vec();
+            buf.extend_from_slice(
+                &cipher.encrypt(STORAGE_CIPHER_CHECK_ID, STORAGE_CIPHER_CHECK_DATA)?,
+            );
+
+            if let Some(dir) = check_file.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| {
+                    let msg = format!(
+                        "create storage cipher check dir error! dir={}, {}",
+                        dir.display(),
+                        e
+                    );
+                    error!("{}", msg);
+                    BuckyError::new(BuckyErrorCode::IoError, msg)
+                })?;
+            }
+
+            std::fs::write(check_file, &buf).map_err(|e| {
+                let msg = format!(
+                    "write storage cipher check file error! file={}, {}",
+                    check_file.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+            info!("init storage cipher check file: {}", check_file.display());
+            return Ok(cipher);
+        }
+
+        let buf = std::fs::read(check_file).map_err(|e| {
+            let msg = format!(
+                "read storage cipher check file error! file={}, {}",
+                check_file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        if buf.len() <= STORAGE_CIPHER_SALT_LEN {
+            let msg = format!(
+                "invalid storage cipher check file! file={}, len={}",
+                check_file.display(),
+                buf.len()
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+
+        let (salt, check) = buf.split_at(STORAGE_CIPHER_SALT_LEN);
+        let cipher = Self::from_passphrase(passphrase, salt);
+        match cipher.decrypt(STORAGE_CIPHER_CHECK_ID, check) {
+            Ok(data) if data == STORAGE_CIPHER_CHECK_DATA => Ok(cipher),
+            _ => {
+                let msg = format!(
+                    "unlock storage cipher failed, the passphrase may be wrong! file={}",
+                    check_file.display()
+                );
+                error!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::PermissionDenied, msg))
+            }
+        }
+    }
+
+    // key = sha256(master + id)，nonce每次加密随机生成并保存在头部，id同时作为附加数据参与认证
+    fn derive_key(&self, id: &[u8]) -> Aes256Gcm {
+        let mut buf = Vec::with_capacity(HashValue::len() + id.len());
+        buf.extend_from_slice(self.master.as_slice());
+        buf.extend_from_slice(id);
+
+        let key = hash_data(&buf);
+        Aes256Gcm::new(GenericArray::from_slice(key.as_slice()))
+    }
+
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.len() >= STORAGE_CIPHER_HEADER_LEN + STORAGE_CIPHER_TAG_LEN
+            && data.starts_with(STORAGE_CIPHER_MAGIC)
+    }
+
+    pub fn encrypted_len(len: usize) -> usize {
+        STORAGE_CIPHER_HEADER_LEN + len + STORAGE_CIPHER_TAG_LEN
+    }
+
+    pub fn encrypt(&self, id: &[u8], data: &[u8]) -> BuckyResult<Vec<u8>> {
+        let nonce: [u8; STORAGE_CIPHER_NONCE_LEN] = rand::random();
+        let key = self.derive_key(id);
+
+        let encrypted = key
+            .encrypt(
+                GenericArray::from_slice(&nonce),
+                Payload { msg: data, aad: id },
+            )
+            .map_err(|_| {
+                let msg = format!("encrypt storage data failed! len={}", data.len());
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::CryptoError, msg)
+            })?;
+
+        let mut buf = Vec::with_capacity(STORAGE_CIPHER_HEADER_LEN + encrypted.len());
+        buf.extend_from_slice(STORAGE_CIPHER_MAGIC);
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(&encrypted);
+
+        Ok(buf)
+    }
+
+    // 密文被篡改、id不匹配或者主密钥错误都会校验失败
+    pub fn decrypt(&self, id: &[u8], data: &[u8]) -> BuckyResult<Vec<u8>> {
+        if !Self::is_encrypted(data) {
+            let msg = format!("invalid encrypted storage data! len={}", data.len());
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+
+        let nonce = &data[STORAGE_CIPHER_MAGIC.len()..STORAGE_CIPHER_HEADER_LEN];
+        let key = self.derive_key(id);
+
+        key.decrypt(
+            GenericArray::from_slice(nonce),
+            Payload {
+                msg: &data[STORAGE_CIPHER_HEADER_LEN..],
+                aad: id,
+            },
+        )
+        .map_err(|_| {
+            let msg = format!(
+                "decrypt storage data failed, the data may be tampered or the key is wrong! len={}",
+                data.len()
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidData, msg)
+        })
+    }
+}
+
+static STORAGE_CIPHER: OnceCell<StorageCipher> = OnceCell::new();
+
+// owner解锁之后由协议栈设置一次，noc和chunk cache在使用时读取，未解锁之前需要加密的写入都会失败
+pub fn init_storage_cipher(cipher: StorageCipher) {
+    if let Err(_) = STORAGE_CIPHER.set(cipher) {
+        warn!("storage cipher already been init!");
+    }
+}
+
+pub fn get_storage_cipher() -> Option<&'static StorageCipher> {
+    STORAGE_CIPHER.get()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cipher() {
+        let cipher = StorageCipher::from_passphrase("test passphrase", b"salt1");
+        let data = b"private object data".to_vec();
+
+        let encrypted = cipher.encrypt(b"id1", &data).unwrap();
+        assert!(StorageCipher::is_encrypted(&encrypted));
+        assert_eq!(encrypted.len(), StorageCipher::encrypted_len(data.len()));
+        assert_eq!(cipher.decrypt(b"id1", &encrypted).unwrap(), data);
+
+        // 同样的数据每次加密的结果不同
+        assert_ne!(cipher.encrypt(b"id1", &data).unwrap(), encrypted);
+
+        // 其它对象的密钥或者错误的主密钥无法解密
+        assert!(cipher.decrypt(b"id2", &encrypted).is_err());
+        let other = StorageCipher::from_passphrase("test passphrase", b"salt2");
+        assert!(other.decrypt(b"id1", &encrypted).is_err());
+
+        // 篡改任意一个字节都会校验失败
+        for i in STORAGE_CIPHER_MAGIC.len()..encrypted.len() {
+            let mut tampered = encrypted.clone();
+            tampered[i] ^= 0x01;
+            assert!(cipher.decrypt(b"id1", &tampered).is_err());
+        }
+
+        assert!(!StorageCipher::is_encrypted(&data));
+        assert!(cipher.decrypt(b"id1", &data).is_err());
+    }
+
+    #[test]
+    fn test_unlock() {
+        let dir = crate::get_temp_path().join("test_storage_cipher_unlock");
+        let check_file = dir.join("storage.check");
+        let _ = std::fs::remove_file(&check_file);
+
+        // 第一次解锁生成校验文件
+        let cipher = StorageCipher::unlock("owner passphrase", &check_file).unwrap();
+        assert!(check_file.is_file());
+        let encrypted = cipher.encrypt(b"id1", b"data").unwrap();
+
+        // 同样的口令得到同样的主密钥
+        let cipher = StorageCipher::unlock("owner passphrase", &check_file).unwrap();
+        assert_eq!(cipher.decrypt(b"id1", &encrypted).unwrap(), b"data");
+
+        let err = StorageCipher::unlock("wrong passphrase", &check_file).unwrap_err();
+        assert_eq!(err.code(), BuckyErrorCode::PermissionDenied);
+
+        std::fs::remove_file(&check_file).unwrap();
+    }
+}
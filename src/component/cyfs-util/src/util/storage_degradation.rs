@@ -0,0 +1,350 @@
+use crate::{StorageRoot, StorageRootCategory, SyncEventManagerSync};
+use cyfs_base::*;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// 连续写入失败多少次之后进入只读模式
+const STORAGE_IO_ERROR_THRESHOLD: u32 = 3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub enum StorageDegradationReason {
+    DiskFull,
+    IoError,
+}
+
+impl StorageDegradationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DiskFull => "disk-full",
+            Self::IoError => "io-error",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageDegradationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// 根目录进入或者退出只读模式时触发
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageDegradationEvent {
+    pub category: StorageRootCategory,
+    pub read_only: bool,
+
+    // 退出只读模式时为None
+    pub reason: Option<StorageDegradationReason>,
+    pub msg: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageDegradationStatus {
+    pub category: StorageRootCategory,
+    pub reason: StorageDegradationReason,
+    pub since: u64,
+    pub msg: String,
+}
+
+#[derive(Default)]
+struct StorageDegradationState {
+    io_errors: u32,
+    read_only: Option<StorageDegradationStatus>,
+}
+
+pub type StorageDegradationEventManager = SyncEventManagerSync<StorageDegradationEvent, ()>;
+
+// 存储写入持续失败时把对应的根目录切换到只读模式，拒绝后续的写入请求，磁盘恢复后自动退出
+pub struct StorageDegradationController {
+    states: Mutex<HashMap<StorageRootCategory, StorageDegradationState>>,
+    event: StorageDegradationEventManager,
+}
+
+impl StorageDegradationController {
+    pub fn new() -> Self {
+        Self {
+            states: Mutex::new(HashMap::new()),
+            event: StorageDegradationEventManager::new(),
+        }
+    }
+
+    pub fn event(&self) -> &StorageDegradationEventManager {
+        &self.event
+    }
+
+    pub fn is_read_only(&self, category: StorageRootCategory) -> bool {
+        self.states
+            .lock()
+            .unwrap()
+            .get(&category)
+            .map(|state| state.read_only.is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn status_list(&self) -> Vec<StorageDegradationStatus> {
+        self.states
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|state| state.read_only.clone())
+            .collect()
+    }
+
+    // 写入前检查，只读模式下返回StorageReadOnly
+    pub fn check_write(&self, category: StorageRootCategory) -> BuckyResult<()> {
+        let states = self.states.lock().unwrap();
+        match states
+            .get(&category)
+            .and_then(|state| state.read_only.as_ref())
+        {
+            Some(status) => {
+                let msg = format!(
+                    "storage is in read-only mode! root={}, reason={}, since={}, {}",
+                    category, status.reason, status.since, status.msg
+                );
+                warn!("{}", msg);
+                Err(BuckyError::new(BuckyErrorCode::StorageReadOnly, msg))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn on_write_result<T>(&self, category: StorageRootCategory, ret: &BuckyResult<T>) {
+        match ret {
+            Ok(_) => self.on_write_success(category),
+            Err(e) => self.on_write_error(category, e),
+        }
+    }
+
+    pub fn on_write_success(&self, category: StorageRootCategory) {
+        let mut states = self.states.lock().unwrap();
+        if let Some(state) = states.get_mut(&category) {
+            state.io_errors = 0;
+        }
+    }
+
+    // 只有磁盘和io相关的错误才计数，其余的业务错误忽略
+    pub fn on_write_error(&self, category: StorageRootCategory, e: &BuckyError) {
+        let reason = match Self::classify_error(e) {
+            Some(reason) => reason,
+            None => return,
+        };
+
+        let enter = {
+            let mut states = self.states.lock().unwrap();
+            let state = states.entry(category).or_default();
+            if state.read_only.is_some() {
+                return;
+            }
+
+            state.io_errors += 1;
+            reason == StorageDegradationReason::DiskFull
+                || state.io_errors >= STORAGE_IO_ERROR_THRESHOLD
+        };
+
+        if enter {
+            self.enter_read_only(category, reason, e.msg().to_owned());
+        }
+    }
+
+    fn classify_error(e: &BuckyError) -> Option<StorageDegradationReason> {
+        let msg = e.msg().to_ascii_lowercase();
+        if msg.contains("no space left") || msg.contains("disk is full") {
+            return Some(StorageDegradationReason::DiskFull);
+        }
+
+        match e.code() {
+            BuckyErrorCode::IoError
+            | BuckyErrorCode::UnknownIOError
+            | BuckyErrorCode::WriteZero => Some(StorageDegradationReason::IoError),
+            BuckyErrorCode::SqliteError if msg.contains("disk i/o error") => {
+                Some(StorageDegradationReason::IoError)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn enter_read_only(
+        &self,
+        category: StorageRootCategory,
+        reason: StorageDegradationReason,
+        msg: String,
+    ) {
+        {
+            let mut states = self.states.lock().unwrap();
+            let state = states.entry(category).or_default();
+            if state.read_only.is_some() {
+                return;
+            }
+
+            state.read_only = Some(StorageDegradationStatus {
+                category,
+                reason,
+                since: bucky_time_now(),
+                msg: msg.clone(),
+            });
+        }
+
+        error!(
+            "storage root enter read-only mode! root={}, reason={}, {}",
+            category, reason, msg
+        );
+
+        self.emit(StorageDegradationEvent {
+            category,
+            read_only: true,
+            reason: Some(reason),
+            msg,
+        });
+    }
+
+    fn leave_read_only(&self, category: StorageRootCategory, msg: String) {
+        {
+            let mut states = self.states.lock().unwrap();
+            match states.get_mut(&category) {
+                Some(state) if state.read_only.is_some() => {
+                    state.read_only = None;
+                    state.io_errors = 0;
+                }
+                _ => return,
+            }
+        }
+
+        info!(
+            "storage root leave read-only mode! root={}, {}",
+            category, msg
+        );
+
+        self.emit(StorageDegradationEvent {
+            category,
+            read_only: false,
+            reason: None,
+            msg,
+        });
+    }
+
+    fn emit(&self, event: StorageDegradationEvent) {
+        if let Err(e) = self.event.emit(&event) {
+            error!(
+                "emit storage degradation event failed! root={}, {}",
+                event.category, e
+            );
+        }
+    }
+
+    // 由空间监控定时调用，磁盘有足够的空间并且可以正常写入后退出只读模式
+    pub fn try_recover(&self, root: &StorageRoot) {
+        let category = root.category();
+        let reason = {
+            let states = self.states.lock().unwrap();
+            match states
+                .get(&category)
+                .and_then(|state| state.read_only.as_ref())
+            {
+                Some(status) => status.reason,
+                None => return,
+            }
+        };
+
+        if let Some(available) = root.available_space() {
+            // 剩余空间恢复到保留值的两倍以上才退出，避免在临界值附近反复切换
+            if available < root.reserved_space().saturating_mul(2) {
+                debug!(
+                    "storage root still low on space, keep read-only! root={}, available={}, reserved={}",
+                    category,
+                    available,
+                    root.reserved_space()
+                );
+                return;
+            }
+        }
+
+        if let Err(e) = Self::probe_write(root) {
+            debug!(
+                "storage root still not writable, keep read-only! root={}, reason={}, {}",
+                category, reason, e
+            );
+            return;
+        }
+
+        let msg = format!("storage recovered from {}", reason);
+        self.leave_read_only(category, msg);
+    }
+
+    fn probe_write(root: &StorageRoot) -> BuckyResult<()> {
+        let file = root.path().join(".write_probe");
+        let ret = std::fs::create_dir_all(root.path())
+            .and_then(|_| std::fs::write(&file, b"cyfs"))
+            .map_err(|e| {
+                let msg = format!(
+                    "probe storage root write failed! file={}, {}",
+                    file.display(),
+                    e
+                );
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            });
+
+        let _ = std::fs::remove_file(&file);
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_degradation() {
+        let controller = StorageDegradationController::new();
+        let category = StorageRootCategory::Noc;
+
+        let events = std::sync::Arc::new(Mutex::new(vec![]));
+        let list = events.clone();
+        controller.event().on(Box::new(
+            move |event: &StorageDegradationEvent| -> BuckyResult<()> {
+                list.lock().unwrap().push(event.read_only);
+                Ok(())
+            },
+        ));
+
+        // 业务错误不影响
+        let e = BuckyError::new(BuckyErrorCode::NotFound, "not found");
+        for _ in 0..STORAGE_IO_ERROR_THRESHOLD {
+            controller.on_write_error(category, &e);
+        }
+        assert!(controller.check_write(category).is_ok());
+
+        // 中间有成功的写入，重新计数
+        let e = BuckyError::new(BuckyErrorCode::IoError, "write failed");
+        controller.on_write_error(category, &e);
+        controller.on_write_result::<()>(category, &Ok(()));
+        for _ in 0..STORAGE_IO_ERROR_THRESHOLD - 1 {
+            controller.on_write_error(category, &e);
+        }
+        assert!(!controller.is_read_only(category));
+
+        controller.on_write_error(category, &e);
+        assert_eq!(
+            controller.check_write(category).unwrap_err().code(),
+            BuckyErrorCode::StorageReadOnly
+        );
+        assert!(controller.check_write(StorageRootCategory::Chunk).is_ok());
+
+        // 磁盘满直接进入只读
+        let e = BuckyError::new(
+            BuckyErrorCode::IoError,
+            "No space left on device (os error 28)",
+        );
+        controller.on_write_error(StorageRootCategory::Chunk, &e);
+        assert!(controller.is_read_only(StorageRootCategory::Chunk));
+        assert_eq!(controller.status_list().len(), 2);
+
+        let root = StorageRoot::new(category, std::env::temp_dir(), 0);
+        controller.try_recover(&root);
+        assert!(controller.check_write(category).is_ok());
+
+        assert_eq!(*events.lock().unwrap(), vec![true, true, false]);
+    }
+}
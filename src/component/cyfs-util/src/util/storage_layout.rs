@@ -0,0 +1,489 @@
+use crate::{StorageDegradationController, StorageDegradationReason, TomlHelper};
+use cyfs_base::*;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+// 协议栈的数据根目录，可以通过{cyfs_root}/etc/storage.toml分别映射到不同的磁盘上
+// [noc]
+// path = "/mnt/disk1/cyfs"
+// reserved = 1073741824
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub enum StorageRootCategory {
+    Noc,
+    Chunk,
+    TransTmp,
+    BackupStaging,
+}
+
+impl StorageRootCategory {
+    pub fn all() -> &'static [StorageRootCategory] {
+        &[Self::Noc, Self::Chunk, Self::TransTmp, Self::BackupStaging]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Noc => "noc",
+            Self::Chunk => "chunk",
+            Self::TransTmp => "trans-tmp",
+            Self::BackupStaging => "backup-staging",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageRootCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// 磁盘剩余空间低于保留值时拒绝写入
+const STORAGE_DEFAULT_RESERVED_SPACE: u64 = 1024 * 1024 * 512;
+
+// 剩余空间的缓存时间，写入前的检查不需要每次都查询磁盘
+const STORAGE_SPACE_CACHE_DURATION: u64 = 1000 * 1000 * 5;
+
+// 后台刷新剩余空间的间隔
+const STORAGE_MONITOR_INTERVAL_SECS: u64 = 60;
+
+// 有根目录处于只读模式时缩短刷新间隔，尽快恢复
+const STORAGE_MONITOR_DEGRADED_INTERVAL_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageRootStatus {
+    pub category: StorageRootCategory,
+    pub path: PathBuf,
+
+    // 查询失败时为None
+    pub total_space: Option<u64>,
+    pub available_space: Option<u64>,
+    pub reserved_space: u64,
+
+    pub is_full: bool,
+}
+
+pub struct StorageRoot {
+    category: StorageRootCategory,
+    path: PathBuf,
+    reserved_space: u64,
+
+    // u64::MAX表示还没有成功获取过
+    total: AtomicU64,
+    available: AtomicU64,
+    last_refresh: AtomicU64,
+}
+
+impl StorageRoot {
+    pub(crate) fn new(category: StorageRootCategory, path: PathBuf, reserved_space: u64) -> Self {
+        Self {
+            category,
+            path,
+            reserved_space,
+            total: AtomicU64::new(u64::MAX),
+            available: AtomicU64::new(u64::MAX),
+            last_refresh: AtomicU64::new(0),
+        }
+    }
+
+    pub fn category(&self) -> StorageRootCategory {
+        self.category
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn reserved_space(&self) -> u64 {
+        self.reserved_space
+    }
+
+    pub fn refresh(&self) -> BuckyResult<()> {
+        // 目录可能还没有创建，使用最近的已存在的上级目录所在的磁盘
+        let mut path = self.path.as_path();
+        while !path.exists() {
+            match path.parent() {
+                Some(parent) => path = parent,
+                None => break,
+            }
+        }
+
+        let (total, available) = fs2::total_space(path)
+            .and_then(|total| fs2::available_space(path).map(|available| (total, available)))
+            .map_err(|e| {
+                let msg = format!(
+                    "get disk space of storage root failed! root={}, path={}, {}",
+                    self.category,
+                    self.path.display(),
+                    e
+                );
+                warn!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+        self.total.store(total, Ordering::SeqCst);
+        self.available.store(available, Ordering::SeqCst);
+        self.last_refresh.store(bucky_time_now(), Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    fn try_refresh(&self) {
+        let now = bucky_time_now();
+        let last = self.last_refresh.load(Ordering::SeqCst);
+        if now < last || now - last >= STORAGE_SPACE_CACHE_DURATION {
+            let _ = self.refresh();
+        }
+    }
+
+    fn get_value(v: &AtomicU64) -> Option<u64> {
+        match v.load(Ordering::SeqCst) {
+            u64::MAX => None,
+            v @ _ => Some(v),
+        }
+    }
+
+    pub fn available_space(&self) -> Option<u64> {
+        self.try_refresh();
+        Self::get_value(&self.available)
+    }
+
+    // 写入前检查磁盘空间，写入后剩余空间需要大于保留值；无法获取磁盘空间时不做限制
+    pub fn check_space(&self, size: u64) -> BuckyResult<()> {
+        let available = match self.available_space() {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        if available < self.reserved_space.saturating_add(size) {
+            let msg = format!(
+                "storage disk is full! root={}, path={}, available={}, reserved={}, required={}",
+                self.category,
+                self.path.display(),
+                available,
+                self.reserved_space,
+                size,
+            );
+            error!("{}", msg);
+            return Err(BuckyError::new(BuckyErrorCode::OutOfLimit, msg));
+        }
+
+        // 预先扣除本次写入的大小，直到下次刷新
+        let _ = self
+            .available
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+                if v == u64::MAX {
+                    None
+                } else {
+                    Some(v.saturating_sub(size))
+                }
+            });
+
+        Ok(())
+    }
+
+    pub fn status(&self) -> StorageRootStatus {
+        let available_space = self.available_space();
+        StorageRootStatus {
+            category: self.category,
+            path: self.path.clone(),
+            total_space: Self::get_value(&self.total),
+            available_space,
+            reserved_space: self.reserved_space,
+            is_full: match available_space {
+                Some(v) => v < self.reserved_space,
+                None => false,
+            },
+        }
+    }
+}
+
+pub struct StorageLayout {
+    roots: HashMap<StorageRootCategory, Arc<StorageRoot>>,
+    degradation: StorageDegradationController,
+    monitor_started: AtomicBool,
+}
+
+impl StorageLayout {
+    // 所有的数据根目录默认都在{cyfs_root}/data
+    pub fn new_default(cyfs_root: &Path) -> Self {
+        let mut roots = HashMap::new();
+        for category in StorageRootCategory::all() {
+            let root = StorageRoot::new(
+                *category,
+                cyfs_root.join("data"),
+                STORAGE_DEFAULT_RESERVED_SPACE,
+            );
+            roots.insert(*category, Arc::new(root));
+        }
+
+        Self {
+            roots,
+            degradation: StorageDegradationController::new(),
+            monitor_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn load(cyfs_root: &Path, file: &Path) -> BuckyResult<Self> {
+        let s = std::fs::read_to_string(file).map_err(|e| {
+            let msg = format!(
+                "read storage layout config failed! file={}, {}",
+                file.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+        Self::load_from_str(cyfs_root, &s)
+    }
+
+    pub fn load_from_str(cyfs_root: &Path, s: &str) -> BuckyResult<Self> {
+        let node: toml::Value = toml::from_str(s).map_err(|e| {
+            let msg = format!("invalid storage layout config! {}, {}", s, e);
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+        })?;
+
+        let mut layout = Self::new_default(cyfs_root);
+        for category in StorageRootCategory::all() {
+            let node = match node.get(category.as_str()) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let node = node.as_table().ok_or_else(|| {
+                let msg = format!(
+                    "invalid storage layout config node, except table! root={}, {}",
+                    category, node
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::InvalidFormat, msg)
+            })?;
+
+            let path = match node.get("path") {
+                Some(v) => {
+                    let path: String = TomlHelper::decode_from_string(v)?;
+
+                    // 相对路径基于cyfs_root
+                    cyfs_root.join(path)
+                }
+                None => cyfs_root.join("data"),
+            };
+
+            let reserved_space = match node.get("reserved") {
+                Some(v) => TomlHelper::decode_to_int(v)?,
+                None => STORAGE_DEFAULT_RESERVED_SPACE,
+            };
+
+            info!(
+                "storage root mapped: root={}, path={}, reserved={}",
+                category,
+                path.display(),
+                reserved_space
+            );
+
+            let root = StorageRoot::new(*category, path, reserved_space);
+            layout.roots.insert(*category, Arc::new(root));
+        }
+
+        Ok(layout)
+    }
+
+    pub fn root(&self, category: StorageRootCategory) -> &Arc<StorageRoot> {
+        self.roots.get(&category).unwrap()
+    }
+
+    pub fn root_path(&self, category: StorageRootCategory) -> PathBuf {
+        self.root(category).path().to_owned()
+    }
+
+    pub fn degradation(&self) -> &StorageDegradationController {
+        &self.degradation
+    }
+
+    // 只读模式下直接拒绝；剩余空间已经低于保留值时切换到只读模式
+    pub fn check_space(&self, category: StorageRootCategory, size: u64) -> BuckyResult<()> {
+        self.degradation.check_write(category)?;
+
+        let root = self.root(category);
+        if let Err(e) = root.check_space(size) {
+            if root.status().is_full {
+                self.degradation.enter_read_only(
+                    category,
+                    StorageDegradationReason::DiskFull,
+                    e.msg().to_owned(),
+                );
+                return self.degradation.check_write(category);
+            }
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    pub fn status_list(&self) -> Vec<StorageRootStatus> {
+        StorageRootCategory::all()
+            .iter()
+            .map(|category| self.root(*category).status())
+            .collect()
+    }
+
+    fn refresh_all(&self) {
+        for category in StorageRootCategory::all() {
+            let root = self.root(*category);
+            if root.refresh().is_err() {
+                continue;
+            }
+
+            let status = root.status();
+            if status.is_full {
+                self.degradation.enter_read_only(
+                    *category,
+                    StorageDegradationReason::DiskFull,
+                    format!(
+                        "available={:?}, reserved={}",
+                        status.available_space, status.reserved_space
+                    ),
+                );
+
+                error!(
+                    "storage root disk is full, write will be rejected! root={}, path={}, available={:?}, reserved={}",
+                    category,
+                    status.path.display(),
+                    status.available_space,
+                    status.reserved_space
+                );
+            } else if let Some(available) = status.available_space {
+                // 剩余空间不足保留值的两倍时提前告警
+                if available < status.reserved_space.saturating_mul(2) {
+                    warn!(
+                        "storage root disk space is low! root={}, path={}, available={}, reserved={}",
+                        category,
+                        status.path.display(),
+                        available,
+                        status.reserved_space
+                    );
+                }
+            }
+
+            self.degradation.try_recover(root);
+        }
+    }
+
+    // 后台定时刷新每个根目录所在磁盘的剩余空间
+    pub fn start_monitor(&'static self) {
+        if self.monitor_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        info!("start storage layout space monitor...");
+        async_std::task::spawn(async move {
+            loop {
+                self.refresh_all();
+
+                let interval = if self.degradation.status_list().is_empty() {
+                    STORAGE_MONITOR_INTERVAL_SECS
+                } else {
+                    STORAGE_MONITOR_DEGRADED_INTERVAL_SECS
+                };
+                async_std::task::sleep(std::time::Duration::from_secs(interval)).await;
+            }
+        });
+    }
+}
+
+static STORAGE_LAYOUT: once_cell::sync::OnceCell<StorageLayout> = once_cell::sync::OnceCell::new();
+
+// 首次使用时从{cyfs_root}/etc/storage.toml加载，配置不存在或者无效时使用默认布局
+pub fn get_storage_layout() -> &'static StorageLayout {
+    STORAGE_LAYOUT.get_or_init(|| {
+        let cyfs_root = crate::get_cyfs_root_path_ref();
+        let file = cyfs_root.join("etc").join("storage.toml");
+        if !file.exists() {
+            return StorageLayout::new_default(cyfs_root);
+        }
+
+        match StorageLayout::load(cyfs_root, &file) {
+            Ok(layout) => layout,
+            Err(e) => {
+                error!(
+                    "load storage layout config failed, now will use default! file={}, {}",
+                    file.display(),
+                    e
+                );
+                StorageLayout::new_default(cyfs_root)
+            }
+        }
+    })
+}
+
+pub fn get_storage_root(category: StorageRootCategory) -> PathBuf {
+    get_storage_layout().root_path(category)
+}
+
+pub fn check_storage_space(category: StorageRootCategory, size: u64) -> BuckyResult<()> {
+    get_storage_layout().check_space(category, size)
+}
+
+// 写入完成后上报结果，连续的io错误会让对应的根目录进入只读模式
+pub fn report_storage_write_result<T>(category: StorageRootCategory, ret: &BuckyResult<T>) {
+    get_storage_layout()
+        .degradation()
+        .on_write_result(category, ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let root = PathBuf::from("/cyfs");
+        let s = r#"
+        [noc]
+        path = "/mnt/disk1/cyfs"
+        reserved = 1024
+
+        [backup-staging]
+        path = "backup"
+        "#;
+
+        let layout = StorageLayout::load_from_str(&root, s).unwrap();
+        assert_eq!(
+            layout.root_path(StorageRootCategory::Noc),
+            PathBuf::from("/mnt/disk1/cyfs")
+        );
+        assert_eq!(layout.root(StorageRootCategory::Noc).reserved_space(), 1024);
+        assert_eq!(
+            layout.root_path(StorageRootCategory::BackupStaging),
+            root.join("backup")
+        );
+        assert_eq!(
+            layout.root_path(StorageRootCategory::Chunk),
+            root.join("data")
+        );
+
+        assert!(StorageLayout::load_from_str(&root, "noc = 1").is_err());
+    }
+
+    #[test]
+    fn test_check_space() {
+        let dir = std::env::temp_dir();
+        let root = StorageRoot::new(StorageRootCategory::TransTmp, dir.clone(), 0);
+        root.refresh().unwrap();
+        assert!(root.check_space(0).is_ok());
+
+        let root = StorageRoot::new(
+            StorageRootCategory::TransTmp,
+            dir.join("not-exists"),
+            u64::MAX - 1,
+        );
+        let e = root.check_space(1).unwrap_err();
+        assert_eq!(e.code(), BuckyErrorCode::OutOfLimit);
+        assert!(root.status().is_full);
+    }
+}
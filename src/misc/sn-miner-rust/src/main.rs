@@ -56,10 +56,16 @@ async fn main() {
 
             log::info!("sn-miner load device from {}, id {}", matches.value_of("desc").unwrap(), device.desc().object_id());
 
-            let service = SnService::new(
+            let config = SnServiceConfig {
+                peer_store: Some(data_folder.join("peers.db")),
+                ..Default::default()
+            };
+
+            let service = SnService::new_with_config(
                 device,
                 private_key,
                 Box::new(SnServiceContractServerImpl::new()),
+                config,
             );
 
             let _ = service.start().await;
@@ -400,6 +400,7 @@ impl AppCmdExecutor {
                 .non_helper
                 .save_app_web_dir(app_id, version, &obj_id)
                 .await;
+            let _ = self.non_helper.pin_app_web_dir(app_id, version).await;
             let _ = self
                 .non_helper
                 .register_app_name(dec_app.name(), app_id)
@@ -408,6 +409,15 @@ impl AppCmdExecutor {
         //版本已经提前设置了
         //status.lock().unwrap().set_version(version);
 
+        let _ = self
+            .non_helper
+            .post_zone_event(
+                ZoneEventCategory::AppInstall,
+                app_id,
+                format!("version={}", version),
+            )
+            .await;
+
         Ok(target_status_code)
     }
 
@@ -445,6 +455,7 @@ impl AppCmdExecutor {
         let mut sub_err = SubErrorCode::None;
 
         if let Some(obj_id) = web_id {
+            let _ = self.non_helper.unpin_app_web_dir(app_id, &ver).await;
             let _ = self
                 .non_helper
                 .remove_app_web_dir(app_id, &ver, &obj_id)
@@ -458,6 +469,15 @@ impl AppCmdExecutor {
             sub_err = e;
         }
 
+        let _ = self
+            .non_helper
+            .post_zone_event(
+                ZoneEventCategory::AppUninstall,
+                app_id,
+                format!("version={}, status={}", ver, target_status_code),
+            )
+            .await;
+
         let _ = self
             .post_change_status(
                 app_id,
@@ -237,6 +237,8 @@ impl AppController {
             let _ = std::fs::remove_dir_all(&app_log_dir);
         }
 
+        let _ = DecCgroup::new(app_id.object_id()).remove();
+
         // docker remove
         // 删除镜像
         let use_docker = self.config.app_use_docker(app_id);
@@ -256,10 +258,13 @@ impl AppController {
         Ok(())
     }
 
-    pub async fn start_app(&self, app_id: &DecAppId, config: RunConfig) -> AppActionResult<()> {
+    pub async fn start_app(&self, app_id: &DecAppId, mut config: RunConfig) -> AppActionResult<()> {
         info!("try to start app:{}", app_id);
         let id = app_id.to_string();
 
+        // 协议栈上配置的资源策略优先于安装时的quota
+        let policy = self.get_resource_policy(app_id).await;
+
         let use_docker = self.config.app_use_docker(app_id);
         info!("app {} use docker start: {}", app_id, use_docker);
         if use_docker {
@@ -267,6 +272,15 @@ impl AppController {
                 warn!("load app failed, appId: {}, err:{}", id, e);
                 SubErrorCode::LoadFailed
             })?;
+            if let Some(policy) = &policy {
+                if let Some(cpu_limit) = policy.cpu_limit {
+                    config.cpu_core = Some(cpu_limit as f64);
+                }
+                if let Some(memory_limit) = policy.memory_limit {
+                    config.memory = Some((memory_limit / 1048576) as i64);
+                }
+            }
+
             let cmd = dapp.get_start_cmd();
             info!("service cmd: {}", &cmd);
             self.docker_api
@@ -289,11 +303,39 @@ impl AppController {
                 SubErrorCode::CommondFailed
             })?;
 
+            if let Some(pid) = dapp.process_id() {
+                Self::attach_cgroup(app_id, pid, policy.as_ref());
+            }
+
             self.dapp_instance.write().unwrap().insert(app_id.clone(), dapp);
         }
         Ok(())
     }
 
+    async fn get_resource_policy(&self, app_id: &DecAppId) -> Option<DecResourcePolicy> {
+        let stack = self.shared_stack.get()?;
+        let req = UtilGetDecResourcePoliciesRequest::new(Some(app_id.object_id().to_owned()));
+        match stack.util().get_dec_resource_policies(req).await {
+            Ok(resp) => resp.list.into_iter().next(),
+            Err(e) => {
+                warn!("get dec resource policy failed, app:{}, err:{}", app_id, e);
+                None
+            }
+        }
+    }
+
+    // 直接运行的app放到独立的cgroup里，协议栈会根据策略的变化更新限制并统计占用
+    fn attach_cgroup(app_id: &DecAppId, pid: u32, policy: Option<&DecResourcePolicy>) {
+        let cgroup = DecCgroup::new(app_id.object_id());
+        let ret = match policy {
+            Some(policy) => cgroup.apply_limits(policy.cpu_limit, policy.memory_limit),
+            None => cgroup.apply_limits(None, None),
+        };
+        if let Err(e) = ret.and_then(|_| cgroup.attach(pid)) {
+            warn!("attach app to cgroup failed, app:{}, pid:{}, err:{}", app_id, pid, e);
+        }
+    }
+
     pub async fn stop_app(&self, app_id: &DecAppId) -> AppActionResult<()> {
         let id = app_id.to_string();
         let use_docker = self.config.app_use_docker(app_id);
@@ -464,6 +506,8 @@ impl AppController {
                     level: NONAPILevel::Router,
                     target: None,
                     flags: CYFS_ROUTER_REQUEST_FLAG_FLUSH,
+                    deadline: None,
+                    request_id: None,
                 },
                 object_id: app_id.clone().into(),
                 inner_path: None,
@@ -529,6 +573,8 @@ mod tests {
                     level: NONAPILevel::NOC,
                     target: None,
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object: NONObjectInfo {
                     object_id: appcmd.desc().calculate_id(),
@@ -536,6 +582,7 @@ mod tests {
                     object: None,
                 },
                 access: None,
+                precondition: None,
             })
             .await
             .unwrap();
@@ -214,6 +214,11 @@ impl DApp {
         Ok(false)
     }
 
+    // 直接运行时启动的进程id，进程已经退出或者不是本次启动的返回None
+    pub fn process_id(&self) -> Option<u32> {
+        self.process.lock().unwrap().as_ref().map(|child| child.id())
+    }
+
     //time_out == 0 wait forever
     fn run_cmd(
         &self,
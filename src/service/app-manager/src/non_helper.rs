@@ -394,6 +394,82 @@ impl NonHelper {
         Ok(())
     }
 
+    // 预取web dir的chunk并固定，失败不影响安装
+    pub async fn pin_app_web_dir(&self, app_id: &DecAppId, ver: &str) -> BuckyResult<()> {
+        let req = UtilPinAppWebDirOutputRequest::new(app_id.object_id().to_owned(), ver);
+        match self.shared_stack.util().pin_app_web_dir(req).await {
+            Ok(resp) => {
+                info!(
+                    "pin app web dir success, app:{}, ver:{}, {}",
+                    app_id, ver, resp
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!(
+                    "pin app web dir failed, app:{}, ver:{}, err:{}",
+                    app_id, ver, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn unpin_app_web_dir(&self, app_id: &DecAppId, ver: &str) -> BuckyResult<()> {
+        let req = UtilUnpinAppWebDirOutputRequest::new(app_id.object_id().to_owned(), ver);
+        match self.shared_stack.util().unpin_app_web_dir(req).await {
+            Ok(resp) => {
+                info!(
+                    "unpin app web dir success, app:{}, ver:{}, {}",
+                    app_id, ver, resp
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!(
+                    "unpin app web dir failed, app:{}, ver:{}, err:{}",
+                    app_id, ver, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    // 记录到zone事件时间线，失败不影响app操作
+    pub async fn post_zone_event(
+        &self,
+        category: ZoneEventCategory,
+        app_id: &DecAppId,
+        detail: String,
+    ) -> BuckyResult<()> {
+        let event = ZoneEvent::create(
+            Some(self.owner.clone()),
+            category,
+            self.shared_stack.local_device_id(),
+            Some(app_id.object_id().to_owned()),
+            detail,
+        );
+
+        let mut req = NONPostObjectOutputRequest::new_router(
+            None,
+            event.desc().calculate_id(),
+            event.to_vec()?,
+        );
+        req.common.req_path = Some(CYFS_SYSTEM_ZONE_EVENT_VIRTUAL_PATH.to_owned());
+
+        match self.shared_stack.non_service().post_object(req).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == BuckyErrorCode::Ok => Ok(()),
+            Err(e) => {
+                warn!(
+                    "post zone event failed, app:{}, category:{}, err:{}",
+                    app_id, category, e
+                );
+                Err(e)
+            }
+        }
+    }
+
     pub async fn register_app_name(&self, app_name: &str, app_id: &DecAppId) -> BuckyResult<()> {
         let app_name_path = Self::get_app_name_register_path(app_name);
         if let Err(e) = self.store_on_map(&app_name_path, app_id.object_id()).await {
@@ -48,6 +48,7 @@ impl ControllerImpl {
         }
 
         let check_status = HashMap::new();
+        let restore_controller = RestoreController::new(bind_state.clone());
 
         Self {
             desc_file,
@@ -64,7 +65,7 @@ impl ControllerImpl {
 
             external_servers: Mutex::new(vec![]),
 
-            restore_controller: RestoreController::new(),
+            restore_controller,
         }
     }
 
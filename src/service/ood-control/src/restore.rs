@@ -2,17 +2,21 @@ use cyfs_backup::*;
 use cyfs_backup_lib::*;
 use cyfs_base::*;
 use cyfs_lib::RequestorHelper;
+use super::bind::BindState;
 
+use std::sync::Arc;
 use tide::{Request, Response, StatusCode};
 
 pub struct RestoreController {
-    restore_manager: RemoteRestoreManager,
+    restore_manager: RemoteRestoreManagerRef,
+    bind_state: BindState,
 }
 
 impl RestoreController {
-    pub fn new() -> Self {
+    pub(super) fn new(bind_state: BindState) -> Self {
         Self {
-            restore_manager: RemoteRestoreManager::new(),
+            restore_manager: Arc::new(RemoteRestoreManager::new()),
+            bind_state,
         }
     }
 
@@ -44,7 +48,35 @@ impl RestoreController {
             return Err(BuckyError::new(BuckyErrorCode::AlreadyExists, msg));
         }
 
-        self.restore_manager.start_remote_restore(params)
+        if !params.bare_metal {
+            return self.restore_manager.start_remote_restore(params);
+        }
+
+        // Bare metal restore will restore the device identity at the end, so reload the bind state once it's complete,
+        // and the device will be activated and join the zone immediately without waiting for the bind monitor
+        let restore_manager = self.restore_manager.clone();
+        let bind_state = self.bind_state.clone();
+        async_std::task::spawn(async move {
+            let id = params.id.clone();
+            match restore_manager.run_remote_restore(params).await {
+                Ok(()) => {
+                    if bind_state.is_bind() {
+                        info!("bare metal restore complete, and device already bind! task={}", id);
+                        return;
+                    }
+
+                    info!("bare metal restore complete, now will reload bind state! task={}", id);
+                    if let Err(e) = bind_state.load() {
+                        error!("reload bind state after bare metal restore failed! task={}, {}", id, e);
+                    }
+                }
+                Err(e) => {
+                    error!("bare metal restore failed! task={}, {}", id, e);
+                }
+            }
+        });
+
+        Ok(())
     }
 
     pub fn process_get_remote_restore_task_status_request(
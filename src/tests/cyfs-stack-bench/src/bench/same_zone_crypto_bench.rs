@@ -98,6 +98,7 @@ impl SameZoneCryptoBench {
                 dec: Some(DEVICE_DEC_ID.clone()),
                 access: AccessPermissions::CallOnly as u8,
             }),
+            validity: None,
         };
 
         meta.add_access(item).await.unwrap();
@@ -84,6 +84,7 @@ impl SameZoneRmetaBench {
         let item = GlobalStatePathAccessItem {
             path: "/a/b".to_owned(),
             access: GlobalStatePathGroupAccess::Default(access.value()),
+            validity: None,
         };
     
         meta.add_access(item).await.unwrap();
@@ -97,6 +98,7 @@ impl SameZoneRmetaBench {
                 dec: Some(OOD_DEC_ID.clone()),
                 access: perm,
             }),
+            validity: None,
         };
     
         meta.add_access(item).await.unwrap();
@@ -110,6 +112,7 @@ impl SameZoneRmetaBench {
                 dec: Some(OOD_DEC_ID.clone()),
                 access: 0,
             }),
+            validity: None,
         };
     
         let ret = meta.remove_access(item).await.unwrap();
@@ -124,6 +127,7 @@ impl SameZoneRmetaBench {
                 dec: Some(OOD_DEC_ID.clone()),
                 access: 0,
             }),
+            validity: None,
         };
     
         let ret = meta.remove_access(item).await.unwrap();
@@ -141,6 +145,7 @@ impl SameZoneRmetaBench {
         let item = GlobalStatePathAccessItem {
             path: "/a/b".to_owned(),
             access: GlobalStatePathGroupAccess::Default(0),
+            validity: None,
         };
     
         let ret = meta.remove_access(item).await.unwrap();
@@ -172,6 +172,7 @@ impl TransBench {
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             owner: Default::default(),
 
@@ -221,6 +222,8 @@ impl TransBench {
                     level: NONAPILevel::NOC,
                     target: None,
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object_id: _dir_resp.object_id.clone(),
                 inner_path: None,
@@ -251,6 +254,7 @@ impl TransBench {
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             owner: Default::default(),
 
@@ -320,6 +324,7 @@ impl TransBench {
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             owner: Default::default(),
 
@@ -441,6 +446,7 @@ impl TransBench {
                     target: None,
                     referer_object: vec![],
                     flags: 0,
+                    deadline: None,
                 },
                 task_id: task_id.clone(),
             };
@@ -488,6 +494,7 @@ impl TransBench {
                 },
                 task_status: Some(TransTaskStatus::Finished),
                 range: Some((0, 10)),
+                page: None,
             })
             .await;
         if ret.is_err() {
@@ -533,6 +540,7 @@ impl TransBench {
                 },
                 task_status: Some(TransTaskStatus::Finished),
                 range: Some((0, 10)),
+                page: None,
             })
             .await;
         if ret.is_err() {
@@ -660,6 +668,7 @@ impl TransBench {
                     target: None,
                     referer_object: vec![],
                     flags: 0,
+                    deadline: None,
                 },
                 task_id: task_id.clone(),
             };
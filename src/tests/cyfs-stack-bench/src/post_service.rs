@@ -259,6 +259,7 @@ impl EventListenerAsyncRoutine<RouterHandlerPostObjectRequest, RouterHandlerPost
                             target: None,
                             referer_object: vec![],
                             flags: 0,
+                            deadline: None,
                         },
                         owner: Default::default(),
                 
@@ -75,6 +75,7 @@ async fn test_handler() {
     let item = GlobalStatePathAccessItem {
         path: TEST_REQ_PATH.to_owned(),
         access: GlobalStatePathGroupAccess::Handler,
+        validity: None,
     };
 
     device1
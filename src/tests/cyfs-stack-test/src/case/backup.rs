@@ -38,6 +38,7 @@ pub async fn test() {
         isolate,
         archive: target_dir,
         password: Some(ProtectedPassword::new("123456")),
+        bare_metal: false,
     };
 
     service.restore_manager().run_uni_restore(params).await.unwrap();
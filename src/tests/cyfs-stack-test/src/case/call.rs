@@ -90,6 +90,7 @@ async fn zone_same_dec_call() {
     let item = GlobalStatePathAccessItem {
         path: call_path.to_owned(),
         access: GlobalStatePathGroupAccess::Default(access.value()),
+        validity: None,
     };
     device2
         .root_state_meta_stub(None, None)
@@ -199,6 +200,7 @@ async fn zone_diff_dec_call() {
     let item = GlobalStatePathAccessItem {
         path: call_path.to_owned(),
         access: GlobalStatePathGroupAccess::Default(access.value()),
+        validity: None,
     };
     device2
         .root_state_meta_stub(None, None)
@@ -261,6 +263,7 @@ async fn inter_zone_same_dec_call() {
     let item = GlobalStatePathAccessItem {
         path: call_path.to_owned(),
         access: GlobalStatePathGroupAccess::Default(access.value()),
+        validity: None,
     };
     device2
         .root_state_meta_stub(None, None)
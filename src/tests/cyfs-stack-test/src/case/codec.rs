@@ -41,6 +41,7 @@ pub async fn test() {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         task_id: "".to_string(),
         action: TransTaskControlAction::Start,
@@ -28,6 +28,7 @@ async fn publish_file(dec_id: &ObjectId) -> (DeviceId, FileId) {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -81,6 +82,7 @@ async fn test_ndn_get_by_context(dec_id: &ObjectId, target: DeviceId, file_id: F
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         context: root_context,
         access: Some(AccessString::dec_default()),
@@ -96,6 +98,7 @@ async fn test_ndn_get_by_context(dec_id: &ObjectId, target: DeviceId, file_id: F
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         context_path: Some("/root/".to_owned()),
         context_id: None,
@@ -111,6 +114,7 @@ async fn test_ndn_get_by_context(dec_id: &ObjectId, target: DeviceId, file_id: F
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         context_path: None,
         context_id: Some(context_id.clone()),
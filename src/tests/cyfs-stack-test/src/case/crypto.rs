@@ -405,6 +405,7 @@ async fn open_hook_access(stack: &SharedCyfsStack) {
             dec: Some(dec_id.clone()),
             access: AccessPermissions::CallOnly as u8,
         }),
+        validity: None,
     };
 
     meta.add_access(item).await.unwrap();
@@ -430,6 +431,7 @@ async fn open_access(stack: &SharedCyfsStack, dec_id: &ObjectId) {
             dec: Some(dec_id.clone()),
             access: AccessPermissions::CallOnly as u8,
         }),
+        validity: None,
     };
 
     meta.add_access(item).await.unwrap();
@@ -33,6 +33,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
         let item = GlobalStatePathAccessItem {
             path: "/a/b".to_owned(),
             access: GlobalStatePathGroupAccess::Default(access.value()),
+            validity: None,
         };
 
         if let Err(e) = meta.add_access(item).await {
@@ -48,6 +49,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
     let item = GlobalStatePathAccessItem {
         path: "/a/b".to_owned(),
         access: GlobalStatePathGroupAccess::Default(access.value()),
+        validity: None,
     };
 
     meta.add_access(item).await.unwrap();
@@ -62,6 +64,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
             dec: Some(other_dec.clone()),
             access: perm,
         }),
+        validity: None,
     };
 
     meta.add_access(item).await.unwrap();
@@ -75,6 +78,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
             dec: Some(other_dec.clone()),
             access: 0,
         }),
+        validity: None,
     };
 
     let ret = meta.remove_access(item).await.unwrap();
@@ -89,6 +93,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
             dec: Some(other_dec.clone()),
             access: 0,
         }),
+        validity: None,
     };
 
     let ret = meta.remove_access(item).await.unwrap();
@@ -106,6 +111,7 @@ async fn test_meta(stack: &SharedCyfsStack) {
     let item = GlobalStatePathAccessItem {
         path: "/a/b".to_owned(),
         access: GlobalStatePathGroupAccess::Default(0),
+        validity: None,
     };
 
     let ret = meta.remove_access(item).await.unwrap();
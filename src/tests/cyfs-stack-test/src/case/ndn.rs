@@ -74,6 +74,7 @@ async fn add_dir(dec_id: &ObjectId) -> (DirId, FileId, String, ChunkId) {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -461,6 +462,7 @@ async fn test_range_file(dec_id: &ObjectId) {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -581,6 +583,7 @@ pub async fn test_chunk_in_bundle() {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -647,6 +650,7 @@ pub async fn test_chunk_in_bundle() {
         let item = GlobalStatePathAccessItem {
             path: req_path.to_owned(),
             access: GlobalStatePathGroupAccess::Default(access.value()),
+            validity: None,
         };
 
         meta.add_access(item).await.unwrap();
@@ -177,6 +177,7 @@ async fn zone_get_with_req_path() {
     let item = GlobalStatePathAccessItem {
         path: path.to_owned(),
         access: GlobalStatePathGroupAccess::Default(access.value()),
+        validity: None,
     };
     device1
         .root_state_meta_stub(Some(ood1.local_device_id().object_id().to_owned()), None)
@@ -39,6 +39,7 @@ async fn add_dir(_dec_id: &ObjectId) -> (DirId, FileId) {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -10,6 +10,8 @@ pub async fn test() {
         selector: format!("obj_type == {}", ObjectTypeCode::File.to_u16()),
         access: GlobalStatePathGroupAccess::Default(AccessString::full_except_write().value()),
         depth: None,
+        versions: None,
+        encrypt: None,
     };
 
     // remove object meta access
@@ -33,6 +35,8 @@ pub async fn test() {
             selector: format!("obj_type == {}", ObjectTypeCode::File.to_u16()),
             access: GlobalStatePathGroupAccess::Default(AccessString::full_except_write().value()),
             depth: None,
+            versions: None,
+            encrypt: None,
         };
 
         meta.add_object_meta(item).await.unwrap();
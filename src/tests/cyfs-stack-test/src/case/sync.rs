@@ -153,6 +153,7 @@ async fn add_file(stack: &SharedCyfsStack) -> FileId {
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -157,6 +157,7 @@ async fn add_dir_impl(
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -207,6 +208,8 @@ async fn add_dir_impl(
                 level: NONAPILevel::NOC,
                 target: None,
                 flags: 0,
+                deadline: None,
+                request_id: None,
             },
             object_id: _dir_resp.object_id.clone(),
             inner_path: None,
@@ -237,6 +240,7 @@ async fn add_file_impl(
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -308,6 +312,7 @@ async fn add_file_impl2(
             target: None,
             referer_object: vec![],
             flags: 0,
+            deadline: None,
         },
         owner: USER1_DATA.get().unwrap().people_id.object_id().to_owned(),
 
@@ -431,6 +436,7 @@ async fn download_file_impl(
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             task_id: task_id.clone(),
         };
@@ -478,6 +484,7 @@ async fn download_file_impl(
             },
             task_status: Some(TransTaskStatus::Finished),
             range: Some((0, 10)),
+            page: None,
         })
         .await;
     if ret.is_err() {
@@ -522,6 +529,7 @@ async fn download_file_impl(
             },
             task_status: Some(TransTaskStatus::Finished),
             range: Some((0, 10)),
+            page: None,
         })
         .await;
     if ret.is_err() {
@@ -697,6 +705,7 @@ async fn download_chunk(chunk_id: ChunkId, chunk: Vec<u8>, device_id: DeviceId)
                 target: None,
                 referer_object: vec![],
                 flags: 0,
+                deadline: None,
             },
             task_id: task_id.clone(),
         };
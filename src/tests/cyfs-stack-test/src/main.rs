@@ -68,12 +68,15 @@ mod main_tests {
                     level: NONAPILevel::Router,
                     target: None,
                     flags: 0,
+                    deadline: None,
+                    request_id: None,
                 },
                 object: NONObjectInfo {
                     object_id: obj.desc().object_id().clone(),
                     object_raw: obj.to_vec().unwrap(),
                     object: None,
                 },
+                precondition: None,
             })
             .await
             .unwrap();
@@ -0,0 +1,265 @@
+use cyfs_base::*;
+use cyfs_lib::*;
+
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// 轮询直到返回Some或者超时
+// 超时使用真实时间，不受虚拟时钟影响
+pub async fn wait_for<T, F, Fut>(timeout: Duration, interval: Duration, mut check: F) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(v) = check().await {
+            return Some(v);
+        }
+
+        if Instant::now() >= deadline {
+            return None;
+        }
+
+        async_std::task::sleep(interval).await;
+    }
+}
+
+pub async fn wait_until<F, Fut>(timeout: Duration, interval: Duration, mut check: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    wait_for(timeout, interval, || {
+        let fut = check();
+        async move {
+            match fut.await {
+                true => Some(()),
+                false => None,
+            }
+        }
+    })
+    .await
+    .is_some()
+}
+
+// sync: 等待对象出现在指定协议栈的noc里
+pub async fn wait_object(
+    stack: &SharedCyfsStack,
+    object_id: &ObjectId,
+    timeout: Duration,
+) -> BuckyResult<NONObjectInfo> {
+    let ret = wait_for(timeout, Duration::from_millis(500), || {
+        let req = NONGetObjectOutputRequest::new_noc(object_id.to_owned(), None);
+        let stack = stack.clone();
+        async move {
+            match stack.non_service().get_object(req).await {
+                Ok(resp) => Some(resp.object),
+                Err(_) => None,
+            }
+        }
+    })
+    .await;
+
+    if ret.is_none() {
+        let msg = format!(
+            "wait object timeout! device={}, obj={}, timeout={:?}",
+            stack.local_device_id(),
+            object_id,
+            timeout
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+    }
+
+    Ok(ret.unwrap())
+}
+
+// sync: 等待从ood的全局状态同步到和主ood一致
+pub async fn wait_root_synced(
+    ood: &SharedCyfsStack,
+    standby_ood: &SharedCyfsStack,
+    timeout: Duration,
+) -> BuckyResult<(ObjectId, u64)> {
+    let (root, revision) = ood.root_state_stub(None, None).get_current_root().await?;
+
+    let ret = wait_until(timeout, Duration::from_secs(1), || {
+        let stub = standby_ood.root_state_stub(None, None);
+        let root = root.clone();
+        async move {
+            match stub.get_current_root().await {
+                Ok((current, current_revision)) => current == root || current_revision > revision,
+                Err(_) => false,
+            }
+        }
+    })
+    .await;
+
+    if !ret {
+        let msg = format!(
+            "wait standby ood root state sync timeout! root={}, revision={}, timeout={:?}",
+            root, revision, timeout
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+    }
+
+    info!(
+        "standby ood root state synced! root={}, revision={}",
+        root, revision
+    );
+
+    Ok((root, revision))
+}
+
+// acl: 从指定协议栈通过router去目标设备上获取对象，检查权限是否符合预期
+pub async fn assert_object_access(
+    stack: &SharedCyfsStack,
+    target: Option<ObjectId>,
+    object_id: &ObjectId,
+    expect_allowed: bool,
+) {
+    let req = NONGetObjectOutputRequest::new_router(target.clone(), object_id.to_owned(), None);
+    let ret = stack.non_service().get_object(req).await;
+
+    if expect_allowed {
+        if let Err(e) = ret {
+            error!(
+                "get object should be allowed! device={}, target={:?}, obj={}, {}",
+                stack.local_device_id(),
+                target,
+                object_id,
+                e
+            );
+            unreachable!();
+        }
+    } else {
+        assert_permission_denied(ret);
+    }
+}
+
+pub fn assert_permission_denied<T>(ret: BuckyResult<T>) {
+    match ret {
+        Ok(_) => {
+            error!("request should be rejected by acl!");
+            unreachable!();
+        }
+        Err(e) => {
+            assert_eq!(e.code(), BuckyErrorCode::PermissionDenied, "{}", e);
+        }
+    }
+}
+
+// ndn: 从源设备下载文件到本地，等待任务完成后和源文件比较hash
+pub async fn download_and_verify(
+    stack: &SharedCyfsStack,
+    dec_id: &ObjectId,
+    file_id: &FileId,
+    source: &DeviceId,
+    save_path: &Path,
+    source_path: &Path,
+    timeout: Duration,
+) -> BuckyResult<()> {
+    let mut common = NDNOutputRequestCommon::new(NDNAPILevel::Router);
+    common.dec_id = Some(dec_id.to_owned());
+
+    let req = TransCreateTaskOutputRequest {
+        common: common.clone(),
+        object_id: file_id.object_id().to_owned(),
+        local_path: save_path.to_owned(),
+        device_list: vec![source.to_owned()],
+        group: None,
+        context: None,
+        auto_start: true,
+    };
+
+    let task_id = stack.trans().create_task(req).await?.task_id;
+    info!(
+        "create download task success! file={}, task={}",
+        file_id, task_id
+    );
+
+    let state = wait_for(timeout, Duration::from_secs(1), || {
+        let req = TransGetTaskStateOutputRequest {
+            common: common.clone(),
+            task_id: task_id.clone(),
+        };
+        let stack = stack.clone();
+        async move {
+            match stack.trans().get_task_state(req).await {
+                Ok(resp) => match resp.state {
+                    TransTaskState::Finished(_) | TransTaskState::Err(_) => Some(resp.state),
+                    _ => None,
+                },
+                Err(_) => None,
+            }
+        }
+    })
+    .await;
+
+    if state.is_none() {
+        let msg = format!(
+            "wait download task timeout! file={}, task={}, timeout={:?}",
+            file_id, task_id, timeout
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::Timeout, msg));
+    }
+
+    if let Some(TransTaskState::Err(code)) = state {
+        let msg = format!(
+            "download task failed! file={}, task={}, code={:?}",
+            file_id, task_id, code
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(code, msg));
+    }
+
+    let (source_hash, source_len) = hash_file(source_path).await?;
+    let (hash, len) = hash_file(save_path).await?;
+    if source_hash != hash || source_len != len {
+        let msg = format!(
+            "downloaded file not match source! file={}, source={}, save={}",
+            file_id,
+            source_path.display(),
+            save_path.display()
+        );
+        error!("{}", msg);
+        return Err(BuckyError::new(BuckyErrorCode::Unmatch, msg));
+    }
+
+    info!(
+        "download and verify file success! file={}, path={}",
+        file_id,
+        save_path.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[async_std::test]
+    async fn test_wait_until() {
+        let mut count = 0;
+        let ret = wait_until(Duration::from_secs(5), Duration::from_millis(10), || {
+            count += 1;
+            let done = count >= 3;
+            async move { done }
+        })
+        .await;
+        assert!(ret);
+        assert_eq!(count, 3);
+
+        let ret = wait_until(
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+            || async { false },
+        )
+        .await;
+        assert!(!ret);
+    }
+}
@@ -0,0 +1,298 @@
+use super::zone::*;
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+
+use async_std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+struct AppFixture {
+    name: String,
+    version: String,
+    status: bool,
+}
+
+struct ObjectFixture {
+    id: String,
+    value: String,
+    access: Option<AccessString>,
+}
+
+struct FileFixture {
+    name: String,
+    size: usize,
+    role: TestDeviceRole,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppInfo {
+    pub dec_id: DecAppId,
+    pub app: DecApp,
+    pub status: AppStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct PublishedFile {
+    pub local_path: PathBuf,
+    pub file_id: FileId,
+
+    // 发布文件的设备，下载时作为源设备
+    pub device_id: DeviceId,
+}
+
+pub struct ZoneFixtures {
+    pub apps: Vec<AppInfo>,
+    pub app_list: Option<AppList>,
+    pub objects: Vec<(Text, ObjectId)>,
+    pub files: Vec<PublishedFile>,
+}
+
+// 在指定zone上准备测试数据：应用对象、普通对象和发布的文件
+// apps和objects都放到zone的ood上，files在指定的设备上发布
+pub struct ZoneFixtureBuilder {
+    index: TestZoneIndex,
+    dec_id: ObjectId,
+    apps: Vec<AppFixture>,
+    objects: Vec<ObjectFixture>,
+    files: Vec<FileFixture>,
+}
+
+impl ZoneFixtureBuilder {
+    pub fn new(index: TestZoneIndex, dec_id: ObjectId) -> Self {
+        Self {
+            index,
+            dec_id,
+            apps: vec![],
+            objects: vec![],
+            files: vec![],
+        }
+    }
+
+    pub fn app(mut self, name: &str, version: &str, status: bool) -> Self {
+        self.apps.push(AppFixture {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            status,
+        });
+        self
+    }
+
+    pub fn object(mut self, id: &str, value: &str, access: Option<AccessString>) -> Self {
+        self.objects.push(ObjectFixture {
+            id: id.to_owned(),
+            value: value.to_owned(),
+            access,
+        });
+        self
+    }
+
+    pub fn file(mut self, name: &str, size: usize, role: TestDeviceRole) -> Self {
+        self.files.push(FileFixture {
+            name: name.to_owned(),
+            size,
+            role,
+        });
+        self
+    }
+
+    pub async fn build(self) -> BuckyResult<ZoneFixtures> {
+        let zone = TestHarness::get().zone(self.index);
+        let ood = zone.ood();
+        let target = Some(zone.device_id(TestDeviceRole::OOD).object_id().to_owned());
+
+        let mut apps = vec![];
+        let mut app_list = None;
+        if !self.apps.is_empty() {
+            let mut list = AppList::create(zone.owner(), "", APPLIST_APP_CATEGORY);
+            for item in &self.apps {
+                let app = DecApp::create(zone.owner(), &item.name);
+                let dec_id = DecAppId::try_from(app.desc().calculate_id()).unwrap();
+                let status = AppStatus::create(
+                    zone.owner(),
+                    dec_id.clone(),
+                    item.version.clone(),
+                    item.status,
+                );
+
+                put_object(
+                    &ood,
+                    target.clone(),
+                    app.desc().calculate_id(),
+                    app.to_vec()?,
+                    None,
+                )
+                .await?;
+                put_object(
+                    &ood,
+                    target.clone(),
+                    status.desc().calculate_id(),
+                    status.to_vec()?,
+                    None,
+                )
+                .await?;
+
+                list.put(status.clone());
+                apps.push(AppInfo {
+                    dec_id,
+                    app,
+                    status,
+                });
+            }
+
+            put_object(
+                &ood,
+                target.clone(),
+                list.desc().calculate_id(),
+                list.to_vec()?,
+                None,
+            )
+            .await?;
+            info!(
+                "put app list fixture to ood success! zone={:?}, list={}, count={}",
+                self.index,
+                list.desc().calculate_id(),
+                apps.len()
+            );
+            app_list = Some(list);
+        }
+
+        let mut objects = vec![];
+        for item in self.objects {
+            let object = Text::build(&item.id, "test_fixture", item.value)
+                .no_create_time()
+                .owner(zone.owner())
+                .dec_id(self.dec_id.clone())
+                .build();
+            let object_id = object.desc().calculate_id();
+
+            put_object(
+                &ood,
+                target.clone(),
+                object_id.clone(),
+                object.to_vec()?,
+                item.access,
+            )
+            .await?;
+            objects.push((object, object_id));
+        }
+
+        let mut files = vec![];
+        if !self.files.is_empty() {
+            let data_dir = cyfs_util::get_app_data_dir("cyfs-test-support").join("fixture");
+            std::fs::create_dir_all(&data_dir).map_err(|e| {
+                let msg = format!(
+                    "create fixture dir failed! dir={}, {}",
+                    data_dir.display(),
+                    e
+                );
+                error!("{}", msg);
+                BuckyError::new(BuckyErrorCode::IoError, msg)
+            })?;
+
+            for item in self.files {
+                let local_path = data_dir.join(&item.name);
+                gen_random_file(&local_path, item.size).await?;
+
+                let stack = zone.stack(item.role);
+                let file_id = publish_file(&stack, &self.dec_id, zone.owner(), &local_path).await?;
+                files.push(PublishedFile {
+                    local_path,
+                    file_id,
+                    device_id: stack.local_device_id(),
+                });
+            }
+        }
+
+        Ok(ZoneFixtures {
+            apps,
+            app_list,
+            objects,
+            files,
+        })
+    }
+}
+
+async fn put_object(
+    stack: &SharedCyfsStack,
+    target: Option<ObjectId>,
+    object_id: ObjectId,
+    object_raw: Vec<u8>,
+    access: Option<AccessString>,
+) -> BuckyResult<()> {
+    let mut req = NONPutObjectOutputRequest::new_router(target, object_id.clone(), object_raw);
+    req.access = access;
+
+    stack.non_service().put_object(req).await.map_err(|e| {
+        error!("put fixture object failed! obj={}, {}", object_id, e);
+        e
+    })?;
+
+    Ok(())
+}
+
+pub async fn gen_random_file(local_path: &Path, size: usize) -> BuckyResult<()> {
+    let mut f = async_std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(local_path)
+        .await
+        .map_err(|e| {
+            let msg = format!(
+                "create fixture file failed! file={}, {}",
+                local_path.display(),
+                e
+            );
+            error!("{}", msg);
+            BuckyError::new(BuckyErrorCode::IoError, msg)
+        })?;
+
+    let buf: Vec<u8> = (0..1024 * 64).map(|_| rand::random::<u8>()).collect();
+    let mut left = size;
+    while left > 0 {
+        let len = std::cmp::min(left, buf.len());
+        f.write_all(&buf[..len]).await?;
+        left -= len;
+    }
+    f.flush().await?;
+
+    Ok(())
+}
+
+pub async fn publish_file(
+    stack: &SharedCyfsStack,
+    dec_id: &ObjectId,
+    owner: ObjectId,
+    local_path: &Path,
+) -> BuckyResult<FileId> {
+    let mut common = NDNOutputRequestCommon::new(NDNAPILevel::Router);
+    common.dec_id = Some(dec_id.to_owned());
+
+    let req = TransPublishFileOutputRequest {
+        common,
+        owner,
+        local_path: local_path.to_owned(),
+        chunk_size: 1024 * 1024 * 4,
+        chunk_method: TransPublishChunkMethod::Track,
+        access: None,
+        file_id: None,
+        dirs: None,
+    };
+
+    let resp = stack.trans().publish_file(req).await.map_err(|e| {
+        error!(
+            "publish fixture file failed! file={}, {}",
+            local_path.display(),
+            e
+        );
+        e
+    })?;
+
+    info!(
+        "publish fixture file success! file={}, id={}",
+        local_path.display(),
+        resp.file_id
+    );
+
+    FileId::try_from(resp.file_id)
+}
@@ -0,0 +1,10 @@
+mod assert;
+mod fixture;
+mod zone;
+
+#[macro_use]
+extern crate log;
+
+pub use assert::*;
+pub use fixture::*;
+pub use zone::*;
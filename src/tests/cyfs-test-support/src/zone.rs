@@ -0,0 +1,216 @@
+use cyfs_base::*;
+use cyfs_core::*;
+use cyfs_lib::*;
+use zone_simulator::*;
+
+use async_std::sync::Mutex as AsyncMutex;
+use once_cell::sync::{Lazy, OnceCell};
+
+#[derive(Debug, Clone)]
+pub struct TestHarnessConfig {
+    // 生成两个zone使用的助记词，为空时使用zone-simulator保存在本地的助记词
+    pub mnemonic: Option<String>,
+
+    pub stack: CyfsStackInsConfig,
+
+    // 使用虚拟时钟，协议栈内的定时器和超时由测试推进
+    pub virtual_time: bool,
+}
+
+impl Default for TestHarnessConfig {
+    fn default() -> Self {
+        Self {
+            mnemonic: None,
+            stack: CyfsStackInsConfig::default(),
+            virtual_time: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TestZoneIndex {
+    // ood + standby ood + 两个runtime
+    Zone1,
+
+    // 单ood + 两个runtime
+    Zone2,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TestDeviceRole {
+    OOD,
+    StandbyOOD,
+    Runtime1,
+    Runtime2,
+}
+
+// 一个zone内的people和所有设备的协议栈
+pub struct TestZone {
+    index: TestZoneIndex,
+    user: &'static TestUser,
+}
+
+impl TestZone {
+    fn new(index: TestZoneIndex) -> Self {
+        let user = TestLoader::get_user(Self::device_index(index, TestDeviceRole::OOD).unwrap());
+        Self { index, user }
+    }
+
+    fn device_index(index: TestZoneIndex, role: TestDeviceRole) -> Option<DeviceIndex> {
+        let ret = match (index, role) {
+            (TestZoneIndex::Zone1, TestDeviceRole::OOD) => DeviceIndex::User1OOD,
+            (TestZoneIndex::Zone1, TestDeviceRole::StandbyOOD) => DeviceIndex::User1StandbyOOD,
+            (TestZoneIndex::Zone1, TestDeviceRole::Runtime1) => DeviceIndex::User1Device1,
+            (TestZoneIndex::Zone1, TestDeviceRole::Runtime2) => DeviceIndex::User1Device2,
+
+            (TestZoneIndex::Zone2, TestDeviceRole::OOD) => DeviceIndex::User2OOD,
+            (TestZoneIndex::Zone2, TestDeviceRole::StandbyOOD) => return None,
+            (TestZoneIndex::Zone2, TestDeviceRole::Runtime1) => DeviceIndex::User2Device1,
+            (TestZoneIndex::Zone2, TestDeviceRole::Runtime2) => DeviceIndex::User2Device2,
+        };
+
+        Some(ret)
+    }
+
+    pub fn index(&self) -> TestZoneIndex {
+        self.index
+    }
+
+    pub fn user(&self) -> &TestUser {
+        self.user
+    }
+
+    pub fn people_id(&self) -> PeopleId {
+        self.user.people.desc().people_id()
+    }
+
+    pub fn owner(&self) -> ObjectId {
+        self.user.people.desc().calculate_id()
+    }
+
+    pub fn has_standby_ood(&self) -> bool {
+        self.user.standby_ood.is_some()
+    }
+
+    pub fn roles(&self) -> Vec<TestDeviceRole> {
+        let mut list = vec![TestDeviceRole::OOD];
+        if self.has_standby_ood() {
+            list.push(TestDeviceRole::StandbyOOD);
+        }
+        list.push(TestDeviceRole::Runtime1);
+        list.push(TestDeviceRole::Runtime2);
+        list
+    }
+
+    pub fn device_id(&self, role: TestDeviceRole) -> DeviceId {
+        self.stack(role).local_device_id()
+    }
+
+    pub fn stack(&self, role: TestDeviceRole) -> SharedCyfsStack {
+        TestLoader::get_shared_stack(self.expect_device_index(role))
+    }
+
+    pub fn ood(&self) -> SharedCyfsStack {
+        self.stack(TestDeviceRole::OOD)
+    }
+
+    pub fn standby_ood(&self) -> Option<SharedCyfsStack> {
+        Self::device_index(self.index, TestDeviceRole::StandbyOOD)
+            .map(|index| TestLoader::get_shared_stack(index))
+    }
+
+    pub fn runtime1(&self) -> SharedCyfsStack {
+        self.stack(TestDeviceRole::Runtime1)
+    }
+
+    pub fn runtime2(&self) -> SharedCyfsStack {
+        self.stack(TestDeviceRole::Runtime2)
+    }
+
+    fn expect_device_index(&self, role: TestDeviceRole) -> DeviceIndex {
+        match Self::device_index(self.index, role) {
+            Some(index) => index,
+            None => {
+                unreachable!(
+                    "zone has no such device! zone={:?}, role={:?}",
+                    self.index, role
+                );
+            }
+        }
+    }
+}
+
+// 协议栈在进程内全局加载，同一个进程里的所有用例共享同一组zone
+pub struct TestHarness {
+    clock: Option<VirtualClock>,
+}
+
+static TEST_HARNESS: OnceCell<TestHarness> = OnceCell::new();
+
+impl TestHarness {
+    // 只有第一次调用的配置生效
+    pub async fn init(config: TestHarnessConfig) -> &'static TestHarness {
+        static INIT_LOCK: Lazy<AsyncMutex<()>> = Lazy::new(|| AsyncMutex::new(()));
+        let _guard = INIT_LOCK.lock().await;
+
+        if let Some(harness) = TEST_HARNESS.get() {
+            return harness;
+        }
+
+        let clock = if config.virtual_time {
+            Some(TestLoader::enable_virtual_time())
+        } else {
+            None
+        };
+
+        match &config.mnemonic {
+            Some(mnemonic) => TEST_PROFILE.set_mnemonic(mnemonic),
+            None => TEST_PROFILE.load(),
+        }
+
+        TestLoader::load_default(&config.stack).await;
+        info!("init test harness zones complete!");
+
+        if let Err(_) = TEST_HARNESS.set(Self { clock }) {
+            unreachable!();
+        }
+
+        TEST_HARNESS.get().unwrap()
+    }
+
+    pub async fn init_default() -> &'static TestHarness {
+        Self::init(TestHarnessConfig::default()).await
+    }
+
+    pub fn get() -> &'static TestHarness {
+        TEST_HARNESS
+            .get()
+            .expect("test harness not initialized yet!")
+    }
+
+    pub fn clock(&self) -> Option<&VirtualClock> {
+        self.clock.as_ref()
+    }
+
+    pub fn zone(&self, index: TestZoneIndex) -> TestZone {
+        TestZone::new(index)
+    }
+
+    pub fn zone1(&self) -> TestZone {
+        self.zone(TestZoneIndex::Zone1)
+    }
+
+    pub fn zone2(&self) -> TestZone {
+        self.zone(TestZoneIndex::Zone2)
+    }
+
+    // 协议栈打开时使用的dec
+    pub fn dec_id(&self) -> &'static ObjectId {
+        TestLoader::get_dec_id()
+    }
+
+    // 用例自己的dec，owner是zone1的people
+    pub fn new_dec(&self, name: &str) -> ObjectId {
+        DecApp::generate_id(self.zone1().owner(), name)
+    }
+}
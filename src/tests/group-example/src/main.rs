@@ -76,7 +76,7 @@ mod group_dec_common {
     use cyfs_stack::{
         CyfsStack, CyfsStackConfigParams, CyfsStackFrontParams, CyfsStackInterfaceParams,
         CyfsStackKnownObjects, CyfsStackKnownObjectsInitMode, CyfsStackMetaParams,
-        CyfsStackNOCParams, CyfsStackParams,
+        CyfsStackNOCParams, CyfsStackParams, HttpCompressionConfig, HttpLimitsConfig,
     };
 
     // |--root
@@ -442,6 +442,7 @@ mod group_dec_common {
             udp_sn_only: None,
             sn_mode: SNMode::Normal,
             ping_interval: None,
+            session_resume: None,
         };
 
         let stack_param = CyfsStackParams {
@@ -450,8 +451,10 @@ mod group_dec_common {
                 sync_service: false,
                 shared_stack: true,
                 perf_service: false,
+                sign_key: None,
+                preserve_file_metadata: false,
             },
-            noc: CyfsStackNOCParams {},
+            noc: CyfsStackNOCParams::default(),
             interface: CyfsStackInterfaceParams {
                 bdt_listeners: vec![NON_STACK_BDT_VPORT, NON_STACK_SYNC_BDT_VPORT],
                 tcp_listeners: vec![SocketAddr::V4(SocketAddrV4::new(
@@ -462,6 +465,9 @@ mod group_dec_common {
                     std::net::Ipv4Addr::new(127, 0, 0, 1),
                     ws_port,
                 ))),
+                require_request_sign: false,
+                compression: HttpCompressionConfig::default(),
+                limits: HttpLimitsConfig::default(),
             },
             meta: CyfsStackMetaParams {
                 target: MetaMinerTarget::Dev,
@@ -1100,6 +1106,8 @@ mod group_dec_service {
                                 level: NONAPILevel::Router,
                                 target: Some(block.owner().clone()),
                                 flags: 0,
+                                deadline: None,
+                                request_id: None,
                             },
                             object_id: proposal_info.proposal,
                             inner_path: None,
@@ -1330,9 +1338,12 @@ async fn main_run() {
                 level: cyfs_lib::NONAPILevel::NOC,
                 target: None,
                 flags: 0,
+                deadline: None,
+                request_id: None,
             },
             object: NONObjectInfo::new(proposal.desc().object_id(), buf, Some(proposal_any)),
             access: Some(AccessString::full()),
+            precondition: None,
         };
         noc.put_object(req).await.expect("put proposal failed.");
         proposals.push(proposal);
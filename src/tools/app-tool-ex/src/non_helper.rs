@@ -98,6 +98,8 @@ impl NonHelper {
                     level: NONAPILevel::Router,
                     target,
                     flags: flag,
+                    deadline: None,
+                    request_id: None,
                 },
                 object_id: obj_id.clone(),
                 inner_path: None,
@@ -0,0 +1,444 @@
+use async_std::{
+    future,
+    io::prelude::{ReadExt, WriteExt},
+    stream::StreamExt,
+    task,
+};
+use serde::Serialize;
+use std::{
+    net::Shutdown,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use cyfs_base::*;
+use cyfs_bdt::*;
+use log::*;
+
+const BENCH_QUESTION_PREFIX: &str = "bench:";
+const BENCH_BLOCK_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BenchMode {
+    Upload,
+    Download,
+    Both,
+}
+
+impl BenchMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Upload => "upload",
+            Self::Download => "download",
+            Self::Both => "both",
+        }
+    }
+
+    fn upload(&self) -> bool {
+        *self != Self::Download
+    }
+
+    fn download(&self) -> bool {
+        *self != Self::Upload
+    }
+}
+
+impl FromStr for BenchMode {
+    type Err = BuckyError;
+
+    fn from_str(s: &str) -> BuckyResult<Self> {
+        match s {
+            "upload" => Ok(Self::Upload),
+            "download" => Ok(Self::Download),
+            "both" => Ok(Self::Both),
+            _ => Err(BuckyError::new(
+                BuckyErrorCode::InvalidParam,
+                format!("invalid bench mode {}, should be upload/download/both", s),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchConfig {
+    pub mode: BenchMode,
+    pub duration: Duration,
+    // 输出进度的间隔
+    pub interval: Duration,
+    // 测量rtt和丢包的ping间隔和超时
+    pub ping_interval: Duration,
+    pub ping_timeout: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchTraffic {
+    pub bytes: u64,
+    // byte/s
+    pub avg_speed: u64,
+    pub max_speed: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchRtt {
+    pub sent: u64,
+    pub received: u64,
+    pub loss_rate: f64,
+    // 单位ms
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    // 相邻两次rtt差值的平均值
+    pub jitter: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchSummary {
+    pub remote: String,
+    pub port: u16,
+    pub mode: String,
+    pub duration_ms: u64,
+    pub upload: Option<BenchTraffic>,
+    pub download: Option<BenchTraffic>,
+    pub rtt: BenchRtt,
+}
+
+#[derive(Default)]
+struct RttSamples {
+    sent: u64,
+    rtts: Vec<u64>,
+}
+
+impl RttSamples {
+    fn summary(&self) -> BenchRtt {
+        let received = self.rtts.len() as u64;
+        let to_ms = |us: u64| us as f64 / 1000.0;
+        let (min, avg, max) = if received > 0 {
+            (
+                to_ms(*self.rtts.iter().min().unwrap()),
+                to_ms(self.rtts.iter().sum::<u64>()) / received as f64,
+                to_ms(*self.rtts.iter().max().unwrap()),
+            )
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+        let jitter = if received > 1 {
+            let total: u64 = self
+                .rtts
+                .windows(2)
+                .map(|w| {
+                    if w[1] > w[0] {
+                        w[1] - w[0]
+                    } else {
+                        w[0] - w[1]
+                    }
+                })
+                .sum();
+            to_ms(total) / (received - 1) as f64
+        } else {
+            0.0
+        };
+
+        BenchRtt {
+            sent: self.sent,
+            received,
+            loss_rate: if self.sent > 0 {
+                (self.sent - received) as f64 / self.sent as f64
+            } else {
+                0.0
+            },
+            min,
+            avg,
+            max,
+            jitter,
+        }
+    }
+}
+
+fn speed_of(bytes: u64, elapsed: Duration) -> u64 {
+    let ms = elapsed.as_millis() as u64;
+    if ms == 0 {
+        0
+    } else {
+        bytes * 1000 / ms
+    }
+}
+
+fn format_speed(speed: u64) -> String {
+    format!("{:.2} Mbps", speed as f64 * 8.0 / 1000.0 / 1000.0)
+}
+
+// 被测端，upload模式下读取并丢弃数据，download模式下一直发送数据，直到对端关闭
+pub async fn bench_listen(stack: &Stack, port: u16) -> BuckyResult<()> {
+    let listener = stack.stream_manager().listen(port)?;
+    println!("bench listen on vport={}", port);
+
+    let mut incoming = listener.incoming();
+    while let Some(pre_stream) = incoming.next().await {
+        let pre_stream = match pre_stream {
+            Ok(pre_stream) => pre_stream,
+            Err(e) => {
+                error!("bench accept stream failed, err={}", e);
+                continue;
+            }
+        };
+
+        let question = String::from_utf8_lossy(&pre_stream.question).to_string();
+        let mode = match question
+            .strip_prefix(BENCH_QUESTION_PREFIX)
+            .ok_or_else(|| BuckyError::new(BuckyErrorCode::InvalidData, "not bench question"))
+            .and_then(|mode| BenchMode::from_str(mode))
+        {
+            Ok(mode) => mode,
+            Err(e) => {
+                println!("ignore stream for invalid question {}, err={}", question, e);
+                let _ = pre_stream.stream.shutdown(Shutdown::Both);
+                continue;
+            }
+        };
+
+        let stream = pre_stream.stream;
+        task::spawn(async move {
+            if let Err(e) = stream.confirm(b"ok").await {
+                println!("confirm bench stream {} failed, err={}", stream, e);
+                return;
+            }
+            println!("accept bench stream {} mode={}", stream, mode.as_str());
+
+            let start = Instant::now();
+            let mut received = 0;
+            let mut sent = 0;
+            if mode.download() {
+                let mut send_stream = stream.clone();
+                let send = task::spawn(async move {
+                    let buf = vec![0u8; BENCH_BLOCK_SIZE];
+                    let mut sent = 0u64;
+                    while let Ok(len) = send_stream.write(&buf).await {
+                        if len == 0 {
+                            break;
+                        }
+                        sent += len as u64;
+                    }
+                    sent
+                });
+                if mode.upload() {
+                    received = recv_all(&stream).await;
+                }
+                sent = send.await;
+            } else {
+                received = recv_all(&stream).await;
+            }
+            let _ = stream.shutdown(Shutdown::Both);
+
+            println!(
+                "bench stream {} finished, elapsed={:?}, received={}, sent={}",
+                stream,
+                start.elapsed(),
+                received,
+                sent
+            );
+        });
+    }
+
+    Ok(())
+}
+
+async fn recv_all(stream: &StreamGuard) -> u64 {
+    let mut stream = stream.clone();
+    let mut buf = vec![0u8; BENCH_BLOCK_SIZE];
+    let mut received = 0u64;
+    while let Ok(len) = stream.read(&mut buf).await {
+        if len == 0 {
+            break;
+        }
+        received += len as u64;
+    }
+    received
+}
+
+pub async fn bench(
+    stack: &Stack,
+    remote: Device,
+    port: u16,
+    config: BenchConfig,
+) -> BuckyResult<BenchSummary> {
+    let question = format!("{}{}", BENCH_QUESTION_PREFIX, config.mode.as_str());
+    let stream = stack
+        .stream_manager()
+        .connect(
+            port,
+            question.into_bytes(),
+            BuildTunnelParams {
+                remote_const: remote.desc().clone(),
+                remote_sn: None,
+                remote_desc: Some(remote.clone()),
+            },
+        )
+        .await?;
+    println!(
+        "connect vport={} success, bench {} for {:?}",
+        port,
+        config.mode.as_str(),
+        config.duration
+    );
+
+    let start = Instant::now();
+    let deadline = start + config.duration;
+    let uploaded = Arc::new(AtomicU64::new(0));
+    let downloaded = Arc::new(AtomicU64::new(0));
+
+    let upload_task = if config.mode.upload() {
+        let mut stream = stream.clone();
+        let uploaded = uploaded.clone();
+        Some(task::spawn(async move {
+            let buf = vec![0u8; BENCH_BLOCK_SIZE];
+            loop {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                match future::timeout(deadline - now, stream.write(&buf)).await {
+                    Ok(Ok(len)) if len > 0 => {
+                        uploaded.fetch_add(len as u64, Ordering::SeqCst);
+                    }
+                    Ok(Err(e)) => {
+                        println!("bench upload break, err={}", e);
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    let download_task = if config.mode.download() {
+        let mut stream = stream.clone();
+        let downloaded = downloaded.clone();
+        Some(task::spawn(async move {
+            let mut buf = vec![0u8; BENCH_BLOCK_SIZE];
+            loop {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                match future::timeout(deadline - now, stream.read(&mut buf)).await {
+                    Ok(Ok(len)) if len > 0 => {
+                        downloaded.fetch_add(len as u64, Ordering::SeqCst);
+                    }
+                    Ok(Err(e)) => {
+                        println!("bench download break, err={}", e);
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // 传输的同时用ping测量负载下的rtt、抖动和丢包
+    let samples = Arc::new(Mutex::new(RttSamples::default()));
+    let ping_task = {
+        let pinger = cyfs_bdt::debug::Pinger::open(stack.to_weak())?;
+        let samples = samples.clone();
+        let remote = remote.clone();
+        let config = config.clone();
+        task::spawn(async move {
+            while Instant::now() + config.ping_timeout < deadline {
+                let begin = Instant::now();
+                let ret = pinger
+                    .ping(remote.clone(), config.ping_timeout, "bench".as_ref())
+                    .await;
+                {
+                    let mut samples = samples.lock().unwrap();
+                    samples.sent += 1;
+                    if let Ok(Some(rtt)) = ret {
+                        samples.rtts.push(rtt);
+                    }
+                }
+                let cost = begin.elapsed();
+                if cost < config.ping_interval {
+                    task::sleep(config.ping_interval - cost).await;
+                }
+            }
+        })
+    };
+
+    let mut max_upload_speed = 0;
+    let mut max_download_speed = 0;
+    let mut last = (start, 0, 0);
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        task::sleep(std::cmp::min(config.interval, deadline - now)).await;
+
+        let now = Instant::now();
+        let up = uploaded.load(Ordering::SeqCst);
+        let down = downloaded.load(Ordering::SeqCst);
+        let up_speed = speed_of(up - last.1, now - last.0);
+        let down_speed = speed_of(down - last.2, now - last.0);
+        max_upload_speed = std::cmp::max(max_upload_speed, up_speed);
+        max_download_speed = std::cmp::max(max_download_speed, down_speed);
+
+        let rtt = samples.lock().unwrap().summary();
+        println!(
+            "[{:>6.1}s] upload {} ({} bytes), download {} ({} bytes), rtt avg {:.2} ms, jitter {:.2} ms, loss {}/{}",
+            (now - start).as_secs_f64(),
+            format_speed(up_speed),
+            up,
+            format_speed(down_speed),
+            down,
+            rtt.avg,
+            rtt.jitter,
+            rtt.sent - rtt.received,
+            rtt.sent
+        );
+        last = (now, up, down);
+    }
+
+    if let Some(task) = upload_task {
+        task.await;
+    }
+    if let Some(task) = download_task {
+        task.await;
+    }
+    ping_task.await;
+    let _ = stream.shutdown(Shutdown::Both);
+
+    let elapsed = start.elapsed();
+    let traffic = |bytes: u64, max_speed: u64| BenchTraffic {
+        bytes,
+        avg_speed: speed_of(bytes, elapsed),
+        max_speed,
+    };
+    let summary = BenchSummary {
+        remote: remote.desc().device_id().to_string(),
+        port,
+        mode: config.mode.as_str().to_owned(),
+        duration_ms: elapsed.as_millis() as u64,
+        upload: if config.mode.upload() {
+            Some(traffic(uploaded.load(Ordering::SeqCst), max_upload_speed))
+        } else {
+            None
+        },
+        download: if config.mode.download() {
+            Some(traffic(
+                downloaded.load(Ordering::SeqCst),
+                max_download_speed,
+            ))
+        } else {
+            None
+        },
+        rtt: samples.lock().unwrap().summary(),
+    };
+
+    Ok(summary)
+}
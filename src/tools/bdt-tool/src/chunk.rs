@@ -0,0 +1,253 @@
+use async_std::{
+    future,
+    io::prelude::{ReadExt, WriteExt},
+    stream::StreamExt,
+    task,
+};
+use rand::Rng;
+use std::{
+    net::Shutdown,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use cyfs_base::*;
+use cyfs_bdt::*;
+use log::*;
+
+const CHUNK_QUESTION_PREFIX: &str = "chunk:";
+
+fn format_speed(speed: u64) -> String {
+    format!("{:.2} Mbps", speed as f64 * 8.0 / 1000.0 / 1000.0)
+}
+
+// 生成随机内容的chunk，放到本地的chunk store里，其他节点可以从这里下载
+pub async fn gen_random_chunk(store: &MemChunkStore, size: usize) -> BuckyResult<ChunkId> {
+    let mut data = vec![0u8; size];
+    rand::thread_rng().fill(&mut data[..]);
+    let chunk_id = ChunkId::calculate(&data).await?;
+    store.add(chunk_id.clone(), Arc::new(data)).await?;
+
+    Ok(chunk_id)
+}
+
+// 从remote下载chunk，每秒输出一次速度，完成后校验内容
+pub async fn download(
+    stack: &Stack,
+    remote: &DeviceDesc,
+    chunk_id: &ChunkId,
+    timeout: Duration,
+) -> BuckyResult<Duration> {
+    let context = SingleSourceContext::from_desc("bdt-tool".to_owned(), remote.clone());
+    let (path, mut reader) = download_chunk(stack, chunk_id.clone(), None, context).await?;
+    info!(
+        "download chunk task created, chunk={}, path={}",
+        chunk_id, path
+    );
+
+    let start = Instant::now();
+    let received = Arc::new(AtomicU64::new(0));
+    let progress = {
+        let received = received.clone();
+        let total = chunk_id.len() as u64;
+        task::spawn(async move {
+            let mut last = 0;
+            loop {
+                task::sleep(Duration::from_secs(1)).await;
+                let cur = received.load(Ordering::SeqCst);
+                println!(
+                    "[{:>6.1}s] download {} ({}/{} bytes)",
+                    start.elapsed().as_secs_f64(),
+                    format_speed(cur - last),
+                    cur,
+                    total
+                );
+                last = cur;
+            }
+        })
+    };
+
+    let ret = future::timeout(timeout, async {
+        let mut data = Vec::with_capacity(chunk_id.len());
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let len = reader.read(&mut buf).await?;
+            if len == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..len]);
+            received.fetch_add(len as u64, Ordering::SeqCst);
+        }
+        Ok::<Vec<u8>, BuckyError>(data)
+    })
+    .await;
+    progress.cancel().await;
+
+    let data = ret.map_err(|_| {
+        BuckyError::new(
+            BuckyErrorCode::Timeout,
+            format!("download chunk {} timeout", chunk_id),
+        )
+    })??;
+    let elapsed = start.elapsed();
+
+    let recv_id = ChunkId::calculate(&data).await?;
+    if recv_id != *chunk_id {
+        return Err(BuckyError::new(
+            BuckyErrorCode::InvalidData,
+            format!(
+                "downloaded chunk unmatch, expect {}, got {}",
+                chunk_id, recv_id
+            ),
+        ));
+    }
+
+    Ok(elapsed)
+}
+
+// 接收端：发送端通过stream告知chunk id，这里从发送端下载后回复耗时
+pub async fn listen(stack: &Stack, port: u16, timeout: Duration) -> BuckyResult<()> {
+    let listener = stack.stream_manager().listen(port)?;
+    println!("chunk listen on vport={}", port);
+
+    let mut incoming = listener.incoming();
+    while let Some(pre_stream) = incoming.next().await {
+        let pre_stream = match pre_stream {
+            Ok(pre_stream) => pre_stream,
+            Err(e) => {
+                error!("chunk accept stream failed, err={}", e);
+                continue;
+            }
+        };
+
+        let question = String::from_utf8_lossy(&pre_stream.question).to_string();
+        let chunk_id = match question
+            .strip_prefix(CHUNK_QUESTION_PREFIX)
+            .ok_or_else(|| BuckyError::new(BuckyErrorCode::InvalidData, "not chunk question"))
+            .and_then(|id| ChunkId::from_str(id))
+        {
+            Ok(chunk_id) => chunk_id,
+            Err(e) => {
+                println!("ignore stream for invalid question {}, err={}", question, e);
+                let _ = pre_stream.stream.shutdown(Shutdown::Both);
+                continue;
+            }
+        };
+
+        let stack = stack.clone();
+        let mut stream = pre_stream.stream;
+        task::spawn(async move {
+            let remote = stream.remote().0.clone();
+            let remote = match stack.device_cache().get(&remote).await {
+                Some(device) => device,
+                None => {
+                    println!("ignore chunk {} for remote {} not cached", chunk_id, remote);
+                    let _ = stream.shutdown(Shutdown::Both);
+                    return;
+                }
+            };
+            if let Err(e) = stream.confirm(b"ok").await {
+                println!("confirm chunk stream failed, err={}", e);
+                return;
+            }
+
+            println!(
+                "will download chunk {} from {}",
+                chunk_id,
+                remote.desc().device_id()
+            );
+            let answer = match download(&stack, remote.desc(), &chunk_id, timeout).await {
+                Ok(elapsed) => {
+                    println!("download chunk {} success, elapsed={:?}", chunk_id, elapsed);
+                    format!("ok:{}", elapsed.as_millis())
+                }
+                Err(e) => {
+                    println!("download chunk {} failed, err={}", chunk_id, e);
+                    format!("err:{}", e)
+                }
+            };
+            let _ = stream.write_all(answer.as_bytes()).await;
+            let _ = stream.shutdown(Shutdown::Write);
+        });
+    }
+
+    Ok(())
+}
+
+// 发送端：生成随机chunk后通知remote来下载，等待remote回复结果
+pub async fn upload(
+    stack: &Stack,
+    store: &MemChunkStore,
+    remote: &Device,
+    port: u16,
+    size: usize,
+    timeout: Duration,
+) -> BuckyResult<Duration> {
+    let chunk_id = gen_random_chunk(store, size).await?;
+    println!("generate chunk {} size={}", chunk_id, size);
+
+    let question = format!("{}{}", CHUNK_QUESTION_PREFIX, chunk_id);
+    let mut stream = stack
+        .stream_manager()
+        .connect(
+            port,
+            question.into_bytes(),
+            BuildTunnelParams {
+                remote_const: remote.desc().clone(),
+                remote_sn: None,
+                remote_desc: Some(remote.clone()),
+            },
+        )
+        .await?;
+
+    let start = Instant::now();
+    let remote_id = remote.desc().device_id();
+    let progress = {
+        let stack = stack.clone();
+        task::spawn(async move {
+            loop {
+                task::sleep(Duration::from_secs(1)).await;
+                let speed = stack
+                    .ndn()
+                    .channel_manager()
+                    .channel_of(&remote_id)
+                    .map(|channel| channel.upload_cur_speed())
+                    .unwrap_or(0);
+                println!(
+                    "[{:>6.1}s] upload {}",
+                    start.elapsed().as_secs_f64(),
+                    format_speed(speed as u64)
+                );
+            }
+        })
+    };
+
+    let mut answer = vec![];
+    let ret = future::timeout(timeout, stream.read_to_end(&mut answer)).await;
+    progress.cancel().await;
+    let _ = stream.shutdown(Shutdown::Both);
+
+    match ret {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Err(e.into()),
+        Err(_) => {
+            return Err(BuckyError::new(
+                BuckyErrorCode::Timeout,
+                format!("wait remote download chunk {} timeout", chunk_id),
+            ))
+        }
+    }
+
+    let answer = String::from_utf8_lossy(&answer).to_string();
+    match answer.strip_prefix("ok:") {
+        Some(_) => Ok(start.elapsed()),
+        None => Err(BuckyError::new(
+            BuckyErrorCode::Failed,
+            format!("remote download chunk {} failed, {}", chunk_id, answer),
+        )),
+    }
+}
@@ -31,6 +31,10 @@ use log::*;
 
 mod sn_bench;
 use crate::sn_bench::*;
+mod pcapng;
+mod bench;
+mod chunk;
+mod trace;
 
 fn load_dev_by_path(path: &str) -> Option<Device> {
     let desc_path = Path::new(path);
@@ -58,6 +62,48 @@ fn load_dev_vec(path: &str) -> Option<Vec<Device>> {
     }
 }
 
+// 从dir下的deamon.desc/deamon.sec加载固定的device，不存在时生成一个新的保存下来，
+// 这样多次运行时使用同一个身份，sn上的在线状态和对端的缓存才有意义
+fn load_or_create_device(dir: &Path, sns: Option<Vec<Device>>, endpoints: Vec<Endpoint>) -> BuckyResult<(Device, PrivateKey)> {
+    let desc_path = dir.join("deamon.desc");
+    let sec_path = dir.join("deamon.sec");
+    if !sec_path.exists() {
+        if !dir.is_dir() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                BuckyError::new(BuckyErrorCode::IoError, format!("create dir {} failed for {}", dir.display(), e))
+            })?;
+        }
+
+        let (device, private_key) = create_device(sns, endpoints);
+        device.encode_to_file(&desc_path, false)?;
+        private_key.encode_to_file(&sec_path, false)?;
+        println!("generate new device {} to {}", device.desc().device_id(), dir.display());
+
+        return Ok((device, private_key));
+    }
+
+    let mut buf = vec![];
+    let (mut device, _) = Device::decode_from_file(&desc_path, &mut buf)?;
+    let mut buf = vec![];
+    let (private_key, _) = PrivateKey::decode_from_file(&sec_path, &mut buf)?;
+
+    if device.desc().public_key() != &private_key.public() {
+        return Err(BuckyError::new(BuckyErrorCode::Unmatch, format!("{} unmatch with {}", desc_path.display(), sec_path.display())));
+    }
+
+    // sn列表以本次指定的为准，endpoints在打开协议栈之前会被替换
+    if let Some(sns) = sns.as_ref() {
+        let sn_list = device.mut_connect_info().mut_sn_list();
+        sn_list.clear();
+        for sn in sns {
+            sn_list.push(sn.desc().device_id());
+        }
+    }
+    println!("load device {} from {}", device.desc().device_id(), dir.display());
+
+    Ok((device, private_key))
+}
+
 async fn init_raw_noc(
         isolate: &str,
         known_objects: CyfsStackKnownObjects,
@@ -91,6 +137,7 @@ async fn init_raw_noc(
                     context: None,
                     last_access_rpath: None,
                     access_string: None,
+                    precondition: None,
                 };
                 let _ = noc2.put_object(&req).await;
             }
@@ -222,8 +269,10 @@ pub fn command_line() -> clap::App<'static, 'static> {
         .arg(Arg::with_name("udp_sn_only").long("udp_sn_only").takes_value(false).default_value("0").help("udp sn only"))
         .arg(Arg::with_name("log_level").long("log_level").default_value("none").help("log level: none/info/debug/warn/error"))
         .arg(Arg::with_name("device_cache").long("device_cache").default_value("").help("device cache"))
+        .arg(Arg::with_name("device_dir").long("device-dir").takes_value(true).help("dir to load or create a persistent device identity (deamon.desc/deamon.sec), generate a random device every run if not set"))
         .arg(Arg::with_name("sn").long("sn").multiple(true).default_value("").help("sn desc file"))
         .arg(Arg::with_name("cmd").long("cmd").takes_value(false).help("sn desc file"))
+        .arg(Arg::with_name("pcapng").long("pcapng").takes_value(true).help("capture decrypted package headers to pcapng file"))
         .subcommand(SubCommand::with_name("ping")
             .arg(Arg::with_name("remote").required(true))
             .arg(Arg::with_name("count").required(true))
@@ -233,6 +282,41 @@ pub fn command_line() -> clap::App<'static, 'static> {
             .arg(Arg::with_name("remote").required(true))
             .arg(Arg::with_name("port").required(true))
         )
+        .subcommand(SubCommand::with_name("bench")
+            .arg(Arg::with_name("remote").required(true))
+            .arg(Arg::with_name("port").required(true))
+            .arg(Arg::with_name("mode").long("mode").default_value("both").help("bench mode: upload/download/both"))
+            .arg(Arg::with_name("duration").long("duration").default_value("10").help("bench duration in seconds"))
+            .arg(Arg::with_name("interval").long("interval").default_value("1").help("progress output interval in seconds"))
+            .arg(Arg::with_name("ping_interval").long("ping_interval").default_value("200").help("rtt probe interval in milliseconds"))
+        )
+        .subcommand(SubCommand::with_name("bench_listen")
+            .arg(Arg::with_name("port").required(true))
+        )
+        .subcommand(SubCommand::with_name("chunk")
+            .arg(Arg::with_name("timeout").long("timeout").default_value("300").help("transfer timeout in seconds"))
+            .subcommand(SubCommand::with_name("listen")
+                .arg(Arg::with_name("port").required(true))
+                .arg(Arg::with_name("size").long("size").takes_value(true).help("also generate a random chunk of size bytes for downloading"))
+            )
+            .subcommand(SubCommand::with_name("upload")
+                .arg(Arg::with_name("remote").required(true))
+                .arg(Arg::with_name("port").required(true))
+                .arg(Arg::with_name("size").long("size").default_value("4194304").help("random chunk size in bytes"))
+            )
+            .subcommand(SubCommand::with_name("download")
+                .arg(Arg::with_name("remote").required(true))
+                .arg(Arg::with_name("chunk_id").required(true))
+            )
+        )
+        .subcommand(SubCommand::with_name("probe")
+            .arg(Arg::with_name("remote").required(true))
+            .arg(Arg::with_name("timeout").required(true))
+        )
+        .subcommand(SubCommand::with_name("trace")
+            .arg(Arg::with_name("remote").required(true))
+            .arg(Arg::with_name("timeout").long("timeout").default_value("10").help("tunnel build timeout in seconds"))
+        )
         .subcommand(SubCommand::with_name("sn_bench_ping")
             .arg(Arg::with_name("remote").required(true))
             .arg(Arg::with_name("port").required(true))
@@ -241,6 +325,9 @@ pub fn command_line() -> clap::App<'static, 'static> {
             .arg(Arg::with_name("remote").required(true))
             .arg(Arg::with_name("port").required(true))
         )
+        .subcommand(SubCommand::with_name("decode")
+            .arg(Arg::with_name("file").required(true))
+        )
 }
 
 async fn remote_device(
@@ -283,6 +370,35 @@ async fn main() {
         .map_err(|err| err.message).unwrap();
     let subcommand = cmd_params.subcommand_name().ok_or_else(|| "no subcommand\r\n".to_string()).unwrap();
 
+    if subcommand == "decode" {
+        let subcommand = cmd_params.subcommand_matches("decode").unwrap();
+        let file = subcommand.value_of("file").unwrap();
+        match pcapng::read_pcapng(Path::new(file)) {
+            Ok(records) => {
+                let start = records.first().map(|(ts, _)| *ts).unwrap_or(0);
+                for (ts, record) in records {
+                    println!("+{:.3}ms {}", ts.saturating_sub(start) as f64 / 1000.0, record);
+                }
+            },
+            Err(e) => {
+                println!("decode {} failed, err={}", file, e);
+            }
+        }
+        return;
+    }
+
+    if let Some(file) = matches.value_of("pcapng") {
+        match pcapng::PcapngWriter::create(Path::new(file)) {
+            Ok(writer) => {
+                let _ = cyfs_bdt::debug::set_package_capture(Box::new(writer));
+            },
+            Err(e) => {
+                println!("create pcapng file {} failed, err={}", file, e);
+                return;
+            }
+        }
+    }
+
     let mut endpoints = vec![];
     for ep in matches.values_of("ep").unwrap() {
         if ep.len() > 0 {
@@ -372,51 +488,18 @@ async fn main() {
         }
     }
 
-    //load device
-    /*
-    let desc_path = Path::new("deamon.desc");
-    let sec_path = Path::new("deamon.sec");
-    if !sec_path.exists() {
-        println!("deamon.desc not exists, generate new one");
-
-        let (device, private_key) = create_device(sns.clone(), endpoints.clone());
-
-        if let Err(err) = device.encode_to_file(&desc_path, false) {
-            println!("generate deamon.desc failed for {}",  err);
-            return;
-        } 
-        if let Err(err) = private_key.encode_to_file(&sec_path, false) {
-            println!("generate deamon.sec failed for {}",  err);
-            return;
-        }
-    }
-    if !desc_path.exists() {
-        println!("{:?} not exists", desc_path);
-        return;
-    }
-    let device = {
-        let mut buf = vec![];
-        Device::decode_from_file(&desc_path, &mut buf).map(|(d, _)| d)
-    }; 
-    if let Err(err) = device {
-        println!("load {:?} failed for {}", desc_path, err);
-        return;
-    } 
-    
-    let private_key = {
-        let mut buf = vec![];
-        PrivateKey::decode_from_file(&sec_path, &mut buf).map(|(k, _)| k)
-    }; 
-    if let Err(err) = private_key {
-        println!("load {:?} failed for {}", sec_path, err);
-        return;
-    } 
-    let private_key = private_key.unwrap();
-
-    let mut device = device.unwrap();
-    */
-
-    let (mut device, private_key) = create_device(sns.clone(), endpoints.clone());
+    let (mut device, private_key) = match matches.value_of("device_dir") {
+        Some(dir) => {
+            match load_or_create_device(Path::new(dir), sns.clone(), endpoints.clone()) {
+                Ok(ret) => ret, 
+                Err(err) => {
+                    println!("load device from {} failed for {}", dir, err);
+                    return;
+                }
+            }
+        },
+        None => create_device(sns.clone(), endpoints.clone())
+    };
 
     info!("device={:?}", device);
 
@@ -432,6 +515,8 @@ async fn main() {
 
     //init stack
     let mut params = StackOpenParams::new(deamon_name.as_str());
+    let chunk_store = MemChunkStore::new();
+    params.chunk_store = Some(chunk_store.clone_as_reader());
     let sns2 = sns.clone();
     params.known_sn = sns;
     if udp_sn_only != 0 {
@@ -542,6 +627,136 @@ async fn main() {
                 }
             }
         },
+        "probe" => {
+            let subcommand = cmd_params.subcommand_matches("probe").unwrap();
+            let remote = remote_device(&stack, subcommand.value_of("remote").unwrap(), channel).await
+                .map_err(|err| format!("load remote desc {} failed for {}\r\n", subcommand.value_of("remote").unwrap(), err)).unwrap();
+            let timeout = u64::from_str(subcommand.value_of("timeout").unwrap()).unwrap();
+
+            let prober = cyfs_bdt::debug::Prober::open(stack.clone().to_weak()).unwrap();
+            match prober.probe(remote, Duration::from_secs(timeout)).await {
+                Ok(result) => {
+                    println!("{}", result);
+                },
+                Err(e) => {
+                    println!("probe err={}", e);
+                }
+            }
+        },
+        "trace" => {
+            let subcommand = cmd_params.subcommand_matches("trace").unwrap();
+            let start = std::time::Instant::now();
+            let remote = remote_device(&stack, subcommand.value_of("remote").unwrap(), channel).await
+                .map_err(|err| format!("load remote desc {} failed for {}\r\n", subcommand.value_of("remote").unwrap(), err)).unwrap();
+            let resolve_remote = start.elapsed();
+            let timeout = Duration::from_secs(u64::from_str(subcommand.value_of("timeout").unwrap()).unwrap());
+
+            match trace::trace(&stack, remote, resolve_remote, timeout).await {
+                Ok(report) => {
+                    println!("{}", report);
+                },
+                Err(e) => {
+                    println!("trace err={}", e);
+                }
+            }
+        },
+        "bench" => {
+            let subcommand = cmd_params.subcommand_matches("bench").unwrap();
+            let remote = remote_device(&stack, subcommand.value_of("remote").unwrap(), channel).await
+                .map_err(|err| format!("load remote desc {} failed for {}\r\n", subcommand.value_of("remote").unwrap(), err)).unwrap();
+            let port = u16::from_str(subcommand.value_of("port").unwrap()).unwrap();
+            let mode = match bench::BenchMode::from_str(subcommand.value_of("mode").unwrap()) {
+                Ok(mode) => mode, 
+                Err(e) => {
+                    println!("{}", e);
+                    return;
+                }
+            };
+            let ping_interval = Duration::from_millis(u64::from_str(subcommand.value_of("ping_interval").unwrap()).unwrap());
+            let config = bench::BenchConfig {
+                mode, 
+                duration: Duration::from_secs(u64::from_str(subcommand.value_of("duration").unwrap()).unwrap()), 
+                interval: Duration::from_secs(u64::from_str(subcommand.value_of("interval").unwrap()).unwrap()), 
+                ping_interval, 
+                ping_timeout: std::cmp::max(ping_interval, Duration::from_secs(1)), 
+            };
+
+            match bench::bench(&stack, remote, port, config).await {
+                Ok(summary) => {
+                    println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+                },
+                Err(e) => {
+                    println!("bench vport={} failed, err={}", port, e);
+                }
+            }
+        },
+        "chunk" => {
+            let subcommand = cmd_params.subcommand_matches("chunk").unwrap();
+            let timeout = Duration::from_secs(u64::from_str(subcommand.value_of("timeout").unwrap()).unwrap());
+            match subcommand.subcommand() {
+                ("listen", Some(action)) => {
+                    let port = u16::from_str(action.value_of("port").unwrap()).unwrap();
+                    if let Some(size) = action.value_of("size") {
+                        let size = usize::from_str(size).unwrap();
+                        match chunk::gen_random_chunk(&chunk_store, size).await {
+                            Ok(chunk_id) => println!("generate chunk {} size={}", chunk_id, size),
+                            Err(e) => {
+                                println!("generate chunk failed, err={}", e);
+                                return;
+                            }
+                        }
+                    }
+                    if let Err(e) = chunk::listen(&stack, port, timeout).await {
+                        println!("chunk listen vport={} failed, err={}", port, e);
+                    }
+                },
+                ("upload", Some(action)) => {
+                    let remote = remote_device(&stack, action.value_of("remote").unwrap(), channel).await
+                        .map_err(|err| format!("load remote desc {} failed for {}\r\n", action.value_of("remote").unwrap(), err)).unwrap();
+                    let port = u16::from_str(action.value_of("port").unwrap()).unwrap();
+                    let size = usize::from_str(action.value_of("size").unwrap()).unwrap();
+                    match chunk::upload(&stack, &chunk_store, &remote, port, size, timeout).await {
+                        Ok(elapsed) => {
+                            println!("upload chunk success, size={}, elapsed={:?}, speed={:.2} Mbps", 
+                                size, elapsed, size as f64 * 8.0 / 1000.0 / 1000.0 / elapsed.as_secs_f64());
+                        },
+                        Err(e) => {
+                            println!("upload chunk failed, err={}", e);
+                        }
+                    }
+                },
+                ("download", Some(action)) => {
+                    let remote = remote_device(&stack, action.value_of("remote").unwrap(), channel).await
+                        .map_err(|err| format!("load remote desc {} failed for {}\r\n", action.value_of("remote").unwrap(), err)).unwrap();
+                    let chunk_id = match ChunkId::from_str(action.value_of("chunk_id").unwrap()) {
+                        Ok(chunk_id) => chunk_id, 
+                        Err(e) => {
+                            println!("invalid chunk id, err={}", e);
+                            return;
+                        }
+                    };
+                    match chunk::download(&stack, remote.desc(), &chunk_id, timeout).await {
+                        Ok(elapsed) => {
+                            println!("download chunk {} success, size={}, elapsed={:?}, speed={:.2} Mbps", 
+                                chunk_id, chunk_id.len(), elapsed, chunk_id.len() as f64 * 8.0 / 1000.0 / 1000.0 / elapsed.as_secs_f64());
+                        },
+                        Err(e) => {
+                            println!("download chunk {} failed, err={}", chunk_id, e);
+                        }
+                    }
+                },
+                _ => {
+                    println!("chunk should be used with listen/upload/download");
+                }
+            }
+        },
+        "bench_listen" => {
+            let subcommand = cmd_params.subcommand_matches("bench_listen").unwrap();
+            let port = u16::from_str(subcommand.value_of("port").unwrap()).unwrap();
+            if let Err(e) = bench::bench_listen(&stack, port).await {
+                println!("bench listen vport={} failed, err={}", port, e);
+            }
+        },
         _ => {
             println!("unspport cmd {}", subcommand);
         }
@@ -0,0 +1,146 @@
+use std::{
+    fs::File,
+    io::{Read, Write, BufWriter},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use cyfs_base::*;
+use cyfs_bdt::debug::{CapturedPackageBox, PackageCapture};
+
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x00000006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+
+// LINKTYPE_USER0，私有链路类型，每个包是一条 CapturedPackageBox 记录
+pub const LINKTYPE_BDT_PACKAGE_BOX: u16 = 147;
+
+fn write_block(w: &mut impl Write, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+    let padding = (4 - body.len() % 4) % 4;
+    let total_len = (12 + body.len() + padding) as u32;
+    w.write_all(&block_type.to_le_bytes())?;
+    w.write_all(&total_len.to_le_bytes())?;
+    w.write_all(body)?;
+    w.write_all(&[0u8; 3][..padding])?;
+    w.write_all(&total_len.to_le_bytes())
+}
+
+pub struct PcapngWriter {
+    file: Mutex<BufWriter<File>>,
+}
+
+impl PcapngWriter {
+    pub fn create(path: &Path) -> BuckyResult<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        let mut shb = vec![];
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes());
+        shb.extend_from_slice(&0u16.to_le_bytes());
+        shb.extend_from_slice(&(-1i64).to_le_bytes());
+        write_block(&mut file, BLOCK_SECTION_HEADER, &shb)?;
+
+        let mut idb = vec![];
+        idb.extend_from_slice(&LINKTYPE_BDT_PACKAGE_BOX.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&0u32.to_le_bytes());
+        write_block(&mut file, BLOCK_INTERFACE_DESCRIPTION, &idb)?;
+        file.flush()?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn write_record(&self, record: &[u8]) -> std::io::Result<()> {
+        // 默认的时间精度是微秒
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros() as u64;
+
+        let mut epb = vec![];
+        epb.extend_from_slice(&0u32.to_le_bytes());
+        epb.extend_from_slice(&((ts >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(ts as u32).to_le_bytes());
+        epb.extend_from_slice(&(record.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(record.len() as u32).to_le_bytes());
+        epb.extend_from_slice(record);
+
+        let mut file = self.file.lock().unwrap();
+        write_block(&mut *file, BLOCK_ENHANCED_PACKET, &epb)?;
+        file.flush()
+    }
+}
+
+impl PackageCapture for PcapngWriter {
+    fn capture(&self, package_box: CapturedPackageBox) {
+        match package_box.to_vec() {
+            Ok(record) => {
+                if let Err(e) = self.write_record(&record) {
+                    log::error!("write capture record failed, err={}", e);
+                }
+            },
+            Err(e) => {
+                log::error!("encode capture record failed, err={}", e);
+            }
+        }
+    }
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]])
+}
+
+// 读出由PcapngWriter写入的记录，返回 (unix时间戳微秒, 记录)
+pub fn read_pcapng(path: &Path) -> BuckyResult<Vec<(u64, CapturedPackageBox)>> {
+    let mut buf = vec![];
+    File::open(path)?.read_to_end(&mut buf)?;
+
+    let mut records = vec![];
+    let mut linktype = None;
+    let mut pos = 0;
+    while pos + 12 <= buf.len() {
+        let block_type = read_u32(&buf, pos);
+        let total_len = read_u32(&buf, pos + 4) as usize;
+        if total_len < 12 || pos + total_len > buf.len() {
+            let msg = format!("invalid pcapng block at {}, len={}", pos, total_len);
+            return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, msg));
+        }
+        let body = &buf[pos + 8..pos + total_len - 4];
+
+        match block_type {
+            BLOCK_SECTION_HEADER => {
+                if body.len() < 4 || read_u32(body, 0) != BYTE_ORDER_MAGIC {
+                    return Err(BuckyError::new(BuckyErrorCode::NotSupport, "only little endian pcapng supported"));
+                }
+            },
+            BLOCK_INTERFACE_DESCRIPTION => {
+                if body.len() >= 2 {
+                    linktype = Some(u16::from_le_bytes([body[0], body[1]]));
+                }
+            },
+            BLOCK_ENHANCED_PACKET => {
+                if linktype != Some(LINKTYPE_BDT_PACKAGE_BOX) {
+                    let msg = format!("unsupport link type {:?}", linktype);
+                    return Err(BuckyError::new(BuckyErrorCode::NotSupport, msg));
+                }
+                if body.len() < 20 {
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, "invalid enhanced packet block"));
+                }
+                let ts = ((read_u32(body, 4) as u64) << 32) | read_u32(body, 8) as u64;
+                let captured_len = read_u32(body, 12) as usize;
+                if 20 + captured_len > body.len() {
+                    return Err(BuckyError::new(BuckyErrorCode::InvalidFormat, "invalid enhanced packet length"));
+                }
+                let record = CapturedPackageBox::from_bytes(&body[20..20 + captured_len])?;
+                records.push((ts, record));
+            },
+            _ => {
+                // 忽略其它类型的block
+            }
+        }
+
+        pos += total_len;
+    }
+
+    Ok(records)
+}
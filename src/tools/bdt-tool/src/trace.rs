@@ -0,0 +1,169 @@
+use std::{fmt, time::Duration};
+
+use cyfs_base::*;
+use cyfs_bdt::{
+    debug::{ProbeResult, Prober},
+    tunnel::{BuildTunnelTraceEvent, ProxyType},
+    *,
+};
+
+pub struct TraceReport {
+    // 从meta或者文件加载对端desc的耗时
+    pub resolve_remote: Duration,
+    pub probe: ProbeResult,
+}
+
+impl TraceReport {
+    fn events(&self) -> impl Iterator<Item = &BuildTunnelTraceEvent> {
+        self.probe.trace.iter().map(|item| &item.event)
+    }
+
+    fn explored_tcp(&self) -> Vec<EndpointPair> {
+        self.events()
+            .filter_map(|event| match event {
+                BuildTunnelTraceEvent::ExploreEndpoint(ep_pair) if !ep_pair.is_udp() => {
+                    Some(ep_pair.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn is_established_on(&self, ep_pair: &EndpointPair) -> bool {
+        match &self.probe.tunnel {
+            Some((used, proxy)) => *proxy == ProxyType::None && used == ep_pair,
+            None => false,
+        }
+    }
+
+    // 根据建立过程给出连接走relay或者失败的可能原因
+    pub fn diagnose(&self) -> String {
+        let resolved_sn = self.events().find_map(|event| match event {
+            BuildTunnelTraceEvent::ResolveSn(sn) => Some(sn.clone()),
+            _ => None,
+        });
+        let sn_responsed = self
+            .events()
+            .any(|event| matches!(event, BuildTunnelTraceEvent::SnResponsed { .. }));
+        let sn_failed = self
+            .events()
+            .any(|event| matches!(event, BuildTunnelTraceEvent::SnFailed { .. }));
+        let explored = self.probe.hole_punch.len() + self.explored_tcp().len();
+
+        match &self.probe.tunnel {
+            Some((_, proxy)) if *proxy != ProxyType::None => format!(
+                "fallback to relay, hole punch on {} udp endpoint pairs got no response, udp may be blocked or both sides are behind symmetric nat",
+                self.probe.hole_punch.len()
+            ),
+            Some((ep_pair, _)) if ep_pair.is_reverse_tcp() => {
+                "tcp endpoints of remote are unreachable, connected by reverse tcp from remote after sn call".to_owned()
+            }
+            Some(_) => "direct connection".to_owned(),
+            None => {
+                if resolved_sn == Some(None) && explored == 0 {
+                    "no sn resolved for remote and remote has no static wan endpoint, check the sn list of remote desc or --sn".to_owned()
+                } else if sn_failed && !sn_responsed {
+                    "call sn failed, remote may be offline or not online on the sn".to_owned()
+                } else if sn_responsed {
+                    "sn responsed but no endpoint pair connected and no proxy available, udp may be blocked or both sides are behind symmetric nat".to_owned()
+                } else if explored > 0 {
+                    format!("no response on {} endpoint pairs", explored)
+                } else {
+                    "tunnel building not finished in timeout".to_owned()
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for TraceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "trace {}", self.probe.remote)?;
+        writeln!(
+            f,
+            "  resolve remote desc cost {}ms",
+            self.resolve_remote.as_millis()
+        )?;
+
+        // 每一步相对开始建立的时间，以及距离上一步的耗时
+        let mut last = Duration::ZERO;
+        for item in &self.probe.trace {
+            writeln!(
+                f,
+                "  +{}ms (+{}ms) {}",
+                item.elapsed.as_millis(),
+                item.elapsed.saturating_sub(last).as_millis(),
+                item.event
+            )?;
+            last = item.elapsed;
+        }
+
+        if !self.probe.hole_punch.is_empty() {
+            writeln!(f, "hole punch:")?;
+            for (ep_pair, attempts) in &self.probe.hole_punch {
+                writeln!(
+                    f,
+                    "  {} {} attempts, {}",
+                    ep_pair,
+                    attempts,
+                    if self.is_established_on(ep_pair) {
+                        "established"
+                    } else {
+                        "no response"
+                    }
+                )?;
+            }
+        }
+
+        let explored_tcp = self.explored_tcp();
+        if !explored_tcp.is_empty() {
+            writeln!(f, "tcp connect:")?;
+            for ep_pair in &explored_tcp {
+                writeln!(
+                    f,
+                    "  {} {}",
+                    ep_pair,
+                    if self.is_established_on(ep_pair) {
+                        "established"
+                    } else {
+                        "no response"
+                    }
+                )?;
+            }
+        }
+
+        match &self.probe.tunnel {
+            Some((ep_pair, proxy)) => writeln!(
+                f,
+                "result: tunnel {} on {}, proxy {:?}, cost {}ms",
+                self.probe.tunnel_type(),
+                ep_pair,
+                proxy,
+                self.probe.elapsed.as_millis()
+            )?,
+            None => writeln!(
+                f,
+                "result: tunnel not established in {}ms",
+                self.probe.elapsed.as_millis()
+            )?,
+        }
+
+        write!(f, "diagnosis: {}", self.diagnose())
+    }
+}
+
+// 类似traceroute，重新建立到remote的tunnel，输出每一步的耗时和最终选中的tunnel
+pub async fn trace(
+    stack: &Stack,
+    remote: Device,
+    resolve_remote: Duration,
+    timeout: Duration,
+) -> BuckyResult<TraceReport> {
+    let prober = Prober::open(stack.to_weak())?;
+    let probe = prober.probe(remote, timeout).await?;
+
+    Ok(TraceReport {
+        resolve_remote,
+        probe,
+    })
+}
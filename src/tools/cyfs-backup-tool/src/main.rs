@@ -97,6 +97,11 @@ async fn main_run() {
             .multiple(true)
             .takes_value(true)
             .help("The key data that meets the filter condition will be ignored, and the filter supports glob pattern")
+    ).arg(
+        Arg::with_name("bare-metal")
+            .long("bare-metal")
+            .takes_value(false)
+            .help("Restore a brand-new device from the archive alone, including the identity, config and data, only valid in restore mode"),
     )
     .get_matches();
 
@@ -247,6 +252,7 @@ async fn main_run() {
                         isolate: isolate.to_owned(),
                         archive: PathBuf::from(archive),
                         password,
+                        bare_metal: matches.is_present("bare-metal"),
                     };
 
                     let restore_manager = restore::RestoreService::new(&params.isolate)
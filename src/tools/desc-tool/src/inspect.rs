@@ -0,0 +1,59 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use cyfs_lib::OBJECT_INSPECTOR;
+use log::*;
+
+// .\desc-tool inspect ${object-path}
+// .\desc-tool inspect ${object-raw-hex} --hex
+
+pub fn inspect_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("inspect")
+        .about("decode any object to json, include desc, body and signs")
+        .arg(
+            Arg::with_name("object")
+                .takes_value(true)
+                .index(1)
+                .required(true)
+                .help("object file to inspect, or raw hex string with --hex"),
+        )
+        .arg(
+            Arg::with_name("hex")
+                .short("x")
+                .long("hex")
+                .help("input is object raw in hex string"),
+        )
+        .arg(
+            Arg::with_name("compact")
+                .short("c")
+                .long("compact")
+                .help("output compact json in one line"),
+        )
+}
+
+pub fn inspect_object(matches: &ArgMatches) {
+    let input = matches.value_of("object").unwrap();
+    let ret = if matches.is_present("hex") {
+        OBJECT_INSPECTOR.inspect_hex(input)
+    } else {
+        match std::fs::read(input) {
+            Ok(buf) => OBJECT_INSPECTOR.inspect(&buf),
+            Err(e) => {
+                error!("read object file {} failed, err {}", input, e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    match ret {
+        Ok(value) => {
+            if matches.is_present("compact") {
+                println!("{}", value);
+            } else {
+                println!("{}", serde_json::to_string_pretty(&value).unwrap());
+            }
+        }
+        Err(e) => {
+            error!("inspect object {} failed, err {}", input, e);
+            std::process::exit(1);
+        }
+    }
+}
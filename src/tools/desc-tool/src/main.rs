@@ -1,6 +1,7 @@
 mod util;
 mod modify;
 mod sign;
+mod inspect;
 
 use clap::{SubCommand, App, Arg};
 use crate::show::{show_desc, show_desc_subcommand};
@@ -9,6 +10,7 @@ use crate::modify::{modify_subcommand, modify_desc};
 use log::*;
 use cyfs_base::{StandardObject, FileDecoder, BuckyError, BuckyErrorCode};
 use crate::sign::{sign_subcommand, sign_desc};
+use crate::inspect::{inspect_subcommand, inspect_object};
 
 pub mod desc;
 mod show;
@@ -73,6 +75,7 @@ async fn main_run() {
         .subcommand(calc_subcommand())
         .subcommand(modify_subcommand())
         .subcommand(sign_subcommand())
+        .subcommand(inspect_subcommand())
         .get_matches();
 
     match matches.subcommand() {
@@ -92,6 +95,9 @@ async fn main_run() {
         ("sign", Some(matches)) => {
             sign_desc(matches).await;
         }
+        ("inspect", Some(matches)) => {
+            inspect_object(matches);
+        }
         v @ _ => {
             error!("unknown command: {}", v.0);
             std::process::exit(1);
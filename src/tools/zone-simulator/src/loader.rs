@@ -98,6 +98,11 @@ impl TestLoader {
         super::zone::SharedStackCache::instance().get(&id).unwrap()
     }
 
+    // 安装虚拟时钟，需要在加载协议栈之前调用；之后协议栈内的定时器和超时都由返回的时钟推进
+    pub fn enable_virtual_time() -> VirtualClock {
+        VirtualClock::install(bucky_time_now()).unwrap()
+    }
+
     pub async fn load_default(stack_config: &CyfsStackInsConfig) {
         let (user1, user2) = TestLoader::load_users(TEST_PROFILE.get_mnemonic(), true, false).await;
 